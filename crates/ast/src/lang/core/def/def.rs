@@ -277,10 +277,12 @@ fn to_pending_def<'a>(
 
         Type(TypeDef::Opaque { .. }) => internal_error!("opaques not implemented"),
         Type(TypeDef::Ability { .. }) => todo_abilities!(),
+        Type(TypeDef::PatternSynonym { .. }) => todo!(),
 
         Value(AstValueDef::Dbg { .. }) => todo!(),
         Value(AstValueDef::Expect { .. }) => todo!(),
         Value(AstValueDef::ExpectFx { .. }) => todo!(),
+        Value(AstValueDef::Bench { .. }) => todo!(),
 
         SpaceBefore(sub_def, _) | SpaceAfter(sub_def, _) => {
             to_pending_def(env, sub_def, scope, pattern_type)
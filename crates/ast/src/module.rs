@@ -16,6 +16,7 @@ pub fn load_module(
         palette: DEFAULT_PALETTE,
         threading,
         exec_mode: ExecutionMode::Check,
+        ..Default::default()
     };
 
     let arena = Bump::new();
@@ -24,7 +25,7 @@ pub fn load_module(
 
     match loaded {
         Ok(x) => x,
-        Err(roc_load::LoadingProblem::FormattedReport(report)) => {
+        Err(roc_load::LoadingProblem::FormattedReport(report, _)) => {
             panic!(
                 "Failed to load module from src_file: {:?}. Report: {}",
                 src_file, report
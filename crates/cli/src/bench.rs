@@ -0,0 +1,307 @@
+//! Runs the top-level `bench` blocks in a module and the modules it imports, for `roc bench`.
+use crate::{
+    handle_error_module, handle_loading_problem, memory_limit, FLAG_BASELINE, FLAG_ITERATIONS,
+    FLAG_MAX_THREADS, FLAG_SAVE_BASELINE, FLAG_WARMUP, ROC_FILE,
+};
+use bumpalo::Bump;
+use clap::ArgMatches;
+use roc_collections::MutMap;
+use roc_load::Threading;
+use roc_module::symbol::ModuleId;
+use roc_packaging::cache::RocCacheDir;
+use roc_region::all::Region;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use target_lexicon::Triple;
+
+const DEFAULT_WARMUP: usize = 10;
+const DEFAULT_ITERATIONS: usize = 100;
+
+/// Summary statistics of one benchmark's timings, in nanoseconds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub mean: f64,
+    pub median: f64,
+    pub stddev: f64,
+}
+
+impl Stats {
+    pub fn from_timings(timings: &[Duration]) -> Self {
+        let mut nanos: Vec<f64> = timings.iter().map(|t| t.as_nanos() as f64).collect();
+        nanos.sort_by(|a, b| a.total_cmp(b));
+
+        let count = nanos.len() as f64;
+        let mean = nanos.iter().sum::<f64>() / count;
+
+        let median = match nanos.len() {
+            0 => f64::NAN,
+            n if n % 2 == 0 => (nanos[n / 2 - 1] + nanos[n / 2]) / 2.0,
+            n => nanos[n / 2],
+        };
+
+        // sample standard deviation; a single run has no spread
+        let stddev = if nanos.len() > 1 {
+            let variance = nanos.iter().map(|n| (n - mean).powi(2)).sum::<f64>() / (count - 1.0);
+            variance.sqrt()
+        } else {
+            0.0
+        };
+
+        Self {
+            mean,
+            median,
+            stddev,
+        }
+    }
+}
+
+/// Parses a baseline file, where each line is a benchmark name and its mean in nanoseconds,
+/// separated by a tab
+pub fn parse_baseline(contents: &str) -> MutMap<String, f64> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (name, mean) = line.rsplit_once('\t')?;
+            Some((name.to_string(), mean.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+pub fn render_baseline<'a>(results: impl IntoIterator<Item = (&'a str, &'a Stats)>) -> String {
+    let mut buf = String::new();
+
+    for (name, stats) in results {
+        buf.push_str(&format!("{}\t{:.0}\n", name, stats.mean));
+    }
+
+    buf
+}
+
+fn format_nanos(nanos: f64) -> String {
+    if nanos >= 1_000_000_000.0 {
+        format!("{:.3} s", nanos / 1_000_000_000.0)
+    } else if nanos >= 1_000_000.0 {
+        format!("{:.3} ms", nanos / 1_000_000.0)
+    } else if nanos >= 1_000.0 {
+        format!("{:.3} µs", nanos / 1_000.0)
+    } else {
+        format!("{:.0} ns", nanos)
+    }
+}
+
+/// A benchmark is named after the comment just above it, or else its location
+fn bench_name(
+    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    module_id: ModuleId,
+    region: Region,
+) -> String {
+    let (path, source) = &sources[&module_id];
+
+    match crate::test_filter::comment_name(source, region) {
+        Some(name) => name.to_string(),
+        None => {
+            let start = (region.start().offset as usize).min(source.len());
+            let line = source[..start].matches('\n').count() + 1;
+
+            format!("bench at {}:{}", path.display(), line)
+        }
+    }
+}
+
+fn parse_count(matches: &ArgMatches, flag: &str, default: usize) -> usize {
+    match matches.value_of(flag) {
+        None => default,
+        Some(value) => match value.parse::<usize>() {
+            Ok(count) => count,
+            Err(_) => roc_error_macros::user_error!(
+                "--{} must be a whole number, but it was {}",
+                flag,
+                value
+            ),
+        },
+    }
+}
+
+#[cfg(windows)]
+pub fn bench(_matches: &ArgMatches, _triple: Triple) -> io::Result<i32> {
+    eprintln!("\nRunning benchmarks does not work on Windows yet, sorry!\n");
+
+    Ok(1)
+}
+
+#[cfg(not(windows))]
+pub fn bench(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
+    use roc_build::program::report_problems_monomorphized;
+    use roc_gen_llvm::llvm::build::LlvmBackendMode;
+    use roc_load::{ExecutionMode, LoadConfig, LoadMonomorphizedError};
+    use roc_mono::ir::OptLevel;
+    use roc_packaging::cache;
+    use roc_target::TargetInfo;
+
+    let start_time = Instant::now();
+    let arena = &Bump::new();
+    let filename = matches.value_of_os(ROC_FILE).unwrap();
+    let path = Path::new(filename);
+
+    if !path.exists() {
+        eprintln!(
+            "\nThis file was not found: {}\n\nYou can run `roc help` for more information on how to provide a .roc file.\n",
+            path.to_string_lossy()
+        );
+
+        return Ok(1);
+    }
+
+    let warmup = parse_count(matches, FLAG_WARMUP, DEFAULT_WARMUP);
+    let iterations = parse_count(matches, FLAG_ITERATIONS, DEFAULT_ITERATIONS).max(1);
+
+    let baseline = match matches.value_of_os(FLAG_BASELINE) {
+        None => None,
+        Some(baseline_path) => match std::fs::read_to_string(baseline_path) {
+            Ok(contents) => Some(parse_baseline(&contents)),
+            Err(err) => {
+                eprintln!(
+                    "\nI could not read the baseline file {}: {}\n",
+                    Path::new(baseline_path).display(),
+                    err
+                );
+
+                return Ok(1);
+            }
+        },
+    };
+
+    let threading = match matches
+        .value_of(FLAG_MAX_THREADS)
+        .and_then(|s| s.parse::<usize>().ok())
+    {
+        None => Threading::AllAvailable,
+        Some(0) => roc_error_macros::user_error!("cannot build with at most 0 threads"),
+        Some(1) => Threading::Single,
+        Some(n) => Threading::AtMost(n),
+    };
+
+    let warning_levels = crate::warnings::warning_levels(matches, path);
+
+    let load_config = LoadConfig {
+        target_info: TargetInfo::from(&triple),
+        render: roc_reporting::cli::terminal_style().target,
+        palette: roc_reporting::cli::terminal_style().palette,
+        threading,
+        exec_mode: ExecutionMode::Bench,
+        memory_limit: memory_limit(matches),
+        ..Default::default()
+    };
+    let load_result = roc_load::load_and_monomorphize(
+        arena,
+        path.to_path_buf(),
+        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+        load_config,
+    );
+
+    let mut loaded = match load_result {
+        Ok(loaded) => loaded,
+        Err(LoadMonomorphizedError::LoadingProblem(problem)) => {
+            return handle_loading_problem(problem);
+        }
+        Err(LoadMonomorphizedError::ErrorModule(module)) => {
+            return handle_error_module(
+                module,
+                start_time.elapsed(),
+                filename,
+                false,
+                &warning_levels,
+            );
+        }
+    };
+    let problems = report_problems_monomorphized(&mut loaded, &warning_levels);
+
+    if problems.warnings > 0 {
+        problems.print_to_stdout(start_time.elapsed());
+        println!(
+            ".\n\nRunning benchmarks…\n\n\x1B[36m{}\x1B[39m",
+            "─".repeat(80)
+        );
+    }
+
+    let sources = std::mem::take(&mut loaded.sources);
+
+    let (lib, expects, _layout_interner) = roc_repl_expect::run::expect_mono_module_to_dylib(
+        arena,
+        triple,
+        loaded,
+        // timings are only meaningful for optimized code
+        OptLevel::Optimize,
+        LlvmBackendMode::CliTest,
+    )
+    .unwrap();
+
+    if expects.benches.is_empty() {
+        println!("No benchmarks were found.");
+
+        return Ok(2);
+    }
+
+    let mut results = Vec::with_capacity(expects.benches.len());
+    let mut crashed = 0;
+
+    for bench in expects.benches.iter().copied() {
+        let name = bench_name(&sources, bench.symbol.module_id(), bench.region);
+
+        match roc_repl_expect::run::run_toplevel_bench(&lib, bench, warmup, iterations) {
+            Ok(timings) => {
+                let stats = Stats::from_timings(&timings);
+
+                println!(
+                    "\x1B[36m{}\x1B[39m\n    mean {}  median {}  stddev {}  ({} runs)",
+                    name,
+                    format_nanos(stats.mean),
+                    format_nanos(stats.median),
+                    format_nanos(stats.stddev),
+                    iterations,
+                );
+
+                if let Some(before) = baseline.as_ref().and_then(|b| b.get(&name)) {
+                    let change = (stats.mean - before) / before * 100.0;
+                    let color = if change > 0.0 { 31 } else { 32 };
+
+                    println!(
+                        "    \x1B[{color}m{:+.2}%\x1B[39m compared to the baseline mean of {}",
+                        change,
+                        format_nanos(*before),
+                    );
+                }
+
+                results.push((name, stats));
+            }
+            Err(roc_panic_message) => {
+                println!(
+                    "\x1B[36m{}\x1B[39m\n    \x1B[31mcrashed:\x1B[39m {}",
+                    name, roc_panic_message
+                );
+
+                crashed += 1;
+            }
+        }
+    }
+
+    if let Some(baseline_path) = matches.value_of_os(FLAG_SAVE_BASELINE) {
+        let contents = render_baseline(results.iter().map(|(name, stats)| (name.as_str(), stats)));
+
+        std::fs::write(baseline_path, contents)?;
+
+        println!(
+            "\nSaved the baseline to {}",
+            Path::new(baseline_path).display()
+        );
+    }
+
+    println!(
+        "\nRan {} benchmarks in {} ms.\n",
+        results.len() + crashed,
+        start_time.elapsed().as_millis(),
+    );
+
+    Ok((crashed > 0) as i32)
+}
@@ -1,29 +1,32 @@
 use bumpalo::Bump;
 use roc_build::{
     link::{
-        legacy_host_filename, link, preprocess_host_wasm32, preprocessed_host_filename,
-        rebuild_host, LinkType, LinkingStrategy,
+        build_host, can_rebuild_host_for_musl, find_mimalloc, get_target_triple_str,
+        host_sources_changed, inline_hosted_bitcode_filename, legacy_host_filename, link,
+        link_static_linux, preprocess_host_wasm32, preprocessed_host_filename, split_debug_info,
+        verify_static_executable, LinkType, LinkingStrategy,
     },
-    program::{self, CodeGenBackend, CodeGenOptions},
+    program::{self, CodeGenBackend, CodeGenOptions, WasmFeatures},
 };
 use roc_builtins::bitcode;
+use roc_error_macros::user_error;
+use roc_load::plugin::Plugins;
 use roc_load::{
     EntryPoint, ExecutionMode, ExpectMetadata, LoadConfig, LoadMonomorphizedError, LoadedModule,
     LoadingProblem, Threading,
 };
+use roc_module::mangle;
 use roc_mono::ir::OptLevel;
 use roc_packaging::cache::RocCacheDir;
-use roc_reporting::{
-    cli::Problems,
-    report::{RenderTarget, DEFAULT_PALETTE},
-};
+use roc_parse::header::{AllocatorStrategy, PlatformAllocator, PlatformHost};
+use roc_reporting::cli::{terminal_style, Problems, WarningLevels};
 use roc_target::TargetInfo;
 use std::{
     path::Path,
     time::{Duration, Instant},
 };
 use std::{path::PathBuf, thread::JoinHandle};
-use target_lexicon::Triple;
+use target_lexicon::{Environment, Triple};
 
 fn report_timing(buf: &mut String, label: &str, duration: Duration) {
     use std::fmt::Write;
@@ -37,6 +40,22 @@ fn report_timing(buf: &mut String, label: &str, duration: Duration) {
     .unwrap()
 }
 
+fn report_link_stats(buf: &mut String, stats: &roc_linker::LinkStats) {
+    use std::fmt::Write;
+
+    for (label, duration) in stats.phases.iter() {
+        report_timing(buf, label, *duration);
+    }
+    report_timing(buf, "Total", stats.total);
+
+    writeln!(
+        buf,
+        "\nPatched {} calls and pointers to {} app functions, and wrote {} bytes",
+        stats.surgeries_applied, stats.symbols_patched, stats.bytes_written,
+    )
+    .unwrap()
+}
+
 pub struct BuiltFile<'a> {
     pub binary_path: PathBuf,
     pub problems: Problems,
@@ -44,6 +63,17 @@ pub struct BuiltFile<'a> {
     pub expect_metadata: ExpectMetadata<'a>,
 }
 
+/// Whether to build the platform's host before linking the app with it, from --prebuilt-platform
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrebuiltPlatform {
+    /// Use the host as it is
+    Yes,
+    /// Always build the host
+    No,
+    /// Build the host only if its sources changed since it was last built and preprocessed
+    IfSourcesChanged,
+}
+
 pub enum BuildOrdering {
     /// Run up through typechecking first; continue building iff that is successful.
     BuildIfChecks,
@@ -79,6 +109,9 @@ pub fn standard_load_config(
     target: &Triple,
     order: BuildOrdering,
     threading: Threading,
+    memory_limit: Option<usize>,
+    keep_dbgs: bool,
+    keep_expects: bool,
 ) -> LoadConfig {
     let target_info = TargetInfo::from(target);
 
@@ -89,10 +122,14 @@ pub fn standard_load_config(
 
     LoadConfig {
         target_info,
-        render: RenderTarget::ColorTerminal,
-        palette: DEFAULT_PALETTE,
+        render: terminal_style().target,
+        palette: terminal_style().palette,
         threading,
         exec_mode,
+        keep_dbgs,
+        keep_expects,
+        memory_limit,
+        ..Default::default()
     }
 }
 
@@ -105,10 +142,15 @@ pub fn build_file<'a>(
     emit_timings: bool,
     link_type: LinkType,
     linking_strategy: LinkingStrategy,
-    prebuilt_requested: bool,
+    prebuilt_requested: PrebuiltPlatform,
     wasm_dev_stack_bytes: Option<u32>,
+    wasm_dev_features: WasmFeatures,
+    allocator_override: Option<AllocatorStrategy>,
+    static_link: bool,
+    split_debuginfo: bool,
     roc_cache_dir: RocCacheDir<'_>,
     load_config: LoadConfig,
+    warning_levels: &WarningLevels,
 ) -> Result<BuiltFile<'a>, BuildFileError<'a>> {
     let compilation_start = Instant::now();
 
@@ -127,8 +169,14 @@ pub fn build_file<'a>(
         linking_strategy,
         prebuilt_requested,
         wasm_dev_stack_bytes,
+        wasm_dev_features,
+        allocator_override,
+        static_link,
+        split_debuginfo,
+        roc_cache_dir,
         loaded,
         compilation_start,
+        warning_levels,
     )
 }
 
@@ -141,10 +189,16 @@ fn build_loaded_file<'a>(
     emit_timings: bool,
     link_type: LinkType,
     linking_strategy: LinkingStrategy,
-    prebuilt_requested: bool,
+    prebuilt_requested: PrebuiltPlatform,
     wasm_dev_stack_bytes: Option<u32>,
+    wasm_dev_features: WasmFeatures,
+    allocator_override: Option<AllocatorStrategy>,
+    static_link: bool,
+    split_debuginfo: bool,
+    roc_cache_dir: RocCacheDir<'_>,
     loaded: roc_load::MonomorphizedModule<'a>,
     compilation_start: Instant,
+    warning_levels: &WarningLevels,
 ) -> Result<BuiltFile<'a>, BuildFileError<'a>> {
     let operating_system = roc_target::OperatingSystem::from(target.operating_system);
 
@@ -153,6 +207,17 @@ fn build_loaded_file<'a>(
         _ => unreachable!(),
     };
 
+    // A static executable is linked against musl, so its host has to be built against musl too,
+    // rather than the system libc the app's target would use.
+    let host_target = if static_link {
+        Triple {
+            environment: Environment::Musl,
+            ..target.clone()
+        }
+    } else {
+        target.clone()
+    };
+
     // the preprocessed host is stored beside the platform's main.roc
     let preprocessed_host_path = if linking_strategy == LinkingStrategy::Legacy {
         if let roc_target::OperatingSystem::Wasi = operating_system {
@@ -160,7 +225,7 @@ fn build_loaded_file<'a>(
             // and has a file called "host.zig"
             platform_main_roc.with_file_name("host.zig")
         } else {
-            platform_main_roc.with_file_name(legacy_host_filename(target).unwrap())
+            platform_main_roc.with_file_name(legacy_host_filename(&host_target).unwrap())
         }
     } else {
         platform_main_roc.with_file_name(preprocessed_host_filename(target).unwrap())
@@ -168,7 +233,41 @@ fn build_loaded_file<'a>(
 
     // For example, if we're loading the platform from a URL, it's automatically prebuilt
     // even if the --prebuilt-platform=true CLI flag wasn't set.
-    let is_platform_prebuilt = prebuilt_requested || loaded.uses_prebuilt_platform;
+    let is_platform_prebuilt = loaded.uses_prebuilt_platform
+        || match prebuilt_requested {
+            PrebuiltPlatform::Yes => true,
+            PrebuiltPlatform::No => false,
+            PrebuiltPlatform::IfSourcesChanged => {
+                !host_sources_changed(&platform_main_roc, &preprocessed_host_path)
+            }
+        };
+    let platform_allocator = match allocator_override {
+        None => loaded.platform_allocator,
+        Some(_) if !loaded.platform_allocator.host_fns().is_empty() => {
+            eprintln!(
+                "The --{} flag only applies to platforms that ask Roc to provide their allocator, but this platform's header has `allocator {{ strategy: {} }}`.",
+                crate::FLAG_ALLOCATOR,
+                loaded.platform_allocator.strategy.as_str()
+            );
+            std::process::exit(1);
+        }
+        Some(strategy) => PlatformAllocator {
+            strategy,
+            ..loaded.platform_allocator
+        },
+    };
+
+    let platform_effects = loaded.platform_effects;
+
+    if platform_effects.is_batched()
+        && !matches!(code_gen_options.backend, program::CodeGenBackend::Llvm)
+    {
+        eprintln!(
+            "This platform's header has `effects {{ submit: batch }}`, but only the LLVM backend can run effects in batches so far, so it can't be built with --{}.",
+            crate::FLAG_DEV
+        );
+        std::process::exit(1);
+    }
 
     let cwd = app_module_path.parent().unwrap();
     let mut output_exe_path = cwd.join(&*loaded.output_path);
@@ -181,12 +280,37 @@ fn build_loaded_file<'a>(
     let rebuild_thread = if matches!(link_type, LinkType::Dylib | LinkType::None) {
         None
     } else if is_platform_prebuilt {
-        if !preprocessed_host_path.exists() {
-            invalid_prebuilt_platform(prebuilt_requested, preprocessed_host_path);
+        if static_link && !preprocessed_host_path.exists() {
+            eprintln!(
+                "--{} needs this platform's host built against musl, at {}, but the platform is prebuilt and doesn't include one.",
+                crate::FLAG_STATIC,
+                preprocessed_host_path.display()
+            );
 
             std::process::exit(1);
         }
 
+        if !preprocessed_host_path.exists() {
+            if loaded.platform_hosts.is_empty() {
+                invalid_prebuilt_platform(prebuilt_requested, preprocessed_host_path);
+
+                std::process::exit(1);
+            }
+
+            let downloaded = download_prebuilt_host(
+                target,
+                &preprocessed_host_path,
+                loaded.platform_hosts,
+                roc_cache_dir,
+            );
+
+            if let Err(message) = downloaded {
+                eprintln!("{message}");
+
+                std::process::exit(1);
+            }
+        }
+
         if linking_strategy == LinkingStrategy::Surgical {
             // Copy preprocessed host to executable location.
             // The surgical linker will modify that copy in-place.
@@ -212,21 +336,33 @@ fn build_loaded_file<'a>(
             .closure_types
             .iter()
             .map(|x| {
-                format!(
-                    "{}_{}",
+                mangle::host_closure_type_name(
                     x.module_string(&loaded.interns),
-                    x.as_str(&loaded.interns)
+                    x.as_str(&loaded.interns),
                 )
             })
             .collect();
 
+        if static_link
+            && (loaded.platform_build_command.is_some()
+                || !can_rebuild_host_for_musl(&platform_main_roc))
+        {
+            eprintln!(
+                "--{} needs to build this platform's host against musl, which Roc can only do for hosts written in Zig or C, without a build command in the platform's header.",
+                crate::FLAG_STATIC
+            );
+
+            std::process::exit(1);
+        }
+
         let join_handle = spawn_rebuild_thread(
             code_gen_options.opt_level,
             linking_strategy,
+            loaded.platform_build_command.map(String::from),
             platform_main_roc.clone(),
             preprocessed_host_path.clone(),
             output_exe_path.clone(),
-            target,
+            &host_target,
             exposed_values,
             exposed_closure_types,
         );
@@ -262,7 +398,8 @@ fn build_loaded_file<'a>(
     // This only needs to be mutable for report_problems. This can't be done
     // inside a nested scope without causing a borrow error!
     let mut loaded = loaded;
-    let problems = program::report_problems_monomorphized(&mut loaded);
+    let problems = program::report_problems_monomorphized(&mut loaded, warning_levels);
+    loaded.platform_allocator = platform_allocator;
     let loaded = loaded;
 
     enum HostRebuildTiming {
@@ -297,8 +434,10 @@ fn build_loaded_file<'a>(
         &app_module_path,
         target,
         code_gen_options,
+        linking_strategy,
         &preprocessed_host_path,
         wasm_dev_stack_bytes,
+        wasm_dev_features,
     );
 
     buf.push('\n');
@@ -342,19 +481,50 @@ fn build_loaded_file<'a>(
     // Step 2: link the prebuilt platform and compiled app
     let link_start = Instant::now();
 
-    match (linking_strategy, link_type) {
+    let link_stats = match (linking_strategy, link_type) {
         (LinkingStrategy::Surgical, _) => {
-            roc_linker::link_preprocessed_host(
+            if let Some(warning) =
+                roc_linker::check_platform_oom_handler(target, &platform_main_roc)
+            {
+                eprintln!("{warning}\n");
+            }
+
+            let checked = roc_linker::check_platform_allocator(
+                target,
+                &platform_main_roc,
+                platform_allocator,
+            )
+            .and_then(|()| {
+                roc_linker::check_platform_effects(target, &platform_main_roc, platform_effects)
+            })
+            .and_then(|()| {
+                roc_linker::check_app_symbols(target, &platform_main_roc, &roc_app_bytes)
+            });
+
+            if let Err(message) = checked {
+                eprintln!("{message}");
+                std::process::exit(1);
+            }
+
+            match roc_linker::link_preprocessed_host(
                 target,
                 &platform_main_roc,
                 &roc_app_bytes,
                 &output_exe_path,
-            );
+            ) {
+                Ok(link_stats) => Some(link_stats),
+                Err(message) => {
+                    eprintln!("{message}");
+                    std::process::exit(1);
+                }
+            }
         }
         (LinkingStrategy::Additive, _) | (LinkingStrategy::Legacy, LinkType::None) => {
             // Just copy the object file to the output folder.
             output_exe_path.set_extension(operating_system.object_file_ext());
             std::fs::write(&output_exe_path, &*roc_app_bytes).unwrap();
+
+            None
         }
         (LinkingStrategy::Legacy, _) => {
             let app_o_file = tempfile::Builder::new()
@@ -380,8 +550,25 @@ fn build_loaded_file<'a>(
                 inputs.push(builtins_host_tempfile.path().to_str().unwrap());
             }
 
-            let (mut child, _) = link(target, output_exe_path.clone(), &inputs, link_type)
-                .map_err(|_| todo!("gracefully handle `ld` failing to spawn."))?;
+            let mimalloc_path;
+            if platform_allocator.strategy == AllocatorStrategy::Mimalloc {
+                mimalloc_path = match find_mimalloc(target, static_link) {
+                    Some(path) => path,
+                    None => {
+                        eprintln!("This platform uses mimalloc as its allocator, but I couldn't find libmimalloc.");
+                        eprintln!("You may need to install mimalloc (probably called libmimalloc-dev or mimalloc).");
+                        std::process::exit(1);
+                    }
+                };
+                inputs.push(mimalloc_path.to_str().unwrap());
+            }
+
+            let (mut child, _) = if static_link {
+                link_static_linux(target, output_exe_path.clone(), &inputs)
+            } else {
+                link(target, output_exe_path.clone(), &inputs, link_type)
+            }
+            .map_err(|_| todo!("gracefully handle `ld` failing to spawn."))?;
 
             let exit_status = child
                 .wait()
@@ -397,6 +584,22 @@ fn build_loaded_file<'a>(
                     exit_status.code()
                 );
             }
+
+            if static_link {
+                if let Err(message) = verify_static_executable(&output_exe_path) {
+                    eprintln!("{message}");
+                    std::process::exit(1);
+                }
+            }
+
+            None
+        }
+    };
+
+    if split_debuginfo {
+        if let Err(err) = split_debug_info(target, &output_exe_path) {
+            eprintln!("Failed to split the debug info out of the executable: {err}");
+            std::process::exit(1);
         }
     }
 
@@ -404,6 +607,13 @@ fn build_loaded_file<'a>(
 
     if emit_timings {
         println!("Finished linking in {} ms\n", linking_time.as_millis());
+
+        if let Some(stats) = link_stats {
+            let mut buf = String::new();
+            report_link_stats(&mut buf, &stats);
+
+            println!("Here's how long each step of linking took:\n\n{}", buf);
+        }
     }
 
     let total_time = compilation_start.elapsed();
@@ -416,10 +626,13 @@ fn build_loaded_file<'a>(
     })
 }
 
-fn invalid_prebuilt_platform(prebuilt_requested: bool, preprocessed_host_path: PathBuf) {
+fn invalid_prebuilt_platform(
+    prebuilt_requested: PrebuiltPlatform,
+    preprocessed_host_path: PathBuf,
+) {
     let prefix = match prebuilt_requested {
-        true => "Because I was run with --prebuilt-platform=true, ",
-        false => "",
+        PrebuiltPlatform::Yes => "Because I was run with --prebuilt-platform=true, ",
+        PrebuiltPlatform::No | PrebuiltPlatform::IfSourcesChanged => "",
     };
 
     eprintln!(
@@ -439,10 +652,112 @@ fn invalid_prebuilt_platform(prebuilt_requested: bool, preprocessed_host_path: P
     );
 }
 
+/// The names of the files a host prebuilt for `target` can have: the preprocessed host and its
+/// metadata for the surgical linker, the host for the legacy linker, and inline hosted bitcode
+fn prebuilt_host_file_names(target: &Triple) -> Vec<String> {
+    let mut names = vec![roc_linker::metadata_file_name(target)];
+
+    names.extend(preprocessed_host_filename(target).map(|name| name.to_string()));
+    names.extend(legacy_host_filename(target));
+    names.extend(inline_hosted_bitcode_filename(target));
+
+    names
+}
+
+/// Copies the files of a host prebuilt for `target` from `host_dir` into `platform_dir`. Anything
+/// else in `host_dir`, like the platform's own .roc files, is left alone, so it can't overwrite
+/// the platform's files.
+fn copy_prebuilt_host_files(
+    target: &Triple,
+    host_dir: &Path,
+    platform_dir: &Path,
+) -> std::io::Result<()> {
+    for name in prebuilt_host_file_names(target) {
+        let path = host_dir.join(&name);
+
+        if path.is_file() {
+            std::fs::copy(path, platform_dir.join(name))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Downloads the host the platform's header says was prebuilt for `target`, and puts its files
+/// beside the platform's main.roc, where building the host for `target` would have put them.
+fn download_prebuilt_host(
+    target: &Triple,
+    preprocessed_host_path: &Path,
+    hosts: &[PlatformHost<'_>],
+    roc_cache_dir: RocCacheDir<'_>,
+) -> Result<(), String> {
+    use roc_packaging::https::Problem;
+
+    let target_str = match get_target_triple_str(target) {
+        Some(target_str) => target_str,
+        None => {
+            return Err(format!(
+                "This platform's header has prebuilt hosts, but I don't know which of them is for {target}, the target I'm building for, because there are no prebuilt hosts for that target.\n\nIf you have the platform's source code locally, you may be able to build its host by re-running this command with --prebuilt-platform=false",
+            ))
+        }
+    };
+
+    let host = match hosts.iter().find(|host| host.target == target_str) {
+        Some(host) => host,
+        None => {
+            let available: Vec<_> = hosts
+                .iter()
+                .map(|host| format!("    {}", host.target))
+                .collect();
+
+            return Err(format!(
+                "This platform's header has hosts prebuilt for these targets:\n\n{}\n\nbut not for {target_str}. I was expecting either one of those, or for this file to exist:\n\n    {}\n\nIf you have the platform's source code locally, you may be able to generate it by re-running this command with --prebuilt-platform=false",
+                available.join("\n"),
+                preprocessed_host_path.display(),
+            ));
+        }
+    };
+
+    let (host_dir, _) =
+        roc_packaging::cache::install_package(roc_cache_dir, host.url).map_err(|problem| {
+            let reason = match problem {
+                Problem::InvalidContentHash { expected, actual } => format!(
+                    "Its contents have the hash {actual}, but the URL says they should have the hash {expected}."
+                ),
+                problem => format!("{problem:?}"),
+            };
+
+            format!(
+                "I couldn't download the host this platform's header says was prebuilt for {target_str}, from:\n\n    {}\n\n{reason}",
+                host.url
+            )
+        })?;
+
+    let platform_dir = preprocessed_host_path.parent().unwrap();
+
+    copy_prebuilt_host_files(target, &host_dir, platform_dir).map_err(|err| {
+        format!(
+            "I downloaded the host prebuilt for {target_str}, but couldn't copy it into {}: {err}",
+            platform_dir.display()
+        )
+    })?;
+
+    if preprocessed_host_path.exists() {
+        Ok(())
+    } else {
+        Err(format!(
+            "The host this platform's header says was prebuilt for {target_str}, from:\n\n    {}\n\ndoesn't have {} in it.",
+            host.url,
+            preprocessed_host_path.file_name().unwrap().to_string_lossy(),
+        ))
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn spawn_rebuild_thread(
     opt_level: OptLevel,
     linking_strategy: LinkingStrategy,
+    build_command: Option<String>,
     platform_main_roc: PathBuf,
     preprocessed_host_path: PathBuf,
     output_exe_path: PathBuf,
@@ -461,8 +776,10 @@ fn spawn_rebuild_thread(
 
         match linking_strategy {
             LinkingStrategy::Additive => {
-                let host_dest = rebuild_host(
+                let host_dest = build_host(
+                    build_command.as_deref(),
                     opt_level,
+                    linking_strategy,
                     &thread_local_target,
                     platform_main_roc.as_path(),
                     None,
@@ -472,6 +789,7 @@ fn spawn_rebuild_thread(
             }
             LinkingStrategy::Surgical => {
                 roc_linker::build_and_preprocess_host(
+                    build_command.as_deref(),
                     opt_level,
                     &thread_local_target,
                     platform_main_roc.as_path(),
@@ -485,8 +803,10 @@ fn spawn_rebuild_thread(
                 std::fs::copy(&preprocessed_host_path, output_exe_path.as_path()).unwrap();
             }
             LinkingStrategy::Legacy => {
-                rebuild_host(
+                build_host(
+                    build_command.as_deref(),
                     opt_level,
+                    linking_strategy,
                     &thread_local_target,
                     platform_main_roc.as_path(),
                     None,
@@ -505,6 +825,10 @@ pub fn check_file<'a>(
     emit_timings: bool,
     roc_cache_dir: RocCacheDir<'_>,
     threading: Threading,
+    warning_levels: &WarningLevels,
+    html_output: Option<&Path>,
+    plugins: Plugins,
+    memory_limit: Option<usize>,
 ) -> Result<(Problems, Duration), LoadingProblem<'a>> {
     let compilation_start = Instant::now();
 
@@ -516,15 +840,20 @@ pub fn check_file<'a>(
 
     let load_config = LoadConfig {
         target_info,
-        // TODO: expose this from CLI?
-        render: RenderTarget::ColorTerminal,
-        palette: DEFAULT_PALETTE,
+        render: terminal_style().target,
+        palette: terminal_style().palette,
         threading,
         exec_mode: ExecutionMode::Check,
+        plugins,
+        memory_limit,
+        ..Default::default()
     };
     let mut loaded =
         roc_load::load_and_typecheck(arena, roc_file_path, roc_cache_dir, load_config)?;
 
+    #[cfg(not(windows))]
+    crate::daemon::record_loaded_modules(loaded.sources.values().map(|(path, _)| path.as_path()));
+
     let buf = &mut String::with_capacity(1024);
 
     let mut it = loaded.timings.iter().peekable();
@@ -568,8 +897,16 @@ pub fn check_file<'a>(
         println!("Finished checking in {} ms\n", compilation_end.as_millis(),);
     }
 
+    if let Some(html_output) = html_output {
+        let page = program::report_problems_html_typechecked(&mut loaded, warning_levels);
+
+        if let Err(err) = std::fs::write(html_output, page) {
+            user_error!("Couldn't write {}: {}", html_output.display(), err);
+        }
+    }
+
     Ok((
-        program::report_problems_typechecked(&mut loaded),
+        program::report_problems_typechecked(&mut loaded, warning_levels),
         compilation_end,
     ))
 }
@@ -586,18 +923,30 @@ pub fn build_str_test<'a>(
         backend: CodeGenBackend::Llvm,
         opt_level: OptLevel::Normal,
         emit_debug_info: false,
+        keep_dbg: false,
+        fast_math: false,
     };
 
     let emit_timings = false;
     let link_type = LinkType::Executable;
     let linking_strategy = LinkingStrategy::Surgical;
     let wasm_dev_stack_bytes = None;
+    let wasm_dev_features = WasmFeatures::default();
 
     let roc_cache_dir = roc_packaging::cache::RocCacheDir::Disallowed;
     let build_ordering = BuildOrdering::AlwaysBuild;
     let threading = Threading::AtMost(2);
+    let keep_dbgs = true;
+    let keep_expects = false;
 
-    let load_config = standard_load_config(&triple, build_ordering, threading);
+    let load_config = standard_load_config(
+        &triple,
+        build_ordering,
+        threading,
+        None,
+        keep_dbgs,
+        keep_expects,
+    );
 
     let compilation_start = std::time::Instant::now();
 
@@ -622,7 +971,109 @@ pub fn build_str_test<'a>(
         linking_strategy,
         assume_prebuild,
         wasm_dev_stack_bytes,
+        wasm_dev_features,
+        None,
+        false,
+        false,
+        roc_cache_dir,
         loaded,
         compilation_start,
+        &WarningLevels::default(),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{copy_prebuilt_host_files, download_prebuilt_host};
+    use roc_packaging::cache::RocCacheDir;
+    use roc_parse::header::PlatformHost;
+    use std::str::FromStr;
+    use target_lexicon::Triple;
+
+    const HOSTS: &[PlatformHost<'static>] = &[PlatformHost {
+        target: "linux-x86_64",
+        url: "https://example.com/linux-x86_64.tar.br",
+    }];
+
+    #[test]
+    fn no_prebuilt_host_for_target() {
+        let target = Triple::from_str("aarch64-apple-darwin").unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let preprocessed_host_path = dir.path().join("macos-arm64.rh1");
+
+        let problem = download_prebuilt_host(
+            &target,
+            &preprocessed_host_path,
+            HOSTS,
+            RocCacheDir::Disallowed,
+        )
+        .unwrap_err();
+
+        assert!(problem.contains("    linux-x86_64"), "{}", problem);
+        assert!(problem.contains("but not for macos-arm64"), "{}", problem);
+    }
+
+    #[test]
+    fn no_prebuilt_hosts_for_unknown_target() {
+        let target = Triple::from_str("riscv64gc-unknown-linux-gnu").unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let preprocessed_host_path = dir.path().join("riscv64.rh1");
+
+        let problem = download_prebuilt_host(
+            &target,
+            &preprocessed_host_path,
+            HOSTS,
+            RocCacheDir::Disallowed,
+        )
+        .unwrap_err();
+
+        assert!(
+            problem.contains("riscv64gc-unknown-linux-gnu"),
+            "{}",
+            problem
+        );
+    }
+
+    #[test]
+    fn only_copies_host_files() {
+        let target = Triple::from_str("x86_64-unknown-linux-gnu").unwrap();
+        let host_dir = tempfile::tempdir().unwrap();
+        let platform_dir = tempfile::tempdir().unwrap();
+
+        for name in [
+            "linux-x86_64.rh1",
+            "metadata_linux-x86_64.rm2",
+            "linux-x86_64.o",
+            "linux-x86_64.inline.bc",
+            "macos-arm64.rh1",
+            "main.roc",
+        ] {
+            std::fs::write(host_dir.path().join(name), "downloaded").unwrap();
+        }
+
+        std::fs::write(platform_dir.path().join("main.roc"), "platform").unwrap();
+
+        copy_prebuilt_host_files(&target, host_dir.path(), platform_dir.path()).unwrap();
+
+        let mut copied: Vec<_> = std::fs::read_dir(platform_dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        copied.sort();
+
+        assert_eq!(
+            copied,
+            [
+                "linux-x86_64.inline.bc",
+                "linux-x86_64.o",
+                "linux-x86_64.rh1",
+                "main.roc",
+                "metadata_linux-x86_64.rm2",
+            ]
+        );
+        assert_eq!(
+            std::fs::read_to_string(platform_dir.path().join("main.roc")).unwrap(),
+            "platform"
+        );
+    }
+}
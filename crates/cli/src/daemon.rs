@@ -0,0 +1,616 @@
+//! `roc --daemon`: a long-running process which `roc check`, `roc build`, and `roc test` hand
+//! their work to whenever it's running, so they don't each start from scratch.
+//!
+//! Each command the daemon is given runs in a fork of it, with the stdin, stdout, and stderr of
+//! the `roc` that asked for it, so it behaves the same as if it had been run directly. The daemon
+//! also remembers the output of each `roc check`, along with the modification times of every
+//! module that check loaded. Until one of those modules changes, the same check is answered from
+//! memory, output and timings included, without loading anything.
+use std::collections::HashMap;
+use std::env;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::mem;
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::{build_app, CMD_BUILD, CMD_CHECK, CMD_TEST, FLAG_OUTPUT_HTML, FLAG_PLUGIN};
+
+/// The commands which are handed to the daemon, when it's running
+const DELEGATED_COMMANDS: &[&str] = &[CMD_CHECK, CMD_BUILD, CMD_TEST];
+
+/// How often the modules of remembered checks are looked at for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Runs a command, given all of its arguments (starting with the name of the program), and
+/// returns its exit code. This is the `roc` binary's own `main`, minus setting up.
+pub type RunCommand = fn(Vec<OsString>) -> io::Result<i32>;
+
+pub fn socket_path() -> PathBuf {
+    roc_packaging::cache::roc_cache_dir().join("daemon.sock")
+}
+
+/// Hands the command this process was started with to the daemon, and returns its exit code, if
+/// the command is one the daemon runs and the daemon is running. Otherwise, returns None, and
+/// the command should be run here.
+pub fn delegate(subcommand: Option<&str>) -> io::Result<Option<i32>> {
+    if !matches!(subcommand, Some(name) if DELEGATED_COMMANDS.contains(&name)) {
+        return Ok(None);
+    }
+
+    let mut stream = match UnixStream::connect(socket_path()) {
+        Ok(stream) => stream,
+        Err(_) => return Ok(None),
+    };
+
+    let request = Request {
+        cwd: env::current_dir()?,
+        args: env::args_os().collect(),
+        env: env::vars_os().collect(),
+        stdout_is_terminal: unsafe { libc::isatty(1) } == 1,
+    };
+
+    send_fds(&stream, &[0, 1, 2])?;
+    request.write(&mut stream)?;
+
+    let mut exit_code = [0; 4];
+
+    match stream.read_exact(&mut exit_code) {
+        Ok(()) => Ok(Some(i32::from_le_bytes(exit_code))),
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => Err(io::Error::new(
+            io::ErrorKind::Other,
+            "the Roc daemon stopped before the command finished",
+        )),
+        Err(err) => Err(err),
+    }
+}
+
+/// Runs the daemon until it gets SIGINT or SIGTERM. Only one daemon runs at a time.
+pub fn serve(run: RunCommand) -> io::Result<i32> {
+    let path = socket_path();
+
+    if UnixStream::connect(&path).is_ok() {
+        eprintln!("The Roc daemon is already running at {}", path.display());
+
+        return Ok(1);
+    }
+
+    // a socket left behind by a daemon which didn't get to clean up after itself
+    let _ = fs::remove_file(&path);
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let listener = UnixListener::bind(&path)?;
+    listener.set_nonblocking(true)?;
+
+    let stop = Arc::new(AtomicBool::new(false));
+    for signal in [signal_hook::consts::SIGINT, signal_hook::consts::SIGTERM] {
+        signal_hook::flag::register(signal, Arc::clone(&stop))?;
+    }
+
+    println!(
+        "The Roc daemon is listening at {}. `roc check`, `roc build`, and `roc test` will use it until it's stopped.",
+        path.display()
+    );
+
+    let mut checks = RememberedChecks::default();
+
+    while !stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                stream.set_nonblocking(false)?;
+
+                if let Err(err) = answer(stream, run, &mut checks) {
+                    eprintln!("The Roc daemon couldn't run a command: {}", err);
+                }
+            }
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                thread::sleep(POLL_INTERVAL);
+                checks.forget_changed();
+            }
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    fs::remove_file(&path)?;
+
+    Ok(0)
+}
+
+/// A command for the daemon to run, as the `roc` which hands it over would have run it
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Request {
+    cwd: PathBuf,
+    args: Vec<OsString>,
+    env: Vec<(OsString, OsString)>,
+    stdout_is_terminal: bool,
+}
+
+impl Request {
+    fn write(&self, stream: &mut UnixStream) -> io::Result<()> {
+        write_bytes(stream, self.cwd.as_os_str().as_bytes())?;
+
+        write_len(stream, self.args.len())?;
+        for arg in self.args.iter() {
+            write_bytes(stream, arg.as_bytes())?;
+        }
+
+        write_len(stream, self.env.len())?;
+        for (key, value) in self.env.iter() {
+            write_bytes(stream, key.as_bytes())?;
+            write_bytes(stream, value.as_bytes())?;
+        }
+
+        stream.write_all(&[self.stdout_is_terminal as u8])
+    }
+
+    fn read(stream: &mut UnixStream) -> io::Result<Self> {
+        let cwd = PathBuf::from(OsString::from_vec(read_bytes(stream)?));
+
+        let args = (0..read_len(stream)?)
+            .map(|_| read_bytes(stream).map(OsString::from_vec))
+            .collect::<io::Result<_>>()?;
+
+        let env = (0..read_len(stream)?)
+            .map(|_| {
+                let key = OsString::from_vec(read_bytes(stream)?);
+                let value = OsString::from_vec(read_bytes(stream)?);
+
+                Ok((key, value))
+            })
+            .collect::<io::Result<_>>()?;
+
+        let mut stdout_is_terminal = [0];
+        stream.read_exact(&mut stdout_is_terminal)?;
+
+        Ok(Self {
+            cwd,
+            args,
+            env,
+            stdout_is_terminal: stdout_is_terminal[0] != 0,
+        })
+    }
+
+    /// Whether the output of this command can be remembered, and given again, until one of the
+    /// modules it loaded changes
+    fn is_rememberable(&self) -> bool {
+        match build_app().try_get_matches_from(&self.args) {
+            Ok(matches) => match matches.subcommand() {
+                Some((CMD_CHECK, matches)) => {
+                    // these depend on more than the modules, or do more than print
+                    ![FLAG_OUTPUT_HTML, FLAG_PLUGIN]
+                        .iter()
+                        .any(|flag| matches.is_present(flag))
+                }
+                _ => false,
+            },
+            Err(_) => false,
+        }
+    }
+}
+
+fn write_len(stream: &mut UnixStream, len: usize) -> io::Result<()> {
+    stream.write_all(&(len as u32).to_le_bytes())
+}
+
+fn read_len(stream: &mut UnixStream) -> io::Result<usize> {
+    let mut len = [0; 4];
+    stream.read_exact(&mut len)?;
+
+    Ok(u32::from_le_bytes(len) as usize)
+}
+
+fn write_bytes(stream: &mut UnixStream, bytes: &[u8]) -> io::Result<()> {
+    write_len(stream, bytes.len())?;
+    stream.write_all(bytes)
+}
+
+fn read_bytes(stream: &mut UnixStream) -> io::Result<Vec<u8>> {
+    let mut bytes = vec![0; read_len(stream)?];
+    stream.read_exact(&mut bytes)?;
+
+    Ok(bytes)
+}
+
+/// Modification times of the modules a check loaded
+type Snapshot = HashMap<PathBuf, Option<SystemTime>>;
+
+fn snapshot(paths: impl IntoIterator<Item = PathBuf>) -> Snapshot {
+    paths
+        .into_iter()
+        .map(|path| {
+            let modified = path.metadata().and_then(|meta| meta.modified()).ok();
+
+            (path, modified)
+        })
+        .collect()
+}
+
+struct RememberedCheck {
+    modules: Snapshot,
+    output: Vec<u8>,
+    exit_code: i32,
+}
+
+#[derive(Default)]
+struct RememberedChecks(HashMap<Request, RememberedCheck>);
+
+impl RememberedChecks {
+    /// Forgets every check which loaded a module that has changed since
+    fn forget_changed(&mut self) {
+        self.0
+            .retain(|_, check| snapshot(check.modules.keys().cloned()) == check.modules);
+    }
+}
+
+fn answer(
+    mut stream: UnixStream,
+    run: RunCommand,
+    checks: &mut RememberedChecks,
+) -> io::Result<()> {
+    let [stdin, mut stdout, stderr] = recv_fds(&stream)?;
+    let request = Request::read(&mut stream)?;
+
+    checks.forget_changed();
+
+    let exit_code = match checks.0.get(&request) {
+        Some(check) => {
+            stdout.write_all(&check.output)?;
+
+            check.exit_code
+        }
+        None if request.is_rememberable() => {
+            let mut output = Vec::new();
+            let (exit_code, modules) = run_in_fork(
+                &request,
+                run,
+                [stdin, stdout.try_clone()?, stderr],
+                Some((&mut stdout, &mut output)),
+            )?;
+
+            if let Some(modules) = modules {
+                let check = RememberedCheck {
+                    modules: snapshot(modules),
+                    output,
+                    exit_code,
+                };
+
+                checks.0.insert(request, check);
+            }
+
+            exit_code
+        }
+        None => run_in_fork(&request, run, [stdin, stdout, stderr], None)?.0,
+    };
+
+    stream.write_all(&exit_code.to_le_bytes())
+}
+
+/// The modules the command run by this process has loaded, when it's a check run by the daemon
+static LOADED_MODULES: Mutex<Option<Vec<PathBuf>>> = Mutex::new(None);
+
+/// Tells the daemon which modules a check loaded, so it knows when the check's output is out of
+/// date. Does nothing outside of the daemon.
+pub(crate) fn record_loaded_modules<'a>(paths: impl IntoIterator<Item = &'a Path>) {
+    if let Some(modules) = LOADED_MODULES.lock().unwrap().as_mut() {
+        modules.extend(paths.into_iter().map(Path::to_path_buf));
+    }
+}
+
+/// Runs `request` in a fork of this process, with `fds` as its stdin, stdout, and stderr, and
+/// returns its exit code. With `capture`, its stdout is also copied into a buffer, and the
+/// modules it loaded are returned, if it recorded them.
+fn run_in_fork(
+    request: &Request,
+    run: RunCommand,
+    fds: [File; 3],
+    capture: Option<(&mut File, &mut Vec<u8>)>,
+) -> io::Result<(i32, Option<Vec<PathBuf>>)> {
+    let pipes = match capture {
+        Some(_) => Some((pipe()?, pipe()?)),
+        None => None,
+    };
+
+    match unsafe { libc::fork() } {
+        -1 => Err(io::Error::last_os_error()),
+        0 => {
+            let stdout = match &pipes {
+                Some(((_, output), _)) => output.as_raw_fd(),
+                None => fds[1].as_raw_fd(),
+            };
+
+            unsafe {
+                libc::dup2(fds[0].as_raw_fd(), 0);
+                libc::dup2(stdout, 1);
+                libc::dup2(fds[2].as_raw_fd(), 2);
+            }
+
+            let modules = pipes.map(|((output_reader, output), (modules_reader, modules))| {
+                drop((output_reader, output, modules_reader));
+                *LOADED_MODULES.lock().unwrap() = Some(Vec::new());
+
+                modules
+            });
+
+            process::exit(run_here(request, run, modules))
+        }
+        pid => {
+            drop(fds);
+
+            let modules = match (capture, pipes) {
+                (
+                    Some((stdout, buf)),
+                    Some(((mut output_reader, output), (mut modules_reader, modules))),
+                ) => {
+                    drop((output, modules));
+
+                    let mut chunk = [0; 8192];
+                    loop {
+                        match output_reader.read(&mut chunk)? {
+                            0 => break,
+                            len => {
+                                stdout.write_all(&chunk[..len])?;
+                                buf.extend_from_slice(&chunk[..len]);
+                            }
+                        }
+                    }
+
+                    let mut listed = Vec::new();
+                    modules_reader.read_to_end(&mut listed)?;
+
+                    // The list ends with an empty line, so a command which stopped partway through
+                    // listing its modules doesn't look like one which loaded fewer of them.
+                    match listed.strip_suffix(b"\n\n") {
+                        Some(listed) => Some(
+                            listed
+                                .split(|byte| *byte == b'\n')
+                                .map(|path| PathBuf::from(OsString::from_vec(path.to_vec())))
+                                .collect(),
+                        ),
+                        None => None,
+                    }
+                }
+                _ => None,
+            };
+
+            let mut status = 0;
+            if unsafe { libc::waitpid(pid, &mut status, 0) } == -1 {
+                return Err(io::Error::last_os_error());
+            }
+
+            let exit_code = if libc::WIFEXITED(status) {
+                libc::WEXITSTATUS(status)
+            } else {
+                // like a shell reports a process killed by a signal
+                128 + libc::WTERMSIG(status)
+            };
+
+            Ok((exit_code, modules))
+        }
+    }
+}
+
+/// In the fork: runs the command, and returns its exit code
+fn run_here(request: &Request, run: RunCommand, modules: Option<File>) -> i32 {
+    if let Err(err) = env::set_current_dir(&request.cwd) {
+        eprintln!("Couldn't change to {}: {}", request.cwd.display(), err);
+
+        return 1;
+    }
+
+    for (key, _) in env::vars_os() {
+        env::remove_var(key);
+    }
+    for (key, value) in request.env.iter() {
+        env::set_var(key, value);
+    }
+
+    crate::set_stdout_is_terminal(request.stdout_is_terminal);
+
+    let exit_code = match run(request.args.clone()) {
+        Ok(exit_code) => exit_code,
+        Err(err) => {
+            eprintln!("{}", err);
+
+            1
+        }
+    };
+
+    let _ = io::stdout().flush();
+
+    if let (Some(mut modules), Some(loaded)) = (modules, LOADED_MODULES.lock().unwrap().take()) {
+        if !loaded.is_empty() {
+            // so the daemon sees the end of the output before it reads the modules
+            unsafe { libc::close(1) };
+
+            let mut listed = Vec::new();
+            for path in loaded {
+                listed.extend_from_slice(path.as_os_str().as_bytes());
+                listed.push(b'\n');
+            }
+            listed.push(b'\n');
+
+            let _ = modules.write_all(&listed);
+        }
+    }
+
+    exit_code
+}
+
+fn pipe() -> io::Result<(File, File)> {
+    let mut fds = [0; 2];
+
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) })
+}
+
+/// Sends open file descriptors over a unix socket, to be used by the process on the other end
+fn send_fds(stream: &UnixStream, fds: &[RawFd; 3]) -> io::Result<()> {
+    let fds_len = mem::size_of_val(fds) as libc::c_uint;
+    let mut byte = [0u8];
+    let mut iov = libc::iovec {
+        iov_base: byte.as_mut_ptr() as *mut libc::c_void,
+        iov_len: byte.len(),
+    };
+
+    let control_len = unsafe { libc::CMSG_SPACE(fds_len) } as usize;
+    // u64s, for the alignment of the cmsghdr
+    let mut control = vec![0u64; (control_len + 7) / 8];
+
+    let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.as_mut_ptr() as *mut libc::c_void;
+    msg.msg_controllen = control_len as _;
+
+    unsafe {
+        let cmsg = libc::CMSG_FIRSTHDR(&msg);
+        (*cmsg).cmsg_level = libc::SOL_SOCKET;
+        (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+        (*cmsg).cmsg_len = libc::CMSG_LEN(fds_len) as _;
+        ptr::copy_nonoverlapping(fds.as_ptr(), libc::CMSG_DATA(cmsg) as *mut RawFd, fds.len());
+
+        if libc::sendmsg(stream.as_raw_fd(), &msg, 0) == -1 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+
+    Ok(())
+}
+
+/// Receives the file descriptors [send_fds] sent
+fn recv_fds(stream: &UnixStream) -> io::Result<[File; 3]> {
+    let mut fds: [RawFd; 3] = [-1; 3];
+    let fds_len = mem::size_of_val(&fds) as libc::c_uint;
+    let mut byte = [0u8];
+    let mut iov = libc::iovec {
+        iov_base: byte.as_mut_ptr() as *mut libc::c_void,
+        iov_len: byte.len(),
+    };
+
+    let control_len = unsafe { libc::CMSG_SPACE(fds_len) } as usize;
+    let mut control = vec![0u64; (control_len + 7) / 8];
+
+    let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.as_mut_ptr() as *mut libc::c_void;
+    msg.msg_controllen = control_len as _;
+
+    unsafe {
+        if libc::recvmsg(stream.as_raw_fd(), &mut msg, 0) == -1 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let cmsg = libc::CMSG_FIRSTHDR(&msg);
+
+        if cmsg.is_null()
+            || (*cmsg).cmsg_level != libc::SOL_SOCKET
+            || (*cmsg).cmsg_type != libc::SCM_RIGHTS
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "expected the stdin, stdout, and stderr of the command",
+            ));
+        }
+
+        ptr::copy_nonoverlapping(
+            libc::CMSG_DATA(cmsg) as *const RawFd,
+            fds.as_mut_ptr(),
+            fds.len(),
+        );
+
+        Ok(fds.map(|fd| File::from_raw_fd(fd)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(args: &[&str]) -> Request {
+        Request {
+            cwd: PathBuf::from("/home/roc/app"),
+            args: args.iter().map(OsString::from).collect(),
+            env: vec![(OsString::from("HOME"), OsString::from("/home/roc"))],
+            stdout_is_terminal: true,
+        }
+    }
+
+    #[test]
+    fn request_round_trip() {
+        let (mut client, mut daemon) = UnixStream::pair().unwrap();
+        let sent = request(&["roc", "check", "main.roc"]);
+
+        sent.write(&mut client).unwrap();
+
+        assert_eq!(Request::read(&mut daemon).unwrap(), sent);
+    }
+
+    #[test]
+    fn truncated_request() {
+        let (mut client, mut daemon) = UnixStream::pair().unwrap();
+
+        // only the working directory, and then the client goes away
+        write_bytes(&mut client, b"/home/roc/app").unwrap();
+        drop(client);
+
+        let err = Request::read(&mut daemon).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn fds_round_trip() {
+        let (client, daemon) = UnixStream::pair().unwrap();
+        let (mut reader, writer) = pipe().unwrap();
+        let fd = writer.as_raw_fd();
+
+        send_fds(&client, &[fd, fd, fd]).unwrap();
+        drop(writer);
+
+        let [_, mut stdout, _] = recv_fds(&daemon).unwrap();
+        stdout.write_all(b"Hi!").unwrap();
+        drop(stdout);
+
+        let mut received = String::new();
+        reader.read_to_string(&mut received).unwrap();
+
+        assert_eq!(received, "Hi!");
+    }
+
+    #[test]
+    fn missing_fds() {
+        let (mut client, daemon) = UnixStream::pair().unwrap();
+
+        // a client which doesn't speak the protocol
+        client.write_all(b"GET / HTTP/1.1\r\n\r\n").unwrap();
+
+        let err = recv_fds(&daemon).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn rememberable_requests() {
+        assert!(request(&["roc", "check", "main.roc"]).is_rememberable());
+        assert!(!request(&["roc", "check", "--output-html", "report.html"]).is_rememberable());
+        assert!(!request(&["roc", "build", "main.roc"]).is_rememberable());
+        assert!(!request(&["roc", "check", "--not-a-flag"]).is_rememberable());
+    }
+}
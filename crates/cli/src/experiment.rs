@@ -0,0 +1,161 @@
+//! Developer tools behind `roc experiment`, which may change or go away at any time.
+use clap::ArgMatches;
+use std::io;
+
+/// Feeds mutated copies of the .roc files in a directory through parse → format → reparse, and
+/// writes a minimized copy of each input that doesn't round-trip to the output directory.
+#[cfg(feature = "fuzz-parse")]
+pub fn fuzz_parse(matches: &ArgMatches) -> io::Result<i32> {
+    fuzz::fuzz_parse(matches)
+}
+
+/// The fuzzer uses the test helpers of the parser and formatter, which aren't worth shipping in
+/// every build of roc.
+#[cfg(not(feature = "fuzz-parse"))]
+pub fn fuzz_parse(_matches: &ArgMatches) -> io::Result<i32> {
+    eprintln!(
+        "\nThis build of roc doesn't include `roc experiment fuzz-parse`. To use it, build roc with `cargo build --release --features fuzz-parse`.\n"
+    );
+
+    Ok(1)
+}
+
+/// Prints the grammar the parser accepts, as EBNF
+pub fn grammar() -> io::Result<i32> {
+    let grammar = roc_parse::grammar::productions();
+
+    print!("{}", roc_parse::grammar::to_ebnf(&grammar));
+
+    Ok(0)
+}
+
+#[cfg(feature = "fuzz-parse")]
+mod fuzz {
+    use crate::format::flatten_directories;
+    use crate::{FLAG_ITERATIONS, FLAG_OUTPUT, FLAG_SEED, ROC_DIR};
+    use clap::ArgMatches;
+    use std::ffi::OsStr;
+    use std::io;
+    use std::path::{Path, PathBuf};
+    use std::time::{SystemTime, UNIX_EPOCH};
+    use test_syntax::fuzz::{check_round_trip, minimize, Mutator};
+    use test_syntax::test_helpers::Input;
+
+    const DEFAULT_FUZZ_ITERATIONS: usize = 10_000;
+    const DEFAULT_FUZZ_OUTPUT: &str = "fuzz-failures";
+
+    pub fn fuzz_parse(matches: &ArgMatches) -> io::Result<i32> {
+        let corpus_dir = PathBuf::from(matches.value_of_os(ROC_DIR).unwrap());
+        let output_dir = Path::new(
+            matches
+                .value_of_os(FLAG_OUTPUT)
+                .unwrap_or_else(|| OsStr::new(DEFAULT_FUZZ_OUTPUT)),
+        );
+
+        let iterations = match matches.value_of(FLAG_ITERATIONS) {
+            None => DEFAULT_FUZZ_ITERATIONS,
+            Some(value) => match value.parse() {
+                Ok(iterations) => iterations,
+                Err(_) => roc_error_macros::user_error!(
+                    "--{} must be a whole number, but it was {}",
+                    FLAG_ITERATIONS,
+                    value
+                ),
+            },
+        };
+
+        let seed = match matches.value_of(FLAG_SEED) {
+            Some(value) => match value.parse() {
+                Ok(seed) => seed,
+                Err(_) => roc_error_macros::user_error!(
+                    "--{} must be a whole number, but it was {}",
+                    FLAG_SEED,
+                    value
+                ),
+            },
+            None => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|time| time.as_nanos() as u64)
+                .unwrap_or_default(),
+        };
+
+        let mut corpus = Vec::new();
+
+        for path in flatten_directories(vec![corpus_dir.clone()]) {
+            corpus.push(std::fs::read(&path)?);
+        }
+
+        if corpus.is_empty() {
+            eprintln!(
+                "\nThere are no .roc files in {} to start fuzzing from.\n",
+                corpus_dir.display()
+            );
+
+            return Ok(1);
+        }
+
+        println!(
+            "Fuzzing {} files for {} iterations with --{} {}",
+            corpus.len(),
+            iterations,
+            FLAG_SEED,
+            seed
+        );
+
+        let mut mutator = Mutator::new(seed);
+        let mut failures = Vec::new();
+
+        for iteration in 0..iterations {
+            let mutated = mutator.mutate(&corpus[iteration % corpus.len()]);
+
+            let source = match String::from_utf8(mutated) {
+                Ok(source) => source,
+                Err(_) => continue,
+            };
+
+            if check_round_trip(Input::Full(&source)).is_ok() {
+                continue;
+            }
+
+            let minimized = minimize(&source, |candidate| {
+                check_round_trip(Input::Full(candidate)).is_err()
+            });
+
+            // different mutations often shrink to the same problem
+            if failures.contains(&minimized) {
+                continue;
+            }
+
+            failures.push(minimized);
+        }
+
+        if failures.is_empty() {
+            println!("Everything round-tripped.");
+
+            return Ok(0);
+        }
+
+        std::fs::create_dir_all(output_dir)?;
+
+        for (index, failure) in failures.iter().enumerate() {
+            let path = output_dir.join(format!("failure_{}.roc", index));
+            let message = check_round_trip(Input::Full(failure)).unwrap_err();
+
+            std::fs::write(&path, failure)?;
+
+            println!(
+                "\n\x1B[31m{}\x1B[39m\n{}",
+                path.display(),
+                message.lines().next().unwrap_or_default()
+            );
+        }
+
+        println!(
+            "\n{} inputs did not round-trip. Minimized copies are in {}",
+            failures.len(),
+            output_dir.display()
+        );
+
+        Ok(1)
+    }
+}
@@ -0,0 +1,550 @@
+//! `roc imports`, which finds the names a module uses without importing them, suggests which
+//! module in its dependencies to import each one from, and with `--fix` adds those imports,
+//! sorting and deduplicating the imports list along the way.
+use crate::{handle_loading_problem, FLAG_FIX, ROC_FILE};
+use bumpalo::Bump;
+use clap::ArgMatches;
+use roc_fmt::rewrite::{byte_region, Rewriter};
+use roc_load::{ExecutionMode, LoadConfig, LoadedModule, Threading};
+use roc_packaging::cache::{self, RocCacheDir};
+use roc_parse::ast::{Collection, Header, Module, Spaced};
+use roc_parse::header::{ExposedName, ImportsEntry, ModuleName, PackageEntry, To};
+use roc_parse::highlight::{highlight, HighlightToken};
+use roc_parse::state::State;
+use roc_problem::can::{Problem, RuntimeError};
+use roc_region::all::{LineInfo, Loc, Region};
+use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
+use roc_target::TargetInfo;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// One entry of an imports list, like `pf.Task.{ Task, await }`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Import {
+    package: Option<String>,
+    module: String,
+    exposed: Vec<String>,
+}
+
+impl Import {
+    fn sort_key(&self) -> (&str, &str) {
+        (self.package.as_deref().unwrap_or(""), &self.module)
+    }
+
+    fn render(&self) -> String {
+        let mut rendered = match &self.package {
+            Some(package) => format!("{}.{}", package, self.module),
+            None => self.module.clone(),
+        };
+
+        if !self.exposed.is_empty() {
+            rendered.push_str(&format!(".{{ {} }}", self.exposed.join(", ")));
+        }
+
+        rendered
+    }
+}
+
+/// A module which could be imported, along with the names it exposes
+struct Candidate {
+    package: Option<String>,
+    module: String,
+    exposes: Vec<String>,
+}
+
+/// Something the module refers to, but which isn't in scope
+enum Missing {
+    /// A value or type, like the `line` in `line "Hi!"`
+    Name(String),
+    /// A module, like the `Stdout` in `Stdout.line "Hi!"`
+    Module(String),
+}
+
+fn unspaced<'a, T>(spaced: &'a Spaced<'a, T>) -> &'a T {
+    match spaced {
+        Spaced::Item(item) => item,
+        Spaced::SpaceBefore(inner, _) | Spaced::SpaceAfter(inner, _) => unspaced(inner),
+    }
+}
+
+fn exposed_names(exposed: &Collection<Loc<Spaced<ExposedName>>>) -> Vec<String> {
+    exposed
+        .items
+        .iter()
+        .map(|name| unspaced(&name.value).as_str().to_string())
+        .collect()
+}
+
+fn module_names(exposed: &Collection<Loc<Spaced<ModuleName>>>) -> Vec<String> {
+    exposed
+        .items
+        .iter()
+        .map(|name| unspaced(&name.value).as_str().to_string())
+        .collect()
+}
+
+fn to_import(entry: &ImportsEntry) -> Import {
+    let (package, module, exposed) = match entry {
+        ImportsEntry::Module(module, exposed) => (None, module, exposed),
+        ImportsEntry::Package(package, module, exposed) => {
+            (Some(package.to_string()), module, exposed)
+        }
+    };
+
+    Import {
+        package,
+        module: module.as_str().to_string(),
+        exposed: exposed_names(exposed),
+    }
+}
+
+/// Merges imports of the same module, and sorts both the imports and what each one exposes
+fn organize(imports: Vec<Import>) -> Vec<Import> {
+    let mut organized: Vec<Import> = Vec::with_capacity(imports.len());
+
+    for import in imports {
+        match organized
+            .iter_mut()
+            .find(|existing| existing.sort_key() == import.sort_key())
+        {
+            Some(existing) => existing.exposed.extend(import.exposed),
+            None => organized.push(import),
+        }
+    }
+
+    for import in organized.iter_mut() {
+        import.exposed.sort();
+        import.exposed.dedup();
+    }
+
+    organized.sort_by(|a, b| a.sort_key().cmp(&b.sort_key()));
+
+    organized
+}
+
+/// The range of the `[ ... ]` after the header's `imports` keyword, including the brackets
+fn imports_list_range(source: &str, header_end: usize) -> Option<Range<usize>> {
+    let tokens = highlight(&source[..header_end]);
+    let text = |token: &Loc<HighlightToken>| {
+        &source[token.region.start().offset as usize..token.region.end().offset as usize]
+    };
+
+    let keyword = tokens.iter().position(|token| {
+        token.value == HighlightToken::HeaderKeyword && text(token) == "imports"
+    })?;
+
+    let open = tokens.get(keyword + 1).filter(|token| text(token) == "[")?;
+    let close = tokens[keyword + 1..]
+        .iter()
+        .find(|token| text(token) == "]")?;
+
+    Some(open.region.start().offset as usize..close.region.end().offset as usize)
+}
+
+/// Renders an imports list, keeping it on one line if it was on one line before. A list on several
+/// lines is rendered as if it started in the first column.
+fn render_imports_list(source: &str, list_range: &Range<usize>, imports: &[Import]) -> String {
+    let entries: Vec<String> = imports.iter().map(Import::render).collect();
+
+    if !source[list_range.clone()].contains('\n') || entries.is_empty() {
+        return format!("[{}]", entries.join(", "));
+    }
+
+    let mut rendered = String::from("[\n");
+
+    for entry in entries {
+        rendered.push_str(&format!("    {},\n", entry));
+    }
+
+    rendered.push(']');
+
+    rendered
+}
+
+/// Finds the main .roc file of each package in the header. Packages given by a path are found
+/// relative to the module; packages downloaded from a URL are found through the modules the
+/// loader already loaded from them.
+fn package_main_files(
+    loaded: &LoadedModule,
+    home_dir: &Path,
+    packages: &[&PackageEntry],
+    platform_shorthand: Option<&str>,
+    imports: &[Import],
+) -> Vec<(String, PathBuf)> {
+    let mut main_files = Vec::new();
+
+    for entry in packages {
+        let package_path = entry.package_name.value.to_str();
+
+        let main_file = if !package_path.contains("://") {
+            Some(home_dir.join(package_path))
+        } else if Some(entry.shorthand) == platform_shorthand {
+            loaded
+                .sources
+                .values()
+                .find(|(_, source)| is_platform(source))
+                .map(|(path, _)| path.clone())
+        } else {
+            // `pf.Foo.Bar` lives in `Foo/Bar.roc` under the package's root
+            imports
+                .iter()
+                .filter(|import| import.package.as_deref() == Some(entry.shorthand))
+                .find_map(|import| {
+                    let (path, _) = loaded.sources.iter().find_map(|(module_id, entry)| {
+                        (loaded.interns.module_name(*module_id).as_str() == import.module)
+                            .then_some(entry)
+                    })?;
+
+                    path.ancestors()
+                        .nth(import.module.split('.').count())
+                        .map(|root| root.join("main.roc"))
+                })
+        };
+
+        if let Some(main_file) = main_file {
+            main_files.push((entry.shorthand.to_string(), main_file));
+        }
+    }
+
+    // the most specific package wins, in case one package lives inside another's directory
+    main_files.sort_by_key(|(_, main_file)| std::cmp::Reverse(main_file.components().count()));
+
+    main_files
+}
+
+fn is_platform(source: &str) -> bool {
+    let arena = Bump::new();
+
+    matches!(
+        roc_parse::module::parse_header(&arena, State::new(source.as_bytes())),
+        Ok((
+            Module {
+                header: Header::Platform(_),
+                ..
+            },
+            _
+        ))
+    )
+}
+
+/// What an interface module's header exposes, or nothing if it isn't an interface module
+fn interface_exposes(source: &str) -> Option<Vec<String>> {
+    let arena = Bump::new();
+
+    match roc_parse::module::parse_header(&arena, State::new(source.as_bytes())) {
+        Ok((module, _)) => match module.header {
+            Header::Interface(header) => Some(exposed_names(&header.exposes.item)),
+            _ => None,
+        },
+        Err(_) => None,
+    }
+}
+
+/// The modules the home module could import: the interface modules which were loaded, and the
+/// modules each package exposes.
+fn candidates(
+    loaded: &LoadedModule,
+    home_dir: &Path,
+    package_main_files: &[(String, PathBuf)],
+) -> Vec<Candidate> {
+    let root = |main_file: &Path| main_file.parent().unwrap_or(main_file).to_path_buf();
+
+    let package_of = |path: &Path| -> Option<Option<String>> {
+        let package = package_main_files
+            .iter()
+            .find(|(_, main_file)| path.starts_with(root(main_file)));
+
+        match package {
+            Some((shorthand, _)) => Some(Some(shorthand.clone())),
+            None if path.starts_with(home_dir) => Some(None),
+            None => None,
+        }
+    };
+
+    let mut candidates = Vec::new();
+
+    for (module_id, (path, source)) in loaded.sources.iter() {
+        if *module_id == loaded.module_id || module_id.is_builtin() {
+            continue;
+        }
+
+        if let (Some(package), Some(exposes)) = (package_of(path), interface_exposes(source)) {
+            candidates.push(Candidate {
+                package,
+                module: loaded.interns.module_name(*module_id).to_string(),
+                exposes,
+            });
+        }
+    }
+
+    for (shorthand, main_file) in package_main_files {
+        let main_source = match std::fs::read_to_string(main_file) {
+            Ok(source) => source,
+            Err(_) => continue,
+        };
+
+        let arena = Bump::new();
+        let exposed_modules =
+            match roc_parse::module::parse_header(&arena, State::new(main_source.as_bytes())) {
+                Ok((module, _)) => match module.header {
+                    Header::Platform(header) => module_names(&header.exposes.item),
+                    Header::Package(header) => module_names(&header.exposes.item),
+                    _ => continue,
+                },
+                Err(_) => continue,
+            };
+
+        for module in exposed_modules {
+            let already_loaded = candidates.iter().any(|candidate| {
+                candidate.package.as_deref() == Some(shorthand) && candidate.module == module
+            });
+
+            if already_loaded {
+                continue;
+            }
+
+            let path = module
+                .split('.')
+                .fold(root(main_file), |path, part| path.join(part))
+                .with_extension("roc");
+
+            let exposes = std::fs::read_to_string(path)
+                .ok()
+                .and_then(|source| interface_exposes(&source));
+
+            if let Some(exposes) = exposes {
+                candidates.push(Candidate {
+                    package: Some(shorthand.clone()),
+                    module,
+                    exposes,
+                });
+            }
+        }
+    }
+
+    candidates
+        .sort_by(|a, b| (a.package.as_deref(), &a.module).cmp(&(b.package.as_deref(), &b.module)));
+
+    candidates
+}
+
+/// The imports which would bring a missing name or module into scope
+fn suggestions(missing: &Missing, candidates: &[Candidate]) -> Vec<Import> {
+    match missing {
+        Missing::Name(name) => candidates
+            .iter()
+            .filter(|candidate| candidate.exposes.contains(name))
+            .map(|candidate| Import {
+                package: candidate.package.clone(),
+                module: candidate.module.clone(),
+                exposed: vec![name.clone()],
+            })
+            .collect(),
+        Missing::Module(module) => candidates
+            .iter()
+            .filter(|candidate| &candidate.module == module)
+            .map(|candidate| Import {
+                package: candidate.package.clone(),
+                module: candidate.module.clone(),
+                exposed: Vec::new(),
+            })
+            .collect(),
+    }
+}
+
+pub fn imports(matches: &ArgMatches) -> io::Result<i32> {
+    let arena = Bump::new();
+    let path = Path::new(matches.value_of_os(ROC_FILE).unwrap());
+    let fix = matches.is_present(FLAG_FIX);
+
+    let load_config = LoadConfig {
+        target_info: TargetInfo::default_x86_64(),
+        render: RenderTarget::ColorTerminal,
+        palette: DEFAULT_PALETTE,
+        threading: Threading::AllAvailable,
+        exec_mode: ExecutionMode::Check,
+        ..Default::default()
+    };
+    let loaded = match roc_load::load_and_typecheck(
+        &arena,
+        path.to_path_buf(),
+        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+        load_config,
+    ) {
+        Ok(loaded) => loaded,
+        Err(problem) => return handle_loading_problem(problem),
+    };
+
+    let home = loaded.module_id;
+    let (home_path, source) = &loaded.sources[&home];
+    let home_dir = home_path.parent().unwrap_or_else(|| Path::new("."));
+
+    let header_arena = Bump::new();
+    let (module, header_end) = match roc_parse::module::parse_header(
+        &header_arena,
+        State::new(source.as_bytes()),
+    ) {
+        Ok((module, state)) => (module, state.pos().offset as usize),
+        Err(_) => {
+            eprintln!("{}'s header doesn't parse, so its imports can't be organized. `roc check` will say what's wrong with it.", home_path.display());
+
+            return Ok(1);
+        }
+    };
+
+    let (entries, packages, platform_shorthand) = match &module.header {
+        Header::App(header) => (
+            header.imports.as_ref().map(|imports| imports.item),
+            header.packages.as_ref().map(|packages| packages.item),
+            match header.provides.to.value {
+                To::ExistingPackage(shorthand) => Some(shorthand),
+                To::NewPackage(_) => None,
+            },
+        ),
+        Header::Interface(header) => (Some(header.imports.item), None, None),
+        Header::Hosted(header) => (Some(header.imports.item), None, None),
+        Header::Platform(header) => (Some(header.imports.item), Some(header.packages.item), None),
+        Header::Package(_) => {
+            eprintln!("Package modules don't have imports.");
+
+            return Ok(1);
+        }
+    };
+
+    let existing: Vec<Import> = entries.map_or_else(Vec::new, |entries| {
+        entries
+            .items
+            .iter()
+            .map(|entry| to_import(unspaced(&entry.value)))
+            .collect()
+    });
+    let packages: Vec<&PackageEntry> = packages.map_or_else(Vec::new, |packages| {
+        packages
+            .items
+            .iter()
+            .map(|entry| unspaced(&entry.value))
+            .collect()
+    });
+
+    let main_files =
+        package_main_files(&loaded, home_dir, &packages, platform_shorthand, &existing);
+    let candidates = candidates(&loaded, home_dir, &main_files);
+
+    let mut missing: Vec<(Missing, Region)> = Vec::new();
+
+    for problem in loaded.can_problems.get(&home).into_iter().flatten() {
+        let (name, region) = match problem {
+            Problem::RuntimeError(RuntimeError::LookupNotInScope(loc_ident, _)) => (
+                Missing::Name(loc_ident.value.as_str().to_string()),
+                loc_ident.region,
+            ),
+            Problem::RuntimeError(RuntimeError::ModuleNotImported {
+                module_name,
+                region,
+                ..
+            }) => (Missing::Module(module_name.as_str().to_string()), *region),
+            _ => continue,
+        };
+
+        missing.push((name, region));
+    }
+
+    let line_info = LineInfo::new(source);
+    let mut additions = Vec::new();
+    let mut unresolved = 0;
+
+    for (name, region) in missing.iter() {
+        let position = line_info.convert_pos(region.start());
+        let described = match name {
+            Missing::Name(name) => format!("`{}` isn't defined or imported", name),
+            Missing::Module(module) => format!("The {} module isn't imported", module),
+        };
+
+        let location = format!(
+            "{}:{}:{}",
+            home_path.display(),
+            position.line + 1,
+            position.column + 1
+        );
+
+        match suggestions(name, &candidates).as_slice() {
+            [] => {
+                println!(
+                    "{}: {}, and nothing in this module's dependencies exposes it.",
+                    location, described
+                );
+
+                unresolved += 1;
+            }
+            [only] => {
+                println!("{}: {}. Import {}", location, described, only.render());
+
+                additions.push(only.clone());
+            }
+            several => {
+                let rendered: Vec<String> = several.iter().map(Import::render).collect();
+
+                println!(
+                    "{}: {}. It could be imported as any of: {}",
+                    location,
+                    described,
+                    rendered.join(", ")
+                );
+
+                unresolved += 1;
+            }
+        }
+    }
+
+    let list_range = imports_list_range(source, header_end);
+    let organized = organize(existing.iter().cloned().chain(additions).collect());
+
+    let rewritten = match &list_range {
+        Some(range) => {
+            let list = render_imports_list(source, range, &organized);
+
+            if source[range.clone()].contains('#') {
+                eprintln!("The imports list has comments in it, so it's left as is.");
+
+                None
+            } else {
+                let mut rewriter = Rewriter::new(source);
+                rewriter.replace_indented(byte_region(range.clone()), list);
+
+                let rewritten = rewriter
+                    .apply()
+                    .map_err(|overlap| io::Error::new(io::ErrorKind::InvalidData, overlap))?;
+
+                Some(rewritten).filter(|rewritten| rewritten[..] != source[..])
+            }
+        }
+        None if organized.is_empty() => None,
+        None => {
+            eprintln!("This module's header has no imports list to add imports to.");
+
+            return Ok(1);
+        }
+    };
+
+    match rewritten {
+        Some(rewritten) if fix => {
+            std::fs::write(home_path, rewritten)?;
+
+            println!("\nUpdated the imports of {}.", home_path.display());
+        }
+        Some(_) => {
+            println!(
+                "\nThe imports of {} can be organized; run again with --{} to do it.",
+                home_path.display(),
+                FLAG_FIX
+            );
+
+            return Ok(1);
+        }
+        None if missing.is_empty() => println!("Nothing to import."),
+        None => {}
+    }
+
+    Ok((unresolved > 0) as i32)
+}
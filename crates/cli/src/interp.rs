@@ -0,0 +1,129 @@
+//! Runs an app's `main` with the mono interpreter, for `roc run --interp`.
+use crate::{
+    handle_error_module, handle_loading_problem, memory_limit, FLAG_INTERP, FLAG_MAX_THREADS,
+    ROC_FILE,
+};
+use bumpalo::Bump;
+use clap::ArgMatches;
+use roc_build::program::report_problems_monomorphized;
+use roc_load::{EntryPoint, ExecutionMode, LoadConfig, LoadMonomorphizedError, Threading};
+use roc_packaging::cache::{self, RocCacheDir};
+use roc_parse::ast::{Expr, StrLiteral};
+use roc_repl_eval::gen::format_answer;
+use roc_repl_eval::interp::{format_interp_error, interp_to_ast};
+use roc_target::TargetInfo;
+use std::io;
+use std::path::Path;
+use std::time::Instant;
+use target_lexicon::Triple;
+
+/// Interprets `main`, and prints what it evaluates to. A `Str` is printed as-is, like a
+/// program that writes it to stdout would; anything else is printed the way the REPL would.
+///
+/// `main` has to be a plain value, since the interpreter can't call the platform's host.
+pub fn interp(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
+    let start_time = Instant::now();
+    let arena = &Bump::new();
+    let filename = matches.value_of_os(ROC_FILE).unwrap();
+    let path = Path::new(filename);
+
+    if !path.exists() {
+        eprintln!(
+            "\nThis file was not found: {}\n\nYou can run `roc help` for more information on how to provide a .roc file.\n",
+            path.to_string_lossy()
+        );
+
+        return Ok(1);
+    }
+
+    let threading = match matches
+        .value_of(FLAG_MAX_THREADS)
+        .and_then(|s| s.parse::<usize>().ok())
+    {
+        None => Threading::AllAvailable,
+        Some(0) => roc_error_macros::user_error!("cannot build with at most 0 threads"),
+        Some(1) => Threading::Single,
+        Some(n) => Threading::AtMost(n),
+    };
+
+    let warning_levels = crate::warnings::warning_levels(matches, path);
+    let target_info = TargetInfo::from(&triple);
+
+    let load_config = LoadConfig {
+        target_info,
+        render: roc_reporting::cli::terminal_style().target,
+        palette: roc_reporting::cli::terminal_style().palette,
+        threading,
+        exec_mode: ExecutionMode::Executable,
+        memory_limit: memory_limit(matches),
+        ..Default::default()
+    };
+    let load_result = roc_load::load_and_monomorphize(
+        arena,
+        path.to_path_buf(),
+        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+        load_config,
+    );
+
+    let mut loaded = match load_result {
+        Ok(loaded) => loaded,
+        Err(LoadMonomorphizedError::LoadingProblem(problem)) => {
+            return handle_loading_problem(problem);
+        }
+        Err(LoadMonomorphizedError::ErrorModule(module)) => {
+            return handle_error_module(
+                module,
+                start_time.elapsed(),
+                filename,
+                false,
+                &warning_levels,
+            );
+        }
+    };
+
+    // Like `roc run`, run the app even if it has errors; they crash when they're reached.
+    let problems = report_problems_monomorphized(&mut loaded, &warning_levels);
+
+    if problems.errors > 0 || problems.warnings > 0 {
+        problems.print_to_stdout(start_time.elapsed());
+        println!(
+            ".\n\nRunning program anyway…\n\n\x1B[36m{}\x1B[39m",
+            "─".repeat(80)
+        );
+    }
+
+    let takes_arguments = match &loaded.entry_point {
+        EntryPoint::Executable {
+            exposed_to_host, ..
+        } => exposed_to_host
+            .iter()
+            .any(|(_, layout)| !layout.arguments.is_empty()),
+        EntryPoint::Test => unreachable!(),
+    };
+
+    if takes_arguments {
+        eprintln!(
+            "\nThe interpreter can only run a `main` that's a plain value, but this one is a function. Try again without --{FLAG_INTERP}.\n"
+        );
+
+        return Ok(1);
+    }
+
+    match interp_to_ast(arena, loaded, target_info) {
+        Ok(Expr::Str(StrLiteral::PlainLine(string))) => {
+            println!("{string}");
+
+            Ok(0)
+        }
+        Ok(expr) => {
+            println!("{}", format_answer(arena, expr));
+
+            Ok(0)
+        }
+        Err(error) => {
+            eprintln!("\n{}\n", format_interp_error(&error));
+
+            Ok(1)
+        }
+    }
+}
@@ -7,12 +7,15 @@ use build::BuiltFile;
 use bumpalo::Bump;
 use clap::{Arg, ArgMatches, Command, ValueSource};
 use roc_build::link::{LinkType, LinkingStrategy};
-use roc_build::program::{CodeGenBackend, CodeGenOptions};
+use roc_build::program::{CodeGenBackend, CodeGenOptions, WasmFeatures};
 use roc_error_macros::{internal_error, user_error};
 use roc_load::{ExpectMetadata, LoadingProblem, Threading};
 use roc_mono::ir::OptLevel;
 use roc_packaging::cache::RocCacheDir;
 use roc_packaging::tarball::Compression;
+use roc_parse::header::AllocatorStrategy;
+use roc_reporting::cli::WarningLevels;
+use roc_reporting::locale::Locale;
 use std::env;
 use std::ffi::{CString, OsStr};
 use std::io;
@@ -20,6 +23,7 @@ use std::mem::ManuallyDrop;
 use std::os::raw::{c_char, c_int};
 use std::path::{Path, PathBuf};
 use std::process;
+use std::sync::Mutex;
 use std::time::Instant;
 use strum::{EnumIter, IntoEnumIterator, IntoStaticStr};
 use target_lexicon::BinaryFormat;
@@ -29,11 +33,25 @@ use target_lexicon::{
 #[cfg(not(target_os = "linux"))]
 use tempfile::TempDir;
 
+mod bench;
 pub mod build;
+#[cfg(not(windows))]
+pub mod daemon;
+pub mod experiment;
 mod format;
+pub mod imports;
+mod interp;
+pub mod migrate;
+pub mod rename;
+pub mod scaffold;
+pub mod test_filter;
+pub mod warnings;
+pub mod watch;
+pub use bench::bench;
 pub use format::format;
+pub use interp::interp;
 
-use crate::build::{standard_load_config, BuildFileError, BuildOrdering};
+use crate::build::{standard_load_config, BuildFileError, BuildOrdering, PrebuiltPlatform};
 
 const DEFAULT_ROC_FILENAME: &str = "main.roc";
 
@@ -47,28 +65,81 @@ pub const CMD_CHECK: &str = "check";
 pub const CMD_VERSION: &str = "version";
 pub const CMD_FORMAT: &str = "format";
 pub const CMD_TEST: &str = "test";
+pub const CMD_BENCH: &str = "bench";
 pub const CMD_GLUE: &str = "glue";
 pub const CMD_GEN_STUB_LIB: &str = "gen-stub-lib";
+pub const CMD_DEMANGLE: &str = "demangle";
+pub const CMD_EXPLAIN: &str = "explain";
+pub const CMD_EXPERIMENT: &str = "experiment";
+pub const CMD_FUZZ_PARSE: &str = "fuzz-parse";
+pub const CMD_GRAMMAR: &str = "grammar";
+pub const CMD_RENAME: &str = "rename";
+pub const CMD_IMPORTS: &str = "imports";
+pub const CMD_MIGRATE_HEADERS: &str = "migrate-headers";
+pub const CMD_NEW: &str = "new";
+pub const CMD_APP: &str = "app";
+pub const CMD_PLATFORM: &str = "platform";
 
 pub const FLAG_DEBUG: &str = "debug";
+pub const FLAG_KEEP_DBG: &str = "keep-dbg";
+pub const FLAG_FAST_MATH: &str = "fast-math";
+pub const FLAG_KEEP_EXPECTS: &str = "keep-expects";
 pub const FLAG_BUNDLE: &str = "bundle";
+pub const FLAG_STATIC: &str = "static";
+pub const FLAG_SPLIT_DEBUGINFO: &str = "split-debuginfo";
 pub const FLAG_DEV: &str = "dev";
 pub const FLAG_OPTIMIZE: &str = "optimize";
 pub const FLAG_MAX_THREADS: &str = "max-threads";
+pub const FLAG_MAX_MEMORY: &str = "max-memory";
 pub const FLAG_OPT_SIZE: &str = "opt-size";
 pub const FLAG_LIB: &str = "lib";
 pub const FLAG_NO_LINK: &str = "no-link";
 pub const FLAG_TARGET: &str = "target";
 pub const FLAG_TIME: &str = "time";
 pub const FLAG_LINKER: &str = "linker";
+pub const FLAG_ALLOCATOR: &str = "allocator";
 pub const FLAG_PREBUILT: &str = "prebuilt-platform";
 pub const FLAG_CHECK: &str = "check";
 pub const FLAG_WASM_STACK_SIZE_KB: &str = "wasm-stack-size-kb";
+pub const FLAG_WASM_FEATURES: &str = "wasm-features";
+pub const FLAG_WATCH: &str = "watch";
+pub const FLAG_HOT: &str = "hot";
+pub const FLAG_CHECK_EXAMPLES: &str = "check-examples";
+pub const FLAG_JSON: &str = "json";
+pub const FLAG_WARMUP: &str = "warmup";
+pub const FLAG_ITERATIONS: &str = "iterations";
+pub const FLAG_BASELINE: &str = "baseline";
+pub const FLAG_SAVE_BASELINE: &str = "save-baseline";
+pub const FLAG_SEED: &str = "seed";
+pub const FLAG_FILTER: &str = "filter";
+pub const FLAG_CHANGED: &str = "changed";
+pub const FLAG_OUTPUT: &str = "output";
+pub const FLAG_FIX: &str = "fix";
+pub const FLAG_ALLOW: &str = "allow";
+pub const FLAG_WARN: &str = "warn";
+pub const FLAG_DENY: &str = "deny";
+pub const FLAG_EXPLAIN_ERROR: &str = "explain-error";
+pub const FLAG_OUTPUT_HTML: &str = "output-html";
+pub const FLAG_PLUGIN: &str = "plugin";
+pub const FLAG_LOCALE: &str = "locale";
+pub const FLAG_COLOR: &str = "color";
+pub const FLAG_THEME: &str = "theme";
+pub const FLAG_LAYOUTS: &str = "layouts";
+pub const FLAG_DAEMON: &str = "daemon";
+pub const FLAG_LANG: &str = "lang";
+pub const FLAG_PLATFORM: &str = "platform";
+pub const FLAG_INTERP: &str = "interp";
 pub const ROC_FILE: &str = "ROC_FILE";
 pub const ROC_DIR: &str = "ROC_DIR";
 pub const GLUE_FILE: &str = "GLUE_FILE";
+pub const SYMBOL_NAMES: &str = "SYMBOL_NAMES";
+pub const LINE: &str = "LINE";
+pub const COLUMN: &str = "COLUMN";
+pub const NEW_NAME: &str = "NEW_NAME";
+pub const ERROR_CODE: &str = "ERROR_CODE";
 pub const DIRECTORY_OR_FILES: &str = "DIRECTORY_OR_FILES";
 pub const ARGS_FOR_APP: &str = "ARGS_FOR_APP";
+pub const PROJECT_DIR: &str = "PROJECT_DIR";
 
 const VERSION: &str = include_str!("../../../version.txt");
 
@@ -85,11 +156,23 @@ pub fn build_app<'a>() -> Command<'a> {
         .validator(|s| s.parse::<usize>())
         .required(false);
 
+    let flag_max_memory = Arg::new(FLAG_MAX_MEMORY)
+        .long(FLAG_MAX_MEMORY)
+        .help("Stop compiling with an error, rather than being killed, if it uses more than this many megabytes of memory\n(Defaults to the ROC_MAX_MEMORY environment variable, if it's set.)")
+        .takes_value(true)
+        .validator(|s| s.parse::<usize>())
+        .required(false);
+
     let flag_opt_size = Arg::new(FLAG_OPT_SIZE)
         .long(FLAG_OPT_SIZE)
         .help("Optimize the compiled program to have a small binary size\n(Optimization takes time to complete.)")
         .required(false);
 
+    let flag_interp = Arg::new(FLAG_INTERP)
+        .long(FLAG_INTERP)
+        .help("Run the program with the interpreter instead of compiling it; only pure code is supported")
+        .required(false);
+
     let flag_dev = Arg::new(FLAG_DEV)
         .long(FLAG_DEV)
         .help("Make compilation finish as soon as possible, at the expense of runtime performance")
@@ -97,7 +180,22 @@ pub fn build_app<'a>() -> Command<'a> {
 
     let flag_debug = Arg::new(FLAG_DEBUG)
         .long(FLAG_DEBUG)
-        .help("Store LLVM debug information in the generated program")
+        .help("Store LLVM debug information in the generated program\n(With --dev for the wasm32 target, this embeds a source map for browser devtools instead.)")
+        .required(false);
+
+    let flag_keep_dbg = Arg::new(FLAG_KEEP_DBG)
+        .long(FLAG_KEEP_DBG)
+        .help("Keep `dbg`s in the compiled program even with --optimize or --opt-size, which otherwise strip them")
+        .required(false);
+
+    let flag_fast_math = Arg::new(FLAG_FAST_MATH)
+        .long(FLAG_FAST_MATH)
+        .help("Let the optimizer assume F32s and F64s are never NaN or infinite, and reorder their arithmetic\n(Without this, F32 and F64 arithmetic follows IEEE-754 exactly. Only the LLVM backend uses this.)")
+        .required(false);
+
+    let flag_keep_expects = Arg::new(FLAG_KEEP_EXPECTS)
+        .long(FLAG_KEEP_EXPECTS)
+        .help("Crash when any inline `expect` fails, like an `expect-always` does\n(Otherwise, inline `expect`s are only checked in development builds.)")
         .required(false);
 
     let flag_time = Arg::new(FLAG_TIME)
@@ -107,14 +205,20 @@ pub fn build_app<'a>() -> Command<'a> {
 
     let flag_linker = Arg::new(FLAG_LINKER)
         .long(FLAG_LINKER)
-        .help("Set which linker to use\n(The surgical linker is enabled by default only when building for wasm32 or x86_64 Linux, because those are the only targets it currently supports. Otherwise the legacy linker is used by default.)")
+        .help("Set which linker to use\n(The surgical linker is enabled by default only when building for x86_64 Linux, or for wasm32 with --dev, because those are the only targets it currently supports. Other wasm32 builds can opt in with --linker=surgical. Otherwise the legacy linker is used by default.)")
         .possible_values(["surgical", "legacy"])
         .required(false);
 
+    let flag_allocator = Arg::new(FLAG_ALLOCATOR)
+        .long(FLAG_ALLOCATOR)
+        .help("Set which allocator Roc uses when the platform asks Roc to provide one\n(This overrides `allocator { strategy: roc }` or `allocator { strategy: mimalloc }` in the platform header. `roc` uses the libc allocator. mimalloc needs libmimalloc installed and --linker=legacy.)")
+        .possible_values(["roc", "mimalloc"])
+        .required(false);
+
     let flag_prebuilt = Arg::new(FLAG_PREBUILT)
         .long(FLAG_PREBUILT)
-        .help("Assume the platform has been prebuilt and skip rebuilding the platform\n(This is enabled by default when using `roc build` with a --target other than `--target <current machine>`.)")
-        .possible_values(["true", "false"])
+        .help("Assume the platform has been prebuilt and skip rebuilding the platform\n(This is enabled by default when using `roc build` with a --target other than `--target <current machine>`. With `auto`, the platform is rebuilt only when its host's sources are newer than its preprocessed host.)")
+        .possible_values(["true", "false", "auto"])
         .required(false);
 
     let flag_wasm_stack_size_kb = Arg::new(FLAG_WASM_STACK_SIZE_KB)
@@ -124,6 +228,59 @@ pub fn build_app<'a>() -> Command<'a> {
         .validator(|s| s.parse::<u32>())
         .required(false);
 
+    let flag_wasm_features = Arg::new(FLAG_WASM_FEATURES)
+        .long(FLAG_WASM_FEATURES)
+        .help("Comma-separated list of post-MVP Wasm features to use for wasm32 target: bulk-memory, simd128, threads\n(Only engines that support these features will be able to run the output. This only applies when --dev also provided.)")
+        .value_name("FEATURES")
+        .takes_value(true)
+        .validator(WasmFeatures::parse)
+        .required(false);
+
+    let warning_flag = |name| {
+        Arg::new(name)
+            .long(name)
+            .value_name("WARNING")
+            .takes_value(true)
+            .multiple_occurrences(true)
+            .required(false)
+    };
+
+    let flag_allow = warning_flag(FLAG_ALLOW)
+        .help("Don't report this kind of warning, like `unused-import`, or `all` of them\n(This overrides the [warnings] table of the nearest roc.toml.)");
+
+    let flag_warn =
+        warning_flag(FLAG_WARN).help("Report this kind of warning, or `all` of them, as a warning\n(Opt-in lints, like non-camel-case-name, are only reported when named on their own.)");
+
+    let flag_deny =
+        warning_flag(FLAG_DENY).help("Report this kind of warning, or `all` of them, as an error");
+
+    let flag_locale = Arg::new(FLAG_LOCALE)
+        .long(FLAG_LOCALE)
+        .help("The language to report errors and warnings in, like `es`\n(Defaults to the ROC_LANG environment variable, and then to English.)")
+        .takes_value(true)
+        .validator(|tag| {
+            Locale::from_tag(tag).ok_or_else(|| {
+                let tags: Vec<_> = Locale::ALL.iter().map(|locale| locale.tag()).collect();
+
+                format!("the supported locales are {}", tags.join(", "))
+            })
+        })
+        .required(false);
+
+    let flag_color = Arg::new(FLAG_COLOR)
+        .long(FLAG_COLOR)
+        .help("Whether to use colors and links when reporting problems\n(`auto` uses them when printing to a terminal, unless NO_COLOR is set.)")
+        .takes_value(true)
+        .possible_values(["auto", "always", "never"])
+        .required(false);
+
+    let flag_theme = Arg::new(FLAG_THEME)
+        .long(FLAG_THEME)
+        .help("Whether to pick colors for a dark or a light terminal background\n(Defaults to the ROC_THEME environment variable, and then to the background the terminal says it has, if any.)")
+        .takes_value(true)
+        .possible_values(["dark", "light"])
+        .required(false);
+
     let roc_file_to_run = Arg::new(ROC_FILE)
         .help("The .roc file of an app to run")
         .allow_invalid_utf8(true)
@@ -145,13 +302,25 @@ pub fn build_app<'a>() -> Command<'a> {
             .about("Build a binary from the given .roc file, but don't run it")
             .arg(flag_optimize.clone())
             .arg(flag_max_threads.clone())
+            .arg(flag_max_memory.clone())
+            .arg(flag_allow.clone())
+            .arg(flag_warn.clone())
+            .arg(flag_deny.clone())
+            .arg(flag_locale.clone())
+            .arg(flag_color.clone())
+            .arg(flag_theme.clone())
             .arg(flag_opt_size.clone())
             .arg(flag_dev.clone())
             .arg(flag_debug.clone())
+            .arg(flag_keep_dbg.clone())
+            .arg(flag_fast_math.clone())
+            .arg(flag_keep_expects.clone())
             .arg(flag_time.clone())
             .arg(flag_linker.clone())
+            .arg(flag_allocator.clone())
             .arg(flag_prebuilt.clone())
             .arg(flag_wasm_stack_size_kb.clone())
+            .arg(flag_wasm_features.clone())
             .arg(
                 Arg::new(FLAG_TARGET)
                     .long(FLAG_TARGET)
@@ -176,6 +345,20 @@ pub fn build_app<'a>() -> Command<'a> {
                     .possible_values([".tar", ".tar.gz", ".tar.br"])
                     .required(false),
             )
+            .arg(
+                Arg::new(FLAG_STATIC)
+                    .long(FLAG_STATIC)
+                    .help("Link the host, builtins, and libc (musl) statically, so the Linux executable runs on any machine\n(This needs --linker=legacy.)")
+                    .conflicts_with_all(&[FLAG_LIB, FLAG_NO_LINK])
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_SPLIT_DEBUGINFO)
+                    .long(FLAG_SPLIT_DEBUGINFO)
+                    .help("Move debug info out of the executable into a sidecar file, so it stays small but crashes can still be symbolicated\n(This writes a .dSYM bundle on macOS and a .debug file on Linux.)")
+                    .conflicts_with_all(&[FLAG_LIB, FLAG_NO_LINK])
+                    .required(false),
+            )
             .arg(
                 Arg::new(FLAG_NO_LINK)
                     .long(FLAG_NO_LINK)
@@ -194,12 +377,41 @@ pub fn build_app<'a>() -> Command<'a> {
             .about("Run all top-level `expect`s in a main module and any modules it imports")
             .arg(flag_optimize.clone())
             .arg(flag_max_threads.clone())
+            .arg(flag_max_memory.clone())
+            .arg(flag_allow.clone())
+            .arg(flag_warn.clone())
+            .arg(flag_deny.clone())
+            .arg(flag_locale.clone())
+            .arg(flag_color.clone())
+            .arg(flag_theme.clone())
             .arg(flag_opt_size.clone())
             .arg(flag_dev.clone())
             .arg(flag_debug.clone())
             .arg(flag_time.clone())
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
+            .arg(
+                Arg::new(FLAG_SEED)
+                    .long(FLAG_SEED)
+                    .help("The seed for `Random.testSeed`, to reproduce an earlier run (default: a new one each run)")
+                    .takes_value(true)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_FILTER)
+                    .long(FLAG_FILTER)
+                    .value_name("PATTERN")
+                    .help("Only run the `expect`s in modules matching this glob, like `Parser`, or named by a comment matching `Module.name`, like `Parser.*url*`")
+                    .takes_value(true)
+                    .multiple_occurrences(true)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_CHANGED)
+                    .long(FLAG_CHANGED)
+                    .help("Only run the `expect`s in modules whose files (or the files of modules they import) changed since all tests last passed")
+                    .required(false),
+            )
             .arg(
                 Arg::new(ROC_FILE)
                     .help("The .roc file for the main module")
@@ -209,19 +421,80 @@ pub fn build_app<'a>() -> Command<'a> {
             )
             .arg(args_for_app.clone())
         )
+        .subcommand(Command::new(CMD_BENCH)
+            .about("Run all top-level `bench`es in a main module and any modules it imports, with optimizations")
+            .arg(flag_max_threads.clone())
+            .arg(flag_max_memory.clone())
+            .arg(flag_allow.clone())
+            .arg(flag_warn.clone())
+            .arg(flag_deny.clone())
+            .arg(flag_locale.clone())
+            .arg(flag_color.clone())
+            .arg(flag_theme.clone())
+            .arg(
+                Arg::new(FLAG_WARMUP)
+                    .long(FLAG_WARMUP)
+                    .help("How many times to run each benchmark before timing it (default: 10)")
+                    .takes_value(true)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_ITERATIONS)
+                    .long(FLAG_ITERATIONS)
+                    .help("How many timed runs of each benchmark to take (default: 100)")
+                    .takes_value(true)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_BASELINE)
+                    .long(FLAG_BASELINE)
+                    .help("Compare the results against a baseline saved by an earlier `--save-baseline`")
+                    .allow_invalid_utf8(true)
+                    .takes_value(true)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_SAVE_BASELINE)
+                    .long(FLAG_SAVE_BASELINE)
+                    .help("Save the results to this file, to compare against later with `--baseline`")
+                    .allow_invalid_utf8(true)
+                    .takes_value(true)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(ROC_FILE)
+                    .help("The .roc file for the main module")
+                    .allow_invalid_utf8(true)
+                    .required(false)
+                    .default_value(DEFAULT_ROC_FILENAME)
+            )
+        )
         .subcommand(Command::new(CMD_REPL)
             .about("Launch the interactive Read Eval Print Loop (REPL)")
+            .arg(flag_interp.clone())
         )
         .subcommand(Command::new(CMD_RUN)
             .about("Run a .roc file even if it has build errors")
             .arg(flag_optimize.clone())
             .arg(flag_max_threads.clone())
+            .arg(flag_max_memory.clone())
+            .arg(flag_allow.clone())
+            .arg(flag_warn.clone())
+            .arg(flag_deny.clone())
+            .arg(flag_locale.clone())
+            .arg(flag_color.clone())
+            .arg(flag_theme.clone())
             .arg(flag_opt_size.clone())
             .arg(flag_dev.clone())
             .arg(flag_debug.clone())
+            .arg(flag_keep_dbg.clone())
+            .arg(flag_fast_math.clone())
+            .arg(flag_keep_expects.clone())
             .arg(flag_time.clone())
             .arg(flag_linker.clone())
+            .arg(flag_allocator.clone())
             .arg(flag_prebuilt.clone())
+            .arg(flag_interp.clone())
             .arg(roc_file_to_run.clone())
             .arg(args_for_app.clone())
         )
@@ -229,12 +502,36 @@ pub fn build_app<'a>() -> Command<'a> {
             .about("`check` a .roc file, and then run it if there were no errors")
             .arg(flag_optimize.clone())
             .arg(flag_max_threads.clone())
+            .arg(flag_max_memory.clone())
+            .arg(flag_allow.clone())
+            .arg(flag_warn.clone())
+            .arg(flag_deny.clone())
+            .arg(flag_locale.clone())
+            .arg(flag_color.clone())
+            .arg(flag_theme.clone())
             .arg(flag_opt_size.clone())
             .arg(flag_dev.clone())
             .arg(flag_debug.clone())
+            .arg(flag_keep_dbg.clone())
+            .arg(flag_fast_math.clone())
+            .arg(flag_keep_expects.clone())
             .arg(flag_time.clone())
             .arg(flag_linker.clone())
+            .arg(flag_allocator.clone())
             .arg(flag_prebuilt.clone())
+            .arg(
+                Arg::new(FLAG_WATCH)
+                    .long(FLAG_WATCH)
+                    .help("(experimental) Rebuild and restart the app whenever one of its .roc files changes")
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_HOT)
+                    .long(FLAG_HOT)
+                    .help("(unsupported) Patch rebuilt app code into the running host instead of restarting it")
+                    .requires(FLAG_WATCH)
+                    .required(false),
+            )
             .arg(roc_file_to_run.clone())
             .arg(args_for_app.clone())
         )
@@ -259,6 +556,40 @@ pub fn build_app<'a>() -> Command<'a> {
             .about("Check the code for problems, but don’t build or run it")
             .arg(flag_time.clone())
             .arg(flag_max_threads.clone())
+            .arg(flag_max_memory.clone())
+            .arg(flag_allow.clone())
+            .arg(flag_warn.clone())
+            .arg(flag_deny.clone())
+            .arg(flag_locale.clone())
+            .arg(flag_color.clone())
+            .arg(flag_theme.clone())
+            .arg(
+                Arg::new(FLAG_OUTPUT_HTML)
+                    .long(FLAG_OUTPUT_HTML)
+                    .help("Also write the problems found to a standalone HTML page, like report.html\n(It shows both errors and warnings, and can filter them by severity.)")
+                    .takes_value(true)
+                    .value_name("FILE")
+                    .allow_invalid_utf8(true)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_PLUGIN)
+                    .long(FLAG_PLUGIN)
+                    .help("Load an analysis plugin from this shared library, and call it with each module once its types are solved\n(It must export `roc_plugin_module_solved`. This can be given more than once.)")
+                    .takes_value(true)
+                    .value_name("LIBRARY")
+                    .multiple_occurrences(true)
+                    .allow_invalid_utf8(true)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_EXPLAIN_ERROR)
+                    .long(FLAG_EXPLAIN_ERROR)
+                    .help("Print the explanation of an error code, like E0401, instead of checking anything")
+                    .takes_value(true)
+                    .value_name("CODE")
+                    .required(false),
+            )
             .arg(
                 Arg::new(ROC_FILE)
                     .help("The .roc file of an app to check")
@@ -270,6 +601,18 @@ pub fn build_app<'a>() -> Command<'a> {
         .subcommand(
             Command::new(CMD_DOCS)
                 .about("Generate documentation for a Roc package")
+                .arg(
+                    Arg::new(FLAG_CHECK_EXAMPLES)
+                        .long(FLAG_CHECK_EXAMPLES)
+                        .help("Instead of generating docs, check that the ```roc code examples in doc comments compile, and that their top-level expects pass")
+                        .required(false),
+                )
+                .arg(
+                    Arg::new(FLAG_JSON)
+                        .long(FLAG_JSON)
+                        .help("Generate a JSON file per module instead of HTML, for use by other tools")
+                        .required(false),
+                )
                 .arg(Arg::new(ROC_FILE)
                     .multiple_values(true)
                     .help("The package's main .roc file")
@@ -278,6 +621,43 @@ pub fn build_app<'a>() -> Command<'a> {
                     .default_value(DEFAULT_ROC_FILENAME),
                 )
         )
+        .subcommand(Command::new(CMD_NEW)
+            .about("Start a new project from a template")
+            .subcommand_required(true)
+            .subcommand(Command::new(CMD_APP)
+                .about("Create an app which uses the given platform")
+                .arg(
+                    Arg::new(PROJECT_DIR)
+                        .help("The directory to create the app in")
+                        .allow_invalid_utf8(true)
+                        .required(true)
+                )
+                .arg(
+                    Arg::new(FLAG_PLATFORM)
+                        .long(FLAG_PLATFORM)
+                        .help("The path or URL of the platform's main.roc")
+                        .takes_value(true)
+                        .required(true)
+                )
+            )
+            .subcommand(Command::new(CMD_PLATFORM)
+                .about("Create a platform, with its host, and an app which uses it")
+                .arg(
+                    Arg::new(PROJECT_DIR)
+                        .help("The directory to create the platform and app in")
+                        .allow_invalid_utf8(true)
+                        .required(true)
+                )
+                .arg(
+                    Arg::new(FLAG_LANG)
+                        .long(FLAG_LANG)
+                        .help("The language to write the host in")
+                        .takes_value(true)
+                        .possible_values(scaffold::HOST_LANGUAGES)
+                        .required(true)
+                )
+            )
+        )
         .subcommand(Command::new(CMD_GLUE)
             .about("Generate glue code between a platform's Roc API and its host language")
             .arg(
@@ -288,10 +668,18 @@ pub fn build_app<'a>() -> Command<'a> {
             )
             .arg(
                 Arg::new(GLUE_FILE)
-                    .help("The filename for the generated glue code\n(Currently, this must be a .rs file because only Rust glue generation is supported so far.)")
+                    .help("The filename for the generated glue code\n(Currently, this must be a .rs file because only Rust glue generation is supported so far, or a .roc file like EffectDoubles.roc to generate test doubles for the platform's Effect module.)")
                     .allow_invalid_utf8(true)
                     .required(true)
             )
+            .arg(
+                Arg::new(FLAG_LAYOUTS)
+                    .long(FLAG_LAYOUTS)
+                    .help("Instead of glue code, describe the memory layout of each type the platform shares with its host, in this format\n(This includes sizes, alignments, field offsets, tag discriminants, and where to find refcounts, for each target.)")
+                    .takes_value(true)
+                    .possible_values(["json"])
+                    .required(false)
+            )
         )
         .subcommand(Command::new(CMD_GEN_STUB_LIB)
             .about("Generate a stubbed shared library that can be used for linking a platform binary.\nThe stubbed library has prototypes, but no function bodies.\n\nNote: This command will be removed in favor of just using `roc build` once all platforms support the surgical linker")
@@ -312,15 +700,145 @@ pub fn build_app<'a>() -> Command<'a> {
                     .required(false),
             )
         )
+        .subcommand(Command::new(CMD_RENAME)
+            .about("Rename a value everywhere it's used, in the given module and the modules it imports")
+            .arg(
+                Arg::new(ROC_FILE)
+                    .help("The .roc file the value is defined or used in")
+                    .allow_invalid_utf8(true)
+                    .required(true)
+            )
+            .arg(
+                Arg::new(LINE)
+                    .help("The line the value's name is on, starting from 1")
+                    .required(true)
+            )
+            .arg(
+                Arg::new(COLUMN)
+                    .help("A column within the value's name, starting from 1")
+                    .required(true)
+            )
+            .arg(
+                Arg::new(NEW_NAME)
+                    .help("The new name")
+                    .required(true)
+            )
+        )
+        .subcommand(Command::new(CMD_IMPORTS)
+            .about("Suggest imports for the names a module uses but doesn't import, and sort and deduplicate its imports list")
+            .arg(
+                Arg::new(FLAG_FIX)
+                    .long(FLAG_FIX)
+                    .help("Add the suggested imports and organize the imports list, instead of only printing what would change\n(Names which several modules expose are left for you to choose.)")
+                    .required(false),
+            )
+            .arg(
+                Arg::new(ROC_FILE)
+                    .help("The .roc file whose imports to organize")
+                    .allow_invalid_utf8(true)
+                    .required(false)
+                    .default_value(DEFAULT_ROC_FILENAME),
+            )
+        )
+        .subcommand(Command::new(CMD_MIGRATE_HEADERS)
+            .about("Rewrite module headers written in older syntax so they parse again, and give files with no header an interface header")
+            .arg(
+                Arg::new(DIRECTORY_OR_FILES)
+                    .index(1)
+                    .multiple_values(true)
+                    .required(false)
+                    .allow_invalid_utf8(true)
+                    .help("The .roc files to migrate, or directories to search for .roc files\n(Defaults to the current directory.)"),
+            )
+            .arg(
+                Arg::new(FLAG_CHECK)
+                    .long(FLAG_CHECK)
+                    .help("Only list the files whose headers need migrating\n(If any do, return a non-zero exit code.)")
+                    .required(false),
+            )
+        )
+        .subcommand(Command::new(CMD_DEMANGLE)
+            .about("Show which Roc procedures the symbol names in compiled code refer to")
+            .arg(
+                Arg::new(SYMBOL_NAMES)
+                    .help("The symbol names to demangle\n(If none are given, demangles every symbol name in stdin, e.g. the output of `perf report`.)")
+                    .multiple_values(true)
+                    .required(false)
+            )
+        )
+        .subcommand(Command::new(CMD_EXPLAIN)
+            .about("Explain an error or warning, given the code at the end of its report")
+            .arg(
+                Arg::new(ERROR_CODE)
+                    .help("The code to explain, like E0401")
+                    .required(true)
+            )
+        )
+        .subcommand(Command::new(CMD_EXPERIMENT)
+            .about("Developer tools for working on the compiler, which may change or go away at any time")
+            .subcommand_required(true)
+            .subcommand(Command::new(CMD_GRAMMAR)
+                .about("Print the syntax the parser accepts as EBNF, which railroad diagram generators can draw")
+            )
+            .subcommand(Command::new(CMD_FUZZ_PARSE)
+                .about("Check that mutated copies of some .roc files still parse the same after formatting, and minimize any that don't (needs roc to be built with the fuzz-parse feature)")
+                .arg(
+                    Arg::new(ROC_DIR)
+                        .help("The directory of .roc files to start from")
+                        .allow_invalid_utf8(true)
+                        .required(true)
+                )
+                .arg(
+                    Arg::new(FLAG_ITERATIONS)
+                        .long(FLAG_ITERATIONS)
+                        .help("How many mutated inputs to try (default: 10000)")
+                        .takes_value(true)
+                        .required(false),
+                )
+                .arg(
+                    Arg::new(FLAG_SEED)
+                        .long(FLAG_SEED)
+                        .help("Seed the mutations, to reproduce an earlier run")
+                        .takes_value(true)
+                        .required(false),
+                )
+                .arg(
+                    Arg::new(FLAG_OUTPUT)
+                        .long(FLAG_OUTPUT)
+                        .help("Where to write the minimized inputs which failed (default: fuzz-failures)")
+                        .allow_invalid_utf8(true)
+                        .takes_value(true)
+                        .required(false),
+                )
+            )
+        )
         .trailing_var_arg(true)
         .arg(flag_optimize)
         .arg(flag_max_threads.clone())
+        .arg(flag_max_memory.clone())
+        .arg(flag_allow)
+        .arg(flag_warn)
+        .arg(flag_deny)
+        .arg(flag_locale)
+        .arg(flag_color)
+        .arg(flag_theme)
         .arg(flag_opt_size)
         .arg(flag_dev)
         .arg(flag_debug)
+        .arg(flag_keep_dbg)
+        .arg(flag_fast_math)
+        .arg(flag_keep_expects)
         .arg(flag_time)
         .arg(flag_linker)
+        .arg(flag_allocator)
         .arg(flag_prebuilt)
+        .arg(
+            Arg::new(FLAG_DAEMON)
+                .long(FLAG_DAEMON)
+                .help("Keep running in the background, so `roc check`, `roc build`, and `roc test` are faster\n(They use the daemon whenever it's running, and remember checks of modules which haven't changed since.)")
+                .conflicts_with(ROC_FILE)
+                .required(false),
+        )
         .arg(roc_file_to_run.required(false))
         .arg(args_for_app);
 
@@ -366,6 +884,8 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
     use roc_target::TargetInfo;
 
     let start_time = Instant::now();
+    // files that change after this point are only tested by the next `--changed` run
+    let started_at = std::time::SystemTime::now();
     let arena = Bump::new();
     let filename = matches.value_of_os(ROC_FILE).unwrap();
     let opt_level = match (
@@ -390,6 +910,26 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
         Some(n) => Threading::AtMost(n),
     };
 
+    let filters: Vec<&str> = matches
+        .values_of(FLAG_FILTER)
+        .map(|values| values.collect())
+        .unwrap_or_default();
+
+    let seed = match matches.value_of(FLAG_SEED) {
+        Some(value) => match value.parse() {
+            Ok(seed) => seed,
+            Err(_) => user_error!(
+                "--{} must be a whole number, but it was {}",
+                FLAG_SEED,
+                value
+            ),
+        },
+        None => std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|time| time.as_nanos() as u64)
+            .unwrap_or_default(),
+    };
+
     let path = Path::new(filename);
 
     // Spawn the root task
@@ -414,15 +954,17 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
     let target = &triple;
     let opt_level = opt_level;
     let target_info = TargetInfo::from(target);
+    let warning_levels = warnings::warning_levels(matches, path);
 
     // Step 1: compile the app and generate the .o file
     let load_config = LoadConfig {
         target_info,
-        // TODO: expose this from CLI?
-        render: roc_reporting::report::RenderTarget::ColorTerminal,
-        palette: roc_reporting::report::DEFAULT_PALETTE,
+        render: roc_reporting::cli::terminal_style().target,
+        palette: roc_reporting::cli::terminal_style().palette,
         threading,
         exec_mode: ExecutionMode::Test,
+        memory_limit: memory_limit(matches),
+        ..Default::default()
     };
     let load_result = roc_load::load_and_monomorphize(
         arena,
@@ -437,16 +979,24 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
             return handle_loading_problem(problem);
         }
         Err(LoadMonomorphizedError::ErrorModule(module)) => {
-            return handle_error_module(module, start_time.elapsed(), filename, false);
+            return handle_error_module(
+                module,
+                start_time.elapsed(),
+                filename,
+                false,
+                &warning_levels,
+            );
         }
     };
-    let problems = report_problems_monomorphized(&mut loaded);
+    let problems = report_problems_monomorphized(&mut loaded, &warning_levels);
 
     let mut expectations = std::mem::take(&mut loaded.expectations);
+    let sources = std::mem::take(&mut loaded.sources);
+    let imports = std::mem::take(&mut loaded.imports);
 
     let interns = loaded.interns.clone();
 
-    let (lib, expects, layout_interner) = roc_repl_expect::run::expect_mono_module_to_dylib(
+    let (lib, mut expects, layout_interner) = roc_repl_expect::run::expect_mono_module_to_dylib(
         arena,
         target.clone(),
         loaded,
@@ -455,6 +1005,39 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
     )
     .unwrap();
 
+    // Only keep the expects that --filter and --changed select. (All the fixtures stay, in case
+    // the remaining expects use them.)
+    let changed = if matches.is_present(FLAG_CHANGED) {
+        test_filter::last_successful_run(path)
+            .map(|since| test_filter::changed_modules(&sources, &imports, since))
+    } else {
+        None
+    };
+
+    let is_selected = |expect: &roc_repl_expect::run::ToplevelExpect| {
+        let module_id = expect.symbol.module_id();
+
+        let is_changed = match &changed {
+            Some(changed) => changed.contains(&module_id),
+            None => true,
+        };
+
+        is_changed
+            && (filters.is_empty() || {
+                let module_name = interns.module_name(module_id);
+                let name = sources
+                    .get(&module_id)
+                    .and_then(|(_, source)| test_filter::comment_name(source, expect.region));
+
+                test_filter::filter_selects(&filters, module_name.as_str(), name)
+            })
+    };
+
+    let total_expects = expects.pure.len() + expects.fx.len();
+    expects.pure.retain(|expect| is_selected(expect));
+    expects.fx.retain(|expect| is_selected(expect));
+    let skipped = total_expects - expects.pure.len() - expects.fx.len();
+
     // Print warnings before running tests.
     {
         debug_assert_eq!(
@@ -473,9 +1056,11 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
 
     let mut writer = std::io::stdout();
 
+    roc_repl_expect::run::set_test_seed(&lib, seed);
+
     let (failed, passed) = roc_repl_expect::run::run_toplevel_expects(
         &mut writer,
-        roc_reporting::report::RenderTarget::ColorTerminal,
+        roc_reporting::cli::terminal_style().target,
         arena,
         interns,
         &layout_interner.into_global(),
@@ -487,7 +1072,25 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
 
     let total_time = start_time.elapsed();
 
-    if failed == 0 && passed == 0 {
+    // a run that skipped some expects on purpose still counts as passing for --changed, since
+    // the skipped ones passed last time and nothing they depend on changed since
+    if failed == 0 && filters.is_empty() {
+        if let Err(err) = test_filter::record_successful_run(path, started_at) {
+            eprintln!(
+                "\nI could not remember that these tests passed, for --{}: {}\n",
+                FLAG_CHANGED, err
+            );
+        }
+    }
+
+    if failed == 0 && passed == 0 && skipped > 0 {
+        println!(
+            "No expectations were selected, out of {}. (--{} and --{} choose which ones run.)",
+            skipped, FLAG_FILTER, FLAG_CHANGED
+        );
+
+        Ok(0)
+    } else if failed == 0 && passed == 0 {
         // TODO print this in a more nicely formatted way!
         println!("No expectations were found.");
 
@@ -504,15 +1107,293 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
             31 // red
         };
 
-        println!(
-            "\n\x1B[{failed_color}m{failed}\x1B[39m failed and \x1B[32m{passed}\x1B[39m passed in {} ms.\n",
-            total_time.as_millis(),
-        );
+        if skipped > 0 {
+            println!(
+                "\n\x1B[{failed_color}m{failed}\x1B[39m failed and \x1B[32m{passed}\x1B[39m passed in {} ms, skipping {skipped}.\n",
+                total_time.as_millis(),
+            );
+        } else {
+            println!(
+                "\n\x1B[{failed_color}m{failed}\x1B[39m failed and \x1B[32m{passed}\x1B[39m passed in {} ms.\n",
+                total_time.as_millis(),
+            );
+        }
+
+        if failed > 0 {
+            println!(
+                "These tests ran with --{} {}, which gives `Random.testSeed` the same value again.\n",
+                FLAG_SEED, seed
+            );
+        }
 
         Ok((failed > 0) as i32)
     }
 }
 
+#[cfg(windows)]
+pub fn check_doc_examples(_matches: &ArgMatches, _triple: Triple) -> io::Result<i32> {
+    eprintln!("\nChecking doc examples does not work on Windows yet, sorry!\n");
+
+    Ok(1)
+}
+
+/// Prints each symbol name demangled, or as-is if it isn't the name of a Roc procedure.
+/// Without any names, copies stdin to stdout with every symbol name in it demangled.
+pub fn demangle(matches: &ArgMatches) -> io::Result<i32> {
+    use roc_module::mangle;
+    use std::io::BufRead;
+
+    match matches.values_of(SYMBOL_NAMES) {
+        Some(names) => {
+            for name in names {
+                match mangle::demangle(name) {
+                    Some(demangled) => println!("{}", demangled),
+                    None => println!("{}", name),
+                }
+            }
+        }
+        None => {
+            for line in io::stdin().lock().lines() {
+                println!("{}", mangle::demangle_text(&line?));
+            }
+        }
+    }
+
+    Ok(0)
+}
+
+/// The most memory compiling may use, in bytes, from `--max-memory`, or else the `ROC_MAX_MEMORY`
+/// environment variable. Both give it in megabytes.
+pub fn memory_limit(matches: &ArgMatches) -> Option<usize> {
+    let megabytes = match matches.value_of(FLAG_MAX_MEMORY) {
+        Some(value) => value.to_string(),
+        None => env::var("ROC_MAX_MEMORY").ok()?,
+    };
+
+    match megabytes.parse::<usize>() {
+        Ok(megabytes) if megabytes > 0 => Some(megabytes << 20),
+        _ => user_error!(
+            "the memory limit must be a whole number of megabytes, greater than 0, but it was {}",
+            megabytes
+        ),
+    }
+}
+
+/// Report problems in the language given by `--locale`, or else by the `ROC_LANG` environment
+/// variable. Languages without translations are reported in English.
+pub fn set_locale(matches: &ArgMatches) {
+    let locale = match matches.value_of(FLAG_LOCALE) {
+        Some(tag) => Locale::from_tag(tag),
+        None => std::env::var("ROC_LANG")
+            .ok()
+            .and_then(|tag| Locale::from_tag(&tag)),
+    };
+
+    roc_reporting::locale::set_locale(locale.unwrap_or_default());
+}
+
+/// Whether stdout is a terminal, when it's not this process's own stdout which matters, like in
+/// the daemon, where it's the stdout of the `roc` which handed over the command
+static STDOUT_IS_TERMINAL: Mutex<Option<bool>> = Mutex::new(None);
+
+pub fn set_stdout_is_terminal(is_terminal: bool) {
+    *STDOUT_IS_TERMINAL.lock().unwrap() = Some(is_terminal);
+}
+
+fn stdout_is_terminal() -> bool {
+    match *STDOUT_IS_TERMINAL.lock().unwrap() {
+        Some(is_terminal) => is_terminal,
+        None => unsafe { libc::isatty(1) == 1 },
+    }
+}
+
+/// Whether the terminal's background is light, according to the COLORFGBG environment variable
+/// some terminals set, like `15;0` (white text on a black background)
+fn terminal_background_is_light() -> bool {
+    match env::var("COLORFGBG") {
+        Ok(colors) => matches!(colors.rsplit(';').next(), Some("7" | "15")),
+        Err(_) => false,
+    }
+}
+
+/// Report problems with colors and hyperlinks per `--color`, and for a background per `--theme`.
+/// Colors are left out when `NO_COLOR` is set, or when stdout isn't a terminal, unless
+/// `--color=always` asks for them. Links are added whenever stdout is a terminal, unless
+/// `--color=never` rules out escape sequences altogether.
+pub fn set_terminal_style(matches: &ArgMatches) {
+    use roc_reporting::cli::TerminalStyle;
+    use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE, LIGHT_PALETTE, PLAIN_PALETTE};
+
+    let is_terminal = stdout_is_terminal();
+    let no_color = env::var_os("NO_COLOR").map_or(false, |value| !value.is_empty());
+    let choice = matches.value_of(FLAG_COLOR).unwrap_or("auto");
+
+    let colored = match choice {
+        "always" => true,
+        "never" => false,
+        _ => is_terminal && !no_color,
+    };
+
+    let light = match matches
+        .value_of(FLAG_THEME)
+        .map(String::from)
+        .or_else(|| env::var("ROC_THEME").ok())
+    {
+        Some(theme) => theme == "light",
+        None => terminal_background_is_light(),
+    };
+
+    let style = TerminalStyle {
+        target: if is_terminal && choice != "never" {
+            RenderTarget::ColorTerminalWithLinks
+        } else {
+            RenderTarget::ColorTerminal
+        },
+        palette: match (colored, light) {
+            (false, _) => PLAIN_PALETTE,
+            (true, false) => DEFAULT_PALETTE,
+            (true, true) => LIGHT_PALETTE,
+        },
+    };
+
+    roc_reporting::cli::set_terminal_style(style);
+}
+
+/// `roc explain CODE` and `roc check --explain-error CODE`: print the longer explanation of the
+/// reports with that code.
+pub fn explain(code: &str) -> io::Result<i32> {
+    match roc_reporting::explain::explanation(code) {
+        Some(explanation) => {
+            println!("{}", explanation);
+
+            Ok(0)
+        }
+        None => {
+            eprintln!(
+                "`{}` isn't an error code I know. Codes look like E0401, and are printed at the end of each report.",
+                code
+            );
+
+            Ok(1)
+        }
+    }
+}
+
+/// `roc docs --check-examples`: type-check every ```roc example in the package's doc comments,
+/// and run the top-level `expect`s of those that have them.
+#[cfg(not(windows))]
+pub fn check_doc_examples(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
+    use roc_build::program::{report_problems_monomorphized, report_problems_typechecked};
+    use roc_gen_llvm::llvm::build::LlvmBackendMode;
+    use roc_load::{ExecutionMode, LoadConfig, LoadMonomorphizedError};
+    use roc_packaging::cache;
+    use roc_target::TargetInfo;
+
+    let start_time = Instant::now();
+    let filename = matches.value_of_os(ROC_FILE).unwrap();
+    let examples = roc_docs::doc_examples(PathBuf::from(filename));
+    let target_info = TargetInfo::from(&triple);
+    let warning_levels = warnings::warning_levels(matches, Path::new(filename));
+
+    let mut failed = 0;
+
+    for example in examples.iter() {
+        let arena = &Bump::new();
+        let load_config = LoadConfig {
+            target_info,
+            render: roc_reporting::cli::terminal_style().target,
+            palette: roc_reporting::cli::terminal_style().palette,
+            threading: Threading::AllAvailable,
+            exec_mode: ExecutionMode::Test,
+            ..Default::default()
+        };
+
+        // The example's source is laid out to match the doc comment it came from, so reporting
+        // problems under the path of the file it's in points at the right lines.
+        let load_result = roc_load::load_and_monomorphize_from_str(
+            arena,
+            example.module_path.clone(),
+            arena.alloc_str(&example.source),
+            example.src_dir.clone(),
+            RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+            load_config,
+        );
+
+        let mut loaded = match load_result {
+            Ok(loaded) => loaded,
+            Err(LoadMonomorphizedError::LoadingProblem(problem)) => {
+                println!("In the example in the docs for {}:", example.location);
+                handle_loading_problem(problem)?;
+                failed += 1;
+                continue;
+            }
+            Err(LoadMonomorphizedError::ErrorModule(mut module)) => {
+                println!("In the example in the docs for {}:", example.location);
+                report_problems_typechecked(&mut module, &warning_levels);
+                failed += 1;
+                continue;
+            }
+        };
+
+        let problems = report_problems_monomorphized(&mut loaded, &warning_levels);
+
+        if problems.errors > 0 {
+            failed += 1;
+            continue;
+        }
+
+        if !example.has_expects {
+            continue;
+        }
+
+        let mut expectations = std::mem::take(&mut loaded.expectations);
+        let interns = loaded.interns.clone();
+
+        let (lib, expects, layout_interner) = roc_repl_expect::run::expect_mono_module_to_dylib(
+            arena,
+            triple.clone(),
+            loaded,
+            OptLevel::Development,
+            LlvmBackendMode::CliTest,
+        )
+        .unwrap();
+
+        let interns = arena.alloc(interns);
+        let mut writer = std::io::stdout();
+
+        let (expects_failed, _) = roc_repl_expect::run::run_toplevel_expects(
+            &mut writer,
+            roc_reporting::cli::terminal_style().target,
+            arena,
+            interns,
+            &layout_interner.into_global(),
+            &lib,
+            &mut expectations,
+            expects,
+        )
+        .unwrap();
+
+        if expects_failed > 0 {
+            println!("(in the example in the docs for {})", example.location);
+            failed += 1;
+        }
+    }
+
+    let failed_color = if failed == 0 {
+        32 // green
+    } else {
+        31 // red
+    };
+
+    println!(
+        "\n\x1B[{failed_color}m{failed}\x1B[39m of {} doc examples failed in {} ms.\n",
+        examples.len(),
+        start_time.elapsed().as_millis(),
+    );
+
+    Ok((failed > 0) as i32)
+}
+
 pub fn build(
     matches: &ArgMatches,
     config: BuildConfig,
@@ -619,6 +1500,9 @@ pub fn build(
         }
     };
     let emit_debug_info = matches.is_present(FLAG_DEBUG);
+    let keep_dbg = matches.is_present(FLAG_KEEP_DBG);
+    let fast_math = matches.is_present(FLAG_FAST_MATH);
+    let keep_expects = matches.is_present(FLAG_KEEP_EXPECTS);
     let emit_timings = matches.is_present(FLAG_TIME);
 
     let threading = match matches
@@ -640,17 +1524,45 @@ pub fn build(
         || matches.value_of(FLAG_LINKER) == Some("legacy")
     {
         LinkingStrategy::Legacy
+    } else if matches!(triple.architecture, Architecture::Wasm32)
+        && matches.value_of(FLAG_LINKER) != Some("surgical")
+    {
+        // Splicing LLVM-built apps into wasm hosts is new, so it's opt-in for now.
+        LinkingStrategy::Legacy
     } else {
         LinkingStrategy::Surgical
     };
 
-    let prebuilt = if matches.is_present(FLAG_PREBUILT) {
-        matches.value_of(FLAG_PREBUILT) == Some("true")
-    } else {
+    let static_link = config == BuildConfig::BuildOnly && matches.is_present(FLAG_STATIC);
+    let split_debuginfo =
+        config == BuildConfig::BuildOnly && matches.is_present(FLAG_SPLIT_DEBUGINFO);
+
+    if static_link {
+        if triple.operating_system != OperatingSystem::Linux {
+            eprintln!("--{FLAG_STATIC} only works when building for Linux.");
+            process::exit(1);
+        }
+
+        // The surgical linker patches the platform's prebuilt host, which is dynamically linked.
+        if linking_strategy != LinkingStrategy::Legacy {
+            eprintln!("--{FLAG_STATIC} can't be used with the surgical linker, because it reuses the platform's prebuilt host, which is dynamically linked.\n\nTry again with --{FLAG_LINKER}=legacy so the host is linked from scratch against musl.");
+            process::exit(1);
+        }
+    }
+
+    let prebuilt = match matches.value_of(FLAG_PREBUILT) {
+        Some("true") => PrebuiltPlatform::Yes,
+        Some("auto") => PrebuiltPlatform::IfSourcesChanged,
+        Some(_) => PrebuiltPlatform::No,
         // When compiling for a different target, default to assuming a prebuilt platform.
         // Otherwise compilation would most likely fail because many toolchains assume you're compiling for the current machine.
         // We make an exception for Wasm, because cross-compiling is the norm in that case.
-        triple != Triple::host() && !matches!(triple.architecture, Architecture::Wasm32)
+        None if triple != Triple::host()
+            && !matches!(triple.architecture, Architecture::Wasm32) =>
+        {
+            PrebuiltPlatform::Yes
+        }
+        None => PrebuiltPlatform::No,
     };
 
     let wasm_dev_stack_bytes: Option<u32> = matches
@@ -660,6 +1572,19 @@ pub fn build(
         .and_then(|s| s.parse::<u32>().ok())
         .map(|x| x * 1024);
 
+    let wasm_dev_features = matches
+        .try_get_one::<&str>(FLAG_WASM_FEATURES)
+        .ok()
+        .flatten()
+        .and_then(|s| WasmFeatures::parse(s).ok())
+        .unwrap_or_default();
+
+    let allocator_override = matches
+        .try_get_one::<&str>(FLAG_ALLOCATOR)
+        .ok()
+        .flatten()
+        .and_then(|allocator| AllocatorStrategy::from_str(allocator));
+
     let build_ordering = match config {
         BuildAndRunIfNoErrors => BuildOrdering::BuildIfChecks,
         _ => BuildOrdering::AlwaysBuild,
@@ -669,9 +1594,22 @@ pub fn build(
         backend: code_gen_backend,
         opt_level,
         emit_debug_info,
+        keep_dbg,
+        fast_math,
     };
 
-    let load_config = standard_load_config(&triple, build_ordering, threading);
+    // Optimized builds strip `dbg`s, unless they're explicitly kept.
+    let keep_dbgs = keep_dbg || !matches!(opt_level, OptLevel::Optimize | OptLevel::Size);
+
+    let load_config = standard_load_config(
+        &triple,
+        build_ordering,
+        threading,
+        memory_limit(matches),
+        keep_dbgs,
+        keep_expects,
+    );
+    let warning_levels = warnings::warning_levels(matches, &path_buf);
 
     let res_binary_path = build_file(
         &arena,
@@ -683,8 +1621,13 @@ pub fn build(
         linking_strategy,
         prebuilt,
         wasm_dev_stack_bytes,
+        wasm_dev_features,
+        allocator_override,
+        static_link,
+        split_debuginfo,
         roc_cache_dir,
         load_config,
+        &warning_levels,
     );
 
     match res_binary_path {
@@ -728,13 +1671,26 @@ pub fn build(
                     // ManuallyDrop will leak the bytes because we don't drop manually
                     let bytes = &ManuallyDrop::new(std::fs::read(&binary_path).unwrap());
 
-                    roc_run(&arena, opt_level, triple, args, bytes, expect_metadata)
+                    roc_run(
+                        &arena,
+                        opt_level,
+                        keep_dbg,
+                        triple,
+                        args,
+                        bytes,
+                        expect_metadata,
+                    )
                 }
                 BuildAndRunIfNoErrors => {
-                    debug_assert_eq!(
-                        problems.errors, 0,
-                        "if there are errors, they should have been returned as an error variant"
-                    );
+                    // errors normally come back as an error variant, but warnings which were
+                    // denied only become errors when they're reported
+                    if problems.errors > 0 {
+                        problems.print_to_stdout(total_time);
+                        println!(".");
+
+                        return Ok(problems.exit_code());
+                    }
+
                     if problems.warnings > 0 {
                         problems.print_to_stdout(total_time);
                         println!(
@@ -749,12 +1705,20 @@ pub fn build(
                     // ManuallyDrop will leak the bytes because we don't drop manually
                     let bytes = &ManuallyDrop::new(std::fs::read(&binary_path).unwrap());
 
-                    roc_run(&arena, opt_level, triple, args, bytes, expect_metadata)
+                    roc_run(
+                        &arena,
+                        opt_level,
+                        keep_dbg,
+                        triple,
+                        args,
+                        bytes,
+                        expect_metadata,
+                    )
                 }
             }
         }
         Err(BuildFileError::ErrorModule { module, total_time }) => {
-            handle_error_module(module, total_time, filename, true)
+            handle_error_module(module, total_time, filename, true, &warning_levels)
         }
         Err(BuildFileError::LoadingProblem(problem)) => handle_loading_problem(problem),
     }
@@ -765,10 +1729,11 @@ fn handle_error_module(
     total_time: std::time::Duration,
     filename: &OsStr,
     print_run_anyway_hint: bool,
+    warning_levels: &WarningLevels,
 ) -> io::Result<i32> {
     debug_assert!(module.total_problems() > 0);
 
-    let problems = roc_build::program::report_problems_typechecked(&mut module);
+    let problems = roc_build::program::report_problems_typechecked(&mut module, warning_levels);
 
     problems.print_to_stdout(total_time);
 
@@ -789,7 +1754,7 @@ fn handle_error_module(
 
 fn handle_loading_problem(problem: LoadingProblem) -> io::Result<i32> {
     match problem {
-        LoadingProblem::FormattedReport(report) => {
+        LoadingProblem::FormattedReport(report, _) => {
             print!("{}", report);
             Ok(1)
         }
@@ -805,6 +1770,7 @@ fn handle_loading_problem(problem: LoadingProblem) -> io::Result<i32> {
 fn roc_run<'a, I: IntoIterator<Item = &'a OsStr>>(
     arena: &Bump,
     opt_level: OptLevel,
+    keep_dbg: bool,
     triple: Triple,
     args: I,
     binary_bytes: &[u8],
@@ -843,7 +1809,14 @@ fn roc_run<'a, I: IntoIterator<Item = &'a OsStr>>(
 
             Ok(0)
         }
-        _ => roc_run_native(arena, opt_level, args, binary_bytes, expect_metadata),
+        _ => roc_run_native(
+            arena,
+            opt_level,
+            keep_dbg,
+            args,
+            binary_bytes,
+            expect_metadata,
+        ),
     }
 }
 
@@ -908,6 +1881,7 @@ fn make_argv_envp<'a, I: IntoIterator<Item = S>, S: AsRef<OsStr>>(
 fn roc_run_native<I: IntoIterator<Item = S>, S: AsRef<OsStr>>(
     arena: &Bump,
     opt_level: OptLevel,
+    keep_dbg: bool,
     args: I,
     binary_bytes: &[u8],
     expect_metadata: ExpectMetadata,
@@ -932,6 +1906,10 @@ fn roc_run_native<I: IntoIterator<Item = S>, S: AsRef<OsStr>>(
 
         match opt_level {
             OptLevel::Development => roc_dev_native(arena, executable, argv, envp, expect_metadata),
+            // kept dbgs report to the parent process, just like in development builds
+            OptLevel::Normal | OptLevel::Size | OptLevel::Optimize if keep_dbg => {
+                roc_dev_native(arena, executable, argv, envp, expect_metadata)
+            }
             OptLevel::Normal | OptLevel::Size | OptLevel::Optimize => {
                 roc_run_native_fast(executable, &argv, &envp);
             }
@@ -1170,6 +2148,7 @@ fn roc_run_executable_file_path(binary_bytes: &[u8]) -> std::io::Result<Executab
 fn roc_run_native<I: IntoIterator<Item = S>, S: AsRef<OsStr>>(
     arena: &Bump, // This should be passed an owned value, not a reference, so we can usefully mem::forget it!
     opt_level: OptLevel,
+    _keep_dbg: bool,
     args: I,
     binary_bytes: &[u8],
     _expect_metadata: ExpectMetadata,
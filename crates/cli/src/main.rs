@@ -1,19 +1,26 @@
 //! The `roc` binary that brings together all functionality in the Roc toolset.
+use clap::ArgMatches;
 use roc_build::link::LinkType;
 use roc_cli::build::check_file;
 use roc_cli::{
-    build_app, format, test, BuildConfig, FormatMode, Target, CMD_BUILD, CMD_CHECK, CMD_DEV,
-    CMD_DOCS, CMD_EDIT, CMD_FORMAT, CMD_GEN_STUB_LIB, CMD_GLUE, CMD_REPL, CMD_RUN, CMD_TEST,
-    CMD_VERSION, DIRECTORY_OR_FILES, FLAG_CHECK, FLAG_LIB, FLAG_NO_LINK, FLAG_TARGET, FLAG_TIME,
+    bench, build_app, check_doc_examples, format, interp, test, BuildConfig, FormatMode, Target,
+    CMD_APP, CMD_BENCH, CMD_BUILD, CMD_CHECK, CMD_DEMANGLE, CMD_DEV, CMD_DOCS, CMD_EDIT,
+    CMD_EXPERIMENT, CMD_EXPLAIN, CMD_FORMAT, CMD_FUZZ_PARSE, CMD_GEN_STUB_LIB, CMD_GLUE,
+    CMD_GRAMMAR, CMD_IMPORTS, CMD_MIGRATE_HEADERS, CMD_NEW, CMD_PLATFORM, CMD_RENAME, CMD_REPL,
+    CMD_RUN, CMD_TEST, CMD_VERSION, DIRECTORY_OR_FILES, ERROR_CODE, FLAG_CHECK,
+    FLAG_CHECK_EXAMPLES, FLAG_EXPLAIN_ERROR, FLAG_HOT, FLAG_INTERP, FLAG_JSON, FLAG_LAYOUTS,
+    FLAG_LIB, FLAG_NO_LINK, FLAG_OUTPUT_HTML, FLAG_PLUGIN, FLAG_TARGET, FLAG_TIME, FLAG_WATCH,
     GLUE_FILE, ROC_FILE,
 };
-use roc_docs::generate_docs_html;
+use roc_docs::{generate_docs_html, generate_docs_json};
 use roc_error_macros::user_error;
+use roc_load::plugin::{DylibPlugin, Plugins};
 use roc_load::{LoadingProblem, Threading};
 use roc_packaging::cache::{self, RocCacheDir};
 use std::fs::{self, FileType};
 use std::io;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use target_lexicon::Triple;
 
 #[macro_use]
@@ -31,7 +38,42 @@ fn main() -> io::Result<()> {
 
     let matches = build_app().get_matches();
 
-    let exit_code = match matches.subcommand() {
+    #[cfg(not(windows))]
+    {
+        if matches.is_present(roc_cli::FLAG_DAEMON) {
+            std::process::exit(roc_cli::daemon::serve(run_args)?);
+        }
+
+        if let Some(exit_code) = roc_cli::daemon::delegate(matches.subcommand_name())? {
+            std::process::exit(exit_code);
+        }
+    }
+
+    let exit_code = run(matches)?;
+
+    std::process::exit(exit_code);
+}
+
+/// Runs the command given by `args`, which start with the name of the program, like [main] would
+#[cfg(not(windows))]
+fn run_args(args: Vec<OsString>) -> io::Result<i32> {
+    run(build_app().get_matches_from(args))
+}
+
+fn run(matches: ArgMatches) -> io::Result<i32> {
+    match matches.subcommand() {
+        None => {
+            roc_cli::set_locale(&matches);
+            roc_cli::set_terminal_style(&matches);
+        }
+        Some((CMD_BUILD | CMD_CHECK | CMD_DEV | CMD_RUN | CMD_TEST | CMD_BENCH, matches)) => {
+            roc_cli::set_locale(matches);
+            roc_cli::set_terminal_style(matches);
+        }
+        Some(_) => {}
+    }
+
+    match matches.subcommand() {
         None => {
             if matches.is_present(ROC_FILE) {
                 build(
@@ -48,7 +90,9 @@ fn main() -> io::Result<()> {
             }
         }
         Some((CMD_RUN, matches)) => {
-            if matches.is_present(ROC_FILE) {
+            if matches.is_present(ROC_FILE) && matches.is_present(FLAG_INTERP) {
+                interp(matches, Triple::host())
+            } else if matches.is_present(ROC_FILE) {
                 build(
                     matches,
                     BuildConfig::BuildAndRun,
@@ -71,8 +115,25 @@ fn main() -> io::Result<()> {
                 Ok(1)
             }
         }
-        Some((CMD_DEV, matches)) => {
+        Some((CMD_BENCH, matches)) => {
             if matches.is_present(ROC_FILE) {
+                bench(matches, Triple::host())
+            } else {
+                eprintln!("What .roc file do you want to benchmark? Specify it at the end of the `roc bench` command.");
+
+                Ok(1)
+            }
+        }
+        Some((CMD_DEV, matches)) => {
+            if matches.is_present(FLAG_HOT) {
+                eprintln!("`roc dev --{FLAG_WATCH} --{FLAG_HOT}` isn't supported yet: Roc can't patch a rebuilt app into a host that is already running. Use `roc dev --{FLAG_WATCH}` on its own to rebuild and restart the app whenever its source files change.");
+
+                Ok(1)
+            } else if matches.is_present(ROC_FILE) && matches.is_present(FLAG_WATCH) {
+                let roc_file = Path::new(matches.value_of_os(ROC_FILE).unwrap());
+
+                roc_cli::watch::watch(roc_file)
+            } else if matches.is_present(ROC_FILE) {
                 build(
                     matches,
                     BuildConfig::BuildAndRunIfNoErrors,
@@ -90,14 +151,36 @@ fn main() -> io::Result<()> {
             let input_path = Path::new(matches.value_of_os(ROC_FILE).unwrap());
             let output_path = Path::new(matches.value_of_os(GLUE_FILE).unwrap());
 
-            if Some("rs") == output_path.extension().and_then(OsStr::to_str) {
-                roc_glue::generate(input_path, output_path)
-            } else {
-                eprintln!("Currently, `roc glue` only supports generating Rust glue files (with the .rs extension). In the future, the plan is to decouple `roc glue` from any particular output format, by having it accept a second .roc file which gets executed as a plugin to generate glue code for any desired language. However, this has not yet been implemented, and for now only .rs is supported.");
+            match output_path.extension().and_then(OsStr::to_str) {
+                // The layouts are the same whatever the host language, so any extension will do
+                _ if matches.value_of(FLAG_LAYOUTS) == Some("json") => {
+                    roc_glue::generate_layouts(input_path, output_path)
+                }
+                Some("rs") => roc_glue::generate(input_path, output_path),
+                // A .roc output file gets test doubles for one of the platform's hosted modules
+                Some("roc") => roc_glue::test_doubles::generate(input_path, output_path),
+                _ => {
+                    eprintln!("Currently, `roc glue` only supports generating Rust glue files (with the .rs extension), and test doubles for a platform's effects (with the .roc extension). In the future, the plan is to decouple `roc glue` from any particular output format, by having it accept a second .roc file which gets executed as a plugin to generate glue code for any desired language. However, this has not yet been implemented, and for now only .rs and .roc are supported.");
 
-                Ok(1)
+                    Ok(1)
+                }
             }
         }
+        Some((CMD_DEMANGLE, matches)) => roc_cli::demangle(matches),
+        Some((CMD_EXPLAIN, matches)) => roc_cli::explain(matches.value_of(ERROR_CODE).unwrap()),
+        Some((CMD_RENAME, matches)) => roc_cli::rename::rename(matches),
+        Some((CMD_IMPORTS, matches)) => roc_cli::imports::imports(matches),
+        Some((CMD_MIGRATE_HEADERS, matches)) => roc_cli::migrate::migrate_headers(matches),
+        Some((CMD_EXPERIMENT, matches)) => match matches.subcommand() {
+            Some((CMD_FUZZ_PARSE, matches)) => roc_cli::experiment::fuzz_parse(matches),
+            Some((CMD_GRAMMAR, _)) => roc_cli::experiment::grammar(),
+            _ => unreachable!(),
+        },
+        Some((CMD_NEW, matches)) => match matches.subcommand() {
+            Some((CMD_APP, matches)) => roc_cli::scaffold::new_app(matches),
+            Some((CMD_PLATFORM, matches)) => roc_cli::scaffold::new_platform(matches),
+            _ => unreachable!(),
+        },
         Some((CMD_GEN_STUB_LIB, matches)) => {
             let input_path = Path::new(matches.value_of_os(ROC_FILE).unwrap());
             let target: Target = matches.value_of_t(FLAG_TARGET).unwrap_or_default();
@@ -127,6 +210,9 @@ fn main() -> io::Result<()> {
                 link_type,
             )?)
         }
+        Some((CMD_CHECK, matches)) if matches.is_present(FLAG_EXPLAIN_ERROR) => {
+            roc_cli::explain(matches.value_of(FLAG_EXPLAIN_ERROR).unwrap())
+        }
         Some((CMD_CHECK, matches)) => {
             let arena = bumpalo::Bump::new();
 
@@ -143,45 +229,40 @@ fn main() -> io::Result<()> {
                 Some(n) => Threading::AtMost(n),
             };
 
+            let warning_levels = roc_cli::warnings::warning_levels(matches, &roc_file_path);
+
+            let mut plugins = Plugins::default();
+            for path in matches.values_of_os(FLAG_PLUGIN).unwrap_or_default() {
+                // Safety: running a plugin is as trusting as running any other program
+                match unsafe { DylibPlugin::open(Path::new(path)) } {
+                    Ok(plugin) => plugins.register(Arc::new(plugin)),
+                    Err(err) => user_error!(
+                        "could not load the plugin {}: {}",
+                        Path::new(path).display(),
+                        err
+                    ),
+                }
+            }
+
             match check_file(
                 &arena,
                 roc_file_path,
                 emit_timings,
                 RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
                 threading,
+                &warning_levels,
+                matches.value_of_os(FLAG_OUTPUT_HTML).map(Path::new),
+                plugins,
+                roc_cli::memory_limit(matches),
             ) {
                 Ok((problems, total_time)) => {
-                    println!(
-                        "\x1B[{}m{}\x1B[39m {} and \x1B[{}m{}\x1B[39m {} found in {} ms.",
-                        if problems.errors == 0 {
-                            32 // green
-                        } else {
-                            33 // yellow
-                        },
-                        problems.errors,
-                        if problems.errors == 1 {
-                            "error"
-                        } else {
-                            "errors"
-                        },
-                        if problems.warnings == 0 {
-                            32 // green
-                        } else {
-                            33 // yellow
-                        },
-                        problems.warnings,
-                        if problems.warnings == 1 {
-                            "warning"
-                        } else {
-                            "warnings"
-                        },
-                        total_time.as_millis(),
-                    );
+                    problems.print_to_stdout(total_time);
+                    println!(".");
 
                     Ok(problems.exit_code())
                 }
 
-                Err(LoadingProblem::FormattedReport(report)) => {
+                Err(LoadingProblem::FormattedReport(report, _)) => {
                     print!("{}", report);
 
                     Ok(1)
@@ -191,7 +272,7 @@ fn main() -> io::Result<()> {
                 }
             }
         }
-        Some((CMD_REPL, _)) => Ok(roc_repl_cli::main()),
+        Some((CMD_REPL, matches)) => Ok(roc_repl_cli::main(matches.is_present(FLAG_INTERP))),
         Some((CMD_EDIT, matches)) => {
             match matches
                 .values_of_os(DIRECTORY_OR_FILES)
@@ -209,11 +290,21 @@ fn main() -> io::Result<()> {
             Ok(0)
         }
         Some((CMD_DOCS, matches)) => {
-            let root_filename = matches.value_of_os(ROC_FILE).unwrap();
+            if matches.is_present(FLAG_CHECK_EXAMPLES) {
+                check_doc_examples(matches, Triple::host())
+            } else if matches.is_present(FLAG_JSON) {
+                let root_filename = matches.value_of_os(ROC_FILE).unwrap();
 
-            generate_docs_html(PathBuf::from(root_filename));
+                generate_docs_json(PathBuf::from(root_filename));
 
-            Ok(0)
+                Ok(0)
+            } else {
+                let root_filename = matches.value_of_os(ROC_FILE).unwrap();
+
+                generate_docs_html(PathBuf::from(root_filename));
+
+                Ok(0)
+            }
         }
         Some((CMD_FORMAT, matches)) => {
             let maybe_values = matches.values_of_os(DIRECTORY_OR_FILES);
@@ -270,9 +361,7 @@ fn main() -> io::Result<()> {
             Ok(0)
         }
         _ => unreachable!(),
-    }?;
-
-    std::process::exit(exit_code);
+    }
 }
 
 fn read_all_roc_files(
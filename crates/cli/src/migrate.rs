@@ -0,0 +1,608 @@
+//! `roc migrate-headers`, which brings module headers written in older syntax up to date. Headers
+//! which still parse are just reformatted. Headers which don't are split into their sections
+//! (`exposes [...]`, `imports [...]`, and so on), which are put back together in the order the
+//! parser expects, with empty defaults for sections which used to be optional. Files with no
+//! header at all get an `interface` header which exposes everything they define.
+use crate::format::flatten_directories;
+use crate::{DIRECTORY_OR_FILES, FLAG_CHECK};
+use bumpalo::Bump;
+use clap::ArgMatches;
+use roc_fmt::module::fmt_module;
+use roc_fmt::rewrite::{byte_region, Rewriter};
+use roc_fmt::Buf;
+use roc_parse::ast::{Defs, Pattern, TypeDef, ValueDef};
+use roc_parse::highlight::{highlight, HighlightToken};
+use roc_parse::module::{module_defs, parse_header};
+use roc_parse::parser::Parser;
+use roc_parse::state::State;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const MODULE_KINDS: [&str; 5] = ["app", "interface", "hosted", "platform", "package"];
+
+/// The keywords which start a section of a header. `to` and `with` aren't here, since they're
+/// part of the `provides` and `generates` sections.
+const SECTION_KEYWORDS: [&str; 7] = [
+    "requires",
+    "exposes",
+    "packages",
+    "imports",
+    "provides",
+    "generates",
+    // platforms used to declare their effects here, before hosted modules existed
+    "effects",
+];
+
+#[derive(Debug, PartialEq, Eq)]
+enum Migration {
+    UpToDate,
+    Migrated(String),
+    Failed(String),
+}
+
+/// A header keyword, and the text after it up to the next keyword
+struct Section<'a> {
+    keyword: &'a str,
+    text: &'a str,
+}
+
+/// Formats the header at the start of `source`, returning it and where it ended in `source`.
+fn format_header(source: &str) -> Option<(String, usize)> {
+    let arena = Bump::new();
+    let (module, state) = parse_header(&arena, State::new(source.as_bytes())).ok()?;
+
+    let mut buf = Buf::new_in(&arena);
+    fmt_module(&mut buf, &module);
+
+    Some((
+        buf.as_str().trim_end().to_string(),
+        state.pos().offset as usize,
+    ))
+}
+
+/// Replaces everything before `header_end`, and the blank space after it, with `header`
+fn with_header(source: &str, header_end: usize, header: &str) -> String {
+    let rest_start = source.len() - source[header_end..].trim_start().len();
+    let separator = if rest_start == source.len() {
+        "\n"
+    } else {
+        "\n\n"
+    };
+
+    let mut rewriter = Rewriter::new(source);
+    rewriter.replace(
+        byte_region(0..rest_start),
+        format!("{}{}", header, separator),
+    );
+
+    rewriter
+        .apply()
+        .expect("a single edit can't overlap another")
+}
+
+/// Splits a header into its sections, ignoring keywords inside brackets, braces, and parens.
+fn split_sections(header: &str) -> Vec<Section> {
+    let mut sections: Vec<Section> = Vec::new();
+    let mut depth = 0;
+    let mut section_start = None;
+
+    for token in highlight(header) {
+        let start = token.region.start().offset as usize;
+        let text = &header[start..token.region.end().offset as usize];
+
+        match text {
+            "[" | "{" | "(" => depth += 1,
+            "]" | "}" | ")" => depth -= 1,
+            _ if depth == 0
+                && (SECTION_KEYWORDS.contains(&text)
+                    || (sections.is_empty() && MODULE_KINDS.contains(&text))) =>
+            {
+                if let (Some(previous), Some(section)) = (section_start, sections.last_mut()) {
+                    section.text = header[previous..start].trim();
+                }
+
+                sections.push(Section {
+                    keyword: text,
+                    text: "",
+                });
+                section_start = Some(start + text.len());
+            }
+            _ => {}
+        }
+    }
+
+    if let (Some(previous), Some(section)) = (section_start, sections.last_mut()) {
+        section.text = header[previous..].trim();
+    }
+
+    sections
+}
+
+/// Where a header which doesn't parse probably ends: at the first line after its first line
+/// which starts in the leftmost column, since that's where the module's defs begin.
+fn guess_header_end(source: &str, header_start: usize) -> usize {
+    let mut offset = header_start;
+
+    for (index, line) in source[header_start..].split_inclusive('\n').enumerate() {
+        let starts_def = line.starts_with(|ch: char| !ch.is_whitespace() && ch != '#');
+
+        if index > 0 && starts_def {
+            return offset;
+        }
+
+        offset += line.len();
+    }
+
+    source.len()
+}
+
+/// Package names used to be written without quotes, like `platform folkertdev/foo`
+fn quoted(name: &str) -> String {
+    if name.starts_with('"') {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name)
+    }
+}
+
+/// Whether a `requires` section starts with its rigids, like the `{ Model }` in
+/// `requires { Model } { main : Model }`, rather than going straight to the signature
+fn has_rigids(requires: &str) -> bool {
+    !requires
+        .trim_start_matches('{')
+        .trim_start()
+        .starts_with(|ch: char| ch.is_ascii_lowercase())
+}
+
+/// Puts a header back together from its sections, in the order the parser expects them.
+fn rebuild(sections: &[Section]) -> Result<String, String> {
+    let kind = sections[0].keyword;
+    let name = sections[0].text;
+
+    let section = |keyword: &str| {
+        sections
+            .iter()
+            .find(|section| section.keyword == keyword)
+            .map(|section| section.text)
+    };
+
+    if section("effects").is_some() {
+        return Err("it declares its effects in its header. Effects are now declared in a hosted module, which this tool can't write for you.".to_string());
+    }
+
+    let expected: &[(&str, Option<&str>)] = match kind {
+        "interface" => &[("exposes", Some("[]")), ("imports", Some("[]"))],
+        "hosted" => &[
+            ("exposes", Some("[]")),
+            ("imports", Some("[]")),
+            ("generates", None),
+        ],
+        "app" => &[
+            ("packages", Some("")),
+            ("imports", Some("")),
+            ("provides", None),
+        ],
+        "platform" => &[
+            ("requires", None),
+            ("exposes", Some("[]")),
+            ("packages", Some("{}")),
+            ("imports", Some("[]")),
+            ("provides", None),
+        ],
+        "package" => &[("exposes", Some("[]")), ("packages", Some("{}"))],
+        _ => unreachable!(),
+    };
+
+    let name = match kind {
+        "platform" | "package" => quoted(name),
+        _ => name.to_string(),
+    };
+
+    for section in sections[1..].iter() {
+        if !expected
+            .iter()
+            .any(|(keyword, _)| *keyword == section.keyword)
+        {
+            return Err(format!(
+                "{} modules don't have a `{}` section anymore, so it needs to be removed by hand.",
+                kind, section.keyword
+            ));
+        }
+    }
+
+    let mut header = format!("{} {}", kind, name);
+
+    for &(keyword, default) in expected {
+        let text = match (section(keyword), default) {
+            // platforms' requires used to have no rigids, like `requires { main : Str }`
+            (Some(text), _) if keyword == "requires" && !has_rigids(text) => {
+                format!("{{}} {}", text)
+            }
+            (Some(text), _) => text.to_string(),
+            (None, Some("")) => continue,
+            (None, Some(default)) => default.to_string(),
+            (None, None) => {
+                return Err(format!(
+                    "its `{}` section is missing, and there's no way to tell what it should be.",
+                    keyword
+                ))
+            }
+        };
+
+        header.push_str(&format!("\n    {} {}", keyword, text));
+    }
+
+    Ok(header)
+}
+
+fn strip_spaces<'a>(pattern: &'a Pattern<'a>) -> &'a Pattern<'a> {
+    match pattern {
+        Pattern::SpaceBefore(inner, _) | Pattern::SpaceAfter(inner, _) => strip_spaces(inner),
+        _ => pattern,
+    }
+}
+
+/// The names of the types and values a module defines at the top level, in order
+fn top_level_names(defs: &Defs) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+
+    for type_def in defs.type_defs.iter() {
+        let header = match type_def {
+            TypeDef::Alias { header, .. }
+            | TypeDef::Opaque { header, .. }
+            | TypeDef::Ability { header, .. }
+            | TypeDef::PatternSynonym { header, .. } => header,
+        };
+
+        names.push(header.name.value.to_string());
+    }
+
+    for value_def in defs.value_defs.iter() {
+        let pattern = match value_def {
+            ValueDef::Annotation(pattern, _) => &pattern.value,
+            ValueDef::Body(pattern, _) => &pattern.value,
+            ValueDef::AnnotatedBody { body_pattern, .. } => &body_pattern.value,
+            _ => continue,
+        };
+
+        if let Pattern::Identifier(name) = strip_spaces(pattern) {
+            if !names.iter().any(|existing| existing == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+
+    names
+}
+
+/// Writes an `interface` header for a file which has none, named after the file
+fn infer_header(path: &Path, source: &str) -> Migration {
+    let module_name = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default();
+
+    if !module_name.starts_with(|ch: char| ch.is_ascii_uppercase()) {
+        return Migration::Failed(format!(
+            "it has no header, and `{}` can't be a module name, since module names start with an uppercase letter.",
+            module_name
+        ));
+    }
+
+    let arena = Bump::new();
+    let defs = match module_defs().parse(&arena, State::new(source.as_bytes()), 0) {
+        Ok((_, defs, _)) => defs,
+        Err(_) => {
+            return Migration::Failed(
+                "it has no header, and its defs don't parse either.".to_string(),
+            )
+        }
+    };
+
+    let header = format!(
+        "interface {}\n    exposes [{}]\n    imports []",
+        module_name,
+        top_level_names(&defs).join(", ")
+    );
+
+    match format_header(&header) {
+        Some((header, _)) => Migration::Migrated(with_header(source, 0, &header)),
+        None => Migration::Failed("the header inferred for it doesn't parse.".to_string()),
+    }
+}
+
+fn migrate(path: &Path, source: &str) -> Migration {
+    if let Some((header, header_end)) = format_header(source) {
+        let migrated = with_header(source, header_end, &header);
+
+        return if migrated == source {
+            Migration::UpToDate
+        } else {
+            Migration::Migrated(migrated)
+        };
+    }
+
+    let first_token = highlight(source).into_iter().find(|token| {
+        !matches!(
+            token.value,
+            HighlightToken::Comment | HighlightToken::DocComment
+        )
+    });
+
+    let header_start = match first_token {
+        Some(token) => {
+            let start = token.region.start().offset as usize;
+            let word = &source[start..token.region.end().offset as usize];
+
+            if !MODULE_KINDS.contains(&word) {
+                return infer_header(path, source);
+            }
+
+            start
+        }
+        None => return Migration::UpToDate,
+    };
+
+    let header_end = guess_header_end(source, header_start);
+    let sections = split_sections(&source[header_start..header_end]);
+
+    let header = match rebuild(&sections) {
+        Ok(header) => header,
+        Err(problem) => return Migration::Failed(problem),
+    };
+
+    match format_header(&format!("{}{}", &source[..header_start], header)) {
+        Some((header, _)) => Migration::Migrated(with_header(source, header_end, &header)),
+        None => Migration::Failed(
+            "its header doesn't parse, even after migrating the syntax this tool knows about."
+                .to_string(),
+        ),
+    }
+}
+
+pub fn migrate_headers(matches: &ArgMatches) -> io::Result<i32> {
+    let check = matches.is_present(FLAG_CHECK);
+    let roots: Vec<PathBuf> = match matches.values_of_os(DIRECTORY_OR_FILES) {
+        Some(os_strs) => os_strs.map(PathBuf::from).collect(),
+        None => vec![PathBuf::from(".")],
+    };
+
+    let mut files = flatten_directories(roots);
+    files.sort();
+
+    let mut migrated = 0;
+    let mut failed = 0;
+
+    for path in files {
+        let source = std::fs::read_to_string(&path)?;
+
+        match migrate(&path, &source) {
+            Migration::UpToDate => {}
+            Migration::Migrated(new_source) => {
+                if !check {
+                    std::fs::write(&path, new_source)?;
+                }
+
+                println!("{}", path.display());
+
+                migrated += 1;
+            }
+            Migration::Failed(problem) => {
+                eprintln!(
+                    "\x1B[31m{}\x1B[39m couldn't be migrated, because {}",
+                    path.display(),
+                    problem
+                );
+
+                failed += 1;
+            }
+        }
+    }
+
+    match (migrated, check) {
+        (0, _) => println!("\nEvery header is up to date."),
+        (_, true) => println!("\n{} headers need to be migrated.", migrated),
+        (_, false) => println!("\nMigrated {} headers.", migrated),
+    }
+
+    Ok((failed > 0 || (check && migrated > 0)) as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{migrate, Migration};
+    use indoc::indoc;
+    use std::path::Path;
+
+    fn migrated(file_name: &str, source: &str) -> String {
+        match migrate(Path::new(file_name), source) {
+            Migration::Migrated(migrated) => migrated,
+            other => panic!("expected {} to be migrated, but got {:?}", file_name, other),
+        }
+    }
+
+    fn failure(file_name: &str, source: &str) -> String {
+        match migrate(Path::new(file_name), source) {
+            Migration::Failed(problem) => problem,
+            other => panic!("expected {} to fail, but got {:?}", file_name, other),
+        }
+    }
+
+    #[test]
+    fn up_to_date_header() {
+        let source = indoc!(
+            r#"
+            interface Numbers
+                exposes [x]
+                imports []
+
+            x = 1
+            "#
+        );
+
+        assert_eq!(
+            migrate(Path::new("Numbers.roc"), source),
+            Migration::UpToDate
+        );
+    }
+
+    #[test]
+    fn missing_section_gets_a_default() {
+        let source = indoc!(
+            r#"
+            interface Numbers
+                exposes [x]
+
+            x = 1
+            "#
+        );
+
+        assert_eq!(
+            migrated("Numbers.roc", source),
+            indoc!(
+                r#"
+                interface Numbers
+                    exposes [x]
+                    imports []
+
+                x = 1
+                "#
+            )
+        );
+    }
+
+    #[test]
+    fn sections_out_of_order() {
+        let source = indoc!(
+            r#"
+            interface Numbers
+                imports []
+                exposes [x]
+
+            x = 1
+            "#
+        );
+
+        assert_eq!(
+            migrated("Numbers.roc", source),
+            indoc!(
+                r#"
+                interface Numbers
+                    exposes [x]
+                    imports []
+
+                x = 1
+                "#
+            )
+        );
+    }
+
+    #[test]
+    fn old_platform_header() {
+        let source = indoc!(
+            r#"
+            platform folkertdev/foo
+                requires { main : Str }
+                exposes []
+                provides [mainForHost]
+
+            mainForHost : Str
+            mainForHost = main
+            "#
+        );
+
+        assert_eq!(
+            migrated("main.roc", source),
+            indoc!(
+                r#"
+                platform "folkertdev/foo"
+                    requires {} { main : Str }
+                    exposes []
+                    packages {}
+                    imports []
+                    provides [mainForHost]
+
+                mainForHost : Str
+                mainForHost = main
+                "#
+            )
+        );
+    }
+
+    #[test]
+    fn no_header() {
+        let source = indoc!(
+            r#"
+            x = 1
+
+            Y : U8
+            "#
+        );
+
+        assert_eq!(
+            migrated("Numbers.roc", source),
+            indoc!(
+                r#"
+                interface Numbers
+                    exposes [Y, x]
+                    imports []
+
+                x = 1
+
+                Y : U8
+                "#
+            )
+        );
+    }
+
+    #[test]
+    fn no_header_and_lowercase_file_name() {
+        let problem = failure("numbers.roc", "x = 1\n");
+
+        assert!(
+            problem.contains("`numbers` can't be a module name"),
+            "{}",
+            problem
+        );
+    }
+
+    #[test]
+    fn effects_in_the_header() {
+        let source = indoc!(
+            r#"
+            platform folkertdev/foo
+                requires { main : Str }
+                exposes []
+                provides [mainForHost]
+                effects fx.Effect { putLine : Str -> Effect {} }
+
+            mainForHost : Str
+            mainForHost = main
+            "#
+        );
+
+        assert!(failure("main.roc", source).contains("declares its effects in its header"));
+    }
+
+    #[test]
+    fn section_which_no_longer_exists() {
+        let source = indoc!(
+            r#"
+            interface Numbers
+                provides [x]
+                exposes [x]
+                imports []
+
+            x = 1
+            "#
+        );
+
+        let problem = failure("Numbers.roc", source);
+
+        assert!(
+            problem.contains("interface modules don't have a `provides` section anymore"),
+            "{}",
+            problem
+        );
+    }
+}
@@ -0,0 +1,320 @@
+//! `roc rename`, which renames a value everywhere it's used in the modules a file loads.
+use crate::{handle_loading_problem, COLUMN, LINE, NEW_NAME, ROC_FILE};
+use bumpalo::Bump;
+use clap::ArgMatches;
+use roc_can::traverse::{symbol_occurrences, NameAt, SymbolOccurrence};
+use roc_fmt::rewrite::{byte_region, Rewriter};
+use roc_load::{ExecutionMode, LoadConfig, Threading};
+use roc_module::symbol::{ModuleId, Symbol};
+use roc_packaging::cache::{self, RocCacheDir};
+use roc_parse::highlight::{highlight, HighlightToken};
+use roc_parse::keyword::KEYWORDS;
+use roc_parse::state::State;
+use roc_region::all::{LineColumn, LineInfo, Region};
+use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
+use roc_target::TargetInfo;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+
+fn is_ident_continue(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || ch == '_'
+}
+
+/// The offsets of each place `name` appears as a whole word in `source[range]`
+fn whole_words<'a>(
+    source: &'a str,
+    range: Range<usize>,
+    name: &'a str,
+) -> impl DoubleEndedIterator<Item = usize> + 'a {
+    let start = range.start;
+
+    source[range]
+        .match_indices(name)
+        .map(move |(offset, _)| start + offset)
+        .filter(move |&offset| {
+            let before = source[..offset].chars().next_back();
+            let after = source[offset + name.len()..].chars().next();
+
+            !before.map_or(false, is_ident_continue) && !after.map_or(false, is_ident_continue)
+        })
+}
+
+/// Where the name is in the source, for one occurrence of a symbol named `name`
+fn name_region(source: &str, occurrence: &SymbolOccurrence, name: &str) -> Option<Region> {
+    let start = occurrence.region.start().offset as usize;
+    let end = occurrence.region.end().offset as usize;
+
+    let offset = match occurrence.name_at {
+        NameAt::End => whole_words(source, start..end, name).next_back(),
+        NameAt::Start => whole_words(source, start..end, name).next(),
+        NameAt::Before => {
+            // only a colon can come between the name and its annotation, so that an earlier
+            // binding with the same name is never mistaken for this one
+            let before = source[..start].trim_end().strip_suffix(':')?.trim_end();
+
+            whole_words(source, 0..before.len(), name)
+                .next_back()
+                .filter(|offset| offset + name.len() == before.len())
+        }
+    }?;
+
+    Some(byte_region(offset..offset + name.len()))
+}
+
+/// Each place a module's header mentions a value called `name`, along with the module whose
+/// `Module.{ ... }` import it's in, if any
+fn header_mentions<'a>(source: &'a str, name: &str) -> Vec<(Option<&'a str>, Region)> {
+    let arena = Bump::new();
+    let header_end = match roc_parse::module::parse_header(&arena, State::new(source.as_bytes())) {
+        Ok((_, state)) => state.pos().offset as usize,
+        Err(_) => return Vec::new(),
+    };
+
+    let tokens = highlight(&source[..header_end]);
+    let text = |index: usize| -> &'a str {
+        let region = tokens[index].region;
+        &source[region.start().offset as usize..region.end().offset as usize]
+    };
+
+    // the module whose `.{ ... }` we're inside of, if any
+    let mut importing_from = None;
+    let mut mentions = Vec::new();
+
+    for (index, token) in tokens.iter().enumerate() {
+        match token.value {
+            HighlightToken::UppercaseIdent
+                if index + 2 < tokens.len() && text(index + 1) == "." && text(index + 2) == "{" =>
+            {
+                importing_from = Some(text(index));
+            }
+            HighlightToken::Punctuation if text(index) == "}" => importing_from = None,
+            HighlightToken::LowercaseIdent if text(index) == name => {
+                let qualifies_something = index + 1 < tokens.len() && text(index + 1) == ".";
+
+                if !qualifies_something {
+                    mentions.push((importing_from, token.region));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    mentions
+}
+
+/// The places a module's header mentions the value: its own exposes (or provides) list if it
+/// defines the value, or the `Module.{ ... }` import of the module which does.
+fn header_regions(
+    source: &str,
+    name: &str,
+    is_defining_module: bool,
+    defining_module_name: &str,
+) -> Vec<Region> {
+    header_mentions(source, name)
+        .into_iter()
+        .filter(|(importing_from, _)| match importing_from {
+            None => is_defining_module,
+            Some(module) => {
+                !is_defining_module && defining_module_name.rsplit('.').next() == Some(*module)
+            }
+        })
+        .map(|(_, region)| region)
+        .collect()
+}
+
+fn is_valid_value_name(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    matches!(chars.next(), Some('a'..='z'))
+        && chars.all(is_ident_continue)
+        && !KEYWORDS.contains(&name)
+}
+
+pub fn rename(matches: &ArgMatches) -> io::Result<i32> {
+    let arena = Bump::new();
+    let path = Path::new(matches.value_of_os(ROC_FILE).unwrap());
+    let new_name = matches.value_of(NEW_NAME).unwrap();
+
+    let (line, column) = match (
+        matches
+            .value_of(LINE)
+            .and_then(|line| line.parse::<u32>().ok()),
+        matches
+            .value_of(COLUMN)
+            .and_then(|col| col.parse::<u32>().ok()),
+    ) {
+        (Some(line), Some(column)) if line > 0 && column > 0 => (line, column),
+        _ => {
+            eprintln!("The line and column need to be positive whole numbers, starting from 1.");
+
+            return Ok(1);
+        }
+    };
+
+    if !is_valid_value_name(new_name) {
+        eprintln!(
+            "`{}` can't be the name of a value. Value names start with a lowercase letter, contain only letters, digits, and underscores, and aren't keywords.",
+            new_name
+        );
+
+        return Ok(1);
+    }
+
+    let load_config = LoadConfig {
+        target_info: TargetInfo::default_x86_64(),
+        render: RenderTarget::ColorTerminal,
+        palette: DEFAULT_PALETTE,
+        threading: Threading::AllAvailable,
+        exec_mode: ExecutionMode::Check,
+        ..Default::default()
+    };
+    let loaded = match roc_load::load_and_typecheck(
+        &arena,
+        path.to_path_buf(),
+        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+        load_config,
+    ) {
+        Ok(loaded) => loaded,
+        Err(problem) => return handle_loading_problem(problem),
+    };
+
+    if loaded.total_problems() > 0 {
+        eprintln!("Please fix the problems `roc check` reports before renaming anything.");
+
+        return Ok(1);
+    }
+
+    let home = loaded.module_id;
+    let interns = &loaded.interns;
+    let (_, home_source) = &loaded.sources[&home];
+    let offset = LineInfo::new(home_source)
+        .convert_line_column(LineColumn {
+            line: line - 1,
+            column: column - 1,
+        })
+        .offset as usize;
+
+    let target: Option<Symbol> = symbol_occurrences(&loaded.declarations_by_id[&home])
+        .iter()
+        .find(|occurrence| {
+            let name = occurrence.symbol.as_str(interns);
+
+            name_region(home_source, occurrence, name).map_or(false, |region| {
+                region.start().offset as usize <= offset && offset <= region.end().offset as usize
+            })
+        })
+        .map(|occurrence| occurrence.symbol);
+
+    let symbol = match target {
+        Some(symbol) if !symbol.is_builtin() => symbol,
+        Some(_) => {
+            eprintln!("Builtins can't be renamed.");
+
+            return Ok(1);
+        }
+        None => {
+            eprintln!(
+                "There's no value defined or used at line {}, column {}. Only values can be renamed; renaming types, tags, and record fields isn't supported yet.",
+                line, column
+            );
+
+            return Ok(1);
+        }
+    };
+
+    let old_name = symbol.as_str(interns).to_string();
+    let defining_module = symbol.module_id();
+    let defining_module_name = interns.module_name(defining_module).to_string();
+    let is_top_level = loaded.declarations_by_id[&defining_module]
+        .symbols
+        .iter()
+        .any(|loc_symbol| loc_symbol.value == symbol);
+
+    let mut module_ids: Vec<ModuleId> = loaded.declarations_by_id.keys().copied().collect();
+    module_ids.sort_by_key(|module_id| interns.module_name(*module_id).to_string());
+
+    let mut renames = Vec::new();
+
+    for module_id in module_ids {
+        let (module_path, source) = match loaded.sources.get(&module_id) {
+            Some(entry) if !module_id.is_builtin() => entry,
+            _ => continue,
+        };
+
+        let mut regions: Vec<Region> = symbol_occurrences(&loaded.declarations_by_id[&module_id])
+            .iter()
+            .filter(|occurrence| occurrence.symbol == symbol)
+            .filter_map(|occurrence| name_region(source, occurrence, &old_name))
+            .collect();
+
+        if is_top_level {
+            regions.extend(header_regions(
+                source,
+                &old_name,
+                module_id == defining_module,
+                &defining_module_name,
+            ));
+        }
+
+        if regions.is_empty() {
+            continue;
+        }
+
+        regions.sort();
+        regions.dedup();
+
+        // Any module the name appears in could have its own `new_name` in scope there, whether
+        // it defines one (in any scope, since IdentIds holds every name in the module) or
+        // imports one unqualified.
+        let name_taken = interns
+            .all_ident_ids
+            .get(&module_id)
+            .and_then(|ident_ids| ident_ids.get_id(new_name))
+            .is_some()
+            || header_mentions(source, new_name)
+                .iter()
+                .any(|(importing_from, _)| importing_from.is_some());
+
+        if name_taken {
+            eprintln!(
+                "`{}` is already the name of something in {}, so renaming `{}` to it could change what some code refers to.",
+                new_name,
+                interns.module_name(module_id),
+                old_name
+            );
+
+            return Ok(1);
+        }
+
+        renames.push((module_path, source, regions));
+    }
+
+    let mut total = 0;
+
+    // only write anything once every module has been checked for clashes
+    for (module_path, source, regions) in renames {
+        let mut rewriter = Rewriter::new(source);
+
+        for region in regions.iter() {
+            rewriter.replace(*region, new_name);
+        }
+
+        let renamed = rewriter
+            .apply()
+            .map_err(|overlap| io::Error::new(io::ErrorKind::InvalidData, overlap))?;
+
+        std::fs::write(module_path, renamed)?;
+
+        println!("{}: {} renamed", module_path.display(), regions.len());
+
+        total += regions.len();
+    }
+
+    println!(
+        "\nRenamed `{}` to `{}` in {} places.",
+        old_name, new_name, total
+    );
+
+    Ok(0)
+}
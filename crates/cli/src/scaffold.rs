@@ -0,0 +1,144 @@
+//! `roc new`, which starts a project from a template: an app, or a platform with a host in Rust,
+//! Zig, or C, along with an app which uses it.
+//!
+//! The hosts are written by hand, but the names of what Roc and the host call on each other come
+//! from the compiler, and Rust hosts get the platform's types from `roc glue`, so a new project
+//! matches what this version of Roc expects of hosts.
+use crate::{FLAG_LANG, FLAG_PLATFORM, PROJECT_DIR};
+use clap::ArgMatches;
+use roc_error_macros::{internal_error, user_error};
+use roc_module::mangle;
+use roc_parse::header::PlatformAllocator;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The languages `roc new platform` can write a host in
+pub const HOST_LANGUAGES: [&str; 3] = ["rust", "zig", "c"];
+
+const APP: &str = include_str!("../templates/new/app.roc");
+const PLATFORM: &str = include_str!("../templates/new/platform.roc");
+const HOST_C: &str = include_str!("../templates/new/host.c");
+const HOST_ZIG: &str = include_str!("../templates/new/host.zig");
+const RUST_CARGO_TOML: &str = include_str!("../templates/new/rust/Cargo.toml");
+const RUST_BUILD_RS: &str = include_str!("../templates/new/rust/build.rs");
+const RUST_HOST_C: &str = include_str!("../templates/new/rust/host.c");
+const RUST_MAIN_RS: &str = include_str!("../templates/new/rust/main.rs");
+const RUST_LIB_RS: &str = include_str!("../templates/new/rust/lib.rs");
+
+/// What the platform provides to its host, as its header says in [PLATFORM]
+const MAIN_FOR_HOST: &str = "mainForHost";
+
+/// `roc new app`: an app which uses the platform `--platform` gives
+pub fn new_app(matches: &ArgMatches) -> io::Result<i32> {
+    let dir = Path::new(matches.value_of_os(PROJECT_DIR).unwrap());
+    let platform = matches.value_of(FLAG_PLATFORM).unwrap();
+    let app_path = dir.join("main.roc");
+
+    create_project_dir(dir)?;
+    fs::write(&app_path, render(APP, dir, platform))?;
+
+    println!(
+        "Created an app in {}. Run it with:\n\n    roc run {}\n",
+        dir.display(),
+        app_path.display()
+    );
+
+    Ok(0)
+}
+
+/// `roc new platform`: a platform, with a host in the language `--lang` gives, in a `platform`
+/// directory, and an app which uses it
+pub fn new_platform(matches: &ArgMatches) -> io::Result<i32> {
+    let dir = Path::new(matches.value_of_os(PROJECT_DIR).unwrap());
+    let lang = matches.value_of(FLAG_LANG).unwrap();
+    let platform_dir = dir.join("platform");
+    let platform_main_roc = platform_dir.join("main.roc");
+    let app_path = dir.join("main.roc");
+
+    create_project_dir(dir)?;
+    fs::create_dir(&platform_dir)?;
+    fs::write(&platform_main_roc, render(PLATFORM, dir, ""))?;
+
+    match lang {
+        "c" => write_host(&platform_dir.join("host.c"), HOST_C, dir)?,
+        "zig" => write_host(&platform_dir.join("host.zig"), HOST_ZIG, dir)?,
+        "rust" => {
+            let src_dir = platform_dir.join("src");
+
+            fs::create_dir(&src_dir)?;
+            fs::write(platform_dir.join("Cargo.toml"), RUST_CARGO_TOML)?;
+            fs::write(platform_dir.join("build.rs"), RUST_BUILD_RS)?;
+            fs::write(platform_dir.join("host.c"), RUST_HOST_C)?;
+            fs::write(src_dir.join("main.rs"), RUST_MAIN_RS)?;
+            write_host(&src_dir.join("lib.rs"), RUST_LIB_RS, dir)?;
+
+            let exit_code = roc_glue::generate(&platform_main_roc, &src_dir.join("glue.rs"))?;
+
+            if exit_code != 0 {
+                return Ok(exit_code);
+            }
+        }
+        _ => unreachable!(),
+    }
+
+    fs::write(&app_path, render(APP, dir, "platform/main.roc"))?;
+
+    println!(
+        "Created a platform with a host in {} in {}, and an app which uses it. Run the app with:\n\n    roc run {}\n",
+        lang,
+        dir.display(),
+        app_path.display()
+    );
+
+    Ok(0)
+}
+
+fn create_project_dir(dir: &Path) -> io::Result<()> {
+    if dir
+        .read_dir()
+        .map_or(false, |mut entries| entries.next().is_some())
+    {
+        user_error!(
+            "{} already has files in it. Give me a new directory, or an empty one, to create the project in.",
+            dir.display()
+        );
+    }
+
+    fs::create_dir_all(dir)
+}
+
+/// Fills in a template's `{{name}}`, `{{platform}}`, and `{{entry_point}}`
+fn render(template: &str, dir: &Path, platform: &str) -> String {
+    let name = match dir.file_name() {
+        Some(name) => name.to_string_lossy(),
+        None => dir.to_string_lossy(),
+    };
+
+    template
+        .replace("{{name}}", &name)
+        .replace("{{platform}}", platform)
+        .replace(
+            "{{entry_point}}",
+            &mangle::host_exposed_generic_name(MAIN_FOR_HOST, 1),
+        )
+}
+
+fn write_host(path: &Path, template: &str, dir: &Path) -> io::Result<()> {
+    let host = render(template, dir, "");
+
+    // The hosts are written by hand, so make sure they still define everything Roc calls
+    let host_fns = PlatformAllocator::default().host_fns();
+
+    for host_fn in host_fns.iter().chain([&roc_linker::OOM_HANDLER_FN]) {
+        if !host.contains(host_fn) {
+            internal_error!(
+                "The template for {} doesn't define {}",
+                path.display(),
+                host_fn
+            );
+        }
+    }
+
+    fs::write(path, host)
+}
@@ -0,0 +1,144 @@
+//! Choosing which top-level `expect`s `roc test` runs, with `--filter` and `--changed`.
+use roc_collections::{MutMap, MutSet};
+use roc_module::symbol::ModuleId;
+use roc_region::all::Region;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Whether `text` matches the glob `pattern`, where `*` matches any number of characters
+/// (including none), and `?` matches exactly one.
+pub fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+
+    // where to resume after the most recent `*`, if the rest of the pattern fails to match
+    let mut backtrack = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    // let the `*` match one more character
+                    backtrack = Some((star, matched + 1));
+                    p = star + 1;
+                    t = matched + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// The name of a top-level `expect` or `bench`, which is the comment just above it.
+/// `region` must start where the comments before it do.
+pub fn comment_name(source: &str, region: Region) -> Option<&str> {
+    let start = (region.start().offset as usize).min(source.len());
+    let first_line = source[start..].lines().next().unwrap_or("").trim();
+
+    match first_line.strip_prefix('#') {
+        Some(comment) => {
+            let comment = comment.trim_start_matches('#').trim();
+
+            (!comment.is_empty()).then_some(comment)
+        }
+        None => None,
+    }
+}
+
+/// Whether any of the `--filter` patterns selects an `expect`. A pattern selects every `expect`
+/// in the modules whose names it matches, like `Parser`, and the `expect`s whose
+/// `Module.name` it matches, like `Parser.*url*`.
+pub fn filter_selects(patterns: &[&str], module_name: &str, expect_name: Option<&str>) -> bool {
+    let qualified = format!("{}.{}", module_name, expect_name.unwrap_or(""));
+
+    patterns
+        .iter()
+        .any(|pattern| glob_matches(pattern, module_name) || glob_matches(pattern, &qualified))
+}
+
+/// The modules whose source files changed since `since`, along with every module that imports
+/// one of them, directly or indirectly, since their `expect`s may behave differently now.
+pub fn changed_modules(
+    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    imports: &MutMap<ModuleId, MutSet<ModuleId>>,
+    since: SystemTime,
+) -> MutSet<ModuleId> {
+    let mut changed: MutSet<ModuleId> = sources
+        .iter()
+        .filter(|(_, (path, _))| modified_since(path, since))
+        .map(|(module_id, _)| *module_id)
+        .collect();
+
+    // keep adding the importers of changed modules, until there are no new ones
+    loop {
+        let before = changed.len();
+
+        for (importer, imported) in imports.iter() {
+            if !changed.contains(importer) && imported.iter().any(|m| changed.contains(m)) {
+                changed.insert(*importer);
+            }
+        }
+
+        if changed.len() == before {
+            return changed;
+        }
+    }
+}
+
+fn modified_since(path: &Path, since: SystemTime) -> bool {
+    match std::fs::metadata(path).and_then(|metadata| metadata.modified()) {
+        Ok(modified) => modified > since,
+        // if we can't tell, assume it changed
+        Err(_) => true,
+    }
+}
+
+/// Where we remember when the tests of the app or package at `root` last all passed
+fn last_run_path(root: &Path) -> PathBuf {
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+
+    let mut hasher = DefaultHasher::new();
+    root.hash(&mut hasher);
+
+    roc_packaging::cache::roc_cache_dir()
+        .join("test-runs")
+        .join(format!("{:016x}", hasher.finish()))
+}
+
+/// When the tests of the app or package at `root` last all passed, if they ever did
+pub fn last_successful_run(root: &Path) -> Option<SystemTime> {
+    let contents = std::fs::read_to_string(last_run_path(root)).ok()?;
+    let nanos = contents.trim().parse::<u64>().ok()?;
+
+    Some(UNIX_EPOCH + Duration::from_nanos(nanos))
+}
+
+/// Remember that all the tests at `root` passed, as of when the run `started`
+pub fn record_successful_run(root: &Path, started: SystemTime) -> io::Result<()> {
+    let path = last_run_path(root);
+    let nanos = started
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_nanos() as u64)
+        .unwrap_or_default();
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    std::fs::write(path, nanos.to_string())
+}
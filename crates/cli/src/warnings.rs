@@ -0,0 +1,226 @@
+//! Which warnings to report, and how. Levels come from the `[warnings]` table of the nearest
+//! `roc.toml`, like
+//!
+//! ```toml
+//! [warnings]
+//! unused-import = "allow"
+//! all = "error"
+//! ```
+//!
+//! and then from `--allow`, `--warn`, and `--deny` flags, which take precedence over the file.
+//!
+//! The same file's `[reports]` table changes how problems are shown:
+//!
+//! ```toml
+//! [reports]
+//! circular-type-unfold-steps = 3
+//! ```
+use crate::{FLAG_ALLOW, FLAG_DENY, FLAG_WARN};
+use clap::ArgMatches;
+use roc_error_macros::user_error;
+use roc_reporting::cli::{WarningLevel, WarningLevels};
+use roc_reporting::error::r#type::set_circular_type_unfold_steps;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+pub const CONFIG_FILENAME: &str = "roc.toml";
+
+/// The `roc.toml` in the directory of the given .roc file, or in the nearest directory above it
+fn find_config(roc_file: &Path) -> Option<PathBuf> {
+    let dir = roc_file.canonicalize().ok()?.parent()?.to_path_buf();
+
+    dir.ancestors()
+        .map(|ancestor| ancestor.join(CONFIG_FILENAME))
+        .find(|config| config.is_file())
+}
+
+/// The parts of `roc.toml` we use. Other tables are ignored, so the file can grow new sections.
+#[derive(Debug, Default, Deserialize)]
+struct Config {
+    #[serde(default)]
+    warnings: BTreeMap<String, String>,
+    #[serde(default)]
+    reports: ReportsConfig,
+}
+
+/// The `[reports]` table of a `roc.toml`
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct ReportsConfig {
+    /// How many times to unfold the `∞` in circular types
+    circular_type_unfold_steps: Option<usize>,
+}
+
+/// Sets the levels from the `[warnings]` table of a `roc.toml`'s contents, and returns its
+/// `[reports]` table. Keys in a TOML table have no order, so `all` is applied first and the levels
+/// of particular warnings override it.
+fn apply_config(levels: &mut WarningLevels, contents: &str) -> Result<ReportsConfig, String> {
+    let config: Config = toml::from_str(contents).map_err(|err| err.to_string())?;
+    let (all, particular): (Vec<_>, Vec<_>) = config
+        .warnings
+        .iter()
+        .partition(|(name, _)| name.as_str() == "all");
+
+    for (name, level) in all.into_iter().chain(particular) {
+        let level = level
+            .parse::<WarningLevel>()
+            .map_err(|problem| format!("the level of `{}` is invalid: {}", name, problem))?;
+
+        levels.set(name, level)?;
+    }
+
+    Ok(config.reports)
+}
+
+/// The warning levels for checking or building the given .roc file. Settings from the `[reports]`
+/// table of its `roc.toml` are applied to reports along the way.
+pub fn warning_levels(matches: &ArgMatches, roc_file: &Path) -> WarningLevels {
+    let mut levels = WarningLevels::default();
+
+    if let Some(config) = find_config(roc_file) {
+        let contents = match std::fs::read_to_string(&config) {
+            Ok(contents) => contents,
+            Err(err) => user_error!("I could not read {}: {}", config.display(), err),
+        };
+
+        match apply_config(&mut levels, &contents) {
+            Ok(reports) => {
+                if let Some(steps) = reports.circular_type_unfold_steps {
+                    set_circular_type_unfold_steps(steps);
+                }
+            }
+            Err(problem) => user_error!("In {}: {}", config.display(), problem),
+        }
+    }
+
+    // flags are applied in the order they were given, so `--deny all --allow unused-def` denies
+    // every warning except unused defs
+    let mut flags: Vec<(usize, &str, WarningLevel)> = Vec::new();
+
+    for (flag, level) in [
+        (FLAG_ALLOW, WarningLevel::Allow),
+        (FLAG_WARN, WarningLevel::Warn),
+        (FLAG_DENY, WarningLevel::Error),
+    ] {
+        if let (Some(indices), Some(names)) = (matches.indices_of(flag), matches.values_of(flag)) {
+            flags.extend(indices.zip(names).map(|(index, name)| (index, name, level)));
+        }
+    }
+
+    flags.sort_by_key(|(index, _, _)| *index);
+
+    for (_, name, level) in flags {
+        if let Err(problem) = levels.set(name, level) {
+            user_error!("{}", problem);
+        }
+    }
+
+    levels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_config, ReportsConfig};
+    use roc_reporting::cli::{WarningLevel, WarningLevels};
+
+    fn levels(contents: &str) -> Result<WarningLevels, String> {
+        let mut levels = WarningLevels::default();
+
+        apply_config(&mut levels, contents)?;
+
+        Ok(levels)
+    }
+
+    fn reports(contents: &str) -> Result<ReportsConfig, String> {
+        apply_config(&mut WarningLevels::default(), contents)
+    }
+
+    #[test]
+    fn no_warnings_table() {
+        let levels = levels("[package]\nname = \"foo\"\n").unwrap();
+
+        assert_eq!(levels.level(Some("unused-def")), WarningLevel::Warn);
+    }
+
+    #[test]
+    fn sets_warning_levels() {
+        let levels = levels(
+            r#"
+            [warnings]
+            unused-def = "allow"
+            "unused-import" = "error" # quoted keys and comments are fine
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(levels.level(Some("unused-def")), WarningLevel::Allow);
+        assert_eq!(levels.level(Some("unused-import")), WarningLevel::Error);
+        assert_eq!(levels.level(Some("unused-argument")), WarningLevel::Warn);
+    }
+
+    #[test]
+    fn all_is_overridden_by_particular_warnings() {
+        let levels = levels(
+            r#"
+            [warnings]
+            unused-import = "allow"
+            all = "error"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(levels.level(Some("unused-import")), WarningLevel::Allow);
+        assert_eq!(levels.level(Some("unused-def")), WarningLevel::Error);
+    }
+
+    #[test]
+    fn unknown_warning() {
+        let problem = levels("[warnings]\nunused-everything = \"allow\"\n").unwrap_err();
+
+        assert!(problem.contains("`unused-everything` isn't the name of a warning"));
+    }
+
+    #[test]
+    fn unknown_level() {
+        let problem = levels("[warnings]\nunused-def = \"ignore\"\n").unwrap_err();
+
+        assert!(problem.contains("`ignore` isn't a warning level"));
+    }
+
+    #[test]
+    fn level_is_not_a_string() {
+        assert!(levels("[warnings]\nunused-def = 1\n").is_err());
+    }
+
+    #[test]
+    fn invalid_toml() {
+        assert!(levels("[warnings\n").is_err());
+    }
+
+    #[test]
+    fn no_reports_table() {
+        let reports = reports("[warnings]\nunused-def = \"allow\"\n").unwrap();
+
+        assert_eq!(reports.circular_type_unfold_steps, None);
+    }
+
+    #[test]
+    fn sets_circular_type_unfold_steps() {
+        let reports = reports("[reports]\ncircular-type-unfold-steps = 5\n").unwrap();
+
+        assert_eq!(reports.circular_type_unfold_steps, Some(5));
+    }
+
+    #[test]
+    fn unknown_report_setting() {
+        let problem = reports("[reports]\nunfold = 5\n").unwrap_err();
+
+        assert!(problem.contains("unknown field `unfold`"), "{}", problem);
+    }
+
+    #[test]
+    fn negative_unfold_steps() {
+        assert!(reports("[reports]\ncircular-type-unfold-steps = -1\n").is_err());
+    }
+}
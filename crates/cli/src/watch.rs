@@ -0,0 +1,204 @@
+//! The dev loop behind `roc dev --watch`: rebuild the app whenever one of its source files
+//! changes, and restart it with the new build.
+//!
+//! This restarts the host process rather than patching the new app code into the running one;
+//! `--hot`, which would do that, is rejected as unsupported.
+use std::collections::HashMap;
+use std::env;
+use std::ffi::{OsStr, OsString};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::{CMD_DEV, FLAG_WATCH};
+
+/// How often the source files are checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Modification times of the .roc files that make up an app.
+type Snapshot = HashMap<PathBuf, SystemTime>;
+
+/// Runs `roc dev` on `roc_file`, and runs it again every time a .roc file in the directory of
+/// `roc_file` (or one of its subdirectories, other than hidden and build output ones) is added,
+/// removed, or modified. Only returns if
+/// something goes wrong; stop it with Ctrl-C.
+pub fn watch(roc_file: &Path) -> io::Result<i32> {
+    let root_dir = match roc_file.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => env::current_dir()?,
+    };
+
+    // Pass along everything we were given, except for the flag that got us here.
+    let watch_flag = format!("--{FLAG_WATCH}");
+    let dev_args: Vec<OsString> = env::args_os()
+        .skip_while(|arg| arg != CMD_DEV)
+        .skip(1)
+        .filter(|arg| arg != watch_flag.as_str())
+        .collect();
+
+    let mut snapshot = snapshot(&root_dir);
+    let mut child = spawn_dev(&dev_args)?;
+
+    loop {
+        thread::sleep(POLL_INTERVAL);
+
+        let latest = self::snapshot(&root_dir);
+
+        if latest == snapshot {
+            continue;
+        }
+
+        snapshot = latest;
+
+        // The app may have exited on its own already, in which case there is nothing to kill.
+        if child.try_wait()?.is_none() {
+            child.kill()?;
+            child.wait()?;
+        }
+
+        println!(
+            "\n\x1B[36m{}\x1B[39m\n\nSource files changed, rebuilding…\n",
+            "─".repeat(80)
+        );
+
+        child = spawn_dev(&dev_args)?;
+    }
+}
+
+fn spawn_dev(dev_args: &[OsString]) -> io::Result<Child> {
+    Command::new(env::current_exe()?)
+        .arg(CMD_DEV)
+        .args(dev_args)
+        .spawn()
+}
+
+/// Directories that never contain an app's sources, but can be large or change during a build
+const IGNORED_DIRS: &[&str] = &["target", "zig-cache", "zig-out", "node_modules"];
+
+fn is_ignored_dir(name: &OsStr) -> bool {
+    match name.to_str() {
+        Some(name) => name.starts_with('.') || IGNORED_DIRS.contains(&name),
+        None => false,
+    }
+}
+
+/// Records the modification time of every .roc file under `root_dir`. Symlinked directories are
+/// not followed, so that a symlink cycle can't keep this from finishing. IO errors are reported
+/// and skipped, so that one unreadable directory doesn't stop the watcher.
+fn snapshot(root_dir: &Path) -> Snapshot {
+    let mut snapshot = Snapshot::default();
+    let mut dirs = vec![root_dir.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        let entries = match dir.read_dir() {
+            Ok(entries) => entries,
+            Err(err) => {
+                eprintln!("Not watching {}: {}", dir.display(), err);
+                continue;
+            }
+        };
+
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(err) => {
+                    eprintln!("Not watching an entry of {}: {}", dir.display(), err);
+                    continue;
+                }
+            };
+
+            // Unlike Path::is_dir, this doesn't follow symlinks.
+            let is_dir = match entry.file_type() {
+                Ok(file_type) => file_type.is_dir(),
+                Err(err) => {
+                    eprintln!("Not watching {}: {}", entry.path().display(), err);
+                    continue;
+                }
+            };
+
+            let path = entry.path();
+
+            if is_dir {
+                if !is_ignored_dir(&entry.file_name()) {
+                    dirs.push(path);
+                }
+            } else if path.extension().and_then(|ext| ext.to_str()) == Some("roc") {
+                // A file may be removed between listing the directory and reading its metadata;
+                // the next snapshot will reflect that.
+                if let Ok(modified) = path.metadata().and_then(|meta| meta.modified()) {
+                    snapshot.insert(path, modified);
+                }
+            }
+        }
+    }
+
+    snapshot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::snapshot;
+    use std::fs;
+    use std::path::Path;
+
+    fn touch(path: &Path) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "").unwrap();
+    }
+
+    #[test]
+    fn snapshot_finds_nested_roc_files() {
+        let dir = tempfile::tempdir().unwrap();
+        touch(&dir.path().join("main.roc"));
+        touch(&dir.path().join("Parser/Json.roc"));
+        touch(&dir.path().join("README.md"));
+
+        let snapshot = snapshot(dir.path());
+
+        let mut found: Vec<_> = snapshot.keys().collect();
+        found.sort();
+        assert_eq!(
+            found,
+            [
+                &dir.path().join("Parser/Json.roc"),
+                &dir.path().join("main.roc")
+            ]
+        );
+    }
+
+    #[test]
+    fn snapshot_skips_ignored_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        touch(&dir.path().join("main.roc"));
+        touch(&dir.path().join(".git/Main.roc"));
+        touch(&dir.path().join("target/Main.roc"));
+        touch(&dir.path().join("zig-cache/Main.roc"));
+
+        let snapshot = snapshot(dir.path());
+
+        assert_eq!(snapshot.len(), 1);
+        assert!(snapshot.contains_key(&dir.path().join("main.roc")));
+    }
+
+    #[test]
+    fn snapshot_skips_unreadable_root() {
+        let dir = tempfile::tempdir().unwrap();
+
+        assert!(snapshot(&dir.path().join("missing")).is_empty());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn snapshot_terminates_on_symlink_cycle() {
+        let dir = tempfile::tempdir().unwrap();
+        touch(&dir.path().join("pkg/main.roc"));
+        std::os::unix::fs::symlink(dir.path(), dir.path().join("pkg/loop")).unwrap();
+
+        let snapshot = snapshot(dir.path());
+
+        assert_eq!(snapshot.len(), 1);
+        assert!(snapshot.contains_key(&dir.path().join("pkg/main.roc")));
+    }
+}
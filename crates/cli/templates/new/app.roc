@@ -0,0 +1,6 @@
+app "{{name}}"
+    packages { pf: "{{platform}}" }
+    imports []
+    provides [main] to pf
+
+main = "Hello, World!\n"
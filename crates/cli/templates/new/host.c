@@ -0,0 +1,99 @@
+#include <errno.h>
+#include <stdbool.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <unistd.h>
+
+#ifndef _WIN32
+#include <sys/mman.h>
+#endif
+
+// The functions Roc calls on the host
+
+void* roc_alloc(size_t size, unsigned int alignment) { return malloc(size); }
+
+void* roc_realloc(void* ptr, size_t new_size, size_t old_size, unsigned int alignment) {
+  return realloc(ptr, new_size);
+}
+
+void roc_dealloc(void* ptr, unsigned int alignment) { free(ptr); }
+
+void roc_panic(void* ptr, unsigned int alignment) {
+  char* msg = (char*)ptr;
+  fprintf(stderr, "Application crashed with message\n\n    %s\n\nShutting down\n", msg);
+  exit(1);
+}
+
+void roc_oom(size_t size, unsigned int alignment) {
+  fprintf(stderr, "Roc ran out of memory allocating %zu bytes\n", size);
+  exit(1);
+}
+
+void* roc_memcpy(void* dest, const void* src, size_t n) { return memcpy(dest, src, n); }
+
+void* roc_memset(void* str, int c, size_t n) { return memset(str, c, n); }
+
+// `roc test` and `roc dev` use these to report failed expects
+
+int roc_shm_open(char* name, int oflag, int mode) {
+#ifdef _WIN32
+  return 0;
+#else
+  return shm_open(name, oflag, mode);
+#endif
+}
+
+void* roc_mmap(void* addr, int length, int prot, int flags, int fd, int offset) {
+#ifdef _WIN32
+  return addr;
+#else
+  return mmap(addr, length, prot, flags, fd, offset);
+#endif
+}
+
+int roc_getppid() {
+#ifdef _WIN32
+  return 0;
+#else
+  return getppid();
+#endif
+}
+
+// The layout of a Roc Str. Strings of up to 23 bytes (on 64-bit targets) are stored in the struct
+// itself, in which case the last byte is their length with its highest bit set.
+
+struct RocStr {
+  char* bytes;
+  size_t len;
+  size_t capacity;
+};
+
+bool is_small_str(struct RocStr str) { return ((ssize_t)str.capacity) < 0; }
+
+size_t roc_str_len(struct RocStr str) {
+  if (is_small_str(str)) {
+    char* bytes = (char*)&str;
+    return (size_t)((unsigned char)bytes[sizeof(str) - 1] ^ 0b10000000);
+  } else {
+    return str.len;
+  }
+}
+
+// The app's `main`, by way of the platform's `mainForHost`
+
+extern void {{entry_point}}(struct RocStr* main);
+
+int main() {
+  struct RocStr str;
+  {{entry_point}}(&str);
+
+  char* str_bytes = is_small_str(str) ? (char*)&str : str.bytes;
+
+  if (write(1, str_bytes, roc_str_len(str)) < 0) {
+    fprintf(stderr, "Error writing to stdout: %s\n", strerror(errno));
+    return 1;
+  }
+
+  return 0;
+}
@@ -0,0 +1,3 @@
+extern int rust_main();
+
+int main() { return rust_main(); }
@@ -0,0 +1,107 @@
+#![allow(non_snake_case)]
+
+// The types the platform shares with its host, from `roc glue main.roc src/glue.rs`. Run that
+// again whenever they change.
+mod glue;
+
+use core::ffi::c_void;
+use roc_std::{RocPanic, RocPanicTag, RocStr};
+use std::io::Write;
+
+// The functions Roc calls on the host
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_alloc(size: usize, _alignment: u32) -> *mut c_void {
+    libc::malloc(size)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_realloc(
+    c_ptr: *mut c_void,
+    new_size: usize,
+    _old_size: usize,
+    _alignment: u32,
+) -> *mut c_void {
+    libc::realloc(c_ptr, new_size)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_dealloc(c_ptr: *mut c_void, _alignment: u32) {
+    libc::free(c_ptr)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_panic(payload: &RocPanic, tag_id: u32) {
+    eprintln!("{}", payload.describe(tag_id));
+
+    let exit_code = RocPanicTag::from_u32(tag_id).map_or(1, RocPanicTag::exit_code);
+    std::process::exit(exit_code);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_oom(size: usize, _alignment: u32) {
+    eprintln!("Roc ran out of memory allocating {} bytes", size);
+    std::process::exit(1);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_memcpy(dst: *mut c_void, src: *mut c_void, n: usize) -> *mut c_void {
+    libc::memcpy(dst, src, n)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_memset(dst: *mut c_void, c: i32, n: usize) -> *mut c_void {
+    libc::memset(dst, c, n)
+}
+
+// `roc test` and `roc dev` use these to report failed expects
+
+#[cfg(unix)]
+#[no_mangle]
+pub unsafe extern "C" fn roc_getppid() -> libc::pid_t {
+    libc::getppid()
+}
+
+#[cfg(unix)]
+#[no_mangle]
+pub unsafe extern "C" fn roc_mmap(
+    addr: *mut libc::c_void,
+    len: libc::size_t,
+    prot: libc::c_int,
+    flags: libc::c_int,
+    fd: libc::c_int,
+    offset: libc::off_t,
+) -> *mut libc::c_void {
+    libc::mmap(addr, len, prot, flags, fd, offset)
+}
+
+#[cfg(unix)]
+#[no_mangle]
+pub unsafe extern "C" fn roc_shm_open(
+    name: *const libc::c_char,
+    oflag: libc::c_int,
+    mode: libc::mode_t,
+) -> libc::c_int {
+    libc::shm_open(name, oflag, mode as libc::c_uint)
+}
+
+// The app's `main`, by way of the platform's `mainForHost`
+
+extern "C" {
+    #[link_name = "{{entry_point}}"]
+    fn roc_main(_: &mut RocStr);
+}
+
+#[no_mangle]
+pub extern "C" fn rust_main() -> i32 {
+    let mut main_str = RocStr::default();
+    unsafe { roc_main(&mut main_str) };
+
+    if let Err(err) = std::io::stdout().write_all(main_str.as_bytes()) {
+        eprintln!("Error writing to stdout: {}", err);
+
+        return 1;
+    }
+
+    0
+}
@@ -10,12 +10,16 @@ extern crate roc_module;
 #[cfg(test)]
 mod cli_run {
     use cli_utils::helpers::{
-        extract_valgrind_errors, file_path_from_root, fixture_file, fixtures_dir, known_bad_file,
-        run_cmd, run_roc, run_with_valgrind, strip_colors, Out, ValgrindError, ValgrindErrorXWhat,
+        build_roc_bin_cached, extract_valgrind_errors, file_path_from_root, fixture_file,
+        fixtures_dir, known_bad_file, run_cmd, run_roc, run_with_valgrind, strip_colors, Out,
+        ValgrindError, ValgrindErrorXWhat,
     };
     use const_format::concatcp;
     use indoc::indoc;
-    use roc_cli::{CMD_BUILD, CMD_CHECK, CMD_DEV, CMD_FORMAT, CMD_RUN, CMD_TEST};
+    use roc_cli::{
+        CMD_BENCH, CMD_BUILD, CMD_CHECK, CMD_DEV, CMD_FORMAT, CMD_IMPORTS, CMD_MIGRATE_HEADERS,
+        CMD_NEW, CMD_PLATFORM, CMD_RENAME, CMD_RUN, CMD_TEST,
+    };
     use roc_test_utils::assert_multiline_str_eq;
     use serial_test::serial;
     use std::iter;
@@ -52,8 +56,10 @@ mod cli_run {
     const LINKER_FLAG: &str = concatcp!("--", roc_cli::FLAG_LINKER);
     const CHECK_FLAG: &str = concatcp!("--", roc_cli::FLAG_CHECK);
     const PREBUILT_PLATFORM: &str = concatcp!("--", roc_cli::FLAG_PREBUILT, "=true");
+    const PREBUILT_PLATFORM_AUTO: &str = concatcp!("--", roc_cli::FLAG_PREBUILT, "=auto");
     #[allow(dead_code)]
     const TARGET_FLAG: &str = concatcp!("--", roc_cli::FLAG_TARGET);
+    const FIX_FLAG: &str = concatcp!("--", roc_cli::FLAG_FIX);
 
     #[derive(Debug)]
     enum CliMode {
@@ -544,6 +550,123 @@ mod cli_run {
         )
     }
 
+    #[test]
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    fn build_static_executable() {
+        // Build in a copy of the platform, so the musl host doesn't race with other tests
+        let dir = tempfile::tempdir().unwrap();
+        let platform_dir = dir.path().join("zig-platform");
+        std::fs::create_dir(&platform_dir).unwrap();
+
+        for file_name in ["host.zig", "main.roc"] {
+            let src = file_path_from_root("examples/platform-switching/zig-platform", file_name);
+            std::fs::copy(src, platform_dir.join(file_name)).unwrap();
+        }
+
+        let app_path = dir.path().join("rocLovesZig.roc");
+        let src = file_path_from_root("examples/platform-switching", "rocLovesZig.roc");
+        std::fs::copy(src, &app_path).unwrap();
+
+        let out = run_roc(
+            [
+                CMD_BUILD,
+                "--static",
+                "--linker=legacy",
+                app_path.to_str().unwrap(),
+            ],
+            &[],
+            &[],
+        );
+        assert!(out.status.success(), "{}{}", out.stdout, out.stderr);
+
+        // The host was built against musl, beside the one a normal build would use
+        assert!(platform_dir.join("linux-x86_64-musl.o").exists());
+
+        let exe_path = dir.path().join("rocLovesZig");
+        roc_build::link::verify_static_executable(&exe_path).unwrap();
+
+        let run = std::process::Command::new(&exe_path).output().unwrap();
+        assert!(run.status.success());
+        assert_eq!(String::from_utf8_lossy(&run.stdout), "Roc <3 Zig!\n");
+    }
+
+    fn test_new_platform(lang: &str) {
+        let dir = tempfile::tempdir().unwrap();
+        let project_dir = dir.path().join("hello");
+
+        let out = run_roc(
+            [
+                CMD_NEW,
+                CMD_PLATFORM,
+                project_dir.to_str().unwrap(),
+                "--lang",
+                lang,
+            ],
+            &[],
+            &[],
+        );
+        assert!(out.status.success(), "{}", out.stderr);
+
+        let out =
+            run_roc_on_failure_is_panic(&project_dir.join("main.roc"), [CMD_RUN], &[], &[], &[]);
+        assert!(out.stdout.ends_with("Hello, World!\n"), "{}", out.stdout);
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn new_platform_c() {
+        test_new_platform("c")
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn new_platform_zig() {
+        test_new_platform("zig")
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn prebuilt_platform_auto_rebuilds_only_when_host_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let project_dir = dir.path().join("hello");
+        let app_path = project_dir.join("main.roc");
+        let host_path = project_dir.join("platform").join("host.c");
+
+        let out = run_roc(
+            [
+                CMD_NEW,
+                CMD_PLATFORM,
+                project_dir.to_str().unwrap(),
+                "--lang",
+                "c",
+            ],
+            &[],
+            &[],
+        );
+        assert!(out.status.success(), "{}", out.stderr);
+
+        let build = || {
+            run_roc_on_failure_is_panic(
+                &app_path,
+                [CMD_BUILD, PREBUILT_PLATFORM_AUTO],
+                &[],
+                &[],
+                &[],
+            )
+        };
+
+        // There's no preprocessed host yet, so the first build makes one
+        assert!(build().stderr.contains("Rebuilding platform"));
+        assert!(!build().stderr.contains("Rebuilding platform"));
+
+        // Make sure the host's new modification time is later than the preprocessed host's
+        std::thread::sleep(std::time::Duration::from_secs(1));
+        let host = std::fs::read_to_string(&host_path).unwrap();
+        std::fs::write(&host_path, host + "\n").unwrap();
+
+        assert!(build().stderr.contains("Rebuilding platform"));
+    }
+
     #[test]
     fn platform_switching_wasm() {
         test_roc_app_slim(
@@ -637,6 +760,20 @@ mod cli_run {
         );
     }
 
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn expects_filtered_out() {
+        let path = file_path_from_root("crates/cli_testing_examples/expects", "expects.roc");
+        let out = run_roc(
+            [CMD_TEST, "--filter", "NoSuchModule", path.to_str().unwrap()],
+            &[],
+            &[],
+        );
+
+        assert!(out.status.success());
+        assert!(strip_colors(&out.stdout).contains("No expectations were selected"));
+    }
+
     #[test]
     #[cfg_attr(
         windows,
@@ -710,6 +847,37 @@ mod cli_run {
         )
     }
 
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn batched_effects() {
+        // None of these effects uses the result of another, so the host gets all of them in one
+        // call to roc_fx_batch, even though each one is in its own Effect
+        test_roc_app_slim(
+            "crates/cli_testing_examples/batched-effects",
+            "hello.roc",
+            "hello",
+            "batch of 3\none\ntwo\nthree\n",
+            UseValgrind::Yes,
+        )
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn batched_effects_waiting_on_a_result() {
+        // Reading the name doesn't wait on the prompt, but printing the name waits on reading it
+        test_roc_app(
+            "crates/cli_testing_examples/batched-effects",
+            "echo.roc",
+            "echo",
+            &["Roc"],
+            &[],
+            &[],
+            "batch of 2\nWhat's your name?\nbatch of 1\nRoc\n",
+            UseValgrind::Yes,
+            TestCliCommands::Run,
+        )
+    }
+
     #[test]
     #[cfg_attr(windows, ignore = "missing __udivdi3 and some other symbols")]
     #[serial(cli_platform)]
@@ -1360,6 +1528,8 @@ mod cli_run {
                 type by wrapping it? If I have an opaque type Age := U32 I can create
                 an instance of this opaque type by doing @Age 23.
 
+                Run roc explain E0401 for more about this.
+
                 ────────────────────────────────────────────────────────────────────────────────
 
                 1 error and 0 warnings found in <ignored for test> ms."#
@@ -1381,6 +1551,8 @@ mod cli_run {
                 You can fix this by adding a definition for bar, or by removing it
                 from exposes.
 
+                Run roc explain E0208 for more about this.
+
                 ────────────────────────────────────────────────────────────────────────────────
 
                 1 error and 0 warnings found in <ignored for test> ms."#
@@ -1404,6 +1576,8 @@ mod cli_run {
 
                 Since Symbol isn't used, you don't need to import it.
 
+                Run roc explain E0211 for more about this.
+
                 ────────────────────────────────────────────────────────────────────────────────
 
                 0 errors and 1 warning found in <ignored for test> ms."#
@@ -1428,6 +1602,8 @@ mod cli_run {
                 Only specific functions like `after` and `map` can be generated.Learn
                 more about hosted modules at TODO.
 
+                Run roc explain E0227 for more about this.
+
                 ────────────────────────────────────────────────────────────────────────────────
 
                 1 error and 0 warnings found in <ignored for test> ms."#
@@ -1453,6 +1629,457 @@ mod cli_run {
         // This doesn't fail, since only "Formatted.roc" and non-roc files are present in this folder
         check_format_check_as_expected(&fixtures_dir("format/formatted_directory"), true);
     }
+
+    #[test]
+    #[cfg(not(windows))]
+    #[serial(daemon)]
+    fn daemon_round_trip() {
+        use std::io::{BufRead, BufReader, Read, Write};
+        use std::os::unix::net::UnixStream;
+        use std::process::{Command, Stdio};
+
+        let cache_dir = tempfile::tempdir().unwrap();
+        let env = [("XDG_CACHE_HOME", cache_dir.path().to_str().unwrap())];
+
+        let mut daemon = Command::new(build_roc_bin_cached())
+            .arg(concatcp!("--", roc_cli::FLAG_DAEMON))
+            .envs(env)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        // Wait until it's listening
+        let mut daemon_stdout = BufReader::new(daemon.stdout.take().unwrap());
+        let mut listening = String::new();
+        daemon_stdout.read_line(&mut listening).unwrap();
+        let socket_path = listening
+            .strip_prefix("The Roc daemon is listening at ")
+            .and_then(|rest| rest.split(". `roc check`").next())
+            .unwrap_or_else(|| panic!("unexpected output from the daemon: {}", listening));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Numbers.roc");
+        let reference = dir.path().join("reference");
+        std::fs::write(
+            &path,
+            "interface Numbers\n    exposes [x]\n    imports []\n\nx : Str\nx = 1\n",
+        )
+        .unwrap();
+
+        let check = || run_roc([CMD_CHECK, path.to_str().unwrap()], &[], &env);
+
+        let out = check();
+        assert!(!out.status.success());
+        assert!(out.stdout.contains("TYPE MISMATCH"), "{}", out.stdout);
+
+        // Fix the module, but keep its modification time. Only a daemon which remembered the
+        // first check still reports the error.
+        run_cmd(
+            "touch",
+            [],
+            &[
+                "-r".into(),
+                path.display().to_string(),
+                reference.display().to_string(),
+            ],
+            [],
+        );
+        std::fs::write(
+            &path,
+            "interface Numbers\n    exposes [x]\n    imports []\n\nx : Str\nx = \"1\"\n",
+        )
+        .unwrap();
+        run_cmd(
+            "touch",
+            [],
+            &[
+                "-r".into(),
+                reference.display().to_string(),
+                path.display().to_string(),
+            ],
+            [],
+        );
+
+        let remembered = check();
+        assert!(!remembered.status.success());
+        assert_eq!(remembered.stdout, out.stdout);
+
+        // Now that the module has changed, it's checked again
+        run_cmd("touch", [], &[path.display().to_string()], []);
+        let out = check();
+        assert!(out.status.success(), "{}", out.stdout);
+
+        // A client which doesn't speak the protocol doesn't stop the daemon
+        let mut stream = UnixStream::connect(socket_path).unwrap();
+        stream.write_all(b"GET / HTTP/1.1\r\n\r\n").unwrap();
+        drop(stream);
+
+        assert!(check().status.success());
+
+        // Only one daemon runs at a time
+        let second = Command::new(build_roc_bin_cached())
+            .arg(concatcp!("--", roc_cli::FLAG_DAEMON))
+            .envs(env)
+            .output()
+            .unwrap();
+        assert!(!second.status.success());
+        assert!(String::from_utf8_lossy(&second.stderr).contains("already running"));
+
+        unsafe { libc::kill(daemon.id() as libc::pid_t, libc::SIGTERM) };
+        let status = daemon.wait().unwrap();
+
+        let mut stderr = String::new();
+        daemon
+            .stderr
+            .take()
+            .unwrap()
+            .read_to_string(&mut stderr)
+            .unwrap();
+
+        assert!(status.success(), "{}", stderr);
+        assert!(
+            stderr.contains(
+                "The Roc daemon couldn't run a command: expected the stdin, stdout, and stderr of the command"
+            ),
+            "{}",
+            stderr
+        );
+        assert!(!std::path::Path::new(socket_path).exists());
+    }
+
+    /// A copy of a fixtures directory, for the tools which rewrite source files
+    fn copy_of_fixtures(dir_name: &str) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+
+        for entry in std::fs::read_dir(fixtures_dir(dir_name)).unwrap() {
+            let path = entry.unwrap().path();
+            std::fs::copy(&path, dir.path().join(path.file_name().unwrap())).unwrap();
+        }
+
+        dir
+    }
+
+    fn read(dir: &tempfile::TempDir, file_name: &str) -> String {
+        std::fs::read_to_string(dir.path().join(file_name)).unwrap()
+    }
+
+    fn rename(
+        dir: &tempfile::TempDir,
+        file_name: &str,
+        line: u32,
+        column: u32,
+        new_name: &str,
+    ) -> Out {
+        let path = dir.path().join(file_name);
+
+        run_roc(
+            [
+                CMD_RENAME,
+                path.to_str().unwrap(),
+                &line.to_string(),
+                &column.to_string(),
+                new_name,
+            ],
+            &[],
+            &[],
+        )
+    }
+
+    #[test]
+    fn rename_across_modules() {
+        let dir = copy_of_fixtures("rename");
+
+        // `greeting` in `loud = Lib.shout greeting`
+        let out = rename(&dir, "Main.roc", 6, 18, "hello");
+        assert!(out.status.success(), "{}", out.stderr);
+
+        assert_multiline_str_eq!(
+            read(&dir, "Lib.roc").as_str(),
+            indoc!(
+                r#"
+                interface Lib
+                    exposes [hello, shout, whisper]
+                    imports []
+
+                hello : Str
+                hello = "Hi"
+
+                shout : Str -> Str
+                shout = \message -> Str.concat message "!"
+
+                whisper : Str -> Str
+                whisper = \message ->
+                    quiet : Str
+                    quiet = Str.concat message "..."
+
+                    quiet
+                "#
+            )
+        );
+        assert_multiline_str_eq!(
+            read(&dir, "Main.roc").as_str(),
+            indoc!(
+                r#"
+                interface Main
+                    exposes [loud, labelled]
+                    imports [Lib.{ hello }]
+
+                loud : Str
+                loud = Lib.shout hello
+
+                labelled : Str -> Str
+                labelled = \label ->
+                    Str.concat label hello
+                "#
+            )
+        );
+    }
+
+    #[test]
+    fn rename_locals_with_the_same_name() {
+        let dir = copy_of_fixtures("rename");
+
+        // `message` in `whisper = \message ->`, which `shout` has its own of
+        let out = rename(&dir, "Lib.roc", 12, 12, "text");
+        assert!(out.status.success(), "{}", out.stderr);
+
+        // `quiet` on the last line, which has an annotation
+        let out = rename(&dir, "Lib.roc", 16, 5, "hushed");
+        assert!(out.status.success(), "{}", out.stderr);
+
+        assert_multiline_str_eq!(
+            read(&dir, "Lib.roc").as_str(),
+            indoc!(
+                r#"
+                interface Lib
+                    exposes [greeting, shout, whisper]
+                    imports []
+
+                greeting : Str
+                greeting = "Hi"
+
+                shout : Str -> Str
+                shout = \message -> Str.concat message "!"
+
+                whisper : Str -> Str
+                whisper = \text ->
+                    hushed : Str
+                    hushed = Str.concat text "..."
+
+                    hushed
+                "#
+            )
+        );
+    }
+
+    #[test]
+    fn rename_refuses_a_name_in_scope_in_another_module() {
+        let dir = copy_of_fixtures("rename");
+        let lib_before = read(&dir, "Lib.roc");
+        let main_before = read(&dir, "Main.roc");
+
+        // `greeting` where Lib defines it. Main has a `label` argument, which would capture it.
+        let out = rename(&dir, "Lib.roc", 5, 1, "label");
+        assert!(!out.status.success());
+        assert!(
+            out.stderr
+                .contains("`label` is already the name of something in Main"),
+            "{}",
+            out.stderr
+        );
+
+        assert_eq!(read(&dir, "Lib.roc"), lib_before);
+        assert_eq!(read(&dir, "Main.roc"), main_before);
+    }
+
+    #[test]
+    fn rename_refuses_a_name_in_scope_in_the_same_module() {
+        let dir = copy_of_fixtures("rename");
+        let lib_before = read(&dir, "Lib.roc");
+
+        // `message` in `whisper = \message ->`, to the name of a top-level value
+        let out = rename(&dir, "Lib.roc", 12, 12, "shout");
+        assert!(!out.status.success());
+
+        assert_eq!(read(&dir, "Lib.roc"), lib_before);
+    }
+
+    #[test]
+    fn imports_fix() {
+        let dir = copy_of_fixtures("imports");
+        let path = dir.path().join("Main.roc");
+
+        let out = run_roc([CMD_IMPORTS, FIX_FLAG, path.to_str().unwrap()], &[], &[]);
+        assert!(out.status.success(), "{}{}", out.stdout, out.stderr);
+        assert!(
+            out.stdout.contains(
+                "Main.roc:6:14: `greeting` isn't defined or imported. Import Lib.{ greeting }"
+            ),
+            "{}",
+            out.stdout
+        );
+
+        // The two imports of Lib are merged, and the list is sorted
+        assert_multiline_str_eq!(
+            read(&dir, "Main.roc").as_str(),
+            indoc!(
+                r#"
+                interface Main
+                    exposes [loud, quiet]
+                    imports [Lib.{ greeting, shout }, Other.{ whisper }]
+
+                loud : Str
+                loud = shout greeting
+
+                quiet : Str -> Str
+                quiet = \message -> whisper message
+                "#
+            )
+        );
+    }
+
+    #[test]
+    fn imports_without_fix_changes_nothing() {
+        let dir = copy_of_fixtures("imports");
+        let path = dir.path().join("Main.roc");
+        let before = read(&dir, "Main.roc");
+
+        let out = run_roc([CMD_IMPORTS, path.to_str().unwrap()], &[], &[]);
+        assert!(!out.status.success());
+        assert!(
+            out.stdout
+                .contains("can be organized; run again with --fix"),
+            "{}",
+            out.stdout
+        );
+
+        assert_eq!(read(&dir, "Main.roc"), before);
+    }
+
+    #[test]
+    fn imports_ambiguous_name() {
+        let dir = copy_of_fixtures("imports");
+        let path = dir.path().join("Ambiguous.roc");
+        let before = read(&dir, "Ambiguous.roc");
+
+        let out = run_roc([CMD_IMPORTS, FIX_FLAG, path.to_str().unwrap()], &[], &[]);
+        assert!(!out.status.success());
+        assert!(
+            out.stdout
+                .contains("`shout` isn't defined or imported. It could be imported as any of: Lib.{ shout }, Other.{ shout }"),
+            "{}",
+            out.stdout
+        );
+
+        assert_eq!(read(&dir, "Ambiguous.roc"), before);
+    }
+
+    #[test]
+    fn migrate_headers_check_and_fix() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Numbers.roc");
+        std::fs::write(&path, "interface Numbers\n    exposes [x]\n\nx = 1\n").unwrap();
+
+        let out = run_roc(
+            [CMD_MIGRATE_HEADERS, CHECK_FLAG, path.to_str().unwrap()],
+            &[],
+            &[],
+        );
+        assert!(!out.status.success());
+        assert!(
+            out.stdout.contains("1 headers need to be migrated."),
+            "{}",
+            out.stdout
+        );
+
+        let out = run_roc(
+            [CMD_MIGRATE_HEADERS, dir.path().to_str().unwrap()],
+            &[],
+            &[],
+        );
+        assert!(out.status.success(), "{}", out.stderr);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "interface Numbers\n    exposes [x]\n    imports []\n\nx = 1\n"
+        );
+
+        let out = run_roc(
+            [CMD_MIGRATE_HEADERS, CHECK_FLAG, path.to_str().unwrap()],
+            &[],
+            &[],
+        );
+        assert!(out.status.success(), "{}", out.stdout);
+        assert!(
+            out.stdout.contains("Every header is up to date."),
+            "{}",
+            out.stdout
+        );
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn bench_runs_top_level_benches() {
+        let path = fixture_file("bench", "Main.roc");
+        let path_str = path.to_str().unwrap();
+
+        let out = run_roc(
+            [CMD_BENCH, "--warmup", "1", "--iterations", "3", path_str],
+            &[],
+            &[],
+        );
+        assert!(out.status.success(), "{}{}", out.stdout, out.stderr);
+
+        let stdout = strip_colors(&out.stdout);
+        assert!(stdout.contains("doubling a list"), "{}", stdout);
+        assert!(
+            stdout.contains(&format!("bench at {}:11", path.display())),
+            "{}",
+            stdout
+        );
+        assert!(stdout.contains("Ran 2 benchmarks"), "{}", stdout);
+    }
+
+    #[test]
+    fn dev_hot_is_unsupported() {
+        let path = fixture_file("multi-dep-str", "Main.roc");
+
+        let out = run_roc(
+            [CMD_DEV, "--watch", "--hot", path.to_str().unwrap()],
+            &[],
+            &[],
+        );
+        assert!(!out.status.success(), "{}{}", out.stdout, out.stderr);
+        assert!(
+            out.stderr
+                .contains("`roc dev --watch --hot` isn't supported yet"),
+            "{}",
+            out.stderr
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "fuzz-parse"))]
+    fn fuzz_parse_needs_its_feature() {
+        let dir = fixtures_dir("multi-dep-str");
+
+        let out = run_roc(
+            [
+                roc_cli::CMD_EXPERIMENT,
+                roc_cli::CMD_FUZZ_PARSE,
+                dir.to_str().unwrap(),
+            ],
+            &[],
+            &[],
+        );
+        assert!(!out.status.success(), "{}{}", out.stdout, out.stderr);
+        assert!(
+            out.stderr
+                .contains("build roc with `cargo build --release --features fuzz-parse`"),
+            "{}",
+            out.stderr
+        );
+    }
 }
 
 #[cfg(feature = "wasm32-cli-run")]
@@ -0,0 +1,11 @@
+interface Main
+    exposes [bench]
+    imports []
+
+bench : U64 -> U64
+bench = \n -> n * 2
+
+# doubling a list
+bench List.map (List.range { start: At 0, end: Length 100 }) bench
+
+bench bench 21
@@ -0,0 +1,6 @@
+interface Ambiguous
+    exposes [loud]
+    imports [Lib, Other]
+
+loud : Str
+loud = shout "Hi"
@@ -0,0 +1,9 @@
+interface Lib
+    exposes [greeting, shout]
+    imports []
+
+greeting : Str
+greeting = "Hi"
+
+shout : Str -> Str
+shout = \message -> Str.concat message "!"
@@ -0,0 +1,9 @@
+interface Main
+    exposes [loud, quiet]
+    imports [Other.{ whisper }, Lib, Lib.{ shout }]
+
+loud : Str
+loud = shout greeting
+
+quiet : Str -> Str
+quiet = \message -> whisper message
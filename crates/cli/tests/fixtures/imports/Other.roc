@@ -0,0 +1,9 @@
+interface Other
+    exposes [shout, whisper]
+    imports []
+
+shout : Str -> Str
+shout = \message -> Str.concat message "!!!"
+
+whisper : Str -> Str
+whisper = \message -> Str.concat message "..."
@@ -64,6 +64,14 @@ export fn roc_panic(c_ptr: *anyopaque, tag_id: u32) callconv(.C) void {
     std.process.exit(0);
 }
 
+export fn roc_oom(size: usize, alignment: u32) callconv(.C) void {
+    _ = alignment;
+
+    const stderr = std.io.getStdErr().writer();
+    stderr.print("Roc ran out of memory allocating {d} bytes\n", .{size}) catch unreachable;
+    std.process.exit(1);
+}
+
 extern fn kill(pid: c_int, sig: c_int) c_int;
 extern fn shm_open(name: *const i8, oflag: c_int, mode: c_uint) c_int;
 extern fn mmap(addr: ?*anyopaque, length: c_uint, prot: c_int, flags: c_int, fd: c_int, offset: c_uint) *anyopaque;
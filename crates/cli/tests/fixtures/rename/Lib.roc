@@ -0,0 +1,16 @@
+interface Lib
+    exposes [greeting, shout, whisper]
+    imports []
+
+greeting : Str
+greeting = "Hi"
+
+shout : Str -> Str
+shout = \message -> Str.concat message "!"
+
+whisper : Str -> Str
+whisper = \message ->
+    quiet : Str
+    quiet = Str.concat message "..."
+
+    quiet
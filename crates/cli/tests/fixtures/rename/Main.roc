@@ -0,0 +1,10 @@
+interface Main
+    exposes [loud, labelled]
+    imports [Lib.{ greeting }]
+
+loud : Str
+loud = Lib.shout greeting
+
+labelled : Str -> Str
+labelled = \label ->
+    Str.concat label greeting
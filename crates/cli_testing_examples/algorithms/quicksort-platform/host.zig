@@ -72,6 +72,14 @@ export fn roc_panic(c_ptr: *anyopaque, tag_id: u32) callconv(.C) void {
     std.process.exit(0);
 }
 
+export fn roc_oom(size: usize, alignment: u32) callconv(.C) void {
+    _ = alignment;
+
+    const stderr = std.io.getStdErr().writer();
+    stderr.print("Roc ran out of memory allocating {d} bytes\n", .{size}) catch unreachable;
+    std.process.exit(1);
+}
+
 export fn roc_memcpy(dst: [*]u8, src: [*]u8, size: usize) callconv(.C) void {
     return memcpy(dst, src, size);
 }
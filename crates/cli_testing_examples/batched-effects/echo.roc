@@ -0,0 +1,10 @@
+app "echo"
+    packages { pf: "platform/main.roc" }
+    imports [pf.Task.{ await }]
+    provides [main] to pf
+
+main =
+    {} <- await (Task.putLine "What's your name?")
+    name <- await Task.getLine
+
+    Task.putLine name
@@ -0,0 +1,10 @@
+app "hello"
+    packages { pf: "platform/main.roc" }
+    imports [pf.Task.{ await }]
+    provides [main] to pf
+
+main =
+    {} <- await (Task.putLine "one")
+    {} <- await (Task.putLine "two")
+
+    Task.putLine "three"
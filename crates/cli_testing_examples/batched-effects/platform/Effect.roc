@@ -0,0 +1,8 @@
+hosted Effect
+    exposes [Effect, after, map, always, putLine, getLine]
+    imports []
+    generates Effect with [after, map, always]
+
+putLine : Str -> Effect {}
+
+getLine : Effect Str
@@ -0,0 +1,22 @@
+interface Task
+    exposes [Task, succeed, await, map, putLine, getLine]
+    imports [pf.Effect]
+
+## These tasks can't fail, so a task only waits on the one before it when it uses its result.
+## Effects which don't wait on each other are run by the host in one batch.
+Task a : Effect.Effect a
+
+succeed : a -> Task a
+succeed = \value -> Effect.always value
+
+await : Task a, (a -> Task b) -> Task b
+await = \task, next -> Effect.after task next
+
+map : Task a, (a -> b) -> Task b
+map = \task, transform -> Effect.map task transform
+
+putLine : Str -> Task {}
+putLine = \line -> Effect.putLine line
+
+getLine : Task Str
+getLine = Effect.getLine
@@ -0,0 +1,135 @@
+const std = @import("std");
+const builtin = @import("builtin");
+const str = @import("str");
+const RocStr = str.RocStr;
+
+comptime {
+    // This is a workaround for https://github.com/ziglang/zig/issues/8218
+    // which is only necessary on macOS.
+    //
+    // Once that issue is fixed, we can undo the changes in
+    // 177cf12e0555147faa4d436e52fc15175c2c4ff0 and go back to passing
+    // -fcompiler-rt in link.rs instead of doing this. Note that this
+    // workaround is present in many host.zig files, so make sure to undo
+    // it everywhere!
+    if (builtin.os.tag == .macos) {
+        _ = @import("compiler_rt");
+    }
+}
+
+extern fn roc__mainForHost_1_exposed_generic([*]u8) void;
+extern fn roc__mainForHost_size() i64;
+extern fn roc__mainForHost_1__Fx_caller(*const u8, [*]u8, [*]u8) void;
+extern fn roc__mainForHost_1__Fx_result_size() i64;
+
+const Align = 2 * @alignOf(usize);
+extern fn malloc(size: usize) callconv(.C) ?*align(Align) anyopaque;
+extern fn realloc(c_ptr: [*]align(Align) u8, size: usize) callconv(.C) ?*anyopaque;
+extern fn free(c_ptr: [*]align(Align) u8) callconv(.C) void;
+extern fn memcpy(dst: [*]u8, src: [*]u8, size: usize) callconv(.C) void;
+extern fn memset(dst: [*]u8, value: i32, size: usize) callconv(.C) void;
+
+export fn roc_alloc(size: usize, alignment: u32) callconv(.C) ?*anyopaque {
+    _ = alignment;
+
+    return malloc(size);
+}
+
+export fn roc_realloc(c_ptr: *anyopaque, new_size: usize, old_size: usize, alignment: u32) callconv(.C) ?*anyopaque {
+    _ = old_size;
+    _ = alignment;
+
+    return realloc(@alignCast(Align, @ptrCast([*]u8, c_ptr)), new_size);
+}
+
+export fn roc_dealloc(c_ptr: *anyopaque, alignment: u32) callconv(.C) void {
+    _ = alignment;
+
+    free(@alignCast(Align, @ptrCast([*]u8, c_ptr)));
+}
+
+export fn roc_panic(c_ptr: *anyopaque, tag_id: u32) callconv(.C) void {
+    _ = tag_id;
+
+    const stderr = std.io.getStdErr().writer();
+    const msg = @ptrCast([*:0]const u8, c_ptr);
+    stderr.print("Application crashed with message\n\n    {s}\n\nShutting down\n", .{msg}) catch unreachable;
+    std.process.exit(0);
+}
+
+export fn roc_oom(size: usize, alignment: u32) callconv(.C) void {
+    _ = alignment;
+
+    const stderr = std.io.getStdErr().writer();
+    stderr.print("Roc ran out of memory allocating {d} bytes\n", .{size}) catch unreachable;
+    std.process.exit(1);
+}
+
+export fn roc_memcpy(dst: [*]u8, src: [*]u8, size: usize) callconv(.C) void {
+    return memcpy(dst, src, size);
+}
+
+export fn roc_memset(dst: [*]u8, value: i32, size: usize) callconv(.C) void {
+    return memset(dst, value, size);
+}
+
+/// One effect in a batch, as Roc hands it to `roc_fx_batch`
+const RocEffect = extern struct {
+    name: [*:0]const u8,
+    arguments: [*]*anyopaque,
+    output: *anyopaque,
+};
+
+/// Runs a batch of effects. This host prints how many effects each batch has before running
+/// them, so tests can see which effects Roc put in the same batch.
+export fn roc_fx_batch(effects: [*]RocEffect, count: usize) callconv(.C) void {
+    const stdout = std.io.getStdOut().writer();
+
+    stdout.print("batch of {d}\n", .{count}) catch unreachable;
+
+    for (effects[0..count]) |effect| {
+        const name = std.mem.span(effect.name);
+
+        if (std.mem.eql(u8, name, "roc_fx_putLine")) {
+            const line = @ptrCast(*RocStr, @alignCast(@alignOf(RocStr), effect.arguments[0]));
+
+            stdout.print("{s}\n", .{line.asSlice()}) catch unreachable;
+        } else if (std.mem.eql(u8, name, "roc_fx_getLine")) {
+            const output = @ptrCast(*RocStr, @alignCast(@alignOf(RocStr), effect.output));
+
+            output.* = getLine();
+        } else {
+            std.debug.panic("unknown effect {s}", .{name});
+        }
+    }
+}
+
+fn getLine() RocStr {
+    const stdin = std.io.getStdIn().reader();
+    var buf: [400]u8 = undefined;
+
+    // make sure to strip `\r` on windows
+    const raw_line: []u8 = (stdin.readUntilDelimiterOrEof(&buf, '\n') catch unreachable) orelse "";
+    const line = std.mem.trimRight(u8, raw_line, &std.ascii.spaces);
+
+    return RocStr.init(line.ptr, line.len);
+}
+
+pub fn main() u8 {
+    // The size might be zero; if so, make it at least 8 so that we don't have a nullptr
+    const size = std.math.max(@intCast(usize, roc__mainForHost_size()), 8);
+    const raw_closure_data = roc_alloc(size, @alignOf(u64)).?;
+    defer roc_dealloc(raw_closure_data, @alignOf(u64));
+
+    const closure_data = @ptrCast([*]u8, raw_closure_data);
+    roc__mainForHost_1_exposed_generic(closure_data);
+
+    const result_size = std.math.max(@intCast(usize, roc__mainForHost_1__Fx_result_size()), 8);
+    const raw_output = roc_alloc(result_size, @alignOf(u64)).?;
+    defer roc_dealloc(raw_output, @alignOf(u64));
+
+    const flags: u8 = 0;
+    roc__mainForHost_1__Fx_caller(&flags, closure_data, @ptrCast([*]u8, raw_output));
+
+    return 0;
+}
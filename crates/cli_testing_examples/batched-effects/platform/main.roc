@@ -0,0 +1,10 @@
+platform "batched-effects"
+    requires {} { main : Task {} }
+    exposes []
+    packages {}
+    imports [Task.{ Task }]
+    provides [mainForHost]
+    effects { submit: batch }
+
+mainForHost : Task {} as Fx
+mainForHost = main
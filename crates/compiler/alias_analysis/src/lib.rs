@@ -644,7 +644,7 @@ fn stmt_spec<'a>(
             let jpid = env.join_points[id];
             builder.add_jump(block, jpid, argument, ret_type_id)
         }
-        Crash(msg, _) => {
+        Crash(msg, ..) => {
             // Model this as a foreign call rather than TERMINATE because
             // we want ownership of the message.
             let result_type = layout_spec(env, builder, interner, layout)?;
@@ -829,7 +829,7 @@ fn call_spec<'a>(
             }
 
             match op {
-                ListMap { xs } => {
+                ListMap { xs } | ListMapConcurrent { xs } => {
                     let list = env.symbols[xs];
 
                     let loop_body = |builder: &mut FuncDefBuilder, block, state| {
@@ -1,8 +1,9 @@
+use crate::perf_map;
 use crate::target::{arch_str, target_zig_str};
 use const_format::concatcp;
 use libloading::{Error, Library};
 use roc_builtins::bitcode;
-use roc_error_macros::internal_error;
+use roc_error_macros::{internal_error, user_error};
 use roc_mono::ir::OptLevel;
 use roc_utils::{cargo, clang, zig};
 use roc_utils::{get_lib_path, rustup};
@@ -12,7 +13,7 @@ use std::io;
 use std::path::{Path, PathBuf};
 use std::process::{self, Child, Command};
 use std::{env, fs};
-use target_lexicon::{Architecture, OperatingSystem, Triple};
+use target_lexicon::{Architecture, Environment, OperatingSystem, Triple};
 use wasi_libc_sys::{WASI_COMPILER_RT_PATH, WASI_LIBC_PATH};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -61,6 +62,142 @@ pub fn link(
     }
 }
 
+/// Link a fully static Linux executable, using the musl libc that ships with zig.
+/// input_paths should include the host, the app, and (for the dev backend) the builtins.
+pub fn link_static_linux(
+    target: &Triple,
+    output_path: PathBuf,
+    input_paths: &[&str],
+) -> io::Result<(Child, PathBuf)> {
+    let zig_target = match target.architecture {
+        Architecture::X86_64 => "x86_64-linux-musl",
+        Architecture::Aarch64(_) => "aarch64-linux-musl",
+        Architecture::X86_32(_) => "i386-linux-musl",
+        _ => internal_error!(
+            "TODO gracefully handle unsupported static linux architecture: {:?}",
+            target.architecture
+        ),
+    };
+
+    let child = zig()
+        .args(["build-exe"])
+        .args(input_paths)
+        .args([
+            "-target",
+            zig_target,
+            "-lc",
+            "-static",
+            &format!("-femit-bin={}", output_path.to_str().unwrap()),
+        ])
+        .spawn()?;
+
+    Ok((child, output_path))
+}
+
+/// Check that an executable has no dynamic dependencies left, so it runs on any Linux machine.
+pub fn verify_static_executable(path: &Path) -> Result<(), String> {
+    use object::{Object, ObjectSection};
+
+    let bytes =
+        fs::read(path).map_err(|err| format!("Could not read {}: {err}", path.display()))?;
+    let file = object::File::parse(&*bytes)
+        .map_err(|err| format!("Could not parse {}: {err}", path.display()))?;
+
+    if file.section_by_name(".interp").is_some() {
+        return Err(format!(
+            "{} still needs a dynamic loader, so it is not fully static.",
+            path.display()
+        ));
+    }
+
+    let imports = file
+        .imports()
+        .map_err(|err| format!("Could not read the imports of {}: {err}", path.display()))?;
+    if !imports.is_empty() {
+        let names: Vec<_> = imports
+            .iter()
+            .map(|import| String::from_utf8_lossy(import.name()))
+            .collect();
+
+        return Err(format!(
+            "{} still imports these symbols from shared libraries, so it is not fully static: {}",
+            path.display(),
+            names.join(", ")
+        ));
+    }
+
+    Ok(())
+}
+
+/// Move the debug info of a linked executable into a sidecar file, and point the executable at it
+/// so debuggers and crash symbolication can still find it. Returns the path of the sidecar.
+///
+/// On macOS this is a `.dSYM` bundle, which debuggers match up by the executable's UUID.
+/// On Linux it's a `.debug` file, which the executable names in its `.gnu_debuglink` section.
+pub fn split_debug_info(target: &Triple, binary_path: &Path) -> io::Result<PathBuf> {
+    let mut sidecar_name = binary_path.file_name().unwrap_or_default().to_os_string();
+
+    match target.operating_system {
+        OperatingSystem::Darwin => {
+            sidecar_name.push(".dSYM");
+            let dsym_path = binary_path.with_file_name(sidecar_name);
+
+            run_debuginfo_command(
+                Command::new("dsymutil")
+                    .arg(binary_path)
+                    .arg("-o")
+                    .arg(&dsym_path),
+            )?;
+            run_debuginfo_command(Command::new("strip").arg("-S").arg(binary_path))?;
+
+            Ok(dsym_path)
+        }
+        OperatingSystem::Linux => {
+            sidecar_name.push(".debug");
+            let debug_path = binary_path.with_file_name(sidecar_name);
+
+            run_debuginfo_command(
+                Command::new("objcopy")
+                    .arg("--only-keep-debug")
+                    .arg(binary_path)
+                    .arg(&debug_path),
+            )?;
+
+            let mut debuglink = std::ffi::OsString::from("--add-gnu-debuglink=");
+            debuglink.push(&debug_path);
+            run_debuginfo_command(
+                Command::new("objcopy")
+                    .arg("--strip-debug")
+                    .arg(debuglink)
+                    .arg(binary_path),
+            )?;
+
+            Ok(debug_path)
+        }
+        _ => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("Splitting debug info is not supported for {target}"),
+        )),
+    }
+}
+
+fn run_debuginfo_command(command: &mut Command) -> io::Result<()> {
+    let output = command.output()?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!(
+                "{:?} failed:\n{}",
+                command,
+                String::from_utf8_lossy(&output.stderr)
+            ),
+        ))
+    }
+}
+
 const PRECOMPILED_HOST_EXT: &str = "rh1"; // Short for "roc host version 1" (so we can change format in the future)
 
 const WASM_TARGET_STR: &str = "wasm32";
@@ -188,7 +325,34 @@ pub fn legacy_host_filename(target: &Triple) -> Option<String> {
     let os = roc_target::OperatingSystem::from(target.operating_system);
     let ext = os.object_file_ext();
 
-    Some(preprocessed_host_filename(target)?.replace(PRECOMPILED_HOST_EXT, ext))
+    if is_linux_musl(target) {
+        // Built for static linking, so it must not be mistaken for the usual (glibc) host
+        Some(
+            preprocessed_host_filename(target)?
+                .replace(&format!(".{PRECOMPILED_HOST_EXT}"), &format!("-musl.{ext}")),
+        )
+    } else {
+        Some(preprocessed_host_filename(target)?.replace(PRECOMPILED_HOST_EXT, ext))
+    }
+}
+
+fn is_linux_musl(target: &Triple) -> bool {
+    target.operating_system == OperatingSystem::Linux && target.environment == Environment::Musl
+}
+
+/// Whether [rebuild_host] can build this platform's host against musl, for `roc build --static`.
+/// That takes a Zig or C host; Rust and Swift toolchains build against the system's libc.
+pub fn can_rebuild_host_for_musl(platform_main_roc: &Path) -> bool {
+    let has = |file_name| platform_main_roc.with_file_name(file_name).exists();
+
+    // The same order rebuild_host checks for them in
+    has("host.zig") || (!has("Cargo.toml") && !has("host.rs") && has("host.c"))
+}
+
+/// LLVM bitcode a platform can ship beside its main.roc, e.g. "linux-x86_64.inline.bc", which
+/// defines hosted functions small enough to inline into the app, like reading a clock
+pub fn inline_hosted_bitcode_filename(target: &Triple) -> Option<String> {
+    Some(format!("{}.inline.bc", get_target_triple_str(target)?))
 }
 
 fn find_zig_str_path() -> PathBuf {
@@ -462,21 +626,23 @@ pub fn build_zig_host_wasm32(
     zig_host_src: &str,
     zig_str_path: &str,
     opt_level: OptLevel,
+    linking_strategy: LinkingStrategy,
     shared_lib_path: Option<&Path>,
 ) -> Command {
     if shared_lib_path.is_some() {
         unimplemented!("Linking a shared library to wasm not yet implemented");
     }
 
-    let zig_target = if matches!(opt_level, OptLevel::Development) {
+    // The additive and surgical linkers splice the app into this object, once it's preprocessed
+    let zig_target = if linking_strategy != LinkingStrategy::Legacy {
         "wasm32-wasi"
     } else {
         // For LLVM backend wasm we are emitting a .bc file anyway so this target is OK
         "i386-linux-musl"
     };
 
-    // NOTE currently just to get compiler warnings if the host code is invalid.
-    // the produced artifact is not used
+    // NOTE for the legacy linker, this is currently just to get compiler warnings if the host
+    // code is invalid. the produced artifact is not used
     //
     // NOTE we're emitting LLVM IR here (again, it is not actually used)
     //
@@ -531,6 +697,27 @@ pub fn build_c_host_native(
     shared_lib_path: Option<&Path>,
     builtins_host_path: &Path,
 ) -> Command {
+    if is_linux_musl(target) && shared_lib_path.is_none() {
+        // Build against the musl that ships with zig, which is what static executables link to
+        let mut zig_cmd = zig();
+        zig_cmd
+            .env_clear()
+            .env("PATH", env_path)
+            .env("CPATH", env_cpath)
+            .env("HOME", env_home)
+            .args(["cc", "-target", target_zig_str(target), "-fPIC", "-c"])
+            .args(sources)
+            .args(["-o", dest]);
+
+        if matches!(opt_level, OptLevel::Optimize) {
+            zig_cmd.arg("-O3");
+        } else if matches!(opt_level, OptLevel::Size) {
+            zig_cmd.arg("-Os");
+        }
+
+        return zig_cmd;
+    }
+
     let mut clang_cmd = clang();
     clang_cmd
         .env_clear()
@@ -643,6 +830,7 @@ pub fn build_swift_host_native(
 
 pub fn rebuild_host(
     opt_level: OptLevel,
+    linking_strategy: LinkingStrategy,
     target: &Triple,
     platform_main_roc: &Path,
     shared_lib_path: Option<&Path>,
@@ -656,26 +844,7 @@ pub fn rebuild_host(
     let swift_host_src = platform_main_roc.with_file_name("host.swift");
     let swift_host_header_src = platform_main_roc.with_file_name("host.h");
 
-    let os = roc_target::OperatingSystem::from(target.operating_system);
-    let executable_extension = match os {
-        roc_target::OperatingSystem::Windows => "exe",
-        roc_target::OperatingSystem::Unix => "",
-        roc_target::OperatingSystem::Wasi => "",
-    };
-
-    let host_dest = if matches!(target.architecture, Architecture::Wasm32) {
-        if matches!(opt_level, OptLevel::Development) {
-            platform_main_roc.with_extension("o")
-        } else {
-            platform_main_roc.with_extension("bc")
-        }
-    } else if shared_lib_path.is_some() {
-        platform_main_roc
-            .with_file_name("dynhost")
-            .with_extension(executable_extension)
-    } else {
-        platform_main_roc.with_file_name(legacy_host_filename(target).unwrap())
-    };
+    let host_dest = host_dest_path(linking_strategy, target, platform_main_roc, shared_lib_path);
 
     let env_path = env::var("PATH").unwrap_or_else(|_| "".to_string());
     let env_home = env::var("HOME").unwrap_or_else(|_| "".to_string());
@@ -697,7 +866,7 @@ pub fn rebuild_host(
 
         let zig_cmd = match target.architecture {
             Architecture::Wasm32 => {
-                let emit_bin = if matches!(opt_level, OptLevel::Development) {
+                let emit_bin = if linking_strategy != LinkingStrategy::Legacy {
                     format!("-femit-bin={}", host_dest.to_str().unwrap())
                 } else {
                     format!("-femit-llvm-ir={}", host_dest.to_str().unwrap())
@@ -709,6 +878,7 @@ pub fn rebuild_host(
                     zig_host_src.to_str().unwrap(),
                     zig_str_path.to_str().unwrap(),
                     opt_level,
+                    linking_strategy,
                     shared_lib_path,
                 )
             }
@@ -791,7 +961,7 @@ pub fn rebuild_host(
         if shared_lib_path.is_some() {
             // For surgical linking, just copy the dynamically linked rust app.
             let mut exe_path = cargo_out_dir.join("host");
-            exe_path.set_extension(executable_extension);
+            exe_path.set_extension(host_dest.extension().unwrap_or_default());
             if let Err(e) = std::fs::copy(&exe_path, &host_dest) {
                 panic!(
                     "unable to copy {} => {}: {:?}\n\nIs the file used by another invocation of roc?",
@@ -945,6 +1115,154 @@ pub fn rebuild_host(
     host_dest
 }
 
+/// Where building the host puts it, beside the platform's main.roc
+fn host_dest_path(
+    linking_strategy: LinkingStrategy,
+    target: &Triple,
+    platform_main_roc: &Path,
+    shared_lib_path: Option<&Path>,
+) -> PathBuf {
+    let os = roc_target::OperatingSystem::from(target.operating_system);
+    let executable_extension = match os {
+        roc_target::OperatingSystem::Windows => "exe",
+        roc_target::OperatingSystem::Unix => "",
+        roc_target::OperatingSystem::Wasi => "",
+    };
+
+    if matches!(target.architecture, Architecture::Wasm32) {
+        if linking_strategy != LinkingStrategy::Legacy {
+            platform_main_roc.with_extension("o")
+        } else {
+            platform_main_roc.with_extension("bc")
+        }
+    } else if shared_lib_path.is_some() {
+        platform_main_roc
+            .with_file_name("dynhost")
+            .with_extension(executable_extension)
+    } else {
+        platform_main_roc.with_file_name(legacy_host_filename(target).unwrap())
+    }
+}
+
+/// The files [rebuild_host] builds hosts from, besides those in the platform's `src` directory,
+/// and the build scripts platforms with a `build` command usually have
+const HOST_SOURCE_FILES: [&str; 9] = [
+    "host.c",
+    "host.h",
+    "host.zig",
+    "host.rs",
+    "host.swift",
+    "Cargo.toml",
+    "build.rs",
+    "build.zig",
+    "build.zig.zon",
+];
+
+/// Whether the platform's host sources, or its main.roc, changed since `built_host` was built
+/// from them, or `built_host` doesn't exist yet
+pub fn host_sources_changed(platform_main_roc: &Path, built_host: &Path) -> bool {
+    let built_at = match fs::metadata(built_host).and_then(|metadata| metadata.modified()) {
+        Ok(built_at) => built_at,
+        Err(_) => return true,
+    };
+
+    HOST_SOURCE_FILES
+        .iter()
+        .map(|name| platform_main_roc.with_file_name(name))
+        .chain([
+            platform_main_roc.to_path_buf(),
+            platform_main_roc.with_file_name("src"),
+        ])
+        .any(|path| changed_since(&path, built_at))
+}
+
+fn changed_since(path: &Path, time: std::time::SystemTime) -> bool {
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(_) => return false,
+    };
+
+    if metadata.is_dir() {
+        match fs::read_dir(path) {
+            Ok(entries) => entries
+                .flatten()
+                .any(|entry| changed_since(&entry.path(), time)),
+            Err(_) => false,
+        }
+    } else {
+        metadata
+            .modified()
+            .map_or(false, |modified| modified > time)
+    }
+}
+
+/// Builds the host with the command from the `build` section of its platform's header, if it has
+/// one, and otherwise the default way for its language, with [rebuild_host]. The command runs in
+/// the platform's directory, and is told where to put the host with `ROC_HOST_OUTPUT`.
+pub fn build_host(
+    build_command: Option<&str>,
+    opt_level: OptLevel,
+    linking_strategy: LinkingStrategy,
+    target: &Triple,
+    platform_main_roc: &Path,
+    shared_lib_path: Option<&Path>,
+) -> PathBuf {
+    let build_command = match build_command {
+        Some(build_command) => build_command,
+        None => {
+            return rebuild_host(
+                opt_level,
+                linking_strategy,
+                target,
+                platform_main_roc,
+                shared_lib_path,
+            )
+        }
+    };
+
+    let host_dest = host_dest_path(linking_strategy, target, platform_main_roc, shared_lib_path);
+
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.arg("/C");
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.arg("-c");
+        command
+    };
+
+    let opt_level_str = match opt_level {
+        OptLevel::Development => "development",
+        OptLevel::Normal => "normal",
+        OptLevel::Size => "size",
+        OptLevel::Optimize => "optimize",
+    };
+
+    command
+        .arg(build_command)
+        .current_dir(platform_main_roc.parent().unwrap())
+        .env("ROC_TARGET", target.to_string())
+        .env("ROC_OPT_LEVEL", opt_level_str)
+        .env("ROC_HOST_OUTPUT", &host_dest);
+
+    if let Some(shared_lib_path) = shared_lib_path {
+        command.env("ROC_APP_STUB", shared_lib_path);
+    }
+
+    run_build_command(command, build_command, 0);
+
+    if !host_dest.exists() {
+        user_error!(
+            "The platform's build command, `{}`, succeeded, but didn't put the host at {}, where ROC_HOST_OUTPUT said to.",
+            build_command,
+            host_dest.display()
+        );
+    }
+
+    host_dest
+}
+
 // there can be multiple release folders, one in target and one in target/x86_64-unknown-linux-musl,
 // we want the one that was most recently used
 fn find_used_target_sub_folder(opt_level: OptLevel, target_folder: PathBuf) -> PathBuf {
@@ -1007,6 +1325,8 @@ fn get_target_str(target: &Triple) -> &str {
         && target.environment == target_lexicon::Environment::Gnu
     {
         "x86_64-windows-gnu"
+    } else if is_linux_musl(target) {
+        target_zig_str(target)
     } else {
         "native"
     }
@@ -1055,6 +1375,44 @@ fn look_for_library(lib_dirs: &[&[&str]], lib_filename: &str) -> Option<PathBuf>
         .find(|path| path.exists())
 }
 
+/// Find libmimalloc, for platforms with `allocator { strategy: mimalloc }`.
+///
+/// We don't vendor mimalloc, so it has to be installed on the system.
+/// Static builds need the archive; otherwise we prefer the shared library.
+pub fn find_mimalloc(target: &Triple, static_link: bool) -> Option<PathBuf> {
+    let architecture = format!("{}-linux-gnu", target.architecture);
+    let maybe_nix_path = nix_path_opt();
+    let nix_path_segments;
+    let usr_lib_arch = ["/usr", "lib", &architecture];
+    let lib_arch = ["/lib", &architecture];
+
+    let mut lib_dirs: Vec<&[&str]> = Vec::with_capacity(7);
+
+    if let Some(nix_path) = &maybe_nix_path {
+        nix_path_segments = [nix_path.as_str()];
+        lib_dirs.push(&nix_path_segments);
+    }
+
+    lib_dirs.extend_from_slice(&[
+        &usr_lib_arch,
+        &lib_arch,
+        &["/usr", "lib"],
+        &["/usr", "lib64"],
+        &["/usr", "local", "lib"],
+        &["/opt", "homebrew", "lib"],
+    ]);
+
+    let filenames: &[&str] = match (static_link, target.operating_system) {
+        (true, _) => &["libmimalloc.a"],
+        (false, OperatingSystem::Darwin) => &["libmimalloc.dylib", "libmimalloc.a"],
+        (false, _) => &["libmimalloc.so", "libmimalloc.a"],
+    };
+
+    filenames
+        .iter()
+        .find_map(|filename| look_for_library(&lib_dirs, filename))
+}
+
 fn link_linux(
     target: &Triple,
     output_path: PathBuf,
@@ -1530,7 +1888,7 @@ pub fn llvm_module_to_dylib(
     // Load the dylib
     let path = dylib_path.as_path().to_str().unwrap();
 
-    if matches!(target.architecture, Architecture::Aarch64(_)) {
+    let lib = if matches!(target.architecture, Architecture::Aarch64(_)) {
         // On AArch64 darwin machines, calling `ldopen` on Roc-generated libs from multiple threads
         // sometimes fails with
         //   cannot dlopen until fork() handlers have completed
@@ -1538,14 +1896,21 @@ pub fn llvm_module_to_dylib(
         // to be okay.
         loop {
             match unsafe { Library::new(path) } {
-                Ok(lib) => return Ok(lib),
+                Ok(lib) => break lib,
                 Err(Error::DlOpen { .. }) => continue,
                 Err(other) => return Err(other),
             }
         }
+    } else {
+        unsafe { Library::new(path) }?
+    };
+
+    // The dylib is deleted along with `dir`, so this is the last chance to tell profilers about it.
+    if perf_map::is_enabled() {
+        perf_map::register_library(&dylib_path, &lib);
     }
 
-    unsafe { Library::new(path) }
+    Ok(lib)
 }
 
 pub fn preprocess_host_wasm32(host_input_path: &Path, preprocessed_host_path: &Path) {
@@ -0,0 +1,237 @@
+//! Tells profilers which Roc procedures are at which addresses in a library loaded into this
+//! process, like the ones the REPL and `roc test` compile and load. Those libraries are deleted
+//! right after they're loaded, so a profiler that looks up symbols after the fact (like perf) can't
+//! find their symbol tables, and attributes their samples to unknown addresses instead.
+//!
+//! With `ROC_PERF_MAP` set, each loaded library's functions are appended to
+//! `/tmp/perf-<pid>.map`, which perf (and other profilers that support JIT symbol maps) reads.
+//! On Linux, they're also recorded in `/tmp/jit-<pid>.dump`, which `perf inject --jit` can use to
+//! annotate the generated machine code as well.
+use libloading::Library;
+use object::{Object, ObjectSymbol, SymbolKind};
+use roc_module::mangle::demangle;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Set this environment variable to write symbol maps for loaded libraries
+pub const ROC_PERF_MAP: &str = "ROC_PERF_MAP";
+
+pub fn is_enabled() -> bool {
+    std::env::var_os(ROC_PERF_MAP).is_some()
+}
+
+/// A function in a loaded library
+struct LoadedFunction {
+    name: String,
+    address: u64,
+    size: u64,
+}
+
+/// Records where the functions of `lib`, which was loaded from `dylib_path`, are in memory.
+/// Failing to do that doesn't affect running the library, so it's only reported.
+pub fn register_library(dylib_path: &Path, lib: &Library) {
+    let result = fs::read(dylib_path).and_then(|bytes| {
+        let functions = loaded_functions(&bytes, lib);
+
+        append_perf_map(&functions)?;
+
+        #[cfg(target_os = "linux")]
+        jitdump::append(&functions)?;
+
+        Ok(())
+    });
+
+    if let Err(error) = result {
+        eprintln!(
+            "Could not write a perf map for {}: {}",
+            dylib_path.display(),
+            error
+        );
+    }
+}
+
+fn loaded_functions(dylib_bytes: &[u8], lib: &Library) -> Vec<LoadedFunction> {
+    let object = match object::File::parse(dylib_bytes) {
+        Ok(object) => object,
+        Err(_) => return Vec::new(),
+    };
+
+    let functions: Vec<(&str, u64, u64)> = object
+        .symbols()
+        .filter(|sym| sym.kind() == SymbolKind::Text && sym.is_definition() && sym.size() > 0)
+        .filter_map(|sym| Some((sym.name().ok()?, sym.address(), sym.size())))
+        .collect();
+
+    // The library is loaded at some offset from the addresses in its symbol table. Looking up
+    // any function it exports tells us what that offset is.
+    let load_offset = functions.iter().find_map(|(name, address, _)| {
+        // Mach-O symbols start with an underscore that dlsym doesn't want.
+        let lookup_name = if cfg!(target_os = "macos") {
+            name.strip_prefix('_')?
+        } else {
+            name
+        };
+        let loaded = unsafe { lib.get::<*const u8>(lookup_name.as_bytes()) }.ok()?;
+
+        Some((*loaded as u64).wrapping_sub(*address))
+    });
+
+    match load_offset {
+        Some(load_offset) => functions
+            .into_iter()
+            .map(|(name, address, size)| LoadedFunction {
+                name: match demangle(name) {
+                    Some(demangled) => demangled.to_string(),
+                    None => name.to_string(),
+                },
+                address: address.wrapping_add(load_offset),
+                size,
+            })
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+fn tmp_file_path(prefix: &str, extension: &str) -> PathBuf {
+    PathBuf::from(format!(
+        "/tmp/{}-{}.{}",
+        prefix,
+        std::process::id(),
+        extension
+    ))
+}
+
+fn append_perf_map(functions: &[LoadedFunction]) -> io::Result<()> {
+    let mut buf = String::new();
+
+    for function in functions {
+        buf.push_str(&format!(
+            "{:x} {:x} {}\n",
+            function.address, function.size, function.name
+        ));
+    }
+
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(tmp_file_path("perf", "map"))?
+        .write_all(buf.as_bytes())
+}
+
+/// Writes perf's jitdump format, described in `tools/perf/Documentation/jitdump-specification.txt`
+/// in the Linux source tree.
+#[cfg(target_os = "linux")]
+mod jitdump {
+    use super::{tmp_file_path, LoadedFunction};
+    use std::fs::{File, OpenOptions};
+    use std::io::{self, Write};
+    use std::os::unix::io::AsRawFd;
+    use std::sync::Mutex;
+
+    const MAGIC: u32 = 0x4A69_5444;
+    const VERSION: u32 = 1;
+    const HEADER_SIZE: u32 = 40;
+    const JIT_CODE_LOAD: u32 = 0;
+
+    /// The dump file, and how many functions have been written to it so far
+    static DUMP: Mutex<Option<(File, u64)>> = Mutex::new(None);
+
+    pub(super) fn append(functions: &[LoadedFunction]) -> io::Result<()> {
+        let mut dump = DUMP.lock().unwrap();
+
+        if dump.is_none() {
+            *dump = Some((create()?, 0));
+        }
+
+        let (file, code_index) = dump.as_mut().unwrap();
+        let pid = std::process::id();
+        let tid = unsafe { libc::syscall(libc::SYS_gettid) } as u32;
+        let mut buf = Vec::new();
+
+        for function in functions {
+            // The dump includes the machine code, so perf can annotate it.
+            let code = unsafe {
+                std::slice::from_raw_parts(function.address as *const u8, function.size as usize)
+            };
+            let record_size = 16 + 40 + function.name.len() + 1 + code.len();
+
+            buf.extend(JIT_CODE_LOAD.to_ne_bytes());
+            buf.extend((record_size as u32).to_ne_bytes());
+            buf.extend(timestamp().to_ne_bytes());
+            buf.extend(pid.to_ne_bytes());
+            buf.extend(tid.to_ne_bytes());
+            buf.extend(function.address.to_ne_bytes());
+            buf.extend(function.address.to_ne_bytes());
+            buf.extend(function.size.to_ne_bytes());
+            buf.extend(code_index.to_ne_bytes());
+            buf.extend(function.name.as_bytes());
+            buf.push(0);
+            buf.extend(code);
+
+            *code_index += 1;
+        }
+
+        file.write_all(&buf)
+    }
+
+    fn create() -> io::Result<File> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(tmp_file_path("jit", "dump"))?;
+
+        let elf_mach: u32 = if cfg!(target_arch = "aarch64") {
+            183 // EM_AARCH64
+        } else {
+            62 // EM_X86_64
+        };
+
+        let mut header = Vec::with_capacity(HEADER_SIZE as usize);
+
+        header.extend(MAGIC.to_ne_bytes());
+        header.extend(VERSION.to_ne_bytes());
+        header.extend(HEADER_SIZE.to_ne_bytes());
+        header.extend(elf_mach.to_ne_bytes());
+        header.extend(0u32.to_ne_bytes());
+        header.extend(std::process::id().to_ne_bytes());
+        header.extend(timestamp().to_ne_bytes());
+        header.extend(0u64.to_ne_bytes());
+
+        file.write_all(&header)?;
+
+        // perf finds the dump through this process mapping it as executable, so the mapping has
+        // to stay around for as long as the process does.
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        let mapping = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                page_size,
+                libc::PROT_READ | libc::PROT_EXEC,
+                libc::MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+
+        if mapping == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(file)
+    }
+
+    /// perf expects timestamps from the monotonic clock (as with `perf record -k mono`)
+    fn timestamp() -> u64 {
+        let mut time = libc::timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        };
+
+        unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut time) };
+
+        time.tv_sec as u64 * 1_000_000_000 + time.tv_nsec as u64
+    }
+}
@@ -1,14 +1,25 @@
 use inkwell::memory_buffer::MemoryBuffer;
-use roc_error_macros::internal_error;
-use roc_gen_llvm::llvm::build::{module_from_builtins, LlvmBackendMode};
-use roc_gen_llvm::llvm::externs::add_default_roc_externs;
+use roc_collections::MutMap;
+use roc_error_macros::{internal_error, user_error};
+use roc_gen_llvm::llvm::build::{
+    bitcode_function_names, internalize_unused_bitcode, link_inline_hosted_bitcode,
+    module_from_builtins, LlvmBackendMode,
+};
+use roc_gen_llvm::llvm::externs::{add_default_roc_externs, add_provided_allocator_fns};
 use roc_load::{EntryPoint, ExpectMetadata, LoadedModule, MonomorphizedModule};
+use roc_module::symbol::ModuleId;
 use roc_mono::ir::{OptLevel, SingleEntryPoint};
-use roc_reporting::cli::{report_problems, Problems};
+use roc_mono::thread_escape::ThreadSharedLayouts;
+use roc_parse::header::{AllocatorStrategy, PlatformAllocator};
+use roc_reporting::cli::{report_problems, report_problems_html, Problems, WarningLevels};
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
+use crate::link::LinkingStrategy;
+
+pub use roc_gen_wasm::WasmFeatures;
+
 #[cfg(feature = "target-wasm32")]
 use roc_collections::all::MutSet;
 
@@ -17,23 +28,61 @@ pub struct CodeGenTiming {
     pub code_gen: Duration,
 }
 
-pub fn report_problems_monomorphized(loaded: &mut MonomorphizedModule) -> Problems {
+/// Lint problems are reported alongside the others, unless their warning levels leave them out
+fn include_lint_problems(
+    can_problems: &mut MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
+    lint_problems: &mut MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
+) {
+    for (module_id, problems) in lint_problems.drain() {
+        can_problems.entry(module_id).or_default().extend(problems);
+    }
+}
+
+pub fn report_problems_monomorphized(
+    loaded: &mut MonomorphizedModule,
+    warning_levels: &WarningLevels,
+) -> Problems {
+    include_lint_problems(&mut loaded.can_problems, &mut loaded.lint_problems);
+
     report_problems(
         loaded.total_problems(),
         &loaded.sources,
         &loaded.interns,
         &mut loaded.can_problems,
         &mut loaded.type_problems,
+        warning_levels,
     )
 }
 
-pub fn report_problems_typechecked(loaded: &mut LoadedModule) -> Problems {
+pub fn report_problems_typechecked(
+    loaded: &mut LoadedModule,
+    warning_levels: &WarningLevels,
+) -> Problems {
+    include_lint_problems(&mut loaded.can_problems, &mut loaded.lint_problems);
+
     report_problems(
         loaded.total_problems(),
         &loaded.sources,
         &loaded.interns,
         &mut loaded.can_problems,
         &mut loaded.type_problems,
+        warning_levels,
+    )
+}
+
+/// A standalone HTML page of the problems, leaving them in place to be reported to the terminal
+pub fn report_problems_html_typechecked(
+    loaded: &mut LoadedModule,
+    warning_levels: &WarningLevels,
+) -> String {
+    include_lint_problems(&mut loaded.can_problems, &mut loaded.lint_problems);
+
+    report_problems_html(
+        &loaded.sources,
+        &loaded.interns,
+        &mut loaded.can_problems.clone(),
+        &mut loaded.type_problems.clone(),
+        warning_levels,
     )
 }
 
@@ -65,6 +114,11 @@ pub struct CodeGenOptions {
     pub backend: CodeGenBackend,
     pub opt_level: OptLevel,
     pub emit_debug_info: bool,
+    /// Keep `dbg`s in optimized builds, which otherwise strip them.
+    pub keep_dbg: bool,
+    /// Optimize float arithmetic like C's `-ffast-math`. Only the LLVM backend uses this; the
+    /// others always follow IEEE-754 exactly.
+    pub fast_math: bool,
 }
 
 type GenFromMono<'a> = (CodeObject, CodeGenTiming, ExpectMetadata<'a>);
@@ -76,8 +130,10 @@ pub fn gen_from_mono_module<'a>(
     roc_file_path: &Path,
     target: &target_lexicon::Triple,
     code_gen_options: CodeGenOptions,
+    linking_strategy: LinkingStrategy,
     preprocessed_host_path: &Path,
     wasm_dev_stack_bytes: Option<u32>,
+    wasm_dev_features: WasmFeatures,
 ) -> GenFromMono<'a> {
     match code_gen_options.backend {
         CodeGenBackend::Assembly => gen_from_mono_module_dev(
@@ -86,17 +142,53 @@ pub fn gen_from_mono_module<'a>(
             target,
             preprocessed_host_path,
             wasm_dev_stack_bytes,
+            wasm_dev_features,
+            code_gen_options.emit_debug_info,
+        ),
+        CodeGenBackend::Llvm => gen_from_mono_module_llvm(
+            arena,
+            loaded,
+            roc_file_path,
+            target,
+            code_gen_options,
+            linking_strategy,
         ),
-        CodeGenBackend::Llvm => {
-            gen_from_mono_module_llvm(arena, loaded, roc_file_path, target, code_gen_options)
-        }
         CodeGenBackend::Wasm => {
             // emit wasm via the llvm backend
-            gen_from_mono_module_llvm(arena, loaded, roc_file_path, target, code_gen_options)
+            gen_from_mono_module_llvm(
+                arena,
+                loaded,
+                roc_file_path,
+                target,
+                code_gen_options,
+                linking_strategy,
+            )
         }
     }
 }
 
+/// The allocator functions Roc must define for this platform, as `(extern_name, builtin_name)`
+pub fn provided_allocator_fns(
+    allocator: PlatformAllocator,
+) -> &'static [(&'static str, &'static str)] {
+    use roc_builtins::bitcode;
+
+    match allocator.strategy {
+        AllocatorStrategy::Malloc => &[],
+        AllocatorStrategy::Arena => &[("roc_dealloc", bitcode::UTILS_NOOP_DEALLOC)],
+        AllocatorStrategy::Roc => &[
+            ("roc_alloc", bitcode::UTILS_DEFAULT_ALLOC),
+            ("roc_realloc", bitcode::UTILS_DEFAULT_REALLOC),
+            ("roc_dealloc", bitcode::UTILS_DEFAULT_DEALLOC),
+        ],
+        AllocatorStrategy::Mimalloc => &[
+            ("roc_alloc", bitcode::UTILS_MIMALLOC_ALLOC),
+            ("roc_realloc", bitcode::UTILS_MIMALLOC_REALLOC),
+            ("roc_dealloc", bitcode::UTILS_MIMALLOC_DEALLOC),
+        ],
+    }
+}
+
 // TODO how should imported modules factor into this? What if those use builtins too?
 // TODO this should probably use more helper functions
 // TODO make this polymorphic in the llvm functions so it can be reused for another backend.
@@ -106,6 +198,7 @@ fn gen_from_mono_module_llvm<'a>(
     roc_file_path: &Path,
     target: &target_lexicon::Triple,
     code_gen_options: CodeGenOptions,
+    linking_strategy: LinkingStrategy,
 ) -> GenFromMono<'a> {
     use crate::target::{self, convert_opt_level};
     use inkwell::attributes::{Attribute, AttributeLoc};
@@ -119,6 +212,7 @@ fn gen_from_mono_module_llvm<'a>(
     let target_info = roc_target::TargetInfo::from(target);
     let context = Context::create();
     let module = arena.alloc(module_from_builtins(target, &context, "app"));
+    let bitcode_fns = bitcode_function_names(module);
 
     // strip Zig debug stuff
     // module.strip_debug_info();
@@ -159,6 +253,8 @@ fn gen_from_mono_module_llvm<'a>(
         backend: _,
         opt_level,
         emit_debug_info,
+        keep_dbg,
+        fast_math,
     } = code_gen_options;
 
     let builder = context.create_builder();
@@ -177,16 +273,40 @@ fn gen_from_mono_module_llvm<'a>(
         target_info,
         mode: match opt_level {
             OptLevel::Development => LlvmBackendMode::BinaryDev,
+            OptLevel::Normal | OptLevel::Size | OptLevel::Optimize if keep_dbg => {
+                LlvmBackendMode::BinaryDev
+            }
             OptLevel::Normal | OptLevel::Size | OptLevel::Optimize => LlvmBackendMode::Binary,
         },
 
         exposed_to_host: loaded.exposed_to_host.values.keys().copied().collect(),
+        thread_shared_layouts: ThreadSharedLayouts::from_procs(
+            &loaded.layout_interner,
+            loaded.procedures.values(),
+        ),
+        fixtures: MutSet::default(),
+        batch_effects: loaded.platform_effects.is_batched(),
+        fast_math,
     };
 
     // does not add any externs for this mode (we have a host) but cleans up some functions around
     // expects that would confuse the surgical linker
     add_default_roc_externs(&env);
 
+    if env.mode.has_host() {
+        add_provided_allocator_fns(&env, provided_allocator_fns(loaded.platform_allocator));
+    }
+
+    // the platform can define small hosted functions in bitcode beside its main.roc
+    let inline_hosted_bitcode = match &loaded.entry_point {
+        EntryPoint::Executable { platform_path, .. } => {
+            crate::link::inline_hosted_bitcode_filename(target)
+                .map(|filename| platform_path.with_file_name(filename))
+                .filter(|path| path.exists())
+        }
+        EntryPoint::Test => None,
+    };
+
     let entry_point = match loaded.entry_point {
         EntryPoint::Executable {
             exposed_to_host,
@@ -212,9 +332,22 @@ fn gen_from_mono_module_llvm<'a>(
 
     env.dibuilder.finalize();
 
+    if let Some(path) = inline_hosted_bitcode {
+        link_inline_hosted_bitcode(&context, module, &path).unwrap_or_else(|err| {
+            user_error!(
+                "I couldn't link in the platform's inline hosted functions from {}:\n{}",
+                path.display(),
+                err
+            )
+        });
+    }
+
     // we don't use the debug info, and it causes weird errors.
     module.strip_debug_info();
 
+    // only keep the builtins our procs actually reference; global DCE removes the rest
+    internalize_unused_bitcode(module, &bitcode_fns);
+
     // Uncomment this to see the module's optimized LLVM instruction output:
     // env.module.print_to_stderr();
 
@@ -402,7 +535,21 @@ fn gen_from_mono_module_llvm<'a>(
             Architecture::Wasm32 => {
                 // Useful for debugging
                 // module.print_to_file(app_ll_file);
-                module.write_bitcode_to_memory()
+
+                if linking_strategy == LinkingStrategy::Surgical {
+                    // The surgical linker splices this into the host, so it must be relocatable,
+                    // but without dynamic linking there's nothing for PIC to be independent of.
+                    let reloc = RelocMode::Static;
+                    let target_machine =
+                        target::target_machine(target, convert_opt_level(opt_level), reloc)
+                            .unwrap();
+
+                    target_machine
+                        .write_to_memory_buffer(env.module, FileType::Object)
+                        .expect("Writing .o file failed")
+                } else {
+                    module.write_bitcode_to_memory()
+                }
             }
             _ => internal_error!(
                 "TODO gracefully handle unsupported architecture: {:?}",
@@ -431,6 +578,8 @@ fn gen_from_mono_module_dev<'a>(
     target: &target_lexicon::Triple,
     preprocessed_host_path: &Path,
     wasm_dev_stack_bytes: Option<u32>,
+    wasm_dev_features: WasmFeatures,
+    emit_debug_info: bool,
 ) -> GenFromMono<'a> {
     use target_lexicon::Architecture;
 
@@ -440,6 +589,8 @@ fn gen_from_mono_module_dev<'a>(
             loaded,
             preprocessed_host_path,
             wasm_dev_stack_bytes,
+            wasm_dev_features,
+            emit_debug_info,
         ),
         Architecture::X86_64 | Architecture::Aarch64(_) => {
             gen_from_mono_module_dev_assembly(arena, loaded, target)
@@ -455,6 +606,8 @@ pub fn gen_from_mono_module_dev<'a>(
     target: &target_lexicon::Triple,
     _host_input_path: &Path,
     _wasm_dev_stack_bytes: Option<u32>,
+    _wasm_dev_features: WasmFeatures,
+    _emit_debug_info: bool,
 ) -> GenFromMono<'a> {
     use target_lexicon::Architecture;
 
@@ -472,6 +625,8 @@ fn gen_from_mono_module_dev_wasm32<'a>(
     loaded: MonomorphizedModule<'a>,
     preprocessed_host_path: &Path,
     wasm_dev_stack_bytes: Option<u32>,
+    wasm_dev_features: WasmFeatures,
+    emit_debug_info: bool,
 ) -> GenFromMono<'a> {
     let code_gen_start = Instant::now();
     let MonomorphizedModule {
@@ -479,6 +634,8 @@ fn gen_from_mono_module_dev_wasm32<'a>(
         procedures,
         mut interns,
         mut layout_interner,
+        def_regions,
+        sources,
         ..
     } = loaded;
 
@@ -494,6 +651,7 @@ fn gen_from_mono_module_dev_wasm32<'a>(
         module_id,
         exposed_to_host,
         stack_bytes: wasm_dev_stack_bytes.unwrap_or(roc_gen_wasm::Env::DEFAULT_STACK_BYTES),
+        features: wasm_dev_features,
     };
 
     let host_bytes = std::fs::read(preprocessed_host_path).unwrap_or_else(|_| {
@@ -512,7 +670,9 @@ fn gen_from_mono_module_dev_wasm32<'a>(
         )
     });
 
-    let final_binary_bytes = roc_gen_wasm::build_app_binary(
+    let app_fns = emit_debug_info.then(|| roc_gen_wasm::app_fn_symbols(&host_module, &procedures));
+
+    let mut final_binary_bytes = roc_gen_wasm::build_app_binary(
         &env,
         &mut layout_interner,
         &mut interns,
@@ -520,6 +680,28 @@ fn gen_from_mono_module_dev_wasm32<'a>(
         procedures,
     );
 
+    // Embed a source map, so that browser devtools can show the Roc code
+    if let Some(app_fns) = app_fns {
+        let source_map = roc_gen_wasm::source_map::SourceMap::new(
+            arena,
+            &final_binary_bytes,
+            &app_fns,
+            &def_regions,
+            &sources,
+        )
+        .unwrap_or_else(|e| {
+            internal_error!(
+                "Failed to build a source map for the generated Wasm, at offset 0x{:x}:\n{}",
+                e.offset,
+                e.message
+            )
+        });
+        roc_gen_wasm::source_map::append_source_mapping_url(
+            &mut final_binary_bytes,
+            &source_map.to_data_url(),
+        );
+    }
+
     let code_gen = code_gen_start.elapsed();
 
     (
@@ -549,6 +731,9 @@ fn gen_from_mono_module_dev_assembly<'a>(
         mut interns,
         exposed_to_host,
         mut layout_interner,
+        def_regions,
+        sources,
+        platform_allocator,
         ..
     } = loaded;
 
@@ -558,6 +743,8 @@ fn gen_from_mono_module_dev_assembly<'a>(
         exposed_to_host: exposed_to_host.values.keys().copied().collect(),
         lazy_literals,
         generate_allocators,
+        provided_allocator_fns: provided_allocator_fns(platform_allocator),
+        def_locations: Some(roc_gen_dev::DefLocations::new(&def_regions, &sources)),
     };
 
     let module_object =
@@ -0,0 +1,76 @@
+// Default implementations of roc_alloc, roc_realloc and roc_dealloc,
+// for platforms whose header asks Roc to provide them.
+//
+// These use libc, so they only exist on targets where libc is linked.
+// malloc guarantees an alignment of 2 * @sizeOf(usize), which is enough for every Roc value;
+// the linker rejects platforms that ask for more.
+
+const panic_utils = @import("panic.zig");
+
+extern fn malloc(size: usize) callconv(.C) ?*anyopaque;
+extern fn realloc(c_ptr: *anyopaque, new_size: usize) callconv(.C) ?*anyopaque;
+extern fn free(c_ptr: *anyopaque) callconv(.C) void;
+
+pub fn defaultAllocC(size: usize, alignment: u32) callconv(.C) ?*anyopaque {
+    _ = alignment;
+    return malloc(size);
+}
+
+pub fn defaultReallocC(c_ptr: *anyopaque, new_size: usize, old_size: usize, alignment: u32) callconv(.C) ?*anyopaque {
+    _ = old_size;
+    _ = alignment;
+    return realloc(c_ptr, new_size);
+}
+
+pub fn defaultDeallocC(c_ptr: *anyopaque, alignment: u32) callconv(.C) void {
+    _ = alignment;
+    free(c_ptr);
+}
+
+// For arena platforms: memory is released all at once by the host, so freeing is a no-op.
+pub fn noopDeallocC(c_ptr: *anyopaque, alignment: u32) callconv(.C) void {
+    _ = c_ptr;
+    _ = alignment;
+}
+
+// mimalloc, for platforms with `allocator { strategy: mimalloc }`.
+// These are weak so that the builtins still link without mimalloc. The CLI refuses to link a
+// mimalloc platform without libmimalloc, so if they're missing anyway we panic rather than
+// quietly using a different allocator.
+const MiMallocAligned = fn (usize, usize) callconv(.C) ?*anyopaque;
+const MiReallocAligned = fn (*anyopaque, usize, usize) callconv(.C) ?*anyopaque;
+const MiFree = fn (*anyopaque) callconv(.C) void;
+
+const mi_malloc_aligned = @extern(?MiMallocAligned, .{ .name = "mi_malloc_aligned", .linkage = .Weak });
+const mi_realloc_aligned = @extern(?MiReallocAligned, .{ .name = "mi_realloc_aligned", .linkage = .Weak });
+const mi_free = @extern(?MiFree, .{ .name = "mi_free", .linkage = .Weak });
+
+pub fn mimallocAllocC(size: usize, alignment: u32) callconv(.C) ?*anyopaque {
+    if (mi_malloc_aligned) |f| {
+        return f(size, alignment);
+    }
+    mimallocMissing();
+    return null;
+}
+
+pub fn mimallocReallocC(c_ptr: *anyopaque, new_size: usize, old_size: usize, alignment: u32) callconv(.C) ?*anyopaque {
+    _ = old_size;
+    if (mi_realloc_aligned) |f| {
+        return f(c_ptr, new_size, alignment);
+    }
+    mimallocMissing();
+    return null;
+}
+
+pub fn mimallocDeallocC(c_ptr: *anyopaque, alignment: u32) callconv(.C) void {
+    _ = alignment;
+    if (mi_free) |f| {
+        return f(c_ptr);
+    }
+    mimallocMissing();
+}
+
+fn mimallocMissing() void {
+    // Short enough to be a small string, so reporting it doesn't allocate.
+    panic_utils.panic_help("mimalloc isn't linked", 0);
+}
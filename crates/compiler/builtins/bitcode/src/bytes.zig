@@ -0,0 +1,43 @@
+const std = @import("std");
+const RocList = @import("list.zig").RocList;
+
+// Each of these reads an integer starting at the given position in a list of
+// bytes, in either byte order. The Bytes module checks that the list has enough
+// bytes before calling them.
+
+pub fn readU16(list: RocList, position: usize, big_endian: bool) callconv(.C) u16 {
+    return read(u16, list, position, big_endian);
+}
+
+pub fn readU32(list: RocList, position: usize, big_endian: bool) callconv(.C) u32 {
+    return read(u32, list, position, big_endian);
+}
+
+pub fn readU64(list: RocList, position: usize, big_endian: bool) callconv(.C) u64 {
+    return read(u64, list, position, big_endian);
+}
+
+fn read(comptime T: type, list: RocList, position: usize, big_endian: bool) T {
+    std.debug.assert(position + @sizeOf(T) <= list.len());
+
+    const bytes = @ptrCast([*]const u8, list.bytes) + position;
+    const endian: std.builtin.Endian = if (big_endian) .Big else .Little;
+
+    return std.mem.readInt(T, bytes[0..@sizeOf(T)], endian);
+}
+
+test "readU16 in both byte orders" {
+    const list = RocList.fromSlice(u8, &[_]u8{ 0x00, 0x12, 0x34 });
+    defer list.deinit(u8);
+
+    try std.testing.expectEqual(@as(u16, 0x3412), readU16(list, 1, false));
+    try std.testing.expectEqual(@as(u16, 0x1234), readU16(list, 1, true));
+}
+
+test "readU32 and readU64 at the end of the list" {
+    const list = RocList.fromSlice(u8, &[_]u8{ 0xff, 1, 2, 3, 4, 5, 6, 7, 8 });
+    defer list.deinit(u8);
+
+    try std.testing.expectEqual(@as(u32, 0x05060708), readU32(list, 5, true));
+    try std.testing.expectEqual(@as(u64, 0x0807060504030201), readU64(list, 1, false));
+}
@@ -45,13 +45,15 @@ pub const RocDec = extern struct {
     }
 
     pub fn fromStr(roc_str: RocStr) ?RocDec {
-        if (roc_str.isEmpty()) {
-            return null;
-        }
+        return parse(roc_str.asSlice()) catch null;
+    }
 
-        const length = roc_str.len();
+    pub fn parse(roc_str_slice: []const u8) num_.ParseError!RocDec {
+        if (roc_str_slice.len == 0) {
+            return error.EmptyStr;
+        }
 
-        const roc_str_slice = roc_str.asSlice();
+        const length = roc_str_slice.len;
 
         var is_negative: bool = roc_str_slice[0] == '-';
         var initial_index: usize = if (is_negative) 1 else 0;
@@ -67,7 +69,7 @@ pub const RocDec = extern struct {
             }
 
             if (!isDigit(byte)) {
-                return null;
+                return error.InvalidChar;
             }
             index += 1;
         }
@@ -79,7 +81,7 @@ pub const RocDec = extern struct {
 
             var after_str_len = (length - 1) - pi;
             if (after_str_len > decimal_places) {
-                @panic("TODO runtime exception for too many decimal places!");
+                return error.TooManyDecimals;
             }
             var diff_decimal_places = decimal_places - after_str_len;
 
@@ -89,14 +91,17 @@ pub const RocDec = extern struct {
         }
 
         var before_str = roc_str_slice[initial_index..before_str_length];
-        var before_val_not_adjusted = std.fmt.parseUnsigned(i128, before_str, 10) catch null;
+        var before_val_not_adjusted = std.fmt.parseUnsigned(i128, before_str, 10) catch |err| switch (err) {
+            error.Overflow => return error.OutOfRange,
+            error.InvalidCharacter => null,
+        };
 
         var before_val_i128: ?i128 = null;
         if (before_val_not_adjusted) |before| {
             var result: i128 = undefined;
             var overflowed = @mulWithOverflow(i128, before, one_point_zero_i128, &result);
             if (overflowed) {
-                @panic("TODO runtime exception for overflow!");
+                return error.OutOfRange;
             }
             before_val_i128 = result;
         }
@@ -107,7 +112,7 @@ pub const RocDec = extern struct {
                     var result: i128 = undefined;
                     var overflowed = @addWithOverflow(i128, before, after, &result);
                     if (overflowed) {
-                        @panic("TODO runtime exception for overflow!");
+                        return error.OutOfRange;
                     }
                     break :blk .{ .num = result };
                 } else {
@@ -116,7 +121,7 @@ pub const RocDec = extern struct {
             } else if (after_val_i128) |after| {
                 break :blk .{ .num = after };
             } else {
-                return null;
+                return error.NoDigits;
             }
         };
 
@@ -838,6 +843,14 @@ test "fromStr: .123.1" {
     try expectEqual(dec, null);
 }
 
+test "parse: errors" {
+    try expectError(error.EmptyStr, RocDec.parse(""));
+    try expectError(error.NoDigits, RocDec.parse("-."));
+    try expectError(error.InvalidChar, RocDec.parse("1e5"));
+    try expectError(error.TooManyDecimals, RocDec.parse("0.1234567890123456789"));
+    try expectError(error.OutOfRange, RocDec.parse("170141183460469231732"));
+}
+
 test "toStr: 123.45" {
     var dec: RocDec = .{ .num = 123450000000000000000 };
     var res_roc_str = dec.toStr();
@@ -1058,10 +1071,10 @@ test "div: 10 / 3" {
 // exports
 
 pub fn fromStr(arg: RocStr) callconv(.C) num_.NumParseResult(i128) {
-    if (@call(.{ .modifier = always_inline }, RocDec.fromStr, .{arg})) |dec| {
+    if (@call(.{ .modifier = always_inline }, RocDec.parse, .{arg.asSlice()})) |dec| {
         return .{ .errorcode = 0, .value = dec.num };
-    } else {
-        return .{ .errorcode = 1, .value = 0 };
+    } else |err| {
+        return .{ .errorcode = num_.parseErrorCode(err), .value = 0 };
     }
 }
 
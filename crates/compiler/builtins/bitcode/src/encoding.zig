@@ -0,0 +1,327 @@
+const std = @import("std");
+const RocList = @import("list.zig").RocList;
+const RocStr = @import("str.zig").RocStr;
+
+// The formats Encoding.roc encodes and decodes, numbered as it numbers them.
+const Format = enum(u8) {
+    base64 = 0,
+    base64_url = 1,
+    hex = 2,
+    percent = 3,
+};
+
+const base64_alphabet = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const base64_url_alphabet = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+const hex_digits = "0123456789abcdef";
+const upper_hex_digits = "0123456789ABCDEF";
+
+pub fn encode(list: RocList, format: u8) callconv(.C) RocStr {
+    const bytes: []const u8 = if (list.bytes) |ptr| ptr[0..list.len()] else &[_]u8{};
+    const encoded_len = switch (@intToEnum(Format, format)) {
+        .base64 => (bytes.len + 2) / 3 * 4,
+        // base64url is usually used without padding, since `=` isn't safe in URLs either
+        .base64_url => (bytes.len * 4 + 2) / 3,
+        .hex => bytes.len * 2,
+        .percent => percentEncodedLen(bytes),
+    };
+
+    var str = RocStr.allocate(encoded_len);
+    const output = str.asU8ptrMut()[0..encoded_len];
+
+    switch (@intToEnum(Format, format)) {
+        .base64 => base64Encode(base64_alphabet, true, bytes, output),
+        .base64_url => base64Encode(base64_url_alphabet, false, bytes, output),
+        .hex => hexEncode(bytes, output),
+        .percent => percentEncode(bytes, output),
+    }
+
+    return str;
+}
+
+/// Decodes a Str which `check` found no problems with
+pub fn decode(str: RocStr, format: u8) callconv(.C) RocList {
+    const encoded = str.asSlice();
+    const data = switch (@intToEnum(Format, format)) {
+        .base64, .base64_url => std.mem.trimRight(u8, encoded, "="),
+        .hex, .percent => encoded,
+    };
+    const decoded_len = switch (@intToEnum(Format, format)) {
+        .base64, .base64_url => data.len * 3 / 4,
+        .hex => data.len / 2,
+        .percent => data.len - 2 * std.mem.count(u8, data, "%"),
+    };
+
+    const list = RocList.allocate(@alignOf(u8), decoded_len, @sizeOf(u8));
+
+    if (list.bytes) |ptr| {
+        const output = ptr[0..decoded_len];
+
+        switch (@intToEnum(Format, format)) {
+            .base64, .base64_url => base64Decode(data, output),
+            .hex => hexDecode(data, output),
+            .percent => percentDecode(data, output),
+        }
+    }
+
+    return list;
+}
+
+/// The index of the first byte in the Str which can't be there in this format,
+/// or the Str's length if there isn't one. Encoding.roc checks the length itself.
+pub fn check(str: RocStr, format: u8) callconv(.C) usize {
+    const encoded = str.asSlice();
+
+    switch (@intToEnum(Format, format)) {
+        .base64, .base64_url => {
+            // up to two `=`s of padding can come at the end
+            var end = encoded.len;
+            while (end > 0 and encoded.len - end < 2 and encoded[end - 1] == '=') {
+                end -= 1;
+            }
+
+            for (encoded[0..end]) |byte, index| {
+                if (base64Value(byte) == null) {
+                    return index;
+                }
+            }
+        },
+        .hex => {
+            for (encoded) |byte, index| {
+                if (hexValue(byte) == null) {
+                    return index;
+                }
+            }
+        },
+        .percent => {
+            var index: usize = 0;
+            while (index < encoded.len) : (index += 1) {
+                if (encoded[index] == '%') {
+                    if (index + 2 >= encoded.len) {
+                        return index;
+                    }
+
+                    if (hexValue(encoded[index + 1]) == null or hexValue(encoded[index + 2]) == null) {
+                        return index;
+                    }
+
+                    index += 2;
+                }
+            }
+        },
+    }
+
+    return encoded.len;
+}
+
+fn base64Encode(comptime alphabet: *const [64]u8, comptime pad: bool, input: []const u8, output: []u8) void {
+    var in: usize = 0;
+    var out: usize = 0;
+
+    while (in + 3 <= input.len) : (in += 3) {
+        const group = @as(u32, input[in]) << 16 | @as(u32, input[in + 1]) << 8 | input[in + 2];
+
+        output[out] = alphabet[group >> 18];
+        output[out + 1] = alphabet[(group >> 12) & 63];
+        output[out + 2] = alphabet[(group >> 6) & 63];
+        output[out + 3] = alphabet[group & 63];
+        out += 4;
+    }
+
+    const remaining = input.len - in;
+
+    if (remaining > 0) {
+        var group = @as(u32, input[in]) << 16;
+        if (remaining == 2) {
+            group |= @as(u32, input[in + 1]) << 8;
+        }
+
+        output[out] = alphabet[group >> 18];
+        output[out + 1] = alphabet[(group >> 12) & 63];
+        out += 2;
+
+        if (remaining == 2) {
+            output[out] = alphabet[(group >> 6) & 63];
+            out += 1;
+        }
+
+        if (pad) {
+            while (out % 4 != 0) : (out += 1) {
+                output[out] = '=';
+            }
+        }
+    }
+}
+
+fn base64Decode(input: []const u8, output: []u8) void {
+    var bits: u32 = 0;
+    var bit_count: u5 = 0;
+    var out: usize = 0;
+
+    for (input) |byte| {
+        bits = bits << 6 | base64Value(byte).?;
+        bit_count += 6;
+
+        if (bit_count >= 8) {
+            bit_count -= 8;
+            output[out] = @truncate(u8, bits >> bit_count);
+            out += 1;
+        }
+    }
+}
+
+/// Both alphabets decode the same way, since they only differ in two characters
+fn base64Value(byte: u8) ?u6 {
+    return switch (byte) {
+        'A'...'Z' => @intCast(u6, byte - 'A'),
+        'a'...'z' => @intCast(u6, byte - 'a' + 26),
+        '0'...'9' => @intCast(u6, byte - '0' + 52),
+        '+', '-' => 62,
+        '/', '_' => 63,
+        else => null,
+    };
+}
+
+const hex_lanes = 16;
+
+fn hexEncode(input: []const u8, output: []u8) void {
+    var index: usize = 0;
+
+    // Zig lowers these vectors to SIMD instructions on targets which have them
+    while (index + hex_lanes <= input.len) : (index += hex_lanes) {
+        const chunk: @Vector(hex_lanes, u8) = input[index..][0..hex_lanes].*;
+        const high = hexDigitsOf(chunk >> @splat(hex_lanes, @as(u3, 4)));
+        const low = hexDigitsOf(chunk & @splat(hex_lanes, @as(u8, 0xf)));
+        const interleaved: [2 * hex_lanes]u8 = @shuffle(u8, high, low, interleave_mask);
+
+        output[2 * index ..][0 .. 2 * hex_lanes].* = interleaved;
+    }
+
+    for (input[index..]) |byte, offset| {
+        output[2 * (index + offset)] = hex_digits[byte >> 4];
+        output[2 * (index + offset) + 1] = hex_digits[byte & 0xf];
+    }
+}
+
+fn hexDigitsOf(nibbles: @Vector(hex_lanes, u8)) @Vector(hex_lanes, u8) {
+    const is_digit = nibbles < @splat(hex_lanes, @as(u8, 10));
+    const offset = @select(u8, is_digit, @splat(hex_lanes, @as(u8, '0')), @splat(hex_lanes, @as(u8, 'a' - 10)));
+
+    return nibbles + offset;
+}
+
+// takes the first digit from `high` and then the first from `low`, and so on
+const interleave_mask = blk: {
+    var mask: [2 * hex_lanes]i32 = undefined;
+
+    for (mask) |*lane, index| {
+        const from = @intCast(i32, index / 2);
+        lane.* = if (index % 2 == 0) from else ~from;
+    }
+
+    break :blk mask;
+};
+
+fn hexDecode(input: []const u8, output: []u8) void {
+    for (output) |*byte, index| {
+        byte.* = @as(u8, hexValue(input[2 * index]).?) << 4 | hexValue(input[2 * index + 1]).?;
+    }
+}
+
+fn hexValue(byte: u8) ?u4 {
+    return switch (byte) {
+        '0'...'9' => @intCast(u4, byte - '0'),
+        'a'...'f' => @intCast(u4, byte - 'a' + 10),
+        'A'...'F' => @intCast(u4, byte - 'A' + 10),
+        else => null,
+    };
+}
+
+/// The characters RFC 3986 says never need to be percent-encoded
+fn isUnreserved(byte: u8) bool {
+    return switch (byte) {
+        'A'...'Z', 'a'...'z', '0'...'9', '-', '.', '_', '~' => true,
+        else => false,
+    };
+}
+
+fn percentEncodedLen(input: []const u8) usize {
+    var len: usize = 0;
+
+    for (input) |byte| {
+        len += if (isUnreserved(byte)) @as(usize, 1) else 3;
+    }
+
+    return len;
+}
+
+fn percentEncode(input: []const u8, output: []u8) void {
+    var out: usize = 0;
+
+    for (input) |byte| {
+        if (isUnreserved(byte)) {
+            output[out] = byte;
+            out += 1;
+        } else {
+            output[out] = '%';
+            output[out + 1] = upper_hex_digits[byte >> 4];
+            output[out + 2] = upper_hex_digits[byte & 0xf];
+            out += 3;
+        }
+    }
+}
+
+fn percentDecode(input: []const u8, output: []u8) void {
+    var in: usize = 0;
+    var out: usize = 0;
+
+    while (in < input.len) : (out += 1) {
+        if (input[in] == '%') {
+            output[out] = @as(u8, hexValue(input[in + 1]).?) << 4 | hexValue(input[in + 2]).?;
+            in += 3;
+        } else {
+            output[out] = input[in];
+            in += 1;
+        }
+    }
+}
+
+fn expectEncoded(expected: []const u8, input: []const u8, format: Format) !void {
+    const list = RocList.fromSlice(u8, input);
+    defer list.deinit(u8);
+
+    const encoded = encode(list, @enumToInt(format));
+    defer encoded.deinit();
+
+    try std.testing.expectEqualStrings(expected, encoded.asSlice());
+
+    const decoded = decode(encoded, @enumToInt(format));
+    defer decoded.deinit(u8);
+
+    try std.testing.expectEqual(encoded.len(), check(encoded, @enumToInt(format)));
+    try std.testing.expectEqualSlices(u8, input, if (decoded.bytes) |ptr| ptr[0..decoded.len()] else &[_]u8{});
+}
+
+test "base64" {
+    try expectEncoded("", "", .base64);
+    try expectEncoded("Zg==", "f", .base64);
+    try expectEncoded("Zm8=", "fo", .base64);
+    try expectEncoded("Zm9vYmFy", "foobar", .base64);
+    try expectEncoded("-_8", &[_]u8{ 0xfb, 0xff }, .base64_url);
+}
+
+test "hex" {
+    try expectEncoded("00ff10", &[_]u8{ 0x00, 0xff, 0x10 }, .hex);
+    try expectEncoded("000102030405060708090a0b0c0d0e0f10deadbeef", &[_]u8{ 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 0xde, 0xad, 0xbe, 0xef }, .hex);
+}
+
+test "percent" {
+    try expectEncoded("a%20b%2Fc~", "a b/c~", .percent);
+}
+
+test "check" {
+    const bad_base64 = RocStr.fromSlice("Zm9v!mFy");
+    try std.testing.expectEqual(@as(usize, 4), check(bad_base64, @enumToInt(Format.base64)));
+
+    const bad_percent = RocStr.fromSlice("a%2");
+    try std.testing.expectEqual(@as(usize, 1), check(bad_percent, @enumToInt(Format.percent)));
+}
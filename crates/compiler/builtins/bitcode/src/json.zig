@@ -0,0 +1,523 @@
+const std = @import("std");
+const RocList = @import("list.zig").RocList;
+const RocStr = @import("str.zig").RocStr;
+
+// The scanning Json.roc does to decode JSON. A scan returns the position it
+// stopped at, shifted left by 2, with how it stopped in the low 2 bits.
+const Status = enum(u64) {
+    // the position is where the thing scanned ends
+    complete = 0,
+    // the bytes ran out first, so with more of them it might be valid
+    incomplete = 1,
+    // the position is the first byte that can't be there
+    invalid = 2,
+    // a number ends where the bytes do, so it's complete unless more are coming
+    at_end = 3,
+};
+
+fn scanResult(position: usize, status: Status) u64 {
+    return @as(u64, position) << 2 | @enumToInt(status);
+}
+
+fn bytesOf(list: RocList) []const u8 {
+    return if (list.bytes) |ptr| ptr[0..list.len()] else &[_]u8{};
+}
+
+// Zig lowers these vectors to SIMD instructions on targets which have them, so
+// long runs of whitespace and of plain characters in strings are skipped 16
+// bytes at a time.
+const lanes = 16;
+const Chunk = @Vector(lanes, u8);
+
+fn splat(byte: u8) Chunk {
+    return @splat(lanes, byte);
+}
+
+// 1 in each lane where `matches` is true, and 0 elsewhere
+fn mask(matches: @Vector(lanes, bool)) Chunk {
+    return @select(u8, matches, splat(1), splat(0));
+}
+
+fn chunkAt(bytes: []const u8, index: usize) Chunk {
+    return bytes[index..][0..lanes].*;
+}
+
+fn isWhitespace(byte: u8) bool {
+    return switch (byte) {
+        ' ', '\t', '\n', '\r' => true,
+        else => false,
+    };
+}
+
+fn skipWhitespaceIn(bytes: []const u8, start: usize) usize {
+    var index = start;
+
+    while (index + lanes <= bytes.len) : (index += lanes) {
+        const chunk = chunkAt(bytes, index);
+        const whitespace = mask(chunk == splat(' ')) | mask(chunk == splat('\t')) | mask(chunk == splat('\n')) | mask(chunk == splat('\r'));
+
+        if (@reduce(.Min, whitespace) == 0) {
+            break;
+        }
+    }
+
+    while (index < bytes.len and isWhitespace(bytes[index])) {
+        index += 1;
+    }
+
+    return index;
+}
+
+/// The index of the first byte at or after `index` which isn't whitespace, or
+/// the list's length if there isn't one
+pub fn skipWhitespace(list: RocList, index: usize) callconv(.C) usize {
+    return skipWhitespaceIn(bytesOf(list), index);
+}
+
+fn hexValue(byte: u8) ?u16 {
+    return switch (byte) {
+        '0'...'9' => byte - '0',
+        'a'...'f' => byte - 'a' + 10,
+        'A'...'F' => byte - 'A' + 10,
+        else => null,
+    };
+}
+
+// The code unit in a `\uXXXX` escape, or the scan result to return if it isn't one
+const UnicodeEscape = union(enum) {
+    unit: u16,
+    err: u64,
+};
+
+fn unicodeEscape(bytes: []const u8, index: usize) UnicodeEscape {
+    var unit: u16 = 0;
+    var offset: usize = 2;
+
+    while (offset < 6) : (offset += 1) {
+        if (index + offset >= bytes.len) {
+            return .{ .err = scanResult(index + offset, .incomplete) };
+        }
+
+        const digit = hexValue(bytes[index + offset]) orelse return .{ .err = scanResult(index + offset, .invalid) };
+        unit = unit << 4 | digit;
+    }
+
+    return .{ .unit = unit };
+}
+
+fn isHighSurrogate(unit: u16) bool {
+    return unit >= 0xD800 and unit <= 0xDBFF;
+}
+
+fn isLowSurrogate(unit: u16) bool {
+    return unit >= 0xDC00 and unit <= 0xDFFF;
+}
+
+fn scanStringIn(bytes: []const u8, start: usize) u64 {
+    var index = start;
+
+    while (true) {
+        while (index + lanes <= bytes.len) : (index += lanes) {
+            const chunk = chunkAt(bytes, index);
+            const special = mask(chunk == splat('"')) | mask(chunk == splat('\\')) | mask(chunk < splat(0x20));
+
+            if (@reduce(.Max, special) == 1) {
+                break;
+            }
+        }
+
+        if (index >= bytes.len) {
+            return scanResult(index, .incomplete);
+        }
+
+        switch (bytes[index]) {
+            '"' => return scanResult(index, .complete),
+            '\\' => {
+                if (index + 1 >= bytes.len) {
+                    return scanResult(index + 1, .incomplete);
+                }
+
+                switch (bytes[index + 1]) {
+                    '"', '\\', '/', 'b', 'f', 'n', 'r', 't' => index += 2,
+                    'u' => {
+                        const unit = switch (unicodeEscape(bytes, index)) {
+                            .unit => |unit| unit,
+                            .err => |err| return err,
+                        };
+
+                        if (isLowSurrogate(unit)) {
+                            return scanResult(index, .invalid);
+                        }
+
+                        index += 6;
+
+                        // a high surrogate must be followed by a low one
+                        if (isHighSurrogate(unit)) {
+                            if (index + 2 > bytes.len) {
+                                return scanResult(bytes.len, .incomplete);
+                            }
+
+                            if (bytes[index] != '\\' or bytes[index + 1] != 'u') {
+                                return scanResult(index, .invalid);
+                            }
+
+                            const low = switch (unicodeEscape(bytes, index)) {
+                                .unit => |low| low,
+                                .err => |err| return err,
+                            };
+
+                            if (!isLowSurrogate(low)) {
+                                return scanResult(index, .invalid);
+                            }
+
+                            index += 6;
+                        }
+                    },
+                    else => return scanResult(index + 1, .invalid),
+                }
+            },
+            0...0x1f => return scanResult(index, .invalid),
+            else => index += 1,
+        }
+    }
+}
+
+/// Scans the rest of a string whose opening quote is just before `index`. It's
+/// complete at the closing quote.
+pub fn scanString(list: RocList, index: usize) callconv(.C) u64 {
+    return scanStringIn(bytesOf(list), index);
+}
+
+fn scanLiteral(bytes: []const u8, index: usize, literal: []const u8) u64 {
+    for (literal) |byte, offset| {
+        if (index + offset >= bytes.len) {
+            return scanResult(index + offset, .incomplete);
+        }
+
+        if (bytes[index + offset] != byte) {
+            return scanResult(index + offset, .invalid);
+        }
+    }
+
+    return scanResult(index + literal.len, .complete);
+}
+
+fn isDigit(byte: u8) bool {
+    return byte >= '0' and byte <= '9';
+}
+
+fn scanNumber(bytes: []const u8, start: usize) u64 {
+    var index = start;
+
+    if (index < bytes.len and bytes[index] == '-') {
+        index += 1;
+    }
+
+    if (index >= bytes.len) return scanResult(index, .incomplete);
+
+    if (bytes[index] == '0') {
+        index += 1;
+    } else if (isDigit(bytes[index])) {
+        while (index < bytes.len and isDigit(bytes[index])) index += 1;
+    } else {
+        return scanResult(index, .invalid);
+    }
+
+    if (index < bytes.len and bytes[index] == '.') {
+        index += 1;
+
+        if (index >= bytes.len) return scanResult(index, .incomplete);
+        if (!isDigit(bytes[index])) return scanResult(index, .invalid);
+
+        while (index < bytes.len and isDigit(bytes[index])) index += 1;
+    }
+
+    if (index < bytes.len and (bytes[index] == 'e' or bytes[index] == 'E')) {
+        index += 1;
+
+        if (index < bytes.len and (bytes[index] == '+' or bytes[index] == '-')) {
+            index += 1;
+        }
+
+        if (index >= bytes.len) return scanResult(index, .incomplete);
+        if (!isDigit(bytes[index])) return scanResult(index, .invalid);
+
+        while (index < bytes.len and isDigit(bytes[index])) index += 1;
+    }
+
+    // numbers don't say where they end, so the next byte could be more digits
+    if (index >= bytes.len) {
+        return scanResult(index, .at_end);
+    }
+
+    return scanResult(index, .complete);
+}
+
+fn scanValueIn(bytes: []const u8, start: usize) u64 {
+    if (start >= bytes.len) {
+        return scanResult(start, .incomplete);
+    }
+
+    switch (bytes[start]) {
+        '"' => {
+            const result = scanStringIn(bytes, start + 1);
+
+            if (@intToEnum(Status, result & 3) == .complete) {
+                return result + (1 << 2);
+            }
+
+            return result;
+        },
+        '[', '{' => {
+            const open = bytes[start];
+            const close: u8 = if (open == '[') ']' else '}';
+            var index = skipWhitespaceIn(bytes, start + 1);
+
+            if (index < bytes.len and bytes[index] == close) {
+                return scanResult(index + 1, .complete);
+            }
+
+            while (true) {
+                if (open == '{') {
+                    if (index >= bytes.len) return scanResult(index, .incomplete);
+                    if (bytes[index] != '"') return scanResult(index, .invalid);
+
+                    const key = scanValueIn(bytes, index);
+                    if (@intToEnum(Status, key & 3) != .complete) return key;
+
+                    index = skipWhitespaceIn(bytes, @intCast(usize, key >> 2));
+
+                    if (index >= bytes.len) return scanResult(index, .incomplete);
+                    if (bytes[index] != ':') return scanResult(index, .invalid);
+
+                    index = skipWhitespaceIn(bytes, index + 1);
+                }
+
+                const element = scanValueIn(bytes, index);
+                switch (@intToEnum(Status, element & 3)) {
+                    .complete => {},
+                    // the closing bracket is still to come
+                    .at_end => return scanResult(bytes.len, .incomplete),
+                    .incomplete, .invalid => return element,
+                }
+
+                index = skipWhitespaceIn(bytes, @intCast(usize, element >> 2));
+
+                if (index >= bytes.len) return scanResult(index, .incomplete);
+
+                if (bytes[index] == close) {
+                    return scanResult(index + 1, .complete);
+                } else if (bytes[index] == ',') {
+                    index = skipWhitespaceIn(bytes, index + 1);
+                } else {
+                    return scanResult(index, .invalid);
+                }
+            }
+        },
+        't' => return scanLiteral(bytes, start, "true"),
+        'f' => return scanLiteral(bytes, start, "false"),
+        'n' => return scanLiteral(bytes, start, "null"),
+        else => return scanNumber(bytes, start),
+    }
+}
+
+/// Scans a whole JSON value starting at `index`, which isn't whitespace. It's
+/// complete just after the value.
+pub fn scanValue(list: RocList, index: usize) callconv(.C) u64 {
+    return scanValueIn(bytesOf(list), index);
+}
+
+fn utf8Len(code_point: u21) usize {
+    return std.unicode.utf8CodepointSequenceLength(code_point) catch unreachable;
+}
+
+// Decodes a string `scanString` found no problems with into `output`, if there
+// is one, and returns how many bytes it decodes to
+fn unescapeInto(input: []const u8, output: ?[]u8) usize {
+    var in: usize = 0;
+    var out: usize = 0;
+
+    while (in < input.len) {
+        if (input[in] != '\\') {
+            if (output) |bytes| bytes[out] = input[in];
+            in += 1;
+            out += 1;
+            continue;
+        }
+
+        const escaped: u8 = switch (input[in + 1]) {
+            'b' => 0x08,
+            'f' => 0x0c,
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'u' => {
+                var code_point: u21 = unicodeEscape(input, in).unit;
+                in += 6;
+
+                if (isHighSurrogate(@intCast(u16, code_point))) {
+                    const low = unicodeEscape(input, in).unit;
+                    code_point = 0x10000 + ((code_point - 0xD800) << 10) + (low - 0xDC00);
+                    in += 6;
+                }
+
+                if (output) |bytes| {
+                    _ = std.unicode.utf8Encode(code_point, bytes[out..]) catch unreachable;
+                }
+                out += utf8Len(code_point);
+
+                continue;
+            },
+            // `"`, `\`, and `/` stand for themselves
+            else => input[in + 1],
+        };
+
+        if (output) |bytes| bytes[out] = escaped;
+        in += 2;
+        out += 1;
+    }
+
+    return out;
+}
+
+/// The bytes of a string from `start` to `end` with their escapes decoded.
+/// `scanString` must have found no problems with them.
+pub fn unescape(list: RocList, start: usize, end: usize) callconv(.C) RocList {
+    const input = bytesOf(list)[start..end];
+
+    if (std.mem.indexOfScalar(u8, input, '\\') == null) {
+        return RocList.fromSlice(u8, input);
+    }
+
+    const len = unescapeInto(input, null);
+    const output = RocList.allocate(@alignOf(u8), len, @sizeOf(u8));
+
+    if (output.bytes) |ptr| {
+        _ = unescapeInto(input, ptr[0..len]);
+    }
+
+    return output;
+}
+
+const hex_digits = "0123456789abcdef";
+
+fn escapedLen(byte: u8) usize {
+    return switch (byte) {
+        '"', '\\', '\n', '\r', '\t', 0x08, 0x0c => 2,
+        0...0x07, 0x0b, 0x0e...0x1f => 6,
+        else => 1,
+    };
+}
+
+/// Appends the Str to the list as a JSON string, in quotes and with the
+/// characters escaped which must be
+pub fn escape(list: RocList, str: RocStr) callconv(.C) RocList {
+    const input = str.asSlice();
+    const old_len = list.len();
+
+    var len: usize = 2;
+    var index: usize = 0;
+
+    while (index + lanes <= input.len) : (index += lanes) {
+        const chunk = chunkAt(input, index);
+        const special = mask(chunk == splat('"')) | mask(chunk == splat('\\')) | mask(chunk < splat(0x20));
+
+        if (@reduce(.Max, special) == 0) {
+            len += lanes;
+        } else {
+            for (input[index..][0..lanes]) |byte| len += escapedLen(byte);
+        }
+    }
+
+    for (input[index..]) |byte| len += escapedLen(byte);
+
+    const output = list.reallocate(@alignOf(u8), old_len + len, @sizeOf(u8));
+    const bytes = (output.bytes orelse unreachable)[old_len .. old_len + len];
+    var out: usize = 1;
+
+    bytes[0] = '"';
+
+    for (input) |byte| {
+        const short: ?u8 = switch (byte) {
+            '"' => '"',
+            '\\' => '\\',
+            '\n' => 'n',
+            '\r' => 'r',
+            '\t' => 't',
+            0x08 => 'b',
+            0x0c => 'f',
+            else => null,
+        };
+
+        if (short) |letter| {
+            bytes[out] = '\\';
+            bytes[out + 1] = letter;
+            out += 2;
+        } else if (byte < 0x20) {
+            std.mem.copy(u8, bytes[out..], "\\u00");
+            bytes[out + 4] = hex_digits[byte >> 4];
+            bytes[out + 5] = hex_digits[byte & 0xf];
+            out += 6;
+        } else {
+            bytes[out] = byte;
+            out += 1;
+        }
+    }
+
+    bytes[out] = '"';
+
+    return output;
+}
+
+fn expectScan(expected_position: usize, expected_status: Status, result: u64) !void {
+    try std.testing.expectEqual(expected_status, @intToEnum(Status, result & 3));
+    try std.testing.expectEqual(@as(u64, expected_position), result >> 2);
+}
+
+test "skipWhitespace" {
+    try std.testing.expectEqual(@as(usize, 20), skipWhitespaceIn("   \n\t\r              x", 0));
+    try std.testing.expectEqual(@as(usize, 3), skipWhitespaceIn("   ", 1));
+}
+
+test "scanString" {
+    try expectScan(5, .complete, scanStringIn("\"abcd\"", 1));
+    try expectScan(30, .complete, scanStringIn("\"a long string with \\\"quotes\\\"\"", 1));
+    try expectScan(14, .complete, scanStringIn("\"\\ud83d\\udc26\"", 1));
+    try expectScan(3, .incomplete, scanStringIn("\"ab", 1));
+    try expectScan(2, .invalid, scanStringIn("\"a\\x\"", 1));
+    try expectScan(1, .invalid, scanStringIn("\"\\udc26\"", 1));
+    try expectScan(2, .invalid, scanStringIn("\"a\n\"", 1));
+}
+
+test "scanValue" {
+    try expectScan(25, .complete, scanValueIn("{ \"a\" : [1,-2.5e3,true] } ", 0));
+    try expectScan(7, .complete, scanValueIn("[[],{}] ", 0));
+    try expectScan(3, .at_end, scanValueIn("123", 0));
+    try expectScan(2, .incomplete, scanValueIn("1.", 0));
+    try expectScan(3, .incomplete, scanValueIn("[12", 0));
+    try expectScan(3, .complete, scanValueIn("123 ", 0));
+    try expectScan(9, .incomplete, scanValueIn("{\"a\": [1,", 0));
+    try expectScan(6, .invalid, scanValueIn("[1, 2 3]", 0));
+    try expectScan(1, .complete, scanValueIn("01", 0));
+    try expectScan(3, .invalid, scanValueIn("nul!", 0));
+}
+
+test "unescape" {
+    const list = RocList.fromSlice(u8, "\"a\\n\\u00e9\\ud83d\\udc26\"");
+    defer list.deinit(u8);
+
+    const unescaped = unescape(list, 1, list.len() - 1);
+    defer unescaped.deinit(u8);
+
+    try std.testing.expectEqualStrings("a\né🐦", unescaped.bytes.?[0..unescaped.len()]);
+}
+
+test "escape" {
+    const str = RocStr.fromSlice("a \"quoted\" line\n\x01");
+    defer str.deinit();
+
+    const escaped = escape(RocList.fromSlice(u8, "["), str);
+    defer escaped.deinit(u8);
+
+    try std.testing.expectEqualStrings("[\"a \\\"quoted\\\" line\\n\\u0001\"", escaped.bytes.?[0..escaped.len()]);
+}
@@ -235,6 +235,78 @@ pub fn listMap(
     }
 }
 
+/// Calls `task` on chunks of `0..count` which together cover all of it, possibly on several
+/// threads at once, and returns once every chunk is done. The platform can provide this, e.g. by
+/// handing the chunks to its thread pool. It's weak, so platforms (and the REPL, and tests)
+/// without one still link; `List.mapConcurrent` then maps one element at a time.
+const ParallelFor = fn (usize, ParallelTask, ?*anyopaque) callconv(.C) void;
+const roc_parallel_for = @extern(?ParallelFor, .{ .name = "roc_parallel_for", .linkage = .Weak });
+
+const ParallelTask = fn (?*anyopaque, usize, usize) callconv(.C) void;
+
+const MapConcurrentContext = struct {
+    caller: Caller1,
+    data: Opaque,
+    source_ptr: [*]u8,
+    target_ptr: [*]u8,
+    old_element_width: usize,
+    new_element_width: usize,
+};
+
+fn mapConcurrentChunk(context_ptr: ?*anyopaque, start: usize, end: usize) callconv(.C) void {
+    const context = @ptrCast(*MapConcurrentContext, @alignCast(@alignOf(MapConcurrentContext), context_ptr.?));
+
+    var i = start;
+    while (i < end) : (i += 1) {
+        context.caller(context.data, context.source_ptr + (i * context.old_element_width), context.target_ptr + (i * context.new_element_width));
+    }
+}
+
+/// Like `listMap`, but lets the platform spread the calls to `caller` across threads.
+/// The compiler only uses this when the reference counts of everything the calls touch are
+/// updated atomically, and the builtins called meanwhile update theirs atomically too.
+pub fn listMapConcurrent(
+    list: RocList,
+    caller: Caller1,
+    data: Opaque,
+    inc_n_data: IncN,
+    data_is_owned: bool,
+    alignment: u32,
+    old_element_width: usize,
+    new_element_width: usize,
+) callconv(.C) RocList {
+    if (list.bytes) |source_ptr| {
+        const size = list.len();
+        const output = RocList.allocate(alignment, size, new_element_width);
+        const target_ptr = output.bytes orelse unreachable;
+
+        if (data_is_owned) {
+            inc_n_data(data, size);
+        }
+
+        var context = MapConcurrentContext{
+            .caller = caller,
+            .data = data,
+            .source_ptr = source_ptr,
+            .target_ptr = target_ptr,
+            .old_element_width = old_element_width,
+            .new_element_width = new_element_width,
+        };
+
+        if (roc_parallel_for) |parallel_for| {
+            utils.enterConcurrentMap();
+            parallel_for(size, mapConcurrentChunk, @ptrCast(?*anyopaque, &context));
+            utils.exitConcurrentMap();
+        } else {
+            mapConcurrentChunk(@ptrCast(?*anyopaque, &context), 0, size);
+        }
+
+        return output;
+    } else {
+        return RocList.empty();
+    }
+}
+
 fn decrementTail(list: RocList, start_index: usize, element_width: usize, dec: Dec) void {
     if (list.bytes) |source| {
         var i = start_index;
@@ -4,6 +4,7 @@ const math = std.math;
 const utils = @import("utils.zig");
 const expect = @import("expect.zig");
 const panic_utils = @import("panic.zig");
+const allocator = @import("allocator.zig");
 
 const ROC_BUILTINS = "roc_builtins";
 const NUM = "num";
@@ -86,6 +87,13 @@ comptime {
         num.exportMulWithOverflow(T, WIDEINTS[i], ROC_BUILTINS ++ "." ++ NUM ++ ".mul_with_overflow.");
         num.exportMulOrPanic(T, WIDEINTS[i], ROC_BUILTINS ++ "." ++ NUM ++ ".mul_or_panic.");
         num.exportMulSaturatedInt(T, WIDEINTS[i], ROC_BUILTINS ++ "." ++ NUM ++ ".mul_saturated.");
+
+        num.exportPopCount(T, ROC_BUILTINS ++ "." ++ NUM ++ ".pop_count.");
+        num.exportLeadingZeros(T, ROC_BUILTINS ++ "." ++ NUM ++ ".leading_zeros.");
+        num.exportTrailingZeros(T, ROC_BUILTINS ++ "." ++ NUM ++ ".trailing_zeros.");
+        num.exportRotateLeft(T, ROC_BUILTINS ++ "." ++ NUM ++ ".rotate_left.");
+        num.exportRotateRight(T, ROC_BUILTINS ++ "." ++ NUM ++ ".rotate_right.");
+        num.exportSwapBytes(T, ROC_BUILTINS ++ "." ++ NUM ++ ".swap_bytes.");
     }
 
     inline for (INTEGERS) |FROM| {
@@ -111,12 +119,15 @@ comptime {
         num.exportSubWithOverflow(T, ROC_BUILTINS ++ "." ++ NUM ++ ".sub_with_overflow.");
         num.exportMulWithOverflow(T, T, ROC_BUILTINS ++ "." ++ NUM ++ ".mul_with_overflow.");
 
+        num.exportIsNan(T, ROC_BUILTINS ++ "." ++ NUM ++ ".is_nan.");
+        num.exportIsInfinite(T, ROC_BUILTINS ++ "." ++ NUM ++ ".is_infinite.");
         num.exportIsFinite(T, ROC_BUILTINS ++ "." ++ NUM ++ ".is_finite.");
     }
 }
 
 // Str Module
 const str = @import("str.zig");
+const regex = @import("regex.zig");
 comptime {
     exportStrFn(str.init, "init");
     exportStrFn(str.strToScalarsC, "to_scalars");
@@ -147,6 +158,9 @@ comptime {
     exportStrFn(str.strCloneTo, "clone_to");
     exportStrFn(str.withCapacity, "with_capacity");
     exportStrFn(str.strGraphemes, "graphemes");
+    exportStrFn(str.formatNumber, "format_number");
+    exportStrFn(regex.matches, "regex_matches");
+    exportStrFn(regex.capture, "regex_capture");
 
     inline for (INTEGERS) |T| {
         str.exportFromInt(T, ROC_BUILTINS ++ "." ++ STR ++ ".from_int.");
@@ -159,13 +173,61 @@ comptime {
     }
 }
 
+// Random Module
+const random = @import("random.zig");
+comptime {
+    exportRandomFn(random.mix64, "mix64");
+    exportRandomFn(random.mixGamma, "mix_gamma");
+    exportRandomFn(random.testSeed, "test_seed");
+}
+
+// Bytes Module
+const bytes = @import("bytes.zig");
+comptime {
+    exportBytesFn(bytes.readU16, "read_u16");
+    exportBytesFn(bytes.readU32, "read_u32");
+    exportBytesFn(bytes.readU64, "read_u64");
+}
+
+// Encoding Module
+const encoding = @import("encoding.zig");
+comptime {
+    exportEncodingFn(encoding.encode, "encode");
+    exportEncodingFn(encoding.decode, "decode");
+    exportEncodingFn(encoding.check, "check");
+}
+
+// Json Module
+const json = @import("json.zig");
+comptime {
+    exportJsonFn(json.skipWhitespace, "skip_whitespace");
+    exportJsonFn(json.scanString, "scan_string");
+    exportJsonFn(json.scanValue, "scan_value");
+    exportJsonFn(json.unescape, "unescape");
+    exportJsonFn(json.escape, "escape");
+}
+
+// Time Module
+const time = @import("time.zig");
+comptime {
+    exportTimeFn(time.format, "format");
+    exportTimeFn(time.check, "check");
+    exportTimeFn(time.parseSeconds, "parse_seconds");
+    exportTimeFn(time.parseNanos, "parse_nanos");
+}
+
 // Utils
 comptime {
     exportUtilsFn(utils.test_panic, "test_panic");
     exportUtilsFn(utils.increfC, "incref");
     exportUtilsFn(utils.decrefC, "decref");
+    exportUtilsFn(utils.increfAtomicC, "incref_atomic");
+    exportUtilsFn(utils.decrefAtomicC, "decref_atomic");
     exportUtilsFn(utils.decrefCheckNullC, "decref_check_null");
+    exportUtilsFn(utils.decrefCheckNullAtomicC, "decref_check_null_atomic");
     exportUtilsFn(utils.allocateWithRefcountC, "allocate_with_refcount");
+    exportUtilsFn(utils.outOfMemoryC, "out_of_memory");
+    exportUtilsFn(allocator.noopDeallocC, "noop_dealloc");
 
     @export(panic_utils.panic, .{ .name = "roc_builtins.utils." ++ "panic", .linkage = .Weak });
 
@@ -178,7 +240,21 @@ comptime {
         // sets the buffer used for expect failures
         @export(expect.setSharedBuffer, .{ .name = "set_shared_buffer", .linkage = .Weak });
 
+        // sets the seed that `Random.testSeed` returns
+        @export(random.setTestSeed, .{ .name = "set_test_seed", .linkage = .Weak });
+
         exportUtilsFn(expect.readSharedBufferEnv, "read_env_shared_buffer");
+
+        // default allocators, for platforms with `allocator { strategy: roc }` or `mimalloc`
+        exportUtilsFn(allocator.defaultAllocC, "default_alloc");
+        exportUtilsFn(allocator.defaultReallocC, "default_realloc");
+        exportUtilsFn(allocator.defaultDeallocC, "default_dealloc");
+        exportUtilsFn(allocator.mimallocAllocC, "mimalloc_alloc");
+        exportUtilsFn(allocator.mimallocReallocC, "mimalloc_realloc");
+        exportUtilsFn(allocator.mimallocDeallocC, "mimalloc_dealloc");
+
+        // wasm has no threads, and maps one element at a time instead
+        exportListFn(list.listMapConcurrent, "map_concurrent");
     }
 
     if (builtin.target.cpu.arch == .aarch64) {
@@ -226,6 +302,21 @@ fn exportListFn(comptime func: anytype, comptime func_name: []const u8) void {
 fn exportDecFn(comptime func: anytype, comptime func_name: []const u8) void {
     exportBuiltinFn(func, "dec." ++ func_name);
 }
+fn exportRandomFn(comptime func: anytype, comptime func_name: []const u8) void {
+    exportBuiltinFn(func, "random." ++ func_name);
+}
+fn exportEncodingFn(comptime func: anytype, comptime func_name: []const u8) void {
+    exportBuiltinFn(func, "encoding." ++ func_name);
+}
+fn exportJsonFn(comptime func: anytype, comptime func_name: []const u8) void {
+    exportBuiltinFn(func, "json." ++ func_name);
+}
+fn exportTimeFn(comptime func: anytype, comptime func_name: []const u8) void {
+    exportBuiltinFn(func, "time." ++ func_name);
+}
+fn exportBytesFn(comptime func: anytype, comptime func_name: []const u8) void {
+    exportBuiltinFn(func, "bytes." ++ func_name);
+}
 
 fn exportUtilsFn(comptime func: anytype, comptime func_name: []const u8) void {
     exportBuiltinFn(func, "utils." ++ func_name);
@@ -15,6 +15,21 @@ pub fn NumParseResult(comptime T: type) type {
     };
 }
 
+/// Why a Str couldn't be parsed as a fraction. Integers only report that they
+/// couldn't be parsed, with error code 1.
+pub const ParseError = error{ EmptyStr, NoDigits, InvalidChar, OutOfRange, TooManyDecimals };
+
+/// The error codes Str.roc turns back into these errors
+pub fn parseErrorCode(err: ParseError) u8 {
+    return switch (err) {
+        error.EmptyStr => 2,
+        error.NoDigits => 3,
+        error.InvalidChar => 4,
+        error.OutOfRange => 5,
+        error.TooManyDecimals => 6,
+    };
+}
+
 pub const U256 = struct {
     hi: u128,
     lo: u128,
@@ -76,16 +91,48 @@ pub fn exportParseInt(comptime T: type, comptime name: []const u8) void {
 pub fn exportParseFloat(comptime T: type, comptime name: []const u8) void {
     comptime var f = struct {
         fn func(buf: RocStr) callconv(.C) NumParseResult(T) {
-            if (std.fmt.parseFloat(T, buf.asSlice())) |success| {
+            if (parseFloat(T, buf.asSlice())) |success| {
                 return .{ .errorcode = 0, .value = success };
-            } else |_| {
-                return .{ .errorcode = 1, .value = 0 };
+            } else |err| {
+                return .{ .errorcode = parseErrorCode(err), .value = 0 };
             }
         }
     }.func;
     @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
 }
 
+fn parseFloat(comptime T: type, slice: []const u8) ParseError!T {
+    if (slice.len == 0) {
+        return error.EmptyStr;
+    }
+
+    const float = std.fmt.parseFloat(T, slice) catch {
+        for (slice) |byte| {
+            if (std.mem.indexOfScalar(u8, "0123456789+-.eE", byte) == null or std.ascii.isDigit(byte)) {
+                return error.InvalidChar;
+            }
+        }
+
+        // every character could be part of a number, but there are no digits
+        return error.NoDigits;
+    };
+
+    // numbers too big for T parse as infinity, which only "inf" and "infinity" should
+    if (math.isInf(float) and std.mem.indexOfAny(u8, slice, "iI") == null) {
+        return error.OutOfRange;
+    }
+
+    return float;
+}
+
+test "parseFloat errors" {
+    try std.testing.expectError(error.EmptyStr, parseFloat(f64, ""));
+    try std.testing.expectError(error.NoDigits, parseFloat(f64, "-."));
+    try std.testing.expectError(error.InvalidChar, parseFloat(f64, "1,5"));
+    try std.testing.expectError(error.OutOfRange, parseFloat(f32, "1e40"));
+    try std.testing.expect(math.isInf(try parseFloat(f32, "-inf")));
+}
+
 pub fn exportPow(comptime T: type, comptime name: []const u8) void {
     comptime var f = struct {
         fn func(base: T, exp: T) callconv(.C) T {
@@ -95,6 +142,24 @@ pub fn exportPow(comptime T: type, comptime name: []const u8) void {
     @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
 }
 
+pub fn exportIsNan(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(input: T) callconv(.C) bool {
+            return std.math.isNan(input);
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
+pub fn exportIsInfinite(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(input: T) callconv(.C) bool {
+            return std.math.isInf(input);
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
 pub fn exportIsFinite(comptime T: type, comptime name: []const u8) void {
     comptime var f = struct {
         fn func(input: T) callconv(.C) bool {
@@ -460,3 +525,68 @@ pub fn exportMulOrPanic(comptime T: type, comptime W: type, comptime name: []con
     }.func;
     @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
 }
+
+// The bit manipulation functions work on the bits of an integer, regardless of its sign.
+fn Bits(comptime T: type) type {
+    return std.meta.Int(.unsigned, @typeInfo(T).Int.bits);
+}
+
+pub fn exportPopCount(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(self: T) callconv(.C) u8 {
+            return @intCast(u8, @popCount(T, self));
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
+pub fn exportLeadingZeros(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(self: T) callconv(.C) u8 {
+            return @intCast(u8, @clz(T, self));
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
+pub fn exportTrailingZeros(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(self: T) callconv(.C) u8 {
+            return @intCast(u8, @ctz(T, self));
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
+pub fn exportRotateLeft(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(self: T, bits: u8) callconv(.C) T {
+            return @bitCast(T, std.math.rotl(Bits(T), @bitCast(Bits(T), self), bits));
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
+pub fn exportRotateRight(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(self: T, bits: u8) callconv(.C) T {
+            return @bitCast(T, std.math.rotr(Bits(T), @bitCast(Bits(T), self), bits));
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
+pub fn exportSwapBytes(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(self: T) callconv(.C) T {
+            return @byteSwap(T, self);
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
+test "rotate" {
+    try std.testing.expectEqual(@as(u8, 0b0000_0011), std.math.rotl(Bits(i8), @bitCast(u8, @as(i8, -127)), @as(u8, 1)));
+    try std.testing.expectEqual(@as(u16, 0x3412), std.math.rotr(Bits(u16), 0x1234, @as(u8, 8)));
+    try std.testing.expectEqual(@as(u32, 1), std.math.rotl(Bits(u32), 1, @as(u8, 32)));
+}
@@ -2,15 +2,28 @@ const std = @import("std");
 const RocStr = @import("str.zig").RocStr;
 const always_inline = std.builtin.CallOptions.Modifier.always_inline;
 
+// What a Roc program passes to roc_panic: the message, followed by where in the source it
+// crashed. The message comes first, so hosts that only read a RocStr still get it.
+// Lines and columns start at 1, and they're all 0 when the crash has no source region,
+// like the ones in these builtins.
+pub const RocPanic = extern struct {
+    message: RocStr,
+    start_line: u32 = 0,
+    start_column: u32 = 0,
+    end_line: u32 = 0,
+    end_column: u32 = 0,
+};
+
 // Signals to the host that the program has panicked
-extern fn roc_panic(msg: *const RocStr, tag_id: u32) callconv(.C) void;
+extern fn roc_panic(payload: *const RocPanic, tag_id: u32) callconv(.C) void;
 
 pub fn panic_help(msg: []const u8, tag_id: u32) void {
-    var str = RocStr.init(msg.ptr, msg.len);
-    roc_panic(&str, tag_id);
+    var payload = RocPanic{ .message = RocStr.init(msg.ptr, msg.len) };
+    roc_panic(&payload, tag_id);
 }
 
 // must export this explicitly because right now it is not used from zig code
 pub fn panic(msg: *const RocStr, alignment: u32) callconv(.C) void {
-    return @call(.{ .modifier = always_inline }, roc_panic, .{ msg, alignment });
+    var payload = RocPanic{ .message = msg.* };
+    return @call(.{ .modifier = always_inline }, roc_panic, .{ &payload, alignment });
 }
@@ -0,0 +1,62 @@
+const std = @import("std");
+
+// The seed `roc test` picked for this run. It stays 0 outside of `roc test`,
+// so programs that ask for it still behave the same way every time they run.
+var TEST_SEED: u64 = 0;
+
+pub fn setTestSeed(seed: u64) callconv(.C) usize {
+    TEST_SEED = seed;
+
+    // the rust side expects that a pointer is returned
+    return 0;
+}
+
+pub fn testSeed() callconv(.C) u64 {
+    return TEST_SEED;
+}
+
+// These are the mixing functions of SplitMix64, from "Fast Splittable
+// Pseudorandom Number Generators" by Steele, Lea and Flood (OOPSLA 2014).
+// A generator is a `state` and an odd `gamma`; each step adds the gamma to the
+// state, and the output is the mixed state.
+
+pub fn mix64(seed: u64) callconv(.C) u64 {
+    var z = seed;
+    z = (z ^ (z >> 30)) *% 0xbf58476d1ce4e5b9;
+    z = (z ^ (z >> 27)) *% 0x94d049bb133111eb;
+    return z ^ (z >> 31);
+}
+
+// Turns a random number into a gamma for a new generator. A gamma has to be odd,
+// and gammas with too few 01 or 10 bit pairs make the generator's output look
+// less random, so we flip half the bits of those.
+pub fn mixGamma(seed: u64) callconv(.C) u64 {
+    var z = seed;
+    z = (z ^ (z >> 33)) *% 0xff51afd7ed558ccd;
+    z = (z ^ (z >> 33)) *% 0xc4ceb9fe1a85ec53;
+    z = (z ^ (z >> 33)) | 1;
+
+    const transitions = @popCount(u64, z ^ (z >> 1));
+
+    if (transitions < 24) {
+        return z ^ 0xaaaaaaaaaaaaaaaa;
+    } else {
+        return z;
+    }
+}
+
+test "mix64 matches SplitMix64" {
+    // the first outputs of a SplitMix64 generator seeded with 0
+    const gamma: u64 = 0x9e3779b97f4a7c15;
+
+    try std.testing.expectEqual(@as(u64, 0xe220a8397b1dcdaf), mix64(gamma));
+    try std.testing.expectEqual(@as(u64, 0x6e789e6aa1b965f4), mix64(gamma *% 2));
+}
+
+test "mixGamma is odd" {
+    var seed: u64 = 0;
+
+    while (seed < 1000) : (seed += 1) {
+        try std.testing.expect(mixGamma(seed) & 1 == 1);
+    }
+}
@@ -0,0 +1,275 @@
+const std = @import("std");
+const utils = @import("utils.zig");
+const RocList = @import("list.zig").RocList;
+const RocStr = @import("str.zig").RocStr;
+
+// Runs the patterns the compiler compiled for Str.matches and Str.capture (see
+// crates/compiler/can/src/regex.rs for how it compiles them). The compiled
+// patterns are stored 7 bits to a byte, so that they can be string literals,
+// and every number in them takes two bytes.
+
+fn number(bytes: []const u8, index: usize) usize {
+    return @as(usize, bytes[index]) | @as(usize, bytes[index + 1]) << 7;
+}
+
+// A DFA is 'D', the number of byte classes, the number of states, the class of
+// each byte, whether each state accepts, and then the states' transitions.
+// State 0 is dead and state 1 is the start.
+const Dfa = struct {
+    class_count: usize,
+    classes: []const u8,
+    accepting: []const u8,
+    transitions: []const u8,
+
+    fn init(compiled: []const u8) Dfa {
+        std.debug.assert(compiled[0] == 'D');
+
+        const state_count = number(compiled, 3);
+        const accepting_start = 5 + 2 * 256;
+        const transitions_start = accepting_start + state_count;
+
+        return Dfa{
+            .class_count = number(compiled, 1),
+            .classes = compiled[5..accepting_start],
+            .accepting = compiled[accepting_start..transitions_start],
+            .transitions = compiled[transitions_start..],
+        };
+    }
+};
+
+pub fn matches(string: RocStr, pattern: RocStr) callconv(.C) bool {
+    const dfa = Dfa.init(pattern.asSlice());
+    var state: usize = 1;
+
+    for (string.asSlice()) |byte| {
+        const class = number(dfa.classes, 2 * @as(usize, byte));
+        state = number(dfa.transitions, 2 * (state * dfa.class_count + class));
+
+        if (state == 0) {
+            return false;
+        }
+    }
+
+    return dfa.accepting[state] == 1;
+}
+
+const Op = enum(u8) {
+    range = 0,
+    split = 1,
+    jump = 2,
+    save = 3,
+    match = 4,
+};
+
+const Inst = struct {
+    op: Op,
+    a: usize,
+    b: usize,
+};
+
+// A program is 'P', the number of instructions, the number of slots, and then
+// each instruction's op and its two operands.
+const Program = struct {
+    compiled: []const u8,
+    inst_count: usize,
+    slot_count: usize,
+
+    fn init(compiled: []const u8) Program {
+        std.debug.assert(compiled[0] == 'P');
+
+        return Program{
+            .compiled = compiled,
+            .inst_count = number(compiled, 1),
+            .slot_count = number(compiled, 3),
+        };
+    }
+
+    fn inst(self: Program, pc: usize) Inst {
+        const start = 5 + 5 * pc;
+
+        return Inst{
+            .op = @intToEnum(Op, self.compiled[start]),
+            .a = number(self.compiled, start + 1),
+            .b = number(self.compiled, start + 3),
+        };
+    }
+};
+
+// The threads at one position in the string, in order of priority, each with
+// the slots it has saved
+const Threads = struct {
+    len: usize,
+    pcs: []usize,
+    slots: []usize,
+};
+
+const unset = std.math.maxInt(usize);
+
+const PikeVm = struct {
+    program: Program,
+    // the generation each instruction was last added to a list in, so that no
+    // instruction is added twice at the same position
+    added: []usize,
+    generation: usize,
+
+    fn addThread(self: *PikeVm, threads: *Threads, pc: usize, slots: []usize, position: usize) void {
+        if (self.added[pc] == self.generation) {
+            return;
+        }
+
+        self.added[pc] = self.generation;
+
+        const inst = self.program.inst(pc);
+
+        switch (inst.op) {
+            .jump => self.addThread(threads, inst.a, slots, position),
+            .split => {
+                self.addThread(threads, inst.a, slots, position);
+                self.addThread(threads, inst.b, slots, position);
+            },
+            .save => {
+                const old = slots[inst.a];
+                slots[inst.a] = position;
+                self.addThread(threads, pc + 1, slots, position);
+                slots[inst.a] = old;
+            },
+            .range, .match => {
+                const slot_count = self.program.slot_count;
+
+                threads.pcs[threads.len] = pc;
+                std.mem.copy(usize, threads.slots[threads.len * slot_count ..][0..slot_count], slots);
+                threads.len += 1;
+            },
+        }
+    }
+};
+
+// Returns where each group started and ended, starting with the whole match, or
+// an empty list if the string doesn't match. A group which didn't take part in
+// the match starts and ends at 0.
+pub fn capture(string: RocStr, pattern: RocStr) callconv(.C) RocList {
+    const program = Program.init(pattern.asSlice());
+    const input = string.asSlice();
+    const n = program.inst_count;
+    const slot_count = program.slot_count;
+
+    // two lists of threads, the generations, and the slots being added
+    const words = 2 * (n + n * slot_count) + n + slot_count;
+    const alignment = @alignOf(usize);
+    const bytes = utils.alloc(words * @sizeOf(usize), alignment) orelse utils.outOfMemory(words * @sizeOf(usize), alignment);
+    defer utils.dealloc(bytes, alignment);
+
+    const memory = @ptrCast([*]usize, @alignCast(alignment, bytes))[0..words];
+
+    var current = Threads{ .len = 0, .pcs = memory[0..n], .slots = memory[n .. n + n * slot_count] };
+    var next = Threads{ .len = 0, .pcs = memory[n + n * slot_count .. 2 * n + n * slot_count], .slots = memory[2 * n + n * slot_count .. 2 * (n + n * slot_count)] };
+    const added = memory[2 * (n + n * slot_count) .. 2 * (n + n * slot_count) + n];
+    const initial_slots = memory[words - slot_count ..];
+
+    std.mem.set(usize, added, unset);
+    std.mem.set(usize, initial_slots, unset);
+
+    var vm = PikeVm{ .program = program, .added = added, .generation = 0 };
+    vm.addThread(&current, 0, initial_slots, 0);
+
+    var position: usize = 0;
+
+    while (current.len > 0) : (position += 1) {
+        vm.generation = position + 1;
+        next.len = 0;
+
+        var index: usize = 0;
+        while (index < current.len) : (index += 1) {
+            const pc = current.pcs[index];
+            const slots = current.slots[index * slot_count ..][0..slot_count];
+            const inst = program.inst(pc);
+
+            switch (inst.op) {
+                .match => {
+                    if (position == input.len) {
+                        // the threads after this one have lower priority
+                        return capturedGroups(slots);
+                    }
+                },
+                .range => {
+                    if (position < input.len and inst.a <= input[position] and input[position] <= inst.b) {
+                        vm.addThread(&next, pc + 1, slots, position + 1);
+                    }
+                },
+                else => unreachable,
+            }
+        }
+
+        if (position == input.len) {
+            break;
+        }
+
+        std.mem.swap(Threads, &current, &next);
+    }
+
+    return RocList.empty();
+}
+
+fn capturedGroups(slots: []const usize) RocList {
+    const list = RocList.allocate(@alignOf(usize), slots.len, @sizeOf(usize));
+    const elements = list.elements(usize) orelse unreachable;
+
+    var group: usize = 0;
+    while (group < slots.len) : (group += 2) {
+        const start = slots[group];
+        const end = slots[group + 1];
+
+        if (start == unset or end == unset) {
+            elements[group] = 0;
+            elements[group + 1] = 0;
+        } else {
+            elements[group] = start;
+            elements[group + 1] = end;
+        }
+    }
+
+    return list;
+}
+
+// Compiled by the compiler from "[a-c]+". Bytes before 'a' are class 0, 'a'
+// to 'c' are class 1, and the rest are class 2. State 2 is after a letter.
+const dfa_a_to_c = [_]u8{ 'D', 3, 0, 3, 0 } ++
+    [_]u8{ 0, 0 } ** 97 ++ [_]u8{ 1, 0 } ** 3 ++ [_]u8{ 2, 0 } ** 156 ++
+    [_]u8{ 0, 0, 1 } ++
+    [_]u8{ 0, 0, 0, 0, 0, 0 } ++ [_]u8{ 0, 0, 2, 0, 0, 0 } ++ [_]u8{ 0, 0, 2, 0, 0, 0 };
+
+test "matches" {
+    const dfa = RocStr.fromSlice(&dfa_a_to_c);
+    defer dfa.deinit();
+
+    try std.testing.expect(matches(RocStr.fromSlice("abcba"), dfa));
+    try std.testing.expect(!matches(RocStr.fromSlice(""), dfa));
+    try std.testing.expect(!matches(RocStr.fromSlice("abd"), dfa));
+}
+
+test "capture" {
+    // Compiled by the compiler from "(a)|(b)"
+    const program = [_]u8{ 'P', 11, 0, 6, 0 } ++
+        [_]u8{ 3, 0, 0, 0, 0 } ++ // save 0
+        [_]u8{ 1, 2, 0, 6, 0 } ++ // split 2 6
+        [_]u8{ 3, 2, 0, 0, 0 } ++ // save 2
+        [_]u8{ 0, 'a', 0, 'a', 0 } ++ // range a a
+        [_]u8{ 3, 3, 0, 0, 0 } ++ // save 3
+        [_]u8{ 2, 9, 0, 0, 0 } ++ // jump 9
+        [_]u8{ 3, 4, 0, 0, 0 } ++ // save 4
+        [_]u8{ 0, 'b', 0, 'b', 0 } ++ // range b b
+        [_]u8{ 3, 5, 0, 0, 0 } ++ // save 5
+        [_]u8{ 3, 1, 0, 0, 0 } ++ // save 1
+        [_]u8{ 4, 0, 0, 0, 0 }; // match
+
+    const pattern = RocStr.fromSlice(&program);
+    defer pattern.deinit();
+
+    const groups = capture(RocStr.fromSlice("b"), pattern);
+    defer groups.deinit(usize);
+
+    try std.testing.expectEqualSlices(usize, &[_]usize{ 0, 1, 0, 0, 0, 1 }, groups.elements(usize).?[0..6]);
+
+    const no_match = capture(RocStr.fromSlice("ab"), pattern);
+    try std.testing.expectEqual(@as(usize, 0), no_match.len());
+}
@@ -788,6 +788,262 @@ fn strFromFloatHelp(comptime T: type, float: T) RocStr {
     return RocStr.init(&buf, result.len);
 }
 
+// Num.toStrWith
+//
+// Formats the digits Num.toStr gave for a number. Working from those digits
+// means every kind of number is rounded the same way: half away from zero,
+// going by the digits Num.toStr shows.
+pub fn formatNumber(
+    digits: RocStr,
+    whole_separator: RocStr,
+    shortest: bool,
+    decimals: u8,
+    scientific: bool,
+) callconv(.C) RocStr {
+    // NaN and the infinities have no digits to format
+    var number = DecimalNumber.parse(digits.asSlice()) orelse return RocStr.init(digits.asU8ptr(), digits.len());
+
+    if (scientific) {
+        if (shortest) {
+            number.trimTrailingZeros();
+        } else {
+            number.roundTo(1 + @as(isize, decimals));
+        }
+    } else if (!shortest) {
+        number.roundTo(number.point + @as(isize, decimals));
+        number.decimals = decimals;
+    }
+
+    const options = FormatOptions{
+        .whole_separator = whole_separator.asSlice(),
+        .shortest = shortest,
+        .decimals = decimals,
+        .scientific = scientific,
+    };
+
+    var counter = FormatOutput{};
+    number.write(options, &counter);
+
+    var result = RocStr.allocate(counter.len);
+    var output = FormatOutput{ .bytes = result.asU8ptrMut() };
+    number.write(options, &output);
+
+    return result;
+}
+
+const FormatOptions = struct {
+    whole_separator: []const u8,
+    shortest: bool,
+    decimals: u8,
+    scientific: bool,
+};
+
+/// Counts the bytes written to it, and writes them too once it has somewhere to
+const FormatOutput = struct {
+    bytes: ?[*]u8 = null,
+    len: usize = 0,
+
+    fn push(self: *FormatOutput, slice: []const u8) void {
+        if (self.bytes) |bytes| {
+            @memcpy(bytes + self.len, slice.ptr, slice.len);
+        }
+
+        self.len += slice.len;
+    }
+
+    fn pushDigit(self: *FormatOutput, digit: u8) void {
+        const byte = [1]u8{'0' + digit};
+        self.push(&byte);
+    }
+};
+
+/// A number written in decimal, as `0.d0d1d2...` times 10 to the power of `point`
+const DecimalNumber = struct {
+    // enough for every digit of an F64 or a Dec, including the zeros
+    const max_digits = 512;
+
+    negative: bool,
+    // without leading zeros, so a number with no digits is zero
+    digits: [max_digits]u8,
+    len: usize,
+    point: isize,
+    // how many digits the number had after its decimal point
+    decimals: usize,
+
+    fn parse(slice: []const u8) ?DecimalNumber {
+        var number = DecimalNumber{
+            .negative = slice.len > 0 and slice[0] == '-',
+            .digits = undefined,
+            .len = 0,
+            .point = 0,
+            .decimals = 0,
+        };
+
+        var seen_point = false;
+        var seen_digit = false;
+
+        for (slice[@boolToInt(number.negative)..]) |byte| {
+            if (byte == '.' and !seen_point) {
+                seen_point = true;
+                continue;
+            }
+
+            if (!isDigit(byte) or number.len == max_digits) {
+                return null;
+            }
+
+            seen_digit = true;
+
+            if (seen_point) {
+                number.decimals += 1;
+            }
+
+            if (number.len == 0 and byte == '0') {
+                // leading zeros aren't digits we keep, but the ones after the point move it
+                if (seen_point) {
+                    number.point -= 1;
+                }
+            } else {
+                number.digits[number.len] = byte - '0';
+                number.len += 1;
+
+                if (!seen_point) {
+                    number.point += 1;
+                }
+            }
+        }
+
+        return if (seen_digit) number else null;
+    }
+
+    /// The digit at this position, counting from the first digit we kept
+    fn digitAt(self: *const DecimalNumber, position: isize) u8 {
+        if (position < 0 or position >= @intCast(isize, self.len)) {
+            return 0;
+        } else {
+            return self.digits[@intCast(usize, position)];
+        }
+    }
+
+    fn trimTrailingZeros(self: *DecimalNumber) void {
+        while (self.len > 0 and self.digits[self.len - 1] == 0) {
+            self.len -= 1;
+        }
+    }
+
+    /// Keeps this many digits, rounding half away from zero
+    fn roundTo(self: *DecimalNumber, keep: isize) void {
+        if (keep < 0) {
+            self.len = 0;
+            return;
+        }
+
+        const cut = @intCast(usize, keep);
+
+        if (cut >= self.len) {
+            return;
+        }
+
+        const round_up = self.digits[cut] >= 5;
+        self.len = cut;
+
+        if (!round_up) {
+            return;
+        }
+
+        var index = cut;
+        while (index > 0) {
+            index -= 1;
+
+            if (self.digits[index] < 9) {
+                self.digits[index] += 1;
+                return;
+            }
+
+            self.digits[index] = 0;
+        }
+
+        // every digit was a 9, so this carries into a new first digit
+        self.digits[0] = 1;
+        self.len = std.math.max(self.len, 1);
+        self.point += 1;
+    }
+
+    fn write(self: *const DecimalNumber, options: FormatOptions, output: *FormatOutput) void {
+        if (self.negative and self.len > 0) {
+            output.push("-");
+        }
+
+        if (options.scientific) {
+            output.pushDigit(self.digitAt(0));
+
+            const decimals = if (options.shortest) std.math.max(self.len, 1) - 1 else options.decimals;
+            self.writeDecimals(1, decimals, output);
+
+            var exponent_buf: [24]u8 = undefined;
+            const exponent = if (self.len == 0) 0 else self.point - 1;
+            output.push(std.fmt.bufPrint(&exponent_buf, "e{d}", .{exponent}) catch unreachable);
+        } else {
+            if (self.point <= 0) {
+                output.push("0");
+            } else {
+                var position: isize = 0;
+                while (position < self.point) : (position += 1) {
+                    if (position > 0 and @mod(self.point - position, 3) == 0) {
+                        output.push(options.whole_separator);
+                    }
+
+                    output.pushDigit(self.digitAt(position));
+                }
+            }
+
+            self.writeDecimals(self.point, self.decimals, output);
+        }
+    }
+
+    fn writeDecimals(self: *const DecimalNumber, start: isize, count: usize, output: *FormatOutput) void {
+        if (count > 0) {
+            output.push(".");
+
+            var position = start;
+            while (position < start + @intCast(isize, count)) : (position += 1) {
+                output.pushDigit(self.digitAt(position));
+            }
+        }
+    }
+};
+
+fn isDigit(byte: u8) bool {
+    return byte >= '0' and byte <= '9';
+}
+
+fn expectFormatted(expected: []const u8, digits: []const u8, whole_separator: []const u8, shortest: bool, decimals: u8, scientific: bool) !void {
+    const formatted = formatNumber(RocStr.fromSlice(digits), RocStr.fromSlice(whole_separator), shortest, decimals, scientific);
+    defer formatted.deinit();
+
+    try testing.expectEqualStrings(expected, formatted.asSlice());
+}
+
+test "formatNumber: fixed" {
+    try expectFormatted("1,234,567.5", "1234567.5", ",", true, 0, false);
+    try expectFormatted("1 234.57", "1234.5678", " ", false, 2, false);
+    try expectFormatted("-0.010", "-0.0095", "", false, 3, false);
+    try expectFormatted("0", "-0.4", "", false, 0, false);
+    try expectFormatted("1000", "999.5", "", false, 0, false);
+    try expectFormatted("42.00", "42", "", false, 2, false);
+}
+
+test "formatNumber: scientific" {
+    try expectFormatted("1.2345e3", "1234.5", "", true, 0, true);
+    try expectFormatted("1.0e-3", "0.000996", "", false, 1, true);
+    try expectFormatted("-4e0", "-4.0", "", true, 0, true);
+    try expectFormatted("0.00e0", "0.0", "", false, 2, true);
+}
+
+test "formatNumber: not a number" {
+    try expectFormatted("-inf", "-inf", ",", false, 2, false);
+}
+
 // Str.split
 pub fn strSplit(string: RocStr, delimiter: RocStr) callconv(.C) RocList {
     const segment_count = countSegments(string, delimiter);
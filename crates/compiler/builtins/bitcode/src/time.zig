@@ -0,0 +1,248 @@
+const std = @import("std");
+const RocStr = @import("str.zig").RocStr;
+
+const seconds_per_day = 86_400;
+
+// The civil date `days` days after 1970-01-01, in the proleptic Gregorian
+// calendar. This is Howard Hinnant's days_from_civil algorithm run backwards:
+// http://howardhinnant.github.io/date_algorithms.html
+const Date = struct {
+    year: i64,
+    month: u8,
+    day: u8,
+
+    fn fromDays(days: i64) Date {
+        // count from 0000-03-01, so that the leap day is at the end of the year
+        const z = days + 719_468;
+        const era = @divFloor(z, 146_097);
+        const day_of_era = z - era * 146_097;
+        const year_of_era = @divFloor(day_of_era - @divFloor(day_of_era, 1_460) + @divFloor(day_of_era, 36_524) - @divFloor(day_of_era, 146_096), 365);
+        const day_of_year = day_of_era - (365 * year_of_era + @divFloor(year_of_era, 4) - @divFloor(year_of_era, 100));
+        const shifted_month = @divFloor(5 * day_of_year + 2, 153);
+        const day = day_of_year - @divFloor(153 * shifted_month + 2, 5) + 1;
+        const month = if (shifted_month < 10) shifted_month + 3 else shifted_month - 9;
+
+        return Date{
+            .year = year_of_era + era * 400 + @boolToInt(month <= 2),
+            .month = @intCast(u8, month),
+            .day = @intCast(u8, day),
+        };
+    }
+
+    fn toDays(self: Date) i64 {
+        const year = if (self.month <= 2) self.year - 1 else self.year;
+        const era = @divFloor(year, 400);
+        const year_of_era = year - era * 400;
+        const shifted_month: i64 = if (self.month > 2) self.month - 3 else self.month + 9;
+        const day_of_year = @divFloor(153 * shifted_month + 2, 5) + self.day - 1;
+        const day_of_era = year_of_era * 365 + @divFloor(year_of_era, 4) - @divFloor(year_of_era, 100) + day_of_year;
+
+        return era * 146_097 + day_of_era - 719_468;
+    }
+};
+
+fn isLeapYear(year: i64) bool {
+    return @mod(year, 4) == 0 and (@mod(year, 100) != 0 or @mod(year, 400) == 0);
+}
+
+fn daysInMonth(year: i64, month: u8) u8 {
+    return switch (month) {
+        2 => if (isLeapYear(year)) @as(u8, 29) else 28,
+        4, 6, 9, 11 => 30,
+        else => 31,
+    };
+}
+
+/// Formats the second `seconds` after the epoch, plus `nanos` nanoseconds, as
+/// ISO 8601 in UTC, e.g. 2023-04-01T12:30:00.250Z
+pub fn format(seconds: i64, nanos: u32) callconv(.C) RocStr {
+    const date = Date.fromDays(@divFloor(seconds, seconds_per_day));
+    const second_of_day = @intCast(u32, @mod(seconds, seconds_per_day));
+
+    // a sign, up to 19 digits of year, and "-MM-DDTHH:MM:SS.nnnnnnnnnZ"
+    var buffer: [50]u8 = undefined;
+    var len: usize = 0;
+
+    if (date.year < 0 or date.year > 9999) {
+        buffer[0] = if (date.year < 0) '-' else '+';
+        len += 1;
+    }
+
+    const year = std.math.absCast(date.year);
+    len += std.fmt.formatIntBuf(buffer[len..], year, 10, .lower, .{ .width = 4, .fill = '0' });
+
+    len += (std.fmt.bufPrint(buffer[len..], "-{d:0>2}-{d:0>2}T{d:0>2}:{d:0>2}:{d:0>2}", .{
+        date.month,
+        date.day,
+        second_of_day / 3600,
+        second_of_day / 60 % 60,
+        second_of_day % 60,
+    }) catch unreachable).len;
+
+    if (nanos != 0) {
+        // whichever of milliseconds, microseconds, and nanoseconds is shortest
+        const digits: u8 = if (nanos % 1_000_000 == 0) 3 else if (nanos % 1_000 == 0) 6 else 9;
+        const fraction = nanos / std.math.pow(u32, 10, 9 - digits);
+
+        buffer[len] = '.';
+        len += 1;
+        len += std.fmt.formatIntBuf(buffer[len..], fraction, 10, .lower, .{ .width = digits, .fill = '0' });
+    }
+
+    buffer[len] = 'Z';
+    len += 1;
+
+    return RocStr.init(&buffer, len);
+}
+
+const Parsed = struct {
+    seconds: i64,
+    nanos: u32,
+};
+
+const ParseResult = union(enum) {
+    ok: Parsed,
+    // the index of the first byte where it stops being valid
+    err: usize,
+};
+
+const Parser = struct {
+    bytes: []const u8,
+    index: usize = 0,
+
+    fn digits(self: *Parser, count: usize) ?u32 {
+        if (self.index + count > self.bytes.len) {
+            return null;
+        }
+
+        var value: u32 = 0;
+        for (self.bytes[self.index .. self.index + count]) |byte| {
+            if (byte < '0' or byte > '9') {
+                return null;
+            }
+
+            value = value * 10 + (byte - '0');
+        }
+
+        self.index += count;
+        return value;
+    }
+
+    fn eat(self: *Parser, byte: u8) bool {
+        if (self.index < self.bytes.len and self.bytes[self.index] == byte) {
+            self.index += 1;
+            return true;
+        }
+
+        return false;
+    }
+
+    // A field of `count` digits which must be between `min` and `max`
+    fn field(self: *Parser, count: usize, min: u32, max: u32) ?u32 {
+        const start = self.index;
+        const value = self.digits(count) orelse return null;
+
+        if (value < min or value > max) {
+            self.index = start;
+            return null;
+        }
+
+        return value;
+    }
+};
+
+fn parse(bytes: []const u8) ParseResult {
+    var p = Parser{ .bytes = bytes };
+
+    const year = p.digits(4) orelse return .{ .err = p.index };
+    if (!p.eat('-')) return .{ .err = p.index };
+    const month = p.field(2, 1, 12) orelse return .{ .err = p.index };
+    if (!p.eat('-')) return .{ .err = p.index };
+    const day = p.field(2, 1, daysInMonth(year, @intCast(u8, month))) orelse return .{ .err = p.index };
+    if (!p.eat('T') and !p.eat('t') and !p.eat(' ')) return .{ .err = p.index };
+    const hour = p.field(2, 0, 23) orelse return .{ .err = p.index };
+    if (!p.eat(':')) return .{ .err = p.index };
+    const minute = p.field(2, 0, 59) orelse return .{ .err = p.index };
+    if (!p.eat(':')) return .{ .err = p.index };
+    // 60 is a leap second, which counts as the next second like in Unix time
+    const second = p.field(2, 0, 60) orelse return .{ .err = p.index };
+
+    var nanos: u32 = 0;
+    if (p.eat('.') or p.eat(',')) {
+        const start = p.index;
+
+        // digits past nanoseconds are dropped
+        while (p.index < bytes.len and bytes[p.index] >= '0' and bytes[p.index] <= '9') : (p.index += 1) {
+            if (p.index - start < 9) {
+                nanos += (bytes[p.index] - '0') * std.math.pow(u32, 10, @intCast(u32, 8 - (p.index - start)));
+            }
+        }
+
+        if (p.index == start) return .{ .err = p.index };
+    }
+
+    var offset_minutes: i64 = 0;
+    if (!p.eat('Z') and !p.eat('z')) {
+        const sign: i64 = if (p.eat('+')) 1 else if (p.eat('-')) -1 else return .{ .err = p.index };
+        const offset_hours = p.field(2, 0, 23) orelse return .{ .err = p.index };
+        if (!p.eat(':')) return .{ .err = p.index };
+        const offset_mins = p.field(2, 0, 59) orelse return .{ .err = p.index };
+
+        offset_minutes = sign * (@as(i64, offset_hours) * 60 + offset_mins);
+    }
+
+    if (p.index != bytes.len) return .{ .err = p.index };
+
+    const days = (Date{ .year = year, .month = @intCast(u8, month), .day = @intCast(u8, day) }).toDays();
+    const seconds = days * seconds_per_day + @as(i64, hour) * 3600 + @as(i64, minute) * 60 + second - offset_minutes * 60;
+
+    return .{ .ok = .{ .seconds = seconds, .nanos = nanos } };
+}
+
+/// The index of the first byte in the Str where it stops being an ISO 8601 date
+/// and time, or the Str's length if all of it is one.
+pub fn check(str: RocStr) callconv(.C) usize {
+    return switch (parse(str.asSlice())) {
+        .ok => str.len(),
+        .err => |index| index,
+    };
+}
+
+/// The seconds since the epoch of a Str which `check` found no problems with
+pub fn parseSeconds(str: RocStr) callconv(.C) i64 {
+    return parse(str.asSlice()).ok.seconds;
+}
+
+/// The nanoseconds after the second of a Str which `check` found no problems with
+pub fn parseNanos(str: RocStr) callconv(.C) u32 {
+    return parse(str.asSlice()).ok.nanos;
+}
+
+fn expectFormatted(expected: []const u8, seconds: i64, nanos: u32) !void {
+    const formatted = format(seconds, nanos);
+    defer formatted.deinit();
+
+    try std.testing.expectEqualStrings(expected, formatted.asSlice());
+
+    const parsed = parse(formatted.asSlice());
+    try std.testing.expectEqual(ParseResult{ .ok = .{ .seconds = seconds, .nanos = nanos } }, parsed);
+}
+
+test "format" {
+    try expectFormatted("1970-01-01T00:00:00Z", 0, 0);
+    try expectFormatted("1969-12-31T23:59:59.999999999Z", -1, 999_999_999);
+    try expectFormatted("2000-02-29T12:34:56.250Z", 951_827_696, 250_000_000);
+    try expectFormatted("2038-01-19T03:14:08.000001Z", 2_147_483_648, 1_000);
+
+    const far_future = format(253_402_300_800, 0);
+    defer far_future.deinit();
+    try std.testing.expectEqualStrings("+10000-01-01T00:00:00Z", far_future.asSlice());
+}
+
+test "parse" {
+    try std.testing.expectEqual(ParseResult{ .ok = .{ .seconds = 946_684_800, .nanos = 0 } }, parse("2000-01-01T05:30:00+05:30"));
+    try std.testing.expectEqual(ParseResult{ .ok = .{ .seconds = 0, .nanos = 123_000_000 } }, parse("1970-01-01 00:00:00.123z"));
+    try std.testing.expectEqual(ParseResult{ .err = 8 }, parse("2023-02-29T00:00:00Z"));
+    try std.testing.expectEqual(ParseResult{ .err = 19 }, parse("2023-02-28T00:00:00"));
+    try std.testing.expectEqual(ParseResult{ .err = 4 }, parse("20230228"));
+}
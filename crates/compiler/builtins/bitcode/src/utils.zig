@@ -1,21 +1,29 @@
 const std = @import("std");
+const panic_utils = @import("panic.zig");
 const always_inline = std.builtin.CallOptions.Modifier.always_inline;
 const Monotonic = std.builtin.AtomicOrder.Monotonic;
+const SeqCst = std.builtin.AtomicOrder.SeqCst;
 
 pub fn WithOverflow(comptime T: type) type {
     return extern struct { value: T, has_overflowed: bool };
 }
 
-// If allocation fails, this must cxa_throw - it must not return a null pointer!
+// If allocation fails, this returns a null pointer, and we call roc_oom.
 extern fn roc_alloc(size: usize, alignment: u32) callconv(.C) ?*anyopaque;
 
 // This should never be passed a null pointer.
-// If allocation fails, this must cxa_throw - it must not return a null pointer!
+// If allocation fails, this returns a null pointer, and we call roc_oom.
 extern fn roc_realloc(c_ptr: *anyopaque, new_size: usize, old_size: usize, alignment: u32) callconv(.C) ?*anyopaque;
 
 // This should never be passed a null pointer.
 extern fn roc_dealloc(c_ptr: *anyopaque, alignment: u32) callconv(.C) void;
 
+// Called when roc_alloc or roc_realloc could not allocate `size` bytes.
+// The host can report the failure (and flush its logs, say), but this must not return!
+// It's weak, so hosts don't have to define it; then running out of memory calls roc_panic.
+const OutOfMemoryHandler = fn (usize, u32) callconv(.C) void;
+const roc_oom = @extern(?OutOfMemoryHandler, .{ .name = "roc_oom", .linkage = .Weak });
+
 // should work just like libc memcpy (we can't assume libc is present)
 extern fn roc_memcpy(dst: [*]u8, src: [*]u8, size: usize) callconv(.C) void;
 
@@ -94,13 +102,30 @@ pub fn alloc(size: usize, alignment: u32) ?[*]u8 {
 }
 
 pub fn realloc(c_ptr: [*]u8, new_size: usize, old_size: usize, alignment: u32) [*]u8 {
-    return @ptrCast([*]u8, roc_realloc(c_ptr, new_size, old_size, alignment));
+    const new_ptr = roc_realloc(c_ptr, new_size, old_size, alignment) orelse outOfMemory(new_size, alignment);
+
+    return @ptrCast([*]u8, new_ptr);
 }
 
 pub fn dealloc(c_ptr: [*]u8, alignment: u32) void {
     return roc_dealloc(c_ptr, alignment);
 }
 
+pub fn outOfMemory(size: usize, alignment: u32) noreturn {
+    if (roc_oom) |handler| {
+        handler(size, alignment);
+    } else {
+        panic_utils.panic_help("Ran out of memory", 0);
+    }
+
+    // neither roc_oom nor roc_panic may return
+    unreachable;
+}
+
+pub fn outOfMemoryC(size: usize, alignment: u32) callconv(.C) void {
+    outOfMemory(size, alignment);
+}
+
 pub fn memcpy(dst: [*]u8, src: [*]u8, size: usize) void {
     roc_memcpy(dst, src, size);
 }
@@ -147,13 +172,39 @@ const Refcount = enum {
 
 const RC_TYPE = Refcount.normal;
 
+// How many `List.mapConcurrent` calls are running right now. The builtins don't know the layouts
+// of the values they free, so while one is running they can't tell whether a value is shared
+// between threads, and update reference counts atomically to be safe.
+var running_concurrent_maps: usize = 0;
+
+pub fn enterConcurrentMap() void {
+    _ = @atomicRmw(usize, &running_concurrent_maps, std.builtin.AtomicRmwOp.Add, 1, SeqCst);
+}
+
+pub fn exitConcurrentMap() void {
+    _ = @atomicRmw(usize, &running_concurrent_maps, std.builtin.AtomicRmwOp.Sub, 1, SeqCst);
+}
+
+fn isInConcurrentMap() bool {
+    return @atomicLoad(usize, &running_concurrent_maps, SeqCst) != 0;
+}
+
 pub fn increfC(ptr_to_refcount: *isize, amount: isize) callconv(.C) void {
-    if (RC_TYPE == Refcount.none) return;
+    return @call(.{ .modifier = always_inline }, incref_ptr_to_refcount, .{ ptr_to_refcount, amount, RC_TYPE });
+}
+
+/// Like `increfC`, for values which may be shared between threads
+pub fn increfAtomicC(ptr_to_refcount: *isize, amount: isize) callconv(.C) void {
+    return @call(.{ .modifier = always_inline }, incref_ptr_to_refcount, .{ ptr_to_refcount, amount, Refcount.atomic });
+}
+
+inline fn incref_ptr_to_refcount(ptr_to_refcount: *isize, amount: isize, comptime rc_type: Refcount) void {
+    if (rc_type == Refcount.none) return;
     // Ensure that the refcount is not whole program lifetime.
     if (ptr_to_refcount.* != REFCOUNT_MAX_ISIZE) {
         // Note: we assume that a refcount will never overflow.
         // As such, we do not need to cap incrementing.
-        switch (RC_TYPE) {
+        switch (rc_type) {
             Refcount.normal => {
                 ptr_to_refcount.* += amount;
             },
@@ -175,7 +226,17 @@ pub fn decrefC(
     // this is of course unsafe, but we trust what we get from the llvm side
     var bytes = @ptrCast([*]isize, bytes_or_null);
 
-    return @call(.{ .modifier = always_inline }, decref_ptr_to_refcount, .{ bytes, alignment });
+    return @call(.{ .modifier = always_inline }, decref_ptr_to_refcount, .{ bytes, alignment, RC_TYPE });
+}
+
+/// Like `decrefC`, for values which may be shared between threads
+pub fn decrefAtomicC(
+    bytes_or_null: ?[*]isize,
+    alignment: u32,
+) callconv(.C) void {
+    var bytes = @ptrCast([*]isize, bytes_or_null);
+
+    return @call(.{ .modifier = always_inline }, decref_ptr_to_refcount, .{ bytes, alignment, Refcount.atomic });
 }
 
 pub fn decrefCheckNullC(
@@ -184,7 +245,18 @@ pub fn decrefCheckNullC(
 ) callconv(.C) void {
     if (bytes_or_null) |bytes| {
         const isizes: [*]isize = @ptrCast([*]isize, @alignCast(@sizeOf(isize), bytes));
-        return @call(.{ .modifier = always_inline }, decref_ptr_to_refcount, .{ isizes - 1, alignment });
+        return @call(.{ .modifier = always_inline }, decref_ptr_to_refcount, .{ isizes - 1, alignment, RC_TYPE });
+    }
+}
+
+/// Like `decrefCheckNullC`, for values which may be shared between threads
+pub fn decrefCheckNullAtomicC(
+    bytes_or_null: ?[*]u8,
+    alignment: u32,
+) callconv(.C) void {
+    if (bytes_or_null) |bytes| {
+        const isizes: [*]isize = @ptrCast([*]isize, @alignCast(@sizeOf(isize), bytes));
+        return @call(.{ .modifier = always_inline }, decref_ptr_to_refcount, .{ isizes - 1, alignment, Refcount.atomic });
     }
 }
 
@@ -201,19 +273,24 @@ pub fn decref(
 
     const isizes: [*]isize = @ptrCast([*]isize, @alignCast(@alignOf(isize), bytes));
 
-    decref_ptr_to_refcount(isizes - 1, alignment);
+    if (isInConcurrentMap()) {
+        decref_ptr_to_refcount(isizes - 1, alignment, Refcount.atomic);
+    } else {
+        decref_ptr_to_refcount(isizes - 1, alignment, RC_TYPE);
+    }
 }
 
 inline fn decref_ptr_to_refcount(
     refcount_ptr: [*]isize,
     alignment: u32,
+    comptime rc_type: Refcount,
 ) void {
-    if (RC_TYPE == Refcount.none) return;
+    if (rc_type == Refcount.none) return;
     const extra_bytes = std.math.max(alignment, @sizeOf(usize));
     // Ensure that the refcount is not whole program lifetime.
     const refcount: isize = refcount_ptr[0];
     if (refcount != REFCOUNT_MAX_ISIZE) {
-        switch (RC_TYPE) {
+        switch (rc_type) {
             Refcount.normal => {
                 refcount_ptr[0] = refcount -% 1;
                 if (refcount == REFCOUNT_ONE_ISIZE) {
@@ -294,7 +371,7 @@ pub fn allocateWithRefcount(
     const alignment = std.math.max(ptr_width, element_alignment);
     const length = alignment + data_bytes;
 
-    var new_bytes: [*]u8 = alloc(length, alignment) orelse unreachable;
+    var new_bytes: [*]u8 = alloc(length, alignment) orelse outOfMemory(length, alignment);
 
     const data_ptr = new_bytes + alignment;
     const refcount_ptr = @ptrCast([*]usize, @alignCast(ptr_width, data_ptr) - ptr_width);
@@ -324,8 +401,7 @@ pub fn unsafeReallocate(
         return source_ptr;
     }
 
-    // TODO handle out of memory
-    // NOTE realloc will dealloc the original allocation
+    // NOTE realloc will dealloc the original allocation, and calls roc_oom if it fails
     const old_allocation = source_ptr - align_width;
     const new_allocation = realloc(old_allocation, new_width, old_width, alignment);
 
@@ -0,0 +1,127 @@
+interface Async
+    exposes [
+        Async,
+        ok,
+        err,
+        fromResult,
+        fromCallback,
+        toCallback,
+        attempt,
+        await,
+        onErr,
+        map,
+        mapErr,
+        sequence,
+    ]
+    imports [List, Result.{ Result }]
+
+## An asynchronous task: a computation which eventually succeeds with an `ok`
+## value, or fails with an `err` value.
+##
+## Rather than returning its result, a task calls a callback with it. That means
+## a task can wait on something the platform does (like a timer, or a network
+## request) without blocking: the platform's scheduler calls the callback once
+## the result is ready, and runs other tasks in the meantime. See [fromCallback]
+## and [toCallback] for how platforms create and run tasks.
+##
+## A platform with its own `Task` module can build it on this one, e.g. with
+## `Task ok err : Async ok err`, so that tasks work the same way on every platform.
+##
+## Tasks always run one after another; [sequence] starts each task once the one
+## before it has finished. There is no way to run tasks in parallel yet, because
+## joining their results needs help from the platform's scheduler, and there is
+## no standard interface to that scheduler. For the same reason, `roc glue` can't
+## generate bindings for `Async` values, so a platform has to run its tasks itself
+## rather than handing them to its host.
+Async ok err := (Result ok err -> {}) -> {}
+
+## A task which succeeds with the given value.
+##
+##     Async.ok "Hello"
+ok : a -> Async a *
+ok = \a -> @Async \continue -> continue (Ok a)
+
+## A task which fails with the given value.
+##
+##     Async.err NotFound
+err : a -> Async * a
+err = \a -> @Async \continue -> continue (Err a)
+
+## A task which succeeds or fails as the given [Result] does.
+fromResult : Result ok err -> Async ok err
+fromResult = \result -> @Async \continue -> continue result
+
+## Creates a task from a function which calls a callback with the task's result.
+##
+## This is how a platform exposes an asynchronous effect as a task: the function
+## starts the work, and the platform calls the callback once the work is done,
+## which may be long after the function has returned.
+fromCallback : ((Result ok err -> {}) -> {}) -> Async ok err
+fromCallback = \run -> @Async run
+
+## Returns a function which runs the task, and calls the given callback with its
+## result.
+##
+## This is how a platform runs a task it was given, e.g. by an application's
+## `main`. The callback is a closure the host can call through the glue for it.
+toCallback : Async ok err -> ((Result ok err -> {}) -> {})
+toCallback = \@Async run -> run
+
+## Runs a task, then passes its [Result] to the given function to get the next
+## task to run.
+attempt : Async a b, (Result a b -> Async c d) -> Async c d
+attempt = \@Async run, transform ->
+    @Async \continue ->
+        run \result ->
+            next = toCallback (transform result)
+
+            next continue
+
+## Runs a task, and if it succeeds, passes its value to the given function to
+## get the next task to run. If it fails, the next task is skipped.
+##
+##     Async.ok 1
+##     |> Async.await \n -> Async.ok (n + 1)
+await : Async a err, (a -> Async b err) -> Async b err
+await = \task, transform ->
+    attempt task \result ->
+        when result is
+            Ok a -> transform a
+            Err e -> err e
+
+## Runs a task, and if it fails, passes its error to the given function to get
+## a task to run instead.
+onErr : Async ok a, (a -> Async ok b) -> Async ok b
+onErr = \task, transform ->
+    attempt task \result ->
+        when result is
+            Ok a -> ok a
+            Err e -> transform e
+
+## Transforms the value a task succeeds with.
+map : Async a err, (a -> b) -> Async b err
+map = \task, transform ->
+    attempt task \result ->
+        when result is
+            Ok a -> ok (transform a)
+            Err e -> err e
+
+## Transforms the value a task fails with.
+mapErr : Async ok a, (a -> b) -> Async ok b
+mapErr = \task, transform ->
+    attempt task \result ->
+        when result is
+            Ok a -> ok a
+            Err e -> err (transform e)
+
+## Runs each task in the list, one after another, and succeeds with the list of
+## their values. If one of them fails, the ones after it are skipped.
+##
+## Each task is started once the one before it has finished.
+sequence : List (Async ok err) -> Async (List ok) err
+sequence = \tasks ->
+    List.walk tasks (ok (List.withCapacity (List.len tasks))) \state, task ->
+        results <- await state
+        value <- map task
+
+        List.append results value
@@ -0,0 +1,158 @@
+interface Bytes
+    exposes [
+        Bytes,
+        fromList,
+        toList,
+        len,
+        isEmpty,
+        readU8,
+        readU16Le,
+        readU16Be,
+        readU32Le,
+        readU32Be,
+        readU64Le,
+        readU64Be,
+        take,
+        skip,
+        split,
+    ]
+    imports [
+        Bool.{ Bool },
+        Result.{ Result },
+        List,
+        Num.{ U8, U16, U32, U64, Nat },
+    ]
+
+## A view of some of the bytes in a [List U8], for decoding binary formats.
+##
+## Reading from a [Bytes] returns the value read along with the bytes after it,
+## so a decoder can pass those on to read the next value:
+##
+##     header = \bytes ->
+##         magic <- Result.try (Bytes.readU32Be bytes)
+##         version <- Result.try (Bytes.readU16Le magic.rest)
+##
+##         Ok { value: { magic: magic.value, version: version.value }, rest: version.rest }
+##
+## Taking some of the bytes or skipping past them doesn't copy the list; every
+## view of it shares the same list.
+Bytes := { list : List U8, start : Nat, len : Nat }
+
+readU16Lowlevel : List U8, Nat, Bool -> U16
+readU32Lowlevel : List U8, Nat, Bool -> U32
+readU64Lowlevel : List U8, Nat, Bool -> U64
+
+## Returns a view of all the bytes in the list.
+fromList : List U8 -> Bytes
+fromList = \list -> @Bytes { list, start: 0, len: List.len list }
+
+## Returns the bytes in the view as a list.
+toList : Bytes -> List U8
+toList = \@Bytes { list, start, len: count } ->
+    List.sublist list { start, len: count }
+
+## Returns the number of bytes in the view.
+len : Bytes -> Nat
+len = \@Bytes { len: count } -> count
+
+## Returns [Bool.true] if there are no bytes in the view.
+isEmpty : Bytes -> Bool
+isEmpty = \@Bytes { len: count } -> count == 0
+
+## Reads one byte, or returns [Err OutOfBounds] if there are none.
+readU8 : Bytes -> Result { value : U8, rest : Bytes } [OutOfBounds]
+readU8 = \bytes ->
+    read bytes 1 \list, position ->
+        when List.get list position is
+            Ok byte -> byte
+            Err OutOfBounds -> crash "Bytes.readU8 read past the end of its list. Definitely a standard library bug."
+
+## Reads a [U16] stored with its least significant byte first, or returns
+## [Err OutOfBounds] if there are fewer than 2 bytes.
+readU16Le : Bytes -> Result { value : U16, rest : Bytes } [OutOfBounds]
+readU16Le = \bytes -> read bytes 2 \list, position -> readU16Lowlevel list position Bool.false
+
+## Reads a [U16] stored with its most significant byte first, or returns
+## [Err OutOfBounds] if there are fewer than 2 bytes.
+readU16Be : Bytes -> Result { value : U16, rest : Bytes } [OutOfBounds]
+readU16Be = \bytes -> read bytes 2 \list, position -> readU16Lowlevel list position Bool.true
+
+## Reads a [U32] stored with its least significant byte first, or returns
+## [Err OutOfBounds] if there are fewer than 4 bytes.
+readU32Le : Bytes -> Result { value : U32, rest : Bytes } [OutOfBounds]
+readU32Le = \bytes -> read bytes 4 \list, position -> readU32Lowlevel list position Bool.false
+
+## Reads a [U32] stored with its most significant byte first, or returns
+## [Err OutOfBounds] if there are fewer than 4 bytes.
+readU32Be : Bytes -> Result { value : U32, rest : Bytes } [OutOfBounds]
+readU32Be = \bytes -> read bytes 4 \list, position -> readU32Lowlevel list position Bool.true
+
+## Reads a [U64] stored with its least significant byte first, or returns
+## [Err OutOfBounds] if there are fewer than 8 bytes.
+readU64Le : Bytes -> Result { value : U64, rest : Bytes } [OutOfBounds]
+readU64Le = \bytes -> read bytes 8 \list, position -> readU64Lowlevel list position Bool.false
+
+## Reads a [U64] stored with its most significant byte first, or returns
+## [Err OutOfBounds] if there are fewer than 8 bytes.
+readU64Be : Bytes -> Result { value : U64, rest : Bytes } [OutOfBounds]
+readU64Be = \bytes -> read bytes 8 \list, position -> readU64Lowlevel list position Bool.true
+
+## Returns a view of the given number of bytes, along with the bytes after them,
+## or [Err OutOfBounds] if there aren't that many.
+##
+##     expect
+##         when Bytes.take (Bytes.fromList [1, 2, 3]) 2 is
+##             Ok { value } -> Bytes.toList value == [1, 2]
+##             Err OutOfBounds -> Bool.false
+take : Bytes, Nat -> Result { value : Bytes, rest : Bytes } [OutOfBounds]
+take = \@Bytes { list, start, len: count }, n ->
+    if n <= count then
+        Ok {
+            value: @Bytes { list, start, len: n },
+            rest: @Bytes { list, start: start + n, len: count - n },
+        }
+    else
+        Err OutOfBounds
+
+## Returns the bytes after the given number of bytes, or [Err OutOfBounds] if
+## there aren't that many.
+skip : Bytes, Nat -> Result Bytes [OutOfBounds]
+skip = \bytes, n ->
+    Result.map (take bytes n) .rest
+
+## Splits the bytes at the given index. If there are fewer bytes than that,
+## `before` has all of them and `others` is empty, as with [List.split].
+split : Bytes, Nat -> { before : Bytes, others : Bytes }
+split = \@Bytes { list, start, len: count }, index ->
+    n = if index < count then index else count
+
+    {
+        before: @Bytes { list, start, len: n },
+        others: @Bytes { list, start: start + n, len: count - n },
+    }
+
+# Checks that there are enough bytes before reading any, so the lowlevels never
+# read past the end of the list.
+read : Bytes, Nat, (List U8, Nat -> a) -> Result { value : a, rest : Bytes } [OutOfBounds]
+read = \@Bytes { list, start, len: count }, size, readAt ->
+    if size <= count then
+        Ok {
+            value: readAt list start,
+            rest: @Bytes { list, start: start + size, len: count - size },
+        }
+    else
+        Err OutOfBounds
+
+expect
+    bytes = fromList [0x12, 0x34, 0x56, 0x78, 0x9a]
+
+    u16 = Result.map (readU16Be bytes) .value
+    u32 = Result.map (readU32Le bytes) .value
+    u64 = Result.map (readU64Be bytes) .value
+
+    u16 == Ok 0x1234 && u32 == Ok 0x7856_3412 && u64 == Err OutOfBounds
+
+expect
+    { before, others } = split (fromList [1, 2, 3]) 5
+
+    toList before == [1, 2, 3] && isEmpty others
@@ -0,0 +1,147 @@
+interface Encoding
+    exposes [
+        base64Encode,
+        base64Decode,
+        base64UrlEncode,
+        base64UrlDecode,
+        hexEncode,
+        hexDecode,
+        percentEncode,
+        percentDecode,
+    ]
+    imports [
+        Bool.{ Bool },
+        Result.{ Result },
+        Str.{ Utf8ByteProblem },
+        List,
+        Num.{ U8, Nat },
+    ]
+
+encodeLowlevel : List U8, U8 -> Str
+decodeLowlevel : Str, U8 -> List U8
+checkLowlevel : Str, U8 -> Nat
+
+# The formats, numbered as the bitcode numbers them
+base64Format : U8
+base64Format = 0
+
+base64UrlFormat : U8
+base64UrlFormat = 1
+
+hexFormat : U8
+hexFormat = 2
+
+percentFormat : U8
+percentFormat = 3
+
+## Encodes bytes as [base64](https://datatracker.ietf.org/doc/html/rfc4648#section-4),
+## padded with `=` to a multiple of 4 characters.
+##
+##     expect Encoding.base64Encode (Str.toUtf8 "Roc") == "Um9j"
+##     expect Encoding.base64Encode [0xff] == "/w=="
+base64Encode : List U8 -> Str
+base64Encode = \bytes -> encodeLowlevel bytes base64Format
+
+## Decodes [base64](https://datatracker.ietf.org/doc/html/rfc4648#section-4),
+## with or without its `=` padding.
+##
+## Returns [Err InvalidChar] with the index of the first byte in the [Str] which
+## isn't base64, or [Err InvalidLength] if there are too few characters at the
+## end to make a byte.
+##
+##     expect Encoding.base64Decode "Um9j" == Ok (Str.toUtf8 "Roc")
+##     expect Encoding.base64Decode "Um9j!" == Err (InvalidChar 4)
+base64Decode : Str -> Result (List U8) [InvalidChar Nat, InvalidLength]
+base64Decode = \str -> base64DecodeHelp str base64Format
+
+## Encodes bytes as [base64url](https://datatracker.ietf.org/doc/html/rfc4648#section-5),
+## which uses `-` and `_` rather than `+` and `/`, so that it can go in URLs and
+## file names. It isn't padded, since `=` can't go in URLs either.
+##
+##     expect Encoding.base64UrlEncode [0xfb, 0xff] == "-_8"
+base64UrlEncode : List U8 -> Str
+base64UrlEncode = \bytes -> encodeLowlevel bytes base64UrlFormat
+
+## Decodes [base64url](https://datatracker.ietf.org/doc/html/rfc4648#section-5),
+## with or without `=` padding. The errors are the same as for
+## [Encoding.base64Decode].
+base64UrlDecode : Str -> Result (List U8) [InvalidChar Nat, InvalidLength]
+base64UrlDecode = \str -> base64DecodeHelp str base64UrlFormat
+
+base64DecodeHelp : Str, U8 -> Result (List U8) [InvalidChar Nat, InvalidLength]
+base64DecodeHelp = \str, format ->
+    len = Str.countUtf8Bytes str
+    valid = checkLowlevel str format
+    padding =
+        if Str.endsWith str "==" then
+            2
+        else if Str.endsWith str "=" then
+            1
+        else
+            0
+
+    if valid < len then
+        Err (InvalidChar valid)
+    else if (len - padding) % 4 == 1 || (padding > 0 && len % 4 != 0) then
+        Err InvalidLength
+    else
+        Ok (decodeLowlevel str format)
+
+## Encodes each byte as two lowercase hexadecimal digits.
+##
+##     expect Encoding.hexEncode [0xca, 0xfe] == "cafe"
+hexEncode : List U8 -> Str
+hexEncode = \bytes -> encodeLowlevel bytes hexFormat
+
+## Decodes pairs of hexadecimal digits, in either case.
+##
+## Returns [Err InvalidChar] with the index of the first byte in the [Str] which
+## isn't a hexadecimal digit, or [Err InvalidLength] if there's an odd number
+## of digits.
+##
+##     expect Encoding.hexDecode "CAFE" == Ok [0xca, 0xfe]
+##     expect Encoding.hexDecode "caf" == Err InvalidLength
+hexDecode : Str -> Result (List U8) [InvalidChar Nat, InvalidLength]
+hexDecode = \str ->
+    len = Str.countUtf8Bytes str
+    valid = checkLowlevel str hexFormat
+
+    if valid < len then
+        Err (InvalidChar valid)
+    else if len % 2 != 0 then
+        Err InvalidLength
+    else
+        Ok (decodeLowlevel str hexFormat)
+
+## [Percent-encodes](https://datatracker.ietf.org/doc/html/rfc3986#section-2.1)
+## every byte of the [Str]'s UTF-8 except the letters, digits, `-`, `.`, `_`,
+## and `~`, so that it can go in any part of a URL.
+##
+##     expect Encoding.percentEncode "a b/ü" == "a%20b%2F%C3%BC"
+percentEncode : Str -> Str
+percentEncode = \str -> encodeLowlevel (Str.toUtf8 str) percentFormat
+
+## Decodes [percent-encoding](https://datatracker.ietf.org/doc/html/rfc3986#section-2.1).
+## (A `+` stays a `+`; it only means a space in HTML form submissions.)
+##
+## Returns [Err InvalidChar] with the index of a `%` which isn't followed by
+## two hexadecimal digits, or [Err BadUtf8] if the decoded bytes aren't UTF-8.
+##
+##     expect Encoding.percentDecode "a%20b%2f" == Ok "a b/"
+percentDecode : Str -> Result Str [InvalidChar Nat, BadUtf8 Utf8ByteProblem Nat]
+percentDecode = \str ->
+    valid = checkLowlevel str percentFormat
+
+    if valid < Str.countUtf8Bytes str then
+        Err (InvalidChar valid)
+    else
+        Str.fromUtf8 (decodeLowlevel str percentFormat)
+
+expect
+    bytes = List.range { start: At 0u16, end: At 255 } |> List.map Num.toU8
+
+    base64Decode (base64Encode bytes) == Ok bytes
+    && base64UrlDecode (base64UrlEncode bytes) == Ok bytes
+    && hexDecode (hexEncode bytes) == Ok bytes
+
+expect base64Decode "Zm9v=" == Err InvalidLength
@@ -19,6 +19,7 @@ interface Hash
         hashStrBytes,
         hashList,
         hashUnordered,
+        hashWith,
     ] imports [
         Bool.{ isEq },
         List,
@@ -38,6 +39,10 @@ Hash has
 ## The [Hasher] ability describes general-purpose hashers. It only allows
 ## emission of 64-bit unsigned integer hashes. It is not suitable for
 ## cryptographically-secure hashing.
+##
+## `Dict` and `Set` hash their keys with a fast hasher which always starts from
+## the same seed. A key type can use another hasher, like a seeded SipHash for
+## keys that come from untrusted input, by implementing [Hash] with [hashWith].
 Hasher has
     ## Adds a list of bytes to the hasher.
     addBytes : a, List U8 -> a | a has Hasher
@@ -128,3 +133,23 @@ hashUnordered = \hasher, container, walk ->
                 nextAccum
         )
     |> \accum -> addU64 hasher accum
+
+## Hashes a value with another [Hasher], and adds the resulting hash to this one.
+##
+## This lets a type choose its own hasher, whichever hasher it's hashed into. For
+## example, keys which come from untrusted input can guard against anyone picking
+## keys whose hashes collide (making a `Dict` of them slow) with a seeded SipHash:
+##
+##     UserName := Str has [Eq, Hash { hash: hashUserName }]
+##
+##     hashUserName = \hasher, @UserName name ->
+##         Hash.hashWith hasher name (sipHasher secretSeed)
+##
+## Records and tags containing a `UserName` hash it this way too, since their
+## derived [Hash] implementations hash each field with its own implementation.
+hashWith : hasher, a, other -> hasher | a has Hash, hasher has Hasher, other has Hasher
+hashWith = \hasher, value, other ->
+    other
+    |> hash value
+    |> complete
+    |> \digest -> addU64 hasher digest
@@ -3,6 +3,11 @@ interface Json
         Json,
         toUtf8,
         fromUtf8,
+        Stream,
+        stream,
+        feed,
+        endOfInput,
+        next,
     ]
     imports [
         List,
@@ -15,7 +20,9 @@ interface Json
         },
         Decode,
         Decode.{
+            Decoding,
             DecoderFormatting,
+            DecodeError,
             DecodeResult,
         },
         Num.{
@@ -37,6 +44,13 @@ interface Json
         Result,
     ]
 
+## The [JSON](https://www.json.org) format, for encoding values with
+## [Encode.toBytes] and decoding them with [Decode.fromBytes].
+##
+## Records are objects, lists are arrays, and a tag like `Point 1 2` is an
+## object with one field holding an array of its payload, like
+## `{"Point":[1,2]}`. Floats which are infinite or *NaN* are encoded as `null`,
+## since JSON has no numbers for them.
 Json := {} has [
          EncoderFormatting {
              u8: encodeU8,
@@ -83,9 +97,62 @@ toUtf8 = @Json {}
 
 fromUtf8 = @Json {}
 
+skipWhitespaceLowlevel : List U8, Nat -> Nat
+scanStringLowlevel : List U8, Nat -> U64
+scanValueLowlevel : List U8, Nat -> U64
+unescapeLowlevel : List U8, Nat, Nat -> List U8
+escapeLowlevel : List U8, Str -> List U8
+
+# How a scan in the bitcode stopped, and where. A number which ends where the
+# bytes do is `AtEnd`, since more digits might be coming.
+Scan : [Complete Nat, Incomplete Nat, Invalid Nat, AtEnd Nat]
+
+scan : U64 -> Scan
+scan = \result ->
+    position = Num.toNat (Num.shiftRightZfBy result 2)
+
+    when Num.bitwiseAnd result 3 is
+        0 -> Complete position
+        1 -> Incomplete position
+        2 -> Invalid position
+        _ -> AtEnd position
+
+# The index just after the value starting at `start`, when all of the value is
+# in the bytes
+valueEnd : List U8, Nat -> Result Nat [Incomplete, Invalid]
+valueEnd = \bytes, start ->
+    when scan (scanValueLowlevel bytes start) is
+        Complete end -> Ok end
+        AtEnd end -> Ok end
+        Incomplete _ -> Err Incomplete
+        Invalid _ -> Err Invalid
+
+# The bytes after a value which ends at `end`, without any whitespace after
+# it, so that a document can end with a newline
+restAfter : List U8, Nat -> List U8
+restAfter = \bytes, end ->
+    List.drop bytes (skipWhitespaceLowlevel bytes end)
+
+# Skips whitespace and then the given byte, returning the bytes after it
+expectByte : List U8, U8 -> Result (List U8) [Unexpected]
+expectByte = \bytes, byte ->
+    index = skipWhitespaceLowlevel bytes 0
+
+    when List.get bytes index is
+        Ok found if found == byte -> Ok (restAfter bytes (index + 1))
+        _ -> Err Unexpected
+
 numToBytes = \n ->
     n |> Num.toStr |> Str.toUtf8
 
+# JSON has no numbers for infinity or NaN. Subtracting those from themselves
+# gives NaN, which isn't zero.
+floatToBytes = \n ->
+    if Num.isZero (n - n) then
+        numToBytes n
+    else
+        Str.toUtf8 "null"
+
 encodeU8 = \n -> Encode.custom \bytes, @Json {} -> List.concat bytes (numToBytes n)
 
 encodeU16 = \n -> Encode.custom \bytes, @Json {} -> List.concat bytes (numToBytes n)
@@ -106,9 +173,9 @@ encodeI64 = \n -> Encode.custom \bytes, @Json {} -> List.concat bytes (numToByte
 
 encodeI128 = \n -> Encode.custom \bytes, @Json {} -> List.concat bytes (numToBytes n)
 
-encodeF32 = \n -> Encode.custom \bytes, @Json {} -> List.concat bytes (numToBytes n)
+encodeF32 = \n -> Encode.custom \bytes, @Json {} -> List.concat bytes (floatToBytes n)
 
-encodeF64 = \n -> Encode.custom \bytes, @Json {} -> List.concat bytes (numToBytes n)
+encodeF64 = \n -> Encode.custom \bytes, @Json {} -> List.concat bytes (floatToBytes n)
 
 encodeDec = \n -> Encode.custom \bytes, @Json {} -> List.concat bytes (numToBytes n)
 
@@ -121,9 +188,7 @@ encodeBool = \b -> Encode.custom \bytes, @Json {} ->
             List.concat bytes (Str.toUtf8 "false")
 
 encodeString = \s -> Encode.custom \bytes, @Json {} ->
-        List.append bytes (Num.toU8 '"')
-        |> List.concat (Str.toUtf8 s)
-        |> List.append (Num.toU8 '"')
+        escapeLowlevel bytes s
 
 encodeList = \lst, encodeElem ->
     Encode.custom \bytes, @Json {} ->
@@ -146,9 +211,7 @@ encodeRecord = \fields ->
     Encode.custom \bytes, @Json {} ->
         writeRecord = \{ buffer, fieldsLeft }, { key, value } ->
             bufferWithKeyValue =
-                List.append buffer (Num.toU8 '"')
-                |> List.concat (Str.toUtf8 key)
-                |> List.append (Num.toU8 '"')
+                escapeLowlevel buffer key
                 |> List.append (Num.toU8 ':')
                 |> appendWith value (@Json {})
 
@@ -180,9 +243,7 @@ encodeTag = \name, payload ->
 
         bytesHead =
             List.append bytes (Num.toU8 '{')
-            |> List.append (Num.toU8 '"')
-            |> List.concat (Str.toUtf8 name)
-            |> List.append (Num.toU8 '"')
+            |> escapeLowlevel name
             |> List.append (Num.toU8 ':')
             |> List.append (Num.toU8 '[')
 
@@ -191,168 +252,76 @@ encodeTag = \name, payload ->
         List.append bytesWithPayload (Num.toU8 ']')
         |> List.append (Num.toU8 '}')
 
-takeWhile = \list, predicate ->
-    helper = \{ taken, rest } ->
-        when List.first rest is
-            Ok elem ->
-                if predicate elem then
-                    helper { taken: List.append taken elem, rest: List.split rest 1 |> .others }
-                else
-                    { taken, rest }
-
-            Err _ -> { taken, rest }
-
-    helper { taken: [], rest: list }
-
-digits : List U8
-digits = List.range { start: At '0', end: At '9' }
-
-takeDigits = \bytes ->
-    takeWhile bytes \n -> List.contains digits n
-
-takeFloat = \bytes ->
-    { taken: intPart, rest } = takeDigits bytes
-
-    when List.get rest 0 is
-        Ok '.' ->
-            { taken: floatPart, rest: afterAll } = takeDigits (List.split rest 1).others
-            builtFloat =
-                List.concat (List.append intPart '.') floatPart
-
-            { taken: builtFloat, rest: afterAll }
-
-        _ ->
-            { taken: intPart, rest }
-
-decodeU8 = Decode.custom \bytes, @Json {} ->
-    { taken, rest } = takeDigits bytes
-
-    when Str.fromUtf8 taken |> Result.try Str.toU8 is
-        Ok n -> { result: Ok n, rest }
-        Err _ -> { result: Err TooShort, rest }
-
-decodeU16 = Decode.custom \bytes, @Json {} ->
-    { taken, rest } = takeDigits bytes
-
-    when Str.fromUtf8 taken |> Result.try Str.toU16 is
-        Ok n -> { result: Ok n, rest }
-        Err _ -> { result: Err TooShort, rest }
-
-decodeU32 = Decode.custom \bytes, @Json {} ->
-    { taken, rest } = takeDigits bytes
-
-    when Str.fromUtf8 taken |> Result.try Str.toU32 is
-        Ok n -> { result: Ok n, rest }
-        Err _ -> { result: Err TooShort, rest }
-
-decodeU64 = Decode.custom \bytes, @Json {} ->
-    { taken, rest } = takeDigits bytes
-
-    when Str.fromUtf8 taken |> Result.try Str.toU64 is
-        Ok n -> { result: Ok n, rest }
-        Err _ -> { result: Err TooShort, rest }
-
-decodeU128 = Decode.custom \bytes, @Json {} ->
-    { taken, rest } = takeDigits bytes
-
-    when Str.fromUtf8 taken |> Result.try Str.toU128 is
-        Ok n -> { result: Ok n, rest }
-        Err _ -> { result: Err TooShort, rest }
-
-decodeI8 = Decode.custom \bytes, @Json {} ->
-    { taken, rest } = takeDigits bytes
-
-    when Str.fromUtf8 taken |> Result.try Str.toI8 is
-        Ok n -> { result: Ok n, rest }
-        Err _ -> { result: Err TooShort, rest }
-
-decodeI16 = Decode.custom \bytes, @Json {} ->
-    { taken, rest } = takeDigits bytes
+decodeNumber : List U8, (Str -> Result val *) -> DecodeResult val
+decodeNumber = \bytes, fromStr ->
+    start = skipWhitespaceLowlevel bytes 0
+    isNumber =
+        when List.get bytes start is
+            Ok byte -> byte == '-' || (byte >= '0' && byte <= '9')
+            Err OutOfBounds -> Bool.false
+
+    if isNumber then
+        when valueEnd bytes start is
+            Ok end ->
+                when Str.fromUtf8 (List.sublist bytes { start, len: end - start }) |> Result.try fromStr is
+                    Ok n -> { result: Ok n, rest: restAfter bytes end }
+                    Err _ -> { result: Err TooShort, rest: bytes }
+
+            Err _ -> { result: Err TooShort, rest: bytes }
+    else
+        { result: Err TooShort, rest: bytes }
 
-    when Str.fromUtf8 taken |> Result.try Str.toI16 is
-        Ok n -> { result: Ok n, rest }
-        Err _ -> { result: Err TooShort, rest }
+decodeU8 = Decode.custom \bytes, @Json {} -> decodeNumber bytes Str.toU8
 
-decodeI32 = Decode.custom \bytes, @Json {} ->
-    { taken, rest } = takeDigits bytes
+decodeU16 = Decode.custom \bytes, @Json {} -> decodeNumber bytes Str.toU16
 
-    when Str.fromUtf8 taken |> Result.try Str.toI32 is
-        Ok n -> { result: Ok n, rest }
-        Err _ -> { result: Err TooShort, rest }
+decodeU32 = Decode.custom \bytes, @Json {} -> decodeNumber bytes Str.toU32
 
-decodeI64 = Decode.custom \bytes, @Json {} ->
-    { taken, rest } = takeDigits bytes
+decodeU64 = Decode.custom \bytes, @Json {} -> decodeNumber bytes Str.toU64
 
-    when Str.fromUtf8 taken |> Result.try Str.toI64 is
-        Ok n -> { result: Ok n, rest }
-        Err _ -> { result: Err TooShort, rest }
+decodeU128 = Decode.custom \bytes, @Json {} -> decodeNumber bytes Str.toU128
 
-decodeI128 = Decode.custom \bytes, @Json {} ->
-    { taken, rest } = takeDigits bytes
+decodeI8 = Decode.custom \bytes, @Json {} -> decodeNumber bytes Str.toI8
 
-    when Str.fromUtf8 taken |> Result.try Str.toI128 is
-        Ok n -> { result: Ok n, rest }
-        Err _ -> { result: Err TooShort, rest }
+decodeI16 = Decode.custom \bytes, @Json {} -> decodeNumber bytes Str.toI16
 
-decodeF32 = Decode.custom \bytes, @Json {} ->
-    { taken, rest } = takeFloat bytes
+decodeI32 = Decode.custom \bytes, @Json {} -> decodeNumber bytes Str.toI32
 
-    when Str.fromUtf8 taken |> Result.try Str.toF32 is
-        Ok n -> { result: Ok n, rest }
-        Err _ -> { result: Err TooShort, rest }
+decodeI64 = Decode.custom \bytes, @Json {} -> decodeNumber bytes Str.toI64
 
-decodeF64 = Decode.custom \bytes, @Json {} ->
-    { taken, rest } = takeFloat bytes
+decodeI128 = Decode.custom \bytes, @Json {} -> decodeNumber bytes Str.toI128
 
-    when Str.fromUtf8 taken |> Result.try Str.toF64 is
-        Ok n -> { result: Ok n, rest }
-        Err _ -> { result: Err TooShort, rest }
+decodeF32 = Decode.custom \bytes, @Json {} -> decodeNumber bytes Str.toF32
 
-decodeDec = Decode.custom \bytes, @Json {} ->
-    { taken, rest } = takeFloat bytes
+decodeF64 = Decode.custom \bytes, @Json {} -> decodeNumber bytes Str.toF64
 
-    when Str.fromUtf8 taken |> Result.try Str.toDec is
-        Ok n -> { result: Ok n, rest }
-        Err _ -> { result: Err TooShort, rest }
+decodeDec = Decode.custom \bytes, @Json {} -> decodeNumber bytes Str.toDec
 
 decodeBool = Decode.custom \bytes, @Json {} ->
-    { before: maybeFalse, others: afterFalse } = List.split bytes 5
-
-    # Note: this could be more performant by traversing both branches char-by-char.
-    # Doing that would also make `rest` more correct in the erroring case.
-    if
-        maybeFalse == ['f', 'a', 'l', 's', 'e']
-    then
-        { result: Ok Bool.false, rest: afterFalse }
-    else
-        { before: maybeTrue, others: afterTrue } = List.split bytes 4
+    start = skipWhitespaceLowlevel bytes 0
 
-        if
-            maybeTrue == ['t', 'r', 'u', 'e']
-        then
-            { result: Ok Bool.true, rest: afterTrue }
-        else
-            { result: Err TooShort, rest: bytes }
+    if List.sublist bytes { start, len: 4 } == ['t', 'r', 'u', 'e'] then
+        { result: Ok Bool.true, rest: restAfter bytes (start + 4) }
+    else if List.sublist bytes { start, len: 5 } == ['f', 'a', 'l', 's', 'e'] then
+        { result: Ok Bool.false, rest: restAfter bytes (start + 5) }
+    else
+        { result: Err TooShort, rest: bytes }
 
 jsonString : List U8 -> DecodeResult Str
 jsonString = \bytes ->
-    { before, others: afterStartingQuote } = List.split bytes 1
+    start = skipWhitespaceLowlevel bytes 0
 
-    if
-        before == ['"']
-    then
-        # TODO: handle escape sequences
-        { taken: strSequence, rest } = takeWhile afterStartingQuote \n -> n != '"'
+    when List.get bytes start is
+        Ok '"' ->
+            when scan (scanStringLowlevel bytes (start + 1)) is
+                Complete end ->
+                    when Str.fromUtf8 (unescapeLowlevel bytes (start + 1) end) is
+                        Ok s -> { result: Ok s, rest: restAfter bytes (end + 1) }
+                        Err _ -> { result: Err TooShort, rest: bytes }
 
-        when Str.fromUtf8 strSequence is
-            Ok s ->
-                { others: afterEndingQuote } = List.split rest 1
+                _ -> { result: Err TooShort, rest: bytes }
 
-                { result: Ok s, rest: afterEndingQuote }
-
-            Err _ -> { result: Err TooShort, rest }
-    else
-        { result: Err TooShort, rest: bytes }
+        _ -> { result: Err TooShort, rest: bytes }
 
 decodeString = Decode.custom \bytes, @Json {} ->
     jsonString bytes
@@ -363,50 +332,28 @@ decodeList = \decodeElem -> Decode.custom \bytes, @Json {} ->
                 { result, rest } ->
                     when result is
                         Ok val ->
-                            # TODO: handle spaces before ','
-                            { before: afterElem, others } = List.split rest 1
+                            when expectByte rest ',' is
+                                Ok afterComma -> decodeElems afterComma (List.append accum val)
+                                Err Unexpected ->
+                                    when expectByte rest ']' is
+                                        Ok afterEndingBrace -> { result: Ok (List.append accum val), rest: afterEndingBrace }
+                                        Err Unexpected -> { result: Err TooShort, rest }
 
-                            if
-                                afterElem == [',']
-                            then
-                                decodeElems others (List.append accum val)
-                            else
-                                Done (List.append accum val) rest
+                        Err e -> { result: Err e, rest }
 
-                        Err e -> Errored e rest
+        when expectByte bytes '[' is
+            Ok afterStartingBrace ->
+                when expectByte afterStartingBrace ']' is
+                    Ok afterEndingBrace -> { result: Ok [], rest: afterEndingBrace }
+                    Err Unexpected -> decodeElems afterStartingBrace []
 
-        { before, others: afterStartingBrace } = List.split bytes 1
-
-        if
-            before == ['[']
-        then
-            # TODO: empty lists
-            when decodeElems afterStartingBrace [] is
-                Errored e rest -> { result: Err e, rest }
-                Done vals rest ->
-                    { before: maybeEndingBrace, others: afterEndingBrace } = List.split rest 1
-
-                    if
-                        maybeEndingBrace == [']']
-                    then
-                        { result: Ok vals, rest: afterEndingBrace }
-                    else
-                        { result: Err TooShort, rest }
-        else
-            { result: Err TooShort, rest: bytes }
+            Err Unexpected -> { result: Err TooShort, rest: bytes }
 
 parseExactChar : List U8, U8 -> DecodeResult {}
 parseExactChar = \bytes, char ->
-    when List.get bytes 0 is
-        Ok c ->
-            if
-                c == char
-            then
-                { result: Ok {}, rest: (List.split bytes 1).others }
-            else
-                { result: Err TooShort, rest: bytes }
-
-        Err _ -> { result: Err TooShort, rest: bytes }
+    when expectByte bytes char is
+        Ok rest -> { result: Ok {}, rest }
+        Err Unexpected -> { result: Err TooShort, rest: bytes }
 
 openBrace : List U8 -> DecodeResult {}
 openBrace = \bytes -> parseExactChar bytes '{'
@@ -417,8 +364,14 @@ closingBrace = \bytes -> parseExactChar bytes '}'
 recordKey : List U8 -> DecodeResult Str
 recordKey = \bytes -> jsonString bytes
 
+# Skips a value of any kind, for fields the record doesn't have
 anything : List U8 -> DecodeResult {}
-anything = \bytes -> { result: Err TooShort, rest: bytes }
+anything = \bytes ->
+    start = skipWhitespaceLowlevel bytes 0
+
+    when valueEnd bytes start is
+        Ok end -> { result: Ok {}, rest: restAfter bytes end }
+        Err _ -> { result: Err TooShort, rest: bytes }
 
 colon : List U8 -> DecodeResult {}
 colon = \bytes -> parseExactChar bytes ':'
@@ -456,10 +409,93 @@ decodeRecord = \initialState, stepField, finalizer -> Decode.custom \bytes, @Jso
 
         { rest: afterBraceBytes } <- bytes |> openBrace |> tryDecode
 
-        { val: endStateResult, rest: beforeClosingBraceBytes } <- decodeFields stepField initialState afterBraceBytes |> tryDecode
+        fieldsResult =
+            when expectByte afterBraceBytes '}' is
+                # an empty object has no fields to decode
+                Ok _ -> { result: Ok initialState, rest: afterBraceBytes }
+                Err Unexpected -> decodeFields stepField initialState afterBraceBytes
+
+        { val: endStateResult, rest: beforeClosingBraceBytes } <- fieldsResult |> tryDecode
 
         { rest: afterRecordBytes } <- beforeClosingBraceBytes |> closingBrace |> tryDecode
 
         when finalizer endStateResult is
             Ok val -> { result: Ok val, rest: afterRecordBytes }
             Err e -> { result: Err e, rest: afterRecordBytes }
+
+## JSON values being decoded as their bytes arrive in chunks, such as a
+## platform reading a file or socket a piece at a time. The values can be
+## whitespace-separated, as in [NDJSON](http://ndjson.org).
+##
+## Each value is decoded once all of its bytes have arrived, and the bytes are
+## dropped once it's been decoded, so the [Stream] only holds on to one value's
+## worth of bytes. A value is scanned again each time a chunk arrives before
+## it's complete.
+##
+##     decodeAll = \chunks ->
+##         chunks
+##         |> List.walk Json.stream Json.feed
+##         |> Json.endOfInput
+##         |> decodeRest []
+##
+##     decodeRest = \s, values ->
+##         when Json.next s is
+##             Ok { value, stream } -> decodeRest stream (List.append values value)
+##             Err EndOfInput -> Ok values
+##             Err e -> Err e
+Stream := { buffer : List U8, consumed : Nat, finished : Bool }
+
+## A [Stream] which hasn't had any chunks yet.
+stream : Stream
+stream = @Stream { buffer: [], consumed: 0, finished: Bool.false }
+
+## Adds a chunk of bytes to the end of the [Stream].
+feed : Stream, List U8 -> Stream
+feed = \@Stream { buffer, consumed, finished }, chunk ->
+    @Stream { buffer: List.concat buffer chunk, consumed, finished }
+
+## Says no more chunks are coming, so a number at the end of the last chunk is
+## complete, and a value which isn't yet never will be.
+endOfInput : Stream -> Stream
+endOfInput = \@Stream { buffer, consumed } ->
+    @Stream { buffer, consumed, finished: Bool.true }
+
+## Decodes the next value, if all of its bytes have arrived.
+##
+## Returns [Err NeedMore] if they haven't, and [Err EndOfInput] if there are no
+## values left after [Json.endOfInput]. [Err InvalidJson] has the index of the
+## first byte (counting from the first chunk) which can't be there, and a value
+## which is JSON but can't be decoded to the type asked for is [Err TooShort],
+## as with [Decode.fromBytes].
+next : Stream -> Result { value : val, stream : Stream } [NeedMore, EndOfInput, InvalidJson Nat]DecodeError | val has Decoding
+next = \@Stream { buffer, consumed, finished } ->
+    start = skipWhitespaceLowlevel buffer 0
+
+    decodeUntil = \end ->
+        when Decode.fromBytes (List.sublist buffer { start, len: end - start }) fromUtf8 is
+            Ok value ->
+                rest = List.drop buffer end
+
+                Ok { value, stream: @Stream { buffer: rest, consumed: consumed + end, finished } }
+
+            Err _ -> Err TooShort
+
+    if start == List.len buffer then
+        if finished then Err EndOfInput else Err NeedMore
+    else
+        when scan (scanValueLowlevel buffer start) is
+            Complete end -> decodeUntil end
+            AtEnd end -> if finished then decodeUntil end else Err NeedMore
+            Incomplete position -> if finished then Err (InvalidJson (consumed + position)) else Err NeedMore
+            Invalid position -> Err (InvalidJson (consumed + position))
+
+expect
+    bytes = Encode.toBytes { name: "a \"quoted\"\nline", values: [1, -2] } toUtf8
+
+    Str.fromUtf8 bytes == Ok "{\"name\":\"a \\\"quoted\\\"\\nline\",\"values\":[1,-2]}"
+
+expect
+    decoded : Result { a : List U8, b : Str } [Leftover (List U8), TooShort]
+    decoded = Decode.fromBytes (Str.toUtf8 " { \"b\" : \"\\u00e9\" , \"skipped\" : [{}, null] , \"a\" : [ ] }\n") fromUtf8
+
+    decoded == Ok { a: [], b: "é" }
@@ -40,6 +40,7 @@ interface List
         max,
         map4,
         mapTry,
+        mapConcurrent,
         walkTry,
         dropFirst,
         joinMap,
@@ -596,6 +597,18 @@ keepErrs = \list, toResult ->
 ## > List.map ["", "a", "bc"] Str.isEmpty
 map : List a, (a -> b) -> List b
 
+## Like [List.map], but the conversion function may be run on several elements
+## at once, on threads provided by the platform. Since the function has no side
+## effects, the result is the same as with [List.map].
+##
+## The platform provides its threads by implementing `roc_parallel_for`, which
+## calls a given function on chunks of the list's indices and returns once all
+## of them are done. On platforms which don't implement it, every element is
+## converted one at a time.
+##
+## > List.mapConcurrent [1, 2, 3] (\num -> num * num)
+mapConcurrent : List a, (a -> b) -> List b
+
 ## Run a transformation function on the first element of each list,
 ## and use that as the first element in the returned list.
 ## Repeat until a list runs out of elements.
@@ -62,6 +62,9 @@ interface Num
         sqrtChecked,
         log,
         logChecked,
+        isNaN,
+        isInfinite,
+        isFinite,
         round,
         ceiling,
         floor,
@@ -77,6 +80,12 @@ interface Num
         shiftLeftBy,
         shiftRightBy,
         shiftRightZfBy,
+        popCount,
+        leadingZeros,
+        trailingZeros,
+        rotateLeftBy,
+        rotateRightBy,
+        swapBytes,
         subWrap,
         subChecked,
         subSaturated,
@@ -91,6 +100,7 @@ interface Num
         divTrunc,
         divTruncChecked,
         toStr,
+        toStrWith,
         isMultipleOf,
         minI8,
         maxI8,
@@ -375,8 +385,15 @@ Int range : Num (Integer range)
 ## cost! Since the most common reason to choose [F64] or [F32] over [Dec] is
 ## access to hardware-accelerated performance, Roc follows these rules exactly.
 ##
+## The exception is a program built with `--fast-math`. That lets the optimizer
+## assume no [F64] or [F32] is ever *NaN* or ±∞, and rearrange arithmetic as if
+## it were exact, so `(a + b) + c` might be calculated as `a + (b + c)`. This
+## can make number-crunching code much faster, but a calculation which does
+## produce one of these values gives an unspecified answer.
+##
 ## There's no literal syntax for these error values, but you can check to see if
-## you ended up with one of them by using #isNaN, #isFinite, and #isInfinite.
+## you ended up with one of them by using [isNaN], [isFinite], and [isInfinite].
+## These checks work the same way with or without `--fast-math`.
 ## Whenever a function in this module could return one of these values, that
 ## possibility is noted in the function's documentation.
 ##
@@ -506,6 +523,40 @@ Dec : Num (FloatingPoint Decimal)
 ##
 ## To get strings in hexadecimal, octal, or binary format, use `Num.format`.
 toStr : Num * -> Str
+
+## Convert a number to a [Str], choosing how many decimal places it has, whether
+## to use scientific notation, and what to put between each group of three
+## digits in its whole part.
+##
+##     expect Num.toStrWith 1234.5678 { decimals: Exactly 2 } == "1234.57"
+##     expect Num.toStrWith 1_000_000 { wholeSep: "," } == "1,000,000"
+##     expect Num.toStrWith 0.00042 { notation: Scientific } == "4.2e-4"
+##
+## The decimal point is always `.`, whatever the locale of the computer the
+## program is running on.
+##
+## By default the number has as many decimal places as [Num.toStr] gives it.
+## With `Exactly`, it's rounded half away from zero (going by the digits
+## [Num.toStr] gives it) or padded with zeros to that many decimal places. In
+## scientific notation, `decimals` is how many digits come after the first one.
+##
+## Non-[finite](Num.isFinite) [F64] and [F32] values are the same as they are
+## with [Num.toStr].
+toStrWith : Num *, { decimals ? [Shortest, Exactly U8], notation ? [Fixed, Scientific], wholeSep ? Str } -> Str
+toStrWith = \num, { decimals ? Shortest, notation ? Fixed, wholeSep ? "" } ->
+    { shortest, places } =
+        when decimals is
+            Shortest -> { shortest: Bool.true, places: 0 }
+            Exactly n -> { shortest: Bool.false, places: n }
+
+    scientific =
+        when notation is
+            Fixed -> Bool.false
+            Scientific -> Bool.true
+
+    toStrWithLowlevel (toStr num) wholeSep shortest places scientific
+
+toStrWithLowlevel : Str, Str, Bool, U8, Bool -> Str
 intCast : Int a -> Int b
 
 bytesToU16Lowlevel : List U8, Nat -> U16
@@ -757,6 +808,26 @@ logChecked = \x ->
     else
         Ok (Num.log x)
 
+## Returns `Bool.true` if the [Frac] is [*NaN*](https://en.wikipedia.org/wiki/NaN).
+## A [Dec] is never *NaN*.
+##
+## *NaN* isn't equal to anything, itself included, so this is the way to check
+## for it.
+##
+## >>> Num.isNaN (0.0f64 / 0.0)
+isNaN : Frac a -> Bool
+
+## Returns `Bool.true` if the [Frac] is ∞ or -∞. A [Dec] is never infinite.
+##
+## >>> Num.isInfinite (1.0f64 / 0.0)
+isInfinite : Frac a -> Bool
+
+## Returns `Bool.true` if the [Frac] is neither *NaN* nor ∞ nor -∞, which is
+## always the case for a [Dec].
+##
+## >>> Num.isFinite 1.5f64
+isFinite : Frac a -> Bool
+
 ## Divide one [Frac] by another.
 ##
 ## `a / b` is shorthand for `Num.div a b`.
@@ -895,6 +966,54 @@ shiftRightBy : Int a, U8 -> Int a
 ## In some languages `shiftRightBy` is implemented as a binary operator `>>`.
 shiftRightZfBy : Int a, U8 -> Int a
 
+## The number of bits which are 1 in the integer, which is also known as its
+## population count or Hamming weight.
+##
+## >>> Num.popCount 0b0110_1000u8 == 3
+##
+## >>> Num.popCount -1i16 == 16
+popCount : Int a -> U8
+
+## The number of 0 bits before the most significant 1 bit. It's the integer's
+## whole width in bits if the integer is 0.
+##
+## >>> Num.leadingZeros 0b0001_0100u8 == 3
+##
+## >>> Num.leadingZeros 0u32 == 32
+leadingZeros : Int a -> U8
+
+## The number of 0 bits after the least significant 1 bit. It's the integer's
+## whole width in bits if the integer is 0.
+##
+## >>> Num.trailingZeros 0b0001_0100u8 == 2
+##
+## >>> Num.trailingZeros 0u32 == 32
+trailingZeros : Int a -> U8
+
+## Bitwise left rotation of a number by another
+##
+## Like [shiftLeftBy], except the most significant bits which are shifted out
+## come back in as the least significant bits. Rotating by the integer's width
+## in bits or more wraps around, so rotating a [U8] by 9 is the same as rotating
+## it by 1.
+##
+## >>> Num.rotateLeftBy 0b1000_0011u8 2 == 0b0000_1110
+rotateLeftBy : Int a, U8 -> Int a
+
+## Bitwise right rotation of a number by another
+##
+## Like [shiftRightZfBy], except the least significant bits which are shifted
+## out come back in as the most significant bits.
+##
+## >>> Num.rotateRightBy 0b1000_0011u8 2 == 0b1110_0000
+rotateRightBy : Int a, U8 -> Int a
+
+## Reverses the order of the integer's bytes, which converts it between
+## big-endian and little-endian.
+##
+## >>> Num.swapBytes 0x1234u16 == 0x3412
+swapBytes : Int a -> Int a
+
 ## Round off the given fraction to the nearest integer.
 round : Frac * -> Int *
 floor : Frac * -> Int *
@@ -927,6 +1046,12 @@ pow : Frac a, Frac a -> Frac a
 ## so large it causes an overflow.
 powInt : Int a, Int a -> Int a
 
+## Add two integers, wrapping around on overflow rather than crashing.
+##
+## This is the same as [Num.add] except that if the sum is too big to fit in
+## the integer type, it wraps around from the maximum to the minimum.
+## For example, if `x : U8` is 200 and `y : U8` is 100, `addWrap x y` will
+## yield 44.
 addWrap : Int range, Int range -> Int range
 
 ## Add two numbers, clamping on the maximum representable number rather than
@@ -953,6 +1078,12 @@ addChecked = \a, b ->
 
 addCheckedLowlevel : Num a, Num a -> { b : Bool, a : Num a }
 
+## Subtract two integers, wrapping around on overflow rather than crashing.
+##
+## This is the same as [Num.sub] except that if the difference is too small to
+## fit in the integer type, it wraps around from the minimum to the maximum.
+## For example, if `x : U8` is 10 and `y : U8` is 20, `subWrap x y` will
+## yield 246.
 subWrap : Int range, Int range -> Int range
 
 ## Subtract two numbers, clamping on the minimum representable number rather
@@ -979,13 +1110,21 @@ subChecked = \a, b ->
 
 subCheckedLowlevel : Num a, Num a -> { b : Bool, a : Num a }
 
+## Multiply two integers, wrapping around on overflow rather than crashing.
+##
+## This is the same as [Num.mul] except that only the lowest bits of the
+## product which fit in the integer type are kept.
+## For example, if `x : U8` is 20 and `y : U8` is 20, `mulWrap x y` will
+## yield 144.
 mulWrap : Int range, Int range -> Int range
 
 ## Multiply two numbers, clamping on the maximum representable number rather than
 ## overflowing.
 ##
 ## This is the same as [Num.mul] except for the saturating behavior if the
-## addition is to overflow.
+## multiplication is to overflow.
+## For example, if `x : U8` is 20 and `y : U8` is 20, `mulSaturated x y` will
+## yield 255, the maximum value of a `U8`.
 mulSaturated : Num a, Num a -> Num a
 
 ## Multiply two numbers and check for overflow.
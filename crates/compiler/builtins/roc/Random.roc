@@ -0,0 +1,139 @@
+interface Random
+    exposes [
+        Seed,
+        fromU64,
+        testSeed,
+        split,
+        u64,
+        u32,
+        bool,
+        f64,
+        below,
+        list,
+    ]
+    imports [Bool.{ Bool }, List, Num.{ U32, U64, F64, Nat }]
+
+## The state of a pseudo-random number generator.
+##
+## The same seed always produces the same numbers, so a test that generates its
+## inputs from a seed can be run again with exactly the same inputs. Every
+## function here returns the next seed along with the number it generated;
+## passing that one on to the next call gives you the next number.
+##
+## Seeds can also be [split] into two independent seeds, so that a function
+## can hand its own seed to each of its helpers without them generating the same
+## numbers. (This is the SplitMix64 algorithm. It's fast and has good statistical
+## properties, but it's not suitable for cryptography.)
+Seed := { state : U64, gamma : U64 }
+
+mix64Lowlevel : U64 -> U64
+mixGammaLowlevel : U64 -> U64
+testSeedLowlevel : {} -> U64
+
+goldenGamma : U64
+goldenGamma = 0x9e37_79b9_7f4a_7c15
+
+## A seed which generates the same numbers every time the program runs.
+##
+##     Random.fromU64 42
+fromU64 : U64 -> Seed
+fromU64 = \n -> @Seed { state: n, gamma: goldenGamma }
+
+## The seed `roc test` picked for this run. It's printed when an `expect` fails,
+## and `roc test --seed` runs the tests with that seed again.
+##
+##     expect
+##         a = Random.u64 (Random.testSeed {})
+##         b = Random.u64 a.seed
+##
+##         Num.addWrap a.value b.value == Num.addWrap b.value a.value
+##
+## Outside of `roc test`, this is always `Random.fromU64 0`.
+testSeed : {} -> Seed
+testSeed = \{} -> fromU64 (testSeedLowlevel {})
+
+step : Seed -> { state : U64, seed : Seed }
+step = \@Seed { state, gamma } ->
+    next = Num.addWrap state gamma
+
+    { state: next, seed: @Seed { state: next, gamma } }
+
+## Splits a seed into two seeds, which generate different numbers from each other
+## and from the original seed.
+split : Seed -> { left : Seed, right : Seed }
+split = \seed ->
+    first = step seed
+    second = step first.seed
+
+    right = @Seed {
+        state: mix64Lowlevel first.state,
+        gamma: mixGammaLowlevel second.state,
+    }
+
+    { left: second.seed, right }
+
+## Generates a [U64], where every value is equally likely.
+u64 : Seed -> { value : U64, seed : Seed }
+u64 = \seed ->
+    stepped = step seed
+
+    { value: mix64Lowlevel stepped.state, seed: stepped.seed }
+
+## Generates a [U32], where every value is equally likely.
+u32 : Seed -> { value : U32, seed : Seed }
+u32 = \seed ->
+    generated = u64 seed
+
+    { value: Num.shiftRightZfBy generated.value 32 |> Num.toU32, seed: generated.seed }
+
+## Generates `Bool.true` or `Bool.false`, with equal chances.
+bool : Seed -> { value : Bool, seed : Seed }
+bool = \seed ->
+    generated = u64 seed
+
+    { value: Num.shiftRightZfBy generated.value 63 == 1, seed: generated.seed }
+
+## Generates an [F64] that's at least `0` and less than `1`.
+f64 : Seed -> { value : F64, seed : Seed }
+f64 = \seed ->
+    generated = u64 seed
+
+    # an F64 has 53 bits of precision, so use the top 53 bits and divide by 2^53
+    value = Num.toF64 (Num.shiftRightZfBy generated.value 11) / 9007199254740992
+
+    { value, seed: generated.seed }
+
+## Generates a [U64] that's less than the given bound, or `0` if the bound is `0`.
+##
+##     Random.below seed 6
+##
+## Smaller values are very slightly more likely than larger ones. The difference
+## is at most `bound / 2^64`, so it only matters for huge bounds.
+below : Seed, U64 -> { value : U64, seed : Seed }
+below = \seed, bound ->
+    generated = u64 seed
+
+    # the top half of the 128-bit product is in `0..bound`
+    value =
+        Num.toU128 generated.value * Num.toU128 bound
+        |> Num.shiftRightZfBy 64
+        |> Num.toU64
+
+    { value, seed: generated.seed }
+
+## Generates a list of the given length, generating each element with the given
+## function.
+##
+##     Random.list seed 10 (\s -> Random.below s 100)
+list : Seed, Nat, (Seed -> { value : a, seed : Seed }) -> { value : List a, seed : Seed }
+list = \seed, length, generate ->
+    listHelp (List.withCapacity length) seed length generate
+
+listHelp : List a, Seed, Nat, (Seed -> { value : a, seed : Seed }) -> { value : List a, seed : Seed }
+listHelp = \values, seed, length, generate ->
+    if List.len values < length then
+        generated = generate seed
+
+        listHelp (List.append values generated.value) generated.seed length generate
+    else
+        { value: values, seed }
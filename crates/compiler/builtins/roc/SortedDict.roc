@@ -0,0 +1,547 @@
+interface SortedDict
+    exposes [
+        SortedDict,
+        empty,
+        single,
+        len,
+        isEmpty,
+        get,
+        contains,
+        insert,
+        remove,
+        first,
+        last,
+        walk,
+        walkBackwards,
+        range,
+        toList,
+        fromList,
+        keys,
+        values,
+    ]
+    imports [
+        Bool.{ Bool },
+        Result.{ Result },
+        List,
+        Num.{ Nat },
+    ]
+
+## A dictionary which keeps its keys in order, so it can walk through them in
+## order, find the first and last ones, and find the ones in a range.
+##
+## Keys are ordered by the function the dictionary is created with, which
+## compares two keys like [Num.compare] does. Two keys are the same key when it
+## returns `EQ`.
+##
+##     meetings =
+##         SortedDict.empty Num.compare
+##         |> SortedDict.insert 1500 "Review"
+##         |> SortedDict.insert 930 "Standup"
+##         |> SortedDict.insert 1200 "Lunch"
+##
+##     expect SortedDict.first meetings == Ok (T 930 "Standup")
+##     expect SortedDict.range meetings { start: At 1000, end: Unbounded } == [T 1200 "Lunch", T 1500 "Review"]
+##
+## Getting, inserting and removing take time proportional to the logarithm of
+## the number of entries, which is slower than a [Dict] for large dictionaries.
+## Use a [Dict] unless the order of the keys matters.
+##
+## The entries are stored in a [B-tree](https://en.wikipedia.org/wiki/B-tree),
+## which keeps up to `maxEntries` of them next to each other in each node.
+SortedDict k v := {
+    root : Node k v,
+    size : Nat,
+    compare : k, k -> [LT, EQ, GT],
+}
+
+# A node has its entries in order and, unless it's a leaf, one more child than
+# it has entries. The keys in the child at index i are between the keys of the
+# entries at i - 1 and i. All the leaves are at the same depth, and every node
+# except the root has between minEntries and maxEntries entries.
+Node k v : [Node (List (T k v)) (List (Node k v))]
+
+T k v : [T k v]
+
+maxEntries : Nat
+maxEntries = 15
+
+minEntries : Nat
+minEntries = 7
+
+## Returns an empty dictionary, which orders its keys with the given function.
+##
+##     SortedDict.empty Num.compare
+empty : (k, k -> [LT, EQ, GT]) -> SortedDict k v
+empty = \compare ->
+    @SortedDict { root: Node [] [], size: 0, compare }
+
+## Returns a dictionary containing the key and value provided, which orders its
+## keys with the given function.
+single : k, v, (k, k -> [LT, EQ, GT]) -> SortedDict k v
+single = \key, value, compare ->
+    insert (empty compare) key value
+
+## Returns a dictionary with the keys and values in the list, which orders its
+## keys with the given function. When the list has a key more than once, the
+## dictionary has its last value.
+fromList : List (T k v), (k, k -> [LT, EQ, GT]) -> SortedDict k v
+fromList = \entries, compare ->
+    List.walk entries (empty compare) \dict, T key value -> insert dict key value
+
+## Returns the number of entries in the dictionary.
+len : SortedDict k v -> Nat
+len = \@SortedDict { size } ->
+    size
+
+## Returns [Bool.true] if the dictionary has no entries.
+isEmpty : SortedDict k v -> Bool
+isEmpty = \@SortedDict { size } ->
+    size == 0
+
+## Returns the value for the given key, or [Err KeyNotFound] if the dictionary
+## doesn't have it.
+get : SortedDict k v, k -> Result v [KeyNotFound]
+get = \@SortedDict { root, compare }, key ->
+    getHelp root key compare
+
+getHelp : Node k v, k, (k, k -> [LT, EQ, GT]) -> Result v [KeyNotFound]
+getHelp = \Node entries children, key, compare ->
+    when locate entries key compare is
+        Found index ->
+            (T _ value) = entryAt entries index
+
+            Ok value
+
+        NotFound index ->
+            if List.isEmpty children then
+                Err KeyNotFound
+            else
+                getHelp (childAt children index) key compare
+
+## Returns [Bool.true] if the dictionary has a value for the given key.
+contains : SortedDict k v, k -> Bool
+contains = \dict, key ->
+    when get dict key is
+        Ok _ -> Bool.true
+        Err KeyNotFound -> Bool.false
+
+## Inserts a value at the given key, replacing the value the dictionary had for
+## it, if any.
+insert : SortedDict k v, k, v -> SortedDict k v
+insert = \@SortedDict { root, size, compare }, key, value ->
+    when insertHelp root key value compare is
+        Replaced node ->
+            @SortedDict { root: node, size, compare }
+
+        Added node ->
+            @SortedDict { root: node, size: size + 1, compare }
+
+        Split left middle right ->
+            # The root was full, so the tree grows a level.
+            @SortedDict { root: Node [middle] [left, right], size: size + 1, compare }
+
+Inserted k v : [
+    Replaced (Node k v),
+    Added (Node k v),
+    Split (Node k v) (T k v) (Node k v),
+]
+
+insertHelp : Node k v, k, v, (k, k -> [LT, EQ, GT]) -> Inserted k v
+insertHelp = \Node entries children, key, value, compare ->
+    when locate entries key compare is
+        Found index ->
+            Replaced (Node (List.set entries index (T key value)) children)
+
+        NotFound index ->
+            if List.isEmpty children then
+                splitIfFull (insertAt entries index (T key value)) []
+            else
+                when insertHelp (childAt children index) key value compare is
+                    Replaced child ->
+                        Replaced (Node entries (List.set children index child))
+
+                    Added child ->
+                        Added (Node entries (List.set children index child))
+
+                    Split left middle right ->
+                        newChildren =
+                            children
+                            |> List.set index left
+                            |> insertAt (index + 1) right
+
+                        splitIfFull (insertAt entries index middle) newChildren
+
+# Splits a node which has one entry too many in two, around its middle entry.
+splitIfFull : List (T k v), List (Node k v) -> Inserted k v
+splitIfFull = \entries, children ->
+    if List.len entries > maxEntries then
+        splitEntries = List.split entries minEntries
+        splitChildren = List.split children (minEntries + 1)
+        left = Node splitEntries.before splitChildren.before
+        right = Node (List.dropFirst splitEntries.others) splitChildren.others
+
+        Split left (entryAt splitEntries.others 0) right
+    else
+        Added (Node entries children)
+
+## Removes the value for the given key, if the dictionary has one.
+remove : SortedDict k v, k -> SortedDict k v
+remove = \@SortedDict { root, size, compare }, key ->
+    when removeHelp root key compare is
+        Removed (Node entries children) ->
+            newRoot =
+                if List.isEmpty entries then
+                    # The root's last two children were merged, so the tree shrinks a level.
+                    List.first children |> Result.withDefault (Node [] [])
+                else
+                    Node entries children
+
+            @SortedDict { root: newRoot, size: size - 1, compare }
+
+        KeyNotFound ->
+            @SortedDict { root, size, compare }
+
+removeHelp : Node k v, k, (k, k -> [LT, EQ, GT]) -> [Removed (Node k v), KeyNotFound]
+removeHelp = \Node entries children, key, compare ->
+    when locate entries key compare is
+        Found index ->
+            if List.isEmpty children then
+                Removed (Node (List.dropAt entries index) [])
+            else
+                # Replace the entry with the one just before it, which is the
+                # last entry in the child before it.
+                removed = removeLast (childAt children index)
+                newEntries = List.set entries index removed.entry
+                newChildren = List.set children index removed.node
+
+                Removed (rebalance newEntries newChildren index)
+
+        NotFound index ->
+            if List.isEmpty children then
+                KeyNotFound
+            else
+                when removeHelp (childAt children index) key compare is
+                    Removed child ->
+                        Removed (rebalance entries (List.set children index child) index)
+
+                    KeyNotFound ->
+                        KeyNotFound
+
+removeLast : Node k v -> { node : Node k v, entry : T k v }
+removeLast = \Node entries children ->
+    if List.isEmpty children then
+        lastIndex = List.len entries - 1
+
+        { node: Node (List.dropAt entries lastIndex) [], entry: entryAt entries lastIndex }
+    else
+        lastIndex = List.len children - 1
+        removed = removeLast (childAt children lastIndex)
+        newChildren = List.set children lastIndex removed.node
+
+        { node: rebalance entries newChildren lastIndex, entry: removed.entry }
+
+# After removing an entry from the child at this index, gives it enough entries
+# again, if it needs them, by moving one over from a sibling which has some to
+# spare, or else by merging it with a sibling.
+rebalance : List (T k v), List (Node k v), Nat -> Node k v
+rebalance = \entries, children, index ->
+    hasSpare = \siblingIndex ->
+        when List.get children siblingIndex is
+            Ok (Node siblingEntries _) -> List.len siblingEntries > minEntries
+            Err OutOfBounds -> Bool.false
+
+    (Node childEntries _) = childAt children index
+
+    if List.len childEntries >= minEntries then
+        Node entries children
+    else if index > 0 then
+        if hasSpare (index - 1) then
+            rotateRight entries children (index - 1)
+        else if hasSpare (index + 1) then
+            rotateLeft entries children index
+        else
+            merge entries children (index - 1)
+    else if hasSpare (index + 1) then
+        rotateLeft entries children index
+    else
+        merge entries children index
+
+# Moves the last entry of the child at this index up to the parent, and the
+# parent's entry after it down to the start of the next child.
+rotateRight : List (T k v), List (Node k v), Nat -> Node k v
+rotateRight = \entries, children, index ->
+    (Node leftEntries leftChildren) = childAt children index
+    (Node rightEntries rightChildren) = childAt children (index + 1)
+    newLeft = Node (List.dropLast leftEntries) (List.dropLast leftChildren)
+    newRight =
+        Node
+            (List.prepend rightEntries (entryAt entries index))
+            (
+                when List.last leftChildren is
+                    Ok child -> List.prepend rightChildren child
+                    Err ListWasEmpty -> rightChildren
+            )
+    newEntries = List.set entries index (entryAt leftEntries (List.len leftEntries - 1))
+    newChildren =
+        children
+        |> List.set index newLeft
+        |> List.set (index + 1) newRight
+
+    Node newEntries newChildren
+
+# Moves the first entry of the child after this index up to the parent, and the
+# parent's entry before it down to the end of the child at this index.
+rotateLeft : List (T k v), List (Node k v), Nat -> Node k v
+rotateLeft = \entries, children, index ->
+    (Node leftEntries leftChildren) = childAt children index
+    (Node rightEntries rightChildren) = childAt children (index + 1)
+    newLeft =
+        Node
+            (List.append leftEntries (entryAt entries index))
+            (
+                when List.first rightChildren is
+                    Ok child -> List.append leftChildren child
+                    Err ListWasEmpty -> leftChildren
+            )
+    newRight = Node (List.dropFirst rightEntries) (List.dropFirst rightChildren)
+    newEntries = List.set entries index (entryAt rightEntries 0)
+    newChildren =
+        children
+        |> List.set index newLeft
+        |> List.set (index + 1) newRight
+
+    Node newEntries newChildren
+
+# Merges the child at this index, the parent's entry at this index, and the
+# child after it into one node.
+merge : List (T k v), List (Node k v), Nat -> Node k v
+merge = \entries, children, index ->
+    (Node leftEntries leftChildren) = childAt children index
+    (Node rightEntries rightChildren) = childAt children (index + 1)
+    mergedEntries =
+        leftEntries
+        |> List.append (entryAt entries index)
+        |> List.concat rightEntries
+    merged = Node mergedEntries (List.concat leftChildren rightChildren)
+    newChildren =
+        children
+        |> List.set index merged
+        |> List.dropAt (index + 1)
+
+    Node (List.dropAt entries index) newChildren
+
+## Returns the entry with the smallest key, or [Err SortedDictWasEmpty] if the
+## dictionary has no entries.
+first : SortedDict k v -> Result (T k v) [SortedDictWasEmpty]
+first = \@SortedDict { root } ->
+    firstHelp root
+
+firstHelp : Node k v -> Result (T k v) [SortedDictWasEmpty]
+firstHelp = \Node entries children ->
+    when List.first children is
+        Ok child -> firstHelp child
+        Err ListWasEmpty -> List.first entries |> Result.mapErr \_ -> SortedDictWasEmpty
+
+## Returns the entry with the largest key, or [Err SortedDictWasEmpty] if the
+## dictionary has no entries.
+last : SortedDict k v -> Result (T k v) [SortedDictWasEmpty]
+last = \@SortedDict { root } ->
+    lastHelp root
+
+lastHelp : Node k v -> Result (T k v) [SortedDictWasEmpty]
+lastHelp = \Node entries children ->
+    when List.last children is
+        Ok child -> lastHelp child
+        Err ListWasEmpty -> List.last entries |> Result.mapErr \_ -> SortedDictWasEmpty
+
+## Iterates through the keys and values in the dictionary, in order of their
+## keys, calling the given function with each one.
+##
+##     expect
+##         SortedDict.fromList [T 3 "c", T 1 "a", T 2 "b"] Num.compare
+##         |> SortedDict.walk "" \state, _, v -> Str.concat state v
+##         |> Bool.isEq "abc"
+walk : SortedDict k v, state, (state, k, v -> state) -> state
+walk = \@SortedDict { root }, state, func ->
+    walkHelp root state func
+
+walkHelp : Node k v, state, (state, k, v -> state) -> state
+walkHelp = \Node entries children, state, func ->
+    if List.isEmpty children then
+        List.walk entries state \current, T k v -> func current k v
+    else
+        walkBranch entries children 0 state func
+
+# Walks the child at this index, then the entry at this index, and so on.
+walkBranch : List (T k v), List (Node k v), Nat, state, (state, k, v -> state) -> state
+walkBranch = \entries, children, index, state, func ->
+    afterChild = walkHelp (childAt children index) state func
+
+    when List.get entries index is
+        Ok (T k v) -> walkBranch entries children (index + 1) (func afterChild k v) func
+        Err OutOfBounds -> afterChild
+
+## Like [SortedDict.walk], but from the largest key to the smallest.
+walkBackwards : SortedDict k v, state, (state, k, v -> state) -> state
+walkBackwards = \@SortedDict { root }, state, func ->
+    walkBackwardsHelp root state func
+
+walkBackwardsHelp : Node k v, state, (state, k, v -> state) -> state
+walkBackwardsHelp = \Node entries children, state, func ->
+    if List.isEmpty children then
+        List.walkBackwards entries state \current, T k v -> func current k v
+    else
+        walkBackwardsBranch entries children (List.len entries) state func
+
+# Walks the child at this index, then the entry before it, and so on.
+walkBackwardsBranch : List (T k v), List (Node k v), Nat, state, (state, k, v -> state) -> state
+walkBackwardsBranch = \entries, children, index, state, func ->
+    afterChild = walkBackwardsHelp (childAt children index) state func
+
+    if index == 0 then
+        afterChild
+    else
+        (T k v) = entryAt entries (index - 1)
+
+        walkBackwardsBranch entries children (index - 1) (func afterChild k v) func
+
+## Returns the entries whose keys are in the given range, in order.
+##
+## The range starts `At` a key, to include it, or `After` a key, to leave it
+## out, and ends `At` or `Before` a key. A range which is `Unbounded` at either
+## end goes on to the smallest or largest key.
+##
+##     expect
+##         SortedDict.fromList [T 1 "a", T 2 "b", T 3 "c", T 4 "d"] Num.compare
+##         |> SortedDict.range { start: After 1, end: At 3 }
+##         |> Bool.isEq [T 2 "b", T 3 "c"]
+range : SortedDict k v, { start : [At k, After k, Unbounded], end : [At k, Before k, Unbounded] } -> List (T k v)
+range = \@SortedDict { root, compare }, { start, end } ->
+    isAfterStart = \key ->
+        when start is
+            At startKey -> compare key startKey != LT
+            After startKey -> compare key startKey == GT
+            Unbounded -> Bool.true
+
+    isBeforeEnd = \key ->
+        when end is
+            At endKey -> compare key endKey != GT
+            Before endKey -> compare key endKey == LT
+            Unbounded -> Bool.true
+
+    rangeHelp root isAfterStart isBeforeEnd []
+
+rangeHelp : Node k v, (k -> Bool), (k -> Bool), List (T k v) -> List (T k v)
+rangeHelp = \Node entries children, isAfterStart, isBeforeEnd, list ->
+    rangeBranch entries children isAfterStart isBeforeEnd 0 list
+
+# Adds the entries in range from the child at this index, then the entry at this
+# index, and so on, stopping at the first entry after the end of the range.
+rangeBranch : List (T k v), List (Node k v), (k -> Bool), (k -> Bool), Nat, List (T k v) -> List (T k v)
+rangeBranch = \entries, children, isAfterStart, isBeforeEnd, index, list ->
+    entry = List.get entries index
+
+    # The keys in the child are all smaller than the entry's key, so if that's
+    # before the start of the range, so are they.
+    withChild =
+        when List.get children index is
+            Ok child ->
+                when entry is
+                    Ok (T k _) if !(isAfterStart k) -> list
+                    _ -> rangeHelp child isAfterStart isBeforeEnd list
+
+            Err OutOfBounds ->
+                list
+
+    when entry is
+        Ok (T k v) ->
+            if !(isBeforeEnd k) then
+                withChild
+            else if isAfterStart k then
+                rangeBranch entries children isAfterStart isBeforeEnd (index + 1) (List.append withChild (T k v))
+            else
+                rangeBranch entries children isAfterStart isBeforeEnd (index + 1) withChild
+
+        Err OutOfBounds ->
+            withChild
+
+## Returns the keys and values in the dictionary, in order of their keys.
+toList : SortedDict k v -> List (T k v)
+toList = \dict ->
+    walk dict (List.withCapacity (len dict)) \list, k, v -> List.append list (T k v)
+
+## Returns the keys in the dictionary, in order.
+keys : SortedDict k v -> List k
+keys = \dict ->
+    walk dict (List.withCapacity (len dict)) \list, k, _ -> List.append list k
+
+## Returns the values in the dictionary, in order of their keys.
+values : SortedDict k v -> List v
+values = \dict ->
+    walk dict (List.withCapacity (len dict)) \list, _, v -> List.append list v
+
+# Where the key is in a node's entries: at an index, or before the entry at an
+# index (and so in the child at that index), which is past the last entry if
+# it's bigger than all of them.
+locate : List (T k v), k, (k, k -> [LT, EQ, GT]) -> [Found Nat, NotFound Nat]
+locate = \entries, key, compare ->
+    locateHelp entries key compare 0
+
+locateHelp : List (T k v), k, (k, k -> [LT, EQ, GT]), Nat -> [Found Nat, NotFound Nat]
+locateHelp = \entries, key, compare, index ->
+    when List.get entries index is
+        Ok (T k _) ->
+            when compare key k is
+                LT -> NotFound index
+                EQ -> Found index
+                GT -> locateHelp entries key compare (index + 1)
+
+        Err OutOfBounds ->
+            NotFound index
+
+insertAt : List a, Nat, a -> List a
+insertAt = \list, index, elem ->
+    { before, others } = List.split list index
+
+    before
+    |> List.append elem
+    |> List.concat others
+
+entryAt : List (T k v), Nat -> T k v
+entryAt = \entries, index ->
+    when List.get entries index is
+        Ok entry -> entry
+        Err OutOfBounds -> crash "unreachable state in SortedDict: a node has too few entries. Definitely a standard library bug."
+
+childAt : List (Node k v), Nat -> Node k v
+childAt = \children, index ->
+    when List.get children index is
+        Ok child -> child
+        Err OutOfBounds -> crash "unreachable state in SortedDict: a node has too few children. Definitely a standard library bug."
+
+expect
+    dict =
+        List.range { start: At 1, end: At 100 }
+        |> List.walk (empty Num.compare) \state, n -> insert state n (n * 10)
+
+    keys dict == List.range { start: At 1, end: At 100 } && get dict 42 == Ok 420
+
+expect
+    dict =
+        List.range { start: At 1, end: At 100 }
+        |> List.walk (empty Num.compare) \state, n -> insert state n {}
+        |> \state -> List.walk (List.range { start: At 1, end: At 50 }) state \current, n -> remove current (n * 2)
+
+    len dict == 50 && keys dict == List.range { start: At 1, end: At 99, step: 2 }
+
+expect
+    dict = fromList [T 5 "e", T 1 "a", T 3 "c"] Num.compare
+
+    first dict == Ok (T 1 "a") && last dict == Ok (T 5 "e")
+
+expect
+    dict =
+        List.range { start: At 1, end: At 100 }
+        |> List.walk (empty Num.compare) \state, n -> insert state n {}
+
+    range dict { start: After 10, end: Before 20 } |> List.map (\T k _ -> k) == List.range { start: At 11, end: At 19 }
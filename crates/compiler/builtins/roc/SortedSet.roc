@@ -0,0 +1,115 @@
+interface SortedSet
+    exposes [
+        SortedSet,
+        empty,
+        single,
+        len,
+        isEmpty,
+        insert,
+        remove,
+        contains,
+        first,
+        last,
+        walk,
+        walkBackwards,
+        range,
+        toList,
+        fromList,
+    ]
+    imports [
+        Bool.{ Bool },
+        Result.{ Result },
+        List,
+        Num.{ Nat },
+        SortedDict.{ SortedDict },
+    ]
+
+## A set which keeps its elements in order, so it can walk through them in
+## order, find the first and last ones, and find the ones in a range.
+##
+## Elements are ordered by the function the set is created with, which compares
+## two elements like [Num.compare] does. See [SortedDict] for more.
+SortedSet k := SortedDict k {}
+
+## Returns an empty set, which orders its elements with the given function.
+empty : (k, k -> [LT, EQ, GT]) -> SortedSet k
+empty = \compare -> @SortedSet (SortedDict.empty compare)
+
+## Returns a set containing the element provided, which orders its elements with
+## the given function.
+single : k, (k, k -> [LT, EQ, GT]) -> SortedSet k
+single = \key, compare -> @SortedSet (SortedDict.single key {} compare)
+
+## Returns a set with the elements in the list, which orders its elements with
+## the given function.
+fromList : List k, (k, k -> [LT, EQ, GT]) -> SortedSet k
+fromList = \list, compare ->
+    List.walk list (empty compare) insert
+
+## Returns the number of elements in the set.
+len : SortedSet k -> Nat
+len = \@SortedSet dict -> SortedDict.len dict
+
+## Returns [Bool.true] if the set has no elements.
+isEmpty : SortedSet k -> Bool
+isEmpty = \@SortedSet dict -> SortedDict.isEmpty dict
+
+## Inserts an element into the set, if it doesn't have it already.
+insert : SortedSet k, k -> SortedSet k
+insert = \@SortedSet dict, key -> @SortedSet (SortedDict.insert dict key {})
+
+## Removes an element from the set, if it has it.
+remove : SortedSet k, k -> SortedSet k
+remove = \@SortedSet dict, key -> @SortedSet (SortedDict.remove dict key)
+
+## Returns [Bool.true] if the set has the element.
+contains : SortedSet k, k -> Bool
+contains = \@SortedSet dict, key -> SortedDict.contains dict key
+
+## Returns the smallest element, or [Err SortedSetWasEmpty] if the set has no
+## elements.
+first : SortedSet k -> Result k [SortedSetWasEmpty]
+first = \@SortedSet dict ->
+    when SortedDict.first dict is
+        Ok (T key {}) -> Ok key
+        Err SortedDictWasEmpty -> Err SortedSetWasEmpty
+
+## Returns the largest element, or [Err SortedSetWasEmpty] if the set has no
+## elements.
+last : SortedSet k -> Result k [SortedSetWasEmpty]
+last = \@SortedSet dict ->
+    when SortedDict.last dict is
+        Ok (T key {}) -> Ok key
+        Err SortedDictWasEmpty -> Err SortedSetWasEmpty
+
+## Iterates through the elements in order, calling the given function with each
+## one.
+walk : SortedSet k, state, (state, k -> state) -> state
+walk = \@SortedSet dict, state, func ->
+    SortedDict.walk dict state \current, key, {} -> func current key
+
+## Like [SortedSet.walk], but from the largest element to the smallest.
+walkBackwards : SortedSet k, state, (state, k -> state) -> state
+walkBackwards = \@SortedSet dict, state, func ->
+    SortedDict.walkBackwards dict state \current, key, {} -> func current key
+
+## Returns the elements in the given range, in order. See [SortedDict.range]
+## for how ranges work.
+##
+##     expect
+##         SortedSet.fromList [1, 2, 3, 4, 5] Num.compare
+##         |> SortedSet.range { start: At 2, end: Before 4 }
+##         |> Bool.isEq [2, 3]
+range : SortedSet k, { start : [At k, After k, Unbounded], end : [At k, Before k, Unbounded] } -> List k
+range = \@SortedSet dict, bounds ->
+    SortedDict.range dict bounds
+    |> List.map \T key {} -> key
+
+## Returns the elements in the set, in order.
+toList : SortedSet k -> List k
+toList = \@SortedSet dict -> SortedDict.keys dict
+
+expect
+    set = fromList [3, 1, 2, 3] Num.compare
+
+    toList set == [1, 2, 3] && first set == Ok 1 && last set == Ok 3
@@ -84,6 +84,13 @@ interface Str
         toUtf8,
         fromUtf8,
         fromUtf8Range,
+        toUtf16,
+        fromUtf16,
+        fromUtf16Lossy,
+        toUtf32,
+        fromUtf32,
+        matches,
+        capture,
         startsWith,
         endsWith,
         trim,
@@ -122,7 +129,7 @@ interface Str
         Bool.{ Bool, Eq },
         Result.{ Result },
         List,
-        Num.{ Nat, Num, U8, U16, U32, U64, U128, I8, I16, I32, I64, I128, F32, F64, Dec },
+        Num.{ Nat, Num, Frac, U8, U16, U32, U64, U128, I8, I16, I32, I64, I128, F32, F64, Dec },
     ]
 
 ## Test
@@ -289,6 +296,157 @@ FromUtf8Result : {
 
 fromUtf8RangeLowlevel : List U8, Nat, Nat -> FromUtf8Result
 
+## Returns a [List] of the string's UTF-16 [code units](https://unicode.org/glossary/#code_unit),
+## which is how Windows and JavaScript store text.
+##
+##     expect Str.toUtf16 "Roc" == [82, 111, 99]
+##     expect Str.toUtf16 "鹏" == [40527]
+##     expect Str.toUtf16 "🐦" == [0xD83D, 0xDC26]
+toUtf16 : Str -> List U16
+toUtf16 = \string ->
+    walkScalars string (List.withCapacity (countUtf8Bytes string)) \units, scalar ->
+        if scalar < 0x10000 then
+            List.append units (Num.toU16 scalar)
+        else
+            # scalars beyond the first 65536 take a pair of surrogates
+            offset = scalar - 0x10000
+
+            units
+            |> List.append (Num.toU16 (0xD800 + Num.shiftRightZfBy offset 10))
+            |> List.append (Num.toU16 (0xDC00 + Num.bitwiseAnd offset 0x3FF))
+
+## Converts a [List] of UTF-16 [code units](https://unicode.org/glossary/#code_unit) to a string.
+##
+## Returns [Err UnpairedSurrogate] with the index of the first
+## [surrogate](https://unicode.org/glossary/#surrogate_code_point) which isn't
+## part of a pair, since those don't stand for any character. (Windows file
+## names, for example, can have them.) [Str.fromUtf16Lossy] replaces them
+## instead.
+##
+##     expect Str.fromUtf16 [82, 111, 99] == Ok "Roc"
+##     expect Str.fromUtf16 [0xD83D, 0xDC26] == Ok "🐦"
+##     expect Str.fromUtf16 [82, 0xD83D] == Err (UnpairedSurrogate 1)
+fromUtf16 : List U16 -> Result Str [UnpairedSurrogate Nat]
+fromUtf16 = \units ->
+    fromUtf16Help units (withCapacity (List.len units)) 0 \_, index -> Err (UnpairedSurrogate index)
+
+## Like [Str.fromUtf16], but replaces each unpaired surrogate with the
+## replacement character, `"\u(FFFD)"`.
+##
+##     expect Str.fromUtf16Lossy [82, 0xD83D, 99] == "R\u(FFFD)c"
+fromUtf16Lossy : List U16 -> Str
+fromUtf16Lossy = \units ->
+    replace = \string, _ -> Ok (appendScalarUnsafe string 0xFFFD)
+
+    when fromUtf16Help units (withCapacity (List.len units)) 0 replace is
+        Ok string -> string
+        Err _ -> crash "fromUtf16Lossy found an unpaired surrogate it didn't replace. Definitely a standard library bug."
+
+fromUtf16Help : List U16, Str, Nat, (Str, Nat -> Result Str err) -> Result Str err
+fromUtf16Help = \units, string, index, onUnpaired ->
+    when List.get units index is
+        Err OutOfBounds -> Ok string
+        Ok unit ->
+            if unit < 0xD800 || unit > 0xDFFF then
+                fromUtf16Help units (appendScalarUnsafe string (Num.toU32 unit)) (index + 1) onUnpaired
+            else
+                next = List.get units (index + 1) |> Result.withDefault 0
+
+                if unit <= 0xDBFF && next >= 0xDC00 && next <= 0xDFFF then
+                    high = Num.shiftLeftBy (Num.toU32 (unit - 0xD800)) 10
+                    scalar = 0x10000 + high + Num.toU32 (next - 0xDC00)
+
+                    fromUtf16Help units (appendScalarUnsafe string scalar) (index + 2) onUnpaired
+                else
+                    when onUnpaired string index is
+                        Ok replaced -> fromUtf16Help units replaced (index + 1) onUnpaired
+                        Err problem -> Err problem
+
+## Returns a [List] of the string's UTF-32 [code units](https://unicode.org/glossary/#code_unit).
+## Each one is a whole [scalar value](https://unicode.org/glossary/#unicode_scalar_value),
+## so this is the same as [Str.toScalars].
+##
+##     expect Str.toUtf32 "🐦" == [128038]
+toUtf32 : Str -> List U32
+toUtf32 = \string -> toScalars string
+
+## Converts a [List] of UTF-32 [code units](https://unicode.org/glossary/#code_unit) to a string.
+##
+## Returns [Err InvalidScalar] with the index of the first one which isn't a
+## [scalar value](https://unicode.org/glossary/#unicode_scalar_value): a
+## [surrogate](https://unicode.org/glossary/#surrogate_code_point), or a number
+## above `0x10FFFF`.
+##
+##     expect Str.fromUtf32 [82, 111, 99] == Ok "Roc"
+##     expect Str.fromUtf32 [82, 0xD83D] == Err (InvalidScalar 1)
+fromUtf32 : List U32 -> Result Str [InvalidScalar Nat]
+fromUtf32 = \scalars ->
+    fromUtf32Help scalars (withCapacity (List.len scalars)) 0
+
+fromUtf32Help : List U32, Str, Nat -> Result Str [InvalidScalar Nat]
+fromUtf32Help = \scalars, string, index ->
+    when List.get scalars index is
+        Err OutOfBounds -> Ok string
+        Ok scalar ->
+            if isValidScalar scalar then
+                fromUtf32Help scalars (appendScalarUnsafe string scalar) (index + 1)
+            else
+                Err (InvalidScalar index)
+
+## Returns [Bool.true] if the whole string matches the pattern.
+##
+##     expect Str.matches "roc-lang" "[a-z]+(-[a-z]+)*"
+##     expect !(Str.matches "roc-lang!" "[a-z]+(-[a-z]+)*")
+##
+## The pattern must be a string literal, which is compiled when the program is
+## built. A pattern which can't be compiled is a compile error, and matching
+## doesn't need to compile anything.
+##
+## Patterns are written like regular expressions:
+## - `.` matches any character, and `[abc]`, `[a-z]`, and `[^abc]` match one of
+##   (or none of) the characters in them.
+## - `\d` matches a digit, `\w` a letter, digit, or `_`, and `\s` whitespace;
+##   `\D`, `\W`, and `\S` match any other character. A `\` before any other
+##   punctuation matches it literally. (In a string literal, `\` is written `\\`.)
+## - `*`, `+`, `?`, `{n}`, `{n,}`, and `{n,m}` repeat what comes before them.
+##   Putting a `?` after them makes them match as few times as they can.
+## - `a|b` matches either `a` or `b`, and `(...)` groups. `(?:...)` groups
+##   without capturing, for [Str.capture].
+##
+## Since a pattern always matches the whole string, there's no `^` or `$`.
+matches : Str, Str -> Bool
+
+## Matches the whole string against the pattern, like [Str.matches], and returns
+## what each group in the pattern matched. A group which wasn't part of the
+## match, like the second group in `(a)|(b)` matching `"a"`, matched `""`.
+##
+##     expect Str.capture "roc@roc-lang.org" "(\\w+)@([\\w-]+)\\.org" == Ok ["roc", "roc-lang"]
+##     expect Str.capture "roc" "(\\d+)" == Err NoMatch
+##
+## If more than one match is possible, each repetition matches as many times as
+## it can, or as few times if it's followed by a `?`, starting with the first.
+capture : Str, Str -> Result (List Str) [NoMatch]
+capture = \string, pattern ->
+    positions = captureLowlevel string pattern
+
+    if List.isEmpty positions then
+        Err NoMatch
+    else
+        # skip the whole match, which is the first group
+        Ok (captureHelp string positions 2 (List.withCapacity (List.len positions // 2 - 1)))
+
+captureLowlevel : Str, Str -> List Nat
+
+captureHelp : Str, List Nat, Nat, List Str -> List Str
+captureHelp = \string, positions, index, groups ->
+    when T (List.get positions index) (List.get positions (index + 1)) is
+        T (Ok start) (Ok end) ->
+            group = substringUnsafe string start (end - start)
+
+            captureHelp string positions (index + 2) (List.append groups group)
+
+        _ -> groups
+
 ## Check if the given [Str] starts with a value.
 ##
 ##     expect Str.startsWith "ABC" "A" == Bool.true
@@ -322,27 +480,51 @@ trimRight : Str -> Str
 ##
 ##     expect Str.toDec "10" == Ok 10dec
 ##     expect Str.toDec "-0.25" == Ok -0.25dec
-##     expect Str.toDec "not a number" == Err InvalidNumStr
-toDec : Str -> Result Dec [InvalidNumStr]
-toDec = \string -> strToNumHelp string
+##
+## If the [Str] isn't a [Dec], the error says why:
+##
+##     expect Str.toDec "" == Err EmptyStr
+##     expect Str.toDec "-." == Err NoDigits
+##     expect Str.toDec "1,5" == Err InvalidChar
+##     expect Str.toDec "1e40" == Err InvalidChar
+##     expect Str.toDec "200000000000000000000" == Err OutOfRange
+##     expect Str.toDec "0.0000000000000000001" == Err TooManyDecimals
+toDec : Str -> Result Dec [EmptyStr, NoDigits, InvalidChar, OutOfRange, TooManyDecimals]
+toDec = \string ->
+    result : { berrorcode : U8, aresult : Dec }
+    result = strToNum string
+
+    when result.berrorcode is
+        0 -> Ok result.aresult
+        6 -> Err TooManyDecimals
+        code -> Err (fracErrorFromCode code)
 
 ## Encode a [Str] to a [F64]. A [F64] value is a 64-bit
 ## [floating-point number](https://en.wikipedia.org/wiki/IEEE_754) and can be
 ## specified with a `f64` suffix.
 ##
 ##     expect Str.toF64 "0.10" == Ok 0.10f64
-##     expect Str.toF64 "not a number" == Err InvalidNumStr
-toF64 : Str -> Result F64 [InvalidNumStr]
-toF64 = \string -> strToNumHelp string
+##     expect Str.toF64 "1e-3" == Ok 0.001f64
+##
+## If the [Str] isn't a [F64], the error says why:
+##
+##     expect Str.toF64 "" == Err EmptyStr
+##     expect Str.toF64 "-." == Err NoDigits
+##     expect Str.toF64 "not a number" == Err InvalidChar
+##     expect Str.toF64 "1e400" == Err OutOfRange
+toF64 : Str -> Result F64 [EmptyStr, NoDigits, InvalidChar, OutOfRange]
+toF64 = \string -> strToFloatHelp string
 
 ## Encode a [Str] to a [F32].A [F32] value is a 32-bit
 ## [floating-point number](https://en.wikipedia.org/wiki/IEEE_754) and can be
 ## specified with a `f32` suffix.
 ##
 ##     expect Str.toF32 "0.10" == Ok 0.10f32
-##     expect Str.toF32 "not a number" == Err InvalidNumStr
-toF32 : Str -> Result F32 [InvalidNumStr]
-toF32 = \string -> strToNumHelp string
+##     expect Str.toF32 "not a number" == Err InvalidChar
+##
+## The errors are the same as for [Str.toF64].
+toF32 : Str -> Result F32 [EmptyStr, NoDigits, InvalidChar, OutOfRange]
+toF32 = \string -> strToFloatHelp string
 
 ## Convert a [Str] to a [Nat]. If the given number doesn't fit in [Nat], it will be [truncated](https://www.ualberta.ca/computing-science/media-library/teaching-resources/java/truncation-rounding.html).
 ## [Nat] has a different maximum number depending on the system you're building
@@ -794,6 +976,25 @@ strToNumHelp = \string ->
     else
         Err InvalidNumStr
 
+strToFloatHelp : Str -> Result (Frac a) [EmptyStr, NoDigits, InvalidChar, OutOfRange]
+strToFloatHelp = \string ->
+    result : { berrorcode : U8, aresult : Frac a }
+    result = strToNum string
+
+    if result.berrorcode == 0 then
+        Ok result.aresult
+    else
+        Err (fracErrorFromCode result.berrorcode)
+
+# The error codes the bitcode gives when it can't parse a fraction
+fracErrorFromCode : U8 -> [EmptyStr, NoDigits, InvalidChar, OutOfRange]*
+fracErrorFromCode = \code ->
+    when code is
+        2 -> EmptyStr
+        3 -> NoDigits
+        5 -> OutOfRange
+        _ -> InvalidChar
+
 ## Adds a prefix to the given [Str].
 ##
 ##     expect Str.withPrefix "Awesome" "Roc" == "RocAwesome"
@@ -0,0 +1,204 @@
+interface Time
+    exposes [
+        Utc,
+        Duration,
+        epoch,
+        fromNanosSinceEpoch,
+        toNanosSinceEpoch,
+        fromMillisSinceEpoch,
+        toMillisSinceEpoch,
+        nanoseconds,
+        milliseconds,
+        seconds,
+        minutes,
+        hours,
+        days,
+        toNanoseconds,
+        toMilliseconds,
+        toSeconds,
+        add,
+        sub,
+        since,
+        addDurations,
+        isBefore,
+        isAfter,
+        toIso8601,
+        fromIso8601,
+    ]
+    imports [
+        Bool.{ Bool },
+        Result.{ Result },
+        Str,
+        Num.{ I64, I128, U32, Nat },
+    ]
+
+## A moment in time, stored as the number of nanoseconds since the Unix epoch,
+## midnight UTC on 1 January 1970. Moments before the epoch are negative.
+##
+## Platforms which can tell the time give a [Utc], so a program can use the
+## same functions to do arithmetic on it and format it on any platform:
+##
+##     Time.toIso8601 (Time.fromMillisSinceEpoch 1_700_000_000_000)
+##
+## Leap seconds aren't counted, as in Unix time, so every day is 86,400 seconds
+## long.
+Utc := I128 has [Eq]
+
+## An amount of time, stored as a number of nanoseconds. It's negative when it
+## goes backwards in time.
+Duration := I128 has [Eq]
+
+formatLowlevel : I64, U32 -> Str
+checkLowlevel : Str -> Nat
+parseSecondsLowlevel : Str -> I64
+parseNanosLowlevel : Str -> U32
+
+nanosPerSecond : I128
+nanosPerSecond = 1_000_000_000
+
+nanosPerMilli : I128
+nanosPerMilli = 1_000_000
+
+## Midnight UTC on 1 January 1970.
+epoch : Utc
+epoch = @Utc 0
+
+fromNanosSinceEpoch : I128 -> Utc
+fromNanosSinceEpoch = \nanos -> @Utc nanos
+
+toNanosSinceEpoch : Utc -> I128
+toNanosSinceEpoch = \@Utc nanos -> nanos
+
+fromMillisSinceEpoch : I128 -> Utc
+fromMillisSinceEpoch = \millis -> @Utc (millis * nanosPerMilli)
+
+## Returns the whole number of milliseconds since the epoch, rounding towards
+## the past, so that a moment just before the epoch is `-1`.
+toMillisSinceEpoch : Utc -> I128
+toMillisSinceEpoch = \@Utc nanos -> floorDiv nanos nanosPerMilli
+
+nanoseconds : I128 -> Duration
+nanoseconds = \n -> @Duration n
+
+milliseconds : I128 -> Duration
+milliseconds = \n -> @Duration (n * nanosPerMilli)
+
+seconds : I128 -> Duration
+seconds = \n -> @Duration (n * nanosPerSecond)
+
+minutes : I128 -> Duration
+minutes = \n -> seconds (n * 60)
+
+hours : I128 -> Duration
+hours = \n -> seconds (n * 3600)
+
+## Returns a [Duration] of the given number of 24-hour days.
+days : I128 -> Duration
+days = \n -> seconds (n * 86_400)
+
+toNanoseconds : Duration -> I128
+toNanoseconds = \@Duration n -> n
+
+## Returns the number of whole milliseconds in the [Duration], rounding towards
+## zero.
+toMilliseconds : Duration -> I128
+toMilliseconds = \@Duration n -> Num.divTrunc n nanosPerMilli
+
+## Returns the number of whole seconds in the [Duration], rounding towards zero.
+##
+##     expect Time.toSeconds (Time.milliseconds 2_500) == 2
+toSeconds : Duration -> I128
+toSeconds = \@Duration n -> Num.divTrunc n nanosPerSecond
+
+## Returns the moment the [Duration] after the given one.
+##
+##     expect
+##         Time.add Time.epoch (Time.days 1)
+##         |> Time.toIso8601
+##         == "1970-01-02T00:00:00Z"
+add : Utc, Duration -> Utc
+add = \@Utc nanos, @Duration n -> @Utc (nanos + n)
+
+## Returns the moment the [Duration] before the given one.
+sub : Utc, Duration -> Utc
+sub = \@Utc nanos, @Duration n -> @Utc (nanos - n)
+
+## Returns how long after the second moment the first one is. It's negative if
+## the first moment is before the second.
+##
+##     expect Time.since (Time.fromMillisSinceEpoch 1_500) Time.epoch == Time.milliseconds 1_500
+since : Utc, Utc -> Duration
+since = \@Utc later, @Utc earlier -> @Duration (later - earlier)
+
+addDurations : Duration, Duration -> Duration
+addDurations = \@Duration a, @Duration b -> @Duration (a + b)
+
+isBefore : Utc, Utc -> Bool
+isBefore = \@Utc a, @Utc b -> a < b
+
+isAfter : Utc, Utc -> Bool
+isAfter = \@Utc a, @Utc b -> a > b
+
+## Formats the moment in [ISO 8601](https://en.wikipedia.org/wiki/ISO_8601)'s
+## extended format, with a `Z` to say it's in UTC. The fraction of a second is
+## written in milliseconds, microseconds, or nanoseconds, whichever is shortest,
+## and left out if it's zero.
+##
+##     expect Time.toIso8601 (Time.fromMillisSinceEpoch 1_500) == "1970-01-01T00:00:01.500Z"
+##
+## Years before 0 or after 9999 are written with a sign, like `+10000`.
+toIso8601 : Utc -> Str
+toIso8601 = \@Utc nanos ->
+    formatLowlevel (Num.toI64 (floorDiv nanos nanosPerSecond)) (Num.toU32 (floorRem nanos nanosPerSecond))
+
+## Parses an [ISO 8601](https://en.wikipedia.org/wiki/ISO_8601) date and time,
+## like `2023-04-01T12:30:00Z`, in the extended format
+## [RFC 3339](https://datatracker.ietf.org/doc/html/rfc3339) uses.
+##
+## It must have a `Z` or an offset like `+05:30` at the end, so that it's clear
+## which moment it means; the [Utc] is the same moment in UTC. The seconds can
+## have a fraction, and a space can separate the date and the time instead of
+## the `T`.
+##
+## Returns [Err InvalidIso8601] with the index of the first byte in the [Str]
+## where it stops being a valid date and time. That's the start of the field if
+## the field is out of range, like a 13th month or a 30th of February.
+##
+##     expect
+##         Time.fromIso8601 "2000-01-01T05:30:00+05:30"
+##         == Ok (Time.fromMillisSinceEpoch 946_684_800_000)
+fromIso8601 : Str -> Result Utc [InvalidIso8601 Nat]
+fromIso8601 = \str ->
+    valid = checkLowlevel str
+
+    if valid < Str.countUtf8Bytes str then
+        Err (InvalidIso8601 valid)
+    else
+        secs = Num.toI128 (parseSecondsLowlevel str)
+        nanos = Num.toI128 (parseNanosLowlevel str)
+
+        Ok (@Utc (secs * nanosPerSecond + nanos))
+
+# Num.divTrunc and Num.rem round towards zero, but a moment before the epoch
+# is still in the second (or millisecond) which started before it.
+floorDiv : I128, I128 -> I128
+floorDiv = \a, b ->
+    if a < 0 && Num.rem a b != 0 then
+        Num.divTrunc a b - 1
+    else
+        Num.divTrunc a b
+
+floorRem : I128, I128 -> I128
+floorRem = \a, b ->
+    rem = Num.rem a b
+
+    if rem < 0 then rem + b else rem
+
+expect toMillisSinceEpoch (@Utc -1) == -1
+
+expect
+    moment = add epoch (addDurations (days 365) (milliseconds 123))
+
+    fromIso8601 (toIso8601 moment) == Ok moment
+
+expect fromIso8601 "2023-02-29T00:00:00Z" == Err (InvalidIso8601 8)
@@ -1,3 +1,3 @@
 package "builtins"
-    exposes [Str, Num, Bool, Result, List, Dict, Set, Decode, Encode, Hash, Set, Box, Json]
+    exposes [Str, Num, Bool, Result, List, Dict, Set, Decode, Encode, Hash, Set, Box, Json, Async, Random, SortedDict, SortedSet, Bytes, Encoding, Time]
     packages {}
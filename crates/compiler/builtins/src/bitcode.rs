@@ -329,6 +329,8 @@ pub const NUM_COS: IntrinsicName = float_intrinsic!("roc_builtins.num.cos");
 pub const NUM_ASIN: IntrinsicName = float_intrinsic!("roc_builtins.num.asin");
 pub const NUM_ACOS: IntrinsicName = float_intrinsic!("roc_builtins.num.acos");
 pub const NUM_ATAN: IntrinsicName = float_intrinsic!("roc_builtins.num.atan");
+pub const NUM_IS_NAN: IntrinsicName = float_intrinsic!("roc_builtins.num.is_nan");
+pub const NUM_IS_INFINITE: IntrinsicName = float_intrinsic!("roc_builtins.num.is_infinite");
 pub const NUM_IS_FINITE: IntrinsicName = float_intrinsic!("roc_builtins.num.is_finite");
 pub const NUM_LOG: IntrinsicName = float_intrinsic!("roc_builtins.num.log");
 pub const NUM_POW: IntrinsicName = float_intrinsic!("roc_builtins.num.pow");
@@ -356,6 +358,13 @@ pub const NUM_MUL_CHECKED_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.
 pub const NUM_MUL_CHECKED_FLOAT: IntrinsicName =
     float_intrinsic!("roc_builtins.num.mul_with_overflow");
 
+pub const NUM_POP_COUNT: IntrinsicName = int_intrinsic!("roc_builtins.num.pop_count");
+pub const NUM_LEADING_ZEROS: IntrinsicName = int_intrinsic!("roc_builtins.num.leading_zeros");
+pub const NUM_TRAILING_ZEROS: IntrinsicName = int_intrinsic!("roc_builtins.num.trailing_zeros");
+pub const NUM_ROTATE_LEFT: IntrinsicName = int_intrinsic!("roc_builtins.num.rotate_left");
+pub const NUM_ROTATE_RIGHT: IntrinsicName = int_intrinsic!("roc_builtins.num.rotate_right");
+pub const NUM_SWAP_BYTES: IntrinsicName = int_intrinsic!("roc_builtins.num.swap_bytes");
+
 pub const NUM_BYTES_TO_U16: &str = "roc_builtins.num.bytes_to_u16";
 pub const NUM_BYTES_TO_U32: &str = "roc_builtins.num.bytes_to_u32";
 
@@ -392,8 +401,12 @@ pub const STR_GET_SCALAR_UNSAFE: &str = "roc_builtins.str.get_scalar_unsafe";
 pub const STR_CLONE_TO: &str = "roc_builtins.str.clone_to";
 pub const STR_WITH_CAPACITY: &str = "roc_builtins.str.with_capacity";
 pub const STR_GRAPHEMES: &str = "roc_builtins.str.graphemes";
+pub const STR_FORMAT_NUMBER: &str = "roc_builtins.str.format_number";
+pub const STR_REGEX_MATCHES: &str = "roc_builtins.str.regex_matches";
+pub const STR_REGEX_CAPTURE: &str = "roc_builtins.str.regex_capture";
 
 pub const LIST_MAP: &str = "roc_builtins.list.map";
+pub const LIST_MAP_CONCURRENT: &str = "roc_builtins.list.map_concurrent";
 pub const LIST_MAP2: &str = "roc_builtins.list.map2";
 pub const LIST_MAP3: &str = "roc_builtins.list.map3";
 pub const LIST_MAP4: &str = "roc_builtins.list.map4";
@@ -427,11 +440,45 @@ pub const DEC_SUB_SATURATED: &str = "roc_builtins.dec.sub_saturated";
 pub const DEC_MUL_OR_PANIC: &str = "roc_builtins.dec.mul_or_panic";
 pub const DEC_MUL_SATURATED: &str = "roc_builtins.dec.mul_saturated";
 
+pub const RANDOM_MIX64: &str = "roc_builtins.random.mix64";
+pub const RANDOM_MIX_GAMMA: &str = "roc_builtins.random.mix_gamma";
+pub const RANDOM_TEST_SEED: &str = "roc_builtins.random.test_seed";
+
+pub const BYTES_READ_U16: &str = "roc_builtins.bytes.read_u16";
+pub const BYTES_READ_U32: &str = "roc_builtins.bytes.read_u32";
+pub const BYTES_READ_U64: &str = "roc_builtins.bytes.read_u64";
+
+pub const ENCODING_ENCODE: &str = "roc_builtins.encoding.encode";
+pub const ENCODING_DECODE: &str = "roc_builtins.encoding.decode";
+pub const ENCODING_CHECK: &str = "roc_builtins.encoding.check";
+
+pub const JSON_SKIP_WHITESPACE: &str = "roc_builtins.json.skip_whitespace";
+pub const JSON_SCAN_STRING: &str = "roc_builtins.json.scan_string";
+pub const JSON_SCAN_VALUE: &str = "roc_builtins.json.scan_value";
+pub const JSON_UNESCAPE: &str = "roc_builtins.json.unescape";
+pub const JSON_ESCAPE: &str = "roc_builtins.json.escape";
+
+pub const TIME_FORMAT: &str = "roc_builtins.time.format";
+pub const TIME_CHECK: &str = "roc_builtins.time.check";
+pub const TIME_PARSE_SECONDS: &str = "roc_builtins.time.parse_seconds";
+pub const TIME_PARSE_NANOS: &str = "roc_builtins.time.parse_nanos";
+
 pub const UTILS_TEST_PANIC: &str = "roc_builtins.utils.test_panic";
 pub const UTILS_ALLOCATE_WITH_REFCOUNT: &str = "roc_builtins.utils.allocate_with_refcount";
+pub const UTILS_OUT_OF_MEMORY: &str = "roc_builtins.utils.out_of_memory";
 pub const UTILS_INCREF: &str = "roc_builtins.utils.incref";
 pub const UTILS_DECREF: &str = "roc_builtins.utils.decref";
+pub const UTILS_INCREF_ATOMIC: &str = "roc_builtins.utils.incref_atomic";
+pub const UTILS_DECREF_ATOMIC: &str = "roc_builtins.utils.decref_atomic";
 pub const UTILS_DECREF_CHECK_NULL: &str = "roc_builtins.utils.decref_check_null";
+pub const UTILS_DECREF_CHECK_NULL_ATOMIC: &str = "roc_builtins.utils.decref_check_null_atomic";
+pub const UTILS_DEFAULT_ALLOC: &str = "roc_builtins.utils.default_alloc";
+pub const UTILS_DEFAULT_REALLOC: &str = "roc_builtins.utils.default_realloc";
+pub const UTILS_DEFAULT_DEALLOC: &str = "roc_builtins.utils.default_dealloc";
+pub const UTILS_MIMALLOC_ALLOC: &str = "roc_builtins.utils.mimalloc_alloc";
+pub const UTILS_MIMALLOC_REALLOC: &str = "roc_builtins.utils.mimalloc_realloc";
+pub const UTILS_MIMALLOC_DEALLOC: &str = "roc_builtins.utils.mimalloc_dealloc";
+pub const UTILS_NOOP_DEALLOC: &str = "roc_builtins.utils.noop_dealloc";
 
 pub const UTILS_EXPECT_FAILED_START_SHARED_BUFFER: &str =
     "roc_builtins.utils.expect_failed_start_shared_buffer";
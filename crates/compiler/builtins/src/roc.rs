@@ -15,6 +15,13 @@ pub fn module_source(module_id: ModuleId) -> &'static str {
         ModuleId::DECODE => DECODE,
         ModuleId::HASH => HASH,
         ModuleId::JSON => JSON,
+        ModuleId::ASYNC => ASYNC,
+        ModuleId::RANDOM => RANDOM,
+        ModuleId::SORTED_DICT => SORTED_DICT,
+        ModuleId::SORTED_SET => SORTED_SET,
+        ModuleId::BYTES => BYTES,
+        ModuleId::ENCODING => ENCODING,
+        ModuleId::TIME => TIME,
         _ => panic!(
             "ModuleId {:?} is not part of the standard library",
             module_id
@@ -34,3 +41,10 @@ const ENCODE: &str = include_str!("../roc/Encode.roc");
 const DECODE: &str = include_str!("../roc/Decode.roc");
 const HASH: &str = include_str!("../roc/Hash.roc");
 const JSON: &str = include_str!("../roc/Json.roc");
+const ASYNC: &str = include_str!("../roc/Async.roc");
+const RANDOM: &str = include_str!("../roc/Random.roc");
+const SORTED_DICT: &str = include_str!("../roc/SortedDict.roc");
+const SORTED_SET: &str = include_str!("../roc/SortedSet.roc");
+const BYTES: &str = include_str!("../roc/Bytes.roc");
+const ENCODING: &str = include_str!("../roc/Encoding.roc");
+const TIME: &str = include_str!("../roc/Time.roc");
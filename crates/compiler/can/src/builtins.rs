@@ -91,7 +91,6 @@ macro_rules! map_symbol_to_lowlevel_and_arity {
                 // these are not implemented, not sure why
                 LowLevel::StrFromInt => unimplemented!(),
                 LowLevel::StrFromFloat => unimplemented!(),
-                LowLevel::NumIsFinite => unimplemented!(),
             }
         }
     };
@@ -126,6 +125,8 @@ map_symbol_to_lowlevel_and_arity! {
     StrGetCapacity; STR_CAPACITY; 1,
     StrWithCapacity; STR_WITH_CAPACITY; 1,
     StrGraphemes; STR_GRAPHEMES; 1,
+    StrMatches; STR_MATCHES; 2,
+    StrCapture; STR_CAPTURE_LOWLEVEL; 2,
 
     ListLen; LIST_LEN; 1,
     ListWithCapacity; LIST_WITH_CAPACITY; 1,
@@ -137,6 +138,7 @@ map_symbol_to_lowlevel_and_arity! {
     ListReplaceUnsafe; LIST_REPLACE_UNSAFE; 3,
     ListConcat; LIST_CONCAT; 2,
     ListMap; LIST_MAP; 2,
+    ListMapConcurrent; LIST_MAP_CONCURRENT; 2,
     ListMap2; LIST_MAP2; 3,
     ListMap3; LIST_MAP3; 4,
     ListMap4; LIST_MAP4; 5,
@@ -187,13 +189,41 @@ map_symbol_to_lowlevel_and_arity! {
     NumAsin; NUM_ASIN; 1,
     NumBytesToU16; NUM_BYTES_TO_U16_LOWLEVEL; 2,
     NumBytesToU32; NUM_BYTES_TO_U32_LOWLEVEL; 2,
+    RandomMix64; RANDOM_MIX64_LOWLEVEL; 1,
+    RandomMixGamma; RANDOM_MIX_GAMMA_LOWLEVEL; 1,
+    RandomTestSeed; RANDOM_TEST_SEED_LOWLEVEL; 1,
+    BytesReadU16; BYTES_READ_U16_LOWLEVEL; 3,
+    BytesReadU32; BYTES_READ_U32_LOWLEVEL; 3,
+    BytesReadU64; BYTES_READ_U64_LOWLEVEL; 3,
+    EncodingEncode; ENCODING_ENCODE_LOWLEVEL; 2,
+    EncodingDecode; ENCODING_DECODE_LOWLEVEL; 2,
+    EncodingCheck; ENCODING_CHECK_LOWLEVEL; 2,
+    JsonSkipWhitespace; JSON_SKIP_WHITESPACE_LOWLEVEL; 2,
+    JsonScanString; JSON_SCAN_STRING_LOWLEVEL; 2,
+    JsonScanValue; JSON_SCAN_VALUE_LOWLEVEL; 2,
+    JsonUnescape; JSON_UNESCAPE_LOWLEVEL; 3,
+    JsonEscape; JSON_ESCAPE_LOWLEVEL; 2,
+    TimeFormat; TIME_FORMAT_LOWLEVEL; 2,
+    TimeCheck; TIME_CHECK_LOWLEVEL; 1,
+    TimeParseSeconds; TIME_PARSE_SECONDS_LOWLEVEL; 1,
+    TimeParseNanos; TIME_PARSE_NANOS_LOWLEVEL; 1,
     NumBitwiseAnd; NUM_BITWISE_AND; 2,
     NumBitwiseXor; NUM_BITWISE_XOR; 2,
     NumBitwiseOr; NUM_BITWISE_OR; 2,
     NumShiftLeftBy; NUM_SHIFT_LEFT; 2,
     NumShiftRightBy; NUM_SHIFT_RIGHT; 2,
     NumShiftRightZfBy; NUM_SHIFT_RIGHT_ZERO_FILL; 2,
+    NumPopCount; NUM_POP_COUNT; 1,
+    NumLeadingZeros; NUM_LEADING_ZEROS; 1,
+    NumTrailingZeros; NUM_TRAILING_ZEROS; 1,
+    NumRotateLeftBy; NUM_ROTATE_LEFT; 2,
+    NumRotateRightBy; NUM_ROTATE_RIGHT; 2,
+    NumSwapBytes; NUM_SWAP_BYTES; 1,
+    NumIsNan; NUM_IS_NAN; 1,
+    NumIsInfinite; NUM_IS_INFINITE; 1,
+    NumIsFinite; NUM_IS_FINITE; 1,
     NumToStr; NUM_TO_STR; 1,
+    NumToStrWith; NUM_TO_STR_WITH_LOWLEVEL; 5,
 
     Eq; BOOL_STRUCTURAL_EQ; 2,
     NotEq; BOOL_STRUCTURAL_NOT_EQ; 2,
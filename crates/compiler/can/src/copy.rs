@@ -650,10 +650,12 @@ fn deep_copy_expr_help<C: CopyEnv>(env: &mut C, copied: &mut Vec<Variable>, expr
             loc_condition,
             loc_continuation,
             lookups_in_cond,
+            always,
         } => Expect {
             loc_condition: Box::new(loc_condition.map(|e| go_help!(e))),
             loc_continuation: Box::new(loc_continuation.map(|e| go_help!(e))),
             lookups_in_cond: lookups_in_cond.to_vec(),
+            always: *always,
         },
 
         ExpectFx {
@@ -671,11 +673,13 @@ fn deep_copy_expr_help<C: CopyEnv>(env: &mut C, copied: &mut Vec<Variable>, expr
             loc_continuation,
             variable,
             symbol,
+            label,
         } => Dbg {
             loc_condition: Box::new(loc_condition.map(|e| go_help!(e))),
             loc_continuation: Box::new(loc_continuation.map(|e| go_help!(e))),
             variable: sub!(*variable),
             symbol: *symbol,
+            label: *label,
         },
 
         TypedHole(v) => TypedHole(sub!(*v)),
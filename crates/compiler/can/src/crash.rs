@@ -0,0 +1,155 @@
+//! Finds exposed functions which crash whenever they're called, because every way through their
+//! bodies reaches a `crash`. Such a function is usually unfinished, and shouldn't be shipped to
+//! the modules which use it.
+use crate::expr::{DeclarationTag, Declarations, Expr};
+use roc_collections::VecSet;
+use roc_module::symbol::Symbol;
+use roc_problem::can::Problem;
+use roc_region::all::{Loc, Region};
+
+/// Whether evaluating the expression always reaches a `crash`. If it does, the regions of the
+/// crashes it reaches are added to `crashes`.
+fn always_crashes(loc_expr: &Loc<Expr>, crashes: &mut Vec<Region>) -> bool {
+    let crashes_before = crashes.len();
+    let crashes_here = always_crashes_help(loc_expr, crashes);
+
+    // a branch which crashes doesn't count when a sibling branch doesn't
+    if !crashes_here {
+        crashes.truncate(crashes_before);
+    }
+
+    crashes_here
+}
+
+fn always_crashes_help(loc_expr: &Loc<Expr>, crashes: &mut Vec<Region>) -> bool {
+    use Expr::*;
+
+    match &loc_expr.value {
+        Crash { .. } => {
+            crashes.push(loc_expr.region);
+
+            true
+        }
+        LetNonRec(def, loc_continuation) => {
+            // a function's body isn't evaluated where the function is defined
+            (!matches!(def.loc_expr.value, Closure(_)) && always_crashes(&def.loc_expr, crashes))
+                || always_crashes(loc_continuation, crashes)
+        }
+        LetRec(_, loc_continuation, _) => always_crashes(loc_continuation, crashes),
+        If {
+            branches,
+            final_else,
+            ..
+        } => {
+            // only the first condition is sure to be evaluated
+            let first_condition_crashes = match branches.first() {
+                Some((loc_condition, _)) => always_crashes(loc_condition, crashes),
+                None => false,
+            };
+
+            first_condition_crashes
+                || all_crash(
+                    branches
+                        .iter()
+                        .map(|(_, loc_body)| loc_body)
+                        .chain(std::iter::once(final_else.as_ref())),
+                    crashes,
+                )
+        }
+        When {
+            loc_cond, branches, ..
+        } => {
+            always_crashes(loc_cond, crashes)
+                || all_crash(branches.iter().map(|branch| &branch.value), crashes)
+        }
+        Expect {
+            loc_continuation, ..
+        }
+        | ExpectFx {
+            loc_continuation, ..
+        }
+        | Dbg {
+            loc_continuation, ..
+        } => always_crashes(loc_continuation, crashes),
+        Call(boxed, args, _) => {
+            let (_, loc_function, _, _) = boxed.as_ref();
+
+            any_crashes(
+                std::iter::once(loc_function).chain(args.iter().map(|(_, loc_arg)| loc_arg)),
+                crashes,
+            )
+        }
+        List { loc_elems, .. } => any_crashes(loc_elems.iter(), crashes),
+        Record { fields, .. } => any_crashes(
+            fields.values().map(|field| field.loc_expr.as_ref()),
+            crashes,
+        ),
+        Tuple { elems, .. } => {
+            any_crashes(elems.iter().map(|(_, loc_elem)| loc_elem.as_ref()), crashes)
+        }
+        Tag { arguments, .. } => any_crashes(arguments.iter().map(|(_, loc_arg)| loc_arg), crashes),
+        OpaqueRef { argument, .. } => always_crashes(&argument.1, crashes),
+        RecordAccess { loc_expr, .. } | TupleAccess { loc_expr, .. } => {
+            always_crashes(loc_expr, crashes)
+        }
+        _ => false,
+    }
+}
+
+/// Whether every one of the branches always crashes
+fn all_crash<'a>(branches: impl Iterator<Item = &'a Loc<Expr>>, crashes: &mut Vec<Region>) -> bool {
+    let mut any_branches = false;
+
+    for loc_branch in branches {
+        if !always_crashes(loc_branch, crashes) {
+            return false;
+        }
+
+        any_branches = true;
+    }
+
+    any_branches
+}
+
+/// Whether any of the expressions, which are all evaluated, always crashes
+fn any_crashes<'a>(
+    mut loc_exprs: impl Iterator<Item = &'a Loc<Expr>>,
+    crashes: &mut Vec<Region>,
+) -> bool {
+    loc_exprs.any(|loc_expr| always_crashes(loc_expr, crashes))
+}
+
+/// Problems for the exposed functions which crash whenever they're called
+pub fn unconditional_crash_problems(
+    declarations: &Declarations,
+    exposed_symbols: &VecSet<Symbol>,
+) -> Vec<Problem> {
+    let mut problems = Vec::new();
+
+    for (index, tag) in declarations.declarations.iter().enumerate() {
+        match tag {
+            DeclarationTag::Function(_)
+            | DeclarationTag::Recursive(_)
+            | DeclarationTag::TailRecursive(_) => {
+                let loc_symbol = declarations.symbols[index];
+
+                if !exposed_symbols.contains(&loc_symbol.value) {
+                    continue;
+                }
+
+                let mut crashes = Vec::new();
+
+                if always_crashes(&declarations.expressions[index], &mut crashes) {
+                    problems.push(Problem::UnconditionalCrash {
+                        function: loc_symbol.value,
+                        region: loc_symbol.region,
+                        crashes,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    problems
+}
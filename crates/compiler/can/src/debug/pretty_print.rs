@@ -34,6 +34,7 @@ pub fn pretty_print_declarations(c: &Ctx, declarations: &Declarations) -> String
             }
             DeclarationTag::Expectation => todo!(),
             DeclarationTag::ExpectationFx => todo!(),
+            DeclarationTag::Benchmark => todo!(),
             DeclarationTag::Destructure(_) => todo!(),
             DeclarationTag::MutualRecursion { .. } => todo!(),
         };
@@ -17,7 +17,9 @@ use crate::expr::Declarations;
 use crate::expr::Expr::{self, *};
 use crate::expr::StructAccessorData;
 use crate::expr::{canonicalize_expr, Output, Recursive};
-use crate::pattern::{canonicalize_def_header_pattern, BindingsFromPattern, Pattern};
+use crate::pattern::{
+    canonicalize_def_header_pattern, canonicalize_pattern_synonym, BindingsFromPattern, Pattern,
+};
 use crate::procedure::References;
 use crate::scope::create_alias;
 use crate::scope::{PendingAbilitiesInScope, Scope};
@@ -94,6 +96,7 @@ pub(crate) struct CanDefs {
     dbgs: ExpectsOrDbgs,
     expects: ExpectsOrDbgs,
     expects_fx: ExpectsOrDbgs,
+    benches: ExpectsOrDbgs,
     def_ordering: DefOrdering,
     aliases: VecMap<Symbol, Alias>,
 }
@@ -103,6 +106,8 @@ pub struct ExpectsOrDbgs {
     pub conditions: Vec<Expr>,
     pub regions: Vec<Region>,
     pub preceding_comment: Vec<Region>,
+    /// Which of these are `expect-always`s; never set for anything but expects
+    pub always: Vec<bool>,
 }
 
 impl ExpectsOrDbgs {
@@ -111,13 +116,15 @@ impl ExpectsOrDbgs {
             conditions: Vec::with_capacity(capacity),
             regions: Vec::with_capacity(capacity),
             preceding_comment: Vec::with_capacity(capacity),
+            always: Vec::with_capacity(capacity),
         }
     }
 
-    fn push(&mut self, loc_can_condition: Loc<Expr>, preceding_comment: Region) {
+    fn push(&mut self, loc_can_condition: Loc<Expr>, preceding_comment: Region, always: bool) {
         self.conditions.push(loc_can_condition.value);
         self.regions.push(loc_can_condition.region);
         self.preceding_comment.push(preceding_comment);
+        self.always.push(always);
     }
 }
 
@@ -182,6 +189,14 @@ enum PendingTypeDef<'a> {
         members: Vec<PendingAbilityMember<'a>>,
     },
 
+    /// A pattern synonym, e.g. `pattern Ok2 x = Ok (Ok x)`
+    PatternSynonym {
+        name: Loc<Symbol>,
+        region: Region,
+        arguments: &'a [Loc<ast::Pattern<'a>>],
+        pattern: &'a Loc<ast::Pattern<'a>>,
+    },
+
     /// An invalid alias, that is ignored in the rest of the pipeline
     /// e.g. a definition like `MyAlias 1 : Int`
     /// with an incorrect pattern
@@ -192,7 +207,7 @@ enum PendingTypeDef<'a> {
         region: Region,
     },
 
-    /// An alias with a name that shadows another symbol
+    /// An alias or pattern synonym with a name that shadows another symbol
     ShadowedAlias,
 
     /// An invalid ability, that is ignored in the rest of the pipeline.
@@ -227,6 +242,7 @@ impl PendingTypeDef<'_> {
                 Some((name.value, region))
             }
             PendingTypeDef::Ability { name, .. } => Some((name.value, name.region)),
+            PendingTypeDef::PatternSynonym { name, region, .. } => Some((name.value, *region)),
             PendingTypeDef::InvalidAlias { symbol, region, .. } => Some((*symbol, *region)),
             PendingTypeDef::ShadowedAlias { .. } => None,
             PendingTypeDef::InvalidAbility { symbol, region } => Some((*symbol, *region)),
@@ -947,6 +963,30 @@ pub(crate) fn canonicalize_defs<'a>(
                     members.iter().map(|mem| mem.name.value).collect(),
                 );
             }
+            if let PendingTypeDef::PatternSynonym {
+                name,
+                region,
+                arguments,
+                pattern,
+            } = &pending_type_def
+            {
+                // Value defs can destructure with a synonym, so it has to be in scope before
+                // their patterns are canonicalized. Synonyms go in order, so each can use the
+                // ones before it.
+                let synonym = canonicalize_pattern_synonym(
+                    env,
+                    scope,
+                    &mut output,
+                    name.value,
+                    *region,
+                    arguments,
+                    pattern,
+                );
+
+                if let Some(synonym) = synonym {
+                    scope.add_pattern_synonym(name.value, synonym);
+                }
+            }
             pending_type_defs.push(pending_type_def);
         }
     }
@@ -1024,6 +1064,7 @@ fn canonicalize_value_defs<'a>(
     let mut pending_dbgs = Vec::with_capacity(value_defs.len());
     let mut pending_expects = Vec::with_capacity(value_defs.len());
     let mut pending_expect_fx = Vec::with_capacity(value_defs.len());
+    let mut pending_benches = Vec::with_capacity(value_defs.len());
 
     for loc_pending_def in value_defs {
         match loc_pending_def.value {
@@ -1044,6 +1085,9 @@ fn canonicalize_value_defs<'a>(
             PendingValue::ExpectFx(pending_expect) => {
                 pending_expect_fx.push(pending_expect);
             }
+            PendingValue::Bench(pending_bench) => {
+                pending_benches.push(pending_bench);
+            }
         }
     }
 
@@ -1084,6 +1128,8 @@ fn canonicalize_value_defs<'a>(
     let mut def_ordering = DefOrdering::from_symbol_to_id(env.home, symbol_to_index, capacity);
 
     for (def_id, pending_def) in pending_value_defs.into_iter().enumerate() {
+        scope.show_rebindings_at(pending_def.loc_pattern().region.start());
+
         let temp_output = canonicalize_pending_value_def(
             env,
             pending_def,
@@ -1104,8 +1150,11 @@ fn canonicalize_value_defs<'a>(
     let mut dbgs = ExpectsOrDbgs::with_capacity(pending_dbgs.len());
     let mut expects = ExpectsOrDbgs::with_capacity(pending_expects.len());
     let mut expects_fx = ExpectsOrDbgs::with_capacity(pending_expects.len());
+    let mut benches = ExpectsOrDbgs::with_capacity(pending_benches.len());
 
     for pending in pending_dbgs {
+        scope.show_rebindings_at(pending.condition.region.start());
+
         let (loc_can_condition, can_output) = canonicalize_expr(
             env,
             var_store,
@@ -1114,12 +1163,14 @@ fn canonicalize_value_defs<'a>(
             &pending.condition.value,
         );
 
-        dbgs.push(loc_can_condition, pending.preceding_comment);
+        dbgs.push(loc_can_condition, pending.preceding_comment, false);
 
         output.union(can_output);
     }
 
     for pending in pending_expects {
+        scope.show_rebindings_at(pending.condition.region.start());
+
         let (loc_can_condition, can_output) = canonicalize_expr(
             env,
             var_store,
@@ -1128,12 +1179,14 @@ fn canonicalize_value_defs<'a>(
             &pending.condition.value,
         );
 
-        expects.push(loc_can_condition, pending.preceding_comment);
+        expects.push(loc_can_condition, pending.preceding_comment, pending.always);
 
         output.union(can_output);
     }
 
     for pending in pending_expect_fx {
+        scope.show_rebindings_at(pending.condition.region.start());
+
         let (loc_can_condition, can_output) = canonicalize_expr(
             env,
             var_store,
@@ -1142,7 +1195,23 @@ fn canonicalize_value_defs<'a>(
             &pending.condition.value,
         );
 
-        expects_fx.push(loc_can_condition, pending.preceding_comment);
+        expects_fx.push(loc_can_condition, pending.preceding_comment, false);
+
+        output.union(can_output);
+    }
+
+    for pending in pending_benches {
+        scope.show_rebindings_at(pending.condition.region.start());
+
+        let (loc_can_body, can_output) = canonicalize_expr(
+            env,
+            var_store,
+            scope,
+            pending.condition.region,
+            &pending.condition.value,
+        );
+
+        benches.push(loc_can_body, pending.preceding_comment, false);
 
         output.union(can_output);
     }
@@ -1152,6 +1221,7 @@ fn canonicalize_value_defs<'a>(
         dbgs,
         expects,
         expects_fx,
+        benches,
         def_ordering,
         aliases,
     };
@@ -1241,6 +1311,7 @@ fn canonicalize_type_defs<'a>(
                 type_defs.insert(name.value, TypeDef::Ability(name, members));
             }
             PendingTypeDef::InvalidAlias { .. }
+            | PendingTypeDef::PatternSynonym { .. }
             | PendingTypeDef::InvalidAbility { .. }
             | PendingTypeDef::AbilityShadows
             | PendingTypeDef::ShadowedAlias { .. }
@@ -1562,6 +1633,7 @@ pub(crate) fn sort_can_defs_new(
         dbgs: _,
         expects,
         expects_fx,
+        benches,
         def_ordering,
         aliases,
     } = defs;
@@ -1605,6 +1677,24 @@ pub(crate) fn sort_can_defs_new(
         declarations.push_expect_fx(preceding_comment, name, Loc::at(region, condition));
     }
 
+    let it = benches
+        .conditions
+        .into_iter()
+        .zip(benches.regions)
+        .zip(benches.preceding_comment);
+
+    for ((body, region), preceding_comment) in it {
+        // like an `expect`, a `bench` has no user-defined name
+        let name = scope.gen_unique_symbol();
+
+        declarations.push_bench(
+            preceding_comment,
+            name,
+            Loc::at(region, body),
+            var_store.fresh(),
+        );
+    }
+
     for (symbol, alias) in aliases.into_iter() {
         output.aliases.insert(symbol, alias);
     }
@@ -1799,6 +1889,8 @@ pub(crate) fn sort_can_defs(
         dbgs,
         expects,
         expects_fx,
+        // `bench` blocks only run at the top level of a module
+        benches: _,
         def_ordering,
         aliases,
     } = defs;
@@ -2406,6 +2498,7 @@ pub fn can_defs_with_return<'a>(
 
     // The def as a whole is a tail call iff its return expression is a tail call.
     // Use its output as a starting point because its tail_call already has the right answer!
+    scope.show_rebindings_at(loc_ret.region.start());
     let (ret_expr, mut output) =
         canonicalize_expr(env, var_store, scope, loc_ret.region, &loc_ret.value);
 
@@ -2438,6 +2531,48 @@ pub fn can_defs_with_return<'a>(
     (loc_expr.value, output)
 }
 
+/// The name a def like `x = ...` defines, or None if its pattern isn't a plain identifier
+fn def_identifier<'a>(loc_pattern: &Loc<ast::Pattern<'a>>) -> Option<&'a str> {
+    match loc_pattern.value.extract_spaces().item {
+        ast::Pattern::Identifier(name) => Some(name),
+        _ => None,
+    }
+}
+
+/// In a def block, a def like `x = x + 1` gives a new value to a local that's already in scope,
+/// rather than shadowing it. The def's own body and the defs before it still see the old value;
+/// the defs after it and the block's return expression see the new one. Since only lookups
+/// change, the block stays order-independent, so defs can still refer to later ones.
+///
+/// Returns the pattern for the new value, or None if the def doesn't rebind anything.
+fn canonicalize_rebinding_pattern<'a>(
+    env: &mut Env<'a>,
+    scope: &mut Scope,
+    output: &mut Output,
+    pattern_type: PatternType,
+    loc_pattern: &Loc<ast::Pattern<'a>>,
+    loc_body: &Loc<ast::Expr<'a>>,
+) -> Option<Loc<Pattern>> {
+    if pattern_type != PatternType::DefExpr {
+        return None;
+    }
+
+    let name = def_identifier(loc_pattern)?;
+    let (existing, original_region) = scope.rebindable(name)?;
+
+    let ident = Ident::from(name);
+    let symbol = scope.scopeless_symbol(&ident, loc_pattern.region);
+    scope.rebind(existing, symbol, loc_body.region.end());
+    output.references.insert_bound(symbol);
+
+    env.problem(Problem::Rebinding {
+        original_region,
+        shadow: Loc::at(loc_pattern.region, ident),
+    });
+
+    Some(Loc::at(loc_pattern.region, Pattern::Identifier(symbol)))
+}
+
 fn decl_to_let(decl: Declaration, loc_ret: Loc<Expr>) -> Loc<Expr> {
     match decl {
         Declaration::Declare(def) => {
@@ -2463,10 +2598,14 @@ fn decl_to_let(decl: Declaration, loc_ret: Loc<Expr>) -> Loc<Expr> {
             let conditions = expects.conditions.into_iter().rev();
             let condition_regions = expects.regions.into_iter().rev();
             let expect_regions = expects.preceding_comment.into_iter().rev();
+            let always = expects.always.into_iter().rev();
 
-            let it = expect_regions.zip(condition_regions).zip(conditions);
+            let it = expect_regions
+                .zip(condition_regions)
+                .zip(conditions)
+                .zip(always);
 
-            for ((expect_region, condition_region), condition) in it {
+            for (((expect_region, condition_region), condition), always) in it {
                 let region = Region::span_across(&expect_region, &loc_ret.region);
                 let lookups_in_cond = get_lookup_symbols(&condition);
 
@@ -2474,6 +2613,7 @@ fn decl_to_let(decl: Declaration, loc_ret: Loc<Expr>) -> Loc<Expr> {
                     loc_condition: Box::new(Loc::at(condition_region, condition)),
                     loc_continuation: Box::new(loc_ret),
                     lookups_in_cond,
+                    always,
                 };
 
                 loc_ret = Loc::at(region, expr);
@@ -2597,6 +2737,31 @@ fn to_pending_type_def<'a>(
             AliasKind::Opaque,
         ),
 
+        PatternSynonym {
+            header: TypeHeader { name, vars },
+            pattern,
+        } => {
+            let region = Region::span_across(&name.region, &pattern.region);
+
+            match scope.introduce_without_shadow_symbol(&Ident::from(name.value), region) {
+                Ok(symbol) => PendingTypeDef::PatternSynonym {
+                    name: Loc::at(name.region, symbol),
+                    region,
+                    arguments: vars,
+                    pattern,
+                },
+                Err((_, original_region, shadowed_symbol)) => {
+                    env.problem(Problem::Shadowing {
+                        original_region,
+                        shadow: shadowed_symbol,
+                        kind: ShadowKind::Variable,
+                    });
+
+                    PendingTypeDef::ShadowedAlias
+                }
+            }
+        }
+
         Ability {
             header, members, ..
         } if pattern_type != PatternType::TopLevelDef => {
@@ -2633,10 +2798,19 @@ fn to_pending_type_def<'a>(
                 // Disallow ability type arguments, at least for now.
                 let variables_region = Region::across_all(vars.iter().map(|v| &v.region));
 
-                env.problem(Problem::AbilityHasTypeVariables {
-                    name: name.value,
-                    variables_region,
-                });
+                if vars.len() > 1 {
+                    // Abilities over several types, like `Convert a b`, would need members to
+                    // specialize on more than one type, which the solver can't do yet.
+                    env.problem(Problem::MultiParameterAbility {
+                        name: name.value,
+                        variables_region,
+                    });
+                } else {
+                    env.problem(Problem::AbilityHasTypeVariables {
+                        name: name.value,
+                        variables_region,
+                    });
+                }
                 return PendingTypeDef::InvalidAbility {
                     symbol: name.value,
                     region: name.region,
@@ -2685,12 +2859,15 @@ enum PendingValue<'a> {
     Dbg(PendingExpectOrDbg<'a>),
     Expect(PendingExpectOrDbg<'a>),
     ExpectFx(PendingExpectOrDbg<'a>),
+    Bench(PendingExpectOrDbg<'a>),
     SignatureDefMismatch,
 }
 
 struct PendingExpectOrDbg<'a> {
     condition: &'a Loc<ast::Expr<'a>>,
     preceding_comment: Region,
+    /// Only ever set for an `expect-always`
+    always: bool,
 }
 
 fn to_pending_value_def<'a>(
@@ -2725,18 +2902,29 @@ fn to_pending_value_def<'a>(
             ))
         }
         Body(loc_pattern, loc_expr) => {
-            // This takes care of checking for shadowing and adding idents to scope.
-            let loc_can_pattern = canonicalize_def_header_pattern(
+            let rebinding = canonicalize_rebinding_pattern(
                 env,
-                var_store,
                 scope,
-                pending_abilities_in_scope,
                 output,
                 pattern_type,
-                &loc_pattern.value,
-                loc_pattern.region,
+                loc_pattern,
+                loc_expr,
             );
 
+            // This takes care of checking for shadowing and adding idents to scope.
+            let loc_can_pattern = rebinding.unwrap_or_else(|| {
+                canonicalize_def_header_pattern(
+                    env,
+                    var_store,
+                    scope,
+                    pending_abilities_in_scope,
+                    output,
+                    pattern_type,
+                    &loc_pattern.value,
+                    loc_pattern.region,
+                )
+            });
+
             PendingValue::Def(PendingValueDef::Body(loc_can_pattern, loc_expr))
         }
 
@@ -2754,18 +2942,29 @@ fn to_pending_value_def<'a>(
                 // { x, y } : { x : Int, y ? Bool }*
                 // { x, y ? False } = rec
                 //
-                // This takes care of checking for shadowing and adding idents to scope.
-                let loc_can_pattern = canonicalize_def_header_pattern(
+                let rebinding = canonicalize_rebinding_pattern(
                     env,
-                    var_store,
                     scope,
-                    pending_abilities_in_scope,
                     output,
                     pattern_type,
-                    &body_pattern.value,
-                    body_pattern.region,
+                    body_pattern,
+                    body_expr,
                 );
 
+                // This takes care of checking for shadowing and adding idents to scope.
+                let loc_can_pattern = rebinding.unwrap_or_else(|| {
+                    canonicalize_def_header_pattern(
+                        env,
+                        var_store,
+                        scope,
+                        pending_abilities_in_scope,
+                        output,
+                        pattern_type,
+                        &body_pattern.value,
+                        body_pattern.region,
+                    )
+                });
+
                 PendingValue::Def(PendingValueDef::TypedBody(
                     body_pattern,
                     loc_can_pattern,
@@ -2792,14 +2991,17 @@ fn to_pending_value_def<'a>(
         } => PendingValue::Dbg(PendingExpectOrDbg {
             condition,
             preceding_comment: *preceding_comment,
+            always: false,
         }),
 
         Expect {
             condition,
             preceding_comment,
+            always,
         } => PendingValue::Expect(PendingExpectOrDbg {
             condition,
             preceding_comment: *preceding_comment,
+            always: *always,
         }),
 
         ExpectFx {
@@ -2808,6 +3010,16 @@ fn to_pending_value_def<'a>(
         } => PendingValue::ExpectFx(PendingExpectOrDbg {
             condition,
             preceding_comment: *preceding_comment,
+            always: false,
+        }),
+
+        Bench {
+            body,
+            preceding_comment,
+        } => PendingValue::Bench(PendingExpectOrDbg {
+            condition: body,
+            preceding_comment: *preceding_comment,
+            always: false,
         }),
     }
 }
@@ -1334,6 +1334,40 @@ fn build_effect_loop_inner_body(
     )
 }
 
+/// The call that runs a hosted effect: either into the host, or to the pure Roc implementation
+/// standing in for it when running tests.
+fn host_call(
+    ident: &str,
+    test_double: Option<Symbol>,
+    args: Vec<(Variable, Expr)>,
+    var_store: &mut VarStore,
+) -> Expr {
+    match test_double {
+        None => Expr::ForeignCall {
+            foreign_symbol: format!("roc_fx_{}", ident).into(),
+            args,
+            ret_var: var_store.fresh(),
+        },
+        Some(double) if args.is_empty() => Expr::Var(double, var_store.fresh()),
+        Some(double) => {
+            let fn_var = var_store.fresh();
+
+            Expr::Call(
+                Box::new((
+                    fn_var,
+                    Loc::at_zero(Expr::Var(double, fn_var)),
+                    var_store.fresh(),
+                    var_store.fresh(),
+                )),
+                args.into_iter()
+                    .map(|(var, expr)| (var, Loc::at_zero(expr)))
+                    .collect(),
+                CalledVia::Space,
+            )
+        }
+    }
+}
+
 pub fn build_host_exposed_def(
     scope: &mut Scope,
     symbol: Symbol,
@@ -1341,6 +1375,7 @@ pub fn build_host_exposed_def(
     effect_symbol: Symbol,
     var_store: &mut VarStore,
     annotation: crate::annotation::Annotation,
+    test_double: Option<Symbol>,
 ) -> Def {
     let expr_var = var_store.fresh();
     let pattern = Pattern::Identifier(symbol);
@@ -1381,12 +1416,8 @@ pub fn build_host_exposed_def(
                     linked_symbol_arguments.push((arg_var, Expr::Var(arg_symbol, arg_var)));
                 }
 
-                let foreign_symbol_name = format!("roc_fx_{}", ident);
-                let low_level_call = Expr::ForeignCall {
-                    foreign_symbol: foreign_symbol_name.into(),
-                    args: linked_symbol_arguments,
-                    ret_var: var_store.fresh(),
-                };
+                let low_level_call =
+                    host_call(ident, test_double, linked_symbol_arguments, var_store);
 
                 let effect_closure_symbol = {
                     let name = format!("effect_closure_{}", ident);
@@ -1435,12 +1466,8 @@ pub fn build_host_exposed_def(
             _ => {
                 // not a function
 
-                let foreign_symbol_name = format!("roc_fx_{}", ident);
-                let low_level_call = Expr::ForeignCall {
-                    foreign_symbol: foreign_symbol_name.into(),
-                    args: linked_symbol_arguments,
-                    ret_var: var_store.fresh(),
-                };
+                let low_level_call =
+                    host_call(ident, test_double, linked_symbol_arguments, var_store);
 
                 let effect_closure_symbol = {
                     let name = format!("effect_closure_{}", ident);
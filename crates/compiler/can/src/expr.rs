@@ -9,12 +9,13 @@ use crate::num::{
 };
 use crate::pattern::{canonicalize_pattern, BindingsFromPattern, Pattern, PermitShadows};
 use crate::procedure::References;
+use crate::regex;
 use crate::scope::Scope;
 use crate::traverse::{walk_expr, Visitor};
 use roc_collections::soa::Index;
 use roc_collections::{SendMap, VecMap, VecSet};
 use roc_error_macros::internal_error;
-use roc_module::called_via::CalledVia;
+use roc_module::called_via::{BinOp, CalledVia};
 use roc_module::ident::{ForeignSymbol, Lowercase, TagName};
 use roc_module::low_level::LowLevel;
 use roc_module::symbol::Symbol;
@@ -252,6 +253,8 @@ pub enum Expr {
         loc_condition: Box<Loc<Expr>>,
         loc_continuation: Box<Loc<Expr>>,
         lookups_in_cond: Vec<ExpectLookup>,
+        /// An `expect-always`, which crashes when it fails even in optimized builds
+        always: bool,
     },
 
     // not parsed, but is generated when lowering toplevel effectful expects
@@ -266,6 +269,9 @@ pub enum Expr {
         loc_continuation: Box<Loc<Expr>>,
         variable: Variable,
         symbol: Symbol,
+        /// Where the string literal label printed with it is, like the `"parsed"` in
+        /// `dbg "parsed" x`
+        label: Option<Region>,
     },
 
     /// Rendered as empty box in editor
@@ -287,6 +293,7 @@ pub struct DbgLookup {
     pub symbol: Symbol,
     pub var: Variable,
     pub region: Region,
+    pub label: Option<Region>,
     pub ability_info: Option<SpecializationId>,
 }
 
@@ -402,40 +409,52 @@ pub struct StructAccessorData {
 }
 
 impl StructAccessorData {
-    pub fn to_closure_data(self, record_symbol: Symbol) -> ClosureData {
+    /// Apply the accessor to a record or tuple directly, turning `.foo r` into `r.foo`
+    pub fn to_access(self, loc_struct: Loc<Expr>) -> Expr {
         let StructAccessorData {
-            name,
-            function_var,
             record_var,
-            closure_var,
             ext_var,
             field_var,
             field,
+            ..
         } = self;
 
-        // IDEA: convert accessor from
-        //
-        // .foo
-        //
-        // into
-        //
-        // (\r -> r.foo)
-        let body = match field {
+        match field {
             IndexOrField::Index(index) => Expr::TupleAccess {
                 tuple_var: record_var,
                 ext_var,
                 elem_var: field_var,
-                loc_expr: Box::new(Loc::at_zero(Expr::Var(record_symbol, record_var))),
+                loc_expr: Box::new(loc_struct),
                 index,
             },
             IndexOrField::Field(field) => Expr::RecordAccess {
                 record_var,
                 ext_var,
                 field_var,
-                loc_expr: Box::new(Loc::at_zero(Expr::Var(record_symbol, record_var))),
+                loc_expr: Box::new(loc_struct),
                 field,
             },
-        };
+        }
+    }
+
+    pub fn to_closure_data(self, record_symbol: Symbol) -> ClosureData {
+        let StructAccessorData {
+            name,
+            function_var,
+            record_var,
+            closure_var,
+            field_var,
+            ..
+        } = self;
+
+        // IDEA: convert accessor from
+        //
+        // .foo
+        //
+        // into
+        //
+        // (\r -> r.foo)
+        let body = self.to_access(Loc::at_zero(Expr::Var(record_symbol, record_var)));
 
         let loc_body = Loc::at_zero(body);
 
@@ -889,10 +908,27 @@ pub fn canonicalize_expr<'a>(
                 };
 
                 (crash, output)
+            } else if let ast::Expr::DbgKeyword = loc_fn.value {
+                canonicalize_dbg_apply(env, var_store, scope, region, loc_args, *application_style)
             } else {
                 // Canonicalize the function expression and its arguments
-                let (fn_expr, fn_expr_output) =
-                    canonicalize_expr(env, var_store, scope, fn_region, &loc_fn.value);
+                let (fn_expr, fn_expr_output) = match loc_fn.value {
+                    // Looked up directly, so that a function which takes a pattern isn't reported
+                    // as used without being called. Its pattern is checked below.
+                    ast::Expr::Var { module_name, ident } => {
+                        let (expr, output) = canonicalize_var_lookup(
+                            env,
+                            var_store,
+                            scope,
+                            module_name,
+                            ident,
+                            fn_region,
+                        );
+
+                        (Loc::at(fn_region, expr), output)
+                    }
+                    _ => canonicalize_expr(env, var_store, scope, fn_region, &loc_fn.value),
+                };
 
                 output.union(fn_expr_output);
 
@@ -903,6 +939,10 @@ pub fn canonicalize_expr<'a>(
                     Var(symbol, _) => {
                         output.references.insert_call(symbol);
 
+                        if regex::takes_pattern(symbol) {
+                            compile_pattern_argument(env, var_store, symbol, &mut args);
+                        }
+
                         // we're tail-calling a symbol by name, check if it's the tail-callable symbol
                         output.tail_call = match &env.tailcallable_symbol {
                             Some(tc_sym) if *tc_sym == symbol => Some(symbol),
@@ -965,7 +1005,32 @@ pub fn canonicalize_expr<'a>(
             }
         }
         ast::Expr::Var { module_name, ident } => {
-            canonicalize_var_lookup(env, var_store, scope, module_name, ident, region)
+            let (expr, output) =
+                canonicalize_var_lookup(env, var_store, scope, module_name, ident, region);
+
+            match expr {
+                Var(symbol, _) if regex::takes_pattern(symbol) => {
+                    // Without a call, there's no pattern to compile
+                    env.problem(Problem::RegexPatternNotLiteral {
+                        function: symbol,
+                        region,
+                    });
+
+                    (
+                        Crash {
+                            msg: Box::new(Loc::at(
+                                region,
+                                Expr::Str(
+                                    String::from("hit an uncompiled pattern!").into_boxed_str(),
+                                ),
+                            )),
+                            ret_var: var_store.fresh(),
+                        },
+                        output,
+                    )
+                }
+                _ => (expr, output),
+            }
         }
         ast::Expr::Underscore(name) => {
             // we parse underscores, but they are not valid expression syntax
@@ -995,6 +1060,60 @@ pub fn canonicalize_expr<'a>(
                 Output::default(),
             )
         }
+        ast::Expr::DbgKeyword => {
+            // Like crash, dbg must be applied where it's used.
+            env.problem(Problem::UnappliedDbg { region });
+
+            (
+                Crash {
+                    msg: Box::new(Loc::at(
+                        region,
+                        Expr::Str(String::from("hit an unapplied dbg!").into_boxed_str()),
+                    )),
+                    ret_var: var_store.fresh(),
+                },
+                Output::default(),
+            )
+        }
+        ast::Expr::Return(loc_value) => {
+            // Every `return` in a function body was desugared into an early exit, except ones
+            // which aren't always evaluated before the rest of the body, so this one is somewhere
+            // it can't leave a function from. We still canonicalize what it returns, so the
+            // names used there count as used.
+            env.problem(Problem::MisplacedReturn { region });
+
+            let (_, output) =
+                canonicalize_expr(env, var_store, scope, loc_value.region, &loc_value.value);
+
+            (
+                Crash {
+                    msg: Box::new(Loc::at(
+                        region,
+                        Expr::Str(String::from("hit a misplaced return!").into_boxed_str()),
+                    )),
+                    ret_var: var_store.fresh(),
+                },
+                output,
+            )
+        }
+        ast::Expr::Try(sub_expr) => {
+            // Every `?` which runs before the rest of a function body was desugared into a `when`,
+            // so this one has nothing to return its `Err` from.
+            env.problem(Problem::MisplacedTry { region });
+
+            let (_, output) = canonicalize_expr(env, var_store, scope, region, sub_expr);
+
+            (
+                Crash {
+                    msg: Box::new(Loc::at(
+                        region,
+                        Expr::Str(String::from("hit a misplaced ?").into_boxed_str()),
+                    )),
+                    ret_var: var_store.fresh(),
+                },
+                output,
+            )
+        }
         ast::Expr::Defs(loc_defs, loc_ret) => {
             // The body expression gets a new scope for canonicalization,
             scope.inner_scope(|inner_scope| {
@@ -1180,12 +1299,14 @@ pub fn canonicalize_expr<'a>(
                     loc_condition: Box::new(loc_condition),
                     loc_continuation: Box::new(loc_continuation),
                     lookups_in_cond,
+                    always: false,
                 },
                 output,
             )
         }
         ast::Expr::Dbg(condition, continuation) => {
             let mut output = Output::default();
+            let (label, condition) = dbg_label(condition);
 
             let (loc_condition, output1) =
                 canonicalize_expr(env, var_store, scope, condition.region, &condition.value);
@@ -1215,6 +1336,7 @@ pub fn canonicalize_expr<'a>(
                     loc_continuation: Box::new(loc_continuation),
                     variable: var_store.fresh(),
                     symbol,
+                    label,
                 },
                 output,
             )
@@ -1843,6 +1965,50 @@ fn canonicalize_var_lookup(
     (can_expr, output)
 }
 
+/// Replaces the string literal pattern given to a function like `Str.matches` with its compiled
+/// form, or with a crash if it can't be compiled.
+fn compile_pattern_argument(
+    env: &mut Env<'_>,
+    var_store: &mut VarStore,
+    function: Symbol,
+    args: &mut [(Variable, Loc<Expr>)],
+) {
+    // The pattern comes after the string, so that `str |> Str.matches "[a-z]+"` works
+    let loc_pattern = match args {
+        [_, (_, loc_pattern)] => loc_pattern,
+        // The wrong number of arguments is a type error
+        _ => return,
+    };
+
+    let problem = match &loc_pattern.value {
+        Expr::Str(pattern) => match regex::compile_pattern_for(function, pattern) {
+            Ok(compiled) => {
+                loc_pattern.value = Expr::Str(compiled.into_boxed_str());
+
+                return;
+            }
+            Err(problem) => Problem::InvalidRegex {
+                region: loc_pattern.region,
+                problem,
+            },
+        },
+        _ => Problem::RegexPatternNotLiteral {
+            function,
+            region: loc_pattern.region,
+        },
+    };
+
+    env.problem(problem);
+
+    loc_pattern.value = Expr::Crash {
+        msg: Box::new(Loc::at(
+            loc_pattern.region,
+            Expr::Str(String::from("hit an uncompiled pattern!").into_boxed_str()),
+        )),
+        ret_var: var_store.fresh(),
+    };
+}
+
 /// Currently uses the heuristic of "only inline if it's a builtin"
 pub fn inline_calls(var_store: &mut VarStore, expr: Expr) -> Expr {
     use Expr::*;
@@ -1975,6 +2141,7 @@ pub fn inline_calls(var_store: &mut VarStore, expr: Expr) -> Expr {
             loc_condition,
             loc_continuation,
             lookups_in_cond,
+            always,
         } => {
             let loc_condition = Loc {
                 region: loc_condition.region,
@@ -1990,6 +2157,7 @@ pub fn inline_calls(var_store: &mut VarStore, expr: Expr) -> Expr {
                 loc_condition: Box::new(loc_condition),
                 loc_continuation: Box::new(loc_continuation),
                 lookups_in_cond,
+                always,
             }
         }
 
@@ -2020,6 +2188,7 @@ pub fn inline_calls(var_store: &mut VarStore, expr: Expr) -> Expr {
             loc_continuation,
             variable,
             symbol,
+            label,
         } => {
             let loc_condition = Loc {
                 region: loc_condition.region,
@@ -2036,6 +2205,7 @@ pub fn inline_calls(var_store: &mut VarStore, expr: Expr) -> Expr {
                 loc_continuation: Box::new(loc_continuation),
                 variable,
                 symbol,
+                label,
             }
         }
 
@@ -2616,6 +2786,25 @@ impl Declarations {
         index
     }
 
+    pub fn push_bench(
+        &mut self,
+        preceding_comment: Region,
+        name: Symbol,
+        loc_expr: Loc<Expr>,
+        expr_var: Variable,
+    ) -> usize {
+        let index = self.declarations.len();
+
+        self.declarations.push(DeclarationTag::Benchmark);
+        self.variables.push(expr_var);
+        self.symbols.push(Loc::at(preceding_comment, name));
+        self.annotations.push(None);
+
+        self.expressions.push(loc_expr);
+
+        index
+    }
+
     pub fn push_value_def(
         &mut self,
         symbol: Loc<Symbol>,
@@ -2801,6 +2990,11 @@ impl Declarations {
                     let loc_expr =
                         toplevel_expect_to_inline_expect_fx(self.expressions[index].clone());
 
+                    collector.visit_expr(&loc_expr.value, loc_expr.region, var);
+                }
+                Benchmark => {
+                    let loc_expr = &self.expressions[index];
+
                     collector.visit_expr(&loc_expr.value, loc_expr.region, var);
                 }
             }
@@ -2817,6 +3011,7 @@ pub enum DeclarationTag {
     Value,
     Expectation,
     ExpectationFx,
+    Benchmark,
     Function(Index<Loc<FunctionDef>>),
     Recursive(Index<Loc<FunctionDef>>),
     TailRecursive(Index<Loc<FunctionDef>>),
@@ -2835,6 +3030,7 @@ impl DeclarationTag {
             Function(_) | Recursive(_) | TailRecursive(_) => 1,
             Value => 1,
             Expectation | ExpectationFx => 1,
+            Benchmark => 1,
             Destructure(_) => 1,
             MutualRecursion { length, .. } => length as usize + 1,
         }
@@ -3079,6 +3275,7 @@ fn toplevel_expect_to_inline_expect_help(mut loc_expr: Loc<Expr>, has_effects: b
             loc_condition: Box::new(loc_expr),
             loc_continuation: Box::new(Loc::at_zero(Expr::EmptyRecord)),
             lookups_in_cond,
+            always: false,
         }
     };
 
@@ -3099,6 +3296,135 @@ fn toplevel_expect_to_inline_expect_help(mut loc_expr: Loc<Expr>, has_effects: b
     loc_expr
 }
 
+/// Splits the label off the condition of a `dbg` statement, like `dbg "parsed" x`. It's parsed as
+/// applying the label to the value, since that's what it would be anywhere else.
+fn dbg_label<'a>(condition: &'a Loc<ast::Expr<'a>>) -> (Option<Region>, &'a Loc<ast::Expr<'a>>) {
+    match condition.value {
+        ast::Expr::Apply(loc_label, [loc_value], CalledVia::Space)
+            if matches!(loc_label.value, ast::Expr::Str(_)) =>
+        {
+            (Some(loc_label.region), *loc_value)
+        }
+        _ => (None, condition),
+    }
+}
+
+/// `dbg` applied like a function, as in `dbg x` or `x |> dbg "label"`, prints its value and then
+/// evaluates to it. So it becomes a `dbg` of a fresh symbol bound to the value, continuing with
+/// that same symbol.
+fn canonicalize_dbg_apply<'a>(
+    env: &mut Env<'a>,
+    var_store: &mut VarStore,
+    scope: &mut Scope,
+    region: Region,
+    loc_args: &'a [&'a Loc<ast::Expr<'a>>],
+    called_via: CalledVia,
+) -> (Expr, Output) {
+    let (label, loc_value) = match (loc_args, called_via) {
+        ([loc_value], _) => (None, *loc_value),
+        // `x |> dbg "label"` pipes the value in before the label
+        ([loc_value, loc_label], CalledVia::BinOp(BinOp::Pizza))
+            if matches!(loc_label.value, ast::Expr::Str(_)) =>
+        {
+            (Some(loc_label.region), *loc_value)
+        }
+        ([loc_label, loc_value], _) if matches!(loc_label.value, ast::Expr::Str(_)) => {
+            (Some(loc_label.region), *loc_value)
+        }
+        _ => {
+            let args_region = Region::span_across(
+                &loc_args.first().unwrap().region,
+                &loc_args.last().unwrap().region,
+            );
+            env.problem(Problem::OverAppliedDbg {
+                region: args_region,
+            });
+
+            // Still canonicalize the arguments, so the names used in them count as used.
+            let mut output = Output::default();
+
+            for loc_arg in loc_args.iter() {
+                let (_, arg_out) =
+                    canonicalize_expr(env, var_store, scope, loc_arg.region, &loc_arg.value);
+
+                output.references.union_mut(&arg_out.references);
+            }
+
+            let crash = Expr::Crash {
+                msg: Box::new(Loc::at(
+                    region,
+                    Expr::Str(String::from("hit an overapplied dbg!").into_boxed_str()),
+                )),
+                ret_var: var_store.fresh(),
+            };
+
+            return (crash, output);
+        }
+    };
+
+    let (loc_value, mut output) =
+        canonicalize_expr(env, var_store, scope, loc_value.region, &loc_value.value);
+
+    // the value is printed before it's returned, so a call in it isn't a tail call anymore
+    output.tail_call = None;
+
+    let value_region = loc_value.region;
+    let symbol = scope.gen_unique_symbol();
+    let expr_var = var_store.fresh();
+
+    let mut pattern_vars = SendMap::default();
+    pattern_vars.insert(symbol, expr_var);
+
+    let def = Def {
+        loc_pattern: Loc::at(value_region, Pattern::Identifier(symbol)),
+        loc_expr: loc_value,
+        expr_var,
+        pattern_vars,
+        annotation: None,
+    };
+
+    let dbg = Expr::Dbg {
+        loc_condition: Box::new(Loc::at(value_region, Expr::Var(symbol, expr_var))),
+        loc_continuation: Box::new(Loc::at(region, Expr::Var(symbol, var_store.fresh()))),
+        variable: var_store.fresh(),
+        symbol,
+        label,
+    };
+
+    (
+        Expr::LetNonRec(Box::new(def), Box::new(Loc::at(region, dbg))),
+        output,
+    )
+}
+
+/// Turns the body of a top-level `bench` into the body of a thunk which evaluates it, and then
+/// returns `{}`. That way every benchmark is called the same way, whatever its body evaluates to.
+pub fn toplevel_bench_to_thunk(
+    loc_body: Loc<Expr>,
+    body_var: Variable,
+    result_symbol: Symbol,
+) -> Loc<Expr> {
+    let region = loc_body.region;
+
+    let mut pattern_vars = SendMap::default();
+    pattern_vars.insert(result_symbol, body_var);
+
+    let def = Def {
+        loc_pattern: Loc::at(region, Pattern::Identifier(result_symbol)),
+        loc_expr: loc_body,
+        expr_var: body_var,
+        pattern_vars,
+        annotation: None,
+    };
+
+    let continuation = Loc::at(region, Expr::EmptyRecord);
+
+    Loc::at(
+        region,
+        Expr::LetNonRec(Box::new(def), Box::new(continuation)),
+    )
+}
+
 pub struct ExpectCollector {
     pub expects: VecMap<Region, Vec<ExpectLookup>>,
     pub dbgs: VecMap<Symbol, DbgLookup>,
@@ -3124,12 +3450,14 @@ impl crate::traverse::Visitor for ExpectCollector {
                 loc_condition,
                 variable,
                 symbol,
+                label,
                 ..
             } => {
                 let lookup = DbgLookup {
                     symbol: *symbol,
                     var: *variable,
                     region: loc_condition.region,
+                    label: *label,
                     ability_info: None,
                 };
 
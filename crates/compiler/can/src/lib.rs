@@ -11,6 +11,7 @@ pub mod annotation;
 pub mod builtins;
 pub mod constraint;
 pub mod copy;
+pub mod crash;
 pub mod def;
 mod derive;
 pub mod effect_module;
@@ -19,11 +20,14 @@ pub mod exhaustive;
 pub mod expected;
 pub mod expr;
 pub mod module;
+pub mod naming;
 pub mod num;
 pub mod operator;
 pub mod pattern;
 pub mod procedure;
+mod regex;
 pub mod scope;
+pub mod semantic_tokens;
 pub mod string;
 pub mod traverse;
 
@@ -6,13 +6,14 @@ use crate::env::Env;
 use crate::expr::{
     ClosureData, DbgLookup, Declarations, ExpectLookup, Expr, Output, PendingDerives,
 };
-use crate::pattern::{BindingsFromPattern, Pattern};
+use crate::pattern::{BindingsFromPattern, Pattern, PatternSynonym};
 use crate::scope::Scope;
 use bumpalo::Bump;
 use roc_collections::{MutMap, SendMap, VecMap, VecSet};
 use roc_error_macros::internal_error;
 use roc_module::ident::Ident;
 use roc_module::ident::Lowercase;
+use roc_module::ident::ModuleName;
 use roc_module::symbol::{IdentIds, IdentIdsByModule, ModuleId, ModuleIds, Symbol};
 use roc_parse::ast::{Defs, TypeAnnotation};
 use roc_parse::header::HeaderType;
@@ -134,6 +135,12 @@ pub struct Module {
     pub abilities_store: PendingAbilitiesStore,
     pub loc_expects: VecMap<Region, Vec<ExpectLookup>>,
     pub loc_dbgs: VecMap<Symbol, DbgLookup>,
+    /// exposed values marked `@deprecated`, and what to use instead
+    pub deprecated: MutMap<Symbol, Box<str>>,
+    /// exposed pattern synonyms, which modules importing them expand in their own patterns
+    pub pattern_synonyms: MutMap<Symbol, PatternSynonym>,
+    /// top-level values marked `@fixture`, which `roc test` evaluates once for all the `expect`s
+    pub fixtures: VecSet<Symbol>,
 }
 
 #[derive(Debug, Default)]
@@ -158,6 +165,52 @@ pub struct ModuleOutput {
     pub scope: Scope,
     pub loc_expects: VecMap<Region, Vec<ExpectLookup>>,
     pub loc_dbgs: VecMap<Symbol, DbgLookup>,
+    pub deprecated: MutMap<Symbol, Box<str>>,
+    pub pattern_synonyms: MutMap<Symbol, PatternSynonym>,
+    pub fixtures: VecSet<Symbol>,
+}
+
+/// The top-level values with a line in their doc comments which starts with `tag`, like
+///
+/// ```text
+/// ## @deprecated Use List.first instead.
+/// ```
+///
+/// along with the rest of that line.
+fn doc_tagged_defs<'a>(defs: &Defs<'a>, tag: &str) -> Vec<(&'a str, Region, &'a str)> {
+    use roc_parse::ast::{CommentOrNewline, Pattern, ValueDef};
+
+    let mut tagged = Vec::new();
+
+    for (index, either_index) in defs.tags.iter().enumerate() {
+        let pattern = match either_index.split() {
+            Err(value_index) => match &defs.value_defs[value_index.index()] {
+                ValueDef::Annotation(loc_pattern, _) => &loc_pattern.value,
+                ValueDef::Body(loc_pattern, _) => &loc_pattern.value,
+                ValueDef::AnnotatedBody { ann_pattern, .. } => &ann_pattern.value,
+                _ => continue,
+            },
+            Ok(_) => continue,
+        };
+
+        let name = match pattern {
+            Pattern::Identifier(name) => *name,
+            _ => continue,
+        };
+
+        let rest = defs.spaces[defs.space_before[index].indices()]
+            .iter()
+            .find_map(|space| match space {
+                CommentOrNewline::DocComment(doc) => doc.trim_start().strip_prefix(tag),
+                _ => None,
+            });
+
+        if let Some(rest) = rest {
+            tagged.push((name, defs.regions[index], rest.trim()));
+        }
+    }
+
+    tagged
 }
 
 fn validate_generate_with<'a>(
@@ -268,6 +321,33 @@ fn has_no_implementation(expr: &Expr) -> bool {
     }
 }
 
+/// A hosted module `Foo` can import a `FooDoubles` interface, which exposes a pure Roc
+/// implementation of each of its effects under the same name: `putLine : Str -> {}` standing in
+/// for `putLine : Str -> Effect {}`. When running tests, the effects call those instead of the host.
+fn test_doubles_module(env: &Env, header_type: &HeaderType) -> Option<ModuleId> {
+    match header_type {
+        HeaderType::Hosted { name, .. } => {
+            let doubles_name = ModuleName::from(format!("{}Doubles", name.as_str()));
+            let module_id = env.module_ids.get_id(&doubles_name)?;
+
+            // Only use it if this module actually imports it
+            env.dep_idents.get(&module_id).map(|_| module_id)
+        }
+        _ => None,
+    }
+}
+
+fn lookup_test_double(env: &mut Env, module_id: ModuleId, ident: &str) -> Option<Symbol> {
+    let ident_id = env.dep_idents.get(&module_id)?.get_id(ident)?;
+    let symbol = Symbol::new(module_id, ident_id);
+
+    // Even when the host's implementation is used, the double counts as used, so that importing
+    // the doubles module isn't reported as unused.
+    env.qualified_value_lookups.insert(symbol);
+
+    Some(symbol)
+}
+
 // TODO trim these down
 #[allow(clippy::too_many_arguments)]
 pub fn canonicalize_module_defs<'a>(
@@ -283,7 +363,10 @@ pub fn canonicalize_module_defs<'a>(
     exposed_imports: MutMap<Ident, (Symbol, Region)>,
     exposed_symbols: VecSet<Symbol>,
     symbols_from_requires: &[(Loc<Symbol>, Loc<TypeAnnotation<'a>>)],
+    deprecated_imports: &MutMap<Symbol, Box<str>>,
+    imported_pattern_synonyms: MutMap<Symbol, PatternSynonym>,
     var_store: &mut VarStore,
+    use_test_doubles: bool,
 ) -> ModuleOutput {
     let mut can_exposed_imports = MutMap::default();
     let mut scope = Scope::new(home, exposed_ident_ids, imported_abilities_state);
@@ -300,8 +383,17 @@ pub fn canonicalize_module_defs<'a>(
         );
     }
 
+    for (name, synonym) in imported_pattern_synonyms {
+        scope.add_pattern_synonym(name, synonym);
+    }
+
+    // the rest of an `@deprecated` line tells people using the value what to use instead
+    let deprecated_names = doc_tagged_defs(loc_defs, "@deprecated");
+    let fixture_names = doc_tagged_defs(loc_defs, "@fixture");
+
     let generated_info =
         GeneratedInfo::from_header_type(&mut env, &mut scope, var_store, header_type);
+    let test_doubles = test_doubles_module(&env, header_type);
 
     // Desugar operators (convert them to Apply calls, taking into account
     // operator precedence and associativity rules), before doing other canonicalization.
@@ -342,12 +434,14 @@ pub fn canonicalize_module_defs<'a>(
                 }
             }
         } else {
-            // This is a type alias or ability
+            // This is a type alias, ability, or pattern synonym
 
             // the symbol should already be added to the scope when this module is canonicalized
             debug_assert!(
-                scope.contains_alias(symbol) || scope.abilities_store.is_ability(symbol),
-                "The {:?} is not a type alias or ability known in {:?}",
+                scope.contains_alias(symbol)
+                    || scope.abilities_store.is_ability(symbol)
+                    || scope.pattern_synonyms.contains_key(&symbol),
+                "The {:?} is not a type alias, ability, or pattern synonym known in {:?}",
                 symbol,
                 home
             );
@@ -379,6 +473,25 @@ pub fn canonicalize_module_defs<'a>(
 
     let pending_derives = output.pending_derives;
 
+    let mut deprecated = MutMap::default();
+
+    for (name, region, message) in deprecated_names {
+        match scope.lookup_str(name, region) {
+            Ok(symbol) if exposed_symbols.contains(&symbol) => {
+                deprecated.insert(symbol, message.into());
+            }
+            _ => {}
+        }
+    }
+
+    let mut fixtures = VecSet::default();
+
+    for (name, region, _) in fixture_names {
+        if let Ok(symbol) = scope.lookup_str(name, region) {
+            fixtures.insert(symbol);
+        }
+    }
+
     // See if any of the new idents we defined went unused.
     // If any were unused and also not exposed, report it.
     //
@@ -548,6 +661,10 @@ pub fn canonicalize_module_defs<'a>(
                                 aliases: Default::default(),
                             };
 
+                            let test_double = test_doubles.and_then(|module_id| {
+                                lookup_test_double(&mut env, module_id, &ident)
+                            });
+
                             let hosted_def = crate::effect_module::build_host_exposed_def(
                                 &mut scope,
                                 *symbol,
@@ -555,6 +672,7 @@ pub fn canonicalize_module_defs<'a>(
                                 effect_symbol,
                                 var_store,
                                 annotation,
+                                test_double.filter(|_| use_test_doubles),
                             );
 
                             declarations.update_builtin_def(index, hosted_def);
@@ -606,6 +724,10 @@ pub fn canonicalize_module_defs<'a>(
                                 aliases: Default::default(),
                             };
 
+                            let test_double = test_doubles.and_then(|module_id| {
+                                lookup_test_double(&mut env, module_id, &ident)
+                            });
+
                             let hosted_def = crate::effect_module::build_host_exposed_def(
                                 &mut scope,
                                 *symbol,
@@ -613,6 +735,7 @@ pub fn canonicalize_module_defs<'a>(
                                 effect_symbol,
                                 var_store,
                                 annotation,
+                                test_double.filter(|_| use_test_doubles),
                             );
 
                             declarations.update_builtin_def(index, hosted_def);
@@ -633,6 +756,7 @@ pub fn canonicalize_module_defs<'a>(
             }
             Expectation => { /* ignore */ }
             ExpectationFx => { /* ignore */ }
+            Benchmark => { /* ignore */ }
         }
     }
 
@@ -661,6 +785,17 @@ pub fn canonicalize_module_defs<'a>(
         aliases.insert(symbol, alias);
     }
 
+    let pattern_synonyms: MutMap<Symbol, PatternSynonym> = scope
+        .pattern_synonyms
+        .iter()
+        .filter(|(symbol, _)| exposed_symbols.contains(symbol))
+        .map(|(symbol, synonym)| (*symbol, synonym.clone()))
+        .collect();
+
+    for symbol in pattern_synonyms.keys() {
+        exposed_but_not_defined.remove(symbol);
+    }
+
     for (ability, members) in scope
         .abilities_store
         .iter_abilities()
@@ -783,7 +918,7 @@ pub fn canonicalize_module_defs<'a>(
                     &mut fix_closures_closure_captures,
                 );
             }
-            ExpectationFx => {
+            ExpectationFx | Benchmark => {
                 let loc_expr = &mut declarations.expressions[index];
                 fix_values_captured_in_closure_expr(
                     &mut loc_expr.value,
@@ -796,6 +931,22 @@ pub fn canonicalize_module_defs<'a>(
 
     let collected = declarations.expects();
 
+    if !deprecated_imports.is_empty() {
+        for occurrence in crate::traverse::symbol_occurrences(&declarations) {
+            if let Some(message) = deprecated_imports.get(&occurrence.symbol) {
+                env.problem(Problem::DeprecatedValue {
+                    symbol: occurrence.symbol,
+                    region: occurrence.region,
+                    message: message.clone(),
+                });
+            }
+        }
+    }
+
+    for problem in crate::crash::unconditional_crash_problems(&declarations, &exposed_symbols) {
+        env.problem(problem);
+    }
+
     ModuleOutput {
         scope,
         aliases,
@@ -810,6 +961,9 @@ pub fn canonicalize_module_defs<'a>(
         loc_expects: collected.expects,
         loc_dbgs: collected.dbgs,
         exposed_symbols,
+        deprecated,
+        pattern_synonyms,
+        fixtures,
     }
 }
 
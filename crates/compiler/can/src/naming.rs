@@ -0,0 +1,214 @@
+//! Lints for Roc's naming conventions: defs are camelCase, types and tags are PascalCase, and a
+//! module's name matches its path. They run after canonicalization, and are opt-in, so the
+//! loader keeps their problems apart from the others.
+use crate::expr::{Declarations, Expr};
+use crate::pattern::Pattern;
+use crate::traverse::{walk_expr, walk_pattern, Visitor};
+use roc_collections::{MutMap, MutSet};
+use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+use roc_problem::can::{NameKind, Problem};
+use roc_region::all::{Position, Region};
+use roc_types::subs::Variable;
+use roc_types::types::Alias;
+use std::path::Path;
+
+/// Joins the words of a snake_case name, capitalizing each one after the first, and the first
+/// one too if `capitalize_first` is set.
+fn join_words(name: &str, capitalize_first: bool) -> String {
+    let mut joined = String::with_capacity(name.len());
+
+    for word in name.split('_').filter(|word| !word.is_empty()) {
+        let mut chars = word.chars();
+
+        if let Some(first) = chars.next() {
+            if joined.is_empty() && !capitalize_first {
+                joined.push(first);
+            } else {
+                joined.extend(first.to_uppercase());
+            }
+
+            joined.push_str(chars.as_str());
+        }
+    }
+
+    joined
+}
+
+/// The camelCase version of a name, or None if it's camelCase already
+pub fn camel_case(name: &str) -> Option<String> {
+    let joined = join_words(name, false);
+
+    (joined != name && !joined.is_empty()).then_some(joined)
+}
+
+/// The PascalCase version of a name, or None if it's PascalCase already
+pub fn pascal_case(name: &str) -> Option<String> {
+    let joined = join_words(name, true);
+
+    (joined != name && !joined.is_empty()).then_some(joined)
+}
+
+fn span(start: u32, end: u32) -> Region {
+    Region::new(Position::new(start), Position::new(end))
+}
+
+struct Names<'a> {
+    home: ModuleId,
+    ident_ids: &'a IdentIds,
+    problems: Vec<Problem>,
+    seen_tags: MutSet<String>,
+}
+
+impl<'a> Names<'a> {
+    /// `region` is where the name is, so it needs narrowing for patterns like `x as y`
+    fn def(&mut self, symbol: Symbol, region: Region) {
+        if symbol.module_id() != self.home {
+            return;
+        }
+
+        let name = match self.ident_ids.get_name(symbol.ident_id()) {
+            Some(name) => name,
+            None => return,
+        };
+
+        if let Some(suggestion) = camel_case(name) {
+            self.problems.push(Problem::NamingConvention {
+                kind: NameKind::Def,
+                name: name.into(),
+                region,
+                suggestion: suggestion.into(),
+            });
+        }
+    }
+
+    /// Tags aren't declared anywhere, so each is reported where it first appears
+    fn tag(&mut self, name: &str, region: Region) {
+        if !self.seen_tags.insert(name.to_string()) {
+            return;
+        }
+
+        if let Some(suggestion) = pascal_case(name) {
+            let start = region.start().offset;
+
+            self.problems.push(Problem::NamingConvention {
+                kind: NameKind::Tag,
+                name: name.into(),
+                region: span(start, start + name.len() as u32),
+                suggestion: suggestion.into(),
+            });
+        }
+    }
+}
+
+impl<'a> Visitor for Names<'a> {
+    fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+        if let Expr::Tag { name, .. } | Expr::ZeroArgumentTag { name, .. } = expr {
+            self.tag(name.0.as_str(), region);
+        }
+
+        walk_expr(self, expr, var);
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern, region: Region, _opt_var: Option<Variable>) {
+        match pattern {
+            Pattern::Identifier(symbol) | Pattern::Shadowed(_, _, symbol) => {
+                self.def(*symbol, region);
+            }
+            Pattern::As(_, symbol) => {
+                // the name comes last, as in `{ x } as point`
+                let name_len = self
+                    .ident_ids
+                    .get_name(symbol.ident_id())
+                    .map_or(0, |name| name.len() as u32);
+                let end = region.end().offset;
+
+                self.def(*symbol, span(end - name_len, end));
+            }
+            Pattern::AppliedTag { tag_name, .. } => self.tag(tag_name.0.as_str(), region),
+            _ => {}
+        }
+
+        walk_pattern(self, pattern);
+    }
+}
+
+/// The module name a file at this path should have, going by the last `segments` parts of its
+/// path, or None if those parts couldn't make up a module name
+fn module_name_for_path(module_path: &Path, segments: usize) -> Option<String> {
+    let mut parts: Vec<&str> = Vec::with_capacity(segments);
+
+    parts.push(module_path.file_stem()?.to_str()?);
+
+    for dir in module_path.parent()?.components().rev().take(segments - 1) {
+        parts.push(dir.as_os_str().to_str()?);
+    }
+
+    let is_module_name_part = |part: &&str| {
+        part.starts_with(|ch: char| ch.is_ascii_uppercase())
+            && part.chars().all(|ch| ch.is_ascii_alphanumeric())
+    };
+
+    if parts.len() == segments && parts.iter().all(is_module_name_part) {
+        parts.reverse();
+
+        Some(parts.join("."))
+    } else {
+        None
+    }
+}
+
+/// Whether a module called `module_name` is where it should be. `name_region` is where its
+/// header gives its name.
+pub fn module_name_problem(
+    module_name: &str,
+    module_path: &Path,
+    name_region: Region,
+) -> Option<Problem> {
+    let expected = module_name_for_path(module_path, module_name.split('.').count())?;
+
+    (expected != module_name).then(|| Problem::NamingConvention {
+        kind: NameKind::Module,
+        name: module_name.into(),
+        region: name_region,
+        suggestion: expected.into(),
+    })
+}
+
+/// The naming convention problems in a module's defs, types, and tags, in source order
+pub fn naming_problems(
+    home: ModuleId,
+    ident_ids: &IdentIds,
+    decls: &Declarations,
+    aliases: &MutMap<Symbol, Alias>,
+) -> Vec<Problem> {
+    let mut names = Names {
+        home,
+        ident_ids,
+        problems: Vec::new(),
+        seen_tags: MutSet::default(),
+    };
+
+    for (symbol, alias) in aliases.iter() {
+        if symbol.module_id() != home {
+            continue;
+        }
+
+        if let Some(name) = ident_ids.get_name(symbol.ident_id()) {
+            if let Some(suggestion) = pascal_case(name) {
+                names.problems.push(Problem::NamingConvention {
+                    kind: NameKind::Type,
+                    name: name.into(),
+                    region: alias.region,
+                    suggestion: suggestion.into(),
+                });
+            }
+        }
+    }
+
+    names.visit_decls(decls);
+
+    let mut problems = names.problems;
+    problems.sort_by_key(|problem| problem.region().map(|region| region.start()));
+
+    problems
+}
@@ -6,8 +6,9 @@ use roc_module::called_via::BinOp::Pizza;
 use roc_module::called_via::{BinOp, CalledVia};
 use roc_module::ident::ModuleName;
 use roc_parse::ast::Expr::{self, *};
-use roc_parse::ast::{AssignedField, ValueDef, WhenBranch};
-use roc_region::all::{Loc, Region};
+use roc_parse::ast::{AssignedField, CommentOrNewline, Pattern, TypeDef, ValueDef, WhenBranch};
+use roc_parse::traverse::{walk_expr, walk_pattern, Visitor};
+use roc_region::all::{Loc, Position, Region};
 
 // BinOp precedence logic adapted from Gluon by Markus Westerlind
 // https://github.com/gluon-lang/gluon - license information can be found in
@@ -95,11 +96,13 @@ fn desugar_value_def<'a>(arena: &'a Bump, def: &'a ValueDef<'a>) -> ValueDef<'a>
         Expect {
             condition,
             preceding_comment,
+            always,
         } => {
             let desugared_condition = &*arena.alloc(desugar_expr(arena, condition));
             Expect {
                 condition: desugared_condition,
                 preceding_comment: *preceding_comment,
+                always: *always,
             }
         }
         ExpectFx {
@@ -112,6 +115,16 @@ fn desugar_value_def<'a>(arena: &'a Bump, def: &'a ValueDef<'a>) -> ValueDef<'a>
                 preceding_comment: *preceding_comment,
             }
         }
+        Bench {
+            body,
+            preceding_comment,
+        } => {
+            let desugared_body = &*arena.alloc(desugar_expr(arena, body));
+            Bench {
+                body: desugared_body,
+                preceding_comment: *preceding_comment,
+            }
+        }
     }
 }
 
@@ -138,8 +151,19 @@ pub fn desugar_expr<'a>(arena: &'a Bump, loc_expr: &'a Loc<Expr<'a>>) -> &'a Loc
         | PrecedenceConflict { .. }
         | Tag(_)
         | OpaqueRef(_)
-        | Crash => loc_expr,
+        | Crash
+        | DbgKeyword => loc_expr,
+
+        Try(sub_expr) => {
+            let region = loc_expr.region;
+            let loc_sub_expr = Loc {
+                region,
+                value: **sub_expr,
+            };
+            let value = Try(&desugar_expr(arena, arena.alloc(loc_sub_expr)).value);
 
+            arena.alloc(Loc { region, value })
+        }
         TupleAccess(sub_expr, paths) => {
             let region = loc_expr.region;
             let loc_sub_expr = Loc {
@@ -211,7 +235,10 @@ pub fn desugar_expr<'a>(arena: &'a Bump, loc_expr: &'a Loc<Expr<'a>>) -> &'a Loc
         }
         Closure(loc_patterns, loc_ret) => arena.alloc(Loc {
             region: loc_expr.region,
-            value: Closure(loc_patterns, desugar_expr(arena, loc_ret)),
+            value: Closure(
+                loc_patterns,
+                desugar_returns(arena, desugar_expr(arena, loc_ret), Tail::Function),
+            ),
         }),
         Backpassing(loc_patterns, loc_body, loc_ret) => {
             // loc_patterns <- loc_body
@@ -376,6 +403,776 @@ pub fn desugar_expr<'a>(arena: &'a Bump, loc_expr: &'a Loc<Expr<'a>>) -> &'a Loc
                 region: loc_expr.region,
             })
         }
+        Return(loc_value) => arena.alloc(Loc {
+            value: Return(desugar_expr(arena, loc_value)),
+            region: loc_expr.region,
+        }),
+    }
+}
+
+/// Replace each `return` in a function body with an early exit from it. At the end of the body,
+/// that's just the value it returns, since returning there is the same as ending with that value.
+/// A guard like `if x < 0 then return Err Neg` was parsed as an `if` whose `else` is the code
+/// after it, so its `return` is at the end too.
+///
+/// Anywhere else, the expression with the `return` in it is evaluated to either
+/// `#Continue value` or `#Return value`, and becomes a `when` on that, with the rest of the
+/// function in the `#Continue` branch. Each `?` becomes a `when` on what it tries the same way,
+/// with the rest of the function in the `Ok` branch, and the `Err` returned as it is.
+///
+/// A `return` or `?` which is only evaluated some of the time, like in a `when` guard, is left
+/// alone, and reported during canonicalization. So is one outside of a function.
+fn desugar_returns<'a>(
+    arena: &'a Bump,
+    loc_expr: &'a Loc<Expr<'a>>,
+    tail: Tail,
+) -> &'a Loc<Expr<'a>> {
+    if let Some(taken) = take_try(arena, loc_expr) {
+        let loc_rest = desugar_returns(arena, taken.rest, tail);
+
+        return try_when(arena, &taken, loc_rest, tail);
+    }
+
+    let value = match &loc_expr.value {
+        Return(loc_value) => return desugar_returns(arena, loc_value, tail.returning()),
+        Defs(defs, loc_ret) => {
+            return desugar_try_in_defs(arena, loc_expr.region, defs, loc_ret, tail)
+        }
+        If(if_thens, final_else_branch) => {
+            let mut if_thens_without_returns = Vec::with_capacity_in(if_thens.len(), arena);
+
+            for (condition, then_branch) in if_thens.iter() {
+                if_thens_without_returns
+                    .push((*condition, *desugar_returns(arena, then_branch, tail)));
+            }
+
+            If(
+                if_thens_without_returns.into_bump_slice(),
+                desugar_returns(arena, final_else_branch, tail),
+            )
+        }
+        When(loc_cond_expr, branches) => {
+            let mut branches_without_returns = Vec::with_capacity_in(branches.len(), arena);
+
+            for branch in branches.iter() {
+                branches_without_returns.push(&*arena.alloc(WhenBranch {
+                    value: *desugar_returns(arena, &branch.value, tail),
+                    ..**branch
+                }));
+            }
+
+            When(*loc_cond_expr, branches_without_returns.into_bump_slice())
+        }
+        Expect(condition, continuation) => {
+            Expect(*condition, desugar_returns(arena, continuation, tail))
+        }
+        Dbg(condition, continuation) => Dbg(*condition, desugar_returns(arena, continuation, tail)),
+        _ => return tail.ended(arena, loc_expr),
+    };
+
+    arena.alloc(Loc {
+        value,
+        region: loc_expr.region,
+    })
+}
+
+/// What the expression being desugared by `desugar_returns` is the end of
+#[derive(Clone, Copy)]
+enum Tail {
+    /// The function body, so its value is what the function returns
+    Function,
+    /// An expression taken out of the function body because there's a `return` in it, so its
+    /// value is `#Continue` with what it evaluates to, or `#Return` with what it returns
+    Continue,
+    /// The value returned by a `return` in an expression like that, so it's a `#Return`
+    /// wherever it ends
+    Return,
+}
+
+impl Tail {
+    /// The tail of the value a `return` returns
+    fn returning(self) -> Tail {
+        match self {
+            Tail::Function => Tail::Function,
+            Tail::Continue | Tail::Return => Tail::Return,
+        }
+    }
+
+    /// An expression the tail ends with, other than by returning
+    fn ended<'a>(self, arena: &'a Bump, loc_expr: &'a Loc<Expr<'a>>) -> &'a Loc<Expr<'a>> {
+        match self {
+            Tail::Function => loc_expr,
+            Tail::Continue => tagged(arena, "#Continue", loc_expr),
+            Tail::Return => tagged(arena, "#Return", loc_expr),
+        }
+    }
+
+    /// A value returned from the function
+    fn returned<'a>(self, arena: &'a Bump, loc_expr: &'a Loc<Expr<'a>>) -> &'a Loc<Expr<'a>> {
+        self.returning().ended(arena, loc_expr)
+    }
+}
+
+fn tagged<'a>(arena: &'a Bump, tag: &'a str, loc_expr: &'a Loc<Expr<'a>>) -> &'a Loc<Expr<'a>> {
+    let region = loc_expr.region;
+
+    arena.alloc(Loc::at(
+        region,
+        Apply(
+            arena.alloc(Loc::at(region, Tag(tag))),
+            arena.alloc([loc_expr]),
+            CalledVia::Space,
+        ),
+    ))
+}
+
+/// A `?`, or an expression with a `return` in it, taken out of the expression it was in
+#[derive(Clone, Copy)]
+struct TakenTry<'a> {
+    /// What the `?` was applied to, or the expression with the `return`
+    tried: &'a Loc<Expr<'a>>,
+    /// Where the `?` and what it was applied to are, or the expression with the `return`
+    region: Region,
+    /// The expression it was in, with it replaced by the `Ok` payload or the `#Continue` value
+    rest: &'a Loc<Expr<'a>>,
+    /// Whether this is an expression with a `return` rather than a `?`
+    returns: bool,
+}
+
+/// The names the `Ok` and `Err` payloads of a `?` are bound to. They can't clash with names in
+/// the source, or with those of another `?`, since no two `?`s end in the same place. That's
+/// only true of the regions `take_try` gives the expressions inside suffixes, though; see
+/// `before_suffix`.
+fn try_names<'a>(arena: &'a Bump, region: Region) -> (&'a str, &'a str) {
+    let offset = region.end().offset;
+
+    (
+        arena.alloc_str(&format!("#try{}", offset)),
+        arena.alloc_str(&format!("#err{}", offset)),
+    )
+}
+
+/// The names the `#Continue` and `#Return` payloads of an expression with a `return` are bound
+/// to. An expression can end in the same place as one with a `return` it was taken out of, but
+/// then its `when` is in what the other's is on, where the other's names aren't in scope.
+fn return_names<'a>(arena: &'a Bump, region: Region) -> (&'a str, &'a str) {
+    let offset = region.end().offset;
+
+    (
+        arena.alloc_str(&format!("#continue{}", offset)),
+        arena.alloc_str(&format!("#return{}", offset)),
+    )
+}
+
+/// The region of what a suffix like the `?` in `x?` or the `.field` in `x?.field` is applied to.
+/// The parser doesn't keep those regions, but suffixes can't have spaces before them, so it's the
+/// region of the whole expression without the suffix.
+fn before_suffix(region: Region, suffix_len: usize) -> Region {
+    // expressions made up while desugaring can have regions too small to hold the suffix
+    let end = region.end().offset.saturating_sub(suffix_len as u32);
+
+    Region::new(
+        region.start(),
+        Position::new(end.max(region.start().offset)),
+    )
+}
+
+/// Take out the first `?` which is evaluated before the rest of this expression, so not one in a
+/// branch, closure, or block. A sub-expression with a `return` in it is taken out whole.
+fn take_try<'a>(arena: &'a Bump, loc_expr: &'a Loc<Expr<'a>>) -> Option<TakenTry<'a>> {
+    let region = loc_expr.region;
+    let rest = |value| &*arena.alloc(Loc { region, value });
+
+    match &loc_expr.value {
+        Try(sub_expr) => {
+            let loc_sub_expr = &*arena.alloc(Loc {
+                region: before_suffix(region, "?".len()),
+                value: **sub_expr,
+            });
+
+            // a `?` in what this one tries happens first
+            if let Some(taken) = take_early(arena, loc_sub_expr) {
+                return Some(TakenTry {
+                    rest: rest(Try(&taken.rest.value)),
+                    ..taken
+                });
+            }
+
+            let (ok_name, _) = try_names(arena, region);
+
+            Some(TakenTry {
+                tried: loc_sub_expr,
+                region,
+                rest: rest(Var {
+                    module_name: "",
+                    ident: ok_name,
+                }),
+                returns: false,
+            })
+        }
+        RecordAccess(sub_expr, field) => {
+            let sub_region = before_suffix(region, ".".len() + field.len());
+            let taken = take_early(arena, arena.alloc(Loc::at(sub_region, **sub_expr)))?;
+
+            Some(TakenTry {
+                rest: rest(RecordAccess(&taken.rest.value, *field)),
+                ..taken
+            })
+        }
+        TupleAccess(sub_expr, index) => {
+            let sub_region = before_suffix(region, ".".len() + index.len());
+            let taken = take_early(arena, arena.alloc(Loc::at(sub_region, **sub_expr)))?;
+
+            Some(TakenTry {
+                rest: rest(TupleAccess(&taken.rest.value, *index)),
+                ..taken
+            })
+        }
+        Apply(loc_fn, loc_args, called_via) => {
+            if let Some(taken) = take_early(arena, loc_fn) {
+                return Some(TakenTry {
+                    rest: rest(Apply(taken.rest, *loc_args, *called_via)),
+                    ..taken
+                });
+            }
+
+            let (taken, loc_args) = take_try_in_items(arena, loc_args)?;
+
+            Some(TakenTry {
+                rest: rest(Apply(*loc_fn, loc_args, *called_via)),
+                ..taken
+            })
+        }
+        List(items) => {
+            let (taken, new_items) = take_try_in_items(arena, items.items)?;
+
+            Some(TakenTry {
+                rest: rest(List(items.replace_items(new_items))),
+                ..taken
+            })
+        }
+        Tuple(items) => {
+            let (taken, new_items) = take_try_in_items(arena, items.items)?;
+
+            Some(TakenTry {
+                rest: rest(Tuple(items.replace_items(new_items))),
+                ..taken
+            })
+        }
+        Record(fields) => {
+            for (index, field) in fields.iter().enumerate() {
+                if let AssignedField::RequiredValue(label, spaces, loc_value) = field.value {
+                    if let Some(taken) = take_early(arena, loc_value) {
+                        let mut new_fields = Vec::from_iter_in(fields.iter().copied(), arena);
+                        new_fields[index].value =
+                            AssignedField::RequiredValue(label, spaces, taken.rest);
+
+                        return Some(TakenTry {
+                            rest: rest(Record(fields.replace_items(new_fields.into_bump_slice()))),
+                            ..taken
+                        });
+                    }
+                }
+            }
+
+            None
+        }
+        If(if_thens, final_else_branch) => {
+            // only the first condition is always evaluated
+            let (condition, then_branch) = if_thens.first()?;
+            let taken = take_early(arena, condition)?;
+
+            let mut new_if_thens = Vec::from_iter_in(if_thens.iter().copied(), arena);
+            new_if_thens[0] = (*taken.rest, *then_branch);
+
+            Some(TakenTry {
+                rest: rest(If(new_if_thens.into_bump_slice(), *final_else_branch)),
+                ..taken
+            })
+        }
+        When(loc_cond_expr, branches) => {
+            let taken = take_early(arena, loc_cond_expr)?;
+
+            Some(TakenTry {
+                rest: rest(When(taken.rest, *branches)),
+                ..taken
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Take out this sub-expression whole if there's a `return` in it, or else its first `?`
+fn take_early<'a>(arena: &'a Bump, loc_expr: &'a Loc<Expr<'a>>) -> Option<TakenTry<'a>> {
+    if !can_return(loc_expr) {
+        return take_try(arena, loc_expr);
+    }
+
+    let region = loc_expr.region;
+    let (continue_name, _) = return_names(arena, region);
+
+    Some(TakenTry {
+        tried: loc_expr,
+        region,
+        rest: arena.alloc(Loc::at(
+            region,
+            Var {
+                module_name: "",
+                ident: continue_name,
+            },
+        )),
+        returns: true,
+    })
+}
+
+/// Whether there's a `return` in this expression, other than in a closure
+fn can_return(loc_expr: &Loc<Expr<'_>>) -> bool {
+    let mut finder = ReturnFinder { found: false };
+
+    finder.visit_expr(&loc_expr.value, loc_expr.region);
+
+    finder.found
+}
+
+struct ReturnFinder {
+    found: bool,
+}
+
+impl<'a> Visitor<'a> for ReturnFinder {
+    fn visit_expr(&mut self, expr: &Expr<'a>, region: Region) {
+        match expr {
+            Return(_) => self.found = true,
+            Closure(..) => {}
+            _ => walk_expr(self, expr, region),
+        }
+    }
+}
+
+fn take_try_in_items<'a>(
+    arena: &'a Bump,
+    items: &'a [&'a Loc<Expr<'a>>],
+) -> Option<(TakenTry<'a>, &'a [&'a Loc<Expr<'a>>])> {
+    for (index, item) in items.iter().enumerate() {
+        if let Some(taken) = take_early(arena, item) {
+            let mut new_items = Vec::from_iter_in(items.iter().copied(), arena);
+            new_items[index] = taken.rest;
+
+            return Some((taken, new_items.into_bump_slice()));
+        }
+    }
+
+    None
+}
+
+/// `when tried is Ok a -> rest; Err e -> Err e`, or for an expression with a `return` in it,
+/// `when tried is #Continue a -> rest; #Return r -> r`. It's all at the region of what was
+/// taken, so that's where problems with it are reported.
+fn try_when<'a>(
+    arena: &'a Bump,
+    taken: &TakenTry<'a>,
+    loc_rest: &'a Loc<Expr<'a>>,
+    tail: Tail,
+) -> &'a Loc<Expr<'a>> {
+    let region = taken.region;
+    let (ok_tag, err_tag, (ok_name, err_name)) = if taken.returns {
+        ("#Continue", "#Return", return_names(arena, region))
+    } else {
+        ("Ok", "Err", try_names(arena, region))
+    };
+
+    let tag_pattern = |tag, name| {
+        Loc::at(
+            region,
+            Pattern::Apply(
+                arena.alloc(Loc::at(region, Pattern::Tag(tag))),
+                arena.alloc([Loc::at(region, Pattern::Identifier(name))]),
+            ),
+        )
+    };
+
+    let ok_branch = WhenBranch {
+        patterns: arena.alloc([tag_pattern(ok_tag, ok_name)]),
+        value: *loc_rest,
+        guard: None,
+    };
+
+    let err_payload = &*arena.alloc(Loc::at(
+        region,
+        Var {
+            module_name: "",
+            ident: err_name,
+        },
+    ));
+    let err_value = if taken.returns {
+        err_payload
+    } else {
+        tagged(arena, "Err", err_payload)
+    };
+    let err_branch = WhenBranch {
+        patterns: arena.alloc([tag_pattern(err_tag, err_name)]),
+        value: *tail.returned(arena, err_value),
+        guard: None,
+    };
+
+    let branches = arena.alloc([&*arena.alloc(ok_branch), &*arena.alloc(err_branch)]);
+
+    let tried = if taken.returns {
+        desugar_returns(arena, taken.tried, Tail::Continue)
+    } else {
+        taken.tried
+    };
+
+    arena.alloc(Loc::at(region, When(tried, branches)))
+}
+
+/// Desugar the `?`s in a block's defs. Everything that depends on what a `?` tries has to wait
+/// until that's known to be `Ok`, so those defs and the return expression go in the `Ok` branch
+/// of a `when` on it, as a block of their own. The defs which don't depend on it stay where they
+/// were. Defs only ever move into a block nested in the one with the defs they depend on, so a
+/// def can still refer to any def it could before, including ones after it. A def whose value has
+/// a `return` in it is taken out the same way, with what depends on it in the `#Continue` branch.
+fn desugar_try_in_defs<'a>(
+    arena: &'a Bump,
+    region: Region,
+    defs: &'a roc_parse::ast::Defs<'a>,
+    loc_ret: &'a Loc<Expr<'a>>,
+    tail: Tail,
+) -> &'a Loc<Expr<'a>> {
+    let mut block = Vec::from_iter_in(
+        defs.defs().enumerate().map(|(index, def)| BlockDef {
+            def,
+            region: defs.regions[index],
+            spaces_before: &defs.spaces[defs.space_before[index].indices()],
+            spaces_after: &defs.spaces[defs.space_after[index].indices()],
+        }),
+        arena,
+    );
+
+    // Each `?` taken out of the block, with the defs that didn't have to wait for it
+    let mut layers = Vec::new_in(arena);
+
+    loop {
+        let tries = Vec::from_iter_in(
+            block
+                .iter()
+                .enumerate()
+                .filter_map(|(index, def)| Some((index, def.take_try(arena)?))),
+            arena,
+        );
+
+        let waiting = waiting_on_try(arena, &block);
+
+        // Take the first `?` which doesn't need a value that's waiting on another one. If they
+        // all do, there's a cycle, which canonicalization reports.
+        let (index, (value_def, taken)) = match tries
+            .iter()
+            .find(|(index, (_, taken))| {
+                !needs_waiting(arena, &block, &waiting, *index, taken.tried)
+            })
+            .or_else(|| tries.first())
+        {
+            Some(found) => *found,
+            None => break,
+        };
+
+        let mut outer = Vec::new_in(arena);
+        let mut inner = Vec::new_in(arena);
+
+        for (other_index, other) in block.iter().enumerate() {
+            if other_index == index {
+                inner.push(BlockDef {
+                    def: Err(&*arena.alloc(value_def)),
+                    ..*other
+                });
+            } else if waiting[other_index] {
+                inner.push(*other);
+            } else {
+                outer.push(*other);
+            }
+        }
+
+        layers.push((outer, taken));
+        block = inner;
+    }
+
+    if layers.is_empty() {
+        return arena.alloc(Loc::at(
+            region,
+            Defs(defs, desugar_returns(arena, loc_ret, tail)),
+        ));
+    }
+
+    let mut loc_expr = desugar_returns(arena, loc_ret, tail);
+
+    if !block.is_empty() {
+        loc_expr = arena.alloc(Loc::at(region, Defs(to_defs(arena, &block), loc_expr)));
+    }
+
+    for (outer, taken) in layers.into_iter().rev() {
+        loc_expr = try_when(arena, &taken, loc_expr, tail);
+
+        if !outer.is_empty() {
+            loc_expr = arena.alloc(Loc::at(region, Defs(to_defs(arena, &outer), loc_expr)));
+        }
+    }
+
+    loc_expr
+}
+
+/// A def of a block being split up by `desugar_try_in_defs`
+#[derive(Clone, Copy)]
+struct BlockDef<'a> {
+    def: Result<&'a TypeDef<'a>, &'a ValueDef<'a>>,
+    region: Region,
+    spaces_before: &'a [CommentOrNewline<'a>],
+    spaces_after: &'a [CommentOrNewline<'a>],
+}
+
+impl<'a> BlockDef<'a> {
+    /// The value def with the first `?` in its value taken out, and that `?`, or with its whole
+    /// value taken out if there's a `return` in it
+    fn take_try(&self, arena: &'a Bump) -> Option<(ValueDef<'a>, TakenTry<'a>)> {
+        let value_def = self.def.err()?;
+
+        match *value_def {
+            ValueDef::Body(loc_pattern, loc_body) => {
+                let taken = take_early(arena, loc_body)?;
+
+                Some((ValueDef::Body(loc_pattern, taken.rest), taken))
+            }
+            ValueDef::AnnotatedBody {
+                ann_pattern,
+                ann_type,
+                comment,
+                body_pattern,
+                body_expr,
+            } => {
+                let taken = take_early(arena, body_expr)?;
+                let value_def = ValueDef::AnnotatedBody {
+                    ann_pattern,
+                    ann_type,
+                    comment,
+                    body_pattern,
+                    body_expr: taken.rest,
+                };
+
+                Some((value_def, taken))
+            }
+            _ => None,
+        }
+    }
+
+    /// The pattern with the names this def defines
+    fn pattern(&self) -> Option<&'a Loc<Pattern<'a>>> {
+        match self.def.err()? {
+            ValueDef::Annotation(loc_pattern, _) => Some(loc_pattern),
+            ValueDef::Body(loc_pattern, _) => Some(*loc_pattern),
+            ValueDef::AnnotatedBody { body_pattern, .. } => Some(*body_pattern),
+            _ => None,
+        }
+    }
+
+    /// The expression this def evaluates
+    fn value(&self) -> Option<&'a Loc<Expr<'a>>> {
+        match *self.def.err()? {
+            ValueDef::Body(_, loc_expr)
+            | ValueDef::AnnotatedBody {
+                body_expr: loc_expr,
+                ..
+            }
+            | ValueDef::Dbg {
+                condition: loc_expr,
+                ..
+            }
+            | ValueDef::Expect {
+                condition: loc_expr,
+                ..
+            }
+            | ValueDef::ExpectFx {
+                condition: loc_expr,
+                ..
+            }
+            | ValueDef::Bench { body: loc_expr, .. } => Some(loc_expr),
+            ValueDef::Annotation(..) => None,
+        }
+    }
+
+    fn is_annotation_only(&self) -> bool {
+        matches!(self.def, Err(ValueDef::Annotation(..)))
+    }
+}
+
+fn to_defs<'a>(arena: &'a Bump, block: &[BlockDef<'a>]) -> &'a roc_parse::ast::Defs<'a> {
+    let mut defs = roc_parse::ast::Defs::default();
+
+    for block_def in block {
+        match block_def.def {
+            Ok(type_def) => defs.push_type_def(
+                *type_def,
+                block_def.region,
+                block_def.spaces_before,
+                block_def.spaces_after,
+            ),
+            Err(value_def) => defs.push_value_def(
+                *value_def,
+                block_def.region,
+                block_def.spaces_before,
+                block_def.spaces_after,
+            ),
+        }
+    }
+
+    arena.alloc(defs)
+}
+
+/// Which defs of a block have to wait until a `?` in the block is known to be `Ok`. That's the
+/// ones with a `?` in their value, the ones whose value looks up a name defined by one of those,
+/// and so on. A def which defines a name again after one of them has to wait too, so rebinding
+/// still happens in order.
+///
+/// Names are matched as they're written, so a def can wait when it doesn't need to, for example
+/// if it has a closure with an argument of the same name. That's fine, since waiting never stops
+/// a def from referring to anything.
+fn waiting_on_try<'a>(arena: &'a Bump, block: &[BlockDef<'a>]) -> Vec<'a, bool> {
+    let bindings = Vec::from_iter_in(block.iter().map(|def| def_bindings(arena, def)), arena);
+    let lookups = Vec::from_iter_in(
+        block.iter().map(|def| {
+            def.value()
+                .map_or(&[][..], |value| expr_lookups(arena, value))
+        }),
+        arena,
+    );
+
+    let mut waiting =
+        Vec::from_iter_in(block.iter().map(|def| def.take_try(arena).is_some()), arena);
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+
+        for index in 0..block.len() {
+            if waiting[index] {
+                continue;
+            }
+
+            let looks_up_waiting = lookups[index].iter().any(|name| {
+                resolve_in_block(&bindings, name, index).map_or(false, |other| waiting[other])
+            });
+
+            // an annotation goes with its def, wherever that is
+            let redefines_waiting = (0..block.len())
+                .filter(|other| *other < index || block[index].is_annotation_only())
+                .any(|other| {
+                    waiting[other]
+                        && bindings[other]
+                            .iter()
+                            .any(|name| bindings[index].contains(name))
+                });
+
+            if looks_up_waiting || redefines_waiting {
+                waiting[index] = true;
+                changed = true;
+            }
+        }
+    }
+
+    waiting
+}
+
+/// Whether `tried`, in the def at `index`, looks up a name defined by another def which is
+/// waiting on a `?`
+fn needs_waiting<'a>(
+    arena: &'a Bump,
+    block: &[BlockDef<'a>],
+    waiting: &[bool],
+    index: usize,
+    tried: &'a Loc<Expr<'a>>,
+) -> bool {
+    let bindings = Vec::from_iter_in(block.iter().map(|def| def_bindings(arena, def)), arena);
+
+    expr_lookups(arena, tried).iter().any(|name| {
+        resolve_in_block(&bindings, name, index)
+            .map_or(false, |other| other != index && waiting[other])
+    })
+}
+
+/// The def of a block that a lookup of `name` in the def at `index` refers to. Like in `Scope`,
+/// that's the last def before it which defines the name, or the first which does if none before
+/// it do.
+fn resolve_in_block(bindings: &[&[&str]], name: &str, index: usize) -> Option<usize> {
+    let mut definitions = bindings
+        .iter()
+        .enumerate()
+        .filter(|(_, names)| names.contains(&name))
+        .map(|(other, _)| other);
+
+    let first = definitions.next()?;
+
+    Some(
+        std::iter::once(first)
+            .chain(definitions)
+            .take_while(|other| *other < index)
+            .last()
+            .unwrap_or(first),
+    )
+}
+
+fn def_bindings<'a>(arena: &'a Bump, def: &BlockDef<'a>) -> &'a [&'a str] {
+    let mut collector = NameCollector {
+        names: Vec::new_in(arena),
+        lookups: false,
+    };
+
+    if let Some(loc_pattern) = def.pattern() {
+        collector.visit_pattern(&loc_pattern.value, loc_pattern.region);
+    }
+
+    collector.names.into_bump_slice()
+}
+
+fn expr_lookups<'a>(arena: &'a Bump, loc_expr: &'a Loc<Expr<'a>>) -> &'a [&'a str] {
+    let mut collector = NameCollector {
+        names: Vec::new_in(arena),
+        lookups: true,
+    };
+
+    collector.visit_expr(&loc_expr.value, loc_expr.region);
+
+    collector.names.into_bump_slice()
+}
+
+/// Collects either the unqualified names looked up in expressions, or the names defined in
+/// patterns
+struct NameCollector<'a> {
+    names: Vec<'a, &'a str>,
+    lookups: bool,
+}
+
+impl<'a> Visitor<'a> for NameCollector<'a> {
+    fn visit_expr(&mut self, expr: &Expr<'a>, region: Region) {
+        if self.lookups {
+            if let Var {
+                module_name: "",
+                ident,
+            } = expr
+            {
+                self.names.push(*ident);
+            }
+        }
+
+        walk_expr(self, expr, region);
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern<'a>, region: Region) {
+        match pattern {
+            Pattern::Identifier(name) if !self.lookups => self.names.push(*name),
+            Pattern::As(_, pattern_as) if !self.lookups => {
+                self.names.push(pattern_as.identifier.value)
+            }
+            _ => {}
+        }
+
+        walk_pattern(self, pattern, region);
     }
 }
 
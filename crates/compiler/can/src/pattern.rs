@@ -9,9 +9,11 @@ use crate::scope::{PendingAbilitiesInScope, Scope};
 use roc_exhaustive::ListArity;
 use roc_module::ident::{Ident, Lowercase, TagName};
 use roc_module::symbol::Symbol;
-use roc_parse::ast::{self, StrLiteral, StrSegment};
+use roc_parse::ast::{self, ExtractSpaces, StrLiteral, StrSegment};
 use roc_parse::pattern::PatternType;
-use roc_problem::can::{MalformedPatternProblem, Problem, RuntimeError, ShadowKind};
+use roc_problem::can::{
+    MalformedPatternProblem, PatternSynonymProblem, Problem, RuntimeError, ShadowKind,
+};
 use roc_region::all::{Loc, Region};
 use roc_types::num::SingleQuoteBound;
 use roc_types::subs::{VarStore, Variable};
@@ -250,6 +252,75 @@ pub enum DestructType {
     Guard(Variable, Loc<Pattern>),
 }
 
+/// A pattern synonym like `pattern Ok2 x = Ok (Ok x)`. Matching on `Ok2 y` is the same as
+/// matching on `Ok (Ok y)`, so synonyms are expanded away during canonicalization, and everything
+/// after (including exhaustiveness checking) only sees the tags they stand for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatternSynonym {
+    pub region: Region,
+    pub arity: usize,
+    pub pattern: SynonymPattern,
+}
+
+/// The pattern a [PatternSynonym] stands for. Unlike a canonical [Pattern] it has no type
+/// variables, so it can be kept around and expanded in any module that imports it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SynonymPattern {
+    /// Whatever pattern is given as the synonym's argument at this index
+    Argument(usize),
+    Underscore,
+    Tag(TagName, Vec<SynonymPattern>),
+}
+
+impl SynonymPattern {
+    fn substitute(&self, arguments: &[SynonymPattern]) -> SynonymPattern {
+        match self {
+            SynonymPattern::Argument(index) => arguments[*index].clone(),
+            SynonymPattern::Underscore => SynonymPattern::Underscore,
+            SynonymPattern::Tag(tag_name, patterns) => SynonymPattern::Tag(
+                tag_name.clone(),
+                patterns.iter().map(|p| p.substitute(arguments)).collect(),
+            ),
+        }
+    }
+
+    /// Every argument is taken out of `arguments` as it's used; a valid synonym uses each
+    /// exactly once.
+    fn instantiate(
+        &self,
+        var_store: &mut VarStore,
+        arguments: &mut [Option<Loc<Pattern>>],
+        region: Region,
+    ) -> Loc<Pattern> {
+        match self {
+            SynonymPattern::Argument(index) => arguments[*index]
+                .take()
+                .expect("pattern synonyms use each of their arguments exactly once"),
+            SynonymPattern::Underscore => Loc::at(region, Pattern::Underscore),
+            SynonymPattern::Tag(tag_name, patterns) => {
+                let tag_arguments = patterns
+                    .iter()
+                    .map(|p| {
+                        (
+                            var_store.fresh(),
+                            p.instantiate(var_store, arguments, region),
+                        )
+                    })
+                    .collect();
+
+                let pattern = Pattern::AppliedTag {
+                    whole_var: var_store.fresh(),
+                    ext_var: var_store.fresh(),
+                    tag_name: tag_name.clone(),
+                    arguments: tag_arguments,
+                };
+
+                Loc::at(region, pattern)
+            }
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn canonicalize_def_header_pattern<'a>(
     env: &mut Env<'a>,
@@ -379,15 +450,20 @@ pub fn canonicalize_pattern<'a>(
                 Err(pattern) => pattern,
             }
         }
-        Tag(name) => {
-            // Canonicalize the tag's name.
-            Pattern::AppliedTag {
-                whole_var: var_store.fresh(),
-                ext_var: var_store.fresh(),
-                tag_name: TagName((*name).into()),
-                arguments: vec![],
+        Tag(name) => match scope.lookup_pattern_synonym(name) {
+            Some((symbol, synonym)) => {
+                expand_pattern_synonym(env, var_store, output, symbol, synonym, vec![], region)
             }
-        }
+            None => {
+                // Canonicalize the tag's name.
+                Pattern::AppliedTag {
+                    whole_var: var_store.fresh(),
+                    ext_var: var_store.fresh(),
+                    tag_name: TagName((*name).into()),
+                    arguments: vec![],
+                }
+            }
+        },
         OpaqueRef(name) => {
             // If this opaque ref had an argument, we would be in the "Apply" branch.
             let loc_name = Loc::at(region, (*name).into());
@@ -414,15 +490,24 @@ pub fn canonicalize_pattern<'a>(
             }
 
             match tag.value {
-                Tag(name) => {
-                    let tag_name = TagName(name.into());
-                    Pattern::AppliedTag {
-                        whole_var: var_store.fresh(),
-                        ext_var: var_store.fresh(),
-                        tag_name,
-                        arguments: can_patterns,
+                Tag(name) => match scope.lookup_pattern_synonym(name) {
+                    Some((symbol, synonym)) => {
+                        let arguments = can_patterns.into_iter().map(|(_, p)| p).collect();
+
+                        expand_pattern_synonym(
+                            env, var_store, output, symbol, synonym, arguments, region,
+                        )
                     }
-                }
+                    None => {
+                        let tag_name = TagName(name.into());
+                        Pattern::AppliedTag {
+                            whole_var: var_store.fresh(),
+                            ext_var: var_store.fresh(),
+                            tag_name,
+                            arguments: can_patterns,
+                        }
+                    }
+                },
 
                 OpaqueRef(name) => match scope.lookup_opaque_ref(name, tag.region) {
                     Ok((opaque, opaque_def)) => {
@@ -908,6 +993,182 @@ fn malformed_pattern(env: &mut Env, problem: MalformedPatternProblem, region: Re
     Pattern::MalformedPattern(problem, region)
 }
 
+/// Canonicalizes the definition of a pattern synonym like `pattern Ok2 x = Ok (Ok x)`, reporting
+/// a problem and returning None if it can't stand for a pattern.
+pub(crate) fn canonicalize_pattern_synonym<'a>(
+    env: &mut Env<'a>,
+    scope: &Scope,
+    output: &mut Output,
+    synonym: Symbol,
+    region: Region,
+    arguments: &[Loc<ast::Pattern<'a>>],
+    loc_pattern: &Loc<ast::Pattern<'a>>,
+) -> Option<PatternSynonym> {
+    let mut names = Vec::with_capacity(arguments.len());
+
+    for loc_argument in arguments {
+        match loc_argument.value.extract_spaces().item {
+            ast::Pattern::Identifier(name) => names.push(Loc::at(loc_argument.region, name)),
+            _ => {
+                env.problem(Problem::InvalidPatternSynonym {
+                    synonym,
+                    region: loc_argument.region,
+                    problem: PatternSynonymProblem::ArgumentNotAName,
+                });
+
+                return None;
+            }
+        }
+    }
+
+    let mut bound = vec![false; names.len()];
+
+    let result = to_synonym_pattern(
+        scope,
+        output,
+        synonym,
+        &names,
+        &mut bound,
+        &loc_pattern.value,
+        loc_pattern.region,
+    );
+
+    let pattern = match result {
+        Ok(pattern) => pattern,
+        Err(problem) => {
+            env.problem(problem);
+            return None;
+        }
+    };
+
+    if let Some(unused) = names.iter().zip(bound).find_map(|(n, b)| (!b).then_some(n)) {
+        env.problem(Problem::InvalidPatternSynonym {
+            synonym,
+            region: unused.region,
+            problem: PatternSynonymProblem::UnusedArgument(unused.value.into()),
+        });
+
+        return None;
+    }
+
+    Some(PatternSynonym {
+        region,
+        arity: names.len(),
+        pattern,
+    })
+}
+
+fn to_synonym_pattern<'a>(
+    scope: &Scope,
+    output: &mut Output,
+    synonym: Symbol,
+    names: &[Loc<&'a str>],
+    bound: &mut [bool],
+    pattern: &ast::Pattern<'a>,
+    region: Region,
+) -> Result<SynonymPattern, Problem> {
+    use ast::Pattern::*;
+
+    let invalid = |problem| Problem::InvalidPatternSynonym {
+        synonym,
+        region,
+        problem,
+    };
+
+    let (name, patterns): (&str, &[Loc<ast::Pattern<'a>>]) = match pattern {
+        SpaceBefore(pattern, _) | SpaceAfter(pattern, _) => {
+            return to_synonym_pattern(scope, output, synonym, names, bound, pattern, region);
+        }
+        Identifier(name) => {
+            return match names.iter().position(|n| n.value == *name) {
+                Some(index) if bound[index] => Err(invalid(
+                    PatternSynonymProblem::DuplicateArgument((*name).into()),
+                )),
+                Some(index) => {
+                    bound[index] = true;
+                    Ok(SynonymPattern::Argument(index))
+                }
+                None => Err(invalid(PatternSynonymProblem::NotAnArgument(
+                    (*name).into(),
+                ))),
+            };
+        }
+        Underscore(_) => return Ok(SynonymPattern::Underscore),
+        Tag(name) => (*name, &[] as &[_]),
+        Apply(
+            Loc {
+                value: Tag(name), ..
+            },
+            patterns,
+        ) => (*name, *patterns),
+        _ => return Err(invalid(PatternSynonymProblem::UnsupportedPattern)),
+    };
+
+    let mut arguments = Vec::with_capacity(patterns.len());
+
+    for loc_pattern in patterns {
+        arguments.push(to_synonym_pattern(
+            scope,
+            output,
+            synonym,
+            names,
+            bound,
+            &loc_pattern.value,
+            loc_pattern.region,
+        )?);
+    }
+
+    // A synonym can be defined in terms of the synonyms before it
+    match scope.lookup_pattern_synonym(name) {
+        Some((other, other_synonym)) => {
+            if other_synonym.arity != arguments.len() {
+                return Err(Problem::PatternSynonymArity {
+                    synonym: other,
+                    region,
+                    expected: other_synonym.arity,
+                    found: arguments.len(),
+                });
+            }
+
+            output.references.insert_type_lookup(other);
+
+            Ok(other_synonym.pattern.substitute(&arguments))
+        }
+        None => Ok(SynonymPattern::Tag(TagName(name.into()), arguments)),
+    }
+}
+
+/// Replaces a pattern synonym matched on with the pattern it stands for.
+fn expand_pattern_synonym(
+    env: &mut Env,
+    var_store: &mut VarStore,
+    output: &mut Output,
+    symbol: Symbol,
+    synonym: &PatternSynonym,
+    arguments: Vec<Loc<Pattern>>,
+    region: Region,
+) -> Pattern {
+    output.references.insert_type_lookup(symbol);
+
+    if arguments.len() != synonym.arity {
+        env.problem(Problem::PatternSynonymArity {
+            synonym: symbol,
+            region,
+            expected: synonym.arity,
+            found: arguments.len(),
+        });
+
+        return Pattern::UnsupportedPattern(region);
+    }
+
+    let mut arguments: Vec<_> = arguments.into_iter().map(Some).collect();
+
+    synonym
+        .pattern
+        .instantiate(var_store, &mut arguments, region)
+        .value
+}
+
 /// An iterator over the bindings made by a pattern.
 ///
 /// We attempt to make no allocations when we can.
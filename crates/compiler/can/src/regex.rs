@@ -0,0 +1,786 @@
+//! Compiles the string literal patterns given to `Str.matches` and `Str.capture` when the program
+//! is built, so that running them never parses or compiles anything.
+//!
+//! `Str.matches` gets a DFA, which decides whether the whole string matches in one pass over its
+//! bytes. `Str.capture` gets a program for a Pike VM instead, since a DFA can't tell where each
+//! group matched. Either way the compiled pattern replaces the string literal, so it's a constant
+//! like any other string literal. The bitcode's regex.zig runs them.
+use roc_collections::MutMap;
+use roc_module::symbol::Symbol;
+use roc_problem::can::RegexProblem;
+
+/// The most instructions a program can have. This keeps large `{n,m}`s from taking forever.
+const MAX_INSTRUCTIONS: usize = 10_000;
+
+/// The most states a DFA can have. Each one takes 2 bytes per byte class.
+const MAX_STATES: usize = 4_096;
+
+/// The biggest `n` or `m` in a `{n,m}`
+const MAX_REPETITIONS: u32 = 1_000;
+
+/// Returns the compiled form of a pattern given to `function`, which is one of the functions
+/// [takes_pattern] is true for.
+pub fn compile_pattern_for(function: Symbol, pattern: &str) -> Result<String, RegexProblem> {
+    let program = compile_program(pattern)?;
+
+    if function == Symbol::STR_MATCHES {
+        program.to_dfa()
+    } else {
+        Ok(program.serialize())
+    }
+}
+
+/// Whether `function` takes a pattern which has to be a string literal
+pub fn takes_pattern(function: Symbol) -> bool {
+    matches!(function, Symbol::STR_MATCHES | Symbol::STR_CAPTURE)
+}
+
+#[derive(Debug)]
+enum Node {
+    /// Any one byte in one of the ranges. With no ranges, this never matches.
+    Bytes(Vec<(u8, u8)>),
+    Concat(Vec<Node>),
+    Alternate(Vec<Node>),
+    Repeat {
+        node: Box<Node>,
+        min: u32,
+        max: Option<u32>,
+        greedy: bool,
+    },
+    Group {
+        index: usize,
+        node: Box<Node>,
+    },
+}
+
+/// What a `\` and the character after it stand for
+enum Escaped {
+    Char(char),
+    Class { ascii: [bool; 128], negated: bool },
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+    group_count: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let next = self.peek();
+
+        if next.is_some() {
+            self.position += 1;
+        }
+
+        next
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.position += 1;
+
+            true
+        } else {
+            false
+        }
+    }
+
+    fn alternation(&mut self) -> Result<Node, RegexProblem> {
+        let mut branches = vec![self.concatenation()?];
+
+        while self.eat('|') {
+            branches.push(self.concatenation()?);
+        }
+
+        if branches.len() == 1 {
+            Ok(branches.pop().unwrap())
+        } else {
+            Ok(Node::Alternate(branches))
+        }
+    }
+
+    fn concatenation(&mut self) -> Result<Node, RegexProblem> {
+        let mut nodes = Vec::new();
+
+        while let Some(next) = self.peek() {
+            if next == '|' || next == ')' {
+                break;
+            }
+
+            let atom = self.atom()?;
+            nodes.push(self.repetitions(atom)?);
+        }
+
+        Ok(Node::Concat(nodes))
+    }
+
+    fn atom(&mut self) -> Result<Node, RegexProblem> {
+        match self.next().unwrap() {
+            '(' => {
+                let index = if self.peek() == Some('?')
+                    && self.chars.get(self.position + 1) == Some(&':')
+                {
+                    self.position += 2;
+
+                    None
+                } else {
+                    self.group_count += 1;
+
+                    Some(self.group_count)
+                };
+
+                let node = self.alternation()?;
+
+                if !self.eat(')') {
+                    return Err(RegexProblem::UnclosedGroup);
+                }
+
+                Ok(match index {
+                    Some(index) => Node::Group {
+                        index,
+                        node: Box::new(node),
+                    },
+                    None => node,
+                })
+            }
+            '*' | '+' | '?' | '{' => Err(RegexProblem::NothingToRepeat),
+            '^' | '$' => Err(RegexProblem::Anchor),
+            '.' => Ok(any_scalar()),
+            '[' => self.class(),
+            '\\' => match self.escape()? {
+                Escaped::Char(c) => Ok(literal(c)),
+                Escaped::Class { ascii, negated } => Ok(class_node(&ascii, negated, &[])),
+            },
+            c => Ok(literal(c)),
+        }
+    }
+
+    fn escape(&mut self) -> Result<Escaped, RegexProblem> {
+        let class = |ranges: &[(u8, u8)], negated| {
+            let mut ascii = [false; 128];
+
+            for &(start, end) in ranges {
+                for byte in start..=end {
+                    ascii[byte as usize] = true;
+                }
+            }
+
+            Escaped::Class { ascii, negated }
+        };
+
+        const DIGIT: &[(u8, u8)] = &[(b'0', b'9')];
+        const WORD: &[(u8, u8)] = &[(b'0', b'9'), (b'A', b'Z'), (b'_', b'_'), (b'a', b'z')];
+        const SPACE: &[(u8, u8)] = &[(b'\t', b'\r'), (b' ', b' ')];
+
+        match self.next() {
+            None => Err(RegexProblem::TrailingBackslash),
+            Some('d') => Ok(class(DIGIT, false)),
+            Some('D') => Ok(class(DIGIT, true)),
+            Some('w') => Ok(class(WORD, false)),
+            Some('W') => Ok(class(WORD, true)),
+            Some('s') => Ok(class(SPACE, false)),
+            Some('S') => Ok(class(SPACE, true)),
+            Some('n') => Ok(Escaped::Char('\n')),
+            Some('r') => Ok(Escaped::Char('\r')),
+            Some('t') => Ok(Escaped::Char('\t')),
+            Some(c) if "\\.*+?()[]{}|^$-/".contains(c) => Ok(Escaped::Char(c)),
+            Some(c) => Err(RegexProblem::UnknownEscape(c)),
+        }
+    }
+
+    fn class(&mut self) -> Result<Node, RegexProblem> {
+        let negated = self.eat('^');
+        let mut ascii = [false; 128];
+        let mut any_non_ascii = false;
+        let mut non_ascii = Vec::new();
+        let mut first = true;
+
+        loop {
+            let start = match self.next() {
+                None => return Err(RegexProblem::UnclosedClass),
+                // a `]` right at the start is part of the class
+                Some(']') if !first => break,
+                Some('\\') => match self.escape()? {
+                    Escaped::Char(c) => c,
+                    Escaped::Class {
+                        ascii: escaped,
+                        negated: escaped_negated,
+                    } => {
+                        for (byte, included) in escaped.iter().enumerate() {
+                            ascii[byte] |= *included != escaped_negated;
+                        }
+
+                        any_non_ascii |= escaped_negated;
+                        first = false;
+
+                        continue;
+                    }
+                },
+                Some(c) => c,
+            };
+
+            first = false;
+
+            let is_range = self.peek() == Some('-')
+                && !matches!(self.chars.get(self.position + 1), None | Some(']'));
+
+            if is_range {
+                self.position += 1;
+
+                let end = match self.next() {
+                    Some('\\') => match self.escape()? {
+                        Escaped::Char(c) => c,
+                        Escaped::Class { .. } => return Err(RegexProblem::BackwardsRange),
+                    },
+                    Some(c) => c,
+                    None => return Err(RegexProblem::UnclosedClass),
+                };
+
+                if end < start {
+                    return Err(RegexProblem::BackwardsRange);
+                } else if !end.is_ascii() {
+                    return Err(RegexProblem::NonAsciiClass);
+                }
+
+                for byte in start as u8..=end as u8 {
+                    ascii[byte as usize] = true;
+                }
+            } else if start.is_ascii() {
+                ascii[start as usize] = true;
+            } else {
+                non_ascii.push(start);
+            }
+        }
+
+        let complement = ascii.map(|included| !included);
+
+        match (negated, any_non_ascii) {
+            (false, false) => Ok(class_node(&ascii, false, &non_ascii)),
+            // every non-ASCII character is in the class, so the ones listed don't matter
+            (false, true) => Ok(class_node(&complement, true, &[])),
+            (true, false) if non_ascii.is_empty() => Ok(class_node(&ascii, true, &[])),
+            (true, false) => Err(RegexProblem::NonAsciiClass),
+            // `[^\D]` has none of the non-ASCII characters, so only its ASCII ones are left
+            (true, true) => Ok(class_node(&complement, false, &[])),
+        }
+    }
+
+    fn repetitions(&mut self, mut atom: Node) -> Result<Node, RegexProblem> {
+        loop {
+            let quantifier = match self.peek() {
+                Some(c @ ('*' | '+' | '?' | '{')) => c,
+                _ => return Ok(atom),
+            };
+
+            self.position += 1;
+
+            let (min, max) = match quantifier {
+                '*' => (0, None),
+                '+' => (1, None),
+                '?' => (0, Some(1)),
+                _ => self.counted()?,
+            };
+
+            let greedy = !self.eat('?');
+
+            atom = Node::Repeat {
+                node: Box::new(atom),
+                min,
+                max,
+                greedy,
+            };
+        }
+    }
+
+    /// Parses the rest of a `{n}`, `{n,}`, or `{n,m}` after its `{`
+    fn counted(&mut self) -> Result<(u32, Option<u32>), RegexProblem> {
+        let min = self.number().ok_or(RegexProblem::InvalidRepetition)?;
+
+        let max = if self.eat(',') {
+            if self.peek() == Some('}') {
+                None
+            } else {
+                Some(self.number().ok_or(RegexProblem::InvalidRepetition)?)
+            }
+        } else {
+            Some(min)
+        };
+
+        if !self.eat('}') || max.map_or(false, |max| max < min) {
+            Err(RegexProblem::InvalidRepetition)
+        } else if min > MAX_REPETITIONS || max.map_or(false, |max| max > MAX_REPETITIONS) {
+            Err(RegexProblem::TooBig)
+        } else {
+            Ok((min, max))
+        }
+    }
+
+    fn number(&mut self) -> Option<u32> {
+        let mut number = None;
+
+        while let Some(digit) = self.peek().and_then(|c| c.to_digit(10)) {
+            self.position += 1;
+
+            // anything too big for a u32 is too big to repeat anyway
+            number = Some(
+                number
+                    .unwrap_or(0u32)
+                    .saturating_mul(10)
+                    .saturating_add(digit),
+            );
+        }
+
+        number
+    }
+}
+
+fn literal(c: char) -> Node {
+    let mut buf = [0; 4];
+    let bytes = c.encode_utf8(&mut buf).as_bytes();
+
+    if bytes.len() == 1 {
+        Node::Bytes(vec![(bytes[0], bytes[0])])
+    } else {
+        Node::Concat(bytes.iter().map(|&b| Node::Bytes(vec![(b, b)])).collect())
+    }
+}
+
+/// Any character whose UTF-8 takes more than one byte. Since a `Str` is always valid UTF-8, this
+/// doesn't need to rule out overlong encodings or surrogates.
+fn any_non_ascii() -> Node {
+    let continuation = || Node::Bytes(vec![(0x80, 0xBF)]);
+
+    Node::Alternate(vec![
+        Node::Concat(vec![Node::Bytes(vec![(0xC2, 0xDF)]), continuation()]),
+        Node::Concat(vec![
+            Node::Bytes(vec![(0xE0, 0xEF)]),
+            continuation(),
+            continuation(),
+        ]),
+        Node::Concat(vec![
+            Node::Bytes(vec![(0xF0, 0xF4)]),
+            continuation(),
+            continuation(),
+            continuation(),
+        ]),
+    ])
+}
+
+fn any_scalar() -> Node {
+    Node::Alternate(vec![Node::Bytes(vec![(0x00, 0x7F)]), any_non_ascii()])
+}
+
+/// A class with the given ASCII characters and non-ASCII ones. A negated class has the ASCII
+/// characters which aren't given, and every non-ASCII character.
+fn class_node(ascii: &[bool; 128], negated: bool, non_ascii: &[char]) -> Node {
+    let mut ranges: Vec<(u8, u8)> = Vec::new();
+
+    for byte in 0..128u8 {
+        if ascii[byte as usize] != negated {
+            match ranges.last_mut() {
+                Some((_, end)) if *end + 1 == byte => *end = byte,
+                _ => ranges.push((byte, byte)),
+            }
+        }
+    }
+
+    let mut branches = Vec::new();
+
+    if !ranges.is_empty() {
+        branches.push(Node::Bytes(ranges));
+    }
+
+    if negated {
+        branches.push(any_non_ascii());
+    }
+
+    branches.extend(non_ascii.iter().map(|&c| literal(c)));
+
+    match branches.len() {
+        0 => Node::Bytes(Vec::new()),
+        1 => branches.pop().unwrap(),
+        _ => Node::Alternate(branches),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Inst {
+    /// Moves past a byte in the range, and on to the next instruction
+    Range(u8, u8),
+    /// Continues with both instructions, preferring the first
+    Split(usize, usize),
+    Jump(usize),
+    /// Records the position in a slot. Group `n` starts at slot `2n` and ends at slot `2n + 1`.
+    Save(usize),
+    Match,
+}
+
+/// A Thompson NFA for a pattern, as instructions for a Pike VM
+#[derive(Debug)]
+struct Program {
+    insts: Vec<Inst>,
+    slot_count: usize,
+}
+
+fn compile_program(pattern: &str) -> Result<Program, RegexProblem> {
+    let mut parser = Parser {
+        chars: pattern.chars().collect(),
+        position: 0,
+        group_count: 0,
+    };
+
+    let root = parser.alternation()?;
+
+    if parser.position < parser.chars.len() {
+        // alternation only stops early at a `)`
+        return Err(RegexProblem::UnmatchedParen);
+    }
+
+    let mut program = Program {
+        insts: Vec::new(),
+        slot_count: 2 * (parser.group_count + 1),
+    };
+
+    // group 0 is the whole match
+    program.compile(&Node::Group {
+        index: 0,
+        node: Box::new(root),
+    })?;
+    program.emit(Inst::Match)?;
+
+    Ok(program)
+}
+
+impl Program {
+    fn emit(&mut self, inst: Inst) -> Result<usize, RegexProblem> {
+        if self.insts.len() == MAX_INSTRUCTIONS {
+            return Err(RegexProblem::TooBig);
+        }
+
+        self.insts.push(inst);
+
+        Ok(self.insts.len() - 1)
+    }
+
+    fn compile(&mut self, node: &Node) -> Result<(), RegexProblem> {
+        match node {
+            Node::Bytes(ranges) => match ranges.as_slice() {
+                // a range which ends before it starts never matches
+                [] => self.emit(Inst::Range(1, 0)).map(|_| ()),
+                [(start, end)] => self.emit(Inst::Range(*start, *end)).map(|_| ()),
+                _ => {
+                    let branches: Vec<_> = ranges.iter().map(|r| Node::Bytes(vec![*r])).collect();
+
+                    self.compile(&Node::Alternate(branches))
+                }
+            },
+            Node::Concat(nodes) => nodes.iter().try_for_each(|node| self.compile(node)),
+            Node::Alternate(branches) => {
+                let (last, rest) = branches.split_last().unwrap();
+                let mut jumps = Vec::with_capacity(rest.len());
+
+                for branch in rest {
+                    let split = self.emit(Inst::Split(0, 0))?;
+                    self.compile(branch)?;
+                    jumps.push(self.emit(Inst::Jump(0))?);
+
+                    self.insts[split] = Inst::Split(split + 1, self.insts.len());
+                }
+
+                self.compile(last)?;
+
+                for jump in jumps {
+                    self.insts[jump] = Inst::Jump(self.insts.len());
+                }
+
+                Ok(())
+            }
+            Node::Repeat {
+                node,
+                min,
+                max,
+                greedy,
+            } => {
+                for _ in 0..*min {
+                    self.compile(node)?;
+                }
+
+                let prefer = |repeat, skip| {
+                    if *greedy {
+                        Inst::Split(repeat, skip)
+                    } else {
+                        Inst::Split(skip, repeat)
+                    }
+                };
+
+                match max {
+                    None => {
+                        let split = self.emit(Inst::Split(0, 0))?;
+                        self.compile(node)?;
+                        self.emit(Inst::Jump(split))?;
+
+                        self.insts[split] = prefer(split + 1, self.insts.len());
+                    }
+                    Some(max) => {
+                        let mut splits = Vec::new();
+
+                        for _ in *min..*max {
+                            splits.push(self.emit(Inst::Split(0, 0))?);
+                            self.compile(node)?;
+                        }
+
+                        // skipping any of the optional copies skips the rest of them too
+                        for split in splits {
+                            self.insts[split] = prefer(split + 1, self.insts.len());
+                        }
+                    }
+                }
+
+                Ok(())
+            }
+            Node::Group { index, node } => {
+                self.emit(Inst::Save(2 * index))?;
+                self.compile(node)?;
+                self.emit(Inst::Save(2 * index + 1))?;
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Adds the `Range` and `Match` instructions reachable from `pc` without moving past a byte
+    fn closure(&self, pc: usize, visited: &mut [bool], found: &mut Vec<usize>) {
+        if std::mem::replace(&mut visited[pc], true) {
+            return;
+        }
+
+        match self.insts[pc] {
+            Inst::Range(..) | Inst::Match => found.push(pc),
+            Inst::Split(first, second) => {
+                self.closure(first, visited, found);
+                self.closure(second, visited, found);
+            }
+            Inst::Jump(to) => self.closure(to, visited, found),
+            Inst::Save(_) => self.closure(pc + 1, visited, found),
+        }
+    }
+
+    fn state_of(&self, pcs: impl Iterator<Item = usize>) -> Vec<usize> {
+        let mut visited = vec![false; self.insts.len()];
+        let mut found = Vec::new();
+
+        for pc in pcs {
+            self.closure(pc, &mut visited, &mut found);
+        }
+
+        found.sort_unstable();
+
+        found
+    }
+
+    /// Builds a DFA with the subset construction, ignoring the groups, and serializes it
+    fn to_dfa(&self) -> Result<String, RegexProblem> {
+        // bytes which no instruction tells apart share a class, so each state only needs one
+        // transition per class rather than per byte
+        let mut boundaries = [false; 257];
+        boundaries[0] = true;
+
+        for inst in self.insts.iter() {
+            if let Inst::Range(start, end) = *inst {
+                if start <= end {
+                    boundaries[start as usize] = true;
+                    boundaries[end as usize + 1] = true;
+                }
+            }
+        }
+
+        let mut class_of_byte = [0usize; 256];
+        let mut representatives = Vec::new();
+
+        for (byte, class) in class_of_byte.iter_mut().enumerate() {
+            if boundaries[byte] {
+                representatives.push(byte as u8);
+            }
+
+            *class = representatives.len() - 1;
+        }
+
+        // state 0 is the dead state, which never matches; state 1 is the start
+        let mut states = vec![Vec::new(), self.state_of(std::iter::once(0))];
+        let mut ids: MutMap<Vec<usize>, usize> = MutMap::default();
+        ids.insert(states[0].clone(), 0);
+        ids.entry(states[1].clone()).or_insert(1);
+
+        let mut transitions = Vec::new();
+        let mut index = 1;
+
+        while index < states.len() {
+            for &byte in representatives.iter() {
+                let next =
+                    self.state_of(states[index].iter().filter_map(|&pc| match self.insts[pc] {
+                        Inst::Range(start, end) if start <= byte && byte <= end => Some(pc + 1),
+                        _ => None,
+                    }));
+
+                let id = match ids.get(&next) {
+                    Some(id) => *id,
+                    None if states.len() == MAX_STATES => return Err(RegexProblem::TooBig),
+                    None => {
+                        ids.insert(next.clone(), states.len());
+                        states.push(next);
+
+                        states.len() - 1
+                    }
+                };
+
+                transitions.push(id);
+            }
+
+            index += 1;
+        }
+
+        let match_pc = self.insts.len() - 1;
+        let mut out = vec![b'D'];
+
+        push_number(&mut out, representatives.len());
+        push_number(&mut out, states.len());
+
+        for class in class_of_byte {
+            push_number(&mut out, class);
+        }
+
+        for state in states.iter() {
+            out.push(state.contains(&match_pc) as u8);
+        }
+
+        // the dead state goes nowhere else
+        for _ in representatives.iter() {
+            push_number(&mut out, 0);
+        }
+
+        for id in transitions {
+            push_number(&mut out, id);
+        }
+
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    fn serialize(&self) -> String {
+        let mut out = vec![b'P'];
+
+        push_number(&mut out, self.insts.len());
+        push_number(&mut out, self.slot_count);
+
+        for inst in self.insts.iter() {
+            let (op, a, b) = match *inst {
+                Inst::Range(start, end) => (0, start as usize, end as usize),
+                Inst::Split(first, second) => (1, first, second),
+                Inst::Jump(to) => (2, to, 0),
+                Inst::Save(slot) => (3, slot, 0),
+                Inst::Match => (4, 0, 0),
+            };
+
+            out.push(op);
+            push_number(&mut out, a);
+            push_number(&mut out, b);
+        }
+
+        String::from_utf8(out).unwrap()
+    }
+}
+
+/// Compiled patterns are stored 7 bits to a byte, so they're ASCII and can be Roc string literals.
+/// Every number in them fits in two of those bytes.
+fn push_number(out: &mut Vec<u8>, number: usize) {
+    debug_assert!(number < 1 << 14);
+
+    out.push((number & 0x7F) as u8);
+    out.push((number >> 7) as u8);
+}
+
+#[cfg(test)]
+mod test {
+    use super::compile_pattern_for;
+    use roc_module::symbol::Symbol;
+    use roc_problem::can::RegexProblem;
+
+    /// Runs a compiled DFA the way regex.zig does
+    fn matches(pattern: &str, input: &str) -> bool {
+        let dfa = compile_pattern_for(Symbol::STR_MATCHES, pattern).unwrap();
+        let dfa = dfa.as_bytes();
+        let number = |index: usize| dfa[index] as usize | (dfa[index + 1] as usize) << 7;
+
+        let class_count = number(1);
+        let state_count = number(3);
+        let classes = 5;
+        let accepting = classes + 2 * 256;
+        let transitions = accepting + state_count;
+
+        let mut state = 1;
+
+        for byte in input.bytes() {
+            let class = number(classes + 2 * byte as usize);
+            state = number(transitions + 2 * (state * class_count + class));
+        }
+
+        dfa[accepting + state] == 1
+    }
+
+    fn problem(pattern: &str) -> RegexProblem {
+        compile_pattern_for(Symbol::STR_CAPTURE, pattern).unwrap_err()
+    }
+
+    #[test]
+    fn matches_whole_string() {
+        assert!(matches("a+b", "aaab"));
+        assert!(!matches("a+b", "aaabb"));
+        assert!(!matches("a+b", "xaab"));
+        assert!(matches("", ""));
+    }
+
+    #[test]
+    fn alternation_and_groups() {
+        assert!(matches("(cat|dog)s?", "dogs"));
+        assert!(matches("(?:ab){2,3}", "ababab"));
+        assert!(!matches("(?:ab){2,3}", "ab"));
+        assert!(!matches("(?:ab){2,3}", "abababab"));
+    }
+
+    #[test]
+    fn classes() {
+        assert!(matches("[a-z_][a-z0-9_]*", "snake_case2"));
+        assert!(!matches("[a-z_][a-z0-9_]*", "2snake"));
+        assert!(matches("\\d{3}-\\d{4}", "555-1234"));
+        assert!(matches("[^,]+", "no commas"));
+        assert!(!matches("[^,]+", "a,b"));
+        assert!(matches("[]a]+", "]a]"));
+    }
+
+    #[test]
+    fn non_ascii() {
+        assert!(matches("caf.", "café"));
+        assert!(matches("[éè]t[éè]", "été"));
+        assert!(matches("\\W+", "🐦 é"));
+        assert!(!matches(".", "ab"));
+    }
+
+    #[test]
+    fn problems() {
+        assert_eq!(problem("(a"), RegexProblem::UnclosedGroup);
+        assert_eq!(problem("a)"), RegexProblem::UnmatchedParen);
+        assert_eq!(problem("[a"), RegexProblem::UnclosedClass);
+        assert_eq!(problem("*a"), RegexProblem::NothingToRepeat);
+        assert_eq!(problem("a{3,1}"), RegexProblem::InvalidRepetition);
+        assert_eq!(problem("[z-a]"), RegexProblem::BackwardsRange);
+        assert_eq!(problem("a\\"), RegexProblem::TrailingBackslash);
+        assert_eq!(problem("\\q"), RegexProblem::UnknownEscape('q'));
+        assert_eq!(problem("^a$"), RegexProblem::Anchor);
+        assert_eq!(problem("[à-é]"), RegexProblem::NonAsciiClass);
+        assert_eq!(problem("(a{1000}){1000}"), RegexProblem::TooBig);
+    }
+}
@@ -2,11 +2,12 @@ use roc_collections::{VecMap, VecSet};
 use roc_module::ident::Ident;
 use roc_module::symbol::{IdentId, IdentIds, ModuleId, Symbol};
 use roc_problem::can::RuntimeError;
-use roc_region::all::{Loc, Region};
+use roc_region::all::{Loc, Position, Region};
 use roc_types::subs::Variable;
 use roc_types::types::{Alias, AliasKind, AliasVar, Type};
 
 use crate::abilities::PendingAbilitiesStore;
+use crate::pattern::PatternSynonym;
 
 use bitvec::vec::BitVec;
 
@@ -21,6 +22,9 @@ pub struct Scope {
     /// The abilities currently in scope, and their implementors.
     pub abilities_store: PendingAbilitiesStore,
 
+    /// The pattern synonyms currently in scope, including imported ones
+    pub pattern_synonyms: VecMap<Symbol, PatternSynonym>,
+
     /// The current module being processed. This will be used to turn
     /// unqualified idents into Symbols.
     home: ModuleId,
@@ -40,6 +44,27 @@ pub struct Scope {
 
     /// Identifiers that are in scope, and defined in the current module
     pub locals: ScopedIdentIds,
+
+    /// Locals which defs in the enclosing def blocks give new values to. Which value is in scope
+    /// depends on where a lookup is, see [Self::show_rebindings_at].
+    rebindings: Vec<Rebinding>,
+
+    /// Where the last lookup the rebindings were shown for is
+    rebindings_shown_at: Option<Position>,
+}
+
+/// A local that a def block gives new values to, like `x` in
+///
+/// ```roc
+/// x = 1
+/// x = x + 1
+/// ```
+#[derive(Clone, Debug)]
+struct Rebinding {
+    /// The value lookups see before any of the rebinding defs
+    original: Symbol,
+    /// Each new value, and the position from which lookups see it
+    versions: Vec<(Position, Symbol)>,
 }
 
 impl Scope {
@@ -62,8 +87,11 @@ impl Scope {
             locals: ScopedIdentIds::from_ident_ids(home, initial_ident_ids),
             aliases: VecMap::default(),
             abilities_store: starting_abilities_store,
+            pattern_synonyms: VecMap::default(),
             shadows: VecMap::default(),
             imports: default_imports,
+            rebindings: Vec::new(),
+            rebindings_shown_at: None,
         }
     }
 
@@ -397,6 +425,93 @@ impl Scope {
         self.aliases.insert(name, alias);
     }
 
+    /// The local a def like `x = x + 1` would give a new value to, and where it was defined, or
+    /// None if there's no such local in scope. Imports and ability members can't be rebound.
+    pub fn rebindable(&self, ident: &str) -> Option<(Symbol, Region)> {
+        if self.has_imported(ident).is_some() {
+            return None;
+        }
+
+        match self.locals.contains_ident(ident) {
+            ContainsIdent::InScope(symbol, region)
+                if !self.abilities_store.is_ability_member_name(symbol) =>
+            {
+                Some((symbol, region))
+            }
+            _ => None,
+        }
+    }
+
+    /// Rebind a local: lookups from `visible_from` onwards see `rebound`, a symbol made with
+    /// [Self::scopeless_symbol], instead of `existing`, until the current inner scope ends.
+    ///
+    /// Nothing changes until [Self::show_rebindings_at] is called, so the rest of a def block
+    /// can still be introduced while `existing` is in scope.
+    pub fn rebind(&mut self, existing: Symbol, rebound: Symbol, visible_from: Position) {
+        debug_assert_eq!(existing.module_id(), self.home);
+        debug_assert_eq!(rebound.module_id(), self.home);
+
+        // An inner block can rebind a value an outer one gave. It joins the outer rebinding, so
+        // lookups see whichever value was given last.
+        let existing_rebinding = self.rebindings.iter_mut().find(|rebinding| {
+            rebinding.original == existing
+                || rebinding
+                    .versions
+                    .iter()
+                    .any(|(_, symbol)| *symbol == existing)
+        });
+
+        match existing_rebinding {
+            Some(rebinding) => rebinding.versions.push((visible_from, rebound)),
+            None => self.rebindings.push(Rebinding {
+                original: existing,
+                versions: vec![(visible_from, rebound)],
+            }),
+        }
+    }
+
+    /// Put the value of each rebound local that a lookup at `position` sees in scope, and take
+    /// its other values out of scope. Def blocks are order-independent, so this is how a def
+    /// body, or the block's return expression, sees the value given by the last def before it.
+    pub fn show_rebindings_at(&mut self, position: Position) {
+        self.rebindings_shown_at = Some(position);
+
+        for rebinding in self.rebindings.iter() {
+            let visible = rebinding
+                .versions
+                .iter()
+                .filter(|(visible_from, _)| *visible_from <= position)
+                .max_by_key(|(visible_from, _)| *visible_from)
+                .map_or(rebinding.original, |(_, symbol)| *symbol);
+
+            let versions = rebinding.versions.iter().map(|(_, symbol)| symbol);
+            for symbol in std::iter::once(&rebinding.original).chain(versions) {
+                self.locals
+                    .in_scope
+                    .set(symbol.ident_id().index(), *symbol == visible);
+            }
+        }
+    }
+
+    /// The pattern synonym a tag-like name in a pattern refers to, if there's one in scope
+    pub fn lookup_pattern_synonym(&self, name: &str) -> Option<(Symbol, &PatternSynonym)> {
+        if self.pattern_synonyms.is_empty() {
+            return None;
+        }
+
+        match self.scope_contains_ident(name) {
+            ContainsIdent::InScope(symbol, _) => self
+                .pattern_synonyms
+                .get(&symbol)
+                .map(|synonym| (symbol, synonym)),
+            ContainsIdent::NotInScope(_) | ContainsIdent::NotPresent => None,
+        }
+    }
+
+    pub fn add_pattern_synonym(&mut self, name: Symbol, synonym: PatternSynonym) {
+        self.pattern_synonyms.insert(name, synonym);
+    }
+
     pub fn lookup_alias(&self, symbol: Symbol) -> Option<&Alias> {
         self.aliases.get(&symbol)
     }
@@ -414,20 +529,49 @@ impl Scope {
         // - abilities_store: ability definitions not allowed in inner scopes
         // - locals: everything introduced in the inner scope is marked as not in scope in the rollback
         // - aliases: stored in a VecMap, we just discard anything added in an inner scope
+        // - pattern_synonyms: same as aliases
         // - exposed_ident_count: unchanged
         // - home: unchanged
         let aliases_count = self.aliases.len();
+        let pattern_synonyms_count = self.pattern_synonyms.len();
         let locals_snapshot = self.locals.in_scope.len();
+        let rebindings_count = self.rebindings.len();
+        let rebindings_shown_at = self.rebindings_shown_at;
 
         let result = f(self);
 
         self.aliases.truncate(aliases_count);
+        self.pattern_synonyms.truncate(pattern_synonyms_count);
 
         // anything added in the inner scope is no longer in scope now
         for i in locals_snapshot..self.locals.in_scope.len() {
             self.locals.in_scope.set(i, false);
         }
 
+        // and the outer locals rebound in the inner scope are back in scope
+        for rebinding in self.rebindings.drain(rebindings_count..) {
+            let index = rebinding.original.ident_id().index();
+
+            if index < locals_snapshot {
+                self.locals.in_scope.set(index, true);
+            }
+        }
+
+        // and so are the values outer locals had before the inner scope rebound them again
+        if !self.rebindings.is_empty() {
+            for rebinding in self.rebindings.iter_mut() {
+                rebinding
+                    .versions
+                    .retain(|(_, symbol)| symbol.ident_id().index() < locals_snapshot);
+            }
+
+            if let Some(position) = rebindings_shown_at {
+                self.show_rebindings_at(position);
+            }
+        }
+
+        self.rebindings_shown_at = rebindings_shown_at;
+
         result
     }
 
@@ -685,6 +829,79 @@ mod test {
         assert!(scope.lookup(&ident, region).is_err());
     }
 
+    #[test]
+    fn rebinding_ends_with_inner_scope() {
+        let _register_module_debug_names = ModuleIds::default();
+        let mut scope = Scope::new(
+            ModuleId::ATTR,
+            IdentIds::default(),
+            PendingAbilitiesStore::default(),
+        );
+
+        let region = Region::zero();
+        let ident = Ident::from("state");
+
+        let original = scope.introduce(ident.clone(), region).unwrap();
+
+        scope.inner_scope(|inner| {
+            let (existing, _) = inner.rebindable(ident.as_str()).unwrap();
+            assert_eq!(existing, original);
+
+            let rebound = inner.scopeless_symbol(&ident, region);
+            inner.rebind(existing, rebound, Position::new(10));
+
+            // the rebinding doesn't take effect until we say where lookups are
+            assert_eq!(inner.lookup(&ident, region), Ok(original));
+
+            inner.show_rebindings_at(Position::new(9));
+            assert_eq!(inner.lookup(&ident, region), Ok(original));
+
+            inner.show_rebindings_at(Position::new(10));
+            assert_eq!(inner.lookup(&ident, region), Ok(rebound));
+        });
+
+        assert_eq!(scope.lookup(&ident, region), Ok(original));
+    }
+
+    #[test]
+    fn inner_rebinding_joins_outer_rebinding() {
+        let _register_module_debug_names = ModuleIds::default();
+        let mut scope = Scope::new(
+            ModuleId::ATTR,
+            IdentIds::default(),
+            PendingAbilitiesStore::default(),
+        );
+
+        let region = Region::zero();
+        let ident = Ident::from("state");
+
+        let original = scope.introduce(ident.clone(), region).unwrap();
+        let outer = scope.scopeless_symbol(&ident, region);
+        scope.rebind(original, outer, Position::new(10));
+        scope.show_rebindings_at(Position::new(5));
+
+        scope.inner_scope(|inner| {
+            // an inner block which starts before the outer rebinding, but rebinds after it
+            let (existing, _) = inner.rebindable(ident.as_str()).unwrap();
+            assert_eq!(existing, original);
+
+            let rebound = inner.scopeless_symbol(&ident, region);
+            inner.rebind(existing, rebound, Position::new(20));
+
+            inner.show_rebindings_at(Position::new(15));
+            assert_eq!(inner.lookup(&ident, region), Ok(outer));
+
+            inner.show_rebindings_at(Position::new(25));
+            assert_eq!(inner.lookup(&ident, region), Ok(rebound));
+        });
+
+        // back to where the inner scope started
+        assert_eq!(scope.lookup(&ident, region), Ok(original));
+
+        scope.show_rebindings_at(Position::new(25));
+        assert_eq!(scope.lookup(&ident, region), Ok(outer));
+    }
+
     #[test]
     fn default_idents_in_scope() {
         let _register_module_debug_names = ModuleIds::default();
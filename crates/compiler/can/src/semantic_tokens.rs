@@ -0,0 +1,351 @@
+//! Semantic tokens for editors, in the shape the Language Server Protocol expects. The
+//! highlighting tokenizer finds where the tokens are; the canonical IR says what each identifier
+//! actually refers to, so e.g. a function parameter and a top-level value get different colors.
+use crate::abilities::AbilitiesStore;
+use crate::expr::{ClosureData, Declarations, Expr};
+use crate::pattern::{DestructType, Pattern, RecordDestruct};
+use crate::traverse::{
+    symbols_introduced_from_pattern, walk_decls, walk_expr, walk_pattern, walk_record_destruct,
+    Visitor,
+};
+use roc_collections::{MutMap, MutSet};
+use roc_module::symbol::{Interns, ModuleId, Symbol};
+use roc_parse::highlight::{highlight, HighlightToken};
+use roc_region::all::Region;
+use roc_types::subs::{Subs, Variable};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum SemanticTokenType {
+    Namespace,
+    Type,
+    Ability,
+    Tag,
+    Function,
+    Variable,
+    Parameter,
+    AbilityMember,
+    Property,
+    Keyword,
+    String,
+    Number,
+    Comment,
+    Operator,
+}
+
+impl SemanticTokenType {
+    /// The names LSP clients know each token type by, in the order of the enum. This is the
+    /// `tokenTypes` of the legend a server advertises.
+    pub const LEGEND: [&'static str; 14] = [
+        "namespace",
+        "type",
+        "interface",
+        "enumMember",
+        "function",
+        "variable",
+        "parameter",
+        "method",
+        "property",
+        "keyword",
+        "string",
+        "number",
+        "comment",
+        "operator",
+    ];
+}
+
+/// Where the identifier is bound, rather than used
+pub const MODIFIER_DECLARATION: u32 = 1 << 0;
+/// A function provided by the platform, which performs effects
+pub const MODIFIER_EFFECT: u32 = 1 << 1;
+/// Something from a builtin module
+pub const MODIFIER_DEFAULT_LIBRARY: u32 = 1 << 2;
+
+/// The names of the modifier bits, from the lowest bit up. This is the `tokenModifiers` of the
+/// legend a server advertises.
+pub const MODIFIER_LEGEND: [&str; 3] = ["declaration", "effect", "defaultLibrary"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SemanticToken {
+    pub region: Region,
+    pub token_type: SemanticTokenType,
+    pub modifiers: u32,
+}
+
+/// Finds what each identifier in a module refers to. Lowercase identifiers are recorded by the
+/// offset they end at, since a qualified name's region starts at its module name.
+struct Resolver<'a> {
+    subs: &'a Subs,
+    hosted_modules: &'a [ModuleId],
+    params: MutSet<Symbol>,
+    tag_names: MutSet<String>,
+    lowercase: MutMap<u32, (SemanticTokenType, u32)>,
+    uppercase: MutMap<u32, (SemanticTokenType, u32)>,
+}
+
+impl<'a> Resolver<'a> {
+    fn value(&self, symbol: Symbol, var: Option<Variable>) -> (SemanticTokenType, u32) {
+        let modifiers = if symbol.is_builtin() {
+            MODIFIER_DEFAULT_LIBRARY
+        } else {
+            0
+        };
+
+        if self.hosted_modules.contains(&symbol.module_id()) {
+            (SemanticTokenType::Function, modifiers | MODIFIER_EFFECT)
+        } else if self.params.contains(&symbol) {
+            (SemanticTokenType::Parameter, modifiers)
+        } else if var.map_or(false, |var| self.subs.is_function(var)) {
+            (SemanticTokenType::Function, modifiers)
+        } else {
+            (SemanticTokenType::Variable, modifiers)
+        }
+    }
+
+    fn add_params(&mut self, data: &ClosureData) {
+        for (_, _, loc_pattern) in data.arguments.iter() {
+            let params = symbols_introduced_from_pattern(loc_pattern);
+
+            self.params
+                .extend(params.map(|loc_symbol| loc_symbol.value));
+        }
+    }
+}
+
+impl<'a> Visitor for Resolver<'a> {
+    fn visit_decls(&mut self, decls: &Declarations) {
+        // parameters are visited after the name of their function, but before its body
+        for function in decls.function_bodies.iter() {
+            self.add_params(&function.value);
+        }
+
+        walk_decls(self, decls);
+    }
+
+    fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+        let end = region.end().offset;
+        let start = region.start().offset;
+
+        match expr {
+            Expr::Var(symbol, var) => {
+                let value = self.value(*symbol, Some(*var));
+                self.lowercase.insert(end, value);
+            }
+            Expr::AbilityMember(symbol, _, _) => {
+                let modifiers = if symbol.is_builtin() {
+                    MODIFIER_DEFAULT_LIBRARY
+                } else {
+                    0
+                };
+
+                self.lowercase
+                    .insert(end, (SemanticTokenType::AbilityMember, modifiers));
+            }
+            Expr::Tag { name, .. } | Expr::ZeroArgumentTag { name, .. } => {
+                self.tag_names.insert(name.0.as_str().to_string());
+                self.uppercase.insert(start, (SemanticTokenType::Tag, 0));
+            }
+            Expr::OpaqueRef { .. } => {
+                self.uppercase.insert(start, (SemanticTokenType::Type, 0));
+            }
+            Expr::Closure(data) => self.add_params(data),
+            _ => {}
+        }
+
+        walk_expr(self, expr, var);
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern, region: Region, opt_var: Option<Variable>) {
+        let end = region.end().offset;
+        let start = region.start().offset;
+
+        match pattern {
+            Pattern::Identifier(symbol)
+            | Pattern::Shadowed(_, _, symbol)
+            | Pattern::AbilityMemberSpecialization { ident: symbol, .. } => {
+                let (token_type, modifiers) = self.value(*symbol, opt_var);
+                self.lowercase
+                    .insert(end, (token_type, modifiers | MODIFIER_DECLARATION));
+            }
+            Pattern::As(_, symbol) => {
+                let (token_type, modifiers) = self.value(*symbol, None);
+                self.lowercase
+                    .insert(end, (token_type, modifiers | MODIFIER_DECLARATION));
+            }
+            Pattern::AppliedTag { .. } => {
+                self.uppercase.insert(start, (SemanticTokenType::Tag, 0));
+            }
+            Pattern::UnwrappedOpaque { .. } => {
+                self.uppercase.insert(start, (SemanticTokenType::Type, 0));
+            }
+            _ => {}
+        }
+
+        walk_pattern(self, pattern);
+    }
+
+    fn visit_record_destruct(&mut self, destruct: &RecordDestruct, region: Region) {
+        // `{ x }` and `{ x ? 0 }` bind `x`, but in `{ x: pat }`, `x` is just the field name
+        let field_end = region.start().offset + destruct.label.as_str().len() as u32;
+
+        let token = match destruct.typ {
+            DestructType::Guard(..) => (SemanticTokenType::Property, 0),
+            DestructType::Required | DestructType::Optional(..) => {
+                let (token_type, modifiers) = self.value(destruct.symbol, Some(destruct.var));
+                (token_type, modifiers | MODIFIER_DECLARATION)
+            }
+        };
+
+        self.lowercase.insert(field_end, token);
+
+        walk_record_destruct(self, destruct);
+    }
+}
+
+/// The semantic tokens of a module's source, in order. Identifiers which can't be resolved (like
+/// type variables) are left out, so that editors fall back to their own highlighting for them.
+pub fn semantic_tokens(
+    source: &str,
+    decls: &Declarations,
+    subs: &Subs,
+    abilities_store: &AbilitiesStore,
+    interns: &Interns,
+    hosted_modules: &[ModuleId],
+) -> Vec<SemanticToken> {
+    let mut resolver = Resolver {
+        subs,
+        hosted_modules,
+        params: MutSet::default(),
+        tag_names: MutSet::default(),
+        lowercase: MutMap::default(),
+        uppercase: MutMap::default(),
+    };
+
+    resolver.visit_decls(decls);
+
+    let abilities: MutSet<&str> = abilities_store
+        .iter_abilities()
+        .map(|(ability, _)| ability.as_str(interns))
+        .collect();
+
+    let highlighted = highlight(source);
+    let mut tokens = Vec::with_capacity(highlighted.len());
+
+    for (index, loc_token) in highlighted.iter().enumerate() {
+        use SemanticTokenType::*;
+
+        let region = loc_token.region;
+        let start = region.start().offset;
+        let end = region.end().offset;
+        let text = &source[start as usize..end as usize];
+
+        let resolved = match loc_token.value {
+            HighlightToken::Keyword | HighlightToken::HeaderKeyword => Some((Keyword, 0)),
+            HighlightToken::String
+            | HighlightToken::StringEscape
+            | HighlightToken::StringInterpolation
+            | HighlightToken::SingleQuote => Some((String, 0)),
+            HighlightToken::Number => Some((Number, 0)),
+            HighlightToken::Comment | HighlightToken::DocComment => Some((Comment, 0)),
+            HighlightToken::Operator => Some((Operator, 0)),
+            HighlightToken::LowercaseIdent => {
+                let after_dot = index > 0
+                    && highlighted[index - 1].region.end() == region.start()
+                    && &source[start as usize - 1..start as usize] == ".";
+
+                match resolver.lowercase.get(&end) {
+                    Some(resolved) => Some(*resolved),
+                    // record field access, like the `name` in `user.name`
+                    None if after_dot => Some((Property, 0)),
+                    None => None,
+                }
+            }
+            HighlightToken::UppercaseIdent | HighlightToken::OpaqueRef => {
+                let before_dot = source[end as usize..].starts_with('.');
+
+                match resolver.uppercase.get(&start) {
+                    Some(resolved) => Some(*resolved),
+                    None if before_dot => Some((Namespace, 0)),
+                    None if resolver.tag_names.contains(text) => Some((Tag, 0)),
+                    None if abilities.contains(text) => Some((Ability, 0)),
+                    None => Some((Type, 0)),
+                }
+            }
+            HighlightToken::Punctuation
+            | HighlightToken::LambdaStart
+            | HighlightToken::Underscore
+            | HighlightToken::Error => None,
+        };
+
+        if let Some((token_type, modifiers)) = resolved {
+            tokens.push(SemanticToken {
+                region,
+                token_type,
+                modifiers,
+            });
+        }
+    }
+
+    tokens
+}
+
+/// Encodes tokens the way LSP sends them: five numbers per token, giving its line and start
+/// relative to the previous token, its length, its type, and its modifiers. Positions are in
+/// UTF-16 code units, and tokens spanning several lines are split into one per line.
+pub fn encode_semantic_tokens(source: &str, tokens: &[SemanticToken]) -> Vec<u32> {
+    let mut data = Vec::with_capacity(tokens.len() * 5);
+
+    let mut offset = 0;
+    let (mut line, mut column) = (0, 0);
+    let (mut previous_line, mut previous_column) = (0, 0);
+
+    for token in tokens {
+        let start = token.region.start().offset as usize;
+        let end = token.region.end().offset as usize;
+
+        for ch in source[offset..start].chars() {
+            if ch == '\n' {
+                line += 1;
+                column = 0;
+            } else {
+                column += ch.len_utf16() as u32;
+            }
+        }
+
+        for (index, segment) in source[start..end].split('\n').enumerate() {
+            if index > 0 {
+                line += 1;
+                column = 0;
+            }
+
+            let length: u32 = segment.chars().map(|ch| ch.len_utf16() as u32).sum();
+            let visible_length = length - segment.ends_with('\r') as u32;
+
+            if visible_length > 0 {
+                let delta_line = line - previous_line;
+                let delta_column = if delta_line == 0 {
+                    column - previous_column
+                } else {
+                    column
+                };
+
+                data.extend([
+                    delta_line,
+                    delta_column,
+                    visible_length,
+                    token.token_type as u32,
+                    token.modifiers,
+                ]);
+
+                previous_line = line;
+                previous_column = column;
+            }
+
+            column += length;
+        }
+
+        offset = end;
+    }
+
+    data
+}
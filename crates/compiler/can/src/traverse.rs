@@ -57,6 +57,11 @@ pub fn walk_decls<V: Visitor>(visitor: &mut V, decls: &Declarations) {
 
                 visitor.visit_expr(&loc_condition.value, loc_condition.region, Variable::BOOL);
             }
+            Benchmark => {
+                let loc_body = &decls.expressions[index];
+
+                visitor.visit_expr(&loc_body.value, loc_body.region, decls.variables[index]);
+            }
             Function(function_index)
             | Recursive(function_index)
             | TailRecursive(function_index) => {
@@ -109,7 +114,7 @@ pub fn walk_decls<V: Visitor>(visitor: &mut V, decls: &Declarations) {
     }
 }
 
-fn walk_decl<V: Visitor>(visitor: &mut V, decl: &Declaration) {
+pub fn walk_decl<V: Visitor>(visitor: &mut V, decl: &Declaration) {
     match decl {
         Declaration::Declare(def) => {
             visitor.visit_def(def);
@@ -283,6 +288,7 @@ pub fn walk_expr<V: Visitor>(visitor: &mut V, expr: &Expr, var: Variable) {
             loc_condition,
             loc_continuation,
             lookups_in_cond: _,
+            always: _,
         } => {
             visitor.visit_expr(&loc_condition.value, loc_condition.region, Variable::BOOL);
             visitor.visit_expr(
@@ -308,6 +314,7 @@ pub fn walk_expr<V: Visitor>(visitor: &mut V, expr: &Expr, var: Variable) {
             loc_condition,
             loc_continuation,
             symbol: _,
+            label: _,
         } => {
             visitor.visit_expr(&loc_condition.value, loc_condition.region, *variable);
             visitor.visit_expr(
@@ -705,3 +712,105 @@ pub fn symbols_introduced_from_pattern(
         }
     }
 }
+
+/// Where a symbol's name is within the region of one of its [SymbolOccurrence]s
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameAt {
+    /// The region ends with the name, like `List.map` or `x` in `[_, ..] as x`
+    End,
+    /// The region starts with the name, like `x` in `{ x ? 0 }`, or `r` in `{ r & x: 1 }` (after
+    /// the brace)
+    Start,
+    /// The name comes just before the region, followed by a colon, like in `x : I64`
+    Before,
+}
+
+/// A place where a value symbol is defined or used
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SymbolOccurrence {
+    pub symbol: Symbol,
+    pub region: Region,
+    pub name_at: NameAt,
+}
+
+/// Finds every place a value symbol is defined, annotated, or used in the declarations
+pub fn symbol_occurrences(decls: &Declarations) -> Vec<SymbolOccurrence> {
+    let mut visitor = Collector {
+        occurrences: Vec::new(),
+    };
+    visitor.visit_decls(decls);
+    return visitor.occurrences;
+
+    struct Collector {
+        occurrences: Vec<SymbolOccurrence>,
+    }
+
+    impl Collector {
+        fn push(&mut self, symbol: Symbol, region: Region, name_at: NameAt) {
+            self.occurrences.push(SymbolOccurrence {
+                symbol,
+                region,
+                name_at,
+            });
+        }
+    }
+
+    impl Visitor for Collector {
+        fn visit_decls(&mut self, decls: &Declarations) {
+            for (index, annotation) in decls.annotations.iter().enumerate() {
+                if let Some(annotation) = annotation {
+                    self.push(
+                        decls.symbols[index].value,
+                        annotation.region,
+                        NameAt::Before,
+                    );
+                }
+            }
+
+            walk_decls(self, decls);
+        }
+
+        fn visit_def(&mut self, def: &Def) {
+            if let (Pattern::Identifier(symbol), Some(annotation)) =
+                (&def.loc_pattern.value, &def.annotation)
+            {
+                self.push(*symbol, annotation.region, NameAt::Before);
+            }
+
+            walk_def(self, def);
+        }
+
+        fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+            match expr {
+                Expr::Var(symbol, _) | Expr::AbilityMember(symbol, _, _) => {
+                    self.push(*symbol, region, NameAt::End)
+                }
+                Expr::RecordUpdate { symbol, .. } => self.push(*symbol, region, NameAt::Start),
+                _ => {}
+            }
+
+            walk_expr(self, expr, var);
+        }
+
+        fn visit_pattern(&mut self, pattern: &Pattern, region: Region, _opt_var: Option<Variable>) {
+            match pattern {
+                Pattern::Identifier(symbol)
+                | Pattern::Shadowed(_, _, symbol)
+                | Pattern::AbilityMemberSpecialization { ident: symbol, .. }
+                | Pattern::As(_, symbol) => self.push(*symbol, region, NameAt::End),
+                _ => {}
+            }
+
+            walk_pattern(self, pattern);
+        }
+
+        fn visit_record_destruct(&mut self, destruct: &RecordDestruct, region: Region) {
+            // in `{ x: pat }`, `x` is only the name of the field
+            if !matches!(destruct.typ, DestructType::Guard(..)) {
+                self.push(destruct.symbol, region, NameAt::Start);
+            }
+
+            walk_record_destruct(self, destruct);
+        }
+    }
+}
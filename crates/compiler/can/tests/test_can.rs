@@ -16,6 +16,7 @@ mod test_can {
     use bumpalo::Bump;
     use roc_can::expr::Expr::{self, *};
     use roc_can::expr::{ClosureData, IntValue, Recursive};
+    use roc_can::pattern::Pattern;
     use roc_problem::can::{CycleEntry, FloatErrorKind, IntErrorKind, Problem, RuntimeError};
     use roc_region::all::{Position, Region};
     use std::{f64, i64};
@@ -427,6 +428,90 @@ mod test_can {
         )));
     }
 
+    #[test]
+    fn rebinding_in_def_block() {
+        let src = indoc!(
+            r#"
+                x = 1
+                x = x + 1
+
+                x
+            "#
+        );
+        let arena = Bump::new();
+        let CanExprOut {
+            loc_expr, problems, ..
+        } = can_expr_with(&arena, test_home(), src);
+
+        assert_eq!(problems.len(), 1);
+        assert!(matches!(
+            problems[0],
+            Problem::Rebinding { ref shadow, .. } if shadow.value.as_str() == "x"
+        ));
+
+        // the return expression refers to the second def, which has a symbol of its own
+        match loc_expr.value {
+            LetNonRec(first, rest) => match rest.value {
+                LetNonRec(second, ret) => match (first.loc_pattern.value, second.loc_pattern.value)
+                {
+                    (Pattern::Identifier(first_symbol), Pattern::Identifier(second_symbol)) => {
+                        assert_ne!(first_symbol, second_symbol);
+                        assert!(matches!(ret.value, Var(symbol, _) if symbol == second_symbol));
+                    }
+                    other => panic!("expected two identifier patterns, got {:?}", other),
+                },
+                other => panic!("expected a second def, got {:?}", other),
+            },
+            other => panic!("expected a def, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rebinding_keeps_forward_references() {
+        // `y` refers to `z`, which is defined after the rebinding of `x`
+        let src = indoc!(
+            r#"
+                x = 1
+                y = z + x
+                x = x + 1
+                z = 10
+
+                y + x
+            "#
+        );
+        let arena = Bump::new();
+        let CanExprOut { problems, .. } = can_expr_with(&arena, test_home(), src);
+
+        assert_eq!(problems.len(), 1);
+        assert!(matches!(
+            problems[0],
+            Problem::Rebinding { ref shadow, .. } if shadow.value.as_str() == "x"
+        ));
+    }
+
+    #[test]
+    fn rebinding_with_annotation() {
+        let src = indoc!(
+            r#"
+                x : I64
+                x = 1
+
+                x : I64
+                x = x + 1
+
+                x
+            "#
+        );
+        let arena = Bump::new();
+        let CanExprOut { problems, .. } = can_expr_with(&arena, test_home(), src);
+
+        assert_eq!(problems.len(), 1);
+        assert!(matches!(
+            problems[0],
+            Problem::Rebinding { ref shadow, .. } if shadow.value.as_str() == "x"
+        ));
+    }
+
     #[test]
     fn correct_nested_unannotated_body() {
         let src = indoc!(
@@ -614,6 +614,7 @@ pub fn constrain_expr(
             loc_condition,
             loc_continuation,
             lookups_in_cond,
+            always: _,
         } => {
             let expected_bool = {
                 let bool_type = constraints.push_variable(Variable::BOOL);
@@ -729,6 +730,7 @@ pub fn constrain_expr(
             loc_continuation,
             variable,
             symbol: _,
+            label: _,
         } => {
             let dbg_type = constraints.push_variable(*variable);
             let expected_dbg = constraints.push_expected_type(Expected::NoExpectation(dbg_type));
@@ -2537,6 +2539,31 @@ pub fn constrain_decls(
                     Generalizable(false),
                 )
             }
+            Benchmark => {
+                let loc_expr = &declarations.expressions[index];
+                let expr_var = declarations.variables[index];
+
+                let body_type = constraints.push_variable(expr_var);
+                let expected = constraints.push_expected_type(Expected::NoExpectation(body_type));
+
+                let bench_constraint = constrain_expr(
+                    types,
+                    constraints,
+                    &mut env,
+                    loc_expr.region,
+                    &loc_expr.value,
+                    expected,
+                );
+
+                constraint = constraints.let_constraint(
+                    [],
+                    [expr_var],
+                    [],
+                    bench_constraint,
+                    constraint,
+                    Generalizable(false),
+                )
+            }
             Function(function_def_index) => {
                 constraint = constrain_function_def(
                     types,
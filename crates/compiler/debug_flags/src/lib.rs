@@ -148,6 +148,13 @@ flags! {
     /// Prints LLVM function verification output.
     ROC_PRINT_LLVM_FN_VERIFICATION
 
+    /// Prints the builtins bitcode functions that no Roc code references, and so get removed.
+    ROC_PRINT_UNUSED_BITCODE
+
+    /// Allocates every list literal on the heap, including the ones which could go in the scratch
+    /// region of the call that makes them.
+    ROC_NO_SCRATCH_REGIONS
+
     // ===WASM Gen===
 
     /// Writes a `final.wasm` file to /tmp
@@ -133,7 +133,13 @@ impl FlatEncodable {
             },
             Content::RangedNumber(_) => Err(Underivable),
             //
-            Content::RecursionVar { .. } => Err(Underivable),
+            Content::RecursionVar { structure, .. } => {
+                // A recursion var is reached when deriving for the payload of a recursive tag
+                // union. Its implementation is the same as that of the enclosing union, so just
+                // look through to the structure. This is cycle-safe, since keys only ever inspect
+                // the surface of the structure.
+                Self::from_var(subs, structure)
+            }
             Content::Error => Err(Underivable),
             Content::FlexVar(_)
             | Content::RigidVar(_)
@@ -54,6 +54,7 @@ impl<'a> Formattable for TypeDef<'a> {
             Alias { ann, .. } => ann.is_multiline(),
             Opaque { typ, .. } => typ.is_multiline(),
             Ability { members, .. } => members.iter().any(|d| d.is_multiline()),
+            PatternSynonym { pattern, .. } => pattern.is_multiline(),
         }
     }
 
@@ -174,6 +175,25 @@ impl<'a> Formattable for TypeDef<'a> {
                     }
                 }
             }
+            PatternSynonym {
+                header: TypeHeader { name, vars },
+                pattern,
+            } => {
+                buf.indent(indent);
+                buf.push_str("pattern ");
+                buf.push_str(name.value);
+
+                for var in *vars {
+                    buf.spaces(1);
+                    fmt_pattern(buf, &var.value, indent, Parens::InApply);
+                    buf.indent(indent);
+                }
+
+                buf.push_str(" =");
+                buf.spaces(1);
+
+                fmt_pattern(buf, &pattern.value, indent, Parens::NotNeeded);
+            }
         }
     }
 }
@@ -191,6 +211,7 @@ impl<'a> Formattable for ValueDef<'a> {
             Expect { condition, .. } => condition.is_multiline(),
             ExpectFx { condition, .. } => condition.is_multiline(),
             Dbg { condition, .. } => condition.is_multiline(),
+            Bench { body, .. } => body.is_multiline(),
         }
     }
 
@@ -266,10 +287,13 @@ impl<'a> Formattable for ValueDef<'a> {
                 fmt_body(buf, &loc_pattern.value, &loc_expr.value, indent);
             }
             Dbg { condition, .. } => fmt_dbg_in_def(buf, condition, self.is_multiline(), indent),
-            Expect { condition, .. } => fmt_expect(buf, condition, self.is_multiline(), indent),
+            Expect {
+                condition, always, ..
+            } => fmt_expect(buf, condition, *always, self.is_multiline(), indent),
             ExpectFx { condition, .. } => {
                 fmt_expect_fx(buf, condition, self.is_multiline(), indent)
             }
+            Bench { body, .. } => fmt_bench(buf, body, self.is_multiline(), indent),
             AnnotatedBody {
                 ann_pattern,
                 ann_type,
@@ -343,12 +367,13 @@ fn fmt_dbg_in_def<'a, 'buf>(
 fn fmt_expect<'a, 'buf>(
     buf: &mut Buf<'buf>,
     condition: &'a Loc<Expr<'a>>,
+    always: bool,
     is_multiline: bool,
     indent: u16,
 ) {
     buf.ensure_ends_with_newline();
     buf.indent(indent);
-    buf.push_str("expect");
+    buf.push_str(if always { "expect-always" } else { "expect" });
 
     let return_indent = if is_multiline {
         buf.newline();
@@ -382,6 +407,27 @@ fn fmt_expect_fx<'a, 'buf>(
     condition.format(buf, return_indent);
 }
 
+fn fmt_bench<'a, 'buf>(
+    buf: &mut Buf<'buf>,
+    body: &'a Loc<Expr<'a>>,
+    is_multiline: bool,
+    indent: u16,
+) {
+    buf.ensure_ends_with_newline();
+    buf.indent(indent);
+    buf.push_str("bench");
+
+    let return_indent = if is_multiline {
+        buf.newline();
+        indent + INDENT
+    } else {
+        buf.spaces(1);
+        indent
+    };
+
+    body.format(buf, return_indent);
+}
+
 pub fn fmt_value_def<'a, 'buf>(
     buf: &mut Buf<'buf>,
     def: &roc_parse::ast::ValueDef<'a>,
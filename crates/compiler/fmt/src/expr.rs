@@ -44,7 +44,8 @@ impl<'a> Formattable for Expr<'a> {
             | MalformedClosure
             | Tag(_)
             | OpaqueRef(_)
-            | Crash => false,
+            | Crash
+            | DbgKeyword => false,
 
             // These expressions always have newlines
             Defs(_, _) | When(_, _) => true,
@@ -60,6 +61,8 @@ impl<'a> Formattable for Expr<'a> {
                 condition.is_multiline() || continuation.is_multiline()
             }
             Dbg(condition, continuation) => condition.is_multiline() || continuation.is_multiline(),
+            Return(value) => value.is_multiline(),
+            Try(inner) => inner.is_multiline(),
 
             If(branches, final_else) => {
                 final_else.is_multiline()
@@ -183,6 +186,16 @@ impl<'a> Formattable for Expr<'a> {
                 buf.indent(indent);
                 buf.push_str("crash");
             }
+            DbgKeyword => {
+                buf.indent(indent);
+                buf.push_str("dbg");
+            }
+            Return(value) => {
+                buf.indent(indent);
+                buf.push_str("return");
+                buf.spaces(1);
+                value.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent);
+            }
             Apply(loc_expr, loc_args, _) => {
                 // Sadly this assertion fails in practice. The fact that the parser produces code like this is going to
                 // confuse the formatter, because it depends on being able to "see" spaces that logically come before the inner
@@ -471,6 +484,10 @@ impl<'a> Formattable for Expr<'a> {
                 buf.push('.');
                 buf.push_str(key);
             }
+            Try(expr) => {
+                expr.format_with_options(buf, Parens::InApply, Newlines::Yes, indent);
+                buf.push('?');
+            }
             MalformedIdent(str, _) => {
                 buf.indent(indent);
                 buf.push_str(str)
@@ -984,6 +1001,17 @@ fn fmt_if<'a, 'buf>(
     //    let is_multiline_condition = loc_condition.is_multiline();
     //    let is_multiline = is_multiline_then || is_multiline_else || is_multiline_condition;
 
+    // A guard like `if x < 0 then return Err Neg` has no `else`; the code after it is the `else`.
+    // That code is always on the lines below, so it doesn't make the guard itself multiline.
+    let is_guard = matches!(branches.last(), Some((_, loc_then)) if loc_then.value.is_return());
+    let is_multiline = if is_guard {
+        branches
+            .iter()
+            .any(|(c, t)| c.is_multiline() || t.is_multiline())
+    } else {
+        is_multiline
+    };
+
     let return_indent = if is_multiline {
         indent + INDENT
     } else {
@@ -1109,6 +1137,26 @@ fn fmt_if<'a, 'buf>(
         }
     }
 
+    if is_guard {
+        buf.ensure_ends_with_newline();
+
+        match &final_else.value {
+            Expr::SpaceBefore(sub_expr, spaces) => {
+                // The first newline is the one ending the guard's line, which is already there
+                let spaces = match spaces.split_first() {
+                    Some((CommentOrNewline::Newline, rest)) => rest,
+                    _ => spaces,
+                };
+
+                fmt_spaces(buf, spaces.iter(), indent);
+                sub_expr.format(buf, indent);
+            }
+            _ => final_else.format(buf, indent),
+        }
+
+        return;
+    }
+
     buf.indent(indent);
     if is_multiline {
         buf.push_str("else");
@@ -7,10 +7,12 @@ use crate::Buf;
 use bumpalo::Bump;
 use roc_parse::ast::{Collection, Header, Module, Spaced, Spaces};
 use roc_parse::header::{
-    AppHeader, ExposedName, ExposesKeyword, GeneratesKeyword, HostedHeader, ImportsEntry,
-    ImportsKeyword, InterfaceHeader, Keyword, KeywordItem, ModuleName, PackageEntry, PackageHeader,
-    PackageKeyword, PackageName, PackagesKeyword, PlatformHeader, PlatformRequires,
-    ProvidesKeyword, ProvidesTo, RequiresKeyword, To, ToKeyword, TypedIdent, WithKeyword,
+    AllocatorEntry, AllocatorKeyword, AppHeader, BuildCommand, BuildKeyword, EffectsEntry,
+    EffectsKeyword, ExposedName, ExposesKeyword, GeneratesKeyword, HostedHeader, HostsEntry,
+    HostsKeyword, ImportsEntry, ImportsKeyword, InterfaceHeader, Keyword, KeywordItem, ModuleName,
+    PackageEntry, PackageHeader, PackageKeyword, PackageName, PackagesKeyword, PlatformHeader,
+    PlatformRequires, ProvidesKeyword, ProvidesTo, RequiresKeyword, To, ToKeyword, TypedIdent,
+    WithKeyword,
 };
 use roc_parse::ident::UppercaseIdent;
 use roc_region::all::Loc;
@@ -75,6 +77,10 @@ keywords! {
     RequiresKeyword,
     ProvidesKeyword,
     ToKeyword,
+    AllocatorKeyword,
+    EffectsKeyword,
+    HostsKeyword,
+    BuildKeyword,
 }
 
 impl<V: Formattable> Formattable for Option<V> {
@@ -260,6 +266,23 @@ pub fn fmt_platform_header<'a, 'buf>(buf: &mut Buf<'buf>, header: &'a PlatformHe
     fmt_imports(buf, header.imports.item, indent);
     header.provides.keyword.format(buf, indent);
     fmt_provides(buf, header.provides.item, None, indent);
+
+    if let Some(allocator) = &header.allocator {
+        allocator.keyword.format(buf, indent);
+        fmt_collection(buf, indent, Braces::Curly, allocator.item, Newlines::No);
+    }
+
+    if let Some(effects) = &header.effects {
+        effects.keyword.format(buf, indent);
+        fmt_collection(buf, indent, Braces::Curly, effects.item, Newlines::No);
+    }
+
+    if let Some(hosts) = &header.hosts {
+        hosts.keyword.format(buf, indent);
+        fmt_collection(buf, indent, Braces::Curly, hosts.item, Newlines::No);
+    }
+
+    header.build.format(buf, indent);
 }
 
 fn fmt_requires<'a, 'buf>(buf: &mut Buf<'buf>, requires: &PlatformRequires<'a>, indent: u16) {
@@ -452,6 +475,108 @@ impl<'a> Formattable for PackageEntry<'a> {
     }
 }
 
+impl<'a> Formattable for AllocatorEntry<'a> {
+    fn is_multiline(&self) -> bool {
+        false
+    }
+
+    fn format_with_options<'buf>(
+        &self,
+        buf: &mut Buf<'buf>,
+        _parens: Parens,
+        _newlines: Newlines,
+        indent: u16,
+    ) {
+        buf.indent(indent);
+
+        match self {
+            AllocatorEntry::Strategy {
+                spaces_after_colon,
+                strategy,
+            } => {
+                buf.push_str("strategy:");
+                fmt_default_spaces(buf, spaces_after_colon, indent);
+                buf.push_str(strategy.value.as_str());
+            }
+            AllocatorEntry::Alignment {
+                spaces_after_colon,
+                alignment,
+            } => {
+                buf.push_str("alignment:");
+                fmt_default_spaces(buf, spaces_after_colon, indent);
+                buf.push_str(&alignment.value.to_string());
+            }
+        }
+    }
+}
+
+impl<'a> Formattable for EffectsEntry<'a> {
+    fn is_multiline(&self) -> bool {
+        false
+    }
+
+    fn format_with_options<'buf>(
+        &self,
+        buf: &mut Buf<'buf>,
+        _parens: Parens,
+        _newlines: Newlines,
+        indent: u16,
+    ) {
+        buf.indent(indent);
+
+        match self {
+            EffectsEntry::Submit {
+                spaces_after_colon,
+                submission,
+            } => {
+                buf.push_str("submit:");
+                fmt_default_spaces(buf, spaces_after_colon, indent);
+                buf.push_str(submission.value.as_str());
+            }
+        }
+    }
+}
+
+impl<'a> Formattable for HostsEntry<'a> {
+    fn is_multiline(&self) -> bool {
+        false
+    }
+
+    fn format_with_options<'buf>(
+        &self,
+        buf: &mut Buf<'buf>,
+        _parens: Parens,
+        _newlines: Newlines,
+        indent: u16,
+    ) {
+        buf.indent(indent);
+        buf.push('"');
+        buf.push_str(self.target.value);
+        buf.push_str("\":");
+        fmt_default_spaces(buf, self.spaces_after_colon, indent);
+        fmt_package_name(buf, self.url.value, indent);
+    }
+}
+
+impl<'a> Formattable for BuildCommand<'a> {
+    fn is_multiline(&self) -> bool {
+        false
+    }
+
+    fn format_with_options<'buf>(
+        &self,
+        buf: &mut Buf<'buf>,
+        _parens: Parens,
+        _newlines: Newlines,
+        indent: u16,
+    ) {
+        buf.indent(indent);
+        buf.push('"');
+        buf.push_str_allow_spaces(self.to_str());
+        buf.push('"');
+    }
+}
+
 impl<'a> Formattable for ImportsEntry<'a> {
     fn is_multiline(&self) -> bool {
         false
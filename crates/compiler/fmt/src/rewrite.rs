@@ -0,0 +1,217 @@
+//! Rewriting parts of a source file in place, leaving the rest of it byte-for-byte as it was.
+//! Tools like `roc rename` and `roc imports --fix` collect edits with a [Rewriter], then apply
+//! them all at once, so no edit has to account for how the others moved things around.
+use roc_region::all::{Position, Region};
+use std::fmt;
+use std::ops::Range;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Edit {
+    region: Region,
+    replacement: String,
+    reindent: bool,
+}
+
+/// Two edits which both change some of the same text, so they can't both be applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverlappingEdits {
+    pub first: Region,
+    pub second: Region,
+}
+
+impl fmt::Display for OverlappingEdits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the edit of bytes {}..{} overlaps the edit of bytes {}..{}",
+            self.first.start().offset,
+            self.first.end().offset,
+            self.second.start().offset,
+            self.second.end().offset
+        )
+    }
+}
+
+impl std::error::Error for OverlappingEdits {}
+
+#[derive(Debug)]
+pub struct Rewriter<'a> {
+    source: &'a str,
+    edits: Vec<Edit>,
+}
+
+impl<'a> Rewriter<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            edits: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Replaces the text in `region` with `replacement`, exactly as it's given
+    pub fn replace(&mut self, region: Region, replacement: impl Into<String>) {
+        self.push(region, replacement.into(), false);
+    }
+
+    /// Replaces the text in `region` with `replacement`, indenting each of its lines after the
+    /// first as much as the line `region` starts on. So `replacement` should be written as if it
+    /// started in the first column.
+    pub fn replace_indented(&mut self, region: Region, replacement: impl Into<String>) {
+        self.push(region, replacement.into(), true);
+    }
+
+    /// Inserts `text` at `position`. Several insertions at the same position go in the order
+    /// they were made.
+    pub fn insert(&mut self, position: Position, text: impl Into<String>) {
+        self.push(Region::new(position, position), text.into(), false);
+    }
+
+    fn push(&mut self, region: Region, replacement: String, reindent: bool) {
+        debug_assert!(region.end().offset as usize <= self.source.len());
+
+        self.edits.push(Edit {
+            region,
+            replacement,
+            reindent,
+        });
+    }
+
+    /// Applies every edit. Making the same edit more than once is the same as making it once,
+    /// but edits which change overlapping text are an error.
+    pub fn apply(mut self) -> Result<String, OverlappingEdits> {
+        // a stable sort, so insertions at the same position keep their order
+        self.edits
+            .sort_by_key(|edit| (edit.region.start(), edit.region.end()));
+        self.edits.dedup();
+
+        for pair in self.edits.windows(2) {
+            let (first, second) = (&pair[0], &pair[1]);
+
+            if first.region.end() > second.region.start() {
+                return Err(OverlappingEdits {
+                    first: first.region,
+                    second: second.region,
+                });
+            }
+        }
+
+        let source = self.source;
+        let mut rewritten = String::with_capacity(source.len());
+        let mut copied_up_to = 0;
+
+        for edit in self.edits.iter() {
+            let start = edit.region.start().offset as usize;
+            let end = edit.region.end().offset as usize;
+
+            rewritten.push_str(&source[copied_up_to..start]);
+
+            if edit.reindent {
+                push_indented(&mut rewritten, &edit.replacement, indent_at(source, start));
+            } else {
+                rewritten.push_str(&edit.replacement);
+            }
+
+            copied_up_to = end;
+        }
+
+        rewritten.push_str(&source[copied_up_to..]);
+
+        Ok(rewritten)
+    }
+}
+
+/// The region between two byte offsets in a source file
+pub fn byte_region(range: Range<usize>) -> Region {
+    Region::new(
+        Position::new(range.start as u32),
+        Position::new(range.end as u32),
+    )
+}
+
+/// The spaces at the start of the line `offset` is on
+fn indent_at(source: &str, offset: usize) -> &str {
+    let line_start = source[..offset].rfind('\n').map_or(0, |index| index + 1);
+    let line = &source[line_start..];
+
+    &line[..line.len() - line.trim_start_matches(' ').len()]
+}
+
+fn push_indented(buf: &mut String, text: &str, indent: &str) {
+    for (index, line) in text.split('\n').enumerate() {
+        if index > 0 {
+            buf.push('\n');
+
+            if !line.is_empty() {
+                buf.push_str(indent);
+            }
+        }
+
+        buf.push_str(line);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn edits_apply_in_source_order() {
+        let mut rewriter = Rewriter::new("foo = bar baz");
+        rewriter.replace(byte_region(10..13), "quux");
+        rewriter.replace(byte_region(0..3), "f");
+        rewriter.insert(Position::new(13), "!");
+
+        assert_eq!(rewriter.apply().unwrap(), "f = bar quux!");
+    }
+
+    #[test]
+    fn repeated_edits_apply_once() {
+        let mut rewriter = Rewriter::new("x + x");
+        rewriter.replace(byte_region(0..1), "y");
+        rewriter.replace(byte_region(0..1), "y");
+        rewriter.replace(byte_region(4..5), "y");
+
+        assert_eq!(rewriter.apply().unwrap(), "y + y");
+    }
+
+    #[test]
+    fn overlapping_edits() {
+        let mut rewriter = Rewriter::new("List.map list f");
+        rewriter.replace(byte_region(0..8), "List.walk");
+        rewriter.replace(byte_region(5..8), "keepIf");
+
+        assert_eq!(
+            rewriter.apply(),
+            Err(OverlappingEdits {
+                first: byte_region(0..8),
+                second: byte_region(5..8),
+            })
+        );
+    }
+
+    #[test]
+    fn adjacent_edits_dont_overlap() {
+        let mut rewriter = Rewriter::new("ab");
+        rewriter.replace(byte_region(0..1), "x");
+        rewriter.insert(Position::new(1), "-");
+        rewriter.replace(byte_region(1..2), "y");
+
+        assert_eq!(rewriter.apply().unwrap(), "x-y");
+    }
+
+    #[test]
+    fn replace_indented() {
+        let source = "interface Foo\n    exposes []\n    imports [A]\n";
+        let mut rewriter = Rewriter::new(source);
+        rewriter.replace_indented(byte_region(41..44), "[\n    A,\n\n    B,\n]");
+
+        assert_eq!(
+            rewriter.apply().unwrap(),
+            "interface Foo\n    exposes []\n    imports [\n        A,\n\n        B,\n    ]\n"
+        );
+    }
+}
@@ -8,9 +8,9 @@ use roc_parse::{
         StrSegment, Tag, TypeAnnotation, TypeDef, TypeHeader, ValueDef, WhenBranch,
     },
     header::{
-        AppHeader, ExposedName, HostedHeader, ImportsEntry, InterfaceHeader, KeywordItem,
-        ModuleName, PackageEntry, PackageHeader, PackageName, PlatformHeader, PlatformRequires,
-        ProvidesTo, To, TypedIdent,
+        AllocatorEntry, AppHeader, BuildCommand, EffectsEntry, ExposedName, HostedHeader,
+        HostsEntry, ImportsEntry, InterfaceHeader, KeywordItem, ModuleName, PackageEntry,
+        PackageHeader, PackageName, PlatformHeader, PlatformRequires, ProvidesTo, To, TypedIdent,
     },
     ident::{BadIdent, UppercaseIdent},
 };
@@ -314,6 +314,10 @@ impl<'a> RemoveSpaces<'a> for Module<'a> {
                 packages: header.packages.remove_spaces(arena),
                 imports: header.imports.remove_spaces(arena),
                 provides: header.provides.remove_spaces(arena),
+                allocator: header.allocator.remove_spaces(arena),
+                effects: header.effects.remove_spaces(arena),
+                hosts: header.hosts.remove_spaces(arena),
+                build: header.build.remove_spaces(arena),
             }),
             Header::Hosted(header) => Header::Hosted(HostedHeader {
                 before_name: &[],
@@ -371,6 +375,12 @@ impl<'a> RemoveSpaces<'a> for PackageName<'a> {
     }
 }
 
+impl<'a> RemoveSpaces<'a> for BuildCommand<'a> {
+    fn remove_spaces(&self, _arena: &'a Bump) -> Self {
+        *self
+    }
+}
+
 impl<'a> RemoveSpaces<'a> for To<'a> {
     fn remove_spaces(&self, arena: &'a Bump) -> Self {
         match *self {
@@ -415,6 +425,42 @@ impl<'a> RemoveSpaces<'a> for PackageEntry<'a> {
     }
 }
 
+impl<'a> RemoveSpaces<'a> for AllocatorEntry<'a> {
+    fn remove_spaces(&self, _arena: &'a Bump) -> Self {
+        match *self {
+            AllocatorEntry::Strategy { strategy, .. } => AllocatorEntry::Strategy {
+                spaces_after_colon: &[],
+                strategy: Loc::at_zero(strategy.value),
+            },
+            AllocatorEntry::Alignment { alignment, .. } => AllocatorEntry::Alignment {
+                spaces_after_colon: &[],
+                alignment: Loc::at_zero(alignment.value),
+            },
+        }
+    }
+}
+
+impl<'a> RemoveSpaces<'a> for EffectsEntry<'a> {
+    fn remove_spaces(&self, _arena: &'a Bump) -> Self {
+        match *self {
+            EffectsEntry::Submit { submission, .. } => EffectsEntry::Submit {
+                spaces_after_colon: &[],
+                submission: Loc::at_zero(submission.value),
+            },
+        }
+    }
+}
+
+impl<'a> RemoveSpaces<'a> for HostsEntry<'a> {
+    fn remove_spaces(&self, _arena: &'a Bump) -> Self {
+        HostsEntry {
+            target: Loc::at_zero(self.target.value),
+            spaces_after_colon: &[],
+            url: Loc::at_zero(self.url.value),
+        }
+    }
+}
+
 impl<'a> RemoveSpaces<'a> for ImportsEntry<'a> {
     fn remove_spaces(&self, arena: &'a Bump) -> Self {
         match *self {
@@ -521,6 +567,16 @@ impl<'a> RemoveSpaces<'a> for TypeDef<'a> {
                 loc_has: loc_has.remove_spaces(arena),
                 members: members.remove_spaces(arena),
             },
+            PatternSynonym {
+                header: TypeHeader { name, vars },
+                pattern,
+            } => PatternSynonym {
+                header: TypeHeader {
+                    name: name.remove_spaces(arena),
+                    vars: vars.remove_spaces(arena),
+                },
+                pattern: pattern.remove_spaces(arena),
+            },
         }
     }
 }
@@ -558,9 +614,11 @@ impl<'a> RemoveSpaces<'a> for ValueDef<'a> {
             Expect {
                 condition,
                 preceding_comment: _,
+                always,
             } => Expect {
                 condition: arena.alloc(condition.remove_spaces(arena)),
                 preceding_comment: Region::zero(),
+                always,
             },
             ExpectFx {
                 condition,
@@ -569,6 +627,13 @@ impl<'a> RemoveSpaces<'a> for ValueDef<'a> {
                 condition: arena.alloc(condition.remove_spaces(arena)),
                 preceding_comment: Region::zero(),
             },
+            Bench {
+                body,
+                preceding_comment: _,
+            } => Bench {
+                body: arena.alloc(body.remove_spaces(arena)),
+                preceding_comment: Region::zero(),
+            },
         }
     }
 }
@@ -674,6 +739,7 @@ impl<'a> RemoveSpaces<'a> for Expr<'a> {
                 arena.alloc(b.remove_spaces(arena)),
             ),
             Expr::Crash => Expr::Crash,
+            Expr::DbgKeyword => Expr::DbgKeyword,
             Expr::Defs(a, b) => {
                 let mut defs = a.clone();
                 defs.space_before = vec![Default::default(); defs.len()];
@@ -704,6 +770,8 @@ impl<'a> RemoveSpaces<'a> for Expr<'a> {
                 arena.alloc(a.remove_spaces(arena)),
                 arena.alloc(b.remove_spaces(arena)),
             ),
+            Expr::Return(a) => Expr::Return(arena.alloc(a.remove_spaces(arena))),
+            Expr::Try(a) => Expr::Try(arena.alloc(a.remove_spaces(arena))),
             Expr::Apply(a, b, c) => Expr::Apply(
                 arena.alloc(a.remove_spaces(arena)),
                 b.remove_spaces(arena),
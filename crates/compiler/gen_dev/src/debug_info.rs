@@ -0,0 +1,357 @@
+//! Minimal DWARF (version 4) debug info for the code the dev backend generates: a line table that
+//! maps the start of each procedure to the line of the def it was specialized from, and a
+//! `DW_TAG_subprogram` for each procedure. That's enough for a debugger to show which Roc function
+//! it's stopped in, and to set breakpoints by `file:line` on the first line of a def.
+//!
+//! Only ELF objects get debug info for now.
+use object::write::{Object, Relocation, SectionId, SymbolId};
+use object::{RelocationEncoding, RelocationKind, SectionKind};
+use roc_collections::all::MutMap;
+use roc_error_macros::internal_error;
+use roc_module::symbol::{ModuleId, Symbol};
+use roc_region::all::{LineInfo, Region};
+use std::path::PathBuf;
+
+const DWARF_VERSION: u16 = 4;
+const ADDRESS_SIZE: u8 = 8;
+
+/// Roc doesn't have a DWARF language code, so this uses the first user-defined one.
+const DW_LANG_ROC: u16 = 0x8000;
+
+const DW_TAG_COMPILE_UNIT: u8 = 0x11;
+const DW_TAG_SUBPROGRAM: u8 = 0x2e;
+const DW_CHILDREN_NO: u8 = 0;
+const DW_CHILDREN_YES: u8 = 1;
+const DW_AT_NAME: u8 = 0x03;
+const DW_AT_STMT_LIST: u8 = 0x10;
+const DW_AT_LOW_PC: u8 = 0x11;
+const DW_AT_HIGH_PC: u8 = 0x12;
+const DW_AT_LANGUAGE: u8 = 0x13;
+const DW_AT_PRODUCER: u8 = 0x25;
+const DW_AT_DECL_FILE: u8 = 0x3a;
+const DW_AT_DECL_LINE: u8 = 0x3b;
+const DW_FORM_ADDR: u8 = 0x01;
+const DW_FORM_DATA2: u8 = 0x05;
+const DW_FORM_DATA8: u8 = 0x07;
+const DW_FORM_STRING: u8 = 0x08;
+const DW_FORM_UDATA: u8 = 0x0f;
+const DW_FORM_SEC_OFFSET: u8 = 0x17;
+
+const DW_LNS_COPY: u8 = 0x01;
+const DW_LNS_ADVANCE_PC: u8 = 0x02;
+const DW_LNS_ADVANCE_LINE: u8 = 0x03;
+const DW_LNS_SET_FILE: u8 = 0x04;
+const DW_LNE_END_SEQUENCE: u8 = 0x01;
+const DW_LNE_SET_ADDRESS: u8 = 0x02;
+
+const LINE_BASE: i8 = -5;
+const LINE_RANGE: u8 = 14;
+const OPCODE_BASE: u8 = 13;
+const STANDARD_OPCODE_LENGTHS: [u8; OPCODE_BASE as usize - 1] =
+    [0, 1, 1, 1, 1, 0, 0, 0, 1, 0, 0, 1];
+
+/// Which file and line each top-level def is on
+#[derive(Debug, Default)]
+pub struct DefLocations {
+    /// Each module's source file
+    files: Vec<(ModuleId, PathBuf)>,
+    /// The index of each def's file in `files`, and its (one-based) line
+    lines: MutMap<Symbol, (usize, u32)>,
+}
+
+impl DefLocations {
+    pub fn new(
+        def_regions: &MutMap<Symbol, Region>,
+        sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    ) -> Self {
+        let mut files = Vec::with_capacity(sources.len());
+        let mut file_indices = MutMap::default();
+
+        for (module_id, (path, src)) in sources {
+            file_indices.insert(*module_id, (files.len(), LineInfo::new(src)));
+            files.push((*module_id, path.clone()));
+        }
+
+        let lines = def_regions
+            .iter()
+            .filter_map(|(symbol, region)| {
+                let (file_index, line_info) = file_indices.get(&symbol.module_id())?;
+                let line = line_info.convert_pos(region.start()).line;
+
+                Some((*symbol, (*file_index, line + 1)))
+            })
+            .collect();
+
+        Self { files, lines }
+    }
+}
+
+/// A procedure that was added to the object
+pub(crate) struct DebugProc {
+    pub name: String,
+    pub symbol: Symbol,
+    pub proc_id: SymbolId,
+}
+
+/// Adds `.debug_abbrev`, `.debug_info`, and `.debug_line` sections describing `procs` to `output`,
+/// as a compile unit named after the source file of `module_id`. Procedures whose def isn't in
+/// `locations` (like refcounting helpers) are left out.
+pub(crate) fn add_debug_info(
+    output: &mut Object,
+    locations: &DefLocations,
+    module_id: ModuleId,
+    procs: &[DebugProc],
+) {
+    let procs: Vec<(&DebugProc, usize, u32, u64)> = procs
+        .iter()
+        .filter_map(|proc| {
+            let (file_index, line) = locations.lines.get(&proc.symbol)?;
+            let size = output.symbol(proc.proc_id).size;
+
+            Some((proc, *file_index, *line, size))
+        })
+        .collect();
+
+    if procs.is_empty() {
+        return;
+    }
+
+    let abbrev_section = output.add_section(vec![], b".debug_abbrev".to_vec(), SectionKind::Debug);
+    let info_section = output.add_section(vec![], b".debug_info".to_vec(), SectionKind::Debug);
+    let line_section = output.add_section(vec![], b".debug_line".to_vec(), SectionKind::Debug);
+
+    output.append_section_data(abbrev_section, &debug_abbrev(), 1);
+
+    // .debug_line
+    let mut line = DwarfBuf::default();
+    let unit_length = line.reserve_u32();
+    line.u16(DWARF_VERSION);
+    let header_length = line.reserve_u32();
+    let header_start = line.bytes.len();
+
+    line.u8(1); // minimum_instruction_length
+    line.u8(1); // maximum_operations_per_instruction
+    line.u8(1); // default_is_stmt
+    line.u8(LINE_BASE as u8);
+    line.u8(LINE_RANGE);
+    line.u8(OPCODE_BASE);
+    line.bytes.extend(STANDARD_OPCODE_LENGTHS);
+    line.u8(0); // no include_directories
+
+    for (_, file) in &locations.files {
+        line.string(&file.to_string_lossy());
+        line.uleb128(0); // directory index
+        line.uleb128(0); // modification time
+        line.uleb128(0); // length
+    }
+
+    line.u8(0);
+    line.patch_u32(header_length, (line.bytes.len() - header_start) as u32);
+
+    let mut line_relocations = Vec::with_capacity(procs.len());
+
+    for (proc, file_index, def_line, size) in &procs {
+        line.u8(0);
+        line.uleb128(1 + ADDRESS_SIZE as u64);
+        line.u8(DW_LNE_SET_ADDRESS);
+        line_relocations.push((line.bytes.len(), proc.proc_id));
+        line.u64(0);
+
+        line.u8(DW_LNS_SET_FILE);
+        line.uleb128(*file_index as u64 + 1);
+        line.u8(DW_LNS_ADVANCE_LINE);
+        line.sleb128(*def_line as i64 - 1);
+        line.u8(DW_LNS_COPY);
+
+        line.u8(DW_LNS_ADVANCE_PC);
+        line.uleb128(*size);
+        line.u8(0);
+        line.uleb128(1);
+        line.u8(DW_LNE_END_SEQUENCE);
+    }
+
+    line.patch_u32(unit_length, (line.bytes.len() - 4) as u32);
+    output.append_section_data(line_section, &line.bytes, 1);
+
+    // .debug_info
+    let mut info = DwarfBuf::default();
+    let unit_length = info.reserve_u32();
+    info.u16(DWARF_VERSION);
+    let abbrev_offset = info.reserve_u32();
+    info.u8(ADDRESS_SIZE);
+
+    info.uleb128(1);
+    info.string(concat!("roc dev backend ", env!("CARGO_PKG_VERSION")));
+    info.u16(DW_LANG_ROC);
+    let compile_unit_name = locations
+        .files
+        .iter()
+        .find(|(file_module_id, _)| *file_module_id == module_id)
+        .map(|(_, path)| path.to_string_lossy())
+        .unwrap_or_default();
+    info.string(&compile_unit_name);
+    let stmt_list = info.reserve_u32();
+
+    let mut info_relocations = Vec::with_capacity(procs.len());
+
+    for (proc, file_index, def_line, size) in &procs {
+        info.uleb128(2);
+        info.string(&proc.name);
+        info_relocations.push((info.bytes.len(), proc.proc_id));
+        info.u64(0);
+        info.u64(*size);
+        info.uleb128(*file_index as u64 + 1);
+        info.uleb128(*def_line as u64);
+    }
+
+    info.u8(0); // end of the compile unit's children
+    info.patch_u32(unit_length, (info.bytes.len() - 4) as u32);
+    output.append_section_data(info_section, &info.bytes, 1);
+
+    // Addresses are relocated against the procedures, and offsets against the start of the
+    // sections they point into, since the linker concatenates these sections across objects.
+    let abbrev_symbol = output.section_symbol(abbrev_section);
+    let line_symbol = output.section_symbol(line_section);
+
+    add_relocation(output, info_section, abbrev_offset, 32, abbrev_symbol);
+    add_relocation(output, info_section, stmt_list, 32, line_symbol);
+
+    for (offset, proc_id) in info_relocations {
+        add_relocation(output, info_section, offset, 64, proc_id);
+    }
+
+    for (offset, proc_id) in line_relocations {
+        add_relocation(output, line_section, offset, 64, proc_id);
+    }
+}
+
+fn debug_abbrev() -> Vec<u8> {
+    let mut abbrev = DwarfBuf::default();
+
+    abbrev.uleb128(1);
+    abbrev.u8(DW_TAG_COMPILE_UNIT);
+    abbrev.u8(DW_CHILDREN_YES);
+    abbrev.bytes.extend([
+        DW_AT_PRODUCER,
+        DW_FORM_STRING,
+        DW_AT_LANGUAGE,
+        DW_FORM_DATA2,
+        DW_AT_NAME,
+        DW_FORM_STRING,
+        DW_AT_STMT_LIST,
+        DW_FORM_SEC_OFFSET,
+        0,
+        0,
+    ]);
+
+    abbrev.uleb128(2);
+    abbrev.u8(DW_TAG_SUBPROGRAM);
+    abbrev.u8(DW_CHILDREN_NO);
+    abbrev.bytes.extend([
+        DW_AT_NAME,
+        DW_FORM_STRING,
+        DW_AT_LOW_PC,
+        DW_FORM_ADDR,
+        // In DWARF 4, a constant high_pc is the size of the code rather than its end address.
+        DW_AT_HIGH_PC,
+        DW_FORM_DATA8,
+        DW_AT_DECL_FILE,
+        DW_FORM_UDATA,
+        DW_AT_DECL_LINE,
+        DW_FORM_UDATA,
+        0,
+        0,
+    ]);
+
+    abbrev.u8(0);
+
+    abbrev.bytes
+}
+
+fn add_relocation(
+    output: &mut Object,
+    section_id: SectionId,
+    offset: usize,
+    size: u8,
+    symbol: SymbolId,
+) {
+    let relocation = Relocation {
+        offset: offset as u64,
+        size,
+        kind: RelocationKind::Absolute,
+        encoding: RelocationEncoding::Generic,
+        symbol,
+        addend: 0,
+    };
+
+    if let Err(e) = output.add_relocation(section_id, relocation) {
+        internal_error!("{:?}", e);
+    }
+}
+
+/// Little-endian encoding of the values DWARF sections are made of
+#[derive(Default)]
+struct DwarfBuf {
+    bytes: Vec<u8>,
+}
+
+impl DwarfBuf {
+    fn u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    fn u16(&mut self, value: u16) {
+        self.bytes.extend(value.to_le_bytes());
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.bytes.extend(value.to_le_bytes());
+    }
+
+    /// Leaves room for a u32 that isn't known yet, and returns where it is
+    fn reserve_u32(&mut self) -> usize {
+        let offset = self.bytes.len();
+        self.bytes.extend([0; 4]);
+
+        offset
+    }
+
+    fn patch_u32(&mut self, offset: usize, value: u32) {
+        self.bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn string(&mut self, string: &str) {
+        self.bytes.extend(string.as_bytes());
+        self.bytes.push(0);
+    }
+
+    fn uleb128(&mut self, mut value: u64) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+
+            if value == 0 {
+                self.bytes.push(byte);
+                break;
+            }
+
+            self.bytes.push(byte | 0x80);
+        }
+    }
+
+    fn sleb128(&mut self, mut value: i64) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+
+            let done = (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0);
+
+            if done {
+                self.bytes.push(byte);
+                break;
+            }
+
+            self.bytes.push(byte | 0x80);
+        }
+    }
+}
@@ -1,10 +1,13 @@
-use crate::generic64::{storage::StorageManager, Assembler, CallConv, RegTrait};
-use crate::Relocation;
+use crate::generic64::{storage::StorageManager, Assembler, CallConv, RegTrait, RoundingMode};
+use crate::{
+    single_register_floats, single_register_int_builtins, single_register_integers,
+    single_register_layouts, Relocation,
+};
 use bumpalo::collections::Vec;
 use packed_struct::prelude::*;
 use roc_error_macros::internal_error;
 use roc_module::symbol::Symbol;
-use roc_mono::layout::{InLayout, STLayoutInterner};
+use roc_mono::layout::{InLayout, Layout, LayoutInterner, STLayoutInterner};
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
 #[allow(dead_code)]
@@ -99,9 +102,44 @@ impl AArch64GeneralReg {
     }
 }
 
+/// The SIMD and floating point registers.
+/// They are used as `Dn` for 64 bit floats and `Sn` for 32 bit floats.
 #[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
 #[allow(dead_code)]
-pub enum AArch64FloatReg {}
+pub enum AArch64FloatReg {
+    V0 = 0,
+    V1 = 1,
+    V2 = 2,
+    V3 = 3,
+    V4 = 4,
+    V5 = 5,
+    V6 = 6,
+    V7 = 7,
+    V8 = 8,
+    V9 = 9,
+    V10 = 10,
+    V11 = 11,
+    V12 = 12,
+    V13 = 13,
+    V14 = 14,
+    V15 = 15,
+    V16 = 16,
+    V17 = 17,
+    V18 = 18,
+    V19 = 19,
+    V20 = 20,
+    V21 = 21,
+    V22 = 22,
+    V23 = 23,
+    V24 = 24,
+    V25 = 25,
+    V26 = 26,
+    V27 = 27,
+    V28 = 28,
+    V29 = 29,
+    V30 = 30,
+    V31 = 31,
+}
 impl RegTrait for AArch64FloatReg {
     fn value(&self) -> u8 {
         *self as u8
@@ -109,7 +147,51 @@ impl RegTrait for AArch64FloatReg {
 }
 impl std::fmt::Display for AArch64FloatReg {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "TODO",)
+        write!(
+            f,
+            "{}",
+            match self {
+                AArch64FloatReg::V0 => "v0",
+                AArch64FloatReg::V1 => "v1",
+                AArch64FloatReg::V2 => "v2",
+                AArch64FloatReg::V3 => "v3",
+                AArch64FloatReg::V4 => "v4",
+                AArch64FloatReg::V5 => "v5",
+                AArch64FloatReg::V6 => "v6",
+                AArch64FloatReg::V7 => "v7",
+                AArch64FloatReg::V8 => "v8",
+                AArch64FloatReg::V9 => "v9",
+                AArch64FloatReg::V10 => "v10",
+                AArch64FloatReg::V11 => "v11",
+                AArch64FloatReg::V12 => "v12",
+                AArch64FloatReg::V13 => "v13",
+                AArch64FloatReg::V14 => "v14",
+                AArch64FloatReg::V15 => "v15",
+                AArch64FloatReg::V16 => "v16",
+                AArch64FloatReg::V17 => "v17",
+                AArch64FloatReg::V18 => "v18",
+                AArch64FloatReg::V19 => "v19",
+                AArch64FloatReg::V20 => "v20",
+                AArch64FloatReg::V21 => "v21",
+                AArch64FloatReg::V22 => "v22",
+                AArch64FloatReg::V23 => "v23",
+                AArch64FloatReg::V24 => "v24",
+                AArch64FloatReg::V25 => "v25",
+                AArch64FloatReg::V26 => "v26",
+                AArch64FloatReg::V27 => "v27",
+                AArch64FloatReg::V28 => "v28",
+                AArch64FloatReg::V29 => "v29",
+                AArch64FloatReg::V30 => "v30",
+                AArch64FloatReg::V31 => "v31",
+            }
+        )
+    }
+}
+
+impl AArch64FloatReg {
+    #[inline(always)]
+    fn id(&self) -> u8 {
+        *self as u8
     }
 }
 
@@ -147,6 +229,7 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         // Don't use platform register: AArch64GeneralReg::PR,
         // Don't use link register: AArch64GeneralReg::LR,
         // Don't use zero register/stack pointer: AArch64GeneralReg::ZRSP,
+        // Don't use the scratch register for loading float literals: AArch64GeneralReg::IP0,
 
         // Use callee saved regs last.
         AArch64GeneralReg::X19,
@@ -175,12 +258,58 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         AArch64GeneralReg::X13,
         AArch64GeneralReg::X14,
         AArch64GeneralReg::X15,
-        AArch64GeneralReg::IP0,
         AArch64GeneralReg::IP1,
     ];
-    const FLOAT_PARAM_REGS: &'static [AArch64FloatReg] = &[];
+    const FLOAT_PARAM_REGS: &'static [AArch64FloatReg] = &[
+        AArch64FloatReg::V0,
+        AArch64FloatReg::V1,
+        AArch64FloatReg::V2,
+        AArch64FloatReg::V3,
+        AArch64FloatReg::V4,
+        AArch64FloatReg::V5,
+        AArch64FloatReg::V6,
+        AArch64FloatReg::V7,
+    ];
     const FLOAT_RETURN_REGS: &'static [AArch64FloatReg] = Self::FLOAT_PARAM_REGS;
-    const FLOAT_DEFAULT_FREE_REGS: &'static [AArch64FloatReg] = &[];
+    const FLOAT_DEFAULT_FREE_REGS: &'static [AArch64FloatReg] = &[
+        // The regs we want to use first should be at the end of this vec.
+        // We will use pop to get which reg to use next
+
+        // Use callee saved regs last.
+        AArch64FloatReg::V8,
+        AArch64FloatReg::V9,
+        AArch64FloatReg::V10,
+        AArch64FloatReg::V11,
+        AArch64FloatReg::V12,
+        AArch64FloatReg::V13,
+        AArch64FloatReg::V14,
+        AArch64FloatReg::V15,
+        // Use caller saved regs first.
+        AArch64FloatReg::V0,
+        AArch64FloatReg::V1,
+        AArch64FloatReg::V2,
+        AArch64FloatReg::V3,
+        AArch64FloatReg::V4,
+        AArch64FloatReg::V5,
+        AArch64FloatReg::V6,
+        AArch64FloatReg::V7,
+        AArch64FloatReg::V16,
+        AArch64FloatReg::V17,
+        AArch64FloatReg::V18,
+        AArch64FloatReg::V19,
+        AArch64FloatReg::V20,
+        AArch64FloatReg::V21,
+        AArch64FloatReg::V22,
+        AArch64FloatReg::V23,
+        AArch64FloatReg::V24,
+        AArch64FloatReg::V25,
+        AArch64FloatReg::V26,
+        AArch64FloatReg::V27,
+        AArch64FloatReg::V28,
+        AArch64FloatReg::V29,
+        AArch64FloatReg::V30,
+        AArch64FloatReg::V31,
+    ];
 
     const SHADOW_SPACE_SIZE: u8 = 0;
 
@@ -201,8 +330,19 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         )
     }
     #[inline(always)]
-    fn float_callee_saved(_reg: &AArch64FloatReg) -> bool {
-        todo!("AArch64 FloatRegs");
+    fn float_callee_saved(reg: &AArch64FloatReg) -> bool {
+        // Only the bottom 64 bits of these are callee saved, which is all we use.
+        matches!(
+            reg,
+            AArch64FloatReg::V8
+                | AArch64FloatReg::V9
+                | AArch64FloatReg::V10
+                | AArch64FloatReg::V11
+                | AArch64FloatReg::V12
+                | AArch64FloatReg::V13
+                | AArch64FloatReg::V14
+                | AArch64FloatReg::V15
+        )
     }
 
     #[inline(always)]
@@ -306,8 +446,8 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
 
     #[inline(always)]
     fn load_args<'a, 'r>(
-        _buf: &mut Vec<'a, u8>,
-        _storage_manager: &mut StorageManager<
+        buf: &mut Vec<'a, u8>,
+        storage_manager: &mut StorageManager<
             'a,
             'r,
             AArch64GeneralReg,
@@ -315,17 +455,92 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
             AArch64Assembler,
             AArch64Call,
         >,
-        _layout_interner: &mut STLayoutInterner<'a>,
-        _args: &'a [(InLayout<'a>, Symbol)],
-        _ret_layout: &InLayout<'a>,
+        layout_interner: &mut STLayoutInterner<'a>,
+        args: &'a [(InLayout<'a>, Symbol)],
+        ret_layout: &InLayout<'a>,
     ) {
-        todo!("Loading args for AArch64");
+        // The frame pointer is the stack pointer of the caller, so stack args start right at it.
+        let mut arg_offset = 0;
+        let mut general_i = 0;
+        let mut float_i = 0;
+        if AArch64Call::returns_via_arg_pointer(layout_interner, ret_layout) {
+            // The result location is passed in its own register, not a param reg.
+            storage_manager.ret_pointer_arg(AArch64GeneralReg::XR);
+        }
+        for (in_layout, sym) in args.iter() {
+            let stack_size = layout_interner.stack_size(*in_layout);
+            match *in_layout {
+                single_register_integers!() => {
+                    if general_i < Self::GENERAL_PARAM_REGS.len() {
+                        storage_manager.general_reg_arg(sym, Self::GENERAL_PARAM_REGS[general_i]);
+                        general_i += 1;
+                    } else {
+                        storage_manager.primitive_stack_arg(sym, arg_offset);
+                        arg_offset += 8;
+                    }
+                }
+                single_register_floats!() => {
+                    if float_i < Self::FLOAT_PARAM_REGS.len() {
+                        storage_manager.float_reg_arg(sym, Self::FLOAT_PARAM_REGS[float_i]);
+                        float_i += 1;
+                    } else {
+                        storage_manager.primitive_stack_arg(sym, arg_offset);
+                        arg_offset += 8;
+                    }
+                }
+                Layout::I128 | Layout::U128 | Layout::DEC => {
+                    // 128 bit values go in an even numbered pair of registers.
+                    general_i += general_i % 2;
+                    if general_i + 1 < Self::GENERAL_PARAM_REGS.len() {
+                        let base_offset = storage_manager.claim_stack_area(sym, 16);
+                        AArch64Assembler::mov_base32_reg64(
+                            buf,
+                            base_offset,
+                            Self::GENERAL_PARAM_REGS[general_i],
+                        );
+                        AArch64Assembler::mov_base32_reg64(
+                            buf,
+                            base_offset + 8,
+                            Self::GENERAL_PARAM_REGS[general_i + 1],
+                        );
+                        general_i += 2;
+                    } else {
+                        general_i = Self::GENERAL_PARAM_REGS.len();
+                        arg_offset += arg_offset % 16;
+                        storage_manager.complex_stack_arg(sym, arg_offset, 16);
+                        arg_offset += 16;
+                    }
+                }
+                _ if stack_size == 0 => {
+                    storage_manager.no_data_arg(sym);
+                }
+                other => match layout_interner.get(other) {
+                    Layout::Boxed(_) => {
+                        // boxed layouts are pointers, which we treat as 64-bit integers
+                        if general_i < Self::GENERAL_PARAM_REGS.len() {
+                            storage_manager
+                                .general_reg_arg(sym, Self::GENERAL_PARAM_REGS[general_i]);
+                            general_i += 1;
+                        } else {
+                            storage_manager.primitive_stack_arg(sym, arg_offset);
+                            arg_offset += 8;
+                        }
+                    }
+                    _ => {
+                        todo!(
+                            "Loading args with layout {:?} for AArch64",
+                            layout_interner.dbg(other)
+                        );
+                    }
+                },
+            }
+        }
     }
 
     #[inline(always)]
     fn store_args<'a, 'r>(
-        _buf: &mut Vec<'a, u8>,
-        _storage_manager: &mut StorageManager<
+        buf: &mut Vec<'a, u8>,
+        storage_manager: &mut StorageManager<
             'a,
             'r,
             AArch64GeneralReg,
@@ -333,18 +548,156 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
             AArch64Assembler,
             AArch64Call,
         >,
-        _layout_interner: &mut STLayoutInterner<'a>,
-        _dst: &Symbol,
-        _args: &[Symbol],
-        _arg_layouts: &[InLayout<'a>],
-        _ret_layout: &InLayout<'a>,
+        layout_interner: &mut STLayoutInterner<'a>,
+        dst: &Symbol,
+        args: &[Symbol],
+        arg_layouts: &[InLayout<'a>],
+        ret_layout: &InLayout<'a>,
     ) {
-        todo!("Storing args for AArch64");
+        let mut tmp_stack_offset = 0;
+        let mut general_i = 0;
+        let mut float_i = 0;
+        if Self::returns_via_arg_pointer(layout_interner, ret_layout) {
+            // Save space on the stack for the result we will be return.
+            let base_offset =
+                storage_manager.claim_stack_area(dst, layout_interner.stack_size(*ret_layout));
+            // Pass the address of that space in the indirect result register.
+            AArch64Assembler::add_reg64_reg64_imm32(
+                buf,
+                AArch64GeneralReg::XR,
+                AArch64GeneralReg::FP,
+                base_offset,
+            );
+        }
+
+        for (sym, layout) in args.iter().zip(arg_layouts.iter()) {
+            match *layout {
+                single_register_integers!() => {
+                    if general_i < Self::GENERAL_PARAM_REGS.len() {
+                        storage_manager.load_to_specified_general_reg(
+                            buf,
+                            sym,
+                            Self::GENERAL_PARAM_REGS[general_i],
+                        );
+                        general_i += 1;
+                    } else {
+                        // Copy to stack using the scratch reg as buffer.
+                        storage_manager.load_to_specified_general_reg(
+                            buf,
+                            sym,
+                            AArch64GeneralReg::IP0,
+                        );
+                        AArch64Assembler::mov_stack32_reg64(
+                            buf,
+                            tmp_stack_offset,
+                            AArch64GeneralReg::IP0,
+                        );
+                        tmp_stack_offset += 8;
+                    }
+                }
+                single_register_floats!() => {
+                    if float_i < Self::FLOAT_PARAM_REGS.len() {
+                        storage_manager.load_to_specified_float_reg(
+                            buf,
+                            sym,
+                            Self::FLOAT_PARAM_REGS[float_i],
+                        );
+                        float_i += 1;
+                    } else {
+                        // Copy to stack using the scratch reg as buffer.
+                        storage_manager.ensure_symbol_on_stack(buf, sym);
+                        let (base_offset, _) = storage_manager.stack_offset_and_size(sym);
+                        AArch64Assembler::mov_reg64_base32(
+                            buf,
+                            AArch64GeneralReg::IP0,
+                            base_offset,
+                        );
+                        AArch64Assembler::mov_stack32_reg64(
+                            buf,
+                            tmp_stack_offset,
+                            AArch64GeneralReg::IP0,
+                        );
+                        tmp_stack_offset += 8;
+                    }
+                }
+                Layout::I128 | Layout::U128 | Layout::DEC => {
+                    // 128 bit values go in an even numbered pair of registers.
+                    general_i += general_i % 2;
+                    let (base_offset, _) = storage_manager.stack_offset_and_size(sym);
+                    if general_i + 1 < Self::GENERAL_PARAM_REGS.len() {
+                        AArch64Assembler::mov_reg64_base32(
+                            buf,
+                            Self::GENERAL_PARAM_REGS[general_i],
+                            base_offset,
+                        );
+                        AArch64Assembler::mov_reg64_base32(
+                            buf,
+                            Self::GENERAL_PARAM_REGS[general_i + 1],
+                            base_offset + 8,
+                        );
+                        general_i += 2;
+                    } else {
+                        general_i = Self::GENERAL_PARAM_REGS.len();
+                        tmp_stack_offset += tmp_stack_offset % 16;
+                        for i in [0, 8] {
+                            AArch64Assembler::mov_reg64_base32(
+                                buf,
+                                AArch64GeneralReg::IP0,
+                                base_offset + i,
+                            );
+                            AArch64Assembler::mov_stack32_reg64(
+                                buf,
+                                tmp_stack_offset + i,
+                                AArch64GeneralReg::IP0,
+                            );
+                        }
+                        tmp_stack_offset += 16;
+                    }
+                }
+                x if layout_interner.stack_size(x) == 0 => {}
+                other => {
+                    // look at the layout in more detail
+                    match layout_interner.get(other) {
+                        Layout::Boxed(_) => {
+                            // treat boxed like a 64-bit integer
+                            if general_i < Self::GENERAL_PARAM_REGS.len() {
+                                storage_manager.load_to_specified_general_reg(
+                                    buf,
+                                    sym,
+                                    Self::GENERAL_PARAM_REGS[general_i],
+                                );
+                                general_i += 1;
+                            } else {
+                                // Copy to stack using the scratch reg as buffer.
+                                storage_manager.load_to_specified_general_reg(
+                                    buf,
+                                    sym,
+                                    AArch64GeneralReg::IP0,
+                                );
+                                AArch64Assembler::mov_stack32_reg64(
+                                    buf,
+                                    tmp_stack_offset,
+                                    AArch64GeneralReg::IP0,
+                                );
+                                tmp_stack_offset += 8;
+                            }
+                        }
+                        _ => {
+                            todo!(
+                                "calling with arg type, {:?} for AArch64",
+                                layout_interner.dbg(other)
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        storage_manager.update_fn_call_stack_size(tmp_stack_offset as u32);
     }
 
     fn return_complex_symbol<'a, 'r>(
-        _buf: &mut Vec<'a, u8>,
-        _storage_manager: &mut StorageManager<
+        buf: &mut Vec<'a, u8>,
+        storage_manager: &mut StorageManager<
             'a,
             'r,
             AArch64GeneralReg,
@@ -352,16 +705,37 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
             AArch64Assembler,
             AArch64Call,
         >,
-        _layout_interner: &mut STLayoutInterner<'a>,
-        _sym: &Symbol,
-        _layout: &InLayout<'a>,
+        layout_interner: &mut STLayoutInterner<'a>,
+        sym: &Symbol,
+        layout: &InLayout<'a>,
     ) {
-        todo!("Returning complex symbols for AArch64");
+        match *layout {
+            single_register_layouts!() => {
+                internal_error!("single register layouts are not complex symbols");
+            }
+            x if layout_interner.stack_size(x) == 0 => {}
+            x if !Self::returns_via_arg_pointer(layout_interner, &x) => {
+                let (base_offset, size) = storage_manager.stack_offset_and_size(sym);
+                debug_assert_eq!(base_offset % 8, 0);
+                AArch64Assembler::mov_reg64_base32(buf, Self::GENERAL_RETURN_REGS[0], base_offset);
+                if size > 8 {
+                    AArch64Assembler::mov_reg64_base32(
+                        buf,
+                        Self::GENERAL_RETURN_REGS[1],
+                        base_offset + 8,
+                    );
+                }
+            }
+            _ => {
+                // This is a large type returned via the indirect result register.
+                storage_manager.copy_symbol_to_arg_pointer(buf, sym, layout);
+            }
+        }
     }
 
     fn load_returned_complex_symbol<'a, 'r>(
-        _buf: &mut Vec<'a, u8>,
-        _storage_manager: &mut StorageManager<
+        buf: &mut Vec<'a, u8>,
+        storage_manager: &mut StorageManager<
             'a,
             'r,
             AArch64GeneralReg,
@@ -369,11 +743,44 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
             AArch64Assembler,
             AArch64Call,
         >,
-        _layout_interner: &mut STLayoutInterner<'a>,
-        _sym: &Symbol,
-        _layout: &InLayout<'a>,
+        layout_interner: &mut STLayoutInterner<'a>,
+        sym: &Symbol,
+        layout: &InLayout<'a>,
     ) {
-        todo!("Loading returned complex symbols for AArch64");
+        match *layout {
+            single_register_layouts!() => {
+                internal_error!("single register layouts are not complex symbols");
+            }
+            x if layout_interner.stack_size(x) == 0 => {}
+            x if !Self::returns_via_arg_pointer(layout_interner, &x) => {
+                let size = layout_interner.stack_size(*layout);
+                let offset = storage_manager.claim_stack_area(sym, size);
+                AArch64Assembler::mov_base32_reg64(buf, offset, Self::GENERAL_RETURN_REGS[0]);
+                if size > 8 {
+                    AArch64Assembler::mov_base32_reg64(
+                        buf,
+                        offset + 8,
+                        Self::GENERAL_RETURN_REGS[1],
+                    );
+                }
+            }
+            _ => {
+                // This should have been recieved via the indirect result register.
+                // That means the value is already loaded onto the stack area we allocated before the call.
+                // Nothing to do.
+            }
+        }
+    }
+}
+
+impl AArch64Call {
+    fn returns_via_arg_pointer<'a>(
+        interner: &STLayoutInterner<'a>,
+        ret_layout: &InLayout<'a>,
+    ) -> bool {
+        // Composites over 16 bytes are returned in memory the caller passes in XR.
+        // details here: https://github.com/ARM-software/abi-aa/blob/main/aapcs64/aapcs64.rst
+        interner.stack_size(*ret_layout) > 16
     }
 }
 
@@ -385,12 +792,12 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
 
     #[inline(always)]
     fn abs_freg64_freg64(
-        _buf: &mut Vec<'_, u8>,
+        buf: &mut Vec<'_, u8>,
         _relocs: &mut Vec<'_, Relocation>,
-        _dst: AArch64FloatReg,
-        _src: AArch64FloatReg,
+        dst: AArch64FloatReg,
+        src: AArch64FloatReg,
     ) {
-        todo!("abs_reg64_reg64 for AArch64");
+        fabs_freg64_freg64(buf, dst, src);
     }
 
     #[inline(always)]
@@ -401,7 +808,7 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         imm32: i32,
     ) {
         if imm32 < 0 {
-            todo!("immediate addition with values less than 0");
+            Self::sub_reg64_reg64_imm32(buf, dst, src, -imm32);
         } else if imm32 < 0xFFF {
             add_reg64_reg64_imm12(buf, dst, src, imm32 as u16);
         } else {
@@ -419,26 +826,30 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
     }
     #[inline(always)]
     fn add_freg32_freg32_freg32(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src1: AArch64FloatReg,
-        _src2: AArch64FloatReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src1: AArch64FloatReg,
+        src2: AArch64FloatReg,
     ) {
-        todo!("adding floats for AArch64");
+        fadd_freg32_freg32_freg32(buf, dst, src1, src2);
     }
     #[inline(always)]
     fn add_freg64_freg64_freg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src1: AArch64FloatReg,
-        _src2: AArch64FloatReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src1: AArch64FloatReg,
+        src2: AArch64FloatReg,
     ) {
-        todo!("adding floats for AArch64");
+        fadd_freg64_freg64_freg64(buf, dst, src1, src2);
     }
 
     #[inline(always)]
-    fn call(_buf: &mut Vec<'_, u8>, _relocs: &mut Vec<'_, Relocation>, _fn_name: String) {
-        todo!("calling functions literal for AArch64");
+    fn call(buf: &mut Vec<'_, u8>, relocs: &mut Vec<'_, Relocation>, fn_name: String) {
+        bl_imm26(buf, 0);
+        relocs.push(Relocation::LinkedFunction {
+            offset: buf.len() as u64 - 4,
+            name: fn_name,
+        });
     }
 
     #[inline(always)]
@@ -492,40 +903,40 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
 
     #[inline(always)]
     fn mul_freg32_freg32_freg32(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src1: AArch64FloatReg,
-        _src2: AArch64FloatReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src1: AArch64FloatReg,
+        src2: AArch64FloatReg,
     ) {
-        todo!("multiplication for floats for AArch64");
+        fmul_freg32_freg32_freg32(buf, dst, src1, src2);
     }
     #[inline(always)]
     fn mul_freg64_freg64_freg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src1: AArch64FloatReg,
-        _src2: AArch64FloatReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src1: AArch64FloatReg,
+        src2: AArch64FloatReg,
     ) {
-        todo!("multiplication for floats for AArch64");
+        fmul_freg64_freg64_freg64(buf, dst, src1, src2);
     }
 
     #[inline(always)]
     fn div_freg32_freg32_freg32(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src1: AArch64FloatReg,
-        _src2: AArch64FloatReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src1: AArch64FloatReg,
+        src2: AArch64FloatReg,
     ) {
-        todo!("division for floats for AArch64");
+        fdiv_freg32_freg32_freg32(buf, dst, src1, src2);
     }
     #[inline(always)]
     fn div_freg64_freg64_freg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src1: AArch64FloatReg,
-        _src2: AArch64FloatReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src1: AArch64FloatReg,
+        src2: AArch64FloatReg,
     ) {
-        todo!("division for floats for AArch64");
+        fdiv_freg64_freg64_freg64(buf, dst, src1, src2);
     }
 
     #[inline(always)]
@@ -551,21 +962,25 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
 
     #[inline(always)]
     fn mov_freg32_imm32(
-        _buf: &mut Vec<'_, u8>,
+        buf: &mut Vec<'_, u8>,
         _relocs: &mut Vec<'_, Relocation>,
-        _dst: AArch64FloatReg,
-        _imm: f32,
+        dst: AArch64FloatReg,
+        imm: f32,
     ) {
-        todo!("loading f32 literal for AArch64");
+        // Build the bits in the scratch register rather than loading them from a data section.
+        Self::mov_reg64_imm64(buf, AArch64GeneralReg::IP0, imm.to_bits() as i64);
+        fmov_freg32_reg32(buf, dst, AArch64GeneralReg::IP0);
     }
     #[inline(always)]
     fn mov_freg64_imm64(
-        _buf: &mut Vec<'_, u8>,
+        buf: &mut Vec<'_, u8>,
         _relocs: &mut Vec<'_, Relocation>,
-        _dst: AArch64FloatReg,
-        _imm: f64,
+        dst: AArch64FloatReg,
+        imm: f64,
     ) {
-        todo!("loading f64 literal for AArch64");
+        // Build the bits in the scratch register rather than loading them from a data section.
+        Self::mov_reg64_imm64(buf, AArch64GeneralReg::IP0, imm.to_bits() as i64);
+        fmov_freg64_reg64(buf, dst, AArch64GeneralReg::IP0);
     }
     #[inline(always)]
     fn mov_reg64_imm64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, imm: i64) {
@@ -585,8 +1000,10 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         }
     }
     #[inline(always)]
-    fn mov_freg64_freg64(_buf: &mut Vec<'_, u8>, _dst: AArch64FloatReg, _src: AArch64FloatReg) {
-        todo!("moving data between float registers for AArch64");
+    fn mov_freg64_freg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+        if dst != src {
+            fmov_freg64_freg64(buf, dst, src);
+        }
     }
     #[inline(always)]
     fn mov_reg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, src: AArch64GeneralReg) {
@@ -594,13 +1011,24 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
     }
 
     #[inline(always)]
-    fn mov_freg64_base32(_buf: &mut Vec<'_, u8>, _dst: AArch64FloatReg, _offset: i32) {
-        todo!("loading floating point reg from base offset for AArch64");
+    fn mov_freg64_base32(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, offset: i32) {
+        if offset < -256 {
+            todo!("base offsets below -256 for AArch64");
+        } else if offset < 0 {
+            ldur_freg64_reg64_imm9(buf, dst, AArch64GeneralReg::FP, offset as i16);
+        } else if offset < (0xFFF << 8) {
+            debug_assert!(offset % 8 == 0);
+            ldr_freg64_reg64_imm12(buf, dst, AArch64GeneralReg::FP, (offset as u16) >> 3);
+        } else {
+            todo!("base offsets over 32k for AArch64");
+        }
     }
     #[inline(always)]
     fn mov_reg64_base32(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, offset: i32) {
-        if offset < 0 {
-            todo!("negative base offsets for AArch64");
+        if offset < -256 {
+            todo!("base offsets below -256 for AArch64");
+        } else if offset < 0 {
+            ldur_reg64_reg64_imm9(buf, dst, AArch64GeneralReg::FP, offset as i16);
         } else if offset < (0xFFF << 8) {
             debug_assert!(offset % 8 == 0);
             ldr_reg64_reg64_imm12(buf, dst, AArch64GeneralReg::FP, (offset as u16) >> 3);
@@ -609,13 +1037,24 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         }
     }
     #[inline(always)]
-    fn mov_base32_freg64(_buf: &mut Vec<'_, u8>, _offset: i32, _src: AArch64FloatReg) {
-        todo!("saving floating point reg to base offset for AArch64");
+    fn mov_base32_freg64(buf: &mut Vec<'_, u8>, offset: i32, src: AArch64FloatReg) {
+        if offset < -256 {
+            todo!("base offsets below -256 for AArch64");
+        } else if offset < 0 {
+            stur_freg64_reg64_imm9(buf, src, AArch64GeneralReg::FP, offset as i16);
+        } else if offset < (0xFFF << 8) {
+            debug_assert!(offset % 8 == 0);
+            str_freg64_reg64_imm12(buf, src, AArch64GeneralReg::FP, (offset as u16) >> 3);
+        } else {
+            todo!("base offsets over 32k for AArch64");
+        }
     }
     #[inline(always)]
     fn mov_base32_reg64(buf: &mut Vec<'_, u8>, offset: i32, src: AArch64GeneralReg) {
-        if offset < 0 {
-            todo!("negative base offsets for AArch64");
+        if offset < -256 {
+            todo!("base offsets below -256 for AArch64");
+        } else if offset < 0 {
+            stur_reg64_reg64_imm9(buf, src, AArch64GeneralReg::FP, offset as i16);
         } else if offset < (0xFFF << 8) {
             debug_assert!(offset % 8 == 0);
             str_reg64_reg64_imm12(buf, src, AArch64GeneralReg::FP, (offset as u16) >> 3);
@@ -689,8 +1128,15 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
     }
 
     #[inline(always)]
-    fn mov_freg64_stack32(_buf: &mut Vec<'_, u8>, _dst: AArch64FloatReg, _offset: i32) {
-        todo!("loading floating point reg from stack for AArch64");
+    fn mov_freg64_stack32(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, offset: i32) {
+        if offset < 0 {
+            todo!("negative stack offsets for AArch64");
+        } else if offset < (0xFFF << 8) {
+            debug_assert!(offset % 8 == 0);
+            ldr_freg64_reg64_imm12(buf, dst, AArch64GeneralReg::ZRSP, (offset as u16) >> 3);
+        } else {
+            todo!("stack offsets over 32k for AArch64");
+        }
     }
     #[inline(always)]
     fn mov_reg64_stack32(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, offset: i32) {
@@ -704,8 +1150,15 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         }
     }
     #[inline(always)]
-    fn mov_stack32_freg64(_buf: &mut Vec<'_, u8>, _offset: i32, _src: AArch64FloatReg) {
-        todo!("saving floating point reg to stack for AArch64");
+    fn mov_stack32_freg64(buf: &mut Vec<'_, u8>, offset: i32, src: AArch64FloatReg) {
+        if offset < 0 {
+            todo!("negative stack offsets for AArch64");
+        } else if offset < (0xFFF << 8) {
+            debug_assert!(offset % 8 == 0);
+            str_freg64_reg64_imm12(buf, src, AArch64GeneralReg::ZRSP, (offset as u16) >> 3);
+        } else {
+            todo!("stack offsets over 32k for AArch64");
+        }
     }
     #[inline(always)]
     fn mov_stack32_reg64(buf: &mut Vec<'_, u8>, offset: i32, src: AArch64GeneralReg) {
@@ -723,6 +1176,15 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         todo!("neg for AArch64");
     }
 
+    #[inline(always)]
+    fn sqrt_freg32_freg32(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+        fsqrt_freg32_freg32(buf, dst, src);
+    }
+    #[inline(always)]
+    fn sqrt_freg64_freg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+        fsqrt_freg64_freg64(buf, dst, src);
+    }
+
     #[inline(always)]
     fn sub_reg64_reg64_imm32(
         buf: &mut Vec<'_, u8>,
@@ -731,7 +1193,7 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         imm32: i32,
     ) {
         if imm32 < 0 {
-            todo!("immediate subtractions with values less than 0");
+            Self::add_reg64_reg64_imm32(buf, dst, src, -imm32);
         } else if imm32 < 0xFFF {
             sub_reg64_reg64_imm12(buf, dst, src, imm32 as u16);
         } else {
@@ -739,6 +1201,24 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         }
     }
     #[inline(always)]
+    fn sub_freg32_freg32_freg32(
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src1: AArch64FloatReg,
+        src2: AArch64FloatReg,
+    ) {
+        fsub_freg32_freg32_freg32(buf, dst, src1, src2);
+    }
+    #[inline(always)]
+    fn sub_freg64_freg64_freg64(
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src1: AArch64FloatReg,
+        src2: AArch64FloatReg,
+    ) {
+        fsub_freg64_freg64_freg64(buf, dst, src1, src2);
+    }
+    #[inline(always)]
     fn sub_reg64_reg64_reg64(
         _buf: &mut Vec<'_, u8>,
         _dst: AArch64GeneralReg,
@@ -809,39 +1289,49 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
     }
 
     #[inline(always)]
-    fn to_float_freg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src: AArch64GeneralReg,
-    ) {
-        todo!("registers to float for AArch64");
+    fn to_float_freg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64GeneralReg) {
+        scvtf_freg64_reg64(buf, dst, src);
     }
 
     #[inline(always)]
-    fn to_float_freg32_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src: AArch64GeneralReg,
-    ) {
-        todo!("registers to float for AArch64");
+    fn to_float_freg32_reg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64GeneralReg) {
+        scvtf_freg32_reg64(buf, dst, src);
     }
 
     #[inline(always)]
-    fn to_float_freg32_freg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src: AArch64FloatReg,
+    fn to_float_freg32_freg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+        fcvt_freg32_freg64(buf, dst, src);
+    }
+
+    #[inline(always)]
+    fn to_float_freg64_freg32(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+        fcvt_freg64_freg32(buf, dst, src);
+    }
+
+    #[inline(always)]
+    fn to_int_reg64_freg32(
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src: AArch64FloatReg,
+        mode: RoundingMode,
     ) {
-        todo!("registers to float for AArch64");
+        match mode {
+            RoundingMode::Floor => fcvtms_reg64_freg32(buf, dst, src),
+            RoundingMode::Ceiling => fcvtps_reg64_freg32(buf, dst, src),
+        }
     }
 
     #[inline(always)]
-    fn to_float_freg64_freg32(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src: AArch64FloatReg,
+    fn to_int_reg64_freg64(
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src: AArch64FloatReg,
+        mode: RoundingMode,
     ) {
-        todo!("registers to float for AArch64");
+        match mode {
+            RoundingMode::Floor => fcvtms_reg64_freg64(buf, dst, src),
+            RoundingMode::Ceiling => fcvtps_reg64_freg64(buf, dst, src),
+        }
     }
 
     #[inline(always)]
@@ -900,6 +1390,52 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         todo!("bitwise xor for AArch64")
     }
 
+    fn rol_reg64_reg64_reg64<'a, 'r, ASM, CC>(
+        _buf: &mut Vec<'a, u8>,
+        _storage_manager: &mut StorageManager<'a, 'r, AArch64GeneralReg, AArch64FloatReg, ASM, CC>,
+        _dst: AArch64GeneralReg,
+        _src1: AArch64GeneralReg,
+        _src2: AArch64GeneralReg,
+    ) where
+        ASM: Assembler<AArch64GeneralReg, AArch64FloatReg>,
+        CC: CallConv<AArch64GeneralReg, AArch64FloatReg, ASM>,
+    {
+        todo!("rol for AArch64")
+    }
+
+    fn ror_reg64_reg64_reg64<'a, 'r, ASM, CC>(
+        _buf: &mut Vec<'a, u8>,
+        _storage_manager: &mut StorageManager<'a, 'r, AArch64GeneralReg, AArch64FloatReg, ASM, CC>,
+        _dst: AArch64GeneralReg,
+        _src1: AArch64GeneralReg,
+        _src2: AArch64GeneralReg,
+    ) where
+        ASM: Assembler<AArch64GeneralReg, AArch64FloatReg>,
+        CC: CallConv<AArch64GeneralReg, AArch64FloatReg, ASM>,
+    {
+        todo!("ror for AArch64")
+    }
+
+    fn popcnt_reg64_reg64(
+        _buf: &mut Vec<'_, u8>,
+        _dst: AArch64GeneralReg,
+        _src: AArch64GeneralReg,
+    ) {
+        todo!("popcnt for AArch64");
+    }
+
+    fn lzcnt_reg64_reg64(_buf: &mut Vec<'_, u8>, _dst: AArch64GeneralReg, _src: AArch64GeneralReg) {
+        todo!("lzcnt for AArch64");
+    }
+
+    fn tzcnt_reg64_reg64(_buf: &mut Vec<'_, u8>, _dst: AArch64GeneralReg, _src: AArch64GeneralReg) {
+        todo!("tzcnt for AArch64");
+    }
+
+    fn bswap_reg64_reg64(_buf: &mut Vec<'_, u8>, _dst: AArch64GeneralReg, _src: AArch64GeneralReg) {
+        todo!("bswap for AArch64");
+    }
+
     fn shl_reg64_reg64_reg64<'a, 'r, ASM, CC>(
         _buf: &mut Vec<'a, u8>,
         _storage_manager: &mut StorageManager<'a, 'r, AArch64GeneralReg, AArch64FloatReg, ASM, CC>,
@@ -1210,7 +1746,7 @@ impl UnconditionalBranchRegister {
 pub struct LoadStoreRegisterImmediate {
     size: Integer<u8, packed_bits::Bits<2>>,
     fixed: Integer<u8, packed_bits::Bits<3>>, // = 0b111,
-    fixed2: bool,
+    v: bool,                                  // SIMD and floating point registers
     fixed3: Integer<u8, packed_bits::Bits<2>>,
     opc: Integer<u8, packed_bits::Bits<2>>,
     imm12: Integer<u16, packed_bits::Bits<12>>,
@@ -1222,17 +1758,17 @@ impl Aarch64Bytes for LoadStoreRegisterImmediate {}
 
 impl LoadStoreRegisterImmediate {
     #[inline(always)]
-    fn new(size: u8, opc: u8, imm12: u16, rn: AArch64GeneralReg, rt: AArch64GeneralReg) -> Self {
+    fn new(size: u8, v: bool, opc: u8, imm12: u16, rn: AArch64GeneralReg, rt: u8) -> Self {
         debug_assert!(size <= 0b11);
         debug_assert!(imm12 <= 0xFFF);
 
         Self {
-            rt: rt.id().into(),
+            rt: rt.into(),
             rn: rn.id().into(),
             imm12: imm12.into(),
             opc: opc.into(),
             fixed3: 0b01.into(),
-            fixed2: false,
+            v,
             fixed: 0b111.into(),
             size: size.into(),
         }
@@ -1240,75 +1776,586 @@ impl LoadStoreRegisterImmediate {
 
     #[inline(always)]
     fn new_load(size: u8, imm12: u16, rn: AArch64GeneralReg, rt: AArch64GeneralReg) -> Self {
-        Self::new(size, 0b01, imm12, rn, rt)
+        Self::new(size, false, 0b01, imm12, rn, rt.id())
     }
 
     #[inline(always)]
     fn new_store(size: u8, imm12: u16, rn: AArch64GeneralReg, rt: AArch64GeneralReg) -> Self {
-        Self::new(size, 0b00, imm12, rn, rt)
+        Self::new(size, false, 0b00, imm12, rn, rt.id())
     }
-}
 
-// Below here are the functions for all of the assembly instructions.
-// Their names are based on the instruction and operators combined.
-// You should call `buf.reserve()` if you push or extend more than once.
-// Unit tests are added at the bottom of the file to ensure correct asm generation.
-// Please keep these in alphanumeric order.
-
-/// `ADD Xd, Xn, imm12` -> Add Xn and imm12 and place the result into Xd.
-#[inline(always)]
-fn add_reg64_reg64_imm12(
-    buf: &mut Vec<'_, u8>,
-    dst: AArch64GeneralReg,
-    src: AArch64GeneralReg,
-    imm12: u16,
-) {
-    let inst = ArithmeticImmediate::new(false, false, dst, src, imm12, false);
+    #[inline(always)]
+    fn new_float_load(size: u8, imm12: u16, rn: AArch64GeneralReg, rt: AArch64FloatReg) -> Self {
+        Self::new(size, true, 0b01, imm12, rn, rt.id())
+    }
 
-    buf.extend(inst.bytes());
+    #[inline(always)]
+    fn new_float_store(size: u8, imm12: u16, rn: AArch64GeneralReg, rt: AArch64FloatReg) -> Self {
+        Self::new(size, true, 0b00, imm12, rn, rt.id())
+    }
 }
 
-/// `ADD Xd, Xm, Xn` -> Add Xm and Xn and place the result into Xd.
-#[inline(always)]
-fn add_reg64_reg64_reg64(
-    buf: &mut Vec<'_, u8>,
-    dst: AArch64GeneralReg,
-    src1: AArch64GeneralReg,
-    src2: AArch64GeneralReg,
-) {
-    let inst = ArithmeticShifted::new(false, false, ShiftType::LSL, 0, src2, src1, dst);
-
-    buf.extend(inst.bytes());
+// Uses a signed offset that is not scaled by the size.
+// opc = 0b01 means load
+// opc = 0b00 means store
+#[derive(PackedStruct, Debug)]
+#[packed_struct(endian = "msb")]
+pub struct LoadStoreRegisterUnscaledImmediate {
+    size: Integer<u8, packed_bits::Bits<2>>,
+    fixed: Integer<u8, packed_bits::Bits<3>>,  // = 0b111,
+    v: bool,                                   // SIMD and floating point registers
+    fixed2: Integer<u8, packed_bits::Bits<2>>, // = 0b00,
+    opc: Integer<u8, packed_bits::Bits<2>>,
+    fixed3: bool, // = 0b0,
+    imm9: Integer<u16, packed_bits::Bits<9>>,
+    fixed4: Integer<u8, packed_bits::Bits<2>>, // = 0b00,
+    rn: Integer<u8, packed_bits::Bits<5>>,
+    rt: Integer<u8, packed_bits::Bits<5>>,
 }
 
-/// `LDR Xt, [Xn, #offset]` -> Load Xn + Offset Xt. ZRSP is SP.
-/// Note: imm12 is the offest divided by 8.
-#[inline(always)]
-fn ldr_reg64_reg64_imm12(
-    buf: &mut Vec<'_, u8>,
-    dst: AArch64GeneralReg,
-    base: AArch64GeneralReg,
-    imm12: u16,
-) {
-    let inst = LoadStoreRegisterImmediate::new_load(0b11, imm12, base, dst);
+impl Aarch64Bytes for LoadStoreRegisterUnscaledImmediate {}
 
-    buf.extend(inst.bytes());
+impl LoadStoreRegisterUnscaledImmediate {
+    #[inline(always)]
+    fn new(size: u8, v: bool, opc: u8, imm9: i16, rn: AArch64GeneralReg, rt: u8) -> Self {
+        debug_assert!(size <= 0b11);
+        debug_assert!((-256..256).contains(&imm9));
+
+        Self {
+            rt: rt.into(),
+            rn: rn.id().into(),
+            fixed4: 0b00.into(),
+            imm9: ((imm9 as u16) & 0x1FF).into(),
+            fixed3: false,
+            opc: opc.into(),
+            fixed2: 0b00.into(),
+            v,
+            fixed: 0b111.into(),
+            size: size.into(),
+        }
+    }
 }
 
-/// `MOV Xd, Xm` -> Move Xm to Xd.
-#[inline(always)]
-fn mov_reg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, src: AArch64GeneralReg) {
-    // MOV is equvalent to `ORR Xd, XZR, XM` in AARCH64.
-    let inst = LogicalShiftedRegister::new(
-        LogicalOp::ORR,
-        ShiftType::LSL,
-        0,
-        src,
-        AArch64GeneralReg::ZRSP,
-        dst,
-    );
+#[derive(Clone, Copy)]
+#[allow(dead_code)]
+enum FloatType {
+    Single = 0b00,
+    Double = 0b01,
+}
 
-    buf.extend(inst.bytes());
+impl FloatType {
+    #[inline(always)]
+    fn id(&self) -> u8 {
+        *self as u8
+    }
+}
+
+#[derive(PackedStruct)]
+#[packed_struct(endian = "msb")]
+pub struct FloatingPointDataProcessingOneSource {
+    m: bool,
+    fixed: bool,
+    s: bool,
+    fixed2: Integer<u8, packed_bits::Bits<5>>, // = 0b11110,
+    ptype: Integer<u8, packed_bits::Bits<2>>,
+    fixed3: bool, // = 0b1,
+    opcode: Integer<u8, packed_bits::Bits<6>>,
+    fixed4: Integer<u8, packed_bits::Bits<5>>, // = 0b10000,
+    rn: Integer<u8, packed_bits::Bits<5>>,
+    rd: Integer<u8, packed_bits::Bits<5>>,
+}
+
+impl Aarch64Bytes for FloatingPointDataProcessingOneSource {}
+
+impl FloatingPointDataProcessingOneSource {
+    #[inline(always)]
+    fn new(ptype: FloatType, opcode: u8, rn: AArch64FloatReg, rd: AArch64FloatReg) -> Self {
+        debug_assert!(opcode <= 0b111111);
+
+        Self {
+            rd: rd.id().into(),
+            rn: rn.id().into(),
+            fixed4: 0b10000.into(),
+            opcode: opcode.into(),
+            fixed3: true,
+            ptype: ptype.id().into(),
+            fixed2: 0b11110.into(),
+            s: false,
+            fixed: false,
+            m: false,
+        }
+    }
+}
+
+#[derive(PackedStruct)]
+#[packed_struct(endian = "msb")]
+pub struct FloatingPointDataProcessingTwoSource {
+    m: bool,
+    fixed: bool,
+    s: bool,
+    fixed2: Integer<u8, packed_bits::Bits<5>>, // = 0b11110,
+    ptype: Integer<u8, packed_bits::Bits<2>>,
+    fixed3: bool, // = 0b1,
+    rm: Integer<u8, packed_bits::Bits<5>>,
+    opcode: Integer<u8, packed_bits::Bits<4>>,
+    fixed4: Integer<u8, packed_bits::Bits<2>>, // = 0b10,
+    rn: Integer<u8, packed_bits::Bits<5>>,
+    rd: Integer<u8, packed_bits::Bits<5>>,
+}
+
+impl Aarch64Bytes for FloatingPointDataProcessingTwoSource {}
+
+impl FloatingPointDataProcessingTwoSource {
+    #[inline(always)]
+    fn new(
+        ptype: FloatType,
+        opcode: u8,
+        rm: AArch64FloatReg,
+        rn: AArch64FloatReg,
+        rd: AArch64FloatReg,
+    ) -> Self {
+        debug_assert!(opcode <= 0b1111);
+
+        Self {
+            rd: rd.id().into(),
+            rn: rn.id().into(),
+            fixed4: 0b10.into(),
+            opcode: opcode.into(),
+            rm: rm.id().into(),
+            fixed3: true,
+            ptype: ptype.id().into(),
+            fixed2: 0b11110.into(),
+            s: false,
+            fixed: false,
+            m: false,
+        }
+    }
+}
+
+// Conversions between floating point and general registers.
+// Rn and Rd are general or float registers depending on the opcode.
+#[derive(PackedStruct)]
+#[packed_struct(endian = "msb")]
+pub struct ConversionBetweenFloatingPointAndInteger {
+    sf: bool,
+    fixed: bool,
+    s: bool,
+    fixed2: Integer<u8, packed_bits::Bits<5>>, // = 0b11110,
+    ptype: Integer<u8, packed_bits::Bits<2>>,
+    fixed3: bool, // = 0b1,
+    rmode: Integer<u8, packed_bits::Bits<2>>,
+    opcode: Integer<u8, packed_bits::Bits<3>>,
+    fixed4: Integer<u8, packed_bits::Bits<6>>, // = 0b000000,
+    rn: Integer<u8, packed_bits::Bits<5>>,
+    rd: Integer<u8, packed_bits::Bits<5>>,
+}
+
+impl Aarch64Bytes for ConversionBetweenFloatingPointAndInteger {}
+
+impl ConversionBetweenFloatingPointAndInteger {
+    #[inline(always)]
+    fn new(sf: bool, ptype: FloatType, rmode: u8, opcode: u8, rn: u8, rd: u8) -> Self {
+        debug_assert!(rmode <= 0b11);
+        debug_assert!(opcode <= 0b111);
+
+        Self {
+            rd: rd.into(),
+            rn: rn.into(),
+            fixed4: 0b000000.into(),
+            opcode: opcode.into(),
+            rmode: rmode.into(),
+            fixed3: true,
+            ptype: ptype.id().into(),
+            fixed2: 0b11110.into(),
+            s: false,
+            fixed: false,
+            sf,
+        }
+    }
+}
+
+#[derive(PackedStruct)]
+#[packed_struct(endian = "msb")]
+pub struct UnconditionalBranchImmediate {
+    op: bool,
+    fixed: Integer<u8, packed_bits::Bits<5>>, // = 0b00101,
+    imm26: Integer<u32, packed_bits::Bits<26>>,
+}
+
+impl Aarch64Bytes for UnconditionalBranchImmediate {}
+
+impl UnconditionalBranchImmediate {
+    #[inline(always)]
+    fn new(op: bool, offset: i32) -> Self {
+        debug_assert!(offset % 4 == 0);
+        debug_assert!((-(1 << 27)..(1 << 27)).contains(&offset));
+
+        Self {
+            imm26: (((offset >> 2) as u32) & 0x3FF_FFFF).into(),
+            fixed: 0b00101.into(),
+            op,
+        }
+    }
+}
+
+// Below here are the functions for all of the assembly instructions.
+// Their names are based on the instruction and operators combined.
+// You should call `buf.reserve()` if you push or extend more than once.
+// Unit tests are added at the bottom of the file to ensure correct asm generation.
+// Please keep these in alphanumeric order.
+
+/// `ADD Xd, Xn, imm12` -> Add Xn and imm12 and place the result into Xd.
+#[inline(always)]
+fn add_reg64_reg64_imm12(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src: AArch64GeneralReg,
+    imm12: u16,
+) {
+    let inst = ArithmeticImmediate::new(false, false, dst, src, imm12, false);
+
+    buf.extend(inst.bytes());
+}
+
+/// `ADD Xd, Xm, Xn` -> Add Xm and Xn and place the result into Xd.
+#[inline(always)]
+fn add_reg64_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    let inst = ArithmeticShifted::new(false, false, ShiftType::LSL, 0, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `BL offset` -> Call the function at the PC relative offset, saving the return address in LR.
+#[inline(always)]
+fn bl_imm26(buf: &mut Vec<'_, u8>, offset: i32) {
+    let inst = UnconditionalBranchImmediate::new(true, offset);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FABS Dd, Dn` -> Place the absolute value of Dn into Dd.
+#[inline(always)]
+fn fabs_freg64_freg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+    let inst = FloatingPointDataProcessingOneSource::new(FloatType::Double, 0b000001, src, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FADD Sd, Sn, Sm` -> Add Sn and Sm and place the result into Sd.
+#[inline(always)]
+fn fadd_freg32_freg32_freg32(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64FloatReg,
+    src1: AArch64FloatReg,
+    src2: AArch64FloatReg,
+) {
+    let inst =
+        FloatingPointDataProcessingTwoSource::new(FloatType::Single, 0b0010, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FADD Dd, Dn, Dm` -> Add Dn and Dm and place the result into Dd.
+#[inline(always)]
+fn fadd_freg64_freg64_freg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64FloatReg,
+    src1: AArch64FloatReg,
+    src2: AArch64FloatReg,
+) {
+    let inst =
+        FloatingPointDataProcessingTwoSource::new(FloatType::Double, 0b0010, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FCVT Sd, Dn` -> Convert the 64 bit float in Dn to a 32 bit float in Sd.
+#[inline(always)]
+fn fcvt_freg32_freg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+    let inst = FloatingPointDataProcessingOneSource::new(FloatType::Double, 0b000100, src, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FCVT Dd, Sn` -> Convert the 32 bit float in Sn to a 64 bit float in Dd.
+#[inline(always)]
+fn fcvt_freg64_freg32(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+    let inst = FloatingPointDataProcessingOneSource::new(FloatType::Single, 0b000101, src, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FCVTMS Xd, Sn` -> Convert Sn to a signed integer in Xd, rounding towards negative infinity.
+#[inline(always)]
+fn fcvtms_reg64_freg32(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, src: AArch64FloatReg) {
+    let inst = ConversionBetweenFloatingPointAndInteger::new(
+        true,
+        FloatType::Single,
+        0b10,
+        0b000,
+        src.id(),
+        dst.id(),
+    );
+
+    buf.extend(inst.bytes());
+}
+
+/// `FCVTMS Xd, Dn` -> Convert Dn to a signed integer in Xd, rounding towards negative infinity.
+#[inline(always)]
+fn fcvtms_reg64_freg64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, src: AArch64FloatReg) {
+    let inst = ConversionBetweenFloatingPointAndInteger::new(
+        true,
+        FloatType::Double,
+        0b10,
+        0b000,
+        src.id(),
+        dst.id(),
+    );
+
+    buf.extend(inst.bytes());
+}
+
+/// `FCVTPS Xd, Sn` -> Convert Sn to a signed integer in Xd, rounding towards positive infinity.
+#[inline(always)]
+fn fcvtps_reg64_freg32(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, src: AArch64FloatReg) {
+    let inst = ConversionBetweenFloatingPointAndInteger::new(
+        true,
+        FloatType::Single,
+        0b01,
+        0b000,
+        src.id(),
+        dst.id(),
+    );
+
+    buf.extend(inst.bytes());
+}
+
+/// `FCVTPS Xd, Dn` -> Convert Dn to a signed integer in Xd, rounding towards positive infinity.
+#[inline(always)]
+fn fcvtps_reg64_freg64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, src: AArch64FloatReg) {
+    let inst = ConversionBetweenFloatingPointAndInteger::new(
+        true,
+        FloatType::Double,
+        0b01,
+        0b000,
+        src.id(),
+        dst.id(),
+    );
+
+    buf.extend(inst.bytes());
+}
+
+/// `FDIV Sd, Sn, Sm` -> Divide Sn by Sm and place the result into Sd.
+#[inline(always)]
+fn fdiv_freg32_freg32_freg32(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64FloatReg,
+    src1: AArch64FloatReg,
+    src2: AArch64FloatReg,
+) {
+    let inst =
+        FloatingPointDataProcessingTwoSource::new(FloatType::Single, 0b0001, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FDIV Dd, Dn, Dm` -> Divide Dn by Dm and place the result into Dd.
+#[inline(always)]
+fn fdiv_freg64_freg64_freg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64FloatReg,
+    src1: AArch64FloatReg,
+    src2: AArch64FloatReg,
+) {
+    let inst =
+        FloatingPointDataProcessingTwoSource::new(FloatType::Double, 0b0001, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FMOV Sd, Wn` -> Move the bits of Wn to Sd.
+#[inline(always)]
+fn fmov_freg32_reg32(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64GeneralReg) {
+    let inst = ConversionBetweenFloatingPointAndInteger::new(
+        false,
+        FloatType::Single,
+        0b00,
+        0b111,
+        src.id(),
+        dst.id(),
+    );
+
+    buf.extend(inst.bytes());
+}
+
+/// `FMOV Dd, Dn` -> Move Dn to Dd.
+#[inline(always)]
+fn fmov_freg64_freg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+    let inst = FloatingPointDataProcessingOneSource::new(FloatType::Double, 0b000000, src, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FMOV Dd, Xn` -> Move the bits of Xn to Dd.
+#[inline(always)]
+fn fmov_freg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64GeneralReg) {
+    let inst = ConversionBetweenFloatingPointAndInteger::new(
+        true,
+        FloatType::Double,
+        0b00,
+        0b111,
+        src.id(),
+        dst.id(),
+    );
+
+    buf.extend(inst.bytes());
+}
+
+/// `FMUL Sd, Sn, Sm` -> Multiply Sn and Sm and place the result into Sd.
+#[inline(always)]
+fn fmul_freg32_freg32_freg32(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64FloatReg,
+    src1: AArch64FloatReg,
+    src2: AArch64FloatReg,
+) {
+    let inst =
+        FloatingPointDataProcessingTwoSource::new(FloatType::Single, 0b0000, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FMUL Dd, Dn, Dm` -> Multiply Dn and Dm and place the result into Dd.
+#[inline(always)]
+fn fmul_freg64_freg64_freg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64FloatReg,
+    src1: AArch64FloatReg,
+    src2: AArch64FloatReg,
+) {
+    let inst =
+        FloatingPointDataProcessingTwoSource::new(FloatType::Double, 0b0000, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FSQRT Sd, Sn` -> Place the square root of Sn into Sd.
+#[inline(always)]
+fn fsqrt_freg32_freg32(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+    let inst = FloatingPointDataProcessingOneSource::new(FloatType::Single, 0b000011, src, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FSQRT Dd, Dn` -> Place the square root of Dn into Dd.
+#[inline(always)]
+fn fsqrt_freg64_freg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+    let inst = FloatingPointDataProcessingOneSource::new(FloatType::Double, 0b000011, src, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FSUB Sd, Sn, Sm` -> Subtract Sm from Sn and place the result into Sd.
+#[inline(always)]
+fn fsub_freg32_freg32_freg32(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64FloatReg,
+    src1: AArch64FloatReg,
+    src2: AArch64FloatReg,
+) {
+    let inst =
+        FloatingPointDataProcessingTwoSource::new(FloatType::Single, 0b0011, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FSUB Dd, Dn, Dm` -> Subtract Dm from Dn and place the result into Dd.
+#[inline(always)]
+fn fsub_freg64_freg64_freg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64FloatReg,
+    src1: AArch64FloatReg,
+    src2: AArch64FloatReg,
+) {
+    let inst =
+        FloatingPointDataProcessingTwoSource::new(FloatType::Double, 0b0011, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDR Dt, [Xn, #offset]` -> Load Xn + Offset Dt. ZRSP is SP.
+/// Note: imm12 is the offest divided by 8.
+#[inline(always)]
+fn ldr_freg64_reg64_imm12(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64FloatReg,
+    base: AArch64GeneralReg,
+    imm12: u16,
+) {
+    let inst = LoadStoreRegisterImmediate::new_float_load(0b11, imm12, base, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDR Xt, [Xn, #offset]` -> Load Xn + Offset Xt. ZRSP is SP.
+/// Note: imm12 is the offest divided by 8.
+#[inline(always)]
+fn ldr_reg64_reg64_imm12(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    base: AArch64GeneralReg,
+    imm12: u16,
+) {
+    let inst = LoadStoreRegisterImmediate::new_load(0b11, imm12, base, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDUR Dt, [Xn, #offset]` -> Load Xn + Offset Dt, where the offset is signed and not scaled.
+#[inline(always)]
+fn ldur_freg64_reg64_imm9(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64FloatReg,
+    base: AArch64GeneralReg,
+    imm9: i16,
+) {
+    let inst = LoadStoreRegisterUnscaledImmediate::new(0b11, true, 0b01, imm9, base, dst.id());
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDUR Xt, [Xn, #offset]` -> Load Xn + Offset Xt, where the offset is signed and not scaled.
+#[inline(always)]
+fn ldur_reg64_reg64_imm9(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    base: AArch64GeneralReg,
+    imm9: i16,
+) {
+    let inst = LoadStoreRegisterUnscaledImmediate::new(0b11, false, 0b01, imm9, base, dst.id());
+
+    buf.extend(inst.bytes());
+}
+
+/// `MOV Xd, Xm` -> Move Xm to Xd.
+#[inline(always)]
+fn mov_reg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, src: AArch64GeneralReg) {
+    // MOV is equvalent to `ORR Xd, XZR, XM` in AARCH64.
+    let inst = LogicalShiftedRegister::new(
+        LogicalOp::ORR,
+        ShiftType::LSL,
+        0,
+        src,
+        AArch64GeneralReg::ZRSP,
+        dst,
+    );
+
+    buf.extend(inst.bytes());
 }
 
 /// `MOVK Xd, imm16` -> Keeps Xd and moves an optionally shifted imm16 to Xd.
@@ -1327,6 +2374,50 @@ fn movz_reg64_imm16(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, imm16: u16, h
     buf.extend(inst.bytes());
 }
 
+/// `SCVTF Sd, Xn` -> Convert the signed integer in Xn to a 32 bit float in Sd.
+#[inline(always)]
+fn scvtf_freg32_reg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64GeneralReg) {
+    let inst = ConversionBetweenFloatingPointAndInteger::new(
+        true,
+        FloatType::Single,
+        0b00,
+        0b010,
+        src.id(),
+        dst.id(),
+    );
+
+    buf.extend(inst.bytes());
+}
+
+/// `SCVTF Dd, Xn` -> Convert the signed integer in Xn to a 64 bit float in Dd.
+#[inline(always)]
+fn scvtf_freg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64GeneralReg) {
+    let inst = ConversionBetweenFloatingPointAndInteger::new(
+        true,
+        FloatType::Double,
+        0b00,
+        0b010,
+        src.id(),
+        dst.id(),
+    );
+
+    buf.extend(inst.bytes());
+}
+
+/// `STR Dt, [Xn, #offset]` -> Store Dt to Xn + Offset. ZRSP is SP.
+/// Note: imm12 is the offest divided by 8.
+#[inline(always)]
+fn str_freg64_reg64_imm12(
+    buf: &mut Vec<'_, u8>,
+    src: AArch64FloatReg,
+    base: AArch64GeneralReg,
+    imm12: u16,
+) {
+    let inst = LoadStoreRegisterImmediate::new_float_store(0b11, imm12, base, src);
+
+    buf.extend(inst.bytes());
+}
+
 /// `STR Xt, [Xn, #offset]` -> Store Xt to Xn + Offset. ZRSP is SP.
 /// Note: imm12 is the offest divided by 8.
 #[inline(always)]
@@ -1341,6 +2432,32 @@ fn str_reg64_reg64_imm12(
     buf.extend(inst.bytes());
 }
 
+/// `STUR Dt, [Xn, #offset]` -> Store Dt to Xn + Offset, where the offset is signed and not scaled.
+#[inline(always)]
+fn stur_freg64_reg64_imm9(
+    buf: &mut Vec<'_, u8>,
+    src: AArch64FloatReg,
+    base: AArch64GeneralReg,
+    imm9: i16,
+) {
+    let inst = LoadStoreRegisterUnscaledImmediate::new(0b11, true, 0b00, imm9, base, src.id());
+
+    buf.extend(inst.bytes());
+}
+
+/// `STUR Xt, [Xn, #offset]` -> Store Xt to Xn + Offset, where the offset is signed and not scaled.
+#[inline(always)]
+fn stur_reg64_reg64_imm9(
+    buf: &mut Vec<'_, u8>,
+    src: AArch64GeneralReg,
+    base: AArch64GeneralReg,
+    imm9: i16,
+) {
+    let inst = LoadStoreRegisterUnscaledImmediate::new(0b11, false, 0b00, imm9, base, src.id());
+
+    buf.extend(inst.bytes());
+}
+
 /// `SUB Xd, Xn, imm12` -> Subtract Xn and imm12 and place the result into Xd.
 #[inline(always)]
 fn sub_reg64_reg64_imm12(
@@ -1392,6 +2509,15 @@ mod tests {
         }
     }
 
+    impl AArch64FloatReg {
+        fn capstone_string(&self, float_type: FloatType) -> String {
+            match float_type {
+                FloatType::Single => format!("s{}", self.id()),
+                FloatType::Double => format!("d{}", self.id()),
+            }
+        }
+    }
+
     const TEST_U16: u16 = 0x1234;
     //const TEST_I32: i32 = 0x12345678;
     //const TEST_I64: i64 = 0x12345678_9ABCDEF0;
@@ -1431,6 +2557,41 @@ mod tests {
         AArch64GeneralReg::ZRSP,
     ];
 
+    const ALL_FLOAT_REGS: &[AArch64FloatReg] = &[
+        AArch64FloatReg::V0,
+        AArch64FloatReg::V1,
+        AArch64FloatReg::V2,
+        AArch64FloatReg::V3,
+        AArch64FloatReg::V4,
+        AArch64FloatReg::V5,
+        AArch64FloatReg::V6,
+        AArch64FloatReg::V7,
+        AArch64FloatReg::V8,
+        AArch64FloatReg::V9,
+        AArch64FloatReg::V10,
+        AArch64FloatReg::V11,
+        AArch64FloatReg::V12,
+        AArch64FloatReg::V13,
+        AArch64FloatReg::V14,
+        AArch64FloatReg::V15,
+        AArch64FloatReg::V16,
+        AArch64FloatReg::V17,
+        AArch64FloatReg::V18,
+        AArch64FloatReg::V19,
+        AArch64FloatReg::V20,
+        AArch64FloatReg::V21,
+        AArch64FloatReg::V22,
+        AArch64FloatReg::V23,
+        AArch64FloatReg::V24,
+        AArch64FloatReg::V25,
+        AArch64FloatReg::V26,
+        AArch64FloatReg::V27,
+        AArch64FloatReg::V28,
+        AArch64FloatReg::V29,
+        AArch64FloatReg::V30,
+        AArch64FloatReg::V31,
+    ];
+
     fn setup_capstone_and_arena<T>(
         arena: &bumpalo::Bump,
     ) -> (bumpalo::collections::Vec<T>, Capstone) {
@@ -1476,6 +2637,323 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bl_imm26() {
+        disassembler_test!(bl_imm26, |imm| format!("bl #0x{:x}", imm), [0x8, 0x1234]);
+    }
+
+    #[test]
+    fn test_fabs_freg64_freg64() {
+        disassembler_test!(
+            fabs_freg64_freg64,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg| format!(
+                "fabs {}, {}",
+                reg1.capstone_string(FloatType::Double),
+                reg2.capstone_string(FloatType::Double)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fadd_freg32_freg32_freg32() {
+        disassembler_test!(
+            fadd_freg32_freg32_freg32,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg, reg3: AArch64FloatReg| format!(
+                "fadd {}, {}, {}",
+                reg1.capstone_string(FloatType::Single),
+                reg2.capstone_string(FloatType::Single),
+                reg3.capstone_string(FloatType::Single)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fadd_freg64_freg64_freg64() {
+        disassembler_test!(
+            fadd_freg64_freg64_freg64,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg, reg3: AArch64FloatReg| format!(
+                "fadd {}, {}, {}",
+                reg1.capstone_string(FloatType::Double),
+                reg2.capstone_string(FloatType::Double),
+                reg3.capstone_string(FloatType::Double)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fcvt_freg32_freg64() {
+        disassembler_test!(
+            fcvt_freg32_freg64,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg| format!(
+                "fcvt {}, {}",
+                reg1.capstone_string(FloatType::Single),
+                reg2.capstone_string(FloatType::Double)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fcvt_freg64_freg32() {
+        disassembler_test!(
+            fcvt_freg64_freg32,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg| format!(
+                "fcvt {}, {}",
+                reg1.capstone_string(FloatType::Double),
+                reg2.capstone_string(FloatType::Single)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fcvtms_reg64_freg32() {
+        disassembler_test!(
+            fcvtms_reg64_freg32,
+            |reg1: AArch64GeneralReg, reg2: AArch64FloatReg| format!(
+                "fcvtms {}, {}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(FloatType::Single)
+            ),
+            ALL_GENERAL_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fcvtms_reg64_freg64() {
+        disassembler_test!(
+            fcvtms_reg64_freg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64FloatReg| format!(
+                "fcvtms {}, {}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(FloatType::Double)
+            ),
+            ALL_GENERAL_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fcvtps_reg64_freg32() {
+        disassembler_test!(
+            fcvtps_reg64_freg32,
+            |reg1: AArch64GeneralReg, reg2: AArch64FloatReg| format!(
+                "fcvtps {}, {}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(FloatType::Single)
+            ),
+            ALL_GENERAL_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fcvtps_reg64_freg64() {
+        disassembler_test!(
+            fcvtps_reg64_freg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64FloatReg| format!(
+                "fcvtps {}, {}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(FloatType::Double)
+            ),
+            ALL_GENERAL_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fdiv_freg32_freg32_freg32() {
+        disassembler_test!(
+            fdiv_freg32_freg32_freg32,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg, reg3: AArch64FloatReg| format!(
+                "fdiv {}, {}, {}",
+                reg1.capstone_string(FloatType::Single),
+                reg2.capstone_string(FloatType::Single),
+                reg3.capstone_string(FloatType::Single)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fdiv_freg64_freg64_freg64() {
+        disassembler_test!(
+            fdiv_freg64_freg64_freg64,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg, reg3: AArch64FloatReg| format!(
+                "fdiv {}, {}, {}",
+                reg1.capstone_string(FloatType::Double),
+                reg2.capstone_string(FloatType::Double),
+                reg3.capstone_string(FloatType::Double)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fmov_freg32_reg32() {
+        disassembler_test!(
+            fmov_freg32_reg32,
+            |reg1: AArch64FloatReg, reg2: AArch64GeneralReg| format!(
+                "fmov {}, {}",
+                reg1.capstone_string(FloatType::Single),
+                reg2.capstone_string(UsesZR).replacen('x', "w", 1)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_fmov_freg64_freg64() {
+        disassembler_test!(
+            fmov_freg64_freg64,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg| format!(
+                "fmov {}, {}",
+                reg1.capstone_string(FloatType::Double),
+                reg2.capstone_string(FloatType::Double)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fmov_freg64_reg64() {
+        disassembler_test!(
+            fmov_freg64_reg64,
+            |reg1: AArch64FloatReg, reg2: AArch64GeneralReg| format!(
+                "fmov {}, {}",
+                reg1.capstone_string(FloatType::Double),
+                reg2.capstone_string(UsesZR)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_fmul_freg32_freg32_freg32() {
+        disassembler_test!(
+            fmul_freg32_freg32_freg32,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg, reg3: AArch64FloatReg| format!(
+                "fmul {}, {}, {}",
+                reg1.capstone_string(FloatType::Single),
+                reg2.capstone_string(FloatType::Single),
+                reg3.capstone_string(FloatType::Single)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fmul_freg64_freg64_freg64() {
+        disassembler_test!(
+            fmul_freg64_freg64_freg64,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg, reg3: AArch64FloatReg| format!(
+                "fmul {}, {}, {}",
+                reg1.capstone_string(FloatType::Double),
+                reg2.capstone_string(FloatType::Double),
+                reg3.capstone_string(FloatType::Double)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fsqrt_freg32_freg32() {
+        disassembler_test!(
+            fsqrt_freg32_freg32,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg| format!(
+                "fsqrt {}, {}",
+                reg1.capstone_string(FloatType::Single),
+                reg2.capstone_string(FloatType::Single)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fsqrt_freg64_freg64() {
+        disassembler_test!(
+            fsqrt_freg64_freg64,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg| format!(
+                "fsqrt {}, {}",
+                reg1.capstone_string(FloatType::Double),
+                reg2.capstone_string(FloatType::Double)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fsub_freg32_freg32_freg32() {
+        disassembler_test!(
+            fsub_freg32_freg32_freg32,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg, reg3: AArch64FloatReg| format!(
+                "fsub {}, {}, {}",
+                reg1.capstone_string(FloatType::Single),
+                reg2.capstone_string(FloatType::Single),
+                reg3.capstone_string(FloatType::Single)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fsub_freg64_freg64_freg64() {
+        disassembler_test!(
+            fsub_freg64_freg64_freg64,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg, reg3: AArch64FloatReg| format!(
+                "fsub {}, {}, {}",
+                reg1.capstone_string(FloatType::Double),
+                reg2.capstone_string(FloatType::Double),
+                reg3.capstone_string(FloatType::Double)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_ldr_freg64_reg64_imm12() {
+        disassembler_test!(
+            ldr_freg64_reg64_imm12,
+            |reg1: AArch64FloatReg, reg2: AArch64GeneralReg, imm| format!(
+                "ldr {}, [{}, {}]",
+                reg1.capstone_string(FloatType::Double),
+                reg2.capstone_string(UsesSP),
+                format!("#0x{:x}", imm << 3)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS,
+            [0x123]
+        );
+    }
+
     #[test]
     fn test_ldr_reg64_reg64_imm12() {
         disassembler_test!(
@@ -1492,6 +2970,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_ldur_freg64_reg64_imm9() {
+        disassembler_test!(
+            ldur_freg64_reg64_imm9,
+            |reg1: AArch64FloatReg, reg2: AArch64GeneralReg, imm| format!(
+                "ldur {}, [{}, {}]",
+                reg1.capstone_string(FloatType::Double),
+                reg2.capstone_string(UsesSP),
+                format!("#-0x{:x}", -imm)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS,
+            [-0x10]
+        );
+    }
+
+    #[test]
+    fn test_ldur_reg64_reg64_imm9() {
+        disassembler_test!(
+            ldur_reg64_reg64_imm9,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
+                "ldur {}, [{}, {}]",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesSP),
+                format!("#-0x{:x}", -imm)
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            [-0x10]
+        );
+    }
+
     #[test]
     fn test_mov_reg64_reg64() {
         disassembler_test!(
@@ -1542,6 +3052,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_scvtf_freg32_reg64() {
+        disassembler_test!(
+            scvtf_freg32_reg64,
+            |reg1: AArch64FloatReg, reg2: AArch64GeneralReg| format!(
+                "scvtf {}, {}",
+                reg1.capstone_string(FloatType::Single),
+                reg2.capstone_string(UsesZR)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_scvtf_freg64_reg64() {
+        disassembler_test!(
+            scvtf_freg64_reg64,
+            |reg1: AArch64FloatReg, reg2: AArch64GeneralReg| format!(
+                "scvtf {}, {}",
+                reg1.capstone_string(FloatType::Double),
+                reg2.capstone_string(UsesZR)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_str_freg64_reg64_imm12() {
+        disassembler_test!(
+            str_freg64_reg64_imm12,
+            |reg1: AArch64FloatReg, reg2: AArch64GeneralReg, imm| format!(
+                "str {}, [{}, {}]",
+                reg1.capstone_string(FloatType::Double),
+                reg2.capstone_string(UsesSP),
+                format!("#0x{:x}", imm << 3)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS,
+            [0x123]
+        );
+    }
+
     #[test]
     fn test_str_reg64_reg64_imm12() {
         disassembler_test!(
@@ -1558,6 +3112,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_stur_freg64_reg64_imm9() {
+        disassembler_test!(
+            stur_freg64_reg64_imm9,
+            |reg1: AArch64FloatReg, reg2: AArch64GeneralReg, imm| format!(
+                "stur {}, [{}, {}]",
+                reg1.capstone_string(FloatType::Double),
+                reg2.capstone_string(UsesSP),
+                format!("#-0x{:x}", -imm)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS,
+            [-0x10]
+        );
+    }
+
+    #[test]
+    fn test_stur_reg64_reg64_imm9() {
+        disassembler_test!(
+            stur_reg64_reg64_imm9,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
+                "stur {}, [{}, {}]",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesSP),
+                format!("#-0x{:x}", -imm)
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            [-0x10]
+        );
+    }
+
     #[test]
     fn test_sub_reg64_reg64_imm12() {
         disassembler_test!(
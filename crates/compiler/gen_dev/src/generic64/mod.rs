@@ -9,7 +9,8 @@ use roc_error_macros::internal_error;
 use roc_module::symbol::{Interns, ModuleId, Symbol};
 use roc_mono::code_gen_help::CodeGenHelp;
 use roc_mono::ir::{
-    BranchInfo, JoinPointId, ListLiteralElement, Literal, Param, ProcLayout, SelfRecursive, Stmt,
+    BranchInfo, CrashRegion, CrashTag, JoinPointId, ListLiteralElement, Literal, Param, ProcLayout,
+    SelfRecursive, Stmt,
 };
 use roc_mono::layout::{
     Builtin, InLayout, Layout, LayoutInterner, STLayoutInterner, TagIdIntType, UnionLayout,
@@ -113,6 +114,23 @@ pub trait CallConv<GeneralReg: RegTrait, FloatReg: RegTrait, ASM: Assembler<Gene
     );
 }
 
+/// How floats are rounded when they are converted to integers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Towards negative infinity.
+    Floor,
+    /// Towards positive infinity.
+    Ceiling,
+}
+
+/// The arithmetic operations which have a `Checked` variant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CheckedOp {
+    Add,
+    Sub,
+    Mul,
+}
+
 /// Assembler contains calls to the backend assembly generator.
 /// These calls do not necessarily map directly to a single assembly instruction.
 /// They are higher level in cases where an instruction would not be common and shared between multiple architectures.
@@ -199,6 +217,35 @@ pub trait Assembler<GeneralReg: RegTrait, FloatReg: RegTrait>: Sized + Copy {
         ASM: Assembler<GeneralReg, FloatReg>,
         CC: CallConv<GeneralReg, FloatReg, ASM>;
 
+    fn rol_reg64_reg64_reg64<'a, 'r, ASM, CC>(
+        buf: &mut Vec<'a, u8>,
+        storage_manager: &mut StorageManager<'a, 'r, GeneralReg, FloatReg, ASM, CC>,
+        dst: GeneralReg,
+        src1: GeneralReg,
+        src2: GeneralReg,
+    ) where
+        ASM: Assembler<GeneralReg, FloatReg>,
+        CC: CallConv<GeneralReg, FloatReg, ASM>;
+
+    fn ror_reg64_reg64_reg64<'a, 'r, ASM, CC>(
+        buf: &mut Vec<'a, u8>,
+        storage_manager: &mut StorageManager<'a, 'r, GeneralReg, FloatReg, ASM, CC>,
+        dst: GeneralReg,
+        src1: GeneralReg,
+        src2: GeneralReg,
+    ) where
+        ASM: Assembler<GeneralReg, FloatReg>,
+        CC: CallConv<GeneralReg, FloatReg, ASM>;
+
+    /// Counts the 1 bits in src.
+    fn popcnt_reg64_reg64(buf: &mut Vec<'_, u8>, dst: GeneralReg, src: GeneralReg);
+    /// Counts the 0 bits before the most significant 1 bit of src, which is 64 if src is 0.
+    fn lzcnt_reg64_reg64(buf: &mut Vec<'_, u8>, dst: GeneralReg, src: GeneralReg);
+    /// Counts the 0 bits after the least significant 1 bit of src, which is 64 if src is 0.
+    fn tzcnt_reg64_reg64(buf: &mut Vec<'_, u8>, dst: GeneralReg, src: GeneralReg);
+    /// Reverses the order of the bytes of src.
+    fn bswap_reg64_reg64(buf: &mut Vec<'_, u8>, dst: GeneralReg, src: GeneralReg);
+
     fn call(buf: &mut Vec<'_, u8>, relocs: &mut Vec<'_, Relocation>, fn_name: String);
 
     /// Jumps by an offset of offset bytes unconditionally.
@@ -325,6 +372,21 @@ pub trait Assembler<GeneralReg: RegTrait, FloatReg: RegTrait>: Sized + Copy {
         ASM: Assembler<GeneralReg, FloatReg>,
         CC: CallConv<GeneralReg, FloatReg, ASM>;
 
+    fn sqrt_freg32_freg32(buf: &mut Vec<'_, u8>, dst: FloatReg, src: FloatReg);
+    fn sqrt_freg64_freg64(buf: &mut Vec<'_, u8>, dst: FloatReg, src: FloatReg);
+
+    fn sub_freg32_freg32_freg32(
+        buf: &mut Vec<'_, u8>,
+        dst: FloatReg,
+        src1: FloatReg,
+        src2: FloatReg,
+    );
+    fn sub_freg64_freg64_freg64(
+        buf: &mut Vec<'_, u8>,
+        dst: FloatReg,
+        src1: FloatReg,
+        src2: FloatReg,
+    );
     fn sub_reg64_reg64_imm32(buf: &mut Vec<'_, u8>, dst: GeneralReg, src1: GeneralReg, imm32: i32);
     fn sub_reg64_reg64_reg64(
         buf: &mut Vec<'_, u8>,
@@ -383,6 +445,22 @@ pub trait Assembler<GeneralReg: RegTrait, FloatReg: RegTrait>: Sized + Copy {
 
     fn to_float_freg64_freg32(buf: &mut Vec<'_, u8>, dst: FloatReg, src: FloatReg);
 
+    /// Converts a float to a signed 64 bit integer, rounding it as `mode` says.
+    fn to_int_reg64_freg32(
+        buf: &mut Vec<'_, u8>,
+        dst: GeneralReg,
+        src: FloatReg,
+        mode: RoundingMode,
+    );
+
+    /// Converts a float to a signed 64 bit integer, rounding it as `mode` says.
+    fn to_int_reg64_freg64(
+        buf: &mut Vec<'_, u8>,
+        dst: GeneralReg,
+        src: FloatReg,
+        mode: RoundingMode,
+    );
+
     fn lte_reg64_reg64_reg64(
         buf: &mut Vec<'_, u8>,
         dst: GeneralReg,
@@ -903,6 +981,15 @@ impl<
                 let src2_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src2);
                 ASM::add_freg32_freg32_freg32(&mut self.buf, dst_reg, src1_reg, src2_reg);
             }
+            Layout::Builtin(Builtin::Decimal) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::DEC_ADD_OR_PANIC.to_string(),
+                    &[*src1, *src2],
+                    &[*layout, *layout],
+                    layout,
+                );
+            }
             x => todo!("NumAdd: layout, {:?}", x),
         }
     }
@@ -915,47 +1002,29 @@ impl<
         num_layout: &InLayout<'a>,
         return_layout: &InLayout<'a>,
     ) {
-        use Builtin::Int;
-
-        let buf = &mut self.buf;
-
-        let struct_size = self.layout_interner.stack_size(*return_layout);
-
-        let base_offset = self.storage_manager.claim_stack_area(dst, struct_size);
-
-        match self.layout_interner.get(*num_layout) {
-            Layout::Builtin(Int(IntWidth::I64 | IntWidth::I32 | IntWidth::I16 | IntWidth::I8)) => {
-                let dst_reg = self
-                    .storage_manager
-                    .claim_general_reg(buf, &Symbol::DEV_TMP);
-
-                let overflow_reg = self
-                    .storage_manager
-                    .claim_general_reg(buf, &Symbol::DEV_TMP2);
-
-                let src1_reg = self.storage_manager.load_to_general_reg(buf, src1);
-                let src2_reg = self.storage_manager.load_to_general_reg(buf, src2);
-
-                ASM::add_reg64_reg64_reg64(buf, dst_reg, src1_reg, src2_reg);
-                ASM::set_if_overflow(buf, overflow_reg);
+        self.build_num_checked(dst, src1, src2, num_layout, return_layout, CheckedOp::Add)
+    }
 
-                ASM::mov_base32_reg64(buf, base_offset, dst_reg);
-                ASM::mov_base32_reg64(buf, base_offset + 8, overflow_reg);
+    fn build_num_sub_checked(
+        &mut self,
+        dst: &Symbol,
+        src1: &Symbol,
+        src2: &Symbol,
+        num_layout: &InLayout<'a>,
+        return_layout: &InLayout<'a>,
+    ) {
+        self.build_num_checked(dst, src1, src2, num_layout, return_layout, CheckedOp::Sub)
+    }
 
-                self.free_symbol(&Symbol::DEV_TMP);
-                self.free_symbol(&Symbol::DEV_TMP2);
-            }
-            Layout::Builtin(Int(IntWidth::U64 | IntWidth::U32 | IntWidth::U16 | IntWidth::U8)) => {
-                todo!("addChecked for unsigned integers")
-            }
-            Layout::Builtin(Builtin::Float(FloatWidth::F64)) => {
-                todo!("addChecked for f64")
-            }
-            Layout::Builtin(Builtin::Float(FloatWidth::F32)) => {
-                todo!("addChecked for f32")
-            }
-            x => todo!("NumAdd: layout, {:?}", x),
-        }
+    fn build_num_mul_checked(
+        &mut self,
+        dst: &Symbol,
+        src1: &Symbol,
+        src2: &Symbol,
+        num_layout: &InLayout<'a>,
+        return_layout: &InLayout<'a>,
+    ) {
+        self.build_num_checked(dst, src1, src2, num_layout, return_layout, CheckedOp::Mul)
     }
 
     fn build_num_mul(&mut self, dst: &Symbol, src1: &Symbol, src2: &Symbol, layout: &InLayout<'a>) {
@@ -1001,6 +1070,15 @@ impl<
                 let src2_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src2);
                 ASM::mul_freg32_freg32_freg32(&mut self.buf, dst_reg, src1_reg, src2_reg);
             }
+            Layout::Builtin(Builtin::Decimal) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::DEC_MUL_OR_PANIC.to_string(),
+                    &[*src1, *src2],
+                    &[*layout, *layout],
+                    layout,
+                );
+            }
             x => todo!("NumMul: layout, {:?}", x),
         }
     }
@@ -1057,6 +1135,15 @@ impl<
                 let src2_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src2);
                 ASM::div_freg32_freg32_freg32(&mut self.buf, dst_reg, src1_reg, src2_reg);
             }
+            Layout::Builtin(Builtin::Decimal) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::DEC_DIV.to_string(),
+                    &[*src1, *src2],
+                    &[*layout, *layout],
+                    layout,
+                );
+            }
             x => todo!("NumDiv: layout, {:?}", x),
         }
     }
@@ -1068,14 +1155,46 @@ impl<
                 let src_reg = self.storage_manager.load_to_general_reg(&mut self.buf, src);
                 ASM::neg_reg64_reg64(&mut self.buf, dst_reg, src_reg);
             }
+            Layout::Builtin(Builtin::Decimal) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::DEC_NEGATE.to_string(),
+                    &[*src],
+                    &[*layout],
+                    layout,
+                );
+            }
             x => todo!("NumNeg: layout, {:?}", x),
         }
     }
 
     fn build_num_sub(&mut self, dst: &Symbol, src1: &Symbol, src2: &Symbol, layout: &InLayout<'a>) {
-        // for the time being, `num_sub` is implemented as wrapping subtraction. In roc, the normal
-        // `sub` should panic on overflow, but we just don't do that yet
-        self.build_num_sub_wrap(dst, src1, src2, layout)
+        match self.layout_interner.get(*layout) {
+            Layout::Builtin(Builtin::Float(FloatWidth::F64)) => {
+                let dst_reg = self.storage_manager.claim_float_reg(&mut self.buf, dst);
+                let src1_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src1);
+                let src2_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src2);
+                ASM::sub_freg64_freg64_freg64(&mut self.buf, dst_reg, src1_reg, src2_reg);
+            }
+            Layout::Builtin(Builtin::Float(FloatWidth::F32)) => {
+                let dst_reg = self.storage_manager.claim_float_reg(&mut self.buf, dst);
+                let src1_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src1);
+                let src2_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src2);
+                ASM::sub_freg32_freg32_freg32(&mut self.buf, dst_reg, src1_reg, src2_reg);
+            }
+            Layout::Builtin(Builtin::Decimal) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::DEC_SUB_OR_PANIC.to_string(),
+                    &[*src1, *src2],
+                    &[*layout, *layout],
+                    layout,
+                );
+            }
+            // for the time being, integer `num_sub` is implemented as wrapping subtraction. In roc,
+            // the normal `sub` should panic on overflow, but we just don't do that yet
+            _ => self.build_num_sub_wrap(dst, src1, src2, layout),
+        }
     }
 
     fn build_num_sub_wrap(
@@ -1112,6 +1231,15 @@ impl<
                     .load_to_general_reg(&mut self.buf, src2);
                 ASM::eq_reg64_reg64_reg64(&mut self.buf, dst_reg, src1_reg, src2_reg);
             }
+            Layout::DEC => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::DEC_EQ.to_string(),
+                    &[*src1, *src2],
+                    &[Layout::DEC, Layout::DEC],
+                    &Layout::BOOL,
+                );
+            }
             x => todo!("NumEq: layout, {:?}", x),
         }
     }
@@ -1128,6 +1256,15 @@ impl<
                     .load_to_general_reg(&mut self.buf, src2);
                 ASM::neq_reg64_reg64_reg64(&mut self.buf, dst_reg, src1_reg, src2_reg);
             }
+            Layout::Builtin(Builtin::Decimal) => {
+                self.build_fn_call(
+                    dst,
+                    bitcode::DEC_NEQ.to_string(),
+                    &[*src1, *src2],
+                    &[*arg_layout, *arg_layout],
+                    &Layout::BOOL,
+                );
+            }
             x => todo!("NumNeq: layout, {:?}", x),
         }
     }
@@ -1254,6 +1391,42 @@ impl<
         }
     }
 
+    fn build_num_sqrt(&mut self, dst: &Symbol, src: &Symbol, layout: &InLayout<'a>) {
+        match self.layout_interner.get(*layout) {
+            Layout::Builtin(Builtin::Float(FloatWidth::F64)) => {
+                let dst_reg = self.storage_manager.claim_float_reg(&mut self.buf, dst);
+                let src_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src);
+                ASM::sqrt_freg64_freg64(&mut self.buf, dst_reg, src_reg);
+            }
+            Layout::Builtin(Builtin::Float(FloatWidth::F32)) => {
+                let dst_reg = self.storage_manager.claim_float_reg(&mut self.buf, dst);
+                let src_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src);
+                ASM::sqrt_freg32_freg32(&mut self.buf, dst_reg, src_reg);
+            }
+            x => todo!("NumSqrt: layout, {:?}", x),
+        }
+    }
+
+    fn build_num_floor(
+        &mut self,
+        dst: &Symbol,
+        src: &Symbol,
+        arg_layout: &InLayout<'a>,
+        ret_layout: &InLayout<'a>,
+    ) {
+        self.build_num_to_int_rounded(dst, src, arg_layout, ret_layout, RoundingMode::Floor)
+    }
+
+    fn build_num_ceiling(
+        &mut self,
+        dst: &Symbol,
+        src: &Symbol,
+        arg_layout: &InLayout<'a>,
+        ret_layout: &InLayout<'a>,
+    ) {
+        self.build_num_to_int_rounded(dst, src, arg_layout, ret_layout, RoundingMode::Ceiling)
+    }
+
     fn build_num_lte(
         &mut self,
         dst: &Symbol,
@@ -1808,6 +1981,52 @@ impl<
         ASM::add_reg64_reg64_imm32(&mut self.buf, dst_reg, CC::BASE_PTR_REG, offset);
     }
 
+    fn build_crash(&mut self, msg: &Symbol, tag: CrashTag, region: CrashRegion) {
+        // roc_panic gets a pointer to the message, followed by the source region. The region is
+        // four u32s, which we write as two u64s; on little-endian targets, that's the same thing.
+        let [start_line, start_column, end_line, end_column] = region.to_array();
+        let start = ((start_column as u64) << 32) | start_line as u64;
+        let end = ((end_column as u64) << 32) | end_line as u64;
+
+        let start_literal = Literal::Int((start as i128).to_ne_bytes());
+        let end_literal = Literal::Int((end as i128).to_ne_bytes());
+        self.load_literal(&Symbol::DEV_TMP3, &Layout::U64, &start_literal);
+        self.load_literal(&Symbol::DEV_TMP4, &Layout::U64, &end_literal);
+
+        let field_layouts = self
+            .env
+            .arena
+            .alloc([Layout::STR, Layout::U64, Layout::U64]);
+        let payload_layout = self
+            .layout_interner
+            .insert(Layout::struct_no_name_order(field_layouts));
+        let fields = self
+            .env
+            .arena
+            .alloc([*msg, Symbol::DEV_TMP3, Symbol::DEV_TMP4]);
+        self.create_struct(&Symbol::DEV_TMP, &payload_layout, fields);
+        self.free_symbol(&Symbol::DEV_TMP3);
+        self.free_symbol(&Symbol::DEV_TMP4);
+
+        self.build_ptr_cast(&Symbol::DEV_TMP2, &Symbol::DEV_TMP);
+
+        let tag_literal = Literal::Int((tag as u32 as i128).to_ne_bytes());
+        self.load_literal(&Symbol::DEV_TMP3, &Layout::U32, &tag_literal);
+
+        self.build_fn_call(
+            &Symbol::DEV_TMP4,
+            "roc_panic".to_string(),
+            &[Symbol::DEV_TMP2, Symbol::DEV_TMP3],
+            &[Layout::U64, Layout::U32],
+            &Layout::UNIT,
+        );
+
+        self.free_symbol(&Symbol::DEV_TMP);
+        self.free_symbol(&Symbol::DEV_TMP2);
+        self.free_symbol(&Symbol::DEV_TMP3);
+        self.free_symbol(&Symbol::DEV_TMP4);
+    }
+
     fn create_empty_array(&mut self, sym: &Symbol) {
         let base_offset = self.storage_manager.claim_stack_area(sym, 24);
         self.storage_manager
@@ -2131,6 +2350,25 @@ impl<
                 let val = *x as f32;
                 ASM::mov_freg32_imm32(&mut self.buf, &mut self.relocs, reg, val);
             }
+            (Literal::Decimal(bytes), Layout::Builtin(Builtin::Decimal)) => {
+                self.storage_manager.with_tmp_general_reg(
+                    &mut self.buf,
+                    |storage_manager, buf, reg| {
+                        let base_offset = storage_manager.claim_stack_area(sym, 16);
+
+                        let mut num_bytes = [0; 8];
+                        num_bytes.copy_from_slice(&bytes[..8]);
+                        let num = i64::from_ne_bytes(num_bytes);
+                        ASM::mov_reg64_imm64(buf, reg, num);
+                        ASM::mov_base32_reg64(buf, base_offset, reg);
+
+                        num_bytes.copy_from_slice(&bytes[8..]);
+                        let num = i64::from_ne_bytes(num_bytes);
+                        ASM::mov_reg64_imm64(buf, reg, num);
+                        ASM::mov_base32_reg64(buf, base_offset + 8, reg);
+                    },
+                );
+            }
             (Literal::Str(x), Layout::Builtin(Builtin::Str)) if x.len() < 24 => {
                 // Load small string.
                 self.storage_manager.with_tmp_general_reg(
@@ -2398,6 +2636,205 @@ impl<
             }
         }
     }
+
+    fn build_int_pop_count(&mut self, dst: &Symbol, src: &Symbol, int_width: IntWidth) {
+        match int_width {
+            IntWidth::U128 | IntWidth::I128 => self.build_fn_call(
+                dst,
+                bitcode::NUM_POP_COUNT[int_width].to_string(),
+                &[*src],
+                &[Layout::int_width(int_width)],
+                &Layout::U8,
+            ),
+            _ => {
+                let buf = &mut self.buf;
+                let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
+                let src_reg = self.storage_manager.load_to_general_reg(buf, src);
+
+                // only count the integer's own bits, and not any sign extension above them
+                let bits = int_width.stack_size() * 8;
+                if bits < 64 {
+                    self.storage_manager
+                        .with_tmp_general_reg(buf, |_, buf, tmp_reg| {
+                            ASM::mov_reg64_imm64(buf, tmp_reg, (1 << bits) - 1);
+                            ASM::and_reg64_reg64_reg64(buf, dst_reg, src_reg, tmp_reg);
+                        });
+                    ASM::popcnt_reg64_reg64(buf, dst_reg, dst_reg);
+                } else {
+                    ASM::popcnt_reg64_reg64(buf, dst_reg, src_reg);
+                }
+            }
+        }
+    }
+
+    fn build_int_leading_zeros(&mut self, dst: &Symbol, src: &Symbol, int_width: IntWidth) {
+        match int_width {
+            IntWidth::U128 | IntWidth::I128 => self.build_fn_call(
+                dst,
+                bitcode::NUM_LEADING_ZEROS[int_width].to_string(),
+                &[*src],
+                &[Layout::int_width(int_width)],
+                &Layout::U8,
+            ),
+            _ => {
+                let buf = &mut self.buf;
+                let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
+                let src_reg = self.storage_manager.load_to_general_reg(buf, src);
+
+                // count the leading zeros of the zero extended integer, and then take off the
+                // ones which come from extending it
+                let bits = int_width.stack_size() * 8;
+                if bits < 64 {
+                    self.storage_manager
+                        .with_tmp_general_reg(buf, |_, buf, tmp_reg| {
+                            ASM::mov_reg64_imm64(buf, tmp_reg, (1 << bits) - 1);
+                            ASM::and_reg64_reg64_reg64(buf, dst_reg, src_reg, tmp_reg);
+                        });
+                    ASM::lzcnt_reg64_reg64(buf, dst_reg, dst_reg);
+                    ASM::sub_reg64_reg64_imm32(buf, dst_reg, dst_reg, 64 - bits as i32);
+                } else {
+                    ASM::lzcnt_reg64_reg64(buf, dst_reg, src_reg);
+                }
+            }
+        }
+    }
+
+    fn build_int_trailing_zeros(&mut self, dst: &Symbol, src: &Symbol, int_width: IntWidth) {
+        match int_width {
+            IntWidth::U128 | IntWidth::I128 => self.build_fn_call(
+                dst,
+                bitcode::NUM_TRAILING_ZEROS[int_width].to_string(),
+                &[*src],
+                &[Layout::int_width(int_width)],
+                &Layout::U8,
+            ),
+            _ => {
+                let buf = &mut self.buf;
+                let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
+                let src_reg = self.storage_manager.load_to_general_reg(buf, src);
+
+                // setting the bit just past the integer makes 0 count as its width in bits
+                let bits = int_width.stack_size() * 8;
+                if bits < 64 {
+                    self.storage_manager
+                        .with_tmp_general_reg(buf, |_, buf, tmp_reg| {
+                            ASM::mov_reg64_imm64(buf, tmp_reg, 1 << bits);
+                            ASM::or_reg64_reg64_reg64(buf, dst_reg, src_reg, tmp_reg);
+                        });
+                    ASM::tzcnt_reg64_reg64(buf, dst_reg, dst_reg);
+                } else {
+                    ASM::tzcnt_reg64_reg64(buf, dst_reg, src_reg);
+                }
+            }
+        }
+    }
+
+    fn build_int_rotate_left(
+        &mut self,
+        dst: &Symbol,
+        src1: &Symbol,
+        src2: &Symbol,
+        int_width: IntWidth,
+    ) {
+        match int_width {
+            IntWidth::U64 | IntWidth::I64 => {
+                let buf = &mut self.buf;
+                let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
+                let src1_reg = self.storage_manager.load_to_general_reg(buf, src1);
+                let src2_reg = self.storage_manager.load_to_general_reg(buf, src2);
+
+                ASM::rol_reg64_reg64_reg64(
+                    buf,
+                    &mut self.storage_manager,
+                    dst_reg,
+                    src1_reg,
+                    src2_reg,
+                );
+            }
+            // the bits which come back in would have to be taken from the right place in the
+            // register, so smaller integers are rotated by the builtin instead
+            _ => self.build_fn_call(
+                dst,
+                bitcode::NUM_ROTATE_LEFT[int_width].to_string(),
+                &[*src1, *src2],
+                &[Layout::int_width(int_width), Layout::U8],
+                &Layout::int_width(int_width),
+            ),
+        }
+    }
+
+    fn build_int_rotate_right(
+        &mut self,
+        dst: &Symbol,
+        src1: &Symbol,
+        src2: &Symbol,
+        int_width: IntWidth,
+    ) {
+        match int_width {
+            IntWidth::U64 | IntWidth::I64 => {
+                let buf = &mut self.buf;
+                let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
+                let src1_reg = self.storage_manager.load_to_general_reg(buf, src1);
+                let src2_reg = self.storage_manager.load_to_general_reg(buf, src2);
+
+                ASM::ror_reg64_reg64_reg64(
+                    buf,
+                    &mut self.storage_manager,
+                    dst_reg,
+                    src1_reg,
+                    src2_reg,
+                );
+            }
+            _ => self.build_fn_call(
+                dst,
+                bitcode::NUM_ROTATE_RIGHT[int_width].to_string(),
+                &[*src1, *src2],
+                &[Layout::int_width(int_width), Layout::U8],
+                &Layout::int_width(int_width),
+            ),
+        }
+    }
+
+    fn build_int_swap_bytes(&mut self, dst: &Symbol, src: &Symbol, int_width: IntWidth) {
+        match int_width {
+            IntWidth::U128 | IntWidth::I128 => self.build_fn_call(
+                dst,
+                bitcode::NUM_SWAP_BYTES[int_width].to_string(),
+                &[*src],
+                &[Layout::int_width(int_width)],
+                &Layout::int_width(int_width),
+            ),
+            _ => {
+                let buf = &mut self.buf;
+                let dst_reg = self.storage_manager.claim_general_reg(buf, dst);
+                let src_reg = self.storage_manager.load_to_general_reg(buf, src);
+
+                match int_width.stack_size() {
+                    // a single byte is its own byte swap
+                    1 => ASM::mov_reg64_reg64(buf, dst_reg, src_reg),
+                    8 => ASM::bswap_reg64_reg64(buf, dst_reg, src_reg),
+                    size => {
+                        // swapping all 8 bytes moves the integer's bytes to the top of the
+                        // register, in the right order, so shift them back down
+                        ASM::bswap_reg64_reg64(buf, dst_reg, src_reg);
+                        self.storage_manager.with_tmp_general_reg(
+                            buf,
+                            |storage_manager, buf, tmp_reg| {
+                                ASM::mov_reg64_imm64(buf, tmp_reg, 64 - size as i64 * 8);
+                                ASM::shr_reg64_reg64_reg64(
+                                    buf,
+                                    storage_manager,
+                                    dst_reg,
+                                    dst_reg,
+                                    tmp_reg,
+                                );
+                            },
+                        )
+                    }
+                }
+            }
+        }
+    }
 }
 
 /// This impl block is for ir related instructions that need backend specific information.
@@ -2445,6 +2882,180 @@ impl<
         ASM::mov_base32_reg64(buf, base_offset + 16, tmp_reg);
     }
 
+    /// Converts a float to a signed integer of at most 64 bits, rounding it as `mode` says.
+    fn build_num_to_int_rounded(
+        &mut self,
+        dst: &Symbol,
+        src: &Symbol,
+        arg_layout: &InLayout<'a>,
+        ret_layout: &InLayout<'a>,
+        mode: RoundingMode,
+    ) {
+        match (
+            self.layout_interner.get(*arg_layout),
+            self.layout_interner.get(*ret_layout),
+        ) {
+            (
+                Layout::Builtin(Builtin::Float(FloatWidth::F64)),
+                Layout::Builtin(Builtin::Int(
+                    IntWidth::I64 | IntWidth::I32 | IntWidth::I16 | IntWidth::I8,
+                )),
+            ) => {
+                let dst_reg = self.storage_manager.claim_general_reg(&mut self.buf, dst);
+                let src_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src);
+                ASM::to_int_reg64_freg64(&mut self.buf, dst_reg, src_reg, mode);
+            }
+            (
+                Layout::Builtin(Builtin::Float(FloatWidth::F32)),
+                Layout::Builtin(Builtin::Int(
+                    IntWidth::I64 | IntWidth::I32 | IntWidth::I16 | IntWidth::I8,
+                )),
+            ) => {
+                let dst_reg = self.storage_manager.claim_general_reg(&mut self.buf, dst);
+                let src_reg = self.storage_manager.load_to_float_reg(&mut self.buf, src);
+                ASM::to_int_reg64_freg32(&mut self.buf, dst_reg, src_reg, mode);
+            }
+            (a, r) => todo!("rounding {:?} to {:?} with {:?}", a, r, mode),
+        }
+    }
+
+    /// Stores `src1 op src2` into the `{ value, overflowed }` struct dst.
+    ///
+    /// 64-bit results are checked with the overflow flag (or, for unsigned addition and
+    /// subtraction, a comparison). Smaller integers are computed exactly in 64 bits, so they
+    /// overflowed if extending the truncated result back to 64 bits changes it.
+    fn build_num_checked(
+        &mut self,
+        dst: &Symbol,
+        src1: &Symbol,
+        src2: &Symbol,
+        num_layout: &InLayout<'a>,
+        return_layout: &InLayout<'a>,
+        op: CheckedOp,
+    ) {
+        let int_width = match self.layout_interner.get(*num_layout) {
+            Layout::Builtin(Builtin::Int(int_width)) if int_width.stack_size() <= 8 => int_width,
+            Layout::Builtin(Builtin::Int(int_width)) => {
+                let intrinsic = match op {
+                    CheckedOp::Add => &bitcode::NUM_ADD_CHECKED_INT,
+                    CheckedOp::Sub => &bitcode::NUM_SUB_CHECKED_INT,
+                    CheckedOp::Mul => &bitcode::NUM_MUL_CHECKED_INT,
+                };
+
+                return self.build_fn_call(
+                    dst,
+                    intrinsic[int_width].to_string(),
+                    &[*src1, *src2],
+                    &[*num_layout, *num_layout],
+                    return_layout,
+                );
+            }
+            Layout::Builtin(Builtin::Decimal) => {
+                let fn_name = match op {
+                    CheckedOp::Add => bitcode::DEC_ADD_WITH_OVERFLOW,
+                    CheckedOp::Sub => bitcode::DEC_SUB_WITH_OVERFLOW,
+                    CheckedOp::Mul => bitcode::DEC_MUL_WITH_OVERFLOW,
+                };
+
+                return self.build_fn_call(
+                    dst,
+                    fn_name.to_string(),
+                    &[*src1, *src2],
+                    &[*num_layout, *num_layout],
+                    return_layout,
+                );
+            }
+            x => todo!("{:?}Checked: layout, {:?}", op, x),
+        };
+
+        let buf = &mut self.buf;
+
+        let struct_size = self.layout_interner.stack_size(*return_layout);
+        let base_offset = self.storage_manager.claim_stack_area(dst, struct_size);
+
+        let src1_reg = self.storage_manager.load_to_general_reg(buf, src1);
+        let src2_reg = self.storage_manager.load_to_general_reg(buf, src2);
+
+        let dst_reg = self
+            .storage_manager
+            .claim_general_reg(buf, &Symbol::DEV_TMP);
+
+        // for unsigned subtraction, the flag has to be worked out before the operands are overwritten
+        let borrowed_reg = if op == CheckedOp::Sub && int_width == IntWidth::U64 {
+            let reg = self
+                .storage_manager
+                .claim_general_reg(buf, &Symbol::DEV_TMP2);
+            ASM::ult_reg64_reg64_reg64(buf, reg, src1_reg, src2_reg);
+            Some(reg)
+        } else {
+            None
+        };
+
+        match (op, int_width.is_signed()) {
+            (CheckedOp::Add, _) => ASM::add_reg64_reg64_reg64(buf, dst_reg, src1_reg, src2_reg),
+            (CheckedOp::Sub, _) => ASM::sub_reg64_reg64_reg64(buf, dst_reg, src1_reg, src2_reg),
+            (CheckedOp::Mul, true) => ASM::imul_reg64_reg64_reg64(buf, dst_reg, src1_reg, src2_reg),
+            (CheckedOp::Mul, false) => ASM::umul_reg64_reg64_reg64(
+                buf,
+                &mut self.storage_manager,
+                dst_reg,
+                src1_reg,
+                src2_reg,
+            ),
+        }
+
+        let overflow_reg = match borrowed_reg {
+            Some(reg) => reg,
+            None => self
+                .storage_manager
+                .claim_general_reg(buf, &Symbol::DEV_TMP2),
+        };
+
+        let size = int_width.stack_size();
+
+        if size == 8 {
+            match (op, int_width) {
+                // a sum which wrapped around is smaller than either of the operands
+                (CheckedOp::Add, IntWidth::U64) => {
+                    ASM::ult_reg64_reg64_reg64(buf, overflow_reg, dst_reg, src1_reg)
+                }
+                (CheckedOp::Sub, IntWidth::U64) => {}
+                _ => ASM::set_if_overflow(buf, overflow_reg),
+            }
+
+            ASM::mov_base32_reg64(buf, base_offset, dst_reg);
+            ASM::mov_base32_reg64(buf, base_offset + 8, overflow_reg);
+        } else {
+            let tmp_reg = self
+                .storage_manager
+                .claim_general_reg(buf, &Symbol::DEV_TMP3);
+
+            // truncate the result by storing it and loading back `size` bytes of it
+            ASM::mov_base32_reg64(buf, base_offset, dst_reg);
+            if int_width.is_signed() {
+                ASM::movsx_reg64_base32(buf, tmp_reg, base_offset, size as u8);
+            } else {
+                ASM::movzx_reg64_base32(buf, tmp_reg, base_offset, size as u8);
+            }
+            ASM::neq_reg64_reg64_reg64(buf, overflow_reg, tmp_reg, dst_reg);
+
+            // the flag goes in the byte right after the value, so build the whole
+            // (at most 8 byte) struct as `value & mask | overflowed << bits`
+            let bits = size * 8;
+            ASM::mov_reg64_imm64(buf, tmp_reg, (1 << bits) - 1);
+            ASM::and_reg64_reg64_reg64(buf, dst_reg, dst_reg, tmp_reg);
+            ASM::mov_reg64_imm64(buf, tmp_reg, 1 << bits);
+            ASM::imul_reg64_reg64_reg64(buf, overflow_reg, overflow_reg, tmp_reg);
+            ASM::or_reg64_reg64_reg64(buf, dst_reg, dst_reg, overflow_reg);
+            ASM::mov_base32_reg64(buf, base_offset, dst_reg);
+
+            self.free_symbol(&Symbol::DEV_TMP3);
+        }
+
+        self.free_symbol(&Symbol::DEV_TMP);
+        self.free_symbol(&Symbol::DEV_TMP2);
+    }
+
     /// Updates a jump instruction to a new offset and returns the number of bytes written.
     fn update_jmp_imm32_offset(
         &mut self,
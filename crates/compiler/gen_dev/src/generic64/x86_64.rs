@@ -1,4 +1,4 @@
-use crate::generic64::{storage::StorageManager, Assembler, CallConv, RegTrait};
+use crate::generic64::{storage::StorageManager, Assembler, CallConv, RegTrait, RoundingMode};
 use crate::{
     single_register_floats, single_register_int_builtins, single_register_integers,
     single_register_layouts, Relocation,
@@ -1308,7 +1308,9 @@ impl Assembler<X86_64GeneralReg, X86_64FloatReg> for X86_64Assembler {
         debug_assert!(size <= 8);
         match size {
             8 => Self::mov_reg64_base32(buf, dst, offset),
-            4 | 2 | 1 => todo!("sign extending {size} byte values"),
+            4 => movsxd_reg64_base32_offset32(buf, dst, X86_64GeneralReg::RBP, offset),
+            2 => movsx_reg64_base16_offset32(buf, dst, X86_64GeneralReg::RBP, offset),
+            1 => movsx_reg64_base8_offset32(buf, dst, X86_64GeneralReg::RBP, offset),
             _ => internal_error!("Invalid size for sign extension: {size}"),
         }
     }
@@ -1317,7 +1319,8 @@ impl Assembler<X86_64GeneralReg, X86_64FloatReg> for X86_64Assembler {
         debug_assert!(size <= 8);
         match size {
             8 => Self::mov_reg64_base32(buf, dst, offset),
-            4 | 2 => todo!("zero extending {size} byte values"),
+            4 => mov_reg32_base32_offset32(buf, dst, X86_64GeneralReg::RBP, offset),
+            2 => movzx_reg64_base16_offset32(buf, dst, X86_64GeneralReg::RBP, offset),
             1 => movzx_reg64_base8_offset32(buf, dst, X86_64GeneralReg::RBP, offset),
             _ => internal_error!("Invalid size for zero extension: {size}"),
         }
@@ -1346,6 +1349,43 @@ impl Assembler<X86_64GeneralReg, X86_64FloatReg> for X86_64Assembler {
         neg_reg64(buf, dst);
     }
 
+    #[inline(always)]
+    fn sqrt_freg32_freg32(buf: &mut Vec<'_, u8>, dst: X86_64FloatReg, src: X86_64FloatReg) {
+        sqrtss_freg32_freg32(buf, dst, src);
+    }
+    #[inline(always)]
+    fn sqrt_freg64_freg64(buf: &mut Vec<'_, u8>, dst: X86_64FloatReg, src: X86_64FloatReg) {
+        sqrtsd_freg64_freg64(buf, dst, src);
+    }
+
+    #[inline(always)]
+    fn sub_freg32_freg32_freg32(
+        buf: &mut Vec<'_, u8>,
+        dst: X86_64FloatReg,
+        src1: X86_64FloatReg,
+        src2: X86_64FloatReg,
+    ) {
+        if dst != src1 {
+            // Subtraction does not commute, so src2 can't be overwritten first.
+            debug_assert_ne!(dst, src2);
+            movss_freg32_freg32(buf, dst, src1);
+        }
+        subss_freg32_freg32(buf, dst, src2);
+    }
+    #[inline(always)]
+    fn sub_freg64_freg64_freg64(
+        buf: &mut Vec<'_, u8>,
+        dst: X86_64FloatReg,
+        src1: X86_64FloatReg,
+        src2: X86_64FloatReg,
+    ) {
+        if dst != src1 {
+            // Subtraction does not commute, so src2 can't be overwritten first.
+            debug_assert_ne!(dst, src2);
+            movsd_freg64_freg64(buf, dst, src1);
+        }
+        subsd_freg64_freg64(buf, dst, src2);
+    }
     #[inline(always)]
     fn sub_reg64_reg64_imm32(
         buf: &mut Vec<'_, u8>,
@@ -1448,6 +1488,26 @@ impl Assembler<X86_64GeneralReg, X86_64FloatReg> for X86_64Assembler {
         cvtss2sd_freg64_freg32(buf, dst, src);
     }
 
+    #[inline(always)]
+    fn to_int_reg64_freg32(
+        _buf: &mut Vec<'_, u8>,
+        _dst: X86_64GeneralReg,
+        _src: X86_64FloatReg,
+        mode: RoundingMode,
+    ) {
+        todo!("rounding floats to integers with {:?} for X86_64", mode);
+    }
+
+    #[inline(always)]
+    fn to_int_reg64_freg64(
+        _buf: &mut Vec<'_, u8>,
+        _dst: X86_64GeneralReg,
+        _src: X86_64FloatReg,
+        mode: RoundingMode,
+    ) {
+        todo!("rounding floats to integers with {:?} for X86_64", mode);
+    }
+
     #[inline(always)]
     fn to_float_freg64_reg64(buf: &mut Vec<'_, u8>, dst: X86_64FloatReg, src: X86_64GeneralReg) {
         cvtsi2sd_freg64_reg64(buf, dst, src);
@@ -1534,6 +1594,55 @@ impl Assembler<X86_64GeneralReg, X86_64FloatReg> for X86_64Assembler {
     {
         shift_reg64_reg64_reg64(buf, storage_manager, sar_reg64_reg64, dst, src1, src2)
     }
+
+    fn rol_reg64_reg64_reg64<'a, 'r, ASM, CC>(
+        buf: &mut Vec<'a, u8>,
+        storage_manager: &mut StorageManager<'a, 'r, X86_64GeneralReg, X86_64FloatReg, ASM, CC>,
+        dst: X86_64GeneralReg,
+        src1: X86_64GeneralReg,
+        src2: X86_64GeneralReg,
+    ) where
+        ASM: Assembler<X86_64GeneralReg, X86_64FloatReg>,
+        CC: CallConv<X86_64GeneralReg, X86_64FloatReg, ASM>,
+    {
+        shift_reg64_reg64_reg64(buf, storage_manager, rol_reg64_reg64, dst, src1, src2)
+    }
+
+    fn ror_reg64_reg64_reg64<'a, 'r, ASM, CC>(
+        buf: &mut Vec<'a, u8>,
+        storage_manager: &mut StorageManager<'a, 'r, X86_64GeneralReg, X86_64FloatReg, ASM, CC>,
+        dst: X86_64GeneralReg,
+        src1: X86_64GeneralReg,
+        src2: X86_64GeneralReg,
+    ) where
+        ASM: Assembler<X86_64GeneralReg, X86_64FloatReg>,
+        CC: CallConv<X86_64GeneralReg, X86_64FloatReg, ASM>,
+    {
+        shift_reg64_reg64_reg64(buf, storage_manager, ror_reg64_reg64, dst, src1, src2)
+    }
+
+    // popcnt, lzcnt, and tzcnt need the POPCNT, LZCNT, and BMI1 extensions, which every x86-64
+    // CPU since Haswell (2013) has. On older CPUs, lzcnt and tzcnt run as bsr and bsf instead.
+    #[inline(always)]
+    fn popcnt_reg64_reg64(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg, src: X86_64GeneralReg) {
+        popcnt_reg64_reg64(buf, dst, src);
+    }
+
+    #[inline(always)]
+    fn lzcnt_reg64_reg64(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg, src: X86_64GeneralReg) {
+        lzcnt_reg64_reg64(buf, dst, src);
+    }
+
+    #[inline(always)]
+    fn tzcnt_reg64_reg64(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg, src: X86_64GeneralReg) {
+        tzcnt_reg64_reg64(buf, dst, src);
+    }
+
+    #[inline(always)]
+    fn bswap_reg64_reg64(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg, src: X86_64GeneralReg) {
+        mov_reg64_reg64(buf, dst, src);
+        bswap_reg64(buf, dst);
+    }
 }
 
 fn shift_reg64_reg64_reg64<'a, 'r, ASM, CC>(
@@ -1716,6 +1825,64 @@ fn shr_reg64_reg64(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg) {
     buf.extend([rex, 0xD3, 0xC0 | (5 << 3) | dst_mod]);
 }
 
+/// `ROL r/m64, CL` -> Rotate 64 bits r/m64 left CL times.
+#[inline(always)]
+fn rol_reg64_reg64(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg) {
+    let rex = add_rm_extension(dst, REX_W);
+
+    let dst_mod = dst as u8 % 8;
+    buf.extend([rex, 0xD3, 0xC0 | dst_mod]);
+}
+
+/// `ROR r/m64, CL` -> Rotate 64 bits r/m64 right CL times.
+#[inline(always)]
+fn ror_reg64_reg64(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg) {
+    let rex = add_rm_extension(dst, REX_W);
+
+    let dst_mod = dst as u8 % 8;
+    buf.extend([rex, 0xD3, 0xC0 | (1 << 3) | dst_mod]);
+}
+
+/// `POPCNT r64, r/m64` -> POPCNT on r/m64.
+#[inline(always)]
+fn popcnt_reg64_reg64(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg, src: X86_64GeneralReg) {
+    count_reg64_reg64(0xB8, buf, dst, src)
+}
+
+/// `LZCNT r64, r/m64` -> Count the number of leading zero bits in r/m64, return result in r64.
+#[inline(always)]
+fn lzcnt_reg64_reg64(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg, src: X86_64GeneralReg) {
+    count_reg64_reg64(0xBD, buf, dst, src)
+}
+
+/// `TZCNT r64, r/m64` -> Count the number of trailing zero bits in r/m64, return result in r64.
+#[inline(always)]
+fn tzcnt_reg64_reg64(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg, src: X86_64GeneralReg) {
+    count_reg64_reg64(0xBC, buf, dst, src)
+}
+
+#[inline(always)]
+fn count_reg64_reg64(
+    op_code: u8,
+    buf: &mut Vec<'_, u8>,
+    dst: X86_64GeneralReg,
+    src: X86_64GeneralReg,
+) {
+    let rex = add_reg_extension(dst, REX_W);
+    let rex = add_rm_extension(src, rex);
+    let dst_mod = (dst as u8 % 8) << 3;
+    let src_mod = src as u8 % 8;
+    buf.extend([0xF3, rex, 0x0F, op_code, 0xC0 | dst_mod | src_mod]);
+}
+
+/// `BSWAP r64` -> Reverses the byte order of a 64-bit register.
+#[inline(always)]
+fn bswap_reg64(buf: &mut Vec<'_, u8>, reg: X86_64GeneralReg) {
+    let rex = add_opcode_extension(reg, REX_W);
+    let reg_mod = reg as u8 % 8;
+    buf.extend([rex, 0x0F, 0xC8 | reg_mod]);
+}
+
 /// `SAR r/m64, CL` -> Signed divide r/m64 by 2, CL times.
 #[inline(always)]
 fn sar_reg64_reg64(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg) {
@@ -2070,6 +2237,17 @@ fn mov_reg64_base64_offset32(
     buf.extend(offset.to_le_bytes());
 }
 
+/// `MOV r32,r/m32` -> Move r/m32 to r32, zeroing the upper 32 bits of r64, where m32 references a base + offset.
+#[inline(always)]
+fn mov_reg32_base32_offset32(
+    buf: &mut Vec<'_, u8>,
+    dst: X86_64GeneralReg,
+    base: X86_64GeneralReg,
+    offset: i32,
+) {
+    extend_reg64_base_offset32(buf, REX, &[0x8B], dst, base, offset)
+}
+
 /// `MOVZX r64,r/m8` -> Move r/m8 with zero extention to r64, where m8 references a base + offset.
 #[inline(always)]
 fn movzx_reg64_base8_offset32(
@@ -2078,12 +2256,74 @@ fn movzx_reg64_base8_offset32(
     base: X86_64GeneralReg,
     offset: i32,
 ) {
-    let rex = add_rm_extension(base, REX_W);
+    extend_reg64_base_offset32(buf, REX_W, &[0x0F, 0xB6], dst, base, offset)
+}
+
+/// `MOVZX r64,r/m16` -> Move r/m16 with zero extention to r64, where m16 references a base + offset.
+#[inline(always)]
+fn movzx_reg64_base16_offset32(
+    buf: &mut Vec<'_, u8>,
+    dst: X86_64GeneralReg,
+    base: X86_64GeneralReg,
+    offset: i32,
+) {
+    extend_reg64_base_offset32(buf, REX_W, &[0x0F, 0xB7], dst, base, offset)
+}
+
+/// `MOVSX r64,r/m8` -> Move r/m8 with sign extention to r64, where m8 references a base + offset.
+#[inline(always)]
+fn movsx_reg64_base8_offset32(
+    buf: &mut Vec<'_, u8>,
+    dst: X86_64GeneralReg,
+    base: X86_64GeneralReg,
+    offset: i32,
+) {
+    extend_reg64_base_offset32(buf, REX_W, &[0x0F, 0xBE], dst, base, offset)
+}
+
+/// `MOVSX r64,r/m16` -> Move r/m16 with sign extention to r64, where m16 references a base + offset.
+#[inline(always)]
+fn movsx_reg64_base16_offset32(
+    buf: &mut Vec<'_, u8>,
+    dst: X86_64GeneralReg,
+    base: X86_64GeneralReg,
+    offset: i32,
+) {
+    extend_reg64_base_offset32(buf, REX_W, &[0x0F, 0xBF], dst, base, offset)
+}
+
+/// `MOVSXD r64,r/m32` -> Move r/m32 with sign extention to r64, where m32 references a base + offset.
+#[inline(always)]
+fn movsxd_reg64_base32_offset32(
+    buf: &mut Vec<'_, u8>,
+    dst: X86_64GeneralReg,
+    base: X86_64GeneralReg,
+    offset: i32,
+) {
+    extend_reg64_base_offset32(buf, REX_W, &[0x63], dst, base, offset)
+}
+
+/// Encodes a load of `[base + offset]` into dst with the given opcode.
+#[inline(always)]
+fn extend_reg64_base_offset32(
+    buf: &mut Vec<'_, u8>,
+    rex: u8,
+    opcode: &[u8],
+    dst: X86_64GeneralReg,
+    base: X86_64GeneralReg,
+    offset: i32,
+) {
+    let rex = add_rm_extension(base, rex);
     let rex = add_reg_extension(dst, rex);
     let dst_mod = (dst as u8 % 8) << 3;
     let base_mod = base as u8 % 8;
     buf.reserve(9);
-    buf.extend([rex, 0x0F, 0xB6, 0x80 | dst_mod | base_mod]);
+    // A plain REX prefix changes nothing, so it's left out.
+    if rex != REX {
+        buf.push(rex);
+    }
+    buf.extend(opcode);
+    buf.push(0x80 | dst_mod | base_mod);
     // Using RSP or R12 requires a secondary index byte.
     if base == X86_64GeneralReg::RSP || base == X86_64GeneralReg::R12 {
         buf.push(0x24);
@@ -2381,6 +2621,86 @@ fn ret(buf: &mut Vec<'_, u8>) {
     buf.push(0xC3);
 }
 
+/// `SQRTSD xmm1,xmm2/m64` -> Compute the square root of the low double-precision floating-point value in xmm2/mem and store the result in xmm1.
+#[inline(always)]
+fn sqrtsd_freg64_freg64(buf: &mut Vec<'_, u8>, dst: X86_64FloatReg, src: X86_64FloatReg) {
+    let dst_high = dst as u8 > 7;
+    let dst_mod = dst as u8 % 8;
+    let src_high = src as u8 > 7;
+    let src_mod = src as u8 % 8;
+    if dst_high || src_high {
+        buf.extend([
+            0xF2,
+            0x40 | ((dst_high as u8) << 2) | (src_high as u8),
+            0x0F,
+            0x51,
+            0xC0 | (dst_mod << 3) | (src_mod),
+        ])
+    } else {
+        buf.extend([0xF2, 0x0F, 0x51, 0xC0 | (dst_mod << 3) | (src_mod)])
+    }
+}
+
+/// `SQRTSS xmm1,xmm2/m32` -> Compute the square root of the low single-precision floating-point value in xmm2/mem and store the result in xmm1.
+#[inline(always)]
+fn sqrtss_freg32_freg32(buf: &mut Vec<'_, u8>, dst: X86_64FloatReg, src: X86_64FloatReg) {
+    let dst_high = dst as u8 > 7;
+    let dst_mod = dst as u8 % 8;
+    let src_high = src as u8 > 7;
+    let src_mod = src as u8 % 8;
+    if dst_high || src_high {
+        buf.extend([
+            0xF3,
+            0x40 | ((dst_high as u8) << 2) | (src_high as u8),
+            0x0F,
+            0x51,
+            0xC0 | (dst_mod << 3) | (src_mod),
+        ])
+    } else {
+        buf.extend([0xF3, 0x0F, 0x51, 0xC0 | (dst_mod << 3) | (src_mod)])
+    }
+}
+
+/// `SUBSD xmm1,xmm2/m64` -> Subtract the low double-precision floating-point value in xmm2/mem from xmm1 and store the result in xmm1.
+#[inline(always)]
+fn subsd_freg64_freg64(buf: &mut Vec<'_, u8>, dst: X86_64FloatReg, src: X86_64FloatReg) {
+    let dst_high = dst as u8 > 7;
+    let dst_mod = dst as u8 % 8;
+    let src_high = src as u8 > 7;
+    let src_mod = src as u8 % 8;
+    if dst_high || src_high {
+        buf.extend([
+            0xF2,
+            0x40 | ((dst_high as u8) << 2) | (src_high as u8),
+            0x0F,
+            0x5C,
+            0xC0 | (dst_mod << 3) | (src_mod),
+        ])
+    } else {
+        buf.extend([0xF2, 0x0F, 0x5C, 0xC0 | (dst_mod << 3) | (src_mod)])
+    }
+}
+
+/// `SUBSS xmm1,xmm2/m32` -> Subtract the low single-precision floating-point value in xmm2/mem from xmm1 and store the result in xmm1.
+#[inline(always)]
+fn subss_freg32_freg32(buf: &mut Vec<'_, u8>, dst: X86_64FloatReg, src: X86_64FloatReg) {
+    let dst_high = dst as u8 > 7;
+    let dst_mod = dst as u8 % 8;
+    let src_high = src as u8 > 7;
+    let src_mod = src as u8 % 8;
+    if dst_high || src_high {
+        buf.extend([
+            0xF3,
+            0x40 | ((dst_high as u8) << 2) | (src_high as u8),
+            0x0F,
+            0x5C,
+            0xC0 | (dst_mod << 3) | (src_mod),
+        ])
+    } else {
+        buf.extend([0xF3, 0x0F, 0x5C, 0xC0 | (dst_mod << 3) | (src_mod)])
+    }
+}
+
 /// `SUB r/m64, imm32` -> Subtract imm32 sign-extended to 64-bits from r/m64.
 #[inline(always)]
 fn sub_reg64_imm32(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg, imm: i32) {
@@ -2452,6 +2772,28 @@ mod tests {
                 X86_64GeneralReg::R15 => "r15b",
             }
         }
+
+        #[allow(dead_code)]
+        fn low_32bits_string(&self) -> &str {
+            match self {
+                X86_64GeneralReg::RAX => "eax",
+                X86_64GeneralReg::RBX => "ebx",
+                X86_64GeneralReg::RCX => "ecx",
+                X86_64GeneralReg::RDX => "edx",
+                X86_64GeneralReg::RBP => "ebp",
+                X86_64GeneralReg::RSP => "esp",
+                X86_64GeneralReg::RDI => "edi",
+                X86_64GeneralReg::RSI => "esi",
+                X86_64GeneralReg::R8 => "r8d",
+                X86_64GeneralReg::R9 => "r9d",
+                X86_64GeneralReg::R10 => "r10d",
+                X86_64GeneralReg::R11 => "r11d",
+                X86_64GeneralReg::R12 => "r12d",
+                X86_64GeneralReg::R13 => "r13d",
+                X86_64GeneralReg::R14 => "r14d",
+                X86_64GeneralReg::R15 => "r15d",
+            }
+        }
     }
     const TEST_I32: i32 = 0x12345678;
     const TEST_I64: i64 = 0x1234_5678_9ABC_DEF0;
@@ -2527,6 +2869,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_sqrtsd_freg64_freg64() {
+        disassembler_test!(
+            sqrtsd_freg64_freg64,
+            |reg1, reg2| format!("sqrtsd {}, {}", reg1, reg2),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_sqrtss_freg32_freg32() {
+        disassembler_test!(
+            sqrtss_freg32_freg32,
+            |reg1, reg2| format!("sqrtss {}, {}", reg1, reg2),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_subsd_freg64_freg64() {
+        disassembler_test!(
+            subsd_freg64_freg64,
+            |reg1, reg2| format!("subsd {}, {}", reg1, reg2),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_subss_freg32_freg32() {
+        disassembler_test!(
+            subss_freg32_freg32,
+            |reg1, reg2| format!("subss {}, {}", reg1, reg2),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
     #[test]
     fn test_sub_reg64_reg64() {
         disassembler_test!(
@@ -2624,6 +3006,59 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_rol_reg64_reg64() {
+        disassembler_test!(
+            rol_reg64_reg64,
+            |reg| format!("rol {reg}, cl"),
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_ror_reg64_reg64() {
+        disassembler_test!(
+            ror_reg64_reg64,
+            |reg| format!("ror {reg}, cl"),
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_popcnt_reg64_reg64() {
+        disassembler_test!(
+            popcnt_reg64_reg64,
+            |reg1, reg2| format!("popcnt {reg1}, {reg2}"),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_lzcnt_reg64_reg64() {
+        disassembler_test!(
+            lzcnt_reg64_reg64,
+            |reg1, reg2| format!("lzcnt {reg1}, {reg2}"),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_tzcnt_reg64_reg64() {
+        disassembler_test!(
+            tzcnt_reg64_reg64,
+            |reg1, reg2| format!("tzcnt {reg1}, {reg2}"),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_bswap_reg64() {
+        disassembler_test!(bswap_reg64, |reg| format!("bswap {reg}"), ALL_GENERAL_REGS);
+    }
+
     #[test]
     fn test_cmovl_reg64_reg64() {
         disassembler_test!(
@@ -2832,6 +3267,66 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_movzx_reg64_base16_offset32() {
+        disassembler_test!(
+            movzx_reg64_base16_offset32,
+            |reg1, reg2, imm| format!("movzx {}, word ptr [{} + 0x{:x}]", reg1, reg2, imm),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            [TEST_I32]
+        );
+    }
+
+    #[test]
+    fn test_mov_reg32_base32_offset32() {
+        disassembler_test!(
+            mov_reg32_base32_offset32,
+            |reg1: X86_64GeneralReg, reg2, imm| format!(
+                "mov {}, dword ptr [{} + 0x{:x}]",
+                reg1.low_32bits_string(),
+                reg2,
+                imm
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            [TEST_I32]
+        );
+    }
+
+    #[test]
+    fn test_movsx_reg64_base8_offset32() {
+        disassembler_test!(
+            movsx_reg64_base8_offset32,
+            |reg1, reg2, imm| format!("movsx {}, byte ptr [{} + 0x{:x}]", reg1, reg2, imm),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            [TEST_I32]
+        );
+    }
+
+    #[test]
+    fn test_movsx_reg64_base16_offset32() {
+        disassembler_test!(
+            movsx_reg64_base16_offset32,
+            |reg1, reg2, imm| format!("movsx {}, word ptr [{} + 0x{:x}]", reg1, reg2, imm),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            [TEST_I32]
+        );
+    }
+
+    #[test]
+    fn test_movsxd_reg64_base32_offset32() {
+        disassembler_test!(
+            movsxd_reg64_base32_offset32,
+            |reg1, reg2, imm| format!("movsxd {}, dword ptr [{} + 0x{:x}]", reg1, reg2, imm),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            [TEST_I32]
+        );
+    }
+
     #[test]
     fn test_movsd_freg64_freg64() {
         disassembler_test!(
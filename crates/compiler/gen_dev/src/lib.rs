@@ -14,8 +14,8 @@ use roc_module::low_level::{LowLevel, LowLevelWrapperType};
 use roc_module::symbol::{Interns, ModuleId, Symbol};
 use roc_mono::code_gen_help::CodeGenHelp;
 use roc_mono::ir::{
-    BranchInfo, CallType, Expr, JoinPointId, ListLiteralElement, Literal, Param, Proc, ProcLayout,
-    SelfRecursive, Stmt,
+    BranchInfo, CallType, CrashRegion, CrashTag, Expr, JoinPointId, ListLiteralElement, Literal,
+    Param, Proc, ProcLayout, SelfRecursive, Stmt,
 };
 use roc_mono::layout::{
     Builtin, InLayout, Layout, LayoutId, LayoutIds, LayoutInterner, STLayoutInterner, TagIdIntType,
@@ -23,8 +23,10 @@ use roc_mono::layout::{
 };
 use roc_mono::list_element_layout;
 
+mod debug_info;
 mod generic64;
 mod object_builder;
+pub use debug_info::DefLocations;
 pub use object_builder::build_module;
 mod run_roc;
 
@@ -34,6 +36,10 @@ pub struct Env<'a> {
     pub exposed_to_host: MutSet<Symbol>,
     pub lazy_literals: bool,
     pub generate_allocators: bool,
+    /// Allocator functions the platform asked Roc to define, as `(extern_name, builtin_name)`
+    pub provided_allocator_fns: &'a [(&'a str, &'a str)],
+    /// Where each def is in the source, for debug info. Without this, no debug info is emitted.
+    pub def_locations: Option<DefLocations>,
 }
 
 // These relocations likely will need a length.
@@ -227,6 +233,11 @@ trait Backend<'a> {
                 self.build_jump(id, args, arg_layouts.into_bump_slice(), ret_layout);
                 self.free_symbols(stmt);
             }
+            Stmt::Crash(msg, tag, region) => {
+                self.load_literal_symbols(&[*msg]);
+                self.build_crash(msg, *tag, *region);
+                self.free_symbols(stmt);
+            }
             x => todo!("the statement, {:?}", x),
         }
     }
@@ -444,9 +455,46 @@ trait Backend<'a> {
                 );
                 self.build_num_add(sym, &args[0], &args[1], ret_layout)
             }
+            LowLevel::NumAddWrap => {
+                debug_assert_eq!(
+                    2,
+                    args.len(),
+                    "NumAddWrap: expected to have exactly two argument"
+                );
+                debug_assert_eq!(
+                    arg_layouts[0], arg_layouts[1],
+                    "NumAddWrap: expected all arguments of to have the same layout"
+                );
+                debug_assert_eq!(
+                    arg_layouts[0], *ret_layout,
+                    "NumAddWrap: expected to have the same argument and return layout"
+                );
+                // integer `num_add` wraps on overflow in the dev backend
+                self.build_num_add(sym, &args[0], &args[1], ret_layout)
+            }
             LowLevel::NumAddChecked => {
                 self.build_num_add_checked(sym, &args[0], &args[1], &arg_layouts[0], ret_layout)
             }
+            LowLevel::NumAddSaturated => match self.interner().get(*ret_layout) {
+                Layout::Builtin(Builtin::Int(int_width)) => self.build_fn_call(
+                    sym,
+                    bitcode::NUM_ADD_SATURATED_INT[int_width].to_string(),
+                    args,
+                    arg_layouts,
+                    ret_layout,
+                ),
+                Layout::Builtin(Builtin::Float(_)) => {
+                    self.build_num_add(sym, &args[0], &args[1], ret_layout)
+                }
+                Layout::Builtin(Builtin::Decimal) => self.build_fn_call(
+                    sym,
+                    bitcode::DEC_ADD_SATURATED.to_string(),
+                    args,
+                    arg_layouts,
+                    ret_layout,
+                ),
+                x => internal_error!("NumAddSaturated is not defined for {:?}", x),
+            },
             LowLevel::NumAcos => self.build_fn_call(
                 sym,
                 bitcode::NUM_ACOS[FloatWidth::F64].to_string(),
@@ -468,6 +516,168 @@ trait Backend<'a> {
                 arg_layouts,
                 ret_layout,
             ),
+            LowLevel::NumIsNan | LowLevel::NumIsInfinite | LowLevel::NumIsFinite => {
+                match self.interner().get(arg_layouts[0]) {
+                    Layout::Builtin(Builtin::Float(float_width)) => {
+                        let intrinsic = match lowlevel {
+                            LowLevel::NumIsNan => &bitcode::NUM_IS_NAN,
+                            LowLevel::NumIsInfinite => &bitcode::NUM_IS_INFINITE,
+                            _ => &bitcode::NUM_IS_FINITE,
+                        };
+
+                        self.build_fn_call(
+                            sym,
+                            intrinsic[float_width].to_string(),
+                            args,
+                            arg_layouts,
+                            ret_layout,
+                        )
+                    }
+                    // a Dec is always finite
+                    Layout::Builtin(Builtin::Decimal) => self.load_literal(
+                        sym,
+                        ret_layout,
+                        &Literal::Bool(*lowlevel == LowLevel::NumIsFinite),
+                    ),
+                    x => internal_error!("{:?} is not defined for {:?}", lowlevel, x),
+                }
+            }
+            LowLevel::RandomMix64 => self.build_fn_call(
+                sym,
+                bitcode::RANDOM_MIX64.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
+            LowLevel::RandomMixGamma => self.build_fn_call(
+                sym,
+                bitcode::RANDOM_MIX_GAMMA.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
+            LowLevel::RandomTestSeed => {
+                // the argument is `{}`, so there's nothing to pass
+                self.build_fn_call(
+                    sym,
+                    bitcode::RANDOM_TEST_SEED.to_string(),
+                    &[],
+                    &[],
+                    ret_layout,
+                )
+            }
+            LowLevel::BytesReadU16 => self.build_fn_call(
+                sym,
+                bitcode::BYTES_READ_U16.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
+            LowLevel::BytesReadU32 => self.build_fn_call(
+                sym,
+                bitcode::BYTES_READ_U32.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
+            LowLevel::BytesReadU64 => self.build_fn_call(
+                sym,
+                bitcode::BYTES_READ_U64.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
+            LowLevel::EncodingEncode => self.build_fn_call(
+                sym,
+                bitcode::ENCODING_ENCODE.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
+            LowLevel::EncodingDecode => self.build_fn_call(
+                sym,
+                bitcode::ENCODING_DECODE.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
+            LowLevel::EncodingCheck => self.build_fn_call(
+                sym,
+                bitcode::ENCODING_CHECK.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
+            LowLevel::JsonSkipWhitespace => self.build_fn_call(
+                sym,
+                bitcode::JSON_SKIP_WHITESPACE.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
+            LowLevel::JsonScanString => self.build_fn_call(
+                sym,
+                bitcode::JSON_SCAN_STRING.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
+            LowLevel::JsonScanValue => self.build_fn_call(
+                sym,
+                bitcode::JSON_SCAN_VALUE.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
+            LowLevel::JsonUnescape => self.build_fn_call(
+                sym,
+                bitcode::JSON_UNESCAPE.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
+            LowLevel::JsonEscape => self.build_fn_call(
+                sym,
+                bitcode::JSON_ESCAPE.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
+            LowLevel::TimeFormat => self.build_fn_call(
+                sym,
+                bitcode::TIME_FORMAT.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
+            LowLevel::TimeCheck => self.build_fn_call(
+                sym,
+                bitcode::TIME_CHECK.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
+            LowLevel::TimeParseSeconds => self.build_fn_call(
+                sym,
+                bitcode::TIME_PARSE_SECONDS.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
+            LowLevel::TimeParseNanos => self.build_fn_call(
+                sym,
+                bitcode::TIME_PARSE_NANOS.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
+            LowLevel::NumToStrWith => self.build_fn_call(
+                sym,
+                bitcode::STR_FORMAT_NUMBER.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
             LowLevel::NumMul => {
                 debug_assert_eq!(
                     2,
@@ -484,6 +694,46 @@ trait Backend<'a> {
                 );
                 self.build_num_mul(sym, &args[0], &args[1], ret_layout)
             }
+            LowLevel::NumMulWrap => {
+                debug_assert_eq!(
+                    2,
+                    args.len(),
+                    "NumMulWrap: expected to have exactly two argument"
+                );
+                debug_assert_eq!(
+                    arg_layouts[0], arg_layouts[1],
+                    "NumMulWrap: expected all arguments of to have the same layout"
+                );
+                debug_assert_eq!(
+                    arg_layouts[0], *ret_layout,
+                    "NumMulWrap: expected to have the same argument and return layout"
+                );
+                // integer `num_mul` wraps on overflow in the dev backend
+                self.build_num_mul(sym, &args[0], &args[1], ret_layout)
+            }
+            LowLevel::NumMulChecked => {
+                self.build_num_mul_checked(sym, &args[0], &args[1], &arg_layouts[0], ret_layout)
+            }
+            LowLevel::NumMulSaturated => match self.interner().get(*ret_layout) {
+                Layout::Builtin(Builtin::Int(int_width)) => self.build_fn_call(
+                    sym,
+                    bitcode::NUM_MUL_SATURATED_INT[int_width].to_string(),
+                    args,
+                    arg_layouts,
+                    ret_layout,
+                ),
+                Layout::Builtin(Builtin::Float(_)) => {
+                    self.build_num_mul(sym, &args[0], &args[1], ret_layout)
+                }
+                Layout::Builtin(Builtin::Decimal) => self.build_fn_call(
+                    sym,
+                    bitcode::DEC_MUL_SATURATED.to_string(),
+                    args,
+                    arg_layouts,
+                    ret_layout,
+                ),
+                x => internal_error!("NumMulSaturated is not defined for {:?}", x),
+            },
             LowLevel::NumDivTruncUnchecked | LowLevel::NumDivFrac => {
                 debug_assert_eq!(
                     2,
@@ -551,6 +801,29 @@ trait Backend<'a> {
                 );
                 self.build_num_sub_wrap(sym, &args[0], &args[1], ret_layout)
             }
+            LowLevel::NumSubChecked => {
+                self.build_num_sub_checked(sym, &args[0], &args[1], &arg_layouts[0], ret_layout)
+            }
+            LowLevel::NumSubSaturated => match self.interner().get(*ret_layout) {
+                Layout::Builtin(Builtin::Int(int_width)) => self.build_fn_call(
+                    sym,
+                    bitcode::NUM_SUB_SATURATED_INT[int_width].to_string(),
+                    args,
+                    arg_layouts,
+                    ret_layout,
+                ),
+                Layout::Builtin(Builtin::Float(_)) => {
+                    self.build_num_sub(sym, &args[0], &args[1], ret_layout)
+                }
+                Layout::Builtin(Builtin::Decimal) => self.build_fn_call(
+                    sym,
+                    bitcode::DEC_SUB_SATURATED.to_string(),
+                    args,
+                    arg_layouts,
+                    ret_layout,
+                ),
+                x => internal_error!("NumSubSaturated is not defined for {:?}", x),
+            },
             LowLevel::NumBitwiseAnd => {
                 if let Layout::Builtin(Builtin::Int(int_width)) = self.interner().get(*ret_layout) {
                     self.build_int_bitwise_and(sym, &args[0], &args[1], int_width)
@@ -593,6 +866,54 @@ trait Backend<'a> {
                     internal_error!("shift right zero-fill on a non-integer")
                 }
             }
+            LowLevel::NumPopCount => {
+                if let Layout::Builtin(Builtin::Int(int_width)) =
+                    self.interner().get(arg_layouts[0])
+                {
+                    self.build_int_pop_count(sym, &args[0], int_width)
+                } else {
+                    internal_error!("pop count on a non-integer")
+                }
+            }
+            LowLevel::NumLeadingZeros => {
+                if let Layout::Builtin(Builtin::Int(int_width)) =
+                    self.interner().get(arg_layouts[0])
+                {
+                    self.build_int_leading_zeros(sym, &args[0], int_width)
+                } else {
+                    internal_error!("leading zeros on a non-integer")
+                }
+            }
+            LowLevel::NumTrailingZeros => {
+                if let Layout::Builtin(Builtin::Int(int_width)) =
+                    self.interner().get(arg_layouts[0])
+                {
+                    self.build_int_trailing_zeros(sym, &args[0], int_width)
+                } else {
+                    internal_error!("trailing zeros on a non-integer")
+                }
+            }
+            LowLevel::NumRotateLeftBy => {
+                if let Layout::Builtin(Builtin::Int(int_width)) = self.interner().get(*ret_layout) {
+                    self.build_int_rotate_left(sym, &args[0], &args[1], int_width)
+                } else {
+                    internal_error!("rotate left on a non-integer")
+                }
+            }
+            LowLevel::NumRotateRightBy => {
+                if let Layout::Builtin(Builtin::Int(int_width)) = self.interner().get(*ret_layout) {
+                    self.build_int_rotate_right(sym, &args[0], &args[1], int_width)
+                } else {
+                    internal_error!("rotate right on a non-integer")
+                }
+            }
+            LowLevel::NumSwapBytes => {
+                if let Layout::Builtin(Builtin::Int(int_width)) = self.interner().get(*ret_layout) {
+                    self.build_int_swap_bytes(sym, &args[0], int_width)
+                } else {
+                    internal_error!("swap bytes on a non-integer")
+                }
+            }
             LowLevel::Eq => {
                 debug_assert_eq!(2, args.len(), "Eq: expected to have exactly two argument");
                 debug_assert_eq!(
@@ -711,6 +1032,34 @@ trait Backend<'a> {
                 arg_layouts,
                 ret_layout,
             ),
+            LowLevel::NumFloor => {
+                debug_assert_eq!(
+                    1,
+                    args.len(),
+                    "NumFloor: expected to have exactly one argument"
+                );
+                self.build_num_floor(sym, &args[0], &arg_layouts[0], ret_layout)
+            }
+            LowLevel::NumCeiling => {
+                debug_assert_eq!(
+                    1,
+                    args.len(),
+                    "NumCeiling: expected to have exactly one argument"
+                );
+                self.build_num_ceiling(sym, &args[0], &arg_layouts[0], ret_layout)
+            }
+            LowLevel::NumSqrtUnchecked => {
+                debug_assert_eq!(
+                    1,
+                    args.len(),
+                    "NumSqrtUnchecked: expected to have exactly one argument"
+                );
+                debug_assert_eq!(
+                    arg_layouts[0], *ret_layout,
+                    "NumSqrtUnchecked: expected to have the same argument and return layout"
+                );
+                self.build_num_sqrt(sym, &args[0], ret_layout)
+            }
             LowLevel::ListLen => {
                 debug_assert_eq!(
                     1,
@@ -798,6 +1147,20 @@ trait Backend<'a> {
                 arg_layouts,
                 ret_layout,
             ),
+            LowLevel::StrMatches => self.build_fn_call(
+                sym,
+                bitcode::STR_REGEX_MATCHES.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
+            LowLevel::StrCapture => self.build_fn_call(
+                sym,
+                bitcode::STR_REGEX_CAPTURE.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
             LowLevel::StrStartsWithScalar => self.build_fn_call(
                 sym,
                 bitcode::STR_STARTS_WITH_SCALAR.to_string(),
@@ -925,7 +1288,7 @@ trait Backend<'a> {
     /// build_num_add stores the sum of src1 and src2 into dst.
     fn build_num_add(&mut self, dst: &Symbol, src1: &Symbol, src2: &Symbol, layout: &InLayout<'a>);
 
-    /// build_num_add_checked stores the sum of src1 and src2 into dst.
+    /// build_num_add_checked stores the sum of src1 and src2 into dst, along with whether it overflowed.
     fn build_num_add_checked(
         &mut self,
         dst: &Symbol,
@@ -935,6 +1298,26 @@ trait Backend<'a> {
         return_layout: &InLayout<'a>,
     );
 
+    /// build_num_sub_checked stores the `src1 - src2` difference into dst, along with whether it overflowed.
+    fn build_num_sub_checked(
+        &mut self,
+        dst: &Symbol,
+        src1: &Symbol,
+        src2: &Symbol,
+        num_layout: &InLayout<'a>,
+        return_layout: &InLayout<'a>,
+    );
+
+    /// build_num_mul_checked stores `src1 * src2` into dst, along with whether it overflowed.
+    fn build_num_mul_checked(
+        &mut self,
+        dst: &Symbol,
+        src1: &Symbol,
+        src2: &Symbol,
+        num_layout: &InLayout<'a>,
+        return_layout: &InLayout<'a>,
+    );
+
     /// build_num_mul stores `src1 * src2` into dst.
     fn build_num_mul(&mut self, dst: &Symbol, src1: &Symbol, src2: &Symbol, layout: &InLayout<'a>);
 
@@ -1010,6 +1393,36 @@ trait Backend<'a> {
         int_width: IntWidth,
     );
 
+    /// stores the `Num.popCount src` into dst.
+    fn build_int_pop_count(&mut self, dst: &Symbol, src: &Symbol, int_width: IntWidth);
+
+    /// stores the `Num.leadingZeros src` into dst.
+    fn build_int_leading_zeros(&mut self, dst: &Symbol, src: &Symbol, int_width: IntWidth);
+
+    /// stores the `Num.trailingZeros src` into dst.
+    fn build_int_trailing_zeros(&mut self, dst: &Symbol, src: &Symbol, int_width: IntWidth);
+
+    /// stores the `Num.rotateLeftBy src1 src2` into dst.
+    fn build_int_rotate_left(
+        &mut self,
+        dst: &Symbol,
+        src1: &Symbol,
+        src2: &Symbol,
+        int_width: IntWidth,
+    );
+
+    /// stores the `Num.rotateRightBy src1 src2` into dst.
+    fn build_int_rotate_right(
+        &mut self,
+        dst: &Symbol,
+        src1: &Symbol,
+        src2: &Symbol,
+        int_width: IntWidth,
+    );
+
+    /// stores the `Num.swapBytes src` into dst.
+    fn build_int_swap_bytes(&mut self, dst: &Symbol, src: &Symbol, int_width: IntWidth);
+
     /// build_eq stores the result of `src1 == src2` into dst.
     fn build_eq(&mut self, dst: &Symbol, src1: &Symbol, src2: &Symbol, arg_layout: &InLayout<'a>);
 
@@ -1043,6 +1456,27 @@ trait Backend<'a> {
         ret_layout: &InLayout<'a>,
     );
 
+    /// build_num_sqrt stores the square root of src into dst.
+    fn build_num_sqrt(&mut self, dst: &Symbol, src: &Symbol, layout: &InLayout<'a>);
+
+    /// build_num_floor stores src, rounded towards negative infinity, into dst.
+    fn build_num_floor(
+        &mut self,
+        dst: &Symbol,
+        src: &Symbol,
+        arg_layout: &InLayout<'a>,
+        ret_layout: &InLayout<'a>,
+    );
+
+    /// build_num_ceiling stores src, rounded towards positive infinity, into dst.
+    fn build_num_ceiling(
+        &mut self,
+        dst: &Symbol,
+        src: &Symbol,
+        arg_layout: &InLayout<'a>,
+        ret_layout: &InLayout<'a>,
+    );
+
     /// build_num_lte stores the result of `src1 <= src2` into dst.
     fn build_num_lte(
         &mut self,
@@ -1132,6 +1566,9 @@ trait Backend<'a> {
     /// build_refcount_getptr loads the pointer to the reference count of src into dst.
     fn build_ptr_cast(&mut self, dst: &Symbol, src: &Symbol);
 
+    /// build_crash calls roc_panic with a pointer to the message and source region, and the tag.
+    fn build_crash(&mut self, msg: &Symbol, tag: CrashTag, region: CrashRegion);
+
     /// literal_map gets the map from symbol to literal and layout, used for lazy loading and literal folding.
     fn literal_map(&mut self) -> &mut MutMap<Symbol, (*const Literal<'a>, *const InLayout<'a>)>;
 
@@ -1378,7 +1815,9 @@ trait Backend<'a> {
             Stmt::Expect { .. } => todo!("expect is not implemented in the dev backend"),
             Stmt::ExpectFx { .. } => todo!("expect-fx is not implemented in the dev backend"),
 
-            Stmt::Crash(..) => todo!("crash is not implemented in the dev backend"),
+            Stmt::Crash(msg, ..) => {
+                self.set_last_seen(*msg, stmt);
+            }
         }
     }
 
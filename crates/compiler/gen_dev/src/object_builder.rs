@@ -1,3 +1,4 @@
+use crate::debug_info::{add_debug_info, DebugProc};
 use crate::generic64::{aarch64, new_backend_64bit, x86_64};
 use crate::{Backend, Env, Relocation};
 use bumpalo::collections::Vec;
@@ -209,6 +210,21 @@ fn build_object<'a, B: Backend<'a>>(
             "roc_panic".into(),
             "roc_builtins.utils.test_panic".into(),
         );
+        generate_wrapper(
+            &mut backend,
+            &mut output,
+            "roc_oom".into(),
+            "roc_builtins.utils.test_panic".into(),
+        );
+    }
+
+    for (name, builtin_name) in backend.env().provided_allocator_fns {
+        generate_wrapper(
+            &mut backend,
+            &mut output,
+            name.to_string(),
+            builtin_name.to_string(),
+        );
     }
 
     // Setup layout_ids for procedure calls.
@@ -230,7 +246,13 @@ fn build_object<'a, B: Backend<'a>>(
 
     // Build procedures from user code
     let mut relocations = bumpalo::vec![in arena];
+    let mut debug_procs = std::vec::Vec::with_capacity(procs.len());
     for (fn_name, section_id, proc_id, proc) in procs {
+        debug_procs.push(DebugProc {
+            name: fn_name.clone(),
+            symbol: proc.name.name(),
+            proc_id,
+        });
         build_proc(
             &mut output,
             &mut backend,
@@ -315,6 +337,18 @@ fn build_object<'a, B: Backend<'a>>(
             Err(e) => internal_error!("{:?}", e),
         }
     }
+
+    if let Some(def_locations) = &backend.env().def_locations {
+        if output.format() == BinaryFormat::Elf {
+            add_debug_info(
+                &mut output,
+                def_locations,
+                backend.env().module_id,
+                &debug_procs,
+            );
+        }
+    }
+
     output
 }
 
@@ -460,13 +494,24 @@ fn build_proc<'a, B: Backend<'a>>(
                     }
                 }
                 if let Some(sym_id) = output.symbol_id(name.as_bytes()) {
-                    write::Relocation {
-                        offset: offset + proc_offset,
-                        size: 32,
-                        kind: RelocationKind::PltRelative,
-                        encoding: RelocationEncoding::X86Branch,
-                        symbol: sym_id,
-                        addend: -4,
+                    match output.architecture() {
+                        // `BL` takes a 26 bit word offset from the call instruction itself.
+                        Architecture::Aarch64 => write::Relocation {
+                            offset: offset + proc_offset,
+                            size: 26,
+                            kind: RelocationKind::PltRelative,
+                            encoding: RelocationEncoding::AArch64Call,
+                            symbol: sym_id,
+                            addend: 0,
+                        },
+                        _ => write::Relocation {
+                            offset: offset + proc_offset,
+                            size: 32,
+                            kind: RelocationKind::PltRelative,
+                            encoding: RelocationEncoding::X86Branch,
+                            symbol: sym_id,
+                            addend: -4,
+                        },
                     }
                 } else {
                     internal_error!("failed to find fn symbol for {:?}", name);
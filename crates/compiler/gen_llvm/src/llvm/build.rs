@@ -1,7 +1,8 @@
-use crate::llvm::bitcode::call_bitcode_fn;
+use crate::llvm::bitcode::{call_bitcode_fn, call_void_bitcode_fn};
 use crate::llvm::build_list::{self, allocate_list, empty_polymorphic_list};
 use crate::llvm::convert::{
-    argument_type_from_layout, basic_type_from_builtin, basic_type_from_layout, zig_str_type,
+    argument_type_from_layout, basic_type_from_builtin, basic_type_from_layout, zig_panic_type,
+    zig_str_type,
 };
 use crate::llvm::expect::{clone_to_shared_memory, SharedMemoryPointer};
 use crate::llvm::refcounting::{
@@ -36,16 +37,20 @@ use roc_builtins::bitcode::{self, FloatWidth, IntWidth};
 use roc_collections::all::{ImMap, MutMap, MutSet};
 use roc_debug_flags::dbg_do;
 #[cfg(debug_assertions)]
-use roc_debug_flags::ROC_PRINT_LLVM_FN_VERIFICATION;
+use roc_debug_flags::{ROC_PRINT_LLVM_FN_VERIFICATION, ROC_PRINT_UNUSED_BITCODE};
+use roc_module::mangle::{self, HostClosureFn};
 use roc_module::symbol::{Interns, ModuleId, Symbol};
+use roc_mono::effect_batch::{self, EffectBatches};
 use roc_mono::ir::{
-    BranchInfo, CallType, CrashTag, EntryPoint, JoinPointId, ListLiteralElement, ModifyRc,
-    OptLevel, ProcLayout, SingleEntryPoint,
+    BranchInfo, Call, CallType, CrashRegion, CrashTag, EntryPoint, Expr, JoinPointId,
+    ListLiteralElement, ModifyRc, OptLevel, ProcLayout, SingleEntryPoint,
 };
 use roc_mono::layout::{
     Builtin, InLayout, LambdaName, LambdaSet, Layout, LayoutIds, LayoutInterner, Niche,
     RawFunctionLayout, STLayoutInterner, TagIdIntType, UnionLayout,
 };
+use roc_mono::scratch_region::ScratchRegion;
+use roc_mono::thread_escape::ThreadSharedLayouts;
 use roc_std::RocDec;
 use roc_target::{PtrWidth, TargetInfo};
 use std::convert::TryInto;
@@ -165,6 +170,11 @@ pub struct Scope<'a, 'ctx> {
     symbols: ImMap<Symbol, (InLayout<'a>, BasicValueEnum<'ctx>)>,
     pub top_level_thunks: ImMap<Symbol, (ProcLayout<'a>, FunctionValue<'ctx>)>,
     join_points: ImMap<JoinPointId, (BasicBlock<'ctx>, std::vec::Vec<PhiValue<'ctx>>)>,
+    /// Where the list literals which go in this call's scratch region keep their elements
+    scratch_lists: ImMap<Symbol, PointerValue<'ctx>>,
+    /// How many effects are in the batch starting with the `let` of each symbol, when the host
+    /// runs effects in batches
+    effect_batches: ImMap<Symbol, usize>,
 }
 
 impl<'a, 'ctx> Scope<'a, 'ctx> {
@@ -249,6 +259,17 @@ pub struct Env<'a, 'ctx, 'env> {
     pub target_info: TargetInfo,
     pub mode: LlvmBackendMode,
     pub exposed_to_host: MutSet<Symbol>,
+    /// Values with these layouts may be shared between threads, so their refcounts are updated
+    /// atomically
+    pub thread_shared_layouts: ThreadSharedLayouts<'a>,
+    /// Top-level values that are only evaluated once, the first time they're used, like the
+    /// fixtures of a module's `expect`s when running `roc test`
+    pub fixtures: MutSet<Symbol>,
+    /// Whether the host runs effects in batches, through `roc_fx_batch`, rather than one call each
+    pub batch_effects: bool,
+    /// Let LLVM assume floats are never NaN or infinite, and reorder float arithmetic, in Roc
+    /// functions. Otherwise it follows IEEE-754 exactly.
+    pub fast_math: bool,
 }
 
 impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
@@ -357,11 +378,39 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
 
         call.set_call_convention(C_CALL_CONV);
 
-        call.try_as_basic_value()
+        let ptr = call
+            .try_as_basic_value()
             .left()
             .unwrap()
-            .into_pointer_value()
-        // TODO check if alloc returned null; if so, runtime error for OOM!
+            .into_pointer_value();
+
+        // roc_alloc returns null when it runs out of memory. Let the host know; this doesn't return.
+        let parent = self
+            .builder
+            .get_insert_block()
+            .and_then(|block| block.get_parent())
+            .unwrap();
+        let out_of_memory_block = self.context.append_basic_block(parent, "out_of_memory");
+        let allocated_block = self.context.append_basic_block(parent, "allocated");
+
+        let is_null = self.builder.build_is_null(ptr, "alloc_is_null");
+        self.builder
+            .build_conditional_branch(is_null, out_of_memory_block, allocated_block);
+
+        self.builder.position_at_end(out_of_memory_block);
+        {
+            call_void_bitcode_fn(
+                self,
+                &[number_of_bytes.into(), alignment.into()],
+                bitcode::UTILS_OUT_OF_MEMORY,
+            );
+
+            self.builder.build_unreachable();
+        }
+
+        self.builder.position_at_end(allocated_block);
+
+        ptr
     }
 
     pub fn call_dealloc(&self, ptr: PointerValue<'ctx>, alignment: u32) -> InstructionValue<'ctx> {
@@ -405,28 +454,58 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
         env: &Env<'a, 'ctx, 'env>,
         message: BasicValueEnum<'ctx>,
         tag: CrashTag,
+        region: CrashRegion,
     ) {
         let function = self.module.get_function("roc_panic").unwrap();
         let tag_id = self.context.i32_type().const_int(tag as u32 as u64, false);
 
-        let msg = match env.target_info.ptr_width() {
+        let msg_ptr = match env.target_info.ptr_width() {
             PtrWidth::Bytes4 => {
-                // we need to pass the message by reference, but we currently hold the value.
+                // we need the message by reference, but we currently hold the value.
                 let alloca = env
                     .builder
                     .build_alloca(message.get_type(), "alloca_panic_msg");
                 env.builder.build_store(alloca, message);
-                alloca.into()
+                alloca
             }
             PtrWidth::Bytes8 => {
                 // string is already held by reference
-                message
+                message.into_pointer_value()
             }
         };
 
+        // roc_panic gets a pointer to the message, followed by the source region
+        let str_type = zig_str_type(env);
+        let payload_type = zig_panic_type(env);
+        let payload = env.builder.build_alloca(payload_type, "panic_payload");
+
+        let msg_ptr = env.builder.build_pointer_cast(
+            msg_ptr,
+            str_type.ptr_type(AddressSpace::default()),
+            "cast_panic_msg",
+        );
+        let msg = env
+            .builder
+            .new_build_load(str_type, msg_ptr, "load_panic_msg");
+        let payload_msg = env
+            .builder
+            .new_build_struct_gep(payload_type, payload, 0, "panic_payload_msg")
+            .unwrap();
+        env.builder.build_store(payload_msg, msg);
+
+        for (index, value) in region.to_array().into_iter().enumerate() {
+            let field = env
+                .builder
+                .new_build_struct_gep(payload_type, payload, 1 + index as u32, "panic_region")
+                .unwrap();
+            let value = self.context.i32_type().const_int(value as u64, false);
+
+            env.builder.build_store(field, value);
+        }
+
         let call = self
             .builder
-            .build_call(function, &[msg.into(), tag_id.into()], "roc_panic");
+            .build_call(function, &[payload.into(), tag_id.into()], "roc_panic");
 
         call.set_call_convention(C_CALL_CONV);
     }
@@ -543,6 +622,150 @@ pub fn module_from_builtins<'ctx>(
     module
 }
 
+/// The functions the builtins bitcode defines. Call this before adding any Roc code to the module.
+pub fn bitcode_function_names(module: &Module) -> MutSet<String> {
+    module
+        .get_functions()
+        .filter(|function| function.count_basic_blocks() > 0)
+        .map(|function| function.get_name().to_str().unwrap().to_string())
+        .collect()
+}
+
+/// LLVM can lower instructions to calls to compiler-rt and libc functions after we're done with
+/// the IR, so the builtins that provide those must stay visible even if nothing calls them yet.
+fn must_stay_external(name: &str) -> bool {
+    name.starts_with("__") || matches!(name, "memcpy" | "memmove" | "memset" | "memcmp" | "bcmp")
+}
+
+/// Give internal linkage to every builtin that Roc code never references, so that global DCE
+/// removes it, like `--gc-sections` does for object files.
+/// `bitcode_fns` must come from `bitcode_function_names`.
+pub fn internalize_unused_bitcode(module: &Module, bitcode_fns: &MutSet<String>) {
+    let mut reachable = MutSet::default();
+    let mut stack = Vec::new();
+
+    // Everything Roc defined is used, since we only generate the procs we need
+    for function in module.get_functions() {
+        let name = function.get_name().to_str().unwrap();
+        let is_root = if bitcode_fns.contains(name) {
+            must_stay_external(name)
+        } else {
+            function.count_basic_blocks() > 0
+        };
+
+        if is_root {
+            reachable.insert(name.to_string());
+            stack.push(function);
+        }
+    }
+
+    while let Some(function) = stack.pop() {
+        for block in function.get_basic_blocks() {
+            let mut next = block.get_first_instruction();
+
+            while let Some(instruction) = next {
+                for index in 0..instruction.get_num_operands() {
+                    let operand = instruction.get_operand(index).and_then(|op| op.left());
+
+                    if let Some(BasicValueEnum::PointerValue(ptr)) = operand {
+                        let name = ptr.get_name().to_str().unwrap_or_default();
+
+                        if bitcode_fns.contains(name) && !reachable.contains(name) {
+                            if let Some(callee) = module.get_function(name) {
+                                reachable.insert(name.to_string());
+                                stack.push(callee);
+                            }
+                        }
+                    }
+                }
+
+                next = instruction.get_next_instruction();
+            }
+        }
+    }
+
+    // Anything we missed (e.g. a function used in a constant expression) still has uses,
+    // so global DCE keeps it. Internal linkage only means the host can't see it.
+    for function in module.get_functions() {
+        let name = function.get_name().to_str().unwrap();
+
+        if bitcode_fns.contains(name) && !reachable.contains(name) {
+            dbg_do!(ROC_PRINT_UNUSED_BITCODE, {
+                eprintln!("Unused builtin: {name}");
+            });
+
+            function.set_linkage(Linkage::Internal);
+        }
+    }
+}
+
+/// Link in bitcode shipped by the platform which defines some of its hosted functions, like
+/// `roc_fx_now`. Those get internal linkage and are always inlined, so calling them costs no more
+/// than any other Roc function, and the host's own definitions of them go unused. Anything else
+/// the bitcode defines keeps the linkage the platform gave it.
+///
+/// The bitcode must have been compiled for the same target as `module`.
+pub fn link_inline_hosted_bitcode<'ctx>(
+    ctx: &'ctx Context,
+    module: &Module<'ctx>,
+    path: &Path,
+) -> Result<(), String> {
+    let inline_module =
+        Module::parse_bitcode_from_path(path, ctx).map_err(|err| err.to_string())?;
+
+    let triple = inline_module.get_triple();
+    let expected_triple = module.get_triple();
+    if triple != expected_triple {
+        return Err(format!(
+            "It was compiled for the target \"{}\", but I'm building for \"{}\".",
+            triple.as_str().to_string_lossy(),
+            expected_triple.as_str().to_string_lossy()
+        ));
+    }
+
+    let data_layout = inline_module
+        .get_data_layout()
+        .as_str()
+        .to_string_lossy()
+        .into_owned();
+    let expected_data_layout = module
+        .get_data_layout()
+        .as_str()
+        .to_string_lossy()
+        .into_owned();
+    if data_layout != expected_data_layout {
+        return Err(format!(
+            "Its data layout is \"{}\", but the target's is \"{}\".",
+            data_layout, expected_data_layout
+        ));
+    }
+
+    let hosted: std::vec::Vec<String> = inline_module
+        .get_functions()
+        .filter(|function| function.count_basic_blocks() > 0)
+        .map(|function| function.get_name().to_str().unwrap().to_string())
+        .filter(|name| name.starts_with("roc_fx_"))
+        .collect();
+
+    module
+        .link_in_module(inline_module)
+        .map_err(|err| err.to_string())?;
+
+    let kind_id = Attribute::get_named_enum_kind_id("alwaysinline");
+    debug_assert!(kind_id > 0);
+    let enum_attr = ctx.create_enum_attribute(kind_id, 1);
+
+    for name in hosted {
+        if let Some(function) = module.get_function(&name) {
+            // so these don't clash with the host's own definitions when linking
+            function.set_linkage(Linkage::Internal);
+            function.add_attribute(AttributeLoc::Function, enum_attr);
+        }
+    }
+
+    Ok(())
+}
+
 pub fn construct_optimization_passes<'a>(
     module: &'a Module,
     opt_level: OptLevel,
@@ -951,6 +1174,18 @@ pub fn build_exp_call<'a, 'ctx, 'env>(
             let callee_var = CalleeSpecVar(&bytes);
             let func_spec = func_spec_solutions.callee_spec(callee_var).unwrap();
 
+            if arguments.is_empty() && env.fixtures.contains(&name.name()) {
+                return call_fixture(
+                    env,
+                    layout_interner,
+                    layout_ids,
+                    parent,
+                    *ret_layout,
+                    *name,
+                    func_spec,
+                );
+            }
+
             roc_call_with_args(
                 env,
                 layout_interner,
@@ -2335,26 +2570,85 @@ fn list_literal<'a, 'ctx, 'env>(
     } else {
         let ptr = allocate_list(env, layout_interner, element_layout, list_length_intval);
 
-        // Copy the elements from the list literal into the array
-        for (index, element) in elems.iter().enumerate() {
-            let val = match element {
-                ListLiteralElement::Literal(literal) => {
-                    build_exp_literal(env, layout_interner, parent, element_layout, literal)
-                }
-                ListLiteralElement::Symbol(symbol) => load_symbol(scope, symbol),
-            };
-            let index_val = ctx.i64_type().const_int(index as u64, false);
-            let elem_ptr = unsafe {
-                builder.new_build_in_bounds_gep(element_type, ptr, &[index_val], "index")
-            };
-
-            store_roc_value(env, layout_interner, element_layout, elem_ptr, val);
-        }
+        store_list_literal_elements(
+            env,
+            layout_interner,
+            parent,
+            scope,
+            element_layout,
+            elems,
+            ptr,
+        );
 
         super::build_list::store_list(env, ptr, list_length_intval).into()
     }
 }
 
+/// Builds a list literal in the scratch region of the current call, rather than on the heap
+fn scratch_list_literal<'a, 'ctx, 'env>(
+    env: &Env<'a, 'ctx, 'env>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    parent: FunctionValue<'ctx>,
+    scope: &Scope<'a, 'ctx>,
+    element_layout: InLayout<'a>,
+    elems: &[ListLiteralElement],
+    elements_ptr: PointerValue<'ctx>,
+) -> BasicValueEnum<'ctx> {
+    let element_type = basic_type_from_layout(env, layout_interner, element_layout);
+    let list_length_intval = env.ptr_int().const_int(elems.len() as _, false);
+
+    let ptr = env.builder.build_pointer_cast(
+        elements_ptr,
+        element_type.ptr_type(AddressSpace::default()),
+        "scratch_list_elements",
+    );
+
+    // The region is released as a whole, so the list must never be freed on its own
+    let refcount_ptr = PointerToRefcount::from_ptr_to_data(env, ptr);
+    refcount_ptr.set_refcount(env, env.ptr_int().const_zero());
+
+    store_list_literal_elements(
+        env,
+        layout_interner,
+        parent,
+        scope,
+        element_layout,
+        elems,
+        ptr,
+    );
+
+    super::build_list::store_list(env, ptr, list_length_intval).into()
+}
+
+fn store_list_literal_elements<'a, 'ctx, 'env>(
+    env: &Env<'a, 'ctx, 'env>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    parent: FunctionValue<'ctx>,
+    scope: &Scope<'a, 'ctx>,
+    element_layout: InLayout<'a>,
+    elems: &[ListLiteralElement],
+    ptr: PointerValue<'ctx>,
+) {
+    let element_type = basic_type_from_layout(env, layout_interner, element_layout);
+
+    // Copy the elements from the list literal into the array
+    for (index, element) in elems.iter().enumerate() {
+        let val = match element {
+            ListLiteralElement::Literal(literal) => {
+                build_exp_literal(env, layout_interner, parent, element_layout, literal)
+            }
+            ListLiteralElement::Symbol(symbol) => load_symbol(scope, symbol),
+        };
+        let index_val = env.context.i64_type().const_int(index as u64, false);
+        let elem_ptr = unsafe {
+            env.builder
+                .new_build_in_bounds_gep(element_type, ptr, &[index_val], "index")
+        };
+
+        store_roc_value(env, layout_interner, element_layout, elem_ptr, val);
+    }
+}
+
 pub fn load_roc_value<'a, 'ctx, 'env>(
     env: &Env<'a, 'ctx, 'env>,
     layout_interner: &mut STLayoutInterner<'a>,
@@ -2478,23 +2772,48 @@ pub fn build_exp_stmt<'a, 'ctx, 'env>(
             }
 
             let mut stack = Vec::with_capacity_in(queue.len(), env.arena);
+            let mut index = 0;
+
+            while let Some(&(symbol, expr, layout)) = queue.get(index) {
+                if let Some(len) = scope.effect_batches.get(symbol).copied() {
+                    let batch = &queue[index..index + len];
+
+                    build_effect_batch(env, layout_interner, scope, parent, batch);
+
+                    stack.extend(batch.iter().map(|(symbol, _, _)| **symbol));
+                    index += len;
+
+                    continue;
+                }
 
-            for (symbol, expr, layout) in queue {
                 debug_assert!(!matches!(
                     layout_interner.get(*layout),
                     Layout::RecursivePointer(_)
                 ));
 
-                let val = build_exp_expr(
-                    env,
-                    layout_interner,
-                    layout_ids,
-                    func_spec_solutions,
-                    scope,
-                    parent,
-                    *layout,
-                    expr,
-                );
+                let val = match (expr, scope.scratch_lists.get(symbol).copied()) {
+                    (Expr::Array { elem_layout, elems }, Some(elements_ptr)) => {
+                        scratch_list_literal(
+                            env,
+                            layout_interner,
+                            parent,
+                            scope,
+                            *elem_layout,
+                            elems,
+                            elements_ptr,
+                        )
+                    }
+                    _ => build_exp_expr(
+                        env,
+                        layout_interner,
+                        layout_ids,
+                        func_spec_solutions,
+                        scope,
+                        parent,
+                        *layout,
+                        expr,
+                    ),
+                };
 
                 // Make a new scope which includes the binding we just encountered.
                 // This should be done *after* compiling the bound expr, since any
@@ -2505,6 +2824,7 @@ pub fn build_exp_stmt<'a, 'ctx, 'env>(
 
                 scope.insert(*symbol, (*layout, val));
                 stack.push(*symbol);
+                index += 1;
             }
 
             let result = build_exp_stmt(
@@ -2789,7 +3109,14 @@ pub fn build_exp_stmt<'a, 'ctx, 'env>(
                             let alignment =
                                 element_layout.alignment_bytes(layout_interner, env.target_info);
 
-                            build_list::decref(env, value.into_struct_value(), alignment);
+                            let is_atomic = env.thread_shared_layouts.contains(layout);
+
+                            build_list::decref(
+                                env,
+                                value.into_struct_value(),
+                                alignment,
+                                is_atomic,
+                            );
                         }
 
                         lay if lay.is_refcounted() => {
@@ -3011,8 +3338,8 @@ pub fn build_exp_stmt<'a, 'ctx, 'env>(
             )
         }
 
-        Crash(sym, tag) => {
-            throw_exception(env, scope, sym, *tag);
+        Crash(sym, tag, region) => {
+            throw_exception(env, scope, sym, *tag, *region);
 
             // unused value (must return a BasicValue)
             let zero = env.context.i64_type().const_zero();
@@ -3526,6 +3853,192 @@ pub fn create_entry_block_alloca<'a, 'ctx>(
     builder.build_alloca(basic_type, name)
 }
 
+/// Allocates the scratch region for the list literals which don't outlive this call of the proc.
+/// Each list gets a stack slot of its own, exactly as big as it needs.
+fn build_scratch_region<'a, 'ctx, 'env>(
+    env: &Env<'a, 'ctx, 'env>,
+    layout_interner: &STLayoutInterner<'a>,
+    scope: &mut Scope<'a, 'ctx>,
+    proc: &roc_mono::ir::Proc<'a>,
+    fn_val: FunctionValue<'ctx>,
+) {
+    if roc_debug_flags::dbg_set!(roc_debug_flags::ROC_NO_SCRATCH_REGIONS) {
+        return;
+    }
+
+    let region = ScratchRegion::from_proc(layout_interner, proc);
+    let i8_type = env.context.i8_type();
+
+    for (symbol, list) in region.lists() {
+        let list_type = i8_type.array_type(list.size);
+        let list_ptr = create_entry_block_alloca(env, fn_val, list_type.into(), "scratch_list");
+
+        list_ptr
+            .as_instruction_value()
+            .unwrap()
+            .set_alignment(list.alignment)
+            .unwrap();
+
+        let bytes_ptr = env.builder.build_pointer_cast(
+            list_ptr,
+            i8_type.ptr_type(AddressSpace::default()),
+            "scratch_list_bytes",
+        );
+        let offset = env.ptr_int().const_int(list.elements_offset as u64, false);
+        let elements_ptr = unsafe {
+            env.builder.new_build_in_bounds_gep(
+                i8_type,
+                bytes_ptr,
+                &[offset],
+                "scratch_list_elements",
+            )
+        };
+
+        scope.scratch_lists.insert(symbol, elements_ptr);
+    }
+}
+
+/// Hands a batch of effects to the host's `roc_fx_batch` with a single call, and adds their
+/// results to the scope. See [roc_mono::effect_batch] for how the host receives them.
+fn build_effect_batch<'a, 'ctx, 'env>(
+    env: &Env<'a, 'ctx, 'env>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    scope: &mut Scope<'a, 'ctx>,
+    parent: FunctionValue<'ctx>,
+    effects: &[(&Symbol, &Expr<'a>, &InLayout<'a>)],
+) {
+    let builder = env.builder;
+    let i8_ptr_type = env.context.i8_type().ptr_type(AddressSpace::default());
+    let arguments_ptr_type = i8_ptr_type.ptr_type(AddressSpace::default());
+    let effect_type = env.context.struct_type(
+        &[
+            i8_ptr_type.into(),
+            arguments_ptr_type.into(),
+            i8_ptr_type.into(),
+        ],
+        false,
+    );
+    let batch_type = effect_type.array_type(effects.len() as u32);
+    let batch_ptr = create_entry_block_alloca(env, parent, batch_type.into(), "effect_batch");
+
+    let mut outputs = Vec::with_capacity_in(effects.len(), env.arena);
+
+    for (index, (symbol, expr, layout)) in effects.iter().enumerate() {
+        let (foreign_symbol, arguments) = match expr {
+            Expr::Call(Call {
+                call_type: CallType::Foreign { foreign_symbol, .. },
+                arguments,
+            }) => (foreign_symbol, *arguments),
+            _ => unreachable!("only effects go in a batch of effects, not {:?}", expr),
+        };
+
+        let arguments_type = i8_ptr_type.array_type(arguments.len() as u32);
+        let arguments_ptr =
+            create_entry_block_alloca(env, parent, arguments_type.into(), "effect_arguments");
+
+        for (argument_index, argument) in arguments.iter().enumerate() {
+            let (value, argument_layout) = load_symbol_and_layout(scope, argument);
+
+            // values passed by reference are pointers already
+            let value_ptr = if layout_interner.is_passed_by_reference(argument_layout) {
+                value.into_pointer_value()
+            } else {
+                let alloca =
+                    create_entry_block_alloca(env, parent, value.get_type(), "effect_argument");
+                builder.build_store(alloca, value);
+
+                alloca
+            };
+
+            let slot = unsafe {
+                builder.new_build_in_bounds_gep(
+                    arguments_type,
+                    arguments_ptr,
+                    &[
+                        env.ptr_int().const_zero(),
+                        env.ptr_int().const_int(argument_index as u64, false),
+                    ],
+                    "effect_argument_slot",
+                )
+            };
+            let value_ptr = builder.build_pointer_cast(value_ptr, i8_ptr_type, "to_i8_ptr");
+
+            builder.build_store(slot, value_ptr);
+        }
+
+        let output_type = basic_type_from_layout(env, layout_interner, **layout);
+        let output_ptr = create_entry_block_alloca(env, parent, output_type, "effect_output");
+        let name = builder
+            .build_global_string_ptr(foreign_symbol.as_str(), "effect_name")
+            .as_pointer_value();
+
+        let effect_ptr = unsafe {
+            builder.new_build_in_bounds_gep(
+                batch_type,
+                batch_ptr,
+                &[
+                    env.ptr_int().const_zero(),
+                    env.ptr_int().const_int(index as u64, false),
+                ],
+                "effect",
+            )
+        };
+        let fields = [
+            builder.build_pointer_cast(name, i8_ptr_type, "effect_name"),
+            builder.build_pointer_cast(arguments_ptr, arguments_ptr_type, "effect_arguments"),
+            builder.build_pointer_cast(output_ptr, i8_ptr_type, "effect_output"),
+        ];
+
+        for (field_index, field) in fields.into_iter().enumerate() {
+            let field_ptr = builder
+                .new_build_struct_gep(effect_type, effect_ptr, field_index as u32, "effect_field")
+                .unwrap();
+
+            builder.build_store(field_ptr, field);
+        }
+
+        outputs.push((**symbol, **layout, output_ptr));
+    }
+
+    let batch_fn = match env.module.get_function(effect_batch::BATCH_FN) {
+        Some(function) => function,
+        None => {
+            let spec = FunctionSpec::cconv(
+                env,
+                CCReturn::Void,
+                None,
+                &[
+                    effect_type.ptr_type(AddressSpace::default()).into(),
+                    env.ptr_int().into(),
+                ],
+            );
+
+            add_func(
+                env.context,
+                env.module,
+                effect_batch::BATCH_FN,
+                spec,
+                Linkage::External,
+            )
+        }
+    };
+
+    let effects_ptr = builder.build_pointer_cast(
+        batch_ptr,
+        effect_type.ptr_type(AddressSpace::default()),
+        "effects",
+    );
+    let count = env.ptr_int().const_int(effects.len() as u64, false);
+    let call = builder.build_call(batch_fn, &[effects_ptr.into(), count.into()], "run_effects");
+    call.set_call_convention(C_CALL_CONV);
+
+    for (symbol, layout, output_ptr) in outputs {
+        let value = load_roc_value(env, layout_interner, layout, output_ptr, "effect_result");
+
+        scope.insert(symbol, (layout, value));
+    }
+}
+
 fn expose_function_to_host<'a, 'ctx, 'env>(
     env: &Env<'a, 'ctx, 'env>,
     layout_interner: &mut STLayoutInterner<'a>,
@@ -4281,14 +4794,14 @@ pub fn build_setjmp_call<'a, 'ctx, 'env>(env: &Env<'a, 'ctx, 'env>) -> BasicValu
     }
 }
 
-/// Pointer to RocStr which is the panic message.
-pub fn get_panic_msg_ptr<'a, 'ctx, 'env>(env: &Env<'a, 'ctx, 'env>) -> PointerValue<'ctx> {
-    let str_typ = zig_str_type(env);
+/// Pointer to the panic payload, which is the message followed by the source region.
+pub fn get_panic_payload_ptr<'a, 'ctx, 'env>(env: &Env<'a, 'ctx, 'env>) -> PointerValue<'ctx> {
+    let payload_typ = zig_panic_type(env);
 
-    let global_name = "roc_panic_msg_str";
+    let global_name = "roc_panic_payload";
     let global = env.module.get_global(global_name).unwrap_or_else(|| {
-        let global = env.module.add_global(str_typ, None, global_name);
-        global.set_initializer(&str_typ.const_zero());
+        let global = env.module.add_global(payload_typ, None, global_name);
+        global.set_initializer(&payload_typ.const_zero());
         global
     });
 
@@ -4358,8 +4871,8 @@ fn set_jump_and_catch_long_jump<'a, 'ctx, 'env>(
     {
         builder.position_at_end(catch_block);
 
-        // RocStr* global
-        let error_msg_ptr = get_panic_msg_ptr(env);
+        // RocPanic* global
+        let error_msg_ptr = get_panic_payload_ptr(env);
         // i64* global
         let error_tag_ptr = get_panic_tag_ptr(env);
 
@@ -4430,7 +4943,7 @@ fn roc_call_result_type<'a, 'ctx, 'env>(
     env.context.struct_type(
         &[
             env.context.i64_type().into(),
-            zig_str_type(env).ptr_type(AddressSpace::default()).into(),
+            zig_panic_type(env).ptr_type(AddressSpace::default()).into(),
             return_type,
         ],
         false,
@@ -4850,17 +5363,12 @@ fn func_spec_name<'a>(
     symbol: Symbol,
     func_spec: FuncSpec,
 ) -> bumpalo::collections::String<'a> {
-    use std::fmt::Write;
-
     let mut buf = bumpalo::collections::String::with_capacity_in(1, arena);
 
     let ident_string = symbol.as_str(interns);
     let module_string = interns.module_ids.get_name(symbol.module_id()).unwrap();
-    write!(buf, "{}_{}_", module_string, ident_string).unwrap();
-
-    for byte in func_spec.0.iter() {
-        write!(buf, "{:x?}", byte).unwrap();
-    }
+    mangle::write_hashed_specialization_name(&mut buf, module_string, ident_string, &func_spec.0)
+        .unwrap();
 
     buf
 }
@@ -4901,6 +5409,10 @@ fn build_proc_header<'a, 'ctx, 'env>(
     let subprogram = env.new_subprogram(&fn_name);
     fn_val.set_subprogram(subprogram);
 
+    if env.fast_math {
+        add_fast_math_attributes(env, fn_val);
+    }
+
     if env.exposed_to_host.contains(&symbol) {
         let arguments = Vec::from_iter_in(proc.args.iter().map(|(layout, _)| *layout), env.arena);
         expose_function_to_host(
@@ -4932,6 +5444,21 @@ fn build_proc_header<'a, 'ctx, 'env>(
     fn_val
 }
 
+/// The function attributes clang adds for `-ffast-math`. The zig builtins don't have them, and
+/// LLVM drops them from a function when it inlines one that doesn't, so builtins like
+/// `Num.isNaN` still give the right answer.
+fn add_fast_math_attributes<'ctx>(env: &Env<'_, 'ctx, '_>, fn_val: FunctionValue<'ctx>) {
+    for key in [
+        "unsafe-fp-math",
+        "no-nans-fp-math",
+        "no-infs-fp-math",
+        "no-signed-zeros-fp-math",
+    ] {
+        let attr = env.context.create_string_attribute(key, "true");
+        fn_val.add_attribute(AttributeLoc::Function, attr);
+    }
+}
+
 fn expose_alias_to_host<'a, 'ctx, 'env>(
     env: &Env<'a, 'ctx, 'env>,
     layout_interner: &mut STLayoutInterner<'a>,
@@ -4943,14 +5470,13 @@ fn expose_alias_to_host<'a, 'ctx, 'env>(
     layout: RawFunctionLayout<'a>,
 ) {
     let ident_string = proc_name.name().as_str(&env.interns);
-    let fn_name: String = format!("{}_1", ident_string);
 
     match layout {
         RawFunctionLayout::Function(arguments, closure, result) => {
             // define closure size and return value size, e.g.
             //
-            // * roc__mainForHost_1_Update_size() -> i64
-            // * roc__mainForHost_1_Update_result_size() -> i64
+            // * roc__mainForHost_1__Update_size() -> i64
+            // * roc__mainForHost_1__Update_result_size() -> i64
 
             let it = top_level.arguments.iter().copied();
             let bytes = roc_alias_analysis::func_name_bytes_help(
@@ -4990,7 +5516,7 @@ fn expose_alias_to_host<'a, 'ctx, 'env>(
             build_closure_caller(
                 env,
                 layout_interner,
-                &fn_name,
+                ident_string,
                 evaluator,
                 alias_symbol,
                 arguments,
@@ -5003,15 +5529,15 @@ fn expose_alias_to_host<'a, 'ctx, 'env>(
         RawFunctionLayout::ZeroArgumentThunk(result) => {
             // Define only the return value size, since this is a thunk
             //
-            // * roc__mainForHost_1_Update_result_size() -> i64
+            // * roc__mainForHost_1__Update_result_size() -> i64
 
             let result_type = basic_type_from_layout(env, layout_interner, result);
 
             build_host_exposed_alias_size_help(
                 env,
-                &fn_name,
+                ident_string,
                 alias_symbol,
-                Some("result"),
+                HostClosureFn::ResultSize,
                 result_type,
             );
         }
@@ -5056,12 +5582,12 @@ fn build_closure_caller<'a, 'ctx, 'env>(
 
     // STEP 1: build function header
 
-    // e.g. `roc__main_1_Fx_caller`
-    let function_name = format!(
-        "roc__{}_{}_{}_caller",
+    // e.g. `roc__mainForHost_1__Fx_caller`
+    let function_name = mangle::host_closure_fn_name(
         def_name,
-        alias_symbol.module_string(&env.interns),
-        alias_symbol.as_str(&env.interns)
+        1,
+        &host_closure_type_name(env, alias_symbol),
+        HostClosureFn::Caller,
     );
 
     let function_spec = FunctionSpec::cconv(env, CCReturn::Void, None, &argument_types);
@@ -5142,7 +5668,13 @@ fn build_closure_caller<'a, 'ctx, 'env>(
     builder.build_return(None);
 
     // STEP 3: build a {} -> u64 function that gives the size of the return type
-    build_host_exposed_alias_size_help(env, def_name, alias_symbol, Some("result"), result_type);
+    build_host_exposed_alias_size_help(
+        env,
+        def_name,
+        alias_symbol,
+        HostClosureFn::ResultSize,
+        result_type,
+    );
 
     // STEP 4: build a {} -> u64 function that gives the size of the closure
     build_host_exposed_alias_size(
@@ -5154,6 +5686,13 @@ fn build_closure_caller<'a, 'ctx, 'env>(
     );
 }
 
+fn host_closure_type_name(env: &Env, alias_symbol: Symbol) -> String {
+    mangle::host_closure_type_name(
+        alias_symbol.module_string(&env.interns),
+        alias_symbol.as_str(&env.interns),
+    )
+}
+
 fn build_host_exposed_alias_size<'a, 'r, 'ctx, 'env>(
     env: &'r Env<'a, 'ctx, 'env>,
     layout_interner: &'r mut STLayoutInterner<'a>,
@@ -5165,7 +5704,7 @@ fn build_host_exposed_alias_size<'a, 'r, 'ctx, 'env>(
         env,
         def_name,
         alias_symbol,
-        None,
+        HostClosureFn::Size,
         basic_type_from_layout(env, layout_interner, layout),
     )
 }
@@ -5174,7 +5713,7 @@ fn build_host_exposed_alias_size_help<'a, 'ctx, 'env>(
     env: &'a Env<'a, 'ctx, 'env>,
     def_name: &str,
     alias_symbol: Symbol,
-    opt_label: Option<&str>,
+    function: HostClosureFn,
     basic_type: BasicTypeEnum<'ctx>,
 ) {
     let builder = env.builder;
@@ -5182,22 +5721,12 @@ fn build_host_exposed_alias_size_help<'a, 'ctx, 'env>(
 
     let i64 = env.context.i64_type().as_basic_type_enum();
     let size_function_spec = FunctionSpec::cconv(env, CCReturn::Return, Some(i64), &[]);
-    let size_function_name: String = if let Some(label) = opt_label {
-        format!(
-            "roc__{}_{}_{}_{}_size",
-            def_name,
-            alias_symbol.module_string(&env.interns),
-            alias_symbol.as_str(&env.interns),
-            label
-        )
-    } else {
-        format!(
-            "roc__{}_{}_{}_size",
-            def_name,
-            alias_symbol.module_string(&env.interns),
-            alias_symbol.as_str(&env.interns)
-        )
-    };
+    let size_function_name = mangle::host_closure_fn_name(
+        def_name,
+        1,
+        &host_closure_type_name(env, alias_symbol),
+        function,
+    );
 
     let size_function = add_func(
         env.context,
@@ -5271,6 +5800,12 @@ pub fn build_proc<'a, 'ctx, 'env>(
         scope.insert(*arg_symbol, (*layout, arg_val));
     }
 
+    build_scratch_region(env, layout_interner, &mut scope, proc, fn_val);
+
+    if env.batch_effects {
+        scope.effect_batches = EffectBatches::from_proc(proc).iter().collect();
+    }
+
     let body = build_exp_stmt(
         env,
         layout_interner,
@@ -5374,6 +5909,88 @@ fn roc_call_with_args<'a, 'ctx, 'env>(
     call_roc_function(env, layout_interner, fn_val, result_layout, arguments)
 }
 
+/// Calls the thunk of a module fixture, which only evaluates it the first time it's called.
+/// The result is kept in a global, which holds on to one reference to it, and every call after
+/// that gets another reference to the same value.
+fn call_fixture<'a, 'ctx, 'env>(
+    env: &Env<'a, 'ctx, 'env>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    layout_ids: &mut LayoutIds<'a>,
+    parent: FunctionValue<'ctx>,
+    result_layout: InLayout<'a>,
+    name: LambdaName<'a>,
+    func_spec: FuncSpec,
+) -> BasicValueEnum<'ctx> {
+    let fn_val = function_value_by_func_spec(
+        env,
+        func_spec,
+        name.name(),
+        &[],
+        name.niche(),
+        result_layout,
+    );
+
+    // every specialization of the fixture has its own cache
+    let fn_name = fn_val.get_name().to_str().unwrap();
+    let cache_name = format!("{}.fixture_value", fn_name);
+    let is_cached_name = format!("{}.fixture_is_cached", fn_name);
+
+    let value_type = basic_type_from_layout(env, layout_interner, result_layout);
+    let flag_type = env.context.bool_type();
+
+    let cache = env.module.get_global(&cache_name).unwrap_or_else(|| {
+        let global = env.module.add_global(value_type, None, &cache_name);
+        global.set_initializer(&value_type.const_zero());
+        global.set_linkage(Linkage::Internal);
+        global
+    });
+
+    let is_cached = env.module.get_global(&is_cached_name).unwrap_or_else(|| {
+        let global = env.module.add_global(flag_type, None, &is_cached_name);
+        global.set_initializer(&flag_type.const_zero());
+        global.set_linkage(Linkage::Internal);
+        global
+    });
+
+    let builder = env.builder;
+
+    let compute_block = env.context.append_basic_block(parent, "fixture_compute");
+    let cached_block = env.context.append_basic_block(parent, "fixture_cached");
+
+    let was_cached = builder.new_build_load(flag_type, is_cached.as_pointer_value(), "is_cached");
+    builder.build_conditional_branch(was_cached.into_int_value(), cached_block, compute_block);
+
+    {
+        builder.position_at_end(compute_block);
+
+        // the cache keeps the reference that the thunk gives us
+        let value = call_roc_function(env, layout_interner, fn_val, result_layout, &[]);
+        store_roc_value(
+            env,
+            layout_interner,
+            result_layout,
+            cache.as_pointer_value(),
+            value,
+        );
+        builder.build_store(is_cached.as_pointer_value(), flag_type.const_int(1, false));
+
+        builder.build_unconditional_branch(cached_block);
+    }
+
+    builder.position_at_end(cached_block);
+
+    let value = load_roc_value(
+        env,
+        layout_interner,
+        result_layout,
+        cache.as_pointer_value(),
+        "fixture_value",
+    );
+    increment_refcount_layout(env, layout_interner, layout_ids, 1, value, result_layout);
+
+    value
+}
+
 pub fn call_roc_function<'a, 'ctx, 'env>(
     env: &Env<'a, 'ctx, 'env>,
     layout_interner: &mut STLayoutInterner<'a>,
@@ -6056,7 +6673,7 @@ pub(crate) fn throw_internal_exception<'a, 'ctx, 'env>(
 
     let str = build_string_literal(env, parent, message);
 
-    env.call_panic(env, str, CrashTag::Roc);
+    env.call_panic(env, str, CrashTag::Roc, CrashRegion::UNKNOWN);
 
     builder.build_unreachable();
 }
@@ -6066,10 +6683,11 @@ pub(crate) fn throw_exception<'a, 'ctx, 'env>(
     scope: &mut Scope<'a, 'ctx>,
     message: &Symbol,
     tag: CrashTag,
+    region: CrashRegion,
 ) {
     let msg_val = load_symbol(scope, message);
 
-    env.call_panic(env, msg_val, tag);
+    env.call_panic(env, msg_val, tag, region);
 
     env.builder.build_unreachable();
 }
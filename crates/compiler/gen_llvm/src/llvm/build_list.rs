@@ -476,6 +476,30 @@ pub(crate) fn list_map<'a, 'ctx, 'env>(
     )
 }
 
+pub(crate) fn list_map_concurrent<'a, 'ctx, 'env>(
+    env: &Env<'a, 'ctx, 'env>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    roc_function_call: RocFunctionCall<'ctx>,
+    list: BasicValueEnum<'ctx>,
+    element_layout: InLayout<'a>,
+    return_layout: InLayout<'a>,
+) -> BasicValueEnum<'ctx> {
+    call_list_bitcode_fn_1(
+        env,
+        list.into_struct_value(),
+        &[
+            roc_function_call.caller.into(),
+            pass_as_opaque(env, roc_function_call.data),
+            roc_function_call.inc_n_data.into(),
+            roc_function_call.data_is_owned.into(),
+            env.alignment_intvalue(layout_interner, return_layout),
+            layout_width(env, layout_interner, element_layout),
+            layout_width(env, layout_interner, return_layout),
+        ],
+        bitcode::LIST_MAP_CONCURRENT,
+    )
+}
+
 pub(crate) fn list_map2<'a, 'ctx, 'env>(
     env: &Env<'a, 'ctx, 'env>,
     layout_interner: &mut STLayoutInterner<'a>,
@@ -800,6 +824,7 @@ pub(crate) fn decref<'a, 'ctx, 'env>(
     env: &Env<'a, 'ctx, 'env>,
     wrapper_struct: StructValue<'ctx>,
     alignment: u32,
+    is_atomic: bool,
 ) {
     let (_, pointer) = load_list(
         env.builder,
@@ -807,5 +832,5 @@ pub(crate) fn decref<'a, 'ctx, 'env>(
         env.context.i8_type().ptr_type(AddressSpace::default()),
     );
 
-    crate::llvm::refcounting::decref_pointer_check_null(env, pointer, alignment);
+    crate::llvm::refcounting::decref_pointer_check_null(env, pointer, alignment, is_atomic);
 }
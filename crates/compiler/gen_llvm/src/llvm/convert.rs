@@ -433,6 +433,11 @@ pub fn zig_str_type<'a, 'ctx, 'env>(env: &Env<'a, 'ctx, 'env>) -> StructType<'ct
     env.module.get_struct_type("str.RocStr").unwrap()
 }
 
+/// The payload passed to roc_panic: the message, followed by the source region
+pub fn zig_panic_type<'a, 'ctx, 'env>(env: &Env<'a, 'ctx, 'env>) -> StructType<'ctx> {
+    env.module.get_struct_type("panic.RocPanic").unwrap()
+}
+
 pub fn zig_dec_type<'a, 'ctx, 'env>(env: &Env<'a, 'ctx, 'env>) -> StructType<'ctx> {
     env.module.get_struct_type("dec.RocDec").unwrap()
 }
@@ -1,7 +1,9 @@
 use crate::llvm::bitcode::call_void_bitcode_fn;
-use crate::llvm::build::{add_func, get_panic_msg_ptr, get_panic_tag_ptr, BuilderExt, C_CALL_CONV};
+use crate::llvm::build::{
+    add_func, get_panic_payload_ptr, get_panic_tag_ptr, BuilderExt, C_CALL_CONV,
+};
 use crate::llvm::build::{CCReturn, Env, FunctionSpec};
-use crate::llvm::convert::zig_str_type;
+use crate::llvm::convert::zig_panic_type;
 use inkwell::module::Linkage;
 use inkwell::types::BasicType;
 use inkwell::AddressSpace;
@@ -22,15 +24,19 @@ pub fn add_default_roc_externs(env: &Env<'_, '_, '_>) {
 
     match env.mode {
         super::build::LlvmBackendMode::CliTest => {
-            // expose this function
-            if let Some(fn_val) = module.get_function("set_shared_buffer") {
-                fn_val.set_linkage(Linkage::External);
+            // expose these functions
+            for name in ["set_shared_buffer", "set_test_seed"] {
+                if let Some(fn_val) = module.get_function(name) {
+                    fn_val.set_linkage(Linkage::External);
+                }
             }
         }
         _ => {
-            // remove this function from the module
-            if let Some(fn_val) = module.get_function("set_shared_buffer") {
-                unsafe { fn_val.delete() };
+            // remove these functions from the module
+            for name in ["set_shared_buffer", "set_test_seed"] {
+                if let Some(fn_val) = module.get_function(name) {
+                    unsafe { fn_val.delete() };
+                }
             }
         }
     }
@@ -160,7 +166,8 @@ pub fn add_default_roc_externs(env: &Env<'_, '_, '_>) {
         unreachable_function(env, "roc_mmap");
         unreachable_function(env, "roc_shm_open");
 
-        add_sjlj_roc_panic(env)
+        // roc_oom is left undefined, so running out of memory calls roc_panic
+        add_sjlj_roc_panic(env);
     }
 }
 
@@ -184,6 +191,44 @@ fn unreachable_function(env: &Env, name: &str) {
     }
 }
 
+/// Define allocator functions that the platform asked Roc to provide, by forwarding each
+/// `(extern_name, builtin_name)` pair to the builtin implementation
+pub fn add_provided_allocator_fns(env: &Env<'_, '_, '_>, fns: &[(&str, &str)]) {
+    for (name, builtin_name) in fns {
+        // The type of this function (but not the implementation) should have
+        // already been defined by the builtins, which rely on it.
+        let fn_val = match env.module.get_function(name) {
+            Some(f) => f,
+            None => panic!("extern function {name} is not defined by the builtins"),
+        };
+        let builtin_val = match env.module.get_function(builtin_name) {
+            Some(f) => f,
+            None => panic!("builtin function {builtin_name} is not defined by the builtins"),
+        };
+
+        // Add a basic block for the entry point
+        let entry = env.context.append_basic_block(fn_val, "entry");
+
+        env.builder.position_at_end(entry);
+
+        let args: Vec<_> = fn_val.get_param_iter().map(|arg| arg.into()).collect();
+        let call = env
+            .builder
+            .build_call(builtin_val, &args, "call_builtin_allocator");
+
+        call.set_call_convention(C_CALL_CONV);
+
+        match call.try_as_basic_value().left() {
+            Some(retval) => env.builder.build_return(Some(&retval)),
+            None => env.builder.build_return(None),
+        };
+
+        if cfg!(debug_assertions) {
+            crate::llvm::build::verify_fn(fn_val);
+        }
+    }
+}
+
 pub fn add_sjlj_roc_panic(env: &Env<'_, '_, '_>) {
     let ctx = env.context;
     let module = env.module;
@@ -195,7 +240,7 @@ pub fn add_sjlj_roc_panic(env: &Env<'_, '_, '_>) {
         // already been defined by the builtins, which rely on it.
         let fn_val = module.get_function("roc_panic").unwrap();
         let mut params = fn_val.get_param_iter();
-        let roc_str_arg = params.next().unwrap();
+        let payload_arg = params.next().unwrap();
 
         let tag_id_arg = params.next().unwrap();
 
@@ -211,23 +256,17 @@ pub fn add_sjlj_roc_panic(env: &Env<'_, '_, '_>) {
 
         builder.position_at_end(entry);
 
-        // write our error message to the RocStr pointer
+        // copy the message and region into our global
         {
-            let loaded_roc_str = match env.target_info.ptr_width() {
-                roc_target::PtrWidth::Bytes4 => roc_str_arg,
-                // On 64-bit we pass RocStrs by reference internally
-                roc_target::PtrWidth::Bytes8 => {
-                    let str_typ = zig_str_type(env);
-                    builder.new_build_load(
-                        str_typ,
-                        roc_str_arg.into_pointer_value(),
-                        "load_roc_str",
-                    )
-                }
-            };
+            let payload_typ = zig_panic_type(env);
+            let loaded_payload = builder.new_build_load(
+                payload_typ,
+                payload_arg.into_pointer_value(),
+                "load_panic_payload",
+            );
 
             env.builder
-                .build_store(get_panic_msg_ptr(env), loaded_roc_str);
+                .build_store(get_panic_payload_ptr(env), loaded_payload);
         }
 
         // write the panic tag.
@@ -148,6 +148,39 @@ pub(crate) fn add_intrinsics<'ctx>(ctx: &'ctx Context, module: &Module<'ctx>) {
     add_int_intrinsic(ctx, module, &LLVM_SUB_SATURATED, |t| {
         t.fn_type(&[t.into(), t.into()], false)
     });
+
+    add_int_intrinsic(ctx, module, &LLVM_CTPOP, |t| t.fn_type(&[t.into()], false));
+
+    add_int_intrinsic(ctx, module, &LLVM_CTLZ, |t| {
+        t.fn_type(&[t.into(), i1_type.into()], false)
+    });
+
+    add_int_intrinsic(ctx, module, &LLVM_CTTZ, |t| {
+        t.fn_type(&[t.into(), i1_type.into()], false)
+    });
+
+    add_int_intrinsic(ctx, module, &LLVM_FSHL, |t| {
+        t.fn_type(&[t.into(), t.into(), t.into()], false)
+    });
+
+    add_int_intrinsic(ctx, module, &LLVM_FSHR, |t| {
+        t.fn_type(&[t.into(), t.into(), t.into()], false)
+    });
+
+    // bswap is only defined for a whole number of pairs of bytes, so there's no i8 version
+    for (int_width, int_type) in [
+        (IntWidth::I16, ctx.i16_type()),
+        (IntWidth::I32, ctx.i32_type()),
+        (IntWidth::I64, ctx.i64_type()),
+        (IntWidth::I128, ctx.i128_type()),
+    ] {
+        add_intrinsic(
+            ctx,
+            module,
+            &LLVM_BSWAP[int_width],
+            int_type.fn_type(&[int_type.into()], false),
+        );
+    }
 }
 
 pub const LLVM_POW: IntrinsicName = float_intrinsic!("llvm.pow");
@@ -180,6 +213,13 @@ pub const LLVM_MUL_WITH_OVERFLOW: IntrinsicName =
 pub const LLVM_ADD_SATURATED: IntrinsicName = llvm_int_intrinsic!("llvm.sadd.sat", "llvm.uadd.sat");
 pub const LLVM_SUB_SATURATED: IntrinsicName = llvm_int_intrinsic!("llvm.ssub.sat", "llvm.usub.sat");
 
+pub const LLVM_CTPOP: IntrinsicName = llvm_int_intrinsic!("llvm.ctpop", "llvm.ctpop");
+pub const LLVM_CTLZ: IntrinsicName = llvm_int_intrinsic!("llvm.ctlz", "llvm.ctlz");
+pub const LLVM_CTTZ: IntrinsicName = llvm_int_intrinsic!("llvm.cttz", "llvm.cttz");
+pub const LLVM_FSHL: IntrinsicName = llvm_int_intrinsic!("llvm.fshl", "llvm.fshl");
+pub const LLVM_FSHR: IntrinsicName = llvm_int_intrinsic!("llvm.fshr", "llvm.fshr");
+pub const LLVM_BSWAP: IntrinsicName = llvm_int_intrinsic!("llvm.bswap", "llvm.bswap");
+
 fn add_intrinsic<'ctx>(
     context: &Context,
     module: &Module<'ctx>,
@@ -29,18 +29,19 @@ use crate::llvm::{
     },
     build_list::{
         list_append_unsafe, list_capacity, list_concat, list_drop_at, list_get_unsafe, list_len,
-        list_map, list_map2, list_map3, list_map4, list_prepend, list_replace_unsafe, list_reserve,
-        list_sort_with, list_sublist, list_swap, list_symbol_to_c_abi, list_with_capacity,
-        pass_update_mode,
+        list_map, list_map2, list_map3, list_map4, list_map_concurrent, list_prepend,
+        list_replace_unsafe, list_reserve, list_sort_with, list_sublist, list_swap,
+        list_symbol_to_c_abi, list_with_capacity, pass_update_mode,
     },
     compare::{generic_eq, generic_neq},
     convert::{
         self, basic_type_from_layout, zig_num_parse_result_type, zig_to_int_checked_result_type,
     },
     intrinsics::{
-        LLVM_ADD_SATURATED, LLVM_ADD_WITH_OVERFLOW, LLVM_CEILING, LLVM_COS, LLVM_FABS, LLVM_FLOOR,
-        LLVM_LOG, LLVM_MUL_WITH_OVERFLOW, LLVM_POW, LLVM_ROUND, LLVM_SIN, LLVM_SQRT,
-        LLVM_SUB_SATURATED, LLVM_SUB_WITH_OVERFLOW,
+        LLVM_ADD_SATURATED, LLVM_ADD_WITH_OVERFLOW, LLVM_BSWAP, LLVM_CEILING, LLVM_COS, LLVM_CTLZ,
+        LLVM_CTPOP, LLVM_CTTZ, LLVM_FABS, LLVM_FLOOR, LLVM_FSHL, LLVM_FSHR, LLVM_LOG,
+        LLVM_MUL_WITH_OVERFLOW, LLVM_POW, LLVM_ROUND, LLVM_SIN, LLVM_SQRT, LLVM_SUB_SATURATED,
+        LLVM_SUB_WITH_OVERFLOW,
     },
 };
 
@@ -625,6 +626,30 @@ pub(crate) fn run_low_level<'a, 'ctx, 'env>(
                 bitcode::STR_GRAPHEMES,
             )
         }
+        StrMatches => {
+            // Str.matches : Str, Str -> Bool
+            arguments!(string, pattern);
+
+            call_str_bitcode_fn(
+                env,
+                &[string, pattern],
+                &[],
+                BitcodeReturns::Basic,
+                bitcode::STR_REGEX_MATCHES,
+            )
+        }
+        StrCapture => {
+            // Str.captureLowlevel : Str, Str -> List Nat
+            arguments!(string, pattern);
+
+            call_str_bitcode_fn(
+                env,
+                &[string, pattern],
+                &[],
+                BitcodeReturns::List,
+                bitcode::STR_REGEX_CAPTURE,
+            )
+        }
         ListLen => {
             // List.len : List * -> Nat
             arguments!(list);
@@ -848,9 +873,21 @@ pub(crate) fn run_low_level<'a, 'ctx, 'env>(
                 _ => unreachable!(),
             }
         }
+        NumToStrWith => {
+            // Num.toStrWithLowlevel : Str, Str, Bool, U8, Bool -> Str
+            arguments!(digits, whole_separator, shortest, decimals, scientific);
+
+            call_str_bitcode_fn(
+                env,
+                &[digits, whole_separator],
+                &[shortest, decimals, scientific],
+                BitcodeReturns::Str,
+                bitcode::STR_FORMAT_NUMBER,
+            )
+        }
         NumAbs | NumNeg | NumRound | NumSqrtUnchecked | NumLogUnchecked | NumSin | NumCos
-        | NumCeiling | NumFloor | NumToFrac | NumIsFinite | NumAtan | NumAcos | NumAsin
-        | NumToIntChecked => {
+        | NumCeiling | NumFloor | NumToFrac | NumIsNan | NumIsInfinite | NumIsFinite | NumAtan
+        | NumAcos | NumAsin | NumToIntChecked => {
             arguments_with_layouts!((arg, arg_layout));
 
             match layout_interner.get(arg_layout) {
@@ -879,6 +916,12 @@ pub(crate) fn run_low_level<'a, 'ctx, 'env>(
                             op,
                             float_width,
                         ),
+                        // a Dec is always finite
+                        Decimal if matches!(op, NumIsNan | NumIsInfinite | NumIsFinite) => env
+                            .context
+                            .bool_type()
+                            .const_int((op == NumIsFinite) as u64, false)
+                            .into(),
                         _ => {
                             unreachable!("Compiler bug: tried to run numeric operation {:?} on invalid builtin layout: ({:?})", op, arg_layout);
                         }
@@ -914,6 +957,217 @@ pub(crate) fn run_low_level<'a, 'ctx, 'env>(
                 bitcode::NUM_BYTES_TO_U32,
             )
         }
+        RandomMix64 => {
+            // Random.mix64Lowlevel : U64 -> U64
+            arguments!(seed);
+
+            call_bitcode_fn(env, &[seed], bitcode::RANDOM_MIX64)
+        }
+        RandomMixGamma => {
+            // Random.mixGammaLowlevel : U64 -> U64
+            arguments!(seed);
+
+            call_bitcode_fn(env, &[seed], bitcode::RANDOM_MIX_GAMMA)
+        }
+        RandomTestSeed => {
+            // Random.testSeedLowlevel : {} -> U64
+            call_bitcode_fn(env, &[], bitcode::RANDOM_TEST_SEED)
+        }
+        BytesReadU16 => {
+            // Bytes.readU16Lowlevel : List U8, Nat, Bool -> U16
+            arguments!(list, position, big_endian);
+
+            call_list_bitcode_fn(
+                env,
+                &[list.into_struct_value()],
+                &[position, big_endian],
+                BitcodeReturns::Basic,
+                bitcode::BYTES_READ_U16,
+            )
+        }
+        BytesReadU32 => {
+            // Bytes.readU32Lowlevel : List U8, Nat, Bool -> U32
+            arguments!(list, position, big_endian);
+
+            call_list_bitcode_fn(
+                env,
+                &[list.into_struct_value()],
+                &[position, big_endian],
+                BitcodeReturns::Basic,
+                bitcode::BYTES_READ_U32,
+            )
+        }
+        BytesReadU64 => {
+            // Bytes.readU64Lowlevel : List U8, Nat, Bool -> U64
+            arguments!(list, position, big_endian);
+
+            call_list_bitcode_fn(
+                env,
+                &[list.into_struct_value()],
+                &[position, big_endian],
+                BitcodeReturns::Basic,
+                bitcode::BYTES_READ_U64,
+            )
+        }
+        EncodingEncode => {
+            // Encoding.encodeLowlevel : List U8, U8 -> Str
+            arguments!(list, format);
+
+            call_list_bitcode_fn(
+                env,
+                &[list.into_struct_value()],
+                &[format],
+                BitcodeReturns::Str,
+                bitcode::ENCODING_ENCODE,
+            )
+        }
+        EncodingDecode => {
+            // Encoding.decodeLowlevel : Str, U8 -> List U8
+            arguments!(string, format);
+
+            call_str_bitcode_fn(
+                env,
+                &[string],
+                &[format],
+                BitcodeReturns::List,
+                bitcode::ENCODING_DECODE,
+            )
+        }
+        EncodingCheck => {
+            // Encoding.checkLowlevel : Str, U8 -> Nat
+            arguments!(string, format);
+
+            call_str_bitcode_fn(
+                env,
+                &[string],
+                &[format],
+                BitcodeReturns::Basic,
+                bitcode::ENCODING_CHECK,
+            )
+        }
+        JsonSkipWhitespace => {
+            // Json.skipWhitespaceLowlevel : List U8, Nat -> Nat
+            arguments!(list, index);
+
+            call_list_bitcode_fn(
+                env,
+                &[list.into_struct_value()],
+                &[index],
+                BitcodeReturns::Basic,
+                bitcode::JSON_SKIP_WHITESPACE,
+            )
+        }
+        JsonScanString => {
+            // Json.scanStringLowlevel : List U8, Nat -> U64
+            arguments!(list, index);
+
+            call_list_bitcode_fn(
+                env,
+                &[list.into_struct_value()],
+                &[index],
+                BitcodeReturns::Basic,
+                bitcode::JSON_SCAN_STRING,
+            )
+        }
+        JsonScanValue => {
+            // Json.scanValueLowlevel : List U8, Nat -> U64
+            arguments!(list, index);
+
+            call_list_bitcode_fn(
+                env,
+                &[list.into_struct_value()],
+                &[index],
+                BitcodeReturns::Basic,
+                bitcode::JSON_SCAN_VALUE,
+            )
+        }
+        JsonUnescape => {
+            // Json.unescapeLowlevel : List U8, Nat, Nat -> List U8
+            arguments!(list, start, end);
+
+            call_list_bitcode_fn(
+                env,
+                &[list.into_struct_value()],
+                &[start, end],
+                BitcodeReturns::List,
+                bitcode::JSON_UNESCAPE,
+            )
+        }
+        JsonEscape => {
+            // Json.escapeLowlevel : List U8, Str -> List U8
+            arguments!(list, string);
+
+            match env.target_info.ptr_width() {
+                PtrWidth::Bytes4 => {
+                    // list and string are both stored as structs on the stack on 32-bit targets
+                    call_str_bitcode_fn(
+                        env,
+                        &[list, string],
+                        &[],
+                        BitcodeReturns::List,
+                        bitcode::JSON_ESCAPE,
+                    )
+                }
+                PtrWidth::Bytes8 => {
+                    // on 64-bit targets, strings are stored as pointers, but that is not what zig expects
+                    call_list_bitcode_fn(
+                        env,
+                        &[list.into_struct_value()],
+                        &[string],
+                        BitcodeReturns::List,
+                        bitcode::JSON_ESCAPE,
+                    )
+                }
+            }
+        }
+        TimeFormat => {
+            // Time.formatLowlevel : I64, U32 -> Str
+            arguments!(seconds, nanos);
+
+            call_str_bitcode_fn(
+                env,
+                &[],
+                &[seconds, nanos],
+                BitcodeReturns::Str,
+                bitcode::TIME_FORMAT,
+            )
+        }
+        TimeCheck => {
+            // Time.checkLowlevel : Str -> Nat
+            arguments!(string);
+
+            call_str_bitcode_fn(
+                env,
+                &[string],
+                &[],
+                BitcodeReturns::Basic,
+                bitcode::TIME_CHECK,
+            )
+        }
+        TimeParseSeconds => {
+            // Time.parseSecondsLowlevel : Str -> I64
+            arguments!(string);
+
+            call_str_bitcode_fn(
+                env,
+                &[string],
+                &[],
+                BitcodeReturns::Basic,
+                bitcode::TIME_PARSE_SECONDS,
+            )
+        }
+        TimeParseNanos => {
+            // Time.parseNanosLowlevel : Str -> U32
+            arguments!(string);
+
+            call_str_bitcode_fn(
+                env,
+                &[string],
+                &[],
+                BitcodeReturns::Basic,
+                bitcode::TIME_PARSE_NANOS,
+            )
+        }
         NumCompare => {
             arguments_with_layouts!((lhs_arg, lhs_layout), (rhs_arg, rhs_layout));
 
@@ -1062,6 +1316,66 @@ pub(crate) fn run_low_level<'a, 'ctx, 'env>(
                 op,
             )
         }
+        NumPopCount | NumLeadingZeros | NumTrailingZeros => {
+            arguments_with_layouts!((arg, arg_layout));
+
+            let int_width = intwidth_from_layout(arg_layout);
+            // the result is defined to be the width in bits when the argument is 0
+            let zero_is_poison = env.context.bool_type().const_zero();
+
+            let count = match op {
+                NumPopCount => env.call_intrinsic(&LLVM_CTPOP[int_width], &[arg]),
+                NumLeadingZeros => {
+                    env.call_intrinsic(&LLVM_CTLZ[int_width], &[arg, zero_is_poison.into()])
+                }
+                NumTrailingZeros => {
+                    env.call_intrinsic(&LLVM_CTTZ[int_width], &[arg, zero_is_poison.into()])
+                }
+                _ => unreachable!(),
+            };
+
+            // the count is at most 128, so it always fits in the U8 it's returned as
+            env.builder
+                .build_int_cast_sign_flag(
+                    count.into_int_value(),
+                    env.context.i8_type(),
+                    false,
+                    "count_to_u8",
+                )
+                .into()
+        }
+        NumRotateLeftBy | NumRotateRightBy => {
+            arguments_with_layouts!((arg, arg_layout), (bits, bits_layout));
+
+            let int_width = intwidth_from_layout(arg_layout);
+
+            debug_assert_eq!(bits_layout, Layout::U8);
+            // a funnel shift of a number with itself is a rotation. The amount is taken modulo
+            // the width, and has to have the same type as the number, so the U8 is cast up (or
+            // down, for 8-bit numbers, which doesn't change it)
+            let bits = env.builder.build_int_cast_sign_flag(
+                bits.into_int_value(),
+                arg.get_type().into_int_type(),
+                false,
+                "cast_for_rotate",
+            );
+
+            let intrinsic = match op {
+                NumRotateLeftBy => &LLVM_FSHL[int_width],
+                _ => &LLVM_FSHR[int_width],
+            };
+
+            env.call_intrinsic(intrinsic, &[arg, arg, bits.into()])
+        }
+        NumSwapBytes => {
+            arguments_with_layouts!((arg, arg_layout));
+
+            match intwidth_from_layout(arg_layout) {
+                // a single byte is its own byte swap
+                IntWidth::U8 | IntWidth::I8 => arg,
+                int_width => env.call_intrinsic(&LLVM_BSWAP[int_width], &[arg]),
+            }
+        }
         NumIntCast => {
             arguments!(arg);
 
@@ -1175,7 +1489,7 @@ pub(crate) fn run_low_level<'a, 'ctx, 'env>(
             unimplemented!()
         }
 
-        ListMap | ListMap2 | ListMap3 | ListMap4 | ListSortWith => {
+        ListMap | ListMapConcurrent | ListMap2 | ListMap3 | ListMap4 | ListSortWith => {
             unreachable!("these are higher order, and are handled elsewhere")
         }
 
@@ -2256,6 +2570,10 @@ fn build_float_unary_op<'a, 'ctx, 'env>(
                 "num_round",
             )
         }
+        NumIsNan => call_bitcode_fn(env, &[arg.into()], &bitcode::NUM_IS_NAN[float_width]),
+        NumIsInfinite => {
+            call_bitcode_fn(env, &[arg.into()], &bitcode::NUM_IS_INFINITE[float_width])
+        }
         NumIsFinite => call_bitcode_fn(env, &[arg.into()], &bitcode::NUM_IS_FINITE[float_width]),
 
         // trigonometry
@@ -2359,6 +2677,71 @@ pub(crate) fn run_higher_order_low_level<'a, 'ctx, 'env>(
                 _ => unreachable!("invalid list layout"),
             }
         }
+        ListMapConcurrent { xs } => {
+            // List.mapConcurrent : List before, (before -> after) -> List after
+            let (list, list_layout) = load_symbol_and_layout(scope, xs);
+
+            let (function, closure, closure_layout) = function_details!();
+
+            match (
+                layout_interner.get(list_layout),
+                layout_interner.get(return_layout),
+            ) {
+                (
+                    Layout::Builtin(Builtin::List(element_layout)),
+                    Layout::Builtin(Builtin::List(result_layout)),
+                ) => {
+                    // The function can only run on several threads at once if the reference counts
+                    // of the values it gets or returns (including the ones it captured) are
+                    // updated atomically. Otherwise, map one at a time.
+                    let is_thread_safe = [
+                        element_layout,
+                        result_layout,
+                        closure_layout.runtime_representation(),
+                    ]
+                    .into_iter()
+                    .all(|layout| {
+                        !layout_interner.contains_refcounted(layout)
+                            || env.thread_shared_layouts.contains(layout)
+                    });
+
+                    let argument_layouts = &[element_layout];
+
+                    let roc_function_call = roc_function_call(
+                        env,
+                        layout_interner,
+                        layout_ids,
+                        function,
+                        closure,
+                        closure_layout,
+                        function_owns_closure_data,
+                        argument_layouts,
+                        result_layout,
+                    );
+
+                    if is_thread_safe {
+                        list_map_concurrent(
+                            env,
+                            layout_interner,
+                            roc_function_call,
+                            list,
+                            element_layout,
+                            result_layout,
+                        )
+                    } else {
+                        list_map(
+                            env,
+                            layout_interner,
+                            roc_function_call,
+                            list,
+                            element_layout,
+                            result_layout,
+                        )
+                    }
+                }
+                _ => unreachable!("invalid list layout"),
+            }
+        }
         ListMap2 { xs, ys } => {
             let (list1, list1_layout) = load_symbol_and_layout(scope, xs);
             let (list2, list2_layout) = load_symbol_and_layout(scope, ys);
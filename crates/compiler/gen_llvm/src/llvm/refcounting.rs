@@ -116,13 +116,20 @@ impl<'ctx> PointerToRefcount<'ctx> {
         layout_interner: &mut STLayoutInterner<'a>,
     ) {
         match mode {
-            CallMode::Inc(inc_amount) => self.increment(inc_amount, env),
+            CallMode::Inc(inc_amount) => self.increment(inc_amount, env, layout),
             CallMode::Dec => self.decrement(env, layout_interner, layout),
         }
     }
 
-    fn increment<'a, 'env>(&self, amount: IntValue<'ctx>, env: &Env<'a, 'ctx, 'env>) {
-        incref_pointer(env, self.value, amount);
+    fn increment<'a, 'env>(
+        &self,
+        amount: IntValue<'ctx>,
+        env: &Env<'a, 'ctx, 'env>,
+        layout: InLayout<'a>,
+    ) {
+        let is_atomic = env.thread_shared_layouts.contains(layout);
+
+        incref_pointer(env, self.value, amount, is_atomic);
     }
 
     pub fn decrement<'a, 'env>(
@@ -139,7 +146,13 @@ impl<'ctx> PointerToRefcount<'ctx> {
         let block = env.builder.get_insert_block().expect("to be in a function");
         let di_location = env.builder.get_current_debug_location().unwrap();
 
-        let fn_name = &format!("decrement_refcounted_ptr_{}", alignment);
+        let is_atomic = env.thread_shared_layouts.contains(layout);
+
+        let fn_name = &if is_atomic {
+            format!("decrement_refcounted_ptr_atomic_{}", alignment)
+        } else {
+            format!("decrement_refcounted_ptr_{}", alignment)
+        };
 
         let function = match env.module.get_function(fn_name) {
             Some(function_value) => function_value,
@@ -161,7 +174,7 @@ impl<'ctx> PointerToRefcount<'ctx> {
                 let subprogram = env.new_subprogram(fn_name);
                 function_value.set_subprogram(subprogram);
 
-                Self::build_decrement_function_body(env, function_value, alignment);
+                Self::build_decrement_function_body(env, function_value, alignment, is_atomic);
 
                 function_value
             }
@@ -183,6 +196,7 @@ impl<'ctx> PointerToRefcount<'ctx> {
         env: &Env<'a, 'ctx, 'env>,
         parent: FunctionValue<'ctx>,
         alignment: u32,
+        is_atomic: bool,
     ) {
         let builder = env.builder;
         let ctx = env.context;
@@ -196,6 +210,7 @@ impl<'ctx> PointerToRefcount<'ctx> {
             env,
             parent.get_nth_param(0).unwrap().into_pointer_value(),
             alignment,
+            is_atomic,
         );
 
         builder.build_return(None);
@@ -206,6 +221,7 @@ fn incref_pointer<'a, 'ctx, 'env>(
     env: &Env<'a, 'ctx, 'env>,
     pointer: PointerValue<'ctx>,
     amount: IntValue<'ctx>,
+    is_atomic: bool,
 ) {
     call_void_bitcode_fn(
         env,
@@ -219,7 +235,11 @@ fn incref_pointer<'a, 'ctx, 'env>(
                 .into(),
             amount.into(),
         ],
-        roc_builtins::bitcode::UTILS_INCREF,
+        if is_atomic {
+            roc_builtins::bitcode::UTILS_INCREF_ATOMIC
+        } else {
+            roc_builtins::bitcode::UTILS_INCREF
+        },
     );
 }
 
@@ -227,6 +247,7 @@ fn decref_pointer<'a, 'ctx, 'env>(
     env: &Env<'a, 'ctx, 'env>,
     pointer: PointerValue<'ctx>,
     alignment: u32,
+    is_atomic: bool,
 ) {
     let alignment = env.context.i32_type().const_int(alignment as _, false);
     call_void_bitcode_fn(
@@ -241,7 +262,11 @@ fn decref_pointer<'a, 'ctx, 'env>(
                 .into(),
             alignment.into(),
         ],
-        roc_builtins::bitcode::UTILS_DECREF,
+        if is_atomic {
+            roc_builtins::bitcode::UTILS_DECREF_ATOMIC
+        } else {
+            roc_builtins::bitcode::UTILS_DECREF
+        },
     );
 }
 
@@ -250,6 +275,7 @@ pub fn decref_pointer_check_null<'a, 'ctx, 'env>(
     env: &Env<'a, 'ctx, 'env>,
     pointer: PointerValue<'ctx>,
     alignment: u32,
+    is_atomic: bool,
 ) {
     let alignment = env.context.i32_type().const_int(alignment as _, false);
     call_void_bitcode_fn(
@@ -264,7 +290,11 @@ pub fn decref_pointer_check_null<'a, 'ctx, 'env>(
                 .into(),
             alignment.into(),
         ],
-        roc_builtins::bitcode::UTILS_DECREF_CHECK_NULL,
+        if is_atomic {
+            roc_builtins::bitcode::UTILS_DECREF_CHECK_NULL_ATOMIC
+        } else {
+            roc_builtins::bitcode::UTILS_DECREF_CHECK_NULL
+        },
     );
 }
 
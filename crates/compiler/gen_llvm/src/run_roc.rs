@@ -1,7 +1,7 @@
 use std::mem::MaybeUninit;
 
 use roc_mono::ir::CrashTag;
-use roc_std::RocStr;
+use roc_std::{RocPanic, RocPanicRegion};
 
 /// This must have the same size as the repr() of RocCallResult!
 pub const ROC_CALL_RESULT_DISCRIMINANT_SIZE: usize = std::mem::size_of::<u64>();
@@ -9,7 +9,7 @@ pub const ROC_CALL_RESULT_DISCRIMINANT_SIZE: usize = std::mem::size_of::<u64>();
 #[repr(C)]
 pub struct RocCallResult<T> {
     tag: u64,
-    error_msg: *mut RocStr,
+    error_msg: *mut RocPanic,
     value: MaybeUninit<T>,
 }
 
@@ -21,6 +21,18 @@ impl<T> RocCallResult<T> {
             value: MaybeUninit::new(value),
         }
     }
+
+    /// Where the program crashed, if it did, and the crash has a source region
+    pub fn panic_region(&self) -> Option<RocPanicRegion> {
+        match self.tag {
+            0 => None,
+            _ => {
+                let region = unsafe { (*self.error_msg).region };
+
+                region.is_known().then_some(region)
+            }
+        }
+    }
 }
 
 impl<T: Default> Default for RocCallResult<T> {
@@ -38,7 +50,7 @@ impl<T: Sized> From<RocCallResult<T>> for Result<T, (String, CrashTag)> {
         match call_result.tag {
             0 => Ok(unsafe { call_result.value.assume_init() }),
             n => Err({
-                let msg: &RocStr = unsafe { &*call_result.error_msg };
+                let msg = unsafe { &(*call_result.error_msg).message };
                 let tag = (n - 1) as u32;
                 let tag = tag
                     .try_into()
@@ -1,15 +1,15 @@
 use bitvec::vec::BitVec;
 use bumpalo::collections::{String, Vec};
 
-use roc_builtins::bitcode::{FloatWidth, IntWidth};
+use roc_builtins::bitcode::{self, FloatWidth, IntWidth};
 use roc_collections::all::MutMap;
 use roc_error_macros::internal_error;
 use roc_module::low_level::{LowLevel, LowLevelWrapperType};
 use roc_module::symbol::{Interns, Symbol};
 use roc_mono::code_gen_help::{CodeGenHelp, HelperOp, REFCOUNT_MAX};
 use roc_mono::ir::{
-    BranchInfo, CallType, CrashTag, Expr, JoinPointId, ListLiteralElement, Literal, ModifyRc,
-    Param, Proc, ProcLayout, Stmt,
+    BranchInfo, CallType, CrashRegion, CrashTag, Expr, JoinPointId, ListLiteralElement, Literal,
+    ModifyRc, Param, Proc, ProcLayout, Stmt,
 };
 use roc_mono::layout::{
     Builtin, InLayout, Layout, LayoutIds, LayoutInterner, STLayoutInterner, TagIdIntType,
@@ -20,7 +20,7 @@ use roc_std::RocDec;
 use roc_wasm_module::linking::{DataSymbol, WasmObjectSymbol};
 use roc_wasm_module::sections::{
     ConstExpr, DataMode, DataSegment, Export, Global, GlobalType, Import, ImportDesc, Limits,
-    MemorySection, NameSection,
+    MemorySection, NameSection, OpaqueSection,
 };
 use roc_wasm_module::{
     round_up_to_alignment, Align, ExportType, LocalId, Signature, SymInfo, ValueType, WasmModule,
@@ -31,10 +31,14 @@ use crate::layout::{CallConv, ReturnMethod, WasmLayout};
 use crate::low_level::{call_higher_order_lowlevel, LowLevelCall};
 use crate::storage::{AddressValue, Storage, StoredValue, StoredVarKind};
 use crate::{
-    copy_memory, CopyMemoryConfig, Env, DEBUG_SETTINGS, MEMORY_NAME, PTR_SIZE, PTR_TYPE,
-    TARGET_INFO,
+    copy_memory, fill_memory, CopyMemoryConfig, Env, FillMemoryConfig, DEBUG_SETTINGS, MEMORY_NAME,
+    PTR_SIZE, PTR_TYPE, TARGET_INFO,
 };
 
+/// Shared memories must declare a maximum size, and engines may reserve all of it up front.
+/// 1GiB is enough for most apps and is supported by browsers.
+const MAX_SHARED_MEMORY_PAGES: u32 = 16 * 1024;
+
 #[derive(Clone, Copy, Debug)]
 pub enum ProcSource {
     Roc,
@@ -136,7 +140,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
             // Function-level data
             block_depth: 0,
             joinpoint_label_map: MutMap::default(),
-            code_builder: CodeBuilder::new(env.arena),
+            code_builder: CodeBuilder::new(env.arena, env.features),
             storage: Storage::new(env.arena),
         }
     }
@@ -190,10 +194,23 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         });
 
         // Set the initial size of the memory
-        self.module.memory = MemorySection::new(
-            self.env.arena,
-            stack_heap_boundary + MemorySection::PAGE_SIZE,
-        );
+        let memory_bytes = stack_heap_boundary + MemorySection::PAGE_SIZE;
+        if self.env.features.threads {
+            // Shared memory is created by the runtime, and imported by every thread's instance
+            self.module.memory = MemorySection::new(self.env.arena, 0);
+            self.module.import.imports.push(Import {
+                module: "env",
+                name: MEMORY_NAME,
+                description: ImportDesc::Mem {
+                    limits: Limits::SharedMinMax(
+                        memory_bytes / MemorySection::PAGE_SIZE,
+                        MAX_SHARED_MEMORY_PAGES,
+                    ),
+                },
+            });
+        } else {
+            self.module.memory = MemorySection::new(self.env.arena, memory_bytes);
+        }
 
         // Export the memory so that JS can interact with it
         self.module.export.append(Export {
@@ -284,6 +301,10 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
     }
 
     pub fn finalize(mut self) -> (WasmModule<'a>, BitVec<usize>) {
+        if self.env.features.threads {
+            self.build_init_shared_memory();
+            self.maybe_export_thread_start();
+        }
         self.set_memory_layout(self.env.stack_bytes);
         self.export_globals();
 
@@ -293,6 +314,82 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         (self.module, self.called_fns)
     }
 
+    /// With shared memory, every thread instantiates the module, but only the first one should
+    /// load the data segments. Otherwise it would overwrite data that is already in use!
+    /// So we make the segments passive, and load them in a start function guarded by a flag.
+    /// This is the same thing wasm-ld does for `--shared-memory`.
+    fn build_init_shared_memory(&mut self) {
+        const UNINITIALIZED: i32 = 0;
+        const INITIALIZING: i32 = 1;
+        const INITIALIZED: i32 = 2;
+
+        let flag_addr = round_up_to_alignment!(self.module.data.end_addr, PTR_SIZE);
+        self.module.data.end_addr = flag_addr + PTR_SIZE;
+        let segments = self.module.data.make_passive(self.env.arena);
+
+        let fn_index = self.called_fns.len() as u32;
+        self.called_fns.push(true);
+        self.module.add_function_signature(Signature {
+            param_types: bumpalo::vec![in self.env.arena],
+            ret_type: None,
+        });
+        debug_assert!(self.module.start.is_empty());
+        self.module.start = OpaqueSection::new_start(self.env.arena, fn_index);
+
+        let cb = &mut self.code_builder;
+        cb.i32_const(flag_addr as i32);
+        cb.i32_const(UNINITIALIZED);
+        cb.i32_const(INITIALIZING);
+        cb.i32_atomic_rmw_cmpxchg(0);
+        cb.if_();
+        {
+            // Another thread got here first. Wait for it to finish.
+            // If it has already finished, the flag is not INITIALIZING, so we don't wait at all.
+            cb.i32_const(flag_addr as i32);
+            cb.i32_const(INITIALIZING);
+            cb.i64_const(-1); // no timeout
+            cb.memory_atomic_wait32(0);
+            cb.drop_();
+        }
+        cb.else_();
+        {
+            for (segment_index, (addr, len)) in segments.iter().enumerate() {
+                cb.i32_const(*addr as i32);
+                cb.i32_const(0);
+                cb.i32_const(*len as i32);
+                cb.memory_init(segment_index as u32);
+            }
+            cb.i32_const(flag_addr as i32);
+            cb.i32_const(INITIALIZED);
+            cb.i32_atomic_store(0);
+            cb.i32_const(flag_addr as i32);
+            cb.i32_const(-1); // wake up all waiting threads
+            cb.memory_atomic_notify(0);
+            cb.drop_();
+        }
+        cb.end();
+        cb.build_fn_header_and_footer(&[], 0, None);
+        self.reset();
+    }
+
+    /// The runtime calls `wasi_thread_start` in each new thread created by the `wasi.thread-spawn` import.
+    /// The platform must define it, because it has to set up the thread's stack pointer.
+    fn maybe_export_thread_start(&mut self) {
+        const THREAD_START: &str = "wasi_thread_start";
+
+        if let Ok(sym_index) = self.module.linking.find_internal_symbol(THREAD_START) {
+            if let SymInfo::Function(WasmObjectSymbol::ExplicitlyNamed { index, .. }) =
+                self.module.linking.symbol_table[sym_index]
+            {
+                self.module.export.append(Export {
+                    name: THREAD_START,
+                    ty: ExportType::Func,
+                    index,
+                });
+            }
+        }
+    }
+
     /// If the host has a `main` function then we need to insert a `_start` to call it.
     /// This is something linkers do, and this backend is also a linker!
     fn maybe_call_host_main(&mut self) {
@@ -727,7 +824,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
             Stmt::Expect { .. } => todo!("expect is not implemented in the wasm backend"),
             Stmt::ExpectFx { .. } => todo!("expect-fx is not implemented in the wasm backend"),
 
-            Stmt::Crash(sym, tag) => self.stmt_crash(*sym, *tag),
+            Stmt::Crash(sym, tag, region) => self.stmt_crash(*sym, *tag, *region),
         }
     }
 
@@ -1019,12 +1116,30 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         };
         self.expr_string_literal(msg, local_id, offset);
 
-        self.stmt_crash(msg_sym, CrashTag::Roc);
+        self.stmt_crash(msg_sym, CrashTag::Roc, CrashRegion::UNKNOWN);
     }
 
-    pub fn stmt_crash(&mut self, msg: Symbol, tag: CrashTag) {
-        // load the pointer
-        self.storage.load_symbols(&mut self.code_builder, &[msg]);
+    pub fn stmt_crash(&mut self, msg: Symbol, tag: CrashTag, region: CrashRegion) {
+        // roc_panic gets a pointer to the message, followed by the source region
+        let payload_size = CrashRegion::payload_size(TARGET_INFO);
+        let (frame_ptr, offset) = self
+            .storage
+            .allocate_anonymous_stack_memory(payload_size, PTR_SIZE);
+
+        let msg_size =
+            self.storage
+                .copy_value_to_memory(&mut self.code_builder, frame_ptr, offset, msg);
+
+        for (index, value) in region.to_array().into_iter().enumerate() {
+            self.code_builder.get_local(frame_ptr);
+            self.code_builder.i32_const(value as i32);
+            self.code_builder
+                .i32_store(Align::Bytes4, offset + msg_size + 4 * index as u32);
+        }
+
+        self.code_builder.get_local(frame_ptr);
+        self.code_builder.i32_const(offset as i32);
+        self.code_builder.i32_add();
         self.code_builder.i32_const(tag as _);
         self.call_host_fn_after_loading_args("roc_panic", 2, false);
 
@@ -1598,12 +1713,16 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
 
             // Store 12 bytes of zeros { elements: null, length: 0, capacity: 0 }
             debug_assert_eq!(Builtin::LIST_WORDS, 3);
-            self.code_builder.get_local(local_id);
-            self.code_builder.i64_const(0);
-            self.code_builder.i64_store(Align::Bytes4, offset);
-            self.code_builder.get_local(local_id);
-            self.code_builder.i32_const(0);
-            self.code_builder.i32_store(Align::Bytes4, offset + 8);
+            fill_memory(
+                &mut self.code_builder,
+                FillMemoryConfig {
+                    to_ptr: local_id,
+                    to_offset: offset,
+                    size: Builtin::LIST_WORDS * PTR_SIZE,
+                    alignment_bytes: PTR_SIZE,
+                    byte: 0,
+                },
+            );
         } else {
             internal_error!("Unexpected storage for {:?}", sym)
         }
@@ -1962,6 +2081,10 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
             self.code_builder.i32_add();
         }
 
+        // Keep the size around, in case we run out of memory and need to report it
+        let size_local_id = self.storage.create_anonymous_local(ValueType::I32);
+        self.code_builder.tee_local(size_local_id);
+
         // Provide a constant for the alignment argument
         self.code_builder.i32_const(alignment_bytes as i32);
 
@@ -1972,6 +2095,19 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         let local_id = self.storage.create_anonymous_local(ValueType::I32);
         self.code_builder.tee_local(local_id);
 
+        // If the allocation failed, let the host know. This doesn't return.
+        self.code_builder.i32_eqz();
+        self.code_builder.if_();
+        {
+            self.code_builder.get_local(size_local_id);
+            self.code_builder.i32_const(alignment_bytes as i32);
+            self.call_host_fn_after_loading_args(bitcode::UTILS_OUT_OF_MEMORY, 2, false);
+            self.code_builder.unreachable_();
+        }
+        self.code_builder.end();
+
+        self.code_builder.get_local(local_id);
+
         // Write the initial refcount
         let refcount_offset = extra_bytes - PTR_SIZE;
         let encoded_refcount = (initial_refcount as i32) - 1 + i32::MIN;
@@ -5,14 +5,14 @@ use roc_wasm_module::linking::IndexRelocType;
 
 use roc_error_macros::internal_error;
 use roc_module::symbol::Symbol;
-use roc_wasm_module::opcodes::{OpCode, OpCode::*};
+use roc_wasm_module::opcodes::{AtomicOp, MiscOp, OpCode, OpCode::*, SimdOp};
 use roc_wasm_module::serialize::SerialBuffer;
 use roc_wasm_module::{
     round_up_to_alignment, Align, LocalId, RelocationEntry, ValueType, WasmModule,
     FRAME_ALIGNMENT_BYTES, STACK_POINTER_GLOBAL_ID,
 };
 
-use crate::DEBUG_SETTINGS;
+use crate::{WasmFeatures, DEBUG_SETTINGS};
 
 macro_rules! log_instruction {
     ($($x: expr),+) => {
@@ -106,11 +106,14 @@ pub struct CodeBuilder<'a> {
     /// Relocations for calls to JS imports
     /// When we remove unused imports, the live ones are re-indexed
     import_relocations: Vec<'a, (usize, u32)>,
+
+    /// Post-MVP instructions we're allowed to emit
+    pub features: WasmFeatures,
 }
 
 #[allow(clippy::new_without_default)]
 impl<'a> CodeBuilder<'a> {
-    pub fn new(arena: &'a Bump) -> Self {
+    pub fn new(arena: &'a Bump, features: WasmFeatures) -> Self {
         let mut vm_block_stack = Vec::with_capacity_in(8, arena);
         let function_block = VmBlock {
             opcode: BLOCK,
@@ -127,6 +130,7 @@ impl<'a> CodeBuilder<'a> {
             inner_length: Vec::with_capacity_in(5, arena),
             vm_block_stack,
             import_relocations: Vec::with_capacity_in(0, arena),
+            features,
         }
     }
 
@@ -565,6 +569,19 @@ impl<'a> CodeBuilder<'a> {
         );
     }
 
+    /// Instruction with a prefix byte and a LEB-encoded sub-opcode
+    fn inst_prefixed(
+        &mut self,
+        prefix: OpCode,
+        sub_op: u32,
+        pops: usize,
+        push: bool,
+    ) -> &mut Vec<'a, u8> {
+        self.inst_base(prefix, pops, push);
+        self.code.encode_u32(sub_op);
+        &mut self.code
+    }
+
     /**********************************************************
 
         INSTRUCTION METHODS
@@ -719,6 +736,82 @@ impl<'a> CodeBuilder<'a> {
         self.code.push(0);
     }
 
+    /// Bulk memory proposal (also available with threads)
+    pub fn memory_init(&mut self, segment_index: u32) {
+        debug_assert!(self.features.bulk_memory || self.features.threads);
+        let code = self.inst_prefixed(MISCPREFIX, MiscOp::MEMORYINIT as u32, 3, false);
+        code.encode_u32(segment_index);
+        code.push(0); // memory index
+        log_instruction!("MEMORYINIT {}\t{:?}", segment_index, self.vm_block_stack);
+    }
+    /// Bulk memory proposal
+    pub fn memory_copy(&mut self) {
+        debug_assert!(self.features.bulk_memory);
+        let code = self.inst_prefixed(MISCPREFIX, MiscOp::MEMORYCOPY as u32, 3, false);
+        code.push(0); // destination memory index
+        code.push(0); // source memory index
+        log_instruction!("MEMORYCOPY\t\t{:?}", self.vm_block_stack);
+    }
+    /// Bulk memory proposal
+    pub fn memory_fill(&mut self) {
+        debug_assert!(self.features.bulk_memory);
+        let code = self.inst_prefixed(MISCPREFIX, MiscOp::MEMORYFILL as u32, 3, false);
+        code.push(0); // memory index
+        log_instruction!("MEMORYFILL\t\t{:?}", self.vm_block_stack);
+    }
+    /// SIMD proposal
+    pub fn v128_load(&mut self, align: Align, offset: u32) {
+        debug_assert!(self.features.simd128);
+        let code = self.inst_prefixed(SIMDPREFIX, SimdOp::V128LOAD as u32, 1, true);
+        code.push(align as u8);
+        code.encode_u32(offset);
+        log_instruction!(
+            "V128LOAD   {:?} {}\t{:?}",
+            align,
+            offset,
+            self.vm_block_stack
+        );
+    }
+    /// SIMD proposal
+    pub fn v128_store(&mut self, align: Align, offset: u32) {
+        debug_assert!(self.features.simd128);
+        let code = self.inst_prefixed(SIMDPREFIX, SimdOp::V128STORE as u32, 2, false);
+        code.push(align as u8);
+        code.encode_u32(offset);
+        log_instruction!(
+            "V128STORE  {:?} {}\t{:?}",
+            align,
+            offset,
+            self.vm_block_stack
+        );
+    }
+
+    /// Threads proposal. Atomic accesses must always be naturally aligned.
+    fn inst_atomic(&mut self, op: AtomicOp, pops: usize, push: bool, offset: u32) {
+        debug_assert!(self.features.threads);
+        let code = self.inst_prefixed(ATOMICPREFIX, op as u32, pops, push);
+        code.push(Align::Bytes4 as u8);
+        code.encode_u32(offset);
+        log_instruction!(
+            "{:10} {}\t{:?}",
+            format!("{:?}", op),
+            offset,
+            self.vm_block_stack
+        );
+    }
+    pub fn memory_atomic_notify(&mut self, offset: u32) {
+        self.inst_atomic(AtomicOp::MEMORYATOMICNOTIFY, 2, true, offset);
+    }
+    pub fn memory_atomic_wait32(&mut self, offset: u32) {
+        self.inst_atomic(AtomicOp::MEMORYATOMICWAIT32, 3, true, offset);
+    }
+    pub fn i32_atomic_store(&mut self, offset: u32) {
+        self.inst_atomic(AtomicOp::I32ATOMICSTORE, 2, false, offset);
+    }
+    pub fn i32_atomic_rmw_cmpxchg(&mut self, offset: u32) {
+        self.inst_atomic(AtomicOp::I32ATOMICRMWCMPXCHG, 3, true, offset);
+    }
+
     fn log_const<T>(&self, opcode: OpCode, x: T)
     where
         T: std::fmt::Debug + std::fmt::Display,
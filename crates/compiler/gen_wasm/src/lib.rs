@@ -6,6 +6,7 @@ mod low_level;
 mod storage;
 
 // Helpers for interfacing to a Wasm module from outside
+pub mod source_map;
 pub mod wasm32_result;
 pub mod wasm32_sized;
 
@@ -46,12 +47,51 @@ pub struct Env<'a> {
     pub module_id: ModuleId,
     pub exposed_to_host: MutSet<Symbol>,
     pub stack_bytes: u32,
+    pub features: WasmFeatures,
 }
 
 impl Env<'_> {
     pub const DEFAULT_STACK_BYTES: u32 = 1024 * 1024;
 }
 
+/// Post-MVP Wasm features that we are allowed to use in the generated code.
+/// They are all disabled by default, since not every engine supports them.
+/// When a feature is disabled, we fall back to equivalent MVP instructions.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct WasmFeatures {
+    /// `memory.copy` and `memory.fill`
+    pub bulk_memory: bool,
+    /// 128-bit SIMD loads and stores
+    pub simd128: bool,
+    /// Shared memory and atomic refcounting, for multi-threaded apps.
+    /// The memory is imported from `env.memory`, so that all threads can share it.
+    pub threads: bool,
+}
+
+impl WasmFeatures {
+    pub const NAMES: [&'static str; 3] = ["bulk-memory", "simd128", "threads"];
+
+    /// Parse a comma-separated list of feature names, like `bulk-memory,simd128`
+    pub fn parse(names: &str) -> Result<Self, String> {
+        let mut features = WasmFeatures::default();
+        for name in names.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            match name {
+                "bulk-memory" => features.bulk_memory = true,
+                "simd128" => features.simd128 = true,
+                "threads" => features.threads = true,
+                _ => {
+                    return Err(format!(
+                        "Unknown Wasm feature `{}`. The supported features are: {}",
+                        name,
+                        Self::NAMES.join(", ")
+                    ))
+                }
+            }
+        }
+        Ok(features)
+    }
+}
+
 /// Parse the preprocessed host binary
 /// If successful, the module can be passed to build_app_binary
 pub fn parse_host<'a>(arena: &'a Bump, host_bytes: &[u8]) -> Result<WasmModule<'a>, ParseError> {
@@ -81,6 +121,23 @@ pub fn build_app_binary<'a, 'r>(
     buffer
 }
 
+/// The Wasm function index of each Roc procedure, for building a source map.
+/// Must be called before `build_app_module`, with the same arguments, since that consumes `procedures`.
+pub fn app_fn_symbols(
+    host_module: &WasmModule,
+    procedures: &MutMap<(Symbol, ProcLayout), Proc>,
+) -> std::vec::Vec<(u32, Symbol)> {
+    let fn_index_offset: u32 =
+        host_module.import.function_count() as u32 + host_module.code.function_count;
+
+    // Same order as the pre-pass in build_app_module
+    procedures
+        .keys()
+        .enumerate()
+        .map(|(i, (sym, _))| (fn_index_offset + i as u32, *sym))
+        .collect()
+}
+
 /// Generate an unserialized Wasm module
 /// Shared by all consumers of gen_wasm: roc_build, roc_repl_wasm, and test_gen
 /// (roc_repl_wasm and test_gen will add more generated code for a wrapper function
@@ -199,6 +256,18 @@ pub struct CopyMemoryConfig {
     alignment_bytes: u32,
 }
 
+/// Below this size, a sequence of loads and stores is faster than a `memory.copy` or `memory.fill`
+const BULK_MEMORY_MIN_BYTES: u32 = 64;
+
+/// Push `ptr + offset` onto the VM stack, for instructions that don't take an offset immediate
+fn push_address(code_builder: &mut CodeBuilder, ptr: LocalId, offset: u32) {
+    code_builder.get_local(ptr);
+    if offset != 0 {
+        code_builder.i32_const(offset as i32);
+        code_builder.i32_add();
+    }
+}
+
 pub fn copy_memory(code_builder: &mut CodeBuilder, config: CopyMemoryConfig) {
     if config.from_ptr == config.to_ptr && config.from_offset == config.to_offset {
         return;
@@ -207,8 +276,26 @@ pub fn copy_memory(code_builder: &mut CodeBuilder, config: CopyMemoryConfig) {
         return;
     }
 
+    let features = code_builder.features;
+    if features.bulk_memory && config.size >= BULK_MEMORY_MIN_BYTES {
+        push_address(code_builder, config.to_ptr, config.to_offset);
+        push_address(code_builder, config.from_ptr, config.from_offset);
+        code_builder.i32_const(config.size as i32);
+        code_builder.memory_copy();
+        return;
+    }
+
     let alignment = Align::from(config.alignment_bytes);
     let mut i = 0;
+    if features.simd128 {
+        while config.size - i >= 16 {
+            code_builder.get_local(config.to_ptr);
+            code_builder.get_local(config.from_ptr);
+            code_builder.v128_load(alignment, i + config.from_offset);
+            code_builder.v128_store(alignment, i + config.to_offset);
+            i += 16;
+        }
+    }
     while config.size - i >= 8 {
         code_builder.get_local(config.to_ptr);
         code_builder.get_local(config.from_ptr);
@@ -232,6 +319,50 @@ pub fn copy_memory(code_builder: &mut CodeBuilder, config: CopyMemoryConfig) {
     }
 }
 
+pub struct FillMemoryConfig {
+    to_ptr: LocalId,
+    to_offset: u32,
+    size: u32,
+    alignment_bytes: u32,
+    byte: u8,
+}
+
+pub fn fill_memory(code_builder: &mut CodeBuilder, config: FillMemoryConfig) {
+    if config.size == 0 {
+        return;
+    }
+
+    if code_builder.features.bulk_memory && config.size >= BULK_MEMORY_MIN_BYTES {
+        push_address(code_builder, config.to_ptr, config.to_offset);
+        code_builder.i32_const(config.byte as i32);
+        code_builder.i32_const(config.size as i32);
+        code_builder.memory_fill();
+        return;
+    }
+
+    let alignment = Align::from(config.alignment_bytes);
+    let pattern = u64::from_ne_bytes([config.byte; 8]);
+    let mut i = 0;
+    while config.size - i >= 8 {
+        code_builder.get_local(config.to_ptr);
+        code_builder.i64_const(pattern as i64);
+        code_builder.i64_store(alignment, i + config.to_offset);
+        i += 8;
+    }
+    if config.size - i >= 4 {
+        code_builder.get_local(config.to_ptr);
+        code_builder.i32_const(pattern as i32);
+        code_builder.i32_store(alignment, i + config.to_offset);
+        i += 4;
+    }
+    while config.size - i > 0 {
+        code_builder.get_local(config.to_ptr);
+        code_builder.i32_const(config.byte as i32);
+        code_builder.i32_store8(alignment, i + config.to_offset);
+        i += 1;
+    }
+}
+
 pub struct WasmDebugSettings {
     proc_start_end: bool,
     user_procs_ir: bool,
@@ -296,6 +296,8 @@ impl<'a> LowLevelCall<'a> {
             }
             StrWithCapacity => self.load_args_and_call_zig(backend, bitcode::STR_WITH_CAPACITY),
             StrGraphemes => self.load_args_and_call_zig(backend, bitcode::STR_GRAPHEMES),
+            StrMatches => self.load_args_and_call_zig(backend, bitcode::STR_REGEX_MATCHES),
+            StrCapture => self.load_args_and_call_zig(backend, bitcode::STR_REGEX_CAPTURE),
 
             // List
             ListLen => match backend.storage.get(&self.arguments[0]) {
@@ -340,7 +342,7 @@ impl<'a> LowLevelCall<'a> {
 
             ListIsUnique => self.load_args_and_call_zig(backend, bitcode::LIST_IS_UNIQUE),
 
-            ListMap | ListMap2 | ListMap3 | ListMap4 | ListSortWith => {
+            ListMap | ListMapConcurrent | ListMap2 | ListMap3 | ListMap4 | ListSortWith => {
                 internal_error!("HigherOrder lowlevels should not be handled here")
             }
 
@@ -845,6 +847,7 @@ impl<'a> LowLevelCall<'a> {
             },
 
             NumToStr => self.num_to_str(backend),
+            NumToStrWith => self.load_args_and_call_zig(backend, bitcode::STR_FORMAT_NUMBER),
             NumAddChecked => {
                 let arg_layout = backend.storage.symbol_layouts[&self.arguments[0]];
                 match backend.layout_interner.get(arg_layout) {
@@ -1555,6 +1558,22 @@ impl<'a> LowLevelCall<'a> {
                 self.load_args_and_call_zig(backend, &bitcode::NUM_POW_INT[width])
             }
 
+            NumIsNan | NumIsInfinite => {
+                let arg_layout = backend.storage.symbol_layouts[&self.arguments[0]];
+                match backend.layout_interner.get(arg_layout) {
+                    Layout::Builtin(Builtin::Float(width)) => {
+                        let intrinsic = match self.lowlevel {
+                            NumIsNan => &bitcode::NUM_IS_NAN,
+                            _ => &bitcode::NUM_IS_INFINITE,
+                        };
+
+                        self.load_args_and_call_zig(backend, &intrinsic[width])
+                    }
+                    // a Dec is never NaN or infinite
+                    Layout::Builtin(Builtin::Decimal) => backend.code_builder.i32_const(0),
+                    x => internal_error!("{:?} is not defined for {:?}", self.lowlevel, x),
+                }
+            }
             NumIsFinite => num_is_finite(backend, self.arguments[0]),
 
             NumAtan => match self.ret_layout_raw {
@@ -1577,6 +1596,29 @@ impl<'a> LowLevelCall<'a> {
             },
             NumBytesToU16 => self.load_args_and_call_zig(backend, bitcode::NUM_BYTES_TO_U16),
             NumBytesToU32 => self.load_args_and_call_zig(backend, bitcode::NUM_BYTES_TO_U32),
+            RandomMix64 => self.load_args_and_call_zig(backend, bitcode::RANDOM_MIX64),
+            RandomMixGamma => self.load_args_and_call_zig(backend, bitcode::RANDOM_MIX_GAMMA),
+            RandomTestSeed => {
+                // the argument is `{}`, so there's nothing to pass
+                backend.call_host_fn_after_loading_args(bitcode::RANDOM_TEST_SEED, 0, true);
+            }
+            BytesReadU16 => self.load_args_and_call_zig(backend, bitcode::BYTES_READ_U16),
+            BytesReadU32 => self.load_args_and_call_zig(backend, bitcode::BYTES_READ_U32),
+            BytesReadU64 => self.load_args_and_call_zig(backend, bitcode::BYTES_READ_U64),
+            EncodingEncode => self.load_args_and_call_zig(backend, bitcode::ENCODING_ENCODE),
+            EncodingDecode => self.load_args_and_call_zig(backend, bitcode::ENCODING_DECODE),
+            EncodingCheck => self.load_args_and_call_zig(backend, bitcode::ENCODING_CHECK),
+            JsonSkipWhitespace => {
+                self.load_args_and_call_zig(backend, bitcode::JSON_SKIP_WHITESPACE)
+            }
+            JsonScanString => self.load_args_and_call_zig(backend, bitcode::JSON_SCAN_STRING),
+            JsonScanValue => self.load_args_and_call_zig(backend, bitcode::JSON_SCAN_VALUE),
+            JsonUnescape => self.load_args_and_call_zig(backend, bitcode::JSON_UNESCAPE),
+            JsonEscape => self.load_args_and_call_zig(backend, bitcode::JSON_ESCAPE),
+            TimeFormat => self.load_args_and_call_zig(backend, bitcode::TIME_FORMAT),
+            TimeCheck => self.load_args_and_call_zig(backend, bitcode::TIME_CHECK),
+            TimeParseSeconds => self.load_args_and_call_zig(backend, bitcode::TIME_PARSE_SECONDS),
+            TimeParseNanos => self.load_args_and_call_zig(backend, bitcode::TIME_PARSE_NANOS),
             NumBitwiseAnd => {
                 self.load_args(backend);
                 match CodeGenNumType::from(self.ret_layout) {
@@ -1712,6 +1754,25 @@ impl<'a> LowLevelCall<'a> {
                     _ => panic_ret_type(),
                 }
             }
+            NumPopCount | NumLeadingZeros | NumTrailingZeros | NumRotateLeftBy
+            | NumRotateRightBy | NumSwapBytes => {
+                let arg_layout = backend.storage.symbol_layouts[&self.arguments[0]];
+                let width = match backend.layout_interner.get(arg_layout) {
+                    Layout::Builtin(Builtin::Int(w)) => w,
+                    x => internal_error!("{:?} is not defined for {:?}", self.lowlevel, x),
+                };
+
+                let intrinsic = match self.lowlevel {
+                    NumPopCount => &bitcode::NUM_POP_COUNT,
+                    NumLeadingZeros => &bitcode::NUM_LEADING_ZEROS,
+                    NumTrailingZeros => &bitcode::NUM_TRAILING_ZEROS,
+                    NumRotateLeftBy => &bitcode::NUM_ROTATE_LEFT,
+                    NumRotateRightBy => &bitcode::NUM_ROTATE_RIGHT,
+                    _ => &bitcode::NUM_SWAP_BYTES,
+                };
+
+                self.load_args_and_call_zig(backend, &intrinsic[width])
+            }
             NumIntCast => {
                 let arg_layout = backend.storage.symbol_layouts[&self.arguments[0]];
                 let arg_type = CodeGenNumType::from(arg_layout);
@@ -1886,8 +1947,20 @@ impl<'a> LowLevelCall<'a> {
                 self.load_args(backend);
                 backend.code_builder.i32_eqz();
             }
-            RefCountInc => self.load_args_and_call_zig(backend, bitcode::UTILS_INCREF),
-            RefCountDec => self.load_args_and_call_zig(backend, bitcode::UTILS_DECREF),
+            RefCountInc => {
+                if backend.env.features.threads {
+                    self.load_args_and_call_zig(backend, bitcode::UTILS_INCREF_ATOMIC)
+                } else {
+                    self.load_args_and_call_zig(backend, bitcode::UTILS_INCREF)
+                }
+            }
+            RefCountDec => {
+                if backend.env.features.threads {
+                    self.load_args_and_call_zig(backend, bitcode::UTILS_DECREF_ATOMIC)
+                } else {
+                    self.load_args_and_call_zig(backend, bitcode::UTILS_DECREF)
+                }
+            }
 
             PtrCast => {
                 let code_builder = &mut backend.code_builder;
@@ -2255,9 +2328,11 @@ pub fn call_higher_order_lowlevel<'a>(
             .unwrap();
         match op {
             ListSortWith { .. } => ProcSource::HigherOrderCompare(passed_proc_index),
-            ListMap { .. } | ListMap2 { .. } | ListMap3 { .. } | ListMap4 { .. } => {
-                ProcSource::HigherOrderMapper(passed_proc_index)
-            }
+            ListMap { .. }
+            | ListMapConcurrent { .. }
+            | ListMap2 { .. }
+            | ListMap3 { .. }
+            | ListMap4 { .. } => ProcSource::HigherOrderMapper(passed_proc_index),
         }
     };
     let wrapper_sym = backend.create_symbol(&format!("#wrap#{:?}", fn_name));
@@ -2319,7 +2394,8 @@ pub fn call_higher_order_lowlevel<'a>(
     };
 
     match op {
-        ListMap { xs } => list_map_n(
+        // Wasm has no threads, so `List.mapConcurrent` maps one element at a time, like `List.map`
+        ListMap { xs } | ListMapConcurrent { xs } => list_map_n(
             bitcode::LIST_MAP,
             backend,
             &[*xs],
@@ -0,0 +1,259 @@
+//! Source maps for the generated Wasm, so that browser devtools can show Roc source code.
+//!
+//! We use the [Source Map v3](https://sourcemaps.info/spec.html) format, which browsers
+//! support for Wasm as well as JS. A Wasm binary has no lines, so everything is on "line" 0
+//! and the "column" is the byte offset in the file.
+//!
+//! The mapping is at the granularity of procedures. Each Roc function body points to the
+//! start of the definition it was specialized from.
+
+use std::path::PathBuf;
+
+use bumpalo::Bump;
+use roc_collections::all::MutMap;
+use roc_module::symbol::{ModuleId, Symbol};
+use roc_region::all::{LineInfo, Region};
+use roc_wasm_module::parse::ParseError;
+use roc_wasm_module::sections::{update_section_size, write_custom_section_header};
+use roc_wasm_module::{SerialBuffer, Serialize, WasmModule};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Mapping {
+    wasm_offset: u32,
+    source_index: u32,
+    line: u32,
+    column: u32,
+}
+
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    sources: Vec<String>,
+    sources_content: Vec<String>,
+    mappings: Vec<Mapping>,
+}
+
+impl SourceMap {
+    /// Map each app function in a serialized module back to the Roc definition it came from.
+    ///   module_bytes   the final Wasm binary
+    ///   app_fns        the function index of each Roc procedure, from `app_fn_symbols`
+    ///   def_regions    where each top-level def is in its module's source
+    ///   sources        path and source code of each module
+    pub fn new(
+        arena: &Bump,
+        module_bytes: &[u8],
+        app_fns: &[(u32, Symbol)],
+        def_regions: &MutMap<Symbol, Region>,
+        sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    ) -> Result<Self, ParseError> {
+        let require_relocatable = false;
+        let module = WasmModule::preload(arena, module_bytes, require_relocatable)?;
+        let import_fn_count = module.import.function_count() as u32;
+
+        let mut source_map = SourceMap::default();
+        let mut source_indices: MutMap<ModuleId, (u32, LineInfo)> = MutMap::default();
+
+        for (fn_index, symbol) in app_fns.iter() {
+            // Dead imports are replaced with dummy functions, so indices are stable.
+            let code_index = match fn_index.checked_sub(import_fn_count) {
+                Some(i) => i as usize,
+                None => continue,
+            };
+            let (fn_offset, region) = match (
+                module.code.function_offsets.get(code_index),
+                def_regions.get(symbol),
+            ) {
+                (Some(offset), Some(region)) => (*offset, *region),
+                _ => continue,
+            };
+
+            let module_id = symbol.module_id();
+            if !source_indices.contains_key(&module_id) {
+                let (path, src) = match sources.get(&module_id) {
+                    Some(source) => source,
+                    None => continue,
+                };
+                let index = source_map.sources.len() as u32;
+                source_map.sources.push(path.to_string_lossy().into_owned());
+                source_map.sources_content.push(src.to_string());
+                source_indices.insert(module_id, (index, LineInfo::new(src)));
+            }
+            let (source_index, line_info) = &source_indices[&module_id];
+
+            let start = line_info.convert_region(region).start;
+            source_map.mappings.push(Mapping {
+                wasm_offset: module.code.section_offset + fn_offset,
+                source_index: *source_index,
+                line: start.line,
+                column: start.column,
+            });
+        }
+
+        source_map.mappings.sort_unstable();
+        Ok(source_map)
+    }
+
+    pub fn to_json(&self) -> String {
+        let json = SourceMapJson {
+            version: 3,
+            sources: &self.sources,
+            sources_content: &self.sources_content,
+            names: &[],
+            mappings: self.mappings_string(),
+        };
+        serde_json::to_string(&json).expect("a source map is always valid JSON")
+    }
+
+    /// A URL that embeds the whole source map, so we don't need a separate file
+    pub fn to_data_url(&self) -> String {
+        let mut url = String::from("data:application/json;base64,");
+        write_base64(&mut url, self.to_json().as_bytes());
+        url
+    }
+
+    /// Every field of a segment is relative to the previous segment
+    fn mappings_string(&self) -> String {
+        let mut out = String::new();
+        let mut prev = Mapping {
+            wasm_offset: 0,
+            source_index: 0,
+            line: 0,
+            column: 0,
+        };
+        for (i, mapping) in self.mappings.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            write_vlq(
+                &mut out,
+                mapping.wasm_offset as i64 - prev.wasm_offset as i64,
+            );
+            write_vlq(
+                &mut out,
+                mapping.source_index as i64 - prev.source_index as i64,
+            );
+            write_vlq(&mut out, mapping.line as i64 - prev.line as i64);
+            write_vlq(&mut out, mapping.column as i64 - prev.column as i64);
+            prev = *mapping;
+        }
+        out
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SourceMapJson<'a> {
+    version: u32,
+    sources: &'a [String],
+    sources_content: &'a [String],
+    names: &'a [String],
+    mappings: String,
+}
+
+/// Append a `sourceMappingURL` custom section to a serialized Wasm module
+pub fn append_source_mapping_url<T: SerialBuffer>(buffer: &mut T, url: &str) {
+    let header_indices = write_custom_section_header(buffer, "sourceMappingURL");
+    url.serialize(buffer);
+    update_section_size(buffer, header_indices);
+}
+
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Base64 variable-length quantity, with the sign in the lowest bit
+fn write_vlq(out: &mut String, value: i64) {
+    let mut vlq = if value < 0 {
+        ((-value) << 1) | 1
+    } else {
+        value << 1
+    } as u64;
+    loop {
+        let mut digit = (vlq & 0b11111) as usize;
+        vlq >>= 5;
+        if vlq != 0 {
+            digit |= 0b100000;
+        }
+        out.push(BASE64_CHARS[digit] as char);
+        if vlq == 0 {
+            break;
+        }
+    }
+}
+
+fn write_base64(out: &mut String, bytes: &[u8]) {
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as usize) << 16 | (b[1] as usize) << 8 | b[2] as usize;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_CHARS[(n >> (18 - 6 * i)) & 0b111111] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vlq(value: i64) -> String {
+        let mut s = String::new();
+        write_vlq(&mut s, value);
+        s
+    }
+
+    #[test]
+    fn test_vlq() {
+        assert_eq!(vlq(0), "A");
+        assert_eq!(vlq(1), "C");
+        assert_eq!(vlq(-1), "D");
+        assert_eq!(vlq(15), "e");
+        assert_eq!(vlq(16), "gB");
+        assert_eq!(vlq(123), "2H");
+        assert_eq!(vlq(-123), "3H");
+    }
+
+    #[test]
+    fn test_base64() {
+        let encode = |bytes: &[u8]| {
+            let mut s = String::new();
+            write_base64(&mut s, bytes);
+            s
+        };
+        assert_eq!(encode(b""), "");
+        assert_eq!(encode(b"f"), "Zg==");
+        assert_eq!(encode(b"fo"), "Zm8=");
+        assert_eq!(encode(b"foo"), "Zm9v");
+        assert_eq!(encode(b"foob"), "Zm9vYg==");
+    }
+
+    #[test]
+    fn test_mappings() {
+        let source_map = SourceMap {
+            sources: vec!["Main.roc".into()],
+            sources_content: vec!["app \"x\"\n".into()],
+            mappings: vec![
+                Mapping {
+                    wasm_offset: 100,
+                    source_index: 0,
+                    line: 2,
+                    column: 0,
+                },
+                Mapping {
+                    wasm_offset: 116,
+                    source_index: 0,
+                    line: 5,
+                    column: 4,
+                },
+            ],
+        };
+        assert_eq!(
+            source_map.to_json(),
+            r#"{"version":3,"sources":["Main.roc"],"sourcesContent":["app \"x\"\n"],"names":[],"mappings":"oGAEA,gBAGI"}"#
+        );
+    }
+}
@@ -26,6 +26,13 @@ const MODULES: &[(ModuleId, &str)] = &[
     (ModuleId::DECODE, "Decode.roc"),
     (ModuleId::HASH, "Hash.roc"),
     (ModuleId::JSON, "Json.roc"),
+    (ModuleId::ASYNC, "Async.roc"),
+    (ModuleId::RANDOM, "Random.roc"),
+    (ModuleId::SORTED_DICT, "SortedDict.roc"),
+    (ModuleId::SORTED_SET, "SortedSet.roc"),
+    (ModuleId::BYTES, "Bytes.roc"),
+    (ModuleId::ENCODING, "Encoding.roc"),
+    (ModuleId::TIME, "Time.roc"),
 ];
 
 fn main() {
@@ -70,7 +77,7 @@ fn write_types_for_module_dummy(output_path: &Path) {
 fn write_types_for_module_real(module_id: ModuleId, filename: &str, output_path: &Path) {
     use roc_can::module::TypeState;
     use roc_load_internal::file::{LoadingProblem, Threading};
-    use roc_reporting::cli::report_problems;
+    use roc_reporting::cli::{report_problems, WarningLevels};
 
     let arena = Bump::new();
     let cwd = std::env::current_dir().unwrap();
@@ -92,7 +99,7 @@ fn write_types_for_module_real(module_id: ModuleId, filename: &str, output_path:
 
     let mut module = match res_module {
         Ok(v) => v,
-        Err(LoadingProblem::FormattedReport(report)) => {
+        Err(LoadingProblem::FormattedReport(report, _)) => {
             panic!("{}", report);
         }
         Err(other) => {
@@ -106,6 +113,7 @@ fn write_types_for_module_real(module_id: ModuleId, filename: &str, output_path:
         &module.interns,
         &mut module.can_problems,
         &mut module.type_problems,
+        &WarningLevels::default(),
     );
 
     if problems.errors + problems.warnings > 0 {
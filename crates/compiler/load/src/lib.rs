@@ -16,11 +16,13 @@ const SKIP_SUBS_CACHE: bool = {
     }
 };
 
+pub use roc_load_internal::cancel::CancellationToken;
 pub use roc_load_internal::docs;
 pub use roc_load_internal::file::{
     EntryPoint, ExecutionMode, ExpectMetadata, Expectations, LoadConfig, LoadResult, LoadStart,
     LoadedModule, LoadingProblem, MonomorphizedModule, Phase, Threading,
 };
+pub use roc_load_internal::plugin;
 
 #[allow(clippy::too_many_arguments)]
 fn load<'a>(
@@ -65,6 +67,10 @@ pub fn load_single_threaded<'a>(
         render,
         palette,
         exec_mode,
+        true,
+        false,
+        Default::default(),
+        Default::default(),
         roc_cache_dir,
     )
 }
@@ -0,0 +1,30 @@
+//! Stopping a load partway through, e.g. when an editor's check of a file is stale because the
+//! file was edited again.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Cancels a load it's given to. Clones share the same state, so one can be kept by whoever wants
+/// to cancel, while another is given to the load. The default is never cancelled.
+///
+/// Cancellation is cooperative: the load checks the token between tasks, like parsing or solving
+/// one module, so a task which has already started runs to completion first.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes loads using this token stop, with [LoadingProblem::Cancelled], as soon as they can.
+    /// Once cancelled, a token stays cancelled.
+    ///
+    /// [LoadingProblem::Cancelled]: crate::file::LoadingProblem::Cancelled
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
@@ -7,12 +7,19 @@ use roc_module::symbol::{IdentIds, ModuleId, ModuleIds, Symbol};
 use roc_parse::ast::AssignedField;
 use roc_parse::ast::{self, ExtractSpaces, TypeHeader};
 use roc_parse::ast::{CommentOrNewline, TypeDef, ValueDef};
+use roc_region::all::Region;
 
 // Documentation generation requirements
 
 #[derive(Debug)]
 pub struct ModuleDocumentation {
     pub name: String,
+    /// The shorthand of the package this module was imported from (e.g. `pf`), or `None` if it
+    /// belongs to the package we're generating docs for.
+    pub package: Option<String>,
+    /// Whether this is a hosted module, whose definitions are effects provided by the platform's
+    /// host. The host implements each one as a `roc_fx_` function, e.g. `roc_fx_putLine`.
+    pub is_hosted: bool,
     pub entries: Vec<DocEntry>,
     pub scope: Scope,
     pub exposed_symbols: VecSet<Symbol>,
@@ -28,6 +35,8 @@ pub enum DocEntry {
 pub struct DocDef {
     pub name: String,
     pub symbol: Symbol,
+    /// Where the def is in its module's source
+    pub region: Region,
     pub type_vars: Vec<String>,
     pub type_annotation: TypeAnnotation,
     pub docs: Option<String>,
@@ -96,6 +105,8 @@ pub fn generate_module_docs(
     home: ModuleId,
     module_ids: &ModuleIds,
     module_name: ModuleName,
+    package: Option<&str>,
+    is_hosted: bool,
     parsed_defs: &roc_parse::ast::Defs,
     exposed_module_ids: &[ModuleId],
     exposed_symbols: VecSet<Symbol>,
@@ -112,6 +123,8 @@ pub fn generate_module_docs(
 
     ModuleDocumentation {
         name: module_name.as_str().to_string(),
+        package: package.map(str::to_string),
+        is_hosted,
         scope,
         entries,
         exposed_symbols,
@@ -177,6 +190,7 @@ fn generate_entry_docs(
         scratchpad.extend(spaces_before);
 
         let docs = comments_or_new_lines_to_docs(&scratchpad);
+        let region = defs.regions[index];
 
         match either_index.split() {
             Err(value_index) => match &defs.value_defs[value_index.index()] {
@@ -186,6 +200,7 @@ fn generate_entry_docs(
                         if let Some(ident_id) = ident_ids.get_id(identifier) {
                             let name = identifier.to_string();
                             let doc_def = DocDef {
+                                region,
                                 name,
                                 symbol: Symbol::new(home, ident_id),
                                 type_annotation: type_to_docs(false, loc_ann.value),
@@ -206,6 +221,7 @@ fn generate_entry_docs(
                         // Check if this module exposes the def
                         if let Some(ident_id) = ident_ids.get_id(identifier) {
                             let doc_def = DocDef {
+                                region,
                                 name: identifier.to_string(),
                                 type_annotation: type_to_docs(false, ann_type.value),
                                 type_vars: Vec::new(),
@@ -232,6 +248,10 @@ fn generate_entry_docs(
                 ValueDef::ExpectFx { .. } => {
                     // Don't generate docs for `expect-fx`s
                 }
+
+                ValueDef::Bench { .. } => {
+                    // Don't generate docs for `bench`es
+                }
             },
             Ok(type_index) => match &defs.type_defs[type_index.index()] {
                 TypeDef::Alias {
@@ -259,6 +279,7 @@ fn generate_entry_docs(
 
                     let ident_id = ident_ids.get_id(name.value).unwrap();
                     let doc_def = DocDef {
+                        region,
                         name: name.value.to_string(),
                         type_annotation,
                         type_vars,
@@ -271,6 +292,10 @@ fn generate_entry_docs(
                 TypeDef::Opaque {
                     header: TypeHeader { name, vars },
                     ..
+                }
+                | TypeDef::PatternSynonym {
+                    header: TypeHeader { name, vars },
+                    ..
                 } => {
                     let mut type_vars = Vec::new();
 
@@ -282,6 +307,7 @@ fn generate_entry_docs(
 
                     let ident_id = ident_ids.get_id(name.value).unwrap();
                     let doc_def = DocDef {
+                        region,
                         name: name.value.to_string(),
                         type_annotation: TypeAnnotation::NoTypeAnn,
                         type_vars,
@@ -322,6 +348,7 @@ fn generate_entry_docs(
 
                     let ident_id = ident_ids.get_id(name.value).unwrap();
                     let doc_def = DocDef {
+                        region,
                         name: name.value.to_string(),
                         type_annotation: TypeAnnotation::Ability { members },
                         symbol: Symbol::new(home, ident_id),
@@ -14,6 +14,7 @@ use roc_can::module::{
     canonicalize_module_defs, ExposedByModule, ExposedForModule, ExposedModuleTypes, Module,
     ResolvedImplementations, TypeState,
 };
+use roc_can::pattern::PatternSynonym;
 use roc_collections::{default_hasher, BumpMap, MutMap, MutSet, VecMap, VecSet};
 use roc_constrain::module::constrain_module;
 use roc_debug_flags::dbg_do;
@@ -42,18 +43,22 @@ use roc_parse::ast::{
     self, CommentOrNewline, Defs, ExtractSpaces, Spaced, StrLiteral, TypeAnnotation,
 };
 use roc_parse::header::{
-    ExposedName, ImportsEntry, PackageEntry, PackageHeader, PlatformHeader, To, TypedIdent,
+    ExposedName, ImportsEntry, PackageEntry, PackageHeader, PlatformAllocator, PlatformEffects,
+    PlatformHeader, PlatformHost, To, TypedIdent,
 };
 use roc_parse::header::{HeaderType, PackageName};
 use roc_parse::module::module_defs;
 use roc_parse::parser::{FileError, Parser, SourceError, SyntaxError};
 use roc_problem::Severity;
-use roc_region::all::{LineInfo, Loc, Region};
-use roc_reporting::report::{Annotation, Palette, RenderTarget};
+use roc_region::all::{LineInfo, Loc, Position, Region};
+use roc_reporting::diagnostic::Diagnostic;
+use roc_reporting::report::{Annotation, Palette, RenderTarget, DEFAULT_PALETTE};
 use roc_solve::module::{extract_module_owned_implementations, Solved, SolvedModule};
 use roc_solve_problem::TypeError;
 use roc_target::TargetInfo;
-use roc_types::subs::{CopiedImport, ExposedTypesStorageSubs, Subs, VarStore, Variable};
+use roc_types::subs::{
+    CopiedImport, Descriptor, ExposedTypesStorageSubs, Subs, VarStore, Variable,
+};
 use roc_types::types::{Alias, Types};
 use std::collections::hash_map::Entry::{Occupied, Vacant};
 use std::collections::HashMap;
@@ -71,6 +76,9 @@ use {
     roc_packaging::https::PackageMetadata,
 };
 
+use crate::cancel::CancellationToken;
+use crate::memory::MemoryBudget;
+use crate::plugin::{Plugins, SolvedModuleInfo};
 pub use crate::work::Phase;
 use crate::work::{DepCycle, Dependencies};
 
@@ -101,6 +109,37 @@ pub struct LoadConfig {
     pub palette: Palette,
     pub threading: Threading,
     pub exec_mode: ExecutionMode,
+    /// Whether `dbg`s are kept in the generated code. If not, they only evaluate to their value.
+    pub keep_dbgs: bool,
+    /// Whether every inline `expect` crashes the program when it fails, like `expect-always`.
+    pub keep_expects: bool,
+    /// Called with each module once its types are solved
+    pub plugins: Plugins,
+    /// Stops the load early when it's cancelled
+    pub cancellation: CancellationToken,
+    /// The most memory, in bytes, the load may use. A load that uses more stops with a report
+    /// saying so, naming the module it was working on.
+    pub memory_limit: Option<usize>,
+}
+
+impl Default for LoadConfig {
+    /// Type-checks for the host, using all available threads, with problems rendered for a color
+    /// terminal, keeping `dbg`s. Callers set the fields they care about and take the rest from
+    /// here, so that a new field doesn't need to be added to each of them.
+    fn default() -> Self {
+        Self {
+            target_info: TargetInfo::from(&target_lexicon::Triple::host()),
+            render: RenderTarget::ColorTerminal,
+            palette: DEFAULT_PALETTE,
+            threading: Threading::AllAvailable,
+            exec_mode: ExecutionMode::Check,
+            keep_dbgs: true,
+            keep_expects: false,
+            plugins: Plugins::default(),
+            cancellation: CancellationToken::default(),
+            memory_limit: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -112,20 +151,23 @@ pub enum ExecutionMode {
     /// Test is like [`ExecutionMode::ExecutableIfCheck`], but rather than producing a proper
     /// executable, run tests.
     Test,
+    /// Like [`ExecutionMode::Test`], but runs `bench` blocks rather than `expect`s.
+    Bench,
 }
 
 impl ExecutionMode {
     fn goal_phase(&self) -> Phase {
         match self {
             ExecutionMode::Executable => Phase::MakeSpecializations,
-            ExecutionMode::Check | ExecutionMode::ExecutableIfCheck | ExecutionMode::Test => {
-                Phase::SolveTypes
-            }
+            ExecutionMode::Check
+            | ExecutionMode::ExecutableIfCheck
+            | ExecutionMode::Test
+            | ExecutionMode::Bench => Phase::SolveTypes,
         }
     }
 
     fn build_if_checks(&self) -> bool {
-        matches!(self, Self::ExecutableIfCheck | Self::Test)
+        matches!(self, Self::ExecutableIfCheck | Self::Test | Self::Bench)
     }
 }
 
@@ -138,6 +180,8 @@ struct ModuleCache<'a> {
     headers: MutMap<ModuleId, ModuleHeader<'a>>,
     parsed: MutMap<ModuleId, ParsedModule<'a>>,
     aliases: MutMap<ModuleId, MutMap<Symbol, (bool, Alias)>>,
+    deprecated: MutMap<ModuleId, MutMap<Symbol, Box<str>>>,
+    pattern_synonyms: MutMap<ModuleId, MutMap<Symbol, PatternSynonym>>,
     pending_abilities: MutMap<ModuleId, PendingAbilitiesStore>,
     constrained: MutMap<ModuleId, ConstrainedModule>,
     typechecked: MutMap<ModuleId, TypeCheckedModule<'a>>,
@@ -147,9 +191,12 @@ struct ModuleCache<'a> {
 
     /// Various information
     imports: MutMap<ModuleId, MutSet<ModuleId>>,
+    header_imports: MutMap<ModuleId, HeaderImports<'a>>,
     top_level_thunks: MutMap<ModuleId, MutSet<Symbol>>,
     documentation: VecMap<ModuleId, ModuleDocumentation>,
     can_problems: MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
+    /// Problems from opt-in lints, which aren't reported unless they've been turned on
+    lint_problems: MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     type_problems: MutMap<ModuleId, Vec<TypeError>>,
 
     sources: MutMap<ModuleId, (PathBuf, &'a str)>,
@@ -201,6 +248,13 @@ impl Default for ModuleCache<'_> {
             DECODE,
             HASH,
             JSON,
+            ASYNC,
+            RANDOM,
+            SORTED_DICT,
+            SORTED_SET,
+            BYTES,
+            ENCODING,
+            TIME,
         }
 
         Self {
@@ -208,6 +262,8 @@ impl Default for ModuleCache<'_> {
             headers: Default::default(),
             parsed: Default::default(),
             aliases: Default::default(),
+            deprecated: Default::default(),
+            pattern_synonyms: Default::default(),
             pending_abilities: Default::default(),
             constrained: Default::default(),
             typechecked: Default::default(),
@@ -215,9 +271,11 @@ impl Default for ModuleCache<'_> {
             late_specializations: Default::default(),
             external_specializations_requested: Default::default(),
             imports: Default::default(),
+            header_imports: Default::default(),
             top_level_thunks: Default::default(),
             documentation: Default::default(),
             can_problems: Default::default(),
+            lint_problems: Default::default(),
             type_problems: Default::default(),
             sources: Default::default(),
         }
@@ -329,9 +387,19 @@ fn start_phase<'a>(
                     .clone();
 
                 let mut aliases = MutMap::default();
+                let mut deprecated = MutMap::default();
+                let mut pattern_synonyms = MutMap::default();
                 let mut abilities_store = PendingAbilitiesStore::default();
 
                 for imported in parsed.imported_modules.keys() {
+                    if let Some(new) = state.module_cache.deprecated.get(imported) {
+                        deprecated.extend(new.iter().map(|(s, m)| (*s, m.clone())));
+                    }
+
+                    if let Some(new) = state.module_cache.pattern_synonyms.get(imported) {
+                        pattern_synonyms.extend(new.iter().map(|(s, p)| (*s, p.clone())));
+                    }
+
                     match state.module_cache.aliases.get(imported) {
                         None => unreachable!(
                             r"imported module {:?} did not register its aliases, so {:?} cannot use them",
@@ -380,15 +448,24 @@ fn start_phase<'a>(
                     state.cached_types.lock().contains_key(&module_id)
                 };
 
+                let is_hosted = matches!(parsed.header_type, HeaderType::Hosted { .. });
+                let docs_target = docs_target(state, module_id, is_hosted);
+
+                // `roc test` runs hosted effects using the platform's test doubles, if it has any
+                let use_test_doubles = matches!(state.exec_mode, ExecutionMode::Test);
+
                 BuildTask::CanonicalizeAndConstrain {
                     parsed,
                     dep_idents,
                     exposed_symbols,
                     module_ids,
                     aliases,
+                    deprecated,
+                    pattern_synonyms,
                     abilities_store,
                     skip_constraint_gen,
-                    exposed_module_ids: state.exposed_modules,
+                    docs_target,
+                    use_test_doubles,
                 }
             }
 
@@ -459,10 +536,12 @@ fn start_phase<'a>(
 
                 let build_expects =
                     matches!(state.exec_mode, ExecutionMode::Test) && expectations.is_some();
+                let build_benches = matches!(state.exec_mode, ExecutionMode::Bench);
 
                 BuildTask::BuildPendingSpecializations {
                     layout_cache,
                     module_id,
+                    source: module_source(&state.module_cache, module_id),
                     module_timing,
                     solved_subs,
                     imported_module_thunks: imported_module_thunks.into_bump_slice(),
@@ -475,6 +554,8 @@ fn start_phase<'a>(
                     derived_module,
                     expectations,
                     build_expects,
+                    build_benches,
+                    keep_expects: state.keep_expects,
                 }
             }
             Phase::MakeSpecializations => {
@@ -599,6 +680,7 @@ fn start_phase<'a>(
 
                 BuildTask::MakeSpecializations {
                     module_id,
+                    source: module_source(&state.module_cache, module_id),
                     ident_ids,
                     subs,
                     procs_base,
@@ -610,6 +692,7 @@ fn start_phase<'a>(
                     exposed_by_module: state.exposed_types.clone(),
                     derived_module,
                     expectations,
+                    keep_expects: state.keep_expects,
                 }
             }
         }
@@ -624,12 +707,17 @@ pub struct LoadedModule {
     pub interns: Interns,
     pub solved: Solved<Subs>,
     pub can_problems: MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
+    /// Problems from opt-in lints, like naming conventions. They aren't counted in
+    /// `total_problems`, since they're only reported when asked for.
+    pub lint_problems: MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     pub type_problems: MutMap<ModuleId, Vec<TypeError>>,
     pub declarations_by_id: MutMap<ModuleId, Declarations>,
     pub exposed_to_host: MutMap<Symbol, Variable>,
     pub dep_idents: IdentIdsByModule,
     pub exposed_aliases: MutMap<Symbol, Alias>,
     pub exposed_values: Vec<Symbol>,
+    /// What each loaded module lists in its `exposes`
+    pub exposed_symbols_by_module: MutMap<ModuleId, VecSet<Symbol>>,
     pub exposed_types_storage: ExposedTypesStorageSubs,
     pub resolved_implementations: ResolvedImplementations,
     pub sources: MutMap<ModuleId, (PathBuf, Box<str>)>,
@@ -692,6 +780,15 @@ struct ModuleHeader<'a> {
     module_timing: ModuleTiming,
 }
 
+/// Where a module's header imports other modules. Unlike the header itself, which is consumed once
+/// the module is parsed, this is kept around so that import cycles can point at each import.
+#[derive(Debug)]
+struct HeaderImports<'a> {
+    module_path: PathBuf,
+    src: &'a [u8],
+    regions: MutMap<ModuleId, Region>,
+}
+
 #[derive(Debug)]
 struct ConstrainedModule {
     module: Module,
@@ -746,6 +843,9 @@ struct LateSpecializationsModule<'a> {
 pub struct ToplevelExpects {
     pub pure: VecMap<Symbol, Region>,
     pub fx: VecMap<Symbol, Region>,
+    /// The `@fixture`s these expects can use. For each one, a thunk which evaluates it, so that
+    /// it can be evaluated once before any of the expects run, and the fixture and its region.
+    pub fixtures: VecMap<Symbol, (Symbol, Region)>,
 }
 
 #[derive(Debug)]
@@ -756,15 +856,32 @@ pub struct MonomorphizedModule<'a> {
     pub layout_interner: STLayoutInterner<'a>,
     pub output_path: Box<Path>,
     pub can_problems: MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
+    /// Problems from opt-in lints, like naming conventions. They aren't counted in
+    /// `total_problems`, since they're only reported when asked for.
+    pub lint_problems: MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     pub type_problems: MutMap<ModuleId, Vec<TypeError>>,
     pub procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    /// Where each top-level def is in its module's source, for debug info
+    pub def_regions: MutMap<Symbol, Region>,
     pub toplevel_expects: ToplevelExpects,
+    /// The `bench` blocks to run, when loading with [`ExecutionMode::Bench`]
+    pub toplevel_benches: VecMap<Symbol, Region>,
     pub entry_point: EntryPoint<'a>,
     pub exposed_to_host: ExposedToHost,
     pub sources: MutMap<ModuleId, (PathBuf, Box<str>)>,
+    /// The modules each module imports directly
+    pub imports: MutMap<ModuleId, MutSet<ModuleId>>,
     pub timings: MutMap<ModuleId, ModuleTiming>,
     pub expectations: VecMap<ModuleId, Expectations>,
     pub uses_prebuilt_platform: bool,
+    /// How the platform's host provides memory, from the `allocator` section of its header
+    pub platform_allocator: PlatformAllocator,
+    /// How the platform's host runs effects, from the `effects` section of its header
+    pub platform_effects: PlatformEffects,
+    /// The hosts the platform ships prebuilt, by target, from the `hosts` section of its header
+    pub platform_hosts: &'a [PlatformHost<'a>],
+    /// The command which builds the platform's host, from the `build` section of its header
+    pub platform_build_command: Option<&'a str>,
 }
 
 /// Values used to render expect output
@@ -790,6 +907,8 @@ pub struct Expectations {
     pub expectations: VecMap<Region, Vec<ExpectLookup>>,
     pub dbgs: VecMap<Symbol, DbgLookup>,
     pub ident_ids: IdentIds,
+    /// The module's top-level values marked `@fixture`
+    pub fixtures: VecSet<Symbol>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -854,6 +973,7 @@ enum Msg<'a> {
         abilities_store: AbilitiesStore,
         loc_expects: LocExpects,
         loc_dbgs: LocDbgs,
+        fixtures: VecSet<Symbol>,
     },
     FinishedAllTypeChecking {
         solved_subs: Solved<Subs>,
@@ -874,6 +994,8 @@ enum Msg<'a> {
         module_timing: ModuleTiming,
         abilities_store: AbilitiesStore,
         toplevel_expects: ToplevelExpects,
+        toplevel_benches: VecMap<Symbol, Region>,
+        def_regions: Vec<(Symbol, Region)>,
         expectations: Option<Expectations>,
     },
     MadeSpecializations {
@@ -914,6 +1036,7 @@ enum Msg<'a> {
 struct CanAndCon {
     constrained_module: ConstrainedModule,
     canonicalization_problems: Vec<roc_problem::can::Problem>,
+    lint_problems: Vec<roc_problem::can::Problem>,
     module_docs: Option<ModuleDocumentation>,
 }
 
@@ -931,6 +1054,10 @@ struct PlatformData<'a> {
     module_id: ModuleId,
     provides: &'a [(Loc<ExposedName<'a>>, Loc<TypedIdent<'a>>)],
     is_prebuilt: bool,
+    allocator: PlatformAllocator,
+    effects: PlatformEffects,
+    hosts: &'a [PlatformHost<'a>],
+    build_command: Option<&'a str>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -974,10 +1101,16 @@ struct State<'a> {
     /// for all others, this will be empty.
     pub exposed_modules: &'a [ModuleId],
 
+    /// The modules exposed by each package the root module depends on, by the shorthand it was
+    /// imported under. Docs are generated for these too, so they can be linked to.
+    pub dependency_exposed_modules: MutMap<&'a str, &'a [ModuleId]>,
+
     pub module_cache: ModuleCache<'a>,
     pub dependencies: Dependencies<'a>,
     pub procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    pub def_regions: MutMap<Symbol, Region>,
     pub toplevel_expects: ToplevelExpects,
+    pub toplevel_benches: VecMap<Symbol, Region>,
     pub exposed_to_host: ExposedToHost,
 
     /// This is the "final" list of IdentIds, after canonicalization and constraint gen
@@ -1008,6 +1141,11 @@ struct State<'a> {
     pub render: RenderTarget,
     pub palette: Palette,
     pub exec_mode: ExecutionMode,
+    pub keep_dbgs: bool,
+    pub keep_expects: bool,
+    pub plugins: Plugins,
+    pub cancellation: CancellationToken,
+    memory: Arc<MemoryBudget>,
 
     /// All abilities across all modules.
     pub world_abilities: WorldAbilities,
@@ -1039,6 +1177,11 @@ impl<'a> State<'a> {
         palette: Palette,
         number_of_workers: usize,
         exec_mode: ExecutionMode,
+        keep_dbgs: bool,
+        keep_expects: bool,
+        plugins: Plugins,
+        cancellation: CancellationToken,
+        memory: Arc<MemoryBudget>,
     ) -> Self {
         let arc_shorthands = Arc::new(Mutex::new(MutMap::default()));
         let cache_dir = roc_packaging::cache::roc_cache_dir();
@@ -1056,9 +1199,12 @@ impl<'a> State<'a> {
             module_cache: ModuleCache::default(),
             dependencies,
             procedures: MutMap::default(),
+            def_regions: MutMap::default(),
             toplevel_expects: ToplevelExpects::default(),
+            toplevel_benches: VecMap::default(),
             exposed_to_host: ExposedToHost::default(),
             exposed_modules: &[],
+            dependency_exposed_modules: MutMap::default(),
             exposed_types,
             arc_modules,
             arc_shorthands,
@@ -1073,6 +1219,11 @@ impl<'a> State<'a> {
             render,
             palette,
             exec_mode,
+            keep_dbgs,
+            keep_expects,
+            plugins,
+            cancellation,
+            memory,
             make_specializations_pass: MakeSpecializationsPass::Pass(1),
             world_abilities: Default::default(),
             layout_interner: GlobalLayoutInterner::with_capacity(128, target_info),
@@ -1214,8 +1365,11 @@ enum BuildTask<'a> {
         dep_idents: IdentIdsByModule,
         exposed_symbols: VecSet<Symbol>,
         aliases: MutMap<Symbol, Alias>,
+        deprecated: MutMap<Symbol, Box<str>>,
+        pattern_synonyms: MutMap<Symbol, PatternSynonym>,
         abilities_store: PendingAbilitiesStore,
-        exposed_module_ids: &'a [ModuleId],
+        docs_target: Option<DocsTarget<'a>>,
+        use_test_doubles: bool,
         skip_constraint_gen: bool,
     },
     Solve {
@@ -1239,6 +1393,7 @@ enum BuildTask<'a> {
         solved_subs: Solved<Subs>,
         imported_module_thunks: &'a [Symbol],
         module_id: ModuleId,
+        source: &'a str,
         ident_ids: IdentIds,
         decls: Declarations,
         exposed_to_host: ExposedToHost,
@@ -1247,9 +1402,12 @@ enum BuildTask<'a> {
         derived_module: SharedDerivedModule,
         expectations: Option<Expectations>,
         build_expects: bool,
+        build_benches: bool,
+        keep_expects: bool,
     },
     MakeSpecializations {
         module_id: ModuleId,
+        source: &'a str,
         ident_ids: IdentIds,
         subs: Subs,
         procs_base: ProcsBase<'a>,
@@ -1260,6 +1418,7 @@ enum BuildTask<'a> {
         world_abilities: WorldAbilities,
         derived_module: SharedDerivedModule,
         expectations: Option<Expectations>,
+        keep_expects: bool,
     },
 }
 
@@ -1276,6 +1435,19 @@ pub struct IncorrectModuleName<'a> {
     pub expected: PQModuleName<'a>,
 }
 
+/// One step in an import cycle: the module `module_id` imports `imported`, the next module of the
+/// cycle, at `region` of its header.
+#[derive(Debug)]
+pub struct ImportCycleEdge<'a> {
+    pub module_id: ModuleId,
+    pub imported: ModuleId,
+    pub module_path: PathBuf,
+    pub src: &'a [u8],
+    pub region: Region,
+    /// The number of (non-builtin) modules that `module_id` imports
+    pub dependency_count: usize,
+}
+
 #[derive(Debug)]
 pub enum LoadingProblem<'a> {
     FileProblem {
@@ -1289,12 +1461,16 @@ pub enum LoadingProblem<'a> {
     ErrJoiningWorkerThreads,
     TriedToImportAppModule,
 
-    /// a formatted report
-    FormattedReport(String),
+    /// a report, formatted for the terminal, and as a diagnostic for other tools
+    FormattedReport(String, Box<Diagnostic>),
 
-    ImportCycle(PathBuf, Vec<ModuleId>),
+    ImportCycle(PathBuf, Vec<ModuleId>, Vec<ImportCycleEdge<'a>>),
     IncorrectModuleName(FileError<'a, IncorrectModuleName<'a>>),
     CouldNotFindCacheDir,
+    /// The load's [CancellationToken] was cancelled before it finished
+    Cancelled,
+    /// The load went over its memory limit while working on this module
+    ExceededMemoryLimit(ModuleId),
 }
 
 pub enum Phases {
@@ -1349,6 +1525,7 @@ pub fn load_and_typecheck_str<'a>(
         palette,
         threading,
         exec_mode: ExecutionMode::Check,
+        ..Default::default()
     };
 
     match load(
@@ -1444,11 +1621,9 @@ impl<'a> LoadStart<'a> {
                         .into_inner()
                         .into_module_ids();
 
-                    let report = report_loading_problem(problem, module_ids, render, palette);
-
                     // TODO try to gracefully recover and continue
                     // instead of changing the control flow to exit.
-                    return Err(LoadingProblem::FormattedReport(report));
+                    return Err(report_loading_problem(problem, module_ids, render, palette));
                 }
             }
         };
@@ -1608,6 +1783,11 @@ pub fn load<'a>(
             load_config.render,
             load_config.palette,
             load_config.exec_mode,
+            load_config.keep_dbgs,
+            load_config.keep_expects,
+            load_config.plugins,
+            load_config.cancellation,
+            load_config.memory_limit,
             roc_cache_dir,
         ),
         Threads::Many(threads) => load_multi_threaded(
@@ -1620,6 +1800,11 @@ pub fn load<'a>(
             load_config.palette,
             threads,
             load_config.exec_mode,
+            load_config.keep_dbgs,
+            load_config.keep_expects,
+            load_config.plugins,
+            load_config.cancellation,
+            load_config.memory_limit,
             roc_cache_dir,
         ),
     }
@@ -1635,6 +1820,11 @@ pub fn load_single_threaded<'a>(
     render: RenderTarget,
     palette: Palette,
     exec_mode: ExecutionMode,
+    keep_dbgs: bool,
+    keep_expects: bool,
+    plugins: Plugins,
+    cancellation: CancellationToken,
+    memory_limit: Option<usize>,
     roc_cache_dir: RocCacheDir<'_>,
 ) -> Result<LoadResult<'a>, LoadingProblem<'a>> {
     let LoadStart {
@@ -1654,6 +1844,7 @@ pub fn load_single_threaded<'a>(
         .map_err(|_| LoadingProblem::MsgChannelDied)?;
 
     let number_of_workers = 1;
+    let memory = Arc::new(MemoryBudget::new(memory_limit));
     let mut state = State::new(
         root_id,
         opt_platform_shorthand,
@@ -1666,6 +1857,11 @@ pub fn load_single_threaded<'a>(
         palette,
         number_of_workers,
         exec_mode,
+        keep_dbgs,
+        keep_expects,
+        plugins,
+        cancellation.clone(),
+        memory.clone(),
     );
 
     // We'll add tasks to this, and then worker threads will take tasks from it.
@@ -1708,6 +1904,8 @@ pub fn load_single_threaded<'a>(
             &src_dir,
             roc_cache_dir,
             target_info,
+            &cancellation,
+            &memory,
         );
 
         match control_flow {
@@ -1729,6 +1927,10 @@ fn state_thread_step<'a>(
     msg_tx: &crossbeam::channel::Sender<Msg<'a>>,
     msg_rx: &crossbeam::channel::Receiver<Msg<'a>>,
 ) -> Result<ControlFlow<LoadResult<'a>, State<'a>>, LoadingProblem<'a>> {
+    if state.cancellation.is_cancelled() {
+        return Err(LoadingProblem::Cancelled);
+    }
+
     match msg_rx.try_recv() {
         Ok(msg) => {
             match msg {
@@ -1785,35 +1987,32 @@ fn state_thread_step<'a>(
                     Ok(ControlFlow::Break(LoadResult::Monomorphized(monomorphized)))
                 }
                 Msg::FailedToReadFile { filename, error } => {
-                    let buf = to_file_problem_report(&filename, error);
-                    Err(LoadingProblem::FormattedReport(buf))
+                    Err(to_file_problem_report(&filename, error))
                 }
 
                 Msg::FailedToParse(problem) => {
                     let module_ids = (*state.arc_modules).lock().clone().into_module_ids();
-                    let buf = to_parse_problem_report(
+                    Err(to_parse_problem_report(
                         problem,
                         module_ids,
                         state.constrained_ident_ids,
                         state.render,
                         state.palette,
-                    );
-                    Err(LoadingProblem::FormattedReport(buf))
+                    ))
                 }
                 Msg::IncorrectModuleName(FileError {
                     problem: SourceError { problem, bytes },
                     filename,
                 }) => {
                     let module_ids = (*state.arc_modules).lock().clone().into_module_ids();
-                    let buf = to_incorrect_module_name_report(
+                    Err(to_incorrect_module_name_report(
                         module_ids,
                         state.constrained_ident_ids,
                         problem,
                         filename,
                         bytes,
                         state.render,
-                    );
-                    Err(LoadingProblem::FormattedReport(buf))
+                    ))
                 }
                 msg => {
                     // This is where most of the main thread's work gets done.
@@ -1848,27 +2047,26 @@ fn state_thread_step<'a>(
 
                             // if parsing failed, this module did not add anything to IdentIds
                             let root_exposed_ident_ids = IdentIds::exposed_builtins(0);
-                            let buf = to_parse_problem_report(
+                            Err(to_parse_problem_report(
                                 problem,
                                 module_ids,
                                 root_exposed_ident_ids,
                                 render,
                                 palette,
-                            );
-                            Err(LoadingProblem::FormattedReport(buf))
+                            ))
                         }
-                        Err(LoadingProblem::ImportCycle(filename, cycle)) => {
+                        Err(LoadingProblem::ImportCycle(filename, cycle, edges)) => {
                             let module_ids = arc_modules.lock().clone().into_module_ids();
 
                             let root_exposed_ident_ids = IdentIds::exposed_builtins(0);
-                            let buf = to_import_cycle_report(
+                            return Err(to_import_cycle_report(
                                 module_ids,
                                 root_exposed_ident_ids,
                                 cycle,
+                                edges,
                                 filename,
                                 render,
-                            );
-                            return Err(LoadingProblem::FormattedReport(buf));
+                            ));
                         }
                         Err(LoadingProblem::IncorrectModuleName(FileError {
                             problem: SourceError { problem, bytes },
@@ -1877,15 +2075,14 @@ fn state_thread_step<'a>(
                             let module_ids = arc_modules.lock().clone().into_module_ids();
 
                             let root_exposed_ident_ids = IdentIds::exposed_builtins(0);
-                            let buf = to_incorrect_module_name_report(
+                            return Err(to_incorrect_module_name_report(
                                 module_ids,
                                 root_exposed_ident_ids,
                                 problem,
                                 filename,
                                 bytes,
                                 render,
-                            );
-                            return Err(LoadingProblem::FormattedReport(buf));
+                            ));
                         }
                         Err(e) => Err(e),
                     }
@@ -1899,12 +2096,13 @@ fn state_thread_step<'a>(
     }
 }
 
-pub fn report_loading_problem(
-    problem: LoadingProblem<'_>,
+/// Turns a problem which can be reported into a [LoadingProblem::FormattedReport]
+pub fn report_loading_problem<'a>(
+    problem: LoadingProblem<'a>,
     module_ids: ModuleIds,
     render: RenderTarget,
     palette: Palette,
-) -> String {
+) -> LoadingProblem<'a> {
     match problem {
         LoadingProblem::ParsingFailed(problem) => {
             // if parsing failed, this module did not add anything to IdentIds
@@ -1912,10 +2110,17 @@ pub fn report_loading_problem(
 
             to_parse_problem_report(problem, module_ids, root_exposed_ident_ids, render, palette)
         }
-        LoadingProblem::ImportCycle(filename, cycle) => {
+        LoadingProblem::ImportCycle(filename, cycle, edges) => {
             let root_exposed_ident_ids = IdentIds::exposed_builtins(0);
 
-            to_import_cycle_report(module_ids, root_exposed_ident_ids, cycle, filename, render)
+            to_import_cycle_report(
+                module_ids,
+                root_exposed_ident_ids,
+                cycle,
+                edges,
+                filename,
+                render,
+            )
         }
         LoadingProblem::IncorrectModuleName(FileError {
             problem: SourceError { problem, bytes },
@@ -1932,7 +2137,7 @@ pub fn report_loading_problem(
                 render,
             )
         }
-        LoadingProblem::FormattedReport(report) => report,
+        problem @ LoadingProblem::FormattedReport(..) => problem,
         LoadingProblem::FileProblem { filename, error } => to_file_problem_report(&filename, error),
         err => todo!("Loading error: {:?}", err),
     }
@@ -1948,6 +2153,11 @@ fn load_multi_threaded<'a>(
     palette: Palette,
     available_threads: usize,
     exec_mode: ExecutionMode,
+    keep_dbgs: bool,
+    keep_expects: bool,
+    plugins: Plugins,
+    cancellation: CancellationToken,
+    memory_limit: Option<usize>,
     roc_cache_dir: RocCacheDir<'_>,
 ) -> Result<LoadResult<'a>, LoadingProblem<'a>> {
     let LoadStart {
@@ -1982,6 +2192,7 @@ fn load_multi_threaded<'a>(
         "`load_multi_threaded` needs at least one worker"
     );
 
+    let memory = Arc::new(MemoryBudget::new(memory_limit));
     let mut state = State::new(
         root_id,
         opt_platform_shorthand,
@@ -1994,6 +2205,11 @@ fn load_multi_threaded<'a>(
         palette,
         num_workers,
         exec_mode,
+        keep_dbgs,
+        keep_expects,
+        plugins,
+        cancellation.clone(),
+        memory.clone(),
     );
 
     // an arena for every worker, stored in an arena-allocated bumpalo vec to make the lifetimes work
@@ -2039,6 +2255,8 @@ fn load_multi_threaded<'a>(
                 // (since other threads need to reference it too). Same with src_dir.
                 let injector = &injector;
                 let src_dir = &src_dir;
+                let cancellation = &cancellation;
+                let memory = &memory;
 
                 // Record this thread's handle so the main thread can join it later.
                 let res_join_handle = thread_scope
@@ -2056,6 +2274,8 @@ fn load_multi_threaded<'a>(
                             src_dir,
                             roc_cache_dir,
                             target_info,
+                            cancellation,
+                            memory,
                         )
                     });
 
@@ -2125,6 +2345,8 @@ fn worker_task_step<'a>(
     src_dir: &Path,
     roc_cache_dir: RocCacheDir<'_>,
     target_info: TargetInfo,
+    cancellation: &CancellationToken,
+    memory: &MemoryBudget,
 ) -> Result<ControlFlow<(), ()>, LoadingProblem<'a>> {
     match worker_msg_rx.try_recv() {
         Ok(msg) => {
@@ -2146,7 +2368,16 @@ fn worker_task_step<'a>(
                     // which will later result in more tasks being
                     // added. In that case, do nothing, and keep waiting
                     // until we receive a Shutdown message.
+                    //
+                    // Once the load is cancelled, the tasks are left undone;
+                    // the main thread stops the load on its next step.
+                    if cancellation.is_cancelled() {
+                        return Ok(ControlFlow::Continue(()));
+                    }
+
                     if let Some(task) = find_task(worker, injector, stealers) {
+                        let module_id = task.module_id();
+                        let allocated_before = worker_arena.allocated_bytes();
                         let result = run_task(
                             task,
                             worker_arena,
@@ -2156,6 +2387,16 @@ fn worker_task_step<'a>(
                             target_info,
                         );
 
+                        if memory.charge(worker_arena.allocated_bytes() - allocated_before) {
+                            if let Some(module_id) = module_id {
+                                msg_tx
+                                    .send(Msg::FailedToLoad(LoadingProblem::ExceededMemoryLimit(
+                                        module_id,
+                                    )))
+                                    .map_err(|_| LoadingProblem::MsgChannelDied)?;
+                            }
+                        }
+
                         match result {
                             Ok(()) => {}
                             Err(LoadingProblem::MsgChannelDied) => {
@@ -2199,6 +2440,8 @@ fn worker_task<'a>(
     src_dir: &Path,
     roc_cache_dir: RocCacheDir<'_>,
     target_info: TargetInfo,
+    cancellation: &CancellationToken,
+    memory: &MemoryBudget,
 ) -> Result<(), LoadingProblem<'a>> {
     // Keep listening until we receive a Shutdown msg
     for msg in worker_msg_rx.iter() {
@@ -2220,6 +2463,13 @@ fn worker_task<'a>(
                 // which will later result in more tasks being
                 // added. In that case, do nothing, and keep waiting
                 // until we receive a Shutdown message.
+                //
+                // Once the load is cancelled, the tasks are left undone;
+                // the main thread stops the load on its next step.
+                if cancellation.is_cancelled() {
+                    continue;
+                }
+
                 if let Some(task) = find_task(&worker, injector, stealers) {
                     log!(
                         ">>> {}",
@@ -2246,6 +2496,8 @@ fn worker_task<'a>(
                         }
                     );
 
+                    let module_id = task.module_id();
+                    let allocated_before = worker_arena.allocated_bytes();
                     let result = run_task(
                         task,
                         worker_arena,
@@ -2255,6 +2507,16 @@ fn worker_task<'a>(
                         target_info,
                     );
 
+                    if memory.charge(worker_arena.allocated_bytes() - allocated_before) {
+                        if let Some(module_id) = module_id {
+                            msg_tx
+                                .send(Msg::FailedToLoad(LoadingProblem::ExceededMemoryLimit(
+                                    module_id,
+                                )))
+                                .map_err(|_| LoadingProblem::MsgChannelDied)?;
+                        }
+                    }
+
                     match result {
                         Ok(()) => {}
                         Err(LoadingProblem::MsgChannelDied) => {
@@ -2495,11 +2757,23 @@ fn update<'a>(
                     }
                     Package {
                         config_shorthand,
+                        exposes,
                         exposes_ids,
-                        ..
                     } => {
                         if header.is_root_module {
                             state.exposed_modules = exposes_ids;
+                        } else {
+                            let module_ids = dependency_exposed_module_ids(
+                                arena,
+                                &state.arc_modules,
+                                config_shorthand,
+                                exposes,
+                                exposes_ids,
+                            );
+
+                            state
+                                .dependency_exposed_modules
+                                .insert(config_shorthand, module_ids);
                         }
 
                         work.extend(state.dependencies.notify_package(config_shorthand));
@@ -2507,7 +2781,12 @@ fn update<'a>(
                     Platform {
                         config_shorthand,
                         provides,
+                        exposes,
                         exposes_ids,
+                        allocator,
+                        effects,
+                        hosts,
+                        build_command,
                         ..
                     } => {
                         work.extend(state.dependencies.notify_package(config_shorthand));
@@ -2539,11 +2818,27 @@ fn update<'a>(
                                 module_id: header.module_id,
                                 provides,
                                 is_prebuilt,
+                                allocator,
+                                effects,
+                                hosts,
+                                build_command,
                             });
                         }
 
                         if header.is_root_module {
                             state.exposed_modules = exposes_ids;
+                        } else {
+                            let module_ids = dependency_exposed_module_ids(
+                                arena,
+                                &state.arc_modules,
+                                config_shorthand,
+                                exposes,
+                                exposes_ids,
+                            );
+
+                            state
+                                .dependency_exposed_modules
+                                .insert(config_shorthand, module_ids);
                         }
                     }
                     Builtin { .. } | Interface { .. } => {
@@ -2619,6 +2914,15 @@ fn update<'a>(
                         .map(|x| *x.as_inner()),
                 );
 
+            state.module_cache.header_imports.insert(
+                header.module_id,
+                HeaderImports {
+                    module_path: header.module_path.clone(),
+                    src: header.parse_state.original_bytes(),
+                    regions: header.imported_modules.clone(),
+                },
+            );
+
             let added_deps_result = state.dependencies.add_module(
                 header.module_id,
                 &header.package_qualified_imported_modules,
@@ -2628,9 +2932,12 @@ fn update<'a>(
             let new_work = match added_deps_result {
                 Ok(work) => work,
                 Err(DepCycle { cycle }) => {
+                    let edges = import_cycle_edges(&state.module_cache.header_imports, &cycle);
+
                     return Err(LoadingProblem::ImportCycle(
                         header.module_path.clone(),
                         cycle,
+                        edges,
                     ));
                 }
             };
@@ -2682,6 +2989,7 @@ fn update<'a>(
         CanonicalizedAndConstrained(CanAndCon {
             constrained_module,
             canonicalization_problems,
+            lint_problems,
             module_docs,
         }) => {
             let module_id = constrained_module.module.module_id;
@@ -2690,6 +2998,10 @@ fn update<'a>(
                 .module_cache
                 .can_problems
                 .insert(module_id, canonicalization_problems);
+            state
+                .module_cache
+                .lint_problems
+                .insert(module_id, lint_problems);
 
             if let Some(docs) = module_docs {
                 state.module_cache.documentation.insert(module_id, docs);
@@ -2702,6 +3014,16 @@ fn update<'a>(
                 .aliases
                 .insert(module_id, constrained_module.module.aliases.clone());
 
+            state
+                .module_cache
+                .deprecated
+                .insert(module_id, constrained_module.module.deprecated.clone());
+
+            state.module_cache.pattern_synonyms.insert(
+                module_id,
+                constrained_module.module.pattern_synonyms.clone(),
+            );
+
             state
                 .module_cache
                 .pending_abilities
@@ -2731,15 +3053,48 @@ fn update<'a>(
             abilities_store,
             loc_expects,
             loc_dbgs,
+            fixtures,
         } => {
             log!("solved types for {:?}", module_id);
             module_timing.end_time = Instant::now();
 
+            let subs_bytes = solved_subs.inner().len() * std::mem::size_of::<Descriptor>();
+            if state.memory.charge(subs_bytes) {
+                return Err(to_memory_limit_report(&state, module_id));
+            }
+
+            if !state.plugins.is_empty() && !module_id.is_builtin() {
+                let (path, src) = state.module_cache.sources.get(&module_id).unwrap();
+                let mut all_ident_ids = state.constrained_ident_ids.clone();
+                all_ident_ids.insert(module_id, ident_ids.clone());
+
+                let interns = Interns {
+                    module_ids: state.arc_modules.lock().clone().into_module_ids(),
+                    all_ident_ids,
+                };
+
+                state.plugins.module_solved(&SolvedModuleInfo {
+                    module_id,
+                    path,
+                    src,
+                    declarations: &decls,
+                    subs: solved_subs.inner(),
+                    interns: &interns,
+                });
+            }
+
             state
                 .module_cache
                 .type_problems
                 .insert(module_id, solved_module.problems);
 
+            // Stripped dbgs are lowered to just their value, so there's nothing to render.
+            let loc_dbgs = if state.keep_dbgs {
+                loc_dbgs
+            } else {
+                LocDbgs::default()
+            };
+
             let should_include_expects = (!loc_expects.is_empty() || !loc_dbgs.is_empty()) && {
                 let modules = state.arc_modules.lock();
                 modules
@@ -2756,6 +3111,7 @@ fn update<'a>(
                     subs: solved_subs.clone().into_inner(),
                     path: path.to_owned(),
                     ident_ids: ident_ids.clone(),
+                    fixtures,
                 })
             } else {
                 None
@@ -2772,7 +3128,7 @@ fn update<'a>(
 
             let add_to_host_exposed = is_host_exposed &&
                 // During testing, we don't need to expose anything to the host.
-                !matches!(state.exec_mode, ExecutionMode::Test);
+                !matches!(state.exec_mode, ExecutionMode::Test | ExecutionMode::Bench);
 
             if add_to_host_exposed {
                 state.exposed_to_host.values.extend(
@@ -2912,6 +3268,8 @@ fn update<'a>(
             module_timing,
             abilities_store,
             toplevel_expects,
+            toplevel_benches,
+            def_regions,
             expectations,
         } => {
             log!("found specializations for {:?}", module_id);
@@ -2920,6 +3278,12 @@ fn update<'a>(
 
             state.toplevel_expects.pure.extend(toplevel_expects.pure);
             state.toplevel_expects.fx.extend(toplevel_expects.fx);
+            state
+                .toplevel_expects
+                .fixtures
+                .extend(toplevel_expects.fixtures);
+            state.toplevel_benches.extend(toplevel_benches);
+            state.def_regions.extend(def_regions);
 
             state
                 .module_cache
@@ -3090,6 +3454,21 @@ fn update<'a>(
 
                     let ident_ids = state.constrained_ident_ids.get_mut(&module_id).unwrap();
 
+                    let batch_effects = matches!(
+                        &state.platform_data,
+                        Some(data) if data.effects.is_batched()
+                    );
+
+                    if batch_effects {
+                        // Effects can only go in a batch together once they're in one procedure
+                        roc_mono::effect_batch::inline_small_procs(
+                            arena,
+                            module_id,
+                            ident_ids,
+                            &mut state.procedures,
+                        );
+                    }
+
                     Proc::insert_reset_reuse_operations(
                         arena,
                         &mut layout_interner,
@@ -3118,6 +3497,10 @@ fn update<'a>(
 
                     debug_print_ir!(state, &layout_interner, ROC_PRINT_IR_AFTER_REFCOUNT);
 
+                    if batch_effects {
+                        roc_mono::effect_batch::gather_effects(arena, &mut state.procedures);
+                    }
+
                     // This is not safe with the new non-recursive RC updates that we do for tag unions
                     //
                     // Proc::optimize_refcount_operations(
@@ -3223,6 +3606,9 @@ fn update<'a>(
                 }
             }
         }
+        Msg::FailedToLoad(LoadingProblem::ExceededMemoryLimit(module_id)) => {
+            Err(to_memory_limit_report(&state, module_id))
+        }
         Msg::FailedToLoad(problem) => {
             // TODO report the error and continue instead of erroring out
             Err(problem)
@@ -3305,7 +3691,9 @@ fn finish_specialization<'a>(
 
     let State {
         toplevel_expects,
+        toplevel_benches,
         procedures,
+        def_regions,
         module_cache,
         output_path,
         platform_path,
@@ -3317,7 +3705,9 @@ fn finish_specialization<'a>(
     let ModuleCache {
         type_problems,
         can_problems,
+        lint_problems,
         sources,
+        imports,
         ..
     } = module_cache;
 
@@ -3328,7 +3718,7 @@ fn finish_specialization<'a>(
 
     let entry_point = {
         match exec_mode {
-            ExecutionMode::Test => EntryPoint::Test,
+            ExecutionMode::Test | ExecutionMode::Bench => EntryPoint::Test,
             ExecutionMode::Executable | ExecutionMode::ExecutableIfCheck => {
                 use PlatformPath::*;
 
@@ -3341,8 +3731,7 @@ fn finish_specialization<'a>(
                     }
                     Valid(To::NewPackage(p_or_p)) => PathBuf::from(p_or_p.as_str()),
                     other => {
-                        let buf = to_missing_platform_report(state.root_id, other);
-                        return Err(LoadingProblem::FormattedReport(buf));
+                        return Err(to_missing_platform_report(state.root_id, other));
                     }
                 };
 
@@ -3394,6 +3783,26 @@ fn finish_specialization<'a>(
         None => current_dir().unwrap().join(DEFAULT_APP_OUTPUT_PATH).into(),
     };
 
+    let platform_allocator = match &platform_data {
+        Some(data) => data.allocator,
+        None => PlatformAllocator::default(),
+    };
+
+    let platform_effects = match &platform_data {
+        Some(data) => data.effects,
+        None => PlatformEffects::default(),
+    };
+
+    let platform_hosts = match &platform_data {
+        Some(data) => data.hosts,
+        None => &[],
+    };
+
+    let platform_build_command = match &platform_data {
+        Some(data) => data.build_command,
+        None => None,
+    };
+
     let uses_prebuilt_platform = match platform_data {
         Some(data) => data.is_prebuilt,
         // If there's no platform data (e.g. because we're building an interface module)
@@ -3403,6 +3812,7 @@ fn finish_specialization<'a>(
 
     Ok(MonomorphizedModule {
         can_problems,
+        lint_problems,
         type_problems,
         output_path,
         expectations: module_expectations,
@@ -3412,11 +3822,18 @@ fn finish_specialization<'a>(
         interns,
         layout_interner,
         procedures,
+        def_regions,
         entry_point,
         sources,
+        imports,
         timings: state.timings,
         toplevel_expects,
+        toplevel_benches,
         uses_prebuilt_platform,
+        platform_allocator,
+        platform_effects,
+        platform_hosts,
+        platform_build_command,
     })
 }
 
@@ -3484,11 +3901,13 @@ fn finish(
         interns,
         solved,
         can_problems: state.module_cache.can_problems,
+        lint_problems: state.module_cache.lint_problems,
         type_problems: state.module_cache.type_problems,
         declarations_by_id: state.declarations_by_id,
         dep_idents,
         exposed_aliases: exposed_aliases_by_symbol,
         exposed_values,
+        exposed_symbols_by_module: state.exposed_symbols_by_module,
         exposed_to_host: exposed_vars_by_symbol.into_iter().collect(),
         exposed_types_storage,
         resolved_implementations,
@@ -3625,6 +4044,76 @@ fn load_package_from_disk<'a>(
     }
 }
 
+/// The modules a package the root module depends on exposes. Once imported, these are registered
+/// under package-qualified names (e.g. `pf.Stdout`), whereas `exposes_ids` were registered
+/// unqualified; docs generation looks modules up by their unqualified names, which can resolve to
+/// either one, so both are included.
+fn dependency_exposed_module_ids<'a>(
+    arena: &'a Bump,
+    arc_modules: &Mutex<PackageModuleIds<'a>>,
+    shorthand: &'a str,
+    exposes: &'a [Loc<roc_parse::header::ModuleName<'a>>],
+    exposes_ids: &'a [ModuleId],
+) -> &'a [ModuleId] {
+    let mut module_ids = bumpalo::collections::Vec::with_capacity_in(2 * exposes.len(), arena);
+
+    {
+        // Lock just long enough to perform the minimal operations necessary.
+        let mut arc_modules = arc_modules.lock();
+
+        for loc_module_name in exposes {
+            let pq_module_name =
+                PQModuleName::Qualified(shorthand, loc_module_name.value.as_str().into());
+
+            module_ids.push(arc_modules.get_or_insert(&pq_module_name));
+        }
+    }
+
+    module_ids.extend_from_slice(exposes_ids);
+
+    module_ids.into_bump_slice()
+}
+
+/// What's needed to generate docs for a module
+#[derive(Debug, Clone, Copy)]
+struct DocsTarget<'a> {
+    /// The modules exposed by the module's package; types from other modules are obscured.
+    exposed_module_ids: &'a [ModuleId],
+    /// The shorthand of the package the module comes from, if that's not the root package
+    package: Option<&'a str>,
+}
+
+/// Docs are generated for the modules exposed by the root package and by each of its
+/// dependencies. Hosted modules are never exposed, but they are how a platform's effects are
+/// implemented, so they get docs as long as their platform does.
+fn docs_target<'a>(
+    state: &State<'a>,
+    module_id: ModuleId,
+    is_hosted: bool,
+) -> Option<DocsTarget<'a>> {
+    match state.arc_modules.lock().get_name(module_id)? {
+        PackageQualified::Qualified(shorthand, _) => {
+            let exposed = state.dependency_exposed_modules.get(shorthand)?;
+
+            (is_hosted || exposed.contains(&module_id)).then_some(DocsTarget {
+                exposed_module_ids: *exposed,
+                package: Some(*shorthand),
+            })
+        }
+        PackageQualified::Unqualified(_) => {
+            let is_root_platform_hosted =
+                is_hosted && matches!(state.platform_path, PlatformPath::RootIsPlatformModule);
+
+            (is_root_platform_hosted || state.exposed_modules.contains(&module_id)).then_some(
+                DocsTarget {
+                    exposed_module_ids: state.exposed_modules,
+                    package: None,
+                },
+            )
+        }
+    }
+}
+
 fn get_exposes_ids<'a>(
     entries: &'a [Loc<Spaced<'a, roc_parse::header::ModuleName<'a>>>],
     arena: &'a Bump,
@@ -3773,6 +4262,13 @@ fn load_module<'a>(
         "Decode", ModuleId::DECODE
         "Hash", ModuleId::HASH
         "Json", ModuleId::JSON
+        "Async", ModuleId::ASYNC
+        "Random", ModuleId::RANDOM
+        "SortedDict", ModuleId::SORTED_DICT
+        "SortedSet", ModuleId::SORTED_SET
+        "Bytes", ModuleId::BYTES
+        "Encoding", ModuleId::ENCODING
+        "Time", ModuleId::TIME
     }
 
     let (filename, opt_shorthand) = module_name_to_path(src_dir, &module_name, arc_shorthands);
@@ -4687,6 +5183,18 @@ fn build_header<'a>(
 }
 
 impl<'a> BuildTask<'a> {
+    /// The module the task is working on, once it's known
+    fn module_id(&self) -> Option<ModuleId> {
+        match self {
+            BuildTask::LoadModule { .. } => None,
+            BuildTask::Parse { header } => Some(header.module_id),
+            BuildTask::CanonicalizeAndConstrain { parsed, .. } => Some(parsed.module_id),
+            BuildTask::Solve { module, .. } => Some(module.module_id),
+            BuildTask::BuildPendingSpecializations { module_id, .. }
+            | BuildTask::MakeSpecializations { module_id, .. } => Some(*module_id),
+        }
+    }
+
     // TODO trim down these arguments - possibly by moving Constraint into Module
     fn solve_module(
         module: Module,
@@ -5111,6 +5619,7 @@ fn run_solve<'a>(
     let mut module = module;
     let loc_expects = std::mem::take(&mut module.loc_expects);
     let loc_dbgs = std::mem::take(&mut module.loc_dbgs);
+    let fixtures = std::mem::take(&mut module.fixtures);
     let module = module;
 
     let (solved_subs, solved_implementations, exposed_vars_by_symbol, problems, abilities_store) = {
@@ -5154,6 +5663,15 @@ fn run_solve<'a>(
     };
 
     let mut solved_subs = solved_subs;
+
+    let mut problems = problems;
+    if !module_id.is_builtin() {
+        problems.extend(roc_solve::ambiguous_number::find_ambiguous_number_literals(
+            solved_subs.inner_mut(),
+            &decls,
+        ));
+    }
+
     let exposed_types = roc_solve::module::exposed_types_storage_subs(
         module_id,
         &mut solved_subs,
@@ -5186,6 +5704,7 @@ fn run_solve<'a>(
         abilities_store,
         loc_expects,
         loc_dbgs,
+        fixtures,
     }
 }
 
@@ -5283,9 +5802,34 @@ fn build_platform_header<'a>(
     let requires_types = unspace(arena, header.requires.item.rigids.items);
     let imports = unspace(arena, header.imports.item.items);
 
+    let allocator = match &header.allocator {
+        Some(allocator) => PlatformAllocator::from_entries(allocator.item.items),
+        None => PlatformAllocator::default(),
+    };
+
+    let effects = match &header.effects {
+        Some(effects) => PlatformEffects::from_entries(effects.item.items),
+        None => PlatformEffects::default(),
+    };
+
+    let hosts = match &header.hosts {
+        Some(hosts) => bumpalo::collections::Vec::from_iter_in(
+            hosts.item.iter().map(PlatformHost::from_entry),
+            arena,
+        )
+        .into_bump_slice(),
+        None => &[],
+    };
+
+    let build_command = header.build.as_ref().map(|build| build.item.value.to_str());
+
     let header_type = HeaderType::Platform {
         // A config_shorthand of "" should be fine
         config_shorthand: opt_shorthand.unwrap_or_default(),
+        allocator,
+        effects,
+        hosts,
+        build_command,
         exposes_ids,
         opt_app_module_id,
         provides: provides.into_bump_slice(),
@@ -5320,15 +5864,20 @@ fn canonicalize_and_constrain<'a>(
     dep_idents: IdentIdsByModule,
     exposed_symbols: VecSet<Symbol>,
     aliases: MutMap<Symbol, Alias>,
+    deprecated_imports: MutMap<Symbol, Box<str>>,
+    imported_pattern_synonyms: MutMap<Symbol, PatternSynonym>,
     imported_abilities_state: PendingAbilitiesStore,
     parsed: ParsedModule<'a>,
     skip_constraint_gen: bool,
-    exposed_module_ids: &[ModuleId],
+    docs_target: Option<DocsTarget<'a>>,
+    use_test_doubles: bool,
 ) -> CanAndCon {
     let canonicalize_start = Instant::now();
 
     let ParsedModule {
         module_id,
+        module_path,
+        src,
         header_type,
         exposed_ident_ids,
         parsed_defs,
@@ -5359,7 +5908,10 @@ fn canonicalize_and_constrain<'a>(
         exposed_imports,
         exposed_symbols,
         &symbols_from_requires,
+        &deprecated_imports,
+        imported_pattern_synonyms,
         &mut var_store,
+        use_test_doubles,
     );
     let mut types = Types::new();
 
@@ -5378,6 +5930,30 @@ fn canonicalize_and_constrain<'a>(
 
     module_timing.canonicalize = canonicalize_end.duration_since(canonicalize_start);
 
+    let mut lint_problems = roc_can::naming::naming_problems(
+        module_id,
+        &module_output.scope.locals.ident_ids,
+        &module_output.declarations,
+        &module_output.aliases,
+    );
+
+    if let HeaderType::Interface { name, .. } | HeaderType::Hosted { name, .. } = &header_type {
+        let name = name.as_str();
+        let name_region = match src.find(name) {
+            Some(start) => Region::new(
+                Position::new(start as u32),
+                Position::new((start + name.len()) as u32),
+            ),
+            None => Region::zero(),
+        };
+
+        lint_problems.extend(roc_can::naming::module_name_problem(
+            name,
+            &module_path,
+            name_region,
+        ));
+    }
+
     // Generate documentation information
     // TODO: store timing information?
     let module_docs = match header_type {
@@ -5388,28 +5964,33 @@ fn canonicalize_and_constrain<'a>(
         }
         HeaderType::Interface { name, .. }
         | HeaderType::Builtin { name, .. }
-        | HeaderType::Hosted { name, .. }
-            if exposed_module_ids.contains(&parsed.module_id) =>
-        {
-            let mut scope = module_output.scope.clone();
-            scope.add_docs_imports();
-            let docs = crate::docs::generate_module_docs(
-                scope,
-                module_id,
-                module_ids,
-                name.as_str().into(),
-                &parsed_defs_for_docs,
+        | HeaderType::Hosted { name, .. } => match docs_target {
+            Some(DocsTarget {
                 exposed_module_ids,
-                module_output.exposed_symbols.clone(),
-                parsed.header_comments,
-            );
+                package,
+            }) => {
+                let mut scope = module_output.scope.clone();
+                scope.add_docs_imports();
+                let docs = crate::docs::generate_module_docs(
+                    scope,
+                    module_id,
+                    module_ids,
+                    name.as_str().into(),
+                    package,
+                    matches!(header_type, HeaderType::Hosted { .. }),
+                    &parsed_defs_for_docs,
+                    exposed_module_ids,
+                    module_output.exposed_symbols.clone(),
+                    parsed.header_comments,
+                );
 
-            Some(docs)
-        }
-        HeaderType::Interface { .. } | HeaderType::Builtin { .. } | HeaderType::Hosted { .. } => {
-            // This module isn't exposed by the platform, so don't generate docs for it!
-            None
-        }
+                Some(docs)
+            }
+            None => {
+                // This module isn't exposed by the platform, so don't generate docs for it!
+                None
+            }
+        },
     };
 
     // _before has an underscore because it's unused in --release builds
@@ -5462,6 +6043,13 @@ fn canonicalize_and_constrain<'a>(
                         | ModuleId::DICT
                         | ModuleId::SET
                         | ModuleId::HASH
+                        | ModuleId::ASYNC
+                        | ModuleId::RANDOM
+                        | ModuleId::SORTED_DICT
+                        | ModuleId::SORTED_SET
+                        | ModuleId::BYTES
+                        | ModuleId::ENCODING
+                        | ModuleId::TIME
                 );
 
                 if !name.is_builtin() || should_include_builtin {
@@ -5482,6 +6070,9 @@ fn canonicalize_and_constrain<'a>(
         abilities_store: module_output.scope.abilities_store,
         loc_expects: module_output.loc_expects,
         loc_dbgs: module_output.loc_dbgs,
+        deprecated: module_output.deprecated,
+        pattern_synonyms: module_output.pattern_synonyms,
+        fixtures: module_output.fixtures,
     };
 
     let constrained_module = ConstrainedModule {
@@ -5501,6 +6092,7 @@ fn canonicalize_and_constrain<'a>(
     CanAndCon {
         constrained_module,
         canonicalization_problems: module_output.problems,
+        lint_problems,
         module_docs,
     }
 }
@@ -5598,6 +6190,14 @@ fn exposed_from_import<'a>(entry: &ImportsEntry<'a>) -> (QualifiedModuleName<'a>
     }
 }
 
+/// The source code of a module, or nothing if it doesn't have any (like the derived modules)
+fn module_source<'a>(module_cache: &ModuleCache<'a>, module_id: ModuleId) -> &'a str {
+    module_cache
+        .sources
+        .get(&module_id)
+        .map_or("", |(_, source)| source)
+}
+
 fn ident_from_exposed(entry: &Spaced<'_, ExposedName<'_>>) -> Ident {
     entry.extract_spaces().item.as_str().into()
 }
@@ -5605,6 +6205,7 @@ fn ident_from_exposed(entry: &Spaced<'_, ExposedName<'_>>) -> Ident {
 fn make_specializations<'a>(
     arena: &'a Bump,
     home: ModuleId,
+    source: &'a str,
     mut ident_ids: IdentIds,
     mut subs: Subs,
     procs_base: ProcsBase<'a>,
@@ -5616,14 +6217,18 @@ fn make_specializations<'a>(
     exposed_by_module: &ExposedByModule,
     derived_module: SharedDerivedModule,
     mut expectations: Option<Expectations>,
+    keep_expects: bool,
 ) -> Msg<'a> {
     let make_specializations_start = Instant::now();
     let mut update_mode_ids = UpdateModeIds::new();
+    let line_info = LineInfo::new(source);
     // do the thing
     let mut mono_env = roc_mono::ir::Env {
         arena,
         subs: &mut subs,
         expectation_subs: expectations.as_mut().map(|e| &mut e.subs),
+        keep_dbgs: expectations.as_ref().map_or(false, |e| !e.dbgs.is_empty()),
+        keep_expects,
         home,
         ident_ids: &mut ident_ids,
         target_info,
@@ -5633,6 +6238,7 @@ fn make_specializations<'a>(
         abilities: AbilitiesView::World(&world_abilities),
         exposed_by_module,
         derived_module: &derived_module,
+        line_info: &line_info,
     };
 
     let mut procs = Procs::new_in(arena);
@@ -5686,6 +6292,7 @@ fn build_pending_specializations<'a>(
     solved_subs: Solved<Subs>,
     imported_module_thunks: &'a [Symbol],
     home: ModuleId,
+    source: &'a str,
     mut ident_ids: IdentIds,
     declarations: Declarations,
     mut module_timing: ModuleTiming,
@@ -5697,11 +6304,14 @@ fn build_pending_specializations<'a>(
     derived_module: SharedDerivedModule,
     mut expectations: Option<Expectations>,
     build_expects: bool,
+    build_benches: bool,
+    keep_expects: bool,
 ) -> Msg<'a> {
     let find_specializations_start = Instant::now();
 
     let mut module_thunks = bumpalo::collections::Vec::new_in(arena);
     let mut toplevel_expects = ToplevelExpects::default();
+    let mut toplevel_benches = VecMap::default();
 
     let mut procs_base = ProcsBase {
         partial_procs: BumpMap::default(),
@@ -5711,12 +6321,20 @@ fn build_pending_specializations<'a>(
         imported_module_thunks,
     };
 
+    let fixtures = match &expectations {
+        Some(expectations) if build_expects => expectations.fixtures.clone(),
+        _ => VecSet::default(),
+    };
+
     let mut update_mode_ids = UpdateModeIds::new();
     let mut subs = solved_subs.into_inner();
+    let line_info = LineInfo::new(source);
     let mut mono_env = roc_mono::ir::Env {
         arena,
         subs: &mut subs,
         expectation_subs: expectations.as_mut().map(|e| &mut e.subs),
+        keep_dbgs: expectations.as_ref().map_or(false, |e| !e.dbgs.is_empty()),
+        keep_expects,
         home,
         ident_ids: &mut ident_ids,
         target_info,
@@ -5729,10 +6347,17 @@ fn build_pending_specializations<'a>(
         abilities: AbilitiesView::Module(&abilities_store),
         exposed_by_module,
         derived_module: &derived_module,
+        line_info: &line_info,
     };
 
     let layout_cache_snapshot = layout_cache.snapshot();
 
+    let def_regions = declarations
+        .symbols
+        .iter()
+        .map(|loc_symbol| (loc_symbol.value, loc_symbol.region))
+        .collect();
+
     // Add modules' decls to Procs
     for index in 0..declarations.len() {
         use roc_can::expr::DeclarationTag::*;
@@ -5818,6 +6443,47 @@ fn build_pending_specializations<'a>(
                         };
 
                         procs_base.partial_procs.insert(symbol, proc);
+
+                        if fixtures.contains(&symbol) {
+                            // A fixture gets a thunk which evaluates it and returns `{}`, like a
+                            // `bench`, so the test runner can evaluate it before any expects run.
+                            let force_symbol = mono_env.unique_symbol();
+                            let thunk_var = Variable::EMPTY_RECORD;
+
+                            module_thunks.push(force_symbol);
+
+                            procs_base.host_specializations.insert_host_exposed(
+                                mono_env.subs,
+                                LambdaName::no_niche(force_symbol),
+                                None,
+                                thunk_var,
+                            );
+
+                            let result_symbol = mono_env.unique_symbol();
+                            let loc_fixture =
+                                Loc::at(body.region, roc_can::expr::Expr::Var(symbol, expr_var));
+                            let force_body = roc_can::expr::toplevel_bench_to_thunk(
+                                loc_fixture,
+                                expr_var,
+                                result_symbol,
+                            );
+
+                            let proc = PartialProc {
+                                annotation: thunk_var,
+                                pattern_symbols: &[],
+                                captured_symbols: CapturedSymbols::None,
+                                body: force_body.value,
+                                body_var: thunk_var,
+                                is_self_recursive: false,
+                            };
+
+                            let region = declarations.symbols[index].region;
+
+                            toplevel_expects
+                                .fixtures
+                                .insert(force_symbol, (symbol, region));
+                            procs_base.partial_procs.insert(force_symbol, proc);
+                        }
                     }
                 }
             }
@@ -6107,6 +6773,48 @@ fn build_pending_specializations<'a>(
                 toplevel_expects.fx.insert(symbol, region);
                 procs_base.partial_procs.insert(symbol, proc);
             }
+            Benchmark => {
+                // skip benchmarks if we're not going to run them
+                if !build_benches {
+                    continue;
+                }
+
+                // mark this symbol as a top-level thunk before any other work on the procs
+                module_thunks.push(symbol);
+
+                // the thunk returns `{}`, whatever the bench evaluates to
+                let thunk_var = Variable::EMPTY_RECORD;
+
+                procs_base.host_specializations.insert_host_exposed(
+                    mono_env.subs,
+                    LambdaName::no_niche(symbol),
+                    annotation,
+                    thunk_var,
+                );
+
+                let result_symbol = mono_env.unique_symbol();
+                let body = roc_can::expr::toplevel_bench_to_thunk(body, expr_var, result_symbol);
+
+                let proc = PartialProc {
+                    annotation: thunk_var,
+                    // This is a 0-arity thunk, so it has no arguments.
+                    pattern_symbols: &[],
+                    // This is a top-level definition, so it cannot capture anything
+                    captured_symbols: CapturedSymbols::None,
+                    body: body.value,
+                    body_var: thunk_var,
+                    // This is a 0-arity thunk, so it cannot be recursive
+                    is_self_recursive: false,
+                };
+
+                // like for expects, include the preceding comment, which names the benchmark
+                let name_region = declarations.symbols[index].region;
+                let expr_region = declarations.expressions[index].region;
+                let region = Region::span_across(&name_region, &expr_region);
+
+                toplevel_benches.insert(symbol, region);
+                procs_base.partial_procs.insert(symbol, proc);
+            }
         }
     }
 
@@ -6127,6 +6835,8 @@ fn build_pending_specializations<'a>(
         module_timing,
         abilities_store,
         toplevel_expects,
+        toplevel_benches,
+        def_regions,
         expectations,
     }
 }
@@ -6181,6 +6891,7 @@ fn load_derived_partial_procs<'a>(
     let mut new_module_thunks = bumpalo::collections::Vec::new_in(arena);
 
     let mut update_mode_ids = UpdateModeIds::new();
+    let line_info = LineInfo::new("");
 
     let derives_to_add = {
         let mut derived_module = derived_module.lock().unwrap();
@@ -6198,6 +6909,8 @@ fn load_derived_partial_procs<'a>(
             subs,
             // There are no derived expectations.
             expectation_subs: None,
+            keep_dbgs: false,
+            keep_expects: false,
             home,
             ident_ids,
             target_info,
@@ -6210,6 +6923,8 @@ fn load_derived_partial_procs<'a>(
             abilities: AbilitiesView::World(world_abilities),
             exposed_by_module,
             derived_module,
+            // Derived implementations don't have source code.
+            line_info: &line_info,
         };
 
         let partial_proc = match derived_expr {
@@ -6300,9 +7015,12 @@ fn run_task<'a>(
             dep_idents,
             exposed_symbols,
             aliases,
+            deprecated,
+            pattern_synonyms,
             abilities_store,
             skip_constraint_gen,
-            exposed_module_ids,
+            docs_target,
+            use_test_doubles,
         } => {
             let can_and_con = canonicalize_and_constrain(
                 arena,
@@ -6310,10 +7028,13 @@ fn run_task<'a>(
                 dep_idents,
                 exposed_symbols,
                 aliases,
+                deprecated,
+                pattern_synonyms,
                 abilities_store,
                 parsed,
                 skip_constraint_gen,
-                exposed_module_ids,
+                docs_target,
+                use_test_doubles,
             );
 
             Ok(Msg::CanonicalizedAndConstrained(can_and_con))
@@ -6349,6 +7070,7 @@ fn run_task<'a>(
         )),
         BuildPendingSpecializations {
             module_id,
+            source,
             ident_ids,
             decls,
             module_timing,
@@ -6361,11 +7083,14 @@ fn run_task<'a>(
             derived_module,
             expectations,
             build_expects,
+            build_benches,
+            keep_expects,
         } => Ok(build_pending_specializations(
             arena,
             solved_subs,
             imported_module_thunks,
             module_id,
+            source,
             ident_ids,
             decls,
             module_timing,
@@ -6377,9 +7102,12 @@ fn run_task<'a>(
             derived_module,
             expectations,
             build_expects,
+            build_benches,
+            keep_expects,
         )),
         MakeSpecializations {
             module_id,
+            source,
             ident_ids,
             subs,
             procs_base,
@@ -6390,9 +7118,11 @@ fn run_task<'a>(
             exposed_by_module,
             derived_module,
             expectations,
+            keep_expects,
         } => Ok(make_specializations(
             arena,
             module_id,
+            source,
             ident_ids,
             subs,
             procs_base,
@@ -6404,6 +7134,7 @@ fn run_task<'a>(
             &exposed_by_module,
             derived_module,
             expectations,
+            keep_expects,
         )),
     }?;
 
@@ -6414,7 +7145,7 @@ fn run_task<'a>(
     Ok(())
 }
 
-fn to_file_problem_report(filename: &Path, error: io::ErrorKind) -> String {
+fn to_file_problem_report<'a>(filename: &Path, error: io::ErrorKind) -> LoadingProblem<'a> {
     use roc_reporting::report::{Report, RocDocAllocator, DEFAULT_PALETTE};
     use ven_pretty::DocAllocator;
 
@@ -6488,20 +7219,45 @@ fn to_file_problem_report(filename: &Path, error: io::ErrorKind) -> String {
         }
     };
 
+    let diagnostic = Diagnostic::from_report(&report, None);
     let mut buf = String::new();
     let palette = DEFAULT_PALETTE;
     report.render_color_terminal(&mut buf, &alloc, &palette);
 
-    buf
+    LoadingProblem::FormattedReport(buf, Box::new(diagnostic))
 }
 
-fn to_import_cycle_report(
+/// Pairs up each module in `cycle` with the place where it imports the next one.
+fn import_cycle_edges<'a>(
+    header_imports: &MutMap<ModuleId, HeaderImports<'a>>,
+    cycle: &[ModuleId],
+) -> Vec<ImportCycleEdge<'a>> {
+    cycle
+        .windows(2)
+        .filter_map(|pair| {
+            let (module_id, imported) = (pair[0], pair[1]);
+            let imports = header_imports.get(&module_id)?;
+
+            Some(ImportCycleEdge {
+                module_id,
+                imported,
+                module_path: imports.module_path.clone(),
+                src: imports.src,
+                region: *imports.regions.get(&imported)?,
+                dependency_count: imports.regions.keys().filter(|id| !id.is_builtin()).count(),
+            })
+        })
+        .collect()
+}
+
+fn to_import_cycle_report<'a>(
     module_ids: ModuleIds,
     all_ident_ids: IdentIdsByModule,
     import_cycle: Vec<ModuleId>,
+    edges: Vec<ImportCycleEdge<'_>>,
     filename: PathBuf,
     render: RenderTarget,
-) -> String {
+) -> LoadingProblem<'a> {
     use roc_reporting::report::{Report, RocDocAllocator, DEFAULT_PALETTE};
     use ven_pretty::DocAllocator;
 
@@ -6510,17 +7266,47 @@ fn to_import_cycle_report(
     debug_assert!(import_cycle.len() >= 2);
     let source_of_cycle = import_cycle.first().unwrap();
 
-    // We won't be printing any lines for this report, so this is okay.
-    // TODO: it would be nice to show how each module imports another in the cycle.
+    // The imports we point at live in different files, so each one gets its own allocator.
     let src_lines = &[];
+    let edge_src_lines: Vec<Vec<&str>> = edges
+        .iter()
+        .map(|edge| {
+            std::str::from_utf8(edge.src)
+                .unwrap_or_default()
+                .lines()
+                .collect()
+        })
+        .collect();
 
     let interns = Interns {
         module_ids,
         all_ident_ids,
     };
     let alloc = RocDocAllocator::new(src_lines, *source_of_cycle, &interns);
+    let edge_allocs: Vec<RocDocAllocator> = edges
+        .iter()
+        .zip(edge_src_lines.iter())
+        .map(|(edge, lines)| RocDocAllocator::new(lines, edge.module_id, &interns))
+        .collect();
 
-    let doc = alloc.stack([
+    let import_snippets = edges
+        .iter()
+        .zip(edge_allocs.iter())
+        .map(|(edge, edge_alloc)| {
+            let line_info = LineInfo::new(std::str::from_utf8(edge.src).unwrap_or_default());
+
+            edge_alloc.stack([
+                edge_alloc.concat([
+                    edge_alloc.module(edge.module_id),
+                    edge_alloc.reflow(" imports "),
+                    edge_alloc.module(edge.imported),
+                    edge_alloc.reflow(" here:"),
+                ]),
+                edge_alloc.region(line_info.convert_region(edge.region)),
+            ])
+        });
+
+    let mut docs = vec![
         alloc.concat([
             alloc.reflow("I can't compile "),
             alloc.module(*source_of_cycle),
@@ -6533,13 +7319,42 @@ fn to_import_cycle_report(
             4,
             alloc.module(*source_of_cycle),
             import_cycle
-                .into_iter()
+                .iter()
                 .skip(1)
-                .map(|module| alloc.module(module))
+                .map(|module| alloc.module(*module))
                 .collect(),
         ),
-        alloc.reflow("Cyclic dependencies are not allowed in Roc! Can you restructure a module in this import chain so that it doesn't have to depend on itself?")
-    ]);
+    ];
+
+    docs.extend(import_snippets);
+    docs.push(alloc.reflow("Cyclic dependencies are not allowed in Roc! Can you restructure a module in this import chain so that it doesn't have to depend on itself?"));
+
+    // Removing the import from the module with the fewest imports of its own is likely to be the
+    // least invasive change. There is nothing to choose between when a module imports itself.
+    if edges.len() > 1 {
+        let edge = edges
+            .iter()
+            .min_by_key(|edge| edge.dependency_count)
+            .unwrap();
+
+        docs.push(alloc.tip().append(alloc.concat([
+            alloc.module(edge.module_id),
+            alloc.reflow(" imports the fewest modules in this chain, so its import of "),
+            alloc.module(edge.imported),
+            alloc.reflow(" may be the easiest one to remove."),
+        ])));
+    }
+
+    let doc = alloc.stack(docs);
+
+    // the import the cycle starts with, which is in the module the report is about
+    let region = edges
+        .first()
+        .filter(|edge| edge.module_path == filename)
+        .map(|edge| {
+            LineInfo::new(std::str::from_utf8(edge.src).unwrap_or_default())
+                .convert_region(edge.region)
+        });
 
     let report = Report {
         filename,
@@ -6548,10 +7363,12 @@ fn to_import_cycle_report(
         severity: Severity::RuntimeError,
     };
 
+    let diagnostic = Diagnostic::from_report(&report, region);
     let mut buf = String::new();
     let palette = DEFAULT_PALETTE;
     report.render(render, &mut buf, &alloc, &palette);
-    buf
+
+    LoadingProblem::FormattedReport(buf, Box::new(diagnostic))
 }
 
 fn to_incorrect_module_name_report<'a>(
@@ -6561,7 +7378,7 @@ fn to_incorrect_module_name_report<'a>(
     filename: PathBuf,
     src: &'a [u8],
     render: RenderTarget,
-) -> String {
+) -> LoadingProblem<'a> {
     use roc_reporting::report::{Report, RocDocAllocator, DEFAULT_PALETTE};
     use ven_pretty::DocAllocator;
 
@@ -6597,10 +7414,12 @@ fn to_incorrect_module_name_report<'a>(
         severity: Severity::RuntimeError,
     };
 
+    let diagnostic = Diagnostic::from_report(&report, Some(lines.convert_region(found.region)));
     let mut buf = String::new();
     let palette = DEFAULT_PALETTE;
     report.render(render, &mut buf, &alloc, &palette);
-    buf
+
+    LoadingProblem::FormattedReport(buf, Box::new(diagnostic))
 }
 
 fn to_parse_problem_report<'a>(
@@ -6609,7 +7428,7 @@ fn to_parse_problem_report<'a>(
     all_ident_ids: IdentIdsByModule,
     render: RenderTarget,
     palette: Palette,
-) -> String {
+) -> LoadingProblem<'a> {
     use roc_reporting::report::{parse_problem, RocDocAllocator};
 
     // TODO this is not in fact safe
@@ -6640,14 +7459,61 @@ fn to_parse_problem_report<'a>(
         problem,
     );
 
+    let diagnostic = Diagnostic::from_report(&report, None);
     let mut buf = String::new();
 
     report.render(render, &mut buf, &alloc, &palette);
 
-    buf
+    LoadingProblem::FormattedReport(buf, Box::new(diagnostic))
+}
+
+fn to_memory_limit_report<'a>(state: &State<'a>, module_id: ModuleId) -> LoadingProblem<'a> {
+    use roc_reporting::report::{Report, RocDocAllocator};
+    use ven_pretty::DocAllocator;
+
+    let limit = match state.memory.limit().unwrap_or_default() {
+        bytes if bytes < 1 << 20 => format!("{} bytes", bytes),
+        bytes => format!("{} MB", bytes >> 20),
+    };
+    let filename = match state.module_cache.sources.get(&module_id) {
+        Some((path, _)) => path.clone(),
+        None => "UNKNOWN.roc".into(),
+    };
+
+    let interns = Interns {
+        module_ids: state.arc_modules.lock().clone().into_module_ids(),
+        all_ident_ids: IdentIds::exposed_builtins(0),
+    };
+    let alloc = RocDocAllocator::new(&[], module_id, &interns);
+
+    let doc = alloc.stack([
+        alloc.concat([
+            alloc.reflow("Compilation exceeded its memory limit of "),
+            alloc.text(limit),
+            alloc.reflow(" while I was working on the "),
+            alloc.module(module_id),
+            alloc.reflow(" module."),
+        ]),
+        alloc.reflow(
+            "Is this module, or one it imports, much bigger than expected? If not, the limit can be raised with --max-memory, or with the ROC_MAX_MEMORY environment variable.",
+        ),
+    ]);
+
+    let report = Report {
+        filename,
+        doc,
+        title: "MEMORY LIMIT EXCEEDED".to_string(),
+        severity: Severity::RuntimeError,
+    };
+
+    let diagnostic = Diagnostic::from_report(&report, None);
+    let mut buf = String::new();
+    report.render(state.render, &mut buf, &alloc, &state.palette);
+
+    LoadingProblem::FormattedReport(buf, Box::new(diagnostic))
 }
 
-fn to_missing_platform_report(module_id: ModuleId, other: PlatformPath) -> String {
+fn to_missing_platform_report<'a>(module_id: ModuleId, other: PlatformPath) -> LoadingProblem<'a> {
     use roc_reporting::report::{Report, RocDocAllocator, DEFAULT_PALETTE};
     use ven_pretty::DocAllocator;
     use PlatformPath::*;
@@ -6724,9 +7590,10 @@ fn to_missing_platform_report(module_id: ModuleId, other: PlatformPath) -> Strin
         }
     };
 
+    let diagnostic = Diagnostic::from_report(&report, None);
     let palette = DEFAULT_PALETTE;
     let mut buf = String::new();
     report.render_color_terminal(&mut buf, &alloc, &palette);
 
-    buf
+    LoadingProblem::FormattedReport(buf, Box::new(diagnostic))
 }
@@ -4,8 +4,11 @@
 #![allow(clippy::large_enum_variant)]
 
 use roc_module::symbol::ModuleId;
+pub mod cancel;
 pub mod docs;
 pub mod file;
+mod memory;
+pub mod plugin;
 mod work;
 
 #[cfg(target_family = "wasm")]
@@ -24,4 +27,11 @@ pub const BUILTIN_MODULES: &[(ModuleId, &str)] = &[
     (ModuleId::DECODE, "Decode"),
     (ModuleId::HASH, "Hash"),
     (ModuleId::JSON, "Json"),
+    (ModuleId::ASYNC, "Async"),
+    (ModuleId::RANDOM, "Random"),
+    (ModuleId::SORTED_DICT, "SortedDict"),
+    (ModuleId::SORTED_SET, "SortedSet"),
+    (ModuleId::BYTES, "Bytes"),
+    (ModuleId::ENCODING, "Encoding"),
+    (ModuleId::TIME, "Time"),
 ];
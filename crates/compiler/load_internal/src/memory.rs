@@ -0,0 +1,33 @@
+//! Keeping a load within a memory limit, so running out of memory is reported like any other
+//! problem, rather than the compiler being killed partway through by the operating system.
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// How much memory a load has used so far, as estimated from what the worker arenas have
+/// allocated and the size of each module's solved types
+#[derive(Debug)]
+pub(crate) struct MemoryBudget {
+    /// In bytes. Without a limit, nothing is counted.
+    limit: Option<usize>,
+    used: AtomicUsize,
+}
+
+impl MemoryBudget {
+    pub fn new(limit: Option<usize>) -> Self {
+        Self {
+            limit,
+            used: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    /// Records that `bytes` more are in use, and returns whether the load is now over its limit
+    pub fn charge(&self, bytes: usize) -> bool {
+        match self.limit {
+            Some(limit) => self.used.fetch_add(bytes, Ordering::Relaxed) + bytes > limit,
+            None => false,
+        }
+    }
+}
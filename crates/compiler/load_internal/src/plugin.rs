@@ -0,0 +1,173 @@
+//! Hooks that let tools outside the compiler, like third-party linters and metrics, look at each
+//! module once its types are solved, without forking the compiler.
+//!
+//! Rust tools implement [AnalysisPlugin] and see the canonical IR directly. Anything else can be
+//! a shared library with the C ABI that [DylibPlugin] loads.
+use roc_can::expr::{DeclarationTag, Declarations};
+use roc_module::symbol::{Interns, ModuleId};
+use roc_types::pretty_print::{name_and_print_var, DebugPrint};
+use roc_types::subs::Subs;
+use std::fmt::Debug;
+use std::path::Path;
+use std::sync::Arc;
+
+/// A module whose types have been solved, as a plugin sees it
+pub struct SolvedModuleInfo<'a> {
+    pub module_id: ModuleId,
+    pub path: &'a Path,
+    pub src: &'a str,
+    /// The module's canonical IR
+    pub declarations: &'a Declarations,
+    /// The solved types. The variables in `declarations` refer to these.
+    pub subs: &'a Subs,
+    /// The names of this module's symbols, and those of every module it depends on
+    pub interns: &'a Interns,
+}
+
+pub trait AnalysisPlugin: Send + Sync {
+    /// Called once for every module which isn't a builtin, on the thread that coordinates
+    /// loading, so it should return quickly.
+    fn module_solved(&self, module: &SolvedModuleInfo<'_>);
+}
+
+/// The plugins to call while loading. There are none by default.
+#[derive(Clone, Default)]
+pub struct Plugins(Vec<Arc<dyn AnalysisPlugin>>);
+
+impl Plugins {
+    pub fn register(&mut self, plugin: Arc<dyn AnalysisPlugin>) {
+        self.0.push(plugin);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub(crate) fn module_solved(&self, module: &SolvedModuleInfo<'_>) {
+        for plugin in self.0.iter() {
+            plugin.module_solved(module);
+        }
+    }
+}
+
+impl Debug for Plugins {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Plugins({})", self.0.len())
+    }
+}
+
+/// A string for the C ABI. It isn't nul-terminated.
+#[repr(C)]
+pub struct RocPluginStr {
+    pub bytes: *const u8,
+    pub len: usize,
+}
+
+impl RocPluginStr {
+    fn new(str: &str) -> Self {
+        Self {
+            bytes: str.as_ptr(),
+            len: str.len(),
+        }
+    }
+}
+
+/// A top-level def, with its type the way `roc check` would print it
+#[repr(C)]
+pub struct RocPluginDef {
+    pub name: RocPluginStr,
+    pub type_: RocPluginStr,
+    /// Byte offsets of the def's name in the module's source
+    pub start: u32,
+    pub end: u32,
+}
+
+#[repr(C)]
+pub struct RocPluginModule {
+    pub name: RocPluginStr,
+    pub path: RocPluginStr,
+    pub defs: *const RocPluginDef,
+    pub defs_len: usize,
+}
+
+type ModuleSolvedFn = unsafe extern "C" fn(*const RocPluginModule);
+
+/// A plugin in a shared library, which exports
+///
+/// ```c
+/// void roc_plugin_module_solved(const RocPluginModule *module);
+/// ```
+///
+/// Nothing it's given lives past the call, so it must copy anything it wants to keep.
+pub struct DylibPlugin {
+    module_solved: ModuleSolvedFn,
+    // keeps `module_solved` loaded
+    _library: libloading::Library,
+}
+
+impl DylibPlugin {
+    pub const MODULE_SOLVED_SYMBOL: &'static [u8] = b"roc_plugin_module_solved";
+
+    /// # Safety
+    ///
+    /// Loading the library runs its initializers, and `roc_plugin_module_solved` must have the
+    /// signature above.
+    pub unsafe fn open(path: &Path) -> Result<Self, libloading::Error> {
+        let library = libloading::Library::new(path)?;
+        let module_solved = *library.get::<ModuleSolvedFn>(Self::MODULE_SOLVED_SYMBOL)?;
+
+        Ok(Self {
+            module_solved,
+            _library: library,
+        })
+    }
+}
+
+impl AnalysisPlugin for DylibPlugin {
+    fn module_solved(&self, module: &SolvedModuleInfo<'_>) {
+        let declarations = module.declarations;
+
+        // naming type variables to print them changes the subs
+        let mut subs = module.subs.clone();
+
+        let mut names_and_types = Vec::with_capacity(declarations.len());
+        for (index, tag) in declarations.declarations.iter().enumerate() {
+            use DeclarationTag::*;
+
+            if let Value | Function(_) | Recursive(_) | TailRecursive(_) = tag {
+                let loc_symbol = declarations.symbols[index];
+                let type_ = name_and_print_var(
+                    declarations.variables[index],
+                    &mut subs,
+                    module.module_id,
+                    module.interns,
+                    DebugPrint::NOTHING,
+                );
+
+                names_and_types.push((loc_symbol, type_));
+            }
+        }
+
+        let defs: Vec<RocPluginDef> = names_and_types
+            .iter()
+            .map(|(loc_symbol, type_)| RocPluginDef {
+                name: RocPluginStr::new(loc_symbol.value.as_str(module.interns)),
+                type_: RocPluginStr::new(type_),
+                start: loc_symbol.region.start().offset,
+                end: loc_symbol.region.end().offset,
+            })
+            .collect();
+
+        let name = module.interns.module_name(module.module_id);
+        let path = module.path.to_string_lossy();
+
+        let c_module = RocPluginModule {
+            name: RocPluginStr::new(name.as_str()),
+            path: RocPluginStr::new(&path),
+            defs: defs.as_ptr(),
+            defs_len: defs.len(),
+        };
+
+        unsafe { (self.module_solved)(&c_module) };
+    }
+}
@@ -17,8 +17,10 @@ mod helpers;
 use crate::helpers::fixtures_dir;
 use bumpalo::Bump;
 use roc_can::module::ExposedByModule;
+use roc_load_internal::cancel::CancellationToken;
 use roc_load_internal::file::{ExecutionMode, LoadConfig, Threading};
 use roc_load_internal::file::{LoadResult, LoadStart, LoadedModule, LoadingProblem};
+use roc_load_internal::plugin::{AnalysisPlugin, Plugins, SolvedModuleInfo};
 use roc_module::ident::ModuleName;
 use roc_module::symbol::{Interns, ModuleId};
 use roc_packaging::cache::RocCacheDir;
@@ -32,12 +34,33 @@ use roc_types::pretty_print::name_and_print_var;
 use roc_types::pretty_print::DebugPrint;
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 
 fn load_and_typecheck(
     arena: &Bump,
     filename: PathBuf,
     exposed_types: ExposedByModule,
     target_info: TargetInfo,
+) -> Result<LoadedModule, LoadingProblem> {
+    load_and_typecheck_with(arena, filename, exposed_types, load_config(target_info))
+}
+
+fn load_config(target_info: TargetInfo) -> LoadConfig {
+    LoadConfig {
+        target_info,
+        render: RenderTarget::Generic,
+        palette: DEFAULT_PALETTE,
+        threading: Threading::Single,
+        exec_mode: ExecutionMode::Check,
+        ..Default::default()
+    }
+}
+
+fn load_and_typecheck_with(
+    arena: &Bump,
+    filename: PathBuf,
+    exposed_types: ExposedByModule,
+    load_config: LoadConfig,
 ) -> Result<LoadedModule, LoadingProblem> {
     use LoadResult::*;
 
@@ -48,13 +71,6 @@ fn load_and_typecheck(
         RocCacheDir::Disallowed,
         DEFAULT_PALETTE,
     )?;
-    let load_config = LoadConfig {
-        target_info,
-        render: RenderTarget::Generic,
-        palette: DEFAULT_PALETTE,
-        threading: Threading::Single,
-        exec_mode: ExecutionMode::Check,
-    };
 
     match roc_load_internal::file::load(
         arena,
@@ -105,7 +121,7 @@ fn multiple_modules(subdir: &str, files: Vec<(&str, &str)>) -> Result<LoadedModu
 
     match multiple_modules_help(subdir, arena, files) {
         Err(io_error) => panic!("IO trouble: {:?}", io_error),
-        Ok(Err(LoadingProblem::FormattedReport(buf))) => Err(buf),
+        Ok(Err(LoadingProblem::FormattedReport(buf, _))) => Err(buf),
         Ok(Err(loading_problem)) => Err(format!("{:?}", loading_problem)),
         Ok(Ok(mut loaded_module)) => {
             let home = loaded_module.module_id;
@@ -191,7 +207,7 @@ fn load_fixture(
     let loaded = load_and_typecheck(&arena, filename, subs_by_module, TARGET_INFO);
     let mut loaded_module = match loaded {
         Ok(x) => x,
-        Err(roc_load_internal::file::LoadingProblem::FormattedReport(report)) => {
+        Err(roc_load_internal::file::LoadingProblem::FormattedReport(report, _)) => {
             println!("{}", report);
             panic!("{}", report);
         }
@@ -288,6 +304,10 @@ fn expect_types(mut loaded_module: LoadedModule, mut expected_types: HashMap<&st
                 // at least at the moment this does not happen
                 panic!("Unexpected expectation in module declarations");
             }
+            Benchmark => {
+                // at least at the moment this does not happen
+                panic!("Unexpected benchmark in module declarations");
+            }
         };
     }
 
@@ -373,7 +393,7 @@ fn interface_with_deps() {
                 def_count += 1;
             }
             MutualRecursion { .. } => { /* do nothing, not a def */ }
-            Expectation | ExpectationFx => { /* do nothing, not a def */ }
+            Expectation | ExpectationFx | Benchmark => { /* do nothing, not a def */ }
         }
     }
 
@@ -387,6 +407,132 @@ fn interface_with_deps() {
     assert_eq!(def_count, 10);
 }
 
+#[test]
+fn plugin_sees_each_solved_module() {
+    #[derive(Default)]
+    struct TypesByModule(Mutex<Vec<(String, Vec<(String, String)>)>>);
+
+    impl AnalysisPlugin for TypesByModule {
+        fn module_solved(&self, module: &SolvedModuleInfo<'_>) {
+            let declarations = module.declarations;
+            let mut subs = module.subs.clone();
+
+            let mut types = Vec::new();
+            for index in 0..declarations.len() {
+                let symbol = declarations.symbols[index].value;
+                let type_ = name_and_print_var(
+                    declarations.variables[index],
+                    &mut subs,
+                    module.module_id,
+                    module.interns,
+                    DebugPrint::NOTHING,
+                );
+
+                types.push((symbol.as_str(module.interns).to_string(), type_));
+            }
+
+            let name = module.interns.module_name(module.module_id).to_string();
+            self.0.lock().unwrap().push((name, types));
+        }
+    }
+
+    let plugin = Arc::new(TypesByModule::default());
+    let mut plugins = Plugins::default();
+    plugins.register(plugin.clone());
+
+    let filename = fixtures_dir()
+        .join("interface_with_deps")
+        .join("Primary.roc");
+    let arena = Bump::new();
+    load_and_typecheck_with(
+        &arena,
+        filename,
+        Default::default(),
+        LoadConfig {
+            plugins,
+            ..load_config(TARGET_INFO)
+        },
+    )
+    .expect("Test module failed to load");
+
+    let mut modules = plugin.0.lock().unwrap().clone();
+    modules.sort();
+
+    let names: Vec<&str> = modules.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["Dep1", "Dep2", "Dep3.Blah", "Primary", "Res"]);
+
+    let (_, dep1_types) = &modules[0];
+    assert!(dep1_types.contains(&("str".to_string(), "Str".to_string())));
+}
+
+#[test]
+fn cancelled_load_stops_early() {
+    // cancels the load as soon as the first module is solved
+    struct CancelOnSolve {
+        cancellation: CancellationToken,
+        solved: Mutex<usize>,
+    }
+
+    impl AnalysisPlugin for CancelOnSolve {
+        fn module_solved(&self, _: &SolvedModuleInfo<'_>) {
+            *self.solved.lock().unwrap() += 1;
+            self.cancellation.cancel();
+        }
+    }
+
+    let cancellation = CancellationToken::new();
+    let plugin = Arc::new(CancelOnSolve {
+        cancellation: cancellation.clone(),
+        solved: Mutex::new(0),
+    });
+    let mut plugins = Plugins::default();
+    plugins.register(plugin.clone());
+
+    let filename = fixtures_dir()
+        .join("interface_with_deps")
+        .join("Primary.roc");
+    let arena = Bump::new();
+    let result = load_and_typecheck_with(
+        &arena,
+        filename,
+        Default::default(),
+        LoadConfig {
+            plugins,
+            cancellation,
+            ..load_config(TARGET_INFO)
+        },
+    );
+
+    assert!(matches!(result, Err(LoadingProblem::Cancelled)));
+    assert_eq!(*plugin.solved.lock().unwrap(), 1);
+}
+
+#[test]
+fn exceeding_memory_limit_is_reported() {
+    let filename = fixtures_dir()
+        .join("interface_with_deps")
+        .join("Primary.roc");
+    let arena = Bump::new();
+    let result = load_and_typecheck_with(
+        &arena,
+        filename,
+        Default::default(),
+        LoadConfig {
+            memory_limit: Some(1),
+            ..load_config(TARGET_INFO)
+        },
+    );
+
+    match result {
+        Err(LoadingProblem::FormattedReport(report, diagnostic)) => {
+            assert_eq!(diagnostic.title, "MEMORY LIMIT EXCEEDED");
+            assert!(report.contains("exceeded its memory limit"), "{}", report);
+        }
+        Err(other) => panic!("expected the memory limit to be reported, got {:?}", other),
+        Ok(_) => panic!("expected the memory limit to be reported"),
+    }
+}
+
 #[test]
 fn load_unit() {
     let subs_by_module = Default::default();
@@ -826,6 +972,137 @@ fn opaque_wrapped_unwrapped_outside_defining_module() {
     );
 }
 
+#[test]
+fn deprecated_value_used_from_another_module() {
+    let modules = vec![
+        (
+            "Util",
+            indoc!(
+                r#"
+                    interface Util exposes [oldAdd, add] imports []
+
+                    ## Adds two numbers.
+                    ## @deprecated Use Util.add instead.
+                    oldAdd = \a, b -> a + b
+
+                    add = \a, b -> a + b
+                    "#
+            ),
+        ),
+        (
+            "Main",
+            indoc!(
+                r#"
+                    interface Main exposes [three] imports [Util]
+
+                    three = Util.oldAdd 1 2
+                    "#
+            ),
+        ),
+    ];
+
+    let err = multiple_modules("deprecated_value_used_from_another_module", modules).unwrap_err();
+    assert_eq!(
+        err,
+        indoc!(
+            r#"
+                ── DEPRECATED VALUE ─────── tmp/deprecated_value_used_from_another_module/Main ─
+
+                Util.oldAdd is deprecated:
+
+                3│  three = Util.oldAdd 1 2
+                            ^^^^^^^^^^^
+
+                Use Util.add instead.
+                "#
+        ),
+        "\n{}",
+        err
+    );
+}
+
+#[test]
+fn exposed_function_always_crashes() {
+    let modules = vec![(
+        "Main",
+        indoc!(
+            r#"
+                interface Main exposes [parse, check] imports []
+
+                parse = \_ -> crash "todo"
+
+                check = \ok -> if ok then ok else crash "not ok"
+                "#
+        ),
+    )];
+
+    let err = multiple_modules("exposed_function_always_crashes", modules).unwrap_err();
+    assert_eq!(
+        err,
+        indoc!(
+            r#"
+                ── UNCONDITIONAL CRASH ────────────── tmp/exposed_function_always_crashes/Main ─
+
+                Every call to `parse` crashes, because it always reaches this crash:
+
+                3│  parse = \_ -> crash "todo"
+                                  ^^^^^^^^^^^^
+
+                `parse` is exposed, so other modules can call it. If it isn't finished
+                yet, finish it before they do; otherwise, consider returning a Result
+                instead of crashing.
+                "#
+        ),
+        "\n{}",
+        err
+    );
+}
+
+#[test]
+fn naming_convention_lints() {
+    let modules = vec![(
+        "Main",
+        indoc!(
+            r#"
+                interface Mian exposes [add_one, favorite, Shade_Kind] imports []
+
+                Shade_Kind : [Light, Dark]
+
+                add_one = \n -> n + 1
+
+                favorite = Dark_Gray
+                "#
+        ),
+    )];
+
+    let mut loaded_module = multiple_modules("naming_convention_lints", modules).unwrap();
+    let home = loaded_module.module_id;
+
+    let suggestions: Vec<(String, String)> = loaded_module
+        .lint_problems
+        .remove(&home)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|problem| match problem {
+            Problem::NamingConvention {
+                name, suggestion, ..
+            } => (name.to_string(), suggestion.to_string()),
+            other => panic!("unexpected lint problem: {:?}", other),
+        })
+        .collect();
+
+    assert_eq!(
+        suggestions,
+        [
+            ("Mian", "Main"),
+            ("Shade_Kind", "ShadeKind"),
+            ("add_one", "addOne"),
+            ("Dark_Gray", "DarkGray"),
+        ]
+        .map(|(name, suggestion)| (name.to_string(), suggestion.to_string()))
+    );
+}
+
 #[test]
 fn issue_2863_module_type_does_not_exist() {
     let modules = vec![
@@ -986,6 +1263,11 @@ fn module_cyclic_import_itself() {
                 │     Age
                 └─────┘
 
+            Age imports Age here:
+
+            1│  interface Age exposes [] imports [Age]
+                                                  ^^^
+
             Cyclic dependencies are not allowed in Roc! Can you restructure a
             module in this import chain so that it doesn't have to depend on
             itself?"#
@@ -1034,9 +1316,22 @@ fn module_cyclic_import_transitive() {
                 │     Age
                 └─────┘
 
+            Age imports Person here:
+
+            1│  interface Age exposes [] imports [Person]
+                                                  ^^^^^^
+
+            Person imports Age here:
+
+            1│  interface Person exposes [] imports [Age]
+                                                     ^^^
+
             Cyclic dependencies are not allowed in Roc! Can you restructure a
             module in this import chain so that it doesn't have to depend on
-            itself?"#
+            itself?
+
+            Tip: Age imports the fewest modules in this chain, so its import of
+            Person may be the easiest one to remove."#
         ),
         "\n{}",
         err
@@ -1084,3 +1379,109 @@ fn nested_module_has_incorrect_name() {
         err
     );
 }
+
+#[test]
+fn incorrect_module_name_diagnostic() {
+    let modules = vec![
+        (
+            "Dep/Foo.roc",
+            indoc!(
+                r#"
+                interface Foo exposes [] imports []
+                "#
+            ),
+        ),
+        (
+            "I.roc",
+            indoc!(
+                r#"
+                interface I exposes [] imports [Dep.Foo]
+                "#
+            ),
+        ),
+    ];
+
+    let arena = Bump::new();
+    let diagnostic =
+        match multiple_modules_help("incorrect_module_name_diagnostic", &arena, modules) {
+            Ok(Err(LoadingProblem::FormattedReport(_, diagnostic))) => diagnostic,
+            _ => panic!("expected the incorrect module name to be reported"),
+        };
+
+    assert_eq!(diagnostic.title, "INCORRECT MODULE NAME");
+    assert_eq!(diagnostic.severity, roc_problem::Severity::RuntimeError);
+    assert!(diagnostic
+        .path
+        .ends_with("tmp/incorrect_module_name_diagnostic/Dep/Foo.roc"));
+
+    let region = diagnostic.region.unwrap();
+    assert_eq!((region.start.line, region.start.column), (0, 10));
+    assert_eq!((region.end.line, region.end.column), (0, 13));
+    assert!(diagnostic
+        .message
+        .contains("I expect it to have name\n\n    Dep.Foo"));
+}
+
+#[test]
+fn semantic_tokens_resolve_identifiers() {
+    use roc_can::semantic_tokens::{
+        semantic_tokens, SemanticTokenType::*, MODIFIER_DECLARATION, MODIFIER_DEFAULT_LIBRARY,
+    };
+
+    let modules = vec![(
+        "Main.roc",
+        indoc!(
+            r#"
+            interface Main exposes [main] imports []
+
+            main = \x ->
+                when x is
+                    Ok n -> Num.toStr n
+                    Err _ -> "none"
+            "#
+        ),
+    )];
+
+    let mut loaded_module =
+        multiple_modules("semantic_tokens_resolve_identifiers", modules).unwrap();
+    let home = loaded_module.module_id;
+    let (_, source) = loaded_module.sources.get(&home).unwrap();
+    let decls = loaded_module.declarations_by_id.remove(&home).unwrap();
+
+    let tokens = semantic_tokens(
+        source,
+        &decls,
+        loaded_module.solved.inner(),
+        &loaded_module.abilities_store,
+        &loaded_module.interns,
+        &[],
+    );
+
+    let header_end = source.find('\n').unwrap() as u32;
+    let identifiers: Vec<_> = tokens
+        .iter()
+        .filter(|token| token.region.start().offset > header_end)
+        .filter(|token| !matches!(token.token_type, Keyword | String | Operator))
+        .map(|token| {
+            let start = token.region.start().offset as usize;
+            let end = token.region.end().offset as usize;
+
+            (&source[start..end], token.token_type, token.modifiers)
+        })
+        .collect();
+
+    assert_eq!(
+        identifiers,
+        vec![
+            ("main", Function, MODIFIER_DECLARATION),
+            ("x", Parameter, MODIFIER_DECLARATION),
+            ("x", Parameter, 0),
+            ("Ok", Tag, 0),
+            ("n", Variable, MODIFIER_DECLARATION),
+            ("Num", Namespace, 0),
+            ("toStr", Function, MODIFIER_DEFAULT_LIBRARY),
+            ("n", Variable, 0),
+            ("Err", Tag, 0),
+        ]
+    );
+}
@@ -108,6 +108,13 @@ impl ModuleName {
     pub const DECODE: &'static str = "Decode";
     pub const HASH: &'static str = "Hash";
     pub const JSON: &'static str = "Json";
+    pub const ASYNC: &'static str = "Async";
+    pub const RANDOM: &'static str = "Random";
+    pub const SORTED_DICT: &'static str = "SortedDict";
+    pub const SORTED_SET: &'static str = "SortedSet";
+    pub const BYTES: &'static str = "Bytes";
+    pub const ENCODING: &'static str = "Encoding";
+    pub const TIME: &'static str = "Time";
 
     pub fn as_str(&self) -> &str {
         self.0.as_str()
@@ -7,5 +7,6 @@
 pub mod called_via;
 pub mod ident;
 pub mod low_level;
+pub mod mangle;
 pub mod module_err;
 pub mod symbol;
@@ -32,6 +32,8 @@ pub enum LowLevel {
     StrGetCapacity,
     StrWithCapacity,
     StrGraphemes,
+    StrMatches,
+    StrCapture,
     ListLen,
     ListWithCapacity,
     ListReserve,
@@ -41,6 +43,7 @@ pub enum LowLevel {
     ListConcat,
     ListPrepend,
     ListMap,
+    ListMapConcurrent,
     ListMap2,
     ListMap3,
     ListMap4,
@@ -84,23 +87,50 @@ pub enum LowLevel {
     NumCeiling,
     NumPowInt,
     NumFloor,
+    NumIsNan,
+    NumIsInfinite,
     NumIsFinite,
     NumAtan,
     NumAcos,
     NumAsin,
     NumBytesToU16,
     NumBytesToU32,
+    RandomMix64,
+    RandomMixGamma,
+    RandomTestSeed,
+    BytesReadU16,
+    BytesReadU32,
+    BytesReadU64,
+    EncodingEncode,
+    EncodingDecode,
+    EncodingCheck,
+    JsonSkipWhitespace,
+    JsonScanString,
+    JsonScanValue,
+    JsonUnescape,
+    JsonEscape,
+    TimeFormat,
+    TimeCheck,
+    TimeParseSeconds,
+    TimeParseNanos,
     NumBitwiseAnd,
     NumBitwiseXor,
     NumBitwiseOr,
     NumShiftLeftBy,
     NumShiftRightBy,
     NumShiftRightZfBy,
+    NumPopCount,
+    NumLeadingZeros,
+    NumTrailingZeros,
+    NumRotateLeftBy,
+    NumRotateRightBy,
+    NumSwapBytes,
     NumIntCast,
     NumToFloatCast,
     NumToIntChecked,
     NumToFloatChecked,
     NumToStr,
+    NumToStrWith,
     Eq,
     NotEq,
     And,
@@ -117,7 +147,7 @@ pub enum LowLevel {
 
 macro_rules! higher_order {
     () => {
-        ListMap | ListMap2 | ListMap3 | ListMap4 | ListSortWith
+        ListMap | ListMapConcurrent | ListMap2 | ListMap3 | ListMap4 | ListSortWith
     };
 }
 
@@ -135,6 +165,7 @@ impl LowLevel {
 
         match self {
             ListMap => 1,
+            ListMapConcurrent => 1,
             ListMap2 => 2,
             ListMap3 => 3,
             ListMap4 => 4,
@@ -193,6 +224,7 @@ macro_rules! map_symbol_to_lowlevel {
                 // these are higher-order lowlevels. these need the surrounding
                 // function to provide enough type information for code generation
                 LowLevel::ListMap => unreachable!(),
+                LowLevel::ListMapConcurrent => unreachable!(),
                 LowLevel::ListMap2 => unreachable!(),
                 LowLevel::ListMap3 => unreachable!(),
                 LowLevel::ListMap4 => unreachable!(),
@@ -218,7 +250,6 @@ macro_rules! map_symbol_to_lowlevel {
                 // these are not implemented, not sure why
                 LowLevel::StrFromInt => unimplemented!(),
                 LowLevel::StrFromFloat => unimplemented!(),
-                LowLevel::NumIsFinite => unimplemented!(),
             }
         }
     };
@@ -253,6 +284,8 @@ map_symbol_to_lowlevel! {
     StrGetCapacity <= STR_CAPACITY,
     StrWithCapacity <= STR_WITH_CAPACITY,
     StrGraphemes <= STR_GRAPHEMES,
+    StrMatches <= STR_MATCHES,
+    StrCapture <= STR_CAPTURE_LOWLEVEL,
     ListLen <= LIST_LEN,
     ListGetCapacity <= LIST_CAPACITY,
     ListWithCapacity <= LIST_WITH_CAPACITY,
@@ -305,13 +338,41 @@ map_symbol_to_lowlevel! {
     NumAsin <= NUM_ASIN,
     NumBytesToU16 <= NUM_BYTES_TO_U16_LOWLEVEL,
     NumBytesToU32 <= NUM_BYTES_TO_U32_LOWLEVEL,
+    RandomMix64 <= RANDOM_MIX64_LOWLEVEL,
+    RandomMixGamma <= RANDOM_MIX_GAMMA_LOWLEVEL,
+    RandomTestSeed <= RANDOM_TEST_SEED_LOWLEVEL,
+    BytesReadU16 <= BYTES_READ_U16_LOWLEVEL,
+    BytesReadU32 <= BYTES_READ_U32_LOWLEVEL,
+    BytesReadU64 <= BYTES_READ_U64_LOWLEVEL,
+    EncodingEncode <= ENCODING_ENCODE_LOWLEVEL,
+    EncodingDecode <= ENCODING_DECODE_LOWLEVEL,
+    EncodingCheck <= ENCODING_CHECK_LOWLEVEL,
+    JsonSkipWhitespace <= JSON_SKIP_WHITESPACE_LOWLEVEL,
+    JsonScanString <= JSON_SCAN_STRING_LOWLEVEL,
+    JsonScanValue <= JSON_SCAN_VALUE_LOWLEVEL,
+    JsonUnescape <= JSON_UNESCAPE_LOWLEVEL,
+    JsonEscape <= JSON_ESCAPE_LOWLEVEL,
+    TimeFormat <= TIME_FORMAT_LOWLEVEL,
+    TimeCheck <= TIME_CHECK_LOWLEVEL,
+    TimeParseSeconds <= TIME_PARSE_SECONDS_LOWLEVEL,
+    TimeParseNanos <= TIME_PARSE_NANOS_LOWLEVEL,
     NumBitwiseAnd <= NUM_BITWISE_AND,
     NumBitwiseXor <= NUM_BITWISE_XOR,
     NumBitwiseOr <= NUM_BITWISE_OR,
     NumShiftLeftBy <= NUM_SHIFT_LEFT,
     NumShiftRightBy <= NUM_SHIFT_RIGHT,
     NumShiftRightZfBy <= NUM_SHIFT_RIGHT_ZERO_FILL,
+    NumPopCount <= NUM_POP_COUNT,
+    NumLeadingZeros <= NUM_LEADING_ZEROS,
+    NumTrailingZeros <= NUM_TRAILING_ZEROS,
+    NumRotateLeftBy <= NUM_ROTATE_LEFT,
+    NumRotateRightBy <= NUM_ROTATE_RIGHT,
+    NumSwapBytes <= NUM_SWAP_BYTES,
+    NumIsNan <= NUM_IS_NAN,
+    NumIsInfinite <= NUM_IS_INFINITE,
+    NumIsFinite <= NUM_IS_FINITE,
     NumToStr <= NUM_TO_STR,
+    NumToStrWith <= NUM_TO_STR_WITH_LOWLEVEL,
     Eq <= BOOL_STRUCTURAL_EQ,
     NotEq <= BOOL_STRUCTURAL_NOT_EQ,
     And <= BOOL_AND,
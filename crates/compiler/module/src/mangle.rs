@@ -0,0 +1,201 @@
+//! The names generated code gives the procedures it defines, and how to turn them back into
+//! something readable (in profiles, debuggers, and the linker's verbose output).
+//!
+//! A specialization of a def is named `<module>_<def>_<layout>`, e.g. `Num_add_3`:
+//!
+//! - `<module>` is the name of the module the def is in. Module names never contain `_`.
+//! - `<def>` is the name of the def. This may contain `_`, as in `#list_eq`.
+//! - `<layout>` tells apart the specializations of the def. It's either a `LayoutId` in decimal,
+//!   or a hash of the specialization in lowercase hex, and never contains `_`.
+//!
+//! Procedures exposed to the host are named `roc__<def>_<suffix>`, e.g.
+//! `roc__mainForHost_1_exposed`, where the suffix says which entry point of the def it is.
+//!
+//! When the host gets a closure from a def, and the closure's type is an alias the platform
+//! declares, like `Fx`, Roc also exposes [HostClosureFn]s for it, named
+//! `roc__<def>_<module>_<alias>_<suffix>`, e.g. `roc__mainForHost_1__Fx_caller`. The
+//! platform module's name is empty, hence the `__`.
+use std::fmt::{self, Write};
+
+/// The prefix of every procedure exposed to the host
+const HOST_EXPOSED_PREFIX: &str = "roc__";
+
+/// The name of the specialization of `module_name.ident` with layout id `layout_id`
+pub fn specialization_name(module_name: &str, ident: &str, layout_id: u32) -> String {
+    format!("{}_{}_{}", module_name, ident, layout_id)
+}
+
+/// Writes the name of the specialization of `module_name.ident` identified by a hash of it
+pub fn write_hashed_specialization_name(
+    buf: &mut impl Write,
+    module_name: &str,
+    ident: &str,
+    hash: &[u8],
+) -> fmt::Result {
+    write!(buf, "{}_{}_", module_name, ident)?;
+
+    for byte in hash {
+        write!(buf, "{:02x}", byte)?;
+    }
+
+    Ok(())
+}
+
+/// The name of the procedure the host calls to run `ident` with layout id `layout_id`
+pub fn host_exposed_name(ident: &str, layout_id: u32) -> String {
+    format!("{}{}_{}_exposed", HOST_EXPOSED_PREFIX, ident, layout_id)
+}
+
+/// The variant of [host_exposed_name] which writes its result to a pointer the host passes in,
+/// rather than returning it
+pub fn host_exposed_generic_name(ident: &str, layout_id: u32) -> String {
+    format!("{}_generic", host_exposed_name(ident, layout_id))
+}
+
+/// The functions Roc exposes for each closure type the host gets from a def
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostClosureFn {
+    /// Calls the closure:
+    /// `void caller(Arg1 *arg1, ..., Closure *closure_data, Result *output)`.
+    /// Roc owns the arguments and the closure data, so the host must not use them afterwards.
+    Caller,
+    /// `int64_t size()`, the size in bytes of the closure data
+    Size,
+    /// `int64_t result_size()`, the size in bytes of what the closure writes to `output`
+    ResultSize,
+}
+
+impl HostClosureFn {
+    pub const ALL: [Self; 3] = [Self::Caller, Self::Size, Self::ResultSize];
+
+    pub fn suffix(self) -> &'static str {
+        match self {
+            HostClosureFn::Caller => "caller",
+            HostClosureFn::Size => "size",
+            HostClosureFn::ResultSize => "result_size",
+        }
+    }
+}
+
+/// The name of a closure type the host gets, like `_Fx` for the platform's `Fx`
+pub fn host_closure_type_name(module_name: &str, alias_name: &str) -> String {
+    format!("{}_{}", module_name, alias_name)
+}
+
+/// The name of `function` for the closure type `closure_type` (see [host_closure_type_name]),
+/// which the host gets from `ident` with layout id `layout_id`
+pub fn host_closure_fn_name(
+    ident: &str,
+    layout_id: u32,
+    closure_type: &str,
+    function: HostClosureFn,
+) -> String {
+    format!(
+        "{}{}_{}_{}_{}",
+        HOST_EXPOSED_PREFIX,
+        ident,
+        layout_id,
+        closure_type,
+        function.suffix()
+    )
+}
+
+/// What a mangled name refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Demangled<'a> {
+    /// A specialization of a def, e.g. `Num_add_3`
+    Specialization {
+        module_name: &'a str,
+        ident: &'a str,
+        layout: &'a str,
+    },
+    /// A procedure exposed to the host, e.g. `roc__mainForHost_1_exposed`
+    HostExposed { ident: &'a str, suffix: &'a str },
+}
+
+impl fmt::Display for Demangled<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Demangled::Specialization {
+                module_name,
+                ident,
+                layout,
+            } => write!(f, "{}.{} [layout {}]", module_name, ident, layout),
+            Demangled::HostExposed { ident, suffix } => {
+                write!(f, "{} [exposed to host: {}]", ident, suffix)
+            }
+        }
+    }
+}
+
+/// Reads a name generated for a Roc procedure, or returns `None` if it isn't one.
+/// The leading underscore Mach-O adds to every symbol is allowed.
+pub fn demangle(name: &str) -> Option<Demangled<'_>> {
+    demangle_help(name).or_else(|| demangle_help(name.strip_prefix('_')?))
+}
+
+fn demangle_help(name: &str) -> Option<Demangled<'_>> {
+    if let Some(rest) = name.strip_prefix(HOST_EXPOSED_PREFIX) {
+        let (ident, suffix) = rest.split_once('_')?;
+
+        return (is_ident(ident) && !suffix.is_empty())
+            .then_some(Demangled::HostExposed { ident, suffix });
+    }
+
+    let (module_name, rest) = name.split_once('_')?;
+    let (ident, layout) = rest.rsplit_once('_')?;
+
+    let is_module_name = module_name.split('.').all(|part| {
+        match part.strip_prefix('#').unwrap_or(part).chars().next() {
+            Some(first) => first.is_ascii_uppercase() && is_ident(part),
+            None => false,
+        }
+    });
+    let is_layout = !layout.is_empty() && layout.bytes().all(|byte| byte.is_ascii_hexdigit());
+
+    (is_module_name && is_ident(ident) && is_layout).then_some(Demangled::Specialization {
+        module_name,
+        ident,
+        layout,
+    })
+}
+
+fn is_ident(string: &str) -> bool {
+    !string.is_empty()
+        && string
+            .chars()
+            .all(|ch| ch.is_alphanumeric() || ch == '_' || ch == '#')
+}
+
+/// Replaces every mangled name in `text` with its demangled form, leaving everything else as-is.
+/// This is for demangling the output of other tools, like `perf report` or a linker map.
+pub fn demangle_text(text: &str) -> String {
+    let mut buf = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find(is_name_char) {
+        buf.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        // A name can't end in a `.`, so that one ending a sentence isn't taken as part of it.
+        let word =
+            rest[..rest.find(|ch| !is_name_char(ch)).unwrap_or(rest.len())].trim_end_matches('.');
+        let end = word.len().max(1);
+        let word = &rest[..end];
+
+        match demangle(word) {
+            Some(demangled) => write!(buf, "{}", demangled).unwrap(),
+            None => buf.push_str(word),
+        }
+
+        rest = &rest[end..];
+    }
+
+    buf.push_str(rest);
+
+    buf
+}
+
+fn is_name_char(ch: char) -> bool {
+    ch.is_alphanumeric() || matches!(ch, '_' | '#' | '.')
+}
@@ -1246,6 +1246,17 @@ define_builtins! {
         145 NUM_MUL_CHECKED_LOWLEVEL: "mulCheckedLowlevel"
         146 NUM_BYTES_TO_U16_LOWLEVEL: "bytesToU16Lowlevel"
         147 NUM_BYTES_TO_U32_LOWLEVEL: "bytesToU32Lowlevel"
+        148 NUM_TO_STR_WITH: "toStrWith"
+        149 NUM_TO_STR_WITH_LOWLEVEL: "toStrWithLowlevel"
+        150 NUM_POP_COUNT: "popCount"
+        151 NUM_LEADING_ZEROS: "leadingZeros"
+        152 NUM_TRAILING_ZEROS: "trailingZeros"
+        153 NUM_ROTATE_LEFT: "rotateLeftBy"
+        154 NUM_ROTATE_RIGHT: "rotateRightBy"
+        155 NUM_SWAP_BYTES: "swapBytes"
+        156 NUM_IS_NAN: "isNaN"
+        157 NUM_IS_INFINITE: "isInfinite"
+        158 NUM_IS_FINITE: "isFinite"
     }
     4 BOOL: "Bool" => {
         0 BOOL_BOOL: "Bool" exposed_type=true // the Bool.Bool type alias
@@ -1319,6 +1330,14 @@ define_builtins! {
         53 STR_WITH_CAPACITY: "withCapacity"
         54 STR_WITH_PREFIX: "withPrefix"
         55 STR_GRAPHEMES: "graphemes"
+        56 STR_TO_UTF16: "toUtf16"
+        57 STR_FROM_UTF16: "fromUtf16"
+        58 STR_FROM_UTF16_LOSSY: "fromUtf16Lossy"
+        59 STR_TO_UTF32: "toUtf32"
+        60 STR_FROM_UTF32: "fromUtf32"
+        61 STR_MATCHES: "matches"
+        62 STR_CAPTURE: "capture"
+        63 STR_CAPTURE_LOWLEVEL: "captureLowlevel"
     }
     6 LIST: "List" => {
         0 LIST_LIST: "List" exposed_apply_type=true // the List.List type alias
@@ -1401,6 +1420,7 @@ define_builtins! {
         77 LIST_COUNT_IF: "countIf"
         78 LIST_WALK_FROM: "walkFrom"
         79 LIST_WALK_FROM_UNTIL: "walkFromUntil"
+        80 LIST_MAP_CONCURRENT: "mapConcurrent"
     }
     7 RESULT: "Result" => {
         0 RESULT_RESULT: "Result" exposed_type=true // the Result.Result type alias
@@ -1545,10 +1565,51 @@ define_builtins! {
         16 HASH_HASH_STR_BYTES: "hashStrBytes"
         17 HASH_HASH_LIST: "hashList"
         18 HASH_HASH_UNORDERED: "hashUnordered"
+        19 HASH_HASH_WITH: "hashWith"
     }
     14 JSON: "Json" => {
         0 JSON_JSON: "Json"
-    }
-
-    num_modules: 15 // Keep this count up to date by hand! (TODO: see the mut_map! macro for how we could determine this count correctly in the macro)
+        1 JSON_STREAM: "Stream" // the Json.Stream opaque type
+        2 JSON_SKIP_WHITESPACE_LOWLEVEL: "skipWhitespaceLowlevel"
+        3 JSON_SCAN_STRING_LOWLEVEL: "scanStringLowlevel"
+        4 JSON_SCAN_VALUE_LOWLEVEL: "scanValueLowlevel"
+        5 JSON_UNESCAPE_LOWLEVEL: "unescapeLowlevel"
+        6 JSON_ESCAPE_LOWLEVEL: "escapeLowlevel"
+    }
+    15 ASYNC: "Async" => {
+        0 ASYNC_ASYNC: "Async" // the Async.Async opaque type
+    }
+    16 RANDOM: "Random" => {
+        0 RANDOM_SEED: "Seed" // the Random.Seed opaque type
+        1 RANDOM_MIX64_LOWLEVEL: "mix64Lowlevel"
+        2 RANDOM_MIX_GAMMA_LOWLEVEL: "mixGammaLowlevel"
+        3 RANDOM_TEST_SEED_LOWLEVEL: "testSeedLowlevel"
+    }
+    17 SORTED_DICT: "SortedDict" => {
+        0 SORTED_DICT_SORTED_DICT: "SortedDict" // the SortedDict.SortedDict opaque type
+    }
+    18 SORTED_SET: "SortedSet" => {
+        0 SORTED_SET_SORTED_SET: "SortedSet" // the SortedSet.SortedSet opaque type
+    }
+    19 BYTES: "Bytes" => {
+        0 BYTES_BYTES: "Bytes" // the Bytes.Bytes opaque type
+        1 BYTES_READ_U16_LOWLEVEL: "readU16Lowlevel"
+        2 BYTES_READ_U32_LOWLEVEL: "readU32Lowlevel"
+        3 BYTES_READ_U64_LOWLEVEL: "readU64Lowlevel"
+    }
+    20 ENCODING: "Encoding" => {
+        0 ENCODING_ENCODE_LOWLEVEL: "encodeLowlevel"
+        1 ENCODING_DECODE_LOWLEVEL: "decodeLowlevel"
+        2 ENCODING_CHECK_LOWLEVEL: "checkLowlevel"
+    }
+    21 TIME: "Time" => {
+        0 TIME_UTC: "Utc" // the Time.Utc opaque type
+        1 TIME_DURATION: "Duration" // the Time.Duration opaque type
+        2 TIME_FORMAT_LOWLEVEL: "formatLowlevel"
+        3 TIME_CHECK_LOWLEVEL: "checkLowlevel"
+        4 TIME_PARSE_SECONDS_LOWLEVEL: "parseSecondsLowlevel"
+        5 TIME_PARSE_NANOS_LOWLEVEL: "parseNanosLowlevel"
+    }
+
+    num_modules: 22 // Keep this count up to date by hand! (TODO: see the mut_map! macro for how we could determine this count correctly in the macro)
 }
@@ -602,7 +602,7 @@ impl<'a> BorrowInfState<'a> {
                     };
 
                 match op {
-                    ListMap { xs } => {
+                    ListMap { xs } | ListMapConcurrent { xs } => {
                         // own the list if the function wants to own the element
                         if function_ps[0].ownership == Ownership::Owned {
                             self.own_var(*xs);
@@ -878,7 +878,7 @@ impl<'a> BorrowInfState<'a> {
 
             Refcounting(_, _) => unreachable!("these have not been introduced yet"),
 
-            Crash(msg, _) => {
+            Crash(msg, ..) => {
                 // Crash is a foreign call, so we must own the argument.
                 self.own_var(*msg);
             }
@@ -963,7 +963,7 @@ pub fn lowlevel_borrow_signature(arena: &Bump, op: LowLevel) -> &[bool] {
         StrToNum => arena.alloc_slice_copy(&[borrowed]),
         ListPrepend => arena.alloc_slice_copy(&[owned, owned]),
         StrJoinWith => arena.alloc_slice_copy(&[borrowed, borrowed]),
-        ListMap => arena.alloc_slice_copy(&[owned, function, closure_data]),
+        ListMap | ListMapConcurrent => arena.alloc_slice_copy(&[owned, function, closure_data]),
         ListMap2 => arena.alloc_slice_copy(&[owned, owned, function, closure_data]),
         ListMap3 => arena.alloc_slice_copy(&[owned, owned, owned, function, closure_data]),
         ListMap4 => arena.alloc_slice_copy(&[owned, owned, owned, owned, function, closure_data]),
@@ -982,20 +982,42 @@ pub fn lowlevel_borrow_signature(arena: &Bump, op: LowLevel) -> &[bool] {
         | NumMulChecked | NumGt | NumGte | NumLt | NumLte | NumCompare | NumDivFrac
         | NumDivTruncUnchecked | NumDivCeilUnchecked | NumRemUnchecked | NumIsMultipleOf
         | NumPow | NumPowInt | NumBitwiseAnd | NumBitwiseXor | NumBitwiseOr | NumShiftLeftBy
-        | NumShiftRightBy | NumShiftRightZfBy => arena.alloc_slice_copy(&[irrelevant, irrelevant]),
+        | NumShiftRightBy | NumShiftRightZfBy | NumRotateLeftBy | NumRotateRightBy => {
+            arena.alloc_slice_copy(&[irrelevant, irrelevant])
+        }
 
         NumToStr | NumAbs | NumNeg | NumSin | NumCos | NumSqrtUnchecked | NumLogUnchecked
-        | NumRound | NumCeiling | NumFloor | NumToFrac | Not | NumIsFinite | NumAtan | NumAcos
-        | NumAsin | NumIntCast | NumToIntChecked | NumToFloatCast | NumToFloatChecked => {
-            arena.alloc_slice_copy(&[irrelevant])
-        }
+        | NumRound | NumCeiling | NumFloor | NumToFrac | Not | NumIsNan | NumIsInfinite
+        | NumIsFinite | NumAtan | NumAcos | NumAsin | NumIntCast | NumToIntChecked
+        | NumToFloatCast | NumToFloatChecked | NumPopCount | NumLeadingZeros | NumTrailingZeros
+        | NumSwapBytes => arena.alloc_slice_copy(&[irrelevant]),
         NumBytesToU16 => arena.alloc_slice_copy(&[borrowed, irrelevant]),
         NumBytesToU32 => arena.alloc_slice_copy(&[borrowed, irrelevant]),
+        RandomMix64 | RandomMixGamma | RandomTestSeed => arena.alloc_slice_copy(&[irrelevant]),
+        // reading only borrows the list, so a chain of reads from one list never touches its
+        // refcount
+        BytesReadU16 | BytesReadU32 | BytesReadU64 => {
+            arena.alloc_slice_copy(&[borrowed, irrelevant, irrelevant])
+        }
+        EncodingEncode | EncodingDecode | EncodingCheck => {
+            arena.alloc_slice_copy(&[borrowed, irrelevant])
+        }
+        JsonSkipWhitespace | JsonScanString | JsonScanValue => {
+            arena.alloc_slice_copy(&[borrowed, irrelevant])
+        }
+        JsonUnescape => arena.alloc_slice_copy(&[borrowed, irrelevant, irrelevant]),
+        JsonEscape => arena.alloc_slice_copy(&[owned, borrowed]),
+        TimeFormat => arena.alloc_slice_copy(&[irrelevant, irrelevant]),
+        TimeCheck | TimeParseSeconds | TimeParseNanos => arena.alloc_slice_copy(&[borrowed]),
         StrStartsWith | StrEndsWith => arena.alloc_slice_copy(&[borrowed, borrowed]),
+        StrMatches | StrCapture => arena.alloc_slice_copy(&[borrowed, borrowed]),
         StrStartsWithScalar => arena.alloc_slice_copy(&[borrowed, irrelevant]),
         StrFromUtf8Range => arena.alloc_slice_copy(&[owned, irrelevant, irrelevant]),
         StrToUtf8 => arena.alloc_slice_copy(&[owned]),
         StrRepeat => arena.alloc_slice_copy(&[borrowed, irrelevant]),
+        NumToStrWith => {
+            arena.alloc_slice_copy(&[borrowed, borrowed, irrelevant, irrelevant, irrelevant])
+        }
         StrFromInt | StrFromFloat => arena.alloc_slice_copy(&[irrelevant]),
         Hash => arena.alloc_slice_copy(&[borrowed, irrelevant]),
 
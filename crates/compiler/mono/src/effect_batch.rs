@@ -0,0 +1,705 @@
+//! Groups the effects a procedure performs into batches, for platforms whose hosts take effects in
+//! batches (with `effects { submit: batch }` in the platform header) rather than one call each.
+//! A host running on io_uring, for example, can then submit a whole batch at once.
+//!
+//! A batch is a run of consecutive `let`s which each call a host effect, like `roc_fx_putLine`.
+//! The host runs every effect in a batch before Roc sees any of their results, so an effect which
+//! uses the result of an earlier effect starts a new batch. Every effect is in exactly one batch,
+//! even if it's the only effect in it.
+//!
+//! The host receives each batch with a single call:
+//!
+//! ```c
+//! struct RocEffect {
+//!     const char* name;   // the effect's host function, like "roc_fx_putLine"
+//!     void** arguments;   // a pointer to each of its arguments
+//!     void* output;       // where to write its result
+//! };
+//!
+//! void roc_fx_batch(struct RocEffect* effects, size_t count);
+//! ```
+//!
+//! The host must run the effects in order, since programs rely on their order just as they do
+//! when the effects are separate calls.
+//!
+//! Each host effect starts out alone in the thunk of its `Effect`, so two passes bring effects
+//! together before batches are found:
+//!
+//! 1. [inline_small_procs] inlines procedures which are a handful of `let`s and make no calls
+//!    of their own, except to host effects. Effect thunks are like this, and so are the
+//!    procedures which build them, and the continuations of `Effect.after` which ignore their
+//!    argument. Inlining those again and again puts the effects of a chain like
+//!    `Effect.after (putLine "a") \{} -> putLine "b"` into the thunk of the outer `after`.
+//! 2. [gather_effects] runs after reference counts are inserted. It moves the `let`s and
+//!    increments which build an effect's arguments up above the effects before it, and the
+//!    decrements after an effect down below the effects after it, so the effects end up next
+//!    to each other.
+//!
+//! Both passes only move things which can't crash, so effects run in the same order, and an
+//! effect still runs before anything which can crash after it in the program.
+//!
+//! This only batches effects which don't wait on each other. In a chain which branches on the
+//! result of each effect, like a `Task` which is an `Effect (Result ok err)` and stops at the
+//! first `Err`, every effect waits on the one before it, so each one is still a batch of its own.
+use crate::ir::{
+    substitute_in_expr, substitute_in_exprs, Call, CallType, Expr, ListLiteralElement, ModifyRc,
+    Proc, ProcLayout, Stmt,
+};
+use crate::layout::InLayout;
+use bumpalo::Bump;
+use roc_collections::all::{BumpMap, MutMap, MutSet};
+use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+
+/// The host function which runs a batch of effects
+pub const BATCH_FN: &str = "roc_fx_batch";
+
+/// The most `let`s a procedure can have and still be inlined by [inline_small_procs]
+const INLINE_MAX_LETS: usize = 32;
+
+/// Where the batches of effects in a procedure start, and how many effects are in each
+#[derive(Debug, Default)]
+pub struct EffectBatches {
+    lengths: MutMap<Symbol, usize>,
+}
+
+impl EffectBatches {
+    pub fn from_proc(proc: &Proc<'_>) -> Self {
+        let mut batches = Self::default();
+        let mut stack = vec![&proc.body];
+
+        while let Some(mut stmt) = stack.pop() {
+            // The batch we're adding effects to: the symbol of its first effect, and the symbols
+            // of all its effects
+            let mut current: Option<(Symbol, MutSet<Symbol>)> = None;
+
+            while let Stmt::Let(symbol, expr, _, cont) = stmt {
+                current = match (effect_arguments(expr), current) {
+                    (Some(arguments), Some((first, mut members)))
+                        if !arguments.iter().any(|arg| members.contains(arg)) =>
+                    {
+                        members.insert(*symbol);
+
+                        Some((first, members))
+                    }
+                    (Some(_), previous) => {
+                        batches.finish(previous);
+
+                        Some((*symbol, MutSet::from_iter([*symbol])))
+                    }
+                    (None, previous) => {
+                        batches.finish(previous);
+
+                        None
+                    }
+                };
+
+                stmt = cont;
+            }
+
+            batches.finish(current);
+
+            match stmt {
+                Stmt::Let(..) => unreachable!("we just went through all the lets"),
+                Stmt::Switch {
+                    branches,
+                    default_branch,
+                    ..
+                } => {
+                    stack.extend(branches.iter().map(|(_, _, branch)| branch));
+                    stack.push(default_branch.1);
+                }
+                Stmt::Join {
+                    body, remainder, ..
+                } => {
+                    stack.push(body);
+                    stack.push(remainder);
+                }
+                Stmt::Refcounting(_, cont) => stack.push(cont),
+                Stmt::Dbg { remainder, .. }
+                | Stmt::Expect { remainder, .. }
+                | Stmt::ExpectFx { remainder, .. } => stack.push(remainder),
+                Stmt::Ret(_) | Stmt::Jump(..) | Stmt::Crash(..) => {}
+            }
+        }
+
+        batches
+    }
+
+    /// The number of effects in the batch which starts with the `let` of this symbol, if one does
+    pub fn batch_len(&self, symbol: Symbol) -> Option<usize> {
+        self.lengths.get(&symbol).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Symbol, usize)> + '_ {
+        self.lengths.iter().map(|(symbol, len)| (*symbol, *len))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lengths.is_empty()
+    }
+
+    fn finish(&mut self, batch: Option<(Symbol, MutSet<Symbol>)>) {
+        if let Some((first, members)) = batch {
+            self.lengths.insert(first, members.len());
+        }
+    }
+}
+
+/// The arguments of a call to a host effect
+fn effect_arguments<'a>(expr: &Expr<'a>) -> Option<&'a [Symbol]> {
+    match expr {
+        Expr::Call(Call {
+            call_type: CallType::Foreign { .. },
+            arguments,
+        }) => Some(arguments),
+        _ => None,
+    }
+}
+
+/// Inlines every call to a procedure which is at most [INLINE_MAX_LETS] `let`s followed by a
+/// `ret`, and which calls nothing but host effects, until there are no such calls left.
+///
+/// Every round of inlining removes at least one call without adding any, so this stops.
+pub fn inline_small_procs<'a>(
+    arena: &'a Bump,
+    home: ModuleId,
+    ident_ids: &mut IdentIds,
+    procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+) {
+    loop {
+        let small_procs = Vec::from_iter(
+            procs
+                .values()
+                .filter(|proc| is_small_proc(proc))
+                .map(|proc| (proc.clone(), &*arena.alloc(proc.body.clone()))),
+        );
+
+        if small_procs.is_empty() {
+            return;
+        }
+
+        let mut env = InlineEnv {
+            arena,
+            home,
+            ident_ids,
+            small_procs: &small_procs,
+        };
+        let mut changed = false;
+
+        for proc in procs.values_mut() {
+            let body = arena.alloc(proc.body.clone());
+
+            if let Some(new_body) = env.inline_calls(body) {
+                proc.body = new_body.clone();
+                changed = true;
+            }
+        }
+
+        if !changed {
+            return;
+        }
+    }
+}
+
+fn is_small_proc(proc: &Proc<'_>) -> bool {
+    let mut stmt = &proc.body;
+    let mut lets = 0;
+
+    while let Stmt::Let(_, expr, _, cont) = stmt {
+        if let Expr::Call(Call { call_type, .. }) = expr {
+            if !matches!(call_type, CallType::Foreign { .. }) {
+                return false;
+            }
+        }
+
+        lets += 1;
+        stmt = cont;
+    }
+
+    matches!(stmt, Stmt::Ret(_)) && lets <= INLINE_MAX_LETS
+}
+
+struct InlineEnv<'a, 'i> {
+    arena: &'a Bump,
+    home: ModuleId,
+    ident_ids: &'i mut IdentIds,
+    /// The procedures to inline, with their bodies in the arena
+    small_procs: &'i [(Proc<'a>, &'a Stmt<'a>)],
+}
+
+impl<'a, 'i> InlineEnv<'a, 'i> {
+    /// The statement with calls to small procedures inlined, if it calls any
+    fn inline_calls(&mut self, stmt: &'a Stmt<'a>) -> Option<&'a Stmt<'a>> {
+        let arena = self.arena;
+
+        match stmt {
+            Stmt::Let(symbol, expr, layout, cont) => {
+                let new_cont = self.inline_calls(cont);
+
+                match self.small_proc_call(expr) {
+                    Some((callee, arguments)) => {
+                        let cont = new_cont.unwrap_or(cont);
+
+                        Some(self.inline_call(callee, arguments, *symbol, cont))
+                    }
+                    None => new_cont
+                        .map(|cont| &*arena.alloc(Stmt::Let(*symbol, expr.clone(), *layout, cont))),
+                }
+            }
+            Stmt::Switch {
+                cond_symbol,
+                cond_layout,
+                branches,
+                default_branch,
+                ret_layout,
+            } => {
+                let new_branches = Vec::from_iter(
+                    branches
+                        .iter()
+                        .map(|(_, _, branch)| self.inline_calls(branch)),
+                );
+                let new_default = self.inline_calls(default_branch.1);
+
+                if new_default.is_none() && new_branches.iter().all(Option::is_none) {
+                    return None;
+                }
+
+                let branches = arena.alloc_slice_fill_iter(branches.iter().zip(new_branches).map(
+                    |((id, info, branch), new)| (*id, info.clone(), new.unwrap_or(branch).clone()),
+                ));
+
+                Some(&*arena.alloc(Stmt::Switch {
+                    cond_symbol: *cond_symbol,
+                    cond_layout: *cond_layout,
+                    branches,
+                    default_branch: (
+                        default_branch.0.clone(),
+                        new_default.unwrap_or(default_branch.1),
+                    ),
+                    ret_layout: *ret_layout,
+                }))
+            }
+            Stmt::Join {
+                id,
+                parameters,
+                body,
+                remainder,
+            } => {
+                let new_body = self.inline_calls(body);
+                let new_remainder = self.inline_calls(remainder);
+
+                if new_body.is_none() && new_remainder.is_none() {
+                    return None;
+                }
+
+                Some(&*arena.alloc(Stmt::Join {
+                    id: *id,
+                    parameters: *parameters,
+                    body: new_body.unwrap_or(body),
+                    remainder: new_remainder.unwrap_or(remainder),
+                }))
+            }
+            Stmt::Refcounting(modify, cont) => self
+                .inline_calls(cont)
+                .map(|cont| &*arena.alloc(Stmt::Refcounting(*modify, cont))),
+            Stmt::Dbg {
+                symbol,
+                variable,
+                remainder,
+            } => self.inline_calls(remainder).map(|remainder| {
+                &*arena.alloc(Stmt::Dbg {
+                    symbol: *symbol,
+                    variable: *variable,
+                    remainder,
+                })
+            }),
+            Stmt::Expect {
+                condition,
+                region,
+                lookups,
+                variables,
+                remainder,
+            } => self.inline_calls(remainder).map(|remainder| {
+                &*arena.alloc(Stmt::Expect {
+                    condition: *condition,
+                    region: *region,
+                    lookups: *lookups,
+                    variables: *variables,
+                    remainder,
+                })
+            }),
+            Stmt::ExpectFx {
+                condition,
+                region,
+                lookups,
+                variables,
+                remainder,
+            } => self.inline_calls(remainder).map(|remainder| {
+                &*arena.alloc(Stmt::ExpectFx {
+                    condition: *condition,
+                    region: *region,
+                    lookups: *lookups,
+                    variables: *variables,
+                    remainder,
+                })
+            }),
+            Stmt::Ret(_) | Stmt::Jump(..) | Stmt::Crash(..) => None,
+        }
+    }
+
+    /// The small procedure this expression calls, and its arguments, if it calls one
+    fn small_proc_call(
+        &self,
+        expr: &Expr<'a>,
+    ) -> Option<(&'i (Proc<'a>, &'a Stmt<'a>), &'a [Symbol])> {
+        match expr {
+            Expr::Call(Call {
+                call_type:
+                    CallType::ByName {
+                        name,
+                        ret_layout,
+                        arg_layouts,
+                        ..
+                    },
+                arguments,
+            }) => self
+                .small_procs
+                .iter()
+                .find(|(proc, _)| {
+                    proc.name == *name
+                        && proc.ret_layout == *ret_layout
+                        && proc
+                            .args
+                            .iter()
+                            .map(|(layout, _)| layout)
+                            .eq(arg_layouts.iter())
+                })
+                .map(|small_proc| (small_proc, *arguments)),
+            _ => None,
+        }
+    }
+
+    /// The `let`s of the callee, with fresh symbols, followed by the continuation of the call
+    fn inline_call(
+        &mut self,
+        (callee, body): &(Proc<'a>, &'a Stmt<'a>),
+        arguments: &[Symbol],
+        result: Symbol,
+        cont: &'a Stmt<'a>,
+    ) -> &'a Stmt<'a> {
+        let arena = self.arena;
+        let mut subs = BumpMap::with_capacity_in(callee.args.len(), arena);
+
+        for ((_, param), argument) in callee.args.iter().zip(arguments) {
+            subs.insert(*param, *argument);
+        }
+
+        let mut lets = Vec::new();
+        let mut stmt = *body;
+
+        while let Stmt::Let(symbol, expr, layout, rest) = stmt {
+            let fresh = Symbol::new(self.home, self.ident_ids.gen_unique());
+            let expr = substitute_in_expr(arena, expr, &subs).unwrap_or_else(|| expr.clone());
+
+            subs.insert(*symbol, fresh);
+            lets.push((fresh, expr, *layout));
+            stmt = rest;
+        }
+
+        let returned = match stmt {
+            Stmt::Ret(symbol) => subs.get(symbol).copied().unwrap_or(*symbol),
+            _ => unreachable!("small procs end in a ret, not {:?}", stmt),
+        };
+
+        let mut new_stmt = cont.clone();
+        substitute_in_exprs(arena, &mut new_stmt, result, returned);
+
+        let mut new_stmt = &*arena.alloc(new_stmt);
+
+        for (symbol, expr, layout) in lets.into_iter().rev() {
+            new_stmt = arena.alloc(Stmt::Let(symbol, expr, layout, new_stmt));
+        }
+
+        new_stmt
+    }
+}
+
+/// Moves the `let`s and reference count changes around effects so that effects which don't
+/// wait on each other are next to each other, and can go in one batch. Runs after reference
+/// counts are inserted, since they would otherwise end up between the effects.
+pub fn gather_effects<'a>(arena: &'a Bump, procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>) {
+    for proc in procs.values_mut() {
+        let body = arena.alloc(proc.body.clone());
+
+        if let Some(new_body) = gather_effects_help(arena, body) {
+            proc.body = new_body.clone();
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Step<'a> {
+    Let(Symbol, &'a Expr<'a>, InLayout<'a>),
+    Refcounting(ModifyRc),
+}
+
+/// The effects which are being gathered, and the steps to put before and after them
+#[derive(Default)]
+struct Gathering<'a> {
+    in_order: Vec<Step<'a>>,
+    before: Vec<Step<'a>>,
+    effects: Vec<Step<'a>>,
+    after: Vec<Step<'a>>,
+    results: MutSet<Symbol>,
+}
+
+impl<'a> Gathering<'a> {
+    /// Adds the step to the gathering, unless it has to stay after the gathered effects
+    fn add(&mut self, step: Step<'a>) -> bool {
+        match step {
+            Step::Let(symbol, expr, _) => {
+                if let Some(arguments) = effect_arguments(expr) {
+                    if self.uses_result(arguments) {
+                        return false;
+                    }
+
+                    self.effects.push(step);
+                    self.results.insert(symbol);
+                } else {
+                    match crash_free_arguments(expr) {
+                        Some(arguments) if !self.uses_result(&arguments) => self.before.push(step),
+                        _ => return false,
+                    }
+                }
+            }
+            Step::Refcounting(ModifyRc::Inc(symbol, _)) => {
+                if self.results.contains(&symbol) {
+                    return false;
+                }
+
+                self.before.push(step);
+            }
+            Step::Refcounting(ModifyRc::Dec(_) | ModifyRc::DecRef(_)) => self.after.push(step),
+        }
+
+        self.in_order.push(step);
+
+        true
+    }
+
+    fn uses_result(&self, symbols: &[Symbol]) -> bool {
+        symbols.iter().any(|symbol| self.results.contains(symbol))
+    }
+
+    fn finish(self, steps: &mut Vec<Step<'a>>) {
+        if self.effects.len() > 1 {
+            steps.extend(self.before);
+            steps.extend(self.effects);
+            steps.extend(self.after);
+        } else {
+            steps.extend(self.in_order);
+        }
+    }
+}
+
+fn gather_effects_help<'a>(arena: &'a Bump, stmt: &'a Stmt<'a>) -> Option<&'a Stmt<'a>> {
+    let mut steps = Vec::new();
+    let mut rest = stmt;
+
+    loop {
+        match rest {
+            Stmt::Let(symbol, expr, layout, cont) => {
+                steps.push(Step::Let(*symbol, expr, *layout));
+                rest = cont;
+            }
+            Stmt::Refcounting(modify, cont) => {
+                steps.push(Step::Refcounting(*modify));
+                rest = cont;
+            }
+            _ => break,
+        }
+    }
+
+    let new_rest = gather_effects_in_branches(arena, rest);
+
+    let mut gathered = Vec::with_capacity(steps.len());
+    let mut gathering: Option<Gathering> = None;
+
+    for step in steps.iter().copied() {
+        if let Some(current) = gathering.as_mut() {
+            if current.add(step) {
+                continue;
+            }
+
+            gathering.take().unwrap().finish(&mut gathered);
+        }
+
+        if matches!(step, Step::Let(_, expr, _) if effect_arguments(expr).is_some()) {
+            let mut current = Gathering::default();
+            current.add(step);
+            gathering = Some(current);
+        } else {
+            gathered.push(step);
+        }
+    }
+
+    if let Some(current) = gathering {
+        current.finish(&mut gathered);
+    }
+
+    let reordered = gathered.iter().zip(&steps).any(|(a, b)| !same_step(a, b));
+
+    if !reordered && new_rest.is_none() {
+        return None;
+    }
+
+    let mut new_stmt = new_rest.unwrap_or(rest);
+
+    for step in gathered.into_iter().rev() {
+        new_stmt = match step {
+            Step::Let(symbol, expr, layout) => {
+                &*arena.alloc(Stmt::Let(symbol, expr.clone(), layout, new_stmt))
+            }
+            Step::Refcounting(modify) => &*arena.alloc(Stmt::Refcounting(modify, new_stmt)),
+        };
+    }
+
+    Some(new_stmt)
+}
+
+/// Gathers the effects in the statements which end a chain of `let`s
+fn gather_effects_in_branches<'a>(arena: &'a Bump, stmt: &'a Stmt<'a>) -> Option<&'a Stmt<'a>> {
+    match stmt {
+        Stmt::Switch {
+            cond_symbol,
+            cond_layout,
+            branches,
+            default_branch,
+            ret_layout,
+        } => {
+            let new_branches = Vec::from_iter(
+                branches
+                    .iter()
+                    .map(|(_, _, branch)| gather_effects_help(arena, branch)),
+            );
+            let new_default = gather_effects_help(arena, default_branch.1);
+
+            if new_default.is_none() && new_branches.iter().all(Option::is_none) {
+                return None;
+            }
+
+            let branches = arena.alloc_slice_fill_iter(branches.iter().zip(new_branches).map(
+                |((id, info, branch), new)| (*id, info.clone(), new.unwrap_or(branch).clone()),
+            ));
+
+            Some(&*arena.alloc(Stmt::Switch {
+                cond_symbol: *cond_symbol,
+                cond_layout: *cond_layout,
+                branches,
+                default_branch: (
+                    default_branch.0.clone(),
+                    new_default.unwrap_or(default_branch.1),
+                ),
+                ret_layout: *ret_layout,
+            }))
+        }
+        Stmt::Join {
+            id,
+            parameters,
+            body,
+            remainder,
+        } => {
+            let new_body = gather_effects_help(arena, body);
+            let new_remainder = gather_effects_help(arena, remainder);
+
+            if new_body.is_none() && new_remainder.is_none() {
+                return None;
+            }
+
+            Some(&*arena.alloc(Stmt::Join {
+                id: *id,
+                parameters: *parameters,
+                body: new_body.unwrap_or(body),
+                remainder: new_remainder.unwrap_or(remainder),
+            }))
+        }
+        Stmt::Dbg {
+            symbol,
+            variable,
+            remainder,
+        } => gather_effects_help(arena, remainder).map(|remainder| {
+            &*arena.alloc(Stmt::Dbg {
+                symbol: *symbol,
+                variable: *variable,
+                remainder,
+            })
+        }),
+        Stmt::Expect {
+            condition,
+            region,
+            lookups,
+            variables,
+            remainder,
+        } => gather_effects_help(arena, remainder).map(|remainder| {
+            &*arena.alloc(Stmt::Expect {
+                condition: *condition,
+                region: *region,
+                lookups: *lookups,
+                variables: *variables,
+                remainder,
+            })
+        }),
+        Stmt::ExpectFx {
+            condition,
+            region,
+            lookups,
+            variables,
+            remainder,
+        } => gather_effects_help(arena, remainder).map(|remainder| {
+            &*arena.alloc(Stmt::ExpectFx {
+                condition: *condition,
+                region: *region,
+                lookups: *lookups,
+                variables: *variables,
+                remainder,
+            })
+        }),
+        Stmt::Let(..) | Stmt::Refcounting(..) => {
+            unreachable!("chains of lets end at a statement which isn't a let")
+        }
+        Stmt::Ret(_) | Stmt::Jump(..) | Stmt::Crash(..) => None,
+    }
+}
+
+fn same_step(a: &Step<'_>, b: &Step<'_>) -> bool {
+    match (a, b) {
+        (Step::Let(a, ..), Step::Let(b, ..)) => a == b,
+        (Step::Refcounting(a), Step::Refcounting(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// The symbols an expression uses, if it can't crash or call anything, so moving it above an
+/// effect changes nothing the program can observe
+fn crash_free_arguments(expr: &Expr<'_>) -> Option<Vec<Symbol>> {
+    match expr {
+        Expr::Literal(_) | Expr::EmptyArray => Some(Vec::new()),
+        Expr::Struct(arguments) | Expr::Tag { arguments, .. } => Some(arguments.to_vec()),
+        Expr::StructAtIndex { structure, .. }
+        | Expr::GetTagId { structure, .. }
+        | Expr::UnionAtIndex { structure, .. } => Some(vec![*structure]),
+        Expr::ExprBox { symbol } | Expr::ExprUnbox { symbol } => Some(vec![*symbol]),
+        Expr::Array { elems, .. } => Some(
+            elems
+                .iter()
+                .filter_map(|elem| match elem {
+                    ListLiteralElement::Symbol(symbol) => Some(*symbol),
+                    ListLiteralElement::Literal(_) => None,
+                })
+                .collect(),
+        ),
+        Expr::Call(_) | Expr::Reuse { .. } | Expr::Reset { .. } | Expr::RuntimeErrorFunction(_) => {
+            None
+        }
+    }
+}
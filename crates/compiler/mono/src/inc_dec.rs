@@ -165,7 +165,7 @@ pub fn occurring_variables(stmt: &Stmt<'_>) -> (MutSet<Symbol>, MutSet<Symbol>)
                 stack.push(default_branch.1);
             }
 
-            Crash(sym, _) => {
+            Crash(sym, ..) => {
                 result.insert(*sym);
             }
         }
@@ -730,7 +730,7 @@ impl<'a, 'i> Context<'a, 'i> {
         let after_arguments = &arguments[op.function_index()..];
 
         match *op {
-            ListMap { xs } => {
+            ListMap { xs } | ListMapConcurrent { xs } => {
                 let ownerships = [(xs, function_ps[0])];
 
                 let b = self.add_dec_after_lowlevel(after_arguments, &borrows, b, b_live_vars);
@@ -1277,7 +1277,7 @@ impl<'a, 'i> Context<'a, 'i> {
                 (expect, b_live_vars)
             }
 
-            Crash(x, _) => {
+            Crash(x, ..) => {
                 let info = self.get_var_info(*x);
 
                 let mut live_vars = MutSet::default();
@@ -1468,7 +1468,7 @@ pub fn collect_stmt(
             vars
         }
 
-        Crash(m, _) => {
+        Crash(m, ..) => {
             vars.insert(*m);
             vars
         }
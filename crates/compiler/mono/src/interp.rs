@@ -0,0 +1,597 @@
+//! A tree-walking interpreter over the mono [IR][crate::ir].
+//!
+//! Running a program this way skips code generation and linking entirely, so it starts almost
+//! instantly, at the cost of running much slower than compiled code. Only the pure parts of
+//! the language are supported; anything that needs the host (like effects) results in an
+//! [InterpError::Unsupported].
+//!
+//! `roc run --interp` and `roc repl --interp` use this instead of LLVM.
+
+use std::cmp::Ordering;
+
+use bumpalo::Bump;
+use roc_builtins::bitcode::{FloatWidth, IntWidth};
+use roc_collections::MutMap;
+use roc_error_macros::internal_error;
+use roc_module::low_level::LowLevel;
+use roc_module::symbol::Symbol;
+
+use crate::ir::{
+    Call, CallType, Expr, HigherOrderLowLevel, JoinPointId, ListLiteralElement, Literal, Param,
+    PassedFunction, Proc, ProcLayout, Stmt,
+};
+use crate::layout::{Builtin, InLayout, LambdaName, Layout, LayoutInterner, TagIdIntType};
+use crate::low_level::HigherOrder;
+
+type Procs<'a> = MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>;
+
+/// A runtime value. Numbers of every integer width but `U128` are stored as an `i128`; the layout
+/// they were produced with determines when arithmetic overflows.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Int(i128),
+    /// A `U128`, which doesn't fit in an `i128`
+    U128(u128),
+    Float(f64),
+    Bool(bool),
+    Str(String),
+    List(Vec<Value>),
+    Struct(Vec<Value>),
+    Tag {
+        tag_id: TagIdIntType,
+        fields: Vec<Value>,
+    },
+}
+
+impl Value {
+    fn unit() -> Self {
+        Value::Struct(Vec::new())
+    }
+
+    fn as_switch_condition(&self) -> u64 {
+        match self {
+            Value::Int(n) => *n as u64,
+            Value::U128(n) => *n as u64,
+            Value::Bool(b) => *b as u64,
+            other => internal_error!("cannot switch on {:?}", other),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum InterpError {
+    /// The program crashed, e.g. because of `crash` or an integer overflow
+    Crash(String),
+    /// The program needs something the interpreter can't do, like calling the host
+    Unsupported(String),
+}
+
+pub struct Interpreter<'a, 'r, I> {
+    arena: &'a Bump,
+    interner: &'r I,
+    procs: &'r Procs<'a>,
+}
+
+impl<'a, 'r, I> Interpreter<'a, 'r, I>
+where
+    I: LayoutInterner<'a>,
+{
+    pub fn new(arena: &'a Bump, interner: &'r I, procs: &'r Procs<'a>) -> Self {
+        Self {
+            arena,
+            interner,
+            procs,
+        }
+    }
+
+    /// Runs `proc` with the given arguments, and returns the value it evaluates to.
+    pub fn eval_proc(&self, proc: &'r Proc<'a>, args: Vec<Value>) -> Result<Value, InterpError> {
+        debug_assert_eq!(proc.args.len(), args.len());
+
+        let mut env = MutMap::default();
+
+        for ((_, symbol), value) in proc.args.iter().zip(args) {
+            env.insert(*symbol, value);
+        }
+
+        self.eval_stmt(&mut env, &proc.body)
+    }
+
+    fn call_by_name(
+        &self,
+        name: LambdaName<'a>,
+        arg_layouts: &'a [InLayout<'a>],
+        ret_layout: InLayout<'a>,
+        args: Vec<Value>,
+    ) -> Result<Value, InterpError> {
+        let proc_layout = ProcLayout::new(self.arena, arg_layouts, name.niche(), ret_layout);
+
+        match self.procs.get(&(name.name(), proc_layout)) {
+            Some(proc) => self.eval_proc(proc, args),
+            None => internal_error!(
+                "no specialization of {:?} for {:?}",
+                name.name(),
+                proc_layout
+            ),
+        }
+    }
+
+    fn eval_stmt(
+        &self,
+        env: &mut MutMap<Symbol, Value>,
+        mut stmt: &'r Stmt<'a>,
+    ) -> Result<Value, InterpError> {
+        let mut join_points: MutMap<JoinPointId, (&'r [Param<'a>], &'r Stmt<'a>)> =
+            MutMap::default();
+
+        loop {
+            match stmt {
+                Stmt::Let(symbol, expr, layout, cont) => {
+                    let value = self.eval_expr(env, expr, *layout)?;
+                    env.insert(*symbol, value);
+                    stmt = *cont;
+                }
+                Stmt::Switch {
+                    cond_symbol,
+                    branches,
+                    default_branch,
+                    ..
+                } => {
+                    let cond = lookup(env, *cond_symbol).as_switch_condition();
+
+                    stmt = match branches.iter().find(|(value, _, _)| *value == cond) {
+                        Some((_, _, branch)) => branch,
+                        None => default_branch.1,
+                    };
+                }
+                Stmt::Ret(symbol) => return Ok(lookup(env, *symbol).clone()),
+                // Memory is managed by Rust here, and `expect`s and `dbg`s are only reported by
+                // the compiled backends.
+                Stmt::Refcounting(_, remainder)
+                | Stmt::Expect { remainder, .. }
+                | Stmt::ExpectFx { remainder, .. }
+                | Stmt::Dbg { remainder, .. } => {
+                    stmt = *remainder;
+                }
+                Stmt::Join {
+                    id,
+                    parameters,
+                    body,
+                    remainder,
+                } => {
+                    join_points.insert(*id, (*parameters, *body));
+                    stmt = *remainder;
+                }
+                Stmt::Jump(id, arguments) => {
+                    let (parameters, body) = match join_points.get(id) {
+                        Some(join_point) => *join_point,
+                        None => internal_error!("jump to unknown join point {:?}", id),
+                    };
+
+                    let values: Vec<_> = arguments
+                        .iter()
+                        .map(|symbol| lookup(env, *symbol).clone())
+                        .collect();
+
+                    for (param, value) in parameters.iter().zip(values) {
+                        env.insert(param.symbol, value);
+                    }
+
+                    stmt = body;
+                }
+                Stmt::Crash(symbol, ..) => {
+                    let message = match lookup(env, *symbol) {
+                        Value::Str(message) => message.clone(),
+                        other => format!("{:?}", other),
+                    };
+
+                    return Err(InterpError::Crash(message));
+                }
+            }
+        }
+    }
+
+    fn eval_expr(
+        &self,
+        env: &MutMap<Symbol, Value>,
+        expr: &Expr<'a>,
+        layout: InLayout<'a>,
+    ) -> Result<Value, InterpError> {
+        let values = |symbols: &[Symbol]| -> Vec<Value> {
+            symbols
+                .iter()
+                .map(|symbol| lookup(env, *symbol).clone())
+                .collect()
+        };
+
+        match expr {
+            Expr::Literal(literal) => self.eval_literal(literal, layout),
+            Expr::Call(Call {
+                call_type,
+                arguments,
+            }) => match call_type {
+                CallType::ByName {
+                    name,
+                    ret_layout,
+                    arg_layouts,
+                    ..
+                } => self.call_by_name(*name, *arg_layouts, *ret_layout, values(arguments)),
+                CallType::LowLevel { op, .. } => {
+                    self.eval_low_level(*op, &values(arguments), layout)
+                }
+                CallType::Foreign { foreign_symbol, .. } => Err(InterpError::Unsupported(format!(
+                    "calling the host function {}",
+                    foreign_symbol.as_str()
+                ))),
+                CallType::HigherOrder(higher_order) => self.eval_higher_order(env, higher_order),
+            },
+            Expr::Tag {
+                tag_id, arguments, ..
+            }
+            | Expr::Reuse {
+                tag_id, arguments, ..
+            } => Ok(Value::Tag {
+                tag_id: *tag_id,
+                fields: values(arguments),
+            }),
+            Expr::Struct(fields) => Ok(Value::Struct(values(fields))),
+            Expr::StructAtIndex {
+                index, structure, ..
+            } => match lookup(env, *structure) {
+                Value::Struct(fields) => Ok(fields[*index as usize].clone()),
+                other => internal_error!("cannot index into {:?}", other),
+            },
+            Expr::GetTagId { structure, .. } => match lookup(env, *structure) {
+                Value::Tag { tag_id, .. } => Ok(Value::Int(*tag_id as i128)),
+                other => internal_error!("cannot get the tag id of {:?}", other),
+            },
+            Expr::UnionAtIndex {
+                structure, index, ..
+            } => match lookup(env, *structure) {
+                Value::Tag { fields, .. } => Ok(fields[*index as usize].clone()),
+                other => internal_error!("cannot index into {:?}", other),
+            },
+            Expr::Array { elem_layout, elems } => {
+                let mut list = Vec::with_capacity(elems.len());
+
+                for elem in elems.iter() {
+                    list.push(match elem {
+                        ListLiteralElement::Literal(literal) => {
+                            self.eval_literal(literal, *elem_layout)?
+                        }
+                        ListLiteralElement::Symbol(symbol) => lookup(env, *symbol).clone(),
+                    });
+                }
+
+                Ok(Value::List(list))
+            }
+            Expr::EmptyArray => Ok(Value::List(Vec::new())),
+            // Boxes are only a matter of memory layout, which the interpreter doesn't model.
+            Expr::ExprBox { symbol } | Expr::ExprUnbox { symbol } => {
+                Ok(lookup(env, *symbol).clone())
+            }
+            // The reuse token is only consumed by `Expr::Reuse`, which ignores it.
+            Expr::Reset { .. } => Ok(Value::unit()),
+            Expr::RuntimeErrorFunction(message) => Err(InterpError::Crash(message.to_string())),
+        }
+    }
+
+    fn eval_low_level(
+        &self,
+        op: LowLevel,
+        args: &[Value],
+        ret_layout: InLayout<'a>,
+    ) -> Result<Value, InterpError> {
+        use LowLevel::*;
+        use Value::*;
+
+        let int_width = || match self.interner.get(ret_layout) {
+            Layout::Builtin(Builtin::Int(width)) => width,
+            other => internal_error!("expected an integer layout, got {:?}", other),
+        };
+
+        let checked = |result: Option<i128>, message: &str| match result {
+            Some(n) if int_fits(int_width(), n) => Ok(Int(n)),
+            _ => Err(InterpError::Crash(message.to_string())),
+        };
+
+        let checked_u128 = |result: Option<u128>, message: &str| match result {
+            Some(n) => Ok(U128(n)),
+            None => Err(InterpError::Crash(message.to_string())),
+        };
+
+        let value = match (op, args) {
+            (NumAdd, [Int(a), Int(b)]) => {
+                checked(a.checked_add(*b), "Integer addition overflowed!")?
+            }
+            (NumSub, [Int(a), Int(b)]) => {
+                checked(a.checked_sub(*b), "Integer subtraction overflowed!")?
+            }
+            (NumMul, [Int(a), Int(b)]) => {
+                checked(a.checked_mul(*b), "Integer multiplication overflowed!")?
+            }
+            (NumAddWrap, [Int(a), Int(b)]) => Int(wrap(int_width(), a.wrapping_add(*b))),
+            (NumSubWrap, [Int(a), Int(b)]) => Int(wrap(int_width(), a.wrapping_sub(*b))),
+            (NumMulWrap, [Int(a), Int(b)]) => Int(wrap(int_width(), a.wrapping_mul(*b))),
+            (NumNeg, [Int(a)]) => checked(a.checked_neg(), "Integer negation overflowed!")?,
+            (NumDivTruncUnchecked | NumRemUnchecked, [Int(_), Int(0)] | [U128(_), U128(0)]) => {
+                return Err(InterpError::Crash("Integer division by 0!".to_string()))
+            }
+            (NumDivTruncUnchecked, [Int(a), Int(b)]) => {
+                checked(a.checked_div(*b), "Integer division overflowed!")?
+            }
+            // The remainder of e.g. `I64.MIN % -1` is 0, even though the quotient overflows.
+            (NumRemUnchecked, [Int(a), Int(b)]) => Int(a.wrapping_rem(*b)),
+            (NumAbs, [Int(a)]) => checked(a.checked_abs(), "Integer absolute value overflowed!")?,
+
+            (NumAdd, [U128(a), U128(b)]) => {
+                checked_u128(a.checked_add(*b), "Integer addition overflowed!")?
+            }
+            (NumSub, [U128(a), U128(b)]) => {
+                checked_u128(a.checked_sub(*b), "Integer subtraction overflowed!")?
+            }
+            (NumMul, [U128(a), U128(b)]) => {
+                checked_u128(a.checked_mul(*b), "Integer multiplication overflowed!")?
+            }
+            (NumAddWrap, [U128(a), U128(b)]) => U128(a.wrapping_add(*b)),
+            (NumSubWrap, [U128(a), U128(b)]) => U128(a.wrapping_sub(*b)),
+            (NumMulWrap, [U128(a), U128(b)]) => U128(a.wrapping_mul(*b)),
+            (NumDivTruncUnchecked, [U128(a), U128(b)]) => U128(a / b),
+            (NumRemUnchecked, [U128(a), U128(b)]) => U128(a % b),
+            (NumAbs, [U128(a)]) => U128(*a),
+            (NumToFrac, [U128(a)]) => Float(self.round_float(ret_layout, *a as f64)),
+
+            (NumAdd, [Float(a), Float(b)]) => Float(self.round_float(ret_layout, a + b)),
+            (NumSub, [Float(a), Float(b)]) => Float(self.round_float(ret_layout, a - b)),
+            (NumMul, [Float(a), Float(b)]) => Float(self.round_float(ret_layout, a * b)),
+            (NumDivFrac, [Float(a), Float(b)]) => Float(self.round_float(ret_layout, a / b)),
+            (NumNeg, [Float(a)]) => Float(-a),
+            (NumAbs, [Float(a)]) => Float(a.abs()),
+            (NumToFrac, [Int(a)]) => Float(self.round_float(ret_layout, *a as f64)),
+
+            (NumGt, [Int(a), Int(b)]) => Bool(a > b),
+            (NumGte, [Int(a), Int(b)]) => Bool(a >= b),
+            (NumLt, [Int(a), Int(b)]) => Bool(a < b),
+            (NumLte, [Int(a), Int(b)]) => Bool(a <= b),
+            (NumGt, [U128(a), U128(b)]) => Bool(a > b),
+            (NumGte, [U128(a), U128(b)]) => Bool(a >= b),
+            (NumLt, [U128(a), U128(b)]) => Bool(a < b),
+            (NumLte, [U128(a), U128(b)]) => Bool(a <= b),
+            (NumGt, [Float(a), Float(b)]) => Bool(a > b),
+            (NumGte, [Float(a), Float(b)]) => Bool(a >= b),
+            (NumLt, [Float(a), Float(b)]) => Bool(a < b),
+            (NumLte, [Float(a), Float(b)]) => Bool(a <= b),
+            (NumCompare, [Int(a), Int(b)]) => from_ordering(a.cmp(b)),
+            (NumCompare, [U128(a), U128(b)]) => from_ordering(a.cmp(b)),
+            (NumCompare, [Float(a), Float(b)]) => {
+                from_ordering(a.partial_cmp(b).unwrap_or(Ordering::Equal))
+            }
+
+            (NumToStr, [Int(a)]) => Str(a.to_string()),
+            (NumToStr, [U128(a)]) => Str(a.to_string()),
+
+            (Eq, [a, b]) => Bool(a == b),
+            (NotEq, [a, b]) => Bool(a != b),
+            (And, [Bool(a), Bool(b)]) => Bool(*a && *b),
+            (Or, [Bool(a), Bool(b)]) => Bool(*a || *b),
+            (Not, [Bool(a)]) => Bool(!a),
+
+            (StrConcat, [Str(a), Str(b)]) => Str(format!("{a}{b}")),
+            (StrIsEmpty, [Str(a)]) => Bool(a.is_empty()),
+            (StrCountUtf8Bytes, [Str(a)]) => Int(a.len() as i128),
+            (StrToUtf8, [Str(a)]) => List(a.bytes().map(|byte| Int(byte as i128)).collect()),
+
+            (ListLen, [List(list)]) => Int(list.len() as i128),
+            (ListGetUnsafe, [List(list), Int(index)]) => list[*index as usize].clone(),
+            (ListConcat, [List(a), List(b)]) => List(a.iter().chain(b).cloned().collect()),
+            (ListAppendUnsafe, [List(list), elem]) => {
+                let mut list = list.clone();
+                list.push(elem.clone());
+                List(list)
+            }
+            (ListPrepend, [List(list), elem]) => List(
+                std::iter::once(elem.clone())
+                    .chain(list.iter().cloned())
+                    .collect(),
+            ),
+            (ListWithCapacity, [_]) => List(Vec::new()),
+            (ListReserve, [List(list), _]) => List(list.clone()),
+
+            _ => {
+                return Err(InterpError::Unsupported(format!(
+                    "the builtin {:?} on {:?}",
+                    op, args
+                )))
+            }
+        };
+
+        Ok(value)
+    }
+
+    fn eval_higher_order(
+        &self,
+        env: &MutMap<Symbol, Value>,
+        higher_order: &HigherOrderLowLevel<'a>,
+    ) -> Result<Value, InterpError> {
+        use HigherOrder::*;
+
+        let HigherOrderLowLevel {
+            op,
+            passed_function,
+            ..
+        } = higher_order;
+
+        let list = |symbol: &Symbol| match lookup(env, *symbol) {
+            Value::List(list) => list.as_slice(),
+            other => internal_error!("expected a list, got {:?}", other),
+        };
+
+        let call = |args: Vec<Value>| self.call_passed_function(env, *op, passed_function, args);
+
+        // Like the builtins, the mapN functions stop at the end of the shortest list.
+        let map_n = |lists: &[&[Value]]| -> Result<Value, InterpError> {
+            let len = lists.iter().map(|list| list.len()).min().unwrap_or(0);
+
+            (0..len)
+                .map(|index| call(lists.iter().map(|list| list[index].clone()).collect()))
+                .collect::<Result<_, _>>()
+                .map(Value::List)
+        };
+
+        match op {
+            ListMap { xs } | ListMapConcurrent { xs } => map_n(&[list(xs)]),
+            ListMap2 { xs, ys } => map_n(&[list(xs), list(ys)]),
+            ListMap3 { xs, ys, zs } => map_n(&[list(xs), list(ys), list(zs)]),
+            ListMap4 { xs, ys, zs, ws } => map_n(&[list(xs), list(ys), list(zs), list(ws)]),
+            ListSortWith { xs } => {
+                let compare = |a: &Value, b: &Value| {
+                    call(vec![a.clone(), b.clone()]).map(|ordering| to_ordering(&ordering))
+                };
+
+                merge_sort(list(xs).to_vec(), &compare).map(Value::List)
+            }
+        }
+    }
+
+    fn call_passed_function(
+        &self,
+        env: &MutMap<Symbol, Value>,
+        op: HigherOrder,
+        passed_function: &PassedFunction<'a>,
+        mut args: Vec<Value>,
+    ) -> Result<Value, InterpError> {
+        // If the function captures anything, its captured environment comes last.
+        if passed_function.argument_layouts.len() > op.function_arity() {
+            args.push(lookup(env, passed_function.captured_environment).clone());
+        }
+
+        self.call_by_name(
+            passed_function.name,
+            passed_function.argument_layouts,
+            passed_function.return_layout,
+            args,
+        )
+    }
+
+    fn eval_literal(&self, literal: &Literal, layout: InLayout<'a>) -> Result<Value, InterpError> {
+        let is_u128 = matches!(
+            self.interner.get(layout),
+            Layout::Builtin(Builtin::Int(IntWidth::U128))
+        );
+
+        let value = match literal {
+            Literal::Int(bytes) if is_u128 => Value::U128(u128::from_ne_bytes(*bytes)),
+            Literal::Int(bytes) => Value::Int(i128::from_ne_bytes(*bytes)),
+            Literal::U128(bytes) => Value::U128(u128::from_ne_bytes(*bytes)),
+            Literal::Float(float) => Value::Float(*float),
+            Literal::Decimal(_) => return Err(InterpError::Unsupported("Dec".to_string())),
+            Literal::Str(string) => Value::Str(string.to_string()),
+            Literal::Bool(b) => Value::Bool(*b),
+            Literal::Byte(byte) => Value::Int(*byte as i128),
+        };
+
+        Ok(value)
+    }
+
+    fn round_float(&self, layout: InLayout<'a>, float: f64) -> f64 {
+        match self.interner.get(layout) {
+            Layout::Builtin(Builtin::Float(FloatWidth::F32)) => float as f32 as f64,
+            _ => float,
+        }
+    }
+}
+
+fn lookup(env: &MutMap<Symbol, Value>, symbol: Symbol) -> &Value {
+    match env.get(&symbol) {
+        Some(value) => value,
+        None => internal_error!("symbol {:?} is not in scope", symbol),
+    }
+}
+
+/// Turns the `[EQ, GT, LT]` a sorting function returns into an [Ordering].
+fn to_ordering(value: &Value) -> Ordering {
+    let tag_id = match value {
+        Value::Int(tag_id) => *tag_id as TagIdIntType,
+        Value::Tag { tag_id, .. } => *tag_id,
+        other => internal_error!("expected an Ordering, got {:?}", other),
+    };
+
+    match tag_id {
+        0 => Ordering::Equal,
+        1 => Ordering::Greater,
+        _ => Ordering::Less,
+    }
+}
+
+/// The inverse of [to_ordering]
+fn from_ordering(ordering: Ordering) -> Value {
+    Value::Int(match ordering {
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+        Ordering::Less => 2,
+    })
+}
+
+/// A stable sort that stops at the first error of `compare`. (The standard library's sorts can't
+/// stop early, and may panic if `compare` isn't a total order.)
+fn merge_sort<F>(mut list: Vec<Value>, compare: &F) -> Result<Vec<Value>, InterpError>
+where
+    F: Fn(&Value, &Value) -> Result<Ordering, InterpError>,
+{
+    if list.len() <= 1 {
+        return Ok(list);
+    }
+
+    let right = merge_sort(list.split_off(list.len() / 2), compare)?;
+    let left = merge_sort(list, compare)?;
+
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+
+    while let (Some(l), Some(r)) = (left.peek(), right.peek()) {
+        if compare(l, r)? == Ordering::Greater {
+            merged.extend(right.next());
+        } else {
+            merged.extend(left.next());
+        }
+    }
+
+    merged.extend(left);
+    merged.extend(right);
+
+    Ok(merged)
+}
+
+fn int_fits(width: IntWidth, n: i128) -> bool {
+    use IntWidth::*;
+
+    match width {
+        U8 => u8::try_from(n).is_ok(),
+        U16 => u16::try_from(n).is_ok(),
+        U32 => u32::try_from(n).is_ok(),
+        U64 => u64::try_from(n).is_ok(),
+        U128 => n >= 0,
+        I8 => i8::try_from(n).is_ok(),
+        I16 => i16::try_from(n).is_ok(),
+        I32 => i32::try_from(n).is_ok(),
+        I64 => i64::try_from(n).is_ok(),
+        I128 => true,
+    }
+}
+
+fn wrap(width: IntWidth, n: i128) -> i128 {
+    use IntWidth::*;
+
+    match width {
+        U8 => n as u8 as i128,
+        U16 => n as u16 as i128,
+        U32 => n as u32 as i128,
+        U64 => n as u64 as i128,
+        I8 => n as i8 as i128,
+        I16 => n as i16 as i128,
+        I32 => n as i32 as i128,
+        I64 => n as i64 as i128,
+        U128 | I128 => n,
+    }
+}
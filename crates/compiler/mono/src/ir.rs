@@ -29,7 +29,7 @@ use roc_module::ident::{ForeignSymbol, Lowercase, TagName};
 use roc_module::low_level::LowLevel;
 use roc_module::symbol::{IdentIds, ModuleId, Symbol};
 use roc_problem::can::{RuntimeError, ShadowKind};
-use roc_region::all::{Loc, Region};
+use roc_region::all::{LineInfo, Loc, Region};
 use roc_std::RocDec;
 use roc_target::TargetInfo;
 use roc_types::subs::{
@@ -77,7 +77,8 @@ fn runtime_error<'a>(env: &mut Env<'a, '_>, msg: &'a str) -> Stmt<'a> {
         sym,
         Expr::Literal(Literal::Str(msg)),
         Layout::STR,
-        env.arena.alloc(Stmt::Crash(sym, CrashTag::Roc)),
+        env.arena
+            .alloc(Stmt::Crash(sym, CrashTag::Roc, CrashRegion::UNKNOWN)),
     )
 }
 
@@ -1393,6 +1394,10 @@ pub struct Env<'a, 'i> {
     /// [Subs] to write specialized variables of lookups in expects.
     /// [None] if this module doesn't produce any expects.
     pub expectation_subs: Option<&'i mut Subs>,
+    /// Whether `dbg`s are kept. If not, they're lowered to just the value they'd print.
+    pub keep_dbgs: bool,
+    /// Whether every inline `expect` crashes when it fails, like an `expect-always` does.
+    pub keep_expects: bool,
     pub home: ModuleId,
     pub ident_ids: &'i mut IdentIds,
     pub target_info: TargetInfo,
@@ -1402,6 +1407,8 @@ pub struct Env<'a, 'i> {
     pub abilities: AbilitiesView<'i>,
     pub exposed_by_module: &'i ExposedByModule,
     pub derived_module: &'i SharedDerivedModule,
+    /// For turning the regions of `crash`es into the lines and columns passed to roc_panic.
+    pub line_info: &'i LineInfo,
 }
 
 impl<'a, 'i> Env<'a, 'i> {
@@ -1584,7 +1591,7 @@ pub enum Stmt<'a> {
         remainder: &'a Stmt<'a>,
     },
     Jump(JoinPointId, &'a [Symbol]),
-    Crash(Symbol, CrashTag),
+    Crash(Symbol, CrashTag, CrashRegion),
 }
 
 /// Source of crash, and its runtime representation to roc_panic.
@@ -1609,6 +1616,56 @@ impl TryFrom<u32> for CrashTag {
     }
 }
 
+/// Where in the source a crash happened, as it's passed to roc_panic after the message.
+/// Lines and columns start at 1, so that a crash with no source region (like an overflow
+/// in a builtin) can be all zeros.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(C)]
+pub struct CrashRegion {
+    pub start_line: u32,
+    pub start_column: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+}
+
+impl CrashRegion {
+    pub const UNKNOWN: Self = Self {
+        start_line: 0,
+        start_column: 0,
+        end_line: 0,
+        end_column: 0,
+    };
+
+    /// The size of the roc_panic payload on a target: the message, followed by the region.
+    pub fn payload_size(target_info: TargetInfo) -> u32 {
+        3 * target_info.ptr_width() as u32 + 4 * 4
+    }
+
+    pub fn from_region(line_info: &LineInfo, region: Region) -> Self {
+        if region.is_empty() {
+            return Self::UNKNOWN;
+        }
+
+        let region = line_info.convert_region(region);
+
+        Self {
+            start_line: region.start.line + 1,
+            start_column: region.start.column + 1,
+            end_line: region.end.line + 1,
+            end_column: region.end.column + 1,
+        }
+    }
+
+    pub fn to_array(self) -> [u32; 4] {
+        [
+            self.start_line,
+            self.start_column,
+            self.end_line,
+            self.end_column,
+        ]
+    }
+}
+
 /// in the block below, symbol `scrutinee` is assumed be be of shape `tag_id`
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum BranchInfo<'a> {
@@ -2254,7 +2311,7 @@ impl<'a> Stmt<'a> {
                 }
             }
 
-            Crash(s, _src) => alloc
+            Crash(s, _src, _region) => alloc
                 .text("Crash ")
                 .append(symbol_to_doc(alloc, *s, pretty)),
 
@@ -4474,7 +4531,33 @@ pub fn with_hole<'a>(
 
         Expect { .. } => unreachable!("I think this is unreachable"),
         ExpectFx { .. } => unreachable!("I think this is unreachable"),
-        Dbg { .. } => unreachable!("I think this is unreachable"),
+        Dbg {
+            loc_condition,
+            loc_continuation,
+            variable: dbg_var,
+            symbol: dbg_symbol,
+            label: _,
+        } => {
+            let rest = with_hole(
+                env,
+                loc_continuation.value,
+                variable,
+                procs,
+                layout_cache,
+                assigned,
+                hole,
+            );
+
+            dbg_before(
+                env,
+                procs,
+                layout_cache,
+                *loc_condition,
+                dbg_var,
+                dbg_symbol,
+                rest,
+            )
+        }
 
         If {
             cond_var,
@@ -4833,6 +4916,8 @@ pub fn with_hole<'a>(
         }
 
         RecordAccessor(accessor_data) => {
+            // An accessor passed along, like in `List.map users .name`, becomes a proc of its own,
+            // specialized to the record it gets. It captures nothing, so its closure data is `{}`.
             let field_var = accessor_data.field_var;
             let fresh_record_symbol = env.unique_symbol();
 
@@ -5276,6 +5361,14 @@ pub fn with_hole<'a>(
             };
 
             match loc_expr.value {
+                roc_can::expr::Expr::RecordAccessor(accessor_data) if loc_args.len() == 1 => {
+                    // Calling an accessor directly, like `.name user` or `user |> .name`,
+                    // is just a field access; there is no need for a proc or closure data.
+                    let (_, loc_arg) = loc_args.into_iter().next().unwrap();
+                    let access = accessor_data.to_access(loc_arg);
+
+                    with_hole(env, access, variable, procs, layout_cache, assigned, hole)
+                }
                 roc_can::expr::Expr::Var(proc_name, _) if is_known(proc_name) => {
                     // a call by a known name
                     call_by_name(
@@ -5638,6 +5731,11 @@ pub fn with_hole<'a>(
                     let xs = arg_symbols[0];
                     match_on_closure_argument!(ListMap, [xs])
                 }
+                ListMapConcurrent => {
+                    debug_assert_eq!(arg_symbols.len(), 2);
+                    let xs = arg_symbols[0];
+                    match_on_closure_argument!(ListMapConcurrent, [xs])
+                }
                 ListSortWith => {
                     debug_assert_eq!(arg_symbols.len(), 2);
                     let xs = arg_symbols[0];
@@ -5712,7 +5810,8 @@ pub fn with_hole<'a>(
                 &msg.value,
                 Variable::STR,
             );
-            let stmt = Stmt::Crash(msg_sym, CrashTag::User);
+            let region = CrashRegion::from_region(env.line_info, msg.region);
+            let stmt = Stmt::Crash(msg_sym, CrashTag::User, region);
 
             assign_to_symbol(env, procs, layout_cache, Variable::STR, *msg, msg_sym, stmt)
         }
@@ -6617,10 +6716,19 @@ pub fn from_can<'a>(
             loc_condition,
             loc_continuation,
             lookups_in_cond,
+            always,
         } => {
             let rest = from_can(env, variable, loc_continuation.value, procs, layout_cache);
             let cond_symbol = env.unique_symbol();
 
+            let rest = if always || env.keep_expects {
+                let region = CrashRegion::from_region(env.line_info, loc_condition.region);
+
+                assert_expectation(env, layout_cache, variable, cond_symbol, region, rest)
+            } else {
+                rest
+            };
+
             let mut lookups = Vec::with_capacity_in(lookups_in_cond.len(), env.arena);
             let mut lookup_variables = Vec::with_capacity_in(lookups_in_cond.len(), env.arena);
             let mut specialized_variables = Vec::with_capacity_in(lookups_in_cond.len(), env.arena);
@@ -6754,44 +6862,19 @@ pub fn from_can<'a>(
             loc_continuation,
             variable,
             symbol: dbg_symbol,
+            label: _,
         } => {
             let rest = from_can(env, variable, loc_continuation.value, procs, layout_cache);
 
-            let spec_var = env
-                .expectation_subs
-                .as_mut()
-                .unwrap()
-                .fresh_unnamed_flex_var();
-
-            let dbg_stmt = Stmt::Dbg {
-                symbol: dbg_symbol,
-                variable: spec_var,
-                remainder: env.arena.alloc(rest),
-            };
-
-            // Now that the dbg value has been specialized, export its specialized type into the
-            // expectations subs.
-            store_specialized_expectation_lookups(env, [variable], &[spec_var]);
-
-            let symbol_is_reused = matches!(
-                can_reuse_symbol(env, procs, &loc_condition.value, variable),
-                ReuseSymbol::Value(_)
-            );
-
-            // skip evaluating the condition if it's just a symbol
-            if symbol_is_reused {
-                dbg_stmt
-            } else {
-                with_hole(
-                    env,
-                    loc_condition.value,
-                    variable,
-                    procs,
-                    layout_cache,
-                    dbg_symbol,
-                    env.arena.alloc(dbg_stmt),
-                )
-            }
+            dbg_before(
+                env,
+                procs,
+                layout_cache,
+                *loc_condition,
+                variable,
+                dbg_symbol,
+                rest,
+            )
         }
 
         LetRec(defs, cont, _cycle_mark) => {
@@ -6829,6 +6912,98 @@ pub fn from_can<'a>(
     }
 }
 
+/// Continues with `rest` if the expectation's condition holds, and crashes otherwise. This keeps an
+/// `expect` as a runtime assertion, even in builds where failed `expect`s aren't reported.
+fn assert_expectation<'a>(
+    env: &mut Env<'a, '_>,
+    layout_cache: &mut LayoutCache<'a>,
+    variable: Variable,
+    cond_symbol: Symbol,
+    region: CrashRegion,
+    rest: Stmt<'a>,
+) -> Stmt<'a> {
+    let ret_layout = return_on_layout_error!(
+        env,
+        layout_cache.from_var(env.arena, variable, env.subs),
+        "assert_expectation"
+    );
+
+    let msg_symbol = env.unique_symbol();
+    let crash = Stmt::Let(
+        msg_symbol,
+        Expr::Literal(Literal::Str("An expectation failed!")),
+        Layout::STR,
+        env.arena
+            .alloc(Stmt::Crash(msg_symbol, CrashTag::User, region)),
+    );
+
+    let branches = env.arena.alloc([(1u64, BranchInfo::None, rest)]);
+    let default_branch = (BranchInfo::None, &*env.arena.alloc(crash));
+
+    Stmt::Switch {
+        cond_symbol,
+        cond_layout: Layout::BOOL,
+        ret_layout,
+        branches,
+        default_branch,
+    }
+}
+
+/// Evaluates the condition of a `dbg` into its symbol, and then prints it (if dbgs are kept)
+/// before continuing with `rest`.
+fn dbg_before<'a>(
+    env: &mut Env<'a, '_>,
+    procs: &mut Procs<'a>,
+    layout_cache: &mut LayoutCache<'a>,
+    loc_condition: Loc<roc_can::expr::Expr>,
+    variable: Variable,
+    dbg_symbol: Symbol,
+    rest: Stmt<'a>,
+) -> Stmt<'a> {
+    let stmt = if env.keep_dbgs {
+        let spec_var = env
+            .expectation_subs
+            .as_mut()
+            .unwrap()
+            .fresh_unnamed_flex_var();
+
+        let dbg_stmt = Stmt::Dbg {
+            symbol: dbg_symbol,
+            variable: spec_var,
+            remainder: env.arena.alloc(rest),
+        };
+
+        // Now that the dbg value has been specialized, export its specialized type into the
+        // expectations subs.
+        store_specialized_expectation_lookups(env, [variable], &[spec_var]);
+
+        dbg_stmt
+    } else {
+        // The value is still evaluated, since the continuation may use it.
+        rest
+    };
+
+    let symbol_is_reused = matches!(
+        can_reuse_symbol(env, procs, &loc_condition.value, variable),
+        ReuseSymbol::Value(_)
+    );
+
+    // skip evaluating the condition if it's just a symbol
+    if symbol_is_reused {
+        stmt
+    } else {
+        with_hole(
+            env,
+            loc_condition.value,
+            variable,
+            procs,
+            layout_cache,
+            dbg_symbol,
+            env.arena.alloc(stmt),
+        )
+    }
+}
+
 fn store_specialized_expectation_lookups(
     env: &mut Env,
     lookup_variables: impl IntoIterator<Item = Variable>,
@@ -7046,7 +7221,12 @@ fn substitute(substitutions: &BumpMap<Symbol, Symbol>, s: Symbol) -> Option<Symb
     }
 }
 
-fn substitute_in_exprs<'a>(arena: &'a Bump, stmt: &mut Stmt<'a>, from: Symbol, to: Symbol) {
+pub(crate) fn substitute_in_exprs<'a>(
+    arena: &'a Bump,
+    stmt: &mut Stmt<'a>,
+    from: Symbol,
+    to: Symbol,
+) {
     let mut subs = BumpMap::with_capacity_in(1, arena);
     subs.insert(from, to);
 
@@ -7268,7 +7448,9 @@ fn substitute_in_stmt_help<'a>(
                 None
             }
         }
-        Crash(msg, tag) => substitute(subs, *msg).map(|new| &*arena.alloc(Crash(new, *tag))),
+        Crash(msg, tag, region) => {
+            substitute(subs, *msg).map(|new| &*arena.alloc(Crash(new, *tag, *region)))
+        }
     }
 }
 
@@ -7325,7 +7507,7 @@ fn substitute_in_call<'a>(
     }
 }
 
-fn substitute_in_expr<'a>(
+pub(crate) fn substitute_in_expr<'a>(
     arena: &'a Bump,
     expr: &'a Expr<'a>,
     subs: &BumpMap<Symbol, Symbol>,
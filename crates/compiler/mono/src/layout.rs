@@ -8,6 +8,7 @@ use roc_collections::all::{default_hasher, FnvMap, MutMap};
 use roc_collections::VecSet;
 use roc_error_macros::{internal_error, todo_abilities};
 use roc_module::ident::{Lowercase, TagName};
+use roc_module::mangle;
 use roc_module::symbol::{Interns, Symbol};
 use roc_problem::can::RuntimeError;
 use roc_target::{PtrWidth, TargetInfo};
@@ -4301,14 +4302,14 @@ impl LayoutId {
     pub fn to_symbol_string(self, symbol: Symbol, interns: &Interns) -> String {
         let ident_string = symbol.as_str(interns);
         let module_string = interns.module_ids.get_name(symbol.module_id()).unwrap();
-        format!("{}_{}_{}", module_string, ident_string, self.0)
+        mangle::specialization_name(module_string, ident_string, self.0)
     }
 
     // Returns something like "roc__foo_1_exposed" when given a symbol that interns to "foo"
     // and a LayoutId of 1.
     pub fn to_exposed_symbol_string(self, symbol: Symbol, interns: &Interns) -> String {
         let ident_string = symbol.as_str(interns);
-        format!("roc__{}_{}_exposed", ident_string, self.0)
+        mangle::host_exposed_name(ident_string, self.0)
     }
 }
 
@@ -9,13 +9,17 @@
 
 pub mod borrow;
 pub mod code_gen_help;
+pub mod effect_batch;
 pub mod inc_dec;
+pub mod interp;
 pub mod ir;
 pub mod layout;
 pub mod layout_soa;
 pub mod low_level;
 pub mod reset_reuse;
+pub mod scratch_region;
 pub mod tail_recursion;
+pub mod thread_escape;
 
 // Temporary, while we can build up test cases and optimize the exhaustiveness checking.
 // For now, following this warning's advice will lead to nasty type inference errors.
@@ -5,6 +5,10 @@ pub enum HigherOrder {
     ListMap {
         xs: Symbol,
     },
+    /// Like `ListMap`, but the backend may run the function on several elements at once
+    ListMapConcurrent {
+        xs: Symbol,
+    },
     ListMap2 {
         xs: Symbol,
         ys: Symbol,
@@ -29,6 +33,7 @@ impl HigherOrder {
     pub fn function_arity(&self) -> usize {
         match self {
             HigherOrder::ListMap { .. } => 1,
+            HigherOrder::ListMapConcurrent { .. } => 1,
             HigherOrder::ListMap2 { .. } => 2,
             HigherOrder::ListMap3 { .. } => 3,
             HigherOrder::ListMap4 { .. } => 4,
@@ -42,7 +47,7 @@ impl HigherOrder {
         use HigherOrder::*;
 
         match self {
-            ListMap { .. } | ListSortWith { .. } => 2,
+            ListMap { .. } | ListMapConcurrent { .. } | ListSortWith { .. } => 2,
             ListMap2 { .. } => 3,
             ListMap3 { .. } => 4,
             ListMap4 { .. } => 5,
@@ -71,6 +76,8 @@ enum FirstOrder {
     StrToUtf8,
     StrRepeat,
     StrFromFloat,
+    StrMatches,
+    StrCapture,
     ListLen,
     ListGetUnsafe,
     ListSublist,
@@ -109,6 +116,8 @@ enum FirstOrder {
     NumCeiling,
     NumPowInt,
     NumFloor,
+    NumIsNan,
+    NumIsInfinite,
     NumIsFinite,
     NumAtan,
     NumAcos,
@@ -120,7 +129,32 @@ enum FirstOrder {
     NumShiftRightBy,
     NumBytesToU16,
     NumBytesToU32,
+    NumToStrWith,
+    RandomMix64,
+    RandomMixGamma,
+    RandomTestSeed,
+    BytesReadU16,
+    BytesReadU32,
+    BytesReadU64,
+    EncodingEncode,
+    EncodingDecode,
+    EncodingCheck,
+    JsonSkipWhitespace,
+    JsonScanString,
+    JsonScanValue,
+    JsonUnescape,
+    JsonEscape,
+    TimeFormat,
+    TimeCheck,
+    TimeParseSeconds,
+    TimeParseNanos,
     NumShiftRightZfBy,
+    NumPopCount,
+    NumLeadingZeros,
+    NumTrailingZeros,
+    NumRotateLeftBy,
+    NumRotateRightBy,
+    NumSwapBytes,
     NumIntCast,
     NumFloatCast,
     Eq,
@@ -0,0 +1,212 @@
+//! Finds the list literals which never outlive the call that makes them, so that they can be
+//! allocated from a scratch region instead of the heap. Each call of a procedure gets its own
+//! region on the stack, which is released all at once when the call returns. Each list in the
+//! region gets exactly the bytes its refcount and elements need.
+//!
+//! A list stays local when it's only ever read: its length and elements may be looked at, but it
+//! is never returned, stored in another value, passed to a procedure, or passed to a join point.
+//! Lists in a region are never freed, and so never decrement their elements; that means their
+//! elements must not be reference counted either. String literals are the exception, since they
+//! are never freed anyway: small ones are stored inline, and the rest are constants.
+use crate::inc_dec::occurring_variables_expr;
+use crate::ir::{CallType, Expr, ListLiteralElement, Literal, Proc, Stmt};
+use crate::layout::{InLayout, LayoutInterner};
+use roc_collections::all::{MutMap, MutSet};
+use roc_module::low_level::LowLevel;
+use roc_module::symbol::Symbol;
+
+/// Regions live on the stack, so lists stop being put in a call's region once it would get bigger
+/// than this
+const MAX_REGION_BYTES: u32 = 4096;
+
+/// The stack memory of one list literal in a scratch region
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScratchList {
+    /// Bytes for the refcount, the padding after it, and the elements
+    pub size: u32,
+    pub alignment: u32,
+    /// Where the first element is. The refcount is stored just before it.
+    pub elements_offset: u32,
+}
+
+/// Where each local list literal of a procedure goes in that procedure's scratch region
+#[derive(Debug, Default)]
+pub struct ScratchRegion {
+    lists: MutMap<Symbol, ScratchList>,
+    size: u32,
+}
+
+impl ScratchRegion {
+    pub fn from_proc<'a, I>(interner: &I, proc: &Proc<'a>) -> Self
+    where
+        I: LayoutInterner<'a>,
+    {
+        let (literals, escaped) = find_literals(interner, &proc.body);
+        let ptr_width = interner.target_info().ptr_width() as u32;
+
+        let mut region = Self::default();
+
+        for (symbol, element_layout, length) in literals {
+            if escaped.contains(&symbol) {
+                continue;
+            }
+
+            // Like on the heap, the refcount goes just before the elements, and the elements are
+            // aligned to at least the pointer width
+            let alignment = interner.alignment_bytes(element_layout).max(ptr_width);
+            let elements_offset = round_up_to_alignment(ptr_width, alignment);
+            let size = elements_offset + length * interner.stack_size(element_layout);
+
+            if region.size + size > MAX_REGION_BYTES {
+                continue;
+            }
+
+            region.lists.insert(
+                symbol,
+                ScratchList {
+                    size,
+                    alignment,
+                    elements_offset,
+                },
+            );
+            region.size += size;
+        }
+
+        region
+    }
+
+    /// Where the list literal bound to this symbol goes, if it goes in the region
+    pub fn get(&self, symbol: Symbol) -> Option<ScratchList> {
+        self.lists.get(&symbol).copied()
+    }
+
+    pub fn lists(&self) -> impl Iterator<Item = (Symbol, ScratchList)> + '_ {
+        self.lists.iter().map(|(symbol, list)| (*symbol, *list))
+    }
+
+    /// The total bytes of every list in the region
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lists.is_empty()
+    }
+}
+
+fn round_up_to_alignment(width: u32, alignment: u32) -> u32 {
+    match alignment {
+        0 => width,
+        alignment => (width + alignment - 1) / alignment * alignment,
+    }
+}
+
+/// Operations which only read a list, and don't keep it or give it back
+fn only_reads_list(expr: &Expr<'_>) -> bool {
+    match expr {
+        Expr::Call(call) => matches!(
+            call.call_type,
+            CallType::LowLevel {
+                op: LowLevel::ListLen | LowLevel::ListGetUnsafe,
+                ..
+            }
+        ),
+        _ => false,
+    }
+}
+
+/// Finds the list literals whose elements are never freed (with their element layout and length),
+/// and every symbol which may escape
+fn find_literals<'a, I>(
+    interner: &I,
+    stmt: &Stmt<'a>,
+) -> (Vec<(Symbol, InLayout<'a>, u32)>, MutSet<Symbol>)
+where
+    I: LayoutInterner<'a>,
+{
+    let mut arrays = Vec::new();
+    let mut str_literals = MutSet::default();
+    let mut escaped = MutSet::default();
+    let mut stack = vec![stmt];
+
+    while let Some(stmt) = stack.pop() {
+        match stmt {
+            Stmt::Let(symbol, expr, _, cont) => {
+                match expr {
+                    Expr::Array { elem_layout, elems } if !elems.is_empty() => {
+                        arrays.push((*symbol, *elem_layout, *elems));
+                    }
+                    Expr::Literal(Literal::Str(_)) => {
+                        str_literals.insert(*symbol);
+                    }
+                    _ => {}
+                }
+
+                if !only_reads_list(expr) {
+                    occurring_variables_expr(expr, &mut escaped);
+                }
+
+                stack.push(cont);
+            }
+            // Refcounts of lists in the region are never changed, so these don't matter
+            Stmt::Refcounting(_, cont) => stack.push(cont),
+            Stmt::Switch {
+                cond_symbol,
+                branches,
+                default_branch,
+                ..
+            } => {
+                escaped.insert(*cond_symbol);
+
+                stack.extend(branches.iter().map(|(_, _, branch)| branch));
+                stack.push(default_branch.1);
+            }
+            Stmt::Ret(symbol) | Stmt::Crash(symbol, ..) => {
+                escaped.insert(*symbol);
+            }
+            Stmt::Jump(_, arguments) => escaped.extend(arguments.iter().copied()),
+            Stmt::Join {
+                body, remainder, ..
+            } => {
+                stack.push(body);
+                stack.push(remainder);
+            }
+            Stmt::Dbg {
+                symbol, remainder, ..
+            } => {
+                escaped.insert(*symbol);
+                stack.push(remainder);
+            }
+            Stmt::Expect {
+                condition,
+                lookups,
+                remainder,
+                ..
+            }
+            | Stmt::ExpectFx {
+                condition,
+                lookups,
+                remainder,
+                ..
+            } => {
+                escaped.insert(*condition);
+                escaped.extend(lookups.iter().copied());
+                stack.push(remainder);
+            }
+        }
+    }
+
+    let literals = arrays
+        .into_iter()
+        .filter(|(_, elem_layout, elems)| {
+            !interner.contains_refcounted(*elem_layout)
+                || elems.iter().all(|elem| match elem {
+                    ListLiteralElement::Literal(literal) => matches!(literal, Literal::Str(_)),
+                    ListLiteralElement::Symbol(symbol) => str_literals.contains(symbol),
+                })
+        })
+        .map(|(symbol, elem_layout, elems)| (symbol, elem_layout, elems.len() as u32))
+        .collect();
+
+    (literals, escaped)
+}
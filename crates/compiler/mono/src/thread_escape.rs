@@ -0,0 +1,131 @@
+//! Finds the values which may escape to other threads, so that only their reference counts have
+//! to be updated atomically. Atomic updates are much slower than plain ones, so everything else
+//! keeps using plain ones.
+//!
+//! A value escapes when it's passed to an operation which may run on several threads at once,
+//! like `List.mapConcurrent`: the elements it maps, the values it returns, and whatever the
+//! mapping function captured. Reference counts are updated by layout, so this works out which
+//! layouts those values (and everything inside them) have.
+use crate::ir::{CallType, Expr, Proc, Stmt};
+use crate::layout::{Builtin, InLayout, Layout, LayoutInterner, UnionLayout};
+use crate::low_level::HigherOrder;
+use roc_collections::all::MutSet;
+
+/// The layouts of values which may be reference counted from several threads at once
+#[derive(Debug, Default)]
+pub struct ThreadSharedLayouts<'a> {
+    layouts: MutSet<InLayout<'a>>,
+}
+
+impl<'a> ThreadSharedLayouts<'a> {
+    pub fn from_procs<'p, I>(interner: &I, procs: impl IntoIterator<Item = &'p Proc<'a>>) -> Self
+    where
+        'a: 'p,
+        I: LayoutInterner<'a>,
+    {
+        let mut shared = Self::default();
+
+        for proc in procs {
+            shared.find_in_stmt(interner, &proc.body);
+        }
+
+        shared
+    }
+
+    /// Whether values with this layout need their reference counts updated atomically
+    pub fn contains(&self, layout: InLayout<'a>) -> bool {
+        self.layouts.contains(&layout)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layouts.is_empty()
+    }
+
+    fn find_in_stmt<I>(&mut self, interner: &I, stmt: &Stmt<'a>)
+    where
+        I: LayoutInterner<'a>,
+    {
+        let mut stack = vec![stmt];
+
+        while let Some(stmt) = stack.pop() {
+            match stmt {
+                Stmt::Let(_, expr, _, cont) => {
+                    if let Expr::Call(call) = expr {
+                        if let CallType::HigherOrder(higher_order) = call.call_type {
+                            if let HigherOrder::ListMapConcurrent { .. } = higher_order.op {
+                                let passed_function = &higher_order.passed_function;
+
+                                for layout in passed_function.argument_layouts {
+                                    self.insert(interner, *layout);
+                                }
+
+                                self.insert(interner, passed_function.return_layout);
+
+                                if let Some(layout) = higher_order.closure_env_layout {
+                                    self.insert(interner, layout);
+                                }
+                            }
+                        }
+                    }
+
+                    stack.push(cont);
+                }
+                Stmt::Switch {
+                    branches,
+                    default_branch,
+                    ..
+                } => {
+                    stack.extend(branches.iter().map(|(_, _, branch)| branch));
+                    stack.push(default_branch.1);
+                }
+                Stmt::Refcounting(_, cont) => stack.push(cont),
+                Stmt::Expect { remainder, .. }
+                | Stmt::ExpectFx { remainder, .. }
+                | Stmt::Dbg { remainder, .. } => stack.push(remainder),
+                Stmt::Join {
+                    body, remainder, ..
+                } => {
+                    stack.push(body);
+                    stack.push(remainder);
+                }
+                Stmt::Ret(_) | Stmt::Jump(_, _) | Stmt::Crash(..) => {}
+            }
+        }
+    }
+
+    /// Marks the layout, and every layout inside it, as shared
+    fn insert<I>(&mut self, interner: &I, layout: InLayout<'a>)
+    where
+        I: LayoutInterner<'a>,
+    {
+        let mut stack = vec![layout];
+
+        while let Some(layout) = stack.pop() {
+            if !self.layouts.insert(layout) {
+                continue;
+            }
+
+            match interner.get(layout) {
+                Layout::Builtin(Builtin::List(element)) => stack.push(element),
+                Layout::Builtin(_) => {}
+                Layout::Struct { field_layouts, .. } => stack.extend(field_layouts),
+                Layout::Boxed(inner) => stack.push(inner),
+                Layout::Union(union_layout) => match union_layout {
+                    UnionLayout::NonRecursive(tags) | UnionLayout::Recursive(tags) => {
+                        stack.extend(tags.iter().flat_map(|fields| fields.iter()))
+                    }
+                    UnionLayout::NonNullableUnwrapped(fields) => stack.extend(fields),
+                    UnionLayout::NullableWrapped { other_tags, .. } => {
+                        stack.extend(other_tags.iter().flat_map(|fields| fields.iter()))
+                    }
+                    UnionLayout::NullableUnwrapped { other_fields, .. } => {
+                        stack.extend(other_fields)
+                    }
+                },
+                Layout::LambdaSet(lambda_set) => stack.push(lambda_set.runtime_representation()),
+                // The union this points to was marked when we got to it
+                Layout::RecursivePointer(_) => {}
+            }
+        }
+    }
+}
@@ -248,6 +248,9 @@ pub enum Expr<'a> {
     /// e.g. `.foo` or `.0`
     AccessorFunction(Accessor<'a>),
 
+    /// Unwrap an `Ok`, or return the `Err` from the enclosing function, e.g. `(Str.toU8 str)?`
+    Try(&'a Expr<'a>),
+
     /// Look up exactly one field on a tuple, e.g. `(x, y).1`.
     TupleAccess(&'a Expr<'a>, &'a str),
 
@@ -274,6 +277,10 @@ pub enum Expr<'a> {
     // The "crash" keyword
     Crash,
 
+    /// The "dbg" keyword applied like a function, e.g. `dbg x` or `x |> dbg "label"`.
+    /// It prints its last argument, and then evaluates to it.
+    DbgKeyword,
+
     // Tags
     Tag(&'a str),
 
@@ -287,6 +294,9 @@ pub enum Expr<'a> {
     Backpassing(&'a [Loc<Pattern<'a>>], &'a Loc<Expr<'a>>, &'a Loc<Expr<'a>>),
     Expect(&'a Loc<Expr<'a>>, &'a Loc<Expr<'a>>),
     Dbg(&'a Loc<Expr<'a>>, &'a Loc<Expr<'a>>),
+    /// Leave the enclosing function early with this value, e.g. `return Err NotFound`.
+    /// Only allowed where the function would otherwise end, like at the end of an `if` branch.
+    Return(&'a Loc<Expr<'a>>),
 
     // Application
     /// To apply by name, do Apply(Var(...), ...)
@@ -396,6 +406,13 @@ pub enum TypeDef<'a> {
         loc_has: Loc<Has<'a>>,
         members: &'a [AbilityMember<'a>],
     },
+
+    /// A pattern synonym, which stands for a bigger pattern wherever it's matched on. E.g.
+    ///   pattern Ok2 x = Ok (Ok x)
+    PatternSynonym {
+        header: TypeHeader<'a>,
+        pattern: Loc<Pattern<'a>>,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -426,12 +443,20 @@ pub enum ValueDef<'a> {
     Expect {
         condition: &'a Loc<Expr<'a>>,
         preceding_comment: Region,
+        /// Whether this is an `expect-always`, which stays a runtime assertion in optimized builds
+        always: bool,
     },
 
     ExpectFx {
         condition: &'a Loc<Expr<'a>>,
         preceding_comment: Region,
     },
+
+    /// A top-level `bench` block, which `roc bench` runs and times
+    Bench {
+        body: &'a Loc<Expr<'a>>,
+        preceding_comment: Region,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Default)]
@@ -1261,6 +1286,14 @@ impl<'a> Expr<'a> {
     pub fn is_opaque(&self) -> bool {
         matches!(self, Expr::OpaqueRef(_))
     }
+
+    /// Whether this is a `return`, ignoring any spaces around it
+    pub fn is_return(&self) -> bool {
+        match self {
+            Expr::SpaceBefore(inner, _) | Expr::SpaceAfter(inner, _) => inner.is_return(),
+            other => matches!(other, Expr::Return(_)),
+        }
+    }
 }
 
 macro_rules! impl_extract_spaces {
@@ -1465,7 +1498,8 @@ impl<'a> Malformed for Expr<'a> {
             Tag(_) |
             OpaqueRef(_) |
             SingleQuote(_) | // This is just a &str - not a bunch of segments
-            Crash => false,
+            Crash |
+            DbgKeyword => false,
 
             Str(inner) => inner.is_malformed(),
 
@@ -1483,6 +1517,8 @@ impl<'a> Malformed for Expr<'a> {
             Backpassing(args, call, body) => args.iter().any(|arg| arg.is_malformed()) || call.is_malformed() || body.is_malformed(),
             Expect(condition, continuation) |
             Dbg(condition, continuation) => condition.is_malformed() || continuation.is_malformed(),
+            Return(value) => value.is_malformed(),
+            Try(inner) => inner.is_malformed(),
             Apply(func, args, _) => func.is_malformed() || args.iter().any(|arg| arg.is_malformed()),
             BinOps(firsts, last) => firsts.iter().any(|(expr, _)| expr.is_malformed()) || last.is_malformed(),
             UnaryOp(expr, _) => expr.is_malformed(),
@@ -1628,6 +1664,9 @@ impl<'a> Malformed for TypeDef<'a> {
                     || loc_has.is_malformed()
                     || members.iter().any(|member| member.is_malformed())
             }
+            TypeDef::PatternSynonym { header, pattern } => {
+                header.is_malformed() || pattern.is_malformed()
+            }
         }
     }
 }
@@ -1704,11 +1743,16 @@ impl<'a> Malformed for ValueDef<'a> {
             | ValueDef::Expect {
                 condition,
                 preceding_comment: _,
+                always: _,
             }
             | ValueDef::ExpectFx {
                 condition,
                 preceding_comment: _,
             } => condition.is_malformed(),
+            ValueDef::Bench {
+                body,
+                preceding_comment: _,
+            } => body.is_malformed(),
         }
     }
 }
@@ -11,8 +11,8 @@ use crate::keyword;
 use crate::parser::{
     self, backtrackable, increment_min_indent, line_min_indent, optional, reset_min_indent,
     sep_by1, sep_by1_e, set_min_indent, specialize, specialize_ref, then, word1, word1_indent,
-    word2, EClosure, EExpect, EExpr, EIf, EInParens, EList, ENumber, EPattern, ERecord, EString,
-    EType, EWhen, Either, ParseResult, Parser,
+    word2, EBench, EClosure, EExpect, EExpr, EIf, EInParens, EList, ENumber, EPattern, ERecord,
+    EReturn, EString, EType, EWhen, Either, ParseResult, Parser,
 };
 use crate::pattern::{closure_param, loc_has_parser};
 use crate::state::State;
@@ -153,12 +153,37 @@ fn record_field_access_chain<'a>() -> impl Parser<'a, Vec<'a, Accessor<'a>>, EEx
     ))
 }
 
+/// A term, and any `?`s right after it, like in `x??` or `record?.field?`
+fn try_suffix<'a>(
+    parser: impl Parser<'a, Loc<Expr<'a>>, EExpr<'a>>,
+) -> impl Parser<'a, Loc<Expr<'a>>, EExpr<'a>> {
+    move |arena: &'a Bump, state: State<'a>, min_indent: u32| {
+        let (progress, mut loc_term, mut state) = parser.parse(arena, state, min_indent)?;
+
+        while state.bytes().first() == Some(&b'?') {
+            state = state.advance(1);
+
+            let value = Expr::Try(arena.alloc(loc_term.value));
+
+            // the term already took the field accesses before this `?`, but not the ones after
+            let (_, accessors, next_state) =
+                record_field_access_chain().parse(arena, state, min_indent)?;
+            let value = apply_expr_access_chain(arena, value, accessors);
+
+            state = next_state;
+            loc_term = Loc::at(Region::new(loc_term.region.start(), state.pos()), value);
+        }
+
+        Ok((progress, loc_term, state))
+    }
+}
+
 /// In some contexts we want to parse the `_` as an expression, so it can then be turned into a
 /// pattern later
 fn loc_term_or_underscore_or_conditional<'a>(
     options: ExprParseOptions,
 ) -> impl Parser<'a, Loc<Expr<'a>>, EExpr<'a>> {
-    one_of!(
+    try_suffix(one_of!(
         loc_expr_in_parens_etc_help(),
         loc!(specialize(EExpr::If, if_expr_help(options))),
         loc!(specialize(EExpr::When, when::expr_help(options))),
@@ -166,6 +191,7 @@ fn loc_term_or_underscore_or_conditional<'a>(
         loc!(specialize(EExpr::Number, positive_number_literal_help())),
         loc!(specialize(EExpr::Closure, closure_help(options))),
         loc!(crash_kw()),
+        loc!(specialize(EExpr::Dbg, dbg_kw())),
         loc!(underscore_expression()),
         loc!(record_literal_help()),
         loc!(specialize(EExpr::List, list_literal_help())),
@@ -173,7 +199,7 @@ fn loc_term_or_underscore_or_conditional<'a>(
             assign_or_destructure_identifier(),
             ident_to_expr
         )),
-    )
+    ))
 }
 
 /// In some contexts we want to parse the `_` as an expression, so it can then be turned into a
@@ -181,7 +207,7 @@ fn loc_term_or_underscore_or_conditional<'a>(
 fn loc_term_or_underscore<'a>(
     options: ExprParseOptions,
 ) -> impl Parser<'a, Loc<Expr<'a>>, EExpr<'a>> {
-    one_of!(
+    try_suffix(one_of!(
         loc_expr_in_parens_etc_help(),
         loc!(specialize(EExpr::Str, string_like_literal_help())),
         loc!(specialize(EExpr::Number, positive_number_literal_help())),
@@ -193,11 +219,11 @@ fn loc_term_or_underscore<'a>(
             assign_or_destructure_identifier(),
             ident_to_expr
         )),
-    )
+    ))
 }
 
 fn loc_term<'a>(options: ExprParseOptions) -> impl Parser<'a, Loc<Expr<'a>>, EExpr<'a>> {
-    one_of!(
+    try_suffix(one_of!(
         loc_expr_in_parens_etc_help(),
         loc!(specialize(EExpr::Str, string_like_literal_help())),
         loc!(specialize(EExpr::Number, positive_number_literal_help())),
@@ -208,7 +234,7 @@ fn loc_term<'a>(options: ExprParseOptions) -> impl Parser<'a, Loc<Expr<'a>>, EEx
             assign_or_destructure_identifier(),
             ident_to_expr
         )),
-    )
+    ))
 }
 
 fn underscore_expression<'a>() -> impl Parser<'a, Expr<'a>, EExpr<'a>> {
@@ -238,6 +264,15 @@ fn crash_kw<'a>() -> impl Parser<'a, Expr<'a>, EExpr<'a>> {
     }
 }
 
+fn dbg_kw<'a>() -> impl Parser<'a, Expr<'a>, EExpect<'a>> {
+    move |arena: &'a Bump, state: State<'a>, min_indent: u32| {
+        let (_, _, next_state) =
+            parser::keyword_e(keyword::DBG, EExpect::Dbg).parse(arena, state, min_indent)?;
+
+        Ok((MadeProgress, Expr::DbgKeyword, next_state))
+    }
+}
+
 fn loc_possibly_negative_or_negated_term<'a>(
     options: ExprParseOptions,
 ) -> impl Parser<'a, Loc<Expr<'a>>, EExpr<'a>> {
@@ -300,7 +335,9 @@ fn expr_start<'a>(options: ExprParseOptions) -> impl Parser<'a, Loc<Expr<'a>>, E
         loc!(specialize(EExpr::When, when::expr_help(options))),
         loc!(specialize(EExpr::Expect, expect_help(options))),
         loc!(specialize(EExpr::Dbg, dbg_help(options))),
+        loc!(specialize(EExpr::Return, return_help(options))),
         loc!(specialize(EExpr::Closure, closure_help(options))),
+        loc!(pattern_synonym_help(options)),
         loc!(expr_operator_chain(options)),
         fail_expr_start_e()
     ]
@@ -535,8 +572,10 @@ fn numeric_negate_expression<'a, T>(
     }
 }
 
+/// Parse one def of a block, or of a module if `toplevel` is set
 pub fn parse_single_def<'a>(
     options: ExprParseOptions,
+    toplevel: bool,
     min_indent: u32,
     arena: &'a Bump,
     state: State<'a>,
@@ -561,7 +600,42 @@ pub fn parse_single_def<'a>(
 
     let parse_expect_vanilla = crate::parser::keyword_e(crate::keyword::EXPECT, EExpect::Expect);
     let parse_expect_fx = crate::parser::keyword_e(crate::keyword::EXPECT_FX, EExpect::Expect);
-    let parse_expect = either!(parse_expect_fx, parse_expect_vanilla);
+    let parse_expect_always =
+        crate::parser::keyword_e(crate::keyword::EXPECT_ALWAYS, EExpect::Expect);
+    let parse_expect = either!(
+        parse_expect_fx,
+        either!(parse_expect_always, parse_expect_vanilla)
+    );
+
+    if let Some((type_def, region, state)) =
+        parse_pattern_synonym(arena, state.clone(), min_indent)?
+    {
+        return Ok((
+            MadeProgress,
+            Some(SingleDef {
+                type_or_value: Either::First(type_def),
+                region,
+                spaces_before: spaces_before_current,
+            }),
+            state,
+        ));
+    }
+
+    if toplevel {
+        if let Some((loc_body, state)) = parse_bench(options, arena, state.clone(), min_indent)? {
+            return Ok(statement_def(
+                state,
+                start,
+                spaces_before_current_start,
+                spaces_before_current,
+                loc_body,
+                |preceding_comment, loc_body| ValueDef::Bench {
+                    body: arena.alloc(loc_body),
+                    preceding_comment,
+                },
+            ));
+        }
+    }
 
     match space0_after_e(crate::pattern::loc_pattern_help(), EPattern::IndentEnd).parse(
         arena,
@@ -583,9 +657,10 @@ pub fn parse_single_def<'a>(
                     spaces_before_current_start,
                     spaces_before_current,
                     |preceding_comment, loc_def_expr| match expect_flavor {
-                        Either::Second(_) => ValueDef::Expect {
+                        Either::Second(always) => ValueDef::Expect {
                             condition: arena.alloc(loc_def_expr),
                             preceding_comment,
+                            always: matches!(always, Either::First(_)),
                         },
                         Either::First(_) => ValueDef::ExpectFx {
                             condition: arena.alloc(loc_def_expr),
@@ -818,6 +893,82 @@ pub fn parse_single_def<'a>(
     }
 }
 
+/// Parses a pattern synonym like `pattern Ok2 x = Ok (Ok x)`. Anything else that starts with
+/// `pattern` is some other def, so this gives back None without making progress.
+fn parse_pattern_synonym<'a>(
+    arena: &'a Bump,
+    state: State<'a>,
+    min_indent: u32,
+) -> Result<Option<(TypeDef<'a>, Region, State<'a>)>, (Progress, EExpr<'a>)> {
+    let start = state.pos();
+
+    let parse_header = skip_first!(
+        and!(
+            parser::keyword_e(keyword::PATTERN, EPattern::Start),
+            space0_e(EPattern::IndentStart)
+        ),
+        space0_after_e(crate::pattern::loc_pattern_help(), EPattern::IndentEnd)
+    );
+
+    let (loc_header, state) = match parse_header.parse(arena, state, min_indent) {
+        Ok((_, loc_header, state)) => (loc_header, state),
+        Err(_) => return Ok(None),
+    };
+
+    let header = match loc_header.value.extract_spaces().item {
+        Pattern::Apply(
+            Loc {
+                value: Pattern::Tag(name),
+                region,
+            },
+            vars,
+        ) => TypeHeader {
+            name: Loc::at(*region, *name),
+            vars,
+        },
+        Pattern::Tag(name) => TypeHeader {
+            name: Loc::at(loc_header.region, name),
+            vars: &[],
+        },
+        _ => return Ok(None),
+    };
+
+    let state = match operator().parse(arena, state, min_indent) {
+        Ok((_, BinOp::Assignment, state)) => state,
+        _ => return Ok(None),
+    };
+
+    let (_, pattern, state) =
+        space0_before_e(crate::pattern::loc_pattern_help(), EPattern::IndentStart)
+            .parse(arena, state, min_indent + 1)
+            .map_err(|(_, fail)| (MadeProgress, EExpr::Pattern(arena.alloc(fail), start)))?;
+
+    let region = Region::new(start, state.pos());
+
+    Ok(Some((
+        TypeDef::PatternSynonym { header, pattern },
+        region,
+        state,
+    )))
+}
+
+/// A pattern synonym at the start of an expression, which goes on to any other defs and then the
+/// final expression.
+fn pattern_synonym_help<'a>(options: ExprParseOptions) -> impl Parser<'a, Expr<'a>, EExpr<'a>> {
+    line_min_indent(move |arena, state: State<'a>, min_indent: u32| {
+        match parse_pattern_synonym(arena, state.clone(), min_indent)? {
+            None => Err((NoProgress, EExpr::Start(state.pos()))),
+            Some((type_def, region, state)) => {
+                let mut defs = Defs::default();
+
+                defs.push_type_def(type_def, region, &[], &[]);
+
+                parse_defs_expr(options, min_indent, defs, arena, state)
+            }
+        }
+    })
+}
+
 /// e.g. Things that can be on their own line in a def, e.g. `expect`, `expect-fx`, or `dbg`
 #[allow(clippy::too_many_arguments)]
 fn parse_statement_inside_def<'a>(
@@ -833,6 +984,25 @@ fn parse_statement_inside_def<'a>(
     let parse_def_expr =
         space0_before_e(increment_min_indent(expr_start(options)), EExpr::IndentEnd);
     let (_, loc_def_expr, state) = parse_def_expr.parse(arena, state, min_indent)?;
+
+    Ok(statement_def(
+        state,
+        start,
+        spaces_before_current_start,
+        spaces_before_current,
+        loc_def_expr,
+        get_value_def,
+    ))
+}
+
+fn statement_def<'a>(
+    state: State<'a>,
+    start: Position,
+    spaces_before_current_start: Position,
+    spaces_before_current: &'a [CommentOrNewline<'a>],
+    loc_def_expr: Loc<Expr<'a>>,
+    get_value_def: impl Fn(Region, Loc<Expr<'a>>) -> ValueDef<'a>,
+) -> (Progress, Option<SingleDef<'a>>, State<'a>) {
     let end = loc_def_expr.region.end();
     let region = Region::new(start, end);
 
@@ -853,7 +1023,7 @@ fn parse_statement_inside_def<'a>(
     let preceding_comment = Region::new(spaces_before_current_start, start);
     let value_def = get_value_def(preceding_comment, loc_def_expr);
 
-    Ok((
+    (
         MadeProgress,
         Some(SingleDef {
             type_or_value: Either::Second(value_def),
@@ -861,7 +1031,43 @@ fn parse_statement_inside_def<'a>(
             spaces_before: spaces_before_current,
         }),
         state,
-    ))
+    )
+}
+
+/// The code timed by a top-level `bench` block, like `bench List.sortAsc bigList`. `bench` is
+/// only special there, and not when it's defined or annotated like `bench = 1`, so it isn't one
+/// of the [keyword::KEYWORDS], and can still be used as a name.
+fn parse_bench<'a>(
+    options: ExprParseOptions,
+    arena: &'a Bump,
+    state: State<'a>,
+    min_indent: u32,
+) -> Result<Option<(Loc<Expr<'a>>, State<'a>)>, (Progress, EExpr<'a>)> {
+    let start = state.pos();
+
+    let state =
+        match parser::keyword_e(keyword::BENCH, EBench::Bench).parse(arena, state, min_indent) {
+            Ok((_, _, state)) => state,
+            Err(_) => return Ok(None),
+        };
+
+    let next = state
+        .bytes()
+        .iter()
+        .find(|byte| !byte.is_ascii_whitespace());
+
+    if matches!(next, Some(b'=' | b':')) {
+        return Ok(None);
+    }
+
+    let (_, loc_body, state) = space0_before_e(
+        specialize_ref(EBench::Body, increment_min_indent(expr_start(options))),
+        EBench::IndentBody,
+    )
+    .parse(arena, state, min_indent)
+    .map_err(|(_, fail)| (MadeProgress, EExpr::Bench(fail, start)))?;
+
+    Ok(Some((loc_body, state)))
 }
 
 // This is a macro only because trying to make it be a function caused lifetime issues.
@@ -925,6 +1131,7 @@ macro_rules! join_alias_to_body {
 
 fn parse_defs_end<'a>(
     options: ExprParseOptions,
+    toplevel: bool,
     min_indent: u32,
     mut defs: Defs<'a>,
     arena: &'a Bump,
@@ -935,7 +1142,7 @@ fn parse_defs_end<'a>(
     loop {
         let state = global_state;
 
-        global_state = match parse_single_def(options, min_indent, arena, state) {
+        global_state = match parse_single_def(options, toplevel, min_indent, arena, state) {
             Ok((_, Some(single_def), next_state)) => {
                 let region = single_def.region;
                 let spaces_before_current = single_def.spaces_before;
@@ -1030,7 +1237,7 @@ fn parse_defs_expr<'a>(
     arena: &'a Bump,
     state: State<'a>,
 ) -> ParseResult<'a, Expr<'a>, EExpr<'a>> {
-    match parse_defs_end(options, min_indent, defs, arena, state) {
+    match parse_defs_end(options, false, min_indent, defs, arena, state) {
         Err(bad) => Err(bad),
         Ok((_, def_state, state)) => {
             // this is no def, because there is no `=` or `:`; parse as an expr
@@ -1902,11 +2109,14 @@ fn expr_to_pattern_help<'a>(arena: &'a Bump, expr: &Expr<'a>) -> Result<Pattern<
         | Expr::When(_, _)
         | Expr::Expect(_, _)
         | Expr::Dbg(_, _)
+        | Expr::Return(_)
+        | Expr::Try(_)
         | Expr::MalformedClosure
         | Expr::PrecedenceConflict { .. }
         | Expr::RecordUpdate { .. }
         | Expr::UnaryOp(_, _)
-        | Expr::Crash => return Err(()),
+        | Expr::Crash
+        | Expr::DbgKeyword => return Err(()),
 
         Expr::Str(string) => Pattern::StrLiteral(string),
         Expr::SingleQuote(string) => Pattern::SingleQuote(string),
@@ -1988,7 +2198,8 @@ pub fn toplevel_defs<'a>() -> impl Parser<'a, Defs<'a>, EExpr<'a>> {
         let mut output = Defs::default();
         let before = Slice::extend_new(&mut output.spaces, initial_space.iter().copied());
 
-        let (_, mut output, state) = parse_defs_end(options, start_column, output, arena, state)?;
+        let (_, mut output, state) =
+            parse_defs_end(options, true, start_column, output, arena, state)?;
 
         let (_, final_space, state) =
             space0_e(EExpr::IndentEnd).parse(arena, state, start_column)?;
@@ -2282,32 +2493,33 @@ mod when {
 }
 
 fn if_branch<'a>() -> impl Parser<'a, (Loc<Expr<'a>>, Loc<Expr<'a>>), EIf<'a>> {
-    skip_second!(
-        and!(
-            skip_second!(
-                space0_around_ee(
-                    specialize_ref(EIf::Condition, loc_expr(true)),
-                    EIf::IndentCondition,
-                    EIf::IndentThenToken,
-                ),
-                parser::keyword_e(keyword::THEN, EIf::Then)
-            ),
+    and!(
+        skip_second!(
             space0_around_ee(
-                specialize_ref(EIf::ThenBranch, loc_expr(true)),
-                EIf::IndentThenBranch,
-                EIf::IndentElseToken,
-            )
+                specialize_ref(EIf::Condition, loc_expr(true)),
+                EIf::IndentCondition,
+                EIf::IndentThenToken,
+            ),
+            parser::keyword_e(keyword::THEN, EIf::Then)
         ),
-        parser::keyword_e(keyword::ELSE, EIf::Else)
+        space0_around_ee(
+            specialize_ref(EIf::ThenBranch, loc_expr(true)),
+            EIf::IndentThenBranch,
+            EIf::IndentElseToken,
+        )
     )
 }
 
 fn expect_help<'a>(options: ExprParseOptions) -> impl Parser<'a, Expr<'a>, EExpect<'a>> {
     move |arena: &'a Bump, state: State<'a>, min_indent| {
         let start_column = state.column();
+        let start = state.pos();
 
-        let (_, _, state) =
-            parser::keyword_e(keyword::EXPECT, EExpect::Expect).parse(arena, state, min_indent)?;
+        let (_, flavor, state) = either!(
+            parser::keyword_e(keyword::EXPECT_ALWAYS, EExpect::Expect),
+            parser::keyword_e(keyword::EXPECT, EExpect::Expect)
+        )
+        .parse(arena, state, min_indent)?;
 
         let (_, condition, state) = space0_before_e(
             specialize_ref(
@@ -2326,7 +2538,23 @@ fn expect_help<'a>(options: ExprParseOptions) -> impl Parser<'a, Expr<'a>, EExpe
 
         let (_, loc_cont, state) = parse_cont.parse(arena, state, min_indent)?;
 
-        let expr = Expr::Expect(arena.alloc(condition), arena.alloc(loc_cont));
+        let expr = match flavor {
+            Either::First(()) => {
+                // an `expect-always` is kept as a def, like it would be after other defs
+                let region = Region::new(start, condition.region.end());
+                let value_def = ValueDef::Expect {
+                    condition: arena.alloc(condition),
+                    preceding_comment: Region::new(start, start),
+                    always: true,
+                };
+
+                let mut defs = Defs::default();
+                defs.push_value_def(value_def, region, &[], &[]);
+
+                Expr::Defs(arena.alloc(defs), arena.alloc(loc_cont))
+            }
+            Either::Second(()) => Expr::Expect(arena.alloc(condition), arena.alloc(loc_cont)),
+        };
 
         Ok((MadeProgress, expr, state))
     }
@@ -2335,10 +2563,13 @@ fn expect_help<'a>(options: ExprParseOptions) -> impl Parser<'a, Expr<'a>, EExpe
 fn dbg_help<'a>(options: ExprParseOptions) -> impl Parser<'a, Expr<'a>, EExpect<'a>> {
     move |arena: &'a Bump, state: State<'a>, min_indent| {
         let start_column = state.column();
+        let start = state.pos();
 
         let (_, _, state) =
             parser::keyword_e(keyword::DBG, EExpect::Dbg).parse(arena, state, min_indent)?;
 
+        let keyword_region = Region::new(start, state.pos());
+
         let (_, condition, state) = space0_before_e(
             specialize_ref(
                 EExpect::Condition,
@@ -2349,16 +2580,72 @@ fn dbg_help<'a>(options: ExprParseOptions) -> impl Parser<'a, Expr<'a>, EExpect<
         .parse(arena, state, start_column + 1)
         .map_err(|(_, f)| (MadeProgress, f))?;
 
-        let parse_cont = specialize_ref(
-            EExpect::Continuation,
-            space0_before_e(expr_start(options), EExpr::IndentEnd),
-        );
+        // Only a `dbg` with more code on the lines after it prints before a continuation, like
+        // `expect`. Otherwise it's applied like a function, and evaluates to what it prints.
+        if let Ok((_, spaces, cont_state)) =
+            space0_e(EExpr::IndentEnd).parse(arena, state.clone(), min_indent)
+        {
+            if !spaces.is_empty() {
+                let cont_start = cont_state.pos();
 
-        let (_, loc_cont, state) = parse_cont.parse(arena, state, min_indent)?;
+                match expr_start(options).parse(arena, cont_state, min_indent) {
+                    Ok((_, loc_cont, state)) => {
+                        let loc_cont = arena
+                            .alloc(loc_cont.value)
+                            .with_spaces_before(spaces, loc_cont.region);
+                        let expr = Expr::Dbg(arena.alloc(condition), arena.alloc(loc_cont));
 
-        let expr = Expr::Dbg(arena.alloc(condition), arena.alloc(loc_cont));
+                        return Ok((MadeProgress, expr, state));
+                    }
+                    Err((MadeProgress, fail)) => {
+                        let fail = EExpect::Continuation(arena.alloc(fail), cont_start);
 
-        Ok((MadeProgress, expr, state))
+                        return Err((MadeProgress, fail));
+                    }
+                    Err((NoProgress, _)) => {}
+                }
+            }
+        }
+
+        let loc_keyword = arena.alloc(Loc::at(keyword_region, Expr::DbgKeyword));
+
+        // `dbg "label" x` parses its argument as applying the label, since that's what it would
+        // be anywhere else
+        let args: &[&Loc<Expr>] = match condition.value {
+            Expr::Apply(loc_label, [loc_value], CalledVia::Space)
+                if matches!(loc_label.value, Expr::Str(_)) =>
+            {
+                arena.alloc([loc_label, *loc_value])
+            }
+            _ => arena.alloc([&*arena.alloc(condition)]),
+        };
+
+        Ok((
+            MadeProgress,
+            Expr::Apply(loc_keyword, args, CalledVia::Space),
+            state,
+        ))
+    }
+}
+
+fn return_help<'a>(options: ExprParseOptions) -> impl Parser<'a, Expr<'a>, EReturn<'a>> {
+    move |arena: &'a Bump, state: State<'a>, min_indent| {
+        let start_column = state.column();
+
+        let (_, _, state) =
+            parser::keyword_e(keyword::RETURN, EReturn::Return).parse(arena, state, min_indent)?;
+
+        let (_, value, state) = space0_before_e(
+            specialize_ref(
+                EReturn::Value,
+                set_min_indent(start_column + 1, expr_start(options)),
+            ),
+            EReturn::IndentValue,
+        )
+        .parse(arena, state, start_column + 1)
+        .map_err(|(_, f)| (MadeProgress, f))?;
+
+        Ok((MadeProgress, Expr::Return(arena.alloc(value)), state))
     }
 }
 
@@ -2371,12 +2658,26 @@ fn if_expr_help<'a>(options: ExprParseOptions) -> impl Parser<'a, Expr<'a>, EIf<
 
         let mut loop_state = state;
 
-        let state_final_else = loop {
+        let (state_final_else, is_guard) = loop {
             let (_, (cond, then_branch), state) =
                 if_branch().parse(arena, loop_state, min_indent)?;
 
+            // A branch which returns doesn't need an `else`, e.g. `if x < 0 then return Err Neg`.
+            // The code after it is used as the `else` instead.
+            let is_guard = then_branch.value.is_return();
+
             branches.push((cond, then_branch));
 
+            let state = match parser::keyword_e(keyword::ELSE, EIf::Else).parse(
+                arena,
+                state.clone(),
+                min_indent,
+            ) {
+                Ok((_, _, state)) => state,
+                Err((NoProgress, _)) if is_guard => break (state, true),
+                Err((_, fail)) => return Err((MadeProgress, fail)),
+            };
+
             // try to parse another `if`
             // NOTE this drops spaces between the `else` and the `if`
             let optional_if = and!(
@@ -2385,7 +2686,7 @@ fn if_expr_help<'a>(options: ExprParseOptions) -> impl Parser<'a, Expr<'a>, EIf<
             );
 
             match optional_if.parse(arena, state.clone(), min_indent) {
-                Err((_, _)) => break state,
+                Err((_, _)) => break (state, false),
                 Ok((_, _, state)) => {
                     loop_state = state;
                     continue;
@@ -2393,13 +2694,36 @@ fn if_expr_help<'a>(options: ExprParseOptions) -> impl Parser<'a, Expr<'a>, EIf<
             }
         };
 
-        let (_, else_branch, state) = space0_before_e(
+        let (_, mut else_branch, state) = space0_before_e(
             specialize_ref(EIf::ElseBranch, expr_start(options)),
             EIf::IndentElseBranch,
         )
         .parse(arena, state_final_else, min_indent)
         .map_err(|(_, f)| (MadeProgress, f))?;
 
+        if is_guard {
+            // The spaces after a guard's `return` come before the code after the guard
+            let (_, last_then) = branches.last_mut().unwrap();
+
+            let spaces_after = match last_then.value {
+                Expr::SpaceAfter(value, spaces_after) => {
+                    last_then.value = *value;
+                    spaces_after
+                }
+                Expr::SpaceBefore(&Expr::SpaceAfter(value, spaces_after), spaces_before) => {
+                    last_then.value = Expr::SpaceBefore(value, spaces_before);
+                    spaces_after
+                }
+                _ => &[],
+            };
+
+            if !spaces_after.is_empty() {
+                else_branch = arena
+                    .alloc(else_branch.value)
+                    .with_spaces_before(spaces_after, else_branch.region);
+            }
+        }
+
         let expr = Expr::If(branches.into_bump_slice(), arena.alloc(else_branch));
 
         Ok((MadeProgress, expr, state))
@@ -0,0 +1,673 @@
+//! A description of the syntax the parser accepts, as data, so it can be printed as EBNF (for
+//! documentation, or for a railroad diagram generator) instead of being read out of the
+//! combinators by hand.
+//!
+//! Every production carries examples, and tests check that the parser accepts each of them, so
+//! when the parser changes in a way this description doesn't, the tests notice.
+use crate::keyword;
+use roc_module::called_via::BinOp;
+use std::fmt::Write;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rule {
+    /// One of the words in [keyword::KEYWORDS]
+    Keyword(&'static str),
+    /// Literal punctuation, like `->` or `{`
+    Token(String),
+    /// Another production, by name
+    Ref(&'static str),
+    /// Something recognized by hand-written code rather than combinators, described in words
+    Lexical(&'static str),
+    Seq(Vec<Rule>),
+    Choice(Vec<Rule>),
+    Optional(Box<Rule>),
+    /// Zero or more
+    Many(Box<Rule>),
+    /// One or more, separated by a token (which may also trail)
+    SepBy1(Box<Rule>, &'static str),
+    /// Must go on lines indented further than the line that started the enclosing construct
+    Indented(Box<Rule>),
+}
+
+/// Which parser entry point a production's examples are checked with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Start {
+    Expr,
+    Defs,
+    Header,
+    /// Only makes sense as part of a bigger construct, so has no examples of its own
+    Fragment,
+}
+
+#[derive(Debug, Clone)]
+pub struct Production {
+    pub name: &'static str,
+    pub rule: Rule,
+    pub start: Start,
+    pub examples: &'static [&'static str],
+}
+
+fn kw(word: &'static str) -> Rule {
+    debug_assert!(keyword::KEYWORDS.contains(&word), "{word} is not a keyword");
+    Rule::Keyword(word)
+}
+
+fn tok(token: impl ToString) -> Rule {
+    Rule::Token(token.to_string())
+}
+
+fn r(name: &'static str) -> Rule {
+    Rule::Ref(name)
+}
+
+fn seq<const N: usize>(rules: [Rule; N]) -> Rule {
+    Rule::Seq(rules.into())
+}
+
+fn choice<const N: usize>(rules: [Rule; N]) -> Rule {
+    Rule::Choice(rules.into())
+}
+
+fn opt(rule: Rule) -> Rule {
+    Rule::Optional(Box::new(rule))
+}
+
+fn many(rule: Rule) -> Rule {
+    Rule::Many(Box::new(rule))
+}
+
+fn sep_by1(rule: Rule, separator: &'static str) -> Rule {
+    Rule::SepBy1(Box::new(rule), separator)
+}
+
+fn indented(rule: Rule) -> Rule {
+    Rule::Indented(Box::new(rule))
+}
+
+fn collection(open: &'static str, item: Rule, close: &'static str) -> Rule {
+    seq([tok(open), opt(sep_by1(item, ",")), tok(close)])
+}
+
+fn production(
+    name: &'static str,
+    start: Start,
+    rule: Rule,
+    examples: &'static [&'static str],
+) -> Production {
+    Production {
+        name,
+        rule,
+        start,
+        examples,
+    }
+}
+
+/// The binary operators, in order from highest to lowest precedence
+const BIN_OPS: [BinOp; 16] = [
+    BinOp::Caret,
+    BinOp::Star,
+    BinOp::Slash,
+    BinOp::DoubleSlash,
+    BinOp::Percent,
+    BinOp::Plus,
+    BinOp::Minus,
+    BinOp::Equals,
+    BinOp::NotEquals,
+    BinOp::LessThan,
+    BinOp::GreaterThan,
+    BinOp::LessThanOrEq,
+    BinOp::GreaterThanOrEq,
+    BinOp::And,
+    BinOp::Or,
+    BinOp::Pizza,
+];
+
+/// The productions of Roc's grammar, starting from a whole module
+pub fn productions() -> Vec<Production> {
+    use Start::*;
+
+    vec![
+        production("module", Fragment, seq([r("header"), many(r("def"))]), &[]),
+        production(
+            "header",
+            Header,
+            choice([
+                seq([
+                    tok("interface"),
+                    r("module_name"),
+                    indented(seq([
+                        tok("exposes"),
+                        collection("[", r("exposed_name"), "]"),
+                        tok("imports"),
+                        collection("[", r("import"), "]"),
+                    ])),
+                ]),
+                seq([
+                    tok("app"),
+                    r("string"),
+                    indented(seq([
+                        tok("packages"),
+                        collection("{", r("package_entry"), "}"),
+                        tok("imports"),
+                        collection("[", r("import"), "]"),
+                        tok("provides"),
+                        collection("[", r("exposed_name"), "]"),
+                        tok("to"),
+                        r("lowercase_ident"),
+                    ])),
+                ]),
+                seq([
+                    tok("hosted"),
+                    r("module_name"),
+                    indented(many(r("header_entry"))),
+                ]),
+                seq([
+                    tok("platform"),
+                    r("string"),
+                    indented(many(r("header_entry"))),
+                ]),
+                seq([
+                    tok("package"),
+                    r("string"),
+                    indented(many(r("header_entry"))),
+                ]),
+            ]),
+            &[
+                "interface Foo exposes [foo, Bar] imports [Baz]",
+                "app \"test\" packages { pf: \"platform/main.roc\" } \
+                 imports [pf.Stdout] provides [main] to pf",
+            ],
+        ),
+        production(
+            "header_entry",
+            Fragment,
+            seq([
+                Rule::Lexical("a header keyword such as `exposes` or `requires`"),
+                choice([
+                    collection("[", r("exposed_name"), "]"),
+                    collection("{", r("package_entry"), "}"),
+                    collection("{", r("allocator_entry"), "}"),
+                    collection("{", r("effects_entry"), "}"),
+                    collection("{", r("hosts_entry"), "}"),
+                    r("string"),
+                ]),
+            ]),
+            &[],
+        ),
+        production(
+            "import",
+            Fragment,
+            seq([
+                opt(seq([r("lowercase_ident"), tok(".")])),
+                r("module_name"),
+                opt(seq([tok("."), collection("{", r("exposed_name"), "}")])),
+            ]),
+            &[],
+        ),
+        production(
+            "package_entry",
+            Fragment,
+            seq([r("lowercase_ident"), tok(":"), r("string")]),
+            &[],
+        ),
+        production(
+            "allocator_entry",
+            Fragment,
+            seq([
+                r("lowercase_ident"),
+                tok(":"),
+                choice([r("lowercase_ident"), r("number")]),
+            ]),
+            &[],
+        ),
+        production(
+            "effects_entry",
+            Fragment,
+            seq([r("lowercase_ident"), tok(":"), r("lowercase_ident")]),
+            &[],
+        ),
+        production(
+            "hosts_entry",
+            Fragment,
+            seq([r("string"), tok(":"), r("string")]),
+            &[],
+        ),
+        production(
+            "exposed_name",
+            Fragment,
+            choice([r("lowercase_ident"), r("uppercase_ident")]),
+            &[],
+        ),
+        production(
+            "def",
+            Defs,
+            choice([
+                seq([r("pattern"), tok(BinOp::IsAliasType), r("type")]),
+                seq([
+                    r("uppercase_ident"),
+                    many(r("lowercase_ident")),
+                    choice([tok(BinOp::IsAliasType), tok(BinOp::IsOpaqueType)]),
+                    r("type"),
+                ]),
+                seq([r("pattern"), tok(BinOp::Assignment), indented(r("expr"))]),
+                seq([
+                    tok(keyword::PATTERN),
+                    r("uppercase_ident"),
+                    many(r("lowercase_ident")),
+                    tok(BinOp::Assignment),
+                    indented(r("pattern")),
+                ]),
+                seq([kw(keyword::EXPECT), indented(r("expr"))]),
+                seq([kw(keyword::EXPECT_FX), indented(r("expr"))]),
+                seq([kw(keyword::EXPECT_ALWAYS), indented(r("expr"))]),
+                seq([tok(keyword::BENCH), indented(r("expr"))]),
+            ]),
+            &[
+                "x = 1",
+                "f : I64 -> I64\nf = \\n -> n",
+                "Age := U8",
+                "pattern Ok2 x = Ok (Ok x)",
+                "expect 1 == 1",
+                "expect-always 1 == 1",
+                "bench List.range { start: At 0, end: Length 100 }",
+            ],
+        ),
+        production(
+            "expr",
+            Expr,
+            choice([
+                seq([r("operand"), many(seq([r("bin_op"), r("operand")]))]),
+                r("closure"),
+                r("if"),
+                r("when"),
+                seq([kw(keyword::EXPECT), r("expr"), r("expr")]),
+                seq([kw(keyword::EXPECT_ALWAYS), r("expr"), r("expr")]),
+                seq([
+                    kw(keyword::DBG),
+                    opt(r("string")),
+                    r("expr"),
+                    opt(r("expr")),
+                ]),
+                seq([kw(keyword::RETURN), r("expr")]),
+                seq([many(r("def")), r("expr")]),
+                seq([
+                    sep_by1(r("pattern"), ","),
+                    tok(BinOp::Backpassing),
+                    r("expr"),
+                    r("expr"),
+                ]),
+            ]),
+            &[
+                "1 + 2 * 3",
+                "x |> f |> g",
+                "x = 1\n\nx",
+                "expect x == 1\n\nx",
+                "expect-always x > 0\n\nx",
+                "dbg x\n\nx",
+                "dbg \"x\" x",
+                "return x",
+                "r <- f\n\nr",
+            ],
+        ),
+        production(
+            "operand",
+            Fragment,
+            choice([
+                seq([r("unary_op"), r("operand")]),
+                seq([r("term"), many(r("term"))]),
+            ]),
+            &[],
+        ),
+        production(
+            "term",
+            Expr,
+            seq([
+                choice([
+                    r("number"),
+                    r("string"),
+                    r("single_quote"),
+                    r("var"),
+                    r("tag"),
+                    seq([tok("@"), r("uppercase_ident")]),
+                    seq([tok("."), r("lowercase_ident")]),
+                    tok("_"),
+                    kw(keyword::CRASH),
+                    kw(keyword::DBG),
+                    collection("[", r("expr"), "]"),
+                    collection("{", r("record_field"), "}"),
+                    seq([
+                        tok("{"),
+                        r("lowercase_ident"),
+                        tok("&"),
+                        sep_by1(r("record_field"), ","),
+                        tok("}"),
+                    ]),
+                    collection("(", r("expr"), ")"),
+                ]),
+                many(seq([tok("."), choice([r("lowercase_ident"), r("number")])])),
+                opt(tok("?")),
+            ]),
+            &[
+                "42",
+                "\"hello\"",
+                "'a'",
+                "List.map",
+                "Ok",
+                "@Age",
+                ".name",
+                "crash \"oops\"",
+                "dbg \"label\"",
+                "[1, 2, 3]",
+                "{ x: 1, y }",
+                "{ r & x: 2 }",
+                "(1, 2).0",
+                "user.name",
+                "(Str.toU8 str)?",
+            ],
+        ),
+        production(
+            "record_field",
+            Fragment,
+            seq([
+                r("lowercase_ident"),
+                opt(seq([choice([tok(":"), tok("?")]), r("expr")])),
+            ]),
+            &[],
+        ),
+        production(
+            "closure",
+            Expr,
+            seq([tok("\\"), sep_by1(r("pattern"), ","), tok("->"), r("expr")]),
+            &["\\x -> x", "\\x, y -> x + y"],
+        ),
+        production(
+            "if",
+            Expr,
+            choice([
+                seq([
+                    kw(keyword::IF),
+                    r("expr"),
+                    kw(keyword::THEN),
+                    r("expr"),
+                    kw(keyword::ELSE),
+                    r("expr"),
+                ]),
+                seq([
+                    kw(keyword::IF),
+                    r("expr"),
+                    kw(keyword::THEN),
+                    kw(keyword::RETURN),
+                    r("expr"),
+                    r("expr"),
+                ]),
+            ]),
+            &[
+                "if x then 1 else 2",
+                "if a then 1 else if b then 2 else 3",
+                "if x then return 1\nx",
+            ],
+        ),
+        production(
+            "when",
+            Expr,
+            seq([
+                kw(keyword::WHEN),
+                r("expr"),
+                kw(keyword::IS),
+                indented(many(seq([
+                    sep_by1(r("pattern"), "|"),
+                    opt(seq([kw(keyword::IF), r("expr")])),
+                    tok("->"),
+                    r("expr"),
+                ]))),
+            ]),
+            &[
+                "when x is\n    Ok a -> a\n    Err _ -> 0",
+                "when x is\n    1 | 2 if y -> 3\n    _ -> 4",
+            ],
+        ),
+        production(
+            "pattern",
+            Fragment,
+            seq([
+                choice([
+                    r("lowercase_ident"),
+                    tok("_"),
+                    r("number"),
+                    r("string"),
+                    r("single_quote"),
+                    seq([r("tag"), many(r("pattern"))]),
+                    seq([tok("@"), r("uppercase_ident"), many(r("pattern"))]),
+                    collection("{", r("record_pattern_field"), "}"),
+                    collection("[", choice([r("pattern"), tok("..")]), "]"),
+                    collection("(", r("pattern"), ")"),
+                ]),
+                opt(seq([kw(keyword::AS), r("lowercase_ident")])),
+            ]),
+            &[],
+        ),
+        production(
+            "record_pattern_field",
+            Fragment,
+            seq([
+                r("lowercase_ident"),
+                opt(seq([choice([tok(":"), tok("?")]), r("pattern")])),
+            ]),
+            &[],
+        ),
+        production(
+            "type",
+            Fragment,
+            choice([
+                seq([sep_by1(r("type_term"), ","), tok("->"), r("type")]),
+                seq([
+                    r("type_term"),
+                    opt(seq([tok("|"), sep_by1(r("ability_bound"), ",")])),
+                ]),
+            ]),
+            &[],
+        ),
+        production(
+            "type_term",
+            Fragment,
+            seq([
+                choice([
+                    seq([r("module_name"), many(r("type_term"))]),
+                    r("lowercase_ident"),
+                    tok("*"),
+                    tok("_"),
+                    seq([
+                        collection("{", r("record_type_field"), "}"),
+                        opt(r("lowercase_ident")),
+                    ]),
+                    seq([
+                        collection("[", seq([r("tag"), many(r("type_term"))]), "]"),
+                        opt(r("lowercase_ident")),
+                    ]),
+                    collection("(", r("type"), ")"),
+                ]),
+                opt(seq([kw(keyword::AS), r("uppercase_ident")])),
+            ]),
+            &[],
+        ),
+        production(
+            "record_type_field",
+            Fragment,
+            seq([
+                r("lowercase_ident"),
+                choice([tok(":"), tok("?")]),
+                r("type"),
+            ]),
+            &[],
+        ),
+        production(
+            "ability_bound",
+            Fragment,
+            seq([
+                r("lowercase_ident"),
+                tok("has"),
+                sep_by1(r("module_name"), "&"),
+            ]),
+            &[],
+        ),
+        production(
+            "bin_op",
+            Fragment,
+            Rule::Choice(BIN_OPS.iter().map(tok).collect()),
+            &[],
+        ),
+        production("unary_op", Fragment, choice([tok("-"), tok("!")]), &[]),
+        production(
+            "var",
+            Fragment,
+            seq([
+                many(seq([r("uppercase_ident"), tok(".")])),
+                r("lowercase_ident"),
+            ]),
+            &[],
+        ),
+        production(
+            "module_name",
+            Fragment,
+            sep_by1(r("uppercase_ident"), "."),
+            &[],
+        ),
+        production("tag", Fragment, r("uppercase_ident"), &[]),
+        production(
+            "lowercase_ident",
+            Fragment,
+            Rule::Lexical("a lowercase letter, then letters and digits, which is not a keyword"),
+            &[],
+        ),
+        production(
+            "uppercase_ident",
+            Fragment,
+            Rule::Lexical("an uppercase letter, then letters and digits"),
+            &[],
+        ),
+        production(
+            "number",
+            Fragment,
+            Rule::Lexical("a number literal, such as `12`, `0x1F`, `1.5`, or `3u8`"),
+            &[],
+        ),
+        production(
+            "string",
+            Fragment,
+            Rule::Lexical("a string literal, with escapes and `\\(...)` interpolation"),
+            &[],
+        ),
+        production(
+            "single_quote",
+            Fragment,
+            Rule::Lexical("a single character between single quotes"),
+            &[],
+        ),
+    ]
+}
+
+fn write_rule(buf: &mut String, rule: &Rule, nested: bool) {
+    match rule {
+        Rule::Keyword(word) => write!(buf, "'{}'", word).unwrap(),
+        Rule::Token(token) if token.contains('\'') => write!(buf, "\"{}\"", token).unwrap(),
+        Rule::Token(token) => write!(buf, "'{}'", token).unwrap(),
+        Rule::Ref(name) => buf.push_str(name),
+        Rule::Lexical(description) => write!(buf, "? {} ?", description).unwrap(),
+        Rule::Seq(rules) => {
+            if nested {
+                buf.push('(');
+            }
+
+            for (index, rule) in rules.iter().enumerate() {
+                if index > 0 {
+                    buf.push(' ');
+                }
+
+                write_rule(buf, rule, true);
+            }
+
+            if nested {
+                buf.push(')');
+            }
+        }
+        Rule::Choice(rules) => {
+            if nested {
+                buf.push('(');
+            }
+
+            for (index, rule) in rules.iter().enumerate() {
+                if index > 0 {
+                    buf.push_str(if nested { " | " } else { "\n    | " });
+                }
+
+                write_rule(buf, rule, true);
+            }
+
+            if nested {
+                buf.push(')');
+            }
+        }
+        Rule::Optional(rule) => {
+            write_rule(buf, rule, true);
+            buf.push('?');
+        }
+        Rule::Many(rule) => {
+            write_rule(buf, rule, true);
+            buf.push('*');
+        }
+        Rule::SepBy1(rule, separator) => {
+            buf.push('(');
+            write_rule(buf, rule, true);
+            write!(buf, " ('{}' ", separator).unwrap();
+            write_rule(buf, rule, true);
+            write!(buf, ")* '{}'?)", separator).unwrap();
+        }
+        Rule::Indented(rule) => {
+            // EBNF has no way to say this, so it goes in a comment
+            write_rule(buf, rule, true);
+            buf.push_str(" /* indented */");
+        }
+    }
+}
+
+/// Renders the productions in the W3C flavor of EBNF, which railroad diagram generators
+/// such as https://www.bottlecaps.de/rr/ui accept.
+pub fn to_ebnf(productions: &[Production]) -> String {
+    let mut buf = String::new();
+
+    for production in productions {
+        write!(buf, "{} ::= ", production.name).unwrap();
+        write_rule(&mut buf, &production.rule, false);
+        buf.push_str("\n\n");
+    }
+
+    buf
+}
+
+/// The names of productions which are used without being defined
+pub fn undefined_refs(productions: &[Production]) -> Vec<&'static str> {
+    fn visit(rule: &Rule, refs: &mut Vec<&'static str>) {
+        match rule {
+            Rule::Ref(name) => refs.push(name),
+            Rule::Keyword(_) | Rule::Token(_) | Rule::Lexical(_) => {}
+            Rule::Seq(rules) | Rule::Choice(rules) => {
+                rules.iter().for_each(|rule| visit(rule, refs))
+            }
+            Rule::Optional(rule)
+            | Rule::Many(rule)
+            | Rule::SepBy1(rule, _)
+            | Rule::Indented(rule) => visit(rule, refs),
+        }
+    }
+
+    let mut refs = Vec::new();
+
+    for production in productions {
+        visit(&production.rule, &mut refs);
+    }
+
+    refs.retain(|name| !productions.iter().any(|p| p.name == *name));
+    refs.dedup();
+
+    refs
+}
@@ -1,13 +1,18 @@
 use crate::ast::{
-    Collection, CommentOrNewline, Malformed, Spaced, Spaces, StrLiteral, TypeAnnotation,
+    Collection, CommentOrNewline, ExtractSpaces, Malformed, Spaced, Spaces, StrLiteral,
+    TypeAnnotation,
 };
 use crate::blankspace::space0_e;
 use crate::ident::{lowercase_ident, UppercaseIdent};
+use crate::parser::Progress::MadeProgress;
 use crate::parser::{optional, then};
-use crate::parser::{specialize, word1, EPackageEntry, EPackageName, Parser};
+use crate::parser::{
+    specialize, word1, EAllocator, EBuild, EEffects, EHosts, EPackageEntry, EPackageName, Parser,
+};
+use crate::state::State;
 use crate::string_literal;
 use roc_module::symbol::{ModuleId, Symbol};
-use roc_region::all::Loc;
+use roc_region::all::{Loc, Region};
 use std::fmt::Debug;
 
 impl<'a> HeaderType<'a> {
@@ -59,6 +64,18 @@ pub enum HeaderType<'a> {
         exposes: &'a [Loc<ModuleName<'a>>],
         exposes_ids: &'a [ModuleId],
 
+        /// how the host provides memory to Roc
+        allocator: PlatformAllocator,
+
+        /// how Roc gives the host its effects to run
+        effects: PlatformEffects,
+
+        /// where to download the host prebuilt for each target
+        hosts: &'a [PlatformHost<'a>],
+
+        /// the shell command which builds the host, if not the default for its language
+        build_command: Option<&'a str>,
+
         /// usually `pf`
         config_shorthand: &'a str,
     },
@@ -175,6 +192,10 @@ keywords! {
     RequiresKeyword => "requires",
     ProvidesKeyword => "provides",
     ToKeyword => "to",
+    AllocatorKeyword => "allocator",
+    EffectsKeyword => "effects",
+    HostsKeyword => "hosts",
+    BuildKeyword => "build",
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -261,6 +282,14 @@ pub struct PlatformHeader<'a> {
     pub imports: KeywordItem<'a, ImportsKeyword, Collection<'a, Loc<Spaced<'a, ImportsEntry<'a>>>>>,
     pub provides:
         KeywordItem<'a, ProvidesKeyword, Collection<'a, Loc<Spaced<'a, ExposedName<'a>>>>>,
+    pub allocator: Option<
+        KeywordItem<'a, AllocatorKeyword, Collection<'a, Loc<Spaced<'a, AllocatorEntry<'a>>>>>,
+    >,
+    pub effects:
+        Option<KeywordItem<'a, EffectsKeyword, Collection<'a, Loc<Spaced<'a, EffectsEntry<'a>>>>>>,
+    pub hosts:
+        Option<KeywordItem<'a, HostsKeyword, Collection<'a, Loc<Spaced<'a, HostsEntry<'a>>>>>>,
+    pub build: Option<KeywordItem<'a, BuildKeyword, Loc<BuildCommand<'a>>>>,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -331,6 +360,325 @@ pub fn package_entry<'a>() -> impl Parser<'a, Spaced<'a, PackageEntry<'a>>, EPac
     )
 }
 
+/// e.g. `strategy: arena` or `alignment: 16` in `allocator { strategy: arena, alignment: 16 }`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AllocatorEntry<'a> {
+    Strategy {
+        spaces_after_colon: &'a [CommentOrNewline<'a>],
+        strategy: Loc<AllocatorStrategy>,
+    },
+    Alignment {
+        spaces_after_colon: &'a [CommentOrNewline<'a>],
+        alignment: Loc<u32>,
+    },
+}
+
+/// How the host's `roc_alloc`, `roc_realloc`, and `roc_dealloc` manage memory
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AllocatorStrategy {
+    /// Each allocation is freed on its own, like with `malloc` and `free`
+    #[default]
+    Malloc,
+    /// Memory is freed all at once by the host, so `roc_dealloc` does nothing and Roc provides it
+    Arena,
+    /// Roc provides all three functions, using the default allocator from its builtins
+    Roc,
+    /// Roc provides all three functions, using mimalloc. The app must be linked with libmimalloc.
+    Mimalloc,
+}
+
+impl AllocatorStrategy {
+    pub const ALL: [AllocatorStrategy; 4] = [
+        AllocatorStrategy::Malloc,
+        AllocatorStrategy::Arena,
+        AllocatorStrategy::Roc,
+        AllocatorStrategy::Mimalloc,
+    ];
+
+    pub fn from_str(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|strategy| strategy.as_str() == name)
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            AllocatorStrategy::Malloc => "malloc",
+            AllocatorStrategy::Arena => "arena",
+            AllocatorStrategy::Roc => "roc",
+            AllocatorStrategy::Mimalloc => "mimalloc",
+        }
+    }
+}
+
+/// The allocator functions every host and Roc app agree on
+pub const ALLOCATOR_FNS: [&str; 3] = ["roc_alloc", "roc_realloc", "roc_dealloc"];
+
+/// What a platform promises about its allocator, from the `allocator` section of its header.
+/// Platforms without one have a malloc-like allocator with no particular alignment guarantees.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PlatformAllocator {
+    pub strategy: AllocatorStrategy,
+    /// Every pointer `roc_alloc` and `roc_realloc` return is aligned to at least this many bytes
+    pub alignment: Option<u32>,
+}
+
+impl PlatformAllocator {
+    pub fn from_entries(entries: &[Loc<Spaced<'_, AllocatorEntry<'_>>>]) -> Self {
+        let mut allocator = PlatformAllocator::default();
+
+        for entry in entries {
+            match entry.value.extract_spaces().item {
+                AllocatorEntry::Strategy { strategy, .. } => allocator.strategy = strategy.value,
+                AllocatorEntry::Alignment { alignment, .. } => {
+                    allocator.alignment = Some(alignment.value)
+                }
+            }
+        }
+
+        allocator
+    }
+
+    /// The allocator functions the host must define
+    pub fn host_fns(&self) -> &'static [&'static str] {
+        match self.strategy {
+            AllocatorStrategy::Malloc => &ALLOCATOR_FNS,
+            AllocatorStrategy::Arena => &ALLOCATOR_FNS[..2],
+            AllocatorStrategy::Roc | AllocatorStrategy::Mimalloc => &[],
+        }
+    }
+
+    /// The allocator functions Roc defines for the host, which the host must not define itself
+    pub fn roc_fns(&self) -> &'static [&'static str] {
+        match self.strategy {
+            AllocatorStrategy::Malloc => &[],
+            AllocatorStrategy::Arena => &ALLOCATOR_FNS[2..],
+            AllocatorStrategy::Roc | AllocatorStrategy::Mimalloc => &ALLOCATOR_FNS,
+        }
+    }
+}
+
+pub fn allocator_entry<'a>() -> impl Parser<'a, Spaced<'a, AllocatorEntry<'a>>, EAllocator> {
+    move |arena, state: State<'a>, min_indent| {
+        let field_pos = state.pos();
+        let (_, field, state) = skip_second!(
+            specialize(|_, pos| EAllocator::Field(pos), lowercase_ident()),
+            word1(b':', EAllocator::Colon)
+        )
+        .parse(arena, state, min_indent)?;
+
+        let (_, spaces_after_colon, state) = space0_e(EAllocator::IndentValue)
+            .parse(arena, state, min_indent)
+            .map_err(|(_, fail)| (MadeProgress, fail))?;
+
+        let value_pos = state.pos();
+        let value_len = state
+            .bytes()
+            .iter()
+            .take_while(|b| b.is_ascii_alphanumeric())
+            .count();
+        let value = std::str::from_utf8(&state.bytes()[..value_len]).unwrap();
+        let state = state.advance(value_len);
+        let region = Region::new(value_pos, state.pos());
+
+        let entry = match field {
+            "strategy" => match AllocatorStrategy::from_str(value) {
+                Some(strategy) => AllocatorEntry::Strategy {
+                    spaces_after_colon,
+                    strategy: Loc::at(region, strategy),
+                },
+                None => return Err((MadeProgress, EAllocator::Strategy(value_pos))),
+            },
+            "alignment" => match value.parse::<u32>() {
+                Ok(alignment) if alignment.is_power_of_two() => AllocatorEntry::Alignment {
+                    spaces_after_colon,
+                    alignment: Loc::at(region, alignment),
+                },
+                _ => return Err((MadeProgress, EAllocator::Alignment(value_pos))),
+            },
+            _ => return Err((MadeProgress, EAllocator::Field(field_pos))),
+        };
+
+        Ok((MadeProgress, Spaced::Item(entry), state))
+    }
+}
+
+/// e.g. `submit: batch` in `effects { submit: batch }`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EffectsEntry<'a> {
+    Submit {
+        spaces_after_colon: &'a [CommentOrNewline<'a>],
+        submission: Loc<EffectSubmission>,
+    },
+}
+
+/// How the host receives the effects a Roc program performs
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum EffectSubmission {
+    /// Each effect is a call to its own host function, like `roc_fx_putLine`
+    #[default]
+    Each,
+    /// Effects performed one after the other are handed to `roc_fx_batch` together, so the host
+    /// can submit them all at once (to io_uring, for example)
+    Batch,
+}
+
+impl EffectSubmission {
+    pub const ALL: [EffectSubmission; 2] = [EffectSubmission::Each, EffectSubmission::Batch];
+
+    pub fn from_str(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|submission| submission.as_str() == name)
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            EffectSubmission::Each => "each",
+            EffectSubmission::Batch => "batch",
+        }
+    }
+}
+
+/// What a platform promises about how its host runs effects, from the `effects` section of its
+/// header. Platforms without one define a host function for each effect.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PlatformEffects {
+    pub submission: EffectSubmission,
+}
+
+impl PlatformEffects {
+    pub fn from_entries(entries: &[Loc<Spaced<'_, EffectsEntry<'_>>>]) -> Self {
+        let mut effects = PlatformEffects::default();
+
+        for entry in entries {
+            match entry.value.extract_spaces().item {
+                EffectsEntry::Submit { submission, .. } => effects.submission = submission.value,
+            }
+        }
+
+        effects
+    }
+
+    pub fn is_batched(&self) -> bool {
+        self.submission == EffectSubmission::Batch
+    }
+}
+
+pub fn effects_entry<'a>() -> impl Parser<'a, Spaced<'a, EffectsEntry<'a>>, EEffects> {
+    move |arena, state: State<'a>, min_indent| {
+        let field_pos = state.pos();
+        let (_, field, state) = skip_second!(
+            specialize(|_, pos| EEffects::Field(pos), lowercase_ident()),
+            word1(b':', EEffects::Colon)
+        )
+        .parse(arena, state, min_indent)?;
+
+        let (_, spaces_after_colon, state) = space0_e(EEffects::IndentValue)
+            .parse(arena, state, min_indent)
+            .map_err(|(_, fail)| (MadeProgress, fail))?;
+
+        let value_pos = state.pos();
+        let value_len = state
+            .bytes()
+            .iter()
+            .take_while(|b| b.is_ascii_alphanumeric())
+            .count();
+        let value = std::str::from_utf8(&state.bytes()[..value_len]).unwrap();
+        let state = state.advance(value_len);
+        let region = Region::new(value_pos, state.pos());
+
+        let entry = match field {
+            "submit" => match EffectSubmission::from_str(value) {
+                Some(submission) => EffectsEntry::Submit {
+                    spaces_after_colon,
+                    submission: Loc::at(region, submission),
+                },
+                None => return Err((MadeProgress, EEffects::Submission(value_pos))),
+            },
+            _ => return Err((MadeProgress, EEffects::Field(field_pos))),
+        };
+
+        Ok((MadeProgress, Spaced::Item(entry), state))
+    }
+}
+
+/// e.g. `"linux-x86_64": "https://example.com/8yJ9Yq7mgf1A1J6CN1ViOwKt9oFQpP3W1Z3YWJ2EtzXQ.tar.br"`
+/// in a `hosts` section
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct HostsEntry<'a> {
+    pub target: Loc<&'a str>,
+    pub spaces_after_colon: &'a [CommentOrNewline<'a>],
+    pub url: Loc<PackageName<'a>>,
+}
+
+/// A host which was built and preprocessed ahead of time for one target, like `linux-x86_64`,
+/// from the `hosts` section of its platform's header. The URL is to a tarball of the files the
+/// platform would otherwise have beside its main.roc after building its host for that target.
+/// Like package URLs, it ends with the hash of the tarball's contents, which verifies them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PlatformHost<'a> {
+    pub target: &'a str,
+    pub url: &'a str,
+}
+
+impl<'a> PlatformHost<'a> {
+    pub fn from_entry(entry: &Loc<Spaced<'a, HostsEntry<'a>>>) -> Self {
+        let entry = entry.value.extract_spaces().item;
+
+        PlatformHost {
+            target: entry.target.value,
+            url: entry.url.value.to_str(),
+        }
+    }
+}
+
+pub fn hosts_entry<'a>() -> impl Parser<'a, Spaced<'a, HostsEntry<'a>>, EHosts> {
+    map!(
+        and!(
+            skip_second!(
+                loc!(specialize(|_, pos| EHosts::Target(pos), package_name())),
+                word1(b':', EHosts::Colon)
+            ),
+            and!(
+                space0_e(EHosts::IndentUrl),
+                loc!(specialize(|_, pos| EHosts::Url(pos), package_name()))
+            )
+        ),
+        |(target, (spaces_after_colon, url))| Spaced::Item(HostsEntry {
+            target: Loc::at(target.region, target.value.to_str()),
+            spaces_after_colon,
+            url,
+        })
+    )
+}
+
+/// e.g. `"zig build"` in `build "zig build"`: the shell command which builds the platform's host,
+/// run in the platform's directory. Without one, the host is built the default way for its
+/// language, from its host.zig, host.c, host.rs, or Cargo.toml.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BuildCommand<'a>(&'a str);
+
+impl<'a> BuildCommand<'a> {
+    pub fn to_str(self) -> &'a str {
+        self.0
+    }
+}
+
+pub fn build_command<'a>() -> impl Parser<'a, BuildCommand<'a>, EBuild> {
+    then(
+        loc!(specialize(
+            |_, pos| EBuild::Command(pos),
+            string_literal::parse_str_literal()
+        )),
+        move |_arena, state, progress, text| match text.value {
+            StrLiteral::PlainLine(text) => Ok((progress, BuildCommand(text), state)),
+            StrLiteral::Line(_) => Err((progress, EBuild::Escapes(text.region.start()))),
+            StrLiteral::Block(_) => Err((progress, EBuild::Command(text.region.start()))),
+        },
+    )
+}
+
 pub fn package_name<'a>() -> impl Parser<'a, PackageName<'a>, EPackageName<'a>> {
     then(
         loc!(specialize(
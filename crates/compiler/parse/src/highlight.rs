@@ -0,0 +1,320 @@
+//! A tokenizer for syntax highlighting. Unlike the parser, it never fails: it only looks at one
+//! token at a time, so code which is still being typed (or doesn't parse at all) gets highlighted
+//! as well as it can be, and anything it doesn't recognize becomes an [HighlightToken::Error].
+use crate::keyword::KEYWORDS;
+use roc_region::all::Loc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HighlightToken {
+    /// One of the words in [KEYWORDS]
+    Keyword,
+    /// A word which is only special in module headers, like `exposes`
+    HeaderKeyword,
+    LowercaseIdent,
+    UppercaseIdent,
+    /// `@Foo`, an opaque type's constructor
+    OpaqueRef,
+    Number,
+    String,
+    /// An escape like `\n` inside a string
+    StringEscape,
+    /// The `\(` and `)` around an interpolation; what's in between is highlighted like other code
+    StringInterpolation,
+    SingleQuote,
+    Comment,
+    DocComment,
+    Operator,
+    /// `(`, `)`, `[`, `]`, `{`, `}`, `,`, `:`, `.`, and the like
+    Punctuation,
+    /// `\` at the start of a closure
+    LambdaStart,
+    Underscore,
+    Error,
+}
+
+const HEADER_KEYWORDS: [&str; 17] = [
+    "app",
+    "interface",
+    "hosted",
+    "platform",
+    "package",
+    "packages",
+    "imports",
+    "exposes",
+    "provides",
+    "requires",
+    "generates",
+    "with",
+    "to",
+    "allocator",
+    "effects",
+    "hosts",
+    "build",
+];
+
+/// Splits the source into highlighting tokens, skipping whitespace. The tokens cover every other
+/// byte of the input, in order.
+pub fn highlight(text: &str) -> Vec<Loc<HighlightToken>> {
+    let mut tokenizer = Tokenizer {
+        bytes: text.as_bytes(),
+        offset: 0,
+        tokens: Vec::new(),
+        // for each interpolation we're inside of, whether its string is triple-quoted, and how
+        // many parens have been opened inside it
+        interpolations: Vec::new(),
+        after_newline: true,
+    };
+
+    tokenizer.run();
+
+    tokenizer.tokens
+}
+
+struct Tokenizer<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    tokens: Vec<Loc<HighlightToken>>,
+    interpolations: Vec<(bool, usize)>,
+    after_newline: bool,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn peek(&self, ahead: usize) -> Option<u8> {
+        self.bytes.get(self.offset + ahead).copied()
+    }
+
+    fn push(&mut self, token: HighlightToken, length: usize) {
+        let start = self.offset;
+        let end = (start + length).min(self.bytes.len());
+
+        self.tokens.push(Loc::new(start as u32, end as u32, token));
+        self.offset = end;
+    }
+
+    fn take_while(&self, start: usize, pred: impl Fn(u8) -> bool) -> usize {
+        self.bytes[self.offset + start..]
+            .iter()
+            .take_while(|b| pred(**b))
+            .count()
+            + start
+    }
+
+    fn run(&mut self) {
+        use HighlightToken::*;
+
+        while let Some(byte) = self.peek(0) {
+            let after_newline = std::mem::replace(&mut self.after_newline, false);
+
+            match byte {
+                b'\n' => {
+                    self.offset += 1;
+                    self.after_newline = true;
+                }
+                b' ' | b'\t' | b'\r' => {
+                    self.offset += 1;
+                    self.after_newline = after_newline;
+                }
+                b'#' => {
+                    let length = self.take_while(0, |b| b != b'\n');
+                    let kind = if self.peek(1) == Some(b'#') {
+                        DocComment
+                    } else {
+                        Comment
+                    };
+
+                    self.push(kind, length);
+                }
+                b'"' => self.string(),
+                b'\'' => {
+                    let length = self.quoted(1, b'\'');
+                    self.push(SingleQuote, length);
+                }
+                b'0'..=b'9' => {
+                    let length = self.take_while(0, |b| b.is_ascii_alphanumeric() || b == b'_');
+                    let length = match (self.peek(length), self.peek(length + 1)) {
+                        (Some(b'.'), Some(b'0'..=b'9')) => {
+                            self.take_while(length + 1, |b| b.is_ascii_alphanumeric() || b == b'_')
+                        }
+                        _ => length,
+                    };
+
+                    self.push(Number, length);
+                }
+                b'a'..=b'z' => {
+                    let length = self.take_while(0, is_ident_continue);
+                    let word = &self.bytes[self.offset..self.offset + length];
+
+                    // `expect-fx` is the only keyword with a dash in it
+                    let length = if word == b"expect"
+                        && self.bytes[self.offset..].starts_with(b"expect-fx")
+                    {
+                        "expect-fx".len()
+                    } else {
+                        length
+                    };
+                    let word = std::str::from_utf8(&self.bytes[self.offset..self.offset + length])
+                        .unwrap_or_default();
+
+                    let kind = if KEYWORDS.contains(&word) {
+                        Keyword
+                    } else if is_header_keyword(after_newline, word) {
+                        HeaderKeyword
+                    } else {
+                        LowercaseIdent
+                    };
+
+                    self.push(kind, length);
+                }
+                b'A'..=b'Z' => {
+                    let length = self.take_while(0, is_ident_continue);
+                    self.push(UppercaseIdent, length);
+                }
+                b'@' => {
+                    let length = self.take_while(1, is_ident_continue);
+                    let kind = if length > 1 { OpaqueRef } else { Error };
+                    self.push(kind, length);
+                }
+                b'_' => {
+                    let length = self.take_while(1, is_ident_continue);
+                    self.push(Underscore, length);
+                }
+                b'\\' => self.push(LambdaStart, 1),
+                b'(' => {
+                    if let Some((_, depth)) = self.interpolations.last_mut() {
+                        *depth += 1;
+                    }
+
+                    self.push(Punctuation, 1);
+                }
+                b')' => match self.interpolations.last_mut() {
+                    Some((_, 0)) => {
+                        let (triple, _) = self.interpolations.pop().unwrap();
+
+                        self.push(StringInterpolation, 1);
+                        self.string_body(triple);
+                    }
+                    Some((_, depth)) => {
+                        *depth -= 1;
+                        self.push(Punctuation, 1);
+                    }
+                    None => self.push(Punctuation, 1),
+                },
+                b'[' | b']' | b'{' | b'}' | b',' | b':' | b'.' | b'?' => {
+                    // `:=` is an operator, `..` is punctuation
+                    match (byte, self.peek(1)) {
+                        (b':', Some(b'=')) => self.push(Operator, 2),
+                        (b'.', Some(b'.')) => self.push(Punctuation, 2),
+                        _ => self.push(Punctuation, 1),
+                    }
+                }
+                _ if is_operator(byte) => {
+                    let length = self.take_while(0, is_operator);
+                    self.push(Operator, length);
+                }
+                _ => {
+                    // skip the rest of a multi-byte character, so regions stay on char boundaries
+                    let length = 1 + self.take_while(1, |b| (b & 0b1100_0000) == 0b1000_0000);
+                    self.push(Error, length);
+                }
+            }
+        }
+    }
+
+    /// The length of a quoted literal starting at `start`, including the closing quote if there is
+    /// one before the end of the line
+    fn quoted(&self, start: usize, quote: u8) -> usize {
+        let mut length = start;
+
+        while let Some(byte) = self.peek(length) {
+            match byte {
+                b'\n' => return length,
+                b'\\' => length += 2,
+                _ if byte == quote => return length + 1,
+                _ => length += 1,
+            }
+        }
+
+        length.min(self.bytes.len() - self.offset)
+    }
+
+    fn string(&mut self) {
+        let triple = self.bytes[self.offset..].starts_with(b"\"\"\"");
+
+        self.push(HighlightToken::String, if triple { 3 } else { 1 });
+        self.string_body(triple);
+    }
+
+    /// Tokenizes the rest of a string, up to and including its closing quote, or until an
+    /// interpolation starts.
+    fn string_body(&mut self, triple: bool) {
+        use HighlightToken::*;
+
+        let mut length = 0;
+
+        while let Some(byte) = self.peek(length) {
+            match byte {
+                b'"' if !triple => {
+                    self.push(String, length + 1);
+                    return;
+                }
+                b'"' if self.bytes[self.offset + length..].starts_with(b"\"\"\"") => {
+                    self.push(String, length + 3);
+                    return;
+                }
+                // an unterminated single-line string ends at the end of the line
+                b'\n' if !triple => break,
+                b'\\' => {
+                    if length > 0 {
+                        self.push(String, length);
+                        length = 0;
+                    }
+
+                    match self.peek(1) {
+                        Some(b'(') => {
+                            self.push(StringInterpolation, 2);
+                            self.interpolations.push((triple, 0));
+                            return;
+                        }
+                        Some(b'u') => {
+                            // `\u(1F600)`
+                            let escape =
+                                self.take_while(2, |b| b != b')' && b != b'"' && b != b'\n');
+                            let escape = if self.peek(escape) == Some(b')') {
+                                escape + 1
+                            } else {
+                                escape
+                            };
+                            self.push(StringEscape, escape);
+                        }
+                        Some(b'\\' | b'"' | b'\'' | b'n' | b'r' | b't' | b'$') => {
+                            self.push(StringEscape, 2)
+                        }
+                        _ => self.push(Error, 1),
+                    }
+                }
+                _ => length += 1,
+            }
+        }
+
+        if length > 0 {
+            self.push(String, length);
+        }
+    }
+}
+
+fn is_operator(byte: u8) -> bool {
+    matches!(
+        byte,
+        b'+' | b'-' | b'*' | b'/' | b'%' | b'^' | b'<' | b'>' | b'=' | b'!' | b'&' | b'|'
+    )
+}
+
+fn is_ident_continue(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_'
+}
+
+/// Header keywords are ordinary identifiers anywhere else, so they're only highlighted when
+/// they start a line
+fn is_header_keyword(after_newline: bool, word: &str) -> bool {
+    after_newline && HEADER_KEYWORDS.contains(&word)
+}
@@ -7,6 +7,29 @@ pub const IS: &str = "is";
 pub const DBG: &str = "dbg";
 pub const EXPECT: &str = "expect";
 pub const EXPECT_FX: &str = "expect-fx";
+pub const EXPECT_ALWAYS: &str = "expect-always";
 pub const CRASH: &str = "crash";
+pub const RETURN: &str = "return";
 
-pub const KEYWORDS: [&str; 10] = [IF, THEN, ELSE, WHEN, AS, IS, DBG, EXPECT, EXPECT_FX, CRASH];
+/// Only special at the start of a def like `pattern Ok2 x = Ok (Ok x)`, so it isn't one of the
+/// [KEYWORDS], and can still be used as a name.
+pub const PATTERN: &str = "pattern";
+
+/// Only special at the start of a top-level def like `bench List.sortAsc bigList`, so it isn't
+/// one of the [KEYWORDS], and can still be used as a name.
+pub const BENCH: &str = "bench";
+
+pub const KEYWORDS: [&str; 12] = [
+    IF,
+    THEN,
+    ELSE,
+    WHEN,
+    AS,
+    IS,
+    DBG,
+    EXPECT,
+    EXPECT_FX,
+    EXPECT_ALWAYS,
+    CRASH,
+    RETURN,
+];
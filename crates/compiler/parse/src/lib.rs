@@ -9,7 +9,9 @@ pub mod parser;
 pub mod ast;
 pub mod blankspace;
 pub mod expr;
+pub mod grammar;
 pub mod header;
+pub mod highlight;
 pub mod ident;
 pub mod keyword;
 pub mod module;
@@ -19,4 +21,5 @@ pub mod problems;
 pub mod state;
 pub mod string_literal;
 pub mod test_helpers;
+pub mod traverse;
 pub mod type_annotation;
@@ -1,17 +1,19 @@
 use crate::ast::{Collection, Defs, Header, Module, Spaced, Spaces};
 use crate::blankspace::{space0_around_ee, space0_before_e, space0_e};
 use crate::header::{
-    package_entry, package_name, AppHeader, ExposedName, ExposesKeyword, GeneratesKeyword,
-    HostedHeader, ImportsEntry, ImportsKeyword, InterfaceHeader, Keyword, KeywordItem, ModuleName,
+    allocator_entry, build_command, effects_entry, hosts_entry, package_entry, package_name,
+    AllocatorEntry, AllocatorKeyword, AppHeader, BuildCommand, BuildKeyword, EffectsEntry,
+    EffectsKeyword, ExposedName, ExposesKeyword, GeneratesKeyword, HostedHeader, HostsEntry,
+    HostsKeyword, ImportsEntry, ImportsKeyword, InterfaceHeader, Keyword, KeywordItem, ModuleName,
     PackageEntry, PackageHeader, PackagesKeyword, PlatformHeader, PlatformRequires,
     ProvidesKeyword, ProvidesTo, RequiresKeyword, To, ToKeyword, TypedIdent, WithKeyword,
 };
 use crate::ident::{self, lowercase_ident, unqualified_ident, uppercase, UppercaseIdent};
 use crate::parser::Progress::{self, *};
 use crate::parser::{
-    backtrackable, increment_min_indent, optional, reset_min_indent, specialize, word1, EExposes,
-    EGenerates, EGeneratesWith, EHeader, EImports, EPackages, EProvides, ERequires, ETypedIdent,
-    Parser, SourceError, SpaceProblem, SyntaxError,
+    backtrackable, increment_min_indent, optional, reset_min_indent, specialize, word1, EAllocator,
+    EBuild, EEffects, EExposes, EGenerates, EGeneratesWith, EHeader, EHosts, EImports, EPackages,
+    EProvides, ERequires, ETypedIdent, Parser, SourceError, SpaceProblem, SyntaxError,
 };
 use crate::state::State;
 use crate::string_literal;
@@ -211,6 +213,10 @@ fn platform_header<'a>() -> impl Parser<'a, PlatformHeader<'a>, EHeader<'a>> {
         packages: specialize(EHeader::Packages, packages()),
         imports: specialize(EHeader::Imports, imports()),
         provides: specialize(EHeader::Provides, provides_exposed()),
+        allocator: optional(specialize(EHeader::Allocator, allocator())),
+        effects: optional(specialize(EHeader::Effects, effects())),
+        hosts: optional(specialize(EHeader::Hosts, hosts())),
+        build: optional(specialize(EHeader::Build, build())),
     })
     .trace("platform_header")
 }
@@ -490,6 +496,88 @@ fn packages<'a>() -> impl Parser<
     })
 }
 
+#[inline(always)]
+fn allocator<'a>() -> impl Parser<
+    'a,
+    KeywordItem<'a, AllocatorKeyword, Collection<'a, Loc<Spaced<'a, AllocatorEntry<'a>>>>>,
+    EAllocator,
+> {
+    record!(KeywordItem {
+        keyword: spaces_around_keyword(
+            AllocatorKeyword,
+            EAllocator::Allocator,
+            EAllocator::IndentAllocator,
+            EAllocator::IndentListStart
+        ),
+        item: collection_trailing_sep_e!(
+            word1(b'{', EAllocator::ListStart),
+            loc!(allocator_entry()),
+            word1(b',', EAllocator::ListEnd),
+            word1(b'}', EAllocator::ListEnd),
+            Spaced::SpaceBefore
+        )
+    })
+}
+
+#[inline(always)]
+fn effects<'a>() -> impl Parser<
+    'a,
+    KeywordItem<'a, EffectsKeyword, Collection<'a, Loc<Spaced<'a, EffectsEntry<'a>>>>>,
+    EEffects,
+> {
+    record!(KeywordItem {
+        keyword: spaces_around_keyword(
+            EffectsKeyword,
+            EEffects::Effects,
+            EEffects::IndentEffects,
+            EEffects::IndentListStart
+        ),
+        item: collection_trailing_sep_e!(
+            word1(b'{', EEffects::ListStart),
+            loc!(effects_entry()),
+            word1(b',', EEffects::ListEnd),
+            word1(b'}', EEffects::ListEnd),
+            Spaced::SpaceBefore
+        )
+    })
+}
+
+#[inline(always)]
+fn hosts<'a>() -> impl Parser<
+    'a,
+    KeywordItem<'a, HostsKeyword, Collection<'a, Loc<Spaced<'a, HostsEntry<'a>>>>>,
+    EHosts,
+> {
+    record!(KeywordItem {
+        keyword: spaces_around_keyword(
+            HostsKeyword,
+            EHosts::Hosts,
+            EHosts::IndentHosts,
+            EHosts::IndentListStart
+        ),
+        item: collection_trailing_sep_e!(
+            word1(b'{', EHosts::ListStart),
+            loc!(hosts_entry()),
+            word1(b',', EHosts::ListEnd),
+            word1(b'}', EHosts::ListEnd),
+            Spaced::SpaceBefore
+        )
+    })
+}
+
+#[inline(always)]
+fn build<'a>() -> impl Parser<'a, KeywordItem<'a, BuildKeyword, Loc<BuildCommand<'a>>>, EBuild> {
+    record!(KeywordItem {
+        keyword: spaces_around_keyword(
+            BuildKeyword,
+            EBuild::Build,
+            EBuild::IndentBuild,
+            EBuild::IndentCommand
+        ),
+        item: loc!(build_command())
+    })
+}
+
 #[inline(always)]
 fn generates<'a>(
 ) -> impl Parser<'a, KeywordItem<'a, GeneratesKeyword, UppercaseIdent<'a>>, EGenerates> {
@@ -81,7 +81,13 @@ macro_rules! impl_space_problem {
 }
 
 impl_space_problem! {
+    EAllocator,
+    EEffects,
+    EHosts,
+    EBuild,
     EExpect<'a>,
+    EReturn<'a>,
+    EBench<'a>,
     EExposes,
     EExpr<'a>,
     EGenerates,
@@ -121,6 +127,10 @@ pub enum EHeader<'a> {
     Packages(EPackages<'a>, Position),
     Generates(EGenerates, Position),
     GeneratesWith(EGeneratesWith, Position),
+    Allocator(EAllocator, Position),
+    Effects(EEffects, Position),
+    Hosts(EHosts, Position),
+    Build(EBuild, Position),
 
     Space(BadInputError, Position),
     Start(Position),
@@ -258,6 +268,66 @@ pub enum EGeneratesWith {
     Space(BadInputError, Position),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EAllocator {
+    Open(Position),
+    Allocator(Position),
+    IndentAllocator(Position),
+    IndentListStart(Position),
+    IndentListEnd(Position),
+    ListStart(Position),
+    ListEnd(Position),
+    Field(Position),
+    Colon(Position),
+    IndentValue(Position),
+    Strategy(Position),
+    Alignment(Position),
+    Space(BadInputError, Position),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EEffects {
+    Open(Position),
+    Effects(Position),
+    IndentEffects(Position),
+    IndentListStart(Position),
+    IndentListEnd(Position),
+    ListStart(Position),
+    ListEnd(Position),
+    Field(Position),
+    Colon(Position),
+    IndentValue(Position),
+    Submission(Position),
+    Space(BadInputError, Position),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EHosts {
+    Open(Position),
+    Hosts(Position),
+    IndentHosts(Position),
+    IndentListStart(Position),
+    IndentListEnd(Position),
+    ListStart(Position),
+    ListEnd(Position),
+    Target(Position),
+    Colon(Position),
+    IndentUrl(Position),
+    Url(Position),
+    Space(BadInputError, Position),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EBuild {
+    Open(Position),
+    Build(Position),
+    IndentBuild(Position),
+    IndentCommand(Position),
+    Command(Position),
+    Escapes(Position),
+    Space(BadInputError, Position),
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BadInputError {
     HasTab,
@@ -348,6 +418,8 @@ pub enum EExpr<'a> {
 
     Expect(EExpect<'a>, Position),
     Dbg(EExpect<'a>, Position),
+    Return(EReturn<'a>, Position),
+    Bench(EBench<'a>, Position),
 
     Closure(EClosure<'a>, Position),
     Underscore(Position),
@@ -516,6 +588,22 @@ pub enum EExpect<'a> {
     IndentCondition(Position),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EReturn<'a> {
+    Space(BadInputError, Position),
+    Return(Position),
+    Value(&'a EExpr<'a>, Position),
+    IndentValue(Position),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EBench<'a> {
+    Space(BadInputError, Position),
+    Bench(Position),
+    Body(&'a EExpr<'a>, Position),
+    IndentBody(Position),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum EPattern<'a> {
     Record(PRecord<'a>, Position),
@@ -0,0 +1,459 @@
+//! Traversals over the parse ast.
+//!
+//! Implement [Visitor], override the `visit_*` methods for the nodes you care about, and call the
+//! matching `walk_*` function from an override to keep descending. Whitespace, comments, and
+//! parentheses are walked through like any other node.
+//!
+//! Some sub-expressions don't have a region of their own, like the record in `x.foo`; they're
+//! visited with the region of the node that contains them.
+
+use roc_region::all::{Loc, Region};
+
+use crate::ast::{
+    AbilityMember, AssignedField, Defs, Expr, HasAbilities, HasAbility, HasClause, HasImpls,
+    Pattern, StrLiteral, StrSegment, Tag, TypeAnnotation, TypeDef, ValueDef, WhenBranch,
+};
+
+pub fn walk_defs<'a, V: Visitor<'a>>(visitor: &mut V, defs: &Defs<'a>) {
+    for (def, region) in defs.defs().zip(defs.regions.iter()) {
+        match def {
+            Ok(type_def) => visitor.visit_type_def(type_def, *region),
+            Err(value_def) => visitor.visit_value_def(value_def, *region),
+        }
+    }
+}
+
+pub fn walk_value_def<'a, V: Visitor<'a>>(visitor: &mut V, def: &ValueDef<'a>) {
+    match def {
+        ValueDef::Annotation(loc_pattern, loc_ann) => {
+            visitor.visit_pattern(&loc_pattern.value, loc_pattern.region);
+            visitor.visit_type_annotation(&loc_ann.value, loc_ann.region);
+        }
+        ValueDef::Body(loc_pattern, loc_expr) => {
+            visitor.visit_pattern(&loc_pattern.value, loc_pattern.region);
+            visitor.visit_expr(&loc_expr.value, loc_expr.region);
+        }
+        ValueDef::AnnotatedBody {
+            ann_pattern,
+            ann_type,
+            comment: _,
+            body_pattern,
+            body_expr,
+        } => {
+            visitor.visit_pattern(&ann_pattern.value, ann_pattern.region);
+            visitor.visit_type_annotation(&ann_type.value, ann_type.region);
+            visitor.visit_pattern(&body_pattern.value, body_pattern.region);
+            visitor.visit_expr(&body_expr.value, body_expr.region);
+        }
+        ValueDef::Dbg {
+            condition,
+            preceding_comment: _,
+        }
+        | ValueDef::Expect {
+            condition,
+            preceding_comment: _,
+            always: _,
+        }
+        | ValueDef::ExpectFx {
+            condition,
+            preceding_comment: _,
+        } => visitor.visit_expr(&condition.value, condition.region),
+        ValueDef::Bench {
+            body,
+            preceding_comment: _,
+        } => visitor.visit_expr(&body.value, body.region),
+    }
+}
+
+pub fn walk_type_def<'a, V: Visitor<'a>>(visitor: &mut V, def: &TypeDef<'a>) {
+    match def {
+        TypeDef::Alias { header, ann } => {
+            walk_type_vars(visitor, header.vars);
+            visitor.visit_type_annotation(&ann.value, ann.region);
+        }
+        TypeDef::Opaque {
+            header,
+            typ,
+            derived,
+        } => {
+            walk_type_vars(visitor, header.vars);
+            visitor.visit_type_annotation(&typ.value, typ.region);
+            if let Some(derived) = derived {
+                walk_has_abilities(visitor, &derived.value);
+            }
+        }
+        TypeDef::Ability {
+            header,
+            loc_has: _,
+            members,
+        } => {
+            walk_type_vars(visitor, header.vars);
+            for AbilityMember { name: _, typ } in members.iter() {
+                visitor.visit_type_annotation(&typ.value, typ.region);
+            }
+        }
+        TypeDef::PatternSynonym { header, pattern } => {
+            walk_type_vars(visitor, header.vars);
+            visitor.visit_pattern(&pattern.value, pattern.region);
+        }
+    }
+}
+
+fn walk_type_vars<'a, V: Visitor<'a>>(visitor: &mut V, vars: &[Loc<Pattern<'a>>]) {
+    for var in vars {
+        visitor.visit_pattern(&var.value, var.region);
+    }
+}
+
+fn walk_has_abilities<'a, V: Visitor<'a>>(visitor: &mut V, has_abilities: &HasAbilities<'a>) {
+    match has_abilities {
+        HasAbilities::Has(abilities) => {
+            for loc_ability in abilities.iter() {
+                walk_has_ability(visitor, &loc_ability.value);
+            }
+        }
+        HasAbilities::SpaceBefore(inner, _) | HasAbilities::SpaceAfter(inner, _) => {
+            walk_has_abilities(visitor, inner)
+        }
+    }
+}
+
+fn walk_has_ability<'a, V: Visitor<'a>>(visitor: &mut V, has_ability: &HasAbility<'a>) {
+    match has_ability {
+        HasAbility::HasAbility { ability, impls } => {
+            visitor.visit_type_annotation(&ability.value, ability.region);
+            if let Some(impls) = impls {
+                walk_has_impls(visitor, &impls.value);
+            }
+        }
+        HasAbility::SpaceBefore(inner, _) | HasAbility::SpaceAfter(inner, _) => {
+            walk_has_ability(visitor, inner)
+        }
+    }
+}
+
+fn walk_has_impls<'a, V: Visitor<'a>>(visitor: &mut V, has_impls: &HasImpls<'a>) {
+    match has_impls {
+        HasImpls::HasImpls(fields) => walk_expr_fields(visitor, fields.iter()),
+        HasImpls::SpaceBefore(inner, _) | HasImpls::SpaceAfter(inner, _) => {
+            walk_has_impls(visitor, inner)
+        }
+    }
+}
+
+pub fn walk_expr<'a, V: Visitor<'a>>(visitor: &mut V, expr: &Expr<'a>, region: Region) {
+    use Expr::*;
+
+    match expr {
+        Float(..) => { /* terminal */ }
+        Num(..) => { /* terminal */ }
+        NonBase10Int { .. } => { /* terminal */ }
+        Str(literal) => walk_str_literal(visitor, literal),
+        SingleQuote(..) => { /* terminal */ }
+        RecordAccess(inner, _field) => visitor.visit_expr(inner, region),
+        AccessorFunction(..) => { /* terminal */ }
+        Try(inner) => visitor.visit_expr(inner, region),
+        TupleAccess(inner, _index) => visitor.visit_expr(inner, region),
+        List(elems) | Tuple(elems) => {
+            for elem in elems.iter() {
+                visitor.visit_expr(&elem.value, elem.region);
+            }
+        }
+        RecordUpdate { update, fields } => {
+            visitor.visit_expr(&update.value, update.region);
+            walk_expr_fields(visitor, fields.iter());
+        }
+        Record(fields) => walk_expr_fields(visitor, fields.iter()),
+        Var { .. } => { /* terminal */ }
+        Underscore(..) => { /* terminal */ }
+        Crash => { /* terminal */ }
+        DbgKeyword => { /* terminal */ }
+        Tag(..) => { /* terminal */ }
+        OpaqueRef(..) => { /* terminal */ }
+        Closure(args, body) => {
+            for arg in args.iter() {
+                visitor.visit_pattern(&arg.value, arg.region);
+            }
+            visitor.visit_expr(&body.value, body.region);
+        }
+        Defs(defs, body) => {
+            visitor.visit_defs(defs);
+            visitor.visit_expr(&body.value, body.region);
+        }
+        Backpassing(args, call, body) => {
+            for arg in args.iter() {
+                visitor.visit_pattern(&arg.value, arg.region);
+            }
+            visitor.visit_expr(&call.value, call.region);
+            visitor.visit_expr(&body.value, body.region);
+        }
+        Expect(condition, continuation) | Dbg(condition, continuation) => {
+            visitor.visit_expr(&condition.value, condition.region);
+            visitor.visit_expr(&continuation.value, continuation.region);
+        }
+        Return(value) => visitor.visit_expr(&value.value, value.region),
+        Apply(function, args, _called_via) => {
+            visitor.visit_expr(&function.value, function.region);
+            for arg in args.iter() {
+                visitor.visit_expr(&arg.value, arg.region);
+            }
+        }
+        BinOps(lefts, last) => {
+            for (operand, _op) in lefts.iter() {
+                visitor.visit_expr(&operand.value, operand.region);
+            }
+            visitor.visit_expr(&last.value, last.region);
+        }
+        UnaryOp(operand, _op) => visitor.visit_expr(&operand.value, operand.region),
+        If(branches, final_else) => {
+            for (condition, body) in branches.iter() {
+                visitor.visit_expr(&condition.value, condition.region);
+                visitor.visit_expr(&body.value, body.region);
+            }
+            visitor.visit_expr(&final_else.value, final_else.region);
+        }
+        When(condition, branches) => {
+            visitor.visit_expr(&condition.value, condition.region);
+            for branch in branches.iter() {
+                walk_when_branch(visitor, branch);
+            }
+        }
+        SpaceBefore(inner, _) | SpaceAfter(inner, _) | ParensAround(inner) => {
+            visitor.visit_expr(inner, region)
+        }
+        MalformedIdent(..) => { /* terminal */ }
+        MalformedClosure => { /* terminal */ }
+        PrecedenceConflict(conflict) => {
+            visitor.visit_expr(&conflict.expr.value, conflict.expr.region)
+        }
+    }
+}
+
+pub fn walk_when_branch<'a, V: Visitor<'a>>(visitor: &mut V, branch: &WhenBranch<'a>) {
+    let WhenBranch {
+        patterns,
+        value,
+        guard,
+    } = branch;
+
+    for pattern in patterns.iter() {
+        visitor.visit_pattern(&pattern.value, pattern.region);
+    }
+    if let Some(guard) = guard {
+        visitor.visit_expr(&guard.value, guard.region);
+    }
+    visitor.visit_expr(&value.value, value.region);
+}
+
+fn walk_str_literal<'a, V: Visitor<'a>>(visitor: &mut V, literal: &StrLiteral<'a>) {
+    let walk_segments = |visitor: &mut V, segments: &[StrSegment<'a>]| {
+        for segment in segments {
+            if let StrSegment::Interpolated(loc_expr) = segment {
+                visitor.visit_expr(loc_expr.value, loc_expr.region);
+            }
+        }
+    };
+
+    match literal {
+        StrLiteral::PlainLine(_) => { /* terminal */ }
+        StrLiteral::Line(segments) => walk_segments(visitor, segments),
+        StrLiteral::Block(lines) => {
+            for segments in lines.iter() {
+                walk_segments(visitor, segments);
+            }
+        }
+    }
+}
+
+fn walk_expr_fields<'a, 'b, V: Visitor<'a>>(
+    visitor: &mut V,
+    fields: impl Iterator<Item = &'b Loc<AssignedField<'a, Expr<'a>>>>,
+) where
+    'a: 'b,
+{
+    for field in fields {
+        walk_assigned_field(visitor, &field.value, &mut |visitor, value| {
+            visitor.visit_expr(&value.value, value.region)
+        });
+    }
+}
+
+fn walk_assigned_field<'a, V: Visitor<'a>, T>(
+    visitor: &mut V,
+    field: &AssignedField<'a, T>,
+    visit_value: &mut impl FnMut(&mut V, &Loc<T>),
+) {
+    match field {
+        AssignedField::RequiredValue(_label, _, value)
+        | AssignedField::OptionalValue(_label, _, value) => visit_value(visitor, value),
+        AssignedField::LabelOnly(_label) => { /* terminal */ }
+        AssignedField::SpaceBefore(inner, _) | AssignedField::SpaceAfter(inner, _) => {
+            walk_assigned_field(visitor, inner, visit_value)
+        }
+        AssignedField::Malformed(_) => { /* terminal */ }
+    }
+}
+
+pub fn walk_pattern<'a, V: Visitor<'a>>(visitor: &mut V, pattern: &Pattern<'a>, region: Region) {
+    use Pattern::*;
+
+    match pattern {
+        Identifier(..) => { /* terminal */ }
+        Tag(..) => { /* terminal */ }
+        OpaqueRef(..) => { /* terminal */ }
+        Apply(function, args) => {
+            visitor.visit_pattern(&function.value, function.region);
+            for arg in args.iter() {
+                visitor.visit_pattern(&arg.value, arg.region);
+            }
+        }
+        RecordDestructure(fields) => {
+            for field in fields.iter() {
+                visitor.visit_pattern(&field.value, field.region);
+            }
+        }
+        RequiredField(_label, guard) => visitor.visit_pattern(&guard.value, guard.region),
+        OptionalField(_label, default) => visitor.visit_expr(&default.value, default.region),
+        NumLiteral(..) => { /* terminal */ }
+        NonBase10Literal { .. } => { /* terminal */ }
+        FloatLiteral(..) => { /* terminal */ }
+        StrLiteral(..) => { /* terminal */ }
+        Underscore(..) => { /* terminal */ }
+        SingleQuote(..) => { /* terminal */ }
+        Tuple(elems) | List(elems) => {
+            for elem in elems.iter() {
+                visitor.visit_pattern(&elem.value, elem.region);
+            }
+        }
+        ListRest(..) => { /* terminal */ }
+        As(inner, _pattern_as) => visitor.visit_pattern(&inner.value, inner.region),
+        SpaceBefore(inner, _) | SpaceAfter(inner, _) => visitor.visit_pattern(inner, region),
+        Malformed(..) => { /* terminal */ }
+        MalformedIdent(..) => { /* terminal */ }
+        QualifiedIdentifier { .. } => { /* terminal */ }
+    }
+}
+
+pub fn walk_type_annotation<'a, V: Visitor<'a>>(
+    visitor: &mut V,
+    ann: &TypeAnnotation<'a>,
+    region: Region,
+) {
+    use TypeAnnotation::*;
+
+    let mut visit_ann = |visitor: &mut V, ann: &Loc<TypeAnnotation<'a>>| {
+        visitor.visit_type_annotation(&ann.value, ann.region)
+    };
+
+    match ann {
+        Function(args, ret) => {
+            for arg in args.iter() {
+                visit_ann(visitor, arg);
+            }
+            visit_ann(visitor, ret);
+        }
+        Apply(_module_name, _name, args) => {
+            for arg in args.iter() {
+                visit_ann(visitor, arg);
+            }
+        }
+        BoundVariable(..) => { /* terminal */ }
+        As(inner, _, header) => {
+            visit_ann(visitor, inner);
+            walk_type_vars(visitor, header.vars);
+        }
+        Record { fields, ext } => {
+            for field in fields.iter() {
+                walk_assigned_field(visitor, &field.value, &mut visit_ann);
+            }
+            if let Some(ext) = ext {
+                visit_ann(visitor, ext);
+            }
+        }
+        Tuple { elems, ext } => {
+            for elem in elems.iter() {
+                visit_ann(visitor, elem);
+            }
+            if let Some(ext) = ext {
+                visit_ann(visitor, ext);
+            }
+        }
+        TagUnion { ext, tags } => {
+            for tag in tags.iter() {
+                walk_tag(visitor, &tag.value);
+            }
+            if let Some(ext) = ext {
+                visit_ann(visitor, ext);
+            }
+        }
+        Inferred => { /* terminal */ }
+        Wildcard => { /* terminal */ }
+        Where(inner, clauses) => {
+            visit_ann(visitor, inner);
+            for clause in clauses.iter() {
+                let HasClause { var: _, abilities } = &clause.value;
+                for ability in abilities.iter() {
+                    visit_ann(visitor, ability);
+                }
+            }
+        }
+        SpaceBefore(inner, _) | SpaceAfter(inner, _) => {
+            visitor.visit_type_annotation(inner, region)
+        }
+        Malformed(..) => { /* terminal */ }
+    }
+}
+
+fn walk_tag<'a, V: Visitor<'a>>(visitor: &mut V, tag: &Tag<'a>) {
+    match tag {
+        Tag::Apply { name: _, args } => {
+            for arg in args.iter() {
+                visitor.visit_type_annotation(&arg.value, arg.region);
+            }
+        }
+        Tag::SpaceBefore(inner, _) | Tag::SpaceAfter(inner, _) => walk_tag(visitor, inner),
+        Tag::Malformed(..) => { /* terminal */ }
+    }
+}
+
+pub trait Visitor<'a>: Sized {
+    /// Most default implementations will call [Visitor::should_visit] to decide whether they
+    /// should descend into a node. Return `false` to skip visiting.
+    fn should_visit(&mut self, _region: Region) -> bool {
+        true
+    }
+
+    fn visit_defs(&mut self, defs: &Defs<'a>) {
+        walk_defs(self, defs);
+    }
+
+    fn visit_value_def(&mut self, def: &ValueDef<'a>, region: Region) {
+        if self.should_visit(region) {
+            walk_value_def(self, def);
+        }
+    }
+
+    fn visit_type_def(&mut self, def: &TypeDef<'a>, region: Region) {
+        if self.should_visit(region) {
+            walk_type_def(self, def);
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Expr<'a>, region: Region) {
+        if self.should_visit(region) {
+            walk_expr(self, expr, region);
+        }
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern<'a>, region: Region) {
+        if self.should_visit(region) {
+            walk_pattern(self, pattern, region);
+        }
+    }
+
+    fn visit_type_annotation(&mut self, ann: &TypeAnnotation<'a>, region: Region) {
+        if self.should_visit(region) {
+            walk_type_annotation(self, ann, region);
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Checks that the grammar description in `roc_parse::grammar` agrees with the parser.
+
+#[cfg(test)]
+mod test_grammar {
+    use bumpalo::Bump;
+    use roc_parse::grammar::{productions, to_ebnf, undefined_refs, Start};
+    use roc_parse::keyword::KEYWORDS;
+    use roc_parse::test_helpers::{parse_defs_with, parse_expr_with, parse_header_with};
+
+    #[test]
+    fn examples_parse() {
+        for production in productions() {
+            for example in production.examples {
+                let arena = Bump::new();
+
+                let parsed = match production.start {
+                    Start::Expr => parse_expr_with(&arena, example).map(|_| ()),
+                    Start::Defs => parse_defs_with(&arena, example).map(|_| ()),
+                    Start::Header => parse_header_with(&arena, example).map(|_| ()),
+                    Start::Fragment => panic!(
+                        "`{}` is a fragment, so its examples can't be checked",
+                        production.name
+                    ),
+                };
+
+                if let Err(problem) = parsed {
+                    panic!(
+                        "The grammar says `{}` accepts\n\n{}\n\nbut the parser gave {:?}",
+                        production.name, example, problem
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn every_ref_is_defined() {
+        assert_eq!(undefined_refs(&productions()), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn every_keyword_is_used() {
+        let ebnf = to_ebnf(&productions());
+
+        for keyword in KEYWORDS {
+            assert!(
+                ebnf.contains(&format!("'{}'", keyword)),
+                "the grammar doesn't mention the `{}` keyword",
+                keyword
+            );
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Tests for the error-tolerant tokenizer in `roc_parse::highlight`.
+
+#[cfg(test)]
+mod test_highlight {
+    use roc_parse::highlight::{highlight, HighlightToken, HighlightToken::*};
+
+    fn tokens(text: &str) -> Vec<(&str, HighlightToken)> {
+        highlight(text)
+            .into_iter()
+            .map(|loc| {
+                let region = loc.region;
+
+                (
+                    &text[region.start().offset as usize..region.end().offset as usize],
+                    loc.value,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn when_expr() {
+        assert_eq!(
+            tokens("when x is\n    Ok n -> n + 1"),
+            vec![
+                ("when", Keyword),
+                ("x", LowercaseIdent),
+                ("is", Keyword),
+                ("Ok", UppercaseIdent),
+                ("n", LowercaseIdent),
+                ("->", Operator),
+                ("n", LowercaseIdent),
+                ("+", Operator),
+                ("1", Number),
+            ]
+        );
+    }
+
+    #[test]
+    fn header_keywords_only_start_lines() {
+        assert_eq!(
+            tokens("interface Foo\n    exposes [to]"),
+            vec![
+                ("interface", HeaderKeyword),
+                ("Foo", UppercaseIdent),
+                ("exposes", HeaderKeyword),
+                ("[", Punctuation),
+                ("to", LowercaseIdent),
+                ("]", Punctuation),
+            ]
+        );
+    }
+
+    #[test]
+    fn string_escapes_and_interpolation() {
+        assert_eq!(
+            tokens(r#""a\n\(f (x)) b""#),
+            vec![
+                ("\"", String),
+                ("a", String),
+                ("\\n", StringEscape),
+                ("\\(", StringInterpolation),
+                ("f", LowercaseIdent),
+                ("(", Punctuation),
+                ("x", LowercaseIdent),
+                (")", Punctuation),
+                (")", StringInterpolation),
+                (" b\"", String),
+            ]
+        );
+    }
+
+    #[test]
+    fn comments() {
+        assert_eq!(
+            tokens("## docs\nx # note"),
+            vec![
+                ("## docs", DocComment),
+                ("x", LowercaseIdent),
+                ("# note", Comment),
+            ]
+        );
+    }
+
+    #[test]
+    fn partially_typed_code() {
+        assert_eq!(
+            tokens("x = \"unfinished\ny = @ ~"),
+            vec![
+                ("x", LowercaseIdent),
+                ("=", Operator),
+                ("\"", String),
+                ("unfinished", String),
+                ("y", LowercaseIdent),
+                ("=", Operator),
+                ("@", Error),
+                ("~", Error),
+            ]
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! Tests for the parse ast visitor in `roc_parse::traverse`.
+
+#[cfg(test)]
+mod test_traverse {
+    use bumpalo::Bump;
+    use indoc::indoc;
+    use roc_parse::ast::{Expr, Pattern, TypeAnnotation};
+    use roc_parse::test_helpers::parse_defs_with;
+    use roc_parse::traverse::{walk_expr, walk_pattern, walk_type_annotation, Visitor};
+    use roc_region::all::{Position, Region};
+
+    #[derive(Default)]
+    struct Names<'s> {
+        src: &'s str,
+        vars: Vec<&'s str>,
+        identifiers: Vec<&'s str>,
+        type_vars: Vec<&'s str>,
+    }
+
+    impl<'s> Names<'s> {
+        fn text(&self, region: Region) -> &'s str {
+            &self.src[region.start().offset as usize..region.end().offset as usize]
+        }
+    }
+
+    impl<'a, 's> Visitor<'a> for Names<'s> {
+        fn visit_expr(&mut self, expr: &Expr<'a>, region: Region) {
+            if let Expr::Var { .. } = expr {
+                let text = self.text(region);
+                self.vars.push(text);
+            }
+
+            walk_expr(self, expr, region);
+        }
+
+        fn visit_pattern(&mut self, pattern: &Pattern<'a>, region: Region) {
+            if let Pattern::Identifier(_) = pattern {
+                let text = self.text(region);
+                self.identifiers.push(text);
+            }
+
+            walk_pattern(self, pattern, region);
+        }
+
+        fn visit_type_annotation(&mut self, ann: &TypeAnnotation<'a>, region: Region) {
+            if let TypeAnnotation::BoundVariable(_) = ann {
+                let text = self.text(region);
+                self.type_vars.push(text);
+            }
+
+            walk_type_annotation(self, ann, region);
+        }
+    }
+
+    fn names(src: &str) -> Names<'_> {
+        let arena = Bump::new();
+        let defs = parse_defs_with(&arena, src).unwrap();
+
+        let mut names = Names {
+            src,
+            ..Default::default()
+        };
+        names.visit_defs(&defs);
+        names
+    }
+
+    #[test]
+    fn nested_exprs_and_patterns() {
+        let names = names(indoc!(
+            r#"
+            f = \x ->
+                y = x + 1
+                when y is
+                    Ok z if z > 0 -> "\(Num.toStr z)"
+                    _ -> ""
+            "#
+        ));
+
+        assert_eq!(names.identifiers, ["f", "x", "y", "z"]);
+        assert_eq!(names.vars, ["x", "y", "z", "Num.toStr", "z"]);
+    }
+
+    #[test]
+    fn type_annotations() {
+        let names = names(indoc!(
+            r#"
+            Pair a : (a, a)
+
+            foo : List a, (b -> c) -> { x : d }e
+            "#
+        ));
+
+        assert_eq!(names.identifiers, ["a", "foo"]);
+        assert_eq!(names.type_vars, ["a", "a", "a", "b", "c", "d", "e"]);
+    }
+
+    #[test]
+    fn should_visit_prunes_subtrees() {
+        struct Enclosing<'s> {
+            src: &'s str,
+            target: Region,
+            exprs: Vec<&'s str>,
+        }
+
+        impl<'a, 's> Visitor<'a> for Enclosing<'s> {
+            fn should_visit(&mut self, region: Region) -> bool {
+                region.contains(&self.target)
+            }
+
+            fn visit_expr(&mut self, expr: &Expr<'a>, region: Region) {
+                if self.should_visit(region) {
+                    let text =
+                        &self.src[region.start().offset as usize..region.end().offset as usize];
+                    self.exprs.push(text);
+
+                    walk_expr(self, expr, region);
+                }
+            }
+        }
+
+        let src = "x = [1, 2]\ny = 3";
+        let arena = Bump::new();
+        let defs = parse_defs_with(&arena, src).unwrap();
+
+        let mut visitor = Enclosing {
+            src,
+            target: Region::new(Position::new(8), Position::new(9)),
+            exprs: Vec::new(),
+        };
+        visitor.visit_defs(&defs);
+
+        assert_eq!(visitor.exprs, ["[1, 2]", "2"]);
+    }
+}
@@ -29,6 +29,19 @@ pub enum ShadowKind {
     Ability(Symbol),
 }
 
+/// What a name which doesn't follow the naming conventions belongs to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NameKind {
+    /// Should be camelCase
+    Def,
+    /// Should be PascalCase
+    Type,
+    /// Should be PascalCase
+    Tag,
+    /// Should match the module's path
+    Module,
+}
+
 /// Problems that can occur in the course of canonicalization.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Problem {
@@ -114,6 +127,10 @@ pub enum Problem {
         name: Symbol,
         variables_region: Region,
     },
+    MultiParameterAbility {
+        name: Symbol,
+        variables_region: Region,
+    },
     HasClauseIsNotAbility {
         region: Region,
     },
@@ -204,6 +221,81 @@ pub enum Problem {
     OverAppliedCrash {
         region: Region,
     },
+    /// A `return` outside of a function body, or somewhere in it which isn't always evaluated
+    /// before the rest of the body, like in a `when` guard, so there's no way to leave the
+    /// function from it
+    MisplacedReturn {
+        region: Region,
+    },
+    /// A `?` outside of a function body, or somewhere in it which isn't always evaluated before
+    /// the rest of the body, like inside a closure passed as an argument
+    MisplacedTry {
+        region: Region,
+    },
+    /// A `dbg` used as a value, without the value to print, like `List.map list dbg`
+    UnappliedDbg {
+        region: Region,
+    },
+    /// A `dbg` given more than an optional string literal label and the value to print
+    OverAppliedDbg {
+        region: Region,
+    },
+    /// A use of a value which the module exposing it has marked `@deprecated`
+    DeprecatedValue {
+        symbol: Symbol,
+        region: Region,
+        /// What the module's author suggests doing instead
+        message: Box<str>,
+    },
+    /// A name which doesn't follow the naming conventions. These are only reported when asked for.
+    NamingConvention {
+        kind: NameKind,
+        name: Box<str>,
+        region: Region,
+        /// The name it should have, for tools which fix problems automatically
+        suggestion: Box<str>,
+    },
+    /// A def which gives a new value to a name that's already in scope, like `x = x + 1`. Code
+    /// after it sees the new value. Unlike other shadowing, this compiles, but it's treated as an
+    /// error unless the `shadowing` warning is allowed.
+    Rebinding {
+        original_region: Region,
+        shadow: Loc<Ident>,
+    },
+    /// An exposed function which crashes whenever it's called, because every way through its
+    /// body reaches a `crash`
+    UnconditionalCrash {
+        function: Symbol,
+        region: Region,
+        /// The `crash`es it reaches
+        crashes: Vec<Region>,
+    },
+    /// A pattern synonym which can't stand for a pattern, like one that doesn't bind each of its
+    /// arguments exactly once
+    InvalidPatternSynonym {
+        synonym: Symbol,
+        region: Region,
+        problem: PatternSynonymProblem,
+    },
+    /// A pattern synonym matched on with a different number of arguments than it was defined with
+    PatternSynonymArity {
+        synonym: Symbol,
+        region: Region,
+        expected: usize,
+        found: usize,
+    },
+    /// A pattern given to `Str.matches` or `Str.capture` which isn't a string literal, or one of
+    /// them used without being called. Patterns are compiled when the program is built, so they
+    /// have to be known then.
+    RegexPatternNotLiteral {
+        function: Symbol,
+        region: Region,
+    },
+    /// A string literal pattern given to `Str.matches` or `Str.capture` which can't be compiled
+    InvalidRegex {
+        region: Region,
+        problem: RegexProblem,
+    },
 }
 
 impl Problem {
@@ -238,6 +330,7 @@ impl Problem {
             Problem::NestedDatatype { .. } => RuntimeError,
             Problem::InvalidExtensionType { .. } => RuntimeError,
             Problem::AbilityHasTypeVariables { .. } => RuntimeError,
+            Problem::MultiParameterAbility { .. } => RuntimeError,
             Problem::HasClauseIsNotAbility { .. } => RuntimeError,
             Problem::IllegalHasClause { .. } => RuntimeError,
             Problem::DuplicateHasAbility { .. } => Warning,
@@ -268,10 +361,52 @@ impl Problem {
             // injecting a crash message
             Problem::UnappliedCrash { .. } => RuntimeError,
             Problem::OverAppliedCrash { .. } => RuntimeError,
+            Problem::MisplacedReturn { .. } => RuntimeError,
+            Problem::MisplacedTry { .. } => RuntimeError,
+            Problem::UnappliedDbg { .. } => RuntimeError,
+            Problem::OverAppliedDbg { .. } => RuntimeError,
             Problem::DefsOnlyUsedInRecursion(_, _) => Warning,
+            Problem::DeprecatedValue { .. } => Warning,
+            Problem::NamingConvention { .. } => Warning,
+            Problem::Rebinding { .. } => Warning,
+            Problem::UnconditionalCrash { .. } => Warning,
+            Problem::InvalidPatternSynonym { .. } => RuntimeError,
+            Problem::PatternSynonymArity { .. } => RuntimeError,
+            Problem::RegexPatternNotLiteral { .. } => RuntimeError,
+            Problem::InvalidRegex { .. } => RuntimeError,
         }
     }
 
+    /// The name a warning can be allowed, or turned into an error, by. Errors have no name, since
+    /// they can't be allowed.
+    pub fn warning_name(&self) -> Option<&'static str> {
+        let name = match self {
+            Problem::UnusedDef(_, _) | Problem::UnusedBranchDef(_, _) => "unused-def",
+            Problem::UnusedImport(_, _) | Problem::UnusedModuleImport(_, _) => "unused-import",
+            Problem::UnusedArgument(_, _, _, _) => "unused-argument",
+            Problem::DefsOnlyUsedInRecursion(_, _) => "only-used-in-recursion",
+            Problem::PhantomTypeArgument { .. } => "unused-type-parameter",
+            Problem::DuplicateRecordFieldValue { .. } => "duplicate-field",
+            Problem::DuplicateHasAbility { .. } => "duplicate-ability",
+            Problem::DuplicateImpl { .. } => "duplicate-impl",
+            Problem::NotAnAbility(_) | Problem::ImplementsNonRequired { .. } => "unnecessary-impl",
+            Problem::NoIdentifiersIntroduced(_) => "no-identifiers-introduced",
+            Problem::OverloadedSpecialization { .. } => "overloaded-specialization",
+            Problem::UnnecessaryOutputWildcard { .. } => "unnecessary-wildcard",
+            Problem::DeprecatedValue { .. } => "deprecated",
+            Problem::NamingConvention { kind, .. } => match kind {
+                NameKind::Def => "non-camel-case-name",
+                NameKind::Type | NameKind::Tag => "non-pascal-case-name",
+                NameKind::Module => "module-name-mismatch",
+            },
+            Problem::Rebinding { .. } => "shadowing",
+            Problem::UnconditionalCrash { .. } => "unconditional-crash",
+            _ => return None,
+        };
+
+        Some(name)
+    }
+
     /// Returns a Region value from the Problem, if possible.
     /// Some problems have more than one region; in those cases,
     /// this tries to pick the one that's closest to the original
@@ -365,6 +500,10 @@ impl Problem {
                 variables_region: region,
                 ..
             }
+            | Problem::MultiParameterAbility {
+                variables_region: region,
+                ..
+            }
             | Problem::HasClauseIsNotAbility { region }
             | Problem::IllegalHasClause { region }
             | Problem::DuplicateHasAbility { region, .. }
@@ -402,6 +541,21 @@ impl Problem {
             | Problem::UnnecessaryOutputWildcard { region }
             | Problem::OverAppliedCrash { region }
             | Problem::UnappliedCrash { region }
+            | Problem::MisplacedReturn { region }
+            | Problem::MisplacedTry { region }
+            | Problem::UnappliedDbg { region }
+            | Problem::OverAppliedDbg { region }
+            | Problem::DeprecatedValue { region, .. }
+            | Problem::NamingConvention { region, .. }
+            | Problem::UnconditionalCrash { region, .. }
+            | Problem::InvalidPatternSynonym { region, .. }
+            | Problem::PatternSynonymArity { region, .. }
+            | Problem::RegexPatternNotLiteral { region, .. }
+            | Problem::InvalidRegex { region, .. }
+            | Problem::Rebinding {
+                shadow: Loc { region, .. },
+                ..
+            }
             | Problem::DefsOnlyUsedInRecursion(_, region) => Some(*region),
             Problem::RuntimeError(RuntimeError::CircularDef(cycle_entries))
             | Problem::BadRecursion(cycle_entries) => {
@@ -597,6 +751,50 @@ impl RuntimeError {
     }
 }
 
+/// Why a pattern synonym like `pattern Ok2 x = Ok (Ok x)` can't stand for the pattern it's defined as
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PatternSynonymProblem {
+    /// An argument which isn't a lowercase name, like the `(A x)` in `pattern P (A x) = x`
+    ArgumentNotAName,
+    /// A name in the pattern which isn't one of the synonym's arguments
+    NotAnArgument(Lowercase),
+    /// An argument the pattern never binds
+    UnusedArgument(Lowercase),
+    /// An argument the pattern binds more than once
+    DuplicateArgument(Lowercase),
+    /// A pattern which isn't a tag, one of the synonym's arguments, or `_`
+    UnsupportedPattern,
+}
+
+/// Why a string literal pattern like `"[a-z]+"` given to `Str.matches` or `Str.capture` can't be
+/// compiled
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegexProblem {
+    /// A `(` without a `)`
+    UnclosedGroup,
+    /// A `)` without a `(`
+    UnmatchedParen,
+    /// A `[` without a `]`
+    UnclosedClass,
+    /// A `*`, `+`, `?`, or `{n,m}` with nothing before it to repeat
+    NothingToRepeat,
+    /// A `{n,m}` which isn't one, or whose `m` is less than its `n`
+    InvalidRepetition,
+    /// A range in a character class like `[z-a]`, which ends before it starts
+    BackwardsRange,
+    /// A `\` at the end of the pattern
+    TrailingBackslash,
+    /// A `\` before a character which doesn't mean anything escaped
+    UnknownEscape(char),
+    /// A `^` or `$`. Patterns always match the whole string, so they aren't needed.
+    Anchor,
+    /// A range of non-ASCII characters, or a non-ASCII character in a negated class, which
+    /// aren't supported yet
+    NonAsciiClass,
+    /// A pattern whose compiled form would be too big, usually because of large `{n,m}`s
+    TooBig,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum MalformedPatternProblem {
     MalformedInt,
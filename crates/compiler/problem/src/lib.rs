@@ -15,3 +15,40 @@ pub enum Severity {
     /// (e.g. unused def, unused import)
     Warning,
 }
+
+/// The names of every kind of warning, for allowing them or turning them into errors
+pub const WARNING_NAMES: [&str; 21] = [
+    "ambiguous-number",
+    "deprecated",
+    "duplicate-ability",
+    "duplicate-field",
+    "duplicate-impl",
+    "module-name-mismatch",
+    "no-identifiers-introduced",
+    "non-camel-case-name",
+    "non-pascal-case-name",
+    "only-used-in-recursion",
+    "overloaded-specialization",
+    "redundant-pattern",
+    "shadowing",
+    "unconditional-crash",
+    "unmatchable-pattern",
+    "unnecessary-impl",
+    "unnecessary-wildcard",
+    "unused-argument",
+    "unused-def",
+    "unused-import",
+    "unused-type-parameter",
+];
+
+/// Lints which aren't reported unless they're given a level of their own, e.g. with
+/// `--warn non-camel-case-name`. Setting the level of `all` warnings doesn't turn them on.
+pub const OPT_IN_WARNING_NAMES: [&str; 3] = [
+    "module-name-mismatch",
+    "non-camel-case-name",
+    "non-pascal-case-name",
+];
+
+/// Warnings which are errors unless they're given a level of their own, e.g. with
+/// `--allow shadowing`. Like opt-in lints, setting the level of `all` warnings doesn't affect them.
+pub const DENY_BY_DEFAULT_WARNING_NAMES: [&str; 1] = ["shadowing"];
@@ -967,19 +967,30 @@ impl DerivableVisitor for DeriveDecoding {
         Ok(Descend(true))
     }
 
+    // There is no decoder for tag unions (recursive or not) yet, so they are reported here
+    // rather than dropped when the derived implementation is looked up.
     #[inline(always)]
-    fn visit_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
-        Ok(Descend(true))
+    fn visit_tag_union(var: Variable) -> Result<Descend, NotDerivable> {
+        Err(NotDerivable {
+            var,
+            context: NotDerivableContext::Decode(NotDerivableDecode::TagUnion),
+        })
     }
 
     #[inline(always)]
-    fn visit_recursive_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
-        Ok(Descend(true))
+    fn visit_recursive_tag_union(var: Variable) -> Result<Descend, NotDerivable> {
+        Err(NotDerivable {
+            var,
+            context: NotDerivableContext::Decode(NotDerivableDecode::TagUnion),
+        })
     }
 
     #[inline(always)]
-    fn visit_function_or_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
-        Ok(Descend(true))
+    fn visit_function_or_tag_union(var: Variable) -> Result<Descend, NotDerivable> {
+        Err(NotDerivable {
+            var,
+            context: NotDerivableContext::Decode(NotDerivableDecode::TagUnion),
+        })
     }
 
     #[inline(always)]
@@ -988,8 +999,11 @@ impl DerivableVisitor for DeriveDecoding {
     }
 
     #[inline(always)]
-    fn visit_empty_tag_union(_var: Variable) -> Result<(), NotDerivable> {
-        Ok(())
+    fn visit_empty_tag_union(var: Variable) -> Result<(), NotDerivable> {
+        Err(NotDerivable {
+            var,
+            context: NotDerivableContext::Decode(NotDerivableDecode::TagUnion),
+        })
     }
 
     #[inline(always)]
@@ -0,0 +1,342 @@
+//! Defaulting of number literals whose type is still ambiguous after solving.
+//!
+//! A number literal like `5` has type `Num *` until something constrains it. If nothing does, the
+//! monomorphizer picks a default (`I64` for integers, `F64` for fractions), which can be
+//! surprising when the literal lives inside an annotated def, like
+//!
+//!     f : U8 -> Str
+//!     f = \n -> Num.toStr 5
+//!
+//! If the annotation of the enclosing def names exactly one concrete number type, and the literal
+//! fits it, the literal takes that type instead of the default. Otherwise, as in
+//!
+//!     main : Str
+//!     main = Num.toStr 5
+//!
+//! the literal is reported, so that the user can make the intended type explicit.
+
+use roc_can::def::Def;
+use roc_can::expr::{DeclarationTag, Declarations, Expr};
+use roc_can::traverse::{walk_def, walk_expr, Visitor};
+use roc_collections::VecSet;
+use roc_module::symbol::Symbol;
+use roc_region::all::Region;
+use roc_solve_problem::TypeError;
+use roc_types::subs::{Content, FlatType, GetSubsSlice, Subs, Variable};
+use roc_types::types::Polarity;
+use roc_unify::unify::{unify, Env, Mode, Unified};
+
+/// Resolves the number literals in the annotated top-level defs of `decls` whose type is neither
+/// fixed by solving nor part of the signature of the def they appear in.
+///
+/// Such a literal is unified with the number type named by the def's annotation, if there is
+/// exactly one and unifying them needs no further checks; otherwise `subs` is left untouched. Literals that are still ambiguous after that will be defaulted during
+/// monomorphization, and are reported, once per ambiguous type.
+pub fn find_ambiguous_number_literals(subs: &mut Subs, decls: &Declarations) -> Vec<TypeError> {
+    use DeclarationTag::*;
+
+    let mut problems = Vec::new();
+
+    for (index, tag) in decls.declarations.iter().enumerate() {
+        match tag {
+            Value | Function(_) | Recursive(_) | TailRecursive(_) => {}
+            Expectation | ExpectationFx | Benchmark | Destructure(_) | MutualRecursion { .. } => {
+                continue
+            }
+        }
+
+        if decls.annotations[index].is_none() {
+            // Without an annotation, the user hasn't told us what type they expect the def to
+            // have, so a defaulted literal is not surprising.
+            continue;
+        }
+
+        let signature = Signature::new(subs, decls.variables[index]);
+
+        let mut collector = LiteralCollector {
+            subs,
+            signature_vars: &signature.vars,
+            nested_defs: 0,
+            found: Vec::new(),
+        };
+
+        let loc_expr = &decls.expressions[index];
+        collector.visit_expr(&loc_expr.value, loc_expr.region, decls.variables[index]);
+
+        let found = collector.found;
+        let mut reported = VecSet::default();
+
+        for literal in found {
+            if let [number_var] = signature.numbers.as_slice() {
+                if unify_or_rollback(subs, literal.num_var, *number_var) {
+                    continue;
+                }
+            }
+
+            // Literals that were unified with one another share their ambiguity; one report is
+            // enough for all of them.
+            let root = subs.get_root_key_without_compacting(literal.unbound);
+            if !reported.insert(root) {
+                problems.push(TypeError::AmbiguousNumberDefault {
+                    region: literal.region,
+                    default: literal.default,
+                });
+            }
+        }
+    }
+
+    problems
+}
+
+/// Unifies `var1` with `var2`, but only if that leaves nothing for the solver to check
+/// afterwards, like an ability the number type must implement or lambda sets to specialize.
+/// Solving is over by the time we get here, so those would be silently dropped. If unification
+/// fails or leaves such obligations, `subs` is rolled back to exactly what the solver produced.
+fn unify_or_rollback(subs: &mut Subs, var1: Variable, var2: Variable) -> bool {
+    let snapshot = subs.snapshot();
+
+    match unify(
+        &mut Env::new(subs),
+        var1,
+        var2,
+        Mode::EQ,
+        Polarity::OF_VALUE,
+    ) {
+        Unified::Success {
+            must_implement_ability,
+            lambda_sets_to_specialize,
+            ..
+        } if must_implement_ability.is_empty() && lambda_sets_to_specialize.is_empty() => {
+            subs.commit_snapshot(snapshot);
+            true
+        }
+        Unified::Success { .. } | Unified::Failure(..) => {
+            subs.rollback_to(snapshot);
+            false
+        }
+    }
+}
+
+struct AmbiguousLiteral {
+    region: Region,
+    num_var: Variable,
+    unbound: Variable,
+    default: &'static str,
+}
+
+struct LiteralCollector<'a> {
+    subs: &'a Subs,
+    signature_vars: &'a VecSet<Variable>,
+    /// Literals in nested value defs are generalized along with the def, and only get their type
+    /// at each use site; we don't look at those.
+    nested_defs: usize,
+    found: Vec<AmbiguousLiteral>,
+}
+
+impl Visitor for LiteralCollector<'_> {
+    fn visit_def(&mut self, def: &Def) {
+        self.nested_defs += 1;
+        walk_def(self, def);
+        self.nested_defs -= 1;
+    }
+
+    fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+        match expr {
+            Expr::Num(num_var, ..) | Expr::Int(num_var, ..) | Expr::Float(num_var, ..) => {
+                if self.nested_defs > 0 {
+                    return;
+                }
+
+                if let Some((unbound, default)) = defaulted_number(self.subs, *num_var) {
+                    let root = self.subs.get_root_key_without_compacting(unbound);
+                    if !self.signature_vars.contains(&root) {
+                        self.found.push(AmbiguousLiteral {
+                            region,
+                            num_var: *num_var,
+                            unbound,
+                            default,
+                        });
+                    }
+                }
+            }
+            _ => walk_expr(self, expr, var),
+        }
+    }
+}
+
+/// If the number type `var` is left unbound, returns the unbound variable and the name of the
+/// type the monomorphizer will default it to.
+fn defaulted_number(subs: &Subs, var: Variable) -> Option<(Variable, &'static str)> {
+    let mut var = var;
+    let mut default = "I64";
+
+    loop {
+        match subs.get_content_without_compacting(var) {
+            Content::FlexVar(_) | Content::FlexAbleVar(..) => return Some((var, default)),
+            Content::RangedNumber(range) => {
+                return Some((var, range.default_compilation_width().type_str()))
+            }
+            Content::Alias(
+                symbol @ (Symbol::NUM_NUM | Symbol::NUM_INTEGER | Symbol::NUM_FLOATINGPOINT),
+                args,
+                _,
+                _,
+            ) => {
+                if *symbol == Symbol::NUM_FLOATINGPOINT {
+                    default = "F64";
+                }
+
+                match subs.get_subs_slice(args.type_variables()) {
+                    [arg] => var = *arg,
+                    _ => return None,
+                }
+            }
+            Content::Alias(Symbol::NUM_INT | Symbol::NUM_FRAC, _, real_var, _) => {
+                var = *real_var;
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// The variables of a def's signature
+struct Signature {
+    /// The root keys of all variables reachable from the signature
+    vars: VecSet<Variable>,
+    /// One variable for each distinct concrete number type the signature names, like `U8`
+    numbers: Vec<Variable>,
+}
+
+impl Signature {
+    fn new(subs: &Subs, var: Variable) -> Self {
+        let vars = collect_root_vars(subs, var);
+
+        let mut number_symbols = VecSet::default();
+        let mut numbers = Vec::new();
+        for var in vars.iter() {
+            if let Content::Alias(symbol, _, _, _) = subs.get_content_without_compacting(*var) {
+                if is_concrete_number(*symbol) && !number_symbols.insert(*symbol) {
+                    numbers.push(*var);
+                }
+            }
+        }
+
+        Self { vars, numbers }
+    }
+}
+
+fn is_concrete_number(symbol: Symbol) -> bool {
+    matches!(
+        symbol,
+        Symbol::NUM_I8
+            | Symbol::NUM_I16
+            | Symbol::NUM_I32
+            | Symbol::NUM_I64
+            | Symbol::NUM_I128
+            | Symbol::NUM_U8
+            | Symbol::NUM_U16
+            | Symbol::NUM_U32
+            | Symbol::NUM_U64
+            | Symbol::NUM_U128
+            | Symbol::NUM_NAT
+            | Symbol::NUM_F32
+            | Symbol::NUM_F64
+            | Symbol::NUM_DEC
+    )
+}
+
+/// Collects the root keys of all variables reachable from `var`.
+fn collect_root_vars(subs: &Subs, var: Variable) -> VecSet<Variable> {
+    use Content::*;
+    use FlatType::*;
+
+    let mut seen = VecSet::default();
+    let mut stack = vec![var];
+
+    macro_rules! push_var_slice {
+        ($slice:expr) => {
+            stack.extend(subs.get_subs_slice($slice))
+        };
+    }
+
+    while let Some(var) = stack.pop() {
+        let root = subs.get_root_key_without_compacting(var);
+        if seen.insert(root) {
+            continue;
+        }
+
+        match subs.get_content_without_compacting(root) {
+            FlexVar(_) | RigidVar(_) | FlexAbleVar(..) | RigidAbleVar(..) => {}
+            RecursionVar { structure, .. } => stack.push(*structure),
+            LambdaSet(..) | RangedNumber(_) | Error => {}
+            Alias(_, args, real_var, _) => {
+                push_var_slice!(args.all_variables());
+                stack.push(*real_var);
+            }
+            Structure(flat_type) => match flat_type {
+                Apply(_, args) => push_var_slice!(*args),
+                Func(args, _closure, ret) => {
+                    push_var_slice!(*args);
+                    stack.push(*ret);
+                }
+                Record(fields, ext) => {
+                    push_var_slice!(fields.variables());
+                    stack.push(*ext);
+                }
+                Tuple(elems, ext) => {
+                    push_var_slice!(elems.variables());
+                    stack.push(*ext);
+                }
+                TagUnion(tags, ext) | RecursiveTagUnion(_, tags, ext) => {
+                    for i in tags.variables() {
+                        push_var_slice!(subs[i]);
+                    }
+                    stack.push(ext.var());
+                }
+                FunctionOrTagUnion(_, _, ext) => stack.push(ext.var()),
+                EmptyRecord | EmptyTuple | EmptyTagUnion => {}
+            },
+        }
+    }
+
+    seen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::unify_or_rollback;
+    use roc_module::symbol::Symbol;
+    use roc_types::subs::{Content, Descriptor, GetSubsSlice, Subs, Variable};
+
+    #[test]
+    fn unify_number_with_no_obligations() {
+        let mut subs = Subs::new();
+        let literal = subs.fresh_unnamed_flex_var();
+
+        assert!(unify_or_rollback(&mut subs, literal, Variable::U8));
+        assert!(subs.equivalent_without_compacting(literal, Variable::U8));
+    }
+
+    #[test]
+    fn rollback_when_unification_leaves_ability_obligations() {
+        let mut subs = Subs::new();
+        let literal = subs.fresh(Descriptor::from(Content::FlexAbleVar(None, Subs::AB_HASH)));
+
+        assert!(!unify_or_rollback(&mut subs, literal, Variable::U8));
+        assert!(!subs.equivalent_without_compacting(literal, Variable::U8));
+        match subs.get_content_without_compacting(literal) {
+            Content::FlexAbleVar(None, abilities) => {
+                assert_eq!(subs.get_subs_slice(*abilities), [Symbol::HASH_HASH_ABILITY])
+            }
+            content => panic!("expected the literal to stay ability-bound, got {content:?}"),
+        }
+    }
+
+    #[test]
+    fn rollback_when_unification_fails() {
+        let mut subs = Subs::new();
+
+        assert!(!unify_or_rollback(&mut subs, Variable::U8, Variable::STR));
+        assert!(!subs.equivalent_without_compacting(Variable::U8, Variable::STR));
+    }
+}
@@ -5,6 +5,7 @@
 #![allow(clippy::large_enum_variant)]
 
 pub mod ability;
+pub mod ambiguous_number;
 pub mod module;
 pub mod solve;
 pub mod specialize;
@@ -3514,7 +3514,7 @@ fn check_for_infinite_type(
 ) {
     let var = loc_var.value;
 
-    'next_occurs_check: while let Err((_, chain)) = subs.occurs(var) {
+    'next_occurs_check: while let Err((recursive_var, chain)) = subs.occurs(var) {
         // walk the chain till we find a tag union or lambda set, starting from the variable that
         // occurred recursively, which is always at the end of the chain.
         for &var in chain.iter().rev() {
@@ -3541,7 +3541,7 @@ fn check_for_infinite_type(
             }
         }
 
-        circular_error(subs, problems, symbol, &loc_var);
+        circular_error(subs, problems, symbol, &loc_var, recursive_var);
     }
 }
 
@@ -3550,10 +3550,12 @@ fn circular_error(
     problems: &mut Vec<TypeError>,
     symbol: Symbol,
     loc_var: &Loc<Variable>,
+    recursive_var: Variable,
 ) {
     let var = loc_var.value;
     let error_type = subs.var_to_error_type(var, Polarity::OF_VALUE);
-    let problem = TypeError::CircularType(loc_var.region, symbol, error_type);
+    let recursive_type = subs.var_to_error_type(recursive_var, Polarity::OF_VALUE);
+    let problem = TypeError::CircularType(loc_var.region, symbol, error_type, recursive_type);
 
     subs.set_content(var, Content::Error);
 
@@ -6590,6 +6590,123 @@ mod solve_expr {
         )
     }
 
+    #[test]
+    fn stdlib_async() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                app "test"
+                    imports [Async]
+                    provides [main] to "./platform"
+
+                main =
+                    Async.ok 1
+                    |> Async.await (\n -> if n > 0 then Async.ok (Num.toStr n) else Async.err Negative)
+                    |> Async.map Str.countGraphemes
+                    |> Async.mapErr (\Negative -> "negative")
+                "#
+            ),
+            "Async Nat Str",
+        )
+    }
+
+    #[test]
+    fn stdlib_random() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                app "test"
+                    imports [Random]
+                    provides [main] to "./platform"
+
+                main =
+                    { left, right } = Random.split (Random.testSeed {})
+
+                    Random.list left (Num.toNat (Random.below right 10).value) Random.bool
+                "#
+            ),
+            "{ seed : Seed, value : List Bool }",
+        )
+    }
+
+    #[test]
+    fn stdlib_sorted_dict() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                app "test"
+                    imports [SortedDict, SortedSet]
+                    provides [main] to "./platform"
+
+                main =
+                    dict =
+                        SortedDict.empty Num.compare
+                        |> SortedDict.insert 2u64 "two"
+                        |> SortedDict.insert 1 "one"
+
+                    keys = SortedSet.fromList (SortedDict.keys dict) Num.compare
+
+                    { dict, keys, range: SortedDict.range dict { start: At 1, end: Unbounded } }
+                "#
+            ),
+            "{ dict : SortedDict U64 Str, keys : SortedSet U64, range : List [T U64 Str] }",
+        )
+    }
+
+    #[test]
+    fn stdlib_bytes() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                app "test"
+                    imports [Bytes]
+                    provides [main] to "./platform"
+
+                main =
+                    length <- Result.try (Bytes.readU16Be (Bytes.fromList [0, 2, 7, 9]))
+                    payload <- Result.try (Bytes.take length.rest (Num.toNat length.value))
+
+                    Ok (Bytes.toList payload.value)
+                "#
+            ),
+            "Result (List U8) [OutOfBounds]",
+        )
+    }
+
+    #[test]
+    fn stdlib_encoding() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                app "test"
+                    imports [Encoding]
+                    provides [main] to "./platform"
+
+                main =
+                    { hex: Encoding.hexEncode [1, 2], decoded: Encoding.base64Decode "AQI=" }
+                "#
+            ),
+            "{ decoded : Result (List U8) [InvalidChar Nat, InvalidLength], hex : Str }",
+        )
+    }
+
+    #[test]
+    fn stdlib_time() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                app "test"
+                    imports [Time]
+                    provides [main] to "./platform"
+
+                main =
+                    { later: Time.add Time.epoch (Time.hours 1), parsed: Time.fromIso8601 "1970-01-01T00:00:00Z" }
+                "#
+            ),
+            "{ later : Utc, parsed : Result Utc [InvalidIso8601 Nat] }",
+        )
+    }
+
     #[test]
     fn encode_record() {
         infer_queries!(
@@ -10,7 +10,8 @@ use roc_types::types::{Category, ErrorType, PatternCategory};
 pub enum TypeError {
     BadExpr(Region, Category, ErrorType, Expected<ErrorType>),
     BadPattern(Region, PatternCategory, ErrorType, PExpected<ErrorType>),
-    CircularType(Region, Symbol, ErrorType),
+    /// The overall type of the symbol, and the part of it that refers to itself.
+    CircularType(Region, Symbol, ErrorType, ErrorType),
     CircularDef(Vec<CycleEntry>),
     UnexposedLookup(Symbol),
     UnfulfilledAbility(Unfulfilled),
@@ -29,6 +30,12 @@ pub enum TypeError {
         expected_opaque: Symbol,
         found_opaque: Symbol,
     },
+    /// A number literal whose type was left unbound, and will be defaulted during
+    /// monomorphization.
+    AmbiguousNumberDefault {
+        region: Region,
+        default: &'static str,
+    },
 }
 
 impl TypeError {
@@ -48,6 +55,47 @@ impl TypeError {
             TypeError::Exhaustive(exhtv) => exhtv.severity(),
             TypeError::StructuralSpecialization { .. } => RuntimeError,
             TypeError::WrongSpecialization { .. } => RuntimeError,
+            TypeError::AmbiguousNumberDefault { .. } => Warning,
+        }
+    }
+
+    /// The name a warning can be allowed, or turned into an error, by. Errors have no name, since
+    /// they can't be allowed.
+    pub fn warning_name(&self) -> Option<&'static str> {
+        match self {
+            TypeError::Exhaustive(roc_exhaustive::Error::Redundant { .. }) => {
+                Some("redundant-pattern")
+            }
+            TypeError::Exhaustive(roc_exhaustive::Error::Unmatchable { .. }) => {
+                Some("unmatchable-pattern")
+            }
+            TypeError::AmbiguousNumberDefault { .. } => Some("ambiguous-number"),
+            _ => None,
+        }
+    }
+
+    /// The place the problem is reported at, if there is one
+    pub fn region(&self) -> Option<Region> {
+        match self {
+            TypeError::BadExpr(region, ..)
+            | TypeError::BadPattern(region, ..)
+            | TypeError::CircularType(region, ..)
+            | TypeError::BadExprMissingAbility(region, ..)
+            | TypeError::BadPatternMissingAbility(region, ..)
+            | TypeError::StructuralSpecialization { region, .. }
+            | TypeError::WrongSpecialization { region, .. }
+            | TypeError::AmbiguousNumberDefault { region, .. } => Some(*region),
+            TypeError::CircularDef(entries) => entries.first().map(|entry| entry.symbol_region),
+            TypeError::UnexposedLookup(_) => None,
+            TypeError::UnfulfilledAbility(Unfulfilled::OpaqueUnderivable {
+                derive_region, ..
+            }) => Some(*derive_region),
+            TypeError::UnfulfilledAbility(_) => None,
+            TypeError::Exhaustive(roc_exhaustive::Error::Incomplete(region, ..)) => Some(*region),
+            TypeError::Exhaustive(
+                roc_exhaustive::Error::Redundant { branch_region, .. }
+                | roc_exhaustive::Error::Unmatchable { branch_region, .. },
+            ) => Some(*branch_region),
         }
     }
 }
@@ -101,6 +149,7 @@ pub enum NotDerivableEncode {
 pub enum NotDerivableDecode {
     Nat,
     OptionalRecordField(Lowercase),
+    TagUnion,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -66,6 +66,15 @@ fn optional_record_field_derive_error() {
     check_underivable(Decoder, v!({ ?a: v!(U8), }), DeriveError::Underivable);
 }
 
+#[test]
+fn recursive_tag_union_derive_error() {
+    check_underivable(
+        Decoder,
+        v!([Nil, Cons v!(U8) v!(^lst) ] as lst),
+        DeriveError::Underivable,
+    );
+}
+
 #[test]
 fn derivable_record_ext_flex_var() {
     check_derivable(
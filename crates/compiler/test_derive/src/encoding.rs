@@ -8,12 +8,12 @@ use insta::assert_snapshot;
 
 use crate::{
     test_key_eq, test_key_neq,
-    util::{check_derivable, check_immediate, derive_test},
+    util::{check_derivable, check_immediate, check_key, derive_test},
     v,
 };
 use roc_derive_key::{encoding::FlatEncodableKey, DeriveBuiltin::ToEncoder, DeriveKey};
 use roc_module::symbol::Symbol;
-use roc_types::subs::Variable;
+use roc_types::subs::{Content, FlatType, Subs, Variable};
 
 // {{{ hash tests
 
@@ -96,6 +96,22 @@ test_key_neq! {
         v!(@Symbol::BOOL_BOOL => v!([ True, False ])), v!(@Symbol::UNDERSCORE => v!([ False, True, Maybe ]))
 }
 
+#[test]
+fn recursion_var_same_key_as_recursive_tag_union() {
+    check_key(
+        ToEncoder,
+        true,
+        v!([ Nil, Cons v!(^lst) ] as lst),
+        |subs: &mut Subs| {
+            let tag_union = v!([ Nil, Cons v!(^lst) ] as lst)(subs);
+            match subs.get_content_without_compacting(tag_union) {
+                Content::Structure(FlatType::RecursiveTagUnion(rec, _, _)) => *rec,
+                _ => unreachable!(),
+            }
+        },
+    )
+}
+
 // }}} hash tests
 
 // {{{ deriver tests
@@ -371,7 +371,7 @@ fn encode_use_stdlib() {
                     _ -> "<bad>"
             "#
         ),
-        RocStr::from("\"Hello, World!\n\""),
+        RocStr::from("\"Hello, World!\\n\""),
         RocStr
     )
 }
@@ -664,6 +664,34 @@ fn encode_derived_nested_tag_string() {
     )
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn encode_derived_recursive_tag_union() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [Encode, Json]
+                provides [main] to "./platform"
+
+            Tree : [Leaf Str, Node (List Tree)]
+
+            main =
+                x : Tree
+                x = Node [Leaf "foo", Node [Leaf "bar"], Node []]
+                result = Str.fromUtf8 (Encode.toBytes x Json.toUtf8)
+                when result is
+                    Ok s -> s
+                    _ -> "<bad>"
+            "#
+        ),
+        RocStr::from(
+            r#"{"Node":[[{"Leaf":["foo"]},{"Node":[[{"Leaf":["bar"]}]]},{"Node":[[]]}]]}"#
+        ),
+        RocStr
+    )
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn encode_derived_nested_record_tag_record() {
@@ -1180,6 +1208,204 @@ fn decode_record_of_record() {
     )
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn encode_json_escapes_strings() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Encode, Json] provides [main] to "./platform"
+
+            main =
+                result = Str.fromUtf8 (Encode.toBytes ["tab\there", "\"quoted\" \\ back", "\u(1)"] Json.toUtf8)
+                when result is
+                    Ok s -> s
+                    _ -> "<bad>"
+            "#
+        ),
+        RocStr::from(r#"["tab\there","\"quoted\" \\ back","\u0001"]"#),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn encode_json_non_finite_floats() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Encode, Json] provides [main] to "./platform"
+
+            main =
+                result = Str.fromUtf8 (Encode.toBytes [1.5f64, 1 / 0, 0 / 0] Json.toUtf8)
+                when result is
+                    Ok s -> s
+                    _ -> "<bad>"
+            "#
+        ),
+        RocStr::from("[1.5,null,null]"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn decode_json_string_escapes() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Json] provides [main] to "./platform"
+
+            main =
+                when Str.toUtf8 "\"a\\n\\\"b\\\" \\u00e9 \\ud83d\\udc26\"" |> Decode.fromBytes Json.fromUtf8 is
+                    Ok s -> s
+                    _ -> "something went wrong"
+            "#
+        ),
+        RocStr::from("a\n\"b\" é 🐦"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn decode_json_whitespace_and_negative_numbers() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Json] provides [main] to "./platform"
+
+            main : List I64
+            main =
+                when Str.toUtf8 " [ -1 ,\n  2,\t-30 ]\n" |> Decode.fromBytes Json.fromUtf8 is
+                    Ok list -> list
+                    _ -> []
+            "#
+        ),
+        RocList::from_slice(&[-1i64, 2, -30]),
+        RocList<i64>
+    )
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn decode_json_empty_list() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Json] provides [main] to "./platform"
+
+            main =
+                decoded : Result (List U8) _
+                decoded = Str.toUtf8 "[ ]" |> Decode.fromBytes Json.fromUtf8
+
+                when decoded is
+                    Ok list -> List.len list + 1
+                    _ -> 0
+            "#
+        ),
+        1usize,
+        usize
+    )
+}
+
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(feature = "gen-llvm-wasm"), // hits a wasm3 stack overflow
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn decode_json_record_skips_unknown_fields() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Json] provides [main] to "./platform"
+
+            main =
+                json = "{ \"extra\": {\"nested\": [1, \"]\", null]}, \"name\": \"roc\", \"also\": true }"
+
+                when Str.toUtf8 json |> Decode.fromBytes Json.fromUtf8 is
+                    Ok { name } -> name
+                    _ -> "something went wrong"
+            "#
+        ),
+        RocStr::from("roc"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(feature = "gen-llvm-wasm"), // hits a wasm3 stack overflow
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn decode_json_stream() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Json] provides [main] to "./platform"
+
+            nextRecord : Json.Stream -> Result { value : { a : Str }, stream : Json.Stream } _
+            nextRecord = \stream -> Json.next stream
+
+            decodeAll = \stream, values ->
+                when nextRecord stream is
+                    Ok { value, stream: rest } -> decodeAll rest (List.append values value.a)
+                    Err NeedMore -> Str.concat (Str.joinWith values ",") " need more"
+                    Err EndOfInput -> Str.joinWith values ","
+                    Err _ -> "something went wrong"
+
+            main =
+                chunks = ["{\"a\":\"x\"}\n{\"a\":", "\"y\"}\n{\"a\"", ":\"z\"}\n"]
+                feedAll = \stream, count ->
+                    List.takeFirst chunks count
+                    |> List.walk stream \s, chunk -> Json.feed s (Str.toUtf8 chunk)
+
+                partial = feedAll Json.stream 1
+                whole = feedAll Json.stream 3 |> Json.endOfInput
+
+                Str.joinWith [decodeAll partial [], decodeAll whole []] " | "
+            "#
+        ),
+        RocStr::from("x need more | x,y,z"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn decode_json_stream_invalid() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Json] provides [main] to "./platform"
+
+            nextList : Json.Stream -> Result { value : List U8, stream : Json.Stream } _
+            nextList = \stream -> Json.next stream
+
+            main =
+                stream =
+                    Json.stream
+                    |> Json.feed (Str.toUtf8 "[1] [2]")
+                    |> Json.feed (Str.toUtf8 " [3,,4]")
+                    |> Json.endOfInput
+
+                result =
+                    nextList stream
+                    |> Result.try \first -> nextList first.stream
+                    |> Result.try \second -> nextList second.stream
+
+                when result is
+                    Err (InvalidJson index) -> index
+                    _ -> 0
+            "#
+        ),
+        11usize,
+        usize
+    )
+}
+
 #[cfg(all(test, any(feature = "gen-llvm", feature = "gen-wasm")))]
 mod hash {
     #[cfg(feature = "gen-llvm")]
@@ -0,0 +1,107 @@
+#[cfg(feature = "gen-llvm")]
+use crate::helpers::llvm::assert_evals_to;
+
+#[cfg(feature = "gen-wasm")]
+use crate::helpers::wasm::assert_evals_to;
+
+use indoc::indoc;
+
+#[allow(unused_imports)]
+use roc_std::RocList;
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn read_u16_in_both_byte_orders() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [Bytes]
+                provides [main] to "./platform"
+
+            main =
+                bytes = Bytes.fromList [0x12, 0x34]
+                little = Bytes.readU16Le bytes |> Result.map .value |> Result.withDefault 0
+                big = Bytes.readU16Be bytes |> Result.map .value |> Result.withDefault 0
+
+                [little, big]
+            "#
+        ),
+        RocList::from_slice(&[0x3412u16, 0x1234]),
+        RocList<u16>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn reads_follow_each_other() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [Bytes]
+                provides [main] to "./platform"
+
+            main =
+                decoded =
+                    tag <- Result.try (Bytes.readU8 (Bytes.fromList [7, 0, 0, 0, 42, 1, 2, 3, 4, 5, 6, 7, 8]))
+                    count <- Result.try (Bytes.readU32Be tag.rest)
+                    big <- Result.try (Bytes.readU64Le count.rest)
+
+                    Ok (Num.toU64 tag.value + Num.toU64 count.value + big.value)
+
+                Result.withDefault decoded 0
+            "#
+        ),
+        0x0807_0605_0403_0201 + 49,
+        u64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn read_past_the_end() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [Bytes]
+                provides [main] to "./platform"
+
+            main =
+                when Bytes.skip (Bytes.fromList [1, 2, 3, 4, 5]) 2 |> Result.try Bytes.readU32Le is
+                    Ok _ -> Bool.false
+                    Err OutOfBounds -> Bool.true
+            "#
+        ),
+        true,
+        bool
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn take_and_split_share_the_list() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [Bytes]
+                provides [main] to "./platform"
+
+            main =
+                { before, others } = Bytes.split (Bytes.fromList [1, 2, 3, 4, 5, 6]) 4
+
+                when Bytes.take before 3 is
+                    Ok { value, rest } ->
+                        List.concat (Bytes.toList value) (Bytes.toList rest)
+                        |> List.concat (Bytes.toList others)
+                        |> List.append (Num.toU8 (Bytes.len others))
+
+                    Err OutOfBounds -> []
+            "#
+        ),
+        RocList::from_slice(&[1u8, 2, 3, 4, 5, 6, 2]),
+        RocList<u8>
+    );
+}
@@ -550,3 +550,83 @@ fn walk_sum_keys() {
         i64
     );
 }
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn key_hashed_with_hasher_that_always_collides() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            # Every key gets the same hash, so every lookup has to fall back to comparing keys
+            Colliding := {} has [Hasher {
+                addBytes: \c, _ -> c,
+                addU8: \c, _ -> c,
+                addU16: \c, _ -> c,
+                addU32: \c, _ -> c,
+                addU64: \c, _ -> c,
+                addU128: \c, _ -> c,
+                complete: \_ -> 0,
+            }]
+
+            Key := I64 has [Eq, Hash { hash: hashKey }]
+
+            hashKey = \hasher, @Key n -> Hash.hashWith hasher n (@Colliding {})
+
+            main =
+                Dict.empty {}
+                |> Dict.insert (@Key 1) 10
+                |> Dict.insert (@Key 2) 20
+                |> Dict.insert (@Key 3) 30
+                |> Dict.remove (@Key 2)
+                |> Dict.insert (@Key 1) 100
+                |> Dict.walk 0 \total, @Key k, v -> total + k * v
+            "#
+        ),
+        190,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn derived_hash_uses_field_hasher() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            # FNV-1a, one byte of each integer at a time
+            Fnv := U64 has [Hasher {
+                addBytes: \hasher, bytes -> List.walk bytes hasher addByte,
+                addU8: \hasher, n -> addByte hasher n,
+                addU16: \hasher, n -> addByte hasher (Num.toU8 n),
+                addU32: \hasher, n -> addByte hasher (Num.toU8 n),
+                addU64: \hasher, n -> addByte hasher (Num.toU8 n),
+                addU128: \hasher, n -> addByte hasher (Num.toU8 n),
+                complete: \@Fnv state -> state,
+            }]
+
+            addByte : Fnv, U8 -> Fnv
+            addByte = \@Fnv state, byte ->
+                @Fnv (Num.mulWrap (Num.bitwiseXor state (Num.toU64 byte)) 0x100_0000_01b3)
+
+            Name := Str has [Eq, Hash { hash: hashName }]
+
+            hashName = \hasher, @Name name -> Hash.hashWith hasher name (@Fnv 0xcbf2_9ce4_8422_2325)
+
+            # The record's derived Hash hashes `name` with Fnv
+            main =
+                Dict.empty {}
+                |> Dict.insert { name: @Name "a", id: 1u8 } 1
+                |> Dict.insert { name: @Name "b", id: 1u8 } 2
+                |> Dict.insert { name: @Name "a", id: 2u8 } 3
+                |> Dict.insert { name: @Name "a", id: 1u8 } 4
+                |> Dict.values
+            "#
+        ),
+        RocList::from_slice(&[4, 2, 3]),
+        RocList<i64>
+    );
+}
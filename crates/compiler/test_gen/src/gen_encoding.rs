@@ -0,0 +1,156 @@
+#[cfg(feature = "gen-llvm")]
+use crate::helpers::llvm::assert_evals_to;
+
+#[cfg(feature = "gen-wasm")]
+use crate::helpers::wasm::assert_evals_to;
+
+use indoc::indoc;
+
+#[allow(unused_imports)]
+use roc_std::{RocList, RocStr};
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn base64_encode() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [Encoding]
+                provides [main] to "./platform"
+
+            main =
+                ["", "f", "fo", "foo", "foob", "fooba", "foobar"]
+                |> List.map \str -> Encoding.base64Encode (Str.toUtf8 str)
+            "#
+        ),
+        RocList::from_slice(&[
+            RocStr::from(""),
+            RocStr::from("Zg=="),
+            RocStr::from("Zm8="),
+            RocStr::from("Zm9v"),
+            RocStr::from("Zm9vYg=="),
+            RocStr::from("Zm9vYmE="),
+            RocStr::from("Zm9vYmFy"),
+        ]),
+        RocList<RocStr>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn base64_url_round_trip() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [Encoding]
+                provides [main] to "./platform"
+
+            main =
+                encoded = Encoding.base64UrlEncode [0xfb, 0xff, 0xbf]
+
+                when Encoding.base64UrlDecode encoded is
+                    Ok bytes -> Str.concat encoded (Num.toStr (List.len bytes))
+                    Err _ -> "error"
+            "#
+        ),
+        RocStr::from("-_-_3"),
+        RocStr
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn base64_decode_errors() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [Encoding]
+                provides [main] to "./platform"
+
+            main =
+                ["Zm9v", "Zm9vY", "Zm9v!", "Zg", "Zg===", "Zm9=v"]
+                |> List.map \str ->
+                    when Encoding.base64Decode str is
+                        Ok bytes -> Num.toI64 (List.len bytes)
+                        Err InvalidLength -> -1
+                        Err (InvalidChar index) -> -10 - Num.toI64 index
+            "#
+        ),
+        RocList::from_slice(&[3, -1, -14, 1, -12, -13]),
+        RocList<i64>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn hex_round_trip() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [Encoding]
+                provides [main] to "./platform"
+
+            main =
+                bytes = List.range { start: At 0u16, end: At 255 } |> List.map Num.toU8
+                hex = Encoding.hexEncode bytes
+
+                Str.countUtf8Bytes hex == 512
+                && Str.startsWith hex "000102030405060708090a0b0c0d0e0f10"
+                && Encoding.hexDecode hex == Ok bytes
+                && Encoding.hexDecode "CAFE" == Ok [0xca, 0xfe]
+                && Encoding.hexDecode "abc" == Err InvalidLength
+                && Encoding.hexDecode "0g" == Err (InvalidChar 1)
+            "#
+        ),
+        true,
+        bool
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn percent_encoding() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [Encoding]
+                provides [main] to "./platform"
+
+            main =
+                encoded = Encoding.percentEncode "a b/ü~"
+
+                when Encoding.percentDecode encoded is
+                    Ok decoded -> Str.joinWith [encoded, decoded] " "
+                    Err _ -> "error"
+            "#
+        ),
+        RocStr::from("a%20b%2F%C3%BC~ a b/ü~"),
+        RocStr
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn percent_decode_errors() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [Encoding]
+                provides [main] to "./platform"
+
+            main =
+                when T (Encoding.percentDecode "100%") (Encoding.percentDecode "%ff") is
+                    T (Err (InvalidChar 3)) (Err (BadUtf8 _ 0)) -> Bool.true
+                    _ -> Bool.false
+            "#
+        ),
+        true,
+        bool
+    );
+}
@@ -0,0 +1,162 @@
+//! Linking in the bitcode a platform can ship for hosted functions small enough to inline.
+
+use inkwell::attributes::{Attribute, AttributeLoc};
+use inkwell::context::Context;
+use inkwell::module::{Linkage, Module};
+use inkwell::targets::{
+    CodeModel, InitializationConfig, RelocMode, Target, TargetMachine, TargetTriple,
+};
+use inkwell::OptimizationLevel;
+use roc_gen_llvm::llvm::build::link_inline_hosted_bitcode;
+use std::path::Path;
+
+fn native_target_machine() -> TargetMachine {
+    Target::initialize_native(&InitializationConfig::default()).unwrap();
+
+    let triple = TargetMachine::get_default_triple();
+
+    Target::from_triple(&triple)
+        .unwrap()
+        .create_target_machine(
+            &triple,
+            "",
+            "",
+            OptimizationLevel::None,
+            RelocMode::Default,
+            CodeModel::Default,
+        )
+        .unwrap()
+}
+
+fn set_target(module: &Module, machine: &TargetMachine) {
+    module.set_triple(&machine.get_triple());
+    module.set_data_layout(&machine.get_target_data().get_data_layout());
+}
+
+/// Writes bitcode like a platform would ship, defining `roc_fx_answer` with the help of a
+/// function which isn't hosted
+fn write_inline_bitcode(ctx: &Context, path: &Path, set_target: impl FnOnce(&Module)) {
+    let module = ctx.create_module("inline");
+    let builder = ctx.create_builder();
+    let i64_type = ctx.i64_type();
+
+    set_target(&module);
+
+    let helper = module.add_function("answer_help", i64_type.fn_type(&[], false), None);
+    builder.position_at_end(ctx.append_basic_block(helper, "entry"));
+    builder.build_return(Some(&i64_type.const_int(42, false)));
+
+    let answer = module.add_function("roc_fx_answer", i64_type.fn_type(&[], false), None);
+    builder.position_at_end(ctx.append_basic_block(answer, "entry"));
+    let value = builder
+        .build_call(helper, &[], "value")
+        .try_as_basic_value()
+        .left()
+        .unwrap();
+    builder.build_return(Some(&value));
+
+    assert!(module.write_bitcode_to_path(path));
+}
+
+/// An app whose `main` calls the hosted `roc_fx_answer`
+fn app_module<'ctx>(ctx: &'ctx Context, machine: &TargetMachine) -> Module<'ctx> {
+    let module = ctx.create_module("app");
+    let builder = ctx.create_builder();
+    let i64_type = ctx.i64_type();
+
+    set_target(&module, machine);
+
+    let answer = module.add_function(
+        "roc_fx_answer",
+        i64_type.fn_type(&[], false),
+        Some(Linkage::External),
+    );
+
+    let main = module.add_function("main", i64_type.fn_type(&[], false), None);
+    builder.position_at_end(ctx.append_basic_block(main, "entry"));
+    let value = builder
+        .build_call(answer, &[], "answer")
+        .try_as_basic_value()
+        .left()
+        .unwrap();
+    builder.build_return(Some(&value));
+
+    module
+}
+
+#[test]
+fn inline_hosted_function() {
+    let machine = native_target_machine();
+    let ctx = Context::create();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("host.inline.bc");
+
+    write_inline_bitcode(&ctx, &path, |module| set_target(module, &machine));
+
+    let module = app_module(&ctx, &machine);
+    link_inline_hosted_bitcode(&ctx, &module, &path).unwrap();
+    module.verify().unwrap();
+
+    let always_inline = Attribute::get_named_enum_kind_id("alwaysinline");
+
+    let answer = module.get_function("roc_fx_answer").unwrap();
+    assert_eq!(answer.get_linkage(), Linkage::Internal);
+    assert!(answer
+        .get_enum_attribute(AttributeLoc::Function, always_inline)
+        .is_some());
+
+    // Only hosted functions are internalized and inlined
+    let helper = module.get_function("answer_help").unwrap();
+    assert_eq!(helper.get_linkage(), Linkage::External);
+    assert!(helper
+        .get_enum_attribute(AttributeLoc::Function, always_inline)
+        .is_none());
+
+    let engine = module
+        .create_jit_execution_engine(OptimizationLevel::None)
+        .unwrap();
+    let main = unsafe {
+        engine
+            .get_function::<unsafe extern "C" fn() -> i64>("main")
+            .unwrap()
+    };
+
+    assert_eq!(unsafe { main.call() }, 42);
+}
+
+#[test]
+fn inline_hosted_bitcode_for_another_target() {
+    let machine = native_target_machine();
+    let ctx = Context::create();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("host.inline.bc");
+
+    let other_triple = "wasm32-unknown-unknown";
+
+    write_inline_bitcode(&ctx, &path, |module| {
+        module.set_triple(&TargetTriple::create(other_triple))
+    });
+
+    let module = app_module(&ctx, &machine);
+    let problem = link_inline_hosted_bitcode(&ctx, &module, &path).unwrap_err();
+
+    assert!(problem.contains(other_triple), "{}", problem);
+    assert!(module.get_function("answer_help").is_none());
+}
+
+#[test]
+fn inline_hosted_bitcode_with_another_data_layout() {
+    let machine = native_target_machine();
+    let ctx = Context::create();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("host.inline.bc");
+
+    write_inline_bitcode(&ctx, &path, |module| {
+        module.set_triple(&machine.get_triple());
+    });
+
+    let module = app_module(&ctx, &machine);
+    let problem = link_inline_hosted_bitcode(&ctx, &module, &path).unwrap_err();
+
+    assert!(problem.contains("data layout"), "{}", problem);
+}
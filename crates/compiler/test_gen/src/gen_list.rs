@@ -1199,6 +1199,79 @@ fn list_count_if_str() {
     );
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn list_map_concurrent() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            offset = 10
+
+            List.mapConcurrent (List.range { start: At 0, end: Before 1000 }) (\x -> x * x + offset)
+            |> List.sum
+            "#
+        ),
+        (0..1000i64).map(|x| x * x + 10).sum::<i64>(),
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn list_map_concurrent_refcounted() {
+    // Strings are reference counted, so their reference counts are updated atomically
+    assert_evals_to!(
+        indoc!(
+            r#"
+            suffix = "!"
+
+            List.mapConcurrent ["a", "b", "c"] (\str -> Str.concat str suffix)
+            "#
+        ),
+        RocList::from_slice(&[RocStr::from("a!"), RocStr::from("b!"), RocStr::from("c!"),]),
+        RocList<RocStr>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn list_map_concurrent_shares_captured_heap_str() {
+    // Too long to be a small string, so every thread updates the same reference count
+    assert_evals_to!(
+        indoc!(
+            r#"
+            suffix = " is much too long to fit in a small string"
+
+            List.mapConcurrent ["a", "b", "c", "d"] (\str -> Str.concat str suffix)
+            |> List.map Str.countUtf8Bytes
+            "#
+        ),
+        RocList::from_slice(&[43, 43, 43, 43]),
+        RocList<usize>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn list_map_concurrent_drops_shared_list() {
+    // Appending to the captured list copies it, since it isn't unique, and the builtin drops the
+    // thread's reference to the original. Every thread does this to the same list.
+    assert_evals_to!(
+        indoc!(
+            r#"
+            shared = List.repeat "much too long to fit in a small string" 10
+
+            appendLen = \i -> List.append shared (Num.toStr i) |> List.len
+
+            List.mapConcurrent (List.range { start: At 0, end: Before 1000 }) appendLen
+            |> List.sum
+            "#
+        ),
+        11000,
+        usize
+    );
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn list_map_on_empty_list_with_int_layout() {
@@ -1823,6 +1896,73 @@ fn loaded_int_list_len() {
     );
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn local_list_literals_len() {
+    // Neither list outlives the call, so they can both go in its scratch region
+    assert_evals_to!(
+        indoc!(
+            r#"
+                bytes : List U8
+                bytes = [1, 2, 3]
+
+                nums : List I64
+                nums = [4, 5, 6, 7, 8]
+
+                List.len bytes + List.len nums
+            "#
+        ),
+        8,
+        usize
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn local_str_list_literals_len() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                words : List Str
+                words = ["small", "a string literal that is too long to be small"]
+
+                List.len words
+            "#
+        ),
+        2,
+        usize
+    );
+}
+
+#[test]
+#[cfg(feature = "gen-llvm")]
+fn local_list_literals_are_not_heap_allocated() {
+    let ir = crate::helpers::llvm::app_procs_llvm_ir(indoc!(
+        r#"
+            bytes : List U8
+            bytes = [1, 2, 3]
+
+            words : List Str
+            words = ["small", "a string literal that is too long to be small"]
+
+            List.len bytes + List.len words
+        "#
+    ));
+
+    assert!(ir.contains("scratch_list"), "{}", ir);
+    assert!(!ir.contains("allocate_with_refcount"), "{}", ir);
+    assert!(!ir.contains("@roc_alloc"), "{}", ir);
+}
+
+#[test]
+#[cfg(feature = "gen-llvm")]
+fn returned_list_literals_are_heap_allocated() {
+    let ir = crate::helpers::llvm::app_procs_llvm_ir("[1u8, 2, 3]");
+
+    assert!(!ir.contains("scratch_list"), "{}", ir);
+    assert!(ir.contains("allocate_with_refcount"), "{}", ir);
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn fn_int_list_len() {
@@ -3451,6 +3591,24 @@ fn with_capacity() {
     );
 }
 
+#[test]
+#[cfg(feature = "gen-llvm")]
+#[should_panic(expected = r#"Roc failed with message: "Ran out of memory""#)]
+fn with_capacity_out_of_memory() {
+    // The test host doesn't define roc_oom, so running out of memory panics instead
+    assert_evals_to!(
+        indoc!(
+            r#"
+            List.withCapacity 1000000000000000000
+                |> List.append 0u8
+                |> List.len
+            "#
+        ),
+        1,
+        usize
+    );
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn with_capacity_append() {
@@ -576,6 +576,34 @@ fn f64_log_negative() {
     );
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn is_nan() {
+    assert_evals_to!("Num.isNaN (0.0f64 / 0.0)", true, bool);
+    assert_evals_to!("Num.isNaN (1.0f64 / 0.0)", false, bool);
+    assert_evals_to!("Num.isNaN 1.5f32", false, bool);
+    assert_evals_to!("Num.isNaN 1.5dec", false, bool);
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn is_infinite() {
+    assert_evals_to!("Num.isInfinite (1.0f64 / 0.0)", true, bool);
+    assert_evals_to!("Num.isInfinite (-1.0f64 / 0.0)", true, bool);
+    assert_evals_to!("Num.isInfinite (0.0f64 / 0.0)", false, bool);
+    assert_evals_to!("Num.isInfinite Num.maxF32", false, bool);
+    assert_evals_to!("Num.isInfinite 1.5dec", false, bool);
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn is_finite() {
+    assert_evals_to!("Num.isFinite 1.5f64", true, bool);
+    assert_evals_to!("Num.isFinite (1.0f64 / 0.0)", false, bool);
+    assert_evals_to!("Num.isFinite (0.0f32 / 0.0)", false, bool);
+    assert_evals_to!("Num.isFinite 1.5dec", true, bool);
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn f64_round() {
@@ -1852,7 +1880,7 @@ fn int_add_checked_err() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn int_add_wrap() {
     assert_evals_to!(
         "Num.addWrap 9_223_372_036_854_775_807 1",
@@ -1921,7 +1949,7 @@ fn float_sub_overflow() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn int_sub_checked() {
     assert_evals_to!(
         indoc!(
@@ -2035,7 +2063,37 @@ fn float_negative_mul_overflow() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn int_wrap_narrow_widths() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            x : U8
+            x = 200
+
+            Num.addWrap x 100
+            "#
+        ),
+        44,
+        u8
+    );
+
+    assert_evals_to!(
+        indoc!(
+            r#"
+            x : U8
+            x = 20
+
+            Num.mulWrap x 20
+            "#
+        ),
+        144,
+        u8
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn int_mul_wrap() {
     assert_evals_to!(
         indoc!(
@@ -2049,7 +2107,7 @@ fn int_mul_wrap() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn int_mul_checked() {
     assert_evals_to!(
         indoc!(
@@ -2076,6 +2134,128 @@ fn int_mul_checked() {
     );
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn int_checked_narrow_widths() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            x : U8
+            x = 200
+
+            when Num.addChecked x 55 is
+                Ok v -> v
+                Err Overflow -> 0
+            "#
+        ),
+        255,
+        u8
+    );
+
+    assert_evals_to!(
+        indoc!(
+            r#"
+            x : U8
+            x = 200
+
+            when Num.addChecked x 56 is
+                Ok v -> v
+                Err Overflow -> 0
+            "#
+        ),
+        0,
+        u8
+    );
+
+    assert_evals_to!(
+        indoc!(
+            r#"
+            x : I16
+            x = -32_768
+
+            when Num.subChecked x 1 is
+                Ok v -> v
+                Err Overflow -> 1
+            "#
+        ),
+        1,
+        i16
+    );
+
+    assert_evals_to!(
+        indoc!(
+            r#"
+            x : U32
+            x = 65_536
+
+            when Num.mulChecked x 65_535 is
+                Ok v -> v
+                Err Overflow -> 0
+            "#
+        ),
+        4_294_901_760,
+        u32
+    );
+
+    assert_evals_to!(
+        indoc!(
+            r#"
+            x : U32
+            x = 65_536
+
+            when Num.mulChecked x 65_536 is
+                Ok v -> v
+                Err Overflow -> 0
+            "#
+        ),
+        0,
+        u32
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn u64_checked() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            x : U64
+            x = 5
+
+            when Num.subChecked x 6 is
+                Ok v -> v
+                Err Overflow -> 42
+            "#
+        ),
+        42,
+        u64
+    );
+
+    assert_evals_to!(
+        indoc!(
+            r#"
+            when Num.addChecked Num.maxU64 1 is
+                Ok v -> v
+                Err Overflow -> 42
+            "#
+        ),
+        42,
+        u64
+    );
+
+    assert_evals_to!(
+        indoc!(
+            r#"
+            when Num.mulChecked 4_294_967_296u64 4_294_967_295 is
+                Ok v -> v
+                Err Overflow -> 42
+            "#
+        ),
+        18_446_744_069_414_584_320,
+        u64
+    );
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn float_mul_checked() {
@@ -2163,6 +2343,81 @@ fn shift_right_zf_by() {
     assert_evals_to!("Num.shiftRightZfBy 0b1000_0000u8 12", 0b0000_0000u8, u8);
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn pop_count() {
+    assert_evals_to!("Num.popCount 0b0110_1000u8", 3, u8);
+    assert_evals_to!("Num.popCount -1i16", 16, u8);
+    assert_evals_to!("Num.popCount 0u32", 0, u8);
+    assert_evals_to!("Num.popCount Num.maxU64", 64, u8);
+    assert_evals_to!("Num.popCount Num.minI64", 1, u8);
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn leading_zeros() {
+    assert_evals_to!("Num.leadingZeros 0b0001_0100u8", 3, u8);
+    assert_evals_to!("Num.leadingZeros -1i8", 0, u8);
+    assert_evals_to!("Num.leadingZeros 0u32", 32, u8);
+    assert_evals_to!("Num.leadingZeros 1u64", 63, u8);
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn trailing_zeros() {
+    assert_evals_to!("Num.trailingZeros 0b0001_0100u8", 2, u8);
+    assert_evals_to!("Num.trailingZeros 0i16", 16, u8);
+    assert_evals_to!("Num.trailingZeros 0u32", 32, u8);
+    assert_evals_to!("Num.trailingZeros Num.minI64", 63, u8);
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn bit_counts_128() {
+    assert_evals_to!("Num.popCount Num.maxU128", 128, u8);
+    assert_evals_to!("Num.leadingZeros 1u128", 127, u8);
+    assert_evals_to!("Num.trailingZeros 0i128", 128, u8);
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn rotate_left_by() {
+    assert_evals_to!("Num.rotateLeftBy 0b1000_0011u8 2", 0b0000_1110u8, u8);
+    assert_evals_to!("Num.rotateLeftBy 0b1000_0011u8 10", 0b0000_1110u8, u8);
+    assert_evals_to!("Num.rotateLeftBy -2i16 1", -3, i16);
+    assert_evals_to!(
+        "Num.rotateLeftBy 0x0123_4567_89AB_CDEFu64 16",
+        0x4567_89AB_CDEF_0123u64,
+        u64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn rotate_right_by() {
+    assert_evals_to!("Num.rotateRightBy 0b1000_0011u8 2", 0b1110_0000u8, u8);
+    assert_evals_to!("Num.rotateRightBy 1u32 1", 0x8000_0000u32, u32);
+    assert_evals_to!(
+        "Num.rotateRightBy 0x0123_4567_89AB_CDEFu64 68",
+        0xF012_3456_789A_BCDEu64,
+        u64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn swap_bytes() {
+    assert_evals_to!("Num.swapBytes 0x12u8", 0x12u8, u8);
+    assert_evals_to!("Num.swapBytes 0x1234u16", 0x3412u16, u16);
+    assert_evals_to!("Num.swapBytes 0x1234_5678u32", 0x7856_3412u32, u32);
+    assert_evals_to!(
+        "Num.swapBytes 0x0123_4567_89AB_CDEFu64",
+        0xEFCD_AB89_6745_2301u64,
+        u64
+    );
+    assert_evals_to!("Num.swapBytes 0x00FFi16", -256, i16);
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn shift_right_cast_i8() {
@@ -3261,6 +3516,49 @@ fn num_to_str_dec() {
     );
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn num_to_str_with() {
+    use roc_std::RocStr;
+
+    assert_evals_to!(
+        r#"Num.toStrWith 1234.5678dec { decimals: Exactly 2 }"#,
+        RocStr::from("1234.57"),
+        RocStr
+    );
+    assert_evals_to!(
+        r#"Num.toStrWith -1234567i64 { wholeSep: "," }"#,
+        RocStr::from("-1,234,567"),
+        RocStr
+    );
+    assert_evals_to!(
+        r#"Num.toStrWith 999.96f64 { decimals: Exactly 1, wholeSep: " " }"#,
+        RocStr::from("1 000.0"),
+        RocStr
+    );
+    assert_evals_to!(
+        r#"Num.toStrWith 0.00042dec { notation: Scientific }"#,
+        RocStr::from("4.2e-4"),
+        RocStr
+    );
+    assert_evals_to!(
+        r#"Num.toStrWith 6u8 { decimals: Exactly 2, notation: Scientific }"#,
+        RocStr::from("6.00e0"),
+        RocStr
+    );
+    assert_evals_to!(
+        indoc!(
+            r#"
+            nan = 0f64 / 0f64
+
+            Num.toStrWith nan { decimals: Exactly 2 } == Num.toStr nan
+            "#
+        ),
+        true,
+        bool
+    );
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn u8_addition_greater_than_i8() {
@@ -3316,7 +3614,7 @@ fn u8_mul_greater_than_i8() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn add_saturated() {
     assert_evals_to!(
         indoc!(
@@ -3362,7 +3660,7 @@ fn add_saturated() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn sub_saturated() {
     assert_evals_to!(
         indoc!(
@@ -3406,7 +3704,7 @@ fn sub_saturated() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn mul_saturated() {
     assert_evals_to!(
         indoc!(
@@ -24,6 +24,30 @@ fn crash_literal() {
     );
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[should_panic = r#"User crash with message: "turns out this was fallible" at 5:16-5:45"#]
+fn crash_region() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            getInfallible = \result -> when result is
+                Ok x -> x
+                _ -> crash "turns out this was fallible"
+
+            main =
+                x : [Ok U64, Err Str]
+                x = Err ""
+                getInfallible x
+            "#
+        ),
+        1u64,
+        u64
+    );
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 #[should_panic = r#"User crash with message: "hello crash""#]
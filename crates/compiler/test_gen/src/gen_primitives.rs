@@ -2509,6 +2509,64 @@ fn function_malformed_pattern() {
     );
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn rebinding_in_def_block() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                x = 3
+                x = x + 1
+                f = \{} -> x
+                x = x * 10
+
+                x + f {}
+            "#
+        ),
+        44,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn rebinding_keeps_forward_references() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                x = 1
+                isEven = \n -> if n == 0 then Bool.true else isOdd (n - 1)
+                x = x + 1
+                isOdd = \n -> if n == 0 then Bool.false else isEven (n - 1)
+
+                if isEven 4 then x * 10 else x
+            "#
+        ),
+        20,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn rebinding_with_annotation() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                x : I64
+                x = 3
+
+                x : I64
+                x = x * 2
+
+                x
+            "#
+        ),
+        6,
+        i64
+    );
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 #[ignore = "causes alias analysis panics, should roc_panic"]
@@ -4276,3 +4334,247 @@ fn function_specialization_information_in_lambda_set_thunk_independent_defs() {
         u8
     );
 }
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn early_return_guards() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            clamp = \n ->
+                if n < 0 then return 0
+                if n > 100 then return 100
+
+                n
+
+            main = clamp -5 + clamp 42 + clamp 500
+            "#
+        ),
+        142,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn early_return_from_when_branch() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            orZero = \result ->
+                when result is
+                    Ok n -> n
+                    Err _ -> return 0
+
+            main = orZero (Ok 7) + orZero (Err {})
+            "#
+        ),
+        7,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn early_return_from_definition() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            halfOrZero = \n ->
+                half = if n % 2 == 1 then return 0 else n // 2
+
+                half + 1
+
+            main = halfOrZero 7 + halfOrZero 40
+            "#
+        ),
+        21,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn early_return_from_argument() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            sumOrMax = \a, b ->
+                Num.add a (if b > 100 then return 100 else b)
+
+            main = sumOrMax 1 500 + sumOrMax 3 4
+            "#
+        ),
+        107,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn early_return_with_try() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            parse : Str -> Result U64 [InvalidNumStr, TooBig]
+            parse = \s ->
+                n = (Str.toU64 s)?
+                checked = if n > 1000 then return Err TooBig else n
+
+                Ok (checked + 1)
+
+            main =
+                when (parse "41", parse "4000", parse "x") is
+                    (Ok n, Err TooBig, Err InvalidNumStr) -> n
+                    _ -> 0
+            "#
+        ),
+        42,
+        u64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn try_propagates_err() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            addBoth : Str, Str -> Result U64 [InvalidNumStr]
+            addBoth = \a, b ->
+                x = (Str.toU64 a)?
+                y = (Str.toU64 b)?
+
+                Ok (x + y)
+
+            main =
+                when (addBoth "40" "2", addBoth "40" "two") is
+                    (Ok n, Err InvalidNumStr) -> n
+                    _ -> 0
+            "#
+        ),
+        42,
+        u64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn try_twice() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            unwrapTwice : Result (Result U64 [Inner, Outer]) [Inner, Outer] -> Result U64 [Inner, Outer]
+            unwrapTwice = \x ->
+                n = x??
+
+                Ok (n + 1)
+
+            main =
+                when (unwrapTwice (Ok (Ok 41)), unwrapTwice (Ok (Err Inner)), unwrapTwice (Err Outer)) is
+                    (Ok n, Err Inner, Err Outer) -> n
+                    _ -> 0
+            "#
+        ),
+        42,
+        u64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn try_keeps_forward_references() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            total : Str -> Result U64 [InvalidNumStr]
+            total = \s ->
+                withTax = \k -> if isEven k then k + tax else k
+                n = (Str.toU64 s)?
+                tax = 2
+                isEven = \k -> if k == 0 then Bool.true else isOdd (k - 1)
+                isOdd = \k -> if k == 0 then Bool.false else isEven (k - 1)
+
+                Ok (withTax n)
+
+            main =
+                when (total "40", total "forty") is
+                    (Ok n, Err InvalidNumStr) -> n
+                    _ -> 0
+            "#
+        ),
+        42,
+        u64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn try_rebinding() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            step : U64 -> Result U64 [TooBig]
+            step = \n -> if n > 10 then Err TooBig else Ok (n * 2)
+
+            run : U64 -> Result U64 [TooBig]
+            run = \start ->
+                state = start
+                state = (step state)?
+                state = (step state)?
+
+                Ok state
+
+            main =
+                when (run 3, run 6) is
+                    (Ok n, Err TooBig) -> n
+                    _ -> 0
+            "#
+        ),
+        12,
+        u64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn try_field_of_tried() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            port : Result { port : Result U64 [NoConfig, NoPort] } [NoConfig, NoPort] -> Result U64 [NoConfig, NoPort]
+            port = \config ->
+                p = config?.port?
+
+                Ok p
+
+            main =
+                when (port (Ok { port: Ok 8080 }), port (Ok { port: Err NoPort }), port (Err NoConfig)) is
+                    (Ok p, Err NoPort, Err NoConfig) -> p
+                    _ -> 0
+            "#
+        ),
+        8080,
+        u64
+    );
+}
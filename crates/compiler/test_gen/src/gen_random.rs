@@ -0,0 +1,114 @@
+#[cfg(feature = "gen-llvm")]
+use crate::helpers::llvm::assert_evals_to;
+
+#[cfg(feature = "gen-wasm")]
+use crate::helpers::wasm::assert_evals_to;
+
+use indoc::indoc;
+
+#[allow(unused_imports)]
+use roc_std::RocList;
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn u64_matches_splitmix64() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [Random]
+                provides [main] to "./platform"
+
+            main =
+                first = Random.u64 (Random.fromU64 0)
+                second = Random.u64 first.seed
+
+                [first.value, second.value]
+            "#
+        ),
+        RocList::from_slice(&[16294208416658607535, 7960286522194355700]),
+        RocList<u64>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn split_seeds_differ() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [Random]
+                provides [main] to "./platform"
+
+            main =
+                { left, right } = Random.split (Random.fromU64 1)
+
+                (Random.u64 left).value != (Random.u64 right).value
+            "#
+        ),
+        true,
+        bool
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn below_stays_in_range() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [Random]
+                provides [main] to "./platform"
+
+            main =
+                rolls = Random.list (Random.fromU64 7) 1000 \seed -> Random.below seed 6
+
+                List.all rolls.value \roll -> roll < 6
+            "#
+        ),
+        true,
+        bool
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn f64_is_below_one() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [Random]
+                provides [main] to "./platform"
+
+            main =
+                floats = Random.list (Random.fromU64 7) 1000 Random.f64
+
+                List.all floats.value \float -> float >= 0 && float < 1
+            "#
+        ),
+        true,
+        bool
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn test_seed_is_zero_outside_tests() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [Random]
+                provides [main] to "./platform"
+
+            main =
+                (Random.u64 (Random.testSeed {})).value == (Random.u64 (Random.fromU64 0)).value
+            "#
+        ),
+        true,
+        bool
+    );
+}
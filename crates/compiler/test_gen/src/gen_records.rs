@@ -833,6 +833,20 @@ fn accessor_single_element_record() {
     );
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn accessor_in_pipeline() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                { foo: 4, bar: "foo" } |> .foo
+                "#
+        ),
+        4,
+        i64
+    );
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn update_record() {
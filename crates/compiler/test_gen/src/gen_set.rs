@@ -310,3 +310,44 @@ fn resolve_set_eq_issue_4671() {
         bool
     );
 }
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn element_hashed_with_its_own_hasher() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            Xor := U64 has [Hasher {
+                addBytes: \hasher, bytes -> List.walk bytes hasher \h, byte -> add h (Num.toU64 byte),
+                addU8: \hasher, n -> add hasher (Num.toU64 n),
+                addU16: \hasher, n -> add hasher (Num.toU64 n),
+                addU32: \hasher, n -> add hasher (Num.toU64 n),
+                addU64: add,
+                addU128: \hasher, n -> add hasher (Num.toU64 n),
+                complete: \@Xor state -> state,
+            }]
+
+            add : Xor, U64 -> Xor
+            add = \@Xor state, n -> @Xor (Num.bitwiseXor (Num.shiftLeftBy state 5) n)
+
+            Id := I64 has [Eq, Hash { hash: hashId }]
+
+            hashId = \hasher, @Id n -> Hash.hashWith hasher n (@Xor 7)
+
+            main =
+                Set.empty {}
+                |> Set.insert (@Id 1)
+                |> Set.insert (@Id 2)
+                |> Set.insert (@Id 1)
+                |> Set.remove (@Id 2)
+                |> Set.insert (@Id 3)
+                |> Set.toList
+                |> List.map \@Id n -> n
+            "#
+        ),
+        RocList::from_slice(&[1, 3]),
+        RocList<i64>
+    );
+}
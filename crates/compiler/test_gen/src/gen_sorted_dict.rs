@@ -0,0 +1,122 @@
+#[cfg(feature = "gen-llvm")]
+use crate::helpers::llvm::assert_evals_to;
+
+#[cfg(feature = "gen-wasm")]
+use crate::helpers::wasm::assert_evals_to;
+
+use indoc::indoc;
+
+#[allow(unused_imports)]
+use roc_std::{RocList, RocStr};
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn keys_in_order() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [SortedDict]
+                provides [main] to "./platform"
+
+            main =
+                # Enough keys for the tree to have several levels
+                List.range { start: At 0, end: Before 1000 }
+                |> List.map \n -> Num.rem (n * 7919) 1000
+                |> List.walk (SortedDict.empty Num.compare) \dict, n -> SortedDict.insert dict n {}
+                |> SortedDict.keys
+                |> Bool.isEq (List.range { start: At 0, end: Before 1000 })
+            "#
+        ),
+        true,
+        bool
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn remove_keeps_the_rest() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [SortedDict]
+                provides [main] to "./platform"
+
+            main =
+                full =
+                    List.range { start: At 0, end: Before 500 }
+                    |> List.walk (SortedDict.empty Num.compare) \dict, n -> SortedDict.insert dict n (n * 2)
+
+                removed =
+                    List.range { start: At 0, end: Before 500, step: 3 }
+                    |> List.walk full SortedDict.remove
+
+                { count: SortedDict.len removed, total: SortedDict.walk removed 0 \total, _, v -> total + v }
+            "#
+        ),
+        // 333 of the 500 keys are left, with values 2 * (0 + ... + 499) - 2 * 3 * (0 + ... + 166)
+        (333, 166_334),
+        (usize, i64)
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn first_last_and_range() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [SortedDict]
+                provides [main] to "./platform"
+
+            main =
+                dict =
+                    List.range { start: At 1, end: At 100 }
+                    |> List.walk (SortedDict.empty \a, b -> Num.compare b a) \state, n -> SortedDict.insert state n (Num.toStr n)
+
+                inRange =
+                    SortedDict.range dict { start: At 52, end: At 49 }
+                    |> List.map \T _ v -> v
+
+                firstAndLast =
+                    when T (SortedDict.first dict) (SortedDict.last dict) is
+                        T (Ok (T _ firstValue)) (Ok (T _ lastValue)) -> [firstValue, lastValue]
+                        _ -> []
+
+                List.concat firstAndLast inRange
+            "#
+        ),
+        RocList::from_slice(&[
+            RocStr::from("100"),
+            RocStr::from("1"),
+            RocStr::from("52"),
+            RocStr::from("51"),
+            RocStr::from("50"),
+            RocStr::from("49"),
+        ]),
+        RocList<RocStr>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn sorted_set_walk_backwards() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [SortedSet]
+                provides [main] to "./platform"
+
+            main =
+                SortedSet.fromList [5, 3, 9, 1, 3, 7] Num.compare
+                |> SortedSet.remove 9
+                |> SortedSet.walkBackwards [] List.append
+            "#
+        ),
+        RocList::from_slice(&[7, 5, 3, 1]),
+        RocList<i64>
+    );
+}
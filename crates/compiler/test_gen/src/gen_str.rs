@@ -1584,6 +1584,62 @@ fn str_to_dec() {
     );
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn str_to_f64_errors() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            describe = \str ->
+                when Str.toF64 str is
+                    Ok _ -> "Ok"
+                    Err EmptyStr -> "EmptyStr"
+                    Err NoDigits -> "NoDigits"
+                    Err InvalidChar -> "InvalidChar"
+                    Err OutOfRange -> "OutOfRange"
+
+            List.map ["1.5e3", "", "-.", "1,5", "1e400"] describe
+            "#
+        ),
+        RocList::from_slice(&[
+            RocStr::from("Ok"),
+            RocStr::from("EmptyStr"),
+            RocStr::from("NoDigits"),
+            RocStr::from("InvalidChar"),
+            RocStr::from("OutOfRange"),
+        ]),
+        RocList<RocStr>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn str_to_dec_errors() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            describe = \str ->
+                when Str.toDec str is
+                    Ok _ -> "Ok"
+                    Err EmptyStr -> "EmptyStr"
+                    Err NoDigits -> "NoDigits"
+                    Err InvalidChar -> "InvalidChar"
+                    Err OutOfRange -> "OutOfRange"
+                    Err TooManyDecimals -> "TooManyDecimals"
+
+            List.map ["-0.25", "1e5", "200000000000000000000", "0.0000000000000000001"] describe
+            "#
+        ),
+        RocList::from_slice(&[
+            RocStr::from("Ok"),
+            RocStr::from("InvalidChar"),
+            RocStr::from("OutOfRange"),
+            RocStr::from("TooManyDecimals"),
+        ]),
+        RocList<RocStr>
+    );
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm"))]
 fn issue_2811() {
@@ -1885,6 +1941,150 @@ fn str_walk_scalars() {
     );
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn str_to_utf16() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            Str.toUtf16 "R鹏🐦"
+            "#
+        ),
+        RocList::from_slice(&[82u16, 40527, 0xD83D, 0xDC26]),
+        RocList<u16>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn str_from_utf16() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            when Str.fromUtf16 [82, 40527, 0xD83D, 0xDC26] is
+                Ok string -> string
+                Err _ -> "<error>"
+            "#
+        ),
+        RocStr::from("R鹏🐦"),
+        RocStr
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn str_from_utf16_unpaired_surrogate() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            describe = \units ->
+                when Str.fromUtf16 units is
+                    Ok _ -> 99
+                    Err (UnpairedSurrogate index) -> index
+
+            List.map [[82, 0xDC26, 99], [82, 0xD83D], [0xD83D, 82]] describe
+            "#
+        ),
+        RocList::from_slice(&[1usize, 1, 0]),
+        RocList<usize>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn str_from_utf16_lossy() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            Str.fromUtf16Lossy [82, 0xD83D, 0xD83D, 0xDC26, 0xDC26]
+            "#
+        ),
+        RocStr::from("R\u{FFFD}🐦\u{FFFD}"),
+        RocStr
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn str_utf32_round_trip() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            when Str.fromUtf32 (Str.toUtf32 "R鹏🐦") is
+                Ok string -> string
+                Err _ -> "<error>"
+            "#
+        ),
+        RocStr::from("R鹏🐦"),
+        RocStr
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn str_from_utf32_invalid_scalar() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            describe = \scalars ->
+                when Str.fromUtf32 scalars is
+                    Ok _ -> 99
+                    Err (InvalidScalar index) -> index
+
+            List.map [[82, 0xD83D], [0x110000]] describe
+            "#
+        ),
+        RocList::from_slice(&[1usize, 0]),
+        RocList<usize>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn str_matches() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            List.map ["roc-lang.org", "roc lang", ""] \str -> Str.matches str "[a-z-]+(\\.[a-z]+)*"
+            "#
+        ),
+        RocList::from_slice(&[true, false, false]),
+        RocList<bool>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn str_capture() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            when Str.capture "roc@roc-lang.org" "(\\w+)@([\\w-]+)\\.org" is
+                Ok groups -> groups
+                Err NoMatch -> []
+            "#
+        ),
+        RocList::from_slice(&[RocStr::from("roc"), RocStr::from("roc-lang")]),
+        RocList<RocStr>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm"))]
+fn str_capture_no_match() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            when Str.capture "roc" "(a)|(b)" is
+                Ok _ -> "matched"
+                Err NoMatch -> "no match"
+            "#
+        ),
+        RocStr::from("no match"),
+        RocStr
+    );
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm-wasm"))]
 fn llvm_wasm_str_layout() {
@@ -1055,6 +1055,39 @@ fn phantom_polymorphic_record() {
     );
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn phantom_opaque_units() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                app "test" provides [main] to "./platform"
+
+                Length unit := F64
+
+                Meters := {}
+                Feet := {}
+
+                meters : F64 -> Length Meters
+                meters = \x -> @Length x
+
+                feet : F64 -> Length Feet
+                feet = \x -> @Length x
+
+                add : Length unit, Length unit -> Length unit
+                add = \@Length a, @Length b -> @Length (a + b)
+
+                toF64 : Length unit -> F64
+                toF64 = \@Length x -> x
+
+                main = toF64 (add (meters 1.5) (meters 2)) + toF64 (add (feet 1) (feet 1))
+                "#
+        ),
+        5.5,
+        f64
+    );
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn result_never() {
@@ -0,0 +1,149 @@
+#[cfg(feature = "gen-llvm")]
+use crate::helpers::llvm::assert_evals_to;
+
+#[cfg(feature = "gen-wasm")]
+use crate::helpers::wasm::assert_evals_to;
+
+use indoc::indoc;
+
+#[allow(unused_imports)]
+use roc_std::{RocList, RocStr};
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn to_iso8601() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [Time]
+                provides [main] to "./platform"
+
+            main =
+                [0, -1, 951_827_696_250, 1_000_000_000_000_001]
+                |> List.map \millis -> Time.toIso8601 (Time.fromMillisSinceEpoch millis)
+            "#
+        ),
+        RocList::from_slice(&[
+            RocStr::from("1970-01-01T00:00:00Z"),
+            RocStr::from("1969-12-31T23:59:59.999Z"),
+            RocStr::from("2000-02-29T12:34:56.250Z"),
+            RocStr::from("+33658-09-27T01:46:40.001Z"),
+        ]),
+        RocList<RocStr>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn to_iso8601_nanoseconds() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [Time]
+                provides [main] to "./platform"
+
+            main =
+                Time.toIso8601 (Time.fromNanosSinceEpoch 1_000_001_500)
+            "#
+        ),
+        RocStr::from("1970-01-01T00:00:01.000001500Z"),
+        RocStr
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn from_iso8601() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [Time]
+                provides [main] to "./platform"
+
+            main =
+                ["2000-01-01T05:30:00+05:30", "1969-12-31 23:59:59.9999999999Z", "2000-01-01T00:00:00-01:00"]
+                |> List.map \str ->
+                    when Time.fromIso8601 str is
+                        Ok utc -> Time.toNanosSinceEpoch utc
+                        Err _ -> 0
+            "#
+        ),
+        RocList::from_slice(&[946_684_800_000_000_000i128, -1, 946_688_400_000_000_000]),
+        RocList<i128>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn from_iso8601_invalid() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [Time]
+                provides [main] to "./platform"
+
+            main =
+                ["2023-13-01T00:00:00Z", "2024-02-30T00:00:00Z", "2023-01-01T24:00:00Z", "2023-01-01T00:00:00", "2023-01-01"]
+                |> List.map \str ->
+                    when Time.fromIso8601 str is
+                        Ok _ -> 99
+                        Err (InvalidIso8601 index) -> index
+            "#
+        ),
+        RocList::from_slice(&[5usize, 8, 11, 19, 10]),
+        RocList<usize>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn duration_arithmetic() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [Time]
+                provides [main] to "./platform"
+
+            main =
+                start = Time.fromMillisSinceEpoch 1_000
+                end = Time.add start (Time.addDurations (Time.days 2) (Time.minutes 3))
+                back = Time.sub end (Time.seconds 60)
+
+                [
+                    Time.toSeconds (Time.since end start),
+                    Time.toMilliseconds (Time.since start end),
+                    Time.toSeconds (Time.since back start),
+                ]
+            "#
+        ),
+        RocList::from_slice(&[172_980i128, -172_980_000, 172_920]),
+        RocList<i128>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn compare() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test"
+                imports [Time]
+                provides [main] to "./platform"
+
+            main =
+                earlier = Time.epoch
+                later = Time.add earlier (Time.nanoseconds 1)
+
+                [Time.isBefore earlier later, Time.isAfter earlier later, later == Time.fromNanosSinceEpoch 1]
+            "#
+        ),
+        RocList::from_slice(&[true, false, true]),
+        RocList<bool>
+    );
+}
@@ -58,6 +58,7 @@ pub fn helper(
         palette: roc_reporting::report::DEFAULT_PALETTE,
         threading: Threading::Single,
         exec_mode: ExecutionMode::Executable,
+        ..Default::default()
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -154,7 +155,10 @@ pub fn helper(
         for problem in can_problems.into_iter() {
             // Ignore "unused" problems
             match problem {
-                UnusedDef(_, _) | UnusedArgument(_, _, _, _) | UnusedModuleImport(_, _) => {
+                UnusedDef(_, _)
+                | UnusedArgument(_, _, _, _)
+                | UnusedModuleImport(_, _)
+                | Rebinding { .. } => {
                     delayed_errors.push(problem);
                     continue;
                 }
@@ -191,6 +195,8 @@ pub fn helper(
         exposed_to_host: exposed_to_host.values.keys().copied().collect(),
         lazy_literals,
         generate_allocators: true, // Needed for testing, since we don't have a platform
+        provided_allocator_fns: &[],
+        def_locations: None,
     };
 
     let target = target_lexicon::Triple::host();
@@ -240,6 +246,10 @@ pub fn helper(
 
     let lib = unsafe { Library::new(path) }.expect("failed to load shared library");
 
+    if roc_build::perf_map::is_enabled() {
+        roc_build::perf_map::register_library(&dylib_path, &lib);
+    }
+
     (main_fn_name, delayed_errors, lib)
 }
 
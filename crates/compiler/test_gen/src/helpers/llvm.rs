@@ -9,9 +9,11 @@ use roc_gen_llvm::llvm::externs::add_default_roc_externs;
 use roc_gen_llvm::{llvm::build::LlvmBackendMode, run_roc::RocCallResult};
 use roc_load::{EntryPoint, ExecutionMode, LoadConfig, LoadMonomorphizedError, Threading};
 use roc_mono::ir::{CrashTag, OptLevel, SingleEntryPoint};
+use roc_mono::thread_escape::ThreadSharedLayouts;
 use roc_packaging::cache::RocCacheDir;
 use roc_region::all::LineInfo;
 use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
+use roc_std::RocPanicRegion;
 use roc_utils::zig;
 use target_lexicon::Triple;
 
@@ -74,6 +76,7 @@ fn create_llvm_module<'a>(
         palette: DEFAULT_PALETTE,
         threading: Threading::Single,
         exec_mode: ExecutionMode::Executable,
+        ..Default::default()
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -88,6 +91,7 @@ fn create_llvm_module<'a>(
         Ok(x) => x,
         Err(LoadMonomorphizedError::LoadingProblem(roc_load::LoadingProblem::FormattedReport(
             report,
+            _,
         ))) => {
             println!("{}", report);
             panic!();
@@ -133,6 +137,7 @@ fn create_llvm_module<'a>(
                 UnusedDef(_, _)
                 | UnusedArgument(_, _, _, _)
                 | UnusedModuleImport(_, _)
+                | Rebinding { .. }
                 | RuntimeError(_)
                 | UnsupportedPattern(_, _)
                 | ExposedButNotDefined(_) => {
@@ -226,6 +231,13 @@ fn create_llvm_module<'a>(
         mode: config.mode,
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
+        thread_shared_layouts: ThreadSharedLayouts::from_procs(
+            &layout_interner,
+            procedures.values(),
+        ),
+        fixtures: MutSet::default(),
+        batch_effects: false,
+        fast_math: false,
     };
 
     // strip Zig debug stuff
@@ -341,6 +353,33 @@ pub fn helper<'a>(
     (main_fn_name, delayed_errors, lib)
 }
 
+/// The LLVM IR of the app's own procedures, before it's optimized, e.g. to check what they call
+#[allow(dead_code)]
+pub fn app_procs_llvm_ir(src: &str) -> String {
+    let arena = bumpalo::Bump::new();
+    let context = inkwell::context::Context::create();
+    let config = HelperConfig {
+        mode: LlvmBackendMode::GenTest,
+        add_debug_info: false,
+        ignore_problems: false,
+        opt_level: OPT_LEVEL,
+    };
+
+    let (_main_fn_name, _delayed_errors, module) =
+        create_llvm_module(&arena, src, config, &context, &Triple::host());
+
+    module
+        .get_functions()
+        .filter(|function| {
+            function
+                .get_name()
+                .to_string_lossy()
+                .starts_with(roc_module::ident::ModuleName::APP)
+        })
+        .map(|function| function.print_to_string().to_string())
+        .collect()
+}
+
 fn annotate_with_debug_info<'ctx>(
     module: &Module<'ctx>,
     context: &'ctx inkwell::context::Context,
@@ -557,7 +596,7 @@ macro_rules! assert_wasm_evals_to {
 pub fn try_run_lib_function<T>(
     main_fn_name: &str,
     lib: &libloading::Library,
-) -> Result<T, (String, CrashTag)> {
+) -> Result<T, (String, CrashTag, Option<RocPanicRegion>)> {
     unsafe {
         let main: libloading::Symbol<unsafe extern "C" fn(*mut RocCallResult<T>)> = lib
             .get(main_fn_name.as_bytes())
@@ -568,7 +607,10 @@ pub fn try_run_lib_function<T>(
         let mut main_result = MaybeUninit::uninit();
         main(main_result.as_mut_ptr());
 
-        main_result.assume_init().into()
+        let main_result = main_result.assume_init();
+        let region = main_result.panic_region();
+
+        Result::from(main_result).map_err(|(msg, tag)| (msg, tag, region))
     }
 }
 
@@ -609,10 +651,14 @@ where
             #[cfg(windows)]
             std::mem::forget(given);
         }
-        Err((msg, tag)) => match tag {
-            CrashTag::Roc => panic!(r#"Roc failed with message: "{}""#, msg),
-            CrashTag::User => panic!(r#"User crash with message: "{}""#, msg),
-        },
+        Err((msg, tag, region)) => {
+            let at = region.map_or(String::new(), |region| format!(" at {}", region));
+
+            match tag {
+                CrashTag::Roc => panic!(r#"Roc failed with message: "{}"{}"#, msg, at),
+                CrashTag::User => panic!(r#"User crash with message: "{}"{}"#, msg, at),
+            }
+        }
     }
 }
 
@@ -32,3 +32,27 @@ pub unsafe fn roc_realloc(
 pub unsafe fn roc_dealloc(c_ptr: *mut c_void, _alignment: u32) {
     libc::free(c_ptr)
 }
+
+/// # Safety
+/// The Roc application needs this if it uses `List.mapConcurrent`.
+#[no_mangle]
+pub unsafe extern "C" fn roc_parallel_for(
+    count: usize,
+    task: extern "C" fn(*mut c_void, usize, usize),
+    context: *mut c_void,
+) {
+    // At least two, so that tests share values between threads even on a single core
+    let threads = std::thread::available_parallelism().map_or(2, |threads| threads.get().max(2));
+    let chunk_size = ((count + threads - 1) / threads).max(1);
+
+    // Raw pointers aren't `Send`, but the task is fine with being called from other threads.
+    let context = context as usize;
+
+    std::thread::scope(|scope| {
+        for start in (0..count).step_by(chunk_size) {
+            let end = (start + chunk_size).min(count);
+
+            scope.spawn(move || task(context as *mut c_void, start, end));
+        }
+    });
+}
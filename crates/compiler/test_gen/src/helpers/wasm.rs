@@ -7,7 +7,7 @@ use roc_gen_wasm::DEBUG_SETTINGS;
 use roc_load::{ExecutionMode, LoadConfig, Threading};
 use roc_packaging::cache::RocCacheDir;
 use roc_reporting::report::DEFAULT_PALETTE_HTML;
-use roc_std::RocStr;
+use roc_std::{RocPanicRegion, RocStr};
 use roc_wasm_interp::{wasi, ImportDispatcher, Instance, WasiDispatcher};
 use roc_wasm_module::{Export, ExportType, Value, WasmModule};
 use std::marker::PhantomData;
@@ -92,6 +92,7 @@ fn compile_roc_to_wasm_bytes<'a, T: Wasm32Result>(
         palette: DEFAULT_PALETTE_HTML,
         threading: Threading::Single,
         exec_mode: ExecutionMode::Executable,
+        ..Default::default()
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -127,6 +128,7 @@ fn compile_roc_to_wasm_bytes<'a, T: Wasm32Result>(
         module_id,
         exposed_to_host,
         stack_bytes: roc_gen_wasm::Env::DEFAULT_STACK_BYTES,
+        features: roc_gen_wasm::WasmFeatures::default(),
     };
 
     let host_module = roc_gen_wasm::parse_host(env.arena, host_bytes).unwrap_or_else(|e| {
@@ -199,12 +201,27 @@ impl<'a> ImportDispatcher for TestDispatcher<'a> {
         if module_name == wasi::MODULE_NAME {
             self.wasi.dispatch(function_name, arguments, memory)
         } else if module_name == "env" && function_name == "send_panic_msg_to_rust" {
-            let msg_ptr = arguments[0].expect_i32().unwrap();
+            let payload_ptr = arguments[0].expect_i32().unwrap() as u32;
             let tag = arguments[1].expect_i32().unwrap();
-            let roc_msg = RocStr::decode(memory, msg_ptr as _);
+            let roc_msg = RocStr::decode(memory, payload_ptr);
+
+            // the source region comes after the message
+            let region_ptr = payload_ptr + 12;
+            let region = RocPanicRegion {
+                start_line: u32::decode(memory, region_ptr),
+                start_column: u32::decode(memory, region_ptr + 4),
+                end_line: u32::decode(memory, region_ptr + 8),
+                end_column: u32::decode(memory, region_ptr + 12),
+            };
+            let at = if region.is_known() {
+                format!(" at {}", region)
+            } else {
+                String::new()
+            };
+
             let msg = match tag {
-                0 => format!(r#"Roc failed with message: "{}""#, roc_msg),
-                1 => format!(r#"User crash with message: "{}""#, roc_msg),
+                0 => format!(r#"Roc failed with message: "{}"{}"#, roc_msg, at),
+                1 => format!(r#"User crash with message: "{}"{}"#, roc_msg, at),
                 tag => format!(r#"Got an invald panic tag: "{}""#, tag),
             };
             panic!("{}", msg)
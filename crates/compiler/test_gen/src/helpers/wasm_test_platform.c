@@ -134,6 +134,16 @@ void roc_panic(void* msg, unsigned int tag_id)
 
 //--------------------------
 
+void roc_oom(size_t size, unsigned int alignment)
+{
+#if ENABLE_PRINTF
+    fprintf(stderr, "roc_oom: out of memory allocating %zd bytes with alignment %d\n", size, alignment);
+#endif
+    exit(101);
+}
+
+//--------------------------
+
 void roc_memcpy(void *dest, const void *src, size_t n)
 {
     memcpy(dest, src, n);
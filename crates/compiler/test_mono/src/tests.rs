@@ -76,10 +76,7 @@ fn promote_expr_to_module(src: &str) -> String {
     buffer
 }
 
-fn compiles_to_ir(test_name: &str, src: &str, mode: &str, no_check: bool) {
-    use roc_packaging::cache::RocCacheDir;
-    use std::path::PathBuf;
-
+fn compiles_to_ir(test_name: &str, src: &str, mode: &str, no_check: bool, src_dir: &str) {
     let exec_mode = match mode {
         "exec" => ExecutionMode::Executable,
         "test" => ExecutionMode::Test,
@@ -88,9 +85,6 @@ fn compiles_to_ir(test_name: &str, src: &str, mode: &str, no_check: bool) {
 
     let arena = &Bump::new();
 
-    let filename = PathBuf::from("Test.roc");
-    let src_dir = PathBuf::from("fake/test/path");
-
     let module_src;
     let temp;
     if src.starts_with("app") || src.starts_with("interface") {
@@ -102,32 +96,7 @@ fn compiles_to_ir(test_name: &str, src: &str, mode: &str, no_check: bool) {
         module_src = &temp;
     }
 
-    let load_config = LoadConfig {
-        target_info: TARGET_INFO,
-        threading: Threading::Single,
-        render: roc_reporting::report::RenderTarget::Generic,
-        palette: roc_reporting::report::DEFAULT_PALETTE,
-        exec_mode,
-    };
-    let loaded = roc_load::load_and_monomorphize_from_str(
-        arena,
-        filename,
-        module_src,
-        src_dir,
-        RocCacheDir::Disallowed,
-        load_config,
-    );
-
-    let mut loaded = match loaded {
-        Ok(x) => x,
-        Err(LoadMonomorphizedError::LoadingProblem(roc_load::LoadingProblem::FormattedReport(
-            report,
-        ))) => {
-            println!("{}", report);
-            panic!();
-        }
-        Err(e) => panic!("{:?}", e),
-    };
+    let mut loaded = load_mono(arena, module_src, exec_mode, src_dir);
 
     use roc_load::MonomorphizedModule;
     let MonomorphizedModule {
@@ -157,6 +126,48 @@ fn compiles_to_ir(test_name: &str, src: &str, mode: &str, no_check: bool) {
     verify_procedures(test_name, layout_interner, procedures, main_fn_symbol);
 }
 
+fn load_mono<'a>(
+    arena: &'a Bump,
+    module_src: &'a str,
+    exec_mode: ExecutionMode,
+    src_dir: &str,
+) -> roc_load::MonomorphizedModule<'a> {
+    use roc_packaging::cache::RocCacheDir;
+    use std::path::PathBuf;
+
+    let filename = PathBuf::from("Test.roc");
+    let src_dir = PathBuf::from(src_dir);
+
+    let load_config = LoadConfig {
+        target_info: TARGET_INFO,
+        threading: Threading::Single,
+        render: roc_reporting::report::RenderTarget::Generic,
+        palette: roc_reporting::report::DEFAULT_PALETTE,
+        exec_mode,
+        ..Default::default()
+    };
+    let loaded = roc_load::load_and_monomorphize_from_str(
+        arena,
+        filename,
+        module_src,
+        src_dir,
+        RocCacheDir::Disallowed,
+        load_config,
+    );
+
+    match loaded {
+        Ok(x) => x,
+        Err(LoadMonomorphizedError::LoadingProblem(roc_load::LoadingProblem::FormattedReport(
+            report,
+            _,
+        ))) => {
+            println!("{}", report);
+            panic!();
+        }
+        Err(e) => panic!("{:?}", e),
+    }
+}
+
 fn check_procedures<'a>(
     arena: &'a Bump,
     interns: &Interns,
@@ -1357,6 +1368,43 @@ fn specialize_ability_call() {
     )
 }
 
+#[test]
+fn phantom_params_are_erased() {
+    let arena = &Bump::new();
+    let loaded = load_mono(
+        arena,
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            Id a := U64
+
+            toU64 : Id a -> U64
+            toU64 = \@Id n -> n
+
+            strId : Id Str
+            strId = @Id 1
+
+            u8Id : Id U8
+            u8Id = @Id 2
+
+            main = toU64 strId + toU64 u8Id
+            "#
+        ),
+        ExecutionMode::Executable,
+        "fake/test/path",
+    );
+
+    // `Id Str` and `Id U8` both have the layout of a U64, so one `toU64` serves both.
+    let specializations = loaded
+        .procedures
+        .keys()
+        .filter(|(symbol, _)| symbol.as_str(&loaded.interns) == "toU64")
+        .count();
+
+    assert_eq!(specializations, 1);
+}
+
 #[mono_test]
 fn opaque_assign_to_symbol() {
     indoc!(
@@ -2178,6 +2226,20 @@ fn toplevel_accessor_fn_thunk() {
     )
 }
 
+#[mono_test]
+fn record_accessor_applied_directly() {
+    r#"
+    .b { a: 1u8, b: 2u8 }
+    "#
+}
+
+#[mono_test]
+fn record_accessor_passed_to_list_map() {
+    r#"
+    List.map [{ a: 1u8, b: 2u8 }] .b
+    "#
+}
+
 #[mono_test]
 fn list_one_vs_one_spread_issue_4685() {
     indoc!(
@@ -2571,3 +2633,39 @@ fn recursive_lambda_set_has_nested_non_recursive_lambda_sets_issue_5026() {
         "#
     )
 }
+
+#[mono_test(src_dir = "../../cli_testing_examples/batched-effects")]
+fn batched_effects_gathered_into_one_procedure() {
+    indoc!(
+        r#"
+        app "test"
+            packages { pf: "platform/main.roc" }
+            imports [pf.Task.{ await }]
+            provides [main] to pf
+
+        main =
+            {} <- await (Task.putLine "one")
+            {} <- await (Task.putLine "two")
+
+            Task.putLine "three"
+        "#
+    )
+}
+
+#[mono_test(src_dir = "../../cli_testing_examples/batched-effects")]
+fn batched_effects_waiting_on_a_result() {
+    indoc!(
+        r#"
+        app "test"
+            packages { pf: "platform/main.roc" }
+            imports [pf.Task.{ await }]
+            provides [main] to pf
+
+        main =
+            {} <- await (Task.putLine "What's your name?")
+            name <- await Task.getLine
+
+            Task.putLine name
+        "#
+    )
+}
@@ -0,0 +1,153 @@
+//! Helpers for fuzzing the parser and formatter outside of cargo-fuzz: mutating existing Roc
+//! files, checking that they survive parse → format → reparse, and shrinking the ones that don't.
+use crate::test_helpers::Input;
+use bumpalo::Bump;
+use std::panic::{self, AssertUnwindSafe};
+
+/// The same tokens cargo-fuzz is given with `-dict`
+const DICT: &str = include_str!("../fuzz/dict.txt");
+
+fn dict_tokens() -> Vec<&'static str> {
+    DICT.lines()
+        .filter_map(|line| line.trim().strip_prefix('"')?.strip_suffix('"'))
+        .collect()
+}
+
+/// A small deterministic mutator, so that a failing run can be reproduced from its seed
+pub struct Mutator {
+    state: u64,
+    tokens: Vec<&'static str>,
+}
+
+impl Mutator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            // xorshift gets stuck on 0
+            state: seed | 1,
+            tokens: dict_tokens(),
+        }
+    }
+
+    fn next(&mut self) -> u64 {
+        // xorshift64
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        match n {
+            0 => 0,
+            n => (self.next() % n as u64) as usize,
+        }
+    }
+
+    /// Applies a few random edits to the input: deleting, duplicating, or overwriting a range of
+    /// bytes, or inserting a token that the parser cares about.
+    pub fn mutate(&mut self, input: &[u8]) -> Vec<u8> {
+        let mut bytes = input.to_vec();
+
+        for _ in 0..1 + self.below(4) {
+            let start = self.below(bytes.len() + 1);
+            let end = (start + self.below(16)).min(bytes.len());
+
+            match self.below(5) {
+                0 => {
+                    bytes.drain(start..end);
+                }
+                1 => {
+                    let range = bytes[start..end].to_vec();
+                    bytes.splice(start..start, range);
+                }
+                2 => {
+                    for byte in &mut bytes[start..end] {
+                        *byte = b" \n()[]{}\\,.:=|#\"'x1_"[self.below(20)];
+                    }
+                }
+                3 => {
+                    let token = self.tokens[self.below(self.tokens.len())];
+                    bytes.splice(start..start, token.bytes());
+                }
+                _ => {
+                    let newline_and_indent: &[u8] = match self.below(3) {
+                        0 => b"\n",
+                        1 => b"\n    ",
+                        _ => b"\n        ",
+                    };
+                    bytes.splice(start..start, newline_and_indent.iter().copied());
+                }
+            }
+        }
+
+        bytes
+    }
+}
+
+/// Checks that the input, if it parses at all, formats to something that parses to the same
+/// AST, and that formatting that again doesn't change it. Returns the failure message otherwise.
+pub fn check_round_trip(input: Input) -> Result<(), String> {
+    {
+        let arena = Bump::new();
+
+        if input.parse_in(&arena).is_err() {
+            return Ok(());
+        }
+    }
+
+    let previous_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| input.check_invariants(|_| (), true)));
+
+    panic::set_hook(previous_hook);
+
+    result.map_err(|payload| {
+        if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else {
+            "the formatter panicked".to_string()
+        }
+    })
+}
+
+/// Shrinks a failing input by repeatedly removing lines, and then characters, for as long as
+/// it keeps failing.
+pub fn minimize(input: &str, still_fails: impl Fn(&str) -> bool) -> String {
+    let mut lines: Vec<&str> = input.split_inclusive('\n').collect();
+    let mut chunk = lines.len() / 2;
+
+    while chunk > 0 {
+        let mut start = 0;
+
+        while start < lines.len() {
+            let end = (start + chunk).min(lines.len());
+            let candidate: String = lines[..start].concat() + &lines[end..].concat();
+
+            if still_fails(&candidate) {
+                lines.drain(start..end);
+            } else {
+                start = end;
+            }
+        }
+
+        chunk /= 2;
+    }
+
+    let mut chars: Vec<char> = lines.concat().chars().collect();
+    let mut index = 0;
+
+    while index < chars.len() {
+        let candidate: String = chars[..index].iter().chain(&chars[index + 1..]).collect();
+
+        if still_fails(&candidate) {
+            chars.remove(index);
+        } else {
+            index += 1;
+        }
+    }
+
+    chars.into_iter().collect()
+}
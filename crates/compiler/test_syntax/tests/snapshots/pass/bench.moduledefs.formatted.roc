@@ -0,0 +1,3 @@
+bench = 5
+
+bench List.len [bench]
@@ -0,0 +1,3 @@
+bench = 5
+
+bench 1
@@ -0,0 +1,3 @@
+expect-always 1 == 1
+
+4
@@ -0,0 +1,3 @@
+pattern Ok2 x = Ok (Ok x)
+
+42
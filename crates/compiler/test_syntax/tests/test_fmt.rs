@@ -5582,6 +5582,101 @@ mod test_fmt {
         ));
     }
 
+    #[test]
+    fn expect_always() {
+        expr_formats_same(indoc!(
+            r#"
+            x = 5
+
+            expect-always x > 0
+
+            expect-always
+                foo bar
+                |> baz
+
+            42
+            "#
+        ));
+    }
+
+    #[test]
+    fn bench() {
+        module_formats_same(indoc!(
+            r#"
+                interface Foo exposes [] imports []
+
+                bench List.sortAsc bigList
+
+                bench
+                    List.range { start: At 0, end: Length 100 }
+                    |> List.map Num.toStr
+
+                bench = 5
+            "#
+        ));
+
+        module_formats_to(
+            indoc!(
+                r#"
+                interface Foo exposes [] imports []
+
+                bench   List.sortAsc  bigList
+                "#
+            ),
+            indoc!(
+                r#"
+                interface Foo exposes [] imports []
+
+                bench List.sortAsc bigList
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn bench_as_a_name() {
+        expr_formats_same(indoc!(
+            r#"
+            bench = \x -> x
+
+            bench 42
+            "#
+        ));
+    }
+
+    #[test]
+    fn pattern_synonym() {
+        expr_formats_same(indoc!(
+            r#"
+            pattern Ok2 x = Ok (Ok x)
+
+            pattern Nothing = Err _
+
+            when result is
+                Ok2 x -> x
+                Nothing -> 0
+                _ -> 1
+            "#
+        ));
+
+        expr_formats_to(
+            indoc!(
+                r#"
+                pattern   Ok2 x=Ok (Ok  x)
+
+                42
+                "#
+            ),
+            indoc!(
+                r#"
+                pattern Ok2 x = Ok (Ok x)
+
+                42
+                "#
+            ),
+        );
+    }
+
     #[test]
     fn single_line_string_literal_in_pattern() {
         expr_formats_same(indoc!(
@@ -5716,6 +5811,168 @@ mod test_fmt {
         ));
     }
 
+    #[test]
+    fn platform_header_allocator() {
+        module_formats_same(indoc!(
+            r#"
+            platform "arena"
+                requires {} { main : Str }
+                exposes []
+                packages {}
+                imports []
+                provides [mainForHost]
+                allocator { strategy: arena, alignment: 16 }
+            "#
+        ));
+
+        module_formats_to(
+            indoc!(
+                r#"
+                platform "roc-alloc"
+                    requires {} { main : Str }
+                    exposes []
+                    packages {}
+                    imports []
+                    provides [mainForHost]
+                    allocator {strategy:roc}
+                "#
+            ),
+            indoc!(
+                r#"
+                platform "roc-alloc"
+                    requires {} { main : Str }
+                    exposes []
+                    packages {}
+                    imports []
+                    provides [mainForHost]
+                    allocator { strategy: roc }
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn platform_header_effects() {
+        module_formats_same(indoc!(
+            r#"
+            platform "uring"
+                requires {} { main : Str }
+                exposes []
+                packages {}
+                imports []
+                provides [mainForHost]
+                allocator { strategy: arena }
+                effects { submit: batch }
+            "#
+        ));
+
+        module_formats_to(
+            indoc!(
+                r#"
+                platform "uring"
+                    requires {} { main : Str }
+                    exposes []
+                    packages {}
+                    imports []
+                    provides [mainForHost]
+                    effects {submit:each}
+                "#
+            ),
+            indoc!(
+                r#"
+                platform "uring"
+                    requires {} { main : Str }
+                    exposes []
+                    packages {}
+                    imports []
+                    provides [mainForHost]
+                    effects { submit: each }
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn platform_header_hosts() {
+        module_formats_same(indoc!(
+            r#"
+            platform "cli"
+                requires {} { main : Str }
+                exposes []
+                packages {}
+                imports []
+                provides [mainForHost]
+                hosts { "linux-x86_64": "https://example.com/linux.tar.br", "macos-arm64": "https://example.com/macos.tar.br" }
+            "#
+        ));
+
+        module_formats_to(
+            indoc!(
+                r#"
+                platform "cli"
+                    requires {} { main : Str }
+                    exposes []
+                    packages {}
+                    imports []
+                    provides [mainForHost]
+                    hosts {"linux-x86_64":"https://example.com/linux.tar.br"}
+                "#
+            ),
+            indoc!(
+                r#"
+                platform "cli"
+                    requires {} { main : Str }
+                    exposes []
+                    packages {}
+                    imports []
+                    provides [mainForHost]
+                    hosts { "linux-x86_64": "https://example.com/linux.tar.br" }
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn platform_header_build() {
+        module_formats_same(indoc!(
+            r#"
+            platform "cli"
+                requires {} { main : Str }
+                exposes []
+                packages {}
+                imports []
+                provides [mainForHost]
+                hosts { "linux-x86_64": "https://example.com/linux.tar.br" }
+                build "zig build -p ."
+            "#
+        ));
+
+        module_formats_to(
+            indoc!(
+                r#"
+                platform "cli"
+                    requires {} { main : Str }
+                    exposes []
+                    packages {}
+                    imports []
+                    provides [mainForHost]
+                    build   "cargo build --release"
+                "#
+            ),
+            indoc!(
+                r#"
+                platform "cli"
+                    requires {} { main : Str }
+                    exposes []
+                    packages {}
+                    imports []
+                    provides [mainForHost]
+                    build "cargo build --release"
+                "#
+            ),
+        );
+    }
+
     #[test]
     fn clauses_with_multiple_abilities() {
         expr_formats_same(indoc!(
@@ -5843,6 +6100,87 @@ mod test_fmt {
         );
     }
 
+    #[test]
+    fn format_return_guard() {
+        expr_formats_same(indoc!(
+            r#"
+            if x < 0 then return Err Negative
+            if x > 100 then return Err TooBig
+
+            Ok x
+            "#
+        ));
+
+        expr_formats_same(indoc!(
+            r#"
+            if x < 0 then
+                return Err Negative
+            Ok x
+            "#
+        ));
+
+        expr_formats_to(
+            indoc!(
+                r#"
+                if x < 0 then return   Err Negative else Ok x
+                "#
+            ),
+            indoc!(
+                r#"
+                if x < 0 then return Err Negative
+                Ok x
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn format_return_in_when_branch() {
+        expr_formats_same(indoc!(
+            r#"
+            when result is
+                Ok n -> n
+                Err _ -> return 0
+            "#
+        ));
+    }
+
+    #[test]
+    fn format_try() {
+        expr_formats_same(indoc!(
+            r#"
+            x = (Str.toU64 str)?
+            y = parse? |> Num.add x
+
+            Ok y
+            "#
+        ));
+    }
+
+    #[test]
+    fn format_try_chains() {
+        expr_formats_same(indoc!(
+            r#"
+            x = nested??
+            y = config?.port?
+
+            Ok (x + y)
+            "#
+        ));
+    }
+
+    #[test]
+    fn format_dbg_applied() {
+        expr_formats_same(indoc!(
+            r#"
+            x = [1, 2] |> dbg "list" |> List.len
+            y = dbg (Num.add x 1)
+
+            dbg "y" y
+            "#
+        ));
+    }
+
     // this is a parse error atm
     //    #[test]
     //    fn multiline_apply() {
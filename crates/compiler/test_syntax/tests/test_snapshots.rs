@@ -277,6 +277,8 @@ mod test_snapshots {
         pass/basic_tag.expr,
         pass/basic_tuple.expr,
         pass/basic_var.expr,
+        pass/bench.moduledefs,
+        pass/bench_as_name.expr,
         pass/bound_variable.expr,
         pass/call_with_newlines.expr,
         pass/closure_in_binop.expr,
@@ -308,6 +310,7 @@ mod test_snapshots {
         pass/equals.expr,
         pass/equals_with_spaces.expr,
         pass/expect.expr,
+        pass/expect_always.expr,
         pass/expect_fx.moduledefs,
         pass/extra_newline_in_parens.expr,
         pass/float_with_underscores.expr,
@@ -408,6 +411,7 @@ mod test_snapshots {
         pass/pattern_as.expr,
         pass/pattern_as_list_rest.expr,
         pass/pattern_as_spaces.expr,
+        pass/pattern_synonym.expr,
         pass/pattern_with_space_in_parens.expr, // https://github.com/roc-lang/roc/issues/929
         pass/plus_if.expr,
         pass/plus_when.expr,
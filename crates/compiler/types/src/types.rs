@@ -3956,6 +3956,50 @@ impl ErrorType {
         }
     }
 
+    /// Replaces every [ErrorType::Infinite] in the type with `replacement`.
+    pub fn replace_infinite(&mut self, replacement: &ErrorType) {
+        use ErrorType::*;
+        match self {
+            Infinite => *self = replacement.clone(),
+            Type(_, ts) | Range(ts) => {
+                for t in ts {
+                    t.replace_infinite(replacement);
+                }
+            }
+            FlexVar(_) | RigidVar(_) | FlexAbleVar(..) | RigidAbleVar(..) | Error => {}
+            Record(fields, _) => {
+                for (_, field) in fields.iter_mut() {
+                    field.as_inner_mut().replace_infinite(replacement);
+                }
+            }
+            Tuple(elems, _) => {
+                for (_, t) in elems {
+                    t.replace_infinite(replacement);
+                }
+            }
+            TagUnion(tags, _, _) | RecursiveTagUnion(_, tags, _, _) => {
+                for (_, ts) in tags.iter_mut() {
+                    for t in ts {
+                        t.replace_infinite(replacement);
+                    }
+                }
+            }
+            Function(args, closure, ret) => {
+                for t in args {
+                    t.replace_infinite(replacement);
+                }
+                closure.replace_infinite(replacement);
+                ret.replace_infinite(replacement);
+            }
+            Alias(_, args, real, _) => {
+                for t in args {
+                    t.replace_infinite(replacement);
+                }
+                real.replace_infinite(replacement);
+            }
+        }
+    }
+
     /// Adds all named type variables used in the type to a set.
     pub fn add_names(&self, taken: &mut MutSet<Lowercase>) {
         use ErrorType::*;
@@ -0,0 +1,275 @@
+//! Extracts the ```roc code examples from doc comments, so that `roc docs --check-examples` can
+//! check that they still compile (and that their `expect`s still pass).
+use crate::load_module_for_docs;
+use bumpalo::Bump;
+use roc_load::docs::DocEntry;
+use roc_parse::test_helpers::parse_defs_with;
+use std::path::PathBuf;
+
+/// The name of the module each example is wrapped in
+const EXAMPLE_MODULE_NAME: &str = "DocExample";
+
+/// A code example from a doc comment, turned into the source of a module that can be checked on
+/// its own.
+#[derive(Debug)]
+pub struct DocExample {
+    /// Where the example was found, e.g. `Str.concat`
+    pub location: String,
+    /// The file whose doc comments contain the example. Wherever possible, `source` is laid out
+    /// so that the example's code is on the same lines (and columns) as in this file, so reports
+    /// about `source` point into the original doc comment.
+    pub module_path: PathBuf,
+    /// The directory the modules imported by `source` are in
+    pub src_dir: PathBuf,
+    pub source: String,
+    /// Whether the example has top-level `expect`s to run
+    pub has_expects: bool,
+}
+
+/// Returns the ```roc examples in the doc comments of the package whose main file is `root_file`.
+/// The examples of the package's dependencies are left to those packages.
+pub fn doc_examples(root_file: PathBuf) -> Vec<DocExample> {
+    let loaded_module = load_module_for_docs(root_file.clone());
+    let src_dir = match root_file.parent() {
+        Some(dir) => dir.to_path_buf(),
+        None => PathBuf::new(),
+    };
+
+    let package_modules: Vec<&str> = loaded_module
+        .docs_by_module
+        .values()
+        .filter(|module| module.package.is_none())
+        .map(|module| module.name.as_str())
+        .collect();
+
+    let mut examples = Vec::new();
+
+    for (module_id, module) in loaded_module.docs_by_module.iter() {
+        if module.package.is_some() {
+            continue;
+        }
+
+        let (module_path, module_src) = match loaded_module.sources.get(module_id) {
+            Some(source) => source,
+            None => continue,
+        };
+        let src_lines: Vec<&str> = module_src.lines().collect();
+
+        // Examples are looked up in the source in the order they appear, so that two identical
+        // examples are mapped to two different places.
+        let mut search_from = 0;
+
+        for entry in &module.entries {
+            let (location, docs) = match entry {
+                DocEntry::DocDef(doc_def) => match &doc_def.docs {
+                    Some(docs) => (format!("{}.{}", module.name, doc_def.name), docs),
+                    None => continue,
+                },
+                DocEntry::DetachedDoc(docs) => (module.name.clone(), docs),
+            };
+
+            for code in roc_code_blocks(docs) {
+                let placement = find_in_source(&src_lines, search_from, &code);
+
+                if let Some(placement) = &placement {
+                    search_from = placement.first_line;
+                }
+
+                let imports: Vec<&str> = package_modules
+                    .iter()
+                    .copied()
+                    .filter(|name| mentions_module(&code, name))
+                    .collect();
+
+                let (source, has_expects) = example_module_source(&code, placement, &imports);
+
+                examples.push(DocExample {
+                    location: location.clone(),
+                    module_path: module_path.clone(),
+                    src_dir: src_dir.clone(),
+                    source,
+                    has_expects,
+                });
+            }
+        }
+    }
+
+    examples
+}
+
+/// The contents of each ```roc fenced code block in some markdown, one line per entry.
+/// Lines keep their indentation, including the space that usually follows `##`.
+fn roc_code_blocks(markdown: &str) -> Vec<Vec<&str>> {
+    let mut blocks = Vec::new();
+    let mut current: Option<Vec<&str>> = None;
+
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+
+        match current.as_mut() {
+            None => {
+                if trimmed == "```roc" {
+                    current = Some(Vec::new());
+                }
+            }
+            Some(block) => {
+                if trimmed.starts_with("```") {
+                    blocks.extend(current.take());
+                } else {
+                    block.push(line);
+                }
+            }
+        }
+    }
+
+    // An unterminated code block still runs to the end of the doc comment.
+    blocks.extend(current);
+
+    blocks
+}
+
+/// Where an example's code is in its module's source
+struct Placement {
+    /// The (zero-based) line the example's first line of code is on
+    first_line: usize,
+    /// For each line of the example, the column its doc comment's text starts at
+    /// (just past the `##`)
+    columns: Vec<usize>,
+}
+
+fn find_in_source(src_lines: &[&str], search_from: usize, code: &[&str]) -> Option<Placement> {
+    // For a doc comment line, the column just past its `##` and the text after that.
+    fn doc_comment(line: &str) -> Option<(usize, &str)> {
+        let column = line.len() - line.trim_start().len();
+
+        line.trim_start()
+            .strip_prefix("##")
+            .map(|text| (column + 2, text))
+    }
+
+    for fence_line in search_from..src_lines.len() {
+        match doc_comment(src_lines[fence_line]) {
+            Some((_, text)) if text.trim() == "```roc" => {}
+            _ => continue,
+        }
+
+        let first_line = fence_line + 1;
+        let mut columns = Vec::with_capacity(code.len());
+
+        for (index, code_line) in code.iter().enumerate() {
+            match src_lines
+                .get(first_line + index)
+                .copied()
+                .and_then(doc_comment)
+            {
+                Some((column, text)) if text == *code_line => columns.push(column),
+                _ => break,
+            }
+        }
+
+        if columns.len() == code.len() {
+            return Some(Placement {
+                first_line,
+                columns,
+            });
+        }
+    }
+
+    None
+}
+
+/// Whether the code refers to something qualified with `module_name`, like `Foo.bar` for `Foo`
+fn mentions_module(code: &[&str], module_name: &str) -> bool {
+    let qualifier = format!("{}.", module_name);
+
+    code.iter().any(|line| {
+        line.match_indices(qualifier.as_str()).any(|(index, _)| {
+            // Don't count `Bar.Foo.bar` or `myFoo.bar` as mentioning `Foo`.
+            match line[..index].chars().next_back() {
+                Some(ch) => !(ch.is_alphanumeric() || ch == '.' || ch == '_'),
+                None => true,
+            }
+        })
+    })
+}
+
+/// Turns an example into the source of a module exposing nothing. An example that's made up of
+/// top-level defs (like `expect`s) is included as-is; otherwise it's treated as an expression,
+/// and becomes the body of a def. Returns the source, and whether it has top-level `expect`s.
+fn example_module_source(
+    code: &[&str],
+    placement: Option<Placement>,
+    imports: &[&str],
+) -> (String, bool) {
+    let common_indent = code
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let dedented: Vec<&str> = code
+        .iter()
+        .map(|line| line.get(common_indent..).unwrap_or_default())
+        .collect();
+
+    let is_defs = {
+        let arena = Bump::new();
+        let src = dedented.join("\n");
+
+        parse_defs_with(&arena, &src).is_ok()
+    };
+    let has_expects = is_defs
+        && dedented
+            .iter()
+            .any(|line| line.split_whitespace().next() == Some("expect"));
+
+    let header = format!(
+        "interface {} exposes [] imports [{}]",
+        EXAMPLE_MODULE_NAME,
+        imports.join(", ")
+    );
+
+    // The header, and the `example =` line an expression needs, have to fit before the first line
+    // of code for it to stay where it is in the original file.
+    let lines_needed = if is_defs { 1 } else { 2 };
+    let placement = placement.filter(|placement| placement.first_line >= lines_needed);
+
+    let first_line = match &placement {
+        Some(placement) => placement.first_line,
+        None => lines_needed,
+    };
+
+    let mut lines = vec![String::new(); first_line];
+
+    lines[0] = header;
+
+    if !is_defs {
+        lines[first_line - 1] = "example =".to_string();
+    }
+
+    for (index, line) in code.iter().enumerate() {
+        let line = if is_defs {
+            // Top-level defs have to start in the first column, so these can only keep their lines.
+            dedented[index].to_string()
+        } else {
+            // The body of `example` must be indented; putting each line of code back at the column
+            // it's at in the doc comment takes care of that.
+            let column = match &placement {
+                Some(placement) => placement.columns[index],
+                None => 4,
+            };
+
+            if line.trim().is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", " ".repeat(column), line)
+            }
+        };
+
+        lines.push(line);
+    }
+
+    lines.push(String::new());
+
+    (lines.join("\n"), has_expects)
+}
@@ -0,0 +1,199 @@
+//! A machine-readable export of the same documentation the HTML docs are generated from, for
+//! alternative documentation browsers and editor tooltips.
+use crate::{
+    host_symbol_name, load_module_for_docs, module_url, type_annotation_to_plain_text, BUILD_DIR,
+};
+use roc_collections::VecSet;
+use roc_load::docs::{DocEntry, ModuleDocumentation};
+use roc_load::LoadedModule;
+use roc_module::symbol::{ModuleId, Symbol};
+use roc_region::all::{LineColumn, LineInfo};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Writes one JSON file per module to the docs build directory: `Str.json` for the `Str` module of
+/// the root package, and `pf/Stdout.json` for the `Stdout` module of the package imported as `pf`.
+///
+/// Each file has the module's `name`, `package` (the shorthand, or `null` for the root package),
+/// source `path`, `url` in the HTML docs, whether it's `hosted`, and its `entries` in source order.
+/// An entry is either an exposed definition:
+///
+/// ```text
+/// { "kind": "def", "name": "concat", "typeVars": [], "signature": "Str, Str -> Str",
+///   "docs": "...", "region": { "start": { "line": 4, "column": 0 }, "end": { ... } } }
+/// ```
+///
+/// or documentation that isn't attached to a definition:
+///
+/// ```text
+/// { "kind": "detachedDoc", "docs": "..." }
+/// ```
+///
+/// `docs` are markdown, and `null` if there are none. Lines and columns are zero-based. The defs of
+/// a hosted module are the platform's effects, and also have the `hostSymbol` the host implements
+/// them with, e.g. `"roc_fx_putLine"`.
+pub fn generate_docs_json(root_file: PathBuf) {
+    let build_dir = Path::new(BUILD_DIR);
+    let loaded_module = load_module_for_docs(root_file);
+
+    // Clear out the generated-docs dir (we'll create a fresh one at the end)
+    if build_dir.exists() {
+        fs::remove_dir_all(build_dir)
+            .expect("TODO gracefully handle being unable to delete build dir");
+    }
+    fs::create_dir_all(build_dir).expect("TODO gracefully handle being unable to create build dir");
+
+    let all_exposed_symbols = {
+        let mut set = VecSet::default();
+
+        for docs in loaded_module.docs_by_module.values() {
+            set.insert_all(docs.exposed_symbols.iter().copied());
+        }
+
+        set
+    };
+
+    for (module_id, module_docs) in loaded_module.docs_by_module.iter() {
+        let package_dir = match &module_docs.package {
+            Some(shorthand) => build_dir.join(shorthand),
+            None => build_dir.to_path_buf(),
+        };
+        let json_path = package_dir.join(format!("{}.json", module_docs.name));
+
+        if let Some(parent) = json_path.parent() {
+            fs::create_dir_all(parent)
+                .expect("TODO gracefully handle not being able to create the module's dir");
+        }
+
+        let rendered = render_module_json(
+            *module_id,
+            module_docs,
+            &loaded_module,
+            &all_exposed_symbols,
+        );
+
+        fs::write(json_path, rendered)
+            .expect("TODO gracefully handle failing to write a module's JSON docs");
+    }
+
+    println!("🎉 JSON docs generated in {}", build_dir.display());
+}
+
+#[derive(Serialize)]
+struct ModuleJson<'a> {
+    name: &'a str,
+    package: Option<&'a str>,
+    path: Option<String>,
+    url: String,
+    hosted: bool,
+    entries: Vec<EntryJson<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+enum EntryJson<'a> {
+    #[serde(rename_all = "camelCase")]
+    Def {
+        name: &'a str,
+        type_vars: &'a [String],
+        signature: String,
+        docs: Option<&'a str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        host_symbol: Option<String>,
+        region: Option<RegionJson>,
+    },
+    DetachedDoc {
+        docs: &'a str,
+    },
+}
+
+#[derive(Serialize)]
+struct RegionJson {
+    start: LineColumnJson,
+    end: LineColumnJson,
+}
+
+#[derive(Serialize)]
+struct LineColumnJson {
+    line: u32,
+    column: u32,
+}
+
+impl From<LineColumn> for LineColumnJson {
+    fn from(line_column: LineColumn) -> Self {
+        LineColumnJson {
+            line: line_column.line,
+            column: line_column.column,
+        }
+    }
+}
+
+fn render_module_json(
+    module_id: ModuleId,
+    module: &ModuleDocumentation,
+    loaded_module: &LoadedModule,
+    all_exposed_symbols: &VecSet<Symbol>,
+) -> String {
+    let (path, line_info) = match loaded_module.sources.get(&module_id) {
+        Some((path, src)) => (Some(path), Some(LineInfo::new(src))),
+        None => (None, None),
+    };
+
+    let mut entries = Vec::with_capacity(module.entries.len());
+
+    for entry in &module.entries {
+        match entry {
+            DocEntry::DocDef(doc_def) => {
+                if !all_exposed_symbols.contains(&doc_def.symbol) {
+                    continue;
+                }
+
+                let mut signature = String::new();
+
+                type_annotation_to_plain_text(&mut signature, &doc_def.type_annotation, false);
+
+                let region = line_info.as_ref().map(|line_info| {
+                    let region = line_info.convert_region(doc_def.region);
+
+                    RegionJson {
+                        start: region.start.into(),
+                        end: region.end.into(),
+                    }
+                });
+
+                entries.push(EntryJson::Def {
+                    name: doc_def.name.as_str(),
+                    type_vars: doc_def.type_vars.as_slice(),
+                    signature,
+                    docs: doc_def.docs.as_deref(),
+                    host_symbol: module
+                        .is_hosted
+                        .then(|| host_symbol_name(doc_def.name.as_str())),
+                    region,
+                });
+            }
+            DocEntry::DetachedDoc(docs) => {
+                entries.push(EntryJson::DetachedDoc {
+                    docs: docs.as_str(),
+                });
+            }
+        }
+    }
+
+    let module_json = ModuleJson {
+        name: module.name.as_str(),
+        package: module.package.as_deref(),
+        path: path.map(|path| path.to_string_lossy().into_owned()),
+        url: module_url(module.package.as_deref(), module.name.as_str()),
+        hosted: module.is_hosted,
+        entries,
+    };
+
+    let mut buf =
+        serde_json::to_string_pretty(&module_json).expect("a module's docs are always valid JSON");
+
+    buf.push('\n');
+
+    buf
+}
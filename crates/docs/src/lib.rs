@@ -5,7 +5,6 @@ extern crate roc_load;
 use bumpalo::Bump;
 use docs_error::{DocsError, DocsResult};
 use html::mark_node_to_html;
-use roc_can::scope::Scope;
 use roc_code_markup::markup::nodes::MarkupNode;
 use roc_code_markup::slow_pool::SlowPool;
 use roc_collections::VecSet;
@@ -13,16 +12,22 @@ use roc_highlight::highlight_parser::{highlight_defs, highlight_expr};
 use roc_load::docs::{DocEntry, TypeAnnotation};
 use roc_load::docs::{ModuleDocumentation, RecordField};
 use roc_load::{ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, Threading};
-use roc_module::symbol::{Interns, Symbol};
+use roc_module::symbol::{ModuleId, Symbol};
 use roc_packaging::cache::{self, RocCacheDir};
 use roc_parse::ident::{parse_ident, Accessor, Ident};
 use roc_parse::state::State;
 use roc_region::all::Region;
+use serde::Serialize;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 mod docs_error;
+mod examples;
 mod html;
+mod json;
+
+pub use examples::{doc_examples, DocExample};
+pub use json::generate_docs_json;
 
 const BUILD_DIR: &str = "./generated-docs";
 
@@ -31,7 +36,7 @@ pub fn generate_docs_html(root_file: PathBuf) {
     let loaded_module = load_module_for_docs(root_file);
 
     // TODO get these from the platform's source file rather than hardcoding them!
-    let package_name = "Documentation".to_string();
+    let root_package_name = "Documentation".to_string();
     let version = String::new();
 
     // Clear out the generated-docs dir (we'll create a fresh one at the end)
@@ -61,27 +66,10 @@ pub fn generate_docs_html(root_file: PathBuf) {
     .expect("TODO gracefully handle failing to make the favicon");
 
     let template_html = include_str!("./static/index.html")
+        .replace("<!-- search-index.js -->", "/search-index.js")
         .replace("<!-- search.js -->", "/search.js")
         .replace("<!-- styles.css -->", "/styles.css")
-        .replace("<!-- favicon.svg -->", "/favicon.svg")
-        .replace(
-            "<!-- Prefetch links -->",
-            loaded_module
-                .docs_by_module
-                .iter()
-                .map(|(_, module)| {
-                    let href = sidebar_link_url(module.name.as_str());
-
-                    format!(r#"<link rel="prefetch" href="{href}"/>"#)
-                })
-                .collect::<Vec<String>>()
-                .join("\n    ")
-                .as_str(),
-        )
-        .replace(
-            "<!-- Module links -->",
-            render_sidebar(loaded_module.docs_by_module.values()).as_str(),
-        );
+        .replace("<!-- favicon.svg -->", "/favicon.svg");
 
     let all_exposed_symbols = {
         let mut set = VecSet::default();
@@ -93,38 +81,128 @@ pub fn generate_docs_html(root_file: PathBuf) {
         set
     };
 
-    // Write each package's module docs html file
+    fs::write(
+        build_dir.join("search-index.js"),
+        render_search_index(&loaded_module, &all_exposed_symbols),
+    )
+    .expect("TODO gracefully handle failing to write the search index");
+
+    // The root package's modules, followed by those of each of its dependencies
+    // (in the order they were first encountered).
+    let mut packages: Vec<(Option<&str>, Vec<&ModuleDocumentation>)> = vec![(None, Vec::new())];
+
     for module_docs in loaded_module.docs_by_module.values() {
-        let module_name = module_docs.name.as_str();
-        let module_dir = build_dir.join(module_name.replace('.', "/").as_str());
+        let package = module_docs.package.as_deref();
 
-        fs::create_dir_all(&module_dir)
-            .expect("TODO gracefully handle not being able to create the module dir");
+        match packages.iter_mut().find(|(name, _)| *name == package) {
+            Some((_, modules)) => modules.push(module_docs),
+            None => packages.push((package, vec![module_docs])),
+        }
+    }
+
+    let dependencies: Vec<&str> = packages.iter().filter_map(|(name, _)| *name).collect();
+
+    for (package, modules) in packages.iter() {
+        // Dependencies don't have a name or version of their own yet, so we go by their shorthand.
+        let package_name = package.unwrap_or(root_package_name.as_str());
+        let package_dir = match package {
+            Some(shorthand) => build_dir.join(shorthand),
+            None => build_dir.to_path_buf(),
+        };
 
-        let rendered_module = template_html
+        let package_html = template_html
             .replace(
-                "<!-- Page title -->",
-                page_title(package_name.as_str(), module_name).as_str(),
+                "<!-- Prefetch links -->",
+                modules
+                    .iter()
+                    .map(|module| {
+                        let href = module_url(module.package.as_deref(), module.name.as_str());
+
+                        format!(r#"<link rel="prefetch" href="{href}"/>"#)
+                    })
+                    .collect::<Vec<String>>()
+                    .join("\n    ")
+                    .as_str(),
+            )
+            .replace(
+                "<!-- Module links -->",
+                render_sidebar(modules.iter().copied()).as_str(),
             )
             .replace(
                 "<!-- Package Name and Version -->",
-                render_name_and_version(package_name.as_str(), version.as_str()).as_str(),
+                render_name_and_version(package_name, version.as_str()).as_str(),
+            );
+
+        // Write each of the package's module docs html files
+        for module_docs in modules {
+            let module_name = module_docs.name.as_str();
+            let module_dir = package_dir.join(module_name.replace('.', "/").as_str());
+
+            fs::create_dir_all(&module_dir)
+                .expect("TODO gracefully handle not being able to create the module dir");
+
+            let rendered_module = package_html
+                .replace(
+                    "<!-- Page title -->",
+                    page_title(package_name, module_name).as_str(),
+                )
+                .replace(
+                    "<!-- Module Docs -->",
+                    render_module_documentation(module_docs, &loaded_module, &all_exposed_symbols)
+                        .as_str(),
+                );
+
+            fs::write(module_dir.join("index.html"), rendered_module)
+                .expect("TODO gracefully handle failing to write index.html inside module's dir");
+        }
+
+        // Write the package's index page
+        fs::create_dir_all(&package_dir)
+            .expect("TODO gracefully handle not being able to create the package dir");
+
+        let package_deps: &[&str] = if package.is_none() {
+            &dependencies
+        } else {
+            // We don't know which packages a dependency itself depends on.
+            &[]
+        };
+
+        let rendered_index = package_html
+            .replace(
+                "<!-- Page title -->",
+                format!("<title>{package_name}</title>").as_str(),
             )
             .replace(
                 "<!-- Module Docs -->",
-                render_module_documentation(module_docs, &loaded_module, &all_exposed_symbols)
-                    .as_str(),
+                render_package_index(package_name, modules, package_deps).as_str(),
             );
 
-        fs::write(module_dir.join("index.html"), rendered_module)
-            .expect("TODO gracefully handle failing to write index.html inside module's dir");
+        fs::write(package_dir.join("index.html"), rendered_index)
+            .expect("TODO gracefully handle failing to write index.html inside package's dir");
     }
 
     println!("🎉 Docs generated in {}", build_dir.display());
 }
 
-fn sidebar_link_url(module_name: &str) -> String {
-    format!("{}{}", base_url(), module_name)
+/// The URL of a module's docs, e.g. "/Str" for a module in the root package, or "/pf/Stdout" for
+/// the `Stdout` module of the package imported as `pf`.
+fn module_url(package: Option<&str>, module_name: &str) -> String {
+    let mut url = package_url(package);
+
+    url.push_str(module_name);
+
+    url
+}
+
+fn package_url(package: Option<&str>) -> String {
+    let mut url = base_url();
+
+    if let Some(shorthand) = package {
+        url.push_str(shorthand);
+        url.push('/');
+    }
+
+    url
 }
 
 fn page_title(package_name: &str, module_name: &str) -> String {
@@ -180,6 +258,11 @@ fn render_module_documentation(
     all_exposed_symbols: &VecSet<Symbol>,
 ) -> String {
     let mut buf = String::new();
+    let type_links = TypeLinks {
+        module,
+        loaded_module: root_module,
+        all_exposed_symbols,
+    };
 
     push_html(&mut buf, "h2", vec![("class", "module-name")], {
         let mut link_buf = String::new();
@@ -194,6 +277,15 @@ fn render_module_documentation(
         link_buf
     });
 
+    if module.is_hosted {
+        push_html(
+            &mut buf,
+            "p",
+            vec![("class", "hosted-module")],
+            "These effects are provided by the platform's host.",
+        );
+    }
+
     for entry in &module.entries {
         match entry {
             DocEntry::DocDef(doc_def) => {
@@ -216,7 +308,7 @@ fn render_module_documentation(
 
                     if !matches!(type_ann, TypeAnnotation::NoTypeAnn) {
                         content.push_str(" : ");
-                        type_annotation_to_html(0, &mut content, type_ann, false);
+                        type_annotation_to_html(0, &mut content, type_ann, false, &type_links);
                     }
 
                     push_html(
@@ -226,27 +318,27 @@ fn render_module_documentation(
                         content.as_str(),
                     );
 
-                    if let Some(docs) = &doc_def.docs {
-                        markdown_to_html(
+                    if module.is_hosted {
+                        let mut host_symbol = String::new();
+
+                        push_html(&mut host_symbol, "code", vec![], host_symbol_name(name));
+                        push_html(
                             &mut buf,
-                            all_exposed_symbols,
-                            &module.scope,
-                            docs,
-                            root_module,
+                            "p",
+                            vec![("class", "host-symbol")],
+                            format!("Implemented by the host as {}", host_symbol),
                         );
                     }
 
+                    if let Some(docs) = &doc_def.docs {
+                        markdown_to_html(&mut buf, all_exposed_symbols, module, docs, root_module);
+                    }
+
                     buf.push_str("</section>");
                 }
             }
             DocEntry::DetachedDoc(docs) => {
-                markdown_to_html(
-                    &mut buf,
-                    all_exposed_symbols,
-                    &module.scope,
-                    docs,
-                    root_module,
-                );
+                markdown_to_html(&mut buf, all_exposed_symbols, module, docs, root_module);
             }
         };
     }
@@ -344,7 +436,7 @@ fn render_sidebar<'a, I: Iterator<Item = &'a ModuleDocumentation>>(modules: I) -
     let mut buf = String::new();
 
     for module in modules {
-        let href = sidebar_link_url(module.name.as_str());
+        let href = module_url(module.package.as_deref(), module.name.as_str());
         let mut sidebar_entry_content = String::new();
 
         push_html(
@@ -397,6 +489,273 @@ fn render_sidebar<'a, I: Iterator<Item = &'a ModuleDocumentation>>(modules: I) -
     buf
 }
 
+fn render_package_index(
+    package_name: &str,
+    modules: &[&ModuleDocumentation],
+    dependencies: &[&str],
+) -> String {
+    let mut buf = String::new();
+
+    push_html(&mut buf, "h2", vec![("class", "module-name")], package_name);
+
+    let (hosted_modules, modules): (Vec<&ModuleDocumentation>, Vec<&ModuleDocumentation>) =
+        modules.iter().partition(|module| module.is_hosted);
+
+    push_html(
+        &mut buf,
+        "ul",
+        vec![("class", "package-modules")],
+        module_list_html(&modules),
+    );
+
+    // A platform's hosted modules are its effects, so app authors can find them in one place.
+    if !hosted_modules.is_empty() {
+        push_html(&mut buf, "h3", vec![], "Effects");
+        push_html(
+            &mut buf,
+            "ul",
+            vec![("class", "package-modules package-effects")],
+            module_list_html(&hosted_modules),
+        );
+    }
+
+    if !dependencies.is_empty() {
+        push_html(&mut buf, "h3", vec![], "Dependencies");
+
+        let mut package_links = String::new();
+
+        for shorthand in dependencies {
+            let href = package_url(Some(*shorthand));
+            let mut link = String::new();
+
+            push_html(&mut link, "a", vec![("href", href.as_str())], shorthand);
+            push_html(&mut package_links, "li", vec![], link);
+        }
+
+        push_html(
+            &mut buf,
+            "ul",
+            vec![("class", "package-dependencies")],
+            package_links,
+        );
+    }
+
+    buf
+}
+
+fn module_list_html(modules: &[&ModuleDocumentation]) -> String {
+    let mut module_links = String::new();
+
+    for module in modules {
+        let href = module_url(module.package.as_deref(), module.name.as_str());
+        let mut link = String::new();
+
+        push_html(
+            &mut link,
+            "a",
+            vec![("href", href.as_str())],
+            module.name.as_str(),
+        );
+        push_html(&mut module_links, "li", vec![], link);
+    }
+
+    module_links
+}
+
+/// The name of the function a host implements a hosted module's effect with, e.g. `roc_fx_putLine`
+/// for `putLine`. This must match the foreign calls canonicalization generates for hosted modules.
+pub(crate) fn host_symbol_name(effect_name: &str) -> String {
+    format!("roc_fx_{}", effect_name)
+}
+
+/// A script defining `ROC_SEARCH_INDEX`, which search.js uses to search the docs of every exposed
+/// definition by name, type signature, and doc comment without a server.
+fn render_search_index(
+    loaded_module: &LoadedModule,
+    all_exposed_symbols: &VecSet<Symbol>,
+) -> String {
+    let mut buf = String::from("window.ROC_SEARCH_INDEX = [\n");
+
+    for module in loaded_module.docs_by_module.values() {
+        let module_href = module_url(module.package.as_deref(), module.name.as_str());
+        let qualified_module_name = match &module.package {
+            Some(shorthand) => format!("{}.{}", shorthand, module.name),
+            None => module.name.clone(),
+        };
+
+        for entry in &module.entries {
+            if let DocEntry::DocDef(doc_def) = entry {
+                if !all_exposed_symbols.contains(&doc_def.symbol) {
+                    continue;
+                }
+
+                let mut signature = String::new();
+
+                type_annotation_to_plain_text(&mut signature, &doc_def.type_annotation, false);
+
+                let docs = doc_def
+                    .docs
+                    .as_deref()
+                    .unwrap_or_default()
+                    .split_whitespace()
+                    .collect::<Vec<&str>>()
+                    .join(" ");
+
+                let entry = SearchIndexEntry {
+                    module: qualified_module_name.as_str(),
+                    name: doc_def.name.as_str(),
+                    url: format!("{}#{}", module_href, doc_def.name),
+                    signature,
+                    docs,
+                };
+
+                buf.push_str("  ");
+                buf.push_str(
+                    serde_json::to_string(&entry)
+                        .expect("a search index entry is always valid JSON")
+                        .as_str(),
+                );
+                buf.push_str(",\n");
+            }
+        }
+    }
+
+    buf.push_str("];\n");
+
+    buf
+}
+
+#[derive(Serialize)]
+struct SearchIndexEntry<'a> {
+    module: &'a str,
+    name: &'a str,
+    url: String,
+    signature: String,
+    docs: String,
+}
+
+/// Renders a type annotation on a single line, the way it would be typed into a search box
+/// (e.g. `Str -> List U8`).
+fn type_annotation_to_plain_text(buf: &mut String, type_ann: &TypeAnnotation, needs_parens: bool) {
+    match type_ann {
+        TypeAnnotation::TagUnion { tags, extension } => {
+            buf.push('[');
+
+            for (index, tag) in tags.iter().enumerate() {
+                if index > 0 {
+                    buf.push_str(", ");
+                }
+
+                buf.push_str(tag.name.as_str());
+
+                for type_value in &tag.values {
+                    buf.push(' ');
+                    type_annotation_to_plain_text(buf, type_value, true);
+                }
+            }
+
+            buf.push(']');
+
+            type_annotation_to_plain_text(buf, extension, true);
+        }
+        TypeAnnotation::BoundVariable(var_name) => {
+            buf.push_str(var_name);
+        }
+        TypeAnnotation::Apply { name, parts } => {
+            let needs_parens = needs_parens && !parts.is_empty();
+
+            if needs_parens {
+                buf.push('(');
+            }
+
+            buf.push_str(name);
+
+            for part in parts {
+                buf.push(' ');
+                type_annotation_to_plain_text(buf, part, true);
+            }
+
+            if needs_parens {
+                buf.push(')');
+            }
+        }
+        TypeAnnotation::Record { fields, extension } => {
+            if fields.is_empty() {
+                buf.push_str("{}");
+            } else {
+                buf.push_str("{ ");
+
+                for (index, field) in fields.iter().enumerate() {
+                    if index > 0 {
+                        buf.push_str(", ");
+                    }
+
+                    match field {
+                        RecordField::RecordField {
+                            name,
+                            type_annotation,
+                        } => {
+                            buf.push_str(name);
+                            buf.push_str(" : ");
+                            type_annotation_to_plain_text(buf, type_annotation, false);
+                        }
+                        RecordField::OptionalField {
+                            name,
+                            type_annotation,
+                        } => {
+                            buf.push_str(name);
+                            buf.push_str(" ? ");
+                            type_annotation_to_plain_text(buf, type_annotation, false);
+                        }
+                        RecordField::LabelOnly { name } => {
+                            buf.push_str(name);
+                        }
+                    }
+                }
+
+                buf.push_str(" }");
+            }
+
+            type_annotation_to_plain_text(buf, extension, true);
+        }
+        TypeAnnotation::Function { args, output } => {
+            if needs_parens {
+                buf.push('(');
+            }
+
+            for (index, arg) in args.iter().enumerate() {
+                if index > 0 {
+                    buf.push_str(", ");
+                }
+
+                // Only function arguments need parens here, since arguments are comma-separated.
+                let arg_needs_parens = matches!(arg, TypeAnnotation::Function { .. });
+
+                type_annotation_to_plain_text(buf, arg, arg_needs_parens);
+            }
+
+            buf.push_str(" -> ");
+
+            type_annotation_to_plain_text(buf, output, false);
+
+            if needs_parens {
+                buf.push(')');
+            }
+        }
+        TypeAnnotation::Ability { members: _ } => {
+            // TODO(abilities): fill me in
+        }
+        TypeAnnotation::ObscuredTagUnion => {
+            buf.push_str("[@..]");
+        }
+        TypeAnnotation::ObscuredRecord => {
+            buf.push_str("{ @.. }");
+        }
+        TypeAnnotation::NoTypeAnn => {}
+        TypeAnnotation::Wildcard => buf.push('*'),
+    }
+}
+
 pub fn load_module_for_docs(filename: PathBuf) -> LoadedModule {
     let arena = Bump::new();
     let load_config = LoadConfig {
@@ -405,6 +764,7 @@ pub fn load_module_for_docs(filename: PathBuf) -> LoadedModule {
         palette: roc_reporting::report::DEFAULT_PALETTE,
         threading: Threading::AllAvailable,
         exec_mode: ExecutionMode::Check,
+        ..Default::default()
     };
     match roc_load::load_and_typecheck(
         &arena,
@@ -413,7 +773,7 @@ pub fn load_module_for_docs(filename: PathBuf) -> LoadedModule {
         load_config,
     ) {
         Ok(loaded) => loaded,
-        Err(LoadingProblem::FormattedReport(report)) => {
+        Err(LoadingProblem::FormattedReport(report, _)) => {
             eprintln!("{}", report);
             std::process::exit(1);
         }
@@ -439,6 +799,7 @@ fn type_annotation_to_html(
     buf: &mut String,
     type_ann: &TypeAnnotation,
     needs_parens: bool,
+    links: &TypeLinks,
 ) {
     let is_multiline = should_be_multiline(type_ann);
     match type_ann {
@@ -473,7 +834,7 @@ fn type_annotation_to_html(
 
                     for type_value in &tag.values {
                         buf.push(' ');
-                        type_annotation_to_html(next_indent_level, buf, type_value, true);
+                        type_annotation_to_html(next_indent_level, buf, type_value, true, links);
                     }
 
                     if is_multiline {
@@ -492,23 +853,23 @@ fn type_annotation_to_html(
                 buf.push(']');
             }
 
-            type_annotation_to_html(indent_level, buf, extension, true);
+            type_annotation_to_html(indent_level, buf, extension, true, links);
         }
         TypeAnnotation::BoundVariable(var_name) => {
             buf.push_str(var_name);
         }
         TypeAnnotation::Apply { name, parts } => {
             if parts.is_empty() {
-                buf.push_str(name);
+                push_type_name(buf, name, links);
             } else {
                 if needs_parens {
                     buf.push('(');
                 }
 
-                buf.push_str(name);
+                push_type_name(buf, name, links);
                 for part in parts {
                     buf.push(' ');
-                    type_annotation_to_html(indent_level, buf, part, true);
+                    type_annotation_to_html(indent_level, buf, part, true, links);
                 }
 
                 if needs_parens {
@@ -556,13 +917,25 @@ fn type_annotation_to_html(
                             type_annotation, ..
                         } => {
                             buf.push_str(" : ");
-                            type_annotation_to_html(next_indent_level, buf, type_annotation, false);
+                            type_annotation_to_html(
+                                next_indent_level,
+                                buf,
+                                type_annotation,
+                                false,
+                                links,
+                            );
                         }
                         RecordField::OptionalField {
                             type_annotation, ..
                         } => {
                             buf.push_str(" ? ");
-                            type_annotation_to_html(next_indent_level, buf, type_annotation, false);
+                            type_annotation_to_html(
+                                next_indent_level,
+                                buf,
+                                type_annotation,
+                                false,
+                                links,
+                            );
                         }
                         RecordField::LabelOnly { .. } => {}
                     }
@@ -585,7 +958,7 @@ fn type_annotation_to_html(
                 buf.push('}');
             }
 
-            type_annotation_to_html(indent_level, buf, extension, true);
+            type_annotation_to_html(indent_level, buf, extension, true, links);
         }
         TypeAnnotation::Function { args, output } => {
             let mut peekable_args = args.iter().peekable();
@@ -597,7 +970,7 @@ fn type_annotation_to_html(
                     indent(buf, indent_level + 1);
                 }
 
-                type_annotation_to_html(indent_level, buf, arg, false);
+                type_annotation_to_html(indent_level, buf, arg, false, links);
 
                 if peekable_args.peek().is_some() {
                     buf.push_str(", ");
@@ -617,7 +990,7 @@ fn type_annotation_to_html(
                 next_indent_level += 1;
             }
 
-            type_annotation_to_html(next_indent_level, buf, output, false);
+            type_annotation_to_html(next_indent_level, buf, output, false, links);
         }
         TypeAnnotation::Ability { members: _ } => {
             // TODO(abilities): fill me in
@@ -704,6 +1077,73 @@ fn should_be_multiline(type_ann: &TypeAnnotation) -> bool {
     }
 }
 
+/// What's needed to link the types named in a signature to their docs, which may be part of
+/// another package's docs.
+struct TypeLinks<'a> {
+    module: &'a ModuleDocumentation,
+    loaded_module: &'a LoadedModule,
+    all_exposed_symbols: &'a VecSet<Symbol>,
+}
+
+fn push_type_name(buf: &mut String, name: &str, links: &TypeLinks) {
+    match type_url(name, links) {
+        Some(url) => push_html(buf, "a", vec![("href", url.as_str())], name),
+        None => buf.push_str(name),
+    }
+}
+
+/// The URL of the docs for a type named in a signature (e.g. `Str` or `Stdout.Err`), if it has any.
+fn type_url(name: &str, links: &TypeLinks) -> Option<String> {
+    let interns = &links.loaded_module.interns;
+    let symbol = match name.rsplit_once('.') {
+        Some((module_name, type_name)) => {
+            let module_id = docs_module_id(links.loaded_module, links.module, module_name)?;
+            let ident_id = interns.all_ident_ids.get(&module_id)?.get_id(type_name)?;
+
+            Symbol::new(module_id, ident_id)
+        }
+        None => links.module.scope.lookup_str(name, Region::zero()).ok()?,
+    };
+
+    // Types that are neither builtins nor exposed don't get docs of their own.
+    if !symbol.is_builtin() && !links.all_exposed_symbols.contains(&symbol) {
+        return None;
+    }
+
+    let mut url = symbol_module_url(links.loaded_module, symbol);
+
+    url.push('#');
+    url.push_str(symbol.as_str(interns));
+
+    Some(url)
+}
+
+/// The id of the module that `module_name` refers to in the docs for `module`. Different packages
+/// can have modules of the same name, in which case the one in `module`'s own package wins.
+fn docs_module_id(
+    loaded_module: &LoadedModule,
+    module: &ModuleDocumentation,
+    module_name: &str,
+) -> Option<ModuleId> {
+    loaded_module
+        .docs_by_module
+        .iter()
+        .filter(|(_, docs)| docs.name == module_name)
+        .min_by_key(|(_, docs)| docs.package != module.package)
+        .map(|(module_id, _)| *module_id)
+        .or_else(|| loaded_module.interns.module_ids.get_id(&module_name.into()))
+}
+
+/// The URL of the docs for the module `symbol` was defined in, which may belong to a dependency.
+fn symbol_module_url(loaded_module: &LoadedModule, symbol: Symbol) -> String {
+    let package = loaded_module
+        .docs_by_module
+        .get(&symbol.module_id())
+        .and_then(|docs| docs.package.as_deref());
+
+    module_url(package, symbol.module_string(&loaded_module.interns))
+}
+
 struct DocUrl {
     url: String,
     title: String,
@@ -711,24 +1151,27 @@ struct DocUrl {
 
 fn doc_url<'a>(
     all_exposed_symbols: &VecSet<Symbol>,
-    scope: &Scope,
-    interns: &'a Interns,
+    module: &ModuleDocumentation,
+    loaded_module: &'a LoadedModule,
     mut module_name: &'a str,
     ident: &str,
 ) -> DocUrl {
-    if module_name.is_empty() {
+    let interns = &loaded_module.interns;
+    let symbol = if module_name.is_empty() {
         // This is an unqualified lookup, so look for the ident
         // in scope!
-        match scope.lookup_str(ident, Region::zero()) {
+        match module.scope.lookup_str(ident, Region::zero()) {
             Ok(symbol) => {
                 // Get the exact module_name from scope. It could be the
                 // current module's name, but it also could be a different
                 // module - for example, if this is in scope from an
                 // unqualified import.
                 module_name = symbol.module_string(interns);
+
+                symbol
             }
             Err(_) => {
-                dbg!(scope);
+                dbg!(&module.scope);
                 // TODO return Err here
                 panic!(
                     "Tried to generate an automatic link in docs for symbol `{}`, but that symbol was not in scope in this module.",
@@ -737,7 +1180,7 @@ fn doc_url<'a>(
             }
         }
     } else {
-        match interns.module_ids.get_id(&module_name.into()) {
+        match docs_module_id(loaded_module, module, module_name) {
             Some(module_id) => {
                 let symbol = interns.symbol(module_id, ident.into());
 
@@ -760,23 +1203,22 @@ fn doc_url<'a>(
 
                 // This is a valid symbol for this dependency,
                 // so proceed using the current module's name.
-                //
-                // TODO: In the future, this is where we'll
-                // incorporate the package name into the link.
+                symbol
             }
             None => {
                 // TODO return Err here
                 panic!("Tried to generate a doc link for `{}.{}` but the `{}` module was not imported!", module_name, ident, module_name);
             }
         }
-    }
-
-    let mut url = base_url();
+    };
 
     // Example:
     //
     // module_name: "Str", ident: "join" => "/Str#join"
-    url.push_str(module_name);
+    // If the module belongs to a dependency, its docs are under that package's shorthand,
+    // e.g. "/pf/Stdout#line"
+    let mut url = symbol_module_url(loaded_module, symbol);
+
     url.push('#');
     url.push_str(ident);
 
@@ -789,7 +1231,7 @@ fn doc_url<'a>(
 fn markdown_to_html(
     buf: &mut String,
     all_exposed_symbols: &VecSet<Symbol>,
-    scope: &Scope,
+    module: &ModuleDocumentation,
     markdown: &str,
     loaded_module: &LoadedModule,
 ) {
@@ -819,8 +1261,8 @@ fn markdown_to_html(
                             Some(Accessor::RecordField(symbol_name)) if iter.next().is_none() => {
                                 let DocUrl { url, title } = doc_url(
                                     all_exposed_symbols,
-                                    scope,
-                                    &loaded_module.interns,
+                                    module,
+                                    loaded_module,
                                     module_name,
                                     symbol_name,
                                 );
@@ -838,13 +1280,8 @@ fn markdown_to_html(
                     Ok((_, Ident::Tag(type_name), _)) => {
                         // This looks like a tag name, but it could
                         // be a type alias that's in scope, e.g. [I64]
-                        let DocUrl { url, title } = doc_url(
-                            all_exposed_symbols,
-                            scope,
-                            &loaded_module.interns,
-                            "",
-                            type_name,
-                        );
+                        let DocUrl { url, title } =
+                            doc_url(all_exposed_symbols, module, loaded_module, "", type_name);
 
                         Some((url.into(), title.into()))
                     }
@@ -1,6 +1,141 @@
 (() => {
   let sidebar = document.getElementById("sidebar-nav");
   let searchBox = document.getElementById("module-search");
+  let searchResults = document.getElementById("search-results");
+  let searchIndex = window.ROC_SEARCH_INDEX || [];
+
+  const MAX_RESULTS = 30;
+
+  // Splits a type signature like "List a, (a -> b) -> List b" into tokens,
+  // dropping module qualifiers so that "Str.Utf8Problem" matches "Utf8Problem".
+  function typeTokens(signature) {
+    return (signature.match(/[A-Za-z][A-Za-z0-9_.]*|->|[()\[\]{},:?*]/g) || []).map(
+      (token) => token.split(".").pop()
+    );
+  }
+
+  function isTypeVariable(token) {
+    return /^[a-z]/.test(token);
+  }
+
+  // Whether the query's tokens appear in the signature's tokens starting at `start`.
+  // A type variable in either one matches any type variable in the other, whatever its name.
+  function typeTokensMatchAt(signatureTokens, queryTokens, start) {
+    for (let i = 0; i < queryTokens.length; i++) {
+      let sigToken = signatureTokens[start + i];
+      let queryToken = queryTokens[i];
+
+      if (sigToken === undefined) {
+        return false;
+      }
+
+      if (!(sigToken === queryToken || (isTypeVariable(sigToken) && isTypeVariable(queryToken)))) {
+        return false;
+      }
+    }
+
+    return true;
+  }
+
+  // Type-based search, for queries like "Str -> List U8". Exact signature matches rank
+  // above signatures that merely contain the query.
+  function typeScore(entry, queryTokens) {
+    let signatureTokens = typeTokens(entry.signature);
+
+    if (signatureTokens.length === queryTokens.length && typeTokensMatchAt(signatureTokens, queryTokens, 0)) {
+      return 100;
+    }
+
+    for (let start = 0; start + queryTokens.length <= signatureTokens.length; start++) {
+      if (typeTokensMatchAt(signatureTokens, queryTokens, start)) {
+        return 50;
+      }
+    }
+
+    return 0;
+  }
+
+  // Fuzzy search by name: exact and prefix matches rank highest, followed by substrings,
+  // then by names containing the query's characters in order (the closer together, the better).
+  function nameScore(entry, text) {
+    let name = entry.name.toLowerCase();
+    let qualifiedName = (entry.module + "." + entry.name).toLowerCase();
+
+    if (name === text || qualifiedName === text) {
+      return 100;
+    } else if (name.startsWith(text) || qualifiedName.startsWith(text)) {
+      return 80;
+    } else if (qualifiedName.includes(text)) {
+      return 60;
+    }
+
+    let gaps = 0;
+    let position = -1;
+
+    for (let char of text) {
+      let next = qualifiedName.indexOf(char, position + 1);
+
+      if (next === -1) {
+        // Not a name match; fall back to the doc comment.
+        return entry.docs.toLowerCase().includes(text) ? 10 : 0;
+      }
+
+      if (position !== -1) {
+        gaps += next - position - 1;
+      }
+
+      position = next;
+    }
+
+    return Math.max(20, 40 - gaps);
+  }
+
+  function showSearchResults(text) {
+    searchResults.replaceChildren();
+
+    if (text === "") {
+      searchResults.classList.add("hidden");
+      return;
+    }
+
+    let queryTokens = typeTokens(text);
+    let isTypeQuery = queryTokens.includes("->");
+    let scored = [];
+
+    searchIndex.forEach((entry) => {
+      let score = isTypeQuery ? typeScore(entry, queryTokens) : nameScore(entry, text.toLowerCase());
+
+      if (score > 0) {
+        scored.push({ entry, score });
+      }
+    });
+
+    scored.sort((a, b) => b.score - a.score || a.entry.name.localeCompare(b.entry.name));
+
+    scored.slice(0, MAX_RESULTS).forEach(({ entry }) => {
+      let item = document.createElement("li");
+      let link = document.createElement("a");
+      let name = document.createElement("span");
+
+      link.href = entry.url;
+      name.className = "search-result-name";
+      name.textContent = entry.module + "." + entry.name;
+      link.appendChild(name);
+
+      if (entry.signature !== "") {
+        let signature = document.createElement("code");
+
+        signature.className = "search-result-signature";
+        signature.textContent = entry.signature;
+        link.appendChild(signature);
+      }
+
+      item.appendChild(link);
+      searchResults.appendChild(item);
+    });
+
+    searchResults.classList.toggle("hidden", scored.length === 0);
+  }
 
   function search() {
     let text = searchBox.value.toLowerCase(); // Search is case-insensitive.
@@ -42,6 +177,7 @@
   }
 
   searchBox.addEventListener("input", search);
+  searchBox.addEventListener("input", () => showSearchResults(searchBox.value.trim()));
 
   search();
 
@@ -61,6 +197,7 @@
 
       // Reset sidebar state
       search();
+      showSearchResults("");
 
     }
   });
@@ -0,0 +1,456 @@
+//! Describes the memory layout of every type a platform shares with its host, as JSON, so that
+//! hosts (and the tools generating their bindings) in any language can rely on it.
+//!
+//! There's one entry in `targets` per architecture, since sizes and offsets depend on the pointer
+//! width. Types refer to each other by their `id`, which is only meaningful within one target.
+//! All sizes and offsets are in bytes.
+//!
+//! Values which point to heap memory Roc manages have a `refcount`: the value stores a pointer at
+//! `pointerOffset`, and the refcount (a pointer-sized integer) is at `refcountOffset` from where
+//! that pointer points. Small strings are stored inline, and so don't have a refcount; neither do
+//! empty lists, or the null variant of a nullable tag union, whose pointers are null.
+use crate::rust_glue::arch_to_str;
+use crate::types::{RocTagUnion, RocType, TypeId, Types};
+use roc_target::TargetInfo;
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct LayoutsJson<'a> {
+    targets: Vec<TargetJson<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TargetJson<'a> {
+    architecture: &'static str,
+    pointer_size: usize,
+    types: Vec<TypeJson<'a>>,
+}
+
+#[derive(Serialize)]
+struct TypeJson<'a> {
+    id: usize,
+    size: u32,
+    alignment: u32,
+    #[serde(flatten)]
+    kind: KindJson<'a>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    refcount: Option<RefcountJson>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+enum KindJson<'a> {
+    Str,
+    Bool,
+    Num {
+        num: String,
+    },
+    Unit,
+    EmptyTagUnion,
+    List {
+        element: usize,
+    },
+    Set {
+        element: usize,
+    },
+    Dict {
+        key: usize,
+        value: usize,
+    },
+    Box {
+        contents: usize,
+    },
+    Result {
+        discriminant: DiscriminantJson,
+        tags: Vec<TagJson<'a>>,
+    },
+    Struct {
+        name: &'a str,
+        fields: Vec<FieldJson>,
+    },
+    TagUnionPayload {
+        name: &'a str,
+        fields: Vec<FieldJson>,
+    },
+    RecursivePointer {
+        #[serde(rename = "type")]
+        pointee: usize,
+    },
+    Function {
+        name: &'a str,
+        args: Vec<usize>,
+        ret: usize,
+        caller: Option<&'a str>,
+    },
+    Enumeration {
+        name: &'a str,
+        discriminant: DiscriminantJson,
+        tags: Vec<TagJson<'a>>,
+    },
+    TagUnion {
+        name: &'a str,
+        discriminant: DiscriminantJson,
+        tags: Vec<TagJson<'a>>,
+    },
+    RecursiveTagUnion {
+        name: &'a str,
+        discriminant: DiscriminantJson,
+        tags: Vec<TagJson<'a>>,
+    },
+    #[serde(rename_all = "camelCase")]
+    NullableWrapped {
+        name: &'a str,
+        discriminant: DiscriminantJson,
+        null_tag: u16,
+        tags: Vec<TagJson<'a>>,
+    },
+    #[serde(rename_all = "camelCase")]
+    NullableUnwrapped {
+        name: &'a str,
+        null_tag: &'a str,
+        non_null_tag: &'a str,
+        payload: usize,
+    },
+    NonNullableUnwrapped {
+        name: &'a str,
+        tag: &'a str,
+        payload: usize,
+    },
+    SingleTagStruct {
+        name: &'a str,
+        tag: &'a str,
+        fields: Vec<FieldJson>,
+    },
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RefcountJson {
+    pointer_offset: i32,
+    refcount_offset: i32,
+}
+
+#[derive(Serialize)]
+struct DiscriminantJson {
+    size: u32,
+    offset: u32,
+}
+
+#[derive(Serialize)]
+struct FieldJson {
+    name: String,
+    #[serde(rename = "type")]
+    type_id: usize,
+    offset: u32,
+}
+
+#[derive(Serialize)]
+struct TagJson<'a> {
+    name: &'a str,
+    discriminant: usize,
+    payload: Option<usize>,
+}
+
+pub fn emit_json(types_and_targets: &[(Types, TargetInfo)]) -> String {
+    let targets = types_and_targets
+        .iter()
+        .map(|(types, target_info)| TargetJson {
+            architecture: arch_to_str(target_info.architecture),
+            pointer_size: target_info.ptr_size(),
+            types: types
+                .ids()
+                .map(|id| type_json(types, *target_info, id))
+                .collect(),
+        })
+        .collect();
+
+    let mut buf = serde_json::to_string_pretty(&LayoutsJson { targets })
+        .expect("type layouts are always valid JSON");
+
+    buf.push('\n');
+
+    buf
+}
+
+fn type_json(types: &Types, target_info: TargetInfo, id: TypeId) -> TypeJson<'_> {
+    let heap_pointer = Some(RefcountJson {
+        pointer_offset: 0,
+        refcount_offset: -(target_info.ptr_size() as i32),
+    });
+
+    let (kind, refcount) = match types.get_type(id) {
+        RocType::RocStr => (KindJson::Str, heap_pointer),
+        RocType::Bool => (KindJson::Bool, None),
+        RocType::Num(num) => (
+            KindJson::Num {
+                num: format!("{num:?}"),
+            },
+            None,
+        ),
+        RocType::Unit => (KindJson::Unit, None),
+        RocType::EmptyTagUnion => (KindJson::EmptyTagUnion, None),
+        RocType::RocList(elem) => (
+            KindJson::List {
+                element: elem.index(),
+            },
+            heap_pointer,
+        ),
+        RocType::RocSet(elem) => (
+            KindJson::Set {
+                element: elem.index(),
+            },
+            None,
+        ),
+        RocType::RocDict(key, value) => (
+            KindJson::Dict {
+                key: key.index(),
+                value: value.index(),
+            },
+            None,
+        ),
+        RocType::RocBox(contents) => (
+            KindJson::Box {
+                contents: contents.index(),
+            },
+            heap_pointer,
+        ),
+        RocType::RocResult(ok, err) => {
+            // like roc_std::RocResult, a C union of the payloads followed by the tag
+            let payload_size = types
+                .size_rounded_to_alignment(*ok)
+                .max(types.size_rounded_to_alignment(*err));
+            let payload_align = types.align(*ok).max(types.align(*err));
+
+            let kind = KindJson::Result {
+                discriminant: DiscriminantJson {
+                    size: 1,
+                    offset: round_up_to_alignment(payload_size, payload_align),
+                },
+                tags: tags_json([("Err", Some(*err)), ("Ok", Some(*ok))]),
+            };
+
+            (kind, None)
+        }
+        RocType::Struct { name, fields } => {
+            let kind = KindJson::Struct {
+                name,
+                fields: fields_json(types, fields.iter().map(|(label, id)| (label.clone(), *id))),
+            };
+
+            (kind, None)
+        }
+        RocType::TagUnionPayload { name, fields } => {
+            let kind = KindJson::TagUnionPayload {
+                name,
+                fields: fields_json(
+                    types,
+                    fields.iter().map(|(index, id)| (index.to_string(), *id)),
+                ),
+            };
+
+            (kind, None)
+        }
+        RocType::RecursivePointer(pointee) => (
+            KindJson::RecursivePointer {
+                pointee: pointee.index(),
+            },
+            None,
+        ),
+        RocType::Function {
+            name,
+            args,
+            ret,
+            caller,
+        } => {
+            let kind = KindJson::Function {
+                name,
+                args: args.iter().map(|arg| arg.index()).collect(),
+                ret: ret.index(),
+                caller: caller.as_deref(),
+            };
+
+            (kind, None)
+        }
+        RocType::TagUnion(union) => tag_union_json(types, union, heap_pointer),
+    };
+
+    TypeJson {
+        id: id.index(),
+        size: types.size_rounded_to_alignment(id),
+        alignment: types.align(id),
+        kind,
+        refcount,
+    }
+}
+
+fn tag_union_json<'a>(
+    types: &Types,
+    union: &'a RocTagUnion,
+    heap_pointer: Option<RefcountJson>,
+) -> (KindJson<'a>, Option<RefcountJson>) {
+    match union {
+        RocTagUnion::Enumeration { name, tags, size } => {
+            let kind = KindJson::Enumeration {
+                name,
+                discriminant: DiscriminantJson {
+                    size: *size,
+                    offset: 0,
+                },
+                tags: tags_json(tags.iter().map(|tag| (tag.as_str(), None))),
+            };
+
+            (kind, None)
+        }
+        RocTagUnion::NonRecursive {
+            name,
+            tags,
+            discriminant_size,
+            discriminant_offset,
+        } => {
+            let kind = KindJson::TagUnion {
+                name,
+                discriminant: DiscriminantJson {
+                    size: *discriminant_size,
+                    offset: *discriminant_offset,
+                },
+                tags: tags_json(
+                    tags.iter()
+                        .map(|(tag_name, payload)| (tag_name.as_str(), *payload)),
+                ),
+            };
+
+            (kind, None)
+        }
+        RocTagUnion::Recursive {
+            name,
+            tags,
+            discriminant_size,
+            discriminant_offset,
+        } => {
+            let kind = KindJson::RecursiveTagUnion {
+                name,
+                discriminant: DiscriminantJson {
+                    size: *discriminant_size,
+                    offset: *discriminant_offset,
+                },
+                tags: tags_json(
+                    tags.iter()
+                        .map(|(tag_name, payload)| (tag_name.as_str(), *payload)),
+                ),
+            };
+
+            (kind, heap_pointer)
+        }
+        RocTagUnion::NullableWrapped {
+            name,
+            index_of_null_tag,
+            tags,
+            discriminant_size,
+            discriminant_offset,
+        } => {
+            let kind = KindJson::NullableWrapped {
+                name,
+                discriminant: DiscriminantJson {
+                    size: *discriminant_size,
+                    offset: *discriminant_offset,
+                },
+                null_tag: *index_of_null_tag,
+                tags: tags_json(
+                    tags.iter()
+                        .map(|(tag_name, payload)| (tag_name.as_str(), *payload)),
+                ),
+            };
+
+            (kind, heap_pointer)
+        }
+        RocTagUnion::NullableUnwrapped {
+            name,
+            null_tag,
+            non_null_tag,
+            non_null_payload,
+            null_represents_first_tag: _,
+        } => {
+            let kind = KindJson::NullableUnwrapped {
+                name,
+                null_tag,
+                non_null_tag,
+                payload: non_null_payload.index(),
+            };
+
+            (kind, heap_pointer)
+        }
+        RocTagUnion::NonNullableUnwrapped {
+            name,
+            tag_name,
+            payload,
+        } => {
+            let kind = KindJson::NonNullableUnwrapped {
+                name,
+                tag: tag_name,
+                payload: payload.index(),
+            };
+
+            (kind, heap_pointer)
+        }
+        RocTagUnion::SingleTagStruct {
+            name,
+            tag_name,
+            payload_fields,
+        } => {
+            let kind = KindJson::SingleTagStruct {
+                name,
+                tag: tag_name,
+                fields: fields_json(
+                    types,
+                    payload_fields
+                        .iter()
+                        .enumerate()
+                        .map(|(index, id)| (index.to_string(), *id)),
+                ),
+            };
+
+            (kind, None)
+        }
+    }
+}
+
+/// Fields are laid out in order, each one at the next offset that suits its alignment, as in C
+fn fields_json(types: &Types, fields: impl Iterator<Item = (String, TypeId)>) -> Vec<FieldJson> {
+    let mut offset = 0;
+
+    fields
+        .map(|(name, id)| {
+            offset = round_up_to_alignment(offset, types.align(id));
+
+            let field = FieldJson {
+                name,
+                type_id: id.index(),
+                offset,
+            };
+
+            offset += types.size_rounded_to_alignment(id);
+
+            field
+        })
+        .collect()
+}
+
+/// Each tag's discriminant is its index, since tags are sorted by name
+fn tags_json<'a>(tags: impl IntoIterator<Item = (&'a str, Option<TypeId>)>) -> Vec<TagJson<'a>> {
+    tags.into_iter()
+        .enumerate()
+        .map(|(discriminant, (name, payload))| TagJson {
+            name,
+            discriminant,
+            payload: payload.map(TypeId::index),
+        })
+        .collect()
+}
+
+fn round_up_to_alignment(width: u32, alignment: u32) -> u32 {
+    match alignment {
+        0 => width,
+        alignment => (width + alignment - 1) / alignment * alignment,
+    }
+}
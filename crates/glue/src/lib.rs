@@ -3,12 +3,14 @@
 //! however, it's a great convenience! Currently supports Rust platforms, and
 //! the plan is to support any language via a plugin model.
 pub mod enums;
+pub mod layouts;
 pub mod load;
 pub mod rust_glue;
 pub mod structs;
+pub mod test_doubles;
 pub mod types;
 
 #[rustfmt::skip]
 pub mod glue;
 
-pub use load::generate;
+pub use load::{generate, generate_layouts};
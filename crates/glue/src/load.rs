@@ -1,3 +1,4 @@
+use crate::layouts;
 use crate::rust_glue;
 use crate::types::{Env, Types};
 use bumpalo::Bump;
@@ -22,6 +23,32 @@ impl IgnoreErrors {
 }
 
 pub fn generate(input_path: &Path, output_path: &Path) -> io::Result<i32> {
+    write_generated(
+        input_path,
+        output_path,
+        "type declarations",
+        |types_and_targets| {
+            let mut buf = std::str::from_utf8(rust_glue::HEADER).unwrap().to_string();
+
+            buf.push_str(&rust_glue::emit(types_and_targets));
+
+            buf
+        },
+    )
+}
+
+/// Writes the memory layout of each type the platform shares with its host to `output_path`,
+/// as JSON. See [crate::layouts] for what it describes.
+pub fn generate_layouts(input_path: &Path, output_path: &Path) -> io::Result<i32> {
+    write_generated(input_path, output_path, "layouts", layouts::emit_json)
+}
+
+fn write_generated(
+    input_path: &Path,
+    output_path: &Path,
+    description: &str,
+    emit: impl Fn(&[(Types, TargetInfo)]) -> String,
+) -> io::Result<i32> {
     match load_types(
         input_path.to_path_buf(),
         Threading::AllAvailable,
@@ -38,10 +65,7 @@ pub fn generate(input_path: &Path, output_path: &Path) -> io::Result<i32> {
                 process::exit(1);
             });
 
-            let mut buf = std::str::from_utf8(rust_glue::HEADER).unwrap().to_string();
-            let body = rust_glue::emit(&types_and_targets);
-
-            buf.push_str(&body);
+            let buf = emit(&types_and_targets);
 
             file.write_all(buf.as_bytes()).unwrap_or_else(|err| {
                 eprintln!(
@@ -54,7 +78,8 @@ pub fn generate(input_path: &Path, output_path: &Path) -> io::Result<i32> {
             });
 
             println!(
-                "🎉 Generated type declarations in:\n\n\t{}",
+                "🎉 Generated {} in:\n\n\t{}",
+                description,
                 output_path.display()
             );
 
@@ -102,10 +127,11 @@ pub fn load_types(
             palette: DEFAULT_PALETTE,
             threading,
             exec_mode: ExecutionMode::Check,
+            ..Default::default()
         },
     )
     .unwrap_or_else(|problem| match problem {
-        LoadingProblem::FormattedReport(report) => {
+        LoadingProblem::FormattedReport(report, _) => {
             eprintln!("{}", report);
 
             process::exit(1);
@@ -133,7 +159,9 @@ pub fn load_types(
         use roc_can::expr::DeclarationTag::*;
 
         match decls.declarations[index] {
-            Value | Function(_) | Recursive(_) | TailRecursive(_) => Some(decls.variables[index]),
+            Value | Function(_) | Recursive(_) | TailRecursive(_) => {
+                Some((decls.symbols[index].value, decls.variables[index]))
+            }
             Destructure(_) => {
                 // figure out if we need to export non-identifier defs - when would that
                 // happen?
@@ -143,7 +171,7 @@ pub fn load_types(
                 // handled by future iterations
                 None
             }
-            Expectation | ExpectationFx => {
+            Expectation | ExpectationFx | Benchmark => {
                 // not publicly visible
                 None
             }
@@ -294,12 +294,96 @@ fn add_type(target_info: TargetInfo, id: TypeId, types: &Types, impls: &mut Impl
             // This is recursively pointing to a type that should already have been added,
             // so no extra work needs to happen.
         }
-        RocType::Function { .. } => {
-            // TODO actually generate glue functions!
+        RocType::Function {
+            name,
+            args,
+            ret,
+            caller: Some(caller),
+        } => add_function(name, target_info, id, args, *ret, caller, types, impls),
+        RocType::Function { caller: None, .. } => {
+            // Roc doesn't expose a way for the host to call this function.
         }
     }
 }
 
+/// A closure the host got from Roc, which it can call through the caller Roc exposes for it.
+#[allow(clippy::too_many_arguments)]
+fn add_function(
+    name: &str,
+    target_info: TargetInfo,
+    id: TypeId,
+    args: &[TypeId],
+    ret: TypeId,
+    caller: &str,
+    types: &Types,
+    impls: &mut Impls,
+) {
+    let name = escape_kw(name.to_string());
+
+    // The host can't do anything with what the closure captured except pass it back to Roc,
+    // so we only need its size and alignment.
+    {
+        let derive = derive_str(types.get_type(id), types, true);
+        let size = types.size_rounded_to_alignment(id);
+        let align = types.align(id).max(1);
+        let body = format!(
+            "{derive}\n#[repr(C, align({align}))]\npub struct {name} {{\n{INDENT}closure_data: [u8; {size}],\n}}"
+        );
+
+        add_decl(impls, None, target_info, body);
+    }
+
+    let ret_type = type_name(ret, types);
+    let mut params = Vec::with_capacity(args.len());
+    let mut extern_params = Vec::with_capacity(args.len() + 2);
+    let mut call_args = Vec::with_capacity(args.len() + 2);
+    let mut moves = String::new();
+
+    for (index, arg_id) in args.iter().enumerate() {
+        let arg_type = type_name(*arg_id, types);
+
+        params.push(format!("arg{index}: {arg_type}"));
+        extern_params.push(format!("arg{index}: *const {arg_type}"));
+        call_args.push(format!("&*arg{index}"));
+
+        // Roc owns the arguments now, so we must not drop them.
+        writeln!(
+            moves,
+            "{INDENT}{INDENT}let arg{index} = core::mem::ManuallyDrop::new(arg{index});"
+        )
+        .unwrap();
+    }
+
+    extern_params.push("closure_data: *const u8".to_string());
+    extern_params.push(format!("output: *mut {ret_type}"));
+    call_args.push("self.closure_data.as_ptr()".to_string());
+    call_args.push("output.as_mut_ptr()".to_string());
+
+    let params = params.join(", ");
+    let extern_params = extern_params.join(", ");
+    let call_args = call_args.join(", ");
+    let body = format!(
+        r#"/// Calls the closure. Roc frees what the closure captured when it's done, so this takes
+    /// the closure by value; don't call a copy of it afterwards.
+    pub fn call(self, {params}) -> {ret_type} {{
+        extern "C" {{
+            #[link_name = "{caller}"]
+            fn caller({extern_params});
+        }}
+
+{moves}        let mut output = core::mem::MaybeUninit::uninit();
+
+        unsafe {{
+            caller({call_args});
+
+            output.assume_init()
+        }}
+    }}"#
+    );
+
+    add_decl(impls, Some(format!("impl {name}")), target_info, body);
+}
+
 fn add_single_tag_struct(
     name: &str,
     tag_name: &str,
@@ -2177,7 +2261,7 @@ pub struct {name} {{
     }
 }
 
-fn arch_to_str(architecture: Architecture) -> &'static str {
+pub(crate) fn arch_to_str(architecture: Architecture) -> &'static str {
     match architecture {
         Architecture::X86_64 => "x86_64",
         Architecture::X86_32 => "x86",
@@ -0,0 +1,319 @@
+//! Generates a skeleton of test doubles for a platform's effects.
+//!
+//! A hosted module `Effect` can import a `EffectDoubles` interface, which exposes a pure Roc
+//! implementation of each effect under the same name. `roc test` calls those instead of the host,
+//! so apps using the platform can test effectful code deterministically. Each double has the type
+//! of its effect without the `Effect`: `putLine : Str -> {}` stands in for
+//! `putLine : Str -> Effect {}`.
+use bumpalo::Bump;
+use roc_load::docs::{DocEntry, ModuleDocumentation, RecordField, TypeAnnotation};
+use roc_load::{ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, Threading};
+use roc_packaging::cache::{self, RocCacheDir};
+use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
+use roc_target::TargetInfo;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process;
+use target_lexicon::Triple;
+
+/// The suffix of the name of a hosted module's doubles module
+const DOUBLES_SUFFIX: &str = "Doubles";
+
+/// The types an effect's result is wrapped in, which its double returns the result without
+const EFFECT_TYPE_NAMES: [&str; 2] = ["Effect", "Task"];
+
+/// Writes a doubles module to `output_path`, for the hosted module its file name refers to.
+/// For example, `EffectDoubles.roc` gets doubles for the platform's `Effect` module.
+pub fn generate(input_path: &Path, output_path: &Path) -> io::Result<i32> {
+    let doubles_name = output_path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default();
+
+    let hosted_name = match doubles_name.strip_suffix(DOUBLES_SUFFIX) {
+        Some(name) if !name.is_empty() => name,
+        _ => {
+            eprintln!(
+                "Test doubles for a hosted module named `Foo` go in a file named `Foo{}.roc`, but the output file was named {}.",
+                DOUBLES_SUFFIX,
+                output_path.display()
+            );
+
+            return Ok(1);
+        }
+    };
+
+    let arena = &Bump::new();
+    let target_info: TargetInfo = (&Triple::host()).into();
+    let LoadedModule { docs_by_module, .. } = roc_load::load_and_typecheck(
+        arena,
+        input_path.to_path_buf(),
+        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+        LoadConfig {
+            target_info,
+            render: RenderTarget::ColorTerminal,
+            palette: DEFAULT_PALETTE,
+            threading: Threading::AllAvailable,
+            exec_mode: ExecutionMode::Check,
+            ..Default::default()
+        },
+    )
+    .unwrap_or_else(|problem| match problem {
+        LoadingProblem::FormattedReport(report, _) => {
+            eprintln!("{}", report);
+
+            process::exit(1);
+        }
+        problem => {
+            eprintln!(
+                "Error loading platform module file {} - {:?}",
+                input_path.display(),
+                problem
+            );
+
+            process::exit(1);
+        }
+    });
+
+    let hosted_modules = || docs_by_module.values().filter(|module| module.is_hosted);
+
+    let module = match hosted_modules().find(|module| module.name == hosted_name) {
+        Some(module) => module,
+        None => {
+            let names: Vec<&str> = hosted_modules()
+                .map(|module| module.name.as_str())
+                .collect();
+
+            eprintln!(
+                "The platform has no hosted module named `{}`. Its hosted modules are: {}",
+                hosted_name,
+                names.join(", ")
+            );
+
+            return Ok(1);
+        }
+    };
+
+    fs::write(output_path, doubles_module(doubles_name, module))?;
+
+    println!(
+        "🎉 Generated test doubles in:\n\n\t{}\n\nAdd `{}` to the imports of `{}` to use them when running `roc test`.",
+        output_path.display(),
+        doubles_name,
+        hosted_name
+    );
+
+    Ok(0)
+}
+
+fn doubles_module(doubles_name: &str, module: &ModuleDocumentation) -> String {
+    let effects: Vec<_> = module
+        .entries
+        .iter()
+        .filter_map(|entry| match entry {
+            DocEntry::DocDef(def)
+                if module.exposed_symbols.contains(&def.symbol)
+                    && def.name.starts_with(char::is_lowercase) =>
+            {
+                Some(def)
+            }
+            _ => None,
+        })
+        .collect();
+
+    let mut buf = String::new();
+    let names: Vec<&str> = effects.iter().map(|def| def.name.as_str()).collect();
+
+    writeln!(buf, "interface {}", doubles_name).unwrap();
+    writeln!(buf, "    exposes [{}]", names.join(", ")).unwrap();
+    writeln!(buf, "    imports []").unwrap();
+
+    for def in effects {
+        buf.push('\n');
+        write!(buf, "{} : ", def.name).unwrap();
+
+        match DoubleType::of_effect(&def.type_annotation) {
+            DoubleType::Function { args, output } => {
+                write_function(&mut buf, args, output);
+
+                let arg_names: Vec<String> = (0..args.len())
+                    .map(|index| format!("arg{}", index))
+                    .collect();
+
+                write!(buf, "\n{} = \\{} ->", def.name, arg_names.join(", ")).unwrap();
+            }
+            DoubleType::Value(value) => {
+                write_type(&mut buf, value, Position::Top);
+                write!(buf, "\n{} =", def.name).unwrap();
+            }
+        }
+
+        writeln!(buf, "\n    crash \"TODO implement {}\"", def.name).unwrap();
+    }
+
+    buf
+}
+
+/// The type of an effect's double: the effect's type, with the `Effect` it returns unwrapped
+enum DoubleType<'a> {
+    Function {
+        args: &'a [TypeAnnotation],
+        output: &'a TypeAnnotation,
+    },
+    Value(&'a TypeAnnotation),
+}
+
+impl<'a> DoubleType<'a> {
+    fn of_effect(effect_type: &'a TypeAnnotation) -> Self {
+        fn unwrap_effect(annotation: &TypeAnnotation) -> &TypeAnnotation {
+            match annotation {
+                TypeAnnotation::Apply { name, parts } if parts.len() == 1 && is_effect(name) => {
+                    &parts[0]
+                }
+                _ => annotation,
+            }
+        }
+
+        match effect_type {
+            TypeAnnotation::Function { args, output } => DoubleType::Function {
+                args,
+                output: unwrap_effect(output),
+            },
+            _ => DoubleType::Value(unwrap_effect(effect_type)),
+        }
+    }
+}
+
+/// Whether a type is the one a hosted module's effects return, like `Effect` or `Effect.Effect`
+fn is_effect(type_name: &str) -> bool {
+    let unqualified = type_name.rsplit('.').next().unwrap_or(type_name);
+
+    EFFECT_TYPE_NAMES.contains(&unqualified)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Position {
+    Top,
+    FunctionArg,
+    ApplyArg,
+}
+
+fn write_function(buf: &mut String, args: &[TypeAnnotation], output: &TypeAnnotation) {
+    for (index, arg) in args.iter().enumerate() {
+        if index > 0 {
+            buf.push_str(", ");
+        }
+
+        write_type(buf, arg, Position::FunctionArg);
+    }
+
+    buf.push_str(" -> ");
+    write_type(buf, output, Position::Top);
+}
+
+fn write_type(buf: &mut String, annotation: &TypeAnnotation, position: Position) {
+    match annotation {
+        TypeAnnotation::Function { args, output } => {
+            let parens = position != Position::Top;
+
+            if parens {
+                buf.push('(');
+            }
+
+            write_function(buf, args, output);
+
+            if parens {
+                buf.push(')');
+            }
+        }
+        TypeAnnotation::Apply { name, parts } => {
+            let parens = !parts.is_empty() && position == Position::ApplyArg;
+
+            if parens {
+                buf.push('(');
+            }
+
+            buf.push_str(name);
+
+            for part in parts {
+                buf.push(' ');
+                write_type(buf, part, Position::ApplyArg);
+            }
+
+            if parens {
+                buf.push(')');
+            }
+        }
+        TypeAnnotation::BoundVariable(name) => buf.push_str(name),
+        TypeAnnotation::Record { fields, extension } => {
+            if fields.is_empty() {
+                buf.push_str("{}");
+            } else {
+                buf.push_str("{ ");
+
+                for (index, field) in fields.iter().enumerate() {
+                    if index > 0 {
+                        buf.push_str(", ");
+                    }
+
+                    match field {
+                        RecordField::RecordField {
+                            name,
+                            type_annotation,
+                        } => {
+                            write!(buf, "{} : ", name).unwrap();
+                            write_type(buf, type_annotation, Position::Top);
+                        }
+                        RecordField::OptionalField {
+                            name,
+                            type_annotation,
+                        } => {
+                            write!(buf, "{} ? ", name).unwrap();
+                            write_type(buf, type_annotation, Position::Top);
+                        }
+                        RecordField::LabelOnly { name } => buf.push_str(name),
+                    }
+                }
+
+                buf.push_str(" }");
+            }
+
+            write_extension(buf, extension);
+        }
+        TypeAnnotation::TagUnion { tags, extension } => {
+            buf.push('[');
+
+            for (index, tag) in tags.iter().enumerate() {
+                if index > 0 {
+                    buf.push_str(", ");
+                }
+
+                buf.push_str(&tag.name);
+
+                for value in tag.values.iter() {
+                    buf.push(' ');
+                    write_type(buf, value, Position::ApplyArg);
+                }
+            }
+
+            buf.push(']');
+            write_extension(buf, extension);
+        }
+        TypeAnnotation::Wildcard => buf.push('*'),
+        TypeAnnotation::ObscuredTagUnion
+        | TypeAnnotation::ObscuredRecord
+        | TypeAnnotation::Ability { .. }
+        | TypeAnnotation::NoTypeAnn => buf.push('_'),
+    }
+}
+
+fn write_extension(buf: &mut String, extension: &TypeAnnotation) {
+    match extension {
+        TypeAnnotation::BoundVariable(_) | TypeAnnotation::Wildcard => {
+            write_type(buf, extension, Position::ApplyArg)
+        }
+        _ => {}
+    }
+}
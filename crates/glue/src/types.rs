@@ -7,8 +7,10 @@ use roc_builtins::bitcode::{
     IntWidth::{self, *},
 };
 use roc_collections::VecMap;
+use roc_error_macros::user_error;
 use roc_module::{
     ident::TagName,
+    mangle::{self, HostClosureFn},
     symbol::{Interns, Symbol},
 };
 use roc_mono::layout::{
@@ -33,6 +35,11 @@ impl TypeId {
 
     /// When adding, we check for overflow based on whether we've exceeded this.
     const MAX: Self = Self(Self::PENDING.0 - 1);
+
+    /// Where this type is in its [Types], counting from 0
+    pub fn index(self) -> usize {
+        self.0
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -299,11 +306,13 @@ impl Types {
                     name: name_a,
                     args: args_a,
                     ret: ret_a,
+                    ..
                 },
                 Function {
                     name: name_b,
                     args: args_b,
                     ret: ret_b,
+                    ..
                 },
             ) => {
                 // for functions, the name is actually important because two functions
@@ -522,6 +531,9 @@ pub enum RocType {
         name: String,
         args: Vec<TypeId>,
         ret: TypeId,
+        /// What the host calls to run the function, like `roc__mainForHost_1__Fx_caller`.
+        /// Roc only exposes one for closures whose type is an alias the platform declares.
+        caller: Option<String>,
     },
     /// A zero-sized type, such as an empty record or a single-tag union with no payload
     Unit,
@@ -664,6 +676,8 @@ pub struct Env<'a> {
     pending_recursive_types: VecMap<TypeId, Variable>,
     known_recursive_types: VecMap<Variable, TypeId>,
     target: TargetInfo,
+    /// The top-level def whose type we're adding, like `mainForHost`
+    entry_point: Option<Symbol>,
 }
 
 impl<'a> Env<'a> {
@@ -684,19 +698,23 @@ impl<'a> Env<'a> {
             known_recursive_types: Default::default(),
             layout_cache: LayoutCache::new(layout_interner, target),
             target,
+            entry_point: None,
         }
     }
 
     pub fn vars_to_types<I>(&mut self, variables: I) -> Types
     where
-        I: Iterator<Item = Variable>,
+        I: Iterator<Item = (Symbol, Variable)>,
     {
         let mut types = Types::with_capacity(variables.size_hint().0);
 
-        for var in variables {
+        for (symbol, var) in variables {
+            self.entry_point = Some(symbol);
             self.add_type(var, &mut types);
         }
 
+        self.entry_point = None;
+
         self.resolve_pending_recursive_types(&mut types);
 
         types
@@ -841,7 +859,24 @@ fn add_type_help<'a>(
                 add_type_help(env, ret_layout, *ret_var, None, types)
             };
 
-            let name = format!("TODO_roc_function_{:?}", closure_var);
+            let (name, caller) = match (opt_name, env.entry_point) {
+                (Some(alias), Some(entry_point)) => {
+                    let closure_type = mangle::host_closure_type_name(
+                        alias.module_string(env.interns),
+                        alias.as_str(env.interns),
+                    );
+                    let caller = mangle::host_closure_fn_name(
+                        entry_point.as_str(env.interns),
+                        1,
+                        &closure_type,
+                        HostClosureFn::Caller,
+                    );
+
+                    (alias.as_str(env.interns).to_string(), Some(caller))
+                }
+                _ => (format!("TODO_roc_function_{:?}", closure_var), None),
+            };
+
             let fn_type_id = types.add_named(
                 &env.layout_cache.interner,
                 name.clone(),
@@ -849,6 +884,7 @@ fn add_type_help<'a>(
                     name,
                     args: arg_type_ids.clone(),
                     ret: ret_type_id,
+                    caller,
                 },
                 layout,
             );
@@ -978,6 +1014,13 @@ fn add_type_help<'a>(
 
                         type_id
                     }
+                    _ if *name == Symbol::ASYNC_ASYNC => {
+                        // A task is a closure which the host would need to call with a
+                        // callback of its own, and hosts can't build Roc closures yet.
+                        user_error!(
+                            "Glue doesn't support the `Async` type yet, because there is no way for a host to run a task's callbacks. Have the platform run its tasks itself, and expose the `Result` they end with instead."
+                        )
+                    }
                     _ => {
                         unreachable!()
                     }
@@ -95,6 +95,12 @@ pub unsafe extern "C" fn roc_panic(c_ptr: *mut c_void, tag_id: u32) {
     }
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn roc_oom(size: usize, _alignment: u32) {
+    eprintln!("Roc ran out of memory allocating {} bytes", size);
+    std::process::exit(1);
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn roc_memcpy(dst: *mut c_void, src: *mut c_void, n: usize) -> *mut c_void {
     libc::memcpy(dst, src, n)
@@ -0,0 +1,129 @@
+#[macro_use]
+extern crate pretty_assertions;
+
+#[macro_use]
+extern crate indoc;
+
+mod helpers;
+
+#[cfg(test)]
+mod test_gen_layouts {
+    use crate::helpers::generate_layouts;
+
+    use serde_json::{json, Value};
+
+    /// The JSON for the type with this name, with the ids it refers to replaced by `"_"`, since ids
+    /// depend on the order the types were found in.
+    fn named_type(json: &Value, name: &str) -> Value {
+        let mut found = json["targets"][0]["types"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|type_json| type_json["name"] == name)
+            .unwrap_or_else(|| panic!("no type named {name} in:\n{json:#}"))
+            .clone();
+
+        erase_ids(&mut found);
+
+        found
+    }
+
+    fn erase_ids(value: &mut Value) {
+        match value {
+            Value::Object(fields) => {
+                for (key, field) in fields.iter_mut() {
+                    if ["id", "type", "payload"].contains(&key.as_str()) && field.is_number() {
+                        *field = json!("_");
+                    } else {
+                        erase_ids(field);
+                    }
+                }
+            }
+            Value::Array(elems) => elems.iter_mut().for_each(erase_ids),
+            _ => {}
+        }
+    }
+
+    fn layouts(module: &str) -> Value {
+        serde_json::from_str(&generate_layouts(module)).unwrap()
+    }
+
+    #[test]
+    fn record_fields_in_c_order() {
+        let module = indoc!(
+            r#"
+            MyRcd : { a : U64, b : I128 }
+
+            main : MyRcd
+            main = { a: 1u64, b: 2i128 }
+        "#
+        );
+
+        let json = layouts(module);
+
+        assert_eq!(json["targets"][0]["architecture"], "x86_64");
+        assert_eq!(json["targets"][0]["pointerSize"], 8);
+        assert_eq!(
+            named_type(&json, "MyRcd"),
+            json!({
+                "id": "_", "size": 32, "alignment": 16, "kind": "struct", "name": "MyRcd",
+                "fields": [
+                    { "name": "b", "type": "_", "offset": 0 },
+                    { "name": "a", "type": "_", "offset": 16 }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn tag_union_discriminant() {
+        let module = indoc!(
+            r#"
+            MyTags : [Foo U64, Bar Str, Baz]
+
+            main : MyTags
+            main = Baz
+        "#
+        );
+
+        assert_eq!(
+            named_type(&layouts(module), "MyTags"),
+            json!({
+                "id": "_", "size": 32, "alignment": 8, "kind": "tagUnion", "name": "MyTags",
+                "discriminant": { "size": 1, "offset": 24 },
+                "tags": [
+                    { "name": "Bar", "discriminant": 0, "payload": "_" },
+                    { "name": "Baz", "discriminant": 1, "payload": null },
+                    { "name": "Foo", "discriminant": 2, "payload": "_" }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn str_has_refcount() {
+        let module = indoc!(
+            r#"
+            MyRcd : { name : Str }
+
+            main : MyRcd
+            main = { name: "foo" }
+        "#
+        );
+
+        let json = layouts(module);
+        let str_type = json["targets"][0]["types"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|type_json| type_json["kind"] == "str")
+            .unwrap();
+
+        assert_eq!(str_type["size"], 24);
+        assert_eq!(str_type["alignment"], 8);
+        assert_eq!(
+            str_type["refcount"],
+            json!({ "pointerOffset": 0, "refcountOffset": -8 })
+        );
+    }
+}
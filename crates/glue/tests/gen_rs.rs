@@ -233,4 +233,65 @@ mod test_gen_rs {
             )
         );
     }
+
+    #[test]
+    fn closure_aliased() {
+        let module = indoc!(
+            r#"
+            Fx : U64 -> Str
+
+            main : Fx
+            main = \n -> Num.toStr n
+        "#
+        );
+
+        assert_eq!(
+            generate_bindings(module)
+                .strip_prefix('\n')
+                .unwrap_or_default(),
+            indoc!(
+                r#"
+                #[cfg(any(
+                    target_arch = "arm",
+                    target_arch = "aarch64",
+                    target_arch = "wasm32",
+                    target_arch = "x86",
+                    target_arch = "x86_64"
+                ))]
+                #[derive(Clone, Copy, Debug, Eq, Ord, Hash, PartialEq, PartialOrd)]
+                #[repr(C, align(1))]
+                pub struct Fx {
+                    closure_data: [u8; 0],
+                }
+
+                impl Fx {
+                    #[cfg(any(
+                        target_arch = "arm",
+                        target_arch = "aarch64",
+                        target_arch = "wasm32",
+                        target_arch = "x86",
+                        target_arch = "x86_64"
+                    ))]
+                    /// Calls the closure. Roc frees what the closure captured when it's done, so this takes
+                    /// the closure by value; don't call a copy of it afterwards.
+                    pub fn call(self, arg0: u64) -> roc_std::RocStr {
+                        extern "C" {
+                            #[link_name = "roc__main_1__Fx_caller"]
+                            fn caller(arg0: *const u64, closure_data: *const u8, output: *mut roc_std::RocStr);
+                        }
+
+                        let arg0 = core::mem::ManuallyDrop::new(arg0);
+                        let mut output = core::mem::MaybeUninit::uninit();
+
+                        unsafe {
+                            caller(&*arg0, self.closure_data.as_ptr(), output.as_mut_ptr());
+
+                            output.assume_init()
+                        }
+                    }
+                }
+            "#
+            )
+        );
+    }
 }
@@ -159,6 +159,113 @@ mod glue_cli_run {
         assert_eq!(all_fixtures, &mut VecSet::default());
     }
 
+    #[test]
+    fn async_is_unsupported() {
+        // This platform isn't in fixtures/, because glue is expected to reject it.
+        let dir = tempfile::tempdir().unwrap();
+        let platform_module_path = dir.path().join("platform.roc");
+
+        fs::write(
+            &platform_module_path,
+            indoc!(
+                r#"
+                platform "test-platform"
+                    requires {} { main : _ }
+                    exposes []
+                    packages {}
+                    imports [Async.{ Async }]
+                    provides [mainForHost]
+
+                mainForHost : Async Str U8
+                mainForHost = main
+                "#
+            ),
+        )
+        .unwrap();
+
+        let glue_out = run_glue([
+            "glue".to_string(),
+            platform_module_path.to_str().unwrap().to_string(),
+            dir.path()
+                .join("test_glue.rs")
+                .to_str()
+                .unwrap()
+                .to_string(),
+        ]);
+
+        assert!(!glue_out.status.success(), "bad status {:?}", glue_out);
+        assert!(
+            glue_out
+                .stderr
+                .contains("Glue doesn't support the `Async` type yet"),
+            "Unexpected stderr:\n\n{}",
+            glue_out.stderr
+        );
+    }
+
+    #[test]
+    fn test_doubles_only_unwrap_effects() {
+        let dir = tempfile::tempdir().unwrap();
+        let platform_module_path = dir.path().join("platform.roc");
+        let doubles_path = dir.path().join("EffectDoubles.roc");
+
+        fs::write(
+            &platform_module_path,
+            indoc!(
+                r#"
+                platform "test-platform"
+                    requires {} { main : Effect.Effect {} }
+                    exposes []
+                    packages {}
+                    imports [pf.Effect]
+                    provides [mainForHost]
+
+                mainForHost : Effect.Effect {} as Fx
+                mainForHost = main
+                "#
+            ),
+        )
+        .unwrap();
+
+        fs::write(
+            dir.path().join("Effect.roc"),
+            indoc!(
+                r#"
+                hosted Effect
+                    exposes [Effect, after, map, always, forever, putLine, getLines, splitLines]
+                    imports []
+                    generates Effect with [after, map, always, forever]
+
+                putLine : Str -> Effect {}
+
+                getLines : Effect (List Str)
+
+                splitLines : Str -> List Str
+                "#
+            ),
+        )
+        .unwrap();
+
+        let glue_out = run_glue([
+            "glue".to_string(),
+            platform_module_path.to_str().unwrap().to_string(),
+            doubles_path.to_str().unwrap().to_string(),
+        ]);
+
+        assert!(glue_out.status.success(), "bad status {:?}", glue_out);
+
+        let doubles = fs::read_to_string(&doubles_path).unwrap();
+
+        assert!(doubles.contains("putLine : Str -> {}\n"), "{}", doubles);
+        assert!(doubles.contains("getLines : List Str\n"), "{}", doubles);
+        // `List` isn't an effect, so it has to stay
+        assert!(
+            doubles.contains("splitLines : Str -> List Str\n"),
+            "{}",
+            doubles
+        );
+    }
+
     fn generate_glue_for<'a, I: IntoIterator<Item = &'a str>>(
         platform_dir: &'a Path,
         args: I,
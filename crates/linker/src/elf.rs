@@ -1,10 +1,9 @@
 use iced_x86::{Decoder, DecoderOptions, Instruction, OpCodeOperandKind, OpKind};
-use memmap2::MmapMut;
 use object::{elf, endian};
 use object::{
     CompressedFileRange, CompressionFormat, LittleEndian as LE, NativeEndian, Object,
-    ObjectSection, ObjectSymbol, RelocationKind, RelocationTarget, Section, SectionIndex,
-    SectionKind, Symbol, SymbolIndex, SymbolSection,
+    ObjectSection, ObjectSymbol, Relocation, RelocationKind, RelocationTarget, Section,
+    SectionIndex, SectionKind, Symbol, SymbolIndex, SymbolSection,
 };
 use roc_collections::all::MutMap;
 use roc_error_macros::{internal_error, user_error};
@@ -13,13 +12,14 @@ use std::ffi::CStr;
 use std::mem;
 use std::os::raw::c_char;
 use std::path::Path;
-use std::time::{Duration, Instant};
+use std::time::Instant;
 
 use crate::metadata::{self, Metadata, VirtualOffset};
 
 use crate::{
     align_by_constraint, align_to_offset_by_constraint, load_struct_inplace,
     load_struct_inplace_mut, load_structs_inplace_mut, open_mmap, open_mmap_mut,
+    print_roc_definitions, LinkStats, OutputFile, ALWAYS_LINKED,
 };
 
 const MIN_SECTION_ALIGNMENT: usize = 0x40;
@@ -27,6 +27,10 @@ const MIN_SECTION_ALIGNMENT: usize = 0x40;
 // TODO: Analyze if this offset is always correct.
 const PLT_ADDRESS_OFFSET: u64 = 0x10;
 
+const GOT_SLOT_SIZE: usize = 8;
+const MOV_OPCODE: u8 = 0x8b;
+const LEA_OPCODE: u8 = 0x8d;
+
 struct ElfDynamicDeps {
     got_app_syms: Vec<(String, usize)>,
     got_sections: Vec<(usize, usize)>,
@@ -35,10 +39,6 @@ struct ElfDynamicDeps {
     shared_lib_index: usize,
 }
 
-fn report_timing(label: &str, duration: Duration) {
-    println!("\t{:9.3} ms   {}", duration.as_secs_f64() * 1000.0, label,);
-}
-
 fn is_roc_symbol(sym: &object::Symbol) -> bool {
     if let Ok(name) = sym.name() {
         name.trim_start_matches('_').starts_with("roc_")
@@ -55,6 +55,64 @@ fn is_roc_undefined(sym: &object::Symbol) -> bool {
     sym.is_undefined() && is_roc_symbol(sym)
 }
 
+/// The size in bits of a relocation. `object` only knows it for the kinds it has names for.
+fn relocation_size(rel: &Relocation) -> u8 {
+    match rel.kind() {
+        RelocationKind::Elf(
+            elf::R_X86_64_GOTPCRELX | elf::R_X86_64_REX_GOTPCRELX | elf::R_X86_64_SIZE32,
+        ) => 32,
+        RelocationKind::Elf(elf::R_X86_64_SIZE64) => 64,
+        _ => rel.size(),
+    }
+}
+
+fn is_got_relative(kind: RelocationKind) -> bool {
+    matches!(
+        kind,
+        RelocationKind::GotRelative
+            | RelocationKind::Elf(elf::R_X86_64_GOTPCRELX | elf::R_X86_64_REX_GOTPCRELX)
+    )
+}
+
+fn is_thread_local(kind: RelocationKind) -> bool {
+    matches!(
+        kind,
+        RelocationKind::Elf(
+            elf::R_X86_64_TPOFF32
+                | elf::R_X86_64_TPOFF64
+                | elf::R_X86_64_GOTTPOFF
+                | elf::R_X86_64_TLSGD
+                | elf::R_X86_64_TLSLD
+                | elf::R_X86_64_DTPOFF32
+                | elf::R_X86_64_DTPOFF64
+        )
+    )
+}
+
+/// Whether the GOT-relative relocation at `offset` in `code` is in a `mov foo@GOTPCREL(%rip), %reg`,
+/// which can load the address of `foo` with a `lea` instead, like other linkers relax it to.
+fn is_relaxable_got_load(code: &[u8], offset: u64) -> bool {
+    let offset = offset as usize;
+
+    // the ModRM byte must be RIP-relative addressing
+    offset >= 2 && code[offset - 2] == MOV_OPCODE && code[offset - 1] & 0xc7 == 0x05
+}
+
+/// Whether the app refers to this symbol weakly and the host doesn't define it, like
+/// `roc_parallel_for` for hosts without a thread pool. Such a symbol's address is null.
+fn is_missing_weak_symbol(md: &Metadata, app_obj: &object::File, index: SymbolIndex) -> bool {
+    match app_obj.symbol_by_index(index) {
+        Ok(sym) => {
+            sym.is_undefined()
+                && sym.is_weak()
+                && !md
+                    .roc_symbol_vaddresses
+                    .contains_key(sym.name().unwrap_or_default())
+        }
+        Err(_) => false,
+    }
+}
+
 fn collect_roc_definitions<'a>(object: &object::File<'a, &'a [u8]>) -> MutMap<String, u64> {
     let mut vaddresses = MutMap::default();
 
@@ -273,8 +331,7 @@ pub(crate) fn preprocess_elf(
     preprocessed_path: &Path,
     shared_lib: &Path,
     verbose: bool,
-    time: bool,
-) {
+) -> LinkStats {
     let total_start = Instant::now();
     let exec_parsing_start = total_start;
     let exec_data = &*open_mmap(host_exe_path);
@@ -291,29 +348,7 @@ pub(crate) fn preprocess_elf(
     };
 
     if verbose {
-        println!(
-            "Found {} roc symbol definitions:",
-            md.roc_symbol_vaddresses.len()
-        );
-
-        let (mut builtins, mut other): (Vec<_>, Vec<_>) = md
-            .roc_symbol_vaddresses
-            .iter()
-            .partition(|(n, _)| n.starts_with("roc_builtins"));
-
-        // sort by address
-        builtins.sort_by_key(|t| t.1);
-        other.sort_by_key(|t| t.1);
-
-        for (name, vaddr) in other.iter() {
-            println!("\t{:#08x}: {}", vaddr, name);
-        }
-
-        println!("Of which {} are builtins", builtins.len(),);
-
-        for (name, vaddr) in builtins.iter() {
-            println!("\t{:#08x}: {}", vaddr, name);
-        }
+        print_roc_definitions(&md.roc_symbol_vaddresses);
     }
 
     let exec_parsing_duration = exec_parsing_start.elapsed();
@@ -470,38 +505,32 @@ pub(crate) fn preprocess_elf(
     out_mmap
         .flush()
         .unwrap_or_else(|e| internal_error!("{}", e));
+    let bytes_written = out_mmap.len();
     // Also drop files to to ensure data is fully written here.
     drop(out_mmap);
     let flushing_data_duration = flushing_data_start.elapsed();
 
-    let total_duration = total_start.elapsed();
+    let mut stats = LinkStats {
+        phases: vec![
+            ("Executable Parsing", exec_parsing_duration),
+            (
+                "Symbol and PLT Processing",
+                symbol_and_plt_processing_duration,
+            ),
+            ("Text Disassembly", text_disassembly_duration),
+            ("Scanning Dynamic Deps", scanning_dynamic_deps_duration),
+            ("Generate Modified Platform", platform_gen_duration),
+            ("Saving Metadata", saving_metadata_duration),
+            ("Flushing Data to Disk", flushing_data_duration),
+        ],
+        symbols_patched: md.app_functions.len(),
+        surgeries_applied: md.surgeries.values().map(Vec::len).sum(),
+        bytes_written,
+        ..Default::default()
+    };
+    stats.finish(total_start.elapsed());
 
-    if verbose || time {
-        println!();
-        println!("Timings");
-        report_timing("Executable Parsing", exec_parsing_duration);
-        report_timing(
-            "Symbol and PLT Processing",
-            symbol_and_plt_processing_duration,
-        );
-        report_timing("Text Disassembly", text_disassembly_duration);
-        report_timing("Scanning Dynamic Deps", scanning_dynamic_deps_duration);
-        report_timing("Generate Modified Platform", platform_gen_duration);
-        report_timing("Saving Metadata", saving_metadata_duration);
-        report_timing("Flushing Data to Disk", flushing_data_duration);
-        report_timing(
-            "Other",
-            total_duration
-                - exec_parsing_duration
-                - symbol_and_plt_processing_duration
-                - text_disassembly_duration
-                - scanning_dynamic_deps_duration
-                - platform_gen_duration
-                - saving_metadata_duration
-                - flushing_data_duration,
-        );
-        report_timing("Total", total_duration);
-    }
+    stats
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -515,7 +544,7 @@ fn gen_elf_le(
     dynamic_lib_count: usize,
     shared_lib_index: usize,
     verbose: bool,
-) -> MmapMut {
+) -> OutputFile {
     let exec_header = load_struct_inplace::<elf::FileHeader64<LE>>(exec_data, 0);
     let ph_offset = exec_header.e_phoff.get(NativeEndian);
     let ph_ent_size = exec_header.e_phentsize.get(NativeEndian);
@@ -619,6 +648,8 @@ fn gen_elf_le(
 
     let mut rel_sections: Vec<(u64, u64)> = vec![];
     let mut rela_sections: Vec<(usize, u64, u64)> = vec![];
+    let mut dynsym_section: Option<(u64, u64)> = None;
+    let mut function_pointer_sections: Vec<(u64, u64)> = vec![];
     for (i, sh) in section_headers.iter_mut().enumerate() {
         let sh_offset = sh.sh_offset.get(NativeEndian);
         let sh_addr = sh.sh_addr.get(NativeEndian);
@@ -635,6 +666,13 @@ fn gen_elf_le(
             rel_sections.push((sh_offset, sh.sh_size.get(NativeEndian)));
         } else if sh_type == elf::SHT_RELA {
             rela_sections.push((i, sh_offset, sh.sh_size.get(NativeEndian)));
+        } else if sh_type == elf::SHT_DYNSYM {
+            dynsym_section = Some((sh_offset, sh.sh_size.get(NativeEndian)));
+        } else if matches!(
+            sh_type,
+            elf::SHT_INIT_ARRAY | elf::SHT_FINI_ARRAY | elf::SHT_PREINIT_ARRAY
+        ) {
+            function_pointer_sections.push((sh_offset, sh.sh_size.get(NativeEndian)));
         }
     }
 
@@ -676,10 +714,15 @@ fn gen_elf_le(
             let r_offset = rel.r_offset.get(NativeEndian);
             if virtual_shift_start <= r_offset {
                 rel.r_offset = endian::U64::new(LE, r_offset + md.added_byte_count);
-                // Deal with potential adjusts to absolute jumps.
-                // TODO: Verify other relocation types.
-                if rel.r_type(LE, false) == elf::R_X86_64_RELATIVE {
-                    let r_addend = rel.r_addend.get(LE);
+            }
+            // Deal with potential adjusts to absolute jumps.
+            // The addend of an IRELATIVE relocation is the address of the IFUNC resolver which
+            // picks what it points to, like the ones glibc has for memcpy in static executables.
+            // TODO: Verify other relocation types.
+            let r_type = rel.r_type(LE, false);
+            if r_type == elf::R_X86_64_RELATIVE || r_type == elf::R_X86_64_IRELATIVE {
+                let r_addend = rel.r_addend.get(LE);
+                if virtual_shift_start <= r_addend as u64 {
                     rel.r_addend.set(LE, r_addend + md.added_byte_count as i64);
                 }
             }
@@ -711,6 +754,8 @@ fn gen_elf_le(
         //     2. move them all to the end of the relocation sections.
         //     3. shrink the relocation section to ignore them.
         //     4. update the dynamic section to reflect the shrink as well.
+        // The section stays where it is, so nothing after it has to move, and the relocations left
+        // past its new end are R_X86_64_NONE in case anything still reads them.
         let mut to_remove = relocations
             .iter()
             .enumerate()
@@ -854,6 +899,23 @@ fn gen_elf_le(
         }
     }
 
+    // The dynamic linker calls the resolvers of IFUNC symbols the host exports at the addresses the
+    // dynamic symbol table has for them.
+    if let Some((dynsym_offset, dynsym_size)) = dynsym_section {
+        let dynamic_symbols = load_structs_inplace_mut::<elf::Sym64<LE>>(
+            &mut out_mmap,
+            dynsym_offset as usize + md.added_byte_count as usize,
+            dynsym_size as usize / mem::size_of::<elf::Sym64<LE>>(),
+        );
+
+        for sym in dynamic_symbols {
+            let addr = sym.st_value.get(NativeEndian);
+            if sym.st_type() == elf::STT_GNU_IFUNC && virtual_shift_start <= addr {
+                sym.st_value = endian::U64::new(LE, addr + md.added_byte_count);
+            }
+        }
+    }
+
     // Update all data in the global offset table.
     for (offset, size) in got_sections {
         let global_offsets = load_structs_inplace_mut::<endian::U64<LE>>(
@@ -869,6 +931,22 @@ fn gen_elf_le(
         }
     }
 
+    // Update the constructors and destructors the dynamic linker and libc call, like C++ static
+    // constructors. The dynamic table only points at these arrays, so they are shifted above.
+    for (offset, size) in function_pointer_sections {
+        let function_pointers = load_structs_inplace_mut::<endian::U64<LE>>(
+            &mut out_mmap,
+            offset as usize + md.added_byte_count as usize,
+            size as usize / mem::size_of::<endian::U64<LE>>(),
+        );
+        for fp in function_pointers.iter_mut() {
+            let fp_addr = fp.get(NativeEndian);
+            if virtual_shift_start <= fp_addr {
+                fp.set(LE, fp_addr + md.added_byte_count);
+            }
+        }
+    }
+
     // TODO: look into shifting all of the debug info and eh_frames.
 
     // Delete shared library from the dynamic table.
@@ -1088,8 +1166,7 @@ pub(crate) fn surgery_elf(
     metadata_path: &Path,
     executable_path: &Path,
     verbose: bool,
-    time: bool,
-) {
+) -> LinkStats {
     let app_obj = match object::File::parse(roc_app_bytes) {
         Ok(obj) => obj,
         Err(err) => {
@@ -1111,6 +1188,18 @@ pub(crate) fn surgery_elf(
         std::process::exit(1);
     }
 
+    if app_obj
+        .sections()
+        .flat_map(|sec| sec.relocations())
+        .any(|(_, reloc)| is_thread_local(reloc.kind()))
+    {
+        eprintln!(
+            "Your app uses thread-local storage, which the surgical linker doesn't support yet."
+        );
+        eprintln!("Please use `--linker=legacy` for now.");
+        std::process::exit(1);
+    }
+
     let total_start = Instant::now();
 
     let loading_metadata_start = total_start;
@@ -1152,29 +1241,31 @@ pub(crate) fn surgery_elf(
         fs::set_permissions(executable_path, perms).unwrap_or_else(|e| internal_error!("{}", e));
     }
 
-    let total_duration = total_start.elapsed();
-
-    if verbose || time {
-        println!("\nTimings");
-        report_timing("Loading Metadata", loading_metadata_duration);
-        report_timing("Loading and mmap-ing", load_and_mmap_duration);
-        report_timing("Output Generation", out_gen_duration);
-        report_timing("Flushing Data to Disk", flushing_data_duration);
-
-        let sum = loading_metadata_duration
-            + load_and_mmap_duration
-            + out_gen_duration
-            + flushing_data_duration;
+    let mut stats = LinkStats {
+        phases: vec![
+            ("Loading Metadata", loading_metadata_duration),
+            ("Loading and mmap-ing", load_and_mmap_duration),
+            ("Output Generation", out_gen_duration),
+            ("Flushing Data to Disk", flushing_data_duration),
+        ],
+        symbols_patched: md.app_functions.len(),
+        surgeries_applied: md
+            .app_functions
+            .iter()
+            .map(|name| md.surgeries.get(name).map_or(0, Vec::len))
+            .sum(),
+        bytes_written: offset,
+        ..Default::default()
+    };
+    stats.finish(total_start.elapsed());
 
-        report_timing("Other", total_duration.saturating_sub(sum));
-        report_timing("Total", total_duration);
-    }
+    stats
 }
 
 fn surgery_elf_help(
     verbose: bool,
     md: &metadata::Metadata,
-    exec_mmap: &mut MmapMut,
+    exec_mmap: &mut OutputFile,
     offset_ref: &mut usize, // TODO return this instead of taking a mutable reference to it
     app_obj: object::File,
 ) {
@@ -1191,6 +1282,7 @@ fn surgery_elf_help(
     let sh_offset = exec_header.e_shoff.get(NativeEndian);
     let sh_ent_size = exec_header.e_shentsize.get(NativeEndian);
     let sh_num = exec_header.e_shnum.get(NativeEndian);
+    let is_position_independent = exec_header.e_type.get(NativeEndian) == elf::ET_DYN;
 
     if verbose {
         println!();
@@ -1258,6 +1350,44 @@ fn surgery_elf_help(
     let mut app_func_vaddr_map: MutMap<String, usize> = MutMap::default();
     let mut app_func_size_map: MutMap<String, u64> = MutMap::default();
 
+    // The host's GOT has no entries for the app's symbols, so GOT-relative relocations that can't
+    // be relaxed into direct ones get slots at the start of the new rodata section instead.
+    let mut got_slots: Vec<SymbolIndex> = Vec::new();
+    for sec in rodata_sections.iter().chain(text_sections.iter()) {
+        let data = sec.data().unwrap_or_default();
+        for (rel_offset, rel) in sec.relocations() {
+            if let RelocationTarget::Symbol(index) = rel.target() {
+                // A `lea` can't load a null address, so missing weak symbols always get a slot
+                let is_relaxable = sec.kind() == SectionKind::Text
+                    && is_relaxable_got_load(data, rel_offset)
+                    && !is_missing_weak_symbol(md, &app_obj, index);
+                if is_got_relative(rel.kind()) && !is_relaxable && !got_slots.contains(&index) {
+                    got_slots.push(index);
+                }
+            }
+        }
+    }
+
+    if !got_slots.is_empty() && is_position_independent {
+        // The slots would need dynamic relocations to hold the right addresses, which we can't add.
+        eprintln!("Your app loads addresses from the GOT in a way the surgical linker can't link into a position-independent host yet.");
+        eprintln!("Please use `--linker=legacy` for now.");
+        std::process::exit(1);
+    }
+
+    let got_offset = offset;
+    let got_vaddr = virt_offset;
+    offset += got_slots.len() * GOT_SLOT_SIZE;
+    virt_offset += got_slots.len() * GOT_SLOT_SIZE;
+    if verbose && !got_slots.is_empty() {
+        println!(
+            "GOT slots for {} app symbols at: {:+x} (virt: {:+x})",
+            got_slots.len(),
+            got_offset,
+            got_vaddr
+        );
+    }
+
     // Calculate addresses and load symbols.
     // Note, it is important the bss sections come after the rodata sections.
     for sec in rodata_sections
@@ -1316,7 +1446,37 @@ fn surgery_elf_help(
     let (new_text_section_offset, new_text_section_vaddr) =
         (*new_text_section_offset, *new_text_section_vaddr);
 
+    // Fill in the GOT slots, now that we know where their symbols are.
+    for (slot, index) in got_slots.iter().enumerate() {
+        let vaddr = match symbol_vaddr_map.get(index) {
+            Some(vaddr) => *vaddr as u64,
+            None => app_obj
+                .symbol_by_index(*index)
+                .and_then(|sym| sym.name())
+                .ok()
+                .and_then(|name| md.roc_symbol_vaddresses.get(name))
+                .map(|address| *address + md.added_byte_count)
+                .unwrap_or_else(|| {
+                    if is_missing_weak_symbol(md, &app_obj, *index) {
+                        return 0;
+                    }
+
+                    internal_error!(
+                        "Undefined Symbol in GOT slot: {:+x?}",
+                        app_obj.symbol_by_index(*index)
+                    )
+                }),
+        };
+        exec_mmap[got_offset + slot * GOT_SLOT_SIZE..][..GOT_SLOT_SIZE]
+            .copy_from_slice(&vaddr.to_le_bytes());
+    }
+
     // Move data and deal with relocations.
+    // Every section's relocations only change that section, so they get dealt with in parallel,
+    // each on its own range of the executable.
+    let mut placed_sections = Vec::new();
+    let mut rest = &mut exec_mmap[..];
+    let mut rest_offset = 0;
     for sec in rodata_sections
         .iter()
         .chain(bss_sections.iter())
@@ -1331,102 +1491,59 @@ fn surgery_elf_help(
         let (section_offset, section_virtual_offset) =
             section_offset_map.get(&sec.index()).unwrap();
         let (section_offset, section_virtual_offset) = (*section_offset, *section_virtual_offset);
-        exec_mmap[section_offset..][..data.len()].copy_from_slice(data);
-        // Deal with definitions and relocations for this section.
-        if verbose {
-            println!();
-            println!(
-                "Processing Relocations for Section: 0x{:+x?} @ {:+x} (virt: {:+x})",
-                sec, section_offset, section_virtual_offset
-            );
-        }
-        for rel in sec.relocations() {
-            if verbose {
-                println!("\tFound Relocation: {:+x?}", rel);
-            }
-            match rel.1.target() {
-                RelocationTarget::Symbol(index) => {
-                    let target_offset = if let Some(target_offset) = symbol_vaddr_map.get(&index) {
-                        if verbose {
-                            println!(
-                                "\t\tRelocation targets symbol in app at: {:+x}",
-                                target_offset
-                            );
-                        }
-                        Some(*target_offset as i64)
-                    } else {
-                        app_obj
-                            .symbol_by_index(index)
-                            .and_then(|sym| sym.name())
-                            .ok()
-                            .and_then(|name| {
-                                md.roc_symbol_vaddresses.get(name).map(|address| {
-                                    let vaddr = (*address + md.added_byte_count) as i64;
-                                    if verbose {
-                                        println!(
-                                            "\t\tRelocation targets symbol in host: {} @ {:+x}",
-                                            name, vaddr
-                                        );
-                                    }
-                                    vaddr
-                                })
-                            })
-                    };
 
-                    if let Some(target_offset) = target_offset {
-                        let virt_base = section_virtual_offset as usize + rel.0 as usize;
-                        let base = section_offset as usize + rel.0 as usize;
-                        let target: i64 = match rel.1.kind() {
-                            RelocationKind::Relative | RelocationKind::PltRelative => {
-                                target_offset - virt_base as i64 + rel.1.addend()
-                            }
-                            x => {
-                                internal_error!("Relocation Kind not yet support: {:?}", x);
-                            }
-                        };
-                        if verbose {
-                            println!(
-                                "\t\tRelocation base location: {base:+x} (virt: {virt_base:+x})",
-                            );
-                            println!("\t\tFinal relocation target offset: {target:+x}");
-                        }
-                        match rel.1.size() {
-                            32 => {
-                                let data = (target as i32).to_le_bytes();
-                                exec_mmap[base..][..4].copy_from_slice(&data);
-                            }
-                            64 => {
-                                let data = target.to_le_bytes();
-                                exec_mmap[base..][..8].copy_from_slice(&data);
-                            }
-                            other => {
-                                internal_error!("Relocation size not yet supported: {other}");
-                            }
-                        }
-                    } else {
-                        // Explicitly ignore some symbols that are currently always linked.
-                        const ALWAYS_LINKED: &[&str] = &["__divti3", "__udivti3"];
+        // The sections were laid out in this order, so each one comes after the last.
+        let (_, after) = mem::take(&mut rest).split_at_mut(section_offset - rest_offset);
+        let (output, after) = after.split_at_mut(data.len());
+        output.copy_from_slice(data);
+        rest = after;
+        rest_offset = section_offset + data.len();
+
+        placed_sections.push(PlacedSection {
+            section: sec,
+            data,
+            output,
+            offset: section_offset,
+            virtual_offset: section_virtual_offset,
+        });
+    }
 
-                        match app_obj.symbol_by_index(index) {
-                            Ok(sym) if ALWAYS_LINKED.contains(&sym.name().unwrap_or_default()) => {
-                                continue
-                            }
-                            _ => {
-                                internal_error!(
-                                    "Undefined Symbol in relocation, {:+x?}: {:+x?}",
-                                    rel,
-                                    app_obj.symbol_by_index(index)
-                                );
-                            }
-                        }
-                    }
-                }
+    let (app_obj, symbol_vaddr_map, got_slots) = (&app_obj, &symbol_vaddr_map, &got_slots);
 
-                _ => {
-                    internal_error!("Relocation target not yet support: {:+x?}", rel);
-                }
-            }
+    if verbose {
+        // Keep what gets printed about each section together.
+        for placed in placed_sections.iter_mut() {
+            relocate_section(
+                verbose,
+                md,
+                app_obj,
+                symbol_vaddr_map,
+                got_slots,
+                got_vaddr,
+                placed,
+            );
         }
+    } else {
+        let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+        let chunk_size = ((placed_sections.len() + threads - 1) / threads).max(1);
+
+        std::thread::scope(|scope| {
+            for chunk in placed_sections.chunks_mut(chunk_size) {
+                scope.spawn(move || {
+                    for placed in chunk.iter_mut() {
+                        relocate_section(
+                            verbose,
+                            md,
+                            app_obj,
+                            symbol_vaddr_map,
+                            got_slots,
+                            got_vaddr,
+                            placed,
+                        );
+                    }
+                });
+            }
+        });
     }
 
     offset = align_by_constraint(offset, MIN_SECTION_ALIGNMENT);
@@ -1633,6 +1750,158 @@ fn surgery_elf_help(
     *offset_ref = offset;
 }
 
+/// One of the app's sections, and the part of the executable it was copied to
+struct PlacedSection<'a, 'data, 'file> {
+    section: &'a Section<'data, 'file>,
+    data: &'data [u8],
+    output: &'a mut [u8],
+    offset: usize,
+    virtual_offset: usize,
+}
+
+/// Deals with the relocations of one of the app's sections, which only change that section
+fn relocate_section(
+    verbose: bool,
+    md: &metadata::Metadata,
+    app_obj: &object::File,
+    symbol_vaddr_map: &MutMap<SymbolIndex, usize>,
+    got_slots: &[SymbolIndex],
+    got_vaddr: usize,
+    placed: &mut PlacedSection,
+) {
+    let PlacedSection {
+        section: sec,
+        data,
+        output,
+        offset: section_offset,
+        virtual_offset: section_virtual_offset,
+    } = placed;
+    let (data, section_offset, section_virtual_offset) =
+        (*data, *section_offset, *section_virtual_offset);
+
+    if verbose {
+        println!();
+        println!(
+            "Processing Relocations for Section: 0x{:+x?} @ {:+x} (virt: {:+x})",
+            sec, section_offset, section_virtual_offset
+        );
+    }
+    for rel in sec.relocations() {
+        if verbose {
+            println!("\tFound Relocation: {:+x?}", rel);
+        }
+        match rel.1.target() {
+            RelocationTarget::Symbol(index) => {
+                let target_offset = if let Some(target_offset) = symbol_vaddr_map.get(&index) {
+                    if verbose {
+                        println!(
+                            "\t\tRelocation targets symbol in app at: {:+x}",
+                            target_offset
+                        );
+                    }
+                    Some(*target_offset as i64)
+                } else {
+                    app_obj
+                        .symbol_by_index(index)
+                        .and_then(|sym| sym.name())
+                        .ok()
+                        .and_then(|name| {
+                            md.roc_symbol_vaddresses.get(name).map(|address| {
+                                let vaddr = (*address + md.added_byte_count) as i64;
+                                if verbose {
+                                    println!(
+                                        "\t\tRelocation targets symbol in host: {} @ {:+x}",
+                                        name, vaddr
+                                    );
+                                }
+                                vaddr
+                            })
+                        })
+                        .or_else(|| is_missing_weak_symbol(md, app_obj, index).then_some(0))
+                };
+
+                if let Some(target_offset) = target_offset {
+                    let virt_base = section_virtual_offset + rel.0 as usize;
+                    let base = rel.0 as usize;
+                    let target: i64 = match rel.1.kind() {
+                        RelocationKind::Relative | RelocationKind::PltRelative => {
+                            target_offset - virt_base as i64 + rel.1.addend()
+                        }
+                        kind if is_got_relative(kind) => {
+                            if sec.kind() == SectionKind::Text
+                                && is_relaxable_got_load(data, rel.0)
+                                && !is_missing_weak_symbol(md, app_obj, index)
+                            {
+                                // `mov foo@GOTPCREL(%rip), %reg` becomes `lea foo(%rip), %reg`
+                                output[base - 2] = LEA_OPCODE;
+                                target_offset - virt_base as i64 + rel.1.addend()
+                            } else {
+                                let slot = got_slots.iter().position(|i| *i == index).unwrap();
+                                let slot_vaddr = got_vaddr + slot * GOT_SLOT_SIZE;
+                                slot_vaddr as i64 - virt_base as i64 + rel.1.addend()
+                            }
+                        }
+                        RelocationKind::Elf(elf::R_X86_64_SIZE32 | elf::R_X86_64_SIZE64) => {
+                            match app_obj.symbol_by_index(index) {
+                                Ok(sym) if sym.is_definition() => {
+                                    sym.size() as i64 + rel.1.addend()
+                                }
+                                _ => {
+                                    internal_error!(
+                                        "Size relocation for a symbol the app doesn't define: {:+x?}",
+                                        rel
+                                    );
+                                }
+                            }
+                        }
+                        x => {
+                            internal_error!("Relocation Kind not yet support: {:?}", x);
+                        }
+                    };
+                    if verbose {
+                        println!(
+                            "\t\tRelocation base location: {:+x} (virt: {virt_base:+x})",
+                            section_offset + base
+                        );
+                        println!("\t\tFinal relocation target offset: {target:+x}");
+                    }
+                    match relocation_size(&rel.1) {
+                        32 => {
+                            let data = (target as i32).to_le_bytes();
+                            output[base..][..4].copy_from_slice(&data);
+                        }
+                        64 => {
+                            let data = target.to_le_bytes();
+                            output[base..][..8].copy_from_slice(&data);
+                        }
+                        other => {
+                            internal_error!("Relocation size not yet supported: {other}");
+                        }
+                    }
+                } else {
+                    // Explicitly ignore some symbols that are currently always linked.
+                    match app_obj.symbol_by_index(index) {
+                        Ok(sym) if ALWAYS_LINKED.contains(&sym.name().unwrap_or_default()) => {
+                            continue
+                        }
+                        _ => {
+                            internal_error!(
+                                "Undefined Symbol in relocation, {:+x?}: {:+x?}",
+                                rel,
+                                app_obj.symbol_by_index(index)
+                            );
+                        }
+                    }
+                }
+            }
+
+            _ => {
+                internal_error!("Relocation target not yet support: {:+x?}", rel);
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1643,6 +1912,16 @@ mod tests {
 
     const ELF64_DYNHOST: &[u8] = include_bytes!("../dynhost_benchmarks_elf64") as &[_];
 
+    #[test]
+    fn relax_got_loads() {
+        // mov 0x0(%rip), %rax
+        assert!(is_relaxable_got_load(&[0x48, 0x8b, 0x05, 0, 0, 0, 0], 3));
+        // call *0x0(%rip)
+        assert!(!is_relaxable_got_load(&[0xff, 0x15, 0, 0, 0, 0], 2));
+        // mov 0x0(%rax), %rax
+        assert!(!is_relaxable_got_load(&[0x48, 0x8b, 0x80, 0, 0, 0, 0], 3));
+    }
+
     #[test]
     fn collect_definitions() {
         let object = object::File::parse(ELF64_DYNHOST).unwrap();
@@ -1696,49 +1975,88 @@ mod tests {
         )
     }
 
+    /// What builds a test host. Platforms' hosts come from all of these, with all sorts of flags,
+    /// and each one lays out and relocates executables a bit differently.
     #[allow(dead_code)]
-    fn zig_host_app_help(dir: &Path, target: &Triple) {
-        let host_zig = indoc!(
-            r#"
-            const std = @import("std");
+    #[derive(Debug, Clone, Copy)]
+    enum Toolchain {
+        Zig,
+        Gcc,
+        Clang,
+        Rustc,
+    }
 
-            extern fn roc_magic1(usize) callconv(.C) [*]const u8;
+    const HOST_ZIG: &str = indoc!(
+        r#"
+        const std = @import("std");
 
-            pub fn main() !void {
-                const stdout = std.io.getStdOut().writer();
-                try stdout.print("Hello {s}\n", .{roc_magic1(0)[0..3]});
-            }
-            "#
-        );
+        extern fn roc_magic1(usize) callconv(.C) [*]const u8;
 
-        let app_zig = indoc!(
-            r#"
-            const X = [_][]const u8 { "foo" };
+        pub fn main() !void {
+            const stdout = std.io.getStdOut().writer();
+            try stdout.print("Hello {s}\n", .{roc_magic1(0)[0..3]});
+        }
+        "#
+    );
 
-            export fn roc_magic1(index: usize) [*]const u8 {
-                return X[index].ptr;
-            }
-            "#
-        );
+    const APP_ZIG: &str = indoc!(
+        r#"
+        const X = [_][]const u8 { "foo" };
 
-        let zig = std::env::var("ROC_ZIG").unwrap_or_else(|_| "zig".into());
+        export fn roc_magic1(index: usize) [*]const u8 {
+            return X[index].ptr;
+        }
+        "#
+    );
 
-        std::fs::write(dir.join("host.zig"), host_zig.as_bytes()).unwrap();
-        std::fs::write(dir.join("app.zig"), app_zig.as_bytes()).unwrap();
+    const HOST_C: &str = indoc!(
+        r#"
+        #include <stddef.h>
+        #include <stdio.h>
 
-        // we need to compile the app first
-        let output = std::process::Command::new(&zig)
+        extern const char *roc_magic1(size_t index);
+
+        int main(void) {
+            printf("Hello %.3s\n", roc_magic1(0));
+            return 0;
+        }
+        "#
+    );
+
+    // An array of pointers would need absolute relocations, which surgery doesn't support yet.
+    const APP_C: &str = indoc!(
+        r#"
+        #include <stddef.h>
+
+        static const char X[][4] = { "foo" };
+
+        const char *roc_magic1(size_t index) {
+            return X[index];
+        }
+        "#
+    );
+
+    const HOST_RS: &str = indoc!(
+        r#"
+        #[link(name = "app")]
+        extern "C" {
+            fn roc_magic1(index: usize) -> *const u8;
+        }
+
+        fn main() {
+            let bytes = unsafe { std::slice::from_raw_parts(roc_magic1(0), 3) };
+            println!("Hello {}", std::str::from_utf8(bytes).unwrap());
+        }
+        "#
+    );
+
+    #[allow(dead_code)]
+    fn run_toolchain(dir: &Path, program: &str, args: &[&str]) {
+        let output = std::process::Command::new(program)
             .current_dir(dir)
-            .args([
-                "build-obj",
-                "app.zig",
-                "-fPIC",
-                "-target",
-                "x86_64-linux-gnu",
-                "-OReleaseFast",
-            ])
+            .args(args)
             .output()
-            .unwrap();
+            .unwrap_or_else(|err| panic!("could not run {}: {}", program, err));
 
         if !output.status.success() {
             use std::io::Write;
@@ -1746,7 +2064,47 @@ mod tests {
             std::io::stdout().write_all(&output.stdout).unwrap();
             std::io::stderr().write_all(&output.stderr).unwrap();
 
-            panic!("zig build-obj failed");
+            panic!("{} {} failed", program, args.join(" "));
+        }
+    }
+
+    /// Builds an app object and a host with `toolchain`, passing `host_flags` when building the
+    /// host, then preprocesses the host and links the app into it as `final`
+    #[allow(dead_code)]
+    fn host_app_help(dir: &Path, target: &Triple, toolchain: Toolchain, host_flags: &[&str]) {
+        let zig = std::env::var("ROC_ZIG").unwrap_or_else(|_| "zig".into());
+        let c_compiler = match toolchain {
+            Toolchain::Clang => "clang",
+            Toolchain::Zig | Toolchain::Gcc | Toolchain::Rustc => "gcc",
+        };
+
+        // we need to compile the app first
+        match toolchain {
+            Toolchain::Zig => {
+                std::fs::write(dir.join("app.zig"), APP_ZIG.as_bytes()).unwrap();
+
+                run_toolchain(
+                    dir,
+                    &zig,
+                    &[
+                        "build-obj",
+                        "app.zig",
+                        "-fPIC",
+                        "-target",
+                        "x86_64-linux-gnu",
+                        "-OReleaseFast",
+                    ],
+                );
+            }
+            Toolchain::Gcc | Toolchain::Clang | Toolchain::Rustc => {
+                std::fs::write(dir.join("app.c"), APP_C.as_bytes()).unwrap();
+
+                run_toolchain(
+                    dir,
+                    c_compiler,
+                    &["-c", "app.c", "-o", "app.o", "-fPIC", "-O2"],
+                );
+            }
         }
 
         // open our app object; we'll copy sections from it later
@@ -1767,29 +2125,40 @@ mod tests {
         std::fs::write(dir.join("libapp.so"), dylib_bytes).unwrap();
 
         // now we can compile the host (it uses libapp.so, hence the order here)
-        let output = std::process::Command::new(&zig)
-            .current_dir(dir)
-            .args([
-                "build-exe",
-                "libapp.so",
-                "host.zig",
-                "-fPIE",
-                "-lc",
-                "-target",
-                "x86_64-linux-gnu",
-                "-OReleaseFast",
-            ])
-            .output()
-            .unwrap();
+        let (program, mut args) = match toolchain {
+            Toolchain::Zig => {
+                std::fs::write(dir.join("host.zig"), HOST_ZIG.as_bytes()).unwrap();
+
+                (
+                    zig.as_str(),
+                    vec![
+                        "build-exe",
+                        "libapp.so",
+                        "host.zig",
+                        "-lc",
+                        "-target",
+                        "x86_64-linux-gnu",
+                        "-OReleaseFast",
+                    ],
+                )
+            }
+            Toolchain::Gcc | Toolchain::Clang => {
+                std::fs::write(dir.join("host.c"), HOST_C.as_bytes()).unwrap();
 
-        if !output.status.success() {
-            use std::io::Write;
+                (c_compiler, vec!["host.c", "libapp.so", "-o", "host", "-O2"])
+            }
+            Toolchain::Rustc => {
+                std::fs::write(dir.join("host.rs"), HOST_RS.as_bytes()).unwrap();
 
-            std::io::stdout().write_all(&output.stdout).unwrap();
-            std::io::stderr().write_all(&output.stderr).unwrap();
+                (
+                    "rustc",
+                    vec!["host.rs", "-o", "host", "-L", ".", "-C", "opt-level=3"],
+                )
+            }
+        };
+        args.extend_from_slice(host_flags);
 
-            panic!("zig build-exe failed");
-        }
+        run_toolchain(dir, program, &args);
 
         let preprocessed_host_filename = dir.join(preprocessed_host_filename(target).unwrap());
 
@@ -1800,30 +2169,20 @@ mod tests {
             &preprocessed_host_filename,
             &dir.join("libapp.so"),
             false,
-            false,
         );
 
         std::fs::copy(&preprocessed_host_filename, &dir.join("final")).unwrap();
 
-        surgery_elf(
-            &roc_app,
-            &dir.join("metadata"),
-            &dir.join("final"),
-            false,
-            false,
-        );
+        surgery_elf(&roc_app, &dir.join("metadata"), &dir.join("final"), false);
     }
 
-    #[cfg(target_os = "linux")]
-    #[test]
-    fn zig_host_app() {
-        use std::str::FromStr;
-
-        let dir = tempfile::tempdir().unwrap();
-        let dir = dir.path();
-
-        zig_host_app_help(dir, &Triple::from_str("x86_64-unknown-linux-musl").unwrap());
+    #[allow(dead_code)]
+    fn zig_host_app_help(dir: &Path, target: &Triple) {
+        host_app_help(dir, target, Toolchain::Zig, &["-fPIE"]);
+    }
 
+    #[allow(dead_code)]
+    fn assert_final_says_hello(dir: &Path) {
         let output = std::process::Command::new(&dir.join("final"))
             .current_dir(dir)
             .output()
@@ -1842,4 +2201,126 @@ mod tests {
 
         assert_eq!("Hello foo\n", output);
     }
+
+    #[allow(dead_code)]
+    fn host_app(toolchain: Toolchain, host_flags: &[&str]) {
+        use std::str::FromStr;
+
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+
+        let target = Triple::from_str("x86_64-unknown-linux-gnu").unwrap();
+        host_app_help(dir, &target, toolchain, host_flags);
+
+        assert_final_says_hello(dir);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn zig_host_app() {
+        use std::str::FromStr;
+
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+
+        zig_host_app_help(dir, &Triple::from_str("x86_64-unknown-linux-musl").unwrap());
+
+        assert_final_says_hello(dir);
+    }
+
+    // A static-pie host can't link against libapp.so, so surgery has nothing to replace in one.
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn zig_no_pie_host_app() {
+        host_app(Toolchain::Zig, &["-fno-PIE"]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn zig_lto_host_app() {
+        host_app(Toolchain::Zig, &["-fPIE", "-flto"]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn gcc_pie_host_app() {
+        host_app(Toolchain::Gcc, &["-fPIE", "-pie"]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn gcc_no_pie_host_app() {
+        host_app(Toolchain::Gcc, &["-fno-pie", "-no-pie"]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn gcc_no_plt_host_app() {
+        host_app(Toolchain::Gcc, &["-fPIE", "-pie", "-fno-plt"]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn gcc_lto_host_app() {
+        host_app(Toolchain::Gcc, &["-fPIE", "-pie", "-flto"]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn gcc_compressed_sections_host_app() {
+        host_app(Toolchain::Gcc, &["-fPIE", "-pie", "-g", "-gz=zlib"]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn clang_pie_host_app() {
+        host_app(Toolchain::Clang, &["-fPIE", "-pie"]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn clang_no_pie_host_app() {
+        host_app(Toolchain::Clang, &["-fno-pie", "-no-pie"]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn clang_no_plt_host_app() {
+        host_app(Toolchain::Clang, &["-fPIE", "-pie", "-fno-plt"]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn clang_lto_host_app() {
+        // the system linker may not have the LLVM plugin LTO needs, but lld always does
+        host_app(
+            Toolchain::Clang,
+            &["-fPIE", "-pie", "-flto", "-fuse-ld=lld"],
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn clang_compressed_sections_host_app() {
+        host_app(Toolchain::Clang, &["-fPIE", "-pie", "-g", "-gz=zlib"]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn rustc_host_app() {
+        host_app(Toolchain::Rustc, &[]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn rustc_no_pie_host_app() {
+        host_app(Toolchain::Rustc, &["-C", "relocation-model=static"]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn rustc_lto_host_app() {
+        host_app(Toolchain::Rustc, &["-C", "lto=fat"]);
+    }
 }
@@ -4,25 +4,60 @@
 //! system and final linking of the executable, it is significantly less
 //! practical to use a regular linker.
 use memmap2::{Mmap, MmapMut};
-use object::Object;
-use roc_build::link::{get_target_triple_str, rebuild_host, LinkType};
+use object::{Object, ObjectSection, ObjectSymbol, RelocationTarget, SectionKind};
+use roc_build::link::{build_host, get_target_triple_str, LinkType, LinkingStrategy};
+use roc_collections::all::MutMap;
 use roc_error_macros::internal_error;
 use roc_load::{EntryPoint, ExecutionMode, LoadConfig, Threading};
+use roc_module::mangle::{self, demangle, HostClosureFn};
+use roc_mono::effect_batch;
 use roc_mono::ir::OptLevel;
 use roc_packaging::cache::RocCacheDir;
+use roc_parse::header::{AllocatorStrategy, PlatformAllocator, PlatformEffects};
 use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
+use std::cell::Cell;
 use std::cmp::Ordering;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
 use std::mem;
+use std::ops::{Deref, DerefMut};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use target_lexicon::Triple;
 
 mod elf;
 mod macho;
 mod pe;
+mod wasm;
 
 mod generate_dylib;
 mod metadata;
 
+/// How long each phase of preprocessing a host, or of linking an app into it, took, and how much
+/// it did, so `roc` can report it along with the rest of the build
+#[derive(Debug, Clone, Default)]
+pub struct LinkStats {
+    /// Each phase in the order it ran, and how long it took
+    pub phases: Vec<(&'static str, Duration)>,
+    pub total: Duration,
+    /// The app's functions the host calls
+    pub symbols_patched: usize,
+    /// The calls and pointers to those functions in the host, which surgery rewrites
+    pub surgeries_applied: usize,
+    /// The size of the preprocessed host, or of the linked executable
+    pub bytes_written: usize,
+}
+
+impl LinkStats {
+    /// Records whatever of `total` the phases don't account for as one more phase
+    pub(crate) fn finish(&mut self, total: Duration) {
+        let accounted = self.phases.iter().map(|(_, duration)| *duration).sum();
+
+        self.phases.push(("Other", total.saturating_sub(accounted)));
+        self.total = total;
+    }
+}
+
 pub fn supported(link_type: LinkType, target: &Triple) -> bool {
     if let LinkType::Executable = link_type {
         match target {
@@ -47,6 +82,11 @@ pub fn supported(link_type: LinkType, target: &Triple) -> bool {
                 ..
             } => true,
 
+            Triple {
+                architecture: target_lexicon::Architecture::Wasm32,
+                ..
+            } => true,
+
             _ => false,
         }
     } else {
@@ -55,13 +95,28 @@ pub fn supported(link_type: LinkType, target: &Triple) -> bool {
 }
 
 pub fn build_and_preprocess_host(
+    build_command: Option<&str>,
     opt_level: OptLevel,
     target: &Triple,
     platform_main_roc: &Path,
     preprocessed_host_path: &Path,
     exposed_to_host: Vec<String>,
     exported_closure_types: Vec<String>,
-) {
+) -> LinkStats {
+    if let target_lexicon::Architecture::Wasm32 = target.architecture {
+        // Wasm hosts import the app's functions, so they don't need a stub library
+        let host = build_host(
+            build_command,
+            opt_level,
+            LinkingStrategy::Surgical,
+            target,
+            platform_main_roc,
+            None,
+        );
+
+        return crate::wasm::preprocess_wasm(&host, preprocessed_host_path);
+    }
+
     let stub_lib = if let target_lexicon::OperatingSystem::Windows = target.operating_system {
         platform_main_roc.with_file_name("libapp.dll")
     } else {
@@ -76,7 +131,14 @@ pub fn build_and_preprocess_host(
 
     let stub_dll_symbols = make_stub_dll_symbols(exposed_to_host, exported_closure_types);
     generate_dynamic_lib(target, &stub_dll_symbols, &stub_lib);
-    rebuild_host(opt_level, target, platform_main_roc, Some(&stub_lib));
+    build_host(
+        build_command,
+        opt_level,
+        LinkingStrategy::Surgical,
+        target,
+        platform_main_roc,
+        Some(&stub_lib),
+    );
 
     let metadata = platform_main_roc.with_file_name(metadata_file_name(target));
     // let prehost = host_input_path.with_file_name(preprocessed_host_filename(target).unwrap());
@@ -89,11 +151,11 @@ pub fn build_and_preprocess_host(
         &stub_lib,
         &stub_dll_symbols,
         false,
-        false,
     )
 }
 
-fn metadata_file_name(target: &Triple) -> String {
+/// The name of the file the surgical linker keeps its metadata about a preprocessed host in
+pub fn metadata_file_name(target: &Triple) -> String {
     let target_triple_str = get_target_triple_str(target);
 
     format!("metadata_{}.rm2", target_triple_str.unwrap_or("unknown"))
@@ -104,9 +166,296 @@ pub fn link_preprocessed_host(
     platform_path: &Path,
     roc_app_bytes: &[u8],
     binary_path: &Path,
-) {
+) -> Result<LinkStats, String> {
     let metadata = platform_path.with_file_name(metadata_file_name(target));
-    surgery(roc_app_bytes, &metadata, binary_path, false, false, target)
+    surgery(roc_app_bytes, &metadata, binary_path, false, target)
+}
+
+/// Check that the preprocessed host defines exactly the allocator functions
+/// its platform header says it does.
+pub fn check_platform_allocator(
+    target: &Triple,
+    platform_path: &Path,
+    allocator: PlatformAllocator,
+) -> Result<(), String> {
+    if allocator.strategy == AllocatorStrategy::Mimalloc {
+        // We can only resolve symbols the host defines, so we can't add libmimalloc here.
+        return Err(
+            "The mimalloc allocator needs libmimalloc at link time, which the surgical linker can't add. Try again with --linker=legacy.".to_string(),
+        );
+    }
+
+    let metadata_path = platform_path.with_file_name(metadata_file_name(target));
+    let ptr_bytes = match target.pointer_width() {
+        Ok(width) => width.bytes() as u32,
+        Err(_) => 8,
+    };
+
+    match target.binary_format {
+        target_lexicon::BinaryFormat::Elf | target_lexicon::BinaryFormat::Macho => {
+            let md = metadata::Metadata::read_from_file(&metadata_path);
+            validate_platform_allocator(allocator, ptr_bytes, |name| {
+                md.roc_symbol_vaddresses.contains_key(name)
+            })
+        }
+        target_lexicon::BinaryFormat::Coff => {
+            let exports = pe::read_host_exports(&metadata_path);
+            validate_platform_allocator(allocator, ptr_bytes, |name| exports.contains_key(name))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn validate_platform_allocator(
+    allocator: PlatformAllocator,
+    ptr_bytes: u32,
+    host_defines: impl Fn(&str) -> bool,
+) -> Result<(), String> {
+    let strategy = allocator.strategy.as_str();
+
+    let missing: Vec<&str> = allocator
+        .host_fns()
+        .iter()
+        .copied()
+        .filter(|name| !host_defines(name))
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!(
+            "The platform's host does not define {}, but its header has `allocator {{ strategy: {} }}`, which says the host provides it.",
+            missing.join(", "),
+            strategy
+        ));
+    }
+
+    let duplicated: Vec<&str> = allocator
+        .roc_fns()
+        .iter()
+        .copied()
+        .filter(|name| host_defines(name))
+        .collect();
+    if !duplicated.is_empty() {
+        return Err(format!(
+            "The platform's host defines {}, but its header has `allocator {{ strategy: {} }}`, which says Roc provides it.",
+            duplicated.join(", "),
+            strategy
+        ));
+    }
+
+    if let Some(alignment) = allocator.alignment {
+        if alignment < ptr_bytes {
+            return Err(format!(
+                "The platform's header promises an allocator alignment of {alignment}, but Roc needs allocations to be aligned to at least {ptr_bytes} bytes on this target."
+            ));
+        }
+
+        // malloc only guarantees alignment for the largest fundamental type
+        let malloc_alignment = 2 * ptr_bytes;
+        if allocator.strategy == AllocatorStrategy::Roc && alignment > malloc_alignment {
+            return Err(format!(
+                "The platform's header promises an allocator alignment of {alignment}, but Roc's default allocator only guarantees {malloc_alignment} bytes on this target."
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Roc calls this when `roc_alloc` or `roc_realloc` return null. Hosts which don't define it get
+/// a `roc_panic` instead.
+pub const OOM_HANDLER_FN: &str = "roc_oom";
+
+/// A warning if the preprocessed host doesn't define `roc_oom`, which Roc calls when it runs out
+/// of memory. It's optional, but otherwise running out of memory looks like any other panic.
+pub fn check_platform_oom_handler(target: &Triple, platform_path: &Path) -> Option<String> {
+    let metadata_path = platform_path.with_file_name(metadata_file_name(target));
+
+    match target.binary_format {
+        target_lexicon::BinaryFormat::Elf | target_lexicon::BinaryFormat::Macho => {
+            let md = metadata::Metadata::read_from_file(&metadata_path);
+            oom_handler_warning(|name| md.roc_symbol_vaddresses.contains_key(name))
+        }
+        target_lexicon::BinaryFormat::Coff => {
+            let exports = pe::read_host_exports(&metadata_path);
+            oom_handler_warning(|name| exports.contains_key(name))
+        }
+        _ => None,
+    }
+}
+
+fn oom_handler_warning(host_defines: impl Fn(&str) -> bool) -> Option<String> {
+    if host_defines(OOM_HANDLER_FN) {
+        None
+    } else {
+        Some(format!(
+            "Warning: the platform's host does not define {OOM_HANDLER_FN}, so when an allocation fails, Roc will call roc_panic instead. To handle it separately, define this; it gets the size and alignment that couldn't be allocated, and must not return:\n\n    void {OOM_HANDLER_FN}(size_t size, uint32_t alignment);"
+        ))
+    }
+}
+
+/// Check that the preprocessed host defines `roc_fx_batch` if its platform header says it runs
+/// effects in batches.
+pub fn check_platform_effects(
+    target: &Triple,
+    platform_path: &Path,
+    effects: PlatformEffects,
+) -> Result<(), String> {
+    if !effects.is_batched() {
+        return Ok(());
+    }
+
+    let metadata_path = platform_path.with_file_name(metadata_file_name(target));
+
+    match target.binary_format {
+        target_lexicon::BinaryFormat::Elf | target_lexicon::BinaryFormat::Macho => {
+            let md = metadata::Metadata::read_from_file(&metadata_path);
+            validate_effect_batch_fn(|name| md.roc_symbol_vaddresses.contains_key(name))
+        }
+        target_lexicon::BinaryFormat::Coff => {
+            let exports = pe::read_host_exports(&metadata_path);
+            validate_effect_batch_fn(|name| exports.contains_key(name))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn validate_effect_batch_fn(host_defines: impl Fn(&str) -> bool) -> Result<(), String> {
+    let batch_fn = effect_batch::BATCH_FN;
+
+    if host_defines(batch_fn) {
+        Ok(())
+    } else {
+        Err(format!(
+            "The platform's host does not define {batch_fn}, but its header has `effects {{ submit: batch }}`, which says the host runs effects in batches:\n\n    void {batch_fn}(struct RocEffect* effects, size_t count);"
+        ))
+    }
+}
+
+/// Symbols the app may refer to without the host defining them, because they're always linked in
+pub(crate) const ALWAYS_LINKED: [&str; 4] = ["__divti3", "__udivti3", "___divti3", "___udivti3"];
+
+/// Check that the preprocessed host defines every symbol the app refers to but doesn't define,
+/// and that the app defines every function the host calls, before surgery starts on them.
+/// Otherwise surgery stops at the first relocation or call it can't resolve, naming only that one.
+pub fn check_app_symbols(
+    target: &Triple,
+    platform_path: &Path,
+    roc_app_bytes: &[u8],
+) -> Result<(), String> {
+    match target.binary_format {
+        target_lexicon::BinaryFormat::Elf | target_lexicon::BinaryFormat::Macho => {
+            let md = metadata::Metadata::read_from_file(
+                &platform_path.with_file_name(metadata_file_name(target)),
+            );
+            let app_obj = object::File::parse(roc_app_bytes)
+                .unwrap_or_else(|err| internal_error!("Failed to parse application file: {err}"));
+
+            // The builtins refer to some symbols weakly, and manage without them. Only surgery on
+            // ELF knows to leave those null.
+            let may_be_missing = |sym: &object::Symbol| {
+                sym.is_weak() && target.binary_format == target_lexicon::BinaryFormat::Elf
+            };
+
+            let mut needed_from_host = Vec::new();
+
+            for section in app_obj
+                .sections()
+                .filter(|section| is_surgery_section(target, section))
+            {
+                for (_, relocation) in section.relocations() {
+                    if let RelocationTarget::Symbol(index) = relocation.target() {
+                        match app_obj.symbol_by_index(index) {
+                            Ok(sym) if sym.is_undefined() && !may_be_missing(&sym) => {
+                                needed_from_host.push(sym.name().unwrap_or_default())
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+
+            let app_defines = |name: &str| {
+                app_obj
+                    .symbols()
+                    .any(|sym| sym.is_definition() && sym.name().ok() == Some(name))
+            };
+
+            validate_app_symbols(
+                needed_from_host,
+                |name| md.roc_symbol_vaddresses.contains_key(name),
+                md.app_functions.iter().map(String::as_str),
+                app_defines,
+            )
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Whether surgery copies `section` from the app into the host
+fn is_surgery_section(target: &Triple, section: &object::Section) -> bool {
+    match target.binary_format {
+        target_lexicon::BinaryFormat::Elf => {
+            let name = section.name().unwrap_or_default();
+
+            name.starts_with(".rodata") || name.starts_with(".bss") || name.starts_with(".text")
+        }
+        _ => matches!(
+            section.kind(),
+            SectionKind::ReadOnlyData | SectionKind::UninitializedData | SectionKind::Text
+        ),
+    }
+}
+
+fn validate_app_symbols<'a>(
+    needed_from_host: impl IntoIterator<Item = &'a str>,
+    host_defines: impl Fn(&str) -> bool,
+    called_by_host: impl IntoIterator<Item = &'a str>,
+    app_defines: impl Fn(&str) -> bool,
+) -> Result<(), String> {
+    let mut missing_from_host: Vec<&str> = needed_from_host
+        .into_iter()
+        .filter(|name| !host_defines(name) && !ALWAYS_LINKED.contains(name))
+        .collect();
+    missing_from_host.sort_unstable();
+    missing_from_host.dedup();
+
+    let mut missing_from_app: Vec<&str> = called_by_host
+        .into_iter()
+        .filter(|name| !app_defines(name))
+        .collect();
+    missing_from_app.sort_unstable();
+    missing_from_app.dedup();
+
+    if missing_from_host.is_empty() && missing_from_app.is_empty() {
+        return Ok(());
+    }
+
+    let list = |names: &[&str]| {
+        names
+            .iter()
+            .map(|name| match demangle(name) {
+                Some(demangled) => format!("    {name} ({demangled})"),
+                None => format!("    {name}"),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let mut message =
+        String::from("The app and the platform's host don't match, so I can't link them.");
+
+    if !missing_from_host.is_empty() {
+        message.push_str("\n\nThe app uses these, which the host does not define:\n\n");
+        message.push_str(&list(&missing_from_host));
+    }
+
+    if !missing_from_app.is_empty() {
+        message.push_str("\n\nThe host calls these, which the app does not define:\n\n");
+        message.push_str(&list(&missing_from_app));
+    }
+
+    message.push_str("\n\nIf the host was built for a different version of the platform, rebuilding it with --prebuilt-platform=false may fix this.");
+
+    Err(message)
 }
 
 // Exposed function to load a platform file and generate a stub lib for it.
@@ -131,6 +480,7 @@ pub fn generate_stub_lib(
             palette: DEFAULT_PALETTE,
             threading: Threading::AllAvailable,
             exec_mode: ExecutionMode::Executable,
+            ..Default::default()
         },
     )
     .unwrap_or_else(|problem| todo!("{:?}", problem));
@@ -147,10 +497,9 @@ pub fn generate_stub_lib(
         .closure_types
         .iter()
         .map(|x| {
-            format!(
-                "{}_{}",
+            mangle::host_closure_type_name(
                 x.module_string(&loaded.interns),
-                x.as_str(&loaded.interns)
+                x.as_str(&loaded.interns),
             )
         })
         .collect();
@@ -178,17 +527,16 @@ fn make_stub_dll_symbols(
 
     for sym in exposed_to_host {
         custom_names.extend([
-            format!("roc__{}_1_exposed", sym),
-            format!("roc__{}_1_exposed_generic", sym),
+            mangle::host_exposed_name(&sym, 1),
+            mangle::host_exposed_generic_name(&sym, 1),
             format!("roc__{}_size", sym),
         ]);
 
         for closure_type in &exported_closure_types {
-            custom_names.extend([
-                format!("roc__{}_1_{}_caller", sym, closure_type),
-                format!("roc__{}_1_{}_size", sym, closure_type),
-                format!("roc__{}_1_{}_result_size", sym, closure_type),
-            ]);
+            custom_names.extend(
+                HostClosureFn::ALL
+                    .map(|function| mangle::host_closure_fn_name(&sym, 1, closure_type, function)),
+            );
         }
     }
 
@@ -342,8 +690,7 @@ fn preprocess(
     shared_lib: &Path,
     stub_dll_symbols: &[String],
     verbose: bool,
-    time: bool,
-) {
+) -> LinkStats {
     if verbose {
         println!("Targeting: {}", target);
     }
@@ -353,29 +700,23 @@ fn preprocess(
         .unwrap_or(target_lexicon::Endianness::Little);
 
     match target.binary_format {
-        target_lexicon::BinaryFormat::Elf => {
-            crate::elf::preprocess_elf(
-                endianness,
-                host_exe_path,
-                metadata_path,
-                preprocessed_path,
-                shared_lib,
-                verbose,
-                time,
-            );
-        }
-
-        target_lexicon::BinaryFormat::Macho => {
-            crate::macho::preprocess_macho(
-                target,
-                host_exe_path,
-                metadata_path,
-                preprocessed_path,
-                shared_lib,
-                verbose,
-                time,
-            );
-        }
+        target_lexicon::BinaryFormat::Elf => crate::elf::preprocess_elf(
+            endianness,
+            host_exe_path,
+            metadata_path,
+            preprocessed_path,
+            shared_lib,
+            verbose,
+        ),
+
+        target_lexicon::BinaryFormat::Macho => crate::macho::preprocess_macho(
+            target,
+            host_exe_path,
+            metadata_path,
+            preprocessed_path,
+            shared_lib,
+            verbose,
+        ),
 
         target_lexicon::BinaryFormat::Coff => {
             crate::pe::preprocess_windows(
@@ -384,13 +725,14 @@ fn preprocess(
                 preprocessed_path,
                 stub_dll_symbols,
                 verbose,
-                time,
             )
             .unwrap_or_else(|e| internal_error!("{}", e));
+
+            LinkStats::default()
         }
 
         target_lexicon::BinaryFormat::Wasm => {
-            todo!("Roc does not yet support web assembly hosts!");
+            crate::wasm::preprocess_wasm(host_exe_path, preprocessed_path)
         }
         target_lexicon::BinaryFormat::Unknown => {
             internal_error!("Roc does not support unknown host binary formats!");
@@ -412,30 +754,31 @@ fn surgery(
     metadata_path: &Path,
     executable_path: &Path,
     verbose: bool,
-    time: bool,
     target: &Triple,
-) {
+) -> Result<LinkStats, String> {
     match target.binary_format {
-        target_lexicon::BinaryFormat::Elf => {
-            crate::elf::surgery_elf(roc_app_bytes, metadata_path, executable_path, verbose, time);
-        }
-
-        target_lexicon::BinaryFormat::Macho => {
-            crate::macho::surgery_macho(
-                roc_app_bytes,
-                metadata_path,
-                executable_path,
-                verbose,
-                time,
-            );
-        }
+        target_lexicon::BinaryFormat::Elf => Ok(crate::elf::surgery_elf(
+            roc_app_bytes,
+            metadata_path,
+            executable_path,
+            verbose,
+        )),
+
+        target_lexicon::BinaryFormat::Macho => Ok(crate::macho::surgery_macho(
+            roc_app_bytes,
+            metadata_path,
+            executable_path,
+            verbose,
+        )),
 
         target_lexicon::BinaryFormat::Coff => {
             crate::pe::surgery_pe(executable_path, metadata_path, roc_app_bytes);
+
+            Ok(LinkStats::default())
         }
 
         target_lexicon::BinaryFormat::Wasm => {
-            todo!("Roc does not yet support web assembly hosts!");
+            crate::wasm::surgery_wasm(roc_app_bytes, executable_path)
         }
         target_lexicon::BinaryFormat::Unknown => {
             internal_error!("Roc does not support unknown host binary formats!");
@@ -513,7 +856,7 @@ pub(crate) fn open_mmap(path: &Path) -> Mmap {
     unsafe { Mmap::map(&in_file).unwrap_or_else(|e| internal_error!("{e}")) }
 }
 
-pub(crate) fn open_mmap_mut(path: &Path, length: usize) -> MmapMut {
+pub(crate) fn open_mmap_mut(path: &Path, length: usize) -> OutputFile {
     let out_file = std::fs::OpenOptions::new()
         .read(true)
         .write(true)
@@ -524,7 +867,123 @@ pub(crate) fn open_mmap_mut(path: &Path, length: usize) -> MmapMut {
         .set_len(length as u64)
         .unwrap_or_else(|e| internal_error!("{e}"));
 
-    unsafe { MmapMut::map_mut(&out_file).unwrap_or_else(|e| internal_error!("{e}")) }
+    match unsafe { MmapMut::map_mut(&out_file) } {
+        Ok(mmap) => OutputFile::Mapped(mmap),
+        // Some network mounts and containers can't map files for writing.
+        Err(_) => OutputFile::buffered(out_file)
+            .unwrap_or_else(|e| internal_error!("failed to read file {path:?}: {e}")),
+    }
+}
+
+/// A file the linker changes in place. It's mapped into memory where the file system allows
+/// that, and otherwise read into a buffer which gets written back to it.
+pub(crate) enum OutputFile {
+    Mapped(MmapMut),
+    Buffered {
+        file: File,
+        bytes: Vec<u8>,
+        /// Whether `bytes` may have changed since they were last written back
+        dirty: Cell<bool>,
+    },
+}
+
+impl OutputFile {
+    fn buffered(mut file: File) -> io::Result<Self> {
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+
+        Ok(OutputFile::Buffered {
+            file,
+            bytes,
+            dirty: Cell::new(false),
+        })
+    }
+
+    pub(crate) fn flush(&self) -> io::Result<()> {
+        match self {
+            OutputFile::Mapped(mmap) => mmap.flush(),
+            OutputFile::Buffered { file, bytes, dirty } => {
+                let mut file = file;
+                file.seek(SeekFrom::Start(0))?;
+                file.write_all(bytes)?;
+                dirty.set(false);
+
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) fn flush_async_range(&self, offset: usize, len: usize) -> io::Result<()> {
+        match self {
+            OutputFile::Mapped(mmap) => mmap.flush_async_range(offset, len),
+            // `flush` writes all of it back anyway.
+            OutputFile::Buffered { .. } => Ok(()),
+        }
+    }
+}
+
+impl Deref for OutputFile {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            OutputFile::Mapped(mmap) => mmap,
+            OutputFile::Buffered { bytes, .. } => bytes,
+        }
+    }
+}
+
+impl DerefMut for OutputFile {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        match self {
+            OutputFile::Mapped(mmap) => mmap,
+            OutputFile::Buffered { bytes, dirty, .. } => {
+                dirty.set(true);
+                bytes
+            }
+        }
+    }
+}
+
+impl Drop for OutputFile {
+    fn drop(&mut self) {
+        // Changes to a mapped file get written back even if it isn't flushed, so do the same here.
+        if let OutputFile::Buffered { dirty, .. } = self {
+            if dirty.get() {
+                self.flush().unwrap_or_else(|e| internal_error!("{e}"));
+            }
+        }
+    }
+}
+
+/// Prints the roc symbols found in a host executable by address, builtins last, with the
+/// demangled name of each symbol generated for a Roc procedure.
+pub(crate) fn print_roc_definitions(roc_symbol_vaddresses: &MutMap<String, u64>) {
+    println!(
+        "Found {} roc symbol definitions:",
+        roc_symbol_vaddresses.len()
+    );
+
+    let (mut builtins, mut other): (Vec<_>, Vec<_>) = roc_symbol_vaddresses
+        .iter()
+        .partition(|(n, _)| n.starts_with("roc_builtins"));
+
+    // sort by address
+    builtins.sort_by_key(|t| t.1);
+    other.sort_by_key(|t| t.1);
+
+    for (name, vaddr) in other.iter() {
+        match demangle(name) {
+            Some(demangled) => println!("\t{:#08x}: {} ({})", vaddr, name, demangled),
+            None => println!("\t{:#08x}: {}", vaddr, name),
+        }
+    }
+
+    println!("Of which {} are builtins", builtins.len(),);
+
+    for (name, vaddr) in builtins.iter() {
+        println!("\t{:#08x}: {}", vaddr, name);
+    }
 }
 
 /// # dbg_hex
@@ -592,3 +1051,125 @@ mod windows_roc_platform_functions {
         libc::free(c_ptr)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allocator(strategy: AllocatorStrategy, alignment: Option<u32>) -> PlatformAllocator {
+        PlatformAllocator {
+            strategy,
+            alignment,
+        }
+    }
+
+    #[test]
+    fn malloc_host_must_define_all_allocator_fns() {
+        let malloc = allocator(AllocatorStrategy::Malloc, None);
+
+        assert!(validate_platform_allocator(malloc, 8, |_| true).is_ok());
+
+        let err = validate_platform_allocator(malloc, 8, |name| name != "roc_realloc").unwrap_err();
+        assert!(err.contains("does not define roc_realloc"), "{err}");
+    }
+
+    #[test]
+    fn arena_host_must_not_define_dealloc() {
+        let arena = allocator(AllocatorStrategy::Arena, None);
+
+        assert!(validate_platform_allocator(arena, 8, |name| name != "roc_dealloc").is_ok());
+
+        let err = validate_platform_allocator(arena, 8, |_| true).unwrap_err();
+        assert!(err.contains("defines roc_dealloc"), "{err}");
+    }
+
+    #[test]
+    fn roc_allocator_alignment() {
+        let host_defines = |_: &str| false;
+
+        let ok = allocator(AllocatorStrategy::Roc, Some(16));
+        assert!(validate_platform_allocator(ok, 8, host_defines).is_ok());
+
+        let too_big = allocator(AllocatorStrategy::Roc, Some(32));
+        assert!(validate_platform_allocator(too_big, 8, host_defines).is_err());
+
+        let too_small = allocator(AllocatorStrategy::Roc, Some(4));
+        assert!(validate_platform_allocator(too_small, 8, host_defines).is_err());
+
+        // mimalloc supports any alignment
+        let mimalloc = allocator(AllocatorStrategy::Mimalloc, Some(64));
+        assert!(validate_platform_allocator(mimalloc, 8, host_defines).is_ok());
+    }
+
+    #[test]
+    fn warns_about_missing_oom_handler() {
+        assert!(oom_handler_warning(|name| name == "roc_oom").is_none());
+
+        let warning = oom_handler_warning(|name| name != "roc_oom").unwrap();
+        assert!(warning.contains("does not define roc_oom"), "{warning}");
+    }
+
+    #[test]
+    fn host_and_app_must_define_what_the_other_uses() {
+        let host_defines = |name: &str| name == "roc_alloc";
+        let app_defines = |name: &str| name == "roc__mainForHost_1_exposed";
+
+        assert!(validate_app_symbols(
+            ["roc_alloc", "__divti3"],
+            host_defines,
+            ["roc__mainForHost_1_exposed"],
+            app_defines,
+        )
+        .is_ok());
+
+        let err = validate_app_symbols(
+            ["roc_alloc", "roc_fx_putLine", "roc_fx_putLine"],
+            host_defines,
+            [
+                "roc__mainForHost_1_exposed",
+                "roc__mainForHost_1_exposed_size",
+            ],
+            app_defines,
+        )
+        .unwrap_err();
+        assert!(
+            err.contains("does not define:\n\n    roc_fx_putLine\n"),
+            "{err}"
+        );
+        assert!(!err.contains("    roc_alloc"), "{err}");
+        assert!(
+            err.contains("    roc__mainForHost_1_exposed_size (mainForHost [exposed to host: 1_exposed_size])"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn batching_host_must_define_batch_fn() {
+        assert!(validate_effect_batch_fn(|name| name == "roc_fx_batch").is_ok());
+
+        let err = validate_effect_batch_fn(|name| name != "roc_fx_batch").unwrap_err();
+        assert!(err.contains("does not define roc_fx_batch"), "{err}");
+    }
+
+    #[test]
+    fn buffered_output_file_writes_back_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out");
+        std::fs::write(&path, b"host").unwrap();
+
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&path)
+            .unwrap();
+        file.set_len(8).unwrap();
+
+        let mut out = OutputFile::buffered(file).unwrap();
+        assert_eq!(&out[..], b"host\0\0\0\0");
+
+        out[4..].copy_from_slice(b"+app");
+        drop(out);
+
+        assert_eq!(std::fs::read(&path).unwrap(), b"host+app");
+    }
+}
@@ -1,6 +1,5 @@
 use crate::metadata::{self, Metadata, VirtualOffset};
 use iced_x86::{Decoder, DecoderOptions, Instruction, OpCodeOperandKind, OpKind};
-use memmap2::MmapMut;
 use object::macho;
 use object::{
     CompressedFileRange, CompressionFormat, LittleEndian, NativeEndian, Object, ObjectSection,
@@ -12,13 +11,13 @@ use roc_error_macros::internal_error;
 use std::ffi::CStr;
 use std::mem;
 use std::path::Path;
-use std::time::{Duration, Instant};
+use std::time::Instant;
 use target_lexicon::Triple;
 
 use crate::{
     align_by_constraint, align_to_offset_by_constraint, load_struct_inplace,
     load_struct_inplace_mut, load_structs_inplace, load_structs_inplace_mut, open_mmap,
-    open_mmap_mut,
+    open_mmap_mut, print_roc_definitions, LinkStats, ALWAYS_LINKED,
 };
 
 const MIN_SECTION_ALIGNMENT: usize = 0x40;
@@ -34,10 +33,6 @@ const STUB_ADDRESS_OFFSET: u64 = 0x06;
 //     shared_lib_index: usize,
 // }
 
-fn report_timing(label: &str, duration: Duration) {
-    println!("\t{:9.3} ms   {}", duration.as_secs_f64() * 1000.0, label,);
-}
-
 fn is_roc_symbol(sym: &object::Symbol) -> bool {
     if let Ok(name) = sym.name() {
         name.trim_start_matches('_').starts_with("roc_")
@@ -261,8 +256,7 @@ pub(crate) fn preprocess_macho(
     preprocessed_path: &Path,
     shared_lib: &Path,
     verbose: bool,
-    time: bool,
-) {
+) -> LinkStats {
     let total_start = Instant::now();
     let exec_parsing_start = total_start;
     let exec_data = &*open_mmap(host_exe_path);
@@ -279,10 +273,7 @@ pub(crate) fn preprocess_macho(
     };
 
     if verbose {
-        println!(
-            "Found roc symbol definitions: {:+x?}",
-            md.roc_symbol_vaddresses
-        );
+        print_roc_definitions(&md.roc_symbol_vaddresses);
     }
 
     let exec_parsing_duration = exec_parsing_start.elapsed();
@@ -545,38 +536,32 @@ pub(crate) fn preprocess_macho(
     out_mmap
         .flush()
         .unwrap_or_else(|e| internal_error!("{}", e));
+    let bytes_written = out_mmap.len();
     // Also drop files to to ensure data is fully written here.
     drop(out_mmap);
     let flushing_data_duration = flushing_data_start.elapsed();
 
-    let total_duration = total_start.elapsed();
+    let mut stats = LinkStats {
+        phases: vec![
+            ("Executable Parsing", exec_parsing_duration),
+            (
+                "Symbol and PLT Processing",
+                symbol_and_plt_processing_duration,
+            ),
+            ("Text Disassembly", text_disassembly_duration),
+            ("Scanning Dynamic Deps", scanning_dynamic_deps_duration),
+            ("Generate Modified Platform", platform_gen_duration),
+            ("Saving Metadata", saving_metadata_duration),
+            ("Flushing Data to Disk", flushing_data_duration),
+        ],
+        symbols_patched: md.app_functions.len(),
+        surgeries_applied: md.surgeries.values().map(Vec::len).sum(),
+        bytes_written,
+        ..Default::default()
+    };
+    stats.finish(total_start.elapsed());
 
-    if verbose || time {
-        println!();
-        println!("Timings");
-        report_timing("Executable Parsing", exec_parsing_duration);
-        report_timing(
-            "Symbol and PLT Processing",
-            symbol_and_plt_processing_duration,
-        );
-        report_timing("Text Disassembly", text_disassembly_duration);
-        report_timing("Scanning Dynamic Deps", scanning_dynamic_deps_duration);
-        report_timing("Generate Modified Platform", platform_gen_duration);
-        report_timing("Saving Metadata", saving_metadata_duration);
-        report_timing("Flushing Data to Disk", flushing_data_duration);
-        report_timing(
-            "Other",
-            total_duration
-                - exec_parsing_duration
-                - symbol_and_plt_processing_duration
-                - text_disassembly_duration
-                - scanning_dynamic_deps_duration
-                - platform_gen_duration
-                - saving_metadata_duration
-                - flushing_data_duration,
-        );
-        report_timing("Total", total_duration);
-    }
+    stats
 }
 
 fn gen_macho_le(
@@ -586,7 +571,7 @@ fn gen_macho_le(
     macho_load_so_offset: usize,
     _target: &Triple,
     _verbose: bool,
-) -> MmapMut {
+) -> OutputFile {
     // Just adding some extra context/useful info here.
     // I was talking to Jakub from the Zig team about macho linking and here are some useful comments:
     // 1) Macho WILL run fine with multiple text segments (and theoretically data segments).
@@ -1139,8 +1124,7 @@ pub(crate) fn surgery_macho(
     metadata_path: &Path,
     executable_path: &Path,
     verbose: bool,
-    time: bool,
-) {
+) -> LinkStats {
     let app_obj = match object::File::parse(roc_app_bytes) {
         Ok(obj) => obj,
         Err(err) => {
@@ -1166,7 +1150,6 @@ pub(crate) fn surgery_macho(
         metadata_path,
         executable_path,
         verbose,
-        time,
         &md,
         &mut exec_mmap,
         &mut offset,
@@ -1198,23 +1181,25 @@ pub(crate) fn surgery_macho(
         fs::set_permissions(executable_path, perms).unwrap_or_else(|e| internal_error!("{}", e));
     }
 
-    let total_duration = total_start.elapsed();
-
-    if verbose || time {
-        println!("\nTimings");
-        report_timing("Loading Metadata", loading_metadata_duration);
-        report_timing("Loading and mmap-ing", load_and_mmap_duration);
-        report_timing("Output Generation", out_gen_duration);
-        report_timing("Flushing Data to Disk", flushing_data_duration);
-
-        let sum = loading_metadata_duration
-            + load_and_mmap_duration
-            + out_gen_duration
-            + flushing_data_duration;
+    let mut stats = LinkStats {
+        phases: vec![
+            ("Loading Metadata", loading_metadata_duration),
+            ("Loading and mmap-ing", load_and_mmap_duration),
+            ("Output Generation", out_gen_duration),
+            ("Flushing Data to Disk", flushing_data_duration),
+        ],
+        symbols_patched: md.app_functions.len(),
+        surgeries_applied: md
+            .app_functions
+            .iter()
+            .map(|name| md.surgeries.get(name).map_or(0, Vec::len))
+            .sum(),
+        bytes_written: offset,
+        ..Default::default()
+    };
+    stats.finish(total_start.elapsed());
 
-        report_timing("Other", total_duration.saturating_sub(sum));
-        report_timing("Total", total_duration);
-    }
+    stats
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -1222,9 +1207,8 @@ fn surgery_macho_help(
     _metadata_filename: &Path,
     _out_filename: &Path,
     verbose: bool,
-    _time: bool,
     md: &metadata::Metadata,
-    exec_mmap: &mut MmapMut,
+    exec_mmap: &mut OutputFile,
     offset_ref: &mut usize, // TODO return this instead of taking a mutable reference to it
     app_obj: object::File,
 ) {
@@ -1427,7 +1411,7 @@ fn surgery_macho_help(
                                 internal_error!("Relocation size not yet supported: {}", x);
                             }
                         }
-                    } else if matches!(app_obj.symbol_by_index(index), Ok(sym) if ["__divti3", "__udivti3", "___divti3", "___udivti3"].contains(&sym.name().unwrap_or_default()))
+                    } else if matches!(app_obj.symbol_by_index(index), Ok(sym) if ALWAYS_LINKED.contains(&sym.name().unwrap_or_default()))
                     {
                         // Explicitly ignore some symbols that are currently always linked.
                         continue;
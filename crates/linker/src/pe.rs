@@ -4,7 +4,6 @@ use std::{
 };
 
 use bincode::{deserialize_from, serialize_into};
-use memmap2::MmapMut;
 use object::{
     pe::{
         self, ImageBaseRelocation, ImageFileHeader, ImageImportDescriptor, ImageNtHeaders64,
@@ -20,7 +19,7 @@ use roc_error_macros::internal_error;
 
 use crate::{
     generate_dylib::APP_DLL, load_struct_inplace, load_struct_inplace_mut,
-    load_structs_inplace_mut, open_mmap, open_mmap_mut,
+    load_structs_inplace_mut, open_mmap, open_mmap_mut, OutputFile,
 };
 
 /// The metadata stores information about/from the host .exe because
@@ -185,7 +184,6 @@ pub(crate) fn preprocess_windows(
     preprocessed_filename: &Path,
     dummy_dll_symbols: &[String],
     _verbose: bool,
-    _time: bool,
 ) -> object::read::Result<()> {
     let data = open_mmap(host_exe_filename);
 
@@ -285,6 +283,11 @@ fn relocate_to(
     }
 }
 
+/// Symbols that the preprocessed host exports, like roc_alloc
+pub(crate) fn read_host_exports(metadata_path: &Path) -> MutMap<String, i64> {
+    PeMetadata::read_from_file(metadata_path).exports
+}
+
 pub(crate) fn surgery_pe(executable_path: &Path, metadata_path: &Path, roc_app_bytes: &[u8]) {
     let md = PeMetadata::read_from_file(metadata_path);
 
@@ -663,7 +666,7 @@ impl Preprocessor {
         data: &[u8],
         dummy_dll_symbols: usize,
         extra_sections: &[[u8; 8]],
-    ) -> MmapMut {
+    ) -> OutputFile {
         let this = Self::new(data, dummy_dll_symbols, extra_sections);
         let mut result = open_mmap_mut(
             output_path,
@@ -755,7 +758,7 @@ impl Preprocessor {
         }
     }
 
-    fn copy(&self, result: &mut MmapMut, data: &[u8]) {
+    fn copy(&self, result: &mut OutputFile, data: &[u8]) {
         let extra_sections_start = self.extra_sections_start;
 
         // copy the headers up to and including the current section table entries
@@ -779,7 +782,7 @@ impl Preprocessor {
         result[self.new_headers_size..][..source.len()].copy_from_slice(source);
     }
 
-    fn write_dummy_sections(&self, result: &mut MmapMut, extra_section_names: &[[u8; 8]]) {
+    fn write_dummy_sections(&self, result: &mut OutputFile, extra_section_names: &[[u8; 8]]) {
         const W: usize = std::mem::size_of::<ImageSectionHeader>();
 
         // only correct for the first section, but that is OK because it's overwritten later
@@ -824,7 +827,7 @@ impl Preprocessor {
         }
     }
 
-    fn fix(&self, result: &mut MmapMut, extra_sections: &[[u8; 8]]) {
+    fn fix(&self, result: &mut OutputFile, extra_sections: &[[u8; 8]]) {
         self.write_dummy_sections(result, extra_sections);
 
         // update the size of the headers
@@ -1591,7 +1594,7 @@ mod test {
         input_data: &[u8],
         new_sections: &[[u8; 8]],
         output_file: &Path,
-    ) -> MmapMut {
+    ) -> OutputFile {
         use object::read::pe::ImageNtHeaders;
 
         let dos_header = object::pe::ImageDosHeader::parse(input_data).unwrap();
@@ -1719,7 +1722,6 @@ mod test {
             &preprocessed_host_filename,
             &names,
             false,
-            false,
         )
         .unwrap();
 
@@ -0,0 +1,677 @@
+use bumpalo::collections::Vec as BumpVec;
+use bumpalo::Bump;
+use roc_error_macros::internal_error;
+use roc_wasm_module::linking::{
+    DataSymbol, IndexRelocType, OffsetRelocType, RelocationEntry, SymInfo, WasmObjectSymbol,
+    WASM_SYM_BINDING_LOCAL, WASM_SYM_UNDEFINED,
+};
+use roc_wasm_module::opcodes::OpCode;
+use roc_wasm_module::sections::{
+    ConstExpr, DataMode, DataSection, DataSegment, ElementSegment, Export, ExportType, Global,
+    GlobalType, Import, ImportDesc, Limits, MemorySection, NameSection, Signature,
+};
+use roc_wasm_module::serialize::{overwrite_padded_i32, overwrite_padded_u32, SerialBuffer};
+use roc_wasm_module::{round_up_to_alignment, ValueType, WasmModule, STACK_POINTER_GLOBAL_ID};
+use std::path::Path;
+use std::time::Instant;
+
+use crate::{validate_app_symbols, LinkStats};
+
+/// The same as the dev backend's default
+const STACK_BYTES: u32 = 1024 * 1024;
+
+/// The name JavaScript sees the memory exported as
+const MEMORY_NAME: &str = "memory";
+
+const PTR_SIZE: u32 = 4;
+
+/// Makes the host relocatable, so that surgery can splice the app into it. The host is an
+/// object file, and wasm-ld adds the builtins and libc it depends on.
+pub(crate) fn preprocess_wasm(host_exe_path: &Path, preprocessed_path: &Path) -> LinkStats {
+    let total_start = Instant::now();
+
+    let relocatable_linking_start = total_start;
+    roc_build::link::preprocess_host_wasm32(host_exe_path, preprocessed_path);
+    let relocatable_linking_duration = relocatable_linking_start.elapsed();
+
+    let bytes_written = std::fs::metadata(preprocessed_path)
+        .unwrap_or_else(|e| internal_error!("{}", e))
+        .len() as usize;
+
+    let mut stats = LinkStats {
+        phases: vec![("Relocatable Linking", relocatable_linking_duration)],
+        bytes_written,
+        ..Default::default()
+    };
+    stats.finish(total_start.elapsed());
+
+    stats
+}
+
+/// Splices the app's functions and data into the preprocessed host at `executable_path`,
+/// and overwrites it with the finished module. Problems with the host are returned as errors,
+/// since they come from the platform rather than from Roc.
+pub(crate) fn surgery_wasm(
+    roc_app_bytes: &[u8],
+    executable_path: &Path,
+) -> Result<LinkStats, String> {
+    let total_start = Instant::now();
+    let arena = Bump::new();
+
+    let loading_start = total_start;
+    let host_bytes = std::fs::read(executable_path).map_err(|e| {
+        format!(
+            "I couldn't read the preprocessed host at {}: {}",
+            executable_path.display(),
+            e
+        )
+    })?;
+    let mut host = WasmModule::preload(&arena, &host_bytes, true).map_err(|e| {
+        format!(
+            "I couldn't parse the preprocessed host at {}. At offset 0x{:x}:\n{}",
+            executable_path.display(),
+            e.offset,
+            e.message
+        )
+    })?;
+    let app = WasmModule::preload(&arena, roc_app_bytes, true).unwrap_or_else(|e| {
+        internal_error!(
+            "Failed to parse application file at offset 0x{:x}:\n{}",
+            e.offset,
+            e.message
+        )
+    });
+    let loading_duration = loading_start.elapsed();
+
+    let splicing_start = Instant::now();
+    let (symbols_patched, surgeries_applied) = link_app(&arena, &mut host, &app)?;
+    finalize(&arena, &mut host)?;
+    let splicing_duration = splicing_start.elapsed();
+
+    let writing_start = Instant::now();
+    let mut buffer = Vec::with_capacity(host.size());
+    host.serialize(&mut buffer);
+    std::fs::write(executable_path, &buffer).map_err(|e| {
+        format!(
+            "I couldn't write the linked module to {}: {}",
+            executable_path.display(),
+            e
+        )
+    })?;
+    let writing_duration = writing_start.elapsed();
+
+    let mut stats = LinkStats {
+        phases: vec![
+            ("Loading Modules", loading_duration),
+            ("Splicing", splicing_duration),
+            ("Writing Module", writing_duration),
+        ],
+        symbols_patched,
+        surgeries_applied,
+        bytes_written: buffer.len(),
+        ..Default::default()
+    };
+    stats.finish(total_start.elapsed());
+
+    Ok(stats)
+}
+
+/// Adds the app's functions after the host's, and its data after the host's data, rewriting the
+/// function, type, global, and table indices and the data addresses in both to match.
+/// Returns how many of the host's imports the app defines, and how many of the app's
+/// relocations were applied.
+fn link_app<'a>(
+    arena: &'a Bump,
+    host: &mut WasmModule<'a>,
+    app: &WasmModule<'a>,
+) -> Result<(usize, usize), String> {
+    // The final module defines its own memory and table
+    host.import.imports.retain(|import| {
+        !matches!(
+            import.description,
+            ImportDesc::Mem { .. } | ImportDesc::Table { .. }
+        )
+    });
+
+    if host.names.function_names.is_empty() {
+        host.names = NameSection::from_imports_and_linking_data(arena, &host.import, &host.linking);
+    }
+
+    // Calls to the app from the host were imports. Linking them replaces each import with a
+    // dummy function, so that the host's other functions keep their indices.
+    let fn_index_offset = host.import.function_count() as u32 + host.code.function_count;
+    let app_import_fn_count = app.import.function_count() as u32;
+
+    let mut host_to_app_map = BumpVec::new_in(arena);
+    let mut symbols_patched = 0;
+    for sym in app.linking.symbol_table.iter() {
+        if let SymInfo::Function(WasmObjectSymbol::ExplicitlyNamed { flags, index, name }) = sym {
+            let is_exported = flags & (WASM_SYM_UNDEFINED | WASM_SYM_BINDING_LOCAL) == 0;
+            let host_imports_it = host
+                .import
+                .imports
+                .iter()
+                .any(|import| import.is_function() && import.name == *name);
+
+            // Anything `roc__` the host doesn't call is for JavaScript, and gets exported
+            if is_exported && (host_imports_it || name.starts_with("roc__")) {
+                host_to_app_map.push((*name, fn_index_offset + index - app_import_fn_count));
+                symbols_patched += host_imports_it as usize;
+            }
+        }
+    }
+    host.link_host_to_app_calls(arena, host_to_app_map);
+
+    let host_lookup = host.get_host_function_lookup(arena);
+    let host_segment_addrs = segment_addrs(&host.data)
+        .map_err(|message| format!("I can't link the app into this host. {message}"))?;
+    let host_data_addr = |name: &str| {
+        host.linking.symbol_table.iter().find_map(|sym| match sym {
+            SymInfo::Data(DataSymbol::Defined {
+                flags,
+                name: sym_name,
+                segment_index,
+                segment_offset,
+                ..
+            }) if *sym_name == name && flags & WASM_SYM_BINDING_LOCAL == 0 => {
+                Some(host_segment_addrs[*segment_index as usize] + segment_offset)
+            }
+            _ => None,
+        })
+    };
+
+    let app_import_fn_names = app
+        .import
+        .imports
+        .iter()
+        .filter(|import| import.is_function())
+        .map(|import| import.name);
+    let app_import_data_names = app.linking.symbol_table.iter().filter_map(|sym| match sym {
+        SymInfo::Data(DataSymbol::Imported { name, .. }) => Some(*name),
+        _ => None,
+    });
+
+    validate_app_symbols(
+        app_import_fn_names.chain(app_import_data_names),
+        |name| host_lookup.iter().any(|(n, _)| *n == name) || host_data_addr(name).is_some(),
+        [],
+        |_| true,
+    )?;
+
+    // The app's functions go right after the host's
+    let mut fn_indices =
+        Vec::with_capacity((app_import_fn_count + app.code.function_count) as usize);
+    for import in app
+        .import
+        .imports
+        .iter()
+        .filter(|import| import.is_function())
+    {
+        let (_, index) = host_lookup
+            .iter()
+            .find(|(name, _)| *name == import.name)
+            .unwrap_or_else(|| internal_error!("The host does not define {}", import.name));
+        fn_indices.push(*index);
+    }
+    fn_indices.extend((0..app.code.function_count).map(|i| fn_index_offset + i));
+
+    // The app's data goes right after the host's, keeping its layout
+    let app_segments = app
+        .data
+        .segments()
+        .unwrap_or_else(|e| internal_error!("{}", e.message));
+    let app_align = app
+        .linking
+        .segment_info
+        .iter()
+        .map(|segment| 1u32 << segment.align_bytes_pow2)
+        .max()
+        .unwrap_or(1);
+    let app_data_start = round_up_to_alignment!(host.data.end_addr, app_align);
+    let app_segment_addrs: Vec<u32> = segment_addrs(&app.data)
+        .unwrap_or_else(|message| internal_error!("{}", message))
+        .into_iter()
+        .map(|addr| app_data_start + addr)
+        .collect();
+
+    let app_global_names: Vec<&str> = app
+        .import
+        .imports
+        .iter()
+        .filter(|import| matches!(import.description, ImportDesc::Global { .. }))
+        .map(|import| import.name)
+        .collect();
+
+    let mut symbol_values = Vec::with_capacity(app.linking.symbol_table.len());
+    for sym in app.linking.symbol_table.iter() {
+        let value = match sym {
+            SymInfo::Function(WasmObjectSymbol::ExplicitlyNamed { index, .. })
+            | SymInfo::Function(WasmObjectSymbol::ImplicitlyNamed { index, .. }) => {
+                fn_indices[*index as usize]
+            }
+            SymInfo::Data(DataSymbol::Defined {
+                segment_index,
+                segment_offset,
+                ..
+            }) => app_segment_addrs[*segment_index as usize] + segment_offset,
+            SymInfo::Data(DataSymbol::Imported { name, .. }) => host_data_addr(name)
+                .unwrap_or_else(|| internal_error!("The host does not define {}", name)),
+            SymInfo::Global(WasmObjectSymbol::ExplicitlyNamed { index, .. })
+            | SymInfo::Global(WasmObjectSymbol::ImplicitlyNamed { index, .. }) => {
+                // Relocatable modules import all of their globals, and the host and app share
+                // the stack pointer, which is the only one we support.
+                match app_global_names.get(*index as usize) {
+                    Some(&"__stack_pointer") => STACK_POINTER_GLOBAL_ID,
+                    Some(name) => {
+                        return Err(format!(
+                            "I can't link the app into this host, because the app uses the global variable {name}. The only global variable I can link is __stack_pointer."
+                        ))
+                    }
+                    None => {
+                        return Err(format!(
+                            "I can't link the app into this host, because the app uses global #{index}, which it doesn't import."
+                        ))
+                    }
+                }
+            }
+            // There is only one table
+            SymInfo::Table(_) => 0,
+            SymInfo::Section(_) | SymInfo::Event(_) => u32::MAX,
+        };
+        symbol_values.push(value);
+    }
+
+    // Index 0 of the table is the null function pointer, so the app's function pointers start
+    // at 1 if the host doesn't have any
+    if host.element.segments.is_empty() {
+        host.element.segments.push(ElementSegment {
+            offset: ConstExpr::I32(1),
+            fn_indices: BumpVec::new_in(arena),
+        });
+    }
+
+    // Functions
+    let mut app_code = app.code.bytes.to_vec();
+    for entry in app.reloc_code.entries.iter() {
+        relocate(host, app, &symbol_values, &mut app_code, 0, entry);
+    }
+
+    if let Some(first_fn_start) = app.code.function_offsets.first() {
+        let first_fn_start = *first_fn_start as usize;
+        let host_code_len = host.code.bytes.len();
+
+        host.code.function_offsets.extend(
+            app.code
+                .function_offsets
+                .iter()
+                .map(|offset| (*offset as usize - first_fn_start + host_code_len) as u32),
+        );
+        host.code
+            .bytes
+            .extend_from_slice(&app_code[first_fn_start..]);
+        host.code.function_count += app.code.function_count;
+    }
+
+    for sig_index in app.function.signatures.iter() {
+        let host_sig_index = host.types.insert_from(&app.types, *sig_index);
+        host.function.add_sig(host_sig_index);
+    }
+
+    let mut app_names: Vec<(u32, &'a str)> = app
+        .linking
+        .symbol_table
+        .iter()
+        .filter_map(|sym| match sym {
+            SymInfo::Function(WasmObjectSymbol::ExplicitlyNamed { flags, index, name })
+                if flags & WASM_SYM_UNDEFINED == 0 =>
+            {
+                Some((fn_indices[*index as usize], *name))
+            }
+            _ => None,
+        })
+        .collect();
+    app_names.sort_by_key(|(index, _)| *index);
+    for (index, name) in app_names {
+        host.names.append_function(index, name);
+    }
+
+    // Data
+    let mut app_data: Vec<(u32, u32, Vec<u8>)> = app_segments
+        .iter()
+        .zip(app_segment_addrs.iter())
+        .map(|((_, offset, init), addr)| (*addr, *offset, init.to_vec()))
+        .collect();
+
+    for entry in app.reloc_data.entries.iter() {
+        let entry_offset = match entry {
+            RelocationEntry::Index { offset, .. } | RelocationEntry::Offset { offset, .. } => {
+                *offset
+            }
+        };
+        let (_, segment_offset, bytes) = app_data
+            .iter_mut()
+            .find(|(_, segment_offset, bytes)| {
+                (*segment_offset..*segment_offset + bytes.len() as u32).contains(&entry_offset)
+            })
+            .unwrap_or_else(|| {
+                internal_error!("reloc.DATA offset 0x{:x} is not in a segment", entry_offset)
+            });
+
+        relocate(host, app, &symbol_values, bytes, *segment_offset, entry);
+    }
+
+    for (addr, _, init) in app_data {
+        host.data.end_addr = host.data.end_addr.max(addr + init.len() as u32);
+        host.data.append_segment(DataSegment {
+            mode: DataMode::active_at(addr),
+            init: BumpVec::from_iter_in(init, arena),
+        });
+    }
+
+    let relocations_applied = app.reloc_code.entries.len() + app.reloc_data.entries.len();
+
+    Ok((symbols_patched, relocations_applied))
+}
+
+/// The address of each of a module's data segments
+fn segment_addrs(data: &DataSection) -> Result<Vec<u32>, String> {
+    let segments = data.segments().map_err(|e| e.message)?;
+
+    segments
+        .iter()
+        .map(|(mode, _, _)| match mode {
+            DataMode::Active {
+                offset: ConstExpr::I32(addr),
+            } => Ok(*addr as u32),
+            _ => Err("I can only link data segments at constant addresses.".to_string()),
+        })
+        .collect()
+}
+
+/// Applies one of the app's relocations to `bytes`, which start at `section_offset` in the
+/// section the relocation is for.
+fn relocate(
+    host: &mut WasmModule,
+    app: &WasmModule,
+    symbol_values: &[u32],
+    bytes: &mut [u8],
+    section_offset: u32,
+    entry: &RelocationEntry,
+) {
+    match entry {
+        RelocationEntry::Index {
+            type_id,
+            offset,
+            symbol_index,
+        } => {
+            use IndexRelocType::*;
+            let idx = (offset - section_offset) as usize;
+            let value = || symbol_values[*symbol_index as usize];
+
+            match type_id {
+                FunctionIndexLeb | GlobalIndexLeb | TableNumberLeb => {
+                    overwrite_padded_u32(&mut bytes[idx..], value());
+                }
+                // For type indices, the "symbol index" is the index in the type section
+                TypeIndexLeb => {
+                    let host_sig_index = host.types.insert_from(&app.types, *symbol_index);
+                    overwrite_padded_u32(&mut bytes[idx..], host_sig_index);
+                }
+                TableIndexSleb => {
+                    let table_index = host.element.get_or_insert_fn(value());
+                    overwrite_padded_i32(&mut bytes[idx..], table_index);
+                }
+                TableIndexI32 => {
+                    let table_index = host.element.get_or_insert_fn(value());
+                    bytes[idx..][..4].copy_from_slice(&table_index.to_le_bytes());
+                }
+                GlobalIndexI32 => {
+                    bytes[idx..][..4].copy_from_slice(&value().to_le_bytes());
+                }
+                _ => internal_error!("Linking relocation type {:?}", type_id),
+            }
+        }
+        RelocationEntry::Offset {
+            type_id,
+            offset,
+            symbol_index,
+            addend,
+        } => {
+            use OffsetRelocType::*;
+            let idx = (offset - section_offset) as usize;
+            let addr = symbol_values[*symbol_index as usize] as i32 + addend;
+
+            match type_id {
+                MemoryAddrLeb => {
+                    overwrite_padded_u32(&mut bytes[idx..], addr as u32);
+                }
+                MemoryAddrSleb => {
+                    overwrite_padded_i32(&mut bytes[idx..], addr);
+                }
+                MemoryAddrI32 => {
+                    bytes[idx..][..4].copy_from_slice(&addr.to_le_bytes());
+                }
+                _ => internal_error!("Linking relocation type {:?}", type_id),
+            }
+        }
+    }
+}
+
+/// The same final steps as the dev backend, to turn the relocatable host, now containing the app,
+/// into a module that can run.
+fn finalize<'a>(arena: &'a Bump, host: &mut WasmModule<'a>) -> Result<(), String> {
+    set_memory_layout(arena, host)?;
+    export_globals(host);
+    export_start(arena, host);
+
+    let fn_table_size = 1 + host.element.max_table_index();
+    host.table.function_table.limits = Limits::MinMax(fn_table_size, fn_table_size);
+
+    Ok(())
+}
+
+/// If the host has some `extern` global variables, we need to create them in the final binary
+/// and make them visible to JavaScript by exporting them
+fn export_globals(host: &mut WasmModule) {
+    for (sym_index, sym) in host.linking.symbol_table.iter().enumerate() {
+        match sym {
+            SymInfo::Data(DataSymbol::Imported { name, .. }) if *name != "__heap_base" => {
+                let global_value_addr = host.data.end_addr;
+                host.data.end_addr += PTR_SIZE;
+
+                host.reloc_code.apply_relocs_u32(
+                    &mut host.code.bytes,
+                    sym_index as u32,
+                    global_value_addr,
+                );
+
+                let global_index = host.global.count;
+                host.global.append(Global {
+                    ty: GlobalType {
+                        value_type: ValueType::I32,
+                        is_mutable: false,
+                    },
+                    init: ConstExpr::I32(global_value_addr as i32),
+                });
+
+                host.export.append(Export {
+                    name: *name,
+                    ty: ExportType::Global,
+                    index: global_index,
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Put the stack above the data, and the heap above the stack, like the dev backend does
+fn set_memory_layout<'a>(arena: &'a Bump, host: &mut WasmModule<'a>) -> Result<(), String> {
+    let mut stack_heap_boundary = host.data.end_addr + STACK_BYTES;
+    stack_heap_boundary = round_up_to_alignment!(stack_heap_boundary, MemorySection::PAGE_SIZE);
+
+    // The host imports the stack pointer, and the final module defines it.
+    // If there were other imported globals, we'd have to relocate them, and we don't.
+    let sp_type = GlobalType {
+        value_type: ValueType::I32,
+        is_mutable: true,
+    };
+    let sp_import = Import {
+        module: "env",
+        name: "__stack_pointer",
+        description: ImportDesc::Global { ty: sp_type },
+    };
+    let imported_globals: Vec<&Import> = host
+        .import
+        .imports
+        .iter()
+        .filter(|import| matches!(import.description, ImportDesc::Global { .. }))
+        .collect();
+    if imported_globals != [&sp_import] {
+        return Err(
+            "I can't link the app into this host. I expected it to have one imported Global called env.__stack_pointer".to_string(),
+        );
+    }
+    host.import
+        .imports
+        .retain(|import| !matches!(import.description, ImportDesc::Global { .. }));
+
+    host.global.append(Global {
+        ty: sp_type,
+        init: ConstExpr::I32(stack_heap_boundary as i32),
+    });
+
+    let memory_bytes = stack_heap_boundary + MemorySection::PAGE_SIZE;
+    host.memory = MemorySection::new(arena, memory_bytes);
+
+    // Export the memory so that JS can interact with it
+    host.export.append(Export {
+        name: MEMORY_NAME,
+        ty: ExportType::Mem,
+        index: 0,
+    });
+
+    // Set the constant that malloc uses to know where the heap begins
+    if host.linking.find_internal_symbol("__heap_base").is_ok() {
+        host.relocate_internal_symbol("__heap_base", stack_heap_boundary)
+            .unwrap_or_else(|e| internal_error!("{}", e));
+    }
+
+    Ok(())
+}
+
+/// Export the host's `_start`, or make one that calls its `main`
+fn export_start<'a>(arena: &'a Bump, host: &mut WasmModule<'a>) {
+    const START: &str = "_start";
+
+    let fn_index_of = |host: &WasmModule, name: &str| -> Option<u32> {
+        let sym_index = host.linking.find_internal_symbol(name).ok()?;
+        match host.linking.symbol_table[sym_index] {
+            SymInfo::Function(WasmObjectSymbol::ExplicitlyNamed { index, .. }) => Some(index),
+            _ => None,
+        }
+    };
+
+    if let Some(index) = fn_index_of(host, START) {
+        host.export.append(Export {
+            name: START,
+            ty: ExportType::Func,
+            index,
+        });
+        return;
+    }
+
+    let main_fn_index = match fn_index_of(host, "main") {
+        Some(index) => index,
+        None => return,
+    };
+
+    let start_fn_index = host.import.function_count() as u32
+        + host.code.dead_import_dummy_count
+        + host.code.function_count;
+
+    host.add_function_signature(Signature {
+        param_types: bumpalo::vec![in arena],
+        ret_type: None,
+    });
+
+    host.export.append(Export {
+        name: START,
+        ty: ExportType::Func,
+        index: start_fn_index,
+    });
+
+    // main(0, NULL)
+    let mut body = Vec::with_capacity(16);
+    body.push(0); // no locals
+    body.push(OpCode::I32CONST as u8);
+    body.encode_i32(0);
+    body.push(OpCode::I32CONST as u8);
+    body.encode_i32(0);
+    body.push(OpCode::CALL as u8);
+    body.encode_u32(main_fn_index);
+    body.push(OpCode::DROP as u8);
+    body.push(OpCode::END as u8);
+
+    host.code
+        .function_offsets
+        .push(host.code.bytes.len() as u32);
+    host.code.bytes.encode_u32(body.len() as u32);
+    host.code.bytes.extend_from_slice(&body);
+    host.code.function_count += 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use roc_wasm_interp::{DefaultImportDispatcher, Instance, Value};
+
+    const HOST: &[u8] = include_bytes!("../wasm_fixtures/host.o");
+    const APP: &[u8] = include_bytes!("../wasm_fixtures/app.o");
+    const APP_WITH_GLOBAL: &[u8] = include_bytes!("../wasm_fixtures/app_with_global.o");
+
+    fn surgery_on_host(app: &[u8]) -> (Result<LinkStats, String>, Vec<u8>) {
+        let dir = tempfile::tempdir().unwrap();
+        let executable_path = dir.path().join("host.wasm");
+        std::fs::write(&executable_path, HOST).unwrap();
+
+        let result = surgery_wasm(app, &executable_path);
+
+        (result, std::fs::read(&executable_path).unwrap())
+    }
+
+    #[test]
+    fn link_and_run() {
+        let (result, linked) = surgery_on_host(APP);
+        let stats = result.unwrap();
+
+        // The host imports roc__app_main from the app
+        assert_eq!(stats.symbols_patched, 1);
+        assert!(stats.surgeries_applied > 0);
+        assert_eq!(stats.bytes_written, linked.len());
+
+        let arena = Bump::new();
+        let module = WasmModule::preload(&arena, &linked, false).unwrap();
+        let mut instance =
+            Instance::for_module(&arena, &module, DefaultImportDispatcher::default(), false)
+                .unwrap();
+
+        // App data, an app function pointer, a call to the host, and a call back into the app
+        let result = instance.call_export("roc__run", []).unwrap();
+
+        assert_eq!(
+            result,
+            Some(Value::I32(30 + 2 * 3 + (1 + 2 + 3 + 4) + 5 * 100))
+        );
+    }
+
+    #[test]
+    fn app_global_is_an_error() {
+        let (result, linked) = surgery_on_host(APP_WITH_GLOBAL);
+        let message = result.unwrap_err();
+
+        assert!(message.contains("app_counter"), "{}", message);
+        assert_eq!(linked, HOST, "the host should be left as it was");
+    }
+}
@@ -0,0 +1,42 @@
+; A tiny app for testing surgery on wasm32. Regenerate app.o after changing this with
+;
+;     llc -O0 -mtriple=wasm32-unknown-unknown -filetype=obj app.ll -o app.o
+
+target datalayout = "e-m:e-p:32:32-p10:8:8-p20:8:8-i64:64-n32:64-S128-ni:1:10:20"
+target triple = "wasm32-unknown-unknown"
+
+@app_numbers = constant [3 x i32] [i32 10, i32 20, i32 30], align 4
+
+; A function pointer in the app's data, which needs a slot in the host's table
+@app_triple_ptr = global i32 (i32)* @app_triple, align 4
+
+declare i32 @host_sum(i32)
+declare i32 @host_call_app(i32)
+
+define internal i32 @app_triple(i32 %x) {
+entry:
+  %result = mul i32 %x, 3
+  ret i32 %result
+}
+
+; The host calls this
+define i32 @roc__app_main(i32 %x) {
+entry:
+  %result = mul i32 %x, 100
+  ret i32 %result
+}
+
+; JavaScript calls this: 30 + 2 * 3 + (1 + 2 + 3 + 4) + 5 * 100
+define i32 @roc__run() {
+entry:
+  %number_ptr = getelementptr [3 x i32], [3 x i32]* @app_numbers, i32 0, i32 2
+  %number = load i32, i32* %number_ptr, align 4
+  %triple = load i32 (i32)*, i32 (i32)** @app_triple_ptr, align 4
+  %tripled = call i32 %triple(i32 2)
+  %sum = call i32 @host_sum(i32 4)
+  %from_app = call i32 @host_call_app(i32 5)
+  %a = add i32 %number, %tripled
+  %b = add i32 %a, %sum
+  %c = add i32 %b, %from_app
+  ret i32 %c
+}
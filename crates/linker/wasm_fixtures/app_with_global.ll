@@ -0,0 +1,15 @@
+; An app which uses a wasm global other than the stack pointer, which surgery can't link.
+; Regenerate app_with_global.o after changing this with
+;
+;     llc -O0 -mtriple=wasm32-unknown-unknown -filetype=obj app_with_global.ll -o app_with_global.o
+
+target datalayout = "e-m:e-p:32:32-p10:8:8-p20:8:8-i64:64-n32:64-S128-ni:1:10:20"
+target triple = "wasm32-unknown-unknown"
+
+@app_counter = external addrspace(1) global i32
+
+define i32 @roc__run() {
+entry:
+  %count = load i32, i32 addrspace(1)* @app_counter
+  ret i32 %count
+}
@@ -0,0 +1,53 @@
+; A tiny wasm host for testing surgery. Regenerate host.o after changing this with
+;
+;     llc -O0 -mtriple=wasm32-unknown-unknown -filetype=obj host.ll -o host.o
+
+target datalayout = "e-m:e-p:32:32-p10:8:8-p20:8:8-i64:64-n32:64-S128-ni:1:10:20"
+target triple = "wasm32-unknown-unknown"
+
+@host_numbers = global [4 x i32] [i32 1, i32 2, i32 3, i32 4], align 4
+
+; The app's function, which surgery puts in place of this import
+declare i32 @roc__app_main(i32)
+
+; Adds up the first `count` of the host's numbers, for the app to call
+define i32 @host_sum(i32 %count) {
+entry:
+  %total = alloca i32, align 4
+  %i = alloca i32, align 4
+  store i32 0, i32* %total, align 4
+  store i32 0, i32* %i, align 4
+  br label %loop
+
+loop:
+  %index = load i32, i32* %i, align 4
+  %done = icmp sge i32 %index, %count
+  br i1 %done, label %exit, label %body
+
+body:
+  %ptr = getelementptr [4 x i32], [4 x i32]* @host_numbers, i32 0, i32 %index
+  %number = load i32, i32* %ptr, align 4
+  %old_total = load i32, i32* %total, align 4
+  %new_total = add i32 %old_total, %number
+  store i32 %new_total, i32* %total, align 4
+  %next = add i32 %index, 1
+  store i32 %next, i32* %i, align 4
+  br label %loop
+
+exit:
+  %result = load i32, i32* %total, align 4
+  ret i32 %result
+}
+
+; Calls back into the app, for the app to call
+define i32 @host_call_app(i32 %arg) {
+entry:
+  %result = call i32 @roc__app_main(i32 %arg)
+  ret i32 %result
+}
+
+define i32 @main(i32 %argc, i8** %argv) {
+entry:
+  %result = call i32 @host_call_app(i32 %argc)
+  ret i32 %result
+}
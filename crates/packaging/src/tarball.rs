@@ -154,6 +154,8 @@ fn write_archive<W: Write>(path: &Path, writer: W) -> io::Result<()> {
                     Some("rh1"),
                     // metadata file
                     Some("rm2"),
+                    // hosted functions to inline into the app
+                    Some("bc"),
                     // legacy linker formats
                     Some("o"),
                     Some("obj"),
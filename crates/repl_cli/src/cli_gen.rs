@@ -9,9 +9,11 @@ use roc_gen_llvm::{run_jit_function, run_jit_function_dynamic_type};
 use roc_load::{EntryPoint, MonomorphizedModule};
 use roc_mono::ir::OptLevel;
 use roc_mono::layout::STLayoutInterner;
+use roc_mono::thread_escape::ThreadSharedLayouts;
 use roc_parse::ast::Expr;
 use roc_repl_eval::eval::jit_to_ast;
 use roc_repl_eval::gen::{compile_to_mono, format_answer, Problems, ReplOutput};
+use roc_repl_eval::interp::{format_interp_error, interp_to_ast};
 use roc_repl_eval::{ReplApp, ReplAppMemory};
 use roc_reporting::report::DEFAULT_PALETTE;
 use roc_std::RocStr;
@@ -95,6 +97,90 @@ pub fn gen_and_eval_llvm<'a, I: Iterator<Item = &'a str>>(
     )
 }
 
+/// Like [gen_and_eval_llvm], but runs the expression with the mono interpreter instead of
+/// compiling it.
+pub fn gen_and_eval_interp<'a, I: Iterator<Item = &'a str>>(
+    defs: I,
+    src: &str,
+    target: Triple,
+) -> (Option<ReplOutput>, Problems) {
+    let arena = Bump::new();
+    let target_info = TargetInfo::from(&target);
+
+    let (mut loaded, mut problems) =
+        match compile_to_mono(&arena, defs, src, target_info, DEFAULT_PALETTE) {
+            (Some(mono), probs) => (mono, probs),
+            (None, probs) => return (None, probs),
+        };
+
+    debug_assert_eq!(loaded.exposed_to_host.values.len(), 1);
+    let (main_fn_symbol, main_fn_var) = loaded.exposed_to_host.values.iter().next().unwrap();
+    let main_fn_symbol = *main_fn_symbol;
+    let main_fn_var = *main_fn_var;
+
+    let expr_type_str = name_and_print_var(
+        main_fn_var,
+        &mut loaded.subs,
+        loaded.module_id,
+        &loaded.interns,
+        DebugPrint::NOTHING,
+    );
+
+    if !loaded.procedures.keys().any(|(s, _)| *s == main_fn_symbol) {
+        debug_assert!(
+            !problems.errors.is_empty(),
+            "Got no errors but also no valid layout for the generated main function in the repl!"
+        );
+
+        return (None, problems);
+    }
+
+    match interp_to_ast(&arena, loaded, target_info) {
+        Ok(expr) => (
+            Some(ReplOutput {
+                expr: format_answer(&arena, expr).to_string(),
+                expr_type: expr_type_str,
+            }),
+            problems,
+        ),
+        Err(error) => {
+            problems.errors.push(format_interp_error(&error));
+
+            (None, problems)
+        }
+    }
+}
+
+/// Compiles `src` like [gen_and_eval_llvm] would, but stops before code generation and only
+/// returns the type of the expression.
+pub fn gen_type_of<'a, I: Iterator<Item = &'a str>>(
+    defs: I,
+    src: &str,
+    target: Triple,
+) -> (Option<String>, Problems) {
+    let arena = Bump::new();
+    let target_info = TargetInfo::from(&target);
+
+    let (mut loaded, problems) =
+        match compile_to_mono(&arena, defs, src, target_info, DEFAULT_PALETTE) {
+            (Some(mono), probs) => (mono, probs),
+            (None, probs) => return (None, probs),
+        };
+
+    debug_assert_eq!(loaded.exposed_to_host.values.len(), 1);
+    let main_fn_var = *loaded.exposed_to_host.values.values().next().unwrap();
+
+    let expr_type_str = name_and_print_var(
+        main_fn_var,
+        &mut loaded.subs,
+        loaded.module_id,
+        &loaded.interns,
+        DebugPrint::NOTHING,
+    );
+
+    (Some(expr_type_str), problems)
+}
+
 struct CliApp {
     lib: Library,
 }
@@ -217,6 +303,13 @@ fn mono_module_to_dylib<'a>(
         mode: LlvmBackendMode::GenTest, // so roc_panic is generated
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
+        thread_shared_layouts: ThreadSharedLayouts::from_procs(
+            &layout_interner,
+            procedures.values(),
+        ),
+        fixtures: MutSet::default(),
+        batch_effects: false,
+        fast_math: false,
     };
 
     // Add roc_alloc, roc_realloc, and roc_dealloc, since the repl has no
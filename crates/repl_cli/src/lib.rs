@@ -25,7 +25,9 @@ pub const WELCOME_MESSAGE: &str = concatcp!(
 // TODO add link to repl tutorial(does not yet exist).
 pub const SHORT_INSTRUCTIONS: &str = "Enter an expression, or :help, or :q to quit.\n\n";
 
-pub fn main() -> i32 {
+/// Runs the REPL. With `use_interp`, expressions run in the mono interpreter instead of being
+/// compiled with LLVM.
+pub fn main(use_interp: bool) -> i32 {
     use rustyline::error::ReadlineError;
     use rustyline::Editor;
 
@@ -35,7 +37,11 @@ pub fn main() -> i32 {
     print!("{}{}", WELCOME_MESSAGE, SHORT_INSTRUCTIONS);
 
     let mut editor = Editor::<ReplState>::new();
-    let repl_helper = ReplState::new();
+    let repl_helper = if use_interp {
+        ReplState::new_interp()
+    } else {
+        ReplState::new()
+    };
     editor.set_helper(Some(repl_helper));
 
     loop {
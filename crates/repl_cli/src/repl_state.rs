@@ -1,8 +1,9 @@
-use crate::cli_gen::gen_and_eval_llvm;
+use crate::cli_gen::{gen_and_eval_interp, gen_and_eval_llvm, gen_type_of};
 use crate::colors::{BLUE, END_COL, GREEN, PINK};
 use bumpalo::Bump;
 use const_format::concatcp;
-use roc_collections::MutSet;
+use roc_collections::{MutMap, MutSet};
+use roc_load::LoadedModule;
 use roc_mono::ir::OptLevel;
 use roc_parse::ast::{Expr, Pattern, TypeDef, TypeHeader, ValueDef};
 use roc_parse::expr::{parse_single_def, ExprParseOptions, SingleDef};
@@ -12,11 +13,16 @@ use roc_parse::parser::{EWhen, Either};
 use roc_parse::state::State;
 use roc_parse::{join_alias_to_body, join_ann_to_body};
 use roc_region::all::Loc;
-use roc_repl_eval::gen::{Problems, ReplOutput};
+use roc_repl_eval::gen::{compile_to_typechecked, Problems, ReplOutput};
+use roc_target::TargetInfo;
+use roc_types::subs::{Content, FlatType, Subs, Variable};
+use roc_types::types::AliasKind;
+use rustyline::completion::Completer;
 use rustyline::highlight::{Highlighter, PromptInfo};
 use rustyline::validate::{self, ValidationContext, ValidationResult, Validator};
-use rustyline_derive::{Completer, Helper, Hinter};
+use rustyline_derive::{Helper, Hinter};
 use std::borrow::Cow;
+use std::cell::RefCell;
 use target_lexicon::Triple;
 
 pub const PROMPT: &str = concatcp!(BLUE, "»", END_COL, " ");
@@ -26,6 +32,26 @@ pub const CONT_PROMPT: &str = concatcp!(BLUE, "…", END_COL, " ");
 /// e.g. if the prefix is "val" then the first expr you enter will be named "val1"
 pub const AUTO_VAR_PREFIX: &str = "val";
 
+/// The command that prints the type of an expression without evaluating it, e.g. `:type 1 + 2`
+pub const TYPE_COMMAND: &str = ":type";
+
+/// What we print for a `bench` block, since only `roc bench` can run and time those
+pub const BENCH_UNSUPPORTED: &str = concatcp!(
+    "\nThe REPL can't run ",
+    BLUE,
+    "bench",
+    END_COL,
+    " blocks. Put this one in a .roc file and run ",
+    BLUE,
+    "roc bench",
+    END_COL,
+    " on that file instead.\n"
+);
+
+const KEYWORDS: &[&str] = &[
+    "if", "then", "else", "when", "is", "as", "expect", "dbg", "crash", "bench", "return",
+];
+
 // TODO add link to repl tutorial(does not yet exist).
 pub const TIPS: &str = concatcp!(
     "\nEnter an expression to evaluate, or a definition (like ",
@@ -55,6 +81,17 @@ pub const TIPS: &str = concatcp!(
     BLUE,
     "  - ",
     END_COL,
+    PINK,
+    "tab",
+    END_COL,
+    " completes the names of past definitions and builtin modules\n\n",
+    BLUE,
+    "  - ",
+    END_COL,
+    ":type <expr> shows the type of an expression without evaluating it\n\n",
+    BLUE,
+    "  - ",
+    END_COL,
     ":q to quit\n\n",
     BLUE,
     "  - ",
@@ -68,12 +105,25 @@ struct PastDef {
     src: String,
 }
 
-#[derive(Completer, Helper, Hinter)]
+/// What tab completion has learned by type-checking the past defs. It's kept until the past defs
+/// change, so that pressing Tab again doesn't type-check them all over again.
+#[derive(Default)]
+struct CompletionCache {
+    /// The modules the REPL imports, and the values each of them exposes
+    modules: Option<MutMap<String, Vec<String>>>,
+    /// The field names of record expressions, by their source
+    record_fields: MutMap<String, Vec<String>>,
+}
+
+#[derive(Helper, Hinter)]
 pub struct ReplState {
     validator: InputValidator,
     past_defs: Vec<PastDef>,
     past_def_idents: MutSet<String>,
     last_auto_ident: u64,
+    /// Run expressions with the mono interpreter instead of compiling them with LLVM
+    use_interp: bool,
+    completion_cache: RefCell<CompletionCache>,
 }
 
 impl Default for ReplState {
@@ -89,6 +139,16 @@ impl ReplState {
             past_defs: Default::default(),
             past_def_idents: Default::default(),
             last_auto_ident: 0,
+            use_interp: false,
+            completion_cache: Default::default(),
+        }
+    }
+
+    /// A REPL that runs expressions with the mono interpreter, like `roc repl --interp`
+    pub fn new_interp() -> Self {
+        Self {
+            use_interp: true,
+            ..Self::new()
         }
     }
 
@@ -124,10 +184,156 @@ impl ReplState {
                 // TODO add link to repl tutorial(does not yet exist).
                 Ok(TIPS.to_string())
             }
+            ParseOutcome::TypeOf(expr) => Ok(self.type_and_format(expr)),
             ParseOutcome::Exit => Err(0),
         }
     }
 
+    /// Type-checks `src` in the context of the past defs, and formats its type, without
+    /// evaluating it or adding it to the past defs.
+    pub fn type_and_format(&self, src: &str) -> String {
+        let (opt_type, problems) = gen_type_of(
+            self.past_defs.iter().map(|def| def.src.as_str()),
+            src,
+            Triple::host(),
+        );
+
+        let output = opt_type.map(|expr_type| ReplOutput {
+            expr: src.trim().to_string(),
+            expr_type,
+        });
+
+        format_output(output, problems, None, None)
+    }
+
+    /// The candidates for completing the word that ends at `pos` in `line`, along with the
+    /// position that word starts at.
+    ///
+    /// Record fields and module members (after a `.`) and module names come from type-checking
+    /// the past defs; other names are the past defs themselves and keywords.
+    pub fn completion_candidates(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+        let start = word_start(&line[..pos], false);
+        let word = &line[start..pos];
+
+        let mut candidates = match line[..start].strip_suffix('.') {
+            Some(before_dot) => {
+                let qualifier = &before_dot[word_start(before_dot, true)..];
+
+                self.member_candidates(qualifier)
+            }
+            None if word.is_empty() => Vec::new(),
+            None if word.starts_with(char::is_uppercase) => self.module_name_candidates(),
+            None => self
+                .past_def_idents
+                .iter()
+                .map(|ident| ident.as_str())
+                .chain(KEYWORDS.iter().copied())
+                .map(|name| name.to_string())
+                .collect(),
+        };
+
+        candidates.retain(|name| name.starts_with(word));
+        candidates.sort();
+        candidates.dedup();
+
+        (start, candidates)
+    }
+
+    /// The names of the modules the REPL imports
+    fn module_name_candidates(&self) -> Vec<String> {
+        self.with_modules(|modules| modules.keys().cloned().collect())
+    }
+
+    /// The values `qualifier` exposes if it's a module, or else its fields if it's a record.
+    fn member_candidates(&self, qualifier: &str) -> Vec<String> {
+        if qualifier.is_empty() {
+            return Vec::new();
+        }
+
+        let is_module_name = qualifier.starts_with(char::is_uppercase) && !qualifier.contains('.');
+
+        if is_module_name {
+            self.with_modules(|modules| modules.get(qualifier).cloned().unwrap_or_default())
+        } else {
+            if let Some(fields) = self.completion_cache.borrow().record_fields.get(qualifier) {
+                return fields.clone();
+            }
+
+            let fields = match self.typecheck(qualifier) {
+                Some(loaded) => match loaded.exposed_to_host.values().next() {
+                    Some(var) => record_field_names(loaded.solved.inner(), *var),
+                    None => Vec::new(),
+                },
+                None => Vec::new(),
+            };
+
+            self.completion_cache
+                .borrow_mut()
+                .record_fields
+                .insert(qualifier.to_string(), fields.clone());
+
+            fields
+        }
+    }
+
+    /// Calls `f` with the modules the REPL imports, along with the values each of them exposes,
+    /// type-checking the past defs to find them if they aren't cached yet.
+    fn with_modules<T>(&self, f: impl FnOnce(&MutMap<String, Vec<String>>) -> T) -> T {
+        if let Some(modules) = &self.completion_cache.borrow().modules {
+            return f(modules);
+        }
+
+        let modules = self.imported_modules();
+        let result = f(&modules);
+
+        self.completion_cache.borrow_mut().modules = Some(modules);
+
+        result
+    }
+
+    fn imported_modules(&self) -> MutMap<String, Vec<String>> {
+        let loaded = match self.typecheck("{}") {
+            Some(loaded) => loaded,
+            None => return MutMap::default(),
+        };
+
+        loaded
+            .dep_idents
+            .keys()
+            .filter(|module_id| **module_id != loaded.module_id)
+            .map(|module_id| {
+                let exposed_values = loaded
+                    .exposed_symbols_by_module
+                    .get(module_id)
+                    .map(|symbols| {
+                        symbols
+                            .iter()
+                            .map(|symbol| symbol.as_str(&loaded.interns))
+                            .filter(|ident| ident.starts_with(char::is_lowercase))
+                            .map(|ident| ident.to_string())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                (
+                    loaded.interns.module_name(*module_id).to_string(),
+                    exposed_values,
+                )
+            })
+            .collect()
+    }
+
+    fn typecheck(&self, src: &str) -> Option<LoadedModule> {
+        let arena = Bump::new();
+
+        compile_to_typechecked(
+            &arena,
+            self.past_defs.iter().map(|def| def.src.as_str()),
+            src,
+            TargetInfo::from(&Triple::host()),
+        )
+    }
+
     pub fn eval_and_format(&mut self, src: &str, dimensions: Option<(usize, usize)>) -> String {
         let arena = Bump::new();
         let pending_past_def;
@@ -205,6 +411,9 @@ impl ReplState {
                     ValueDef::ExpectFx { .. } => {
                         todo!("handle receiving an `expect-fx` - what should the repl do for that?")
                     }
+                    ValueDef::Bench { .. } => {
+                        return BENCH_UNSUPPORTED.to_string();
+                    }
                 }
             }
             ParseOutcome::TypeDef(TypeDef::Alias {
@@ -230,6 +439,14 @@ impl ReplState {
                         ..
                     },
                 ..
+            })
+            | ParseOutcome::TypeDef(TypeDef::PatternSynonym {
+                header:
+                    TypeHeader {
+                        name: Loc { value: ident, .. },
+                        ..
+                    },
+                ..
             }) => {
                 // Record the type for future use.
                 self.add_past_def(ident.trim_end().to_string(), src.to_string());
@@ -238,7 +455,10 @@ impl ReplState {
                 // can be evaluated as expressions.
                 return String::new();
             }
-            ParseOutcome::Empty | ParseOutcome::Help | ParseOutcome::Exit => unreachable!(),
+            ParseOutcome::Empty
+            | ParseOutcome::Help
+            | ParseOutcome::TypeOf(_)
+            | ParseOutcome::Exit => unreachable!(),
         };
 
         // Record e.g. "val1" as a past def, unless our input was exactly the name of
@@ -249,20 +469,10 @@ impl ReplState {
                 Some(existing_ident) => {
                     opt_var_name = Some(existing_ident);
 
-                    gen_and_eval_llvm(
-                        self.past_defs.iter().map(|def| def.src.as_str()),
-                        src,
-                        Triple::host(),
-                        OptLevel::Normal,
-                    )
+                    self.gen_and_eval(src)
                 }
                 None => {
-                    let (output, problems) = gen_and_eval_llvm(
-                        self.past_defs.iter().map(|def| def.src.as_str()),
-                        src,
-                        Triple::host(),
-                        OptLevel::Normal,
-                    );
+                    let (output, problems) = self.gen_and_eval(src);
 
                     // Don't persist defs that have compile errors
                     if problems.errors.is_empty() {
@@ -287,12 +497,25 @@ impl ReplState {
         format_output(output, problems, opt_var_name, dimensions)
     }
 
+    fn gen_and_eval(&self, src: &str) -> (Option<ReplOutput>, Problems) {
+        let defs = self.past_defs.iter().map(|def| def.src.as_str());
+
+        if self.use_interp {
+            gen_and_eval_interp(defs, src, Triple::host())
+        } else {
+            gen_and_eval_llvm(defs, src, Triple::host(), OptLevel::Normal)
+        }
+    }
+
     fn next_auto_ident(&mut self) -> u64 {
         self.last_auto_ident += 1;
         self.last_auto_ident
     }
 
     fn add_past_def(&mut self, ident: String, src: String) {
+        // what's in scope has changed, so completions need to type-check the past defs again
+        *self.completion_cache.get_mut() = CompletionCache::default();
+
         let existing_idents = &mut self.past_def_idents;
 
         existing_idents.insert(ident.clone());
@@ -310,10 +533,17 @@ enum ParseOutcome<'a> {
     SyntaxErr,
     Empty,
     Help,
+    TypeOf(&'a str),
     Exit,
 }
 
 fn parse_src<'a>(arena: &'a Bump, line: &'a str) -> ParseOutcome<'a> {
+    if let Some(expr) = line.trim_start().strip_prefix(TYPE_COMMAND) {
+        if expr.is_empty() || expr.starts_with(char::is_whitespace) {
+            return ParseOutcome::TypeOf(expr);
+        }
+    }
+
     match line.trim().to_lowercase().as_str() {
         "" => ParseOutcome::Empty,
         ":help" => ParseOutcome::Help,
@@ -321,6 +551,27 @@ fn parse_src<'a>(arena: &'a Bump, line: &'a str) -> ParseOutcome<'a> {
         _ => {
             let src_bytes = line.as_bytes();
 
+            // `bench` is only special at the start of a def, so `bench x` would parse as a call
+            if let Ok((
+                _,
+                Some(SingleDef {
+                    type_or_value: Either::Second(value_def @ ValueDef::Bench { .. }),
+                    ..
+                }),
+                _,
+            )) = parse_single_def(
+                ExprParseOptions {
+                    accept_multi_backpassing: true,
+                    check_for_arrow: true,
+                },
+                true,
+                0,
+                arena,
+                State::new(src_bytes),
+            ) {
+                return ParseOutcome::ValueDef(value_def);
+            }
+
             match roc_parse::expr::loc_expr(true).parse(arena, State::new(src_bytes), 0) {
                 Ok((_, loc_expr, _)) => ParseOutcome::Expr(loc_expr.value),
                 // Special case some syntax errors to allow for multi-line inputs
@@ -336,6 +587,7 @@ fn parse_src<'a>(arena: &'a Bump, line: &'a str) -> ParseOutcome<'a> {
                             accept_multi_backpassing: true,
                             check_for_arrow: true,
                         },
+                        true,
                         0,
                         arena,
                         State::new(src_bytes),
@@ -360,6 +612,7 @@ fn parse_src<'a>(arena: &'a Bump, line: &'a str) -> ParseOutcome<'a> {
                                     accept_multi_backpassing: true,
                                     check_for_arrow: true,
                                 },
+                                true,
                                 0,
                                 arena,
                                 state,
@@ -408,6 +661,7 @@ fn parse_src<'a>(arena: &'a Bump, line: &'a str) -> ParseOutcome<'a> {
                                     accept_multi_backpassing: true,
                                     check_for_arrow: true,
                                 },
+                                true,
                                 0,
                                 arena,
                                 state,
@@ -509,16 +763,158 @@ pub fn is_incomplete(input: &str) -> bool {
             // them until they enter a blank line!
             !input.ends_with('\n')
         }
+        // Many syntax errors are just the input running out halfway through an expression, e.g.
+        // because the user pressed Enter after an opening paren. Ask for more input in that
+        // case; a blank line still submits the input as-is, so the error can be reported.
+        ParseOutcome::SyntaxErr => !input.ends_with('\n') && looks_unfinished(input),
         ParseOutcome::Empty
         | ParseOutcome::Help
+        | ParseOutcome::TypeOf(_)
         | ParseOutcome::Exit
         | ParseOutcome::ValueDef(_)
         | ParseOutcome::TypeDef(_)
-        | ParseOutcome::SyntaxErr
         | ParseOutcome::Expr(_) => false,
     }
 }
 
+/// Tokens that can't end an expression, so a line ending in one of them continues on the next.
+const CONTINUATION_TOKENS: &[&str] = &[
+    "=", ":", "->", "<-", "\\", ",", "|>", "&", "+", "-", "*", "/", "//", "%", "^", "==", "!=",
+    "<", ">", "<=", ">=", "&&", "||", "then", "else", "is",
+];
+
+/// Whether `src` stops in the middle of an expression: a bracket or an `if` is still open, a
+/// multi-line string is unterminated, or the last token is one that needs something after it.
+///
+/// The parser doesn't tell us whether it failed because it ran out of input, so this takes a
+/// rough look at the tokens itself.
+fn looks_unfinished(src: &str) -> bool {
+    let bytes = src.as_bytes();
+    let mut open_delimiters = 0;
+    let mut open_ifs = 0;
+    let mut last_token = "";
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let start = index;
+
+        match bytes[index] {
+            b'#' => {
+                while index < bytes.len() && bytes[index] != b'\n' {
+                    index += 1;
+                }
+
+                continue;
+            }
+            b'"' if src[index..].starts_with("\"\"\"") => {
+                match src[index + 3..].find("\"\"\"") {
+                    Some(end) => index += 3 + end + 3,
+                    None => return true,
+                }
+
+                last_token = "\"";
+                continue;
+            }
+            b'"' | b'\'' => {
+                let quote = bytes[index];
+                index += 1;
+
+                while index < bytes.len() && bytes[index] != quote && bytes[index] != b'\n' {
+                    if bytes[index] == b'\\' {
+                        index += 1;
+                    }
+
+                    index += 1;
+                }
+
+                index += 1;
+                last_token = "\"";
+                continue;
+            }
+            b'(' | b'[' | b'{' => {
+                open_delimiters += 1;
+                index += 1;
+            }
+            b')' | b']' | b'}' => {
+                open_delimiters -= 1;
+                index += 1;
+            }
+            byte if byte.is_ascii_whitespace() => {
+                index += 1;
+                continue;
+            }
+            byte if byte.is_ascii_alphanumeric() || byte == b'_' || !byte.is_ascii() => {
+                while index < bytes.len()
+                    && (bytes[index].is_ascii_alphanumeric()
+                        || bytes[index] == b'_'
+                        || !bytes[index].is_ascii())
+                {
+                    index += 1;
+                }
+
+                match &src[start..index] {
+                    "if" => open_ifs += 1,
+                    "else" => open_ifs -= 1,
+                    _ => {}
+                }
+            }
+            _ => {
+                index += 1;
+
+                while index < bytes.len()
+                    && bytes[index].is_ascii_punctuation()
+                    && !b"()[]{}\"'#_".contains(&bytes[index])
+                {
+                    index += 1;
+                }
+            }
+        }
+
+        last_token = &src[start..index];
+    }
+
+    open_delimiters > 0 || open_ifs > 0 || CONTINUATION_TOKENS.contains(&last_token)
+}
+
+/// Where the word that ends at the end of `src` starts. With `allow_dots`, that word can be
+/// a qualified name or a chain of record field accesses, like `rec.a.b`.
+fn word_start(src: &str, allow_dots: bool) -> usize {
+    src.char_indices()
+        .rev()
+        .find(|(_, c)| !(c.is_alphanumeric() || *c == '_' || (allow_dots && *c == '.')))
+        .map(|(index, c)| index + c.len_utf8())
+        .unwrap_or(0)
+}
+
+/// The field names of `var`, if it's a record (or an alias of one)
+fn record_field_names(subs: &Subs, mut var: Variable) -> Vec<String> {
+    loop {
+        match subs.get_content_without_compacting(var) {
+            Content::Alias(_, _, real_var, AliasKind::Structural) => var = *real_var,
+            Content::Structure(FlatType::Record(fields, ext)) => {
+                return match fields.unsorted_iterator(subs, *ext) {
+                    Ok(fields) => fields.map(|(name, _)| name.to_string()).collect(),
+                    Err(_) => Vec::new(),
+                };
+            }
+            _ => return Vec::new(),
+        }
+    }
+}
+
+impl Completer for ReplState {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &rustyline::Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.completion_candidates(line, pos))
+    }
+}
+
 impl Highlighter for ReplState {
     fn has_continuation_prompt(&self) -> bool {
         true
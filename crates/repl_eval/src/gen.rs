@@ -1,8 +1,8 @@
 use bumpalo::Bump;
-use roc_load::{ExecutionMode, LoadConfig, LoadMonomorphizedError, Threading};
+use roc_load::{ExecutionMode, LoadConfig, LoadMonomorphizedError, LoadedModule, Threading};
 use roc_packaging::cache::{self, RocCacheDir};
 use roc_problem::Severity;
-use roc_reporting::report::Palette;
+use roc_reporting::report::{Palette, DEFAULT_PALETTE};
 use std::path::PathBuf;
 
 use roc_fmt::annotation::Formattable;
@@ -50,10 +50,41 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
     expr: &str,
     target_info: TargetInfo,
     palette: Palette,
+) -> (Option<MonomorphizedModule<'a>>, Problems) {
+    let (bytes_before_expr, module_src) = promote_expr_to_module(arena, defs, expr);
+
+    load_and_report(arena, module_src, bytes_before_expr, target_info, palette)
+}
+
+/// Compiles `def` in the context of `defs`, to check it before it becomes a def itself.
+/// Unlike `compile_to_mono`, this reports the problems in `def` rather than filtering them out.
+pub fn compile_def_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
+    arena: &'a Bump,
+    defs: I,
+    def: &'i str,
+    target_info: TargetInfo,
+    palette: Palette,
+) -> (Option<MonomorphizedModule<'a>>, Problems) {
+    let (bytes_before_expr, module_src) =
+        promote_expr_to_module(arena, defs.chain(std::iter::once(def)), "{}");
+
+    // The new def comes right before the main def
+    let bytes_before_def = bytes_before_expr - REPL_MODULE_MAIN_DEF.len() - def.len() - 2;
+
+    load_and_report(arena, module_src, bytes_before_def, target_info, palette)
+}
+
+/// Reports the problems whose regions end after `bytes_before_reported`; the ones before that
+/// are in past defs, which were already reported.
+fn load_and_report<'a>(
+    arena: &'a Bump,
+    module_src: &'a str,
+    bytes_before_reported: usize,
+    target_info: TargetInfo,
+    palette: Palette,
 ) -> (Option<MonomorphizedModule<'a>>, Problems) {
     let filename = PathBuf::from("");
     let src_dir = PathBuf::from("fake/test/path");
-    let (bytes_before_expr, module_src) = promote_expr_to_module(arena, defs, expr);
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
         filename,
@@ -66,6 +97,7 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
             palette,
             threading: Threading::Single,
             exec_mode: ExecutionMode::Executable,
+            ..Default::default()
         },
     );
 
@@ -77,7 +109,7 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
                 (m.can_problems, m.type_problems)
             );
         }
-        Err(LoadMonomorphizedError::LoadingProblem(LoadingProblem::FormattedReport(report))) => {
+        Err(LoadMonomorphizedError::LoadingProblem(LoadingProblem::FormattedReport(report, _))) => {
             return (
                 None,
                 Problems {
@@ -125,7 +157,7 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
             // because they must be part of the defs (excluding the most renently added def,
             // if that's the one being evaluated) and therefore not things we should show.
             // This filters out things like shadowing warnings and unused def warnings.
-            if problem.region().unwrap_or_default().end().offset as usize >= bytes_before_expr {
+            if problem.region().unwrap_or_default().end().offset as usize >= bytes_before_reported {
                 let report = can_problem(&alloc, &line_info, module_path.clone(), problem);
                 let severity = report.severity;
                 let mut buf = String::new();
@@ -165,13 +197,37 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
     (Some(loaded), problems)
 }
 
+/// Type-checks `expr` in the context of `defs`, without reporting any problems. This is for
+/// things like tab completion, which only want to look at the types and names in scope.
+pub fn compile_to_typechecked<'a, 'i, I: Iterator<Item = &'i str>>(
+    arena: &'a Bump,
+    defs: I,
+    expr: &str,
+    target_info: TargetInfo,
+) -> Option<LoadedModule> {
+    let (_, module_src) = promote_expr_to_module(arena, defs, expr);
+
+    roc_load::load_and_typecheck_str(
+        arena,
+        PathBuf::from(""),
+        module_src,
+        PathBuf::from("fake/test/path"),
+        target_info,
+        roc_reporting::report::RenderTarget::Generic,
+        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+        DEFAULT_PALETTE,
+    )
+    .ok()
+}
+
+const REPL_MODULE_MAIN_DEF: &str = "replOutput =\n";
+
 fn promote_expr_to_module<'a, 'i, I: Iterator<Item = &'i str>>(
     arena: &'a Bump,
     defs: I,
     expr: &str,
 ) -> (usize, &'a str) {
     const REPL_MODULE_HEADER: &str = "app \"app\" provides [replOutput] to \"./platform\"\n\n";
-    const REPL_MODULE_MAIN_DEF: &str = "replOutput =\n";
     const INDENT: &str = "    ";
 
     let mut buffer = bumpalo::collections::string::String::from_str_in(REPL_MODULE_HEADER, arena);
@@ -0,0 +1,493 @@
+//! Evaluates the REPL's expressions with the mono [interpreter][roc_mono::interp] instead of
+//! compiling them.
+//!
+//! The interpreter's [Value]s are written into a byte buffer in the same memory layout compiled
+//! code would use, so [jit_to_ast] can read them back like it reads the result of a real app.
+use bumpalo::Bump;
+use roc_builtins::bitcode::FloatWidth;
+use roc_load::{EntryPoint, MonomorphizedModule};
+use roc_mono::interp::{InterpError, Interpreter, Value};
+use roc_mono::layout::{
+    Builtin, Discriminant, InLayout, Layout, LayoutInterner, STLayoutInterner, UnionLayout,
+};
+use roc_parse::ast::Expr;
+use roc_target::{PtrWidth, TargetInfo};
+
+use crate::eval::jit_to_ast;
+use crate::{ReplApp, ReplAppMemory};
+
+/// Allocations are aligned to this, which also leaves the low bits of every pointer free for
+/// recursive unions that keep their tag id there.
+const ALLOCATION_ALIGNMENT: usize = 8;
+
+/// Interprets the main function of `loaded` and turns its result into an [Expr], like
+/// [jit_to_ast] does for compiled code. Functions are shown as `<function>` without running
+/// anything.
+///
+/// `target_info` has to be the host's, since some results are read back as Rust values.
+pub fn interp_to_ast<'a>(
+    arena: &'a Bump,
+    loaded: MonomorphizedModule<'a>,
+    target_info: TargetInfo,
+) -> Result<Expr<'a>, InterpError> {
+    let MonomorphizedModule {
+        procedures,
+        entry_point,
+        exposed_to_host,
+        interns,
+        subs,
+        layout_interner,
+        ..
+    } = loaded;
+
+    let (main_fn_symbol, main_fn_layout) = match entry_point {
+        EntryPoint::Executable {
+            exposed_to_host, ..
+        } => {
+            debug_assert_eq!(exposed_to_host.len(), 1);
+            exposed_to_host[0]
+        }
+        EntryPoint::Test => unreachable!(),
+    };
+
+    let main_fn_var = exposed_to_host.values[&main_fn_symbol];
+
+    let mut builder = MemoryBuilder::new(target_info);
+    let mut result_addr = 0;
+
+    // Only thunks get run; for anything else, `jit_to_ast` never calls the app.
+    if main_fn_layout.arguments.is_empty() {
+        let proc = &procedures[&(main_fn_symbol, main_fn_layout)];
+        let value =
+            Interpreter::new(arena, &layout_interner, &procedures).eval_proc(proc, vec![])?;
+
+        result_addr = builder.alloc(layout_interner.stack_size(main_fn_layout.result) as usize);
+        builder.write_value(
+            &layout_interner,
+            result_addr,
+            &value,
+            main_fn_layout.result,
+            None,
+        );
+    }
+
+    let mut app = InterpApp {
+        memory: arena.alloc(InterpMemory {
+            bytes: arena.alloc_slice_copy(&builder.bytes),
+            target_info,
+        }),
+        result_addr,
+    };
+
+    Ok(jit_to_ast(
+        arena,
+        &mut app,
+        "",
+        main_fn_layout,
+        main_fn_var,
+        &subs,
+        arena.alloc(interns),
+        layout_interner.into_global().fork(),
+        target_info,
+    ))
+}
+
+/// Renders an [InterpError] the way a crash or missing feature is reported to the user.
+pub fn format_interp_error(error: &InterpError) -> String {
+    match error {
+        InterpError::Crash(message) => format!("This Roc code crashed with: \"{message}\""),
+        InterpError::Unsupported(what) => {
+            format!("The interpreter doesn't support {what} yet. Try again without --interp.")
+        }
+    }
+}
+
+struct InterpApp<'a> {
+    memory: &'a InterpMemory<'a>,
+    result_addr: usize,
+}
+
+impl<'a> ReplApp<'a> for InterpApp<'a> {
+    type Memory = InterpMemory<'a>;
+
+    fn call_function<Return, F>(&mut self, _main_fn_name: &str, mut transform: F) -> Expr<'a>
+    where
+        F: FnMut(&'a Self::Memory, Return) -> Expr<'a>,
+        Self::Memory: 'a,
+    {
+        let bytes = &self.memory.bytes[self.result_addr..];
+        assert!(bytes.len() >= std::mem::size_of::<Return>());
+
+        let result: Return = unsafe { (bytes.as_ptr() as *const Return).read_unaligned() };
+
+        transform(self.memory, result)
+    }
+
+    fn call_function_dynamic_size<T, F>(
+        &mut self,
+        _main_fn_name: &str,
+        _ret_bytes: usize,
+        mut transform: F,
+    ) -> T
+    where
+        F: FnMut(&'a Self::Memory, usize) -> T,
+        Self::Memory: 'a,
+    {
+        transform(self.memory, self.result_addr)
+    }
+}
+
+/// The interpreted value, laid out by a [MemoryBuilder]
+struct InterpMemory<'a> {
+    bytes: &'a [u8],
+    target_info: TargetInfo,
+}
+
+macro_rules! deref_number {
+    ($name: ident, $t: ty) => {
+        fn $name(&self, addr: usize) -> $t {
+            const N: usize = std::mem::size_of::<$t>();
+            let mut array = [0; N];
+            array.copy_from_slice(&self.bytes[addr..][..N]);
+            <$t>::from_le_bytes(array)
+        }
+    };
+}
+
+impl<'a> ReplAppMemory for InterpMemory<'a> {
+    fn deref_bool(&self, addr: usize) -> bool {
+        self.bytes[addr] != 0
+    }
+
+    deref_number!(deref_u8, u8);
+    deref_number!(deref_u16, u16);
+    deref_number!(deref_u32, u32);
+    deref_number!(deref_u64, u64);
+    deref_number!(deref_u128, u128);
+
+    fn deref_usize(&self, addr: usize) -> usize {
+        match self.target_info.ptr_width() {
+            PtrWidth::Bytes4 => self.deref_u32(addr) as usize,
+            PtrWidth::Bytes8 => self.deref_u64(addr) as usize,
+        }
+    }
+
+    deref_number!(deref_i8, i8);
+    deref_number!(deref_i16, i16);
+    deref_number!(deref_i32, i32);
+    deref_number!(deref_i64, i64);
+    deref_number!(deref_i128, i128);
+
+    fn deref_isize(&self, addr: usize) -> isize {
+        match self.target_info.ptr_width() {
+            PtrWidth::Bytes4 => self.deref_i32(addr) as isize,
+            PtrWidth::Bytes8 => self.deref_i64(addr) as isize,
+        }
+    }
+
+    deref_number!(deref_f32, f32);
+    deref_number!(deref_f64, f64);
+
+    fn deref_str(&self, addr: usize) -> &str {
+        // Strings are always written out as big strings.
+        let bytes_addr = self.deref_usize(addr);
+        let len = self.deref_usize(addr + self.target_info.ptr_size());
+
+        std::str::from_utf8(&self.bytes[bytes_addr..][..len]).unwrap()
+    }
+
+    fn deref_pointer_with_tag_id(&self, addr: usize) -> (u16, u64) {
+        let (tag_id_bits, _) = UnionLayout::tag_id_pointer_bits_and_mask(self.target_info);
+        let tag_id_mask = (1 << tag_id_bits) - 1;
+
+        let addr_with_id = self.deref_usize(addr);
+        let tag_id = addr_with_id & tag_id_mask;
+        let data_addr = addr_with_id & !tag_id_mask;
+
+        (tag_id as _, data_addr as _)
+    }
+}
+
+/// Writes interpreted values into memory. Numbers are little-endian, and pointers are
+/// `target_info.ptr_width()` bytes wide. Address 0 is never allocated, so it can be the null
+/// pointer of nullable unions.
+struct MemoryBuilder {
+    bytes: Vec<u8>,
+    target_info: TargetInfo,
+}
+
+impl MemoryBuilder {
+    fn new(target_info: TargetInfo) -> Self {
+        Self {
+            bytes: vec![0; ALLOCATION_ALIGNMENT],
+            target_info,
+        }
+    }
+
+    /// Allocates `size` zeroed bytes
+    fn alloc(&mut self, size: usize) -> usize {
+        let addr = self.bytes.len();
+        let end = addr + size.max(1);
+        let aligned_end =
+            (end + ALLOCATION_ALIGNMENT - 1) / ALLOCATION_ALIGNMENT * ALLOCATION_ALIGNMENT;
+
+        self.bytes.resize(aligned_end, 0);
+
+        addr
+    }
+
+    fn write_bytes(&mut self, addr: usize, bytes: &[u8]) {
+        self.bytes[addr..][..bytes.len()].copy_from_slice(bytes);
+    }
+
+    fn write_usize(&mut self, addr: usize, n: usize) {
+        match self.target_info.ptr_width() {
+            PtrWidth::Bytes4 => self.write_bytes(addr, &(n as u32).to_le_bytes()),
+            PtrWidth::Bytes8 => self.write_bytes(addr, &(n as u64).to_le_bytes()),
+        }
+    }
+
+    /// Writes `(ptr, len, capacity)`, the way both `Str` and `List` are laid out.
+    fn write_ptr_len_cap(&mut self, addr: usize, ptr: usize, len: usize) {
+        let ptr_size = self.target_info.ptr_size();
+
+        self.write_usize(addr, ptr);
+        self.write_usize(addr + ptr_size, len);
+        self.write_usize(addr + 2 * ptr_size, len);
+    }
+
+    /// Writes the fields one after another, the way [jit_to_ast] reads structs and tag payloads.
+    fn write_fields<'a>(
+        &mut self,
+        interner: &STLayoutInterner<'a>,
+        mut addr: usize,
+        fields: &[Value],
+        layouts: &[InLayout<'a>],
+        enclosing_union: Option<InLayout<'a>>,
+    ) {
+        debug_assert_eq!(fields.len(), layouts.len());
+
+        for (field, layout) in fields.iter().zip(layouts) {
+            self.write_value(interner, addr, field, *layout, enclosing_union);
+            addr += interner.stack_size(*layout) as usize;
+        }
+    }
+
+    fn write_tag_id(&mut self, addr: usize, union_layout: UnionLayout, tag_id: u16) {
+        match union_layout.discriminant() {
+            Discriminant::U0 => {}
+            Discriminant::U1 | Discriminant::U8 => self.write_bytes(addr, &[tag_id as u8]),
+            Discriminant::U16 => self.write_bytes(addr, &tag_id.to_le_bytes()),
+        }
+    }
+
+    /// Puts the payload of a recursive union's tag on the heap, and returns the pointer to it,
+    /// with the tag id in its low bits if that's where this union keeps it.
+    fn alloc_recursive_tag<'a>(
+        &mut self,
+        interner: &STLayoutInterner<'a>,
+        union_layout: UnionLayout<'a>,
+        layout: InLayout<'a>,
+        tag_id: u16,
+        fields: &[Value],
+        field_layouts: &[InLayout<'a>],
+    ) -> usize {
+        let (size, _) = union_layout.data_size_and_alignment(interner, self.target_info);
+        let data_addr = self.alloc(size as usize);
+
+        self.write_fields(interner, data_addr, fields, field_layouts, Some(layout));
+
+        if union_layout.stores_tag_id_in_pointer(self.target_info) {
+            data_addr | tag_id as usize
+        } else {
+            if let Some(offset) = union_layout.data_size_without_tag_id(interner, self.target_info)
+            {
+                self.write_tag_id(data_addr + offset as usize, union_layout, tag_id);
+            }
+
+            data_addr
+        }
+    }
+
+    fn write_value<'a>(
+        &mut self,
+        interner: &STLayoutInterner<'a>,
+        addr: usize,
+        value: &Value,
+        layout: InLayout<'a>,
+        enclosing_union: Option<InLayout<'a>>,
+    ) {
+        let int_bytes = |value: &Value| match value {
+            Value::Int(n) => n.to_le_bytes(),
+            Value::U128(n) => n.to_le_bytes(),
+            Value::Bool(b) => (*b as u128).to_le_bytes(),
+            other => unreachable!("expected an integer, got {:?}", other),
+        };
+
+        match interner.get(layout) {
+            Layout::Builtin(Builtin::Int(width)) => {
+                let size = width.stack_size() as usize;
+                self.write_bytes(addr, &int_bytes(value)[..size]);
+            }
+            Layout::Builtin(Builtin::Bool) => {
+                self.write_bytes(addr, &int_bytes(value)[..1]);
+            }
+            Layout::Builtin(Builtin::Float(width)) => match value {
+                Value::Float(float) => match width {
+                    FloatWidth::F32 => self.write_bytes(addr, &(*float as f32).to_le_bytes()),
+                    FloatWidth::F64 => self.write_bytes(addr, &float.to_le_bytes()),
+                },
+                other => unreachable!("expected a float, got {:?}", other),
+            },
+            Layout::Builtin(Builtin::Decimal) => {
+                unreachable!("the interpreter doesn't produce Dec values")
+            }
+            Layout::Builtin(Builtin::Str) => match value {
+                Value::Str(string) => {
+                    let bytes_addr = self.alloc(string.len());
+                    self.write_bytes(bytes_addr, string.as_bytes());
+                    self.write_ptr_len_cap(addr, bytes_addr, string.len());
+                }
+                other => unreachable!("expected a string, got {:?}", other),
+            },
+            Layout::Builtin(Builtin::List(elem_layout)) => match value {
+                Value::List(elems) => {
+                    let elem_size = interner.stack_size(elem_layout) as usize;
+                    let elems_addr = self.alloc(elem_size * elems.len());
+
+                    for (index, elem) in elems.iter().enumerate() {
+                        let elem_addr = elems_addr + index * elem_size;
+                        self.write_value(interner, elem_addr, elem, elem_layout, enclosing_union);
+                    }
+
+                    self.write_ptr_len_cap(addr, elems_addr, elems.len());
+                }
+                other => unreachable!("expected a list, got {:?}", other),
+            },
+            Layout::Struct { field_layouts, .. } => match value {
+                Value::Struct(fields) => {
+                    self.write_fields(interner, addr, fields, field_layouts, enclosing_union)
+                }
+                other => unreachable!("expected a struct, got {:?}", other),
+            },
+            // Boxes are transparent to the interpreter, so the value is the boxed value itself.
+            Layout::Boxed(inner_layout) => {
+                let inner_addr = self.alloc(interner.stack_size(inner_layout) as usize);
+                self.write_value(interner, inner_addr, value, inner_layout, enclosing_union);
+                self.write_usize(addr, inner_addr);
+            }
+            Layout::LambdaSet(lambda_set) => self.write_value(
+                interner,
+                addr,
+                value,
+                lambda_set.runtime_representation(),
+                enclosing_union,
+            ),
+            Layout::RecursivePointer(union_layout) => {
+                let union_layout = match enclosing_union {
+                    Some(enclosing) if union_layout == Layout::VOID => enclosing,
+                    _ => union_layout,
+                };
+
+                self.write_value(interner, addr, value, union_layout, enclosing_union)
+            }
+            Layout::Union(union_layout) => match union_layout {
+                UnionLayout::NonRecursive(tags) => {
+                    let (tag_id, fields) = expect_tag(value);
+
+                    self.write_fields(
+                        interner,
+                        addr,
+                        fields,
+                        tags[tag_id as usize],
+                        enclosing_union,
+                    );
+
+                    if let Some(offset) =
+                        union_layout.data_size_without_tag_id(interner, self.target_info)
+                    {
+                        self.write_tag_id(addr + offset as usize, union_layout, tag_id);
+                    }
+                }
+                UnionLayout::Recursive(tags) => {
+                    let (tag_id, fields) = expect_tag(value);
+                    let ptr = self.alloc_recursive_tag(
+                        interner,
+                        union_layout,
+                        layout,
+                        tag_id,
+                        fields,
+                        tags[tag_id as usize],
+                    );
+
+                    self.write_usize(addr, ptr);
+                }
+                UnionLayout::NonNullableUnwrapped(field_layouts) => {
+                    let (_, fields) = expect_tag(value);
+                    let ptr = self.alloc_recursive_tag(
+                        interner,
+                        union_layout,
+                        layout,
+                        0,
+                        fields,
+                        field_layouts,
+                    );
+
+                    self.write_usize(addr, ptr);
+                }
+                UnionLayout::NullableWrapped {
+                    nullable_id,
+                    other_tags,
+                } => {
+                    let (tag_id, fields) = expect_tag(value);
+
+                    // The null pointer is already there, since memory starts out zeroed.
+                    if tag_id != nullable_id {
+                        let index = if tag_id > nullable_id {
+                            tag_id - 1
+                        } else {
+                            tag_id
+                        };
+
+                        let ptr = self.alloc_recursive_tag(
+                            interner,
+                            union_layout,
+                            layout,
+                            tag_id,
+                            fields,
+                            other_tags[index as usize],
+                        );
+
+                        self.write_usize(addr, ptr);
+                    }
+                }
+                UnionLayout::NullableUnwrapped {
+                    nullable_id,
+                    other_fields,
+                } => {
+                    let (tag_id, fields) = expect_tag(value);
+
+                    if tag_id != nullable_id as u16 {
+                        let ptr = self.alloc_recursive_tag(
+                            interner,
+                            union_layout,
+                            layout,
+                            tag_id,
+                            fields,
+                            other_fields,
+                        );
+
+                        self.write_usize(addr, ptr);
+                    }
+                }
+            },
+        }
+    }
+}
+
+fn expect_tag(value: &Value) -> (u16, &[Value]) {
+    match value {
+        Value::Tag { tag_id, fields } => (*tag_id, fields),
+        other => unreachable!("expected a tag, got {:?}", other),
+    }
+}
@@ -5,6 +5,7 @@ use roc_target::TargetInfo;
 
 pub mod eval;
 pub mod gen;
+pub mod interp;
 
 pub trait ReplApp<'a> {
     type Memory: 'a + ReplAppMemory;
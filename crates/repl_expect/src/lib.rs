@@ -104,6 +104,27 @@ mod test {
     use super::*;
 
     fn run_expect_test(source: &str, expected: &str) {
+        let actual = run_expects_to_string(source);
+
+        if !actual.is_empty() {
+            // trim off the first line; it contains a path in a tempdir that
+            // changes between test runs
+            let p = actual.bytes().position(|c| c == b'\n').unwrap();
+            let (_, x) = actual.split_at(p);
+            let x = x.trim();
+            let expected = expected.trim_end();
+
+            if x != expected {
+                println!("{}", x);
+            }
+
+            assert_eq!(x, expected);
+        } else {
+            assert_eq!(actual, expected);
+        }
+    }
+
+    fn run_expects_to_string(source: &str) -> String {
         let arena = bumpalo::Bump::new();
         let arena = &arena;
 
@@ -125,6 +146,7 @@ mod test {
             palette: DEFAULT_PALETTE,
             threading: Threading::Single,
             exec_mode: ExecutionMode::Test,
+            ..Default::default()
         };
         let loaded = match roc_load::load_and_monomorphize_from_str(
             arena,
@@ -188,24 +210,7 @@ mod test {
         //     Before: "42 \u{1b}[35m:\u{1b}[0m Num *"
         //     After:  "42 : Num *"
         let bytes = strip_ansi_escapes::strip(writer).unwrap();
-        let actual = String::from_utf8(bytes).unwrap();
-
-        if !actual.is_empty() {
-            // trim off the first line; it contains a path in a tempdir that
-            // changes between test runs
-            let p = actual.bytes().position(|c| c == b'\n').unwrap();
-            let (_, x) = actual.split_at(p);
-            let x = x.trim();
-            let expected = expected.trim_end();
-
-            if x != expected {
-                println!("{}", x);
-            }
-
-            assert_eq!(x, expected);
-        } else {
-            assert_eq!(actual, expected);
-        }
+        String::from_utf8(bytes).unwrap()
     }
 
     #[test]
@@ -222,6 +227,33 @@ mod test {
         );
     }
 
+    #[test]
+    fn fixture_shared_between_expects() {
+        let actual = run_expects_to_string(indoc!(
+            r#"
+            interface Test exposes [] imports []
+
+            ## @fixture
+            users =
+                dbg "loading users"
+
+                List.range { start: At 0, end: Before 1000 } |> List.map Num.toStr
+
+            expect List.len users == 1000
+
+            expect List.first users == Ok "0"
+            "#
+        ));
+
+        // the fixture runs once, no matter how many expects use it
+        assert_eq!(
+            actual.matches(r#"] "loading users""#).count(),
+            1,
+            "{actual}"
+        );
+        assert_eq!(actual.lines().count(), 1, "{actual}");
+    }
+
     #[test]
     fn equals_fail() {
         run_expect_test(
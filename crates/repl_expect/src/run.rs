@@ -20,6 +20,7 @@ use roc_gen_llvm::{
 };
 use roc_load::{Expectations, MonomorphizedModule};
 use roc_module::symbol::{Interns, ModuleId, Symbol};
+use roc_mono::thread_escape::ThreadSharedLayouts;
 use roc_mono::{
     ir::OptLevel,
     layout::{GlobalLayoutInterner, STLayoutInterner},
@@ -125,6 +126,13 @@ impl<'a> ExpectMemory<'a> {
     }
 }
 
+/// Sets the seed that `Random.testSeed` returns in the expects of this library.
+pub fn set_test_seed(lib: &libloading::Library, seed: u64) {
+    let set_test_seed = run_roc_dylib!(lib, "set_test_seed", u64, ());
+    let mut result = RocCallResult::default();
+    unsafe { set_test_seed(seed, &mut result) };
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn run_inline_expects<'a, W: std::io::Write>(
     writer: &mut W,
@@ -194,6 +202,27 @@ pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
     let mut failed = 0;
     let mut passed = 0;
 
+    // evaluate every fixture once up front, so the expects (including the ones that run in
+    // a forked process) all share the cached values
+    for fixture in expects.fixtures {
+        if !run_fixture(
+            writer,
+            render_target,
+            arena,
+            interns,
+            layout_interner,
+            lib,
+            expectations,
+            memory,
+            fixture,
+        )? {
+            // the expects can't run without their fixtures
+            failed += expects.pure.len() + expects.fx.len();
+
+            return Ok((failed, passed));
+        }
+    }
+
     for expect in expects.fx {
         let result = run_expect_fx(
             writer,
@@ -237,6 +266,134 @@ pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
     Ok((failed, passed))
 }
 
+/// Evaluates a `@fixture`, and reports a crash at the fixture's definition.
+///
+/// The fixture runs on a thread of its own, so that its value is cached in this process for the
+/// expects to share, while this thread renders its `dbg`s the way the parent process of a forked
+/// expect would.
+#[allow(clippy::too_many_arguments)]
+fn run_fixture<'a, W: std::io::Write>(
+    writer: &mut W,
+    render_target: RenderTarget,
+    arena: &'a Bump,
+    interns: &'a Interns,
+    layout_interner: &GlobalLayoutInterner<'a>,
+    lib: &libloading::Library,
+    expectations: &mut VecMap<ModuleId, Expectations>,
+    memory: &mut ExpectMemory,
+    fixture: ToplevelExpect<'_>,
+) -> std::io::Result<bool> {
+    use roc_gen_llvm::try_run_jit_function;
+    use std::sync::atomic::Ordering;
+
+    memory.set_shared_buffer(lib);
+    memory.reset();
+
+    let done = Arc::new(AtomicBool::new(false));
+
+    let result: Result<(), (String, _)> = std::thread::scope(|scope| {
+        let fixture_thread = scope.spawn({
+            let done = Arc::clone(&done);
+
+            move || {
+                let result: Result<(), (String, _)> =
+                    try_run_jit_function!(lib, fixture.name, (), |v: ()| v);
+
+                done.store(true, Ordering::Relaxed);
+
+                result
+            }
+        });
+
+        loop {
+            match memory.wait_for_child(Arc::clone(&done)) {
+                ChildProcessMsg::Terminate => break,
+                ChildProcessMsg::Expect => {
+                    render_expects_in_memory(
+                        writer,
+                        arena,
+                        expectations,
+                        interns,
+                        layout_interner,
+                        memory,
+                    )?;
+
+                    memory.reset();
+                }
+                ChildProcessMsg::Dbg => {
+                    render_dbgs_in_memory(
+                        writer,
+                        arena,
+                        expectations,
+                        interns,
+                        layout_interner,
+                        memory,
+                    )?;
+
+                    memory.reset();
+                }
+            }
+        }
+
+        Ok(fixture_thread.join().unwrap())
+    })?;
+
+    match result {
+        Ok(()) => Ok(true),
+        Err((roc_panic_message, _roc_panic_tag)) => {
+            let module_id = fixture.symbol.module_id();
+            let data = expectations.get_mut(&module_id).unwrap();
+
+            let path = &data.path;
+            let filename = data.path.to_owned();
+            let source = std::fs::read_to_string(path).unwrap();
+
+            let renderer =
+                Renderer::new(arena, interns, render_target, module_id, filename, &source);
+
+            renderer.render_panic(writer, &roc_panic_message, fixture.region)?;
+
+            writeln!(writer)?;
+
+            Ok(false)
+        }
+    }
+}
+
+/// Runs a top-level `bench` `warmup + iterations` times, and returns how long each of the last
+/// `iterations` runs took. If a run crashes, returns the panic message instead.
+pub fn run_toplevel_bench(
+    lib: &libloading::Library,
+    bench: ToplevelExpect<'_>,
+    warmup: usize,
+    iterations: usize,
+) -> Result<Vec<std::time::Duration>, String> {
+    use roc_gen_llvm::try_run_jit_function;
+
+    // any inline `expect`s in the benchmark report into this buffer
+    let shm_name = format!("/roc_expect_buffer_{}", std::process::id());
+    let mut memory = ExpectMemory::create_or_reuse_mmap(&shm_name);
+    memory.set_shared_buffer(lib);
+
+    let mut timings = Vec::with_capacity(iterations);
+
+    for run in 0..warmup + iterations {
+        let start = std::time::Instant::now();
+        let result: Result<(), (String, _)> = try_run_jit_function!(lib, bench.name, (), |v: ()| v);
+        let elapsed = start.elapsed();
+
+        if let Err((roc_panic_message, _roc_panic_tag)) = result {
+            return Err(roc_panic_message);
+        }
+
+        if run >= warmup {
+            timings.push(elapsed);
+        }
+    }
+
+    Ok(timings)
+}
+
 #[allow(clippy::too_many_arguments)]
 fn run_expect_pure<'a, W: std::io::Write>(
     writer: &mut W,
@@ -522,10 +679,11 @@ fn render_dbg_failure<'a>(
         Some(current) => current,
     };
     let failure_region = current.region;
+    let label_region = current.label;
 
     let subs = arena.alloc(&mut data.subs);
 
-    let (offset, expressions, _variables) = crate::get_values(
+    let (offset, expressions, variables) = crate::get_values(
         target_info,
         arena,
         subs,
@@ -536,7 +694,15 @@ fn render_dbg_failure<'a>(
         1,
     );
 
-    renderer.render_dbg(writer, &expressions, expect_region, failure_region)?;
+    renderer.render_dbg(
+        writer,
+        subs,
+        &expressions,
+        &variables,
+        expect_region,
+        failure_region,
+        label_region,
+    )?;
 
     Ok(offset)
 }
@@ -696,6 +862,10 @@ pub struct ToplevelExpect<'a> {
 pub struct ExpectFunctions<'a> {
     pub pure: BumpVec<'a, ToplevelExpect<'a>>,
     pub fx: BumpVec<'a, ToplevelExpect<'a>>,
+    /// The `bench` blocks, which are called the same way as `expect`s
+    pub benches: BumpVec<'a, ToplevelExpect<'a>>,
+    /// Thunks that evaluate the `@fixture`s, which must run before any of the `expect`s
+    pub fixtures: BumpVec<'a, ToplevelExpect<'a>>,
 }
 
 pub fn expect_mono_module_to_dylib<'a>(
@@ -716,6 +886,7 @@ pub fn expect_mono_module_to_dylib<'a>(
 
     let MonomorphizedModule {
         toplevel_expects,
+        toplevel_benches,
         procedures,
         interns,
         mut layout_interner,
@@ -747,17 +918,33 @@ pub fn expect_mono_module_to_dylib<'a>(
         mode,
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
+        thread_shared_layouts: ThreadSharedLayouts::from_procs(
+            &layout_interner,
+            procedures.values(),
+        ),
+        fixtures: toplevel_expects
+            .fixtures
+            .values()
+            .map(|(fixture, _)| *fixture)
+            .collect(),
+        batch_effects: false,
+        fast_math: false,
     };
 
     // Add roc_alloc, roc_realloc, and roc_dealloc, since the repl has no
     // platform to provide them.
     add_default_roc_externs(&env);
 
-    let capacity = toplevel_expects.pure.len() + toplevel_expects.fx.len();
+    let capacity = toplevel_expects.pure.len()
+        + toplevel_expects.fx.len()
+        + toplevel_benches.len()
+        + toplevel_expects.fixtures.len();
     let mut expect_symbols = BumpVec::with_capacity_in(capacity, env.arena);
 
     expect_symbols.extend(toplevel_expects.pure.keys().copied());
     expect_symbols.extend(toplevel_expects.fx.keys().copied());
+    expect_symbols.extend(toplevel_benches.keys().copied());
+    expect_symbols.extend(toplevel_expects.fixtures.keys().copied());
 
     let expect_names = roc_gen_llvm::llvm::build::build_procedures_expose_expects(
         &env,
@@ -793,9 +980,46 @@ pub fn expect_mono_module_to_dylib<'a>(
         env.arena,
     );
 
+    let benches_len = toplevel_benches.len();
+
+    let benches = bumpalo::collections::Vec::from_iter_in(
+        toplevel_benches
+            .into_iter()
+            .zip(
+                expect_names
+                    .iter()
+                    .skip(expects_pure.len() + expects_fx.len()),
+            )
+            .map(|((symbol, region), name)| ToplevelExpect {
+                symbol,
+                region,
+                name,
+            }),
+        env.arena,
+    );
+
+    let fixtures = bumpalo::collections::Vec::from_iter_in(
+        toplevel_expects
+            .fixtures
+            .into_iter()
+            .zip(
+                expect_names
+                    .iter()
+                    .skip(expects_pure.len() + expects_fx.len() + benches_len),
+            )
+            .map(|((symbol, (_, region)), name)| ToplevelExpect {
+                symbol,
+                region,
+                name,
+            }),
+        env.arena,
+    );
+
     let expects = ExpectFunctions {
         pure: expects_pure,
         fx: expects_fx,
+        benches,
+        fixtures,
     };
 
     env.dibuilder.finalize();
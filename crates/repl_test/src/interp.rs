@@ -0,0 +1,125 @@
+use roc_repl_cli::repl_state::ReplState;
+
+// These run the REPL with the mono interpreter, like `roc repl --interp` does. They mirror some
+// of the tests in `tests.rs`, which compile with LLVM, so both backends print the same answers.
+
+#[test]
+fn literals() {
+    expect_success("42", "42 : Num *");
+    expect_success("4.2", "4.2 : Float *");
+    expect_success("\"hello\"", "\"hello\" : Str");
+    expect_success("1 == 1", "Bool.true : Bool");
+}
+
+#[test]
+fn arithmetic() {
+    expect_success("1 + 2", "3 : Num *");
+    expect_success("1.1 + 2", "3.1 : Float *");
+    expect_success("299 % 10", "9 : Int *");
+    expect_success("Num.divTrunc 4 3", "1 : Int *");
+}
+
+#[test]
+fn max_u128() {
+    expect_success(
+        "Num.maxU128",
+        "340282366920938463463374607431768211455 : U128",
+    );
+}
+
+#[test]
+fn records_and_tags() {
+    expect_success(
+        "{ x: Foo 1 2 3, y : 4 }",
+        "{ x: Foo 1 2 3, y: 4 } : { x : [Foo (Num *) (Num *) (Num *)], y : Num * }",
+    );
+    expect_success(
+        "if 1 != 1 then True 3 else False 3.14",
+        "False 3.14 : [False (Float *), True (Num *)]",
+    );
+}
+
+#[test]
+fn str_concat() {
+    expect_success(
+        "Str.concat \"Hello, \" \"World!\"",
+        "\"Hello, World!\" : Str",
+    );
+}
+
+#[test]
+fn list_map() {
+    expect_success(
+        "List.map [1, 2, 3] \\x -> x * 2",
+        "[2, 4, 6] : List (Num *)",
+    );
+}
+
+#[test]
+fn list_map_with_captures() {
+    expect_success(
+        "(\\n -> List.map2 [1, 2, 3] [4, 5] \\a, b -> a + b + n) 10",
+        "[15, 17] : List (Num *)",
+    );
+}
+
+#[test]
+fn list_sort_with() {
+    expect_success(
+        "List.sortWith [3, 1, 2] Num.compare",
+        "[1, 2, 3] : List (Num *)",
+    );
+}
+
+#[test]
+fn recursive_tag_union() {
+    let mut state = ReplState::new_interp();
+
+    step(&mut state, "ConsList a : [Cons a (ConsList a), Nil]");
+    expect_step(
+        &mut state,
+        "x : ConsList Str\nx = Cons \"a\" (Cons \"b\" Nil)",
+        "Cons \"a\" (Cons \"b\" Nil) : ConsList Str",
+    );
+}
+
+#[test]
+fn division_by_zero() {
+    expect_failure(
+        "Num.divTrunc 1 0",
+        "This Roc code crashed with: \"Integer division by 0!\"",
+    );
+}
+
+#[test]
+fn division_overflow() {
+    expect_failure(
+        "Num.divTrunc Num.minI64 -1",
+        "This Roc code crashed with: \"Integer division overflowed!\"",
+    );
+}
+
+fn step(state: &mut ReplState, input: &str) -> String {
+    let output = state.step(input, None).unwrap();
+
+    String::from_utf8(strip_ansi_escapes::strip(output.trim()).unwrap()).unwrap()
+}
+
+/// Checks the answer and its type, ignoring the name it gets
+fn expect_step(state: &mut ReplState, input: &str, expected: &str) {
+    let output = step(state, input);
+    let answer = match output.rfind('#') {
+        Some(comment_index) => output[..comment_index].trim(),
+        None => output.as_str(),
+    };
+
+    assert_eq!(answer, expected);
+}
+
+fn expect_success(input: &str, expected: &str) {
+    expect_step(&mut ReplState::new_interp(), input, expected);
+}
+
+fn expect_failure(input: &str, expected: &str) {
+    assert_eq!(step(&mut ReplState::new_interp(), input), expected);
+}
@@ -4,6 +4,9 @@ mod tests;
 #[cfg(test)]
 mod state;
 
+#[cfg(test)]
+mod interp;
+
 #[cfg(all(test, not(feature = "wasm")))]
 mod cli;
 
@@ -1,5 +1,5 @@
 use indoc::indoc;
-use roc_repl_cli::repl_state::{is_incomplete, ReplState, TIPS};
+use roc_repl_cli::repl_state::{is_incomplete, ReplState, BENCH_UNSUPPORTED, TIPS};
 
 // These are tests of the REPL state machine. They work without actually
 // running the CLI, and without using rustyline, and instead verify
@@ -101,6 +101,154 @@ fn standalone_annotation() {
     assert_eq!(state.step(&input, None), Ok(String::new()));
 }
 
+#[test]
+fn type_of_expr() {
+    let mut state = ReplState::new();
+
+    complete("x = 5", &mut state, Ok(("5 : Num *", "x")));
+
+    let output = state.step(":type x + 1.5", None).unwrap();
+    let escaped =
+        std::string::String::from_utf8(strip_ansi_escapes::strip(output.trim()).unwrap()).unwrap();
+
+    assert_eq!(escaped, "x + 1.5 : Frac *");
+
+    // The expression was not evaluated, so it didn't get a name.
+    complete("2 * 3", &mut state, Ok(("6 : Num *", "val1")));
+}
+
+#[test]
+fn bench_is_unsupported() {
+    let mut state = ReplState::new();
+
+    assert_eq!(
+        state.step("bench List.sum [1, 2, 3]", None),
+        Ok(BENCH_UNSUPPORTED.to_string())
+    );
+
+    // It wasn't evaluated, so it didn't get a name.
+    complete("2 * 3", &mut state, Ok(("6 : Num *", "val1")));
+}
+
+#[test]
+fn tab_completion() {
+    let mut state = ReplState::new();
+
+    complete("value = 5", &mut state, Ok(("5 : Num *", "value")));
+    complete("1 + 1", &mut state, Ok(("2 : Num *", "val1")));
+
+    assert_eq!(
+        state.completion_candidates("1 + va", 6),
+        (4, vec!["val1".to_string(), "value".to_string()])
+    );
+    assert_eq!(
+        state.completion_candidates("Li", 2),
+        (0, vec!["List".to_string()])
+    );
+
+    // Module members come from what the module exposes.
+    let (start, members) = state.completion_candidates("List.ma", 7);
+    assert_eq!(start, 5);
+    assert!(members.contains(&"map".to_string()));
+    assert!(members.contains(&"map2".to_string()));
+    assert!(members.iter().all(|member| member.starts_with("ma")));
+}
+
+#[test]
+fn tab_completion_of_record_fields() {
+    let mut state = ReplState::new();
+
+    complete(
+        "rec = { name: \"Sam\", nested: { age: 5 } }",
+        &mut state,
+        Ok((
+            "{ name: \"Sam\", nested: { age: 5 } } : { name : Str, nested : { age : Num * } }",
+            "rec",
+        )),
+    );
+
+    assert_eq!(
+        state.completion_candidates("rec.na", 6),
+        (4, vec!["name".to_string(), "nested".to_string()])
+    );
+    assert_eq!(
+        state.completion_candidates("rec.nested.a", 12),
+        (11, vec!["age".to_string()])
+    );
+    assert_eq!(state.completion_candidates("value.a", 7), (6, Vec::new()));
+}
+
+#[test]
+fn tab_completion_only_offers_exposed_members() {
+    let state = ReplState::new();
+
+    // Str.replaceEachHelp is defined in Str, but not exposed.
+    assert_eq!(
+        state.completion_candidates("Str.replaceE", 12),
+        (4, vec!["replaceEach".to_string()])
+    );
+}
+
+#[test]
+fn tab_completion_sees_new_defs() {
+    let mut state = ReplState::new();
+
+    // Nothing is called `rec` yet; this is remembered until the past defs change.
+    assert_eq!(state.completion_candidates("rec.", 4), (4, Vec::new()));
+
+    complete(
+        "rec = { name: \"Sam\" }",
+        &mut state,
+        Ok(("{ name: \"Sam\" } : { name : Str }", "rec")),
+    );
+
+    assert_eq!(
+        state.completion_candidates("rec.", 4),
+        (4, vec!["name".to_string()])
+    );
+}
+
+#[test]
+fn tab_completion_after_multi_byte_separator() {
+    let mut state = ReplState::new();
+
+    complete("value = 5", &mut state, Ok(("5 : Num *", "value")));
+
+    // "→" is three bytes long
+    assert_eq!(
+        state.completion_candidates("1 →va", 7),
+        (5, vec!["value".to_string()])
+    );
+    assert_eq!(state.completion_candidates("→", 3), (3, Vec::new()));
+}
+
+#[test]
+fn unfinished_expressions() {
+    assert!(is_incomplete("(1 +"));
+    assert!(is_incomplete("[1, 2,"));
+    assert!(is_incomplete("x = 1 |>"));
+    assert!(is_incomplete("if Bool.true then 1"));
+    assert!(is_incomplete("\"\"\"multi-line string"));
+
+    // Brackets inside strings and comments don't count
+    assert!(!is_incomplete("\"(\" )"));
+    assert!(!is_incomplete("1 ) # (comment"));
+
+    // A blank line gives up on waiting for the rest, so the syntax error gets reported.
+    assert!(!is_incomplete("(1 +\n"));
+}
+
+#[test]
+fn multiline_parens() {
+    let mut input = "(1 +".to_string();
+
+    incomplete(&mut input);
+
+    input.push_str("    2)");
+
+    complete(&input, &mut ReplState::new(), Ok(("3 : Num *", "val1")));
+}
+
 /// validate and step the given input, then check the Result vs the output
 /// with ANSI escape codes stripped.
 fn complete(input: &str, state: &mut ReplState, expected_step_result: Result<(&str, &str), i32>) {
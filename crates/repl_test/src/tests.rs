@@ -460,6 +460,15 @@ fn empty_record() {
     expect_success("{}", "{} : {}");
 }
 
+#[test]
+fn list_map_concurrent_without_a_thread_pool() {
+    // The REPL doesn't provide `roc_parallel_for`, so this maps one element at a time
+    expect_success(
+        r#"List.mapConcurrent [1, 2, 3] \x -> x * 2"#,
+        "[2, 4, 6] : List (Num *)",
+    );
+}
+
 #[test]
 fn basic_1_field_i64_record() {
     // Even though this gets unwrapped at runtime, the repl should still
@@ -123,12 +123,17 @@ impl<'a> ImportDispatcher for CompilerDispatcher<'a> {
 }
 
 fn run(src: &'static str) -> Result<String, String> {
+    run_steps(&[src]).pop().unwrap()
+}
+
+/// Runs each input in turn in the same compiler instance, so later inputs can use earlier defs
+fn run_steps(inputs: &[&'static str]) -> Vec<Result<String, String>> {
     let arena = Bump::new();
 
     let mut instance = {
         let dispatcher = CompilerDispatcher {
             arena: &arena,
-            src,
+            src: "",
             answer: String::new(),
             wasi: WasiDispatcher::default(),
             app: None,
@@ -139,20 +144,27 @@ fn run(src: &'static str) -> Result<String, String> {
         Instance::from_bytes(&arena, COMPILER_BYTES, dispatcher, is_debug_mode).unwrap()
     };
 
-    let len = Value::I32(src.len() as i32);
-    let wasm_ok: i32 = instance
-        .call_export("entrypoint_from_test", [len])
-        .unwrap()
-        .unwrap()
-        .expect_i32()
-        .unwrap();
-    let answer_str = instance.import_dispatcher.answer.to_owned();
-
-    if wasm_ok == 0 {
-        Err(answer_str)
-    } else {
-        Ok(answer_str)
-    }
+    inputs
+        .iter()
+        .map(|src| {
+            instance.import_dispatcher.src = src;
+
+            let len = Value::I32(src.len() as i32);
+            let wasm_ok: i32 = instance
+                .call_export("entrypoint_from_test", [len])
+                .unwrap()
+                .unwrap()
+                .expect_i32()
+                .unwrap();
+            let answer_str = std::mem::take(&mut instance.import_dispatcher.answer);
+
+            if wasm_ok == 0 {
+                Err(answer_str)
+            } else {
+                Ok(answer_str)
+            }
+        })
+        .collect()
 }
 
 #[allow(dead_code)]
@@ -164,3 +176,61 @@ pub fn expect_success(input: &'static str, expected: &str) {
 pub fn expect_failure(input: &'static str, expected: &str) {
     assert_eq!(run(input), Err(expected.into()));
 }
+
+#[test]
+fn persisted_defs() {
+    assert_eq!(
+        run_steps(&["x = 5", "y = x + 1", "x + y"]),
+        [
+            Ok("5 : Num * # x".into()),
+            Ok("6 : Num * # y".into()),
+            Ok("11 : Num *".into()),
+        ]
+    );
+}
+
+#[test]
+fn persisted_type_defs() {
+    let results = run_steps(&[
+        "Point : { x : I64, y : I64 }",
+        "p : Point\np = { x: 1, y: 2 }",
+    ]);
+
+    assert_eq!(
+        results,
+        [Ok("".into()), Ok("{ x: 1, y: 2 } : Point # p".into())]
+    );
+}
+
+#[test]
+fn invalid_type_def_is_not_persisted() {
+    let results = run_steps(&["Broken : Missing", "1 + 1"]);
+
+    assert!(results[0].is_err());
+    assert_eq!(results[1], Ok("2 : Num *".into()));
+}
+
+#[test]
+fn invalid_value_def_is_not_persisted() {
+    let results = run_steps(&["x = missing", "x = 3", "x"]);
+
+    assert!(results[0].is_err());
+    assert_eq!(results[1], Ok("3 : Num * # x".into()));
+    assert_eq!(results[2], Ok("3 : Num *".into()));
+}
+
+#[test]
+fn package_imports_are_unsupported() {
+    expect_failure(
+        "import pf.Stdout",
+        "Importing packages isn't supported in the web REPL yet.",
+    );
+}
+
+#[test]
+fn url_package_imports_are_unsupported() {
+    expect_failure(
+        r#"import "https://example.com/pkg.tar.br" as pkg"#,
+        "Importing packages from URLs isn't supported in the web REPL yet, because it can't download them.",
+    );
+}
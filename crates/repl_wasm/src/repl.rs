@@ -1,13 +1,19 @@
 use bumpalo::{collections::vec::Vec, Bump};
+use std::cell::RefCell;
 use std::mem::size_of;
 
 use roc_collections::all::MutSet;
+use roc_gen_wasm::source_map::{append_source_mapping_url, SourceMap};
 use roc_gen_wasm::wasm32_result;
 use roc_load::MonomorphizedModule;
-use roc_parse::ast::Expr;
+use roc_parse::ast::{Expr, Pattern, ValueDef};
+use roc_parse::expr::{parse_single_def, ExprParseOptions, SingleDef};
+use roc_parse::parser::{EExpr, Either, Parser};
+use roc_parse::state::State;
+use roc_region::all::Loc;
 use roc_repl_eval::{
     eval::jit_to_ast,
-    gen::{compile_to_mono, format_answer},
+    gen::{compile_def_to_mono, compile_to_mono, format_answer, Problems},
     ReplApp, ReplAppMemory,
 };
 use roc_reporting::report::DEFAULT_PALETTE_HTML;
@@ -18,6 +24,75 @@ use crate::{js_create_app, js_get_result_and_memory, js_run_app};
 
 const WRAPPER_NAME: &str = "wrapper";
 
+thread_local! {
+    /// The source of every definition entered so far, so later entries can refer to them.
+    static PAST_DEFS: RefCell<std::vec::Vec<String>> = RefCell::new(std::vec::Vec::new());
+}
+
+enum Input<'a> {
+    Expr,
+    /// A definition like `x = 1`, which we evaluate by looking up its name
+    ValueDef(&'a str),
+    /// A definition that can't be evaluated, like a type alias or a standalone annotation
+    OtherDef,
+    /// An `import` of a package, which the web REPL can't load. Packages given by URL would have
+    /// to be downloaded and cached by the page, which there are no JS hooks for yet.
+    Import {
+        from_url: bool,
+    },
+}
+
+fn classify_input<'a>(arena: &'a Bump, src: &'a str) -> Input<'a> {
+    if src.split_whitespace().next() == Some("import") {
+        return Input::Import {
+            from_url: src.contains("://"),
+        };
+    }
+
+    let src_bytes = src.as_bytes();
+
+    match roc_parse::expr::loc_expr(true).parse(arena, State::new(src_bytes), 0) {
+        Err((_, EExpr::DefMissingFinalExpr(_))) | Err((_, EExpr::DefMissingFinalExpr2(_, _))) => {
+            let options = ExprParseOptions {
+                accept_multi_backpassing: true,
+                check_for_arrow: true,
+            };
+
+            match parse_single_def(options, false, 0, arena, State::new(src_bytes)) {
+                Ok((
+                    _,
+                    Some(SingleDef {
+                        type_or_value:
+                            Either::Second(
+                                ValueDef::Body(
+                                    Loc {
+                                        value: Pattern::Identifier(ident),
+                                        ..
+                                    },
+                                    _,
+                                )
+                                | ValueDef::AnnotatedBody {
+                                    body_pattern:
+                                        Loc {
+                                            value: Pattern::Identifier(ident),
+                                            ..
+                                        },
+                                    ..
+                                },
+                            ),
+                        ..
+                    }),
+                    _,
+                )) => Input::ValueDef(ident),
+                Ok((_, Some(_), _)) => Input::OtherDef,
+                // Let compilation report the syntax error.
+                Ok((_, None, _)) | Err(_) => Input::Expr,
+            }
+        }
+        _ => Input::Expr,
+    }
+}
+
 pub struct WasmReplApp<'a> {
     arena: &'a Bump,
 }
@@ -172,33 +247,56 @@ pub async fn entrypoint_from_js(src: String) -> Result<String, String> {
     console_error_panic_hook::set_once();
 
     let arena = &Bump::new();
+    let src: &str = arena.alloc_str(&src);
+
+    let past_defs = PAST_DEFS.with(|defs| defs.borrow().clone());
 
-    // Compile the app
     let target_info = TargetInfo::default_wasm32();
-    // TODO use this to filter out problems and warnings in wrapped defs.
-    // See the variable by the same name in the CLI REPL for how to do this!
-    let mono = match compile_to_mono(
-        arena,
-        std::iter::empty(),
-        &src,
-        target_info,
-        DEFAULT_PALETTE_HTML,
-    ) {
-        (Some(m), problems) if problems.is_empty() => m, // TODO render problems and continue if possible
-        (_, problems) => {
-            // TODO always report these, but continue if possible with the MonomorphizedModule if we have one.
-            let mut buf = String::new();
+    let past_defs = past_defs.iter().map(|def| def.as_str());
+
+    // Definitions only become past defs once they compile without problems, so that one bad
+    // entry can't break every entry after it.
+    let (new_def, expr) = match classify_input(arena, src) {
+        Input::Expr => (None, src),
+        Input::ValueDef(ident) => {
+            // Evaluate the def followed by a lookup of its name, like the CLI REPL does, so that
+            // the problems in the def get reported along with the expression's.
+            let expr = arena.alloc_str(&format!("{src}\n{ident}"));
+
+            (Some(ident), &*expr)
+        }
+        Input::OtherDef => {
+            // There's nothing to evaluate, but it still has to compile.
+            let (_, problems) =
+                compile_def_to_mono(arena, past_defs, src, target_info, DEFAULT_PALETTE_HTML);
+
+            // Like the CLI REPL, only errors stop a def from being persisted; warnings like the
+            // def being unused are expected here.
+            if !problems.errors.is_empty() {
+                return Err(join_problems(problems));
+            }
 
-            // Join all the errors and warnings together with blank lines.
-            for message in problems.errors.iter().chain(problems.warnings.iter()) {
-                if !buf.is_empty() {
-                    buf.push_str("\n\n");
-                }
+            PAST_DEFS.with(|defs| defs.borrow_mut().push(src.to_string()));
 
-                buf.push_str(message);
-            }
+            return Ok(String::new());
+        }
+        Input::Import { from_url: true } => {
+            return Err(
+                "Importing packages from URLs isn't supported in the web REPL yet, because it can't download them."
+                    .to_string(),
+            );
+        }
+        Input::Import { from_url: false } => {
+            return Err("Importing packages isn't supported in the web REPL yet.".to_string());
+        }
+    };
 
-            return Err(buf);
+    // Compile the app
+    let mono = match compile_to_mono(arena, past_defs, expr, target_info, DEFAULT_PALETTE_HTML) {
+        (Some(m), problems) if problems.is_empty() => m, // TODO render problems and continue if possible
+        (_, problems) => {
+            // TODO always report these, but continue if possible with the MonomorphizedModule if we have one.
+            return Err(join_problems(problems));
         }
     };
 
@@ -209,6 +307,8 @@ pub async fn entrypoint_from_js(src: String) -> Result<String, String> {
         mut subs,
         exposed_to_host,
         mut layout_interner,
+        def_regions,
+        sources,
         ..
     } = mono;
 
@@ -226,6 +326,10 @@ pub async fn entrypoint_from_js(src: String) -> Result<String, String> {
         DebugPrint::NOTHING,
     );
 
+    if new_def.is_some() {
+        PAST_DEFS.with(|defs| defs.borrow_mut().push(src.to_string()));
+    }
+
     let (_, main_fn_layout) = match procedures.keys().find(|(s, _)| *s == main_fn_symbol) {
         Some(layout) => *layout,
         None => return Ok(format!("<function> : {}", expr_type_str)),
@@ -236,6 +340,7 @@ pub async fn entrypoint_from_js(src: String) -> Result<String, String> {
             arena,
             module_id,
             stack_bytes: roc_gen_wasm::Env::DEFAULT_STACK_BYTES,
+            features: roc_gen_wasm::WasmFeatures::default(),
             exposed_to_host: exposed_to_host
                 .values
                 .keys()
@@ -243,16 +348,16 @@ pub async fn entrypoint_from_js(src: String) -> Result<String, String> {
                 .collect::<MutSet<_>>(),
         };
 
-        let (mut module, mut called_fns, main_fn_index) = {
-            let host_module = roc_gen_wasm::parse_host(env.arena, PRE_LINKED_BINARY).unwrap();
-            roc_gen_wasm::build_app_module(
-                &env,
-                &mut layout_interner,
-                &mut interns, // NOTE: must drop this mutable ref before jit_to_ast
-                host_module,
-                procedures,
-            )
-        };
+        let host_module = roc_gen_wasm::parse_host(env.arena, PRE_LINKED_BINARY).unwrap();
+        let app_fns = roc_gen_wasm::app_fn_symbols(&host_module, &procedures);
+
+        let (mut module, mut called_fns, main_fn_index) = roc_gen_wasm::build_app_module(
+            &env,
+            &mut layout_interner,
+            &mut interns, // NOTE: must drop this mutable ref before jit_to_ast
+            host_module,
+            procedures,
+        );
 
         wasm32_result::insert_wrapper_for_layout(
             arena,
@@ -269,6 +374,11 @@ pub async fn entrypoint_from_js(src: String) -> Result<String, String> {
         let mut buffer = Vec::with_capacity_in(module.size(), arena);
         module.serialize(&mut buffer);
 
+        // Let browser devtools show the Roc source when debugging the app
+        if let Ok(source_map) = SourceMap::new(arena, &buffer, &app_fns, &def_regions, &sources) {
+            append_source_mapping_url(&mut buffer, &source_map.to_data_url());
+        }
+
         buffer
     };
 
@@ -293,11 +403,29 @@ pub async fn entrypoint_from_js(src: String) -> Result<String, String> {
         target_info,
     );
 
-    let var_name = String::new(); // TODO turn this into something like " # val1"
+    let var_name = match new_def {
+        Some(ident) => format!(" # {ident}"),
+        None => String::new(),
+    };
 
     // Transform the Expr to a string
     // `Result::Err` becomes a JS exception that will be caught and displayed
-    let expr = format_answer(arena, res_answer);
+    let answer = format_answer(arena, res_answer);
+
+    Ok(format!("{answer} : {expr_type_str}{var_name}"))
+}
+
+/// Joins all the errors and warnings together with blank lines.
+fn join_problems(problems: Problems) -> String {
+    let mut buf = String::new();
+
+    for message in problems.errors.iter().chain(problems.warnings.iter()) {
+        if !buf.is_empty() {
+            buf.push_str("\n\n");
+        }
+
+        buf.push_str(message);
+    }
 
-    Ok(format!("{expr} : {expr_type_str}{var_name}"))
+    buf
 }
@@ -64,6 +64,16 @@ void roc_panic(void *ptr, unsigned int alignment)
 
 //--------------------------
 
+void roc_oom(size_t size, unsigned int alignment)
+{
+#if ENABLE_PRINTF
+    fprintf(stderr, "Ran out of memory allocating %zd bytes with alignment %d\n", size, alignment);
+#endif
+    abort();
+}
+
+//--------------------------
+
 void *roc_memcpy(void *dest, const void *src, size_t n)
 {
     return memcpy(dest, src, n);
@@ -1,8 +1,15 @@
 use std::path::PathBuf;
+use std::sync::Mutex;
 
+use crate::diagnostic::Diagnostic;
+use crate::report::{
+    can_problem, type_problem, Palette, RenderTarget, Report, RocDocAllocator, DEFAULT_PALETTE,
+    PLAIN_PALETTE,
+};
 use roc_collections::MutMap;
 use roc_module::symbol::{Interns, ModuleId};
-use roc_region::all::LineInfo;
+use roc_problem::{Severity, DENY_BY_DEFAULT_WARNING_NAMES, OPT_IN_WARNING_NAMES, WARNING_NAMES};
+use roc_region::all::{LineColumnRegion, LineInfo};
 use roc_solve_problem::TypeError;
 
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
@@ -22,25 +29,30 @@ impl Problems {
     }
 
     pub fn print_to_stdout(&self, total_time: std::time::Duration) {
-        const GREEN: usize = 32;
-        const YELLOW: usize = 33;
+        const GREEN: &str = "\x1B[32m";
+        const YELLOW: &str = "\x1B[33m";
+        const RESET: &str = "\x1B[39m";
+
+        let colored = terminal_style().is_colored();
+        let color = |count: usize| match (colored, count) {
+            (false, _) => "",
+            (true, 0) => GREEN,
+            (true, _) => YELLOW,
+        };
+        let reset = if colored { RESET } else { "" };
 
         print!(
-            "\x1B[{}m{}\x1B[39m {} and \x1B[{}m{}\x1B[39m {} found in {} ms",
-            match self.errors {
-                0 => GREEN,
-                _ => YELLOW,
-            },
+            "{}{}{} {} and {}{}{} {} found in {} ms",
+            color(self.errors),
             self.errors,
+            reset,
             match self.errors {
                 1 => "error",
                 _ => "errors",
             },
-            match self.warnings {
-                0 => GREEN,
-                _ => YELLOW,
-            },
+            color(self.warnings),
             self.warnings,
+            reset,
             match self.warnings {
                 1 => "warning",
                 _ => "warnings",
@@ -50,22 +62,135 @@ impl Problems {
     }
 }
 
-pub fn report_problems(
-    total_problems: usize,
+/// How problems are rendered when they're printed to the terminal
+#[derive(Clone, Copy, Debug)]
+pub struct TerminalStyle {
+    pub target: RenderTarget,
+    pub palette: Palette,
+}
+
+impl TerminalStyle {
+    pub const DEFAULT: Self = TerminalStyle {
+        target: RenderTarget::ColorTerminal,
+        palette: DEFAULT_PALETTE,
+    };
+
+    /// No colors or other escape sequences, e.g. for `NO_COLOR` or output to a file. The text is
+    /// the same as with colors, so it reads the same either way.
+    pub const PLAIN: Self = TerminalStyle {
+        target: RenderTarget::ColorTerminal,
+        palette: PLAIN_PALETTE,
+    };
+
+    pub fn is_colored(&self) -> bool {
+        !self.palette.reset.is_empty()
+    }
+}
+
+static TERMINAL_STYLE: Mutex<TerminalStyle> = Mutex::new(TerminalStyle::DEFAULT);
+
+/// Render problems printed to the terminal in this style from now on
+pub fn set_terminal_style(style: TerminalStyle) {
+    *TERMINAL_STYLE.lock().unwrap() = style;
+}
+
+/// The style problems printed to the terminal are currently rendered in
+pub fn terminal_style() -> TerminalStyle {
+    *TERMINAL_STYLE.lock().unwrap()
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WarningLevel {
+    /// Don't report the warning at all
+    Allow,
+    Warn,
+    /// Report the warning as an error, e.g. so CI fails on it
+    Error,
+}
+
+impl std::str::FromStr for WarningLevel {
+    type Err = String;
+
+    fn from_str(level: &str) -> Result<Self, Self::Err> {
+        match level {
+            "allow" => Ok(WarningLevel::Allow),
+            "warn" => Ok(WarningLevel::Warn),
+            "error" => Ok(WarningLevel::Error),
+            _ => Err(format!(
+                "`{}` isn't a warning level. The levels are allow, warn, and error.",
+                level
+            )),
+        }
+    }
+}
+
+/// How each kind of warning should be reported, by its name in [WARNING_NAMES]
+#[derive(Clone, Debug, Default)]
+pub struct WarningLevels {
+    /// For warnings which haven't been given a level of their own
+    default: Option<WarningLevel>,
+    levels: MutMap<&'static str, WarningLevel>,
+}
+
+impl WarningLevels {
+    /// Sets the level of one kind of warning, or of every kind if the name is `all`
+    pub fn set(&mut self, name: &str, level: WarningLevel) -> Result<(), String> {
+        if name == "all" {
+            self.default = Some(level);
+            self.levels.clear();
+
+            return Ok(());
+        }
+
+        match WARNING_NAMES.iter().find(|known| **known == name) {
+            Some(known) => {
+                self.levels.insert(known, level);
+
+                Ok(())
+            }
+            None => Err(format!(
+                "`{}` isn't the name of a warning. The warnings are: all, {}",
+                name,
+                WARNING_NAMES.join(", ")
+            )),
+        }
+    }
+
+    pub fn level(&self, name: Option<&str>) -> WarningLevel {
+        if let Some(level) = name.and_then(|name| self.levels.get(name)) {
+            return *level;
+        }
+
+        match name {
+            Some(name) if OPT_IN_WARNING_NAMES.contains(&name) => WarningLevel::Allow,
+            Some(name) if DENY_BY_DEFAULT_WARNING_NAMES.contains(&name) => WarningLevel::Error,
+            _ => self.default.unwrap_or(WarningLevel::Warn),
+        }
+    }
+
+    /// The severity to report a problem with, or None if it shouldn't be reported
+    fn severity(&self, severity: Severity, name: Option<&str>) -> Option<Severity> {
+        match (severity, self.level(name)) {
+            (Severity::RuntimeError, _) => Some(Severity::RuntimeError),
+            // warnings without a name can't be configured individually, but can be with `all`
+            (Severity::Warning, WarningLevel::Allow) => None,
+            (Severity::Warning, WarningLevel::Warn) => Some(Severity::Warning),
+            (Severity::Warning, WarningLevel::Error) => Some(Severity::RuntimeError),
+        }
+    }
+}
+
+/// Calls `f` with the report of each problem which should be reported, given the warning levels,
+/// in the severity it should be reported with, with where in its module the problem is, and with
+/// the allocator the report was made with
+pub fn for_each_report(
     sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
     interns: &Interns,
     can_problems: &mut MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     type_problems: &mut MutMap<ModuleId, Vec<TypeError>>,
-) -> Problems {
-    use crate::report::{can_problem, type_problem, Report, RocDocAllocator, DEFAULT_PALETTE};
-    use roc_problem::Severity::*;
-    let palette = DEFAULT_PALETTE;
-
-    // This will often over-allocate total memory, but it means we definitely
-    // never need to re-allocate either the warnings or the errors vec!
-    let mut warnings = Vec::with_capacity(total_problems);
-    let mut errors = Vec::with_capacity(total_problems);
-
+    warning_levels: &WarningLevels,
+    mut f: impl for<'b> FnMut(Report<'b>, Option<LineColumnRegion>, &'b RocDocAllocator<'b>),
+) {
     for (home, (module_path, src)) in sources.iter() {
         let mut src_lines: Vec<&str> = Vec::new();
 
@@ -79,11 +204,65 @@ pub fn report_problems(
         let problems = can_problems.remove(home).unwrap_or_default();
 
         for problem in problems.into_iter() {
-            let report = can_problem(&alloc, &lines, module_path.clone(), problem);
+            let name = problem.warning_name();
+            let region = problem.region().map(|region| lines.convert_region(region));
+            let mut report = can_problem(&alloc, &lines, module_path.clone(), problem);
+            let severity = match warning_levels.severity(report.severity, name) {
+                Some(severity) => severity,
+                None => continue,
+            };
+            report.severity = severity;
+
+            f(report, region, &alloc);
+        }
+
+        let problems = type_problems.remove(home).unwrap_or_default();
+
+        for problem in problems {
+            let name = problem.warning_name();
+            let region = problem.region().map(|region| lines.convert_region(region));
+
+            if let Some(mut report) = type_problem(&alloc, &lines, module_path.clone(), problem) {
+                let severity = match warning_levels.severity(report.severity, name) {
+                    Some(severity) => severity,
+                    None => continue,
+                };
+                report.severity = severity;
+
+                f(report, region, &alloc);
+            }
+        }
+    }
+}
+
+pub fn report_problems(
+    total_problems: usize,
+    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    interns: &Interns,
+    can_problems: &mut MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
+    type_problems: &mut MutMap<ModuleId, Vec<TypeError>>,
+    warning_levels: &WarningLevels,
+) -> Problems {
+    use roc_problem::Severity::*;
+    let TerminalStyle { target, palette } = terminal_style();
+
+    // This will often over-allocate total memory, but it means we definitely
+    // never need to re-allocate either the warnings or the errors vec!
+    let mut warnings = Vec::with_capacity(total_problems);
+    let mut errors = Vec::with_capacity(total_problems);
+
+    for_each_report(
+        sources,
+        interns,
+        can_problems,
+        type_problems,
+        warning_levels,
+        |report, _, alloc| {
             let severity = report.severity;
+            let report = report.with_explain_footer(alloc);
             let mut buf = String::new();
 
-            report.render_color_terminal(&mut buf, &alloc, &palette);
+            report.render(target, &mut buf, alloc, &palette);
 
             match severity {
                 Warning => {
@@ -93,28 +272,8 @@ pub fn report_problems(
                     errors.push(buf);
                 }
             }
-        }
-
-        let problems = type_problems.remove(home).unwrap_or_default();
-
-        for problem in problems {
-            if let Some(report) = type_problem(&alloc, &lines, module_path.clone(), problem) {
-                let severity = report.severity;
-                let mut buf = String::new();
-
-                report.render_color_terminal(&mut buf, &alloc, &palette);
-
-                match severity {
-                    Warning => {
-                        warnings.push(buf);
-                    }
-                    RuntimeError => {
-                        errors.push(buf);
-                    }
-                }
-            }
-        }
-    }
+        },
+    );
 
     let problems_reported;
 
@@ -140,7 +299,7 @@ pub fn report_problems(
     // compiling it, as it lets you clearly see where the compiler
     // errors/warnings end and the program output begins.
     if problems_reported > 0 {
-        println!("{}\u{001B}[0m\n", Report::horizontal_rule(&palette));
+        println!("{}{}\n", Report::horizontal_rule(&palette), palette.reset);
     }
 
     Problems {
@@ -148,3 +307,75 @@ pub fn report_problems(
         warnings: warnings.len(),
     }
 }
+
+/// A standalone HTML page of the problems, for `roc check --output-html`. Unlike the terminal,
+/// which only shows warnings when there are no errors, the page shows both.
+pub fn report_problems_html(
+    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    interns: &Interns,
+    can_problems: &mut MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
+    type_problems: &mut MutMap<ModuleId, Vec<TypeError>>,
+    warning_levels: &WarningLevels,
+) -> String {
+    use crate::html::{report_page, HtmlReport};
+
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let mut reports = Vec::new();
+
+    for_each_report(
+        sources,
+        interns,
+        can_problems,
+        type_problems,
+        warning_levels,
+        |report, _, _| {
+            let path = report
+                .filename
+                .strip_prefix(&cwd)
+                .unwrap_or(&report.filename)
+                .display()
+                .to_string();
+            let severity = report.severity;
+            let title = report.title.clone();
+            let code = report.code();
+            let mut body = String::new();
+
+            report.render_html(&mut body);
+
+            reports.push(HtmlReport {
+                severity,
+                title,
+                code,
+                path,
+                body,
+            });
+        },
+    );
+
+    report_page(&reports)
+}
+
+/// Every problem which should be reported, given the warning levels, as owned [Diagnostic]s,
+/// e.g. for `roc check --json` or an editor to hold onto
+pub fn diagnostics(
+    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    interns: &Interns,
+    can_problems: &mut MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
+    type_problems: &mut MutMap<ModuleId, Vec<TypeError>>,
+    warning_levels: &WarningLevels,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for_each_report(
+        sources,
+        interns,
+        can_problems,
+        type_problems,
+        warning_levels,
+        |report, region, _| {
+            diagnostics.push(Diagnostic::from_report(&report, region));
+        },
+    );
+
+    diagnostics
+}
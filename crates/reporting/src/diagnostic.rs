@@ -0,0 +1,85 @@
+//! Reports as plain owned data. A [Report] borrows the allocator it was built with, which borrows
+//! the module's source and interns, so tools which hold onto problems, like editors, or which
+//! print them as JSON, use [Diagnostic]s instead.
+use crate::report::{CiWrite, Report};
+use roc_problem::Severity;
+use roc_region::all::LineColumnRegion;
+use serde::{Serialize, Serializer};
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    #[serde(serialize_with = "serialize_severity")]
+    pub severity: Severity,
+    pub title: String,
+    /// The code `roc explain` takes, like E0401, if this kind of problem has one
+    pub code: Option<&'static str>,
+    pub path: PathBuf,
+    /// Where the problem is, if it's about one place. Lines and columns are zero-based.
+    #[serde(serialize_with = "serialize_region")]
+    pub region: Option<LineColumnRegion>,
+    /// The report as plain text, without the header with the title and path
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn from_report(report: &Report<'_>, region: Option<LineColumnRegion>) -> Self {
+        let mut message = String::new();
+
+        report
+            .doc
+            .clone()
+            .1
+            .render_raw(70, &mut CiWrite::new(&mut message))
+            .expect("<buffer is not a utf-8 encoded string>");
+
+        Self {
+            severity: report.severity,
+            title: report.title.clone(),
+            code: report.code(),
+            path: report.filename.clone(),
+            region,
+            message,
+        }
+    }
+}
+
+fn serialize_severity<S: Serializer>(
+    severity: &Severity,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(match severity {
+        Severity::RuntimeError => "error",
+        Severity::Warning => "warning",
+    })
+}
+
+fn serialize_region<S: Serializer>(
+    region: &Option<LineColumnRegion>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    #[derive(Serialize)]
+    struct LineColumn {
+        line: u32,
+        column: u32,
+    }
+
+    #[derive(Serialize)]
+    struct Region {
+        start: LineColumn,
+        end: LineColumn,
+    }
+
+    region
+        .map(|region| Region {
+            start: LineColumn {
+                line: region.start.line,
+                column: region.start.column,
+            },
+            end: LineColumn {
+                line: region.end.line,
+                column: region.end.column,
+            },
+        })
+        .serialize(serializer)
+}
@@ -3,8 +3,8 @@ use roc_module::ident::{Ident, Lowercase, ModuleName};
 use roc_module::symbol::DERIVABLE_ABILITIES;
 use roc_problem::can::PrecedenceProblem::BothNonAssociative;
 use roc_problem::can::{
-    BadPattern, CycleEntry, ExtensionTypeKind, FloatErrorKind, IntErrorKind, Problem, RuntimeError,
-    ShadowKind,
+    BadPattern, CycleEntry, ExtensionTypeKind, FloatErrorKind, IntErrorKind, NameKind,
+    PatternSynonymProblem, Problem, RegexProblem, RuntimeError, ShadowKind,
 };
 use roc_problem::Severity;
 use roc_region::all::{LineColumn, LineColumnRegion, LineInfo, Loc, Region};
@@ -42,6 +42,7 @@ const OPAQUE_NOT_APPLIED: &str = "OPAQUE TYPE NOT APPLIED";
 const OPAQUE_OVER_APPLIED: &str = "OPAQUE TYPE APPLIED TO TOO MANY ARGS";
 const INVALID_EXTENSION_TYPE: &str = "INVALID_EXTENSION_TYPE";
 const ABILITY_HAS_TYPE_VARIABLES: &str = "ABILITY HAS TYPE VARIABLES";
+const MULTI_PARAMETER_ABILITY: &str = "MULTI-PARAMETER ABILITY";
 const HAS_CLAUSE_IS_NOT_AN_ABILITY: &str = "HAS CLAUSE IS NOT AN ABILITY";
 const ILLEGAL_HAS_CLAUSE: &str = "ILLEGAL HAS CLAUSE";
 const ABILITY_MEMBER_MISSING_HAS_CLAUSE: &str = "ABILITY MEMBER MISSING HAS CLAUSE";
@@ -59,6 +60,11 @@ const ABILITY_IMPLEMENTATION_NOT_IDENTIFIER: &str = "ABILITY IMPLEMENTATION NOT
 const DUPLICATE_IMPLEMENTATION: &str = "DUPLICATE IMPLEMENTATION";
 const UNNECESSARY_IMPLEMENTATIONS: &str = "UNNECESSARY IMPLEMENTATIONS";
 const INCOMPLETE_ABILITY_IMPLEMENTATION: &str = "INCOMPLETE ABILITY IMPLEMENTATION";
+const DEPRECATED_VALUE: &str = "DEPRECATED VALUE";
+const NAMING_CONVENTION: &str = "NAMING CONVENTION";
+const REBOUND_NAME: &str = "REBOUND NAME";
+const UNCONDITIONAL_CRASH: &str = "UNCONDITIONAL CRASH";
+const INVALID_PATTERN_SYNONYM: &str = "INVALID PATTERN SYNONYM";
 
 pub fn can_problem<'b>(
     alloc: &'b RocDocAllocator<'b>,
@@ -325,11 +331,17 @@ pub fn can_problem<'b>(
                 ]),
                 alloc.region(lines.convert_region(variable_region)),
                 alloc.reflow("Roc does not allow unused type parameters!"),
-                // TODO add link to this guide section
-                alloc.tip().append(alloc.reflow(
-                    "If you want an unused type parameter (a so-called \"phantom type\"), \
-                read the guide section on phantom values.",
-                )),
+                alloc.tip().append(alloc.concat([
+                    alloc.reflow(
+                        "If you want an unused type parameter (a so-called \"phantom type\"), make ",
+                    ),
+                    alloc.symbol_unqualified(alias),
+                    alloc.reflow(" an opaque type by defining it with "),
+                    alloc.keyword(":="),
+                    alloc.reflow(" instead of "),
+                    alloc.keyword(":"),
+                    alloc.reflow(". Opaque types can have phantom type parameters."),
+                ])),
             ]);
 
             title = UNUSED_ALIAS_PARAM.to_string();
@@ -648,6 +660,28 @@ pub fn can_problem<'b>(
             title = ABILITY_HAS_TYPE_VARIABLES.to_string();
         }
 
+        Problem::MultiParameterAbility {
+            name,
+            variables_region,
+        } => {
+            doc = alloc.stack([
+                alloc.concat([
+                    alloc.reflow("The definition of the "),
+                    alloc.symbol_unqualified(name),
+                    alloc.reflow(" ability is parameterized over several types:"),
+                ]),
+                alloc.region(lines.convert_region(variables_region)),
+                alloc.reflow("Abilities over more than one type are not supported yet."),
+                alloc.concat([
+                    alloc.tip(),
+                    alloc.reflow("An ability describes one type, the one its members name in a "),
+                    alloc.keyword("has"),
+                    alloc.reflow(" clause. Other types can be arguments of the members instead."),
+                ]),
+            ]);
+            title = MULTI_PARAMETER_ABILITY.to_string();
+        }
+
         Problem::HasClauseIsNotAbility {
             region: clause_region,
         } => {
@@ -1093,6 +1127,340 @@ pub fn can_problem<'b>(
             ]);
             title = "OVERAPPLIED CRASH".to_string();
         }
+        Problem::MisplacedReturn { region } => {
+            doc = alloc.stack([
+                alloc.concat([
+                    alloc.reflow("This "),
+                    alloc.keyword("return"),
+                    alloc.reflow(" can't leave a function from here:"),
+                ]),
+                alloc.region(lines.convert_region(region)),
+                alloc.concat([
+                    alloc.keyword("return"),
+                    alloc.reflow(" can only be used in a function body, where the rest of the body runs after it, and not in a "),
+                    alloc.keyword("when"),
+                    alloc.reflow(" guard or an "),
+                    alloc.keyword("if"),
+                    alloc.reflow(" condition after the first one."),
+                ]),
+            ]);
+            title = "MISPLACED RETURN".to_string();
+        }
+        Problem::MisplacedTry { region } => {
+            doc = alloc.stack([
+                alloc.concat([
+                    alloc.reflow("This "),
+                    alloc.keyword("?"),
+                    alloc.reflow(" can't return an "),
+                    alloc.tag("Err".into()),
+                    alloc.reflow(" from a function here:"),
+                ]),
+                alloc.region(lines.convert_region(region)),
+                alloc.concat([
+                    alloc.keyword("?"),
+                    alloc.reflow(" can only be used in a function body, where the rest of the body runs after it. Try giving what it unwraps its own definition, or use a "),
+                    alloc.keyword("when"),
+                    alloc.reflow(" instead."),
+                ]),
+            ]);
+            title = "MISPLACED TRY".to_string();
+        }
+        Problem::UnappliedDbg { region } => {
+            doc = alloc.stack([
+                alloc.concat([
+                    alloc.reflow("This "),
+                    alloc.keyword("dbg"),
+                    alloc.reflow(" doesn't have a value given to it:"),
+                ]),
+                alloc.region(lines.convert_region(region)),
+                alloc.concat([
+                    alloc.keyword("dbg"),
+                    alloc.reflow(" must be applied to the value it prints where it's used, like "),
+                    alloc.parser_suggestion("dbg x"),
+                    alloc.reflow(" or "),
+                    alloc.parser_suggestion("x |> dbg"),
+                    alloc.reflow(". It can't be passed around like a function."),
+                ]),
+            ]);
+            title = "UNAPPLIED DBG".to_string();
+        }
+        Problem::OverAppliedDbg { region } => {
+            doc = alloc.stack([
+                alloc.concat([
+                    alloc.reflow("This "),
+                    alloc.keyword("dbg"),
+                    alloc.reflow(" has too many values given to it:"),
+                ]),
+                alloc.region(lines.convert_region(region)),
+                alloc.concat([
+                    alloc.keyword("dbg"),
+                    alloc.reflow(" must be given the value to print, and optionally a string literal label before it, like "),
+                    alloc.parser_suggestion("dbg \"parsed\" x"),
+                    alloc.reflow("."),
+                ]),
+            ]);
+            title = "OVERAPPLIED DBG".to_string();
+        }
+        Problem::DeprecatedValue {
+            symbol,
+            region,
+            message,
+        } => {
+            let hint = if message.is_empty() {
+                alloc.reflow("Its module doesn't say what to use instead.")
+            } else {
+                alloc.string(message.into())
+            };
+
+            doc = alloc.stack([
+                alloc.concat([
+                    alloc.symbol_qualified(symbol),
+                    alloc.reflow(" is deprecated:"),
+                ]),
+                alloc.region(lines.convert_region(region)),
+                hint,
+            ]);
+            title = DEPRECATED_VALUE.to_string();
+        }
+        Problem::NamingConvention {
+            kind,
+            name,
+            region,
+            suggestion,
+        } => {
+            let (what, convention) = match kind {
+                NameKind::Def => ("definition", "camelCase"),
+                NameKind::Type => ("type", "PascalCase"),
+                NameKind::Tag => ("tag", "PascalCase"),
+                NameKind::Module => ("module", "the same as its path"),
+            };
+
+            let styled = |name: &str| match kind {
+                NameKind::Def => alloc.ident(name.into()),
+                NameKind::Type | NameKind::Tag => alloc.type_str(name),
+                NameKind::Module => alloc.module_name(name.into()),
+            };
+
+            let rename = match kind {
+                NameKind::Module => alloc.concat([
+                    alloc.reflow("Rename the module to "),
+                    styled(&suggestion),
+                    alloc.reflow(", or move the file so its path matches its name."),
+                ]),
+                _ => alloc.concat([
+                    alloc.reflow("Rename it to "),
+                    styled(&suggestion),
+                    alloc.reflow(" to follow the convention."),
+                ]),
+            };
+
+            doc = alloc.stack([
+                alloc.concat([
+                    alloc.reflow("The name of this "),
+                    alloc.reflow(what),
+                    alloc.reflow(", "),
+                    styled(&name),
+                    alloc.reflow(", isn't "),
+                    alloc.reflow(convention),
+                    alloc.reflow(":"),
+                ]),
+                alloc.region(lines.convert_region(region)),
+                rename,
+            ]);
+            title = NAMING_CONVENTION.to_string();
+        }
+        Problem::Rebinding {
+            original_region,
+            shadow,
+        } => {
+            doc = alloc.stack([
+                alloc
+                    .text("The ")
+                    .append(alloc.ident(shadow.value))
+                    .append(alloc.reflow(" name is first defined here:")),
+                alloc.region(lines.convert_region(original_region)),
+                alloc.reflow("But then it's given a new value here:"),
+                alloc.region(lines.convert_region(shadow.region)),
+                alloc.reflow(
+                    "Code after the second definition can only see the new value, so it's easy to use the wrong one on accident.",
+                ),
+                alloc.tip().append(alloc.concat([
+                    alloc.reflow("To allow rebinding names like this, pass "),
+                    alloc.keyword("--allow shadowing"),
+                    alloc.reflow(", or add "),
+                    alloc.keyword("shadowing = \"allow\""),
+                    alloc.reflow(" to the [warnings] table of roc.toml."),
+                ])),
+            ]);
+            title = REBOUND_NAME.to_string();
+        }
+        Problem::UnconditionalCrash {
+            function,
+            region: _,
+            crashes,
+        } => {
+            let mut stack = vec![alloc.concat([
+                alloc.reflow("Every call to "),
+                alloc.symbol_unqualified(function),
+                alloc.reflow(" crashes, because it always reaches "),
+                if crashes.len() == 1 {
+                    alloc.reflow("this crash:")
+                } else {
+                    alloc.reflow("one of these crashes:")
+                },
+            ])];
+
+            stack.extend(
+                crashes
+                    .into_iter()
+                    .map(|crash_region| alloc.region(lines.convert_region(crash_region))),
+            );
+
+            stack.push(alloc.concat([
+                alloc.symbol_unqualified(function),
+                alloc.reflow(" is exposed, so other modules can call it. If it isn't finished yet, finish it before they do; otherwise, consider returning a "),
+                alloc.type_str("Result"),
+                alloc.reflow(" instead of crashing."),
+            ]));
+
+            doc = alloc.stack(stack);
+            title = UNCONDITIONAL_CRASH.to_string();
+        }
+        Problem::InvalidPatternSynonym {
+            synonym,
+            region,
+            problem,
+        } => {
+            let hint = match problem {
+                PatternSynonymProblem::ArgumentNotAName => alloc.concat([
+                    alloc.reflow("This argument isn't a name. The arguments of a pattern synonym are the names its pattern binds, like the "),
+                    alloc.ident("x".into()),
+                    alloc.reflow(" in "),
+                    alloc.parser_suggestion("pattern Ok2 x = Ok (Ok x)"),
+                    alloc.reflow("."),
+                ]),
+                PatternSynonymProblem::NotAnArgument(name) => alloc.concat([
+                    alloc.reflow("This binds "),
+                    alloc.ident(name.as_str().into()),
+                    alloc.reflow(", which isn't one of its arguments. Use "),
+                    alloc.keyword("_"),
+                    alloc.reflow(" to match anything without binding it, or make "),
+                    alloc.ident(name.as_str().into()),
+                    alloc.reflow(" an argument."),
+                ]),
+                PatternSynonymProblem::UnusedArgument(name) => alloc.concat([
+                    alloc.reflow("The argument "),
+                    alloc.ident(name.as_str().into()),
+                    alloc.reflow(" is never bound by its pattern, so matching on it wouldn't give it a value."),
+                ]),
+                PatternSynonymProblem::DuplicateArgument(name) => alloc.concat([
+                    alloc.reflow("The argument "),
+                    alloc.ident(name.as_str().into()),
+                    alloc.reflow(" is bound more than once by its pattern, but each argument can only be bound once."),
+                ]),
+                PatternSynonymProblem::UnsupportedPattern => alloc.concat([
+                    alloc.reflow("A pattern synonym can only stand for tags, its arguments, and "),
+                    alloc.keyword("_"),
+                    alloc.reflow("."),
+                ]),
+            };
+
+            doc = alloc.stack([
+                alloc.concat([
+                    alloc.reflow("There's a problem with the "),
+                    alloc.symbol_unqualified(synonym),
+                    alloc.reflow(" pattern synonym:"),
+                ]),
+                alloc.region(lines.convert_region(region)),
+                hint,
+            ]);
+            title = INVALID_PATTERN_SYNONYM.to_string();
+        }
+        Problem::PatternSynonymArity {
+            synonym,
+            region,
+            expected,
+            found,
+        } => {
+            let expected_arguments = if expected == 1 {
+                alloc.reflow("1 argument")
+            } else {
+                alloc
+                    .text(expected.to_string())
+                    .append(alloc.reflow(" arguments"))
+            };
+
+            doc = alloc.stack([
+                alloc.concat([
+                    alloc.reflow("The "),
+                    alloc.symbol_unqualified(synonym),
+                    alloc.reflow(" pattern synonym expects "),
+                    expected_arguments,
+                    alloc.reflow(", but it got "),
+                    alloc.text(found.to_string()),
+                    alloc.reflow(" instead:"),
+                ]),
+                alloc.region(lines.convert_region(region)),
+                alloc.reflow("Are there missing parentheses?"),
+            ]);
+
+            title = if found > expected {
+                "TOO MANY PATTERN SYNONYM ARGUMENTS".to_string()
+            } else {
+                "TOO FEW PATTERN SYNONYM ARGUMENTS".to_string()
+            };
+        }
+        Problem::RegexPatternNotLiteral { function, region } => {
+            doc = alloc.stack([
+                alloc.concat([
+                    alloc.reflow("This "),
+                    alloc.symbol_qualified(function),
+                    alloc.reflow(" doesn't have a string literal pattern:"),
+                ]),
+                alloc.region(lines.convert_region(region)),
+                alloc.concat([
+                    alloc.symbol_qualified(function),
+                    alloc.reflow(" compiles its pattern when the program is built, so it must be called with the pattern written right there, like "),
+                    alloc.parser_suggestion("Str.matches str \"[a-z]+\""),
+                    alloc.reflow(". It can't be passed around like a function."),
+                ]),
+            ]);
+            title = "PATTERN NOT A LITERAL".to_string();
+        }
+        Problem::InvalidRegex { region, problem } => {
+            let hint = match problem {
+                RegexProblem::UnclosedGroup => alloc.reflow("It has a ( without a ) to close it."),
+                RegexProblem::UnmatchedParen => alloc.reflow("It has a ) without a ( before it."),
+                RegexProblem::UnclosedClass => alloc.reflow("It has a [ without a ] to close it."),
+                RegexProblem::NothingToRepeat => alloc.reflow("It has a *, +, ?, or { with nothing before it to repeat. Put a \\ before it to match it literally."),
+                RegexProblem::InvalidRepetition => alloc.concat([
+                    alloc.reflow("It has a repetition which isn't written like "),
+                    alloc.parser_suggestion("{n}"),
+                    alloc.reflow(", "),
+                    alloc.parser_suggestion("{n,}"),
+                    alloc.reflow(", or "),
+                    alloc.parser_suggestion("{n,m}"),
+                    alloc.reflow(" with n no bigger than m."),
+                ]),
+                RegexProblem::BackwardsRange => alloc.reflow("It has a range of characters which ends before it starts."),
+                RegexProblem::TrailingBackslash => alloc.reflow("It ends with a \\ which has nothing after it to escape."),
+                RegexProblem::UnknownEscape(c) => alloc.concat([
+                    alloc.reflow("It has a \\ before "),
+                    alloc.string(c.to_string()),
+                    alloc.reflow(", which doesn't mean anything escaped."),
+                ]),
+                RegexProblem::Anchor => alloc.reflow("It has a ^ or $, but patterns always match the whole string, so those aren't needed. Put a \\ before it to match it literally."),
+                RegexProblem::NonAsciiClass => alloc.reflow("It has a range of non-ASCII characters, or a non-ASCII character in a [^...] class, which aren't supported yet."),
+                RegexProblem::TooBig => alloc.reflow("It would be too big once compiled. Try using smaller repetitions like {n,m}."),
+            };
+
+            doc = alloc.stack([
+                alloc.reflow("This pattern can't be compiled:"),
+                alloc.region(lines.convert_region(region)),
+                hint,
+            ]);
+            title = "INVALID PATTERN".to_string();
+        }
     };
 
     Report {
@@ -16,6 +16,7 @@ pub struct Renderer<'a> {
     arena: &'a Bump,
     alloc: RocDocAllocator<'a>,
     filename: PathBuf,
+    source: &'a str,
     line_info: LineInfo,
     render_target: RenderTarget,
 }
@@ -39,6 +40,7 @@ impl<'a> Renderer<'a> {
             alloc,
             line_info,
             filename,
+            source,
             render_target,
         }
     }
@@ -170,13 +172,22 @@ impl<'a> Renderer<'a> {
         write!(writer, "{}", buf)
     }
 
+    /// The source text of the given region.
+    fn source_of(&self, region: Region) -> &'a str {
+        &self.source[region.start().offset as usize..region.end().offset as usize]
+    }
+
+    /// Renders a `dbg` as `[file line:col] label: source : Type = value`.
     #[allow(clippy::too_many_arguments)]
     pub fn render_dbg<W>(
         &self,
         writer: &mut W,
+        subs: &mut Subs,
         expressions: &[Expr<'_>],
+        variables: &[Variable],
         expect_region: Option<Region>,
         dbg_expr_region: Region,
+        label_region: Option<Region>,
     ) -> std::io::Result<()>
     where
         W: std::io::Write,
@@ -190,6 +201,21 @@ impl<'a> Renderer<'a> {
             line_col_region.start.column + 1
         )?;
 
+        if let Some(label_region) = label_region {
+            write!(
+                writer,
+                "{}: ",
+                self.source_of(label_region).trim_matches('"')
+            )?;
+        }
+
+        let error_type = subs.var_to_error_type(variables[0], Polarity::OF_VALUE);
+        let mut type_buf = String::new();
+        self.render_expr(error_type)
+            .1
+            .render_raw(70, &mut crate::report::CiWrite::new(&mut type_buf))
+            .expect("<buffer is not a utf-8 encoded string>");
+
         let expr = expressions[0];
 
         let mut buf = roc_fmt::Buf::new_in(self.arena);
@@ -198,7 +224,13 @@ impl<'a> Renderer<'a> {
             expr.format(&mut buf, 0);
         }
 
-        writeln!(writer, "{}", buf.as_str())
+        writeln!(
+            writer,
+            "{} : {} = {}",
+            self.source_of(dbg_expr_region),
+            type_buf,
+            buf.as_str()
+        )
     }
 
     pub fn render_panic<W>(
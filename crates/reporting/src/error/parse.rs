@@ -171,6 +171,8 @@ enum Node {
     StringFormat,
     Dbg,
     Expect,
+    Return,
+    Bench,
 }
 
 fn to_expr_report<'a>(
@@ -400,6 +402,22 @@ fn to_expr_report<'a>(
                     Node::ListElement => (pos, alloc.text("a list")),
                     Node::Dbg => (pos, alloc.text("a dbg statement")),
                     Node::Expect => (pos, alloc.text("an expect statement")),
+                    Node::Return => (
+                        pos,
+                        alloc.concat([
+                            alloc.text("a "),
+                            alloc.keyword("return"),
+                            alloc.text(" expression"),
+                        ]),
+                    ),
+                    Node::Bench => (
+                        pos,
+                        alloc.concat([
+                            alloc.text("a "),
+                            alloc.keyword("bench"),
+                            alloc.text(" block"),
+                        ]),
+                    ),
                     Node::RecordConditionalDefault => (pos, alloc.text("record field default")),
                     Node::StringFormat => (pos, alloc.text("a string format")),
                     Node::InsideParens => (pos, alloc.text("some parentheses")),
@@ -621,6 +639,12 @@ fn to_expr_report<'a>(
         EExpr::Dbg(e_expect, _position) => {
             to_dbg_or_expect_report(alloc, lines, filename, context, Node::Dbg, e_expect, start)
         }
+        EExpr::Return(e_return, _position) => {
+            to_return_report(alloc, lines, filename, context, e_return, start)
+        }
+        EExpr::Bench(e_bench, _position) => {
+            to_bench_report(alloc, lines, filename, context, e_bench, start)
+        }
         _ => todo!("unhandled parse error: {:?}", parse_problem),
     }
 }
@@ -1356,6 +1380,104 @@ fn to_dbg_or_expect_report<'a>(
     }
 }
 
+fn to_return_report<'a>(
+    alloc: &'a RocDocAllocator<'a>,
+    lines: &LineInfo,
+    filename: PathBuf,
+    context: Context,
+    parse_problem: &roc_parse::parser::EReturn<'a>,
+    start: Position,
+) -> Report<'a> {
+    use roc_parse::parser::EReturn;
+
+    match parse_problem {
+        EReturn::Space(error, pos) => to_space_report(alloc, lines, filename, error, *pos),
+
+        EReturn::Return(_) => unreachable!("another branch would be taken"),
+
+        EReturn::Value(e_expr, value_start) => {
+            let context = Context::InNode(Node::Return, start, Box::new(context));
+            to_expr_report(alloc, lines, filename, context, e_expr, *value_start)
+        }
+
+        EReturn::IndentValue(pos) => {
+            let surroundings = Region::new(start, *pos);
+            let region = LineColumnRegion::from_pos(lines.convert_pos(*pos));
+
+            let doc = alloc.stack([
+                alloc.concat([
+                    alloc.reflow(r"I was partway through parsing a "),
+                    alloc.keyword("return"),
+                    alloc.reflow(r" expression, but I got stuck here:"),
+                ]),
+                alloc.region_with_subregion(lines.convert_region(surroundings), region),
+                alloc.concat([
+                    alloc.reflow(r"I was expecting to see the value to return next, like "),
+                    alloc.parser_suggestion("return Err NotFound"),
+                    alloc.reflow(r". It has to be indented more than the "),
+                    alloc.keyword("return"),
+                    alloc.reflow(r" keyword if it's on another line."),
+                ]),
+            ]);
+
+            Report {
+                filename,
+                doc,
+                title: "UNFINISHED RETURN".to_string(),
+                severity: Severity::RuntimeError,
+            }
+        }
+    }
+}
+
+fn to_bench_report<'a>(
+    alloc: &'a RocDocAllocator<'a>,
+    lines: &LineInfo,
+    filename: PathBuf,
+    context: Context,
+    parse_problem: &roc_parse::parser::EBench<'a>,
+    start: Position,
+) -> Report<'a> {
+    use roc_parse::parser::EBench;
+
+    match parse_problem {
+        EBench::Space(error, pos) => to_space_report(alloc, lines, filename, error, *pos),
+
+        EBench::Bench(_) => unreachable!("another branch would be taken"),
+
+        EBench::Body(e_expr, body_start) => {
+            let context = Context::InNode(Node::Bench, start, Box::new(context));
+            to_expr_report(alloc, lines, filename, context, e_expr, *body_start)
+        }
+
+        EBench::IndentBody(pos) => {
+            let surroundings = Region::new(start, *pos);
+            let region = LineColumnRegion::from_pos(lines.convert_pos(*pos));
+
+            let doc = alloc.stack([
+                alloc.concat([
+                    alloc.reflow(r"I was partway through parsing a "),
+                    alloc.keyword("bench"),
+                    alloc.reflow(r" block, but I got stuck here:"),
+                ]),
+                alloc.region_with_subregion(lines.convert_region(surroundings), region),
+                alloc.concat([
+                    alloc.reflow(r"I was expecting to see the code to time next, like "),
+                    alloc.parser_suggestion("bench List.sortAsc bigList"),
+                    alloc.reflow(r". It has to be indented if it's on another line."),
+                ]),
+            ]);
+
+            Report {
+                filename,
+                doc,
+                title: "UNFINISHED BENCH".to_string(),
+                severity: Severity::RuntimeError,
+            }
+        }
+    }
+}
+
 fn to_if_report<'a>(
     alloc: &'a RocDocAllocator<'a>,
     lines: &LineInfo,
@@ -3325,6 +3447,309 @@ fn to_header_report<'a>(
         EHeader::GeneratesWith(generates_with, pos) => {
             to_generates_with_report(alloc, lines, filename, generates_with, *pos)
         }
+
+        EHeader::Allocator(allocator, pos) => {
+            to_allocator_report(alloc, lines, filename, allocator, *pos)
+        }
+
+        EHeader::Effects(effects, pos) => to_effects_report(alloc, lines, filename, effects, *pos),
+
+        EHeader::Hosts(hosts, pos) => to_hosts_report(alloc, lines, filename, hosts, *pos),
+
+        EHeader::Build(build, pos) => to_build_report(alloc, lines, filename, build, *pos),
+    }
+}
+
+fn to_allocator_report<'a>(
+    alloc: &'a RocDocAllocator<'a>,
+    lines: &LineInfo,
+    filename: PathBuf,
+    parse_problem: &roc_parse::parser::EAllocator,
+    start: Position,
+) -> Report<'a> {
+    use roc_parse::header::AllocatorStrategy;
+    use roc_parse::parser::EAllocator;
+
+    let stuck_here = |pos: Position| {
+        let surroundings = Region::new(start, pos);
+        let region = LineColumnRegion::from_pos(lines.convert_pos(pos));
+
+        alloc.region_with_subregion(lines.convert_region(surroundings), region)
+    };
+
+    let doc = match *parse_problem {
+        EAllocator::Strategy(pos) => alloc.stack([
+            alloc.reflow(
+                r"I am partway through parsing an allocator section, but I got stuck here:",
+            ),
+            stuck_here(pos),
+            alloc.concat([
+                alloc.reflow("The allocator "),
+                alloc.keyword("strategy"),
+                alloc.reflow(" must be one of these:"),
+            ]),
+            alloc.stack(
+                AllocatorStrategy::ALL
+                    .iter()
+                    .map(|strategy| alloc.parser_suggestion(strategy.as_str()).indent(4)),
+            ),
+        ]),
+
+        EAllocator::Alignment(pos) => alloc.stack([
+            alloc.reflow(
+                r"I am partway through parsing an allocator section, but I got stuck here:",
+            ),
+            stuck_here(pos),
+            alloc.concat([
+                alloc.reflow("The allocator "),
+                alloc.keyword("alignment"),
+                alloc.reflow(" must be a power of two number of bytes, like"),
+            ]),
+            alloc.parser_suggestion("alignment: 16").indent(4),
+        ]),
+
+        EAllocator::Allocator(pos) | EAllocator::ListStart(pos) => alloc.stack([
+            alloc.reflow(r"I am partway through parsing a header, but I got stuck here:"),
+            stuck_here(pos),
+            alloc.concat([
+                alloc.reflow("I am expecting the "),
+                alloc.keyword("allocator"),
+                alloc.reflow(" keyword next, followed by the allocator's fields, like"),
+            ]),
+            alloc
+                .parser_suggestion("allocator { strategy: arena, alignment: 16 }")
+                .indent(4),
+        ]),
+
+        EAllocator::Space(error, pos) => {
+            return to_space_report(alloc, lines, filename, &error, pos)
+        }
+
+        EAllocator::Open(pos)
+        | EAllocator::IndentAllocator(pos)
+        | EAllocator::IndentListStart(pos)
+        | EAllocator::IndentListEnd(pos)
+        | EAllocator::ListEnd(pos)
+        | EAllocator::Field(pos)
+        | EAllocator::Colon(pos)
+        | EAllocator::IndentValue(pos) => alloc.stack([
+            alloc.reflow(
+                r"I am partway through parsing an allocator section, but I got stuck here:",
+            ),
+            stuck_here(pos),
+            alloc.concat([
+                alloc.reflow("I was expecting a "),
+                alloc.keyword("strategy"),
+                alloc.reflow(" or an "),
+                alloc.keyword("alignment"),
+                alloc.reflow(" field next, like"),
+            ]),
+            alloc
+                .parser_suggestion("allocator { strategy: arena, alignment: 16 }")
+                .indent(4),
+        ]),
+    };
+
+    Report {
+        filename,
+        doc,
+        title: "WEIRD ALLOCATOR".to_string(),
+        severity: Severity::RuntimeError,
+    }
+}
+
+fn to_effects_report<'a>(
+    alloc: &'a RocDocAllocator<'a>,
+    lines: &LineInfo,
+    filename: PathBuf,
+    parse_problem: &roc_parse::parser::EEffects,
+    start: Position,
+) -> Report<'a> {
+    use roc_parse::header::EffectSubmission;
+    use roc_parse::parser::EEffects;
+
+    let stuck_here = |pos: Position| {
+        let surroundings = Region::new(start, pos);
+        let region = LineColumnRegion::from_pos(lines.convert_pos(pos));
+
+        alloc.region_with_subregion(lines.convert_region(surroundings), region)
+    };
+
+    let doc = match *parse_problem {
+        EEffects::Submission(pos) => alloc.stack([
+            alloc.reflow(r"I am partway through parsing an effects section, but I got stuck here:"),
+            stuck_here(pos),
+            alloc.concat([
+                alloc.reflow("The effects "),
+                alloc.keyword("submit"),
+                alloc.reflow(" field must be one of these:"),
+            ]),
+            alloc.stack(
+                EffectSubmission::ALL
+                    .iter()
+                    .map(|submission| alloc.parser_suggestion(submission.as_str()).indent(4)),
+            ),
+        ]),
+
+        EEffects::Effects(pos) | EEffects::ListStart(pos) => alloc.stack([
+            alloc.reflow(r"I am partway through parsing a header, but I got stuck here:"),
+            stuck_here(pos),
+            alloc.concat([
+                alloc.reflow("I am expecting the "),
+                alloc.keyword("effects"),
+                alloc.reflow(" keyword next, followed by how the host runs effects, like"),
+            ]),
+            alloc
+                .parser_suggestion("effects { submit: batch }")
+                .indent(4),
+        ]),
+
+        EEffects::Space(error, pos) => return to_space_report(alloc, lines, filename, &error, pos),
+
+        EEffects::Open(pos)
+        | EEffects::IndentEffects(pos)
+        | EEffects::IndentListStart(pos)
+        | EEffects::IndentListEnd(pos)
+        | EEffects::ListEnd(pos)
+        | EEffects::Field(pos)
+        | EEffects::Colon(pos)
+        | EEffects::IndentValue(pos) => alloc.stack([
+            alloc.reflow(r"I am partway through parsing an effects section, but I got stuck here:"),
+            stuck_here(pos),
+            alloc.concat([
+                alloc.reflow("I was expecting a "),
+                alloc.keyword("submit"),
+                alloc.reflow(" field next, like"),
+            ]),
+            alloc
+                .parser_suggestion("effects { submit: batch }")
+                .indent(4),
+        ]),
+    };
+
+    Report {
+        filename,
+        doc,
+        title: "WEIRD EFFECTS".to_string(),
+        severity: Severity::RuntimeError,
+    }
+}
+
+fn to_hosts_report<'a>(
+    alloc: &'a RocDocAllocator<'a>,
+    lines: &LineInfo,
+    filename: PathBuf,
+    parse_problem: &roc_parse::parser::EHosts,
+    start: Position,
+) -> Report<'a> {
+    use roc_parse::parser::EHosts;
+
+    let stuck_here = |pos: Position| {
+        let surroundings = Region::new(start, pos);
+        let region = LineColumnRegion::from_pos(lines.convert_pos(pos));
+
+        alloc.region_with_subregion(lines.convert_region(surroundings), region)
+    };
+
+    let example = r#"hosts { "linux-x86_64": "https://example.com/8yJ9Yq7mgf1A1J6CN1ViOwKt9oFQpP3W1Z3YWJ2EtzXQ.tar.br" }"#;
+
+    let doc = match *parse_problem {
+        EHosts::Hosts(pos) | EHosts::ListStart(pos) => alloc.stack([
+            alloc.reflow(r"I am partway through parsing a header, but I got stuck here:"),
+            stuck_here(pos),
+            alloc.concat([
+                alloc.reflow("I am expecting the "),
+                alloc.keyword("hosts"),
+                alloc.reflow(" keyword next, followed by where to download the host prebuilt for each target, like"),
+            ]),
+            alloc.parser_suggestion(example).indent(4),
+        ]),
+
+        EHosts::Url(pos) | EHosts::IndentUrl(pos) => alloc.stack([
+            alloc.reflow(r"I am partway through parsing a hosts section, but I got stuck here:"),
+            stuck_here(pos),
+            alloc.reflow("I was expecting the URL of the prebuilt host next, as a string, like"),
+            alloc.parser_suggestion(example).indent(4),
+        ]),
+
+        EHosts::Space(error, pos) => return to_space_report(alloc, lines, filename, &error, pos),
+
+        EHosts::Open(pos)
+        | EHosts::IndentHosts(pos)
+        | EHosts::IndentListStart(pos)
+        | EHosts::IndentListEnd(pos)
+        | EHosts::ListEnd(pos)
+        | EHosts::Target(pos)
+        | EHosts::Colon(pos) => alloc.stack([
+            alloc.reflow(r"I am partway through parsing a hosts section, but I got stuck here:"),
+            stuck_here(pos),
+            alloc.reflow("I was expecting a target as a string, then a colon, then the URL of the host prebuilt for that target, like"),
+            alloc.parser_suggestion(example).indent(4),
+        ]),
+    };
+
+    Report {
+        filename,
+        doc,
+        title: "WEIRD HOSTS".to_string(),
+        severity: Severity::RuntimeError,
+    }
+}
+
+fn to_build_report<'a>(
+    alloc: &'a RocDocAllocator<'a>,
+    lines: &LineInfo,
+    filename: PathBuf,
+    parse_problem: &roc_parse::parser::EBuild,
+    start: Position,
+) -> Report<'a> {
+    use roc_parse::parser::EBuild;
+
+    let stuck_here = |pos: Position| {
+        let surroundings = Region::new(start, pos);
+        let region = LineColumnRegion::from_pos(lines.convert_pos(pos));
+
+        alloc.region_with_subregion(lines.convert_region(surroundings), region)
+    };
+
+    let example = r#"build "zig build -Doptimize=ReleaseFast""#;
+
+    let doc = match *parse_problem {
+        EBuild::Build(pos) => alloc.stack([
+            alloc.reflow(r"I am partway through parsing a header, but I got stuck here:"),
+            stuck_here(pos),
+            alloc.concat([
+                alloc.reflow("I am expecting the "),
+                alloc.keyword("build"),
+                alloc.reflow(" keyword next, followed by the command which builds the host, like"),
+            ]),
+            alloc.parser_suggestion(example).indent(4),
+        ]),
+
+        EBuild::Escapes(pos) => alloc.stack([
+            alloc.reflow(r"I am partway through parsing a build command, but I got stuck here:"),
+            stuck_here(pos),
+            alloc.reflow("The command is run as it is written, so it can't have escapes or interpolation in it."),
+        ]),
+
+        EBuild::Space(error, pos) => return to_space_report(alloc, lines, filename, &error, pos),
+
+        EBuild::Open(pos)
+        | EBuild::IndentBuild(pos)
+        | EBuild::IndentCommand(pos)
+        | EBuild::Command(pos) => alloc.stack([
+            alloc.reflow(r"I am partway through parsing a build command, but I got stuck here:"),
+            stuck_here(pos),
+            alloc.reflow("I was expecting the command which builds the host next, as a single-line string, like"),
+            alloc.parser_suggestion(example).indent(4),
+        ]),
+    };
+
+    Report {
+        filename,
+        doc,
+        title: "WEIRD BUILD COMMAND".to_string(),
+        severity: Severity::RuntimeError,
     }
 }
 
@@ -23,6 +23,7 @@ use roc_types::types::{
     RecordField, TypeExt,
 };
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use ven_pretty::DocAllocator;
 
 const ADD_ANNOTATIONS: &str = r#"Can more type annotations be added? Type annotations always help me give more specific messages, and I think they could help a lot in this case"#;
@@ -60,7 +61,7 @@ pub fn type_problem<'b>(
         BadPattern(region, category, found, expected) => Some(to_pattern_report(
             alloc, lines, filename, severity, region, category, found, expected,
         )),
-        CircularType(region, symbol, overall_type) => Some(to_circular_report(
+        CircularType(region, symbol, overall_type, recursive_type) => Some(to_circular_report(
             alloc,
             lines,
             filename,
@@ -68,6 +69,7 @@ pub fn type_problem<'b>(
             region,
             symbol,
             overall_type,
+            recursive_type,
         )),
         UnexposedLookup(symbol) => {
             let title = "UNRECOGNIZED NAME".to_string();
@@ -207,6 +209,30 @@ pub fn type_problem<'b>(
                 severity,
             })
         }
+        AmbiguousNumberDefault { region, default } => {
+            let stack = [
+                alloc.reflow("I could not figure out the type of this number:"),
+                alloc.region(lines.convert_region(region)),
+                alloc.concat([
+                    alloc.reflow("Nothing in the annotation of the surrounding definition decides it, so it will default to "),
+                    alloc.type_str(default),
+                    alloc.reflow("."),
+                ]),
+                alloc.concat([
+                    alloc.tip(),
+                    alloc.reflow("Add a type suffix like "),
+                    alloc.parser_suggestion("5u8"),
+                    alloc.reflow(", or an annotation on an intermediate definition, to make the type explicit."),
+                ]),
+            ];
+
+            Some(Report {
+                title: "AMBIGUOUS NUMBER TYPE".to_string(),
+                filename,
+                doc: alloc.stack(stack),
+                severity,
+            })
+        }
     }
 }
 
@@ -406,6 +432,13 @@ fn underivable_hint<'b>(
                     alloc.reflow("?"),
                 ])))
             }
+            NotDerivableDecode::TagUnion => {
+                Some(alloc.note("").append(alloc.concat([
+                    alloc.reflow("Decoding to a tag union is not supported yet. Consider decoding to a record or a "),
+                    alloc.type_str("Str"),
+                    alloc.reflow(", then converting that to the tag union."),
+                ])))
+            }
         },
         NotDerivableContext::Eq(reason) => match reason {
             NotDerivableEq::FloatingPoint => {
@@ -2067,6 +2100,40 @@ fn add_pattern_category<'b>(
     alloc.concat([i_am_trying_to_match, rest])
 }
 
+/// How many times the `∞` in a circular type is unfolded in reports, unless set otherwise with
+/// [set_circular_type_unfold_steps].
+pub const DEFAULT_CIRCULAR_TYPE_UNFOLD_STEPS: usize = 2;
+
+/// How many times the `∞` in a circular type is unfolded in reports, for the whole process
+static CIRCULAR_TYPE_UNFOLD_STEPS: AtomicUsize =
+    AtomicUsize::new(DEFAULT_CIRCULAR_TYPE_UNFOLD_STEPS);
+
+/// Unfold the `∞` in circular types this many times in reports from now on. With 0, reports
+/// don't show an unfolded type at all.
+pub fn set_circular_type_unfold_steps(steps: usize) {
+    CIRCULAR_TYPE_UNFOLD_STEPS.store(steps, Ordering::Relaxed);
+}
+
+/// How many times the `∞` in a circular type is currently unfolded in reports
+pub fn circular_type_unfold_steps() -> usize {
+    CIRCULAR_TYPE_UNFOLD_STEPS.load(Ordering::Relaxed)
+}
+
+/// Unfolds a circular type `steps` times, by substituting the self-referential part of the type
+/// for each `∞` in it.
+pub fn unfold_circular_type(
+    mut overall_type: ErrorType,
+    recursive_type: &ErrorType,
+    steps: usize,
+) -> ErrorType {
+    for _ in 0..steps {
+        overall_type.replace_infinite(recursive_type);
+    }
+
+    overall_type
+}
+
+#[allow(clippy::too_many_arguments)]
 fn to_circular_report<'b>(
     alloc: &'b RocDocAllocator<'b>,
     lines: &LineInfo,
@@ -2075,27 +2142,56 @@ fn to_circular_report<'b>(
     region: roc_region::all::Region,
     symbol: Symbol,
     overall_type: ErrorType,
+    recursive_type: ErrorType,
 ) -> Report<'b> {
+    let steps = alloc.circular_type_unfold_steps;
+    let unfolded_type = unfold_circular_type(overall_type.clone(), &recursive_type, steps);
+
+    let mut doc = vec![
+        alloc
+            .reflow("I'm inferring a weird self-referential type for ")
+            .append(alloc.symbol_unqualified(symbol))
+            .append(alloc.text(":")),
+        alloc.region(lines.convert_region(region)),
+        alloc.stack([
+            alloc.reflow(
+                "Here is my best effort at writing down the type. \
+                You will see ∞ for parts of the type that repeat \
+                something already printed out infinitely.",
+            ),
+            alloc.type_block(to_doc(alloc, Parens::Unnecessary, overall_type).0),
+        ]),
+        alloc.stack([
+            alloc.reflow("The ∞ stands for this part of the type, which contains itself:"),
+            alloc.type_block(to_doc(alloc, Parens::Unnecessary, recursive_type).0),
+        ]),
+    ];
+
+    let unfolding = match steps {
+        0 => None,
+        1 => Some("Unfolding the ∞ once, the type looks like:"),
+        2 => Some("Unfolding the ∞ a couple of times, the type looks like:"),
+        _ => Some("Unfolding the ∞ a few times, the type looks like:"),
+    };
+
+    if let Some(unfolding) = unfolding {
+        doc.push(alloc.stack([
+            alloc.reflow(unfolding),
+            alloc.type_block(to_doc(alloc, Parens::Unnecessary, unfolded_type).0),
+        ]));
+    }
+
+    doc.push(alloc.concat([
+        alloc.tip(),
+        alloc.reflow("A type can only refer to itself through a tag union, like "),
+        alloc.parser_suggestion("LinkedList a : [Nil, Cons a (LinkedList a)]"),
+        alloc.reflow(". Maybe you need to wrap part of this value in a tag, or in an opaque type around one?"),
+    ]));
+
     Report {
         title: "CIRCULAR TYPE".to_string(),
         filename,
-        doc: {
-            alloc.stack([
-                alloc
-                    .reflow("I'm inferring a weird self-referential type for ")
-                    .append(alloc.symbol_unqualified(symbol))
-                    .append(alloc.text(":")),
-                alloc.region(lines.convert_region(region)),
-                alloc.stack([
-                    alloc.reflow(
-                        "Here is my best effort at writing down the type. \
-                        You will see ∞ for parts of the type that repeat \
-                        something already printed out infinitely.",
-                    ),
-                    alloc.type_block(to_doc(alloc, Parens::Unnecessary, overall_type).0),
-                ]),
-            ])
-        },
+        doc: alloc.stack(doc),
         severity,
     }
 }
@@ -2180,6 +2276,14 @@ pub mod suggest {
 
         options
     }
+
+    /// Whether `option` is similar enough to `typo` to be worth suggesting. Sorting alone
+    /// always produces a "nearest" option, even when none of them look anything like the typo.
+    pub fn is_close(typo: &str, option: &str) -> bool {
+        let longest = typo.chars().count().max(option.chars().count());
+
+        distance::damerau_levenshtein(typo, option) <= (longest / 3).max(1)
+    }
 }
 
 pub struct Comparison<'b> {
@@ -2492,6 +2596,7 @@ fn to_doc_help<'b>(
                     .map(|(k, v)| (alloc.tag_name(k), v))
                     .collect(),
                 tag_ext_to_doc(alloc, pol, gen_usages, ext),
+                0, // zero tags omitted, since this isn't a diff
             )
         }
 
@@ -3504,10 +3609,24 @@ fn diff_tag_union<'b>(
             able.into_iter().flatten().collect(),
         )
     };
-    let shared_keys = fields1
+    let mut shared_keys = fields1
         .clone()
         .intersection_with(fields2.clone(), |v1, v2| (v1, v2));
 
+    // In large unions, leave out the tags that are the same on both sides, so that the
+    // differences stand out.
+    let mut same_tags_same_types = 0;
+    if fields1.len().max(fields2.len()) >= OMITTED_ENTRIES_COUNT_THRESHOLD {
+        shared_keys.retain(|_, (args1, args2)| {
+            if args1 == args2 {
+                same_tags_same_types += 1;
+                false
+            } else {
+                true
+            }
+        });
+    }
+
     let left_keys = fields1.clone().relative_complement(fields2.clone());
     let right_keys = fields2.clone().relative_complement(fields1.clone());
 
@@ -3584,8 +3703,8 @@ fn diff_tag_union<'b>(
         .map(|(_, a, b)| (a, b))
         .collect();
 
-    let doc1 = report_text::tag_union(alloc, lefts, ext_diff.left);
-    let doc2 = report_text::tag_union(alloc, rights, ext_diff.right);
+    let doc1 = report_text::tag_union(alloc, lefts, ext_diff.left, same_tags_same_types);
+    let doc2 = report_text::tag_union(alloc, rights, ext_diff.right, same_tags_same_types);
 
     fields_diff.status.merge(status);
 
@@ -3768,7 +3887,8 @@ mod report_text {
             } else {
                 alloc
                     .text("{ ")
-                    .append(alloc.ellipsis().append(alloc.text(" }")))
+                    .append(omitted(alloc, fields_omitted, "fields"))
+                    .append(alloc.text(" }"))
             }
             .append(ext_doc)
         } else if entries.len() == 1 {
@@ -3779,7 +3899,9 @@ mod report_text {
                 .append(if fields_omitted == 0 {
                     alloc.text("")
                 } else {
-                    alloc.text(", ").append(alloc.ellipsis())
+                    alloc
+                        .text(", ")
+                        .append(omitted(alloc, fields_omitted, "fields"))
                 })
                 .append(alloc.text(" }"))
                 .append(ext_doc)
@@ -3788,7 +3910,7 @@ mod report_text {
                 alloc.reflow("}")
             } else {
                 alloc.vcat([
-                    alloc.ellipsis().indent(super::RECORD_FIELD_INDENT),
+                    omitted(alloc, fields_omitted, "fields").indent(super::RECORD_FIELD_INDENT),
                     alloc.reflow("}"),
                 ])
             };
@@ -3945,6 +4067,7 @@ mod report_text {
         alloc: &'b RocDocAllocator<'b>,
         entries: Vec<(RocDocBuilder<'b>, Vec<RocDocBuilder<'b>>)>,
         opt_ext: Option<RocDocBuilder<'b>>,
+        tags_omitted: usize,
     ) -> RocDocBuilder<'b> {
         let ext_doc = if let Some(t) = opt_ext {
             t
@@ -3952,8 +4075,18 @@ mod report_text {
             alloc.nil()
         };
 
+        let omitted_doc = if tags_omitted == 0 {
+            alloc.nil()
+        } else if entries.is_empty() {
+            omitted(alloc, tags_omitted, "tags")
+        } else {
+            alloc
+                .reflow(", ")
+                .append(omitted(alloc, tags_omitted, "tags"))
+        };
+
         if entries.is_empty() {
-            alloc.text("[]")
+            alloc.text("[").append(omitted_doc).append(alloc.text("]"))
         } else {
             let entry_to_doc = |(tag_name, arguments): (RocDocBuilder<'b>, Vec<_>)| {
                 if arguments.is_empty() {
@@ -3975,7 +4108,25 @@ mod report_text {
                     .map(|(entry, start)| start.append(entry_to_doc(entry))),
             );
 
-            entries_doc.append(alloc.reflow("]")).append(ext_doc)
+            entries_doc
+                .append(omitted_doc)
+                .append(alloc.reflow("]"))
+                .append(ext_doc)
+        }
+    }
+
+    /// An ellipsis standing for entries left out of a diff because they match on both sides.
+    fn omitted<'b>(
+        alloc: &'b RocDocAllocator<'b>,
+        count: usize,
+        entry_kind: &str,
+    ) -> RocDocBuilder<'b> {
+        if count < super::OMITTED_ENTRIES_COUNT_THRESHOLD {
+            alloc.ellipsis()
+        } else {
+            alloc
+                .ellipsis()
+                .append(alloc.text(format!(" {} matching {} omitted", count, entry_kind)))
         }
     }
 
@@ -4092,6 +4243,9 @@ fn type_problem_to_pretty<'b>(
 
             match suggestions.get(0) {
                 None => alloc.nil(),
+                Some(nearest) if !suggest::is_close(typo.as_str(), nearest.as_str()) => {
+                    alloc.tip().append(alloc.reflow(ADD_ANNOTATIONS))
+                }
                 Some(nearest) => {
                     let typo_str = format!("{}", typo);
                     let nearest_str = format!("{}", nearest);
@@ -4144,6 +4298,9 @@ fn type_problem_to_pretty<'b>(
 
             match suggestions.get(0) {
                 None => alloc.nil(),
+                Some(nearest) if !suggest::is_close(&typo_str, nearest.as_str()) => {
+                    alloc.tip().append(alloc.reflow(ADD_ANNOTATIONS))
+                }
                 Some(nearest) => {
                     let nearest_str = format!("{}", nearest);
 
@@ -4754,6 +4911,8 @@ fn exhaustive_pattern_to_doc<'b>(
 
 const AFTER_TAG_INDENT: &str = "    ";
 const RECORD_FIELD_INDENT: usize = 4;
+/// When at least this many matching fields or tags are left out of a diff, say how many.
+const OMITTED_ENTRIES_COUNT_THRESHOLD: usize = 5;
 
 fn pattern_to_doc_help<'b>(
     alloc: &'b RocDocAllocator<'b>,
@@ -0,0 +1,217 @@
+//! Stable codes for every kind of report, and the longer explanations `roc explain` prints
+//! for them.
+//!
+//! A report's code is looked up by its title, so renaming a report's title means updating its
+//! entry here. Codes are never renumbered or reused.
+
+/// Every report title, with its code. A code can have more than one title, e.g. singular and
+/// plural forms of the same report.
+pub const CODES: [(&str, &str); 160] = [
+    ("E0101", "SYNTAX PROBLEM"),
+    ("E0102", "PARSE PROBLEM"),
+    ("E0103", "RECORD PARSE PROBLEM"),
+    ("E0104", "MISSING HEADER"),
+    ("E0105", "INCOMPLETE HEADER"),
+    ("E0106", "WEIRD MODULE NAME"),
+    ("E0107", "WEIRD APP NAME"),
+    ("E0108", "INVALID PACKAGE NAME"),
+    ("E0109", "INVALID PLATFORM NAME"),
+    ("E0110", "WEIRD EXPOSES"),
+    ("E0111", "WEIRD IMPORTS"),
+    ("E0112", "WEIRD PROVIDES"),
+    ("E0113", "WEIRD GENERATES"),
+    ("E0114", "WEIRD GENERATED TYPE NAME"),
+    ("E0115", "MISSING PACKAGES"),
+    ("E0116", "MISSING REQUIRES"),
+    ("E0117", "BAD REQUIRES"),
+    ("E0118", "BAD REQUIRES RIGIDS"),
+    ("E0119", "NOT END OF FILE"),
+    ("E0120", "END OF FILE"),
+    ("E0121", "TAB CHARACTER"),
+    ("E0122", "NEED MORE INDENTATION"),
+    ("E0123", "INDENT ENDS AFTER EXPRESSION"),
+    ("E0124", "INSUFFICIENT INDENT IN MULTI-LINE STRING"),
+    ("E0125", "ARGUMENTS BEFORE EQUALS"),
+    ("E0126", "MISSING EXPRESSION"),
+    ("E0127", "MISSING FINAL EXPRESSION"),
+    ("E0128", "MISSING ARROW"),
+    ("E0129", "WEIRD ARROW"),
+    ("E0130", "UNEXPECTED ARROW"),
+    ("E0131", "BAD BACKPASSING ARROW"),
+    ("E0132", "UNKNOWN OPERATOR"),
+    ("E0133", "DOUBLE DOT"),
+    ("E0134", "TRAILING DOT"),
+    ("E0135", "DOUBLE COMMA"),
+    ("E0136", "EMPTY PARENTHESES"),
+    ("E0137", "UNFINISHED PARENTHESES"),
+    ("E0138", "UNFINISHED LIST"),
+    ("E0139", "UNFINISHED LIST PATTERN"),
+    ("E0140", "UNFINISHED PATTERN"),
+    ("E0141", "UNFINISHED RECORD PATTERN"),
+    ("E0142", "PROBLEM IN RECORD PATTERN"),
+    ("E0143", "INCORRECT REST PATTERN"),
+    ("E0144", "MULTIPLE LIST REST PATTERNS"),
+    ("E0145", "UNFINISHED RECORD TYPE"),
+    ("E0146", "PROBLEM IN RECORD TYPE"),
+    ("E0147", "UNFINISHED TAG UNION TYPE"),
+    ("E0148", "UNFINISHED TYPE"),
+    ("E0149", "BAD TYPE VARIABLE"),
+    ("E0150", "TYPE ARGUMENT NOT LOWERCASE"),
+    ("E0151", "UNFINISHED INLINE ALIAS"),
+    ("E0152", "NOT AN INLINE ALIAS"),
+    ("E0153", "QUALIFIED ALIAS NAME"),
+    ("E0154", "UNFINISHED FUNCTION"),
+    ("E0155", "UNFINISHED ARGUMENT LIST"),
+    ("E0156", "UNFINISHED IF"),
+    ("E0157", "UNFINISHED WHEN"),
+    ("E0158", "IF GUARD NO CONDITION"),
+    ("E0159", "UNFINISHED ABILITY"),
+    ("E0160", "WEIRD IDENTIFIER"),
+    ("E0161", "WEIRD QUALIFIED NAME"),
+    ("E0162", "WEIRD TAG NAME"),
+    ("E0163", "ENDLESS STRING"),
+    ("E0164", "ENDLESS FORMAT"),
+    ("E0165", "ENDLESS SCALAR"),
+    ("E0166", "EXPECTED STRING"),
+    ("E0167", "INVALID SCALAR"),
+    ("E0168", "WEIRD ESCAPE"),
+    ("E0169", "WEIRD CODE POINT"),
+    ("E0170", "INVALID UNICODE"),
+    ("E0171", "INVALID NUMBER LITERAL"),
+    ("E0172", "WEIRD ALLOCATOR"),
+    ("E0173", "WEIRD EFFECTS"),
+    ("E0174", "WEIRD HOSTS"),
+    ("E0175", "WEIRD BUILD COMMAND"),
+    ("E0201", "UNRECOGNIZED NAME"),
+    ("E0202", "DUPLICATE NAME"),
+    ("E0203", "REBOUND NAME"),
+    ("E0204", "NAMING PROBLEM"),
+    ("E0205", "NAMING CONVENTION"),
+    ("E0206", "NOT EXPOSED"),
+    ("E0207", "MODULE NOT IMPORTED"),
+    ("E0208", "MISSING DEFINITION"),
+    ("E0209", "UNUSED DEFINITION"),
+    ("E0210", "UNUSED ARGUMENT"),
+    ("E0211", "UNUSED IMPORT"),
+    ("E0212", "DEFINITION ONLY USED IN RECURSION"),
+    ("E0212", "DEFINITIONs ONLY USED IN RECURSION"),
+    ("E0213", "CIRCULAR DEFINITION"),
+    ("E0214", "UNNECESSARY DEFINITION"),
+    ("E0215", "DUPLICATE FIELD NAME"),
+    ("E0216", "DUPLICATE TAG NAME"),
+    ("E0217", "BAD OPTIONAL VALUE"),
+    ("E0218", "NAME NOT BOUND IN ALL PATTERNS"),
+    ("E0219", "DEGENERATE BRANCH"),
+    ("E0220", "CONFLICTING NUMBER SUFFIX"),
+    ("E0221", "NUMBER OVERFLOWS SUFFIX"),
+    ("E0222", "NUMBER UNDERFLOWS SUFFIX"),
+    ("E0223", "UNAPPLIED CRASH"),
+    ("E0224", "OVERAPPLIED CRASH"),
+    ("E0225", "UNCONDITIONAL CRASH"),
+    ("E0226", "DEPRECATED VALUE"),
+    ("E0227", "UNKNOWN GENERATES FUNCTION"),
+    ("E0228", "MISPLACED RETURN"),
+    ("E0229", "MISPLACED TRY"),
+    ("E0230", "UNAPPLIED DBG"),
+    ("E0231", "OVERAPPLIED DBG"),
+    ("E0232", "INVALID PATTERN SYNONYM"),
+    ("E0233", "TOO FEW PATTERN SYNONYM ARGUMENTS"),
+    ("E0234", "TOO MANY PATTERN SYNONYM ARGUMENTS"),
+    ("E0235", "PATTERN NOT A LITERAL"),
+    ("E0236", "INVALID PATTERN"),
+    ("E0301", "CYCLIC ALIAS"),
+    ("E0302", "NESTED DATATYPE"),
+    ("E0303", "UNUSED TYPE ALIAS PARAMETER"),
+    ("E0304", "UNBOUND TYPE VARIABLE"),
+    ("E0305", "TOO FEW TYPE ARGUMENTS"),
+    ("E0306", "TOO MANY TYPE ARGUMENTS"),
+    ("E0307", "INVALID_EXTENSION_TYPE"),
+    ("E0308", "OPAQUE TYPE NOT DEFINED"),
+    ("E0309", "OPAQUE TYPE DECLARED OUTSIDE SCOPE"),
+    ("E0310", "OPAQUE TYPE NOT APPLIED"),
+    ("E0311", "OPAQUE TYPE APPLIED TO TOO MANY ARGS"),
+    ("E0312", "ABILITY HAS TYPE VARIABLES"),
+    ("E0313", "HAS CLAUSE IS NOT AN ABILITY"),
+    ("E0314", "ILLEGAL HAS CLAUSE"),
+    ("E0315", "ABILITY MEMBER MISSING HAS CLAUSE"),
+    ("E0316", "ABILITY MEMBER BINDS MULTIPLE VARIABLES"),
+    ("E0317", "ABILITY NOT ON TOP-LEVEL"),
+    ("E0318", "SPECIALIZATION NOT ON TOP-LEVEL"),
+    ("E0319", "ABILITY USED AS TYPE"),
+    ("E0320", "DUPLICATE BOUND ABILITY"),
+    ("E0321", "ILLEGAL DERIVE"),
+    ("E0322", "IMPLEMENTATION NOT FOUND"),
+    ("E0323", "NOT AN ABILITY MEMBER"),
+    ("E0324", "NOT AN ABILITY"),
+    ("E0325", "OPTIONAL ABILITY IMPLEMENTATION"),
+    ("E0326", "QUALIFIED ABILITY IMPLEMENTATION"),
+    ("E0327", "ABILITY IMPLEMENTATION NOT IDENTIFIER"),
+    ("E0328", "DUPLICATE IMPLEMENTATION"),
+    ("E0329", "UNNECESSARY IMPLEMENTATIONS"),
+    ("E0330", "INCOMPLETE ABILITY IMPLEMENTATION"),
+    ("E0331", "ILLEGAL SPECIALIZATION"),
+    ("E0332", "WRONG SPECIALIZATION TYPE"),
+    ("E0333", "OVERLOADED SPECIALIZATION"),
+    ("E0334", "MULTI-PARAMETER ABILITY"),
+    ("E0401", "TYPE MISMATCH"),
+    ("E0402", "CIRCULAR TYPE"),
+    ("E0403", "TOO MANY ARGS"),
+    ("E0404", "TOO FEW ARGS"),
+    ("E0405", "AMBIGUOUS NUMBER TYPE"),
+    ("E0406", "UNSAFE PATTERN"),
+    ("E0407", "REDUNDANT PATTERN"),
+    ("E0408", "UNMATCHABLE PATTERN"),
+    ("E0409", "UNNECESSARY WILDCARD"),
+    ("E0501", "FILE NOT FOUND"),
+    ("E0502", "FILE PERMISSION DENIED"),
+    ("E0503", "FILE PROBLEM"),
+    ("E0504", "IMPORT CYCLE"),
+    ("E0505", "INCORRECT MODULE NAME"),
+    ("E0506", "NO PLATFORM"),
+    ("E0601", "EXPECT FAILED"),
+    ("E0602", "EXPECT PANICKED"),
+];
+
+const EXPLANATIONS: &str = include_str!("explanations.md");
+
+/// The code of the reports with this title, if it has one
+pub fn code_for_title(title: &str) -> Option<&'static str> {
+    CODES
+        .iter()
+        .find(|(_, known)| *known == title)
+        .map(|(code, _)| *code)
+}
+
+/// Every code, in order, each with the title its explanation is headed with
+pub fn codes() -> impl Iterator<Item = (&'static str, &'static str)> {
+    EXPLANATIONS.lines().filter_map(|line| {
+        line.strip_prefix("## ")
+            .and_then(|heading| heading.split_once(' '))
+    })
+}
+
+/// Turn the code as written by the user into the form the explanations use, so `e201`, `E201`,
+/// and `E0201` all find the same one.
+pub fn normalize_code(code: &str) -> Option<String> {
+    let digits = code.trim().strip_prefix(['E', 'e'])?;
+
+    if digits.is_empty() || digits.len() > 4 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    Some(format!("E{:0>4}", digits))
+}
+
+/// The explanation for a code, as Markdown, starting with its heading
+pub fn explanation(code: &str) -> Option<&'static str> {
+    let code = normalize_code(code)?;
+    let heading = format!("## {} ", code);
+    let start = EXPLANATIONS.find(&heading)?;
+    let section = &EXPLANATIONS[start..];
+
+    let end = section[heading.len()..]
+        .find("\n## ")
+        .map_or(section.len(), |end| heading.len() + end);
+
+    Some(section[..end].trim_end())
+}
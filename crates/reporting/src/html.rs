@@ -0,0 +1,131 @@
+//! Standalone HTML pages of reports, like the one `roc check --output-html` writes, e.g. to keep
+//! as a CI artifact. Pages have no external resources: filtering by severity is done in CSS.
+use crate::report::escape_html;
+use roc_problem::Severity;
+
+/// A report, rendered for a page
+#[derive(Clone, Debug)]
+pub struct HtmlReport {
+    pub severity: Severity,
+    pub title: String,
+    pub code: Option<&'static str>,
+    /// The path of the file the report is about, as it should be shown
+    pub path: String,
+    /// The report's text as HTML, to go in a `<pre>`
+    pub body: String,
+}
+
+const STYLE: &str = r#"
+body { font-family: system-ui, sans-serif; margin: 2em auto; max-width: 60em; padding: 0 1em; color: #222; }
+h1 { font-size: 1.5em; }
+nav ol { padding-left: 1.5em; }
+nav a, h2 a { color: inherit; }
+label { margin-right: 1.5em; }
+section { border-left: 4px solid; margin: 1.5em 0; padding: 0 1em; }
+section.error-item { border-color: #c62828; }
+section.warning-item { border-color: #f9a825; }
+section h2 { font-size: 1.1em; margin-bottom: 0.2em; }
+.severity { font-size: 0.8em; font-weight: normal; text-transform: uppercase; }
+.code { font-family: monospace; font-size: 0.9em; font-weight: normal; color: #666; }
+.path { font-family: monospace; color: #666; margin: 0; }
+pre { background: #f6f6f6; padding: 1em; overflow-x: auto; }
+.keyword, .module, .binop, .structure { color: #2e7d32; }
+.symbol { color: #1565c0; }
+.type-variable, .alias, .opaque, .typo, .typo-suggestion, .parser-suggestion { color: #8e24aa; }
+.error { color: #c62828; }
+.line-number, .gutter-bar { color: #00838f; }
+.emphasized { font-weight: bold; }
+.url, .tip { text-decoration: underline; }
+#show-errors:not(:checked) ~ * .error-item, #show-warnings:not(:checked) ~ * .warning-item { display: none; }
+"#;
+
+fn severity_class(severity: Severity) -> &'static str {
+    match severity {
+        Severity::RuntimeError => "error",
+        Severity::Warning => "warning",
+    }
+}
+
+fn plural(count: usize, singular: &str, plural: &str) -> String {
+    format!("{} {}", count, if count == 1 { singular } else { plural })
+}
+
+/// A standalone page of the reports, errors first, each with an anchor to link to
+pub fn report_page(reports: &[HtmlReport]) -> String {
+    let mut reports: Vec<&HtmlReport> = reports.iter().collect();
+
+    // a stable sort, so reports stay in the order they were found otherwise
+    reports.sort_by_key(|report| match report.severity {
+        Severity::RuntimeError => 0,
+        Severity::Warning => 1,
+    });
+
+    let errors = reports
+        .iter()
+        .filter(|report| matches!(report.severity, Severity::RuntimeError))
+        .count();
+    let warnings = reports.len() - errors;
+    let summary = format!(
+        "{} and {}",
+        plural(errors, "error", "errors"),
+        plural(warnings, "warning", "warnings")
+    );
+
+    let mut page = String::new();
+
+    page.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    page.push_str(&format!("<title>roc check: {}</title>\n", summary));
+    page.push_str(&format!("<style>{}</style>\n</head>\n<body>\n", STYLE));
+    page.push_str(&format!("<h1>{} found</h1>\n", summary));
+
+    page.push_str("<input type=\"checkbox\" id=\"show-errors\" checked>\n");
+    page.push_str(&format!(
+        "<label for=\"show-errors\">Show {}</label>\n",
+        plural(errors, "error", "errors")
+    ));
+    page.push_str("<input type=\"checkbox\" id=\"show-warnings\" checked>\n");
+    page.push_str(&format!(
+        "<label for=\"show-warnings\">Show {}</label>\n",
+        plural(warnings, "warning", "warnings")
+    ));
+
+    page.push_str("<nav>\n<ol>\n");
+
+    for (index, report) in reports.iter().enumerate() {
+        page.push_str(&format!(
+            "<li class=\"{}-item\"><a href=\"#problem-{}\">{}</a> <span class=\"path\">{}</span></li>\n",
+            severity_class(report.severity),
+            index + 1,
+            escape_html(&report.title),
+            escape_html(&report.path),
+        ));
+    }
+
+    page.push_str("</ol>\n</nav>\n<main>\n");
+
+    for (index, report) in reports.iter().enumerate() {
+        let severity = severity_class(report.severity);
+        let code = match report.code {
+            Some(code) => format!(" <span class=\"code\">{}</span>", code),
+            None => String::new(),
+        };
+
+        page.push_str(&format!(
+            "<section class=\"{severity}-item\" id=\"problem-{number}\">\n\
+             <h2><span class=\"severity\">{severity}</span> <a href=\"#problem-{number}\">{title}</a>{code}</h2>\n\
+             <p class=\"path\">{path}</p>\n\
+             <pre>{body}</pre>\n\
+             </section>\n",
+            severity = severity,
+            number = index + 1,
+            title = escape_html(&report.title),
+            code = code,
+            path = escape_html(&report.path),
+            body = report.body,
+        ));
+    }
+
+    page.push_str("</main>\n</body>\n</html>\n");
+
+    page
+}
@@ -4,5 +4,9 @@
 #![allow(clippy::large_enum_variant)]
 
 pub mod cli;
+pub mod diagnostic;
 pub mod error;
+pub mod explain;
+pub mod html;
+pub mod locale;
 pub mod report;
@@ -0,0 +1,86 @@
+//! Translations of the prose in reports into languages other than English.
+//!
+//! Reports are written in English, and each locale has a table, in `locales/`, mapping pieces of
+//! that English text to their translations. Text is translated where it's turned into a document,
+//! by [RocDocAllocator::reflow](crate::report::RocDocAllocator) and report titles, so code
+//! excerpts, names, and types are never touched. Text a table doesn't have stays in English.
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Locale {
+    #[default]
+    English,
+    Spanish,
+}
+
+/// The locale reports are rendered in, for the whole process
+static CURRENT: AtomicU8 = AtomicU8::new(Locale::English as u8);
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::English, Locale::Spanish];
+
+    /// The language tag of the locale, like `es`
+    pub fn tag(self) -> &'static str {
+        match self {
+            Locale::English => "en",
+            Locale::Spanish => "es",
+        }
+    }
+
+    /// The locale for a language tag, like `es`, `es-MX`, or `es_ES.UTF-8`. Only the language
+    /// is used; all regions of a language get the same translations.
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let language = tag
+            .split(|c| c == '-' || c == '_' || c == '.')
+            .next()
+            .unwrap_or_default();
+
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|locale| locale.tag().eq_ignore_ascii_case(language))
+    }
+
+    fn table(self) -> &'static str {
+        match self {
+            Locale::English => "",
+            Locale::Spanish => include_str!("locales/es.txt"),
+        }
+    }
+
+    /// The translations of this locale, as `(english, translated)` pairs
+    pub fn translations(self) -> impl Iterator<Item = (&'static str, &'static str)> {
+        self.table().lines().filter_map(|line| {
+            let (english, translated) = line
+                .strip_prefix('"')?
+                .strip_suffix('"')?
+                .split_once("\" = \"")?;
+
+            Some((english, translated))
+        })
+    }
+
+    /// The translation of a piece of English text, if this locale has one
+    pub fn translate(self, english: &str) -> Option<&'static str> {
+        self.translations()
+            .find(|(known, _)| *known == english)
+            .map(|(_, translated)| translated)
+    }
+}
+
+/// Render reports in this locale from now on
+pub fn set_locale(locale: Locale) {
+    CURRENT.store(locale as u8, Ordering::Relaxed);
+}
+
+/// The locale reports are currently rendered in
+pub fn current_locale() -> Locale {
+    let current = CURRENT.load(Ordering::Relaxed);
+
+    Locale::ALL
+        .iter()
+        .copied()
+        .find(|locale| *locale as u8 == current)
+        .unwrap_or_default()
+}
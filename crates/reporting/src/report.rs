@@ -1,3 +1,5 @@
+use crate::error::r#type::circular_type_unfold_steps;
+use crate::locale::{current_locale, Locale};
 use roc_module::ident::Ident;
 use roc_module::ident::{Lowercase, ModuleName, TagName, Uppercase};
 use roc_module::symbol::{Interns, ModuleId, PQModuleName, PackageQualified, Symbol};
@@ -63,7 +65,7 @@ pub fn cycle<'b>(
 const HEADER_WIDTH: usize = 80;
 
 pub fn pretty_header(title: &str) -> String {
-    let title_width = title.len() + 4;
+    let title_width = title.chars().count() + 4;
     let header = format!("── {} {}", title, "─".repeat(HEADER_WIDTH - title_width));
     header
 }
@@ -77,7 +79,7 @@ pub fn pretty_header_with_path(title: &str, path: &Path) -> String {
     .to_str()
     .unwrap();
 
-    let title_width = title.len() + 4;
+    let title_width = title.chars().count() + 4;
     let relative_path_width = relative_path.len() + 3;
     let available_path_width = HEADER_WIDTH - title_width - 1;
 
@@ -103,6 +105,11 @@ pub fn pretty_header_with_path(title: &str, path: &Path) -> String {
 #[derive(Clone, Copy, Debug)]
 pub enum RenderTarget {
     ColorTerminal,
+    /// A color terminal which understands OSC 8 hyperlinks, so that headers and line numbers
+    /// can link to the file (and line) they refer to
+    ColorTerminalWithLinks,
+    /// HTML, with a `<span>` for each annotation whose class says what it is, like `keyword`
+    Html,
     Generic,
 }
 
@@ -118,18 +125,33 @@ impl<'b> Report<'b> {
     pub fn render(
         self,
         target: RenderTarget,
-        buf: &'b mut String,
+        buf: &mut String,
         alloc: &'b RocDocAllocator<'b>,
-        palette: &'b Palette,
+        palette: &Palette,
     ) {
         match target {
             RenderTarget::Generic => self.render_ci(buf, alloc),
             RenderTarget::ColorTerminal => self.render_color_terminal(buf, alloc, palette),
+            RenderTarget::ColorTerminalWithLinks => {
+                self.render_color_terminal_with_links(buf, alloc, palette)
+            }
+            RenderTarget::Html => self.render_html(buf),
         }
     }
 
+    /// Render to HTML, without the header, since pages show the title and file in their own way.
+    /// The result belongs in a `<pre>`.
+    pub fn render_html(self, buf: &mut String) {
+        let err_msg = "<buffer is not a utf-8 encoded string>";
+
+        self.doc
+            .1
+            .render_raw(70, &mut HtmlWrite::new(buf))
+            .expect(err_msg);
+    }
+
     /// Render to CI console output, where no colors are available.
-    pub fn render_ci(self, buf: &'b mut String, alloc: &'b RocDocAllocator<'b>) {
+    pub fn render_ci(self, buf: &mut String, alloc: &'b RocDocAllocator<'b>) {
         let err_msg = "<buffer is not a utf-8 encoded string>";
 
         self.pretty(alloc)
@@ -144,7 +166,7 @@ impl<'b> Report<'b> {
         self,
         buf: &mut String,
         alloc: &'b RocDocAllocator<'b>,
-        palette: &'b Palette,
+        palette: &Palette,
     ) {
         let err_msg = "<buffer is not a utf-8 encoded string>";
 
@@ -154,21 +176,61 @@ impl<'b> Report<'b> {
             .expect(err_msg);
     }
 
+    /// Render to a color terminal, with hyperlinks from the header and line numbers to the file
+    pub fn render_color_terminal_with_links(
+        self,
+        buf: &mut String,
+        alloc: &'b RocDocAllocator<'b>,
+        palette: &Palette,
+    ) {
+        let err_msg = "<buffer is not a utf-8 encoded string>";
+        let file_url = file_url(&self.filename);
+
+        self.pretty(alloc)
+            .1
+            .render_raw(70, &mut ColorWrite::with_links(palette, buf, file_url))
+            .expect(err_msg);
+    }
+
     pub fn pretty(self, alloc: &'b RocDocAllocator<'b>) -> RocDocBuilder<'b> {
         if self.title.is_empty() {
             self.doc
         } else {
+            let title = alloc.localized(&self.title);
             let header = if self.filename == PathBuf::from("") {
-                crate::report::pretty_header(&self.title)
+                crate::report::pretty_header(title)
             } else {
-                crate::report::pretty_header_with_path(&self.title, &self.filename)
+                crate::report::pretty_header_with_path(title, &self.filename)
             };
 
             alloc.stack([alloc.text(header).annotate(Annotation::Header), self.doc])
         }
     }
 
-    pub fn horizontal_rule(palette: &'b Palette) -> String {
+    /// The stable code of this kind of report, which `roc explain` takes
+    pub fn code(&self) -> Option<&'static str> {
+        crate::explain::code_for_title(&self.title)
+    }
+
+    /// Add a footer pointing to the longer explanation of this kind of report, if it has one
+    pub fn with_explain_footer(mut self, alloc: &'b RocDocAllocator<'b>) -> Self {
+        if let Some(code) = self.code() {
+            self.doc = alloc.stack([
+                self.doc,
+                alloc.concat([
+                    alloc.reflow("Run "),
+                    alloc
+                        .text(format!("roc explain {}", code))
+                        .annotate(Annotation::Keyword),
+                    alloc.reflow(" for more about this."),
+                ]),
+            ]);
+        }
+
+        self
+    }
+
+    pub fn horizontal_rule(palette: &Palette) -> String {
         format!("{}{}", palette.header, "─".repeat(80))
     }
 }
@@ -231,8 +293,28 @@ const fn default_palette_from_style_codes(codes: StyleCodes) -> Palette {
     }
 }
 
+/// Like the default palette, but readable on light backgrounds: plain text uses the terminal's
+/// own foreground color rather than white, and magenta takes the place of yellow.
+const fn light_palette_from_style_codes(codes: StyleCodes) -> Palette {
+    Palette {
+        primary: codes.color_reset,
+        code_block: codes.color_reset,
+        type_variable: codes.magenta,
+        alias: codes.magenta,
+        opaque: codes.magenta,
+        typo: codes.magenta,
+        typo_suggestion: codes.magenta,
+        parser_suggestion: codes.magenta,
+        ..default_palette_from_style_codes(codes)
+    }
+}
+
 pub const DEFAULT_PALETTE: Palette = default_palette_from_style_codes(ANSI_STYLE_CODES);
 
+pub const LIGHT_PALETTE: Palette = light_palette_from_style_codes(ANSI_STYLE_CODES);
+
+pub const PLAIN_PALETTE: Palette = default_palette_from_style_codes(PLAIN_STYLE_CODES);
+
 pub const DEFAULT_PALETTE_HTML: Palette = default_palette_from_style_codes(HTML_STYLE_CODES);
 
 /// A machine-readable format for text styles (colors and other styles)
@@ -264,6 +346,21 @@ pub const ANSI_STYLE_CODES: StyleCodes = StyleCodes {
     color_reset: "\u{1b}[39m",
 };
 
+/// No styles at all, for output that isn't going to a terminal
+pub const PLAIN_STYLE_CODES: StyleCodes = StyleCodes {
+    red: "",
+    green: "",
+    yellow: "",
+    blue: "",
+    magenta: "",
+    cyan: "",
+    white: "",
+    bold: "",
+    underline: "",
+    reset: "",
+    color_reset: "",
+};
+
 macro_rules! html_color {
     ($name: expr) => {
         concat!("<span class='color-", $name, "'>")
@@ -290,6 +387,9 @@ pub struct RocDocAllocator<'a> {
     pub src_lines: &'a [&'a str],
     pub home: ModuleId,
     pub interns: &'a Interns,
+    pub locale: Locale,
+    /// How many times the `∞` in a circular type is unfolded
+    pub circular_type_unfold_steps: usize,
 }
 
 pub type RocDocBuilder<'b> = DocBuilder<'b, RocDocAllocator<'b>, Annotation>;
@@ -317,6 +417,18 @@ where
     ) -> <Self::Doc as ven_pretty::DocPtr<'a, A>>::WidthFn {
         self.upstream.alloc_width_fn(f)
     }
+
+    /// Like the default, but in the allocator's locale, if it has a translation of the text
+    fn reflow(&'a self, text: &'a str) -> DocBuilder<'a, Self, A>
+    where
+        Self: Sized,
+        Self::Doc: Clone,
+        A: Clone,
+    {
+        let text = self.locale.translate(text).unwrap_or(text);
+
+        self.intersperse(text.split(char::is_whitespace), self.line().group())
+    }
 }
 
 impl<'a> RocDocAllocator<'a> {
@@ -326,9 +438,16 @@ impl<'a> RocDocAllocator<'a> {
             home,
             src_lines,
             interns,
+            locale: current_locale(),
+            circular_type_unfold_steps: circular_type_unfold_steps(),
         }
     }
 
+    /// The text in the allocator's locale, if it has a translation of it
+    pub fn localized<'s>(&self, english: &'s str) -> &'s str {
+        self.locale.translate(english).unwrap_or(english)
+    }
+
     /// vertical concatenation. Adds a newline between elements
     pub fn vcat<A, I>(&'a self, docs: I) -> DocBuilder<'a, Self, A>
     where
@@ -503,21 +622,21 @@ impl<'a> RocDocAllocator<'a> {
     }
 
     pub fn tip(&'a self) -> DocBuilder<'a, Self, Annotation> {
-        self.text("Tip")
+        self.text(self.localized("Tip"))
             .annotate(Annotation::Tip)
             .append(":")
             .append(self.softline())
     }
 
     pub fn note(&'a self, line: &'a str) -> DocBuilder<'a, Self, Annotation> {
-        self.text("Note")
+        self.text(self.localized("Note"))
             .annotate(Annotation::Tip)
             .append(": ")
             .append(line)
     }
 
     pub fn hint(&'a self, line: &'a str) -> DocBuilder<'a, Self, Annotation> {
-        self.text("Hint")
+        self.text(self.localized("Hint"))
             .annotate(Annotation::Tip)
             .append(": ")
             .append(line)
@@ -871,6 +990,10 @@ pub struct ColorWrite<'a, W> {
     style_stack: Vec<Annotation>,
     palette: &'a Palette,
     upstream: W,
+    /// The file to link headers and line numbers to, if links are wanted
+    file_url: Option<String>,
+    /// The text of the header or line number being written, which becomes a link once it ends
+    link_text: Option<String>,
 }
 
 impl<'a, W> ColorWrite<'a, W> {
@@ -879,8 +1002,32 @@ impl<'a, W> ColorWrite<'a, W> {
             style_stack: vec![],
             palette,
             upstream,
+            file_url: None,
+            link_text: None,
         }
     }
+
+    pub fn with_links(palette: &'a Palette, upstream: W, file_url: String) -> ColorWrite<'a, W> {
+        ColorWrite {
+            file_url: Some(file_url),
+            ..Self::new(palette, upstream)
+        }
+    }
+}
+
+/// A `file://` URL for the file, for terminals to open when its hyperlinks are clicked
+pub fn file_url(path: &Path) -> String {
+    let path = match std::env::current_dir() {
+        Ok(cwd) if path.is_relative() => cwd.join(path),
+        _ => path.to_path_buf(),
+    };
+
+    format!("file://{}", path.display()).replace(' ', "%20")
+}
+
+/// The OSC 8 escape sequences which make the text a hyperlink to the URL
+fn hyperlink(url: &str, text: &str) -> String {
+    format!("\u{1b}]8;;{}\u{1b}\\{}\u{1b}]8;;\u{1b}\\", url, text)
 }
 
 impl<W> Render for CiWrite<W>
@@ -898,6 +1045,101 @@ where
     }
 }
 
+/// Render to HTML, escaping the text
+pub struct HtmlWrite<W> {
+    upstream: W,
+}
+
+impl<W> HtmlWrite<W> {
+    pub fn new(upstream: W) -> HtmlWrite<W> {
+        HtmlWrite { upstream }
+    }
+}
+
+/// The text, with the characters which mean something in HTML escaped
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+impl Annotation {
+    /// The class of the `<span>` for this annotation in HTML
+    pub fn html_class(&self) -> &'static str {
+        use Annotation::*;
+
+        match self {
+            Emphasized => "emphasized",
+            Url => "url",
+            Keyword => "keyword",
+            Ellipsis => "ellipsis",
+            Tag => "tag",
+            RecordField => "record-field",
+            TupleElem => "tuple-elem",
+            TypeVariable => "type-variable",
+            Alias => "alias",
+            Opaque => "opaque",
+            Structure => "structure",
+            Symbol => "symbol",
+            BinOp => "binop",
+            Error => "error",
+            GutterBar => "gutter-bar",
+            LineNumber => "line-number",
+            PlainText => "plain-text",
+            CodeBlock => "code-block",
+            TypeBlock => "type-block",
+            InlineTypeBlock => "inline-type-block",
+            Module => "module",
+            Typo => "typo",
+            TypoSuggestion => "typo-suggestion",
+            Tip => "tip",
+            Header => "header",
+            ParserSuggestion => "parser-suggestion",
+        }
+    }
+}
+
+impl<W> Render for HtmlWrite<W>
+where
+    W: fmt::Write,
+{
+    type Error = fmt::Error;
+
+    fn write_str(&mut self, s: &str) -> Result<usize, fmt::Error> {
+        self.write_str_all(s).map(|_| s.len())
+    }
+
+    fn write_str_all(&mut self, s: &str) -> fmt::Result {
+        self.upstream.write_str(&escape_html(s))
+    }
+}
+
+impl<W> RenderAnnotated<Annotation> for HtmlWrite<W>
+where
+    W: fmt::Write,
+{
+    fn push_annotation(&mut self, annotation: &Annotation) -> Result<(), Self::Error> {
+        self.upstream.write_str("<span class=\"")?;
+        self.upstream.write_str(annotation.html_class())?;
+        self.upstream.write_str("\">")
+    }
+
+    fn pop_annotation(&mut self) -> Result<(), Self::Error> {
+        self.upstream.write_str("</span>")
+    }
+}
+
 impl<W> RenderAnnotated<Annotation> for CiWrite<W>
 where
     W: fmt::Write,
@@ -981,7 +1223,14 @@ where
     }
 
     fn write_str_all(&mut self, s: &str) -> fmt::Result {
-        self.upstream.write_str(s)
+        match &mut self.link_text {
+            Some(link_text) => {
+                link_text.push_str(s);
+
+                Ok(())
+            }
+            None => self.upstream.write_str(s),
+        }
     }
 }
 
@@ -991,6 +1240,49 @@ where
 {
     fn push_annotation(&mut self, annotation: &Annotation) -> Result<(), Self::Error> {
         use Annotation::*;
+
+        self.push_style(annotation)?;
+        self.style_stack.push(*annotation);
+
+        if self.file_url.is_some() && matches!(annotation, Header | LineNumber) {
+            // the style goes before the link, and the link's text is held back until it ends
+            self.link_text = Some(String::new());
+        }
+
+        Ok(())
+    }
+
+    fn pop_annotation(&mut self) -> Result<(), Self::Error> {
+        use Annotation::*;
+
+        let ends_link = matches!(self.style_stack.last(), Some(Header | LineNumber));
+
+        if let (true, Some(file_url)) = (ends_link, &self.file_url) {
+            let link_text = self.link_text.take().unwrap_or_default();
+            let url = match self.style_stack.last() {
+                Some(LineNumber) => match link_text.trim().parse::<u32>() {
+                    Ok(line) => Some(format!("{}#L{}", file_url, line)),
+                    Err(_) => None,
+                },
+                _ => Some(file_url.clone()),
+            };
+
+            match url {
+                Some(url) => self.upstream.write_str(&hyperlink(&url, &link_text))?,
+                None => self.upstream.write_str(&link_text)?,
+            }
+        }
+
+        self.pop_style()
+    }
+}
+
+impl<'a, W> ColorWrite<'a, W>
+where
+    W: fmt::Write,
+{
+    fn push_style(&mut self, annotation: &Annotation) -> fmt::Result {
+        use Annotation::*;
         match annotation {
             Emphasized => {
                 self.write_str(self.palette.bold)?;
@@ -1054,11 +1346,10 @@ where
             }
             TypeBlock | InlineTypeBlock | Tag | RecordField | TupleElem => { /* nothing yet */ }
         }
-        self.style_stack.push(*annotation);
         Ok(())
     }
 
-    fn pop_annotation(&mut self) -> Result<(), Self::Error> {
+    fn pop_style(&mut self) -> fmt::Result {
         use Annotation::*;
 
         match self.style_stack.pop() {
@@ -127,6 +127,7 @@ mod test_reporting {
                 palette: DEFAULT_PALETTE,
                 threading: Threading::Single,
                 exec_mode: ExecutionMode::Check,
+                ..Default::default()
             };
             let result = roc_load::load_and_typecheck(
                 arena,
@@ -183,7 +184,7 @@ mod test_reporting {
         let mut buf = String::new();
 
         match infer_expr_help_new(subdir, arena, src) {
-            Err(LoadingProblem::FormattedReport(fail)) => fail,
+            Err(LoadingProblem::FormattedReport(fail, _)) => fail,
             Ok((module_src, type_problems, can_problems, home, interns)) => {
                 let lines = LineInfo::new(&module_src);
                 let src_lines: Vec<&str> = module_src.split('\n').collect();
@@ -1120,6 +1121,18 @@ mod test_reporting {
     infinitely.
 
         (∞ -> a) -> a
+
+    The ∞ stands for this part of the type, which contains itself:
+
+        ∞ -> a
+
+    Unfolding the ∞ a couple of times, the type looks like:
+
+        (((∞ -> a) -> a) -> a) -> a
+
+    Tip: A type can only refer to itself through a tag union, like
+    LinkedList a : [Nil, Cons a (LinkedList a)]. Maybe you need to wrap
+    part of this value in a tag, or in an opaque type around one?
     "###
     );
 
@@ -1145,6 +1158,18 @@ mod test_reporting {
     infinitely.
 
         List ∞ -> *
+
+    The ∞ stands for this part of the type, which contains itself:
+
+        List ∞
+
+    Unfolding the ∞ a couple of times, the type looks like:
+
+        List (List (List ∞)) -> *
+
+    Tip: A type can only refer to itself through a tag union, like
+    LinkedList a : [Nil, Cons a (LinkedList a)]. Maybe you need to wrap
+    part of this value in a tag, or in an opaque type around one?
     "###
     );
 
@@ -1172,6 +1197,18 @@ mod test_reporting {
 
         List ∞ -> *
 
+    The ∞ stands for this part of the type, which contains itself:
+
+        List ∞
+
+    Unfolding the ∞ a couple of times, the type looks like:
+
+        List (List (List ∞)) -> *
+
+    Tip: A type can only refer to itself through a tag union, like
+    LinkedList a : [Nil, Cons a (LinkedList a)]. Maybe you need to wrap
+    part of this value in a tag, or in an opaque type around one?
+
     ── CIRCULAR TYPE ───────────────────────────────────────── /code/proj/Main.roc ─
 
     I'm inferring a weird self-referential type for `g`:
@@ -1184,6 +1221,18 @@ mod test_reporting {
     infinitely.
 
         List ∞ -> *
+
+    The ∞ stands for this part of the type, which contains itself:
+
+        List ∞
+
+    Unfolding the ∞ a couple of times, the type looks like:
+
+        List (List (List ∞)) -> *
+
+    Tip: A type can only refer to itself through a tag union, like
+    LinkedList a : [Nil, Cons a (LinkedList a)]. Maybe you need to wrap
+    part of this value in a tag, or in an opaque type around one?
     "###
     );
 
@@ -1281,6 +1330,18 @@ mod test_reporting {
     infinitely.
 
         List ∞ -> *
+
+    The ∞ stands for this part of the type, which contains itself:
+
+        List ∞
+
+    Unfolding the ∞ a couple of times, the type looks like:
+
+        List (List (List ∞)) -> *
+
+    Tip: A type can only refer to itself through a tag union, like
+    LinkedList a : [Nil, Cons a (LinkedList a)]. Maybe you need to wrap
+    part of this value in a tag, or in an opaque type around one?
     "###
     );
 
@@ -1307,6 +1368,18 @@ mod test_reporting {
     infinitely.
 
         List ∞ -> List *
+
+    The ∞ stands for this part of the type, which contains itself:
+
+        List ∞
+
+    Unfolding the ∞ a couple of times, the type looks like:
+
+        List (List (List ∞)) -> List *
+
+    Tip: A type can only refer to itself through a tag union, like
+    LinkedList a : [Nil, Cons a (LinkedList a)]. Maybe you need to wrap
+    part of this value in a tag, or in an opaque type around one?
     "###
     );
 
@@ -1346,6 +1419,18 @@ mod test_reporting {
 
         List ∞ -> List *
 
+    The ∞ stands for this part of the type, which contains itself:
+
+        List ∞
+
+    Unfolding the ∞ a couple of times, the type looks like:
+
+        List (List (List ∞)) -> List *
+
+    Tip: A type can only refer to itself through a tag union, like
+    LinkedList a : [Nil, Cons a (LinkedList a)]. Maybe you need to wrap
+    part of this value in a tag, or in an opaque type around one?
+
     ── CIRCULAR TYPE ───────────────────────────────────────── /code/proj/Main.roc ─
 
     I'm inferring a weird self-referential type for `g`:
@@ -1358,6 +1443,18 @@ mod test_reporting {
     infinitely.
 
         List ∞ -> List *
+
+    The ∞ stands for this part of the type, which contains itself:
+
+        List ∞
+
+    Unfolding the ∞ a couple of times, the type looks like:
+
+        List (List (List ∞)) -> List *
+
+    Tip: A type can only refer to itself through a tag union, like
+    LinkedList a : [Nil, Cons a (LinkedList a)]. Maybe you need to wrap
+    part of this value in a tag, or in an opaque type around one?
     "###
     );
 
@@ -1386,6 +1483,18 @@ mod test_reporting {
 
         List ∞ -> List *
 
+    The ∞ stands for this part of the type, which contains itself:
+
+        List ∞
+
+    Unfolding the ∞ a couple of times, the type looks like:
+
+        List (List (List ∞)) -> List *
+
+    Tip: A type can only refer to itself through a tag union, like
+    LinkedList a : [Nil, Cons a (LinkedList a)]. Maybe you need to wrap
+    part of this value in a tag, or in an opaque type around one?
+
     ── CIRCULAR TYPE ───────────────────────────────────────── /code/proj/Main.roc ─
 
     I'm inferring a weird self-referential type for `g`:
@@ -1398,6 +1507,18 @@ mod test_reporting {
     infinitely.
 
         List ∞ -> List *
+
+    The ∞ stands for this part of the type, which contains itself:
+
+        List ∞
+
+    Unfolding the ∞ a couple of times, the type looks like:
+
+        List (List (List ∞)) -> List *
+
+    Tip: A type can only refer to itself through a tag union, like
+    LinkedList a : [Nil, Cons a (LinkedList a)]. Maybe you need to wrap
+    part of this value in a tag, or in an opaque type around one?
     "###
     );
 
@@ -1429,8 +1550,6 @@ mod test_reporting {
 
         { foo : Int * }
 
-    Tip: Seems like a record field typo. Maybe `bar` should be `foo`?
-
     Tip: Can more type annotations be added? Type annotations always help
     me give more specific messages, and I think they could help a lot in
     this case
@@ -1463,8 +1582,6 @@ mod test_reporting {
 
         [Green, Red]
 
-    Tip: Seems like a tag typo. Maybe `Blue` should be `Red`?
-
     Tip: Can more type annotations be added? Type annotations always help
     me give more specific messages, and I think they could help a lot in
     this case
@@ -1497,8 +1614,6 @@ mod test_reporting {
 
         [Green Str, Red (Int *)]
 
-    Tip: Seems like a tag typo. Maybe `Blue` should be `Red`?
-
     Tip: Can more type annotations be added? Type annotations always help
     me give more specific messages, and I think they could help a lot in
     this case
@@ -3330,7 +3445,8 @@ mod test_reporting {
     Roc does not allow unused type parameters!
 
     Tip: If you want an unused type parameter (a so-called "phantom
-    type"), read the guide section on phantom values.
+    type"), make `Foo` an opaque type by defining it with := instead of :.
+    Opaque types can have phantom type parameters.
     "###
     );
 
@@ -5428,27 +5544,37 @@ Tab characters are not allowed."###,
     );
 
     test_report!(
-        dbg_without_final_expression,
+        dbg_unapplied,
         indoc!(
             r#"
-            dbg 42
+            List.map [1] dbg
             "#
         ),
         @r###"
-    ── INDENT ENDS AFTER EXPRESSION ──── tmp/dbg_without_final_expression/Test.roc ─
-
-    I am partway through parsing a dbg statement, but I got stuck here:
+    ── UNAPPLIED DBG ───────────────────────────────────────── /code/proj/Main.roc ─
 
-    4│      dbg 42
-                  ^
+    This `dbg` doesn't have a value given to it:
 
-    I was expecting a final expression, like so
+    4│      List.map [1] dbg
+                         ^^^
 
-        dbg 42
-        "done"
+    `dbg` must be applied to the value it prints where it's used, like
+    dbg x or x |> dbg. It can't be passed around like a function.
     "###
     );
 
+    test_no_problem!(
+        dbg_passes_value_through,
+        indoc!(
+            r#"
+            n : U64
+            n = [1, 2] |> dbg "list" |> List.len
+
+            dbg "n" n
+            "#
+        )
+    );
+
     test_report!(
         expect_without_final_expression,
         indoc!(
@@ -5472,6 +5598,31 @@ Tab characters are not allowed."###,
     "###
     );
 
+    test_report!(
+        return_without_value,
+        indoc!(
+            r#"
+            f = \x ->
+                return
+
+            f 1
+            "#
+        ),
+        @r###"
+    ── UNFINISHED RETURN ─────────────────────── tmp/return_without_value/Test.roc ─
+
+    I was partway through parsing a `return` expression, but I got stuck
+    here:
+
+    5│          return
+                      ^
+
+    I was expecting to see the value to return next, like
+    return Err NotFound. It has to be indented more than the `return`
+    keyword if it's on another line.
+    "###
+    );
+
     // https://github.com/roc-lang/roc/issues/1714
     test_report!(
     interpolate_concat_is_transparent_1714,
@@ -6649,8 +6800,6 @@ In roc, functions are always written as a lambda, like{}
 
         [Email Str]
 
-    Tip: Seems like a tag typo. Maybe `Name` should be `Email`?
-
     Tip: Can more type annotations be added? Type annotations always help
     me give more specific messages, and I think they could help a lot in
     this case
@@ -8356,7 +8505,7 @@ In roc, functions are always written as a lambda, like{}
             r#"
             app "test" provides [] to "./platform"
 
-            MHash a b c has
+            MHash a has
               hash : a -> U64 | a has MHash
             "#
         ),
@@ -8365,8 +8514,8 @@ In roc, functions are always written as a lambda, like{}
 
     The definition of the `MHash` ability includes type variables:
 
-    3│  MHash a b c has
-              ^^^^^
+    3│  MHash a has
+              ^
 
     Abilities cannot depend on type variables, but their member values
     can!
@@ -8375,7 +8524,7 @@ In roc, functions are always written as a lambda, like{}
 
     `MHash` is not used anywhere in your code.
 
-    3│  MHash a b c has
+    3│  MHash a has
         ^^^^^
 
     If you didn't intend on using `MHash` then remove it so future readers
@@ -8383,6 +8532,42 @@ In roc, functions are always written as a lambda, like{}
     "###
     );
 
+    test_report!(
+        multi_parameter_ability,
+        indoc!(
+            r#"
+            app "test" provides [] to "./platform"
+
+            Convert a b has
+              convert : a -> b | a has Convert
+            "#
+        ),
+        @r###"
+    ── MULTI-PARAMETER ABILITY ─────────────────────────────── /code/proj/Main.roc ─
+
+    The definition of the `Convert` ability is parameterized over several
+    types:
+
+    3│  Convert a b has
+                ^^^
+
+    Abilities over more than one type are not supported yet.
+
+    Tip: An ability describes one type, the one its members name in a
+    `has` clause. Other types can be arguments of the members instead.
+
+    ── UNUSED DEFINITION ───────────────────────────────────── /code/proj/Main.roc ─
+
+    `Convert` is not used anywhere in your code.
+
+    3│  Convert a b has
+        ^^^^^^^
+
+    If you didn't intend on using `Convert` then remove it so future
+    readers of your code don't wonder why it is there.
+    "###
+    );
+
     test_report!(
         alias_in_has_clause,
         indoc!(
@@ -9193,6 +9378,18 @@ In roc, functions are always written as a lambda, like{}
 
         { set : Set ∞ }
 
+    The ∞ stands for this part of the type, which contains itself:
+
+        Set ∞
+
+    Unfolding the ∞ a couple of times, the type looks like:
+
+        { set : Set (Set (Set ∞)) }
+
+    Tip: A type can only refer to itself through a tag union, like
+    LinkedList a : [Nil, Cons a (LinkedList a)]. Maybe you need to wrap
+    part of this value in a tag, or in an opaque type around one?
+
     ── CIRCULAR TYPE ───────────────────────────────────────── /code/proj/Main.roc ─
 
     I'm inferring a weird self-referential type for `goal`:
@@ -9205,6 +9402,18 @@ In roc, functions are always written as a lambda, like{}
     infinitely.
 
         Set ∞
+
+    The ∞ stands for this part of the type, which contains itself:
+
+        Set ∞
+
+    Unfolding the ∞ a couple of times, the type looks like:
+
+        Set (Set (Set ∞))
+
+    Tip: A type can only refer to itself through a tag union, like
+    LinkedList a : [Nil, Cons a (LinkedList a)]. Maybe you need to wrap
+    part of this value in a tag, or in an opaque type around one?
     "###
     );
 
@@ -9874,6 +10083,64 @@ In roc, functions are always written as a lambda, like{}
     "###
     );
 
+    test_no_problem!(
+        opaque_phantom_parameter_is_not_unused,
+        indoc!(
+            r#"
+            Length unit := F64
+
+            Meters := {}
+
+            meters : F64 -> Length Meters
+            meters = \x -> @Length x
+
+            toF64 : Length unit -> F64
+            toF64 = \@Length x -> x
+
+            toF64 (meters 1)
+            "#
+        )
+    );
+
+    test_report!(
+        opaque_phantom_units_do_not_mix,
+        indoc!(
+            r#"
+            Length unit := F64
+
+            Meters := {}
+            Feet := {}
+
+            meters : F64 -> Length Meters
+            meters = \x -> @Length x
+
+            feet : F64 -> Length Feet
+            feet = \x -> @Length x
+
+            add : Length unit, Length unit -> Length unit
+            add = \@Length a, @Length b -> @Length (a + b)
+
+            add (meters 1) (feet 2)
+            "#
+        ),
+        @r###"
+    ── TYPE MISMATCH ───────────────────────────────────────── /code/proj/Main.roc ─
+
+    This 2nd argument to `add` has an unexpected type:
+
+    19│      add (meters 1) (feet 2)
+                             ^^^^^^
+
+    This `feet` call produces:
+
+        Length Feet
+
+    But `add` needs its 2nd argument to be:
+
+        Length Meters
+    "###
+    );
+
     test_report!(
         #[ignore = "TODO This should be a type error"]
         phantom_type_bound_to_ability_not_implementing,
@@ -13094,6 +13361,74 @@ I recommend using camelCase. It's the standard style in Roc code!
     "###
     );
 
+    test_report!(
+        derive_decoding_for_tag_union,
+        indoc!(
+            r#"
+            app "test" imports [Decode.{decoder}] provides [main] to "./platform"
+
+            main =
+                myDecoder : Decoder [Leaf Str, Node] fmt | fmt has DecoderFormatting
+                myDecoder = decoder
+
+                myDecoder
+            "#
+        ),
+        @r###"
+    ── TYPE MISMATCH ───────────────────────────────────────── /code/proj/Main.roc ─
+
+    This expression has a type that does not implement the abilities it's expected to:
+
+    5│      myDecoder = decoder
+                        ^^^^^^^
+
+    I can't generate an implementation of the `Decoding` ability for
+
+        [Leaf Str, Node]
+
+    Note: Decoding to a tag union is not supported yet. Consider decoding
+    to a record or a Str, then converting that to the tag union.
+    "###
+    );
+
+    test_report!(
+        derive_decoding_for_recursive_tag_union,
+        indoc!(
+            r#"
+            app "test" imports [Decode.{decoder}] provides [main] to "./platform"
+
+            Tree : [Leaf Str, Node (List Tree)]
+
+            main =
+                myDecoder : Decoder Tree fmt | fmt has DecoderFormatting
+                myDecoder = decoder
+
+                myDecoder
+            "#
+        ),
+        @r###"
+    ── TYPE MISMATCH ───────────────────────────────────────── /code/proj/Main.roc ─
+
+    This expression has a type that does not implement the abilities it's expected to:
+
+    7│      myDecoder = decoder
+                        ^^^^^^^
+
+    I can't generate an implementation of the `Decoding` ability for
+
+        Tree
+
+    In particular, an implementation for
+
+        [Leaf Str, Node (List a)] as a
+
+    cannot be generated.
+
+    Note: Decoding to a tag union is not supported yet. Consider decoding
+    to a record or a Str, then converting that to the tag union.
+    "###
+    );
+
     test_report!(
         derive_encoding_for_nat,
         indoc!(
@@ -13170,4 +13505,530 @@ I recommend using camelCase. It's the standard style in Roc code!
     I would have to crash if I saw one of those! Add branches for them!
     "###
     );
+
+    test_report!(
+        ambiguous_number_default_in_annotated_def,
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            main : Str
+            main = Num.toStr 5
+            "#
+        ),
+        @r###"
+    ── AMBIGUOUS NUMBER TYPE ───────────────────────────────── /code/proj/Main.roc ─
+
+    I could not figure out the type of this number:
+
+    4│  main = Num.toStr 5
+                         ^
+
+    Nothing in the annotation of the surrounding definition decides it, so
+    it will default to I64.
+
+    Tip: Add a type suffix like 5u8, or an annotation on an intermediate
+    definition, to make the type explicit.
+    "###
+    );
+
+    test_report!(
+        ambiguous_number_takes_the_annotated_number_type,
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            main : U8 -> Str
+            main = \_ -> Num.toStr 5
+            "#
+        ),
+        @"" // The annotation decides that 5 is a U8
+    );
+
+    test_report!(
+        ambiguous_number_with_several_annotated_number_types,
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            main : U8, I32 -> Str
+            main = \_, _ -> Num.toStr (1 + 2)
+            "#
+        ),
+        @r###"
+    ── AMBIGUOUS NUMBER TYPE ───────────────────────────────── /code/proj/Main.roc ─
+
+    I could not figure out the type of this number:
+
+    4│  main = \_, _ -> Num.toStr (1 + 2)
+                                   ^
+
+    Nothing in the annotation of the surrounding definition decides it, so
+    it will default to I64.
+
+    Tip: Add a type suffix like 5u8, or an annotation on an intermediate
+    definition, to make the type explicit.
+    "###
+    );
+
+    test_report!(
+        return_misplaced,
+        indoc!(
+            r#"
+            x = return 1
+
+            x
+            "#
+        ),
+    @r###"
+    ── MISPLACED RETURN ────────────────────────────────────── /code/proj/Main.roc ─
+
+    This `return` can't leave a function from here:
+
+    4│      x = return 1
+                ^^^^^^^^
+
+    `return` can only be used in a function body, where the rest of the
+    body runs after it, and not in a `when` guard or an `if` condition
+    after the first one.
+    "###
+    );
+
+    test_report!(
+        try_misplaced,
+        indoc!(
+            r#"
+            x = (Str.toU64 "1")?
+
+            x
+            "#
+        ),
+    @r###"
+    ── MISPLACED TRY ───────────────────────────────────────── /code/proj/Main.roc ─
+
+    This `?` can't return an `Err` from a function here:
+
+    4│      x = (Str.toU64 "1")?
+                ^^^^^^^^^^^^^^^^
+
+    `?` can only be used in a function body, where the rest of the body
+    runs after it. Try giving what it unwraps its own definition, or use a
+    `when` instead.
+    "###
+    );
+
+    test_report!(
+        large_tag_union_diff_omits_matching_tags,
+        indoc!(
+            r#"
+            f : [A, B, C, D, E, F Str] -> Str
+            f = \_ -> ""
+
+            g : [A, B, C, D, E, F U8]
+            g = A
+
+            f g
+            "#
+        ),
+        @r###"
+    ── TYPE MISMATCH ───────────────────────────────────────── /code/proj/Main.roc ─
+
+    This 1st argument to `f` has an unexpected type:
+
+    10│      f g
+               ^
+
+    This `g` value is a:
+
+        [F U8, … 5 matching tags omitted]
+
+    But `f` needs its 1st argument to be:
+
+        [F Str, … 5 matching tags omitted]
+    "###
+    );
+
+    test_report!(
+        close_tag_typo_suggestion,
+        indoc!(
+            r#"
+            f : [Red, Green] -> [Yes, No]
+            f = \_ -> Yes
+
+            f Gren
+            "#
+        ),
+        @r###"
+    ── TYPE MISMATCH ───────────────────────────────────────── /code/proj/Main.roc ─
+
+    This 1st argument to `f` has an unexpected type:
+
+    7│      f Gren
+              ^^^^
+
+    This `Gren` tag has the type:
+
+        [Gren]
+
+    But `f` needs its 1st argument to be:
+
+        [Green, Red]
+
+    Tip: Seems like a tag typo. Maybe `Gren` should be `Green`?
+
+    Tip: Can more type annotations be added? Type annotations always help
+    me give more specific messages, and I think they could help a lot in
+    this case
+    "###
+    );
+
+    test_no_problem!(
+        pattern_synonym_expands_in_when,
+        indoc!(
+            r#"
+            pattern Ok2 x = Ok (Ok x)
+
+            f : Result (Result U8 Str) Str -> U8
+            f = \result ->
+                when result is
+                    Ok2 n -> n
+                    Ok (Err _) -> 0
+                    Err _ -> 1
+
+            f (Ok (Ok 1))
+            "#
+        )
+    );
+
+    test_report!(
+        pattern_synonym_duplicate_argument,
+        indoc!(
+            r#"
+            pattern Both x = Pair x x
+
+            Pair 1 2
+            "#
+        ),
+    @r###"
+    ── INVALID PATTERN SYNONYM ─────────────────────────────── /code/proj/Main.roc ─
+
+    There's a problem with the `Both` pattern synonym:
+
+    4│      pattern Both x = Pair x x
+                                  ^
+
+    The argument `x` is bound more than once by its pattern, but each
+    argument can only be bound once.
+
+    ── UNUSED DEFINITION ───────────────────────────────────── /code/proj/Main.roc ─
+
+    `Both` is not used anywhere in your code.
+
+    4│      pattern Both x = Pair x x
+                    ^^^^^^^^^^^^^^^^^
+
+    If you didn't intend on using `Both` then remove it so future readers of
+    your code don't wonder why it is there.
+    "###
+    );
+
+    test_report!(
+        pattern_synonym_too_few_arguments,
+        indoc!(
+            r#"
+            pattern Ok2 x = Ok (Ok x)
+
+            when Ok (Ok 1) is
+                Ok2 -> 0
+                _ -> 1
+            "#
+        ),
+    @r###"
+    ── TOO FEW PATTERN SYNONYM ARGUMENTS ───────────────────── /code/proj/Main.roc ─
+
+    The `Ok2` pattern synonym expects 1 argument, but it got 0 instead:
+
+    7│          Ok2 -> 0
+                ^^^
+
+    Are there missing parentheses?
+    "###
+    );
+
+    test_report!(
+        regex_pattern_not_literal,
+        indoc!(
+            r#"
+            regex = "[a-z]+"
+
+            Str.matches "roc" regex
+            "#
+        ),
+    @r###"
+    ── PATTERN NOT A LITERAL ───────────────────────────────── /code/proj/Main.roc ─
+
+    This `Str.matches` doesn't have a string literal pattern:
+
+    6│      Str.matches "roc" regex
+                              ^^^^^
+
+    `Str.matches` compiles its pattern when the program is built, so it must
+    be called with the pattern written right there, like
+    Str.matches str "[a-z]+". It can't be passed around like a function.
+    "###
+    );
+
+    test_report!(
+        regex_unclosed_class,
+        indoc!(
+            r#"
+            Str.matches "roc" "[a-z"
+            "#
+        ),
+    @r###"
+    ── INVALID PATTERN ─────────────────────────────────────── /code/proj/Main.roc ─
+
+    This pattern can't be compiled:
+
+    4│      Str.matches "roc" "[a-z"
+                              ^^^^^^
+
+    It has a [ without a ] to close it.
+    "###
+    );
+
+    #[test]
+    fn every_error_code_is_explained() {
+        use roc_reporting::explain::{code_for_title, codes, explanation, CODES};
+
+        for (code, title) in CODES {
+            let explanation = explanation(code)
+                .unwrap_or_else(|| panic!("{} ({}) has no explanation", code, title));
+
+            assert!(explanation.lines().count() > 2, "{} isn't explained", code);
+        }
+
+        for (code, title) in codes() {
+            assert_eq!(code_for_title(title), Some(code));
+        }
+    }
+
+    #[test]
+    fn error_codes_are_normalized() {
+        use roc_reporting::explain::explanation;
+
+        assert_eq!(explanation("e401"), explanation("E0401"));
+        assert!(explanation("E0401")
+            .unwrap()
+            .starts_with("## E0401 TYPE MISMATCH"));
+        assert_eq!(explanation("E9999"), None);
+        assert_eq!(explanation("401"), None);
+    }
+
+    #[test]
+    fn report_in_spanish() {
+        use roc_reporting::locale::Locale;
+
+        let arena = Bump::new();
+        let src = indoc!(
+            r#"
+            x = 1
+            y = 2
+
+            x
+            "#
+        );
+        let src_lines: Vec<&str> = src.split('\n').collect();
+        let lines = LineInfo::new(src);
+        let filename = filename_from_string(r"/code/proj/Main.roc");
+
+        let (can_problems, home, interns) = match infer_expr_help(&arena, src) {
+            Ok((_, can_problems, home, interns)) => (can_problems, home, interns),
+            Err(_) => panic!("the source should parse"),
+        };
+        let mut alloc = RocDocAllocator::new(&src_lines, home, &interns);
+        alloc.locale = Locale::Spanish;
+
+        let mut buf = String::new();
+
+        for problem in can_problems {
+            can_problem(&alloc, &lines, filename.clone(), problem).render_ci(&mut buf, &alloc);
+        }
+
+        let header = buf.lines().next().unwrap();
+        let prose = buf.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        assert!(header.starts_with("── DEFINICIÓN SIN USAR ──"));
+        assert_eq!(header.chars().count(), 80);
+        assert!(prose.contains("`y` no se usa en ninguna parte de tu código."));
+        assert!(prose.contains("Si no pensabas usar `y` entonces elimínalo,"));
+    }
+
+    #[test]
+    fn circular_type_unfold_steps() {
+        let arena = Bump::new();
+        let src = indoc!(
+            r#"
+            f = \g -> g g
+
+            f
+            "#
+        );
+        let src_lines: Vec<&str> = src.split('\n').collect();
+        let lines = LineInfo::new(src);
+        let filename = filename_from_string(r"/code/proj/Main.roc");
+
+        let (type_problems, home, interns) = match infer_expr_help(&arena, src) {
+            Ok((type_problems, _, home, interns)) => (type_problems, home, interns),
+            Err(_) => panic!("the source should parse"),
+        };
+
+        let render = |steps: usize| {
+            let mut alloc = RocDocAllocator::new(&src_lines, home, &interns);
+            alloc.circular_type_unfold_steps = steps;
+
+            let mut buf = String::new();
+
+            for problem in type_problems.iter().cloned() {
+                if let Some(report) = type_problem(&alloc, &lines, filename.clone(), problem) {
+                    report.render_ci(&mut buf, &alloc);
+                }
+            }
+
+            buf
+        };
+
+        let once = render(1);
+        assert!(once.contains("Unfolding the ∞ once, the type looks like:"));
+        assert!(once.contains("    ((∞ -> a) -> a) -> a\n"), "{}", once);
+
+        let thrice = render(3);
+        assert!(thrice.contains("Unfolding the ∞ a few times, the type looks like:"));
+        assert!(
+            thrice.contains("    ((((∞ -> a) -> a) -> a) -> a) -> a\n"),
+            "{}",
+            thrice
+        );
+
+        let never = render(0);
+        assert!(never.contains("The ∞ stands for this part of the type"));
+        assert!(!never.contains("Unfolding"), "{}", never);
+    }
+
+    #[test]
+    fn locale_tags() {
+        use roc_reporting::locale::Locale;
+
+        assert_eq!(Locale::from_tag("es"), Some(Locale::Spanish));
+        assert_eq!(Locale::from_tag("es_ES.UTF-8"), Some(Locale::Spanish));
+        assert_eq!(Locale::from_tag("ES-mx"), Some(Locale::Spanish));
+        assert_eq!(Locale::from_tag("en_US"), Some(Locale::English));
+        assert_eq!(Locale::from_tag("xx"), None);
+
+        for locale in Locale::ALL {
+            for (english, translated) in locale.translations() {
+                assert!(!translated.is_empty(), "{:?} has no {:?}", locale, english);
+            }
+        }
+    }
+
+    #[test]
+    fn terminal_hyperlinks() {
+        use roc_reporting::report::PLAIN_PALETTE;
+
+        let arena = Bump::new();
+        let src = indoc!(
+            r#"
+            x = 1
+            y = 2
+
+            x
+            "#
+        );
+        let src_lines: Vec<&str> = src.split('\n').collect();
+        let lines = LineInfo::new(src);
+        let filename = filename_from_string(r"/code/proj/Main.roc");
+
+        let (can_problems, home, interns) = match infer_expr_help(&arena, src) {
+            Ok((_, can_problems, home, interns)) => (can_problems, home, interns),
+            Err(_) => panic!("the source should parse"),
+        };
+        let alloc = RocDocAllocator::new(&src_lines, home, &interns);
+
+        let mut buf = String::new();
+
+        for problem in can_problems {
+            can_problem(&alloc, &lines, filename.clone(), problem).render(
+                RenderTarget::ColorTerminalWithLinks,
+                &mut buf,
+                &alloc,
+                &PLAIN_PALETTE,
+            );
+        }
+
+        assert!(
+            buf.starts_with("\u{1b}]8;;file:///code/proj/Main.roc\u{1b}\\── UNUSED DEFINITION ──")
+        );
+        assert!(buf.contains("\u{1b}]8;;file:///code/proj/Main.roc#L5\u{1b}\\5\u{1b}]8;;\u{1b}\\│"));
+    }
+
+    #[test]
+    fn html_report_page() {
+        use roc_reporting::html::{report_page, HtmlReport};
+
+        let arena = Bump::new();
+        let src = indoc!(
+            r#"
+            x = 1
+            y = 2
+
+            x
+            "#
+        );
+        let src_lines: Vec<&str> = src.split('\n').collect();
+        let lines = LineInfo::new(src);
+        let filename = filename_from_string(r"/code/proj/Main.roc");
+
+        let (can_problems, home, interns) = match infer_expr_help(&arena, src) {
+            Ok((_, can_problems, home, interns)) => (can_problems, home, interns),
+            Err(_) => panic!("the source should parse"),
+        };
+        let alloc = RocDocAllocator::new(&src_lines, home, &interns);
+
+        let mut reports = Vec::new();
+
+        for problem in can_problems {
+            let report = can_problem(&alloc, &lines, filename.clone(), problem);
+            let mut body = String::new();
+
+            reports.push(HtmlReport {
+                severity: report.severity,
+                title: report.title.clone(),
+                code: report.code(),
+                path: "Main.roc".to_string(),
+                body: {
+                    report.render_html(&mut body);
+                    body
+                },
+            });
+        }
+
+        reports.push(HtmlReport {
+            severity: Severity::RuntimeError,
+            title: "TYPE MISMATCH".to_string(),
+            code: Some("E0401"),
+            path: "Main.roc".to_string(),
+            body: "a &lt; b".to_string(),
+        });
+
+        let page = report_page(&reports);
+
+        assert!(page.contains("<h1>1 error and 1 warning found</h1>"));
+        // errors come first
+        assert!(page.contains(
+            "<section class=\"error-item\" id=\"problem-1\">\n<h2><span class=\"severity\">error</span> <a href=\"#problem-1\">TYPE MISMATCH</a> <span class=\"code\">E0401</span></h2>"
+        ));
+        assert!(page.contains("<section class=\"warning-item\" id=\"problem-2\">"));
+        assert!(page.contains("<span class=\"symbol\">y</span> is not used anywhere"));
+    }
 }
@@ -14,6 +14,7 @@ use core::str;
 mod roc_box;
 mod roc_dict;
 mod roc_list;
+mod roc_panic;
 mod roc_set;
 mod roc_str;
 mod storage;
@@ -21,6 +22,7 @@ mod storage;
 pub use roc_box::RocBox;
 pub use roc_dict::RocDict;
 pub use roc_list::{RocList, SendSafeRocList};
+pub use roc_panic::{RocPanic, RocPanicRegion, RocPanicTag};
 pub use roc_set::RocSet;
 pub use roc_str::{InteriorNulError, RocStr, SendSafeRocStr};
 pub use storage::Storage;
@@ -0,0 +1,103 @@
+use core::fmt;
+
+use crate::RocStr;
+
+/// What a Roc program passes to `roc_panic`, along with a [RocPanicTag]:
+///
+/// ```c
+/// void roc_panic(RocPanic* payload, uint32_t tag_id);
+/// ```
+///
+/// The message comes first, so hosts which only read a `RocStr` from the pointer still work.
+#[repr(C)]
+pub struct RocPanic {
+    pub message: RocStr,
+    pub region: RocPanicRegion,
+}
+
+impl RocPanic {
+    /// Shows the panic the way hosts should print it, like `Roc crashed at 3:5-3:20: oops`
+    pub fn describe(&self, tag_id: u32) -> impl fmt::Display + '_ {
+        Describe {
+            panic: self,
+            tag_id,
+        }
+    }
+}
+
+struct Describe<'a> {
+    panic: &'a RocPanic,
+    tag_id: u32,
+}
+
+impl fmt::Display for Describe<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match RocPanicTag::from_u32(self.tag_id) {
+            Some(RocPanicTag::Roc) => write!(f, "Roc failed")?,
+            Some(RocPanicTag::User) => write!(f, "Roc crashed")?,
+            None => write!(f, "Roc panicked with unknown tag {}", self.tag_id)?,
+        }
+
+        if self.panic.region.is_known() {
+            write!(f, " at {}", self.panic.region)?;
+        }
+
+        write!(f, ": {}", self.panic.message.as_str())
+    }
+}
+
+/// Where in the source a Roc program crashed. Lines and columns start at 1, and they're all 0
+/// when the crash has no source region, like an overflow in a builtin.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RocPanicRegion {
+    pub start_line: u32,
+    pub start_column: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+}
+
+impl RocPanicRegion {
+    pub fn is_known(&self) -> bool {
+        self.start_line != 0
+    }
+}
+
+impl fmt::Display for RocPanicRegion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}-{}:{}",
+            self.start_line, self.start_column, self.end_line, self.end_column
+        )
+    }
+}
+
+/// Why a Roc program panicked, which is the second argument to `roc_panic`
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RocPanicTag {
+    /// Roc itself failed, in a builtin (like an integer overflow) or because of a compile error
+    Roc = 0,
+    /// The program ran into a `crash`, or an `expect` that must hold
+    User = 1,
+}
+
+impl RocPanicTag {
+    pub fn from_u32(tag_id: u32) -> Option<Self> {
+        match tag_id {
+            0 => Some(Self::Roc),
+            1 => Some(Self::User),
+            _ => None,
+        }
+    }
+
+    /// The exit code for hosts which end the process when the program panics. A `crash` is
+    /// like any other error the program reports, whereas a failure in Roc itself is a bug.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Self::Roc => 101,
+            Self::User => 1,
+        }
+    }
+}
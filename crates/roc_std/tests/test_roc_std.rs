@@ -59,7 +59,10 @@ pub unsafe extern "C" fn roc_memset(dst: *mut c_void, c: i32, n: usize) -> *mut
 
 #[cfg(test)]
 mod test_roc_std {
-    use roc_std::{RocBox, RocDec, RocList, RocResult, RocStr, SendSafeRocList, SendSafeRocStr};
+    use roc_std::{
+        RocBox, RocDec, RocList, RocPanic, RocPanicRegion, RocResult, RocStr, SendSafeRocList,
+        SendSafeRocStr,
+    };
 
     fn roc_str_byte_representation(string: &RocStr) -> [u8; RocStr::SIZE] {
         unsafe { core::mem::transmute_copy(string) }
@@ -98,6 +101,32 @@ mod test_roc_std {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn panic_description() {
+        let crashed = RocPanic {
+            message: RocStr::from("oops"),
+            region: RocPanicRegion {
+                start_line: 3,
+                start_column: 5,
+                end_line: 3,
+                end_column: 11,
+            },
+        };
+        let failed = RocPanic {
+            message: RocStr::from("integer addition overflowed!"),
+            region: RocPanicRegion::default(),
+        };
+
+        assert_eq!(
+            crashed.describe(1).to_string(),
+            "Roc crashed at 3:5-3:11: oops"
+        );
+        assert_eq!(
+            failed.describe(0).to_string(),
+            "Roc failed: integer addition overflowed!"
+        );
+    }
+
     #[test]
     fn empty_string_from_str() {
         let a = RocStr::from("");
@@ -22,6 +22,7 @@ fn build_host() {
         platform_main_roc.with_file_name(preprocessed_host_filename(&target).unwrap());
 
     build_and_preprocess_host(
+        None,
         roc_mono::ir::OptLevel::Normal,
         &target,
         &platform_main_roc,
@@ -102,7 +103,7 @@ fn valgrind_test_linux(source: &str) {
             run_with_valgrind(&binary_path);
         }
         Err(roc_cli::build::BuildFileError::LoadingProblem(
-            roc_load::LoadingProblem::FormattedReport(report),
+            roc_load::LoadingProblem::FormattedReport(report, _),
         )) => {
             eprintln!("{}", report);
             panic!("");
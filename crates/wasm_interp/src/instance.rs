@@ -2,7 +2,7 @@ use bumpalo::{collections::Vec, Bump};
 use std::fmt::{self, Write};
 use std::iter::{self, once, Iterator};
 
-use roc_wasm_module::opcodes::OpCode;
+use roc_wasm_module::opcodes::{MiscOp, OpCode};
 use roc_wasm_module::parse::{Parse, SkipBytes};
 use roc_wasm_module::sections::{ImportDesc, MemorySection, SignatureParamsIter};
 use roc_wasm_module::{ExportType, WasmModule};
@@ -1692,6 +1692,41 @@ impl<'a, I: ImportDispatcher> Instance<'a, I> {
                 self.value_store
                     .push(Value::F64(f64::from_ne_bytes(x.to_ne_bytes())));
             }
+
+            MISCPREFIX => {
+                let sub_op = self.fetch_immediate_u32(module);
+                if sub_op == MiscOp::MEMORYCOPY as u32 {
+                    // destination and source memory indices, always zero
+                    self.program_counter += 2;
+                    let size = self.value_store.pop_u32()? as usize;
+                    let src = self.value_store.pop_u32()? as usize;
+                    let dst = self.value_store.pop_u32()? as usize;
+                    self.memory.copy_within(src..src + size, dst);
+                } else if sub_op == MiscOp::MEMORYFILL as u32 {
+                    // memory index, always zero
+                    self.program_counter += 1;
+                    let size = self.value_store.pop_u32()? as usize;
+                    let byte = self.value_store.pop_i32()? as u8;
+                    let dst = self.value_store.pop_u32()? as usize;
+                    self.memory[dst..dst + size].fill(byte);
+                } else {
+                    unimplemented!("Wasm instruction 0x{:02x} {}", op_code as u8, sub_op);
+                }
+            }
+            SIMDPREFIX => {
+                let sub_op = self.fetch_immediate_u32(module);
+                unimplemented!(
+                    "SIMD instruction {}. The interpreter does not support the SIMD proposal.",
+                    sub_op
+                );
+            }
+            ATOMICPREFIX => {
+                let sub_op = self.fetch_immediate_u32(module);
+                unimplemented!(
+                    "Atomic instruction {}. The interpreter does not support the threads proposal.",
+                    sub_op
+                );
+            }
         }
 
         if let Some(debug_string) = &self.debug_string {
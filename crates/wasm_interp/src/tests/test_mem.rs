@@ -2,7 +2,7 @@ use super::create_exported_function_no_locals;
 use crate::{DefaultImportDispatcher, Instance};
 use bumpalo::{collections::Vec, Bump};
 use roc_wasm_module::{
-    opcodes::OpCode,
+    opcodes::{MiscOp, OpCode},
     sections::{DataMode, DataSegment, MemorySection},
     ConstExpr, SerialBuffer, Signature, Value, ValueType, WasmModule,
 };
@@ -49,6 +49,54 @@ fn test_growmemory() {
     assert_eq!(state.memory.len(), 5 * MemorySection::PAGE_SIZE as usize);
 }
 
+#[test]
+fn test_memory_copy() {
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+    let pc = 0;
+    module.memory = MemorySection::new(&arena, MemorySection::PAGE_SIZE);
+
+    let buf = &mut module.code.bytes;
+    for x in [0x20, 0x10, 3] {
+        buf.push(OpCode::I32CONST as u8);
+        buf.encode_i32(x);
+    }
+    buf.push(OpCode::MISCPREFIX as u8);
+    buf.encode_u32(MiscOp::MEMORYCOPY as u32);
+    buf.push(0);
+    buf.push(0);
+
+    let mut state = Instance::new(&arena, 1, pc, [], DefaultImportDispatcher::default());
+    state.memory[0x10..][..3].copy_from_slice(&[1, 2, 3]);
+    for _ in 0..4 {
+        state.execute_next_instruction(&module).unwrap();
+    }
+    assert_eq!(&state.memory[0x20..][..4], &[1, 2, 3, 0]);
+}
+
+#[test]
+fn test_memory_fill() {
+    let arena = Bump::new();
+    let mut module = WasmModule::new(&arena);
+    let pc = 0;
+    module.memory = MemorySection::new(&arena, MemorySection::PAGE_SIZE);
+
+    let buf = &mut module.code.bytes;
+    for x in [0x10, 0xab, 3] {
+        buf.push(OpCode::I32CONST as u8);
+        buf.encode_i32(x);
+    }
+    buf.push(OpCode::MISCPREFIX as u8);
+    buf.encode_u32(MiscOp::MEMORYFILL as u32);
+    buf.push(0);
+
+    let mut state = Instance::new(&arena, 1, pc, [], DefaultImportDispatcher::default());
+    for _ in 0..4 {
+        state.execute_next_instruction(&module).unwrap();
+    }
+    assert_eq!(&state.memory[0x0f..][..5], &[0, 0xab, 0xab, 0xab, 0]);
+}
+
 fn test_load(load_op: OpCode, ty: ValueType, data: &[u8], addr: u32, offset: u32) -> Value {
     let arena = Bump::new();
     let mut module = WasmModule::new(&arena);
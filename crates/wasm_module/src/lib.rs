@@ -93,6 +93,7 @@ impl<'a> WasmModule<'a> {
         self.export.serialize(buffer);
         self.start.serialize(buffer);
         self.element.serialize(buffer);
+        self.data.serialize_count_section(buffer);
         self.code.serialize(buffer);
         self.data.serialize(buffer);
         self.names.serialize(buffer);
@@ -218,7 +219,7 @@ impl<'a> WasmModule<'a> {
         // Mark all live functions
         //
 
-        let import_count = self.import.imports.len();
+        let import_count = self.import.function_count();
         let fn_index_min = import_count as u32 + self.code.dead_import_dummy_count;
         let fn_index_max = called_fns.len() as u32;
 
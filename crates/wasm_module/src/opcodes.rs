@@ -183,6 +183,43 @@ pub enum OpCode {
     I64REINTERPRETF64 = 0xbd,
     F32REINTERPRETI32 = 0xbe,
     F64REINTERPRETI64 = 0xbf,
+
+    /// Prefix for miscellaneous instructions such as bulk memory operations.
+    /// The prefix is followed by a LEB-encoded u32 identifying the instruction. See `MiscOp`.
+    MISCPREFIX = 0xfc,
+    /// Prefix for 128-bit SIMD instructions.
+    /// The prefix is followed by a LEB-encoded u32 identifying the instruction. See `SimdOp`.
+    SIMDPREFIX = 0xfd,
+    /// Prefix for atomic memory instructions from the threads proposal.
+    /// The prefix is followed by a LEB-encoded u32 identifying the instruction. See `AtomicOp`.
+    ATOMICPREFIX = 0xfe,
+}
+
+/// Instructions encoded after the `MISCPREFIX` byte (only the ones we generate)
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MiscOp {
+    MEMORYINIT = 8,
+    MEMORYCOPY = 10,
+    MEMORYFILL = 11,
+}
+
+/// Instructions encoded after the `SIMDPREFIX` byte (only the ones we generate)
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SimdOp {
+    V128LOAD = 0,
+    V128STORE = 11,
+}
+
+/// Instructions encoded after the `ATOMICPREFIX` byte (only the ones we generate)
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AtomicOp {
+    MEMORYATOMICNOTIFY = 0x00,
+    MEMORYATOMICWAIT32 = 0x01,
+    I32ATOMICSTORE = 0x17,
+    I32ATOMICRMWCMPXCHG = 0x48,
 }
 
 impl From<u8> for OpCode {
@@ -204,6 +241,8 @@ enum OpImmediates {
     Leb64x1,
     Leb32x2,
     BrTable,
+    /// A LEB-encoded sub-opcode, followed by immediates that depend on it
+    Prefixed,
 }
 
 fn immediates_for(op: OpCode) -> Result<OpImmediates, String> {
@@ -257,6 +296,8 @@ fn immediates_for(op: OpCode) -> Result<OpImmediates, String> {
             NoImmediate
         }
 
+        MISCPREFIX | SIMDPREFIX | ATOMICPREFIX => Prefixed,
+
         // Catch-all in case of an invalid cast from u8 to OpCode while parsing binary
         // (rustc keeps this code, I verified in Compiler Explorer)
         #[allow(unreachable_patterns)]
@@ -312,13 +353,152 @@ impl SkipBytes for OpCode {
                     u32::skip_bytes(bytes, cursor)?;
                 }
             }
+            Prefixed => {
+                *cursor += 1;
+                let sub_op_offset = *cursor;
+                let sub_op = u32::parse((), bytes, cursor)?;
+                let skipped = match opcode {
+                    OpCode::MISCPREFIX => skip_misc_immediates(sub_op, bytes, cursor)?,
+                    OpCode::SIMDPREFIX => skip_simd_immediates(sub_op, bytes, cursor)?,
+                    _ => skip_atomic_immediates(sub_op, bytes, cursor)?,
+                };
+                if !skipped {
+                    return Err(ParseError {
+                        message: format!(
+                            "Unknown Wasm instruction 0x{:02x} {}",
+                            opcode as u8, sub_op
+                        ),
+                        offset: sub_op_offset,
+                    });
+                }
+            }
         }
         Ok(())
     }
 }
 
+/// Skip the immediates of an instruction with the `MISCPREFIX` prefix.
+/// Returns false if the sub-opcode is unknown.
+fn skip_misc_immediates(sub_op: u32, bytes: &[u8], cursor: &mut usize) -> Result<bool, ParseError> {
+    match sub_op {
+        // saturating float-to-int conversions
+        0..=7 => {}
+        // memory.init: data index, memory index
+        8 => {
+            u32::skip_bytes(bytes, cursor)?;
+            *cursor += 1;
+        }
+        // data.drop, elem.drop, table.grow, table.size, table.fill
+        9 | 13 | 15 | 16 | 17 => u32::skip_bytes(bytes, cursor)?,
+        // memory.copy: destination and source memory indices
+        10 => *cursor += 2,
+        // memory.fill: memory index
+        11 => *cursor += 1,
+        // table.init, table.copy
+        12 | 14 => {
+            u32::skip_bytes(bytes, cursor)?;
+            u32::skip_bytes(bytes, cursor)?;
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+/// Skip the immediates of an instruction with the `SIMDPREFIX` prefix.
+/// Returns false if the sub-opcode is unknown.
+fn skip_simd_immediates(sub_op: u32, bytes: &[u8], cursor: &mut usize) -> Result<bool, ParseError> {
+    match sub_op {
+        // loads and stores: alignment, offset
+        0..=11 | 92 | 93 => {
+            u32::skip_bytes(bytes, cursor)?;
+            u32::skip_bytes(bytes, cursor)?;
+        }
+        // v128.const, i8x16.shuffle
+        12 | 13 => *cursor += 16,
+        // lane extract & replace: lane index
+        21..=34 => *cursor += 1,
+        // lane loads & stores: alignment, offset, lane index
+        84..=91 => {
+            u32::skip_bytes(bytes, cursor)?;
+            u32::skip_bytes(bytes, cursor)?;
+            *cursor += 1;
+        }
+        // everything else operates only on the value stack
+        14..=20 | 35..=83 | 94..=275 => {}
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
 impl Serialize for OpCode {
     fn serialize<T: crate::SerialBuffer>(&self, buffer: &mut T) {
         (*self as u8).serialize(buffer)
     }
 }
+
+/// Skip the immediates of an instruction with the `ATOMICPREFIX` prefix.
+/// Returns false if the sub-opcode is unknown.
+fn skip_atomic_immediates(
+    sub_op: u32,
+    bytes: &[u8],
+    cursor: &mut usize,
+) -> Result<bool, ParseError> {
+    match sub_op {
+        // atomic.fence: a single zero byte
+        0x03 => *cursor += 1,
+        // everything else accesses memory: alignment, offset
+        0x00..=0x02 | 0x10..=0x4e => {
+            u32::skip_bytes(bytes, cursor)?;
+            u32::skip_bytes(bytes, cursor)?;
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skip_prefixed_instructions() {
+        let bytes = &[
+            OpCode::MISCPREFIX as u8,
+            MiscOp::MEMORYCOPY as u8,
+            0,
+            0,
+            OpCode::MISCPREFIX as u8,
+            MiscOp::MEMORYFILL as u8,
+            0,
+            OpCode::SIMDPREFIX as u8,
+            SimdOp::V128LOAD as u8,
+            4,
+            0x80,
+            0x01,
+            OpCode::SIMDPREFIX as u8,
+            SimdOp::V128STORE as u8,
+            3,
+            0,
+            OpCode::ATOMICPREFIX as u8,
+            AtomicOp::I32ATOMICRMWCMPXCHG as u8,
+            2,
+            0,
+            OpCode::END as u8,
+        ];
+        let mut cursor = 0;
+        let mut instruction_starts = vec![];
+        while cursor < bytes.len() {
+            instruction_starts.push(cursor);
+            OpCode::skip_bytes(bytes, &mut cursor).unwrap();
+        }
+        assert_eq!(instruction_starts, [0, 4, 7, 12, 16, 20]);
+        assert_eq!(cursor, bytes.len());
+    }
+
+    #[test]
+    fn test_skip_unknown_prefixed_instruction() {
+        let bytes = &[OpCode::MISCPREFIX as u8, 0x7f];
+        let mut cursor = 0;
+        assert!(OpCode::skip_bytes(bytes, &mut cursor).is_err());
+    }
+}
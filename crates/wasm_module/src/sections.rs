@@ -274,6 +274,21 @@ impl<'a> TypeSection<'a> {
         let mut sig_bytes = Vec::with_capacity_in(signature.param_types.len() + 4, self.arena);
         signature.serialize(&mut sig_bytes);
 
+        self.insert_bytes(&sig_bytes)
+    }
+
+    /// Find or insert a signature from another module's TypeSection. Return its index in this one.
+    pub fn insert_from(&mut self, other: &TypeSection<'_>, sig_index: u32) -> u32 {
+        let start = other.offsets[sig_index as usize];
+        let end = match other.offsets.get(sig_index as usize + 1) {
+            Some(next) => *next,
+            None => other.bytes.len(),
+        };
+
+        self.insert_bytes(&other.bytes[start..end])
+    }
+
+    fn insert_bytes(&mut self, sig_bytes: &[u8]) -> u32 {
         let sig_len = sig_bytes.len();
         let bytes_len = self.bytes.len();
 
@@ -282,14 +297,14 @@ impl<'a> TypeSection<'a> {
             if end > bytes_len {
                 break;
             }
-            if &self.bytes[*offset..end] == sig_bytes.as_slice() {
+            if &self.bytes[*offset..end] == sig_bytes {
                 return i as u32;
             }
         }
 
         let sig_id = self.offsets.len();
         self.offsets.push(bytes_len);
-        self.bytes.extend_from_slice(&sig_bytes);
+        self.bytes.extend_from_slice(sig_bytes);
 
         sig_id as u32
     }
@@ -687,7 +702,7 @@ impl TableSection {
         let ref_type_bytes = 1;
         let limits_bytes = match self.function_table.limits {
             Limits::Min(_) => MAX_SIZE_ENCODED_U32,
-            Limits::MinMax(..) => 2 * MAX_SIZE_ENCODED_U32,
+            Limits::MinMax(..) | Limits::SharedMinMax(..) => 2 * MAX_SIZE_ENCODED_U32,
         };
 
         section_id_bytes + section_length_bytes + num_tables_bytes + ref_type_bytes + limits_bytes
@@ -755,12 +770,15 @@ impl Serialize for TableSection {
 pub enum Limits {
     Min(u32),
     MinMax(u32, u32),
+    /// Shared memory, from the threads proposal. Shared memories must have a maximum size.
+    SharedMinMax(u32, u32),
 }
 
 #[repr(u8)]
 enum LimitsId {
     Min = 0,
     MinMax = 1,
+    SharedMinMax = 3,
 }
 
 impl Serialize for Limits {
@@ -775,6 +793,11 @@ impl Serialize for Limits {
                 buffer.encode_u32(*min);
                 buffer.encode_u32(*max);
             }
+            Self::SharedMinMax(min, max) => {
+                buffer.append_u8(LimitsId::SharedMinMax as u8);
+                buffer.encode_u32(*min);
+                buffer.encode_u32(*max);
+            }
         }
     }
 }
@@ -784,7 +807,7 @@ impl SkipBytes for Limits {
         let variant_id = bytes[*cursor];
         u8::skip_bytes(bytes, cursor)?; // advance past the variant byte
         u32::skip_bytes(bytes, cursor)?; // skip "min"
-        if variant_id == LimitsId::MinMax as u8 {
+        if variant_id == LimitsId::MinMax as u8 || variant_id == LimitsId::SharedMinMax as u8 {
             u32::skip_bytes(bytes, cursor)?; // skip "max"
         }
         Ok(())
@@ -803,6 +826,9 @@ impl Parse<()> for Limits {
         if variant_id == LimitsId::MinMax as u8 {
             let max = u32::parse((), bytes, cursor).unwrap();
             Ok(Limits::MinMax(min, max))
+        } else if variant_id == LimitsId::SharedMinMax as u8 {
+            let max = u32::parse((), bytes, cursor).unwrap();
+            Ok(Limits::SharedMinMax(min, max))
         } else {
             Ok(Limits::Min(min))
         }
@@ -839,7 +865,7 @@ impl<'a> MemorySection<'a> {
         let mut cursor = 0;
         let memory_limits = Limits::parse((), &self.bytes, &mut cursor)?;
         let min_pages = match memory_limits {
-            Limits::Min(pages) | Limits::MinMax(pages, _) => pages,
+            Limits::Min(pages) | Limits::MinMax(pages, _) | Limits::SharedMinMax(pages, _) => pages,
         };
         Ok(min_pages * MemorySection::PAGE_SIZE)
     }
@@ -849,7 +875,9 @@ impl<'a> MemorySection<'a> {
         let memory_limits = Limits::parse((), &self.bytes, &mut cursor)?;
         let bytes = match memory_limits {
             Limits::Min(_) => None,
-            Limits::MinMax(_, pages) => Some(pages * MemorySection::PAGE_SIZE),
+            Limits::MinMax(_, pages) | Limits::SharedMinMax(_, pages) => {
+                Some(pages * MemorySection::PAGE_SIZE)
+            }
         };
         Ok(bytes)
     }
@@ -1545,7 +1573,11 @@ impl Serialize for DataSegment<'_> {
 pub struct DataSection<'a> {
     pub end_addr: u32,
     count: u32,
+    /// Size of the segment count as encoded in a preloaded file.
+    /// Relocation offsets count from the start of the section body, so they include it.
+    count_size: u32,
     bytes: Vec<'a, u8>,
+    has_passive_segments: bool,
 }
 
 impl<'a> DataSection<'a> {
@@ -1555,7 +1587,9 @@ impl<'a> DataSection<'a> {
         DataSection {
             end_addr: 0,
             count: 0,
+            count_size: 0,
             bytes: Vec::new_in(arena),
+            has_passive_segments: false,
         }
     }
 
@@ -1566,10 +1600,65 @@ impl<'a> DataSection<'a> {
     pub fn append_segment(&mut self, segment: DataSegment<'a>) -> u32 {
         let index = self.count;
         self.count += 1;
+        if matches!(segment.mode, DataMode::Passive) {
+            self.has_passive_segments = true;
+        }
         segment.serialize(&mut self.bytes);
         index
     }
 
+    /// Convert all active segments to passive ones, so that they are not loaded automatically
+    /// on instantiation. (With shared memory, every thread instantiates the module again!)
+    /// Returns the target address and length of each segment, for use with `memory.init`.
+    pub fn make_passive(&mut self, arena: &'a Bump) -> Vec<'a, (u32, u32)> {
+        let mut segments = Vec::with_capacity_in(self.count as usize, arena);
+        let mut bytes = Vec::with_capacity_in(self.bytes.len(), arena);
+        let mut cursor = 0;
+        for _ in 0..self.count {
+            let mode = DataMode::parse((), &self.bytes, &mut cursor).unwrap();
+            let addr = match mode {
+                DataMode::Active {
+                    offset: ConstExpr::I32(addr),
+                } => addr as u32,
+                _ => internal_error!("Data section: cannot make segment {:?} passive", mode),
+            };
+            let len = u32::parse((), &self.bytes, &mut cursor).unwrap();
+            DataMode::Passive.serialize(&mut bytes);
+            bytes.encode_u32(len);
+            bytes.extend_from_slice(&self.bytes[cursor..][..len as usize]);
+            cursor += len as usize;
+            segments.push((addr, len));
+        }
+        self.bytes = bytes;
+        self.has_passive_segments = true;
+        segments
+    }
+
+    /// The DataCount section goes before the Code section.
+    /// It is required if the code uses `memory.init`, which is the only way to load passive segments.
+    pub fn serialize_count_section<B: SerialBuffer>(&self, buffer: &mut B) {
+        if self.has_passive_segments {
+            let header_indices = write_section_header(buffer, SectionId::DataCount);
+            buffer.encode_u32(self.count);
+            update_section_size(buffer, header_indices);
+        }
+    }
+
+    /// Each segment's mode and initial bytes, along with the offset of those bytes from the start
+    /// of the section body in the preloaded file. That's what offsets in `reloc.DATA` refer to.
+    pub fn segments(&self) -> Result<std::vec::Vec<(DataMode, u32, &[u8])>, ParseError> {
+        let mut segments = std::vec::Vec::with_capacity(self.count as usize);
+        let mut cursor = 0;
+        for _ in 0..self.count {
+            let mode = DataMode::parse((), &self.bytes, &mut cursor)?;
+            let len = u32::parse((), &self.bytes, &mut cursor)? as usize;
+            let offset = self.count_size + cursor as u32;
+            segments.push((mode, offset, &self.bytes[cursor..][..len]));
+            cursor += len;
+        }
+        Ok(segments)
+    }
+
     pub fn load_into(&self, memory: &mut [u8]) -> Result<(), String> {
         let mut cursor = 0;
         for _ in 0..self.count {
@@ -1601,27 +1690,42 @@ impl<'a> Parse<&'a Bump> for DataSection<'a> {
             return Ok(DataSection {
                 end_addr: 0,
                 count: 0,
+                count_size: 0,
                 bytes: Vec::<u8>::new_in(arena),
+                has_passive_segments: false,
             });
         }
+        let section_start = *cursor;
         let (count, range) = parse_section(Self::ID, module_bytes, cursor)?;
 
+        let count_size = if range.is_empty() {
+            0
+        } else {
+            let mut count_start = section_start + 1;
+            u32::skip_bytes(module_bytes, &mut count_start)?; // section body size
+            (range.start - count_start) as u32
+        };
+
         let end = range.end;
         let mut bytes = Vec::<u8>::with_capacity_in(range.len() * 2, arena);
         bytes.extend_from_slice(&module_bytes[range]);
 
         let mut end_addr = 0;
+        let mut has_passive_segments = false;
         for _ in 0..count {
             let mode = DataMode::parse((), module_bytes, cursor)?;
+            let segment_bytes_len = u32::parse((), module_bytes, cursor)?;
             match mode {
                 DataMode::Active {
                     offset: ConstExpr::I32(offset_addr),
-                } if offset_addr > end_addr => {
-                    end_addr = offset_addr;
+                } => {
+                    end_addr = end_addr.max(offset_addr + segment_bytes_len as i32);
+                }
+                DataMode::Passive => {
+                    has_passive_segments = true;
                 }
                 _ => {}
             }
-            let segment_bytes_len = u32::parse((), module_bytes, cursor)?;
             *cursor += segment_bytes_len as usize;
         }
 
@@ -1630,7 +1734,9 @@ impl<'a> Parse<&'a Bump> for DataSection<'a> {
         Ok(DataSection {
             end_addr: end_addr as u32,
             count,
+            count_size,
             bytes,
+            has_passive_segments,
         })
     }
 }
@@ -1659,6 +1765,21 @@ impl<'a> OpaqueSection<'a> {
         OpaqueSection { bytes: &[] }
     }
 
+    /// A Start section, to call a function whenever the module is instantiated
+    pub fn new_start(arena: &'a Bump, fn_index: u32) -> Self {
+        let mut bytes = Vec::with_capacity_in(1 + 2 * MAX_SIZE_ENCODED_U32, arena);
+        let header_indices = write_section_header(&mut bytes, SectionId::Start);
+        bytes.encode_u32(fn_index);
+        update_section_size(&mut bytes, header_indices);
+        OpaqueSection {
+            bytes: bytes.into_bump_slice(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
     pub fn size(&self) -> usize {
         self.bytes.len()
     }
@@ -1920,4 +2041,44 @@ mod tests {
         }
         test_assert_types_preload(arena, &section);
     }
+
+    #[test]
+    fn test_shared_memory_limits() {
+        let arena = &Bump::new();
+        let limits = Limits::SharedMinMax(17, 1024);
+        let mut bytes = Vec::with_capacity_in(2 * MAX_SIZE_ENCODED_U32 + 1, arena);
+        limits.serialize(&mut bytes);
+        assert_eq!(bytes[0], 3);
+
+        let mut cursor = 0;
+        assert_eq!(Limits::parse((), &bytes, &mut cursor).unwrap(), limits);
+        assert_eq!(cursor, bytes.len());
+
+        cursor = 0;
+        Limits::skip_bytes(&bytes, &mut cursor).unwrap();
+        assert_eq!(cursor, bytes.len());
+    }
+
+    #[test]
+    fn test_make_data_passive() {
+        let arena = &Bump::new();
+        let mut section = DataSection::new(arena);
+        section.append_segment(DataSegment {
+            mode: DataMode::active_at(16),
+            init: bumpalo::vec![in arena; 1, 2, 3],
+        });
+        section.append_segment(DataSegment {
+            mode: DataMode::active_at(1024),
+            init: bumpalo::vec![in arena; 4, 5],
+        });
+
+        let segments = section.make_passive(arena);
+        assert_eq!(segments.as_slice(), &[(16, 3), (1024, 2)]);
+        assert_eq!(section.bytes.as_slice(), &[1, 3, 1, 2, 3, 1, 2, 4, 5]);
+
+        let mut count_section = Vec::with_capacity_in(8, arena);
+        section.serialize_count_section(&mut count_section);
+        assert_eq!(count_section[0], SectionId::DataCount as u8);
+        assert_eq!(count_section.last(), Some(&2));
+    }
 }
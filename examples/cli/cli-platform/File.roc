@@ -8,14 +8,13 @@ WriteErr : InternalFile.WriteErr
 
 ## Encodes a value using the given `EncodingFormat` and writes it to a file.
 ##
-## For example, suppose you have a [JSON](https://en.wikipedia.org/wiki/JSON)
-## `EncodingFormat` named `Json.toCompactUtf8`. Then you can use that format
-## to write some encodable data to a file as JSON, like so:
+## For example, the builtin [JSON](https://en.wikipedia.org/wiki/JSON) format
+## `Json.toUtf8` can write some encodable data to a file as JSON, like so:
 ##
 ##     File.write
 ##         (Path.fromStr "output.json")
 ##         { some: "json stuff" }
-##         Json.toCompactUtf8
+##         Json.toUtf8
 ##     # Writes the following to the file `output.json`:
 ##     #
 ##     # {"some":"json stuff"}
@@ -7,12 +7,11 @@ use core::alloc::Layout;
 use core::ffi::c_void;
 use core::mem::MaybeUninit;
 use glue::Metadata;
-use roc_std::{RocDict, RocList, RocResult, RocStr};
+use roc_std::{RocDict, RocList, RocPanic, RocPanicTag, RocResult, RocStr};
 use std::borrow::{Borrow, Cow};
-use std::ffi::{CStr, OsStr};
+use std::ffi::OsStr;
 use std::fs::File;
 use std::io::Write;
-use std::os::raw::c_char;
 use std::path::Path;
 use std::time::Duration;
 
@@ -58,22 +57,19 @@ pub unsafe extern "C" fn roc_dealloc(c_ptr: *mut c_void, _alignment: u32) {
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn roc_panic(msg: &RocStr, tag_id: u32) {
-    match tag_id {
-        0 => {
-            eprintln!("Roc crashed with:\n\n\t{}\n", msg.as_str());
+pub unsafe extern "C" fn roc_panic(payload: &RocPanic, tag_id: u32) {
+    eprintln!("{}\n", payload.describe(tag_id));
 
-            print_backtrace();
-            std::process::exit(1);
-        }
-        1 => {
-            eprintln!("The program crashed with:\n\n\t{}\n", msg.as_str());
+    print_backtrace();
 
-            print_backtrace();
-            std::process::exit(1);
-        }
-        _ => todo!(),
-    }
+    let exit_code = RocPanicTag::from_u32(tag_id).map_or(1, RocPanicTag::exit_code);
+    std::process::exit(exit_code);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_oom(size: usize, _alignment: u32) {
+    eprintln!("Roc ran out of memory allocating {} bytes", size);
+    std::process::exit(1);
 }
 
 #[cfg(unix)]
@@ -30,6 +30,11 @@ void roc_panic(void* ptr, unsigned int alignment) {
   exit(0);
 }
 
+void roc_oom(size_t size, unsigned int alignment) {
+  fprintf(stderr, "Roc ran out of memory allocating %zu bytes\n", size);
+  exit(1);
+}
+
 void* roc_memcpy(void* dest, const void* src, size_t n) {
   return memcpy(dest, src, n);
 }
@@ -1,10 +1,8 @@
 #![allow(non_snake_case)]
 
 use core::ffi::c_void;
-use roc_std::RocStr;
-use std::ffi::CStr;
+use roc_std::{RocPanic, RocPanicTag, RocStr};
 use std::io::Write;
-use std::os::raw::c_char;
 
 extern "C" {
     #[link_name = "roc__mainForHost_1_exposed_generic"]
@@ -32,16 +30,17 @@ pub unsafe extern "C" fn roc_dealloc(c_ptr: *mut c_void, _alignment: u32) {
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn roc_panic(c_ptr: *mut c_void, tag_id: u32) {
-    match tag_id {
-        0 => {
-            let slice = CStr::from_ptr(c_ptr as *const c_char);
-            let string = slice.to_str().unwrap();
-            eprintln!("Roc hit a panic: {}", string);
-            std::process::exit(1);
-        }
-        _ => todo!(),
-    }
+pub unsafe extern "C" fn roc_panic(payload: &RocPanic, tag_id: u32) {
+    eprintln!("{}", payload.describe(tag_id));
+
+    let exit_code = RocPanicTag::from_u32(tag_id).map_or(1, RocPanicTag::exit_code);
+    std::process::exit(exit_code);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_oom(size: usize, _alignment: u32) {
+    eprintln!("Roc ran out of memory allocating {} bytes", size);
+    std::process::exit(1);
 }
 
 #[no_mangle]
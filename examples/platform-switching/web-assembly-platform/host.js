@@ -26,6 +26,9 @@ async function roc_web_platform_run(wasm_filename, callback) {
       roc_panic: (_pointer, _tag_id) => {
         throw "Roc panicked!";
       },
+      roc_oom: (size, _alignment) => {
+        throw `Roc ran out of memory allocating ${size} bytes!`;
+      },
     },
   };
 
@@ -37,7 +37,7 @@ export fn roc_memcpy(dest: *anyopaque, src: *anyopaque, count: usize) callconv(.
     _ = memcpy(dest, src, count);
 }
 
-// NOTE roc_panic is provided in the JS file, so it can throw an exception
+// NOTE roc_panic and roc_oom are provided in the JS file, so they can throw an exception
 
 extern fn roc__mainForHost_1_exposed(*RocStr) void;
 
@@ -23,6 +23,11 @@ __attribute__((noreturn)) void roc_panic(void *ptr, unsigned int alignment)
     rb_raise(rb_eException, "%s", (char *)ptr);
 }
 
+__attribute__((noreturn)) void roc_oom(size_t size, unsigned int alignment)
+{
+    rb_raise(rb_eNoMemError, "Roc ran out of memory allocating %zu bytes", size);
+}
+
 void *roc_memcpy(void *dest, const void *src, size_t n)
 {
     return memcpy(dest, src, n);